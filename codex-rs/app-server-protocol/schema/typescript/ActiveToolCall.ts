@@ -0,0 +1,6 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActiveToolCallStatus } from "./ActiveToolCallStatus";
+
+export type ActiveToolCall = { call_id: string, tool: string, elapsed_ms: bigint, status: ActiveToolCallStatus, };
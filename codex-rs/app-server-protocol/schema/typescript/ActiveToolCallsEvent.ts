@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ActiveToolCall } from "./ActiveToolCall";
+
+/**
+ * Response payload for `Op::GetActiveToolCalls`.
+ */
+export type ActiveToolCallsEvent = { 
+/**
+ * The executing calls, the longest-running first.
+ */
+calls: Array<ActiveToolCall>, };
@@ -0,0 +1,15 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ReviewDecision } from "./ReviewDecision";
+
+export type ApprovalResolvedEvent = { 
+/**
+ * Id of the approval request that was answered.
+ */
+id: string, decision: ReviewDecision, 
+/**
+ * Id of the attached client that answered, or `None` for the thread's
+ * primary client.
+ */
+client?: string, };
@@ -0,0 +1,8 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An attachment listed in a session archive's manifest.
+ */
+export type ArchivedAttachment = { id: string, content_type: string, name?: string, bytes: bigint, };
@@ -0,0 +1,8 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Coarse risk level assigned to a command before it is shown for approval.
+ */
+export type CommandRiskLevel = "low" | "medium" | "high";
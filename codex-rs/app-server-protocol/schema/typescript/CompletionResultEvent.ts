@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TextPosition } from "./TextPosition";
+
+/**
+ * Response payload for `Op::Complete`.
+ */
+export type CompletionResultEvent = { path: string, cursor: TextPosition, 
+/**
+ * Text to insert at the cursor; empty when the model has nothing to add.
+ */
+completion: string, };
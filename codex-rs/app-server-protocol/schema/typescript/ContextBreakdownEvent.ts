@@ -0,0 +1,17 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ContextItemBreakdown } from "./ContextItemBreakdown";
+
+/**
+ * Response payload for `Op::GetContextBreakdown`.
+ */
+export type ContextBreakdownEvent = { 
+/**
+ * The items of the context, oldest first.
+ */
+items: Array<ContextItemBreakdown>, 
+/**
+ * The estimated tokens of the items summed.
+ */
+estimated_tokens: number, };
@@ -0,0 +1,15 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HistoryItemMetadata } from "./HistoryItemMetadata";
+
+export type ContextItemBreakdown = { 
+/**
+ * Item type as sent to the model, e.g. `message` or
+ * `function_call_output`.
+ */
+kind: string, 
+/**
+ * Role of `message` items.
+ */
+role: string | null, estimated_tokens: number, metadata: HistoryItemMetadata, };
@@ -0,0 +1,21 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * An advisory affecting a package version pinned by a lockfile.
+ */
+export type DependencyAdvisoryFinding = { 
+/**
+ * OSV ecosystem of the package: `crates.io`, `npm`, or `PyPI`.
+ */
+ecosystem: string, package: string, version: string, lockfile: string, 
+/**
+ * OSV id of the advisory, e.g. `RUSTSEC-2024-0001` or `GHSA-...`.
+ */
+advisory_id: string, aliases?: Array<string>, summary: string, severity?: string, 
+/**
+ * Lowest version newer than `version` that fixes the advisory, if one
+ * is released.
+ */
+suggested_version?: string, };
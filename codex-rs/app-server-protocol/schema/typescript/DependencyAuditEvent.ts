@@ -0,0 +1,22 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DependencyAdvisoryFinding } from "./DependencyAdvisoryFinding";
+
+/**
+ * Response payload for `Op::AuditDependencies`.
+ */
+export type DependencyAuditEvent = { 
+/**
+ * Lockfiles read, relative to the session cwd.
+ */
+lockfiles: Array<string>, 
+/**
+ * Distinct packages checked.
+ */
+packages_checked: bigint, findings: Array<DependencyAdvisoryFinding>, 
+/**
+ * Advisories that could not be looked up. Findings are incomplete when
+ * this is not empty.
+ */
+errors?: Array<string>, };
@@ -0,0 +1,17 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * License of a package version pinned by a lockfile.
+ */
+export type DependencyLicense = { 
+/**
+ * OSV ecosystem of the package: `crates.io`, `npm`, or `PyPI`.
+ */
+ecosystem: string, package: string, version: string, lockfile: string, 
+/**
+ * SPDX expression, e.g. `MIT OR Apache-2.0`; `None` when neither local
+ * package metadata nor the registry declares one.
+ */
+license?: string, };
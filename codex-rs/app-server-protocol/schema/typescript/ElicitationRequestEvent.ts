@@ -1,5 +1,20 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
 
-export type ElicitationRequestEvent = { server_name: string, id: string | number, message: string, };
+export type ElicitationRequestEvent = { server_name: string, id: string | number, message: string, 
+/**
+ * JSON schema of the answers a form request asks for: an object of
+ * primitive properties. The answers go back as the `content` of
+ * `Op::ResolveElicitation`.
+ */
+requested_schema?: JsonValue, 
+/**
+ * Page a URL request asks the user to open.
+ */
+url?: string, 
+/**
+ * How long the request waits for an answer before it is cancelled.
+ */
+timeout_ms?: bigint, };
@@ -0,0 +1,12 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response payload for `Op::SetEnabledTools`.
+ */
+export type EnabledToolsUpdatedEvent = { 
+/**
+ * Every tool and MCP server now turned off, sorted.
+ */
+disabled: Array<string>, };
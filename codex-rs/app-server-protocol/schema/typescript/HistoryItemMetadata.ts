@@ -0,0 +1,25 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * When and where an item of the model's context came from.
+ */
+export type HistoryItemMetadata = { 
+/**
+ * When the item was recorded, in RFC 3339. Items restored from a
+ * rollout carry the time they were restored.
+ */
+recorded_at: string, 
+/**
+ * Tool behind tool calls and their outputs, as the model names it.
+ */
+tool: string | null, 
+/**
+ * MCP server of that tool.
+ */
+connector: string | null, 
+/**
+ * Model of the turn that recorded the item.
+ */
+model: string | null, };
@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { HistoryItemMetadata } from "./HistoryItemMetadata";
+
+/**
+ * Payload of `EventMsg::HistoryMetadataRecorded`, which is only written to
+ * the rollout.
+ */
+export type HistoryMetadataRecordedEvent = { 
+/**
+ * One entry per recorded item, oldest first.
+ */
+metadata: Array<HistoryItemMetadata>, };
@@ -0,0 +1,17 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { LineRange } from "./LineRange";
+
+/**
+ * Response payload for `Op::InlineEdit`.
+ */
+export type InlineEditResultEvent = { path: string, range: LineRange, 
+/**
+ * The text of the range the edit was made against.
+ */
+original: string, 
+/**
+ * Text to put in place of `original`.
+ */
+replacement: string, };
@@ -0,0 +1,20 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { DependencyLicense } from "./DependencyLicense";
+import type { LicenseViolation } from "./LicenseViolation";
+import type { SourceHeaderLicense } from "./SourceHeaderLicense";
+
+/**
+ * Response payload for `Op::ScanLicenses`.
+ */
+export type LicenseReportEvent = { dependencies: Array<DependencyLicense>, 
+/**
+ * Licenses declared by `SPDX-License-Identifier` source file headers.
+ */
+source_headers: Array<SourceHeaderLicense>, violations: Array<LicenseViolation>, 
+/**
+ * Licenses that could not be looked up. The report is incomplete when
+ * this is not empty.
+ */
+errors?: Array<string>, };
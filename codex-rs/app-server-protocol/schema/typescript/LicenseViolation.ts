@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A dependency or source file whose license breaks the license policy.
+ */
+export type LicenseViolation = { 
+/**
+ * `package@version (lockfile)` for dependencies, or a file path for
+ * source headers.
+ */
+subject: string, license?: string, reason: string, };
@@ -0,0 +1,19 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { McpLogLevel } from "./McpLogLevel";
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type McpToolCallLogEvent = { 
+/**
+ * Identifier of the McpToolCallBegin the server was running.
+ */
+call_id: string, level: McpLogLevel, 
+/**
+ * Name of the server's logger, if it sent one.
+ */
+logger: string | null, 
+/**
+ * The message: a string or any JSON value.
+ */
+data: JsonValue, };
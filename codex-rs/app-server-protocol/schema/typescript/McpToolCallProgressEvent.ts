@@ -0,0 +1,17 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type McpToolCallProgressEvent = { 
+/**
+ * Identifier of the McpToolCallBegin the progress is for.
+ */
+call_id: string, 
+/**
+ * Progress so far, in the server's units. Increases with every update.
+ */
+progress: number, 
+/**
+ * Progress at completion, when the server knows it.
+ */
+total: number | null, message: string | null, };
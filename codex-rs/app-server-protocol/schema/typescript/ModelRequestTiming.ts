@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ModelRequestTiming = { 
+/**
+ * From sending the request to the first output from the model, or
+ * `None` if no output arrived.
+ */
+time_to_first_token_ms: number | null, 
+/**
+ * From the first output to the end of the response.
+ */
+streaming_ms: number, };
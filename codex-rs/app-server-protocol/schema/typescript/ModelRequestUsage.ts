@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A model request within a turn, identifying the usage a [`TokenCountEvent`]
+ * reports.
+ */
+export type ModelRequestUsage = { 
+/**
+ * 1-based position of the request in its turn; tool-call follow-ups
+ * count as new requests.
+ */
+index: number, model: string, };
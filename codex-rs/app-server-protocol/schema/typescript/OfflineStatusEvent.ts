@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type OfflineStatusEvent = { offline: boolean, 
+/**
+ * Turns waiting to run once the session is back online.
+ */
+queued_turns: number, };
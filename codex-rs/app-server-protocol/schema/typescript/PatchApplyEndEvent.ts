@@ -1,6 +1,7 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileAnchor } from "./FileAnchor";
 import type { FileChange } from "./FileChange";
 import type { PatchApplyStatus } from "./PatchApplyStatus";
 
@@ -33,4 +34,8 @@ changes: { [key in string]?: FileChange },
 /**
  * Completion status for this patch application.
  */
-status: PatchApplyStatus, };
+status: PatchApplyStatus, 
+/**
+ * The lines this patch wrote, for clients that link to them.
+ */
+anchors?: Array<FileAnchor>, };
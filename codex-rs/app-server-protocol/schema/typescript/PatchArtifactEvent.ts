@@ -0,0 +1,16 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response payload for `Op::GetPatchArtifact`.
+ */
+export type PatchArtifactEvent = { 
+/**
+ * Every proposed change as one unified diff, in `git diff` format.
+ */
+patch: string, 
+/**
+ * Where the artifact is also written, if configured.
+ */
+path?: string, };
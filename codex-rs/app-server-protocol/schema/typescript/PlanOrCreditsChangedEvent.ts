@@ -0,0 +1,7 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CreditsSnapshot } from "./CreditsSnapshot";
+import type { PlanType } from "./PlanType";
+
+export type PlanOrCreditsChangedEvent = { previous_plan_type: PlanType | null, plan_type: PlanType | null, previous_credits: CreditsSnapshot | null, credits: CreditsSnapshot | null, };
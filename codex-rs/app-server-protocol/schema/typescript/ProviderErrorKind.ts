@@ -0,0 +1,8 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Why a model provider rejected a request, classified from its HTTP response.
+ */
+export type ProviderErrorKind = "unauthorized" | "organization_verification_required" | "rate_limited" | "server_error";
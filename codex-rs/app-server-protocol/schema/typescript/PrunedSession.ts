@@ -0,0 +1,10 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ThreadId } from "./ThreadId";
+
+export type PrunedSession = { thread_id: ThreadId, path: string, bytes: bigint, 
+/**
+ * Which retention limit selected the session.
+ */
+reason: string, };
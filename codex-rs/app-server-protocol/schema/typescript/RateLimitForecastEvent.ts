@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RateLimitWindowForecast } from "./RateLimitWindowForecast";
+
+/**
+ * Response payload for `Op::ForecastRateLimits`.
+ */
+export type RateLimitForecastEvent = { windows: Array<RateLimitWindowForecast>, };
@@ -0,0 +1,29 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RateLimitWindowKind } from "./RateLimitWindowKind";
+
+export type RateLimitWindowForecast = { limit_id: string, window: RateLimitWindowKind, 
+/**
+ * Percentage (0-100) of the window consumed at the latest snapshot.
+ */
+used_percent: number, 
+/**
+ * Unix timestamp (seconds since epoch) when the window resets.
+ */
+resets_at: number | null, 
+/**
+ * Percentage of the window consumed per hour since it last reset, or
+ * `None` until two snapshots of it have been seen.
+ */
+burn_percent_per_hour: number | null, 
+/**
+ * Unix timestamp when the window runs out at the current burn rate, or
+ * `None` if it resets first.
+ */
+exhausted_at: number | null, 
+/**
+ * The highest burn rate, in percent per hour, that lasts until the
+ * window resets.
+ */
+sustainable_percent_per_hour: number | null, };
@@ -0,0 +1,12 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response payload for `Op::SelectCandidate`.
+ */
+export type ResponseCandidateSelectedEvent = { index: number, 
+/**
+ * The text of the response now in the thread history.
+ */
+message: string, };
@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * Response payload for `Op::Regenerate`.
+ */
+export type ResponseCandidatesEvent = { 
+/**
+ * The text of each candidate response, in the order `Op::SelectCandidate`
+ * indexes them.
+ */
+candidates: Array<string>, };
@@ -0,0 +1,8 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CallToolResult } from "./CallToolResult";
+import type { ContentItem } from "./ContentItem";
+import type { FunctionCallOutputPayload } from "./FunctionCallOutputPayload";
+
+export type ResponseInputItem = { "type": "message", role: string, content: Array<ContentItem>, } | { "type": "function_call_output", call_id: string, output: FunctionCallOutputPayload, } | { "type": "mcp_tool_call_output", call_id: string, result: { Ok : CallToolResult } | { Err : string }, } | { "type": "custom_tool_call_output", call_id: string, output: string, };
@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArchivedAttachment } from "./ArchivedAttachment";
+import type { ThreadId } from "./ThreadId";
+
+/**
+ * Response payload for `Op::ExportSession`.
+ */
+export type SessionExportedEvent = { thread_id: ThreadId, 
+/**
+ * Where the archive was written.
+ */
+path: string, attachments: Array<ArchivedAttachment>, };
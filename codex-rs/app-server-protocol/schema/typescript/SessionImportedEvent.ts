@@ -0,0 +1,23 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ArchivedAttachment } from "./ArchivedAttachment";
+import type { ThreadId } from "./ThreadId";
+
+/**
+ * Response payload for `Op::ImportSession`.
+ */
+export type SessionImportedEvent = { thread_id: ThreadId, 
+/**
+ * The imported rollout; resume the session from this path.
+ */
+rollout_path: string, 
+/**
+ * Working directory of the session on the machine it was exported from.
+ */
+source_cwd: string, 
+/**
+ * Attachments the session had when it was exported. Their content is
+ * not archived; attach them again to make them readable.
+ */
+attachments: Array<ArchivedAttachment>, };
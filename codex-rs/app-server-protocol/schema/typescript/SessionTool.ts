@@ -0,0 +1,16 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+export type SessionTool = { name: string, description: string, 
+/**
+ * JSON schema of the arguments of a function tool. `None` for tools
+ * that take raw input, such as the freeform `apply_patch`.
+ */
+input_schema: JsonValue | null, 
+/**
+ * Whether calls may stop for the user's approval under the session's
+ * approval policy.
+ */
+requires_approval: boolean, };
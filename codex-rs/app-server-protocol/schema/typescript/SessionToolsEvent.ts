@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SessionTool } from "./SessionTool";
+
+/**
+ * Response payload for `Op::ListSessionTools`.
+ */
+export type SessionToolsEvent = { tools: Array<SessionTool>, };
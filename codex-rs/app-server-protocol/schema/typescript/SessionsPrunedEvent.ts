@@ -0,0 +1,21 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { PrunedSession } from "./PrunedSession";
+
+/**
+ * Response payload for `Op::PruneSessions`.
+ */
+export type SessionsPrunedEvent = { 
+/**
+ * When true, nothing was deleted and `pruned` lists what would be.
+ */
+dry_run: boolean, pruned: Array<PrunedSession>, 
+/**
+ * Total size of the pruned rollouts.
+ */
+freed_bytes: bigint, 
+/**
+ * Total size of the rollouts that remain.
+ */
+kept_bytes: bigint, };
@@ -0,0 +1,12 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A license declared by source file headers.
+ */
+export type SourceHeaderLicense = { license: string, 
+/**
+ * Files declaring the license, relative to the session cwd.
+ */
+files: Array<string>, };
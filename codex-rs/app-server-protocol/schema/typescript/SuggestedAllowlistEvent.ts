@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { SuggestedCommand } from "./SuggestedCommand";
+
+/**
+ * Response payload for `Op::ReviewSuggestedAllowlist`.
+ */
+export type SuggestedAllowlistEvent = { project_root: string, 
+/**
+ * The remaining suggestions, most approved first.
+ */
+commands: Array<SuggestedCommand>, };
@@ -0,0 +1,20 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A command approved while learning approved commands.
+ */
+export type SuggestedCommand = { 
+/**
+ * The command as an execpolicy prefix rule would match it.
+ */
+command: Array<string>, 
+/**
+ * How many times it was approved.
+ */
+approvals: number, 
+/**
+ * RFC 3339 timestamp of the latest approval.
+ */
+last_approved_at: string, };
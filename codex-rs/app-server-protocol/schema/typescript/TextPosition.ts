@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A position in a text file. Both fields are 1-based; `column` counts
+ * characters, so column 1 is before the first character of the line.
+ */
+export type TextPosition = { line: number, column: number, };
@@ -1,7 +1,13 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelRequestUsage } from "./ModelRequestUsage";
 import type { RateLimitSnapshot } from "./RateLimitSnapshot";
 import type { TokenUsageInfo } from "./TokenUsageInfo";
 
-export type TokenCountEvent = { info: TokenUsageInfo | null, rate_limits: RateLimitSnapshot | null, };
+export type TokenCountEvent = { info: TokenUsageInfo | null, rate_limits: RateLimitSnapshot | null, 
+/**
+ * The model request `info.last_token_usage` belongs to, when the event
+ * follows one.
+ */
+request?: ModelRequestUsage, };
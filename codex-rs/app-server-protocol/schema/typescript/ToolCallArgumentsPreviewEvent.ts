@@ -0,0 +1,20 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { JsonValue } from "./serde_json/JsonValue";
+
+/**
+ * Sent periodically while a function call's arguments stream in, so clients
+ * can preview large calls (for example a long command) before they run.
+ */
+export type ToolCallArgumentsPreviewEvent = { call_id: string, tool_name: string, 
+/**
+ * The arguments received so far, with unterminated strings and
+ * containers closed and any trailing incomplete member dropped.
+ */
+arguments?: JsonValue, 
+/**
+ * Set when the arguments can no longer form a valid JSON object; no
+ * further previews follow for the call.
+ */
+error?: string, };
@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ResponseInputItem } from "./ResponseInputItem";
+
+/**
+ * Response payload for `Op::InvokeTool`.
+ */
+export type ToolInvocationResultEvent = { call_id: string, 
+/**
+ * The output as the model would have received it.
+ */
+output: ResponseInputItem, };
@@ -0,0 +1,17 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ToolLockHeldEvent = { 
+/**
+ * The call holding the lock.
+ */
+call_id: string, tool: string, held_ms: bigint, 
+/**
+ * Calls waiting for the lock.
+ */
+waiting_call_ids: Array<string>, 
+/**
+ * Whether the watchdog cancelled the call.
+ */
+cancelled: boolean, };
@@ -0,0 +1,21 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ToolProgressEvent = { 
+/**
+ * The call reporting progress.
+ */
+call_id: string, 
+/**
+ * Steps done so far.
+ */
+completed: bigint, 
+/**
+ * Steps in all, when known.
+ */
+total: bigint | null, 
+/**
+ * What the call is doing now, e.g. the file being patched.
+ */
+message: string | null, };
@@ -0,0 +1,14 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ToolSelectionEvent = { 
+/**
+ * IDs of the connectors explicitly selected, sorted.
+ */
+connectors: Array<string>, 
+/**
+ * Fully-qualified names of the MCP tools selected through tool search,
+ * in the order they were selected.
+ */
+mcp_tools: Array<string>, };
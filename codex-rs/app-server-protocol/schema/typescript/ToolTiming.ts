@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type ToolTiming = { tool: string, calls: number, 
+/**
+ * Wall time of the calls summed, including waiting for exclusive access.
+ */
+wall_time_ms: number, };
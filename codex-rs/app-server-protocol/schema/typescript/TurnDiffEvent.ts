@@ -1,5 +1,11 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileAnchor } from "./FileAnchor";
 
-export type TurnDiffEvent = { unified_diff: string, };
+export type TurnDiffEvent = { unified_diff: string, 
+/**
+ * Where the lines written by this turn's patches are now, after any
+ * later edits in the turn moved them.
+ */
+anchors?: Array<FileAnchor>, };
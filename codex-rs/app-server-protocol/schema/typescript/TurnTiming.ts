@@ -0,0 +1,32 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelRequestTiming } from "./ModelRequestTiming";
+import type { ToolTiming } from "./ToolTiming";
+
+/**
+ * Where the time of one turn went. Durations are in milliseconds.
+ */
+export type TurnTiming = { turn_id: string, 
+/**
+ * From accepting the submission to the turn starting, including waiting
+ * for the previous turn to stop.
+ */
+queue_wait_ms: number, 
+/**
+ * Building the prompts: history, tools, and instructions.
+ */
+prompt_assembly_ms: number, 
+/**
+ * One entry per model request, retries included, in order.
+ */
+model_requests: Array<ModelRequestTiming>, 
+/**
+ * Tool calls by tool, slowest first. Parallel calls overlap, so these
+ * can add up to more than the turn took.
+ */
+tools: Array<ToolTiming>, 
+/**
+ * Recording items into the history and the rollout.
+ */
+serialization_ms: number, total_ms: number, };
@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TurnTiming } from "./TurnTiming";
+
+/**
+ * Response payload for `Op::GetTurnTimings`.
+ */
+export type TurnTimingsEvent = { 
+/**
+ * The session's most recent finished or aborted turns, oldest first.
+ */
+turns: Array<TurnTiming>, };
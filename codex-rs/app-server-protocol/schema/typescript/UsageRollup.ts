@@ -0,0 +1,22 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TokenUsage } from "./TokenUsage";
+
+/**
+ * Usage of one model in one project on one day.
+ */
+export type UsageRollup = { 
+/**
+ * Local date, `YYYY-MM-DD`.
+ */
+date: string, project_root: string, model: string, 
+/**
+ * Number of model requests.
+ */
+requests: number, token_usage: TokenUsage, 
+/**
+ * Estimated cost in US dollars, or `None` if `model_pricing` has no
+ * price for the model.
+ */
+cost_usd: number | null, };
@@ -0,0 +1,13 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { UsageRollup } from "./UsageRollup";
+
+/**
+ * Response payload for `Op::QueryUsage`.
+ */
+export type UsageRollupsEvent = { 
+/**
+ * Oldest day first, then by project and model.
+ */
+rollups: Array<UsageRollup>, };
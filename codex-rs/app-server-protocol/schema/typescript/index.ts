@@ -1,6 +1,9 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 export type { AbsolutePathBuf } from "./AbsolutePathBuf";
+export type { ActiveToolCall } from "./ActiveToolCall";
+export type { ActiveToolCallStatus } from "./ActiveToolCallStatus";
+export type { ActiveToolCallsEvent } from "./ActiveToolCallsEvent";
 export type { AddConversationListenerParams } from "./AddConversationListenerParams";
 export type { AddConversationSubscriptionResponse } from "./AddConversationSubscriptionResponse";
 export type { AgentMessageContent } from "./AgentMessageContent";
@@ -17,8 +20,10 @@ export type { AgentStatus } from "./AgentStatus";
 export type { ApplyPatchApprovalParams } from "./ApplyPatchApprovalParams";
 export type { ApplyPatchApprovalRequestEvent } from "./ApplyPatchApprovalRequestEvent";
 export type { ApplyPatchApprovalResponse } from "./ApplyPatchApprovalResponse";
+export type { ApprovalResolvedEvent } from "./ApprovalResolvedEvent";
 export type { ArchiveConversationParams } from "./ArchiveConversationParams";
 export type { ArchiveConversationResponse } from "./ArchiveConversationResponse";
+export type { ArchivedAttachment } from "./ArchivedAttachment";
 export type { AskForApproval } from "./AskForApproval";
 export type { AuthMode } from "./AuthMode";
 export type { AuthStatusChangeNotification } from "./AuthStatusChangeNotification";
@@ -45,16 +50,26 @@ export type { CollabWaitingBeginEvent } from "./CollabWaitingBeginEvent";
 export type { CollabWaitingEndEvent } from "./CollabWaitingEndEvent";
 export type { CollaborationMode } from "./CollaborationMode";
 export type { CollaborationModeMask } from "./CollaborationModeMask";
+export type { CommandRiskAssessment } from "./CommandRiskAssessment";
+export type { CommandRiskLevel } from "./CommandRiskLevel";
+export type { CompletionResultEvent } from "./CompletionResultEvent";
 export type { ContentItem } from "./ContentItem";
+export type { ContextBreakdownEvent } from "./ContextBreakdownEvent";
 export type { ContextCompactedEvent } from "./ContextCompactedEvent";
 export type { ContextCompactionItem } from "./ContextCompactionItem";
+export type { ContextItemBreakdown } from "./ContextItemBreakdown";
 export type { ConversationGitInfo } from "./ConversationGitInfo";
 export type { ConversationSummary } from "./ConversationSummary";
 export type { CreditsSnapshot } from "./CreditsSnapshot";
 export type { CustomPrompt } from "./CustomPrompt";
+export type { DependencyAdvisoryFinding } from "./DependencyAdvisoryFinding";
+export type { DependencyAuditEvent } from "./DependencyAuditEvent";
+export type { DependencyLicense } from "./DependencyLicense";
 export type { DeprecationNoticeEvent } from "./DeprecationNoticeEvent";
 export type { DynamicToolCallRequest } from "./DynamicToolCallRequest";
 export type { ElicitationRequestEvent } from "./ElicitationRequestEvent";
+export type { ElicitationTimedOutEvent } from "./ElicitationTimedOutEvent";
+export type { EnabledToolsUpdatedEvent } from "./EnabledToolsUpdatedEvent";
 export type { ErrorEvent } from "./ErrorEvent";
 export type { EventMsg } from "./EventMsg";
 export type { ExecApprovalRequestEvent } from "./ExecApprovalRequestEvent";
@@ -70,6 +85,7 @@ export type { ExecOneOffCommandResponse } from "./ExecOneOffCommandResponse";
 export type { ExecOutputStream } from "./ExecOutputStream";
 export type { ExecPolicyAmendment } from "./ExecPolicyAmendment";
 export type { ExitedReviewModeEvent } from "./ExitedReviewModeEvent";
+export type { FileAnchor } from "./FileAnchor";
 export type { FileChange } from "./FileChange";
 export type { ForcedLoginMethod } from "./ForcedLoginMethod";
 export type { ForkConversationParams } from "./ForkConversationParams";
@@ -94,15 +110,21 @@ export type { GitDiffToRemoteParams } from "./GitDiffToRemoteParams";
 export type { GitDiffToRemoteResponse } from "./GitDiffToRemoteResponse";
 export type { GitSha } from "./GitSha";
 export type { HistoryEntry } from "./HistoryEntry";
+export type { HistoryItemMetadata } from "./HistoryItemMetadata";
+export type { HistoryMetadataRecordedEvent } from "./HistoryMetadataRecordedEvent";
 export type { InitializeCapabilities } from "./InitializeCapabilities";
 export type { InitializeParams } from "./InitializeParams";
 export type { InitializeResponse } from "./InitializeResponse";
+export type { InlineEditResultEvent } from "./InlineEditResultEvent";
 export type { InputItem } from "./InputItem";
 export type { InputModality } from "./InputModality";
 export type { InterruptConversationParams } from "./InterruptConversationParams";
 export type { InterruptConversationResponse } from "./InterruptConversationResponse";
 export type { ItemCompletedEvent } from "./ItemCompletedEvent";
 export type { ItemStartedEvent } from "./ItemStartedEvent";
+export type { LicenseReportEvent } from "./LicenseReportEvent";
+export type { LicenseViolation } from "./LicenseViolation";
+export type { LineRange } from "./LineRange";
 export type { ListConversationsParams } from "./ListConversationsParams";
 export type { ListConversationsResponse } from "./ListConversationsResponse";
 export type { ListCustomPromptsResponseEvent } from "./ListCustomPromptsResponseEvent";
@@ -119,14 +141,19 @@ export type { LogoutChatGptResponse } from "./LogoutChatGptResponse";
 export type { McpAuthStatus } from "./McpAuthStatus";
 export type { McpInvocation } from "./McpInvocation";
 export type { McpListToolsResponseEvent } from "./McpListToolsResponseEvent";
+export type { McpLogLevel } from "./McpLogLevel";
 export type { McpStartupCompleteEvent } from "./McpStartupCompleteEvent";
 export type { McpStartupFailure } from "./McpStartupFailure";
 export type { McpStartupStatus } from "./McpStartupStatus";
 export type { McpStartupUpdateEvent } from "./McpStartupUpdateEvent";
 export type { McpToolCallBeginEvent } from "./McpToolCallBeginEvent";
 export type { McpToolCallEndEvent } from "./McpToolCallEndEvent";
+export type { McpToolCallLogEvent } from "./McpToolCallLogEvent";
+export type { McpToolCallProgressEvent } from "./McpToolCallProgressEvent";
 export type { MessagePhase } from "./MessagePhase";
 export type { ModeKind } from "./ModeKind";
+export type { ModelRequestTiming } from "./ModelRequestTiming";
+export type { ModelRequestUsage } from "./ModelRequestUsage";
 export type { ModelRerouteEvent } from "./ModelRerouteEvent";
 export type { ModelRerouteReason } from "./ModelRerouteReason";
 export type { NetworkAccess } from "./NetworkAccess";
@@ -134,18 +161,27 @@ export type { NetworkApprovalContext } from "./NetworkApprovalContext";
 export type { NetworkApprovalProtocol } from "./NetworkApprovalProtocol";
 export type { NewConversationParams } from "./NewConversationParams";
 export type { NewConversationResponse } from "./NewConversationResponse";
+export type { ObserverPromotedEvent } from "./ObserverPromotedEvent";
+export type { OfflineStatusEvent } from "./OfflineStatusEvent";
 export type { ParsedCommand } from "./ParsedCommand";
 export type { PatchApplyBeginEvent } from "./PatchApplyBeginEvent";
 export type { PatchApplyEndEvent } from "./PatchApplyEndEvent";
 export type { PatchApplyStatus } from "./PatchApplyStatus";
+export type { PatchArtifactEvent } from "./PatchArtifactEvent";
 export type { Personality } from "./Personality";
 export type { PlanDeltaEvent } from "./PlanDeltaEvent";
 export type { PlanItem } from "./PlanItem";
 export type { PlanItemArg } from "./PlanItemArg";
+export type { PlanOrCreditsChangedEvent } from "./PlanOrCreditsChangedEvent";
 export type { PlanType } from "./PlanType";
 export type { Profile } from "./Profile";
+export type { ProviderErrorKind } from "./ProviderErrorKind";
+export type { PrunedSession } from "./PrunedSession";
+export type { RateLimitForecastEvent } from "./RateLimitForecastEvent";
 export type { RateLimitSnapshot } from "./RateLimitSnapshot";
 export type { RateLimitWindow } from "./RateLimitWindow";
+export type { RateLimitWindowForecast } from "./RateLimitWindowForecast";
+export type { RateLimitWindowKind } from "./RateLimitWindowKind";
 export type { RawResponseItemEvent } from "./RawResponseItemEvent";
 export type { ReadOnlyAccess } from "./ReadOnlyAccess";
 export type { RealtimeAudioFrame } from "./RealtimeAudioFrame";
@@ -171,6 +207,9 @@ export type { RequestUserInputQuestion } from "./RequestUserInputQuestion";
 export type { RequestUserInputQuestionOption } from "./RequestUserInputQuestionOption";
 export type { Resource } from "./Resource";
 export type { ResourceTemplate } from "./ResourceTemplate";
+export type { ResponseCandidateSelectedEvent } from "./ResponseCandidateSelectedEvent";
+export type { ResponseCandidatesEvent } from "./ResponseCandidatesEvent";
+export type { ResponseInputItem } from "./ResponseInputItem";
 export type { ResponseItem } from "./ResponseItem";
 export type { ResumeConversationParams } from "./ResumeConversationParams";
 export type { ResumeConversationResponse } from "./ResumeConversationResponse";
@@ -192,8 +231,13 @@ export type { ServerNotification } from "./ServerNotification";
 export type { ServerRequest } from "./ServerRequest";
 export type { SessionConfiguredEvent } from "./SessionConfiguredEvent";
 export type { SessionConfiguredNotification } from "./SessionConfiguredNotification";
+export type { SessionExportedEvent } from "./SessionExportedEvent";
+export type { SessionImportedEvent } from "./SessionImportedEvent";
 export type { SessionNetworkProxyRuntime } from "./SessionNetworkProxyRuntime";
 export type { SessionSource } from "./SessionSource";
+export type { SessionTool } from "./SessionTool";
+export type { SessionToolsEvent } from "./SessionToolsEvent";
+export type { SessionsPrunedEvent } from "./SessionsPrunedEvent";
 export type { SetDefaultModelParams } from "./SetDefaultModelParams";
 export type { SetDefaultModelResponse } from "./SetDefaultModelResponse";
 export type { Settings } from "./Settings";
@@ -204,11 +248,15 @@ export type { SkillMetadata } from "./SkillMetadata";
 export type { SkillScope } from "./SkillScope";
 export type { SkillToolDependency } from "./SkillToolDependency";
 export type { SkillsListEntry } from "./SkillsListEntry";
+export type { SourceHeaderLicense } from "./SourceHeaderLicense";
 export type { StepStatus } from "./StepStatus";
 export type { StreamErrorEvent } from "./StreamErrorEvent";
 export type { SubAgentSource } from "./SubAgentSource";
+export type { SuggestedAllowlistEvent } from "./SuggestedAllowlistEvent";
+export type { SuggestedCommand } from "./SuggestedCommand";
 export type { TerminalInteractionEvent } from "./TerminalInteractionEvent";
 export type { TextElement } from "./TextElement";
+export type { TextPosition } from "./TextPosition";
 export type { ThreadId } from "./ThreadId";
 export type { ThreadNameUpdatedEvent } from "./ThreadNameUpdatedEvent";
 export type { ThreadRolledBackEvent } from "./ThreadRolledBackEvent";
@@ -216,6 +264,12 @@ export type { TokenCountEvent } from "./TokenCountEvent";
 export type { TokenUsage } from "./TokenUsage";
 export type { TokenUsageInfo } from "./TokenUsageInfo";
 export type { Tool } from "./Tool";
+export type { ToolCallArgumentsPreviewEvent } from "./ToolCallArgumentsPreviewEvent";
+export type { ToolInvocationResultEvent } from "./ToolInvocationResultEvent";
+export type { ToolLockHeldEvent } from "./ToolLockHeldEvent";
+export type { ToolProgressEvent } from "./ToolProgressEvent";
+export type { ToolSelectionEvent } from "./ToolSelectionEvent";
+export type { ToolTiming } from "./ToolTiming";
 export type { Tools } from "./Tools";
 export type { TurnAbortReason } from "./TurnAbortReason";
 export type { TurnAbortedEvent } from "./TurnAbortedEvent";
@@ -223,9 +277,13 @@ export type { TurnCompleteEvent } from "./TurnCompleteEvent";
 export type { TurnDiffEvent } from "./TurnDiffEvent";
 export type { TurnItem } from "./TurnItem";
 export type { TurnStartedEvent } from "./TurnStartedEvent";
+export type { TurnTiming } from "./TurnTiming";
+export type { TurnTimingsEvent } from "./TurnTimingsEvent";
 export type { UndoCompletedEvent } from "./UndoCompletedEvent";
 export type { UndoStartedEvent } from "./UndoStartedEvent";
 export type { UpdatePlanArgs } from "./UpdatePlanArgs";
+export type { UsageRollup } from "./UsageRollup";
+export type { UsageRollupsEvent } from "./UsageRollupsEvent";
 export type { UserInfoResponse } from "./UserInfoResponse";
 export type { UserInput } from "./UserInput";
 export type { UserMessageEvent } from "./UserMessageEvent";
@@ -2,6 +2,7 @@
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { CommandAction } from "./CommandAction";
+import type { CommandRiskAssessment } from "./CommandRiskAssessment";
 import type { ExecPolicyAmendment } from "./ExecPolicyAmendment";
 import type { NetworkApprovalContext } from "./NetworkApprovalContext";
 
@@ -39,4 +40,9 @@ commandActions?: Array<CommandAction> | null,
 /**
  * Optional proposed execpolicy amendment to allow similar commands without prompting.
  */
-proposedExecpolicyAmendment?: ExecPolicyAmendment | null, };
+proposedExecpolicyAmendment?: ExecPolicyAmendment | null, 
+/**
+ * Local risk classification of the command, computed before the request
+ * is sent.
+ */
+riskAssessment?: CommandRiskAssessment | null, };
@@ -0,0 +1,23 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CommandRiskLevel } from "./CommandRiskLevel";
+
+/**
+ * Best-effort explanation of a command awaiting approval, computed locally
+ * and optionally refined by the `command_risk_model`.
+ */
+export type CommandRiskAssessment = { 
+/**
+ * Short human-readable description of what the command does.
+ */
+summary: string, level: CommandRiskLevel, 
+/**
+ * Whether the command deletes or overwrites existing data.
+ */
+destructive: boolean, 
+/**
+ * Paths the command reads or writes, as written in the command (relative
+ * paths are relative to the approval request's `cwd`).
+ */
+paths: Array<string>, };
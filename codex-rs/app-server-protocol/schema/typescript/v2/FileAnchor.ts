@@ -0,0 +1,9 @@
+// GENERATED CODE! DO NOT MODIFY BY HAND!
+
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+/**
+ * A span of lines in a file, 1-based and inclusive, that clients can jump
+ * to or decorate.
+ */
+export type FileAnchor = { path: string, startLine: number, endLine: number, };
@@ -1,6 +1,12 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ModelRequestUsage } from "./ModelRequestUsage";
 import type { ThreadTokenUsage } from "./ThreadTokenUsage";
 
-export type ThreadTokenUsageUpdatedNotification = { threadId: string, turnId: string, tokenUsage: ThreadTokenUsage, };
+export type ThreadTokenUsageUpdatedNotification = { threadId: string, turnId: string, tokenUsage: ThreadTokenUsage, 
+/**
+ * The model request `tokenUsage.last` belongs to, when the update
+ * follows one.
+ */
+request: ModelRequestUsage | null, };
@@ -1,9 +1,15 @@
 // GENERATED CODE! DO NOT MODIFY BY HAND!
 
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { FileAnchor } from "./FileAnchor";
 
 /**
  * Notification that the turn-level unified diff has changed.
  * Contains the latest aggregated diff across all file changes in the turn.
  */
-export type TurnDiffUpdatedNotification = { threadId: string, turnId: string, diff: string, };
+export type TurnDiffUpdatedNotification = { threadId: string, turnId: string, diff: string, 
+/**
+ * Where the lines written by the turn's file changes are now, after any
+ * later edits in the turn moved them.
+ */
+anchors: Array<FileAnchor>, };
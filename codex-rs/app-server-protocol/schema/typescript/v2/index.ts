@@ -39,6 +39,8 @@ export type { CommandExecutionOutputDeltaNotification } from "./CommandExecution
 export type { CommandExecutionRequestApprovalParams } from "./CommandExecutionRequestApprovalParams";
 export type { CommandExecutionRequestApprovalResponse } from "./CommandExecutionRequestApprovalResponse";
 export type { CommandExecutionStatus } from "./CommandExecutionStatus";
+export type { CommandRiskAssessment } from "./CommandRiskAssessment";
+export type { CommandRiskLevel } from "./CommandRiskLevel";
 export type { Config } from "./Config";
 export type { ConfigBatchWriteParams } from "./ConfigBatchWriteParams";
 export type { ConfigEdit } from "./ConfigEdit";
@@ -67,6 +69,7 @@ export type { ExperimentalFeatureListResponse } from "./ExperimentalFeatureListR
 export type { ExperimentalFeatureStage } from "./ExperimentalFeatureStage";
 export type { FeedbackUploadParams } from "./FeedbackUploadParams";
 export type { FeedbackUploadResponse } from "./FeedbackUploadResponse";
+export type { FileAnchor } from "./FileAnchor";
 export type { FileChangeApprovalDecision } from "./FileChangeApprovalDecision";
 export type { FileChangeOutputDeltaNotification } from "./FileChangeOutputDeltaNotification";
 export type { FileChangeRequestApprovalParams } from "./FileChangeRequestApprovalParams";
@@ -98,6 +101,7 @@ export type { MergeStrategy } from "./MergeStrategy";
 export type { Model } from "./Model";
 export type { ModelListParams } from "./ModelListParams";
 export type { ModelListResponse } from "./ModelListResponse";
+export type { ModelRequestUsage } from "./ModelRequestUsage";
 export type { ModelRerouteReason } from "./ModelRerouteReason";
 export type { ModelReroutedNotification } from "./ModelReroutedNotification";
 export type { NetworkAccess } from "./NetworkAccess";
@@ -110,6 +114,7 @@ export type { PatchChangeKind } from "./PatchChangeKind";
 export type { PlanDeltaNotification } from "./PlanDeltaNotification";
 export type { ProductSurface } from "./ProductSurface";
 export type { ProfileV2 } from "./ProfileV2";
+export type { ProviderErrorKind } from "./ProviderErrorKind";
 export type { RateLimitSnapshot } from "./RateLimitSnapshot";
 export type { RateLimitWindow } from "./RateLimitWindow";
 export type { RawResponseItemCompletedNotification } from "./RawResponseItemCompletedNotification";
@@ -3,6 +3,7 @@ use crate::protocol::v2::CollabAgentTool;
 use crate::protocol::v2::CollabAgentToolCallStatus;
 use crate::protocol::v2::CommandAction;
 use crate::protocol::v2::CommandExecutionStatus;
+use crate::protocol::v2::FileAnchor;
 use crate::protocol::v2::FileUpdateChange;
 use crate::protocol::v2::McpToolCallError;
 use crate::protocol::v2::McpToolCallResult;
@@ -32,6 +33,7 @@ use codex_protocol::protocol::McpToolCallBeginEvent;
 use codex_protocol::protocol::McpToolCallEndEvent;
 use codex_protocol::protocol::PatchApplyBeginEvent;
 use codex_protocol::protocol::PatchApplyEndEvent;
+use codex_protocol::protocol::ResponseCandidateSelectedEvent;
 use codex_protocol::protocol::ReviewOutputEvent;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::ThreadRolledBackEvent;
@@ -67,6 +69,9 @@ pub struct ThreadHistoryBuilder {
     turns: Vec<Turn>,
     current_turn: Option<PendingTurn>,
     next_item_index: i64,
+    /// ID of the user message whose `ItemCompleted` was just seen; the legacy
+    /// `UserMessage` event that follows takes it.
+    next_user_message_id: Option<String>,
 }
 
 impl Default for ThreadHistoryBuilder {
@@ -81,6 +86,7 @@ impl ThreadHistoryBuilder {
             turns: Vec::new(),
             current_turn: None,
             next_item_index: 1,
+            next_user_message_id: None,
         }
     }
 
@@ -152,6 +158,9 @@ impl ThreadHistoryBuilder {
             EventMsg::Error(payload) => self.handle_error(payload),
             EventMsg::TokenCount(_) => {}
             EventMsg::ThreadRolledBack(payload) => self.handle_thread_rollback(payload),
+            EventMsg::ResponseCandidateSelected(payload) => {
+                self.handle_response_candidate_selected(payload)
+            }
             EventMsg::UndoCompleted(_) => {}
             EventMsg::TurnAborted(payload) => self.handle_turn_aborted(payload),
             EventMsg::TurnStarted(payload) => self.handle_turn_started(payload),
@@ -184,7 +193,10 @@ impl ThreadHistoryBuilder {
             .current_turn
             .take()
             .unwrap_or_else(|| self.new_turn(None));
-        let id = self.next_item_id();
+        let id = match self.next_user_message_id.take() {
+            Some(id) => id,
+            None => self.next_item_id(),
+        };
         let content = self.build_user_inputs(payload);
         turn.items.push(ThreadItem::UserMessage { id, content });
         self.current_turn = Some(turn);
@@ -271,8 +283,10 @@ impl ThreadHistoryBuilder {
                     ThreadItem::from(payload.item.clone()),
                 );
             }
-            codex_protocol::items::TurnItem::UserMessage(_)
-            | codex_protocol::items::TurnItem::AgentMessage(_)
+            codex_protocol::items::TurnItem::UserMessage(item) => {
+                self.next_user_message_id = Some(item.id.clone());
+            }
+            codex_protocol::items::TurnItem::AgentMessage(_)
             | codex_protocol::items::TurnItem::Reasoning(_)
             | codex_protocol::items::TurnItem::WebSearch(_)
             | codex_protocol::items::TurnItem::ContextCompaction(_) => {}
@@ -360,6 +374,7 @@ impl ThreadHistoryBuilder {
             id: payload.call_id.clone(),
             changes: convert_patch_changes(&payload.changes),
             status: PatchApplyStatus::InProgress,
+            anchors: Vec::new(),
         };
         if payload.turn_id.is_empty() {
             self.upsert_item_in_current_turn(item);
@@ -374,6 +389,12 @@ impl ThreadHistoryBuilder {
             id: payload.call_id.clone(),
             changes: convert_patch_changes(&payload.changes),
             status,
+            anchors: payload
+                .anchors
+                .iter()
+                .cloned()
+                .map(FileAnchor::from)
+                .collect(),
         };
         if payload.turn_id.is_empty() {
             self.upsert_item_in_current_turn(item);
@@ -799,6 +820,20 @@ impl ThreadHistoryBuilder {
         self.next_item_index = i64::try_from(item_count.saturating_add(1)).unwrap_or(i64::MAX);
     }
 
+    /// Shows the selected candidate in place of the response it replaced.
+    fn handle_response_candidate_selected(&mut self, payload: &ResponseCandidateSelectedEvent) {
+        self.finish_current_turn();
+        if let Some(ThreadItem::AgentMessage { text, .. }) =
+            self.turns.last_mut().and_then(|turn| {
+                turn.items
+                    .iter_mut()
+                    .rfind(|item| matches!(item, ThreadItem::AgentMessage { .. }))
+            })
+        {
+            *text = payload.message.clone();
+        }
+    }
+
     fn finish_current_turn(&mut self) {
         if let Some(turn) = self.current_turn.take() {
             if turn.items.is_empty() && !turn.opened_explicitly && !turn.saw_compaction {
@@ -1176,6 +1211,42 @@ mod tests {
         );
     }
 
+    #[test]
+    fn user_messages_keep_their_recorded_item_id() {
+        let events = vec![
+            EventMsg::ItemCompleted(ItemCompletedEvent {
+                thread_id: ThreadId::new(),
+                turn_id: "turn-1".to_string(),
+                item: CoreTurnItem::UserMessage(CoreUserMessageItem {
+                    id: "user-item-id".to_string(),
+                    content: Vec::new(),
+                }),
+            }),
+            EventMsg::UserMessage(UserMessageEvent {
+                message: "hello".into(),
+                images: None,
+                text_elements: Vec::new(),
+                local_images: Vec::new(),
+            }),
+        ];
+
+        let items = events
+            .into_iter()
+            .map(RolloutItem::EventMsg)
+            .collect::<Vec<_>>();
+        let turns = build_turns_from_rollout_items(&items);
+        assert_eq!(
+            turns[0].items,
+            vec![ThreadItem::UserMessage {
+                id: "user-item-id".into(),
+                content: vec![UserInput::Text {
+                    text: "hello".into(),
+                    text_elements: Vec::new(),
+                }],
+            }]
+        );
+    }
+
     #[test]
     fn preserves_agent_message_phase_in_history() {
         let events = vec![EventMsg::AgentMessage(AgentMessageEvent {
@@ -1652,6 +1723,7 @@ mod tests {
                 .into_iter()
                 .collect(),
                 status: CorePatchApplyStatus::Declined,
+                anchors: Vec::new(),
             }),
         ];
 
@@ -1688,6 +1760,7 @@ mod tests {
                     diff: "hello\n".into(),
                 }],
                 status: PatchApplyStatus::Declined,
+                anchors: Vec::new(),
             }
         );
     }
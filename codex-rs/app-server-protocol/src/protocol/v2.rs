@@ -4,6 +4,8 @@ use std::path::PathBuf;
 use crate::protocol::common::AuthMode;
 use codex_experimental_api_macros::ExperimentalApi;
 use codex_protocol::account::PlanType;
+use codex_protocol::approvals::CommandRiskAssessment as CoreCommandRiskAssessment;
+use codex_protocol::approvals::CommandRiskLevel as CoreCommandRiskLevel;
 use codex_protocol::approvals::ExecPolicyAmendment as CoreExecPolicyAmendment;
 use codex_protocol::approvals::NetworkApprovalContext as CoreNetworkApprovalContext;
 use codex_protocol::approvals::NetworkApprovalProtocol as CoreNetworkApprovalProtocol;
@@ -33,6 +35,8 @@ use codex_protocol::protocol::AskForApproval as CoreAskForApproval;
 use codex_protocol::protocol::CodexErrorInfo as CoreCodexErrorInfo;
 use codex_protocol::protocol::CreditsSnapshot as CoreCreditsSnapshot;
 use codex_protocol::protocol::ExecCommandStatus as CoreExecCommandStatus;
+use codex_protocol::protocol::FileAnchor as CoreFileAnchor;
+use codex_protocol::protocol::ModelRequestUsage as CoreModelRequestUsage;
 use codex_protocol::protocol::ModelRerouteReason as CoreModelRerouteReason;
 use codex_protocol::protocol::NetworkAccess as CoreNetworkAccess;
 use codex_protocol::protocol::PatchApplyStatus as CorePatchApplyStatus;
@@ -125,6 +129,22 @@ pub enum CodexErrorInfo {
         #[ts(rename = "httpStatusCode")]
         http_status_code: Option<u16>,
     },
+    TaskLimitReached,
+    /// The session is offline, so no model request was made.
+    Offline,
+    /// The model provider rejected the request for a reason the user can act on.
+    ProviderError {
+        kind: ProviderErrorKind,
+        #[serde(rename = "httpStatusCode")]
+        #[ts(rename = "httpStatusCode")]
+        http_status_code: Option<u16>,
+        /// Delay the provider asked for before retrying, from `Retry-After`.
+        #[serde(rename = "retryAfterSeconds")]
+        #[ts(rename = "retryAfterSeconds")]
+        retry_after_seconds: Option<u64>,
+        /// What the user can do about it.
+        remediation: String,
+    },
     Other,
 }
 
@@ -151,6 +171,19 @@ impl From<CoreCodexErrorInfo> for CodexErrorInfo {
             CoreCodexErrorInfo::ResponseTooManyFailedAttempts { http_status_code } => {
                 CodexErrorInfo::ResponseTooManyFailedAttempts { http_status_code }
             }
+            CoreCodexErrorInfo::TaskLimitReached => CodexErrorInfo::TaskLimitReached,
+            CoreCodexErrorInfo::Offline => CodexErrorInfo::Offline,
+            CoreCodexErrorInfo::ProviderError {
+                kind,
+                http_status_code,
+                retry_after_seconds,
+                remediation,
+            } => CodexErrorInfo::ProviderError {
+                kind: kind.into(),
+                http_status_code,
+                retry_after_seconds,
+                remediation,
+            },
             CoreCodexErrorInfo::Other => CodexErrorInfo::Other,
         }
     }
@@ -259,6 +292,15 @@ v2_enum_from_core!(
     }
 );
 
+v2_enum_from_core!(
+    pub enum ProviderErrorKind from codex_protocol::protocol::ProviderErrorKind {
+        Unauthorized,
+        OrganizationVerificationRequired,
+        RateLimited,
+        ServerError
+    }
+);
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
 #[ts(tag = "type")]
@@ -713,6 +755,41 @@ impl From<CoreNetworkApprovalContext> for NetworkApprovalContext {
     }
 }
 
+v2_enum_from_core! {
+    pub enum CommandRiskLevel from CoreCommandRiskLevel {
+        Low,
+        Medium,
+        High,
+    }
+}
+
+/// Best-effort explanation of a command awaiting approval, computed locally
+/// and optionally refined by the `command_risk_model`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct CommandRiskAssessment {
+    /// Short human-readable description of what the command does.
+    pub summary: String,
+    pub level: CommandRiskLevel,
+    /// Whether the command deletes or overwrites existing data.
+    pub destructive: bool,
+    /// Paths the command reads or writes, as written in the command (relative
+    /// paths are relative to the approval request's `cwd`).
+    pub paths: Vec<String>,
+}
+
+impl From<CoreCommandRiskAssessment> for CommandRiskAssessment {
+    fn from(value: CoreCommandRiskAssessment) -> Self {
+        Self {
+            summary: value.summary,
+            level: value.level.into(),
+            destructive: value.destructive,
+            paths: value.paths,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
 #[serde(rename_all = "camelCase")]
 #[ts(export_to = "v2/")]
@@ -2139,6 +2216,9 @@ pub struct SkillToolDependency {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub version: Option<String>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2222,6 +2302,7 @@ impl From<CoreSkillToolDependency> for SkillToolDependency {
             transport: value.transport,
             command: value.command,
             url: value.url,
+            version: value.version,
         }
     }
 }
@@ -2300,6 +2381,29 @@ pub struct ThreadTokenUsageUpdatedNotification {
     pub thread_id: String,
     pub turn_id: String,
     pub token_usage: ThreadTokenUsage,
+    /// The model request `tokenUsage.last` belongs to, when the update
+    /// follows one.
+    pub request: Option<ModelRequestUsage>,
+}
+
+/// A model request within a turn.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct ModelRequestUsage {
+    /// 1-based position of the request in its turn; tool-call follow-ups
+    /// count as new requests.
+    pub index: u32,
+    pub model: String,
+}
+
+impl From<CoreModelRequestUsage> for ModelRequestUsage {
+    fn from(value: CoreModelRequestUsage) -> Self {
+        Self {
+            index: value.index,
+            model: value.model,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -2489,6 +2593,11 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// Review the changes in a pull request, identified by its URL.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PullRequest { url: String },
+
     /// Arbitrary instructions, equivalent to the old free-form prompt.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -2725,6 +2834,8 @@ pub enum ThreadItem {
         id: String,
         changes: Vec<FileUpdateChange>,
         status: PatchApplyStatus,
+        /// The lines the change wrote, once it has been applied.
+        anchors: Vec<FileAnchor>,
     },
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -2925,6 +3036,27 @@ pub struct FileUpdateChange {
     pub diff: String,
 }
 
+/// A span of lines in a file, 1-based and inclusive, that clients can jump
+/// to or decorate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "camelCase")]
+#[ts(export_to = "v2/")]
+pub struct FileAnchor {
+    pub path: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
+}
+
+impl From<CoreFileAnchor> for FileAnchor {
+    fn from(value: CoreFileAnchor) -> Self {
+        Self {
+            path: value.path,
+            start_line: value.start_line,
+            end_line: value.end_line,
+        }
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
 #[serde(tag = "type", rename_all = "camelCase")]
 #[ts(tag = "type")]
@@ -3125,6 +3257,9 @@ pub struct TurnDiffUpdatedNotification {
     pub thread_id: String,
     pub turn_id: String,
     pub diff: String,
+    /// Where the lines written by the turn's file changes are now, after any
+    /// later edits in the turn moved them.
+    pub anchors: Vec<FileAnchor>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
@@ -3400,6 +3535,10 @@ pub struct CommandExecutionRequestApprovalParams {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional = nullable)]
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
+    /// Local risk classification of the command, computed before the request
+    /// is sent.
+    #[ts(optional = nullable)]
+    pub risk_assessment: Option<CommandRiskAssessment>,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema, TS)]
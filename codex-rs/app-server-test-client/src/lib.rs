@@ -1496,6 +1496,7 @@ impl CodexClient {
             cwd,
             command_actions,
             proposed_execpolicy_amendment,
+            risk_assessment,
         } = params;
 
         println!(
@@ -1524,6 +1525,9 @@ impl CodexClient {
         if let Some(execpolicy_amendment) = proposed_execpolicy_amendment.as_ref() {
             println!("< proposed execpolicy amendment: {execpolicy_amendment:?}");
         }
+        if let Some(risk_assessment) = risk_assessment.as_ref() {
+            println!("< risk assessment: {risk_assessment:?}");
+        }
 
         let decision = match self.command_approval_behavior {
             CommandApprovalBehavior::AlwaysAccept => CommandExecutionApprovalDecision::Accept,
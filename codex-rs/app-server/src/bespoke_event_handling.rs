@@ -24,6 +24,7 @@ use codex_app_server_protocol::CommandExecutionOutputDeltaNotification;
 use codex_app_server_protocol::CommandExecutionRequestApprovalParams;
 use codex_app_server_protocol::CommandExecutionRequestApprovalResponse;
 use codex_app_server_protocol::CommandExecutionStatus;
+use codex_app_server_protocol::CommandRiskAssessment as V2CommandRiskAssessment;
 use codex_app_server_protocol::ContextCompactedNotification;
 use codex_app_server_protocol::DeprecationNoticeNotification;
 use codex_app_server_protocol::DynamicToolCallParams;
@@ -31,6 +32,7 @@ use codex_app_server_protocol::ErrorNotification;
 use codex_app_server_protocol::ExecCommandApprovalParams;
 use codex_app_server_protocol::ExecCommandApprovalResponse;
 use codex_app_server_protocol::ExecPolicyAmendment as V2ExecPolicyAmendment;
+use codex_app_server_protocol::FileAnchor as V2FileAnchor;
 use codex_app_server_protocol::FileChangeApprovalDecision;
 use codex_app_server_protocol::FileChangeOutputDeltaNotification;
 use codex_app_server_protocol::FileChangeRequestApprovalParams;
@@ -43,6 +45,7 @@ use codex_app_server_protocol::JSONRPCErrorError;
 use codex_app_server_protocol::McpToolCallError;
 use codex_app_server_protocol::McpToolCallResult;
 use codex_app_server_protocol::McpToolCallStatus;
+use codex_app_server_protocol::ModelRequestUsage;
 use codex_app_server_protocol::ModelReroutedNotification;
 use codex_app_server_protocol::NetworkApprovalContext as V2NetworkApprovalContext;
 use codex_app_server_protocol::PatchApplyStatus;
@@ -211,6 +214,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                             id: item_id.clone(),
                             changes: patch_changes.clone(),
                             status: PatchApplyStatus::InProgress,
+                            anchors: Vec::new(),
                         };
                         let notification = ItemStartedNotification {
                             thread_id: conversation_id.to_string(),
@@ -264,6 +268,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                 network_approval_context,
                 proposed_execpolicy_amendment,
                 parsed_cmd,
+                risk_assessment,
                 ..
             } = ev;
             match api_version {
@@ -337,6 +342,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                         cwd,
                         command_actions,
                         proposed_execpolicy_amendment: proposed_execpolicy_amendment_v2,
+                        risk_assessment: risk_assessment.map(V2CommandRiskAssessment::from),
                     };
                     let rx = outgoing
                         .send_request(ServerRequestPayload::CommandExecutionRequestApproval(
@@ -992,6 +998,7 @@ pub(crate) async fn apply_bespoke_event_handling(
                     id: item_id.clone(),
                     changes: convert_patch_changes(&patch_begin_event.changes),
                     status: PatchApplyStatus::InProgress,
+                    anchors: Vec::new(),
                 };
                 let notification = ItemStartedNotification {
                     thread_id: conversation_id.to_string(),
@@ -1010,11 +1017,17 @@ pub(crate) async fn apply_bespoke_event_handling(
 
             let status: PatchApplyStatus = (&patch_end_event.status).into();
             let changes = convert_patch_changes(&patch_end_event.changes);
+            let anchors = patch_end_event
+                .anchors
+                .into_iter()
+                .map(V2FileAnchor::from)
+                .collect();
             complete_file_change_item(
                 conversation_id,
                 item_id,
                 changes,
                 status,
+                anchors,
                 event_turn_id.clone(),
                 &outgoing,
                 &thread_state,
@@ -1324,6 +1337,11 @@ async fn handle_turn_diff(
             thread_id: conversation_id.to_string(),
             turn_id: event_turn_id.to_string(),
             diff: turn_diff_event.unified_diff,
+            anchors: turn_diff_event
+                .anchors
+                .into_iter()
+                .map(V2FileAnchor::from)
+                .collect(),
         };
         outgoing
             .send_server_notification(ServerNotification::TurnDiffUpdated(notification))
@@ -1377,11 +1395,13 @@ async fn emit_turn_completed_with_status(
         .await;
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn complete_file_change_item(
     conversation_id: ThreadId,
     item_id: String,
     changes: Vec<FileUpdateChange>,
     status: PatchApplyStatus,
+    anchors: Vec<V2FileAnchor>,
     turn_id: String,
     outgoing: &ThreadScopedOutgoingMessageSender,
     thread_state: &Arc<Mutex<ThreadState>>,
@@ -1394,6 +1414,7 @@ async fn complete_file_change_item(
         id: item_id,
         changes,
         status,
+        anchors,
     };
     let notification = ItemCompletedNotification {
         thread_id: conversation_id.to_string(),
@@ -1529,12 +1550,17 @@ async fn handle_token_count_event(
     token_count_event: TokenCountEvent,
     outgoing: &ThreadScopedOutgoingMessageSender,
 ) {
-    let TokenCountEvent { info, rate_limits } = token_count_event;
+    let TokenCountEvent {
+        info,
+        rate_limits,
+        request,
+    } = token_count_event;
     if let Some(token_usage) = info.map(ThreadTokenUsage::from) {
         let notification = ThreadTokenUsageUpdatedNotification {
             thread_id: conversation_id.to_string(),
             turn_id,
             token_usage,
+            request: request.map(ModelRequestUsage::from),
         };
         outgoing
             .send_server_notification(ServerNotification::ThreadTokenUsageUpdated(notification))
@@ -1817,6 +1843,7 @@ async fn on_file_change_request_approval_response(
             item_id.clone(),
             changes,
             status,
+            Vec::new(),
             event_turn_id.clone(),
             &outgoing,
             &thread_state,
@@ -2066,7 +2093,9 @@ mod tests {
     use codex_protocol::protocol::CollabResumeBeginEvent;
     use codex_protocol::protocol::CollabResumeEndEvent;
     use codex_protocol::protocol::CreditsSnapshot;
+    use codex_protocol::protocol::FileAnchor as CoreFileAnchor;
     use codex_protocol::protocol::McpInvocation;
+    use codex_protocol::protocol::ModelRequestUsage as CoreModelRequestUsage;
     use codex_protocol::protocol::RateLimitSnapshot;
     use codex_protocol::protocol::RateLimitWindow;
     use codex_protocol::protocol::TokenUsage;
@@ -2397,6 +2426,10 @@ mod tests {
             TokenCountEvent {
                 info: Some(info),
                 rate_limits: Some(rate_limits),
+                request: Some(CoreModelRequestUsage {
+                    index: 2,
+                    model: "gpt-5".to_string(),
+                }),
             },
             &outgoing,
         )
@@ -2414,6 +2447,13 @@ mod tests {
                 assert_eq!(usage.total.cached_input_tokens, 25);
                 assert_eq!(usage.last.output_tokens, 7);
                 assert_eq!(usage.model_context_window, Some(4096));
+                assert_eq!(
+                    payload.request,
+                    Some(ModelRequestUsage {
+                        index: 2,
+                        model: "gpt-5".to_string(),
+                    })
+                );
             }
             other => bail!("unexpected notification: {other:?}"),
         }
@@ -2447,6 +2487,7 @@ mod tests {
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                request: None,
             },
             &outgoing,
         )
@@ -2738,6 +2779,11 @@ mod tests {
             "turn-1",
             TurnDiffEvent {
                 unified_diff: unified_diff.clone(),
+                anchors: vec![CoreFileAnchor {
+                    path: PathBuf::from("src/lib.rs"),
+                    start_line: 3,
+                    end_line: 5,
+                }],
             },
             ApiVersion::V2,
             &outgoing,
@@ -2752,6 +2798,14 @@ mod tests {
                 assert_eq!(notification.thread_id, conversation_id.to_string());
                 assert_eq!(notification.turn_id, "turn-1");
                 assert_eq!(notification.diff, unified_diff);
+                assert_eq!(
+                    notification.anchors,
+                    vec![V2FileAnchor {
+                        path: PathBuf::from("src/lib.rs"),
+                        start_line: 3,
+                        end_line: 5,
+                    }]
+                );
             }
             other => bail!("unexpected message: {other:?}"),
         }
@@ -2771,6 +2825,7 @@ mod tests {
             "turn-1",
             TurnDiffEvent {
                 unified_diff: "diff".to_string(),
+                anchors: Vec::new(),
             },
             ApiVersion::V1,
             &outgoing,
@@ -495,6 +495,13 @@ impl CodexMessageProcessor {
                     .filter(|t| !t.is_empty());
                 ApiReviewTarget::Commit { sha, title }
             }
+            ApiReviewTarget::PullRequest { url } => {
+                let url = url.trim().to_string();
+                if url.is_empty() {
+                    return Err(invalid_request("url must not be empty".to_string()));
+                }
+                ApiReviewTarget::PullRequest { url }
+            }
             ApiReviewTarget::Custom { instructions } => {
                 let trimmed = instructions.trim().to_string();
                 if trimmed.is_empty() {
@@ -512,6 +519,7 @@ impl CodexMessageProcessor {
             ApiReviewTarget::UncommittedChanges => CoreReviewTarget::UncommittedChanges,
             ApiReviewTarget::BaseBranch { branch } => CoreReviewTarget::BaseBranch { branch },
             ApiReviewTarget::Commit { sha, title } => CoreReviewTarget::Commit { sha, title },
+            ApiReviewTarget::PullRequest { url } => CoreReviewTarget::PullRequest { url },
             ApiReviewTarget::Custom { instructions } => CoreReviewTarget::Custom { instructions },
         };
 
@@ -4896,6 +4904,7 @@ impl CodexMessageProcessor {
                 final_output_json_schema: output_schema,
                 collaboration_mode: None,
                 personality: None,
+                sampling: None,
             })
             .await;
 
@@ -5872,6 +5881,7 @@ impl CodexMessageProcessor {
         let fallback_model_provider = self.config.model_provider_id.clone();
         let single_client_mode = self.single_client_mode;
         let codex_home = self.config.codex_home.clone();
+        let annotate_events = self.config.annotate_events;
         tokio::spawn(async move {
             loop {
                 tokio::select! {
@@ -5912,6 +5922,14 @@ impl CodexMessageProcessor {
                             "conversationId".to_string(),
                             conversation_id.to_string().into(),
                         );
+                        if annotate_events {
+                            let annotations = event.msg.annotations();
+                            if !annotations.is_empty()
+                                && let Ok(annotations) = serde_json::to_value(annotations)
+                            {
+                                params.insert("annotations".to_string(), annotations);
+                            }
+                        }
                         let (subscribed_connection_ids, raw_events_enabled) = {
                             let mut thread_state = thread_state.lock().await;
                             if !single_client_mode {
@@ -6541,6 +6559,7 @@ fn skills_to_info(
                                 transport: tool.transport,
                                 command: tool.command,
                                 url: tool.url,
+                                version: tool.version,
                             })
                             .collect(),
                     }
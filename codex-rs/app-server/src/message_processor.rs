@@ -44,6 +44,7 @@ use codex_core::config_loader::LoaderOverrides;
 use codex_core::default_client::SetOriginatorError;
 use codex_core::default_client::USER_AGENT_SUFFIX;
 use codex_core::default_client::get_codex_user_agent;
+use codex_core::default_client::set_default_client_http_network_config;
 use codex_core::default_client::set_default_client_residency_requirement;
 use codex_core::default_client::set_default_originator;
 use codex_feedback::CodexFeedback;
@@ -305,6 +306,9 @@ impl MessageProcessor {
                         }
                     }
                     set_default_client_residency_requirement(self.config.enforce_residency.value());
+                    if let Err(err) = set_default_client_http_network_config(&self.config.http) {
+                        tracing::warn!("failed to apply [http] settings: {err}");
+                    }
                     let user_agent_suffix = format!("{name}; {version}");
                     if let Ok(mut suffix) = USER_AGENT_SUFFIX.lock() {
                         *suffix = Some(user_agent_suffix);
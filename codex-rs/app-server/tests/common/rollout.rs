@@ -84,6 +84,8 @@ pub fn create_fake_rollout_with_source(
         model_provider: model_provider.map(str::to_string),
         base_instructions: None,
         dynamic_tools: None,
+        instruction_layers: None,
+        experiments: None,
     };
     let payload = serde_json::to_value(SessionMetaLine {
         meta,
@@ -165,6 +167,8 @@ pub fn create_fake_rollout_with_text_elements(
         model_provider: model_provider.map(str::to_string),
         base_instructions: None,
         dynamic_tools: None,
+        instruction_layers: None,
+        experiments: None,
     };
     let payload = serde_json::to_value(SessionMetaLine {
         meta,
@@ -1280,12 +1280,14 @@ async fn turn_start_file_change_approval_v2() -> Result<()> {
         ref id,
         status,
         ref changes,
+        ref anchors,
     } = started_file_change
     else {
         unreachable!("loop ensures we break on file change items");
     };
     assert_eq!(id, "patch-call");
     assert_eq!(status, PatchApplyStatus::InProgress);
+    assert!(anchors.is_empty());
     let started_changes = changes.clone();
 
     let server_req = timeout(
@@ -1643,12 +1645,14 @@ async fn turn_start_file_change_approval_decline_v2() -> Result<()> {
         ref id,
         status,
         ref changes,
+        ref anchors,
     } = started_file_change
     else {
         unreachable!("loop ensures we break on file change items");
     };
     assert_eq!(id, "patch-call");
     assert_eq!(status, PatchApplyStatus::InProgress);
+    assert!(anchors.is_empty());
     let started_changes = changes.clone();
 
     let server_req = timeout(
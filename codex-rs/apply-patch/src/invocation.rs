@@ -18,7 +18,7 @@ use crate::MaybeApplyPatchVerified;
 use crate::parser::Hunk;
 use crate::parser::ParseError;
 use crate::parser::parse_patch;
-use crate::unified_diff_from_chunks;
+use crate::unified_diff_from_chunks_with_context;
 use std::str::Utf8Error;
 use tree_sitter::LanguageError;
 
@@ -128,8 +128,13 @@ pub fn maybe_parse_apply_patch(argv: &[String]) -> MaybeApplyPatch {
 }
 
 /// cwd must be an absolute path so that we can resolve relative paths in the
-/// patch.
-pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApplyPatchVerified {
+/// patch. Drifted hunk context is re-anchored within `fuzz_window` lines, as
+/// [`crate::apply_patch`] would.
+pub fn maybe_parse_apply_patch_verified(
+    argv: &[String],
+    cwd: &Path,
+    fuzz_window: usize,
+) -> MaybeApplyPatchVerified {
     // Detect a raw patch body passed directly as the command or as the body of a shell
     // script. In these cases, report an explicit error rather than applying the patch.
     if let [body] = argv
@@ -187,7 +192,12 @@ pub fn maybe_parse_apply_patch_verified(argv: &[String], cwd: &Path) -> MaybeApp
                         let ApplyPatchFileUpdate {
                             unified_diff,
                             content: contents,
-                        } = match unified_diff_from_chunks(&path, &chunks) {
+                        } = match unified_diff_from_chunks_with_context(
+                            &path,
+                            &chunks,
+                            1,
+                            fuzz_window,
+                        ) {
                             Ok(diff) => diff,
                             Err(e) => {
                                 return MaybeApplyPatchVerified::CorrectnessError(e);
@@ -371,6 +381,7 @@ fn extract_apply_patch_from_bash(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::unified_diff_from_chunks;
     use assert_matches::assert_matches;
     use pretty_assertions::assert_eq;
     use std::fs;
@@ -456,7 +467,7 @@ mod tests {
         let args = vec![patch];
         let dir = tempdir().unwrap();
         assert_matches!(
-            maybe_parse_apply_patch_verified(&args, dir.path()),
+            maybe_parse_apply_patch_verified(&args, dir.path(), 0),
             MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::ImplicitInvocation)
         );
     }
@@ -467,7 +478,7 @@ mod tests {
         let args = args_bash(script);
         let dir = tempdir().unwrap();
         assert_matches!(
-            maybe_parse_apply_patch_verified(&args, dir.path()),
+            maybe_parse_apply_patch_verified(&args, dir.path(), 0),
             MaybeApplyPatchVerified::CorrectnessError(ApplyPatchError::ImplicitInvocation)
         );
     }
@@ -739,7 +750,7 @@ PATCH"#,
                 .to_string(),
         ];
 
-        let result = maybe_parse_apply_patch_verified(&argv, session_dir.path());
+        let result = maybe_parse_apply_patch_verified(&argv, session_dir.path(), 0);
 
         // Verify the patch contents - as otherwise we may have pulled contents
         // from the wrong file (as we're using relative paths)
@@ -787,7 +798,7 @@ PATCH"#,
         let shell_script = format!("cd {worktree_rel} && apply_patch <<'PATCH'\n{patch}\nPATCH");
         let argv = vec!["bash".into(), "-lc".into(), shell_script];
 
-        let result = maybe_parse_apply_patch_verified(&argv, session_dir.path());
+        let result = maybe_parse_apply_patch_verified(&argv, session_dir.path(), 0);
         let action = match result {
             MaybeApplyPatchVerified::Body(action) => action,
             other => panic!("expected verified body, got {other:?}"),
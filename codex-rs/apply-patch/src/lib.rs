@@ -17,6 +17,8 @@ pub use parser::parse_patch;
 use similar::TextDiff;
 use thiserror::Error;
 
+pub use invocation::MaybeApplyPatch;
+pub use invocation::maybe_parse_apply_patch;
 pub use invocation::maybe_parse_apply_patch_verified;
 pub use standalone_executable::main;
 
@@ -34,6 +36,19 @@ pub const APPLY_PATCH_TOOL_INSTRUCTIONS: &str = include_str!("../apply_patch_too
 /// dispatcher.
 pub const CODEX_CORE_APPLY_PATCH_ARG1: &str = "--codex-run-as-apply-patch";
 
+/// Environment variable through which the Codex executable hands the fuzz
+/// window to its `apply_patch` self-invocation. Unset means `0`.
+pub const CODEX_APPLY_PATCH_FUZZ_WINDOW_ENV_VAR: &str = "CODEX_APPLY_PATCH_FUZZ_WINDOW";
+
+/// The fuzz window handed to this process through
+/// [`CODEX_APPLY_PATCH_FUZZ_WINDOW_ENV_VAR`], for executable entry points.
+pub fn fuzz_window_from_env() -> usize {
+    std::env::var(CODEX_APPLY_PATCH_FUZZ_WINDOW_ENV_VAR)
+        .ok()
+        .and_then(|window| window.trim().parse().ok())
+        .unwrap_or(0)
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum ApplyPatchError {
     #[error(transparent)]
@@ -180,8 +195,12 @@ impl ApplyPatchAction {
 }
 
 /// Applies the patch and prints the result to stdout/stderr.
+///
+/// `fuzz_window` is how many lines past where a hunk was expected its drifted
+/// context may be re-anchored; `0` turns re-anchoring off.
 pub fn apply_patch(
     patch: &str,
+    fuzz_window: usize,
     stdout: &mut impl std::io::Write,
     stderr: &mut impl std::io::Write,
 ) -> Result<(), ApplyPatchError> {
@@ -207,7 +226,7 @@ pub fn apply_patch(
         }
     };
 
-    apply_hunks(&hunks, stdout, stderr)?;
+    apply_hunks(&hunks, fuzz_window, stdout, stderr)?;
 
     Ok(())
 }
@@ -215,6 +234,7 @@ pub fn apply_patch(
 /// Applies hunks and continues to update stdout/stderr
 pub fn apply_hunks(
     hunks: &[Hunk],
+    fuzz_window: usize,
     stdout: &mut impl std::io::Write,
     stderr: &mut impl std::io::Write,
 ) -> Result<(), ApplyPatchError> {
@@ -245,7 +265,7 @@ pub fn apply_hunks(
         .collect::<Vec<&Path>>();
 
     // Delegate to a helper that applies each hunk to the filesystem.
-    match apply_hunks_to_files(hunks) {
+    match apply_hunks_to_files(hunks, fuzz_window) {
         Ok(affected) => {
             print_summary(&affected, stdout).map_err(ApplyPatchError::from)?;
             Ok(())
@@ -272,35 +292,52 @@ pub struct AffectedPaths {
     pub added: Vec<PathBuf>,
     pub modified: Vec<PathBuf>,
     pub deleted: Vec<PathBuf>,
+    pub reanchored: Vec<ReanchoredHunk>,
+}
+
+/// A hunk whose context lines did not match the file exactly and that was
+/// applied where the file's lines were most similar.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReanchoredHunk {
+    pub path: PathBuf,
+    /// 1-based line of the original file where the hunk was applied.
+    pub line: usize,
+    /// How similar the file's lines were to the hunk's context (1.0 is identical).
+    pub similarity: f64,
 }
 
 /// Apply the hunks to the filesystem, returning which files were added, modified, or deleted.
-/// Returns an error if the patch could not be applied.
-fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
+/// Returns an error if the patch could not be applied, in which case no file is changed.
+///
+/// Every hunk is checked and the resulting contents computed before anything is written, so a
+/// hunk that does not apply fails the whole patch up front. The new contents are then written to
+/// temporary files and renamed into place; if a write still fails, the files already changed are
+/// restored. Directories created for new files are left behind.
+fn apply_hunks_to_files(hunks: &[Hunk], fuzz_window: usize) -> anyhow::Result<AffectedPaths> {
     if hunks.is_empty() {
         anyhow::bail!("No files were modified.");
     }
 
+    let mut staged = StagedFiles::default();
     let mut added: Vec<PathBuf> = Vec::new();
     let mut modified: Vec<PathBuf> = Vec::new();
     let mut deleted: Vec<PathBuf> = Vec::new();
+    let mut reanchored: Vec<ReanchoredHunk> = Vec::new();
     for hunk in hunks {
         match hunk {
             Hunk::AddFile { path, contents } => {
-                if let Some(parent) = path.parent()
-                    && !parent.as_os_str().is_empty()
-                {
-                    std::fs::create_dir_all(parent).with_context(|| {
-                        format!("Failed to create parent directories for {}", path.display())
-                    })?;
-                }
-                std::fs::write(path, contents)
-                    .with_context(|| format!("Failed to write file {}", path.display()))?;
+                staged.stage(path, Some(contents.clone()));
                 added.push(path.clone());
             }
             Hunk::DeleteFile { path } => {
-                std::fs::remove_file(path)
-                    .with_context(|| format!("Failed to delete file {}", path.display()))?;
+                if !matches!(staged.contents.get(path), Some(Some(_))) {
+                    let metadata = std::fs::metadata(path)
+                        .with_context(|| format!("Failed to delete file {}", path.display()))?;
+                    if !metadata.is_file() {
+                        anyhow::bail!("Failed to delete file {}: not a file", path.display());
+                    }
+                }
+                staged.stage(path, None);
                 deleted.push(path.clone());
             }
             Hunk::UpdateFile {
@@ -308,36 +345,139 @@ fn apply_hunks_to_files(hunks: &[Hunk]) -> anyhow::Result<AffectedPaths> {
                 move_path,
                 chunks,
             } => {
-                let AppliedPatch { new_contents, .. } =
-                    derive_new_contents_from_chunks(path, chunks)?;
+                let original_contents = staged.read(path)?;
+                let new_contents = apply_chunks_reanchoring(
+                    path,
+                    &original_contents,
+                    chunks,
+                    fuzz_window,
+                    &mut reanchored,
+                )?;
                 if let Some(dest) = move_path {
-                    if let Some(parent) = dest.parent()
-                        && !parent.as_os_str().is_empty()
-                    {
-                        std::fs::create_dir_all(parent).with_context(|| {
-                            format!("Failed to create parent directories for {}", dest.display())
-                        })?;
-                    }
-                    std::fs::write(dest, new_contents)
-                        .with_context(|| format!("Failed to write file {}", dest.display()))?;
-                    std::fs::remove_file(path)
-                        .with_context(|| format!("Failed to remove original {}", path.display()))?;
+                    staged.stage(dest, Some(new_contents));
+                    staged.stage(path, None);
                     modified.push(dest.clone());
                 } else {
-                    std::fs::write(path, new_contents)
-                        .with_context(|| format!("Failed to write file {}", path.display()))?;
+                    staged.stage(path, Some(new_contents));
                     modified.push(path.clone());
                 }
             }
         }
     }
+    staged.commit()?;
     Ok(AffectedPaths {
         added,
         modified,
         deleted,
+        reanchored,
     })
 }
 
+/// The contents a patch leaves each file it touches with, in the order the files were first
+/// touched. `None` marks a deleted file.
+#[derive(Default)]
+struct StagedFiles {
+    order: Vec<PathBuf>,
+    contents: HashMap<PathBuf, Option<String>>,
+}
+
+impl StagedFiles {
+    fn stage(&mut self, path: &Path, contents: Option<String>) {
+        if self.contents.insert(path.to_path_buf(), contents).is_none() {
+            self.order.push(path.to_path_buf());
+        }
+    }
+
+    /// The contents of `path` once the hunks staged so far are applied.
+    fn read(&self, path: &Path) -> std::result::Result<String, ApplyPatchError> {
+        let contents = match self.contents.get(path) {
+            Some(Some(contents)) => return Ok(contents.clone()),
+            Some(None) => Err(std::io::Error::from(std::io::ErrorKind::NotFound)),
+            None => std::fs::read_to_string(path),
+        };
+        contents.map_err(|err| {
+            ApplyPatchError::IoError(IoError {
+                context: format!("Failed to read file to update {}", path.display()),
+                source: err,
+            })
+        })
+    }
+
+    /// Writes the staged files, restoring the ones already written if one fails.
+    fn commit(self) -> anyhow::Result<()> {
+        let mut committed: Vec<(PathBuf, Option<Vec<u8>>)> = Vec::new();
+        for path in self.order {
+            let original = std::fs::read(&path).ok();
+            let result = match &self.contents[&path] {
+                Some(contents) => write_file_atomically(&path, contents),
+                None => std::fs::remove_file(&path)
+                    .or_else(|err| {
+                        // A file added and deleted by the same patch never existed.
+                        if err.kind() == std::io::ErrorKind::NotFound {
+                            Ok(())
+                        } else {
+                            Err(err)
+                        }
+                    })
+                    .with_context(|| format!("Failed to delete file {}", path.display())),
+            };
+            match result {
+                Ok(()) => committed.push((path, original)),
+                Err(err) => {
+                    for (path, original) in committed.into_iter().rev() {
+                        let _ = match original {
+                            Some(original) => std::fs::write(&path, original),
+                            None => std::fs::remove_file(&path),
+                        };
+                    }
+                    return Err(err);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes `contents` to a temporary file next to `path` and renames it into place, so `path`
+/// never holds partial contents. A symlink is followed and its target replaced.
+fn write_file_atomically(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let path = match std::fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.file_type().is_symlink() => std::fs::canonicalize(path)
+            .with_context(|| format!("Failed to write file {}", path.display()))?,
+        _ => path.to_path_buf(),
+    };
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        std::fs::create_dir_all(parent).with_context(|| {
+            format!("Failed to create parent directories for {}", path.display())
+        })?;
+    }
+    let Some(file_name) = path.file_name() else {
+        anyhow::bail!("Failed to write file {}: not a file path", path.display());
+    };
+    let temp_path =
+        path.with_file_name(format!(".{}.apply_patch.tmp", file_name.to_string_lossy()));
+    // Renaming over a file only needs the directory to be writable, so check that the file
+    // itself may be written first.
+    let existing = match std::fs::OpenOptions::new().append(true).open(&path) {
+        Ok(file) => Some(file.metadata()?),
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to write file {}", path.display()));
+        }
+    };
+    std::fs::write(&temp_path, contents)
+        .with_context(|| format!("Failed to write file {}", path.display()))?;
+    if let Some(metadata) = existing {
+        let _ = std::fs::set_permissions(&temp_path, metadata.permissions());
+    }
+    std::fs::rename(&temp_path, &path)
+        .inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp_path);
+        })
+        .with_context(|| format!("Failed to write file {}", path.display()))
+}
 struct AppliedPatch {
     original_contents: String,
     new_contents: String,
@@ -348,6 +488,7 @@ struct AppliedPatch {
 fn derive_new_contents_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
+    fuzz_window: usize,
 ) -> std::result::Result<AppliedPatch, ApplyPatchError> {
     let original_contents = match std::fs::read_to_string(path) {
         Ok(contents) => contents,
@@ -358,7 +499,40 @@ fn derive_new_contents_from_chunks(
             }));
         }
     };
+    let new_contents = apply_chunks_to_contents(path, &original_contents, chunks, fuzz_window)?;
+    Ok(AppliedPatch {
+        original_contents,
+        new_contents,
+    })
+}
+
+/// Return the contents that result from applying `chunks` to
+/// `original_contents`, the current contents of `path`, without touching the
+/// filesystem. Drifted context is re-anchored within `fuzz_window` lines.
+pub fn apply_chunks_to_contents(
+    path: &Path,
+    original_contents: &str,
+    chunks: &[UpdateFileChunk],
+    fuzz_window: usize,
+) -> std::result::Result<String, ApplyPatchError> {
+    apply_chunks_reanchoring(
+        path,
+        original_contents,
+        chunks,
+        fuzz_window,
+        &mut Vec::new(),
+    )
+}
 
+/// Like [`apply_chunks_to_contents`], also recording the hunks whose drifted
+/// context had to be re-anchored.
+fn apply_chunks_reanchoring(
+    path: &Path,
+    original_contents: &str,
+    chunks: &[UpdateFileChunk],
+    fuzz_window: usize,
+    reanchored: &mut Vec<ReanchoredHunk>,
+) -> std::result::Result<String, ApplyPatchError> {
     let mut original_lines: Vec<String> = original_contents.split('\n').map(String::from).collect();
 
     // Drop the trailing empty element that results from the final newline so
@@ -367,17 +541,14 @@ fn derive_new_contents_from_chunks(
         original_lines.pop();
     }
 
-    let replacements = compute_replacements(&original_lines, path, chunks)?;
+    let replacements =
+        compute_replacements(&original_lines, path, chunks, fuzz_window, reanchored)?;
     let new_lines = apply_replacements(original_lines, &replacements);
     let mut new_lines = new_lines;
     if !new_lines.last().is_some_and(String::is_empty) {
         new_lines.push(String::new());
     }
-    let new_contents = new_lines.join("\n");
-    Ok(AppliedPatch {
-        original_contents,
-        new_contents,
-    })
+    Ok(new_lines.join("\n"))
 }
 
 /// Compute a list of replacements needed to transform `original_lines` into the
@@ -387,6 +558,8 @@ fn compute_replacements(
     original_lines: &[String],
     path: &Path,
     chunks: &[UpdateFileChunk],
+    fuzz_window: usize,
+    reanchored: &mut Vec<ReanchoredHunk>,
 ) -> std::result::Result<Vec<(usize, usize, Vec<String>)>, ApplyPatchError> {
     let mut replacements: Vec<(usize, usize, Vec<String>)> = Vec::new();
     let mut line_index: usize = 0;
@@ -459,12 +632,65 @@ fn compute_replacements(
         if let Some(start_idx) = found {
             replacements.push((start_idx, pattern.len(), new_slice.to_vec()));
             line_index = start_idx + pattern.len();
-        } else {
-            return Err(ApplyPatchError::ComputeReplacements(format!(
-                "Failed to find expected lines in {}:\n{}",
-                path.display(),
-                chunk.old_lines.join("\n"),
-            )));
+            continue;
+        }
+
+        // The lines are not in the file as written. If only the hunk's context
+        // drifted, re-anchor it where the file's lines are most similar, keeping
+        // the file's own version of the context lines.
+        let diff = similar::capture_diff_slices(similar::Algorithm::Myers, pattern, new_slice);
+        let mut changed = vec![true; pattern.len()];
+        for op in &diff {
+            if let similar::DiffOp::Equal { old_index, len, .. } = *op {
+                changed[old_index..old_index + len].fill(false);
+            }
+        }
+        match seek_sequence::seek_fuzzy(original_lines, pattern, &changed, line_index, fuzz_window)
+        {
+            Ok(fuzzy) => {
+                let mut new_lines = Vec::new();
+                for op in &diff {
+                    match *op {
+                        similar::DiffOp::Equal { old_index, len, .. } => new_lines
+                            .extend_from_slice(
+                                &original_lines
+                                    [fuzzy.start + old_index..fuzzy.start + old_index + len],
+                            ),
+                        similar::DiffOp::Delete { .. } => {}
+                        similar::DiffOp::Insert {
+                            new_index, new_len, ..
+                        }
+                        | similar::DiffOp::Replace {
+                            new_index, new_len, ..
+                        } => {
+                            new_lines.extend_from_slice(&new_slice[new_index..new_index + new_len])
+                        }
+                    }
+                }
+                replacements.push((fuzzy.start, pattern.len(), new_lines));
+                reanchored.push(ReanchoredHunk {
+                    path: path.to_path_buf(),
+                    line: fuzzy.start + 1,
+                    similarity: fuzzy.similarity,
+                });
+                line_index = fuzzy.start + pattern.len();
+            }
+            Err(closest) => {
+                let closest = closest
+                    .map(|closest| {
+                        format!(
+                            "\nThe closest match, at line {}, was only {:.0}% similar.",
+                            closest.start + 1,
+                            closest.similarity * 100.0
+                        )
+                    })
+                    .unwrap_or_default();
+                return Err(ApplyPatchError::ComputeReplacements(format!(
+                    "Failed to find expected lines in {}:\n{}{closest}",
+                    path.display(),
+                    chunk.old_lines.join("\n"),
+                )));
+            }
         }
     }
 
@@ -512,18 +738,19 @@ pub fn unified_diff_from_chunks(
     path: &Path,
     chunks: &[UpdateFileChunk],
 ) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
-    unified_diff_from_chunks_with_context(path, chunks, 1)
+    unified_diff_from_chunks_with_context(path, chunks, 1, 0)
 }
 
 pub fn unified_diff_from_chunks_with_context(
     path: &Path,
     chunks: &[UpdateFileChunk],
     context: usize,
+    fuzz_window: usize,
 ) -> std::result::Result<ApplyPatchFileUpdate, ApplyPatchError> {
     let AppliedPatch {
         original_contents,
         new_contents,
-    } = derive_new_contents_from_chunks(path, chunks)?;
+    } = derive_new_contents_from_chunks(path, chunks, fuzz_window)?;
     let text_diff = TextDiff::from_lines(&original_contents, &new_contents);
     let unified_diff = text_diff.unified_diff().context_radius(context).to_string();
     Ok(ApplyPatchFileUpdate {
@@ -548,6 +775,21 @@ pub fn print_summary(
     for path in &affected.deleted {
         writeln!(out, "D {}", path.display())?;
     }
+    if !affected.reanchored.is_empty() {
+        writeln!(
+            out,
+            "Some hunks' context did not match exactly and was re-anchored; check these:"
+        )?;
+        for hunk in &affected.reanchored {
+            writeln!(
+                out,
+                "~ {}:{} ({:.0}% similar)",
+                hunk.path.display(),
+                hunk.line,
+                hunk.similarity * 100.0
+            )?;
+        }
+    }
     Ok(())
 }
 
@@ -576,7 +818,7 @@ mod tests {
         ));
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         // Verify expected stdout and stderr outputs.
         let stdout_str = String::from_utf8(stdout).unwrap();
         let stderr_str = String::from_utf8(stderr).unwrap();
@@ -598,7 +840,7 @@ mod tests {
         let patch = wrap_patch(&format!("*** Delete File: {}", path.display()));
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         let stdout_str = String::from_utf8(stdout).unwrap();
         let stderr_str = String::from_utf8(stderr).unwrap();
         let expected_out = format!(
@@ -625,7 +867,7 @@ mod tests {
         ));
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         // Validate modified file contents and expected stdout/stderr.
         let stdout_str = String::from_utf8(stdout).unwrap();
         let stderr_str = String::from_utf8(stderr).unwrap();
@@ -656,7 +898,7 @@ mod tests {
         ));
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         // Validate move semantics and expected stdout/stderr.
         let stdout_str = String::from_utf8(stdout).unwrap();
         let stderr_str = String::from_utf8(stderr).unwrap();
@@ -696,7 +938,7 @@ mod tests {
         ));
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         let stdout_str = String::from_utf8(stdout).unwrap();
         let stderr_str = String::from_utf8(stderr).unwrap();
         let expected_out = format!(
@@ -745,7 +987,7 @@ mod tests {
 
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
 
         let stdout_str = String::from_utf8(stdout).unwrap();
         let stderr_str = String::from_utf8(stderr).unwrap();
@@ -780,7 +1022,7 @@ mod tests {
         ));
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         let contents = fs::read_to_string(path).unwrap();
         assert_eq!(
             contents,
@@ -814,7 +1056,7 @@ mod tests {
 
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
 
         // File should now contain the replaced comment.
         let expected = "import asyncio  # HELLO\n";
@@ -1037,7 +1279,7 @@ mod tests {
 
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        apply_patch(&patch, &mut stdout, &mut stderr).unwrap();
+        apply_patch(&patch, 0, &mut stdout, &mut stderr).unwrap();
         let contents = fs::read_to_string(path).unwrap();
         assert_eq!(
             contents,
@@ -1068,7 +1310,72 @@ g
 
         let mut stdout = Vec::new();
         let mut stderr = Vec::new();
-        let result = apply_patch(&patch, &mut stdout, &mut stderr);
+        let result = apply_patch(&patch, 0, &mut stdout, &mut stderr);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_update_reanchors_drifted_context_and_reports_it() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("drifted.rs");
+        fs::write(
+            &path,
+            "fn main() {\n    let total = items.len();\n    println!(\"{total}\");\n}\n",
+        )
+        .unwrap();
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n     let total = item.len();\n-    println!(\"{{total}}\");\n+    println!(\"total: {{total}}\");",
+            path.display()
+        ));
+
+        // Re-anchoring is off without a fuzz window.
+        assert!(apply_patch(&patch, 0, &mut Vec::new(), &mut Vec::new()).is_err());
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        apply_patch(&patch, 10, &mut stdout, &mut stderr).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "fn main() {\n    let total = items.len();\n    println!(\"total: {total}\");\n}\n"
+        );
+        assert_eq!(
+            String::from_utf8(stdout).unwrap(),
+            format!(
+                "Success. Updated the following files:\nM {path}\nSome hunks' context did not match exactly and was re-anchored; check these:\n~ {path}:2 (98% similar)\n",
+                path = path.display()
+            )
+        );
+    }
+
+    #[test]
+    fn test_failed_write_rolls_back_files_already_written() {
+        let dir = tempdir().unwrap();
+        let updated = dir.path().join("updated.txt");
+        let added = dir.path().join("added.txt");
+        // A directory where the patch adds a file makes the last write fail, even for root.
+        let blocked = dir.path().join("blocked.txt");
+        fs::write(&updated, "before\n").unwrap();
+        fs::create_dir(&blocked).unwrap();
+
+        let patch = wrap_patch(&format!(
+            "*** Update File: {}\n@@\n-before\n+after\n*** Add File: {}\n+new\n*** Add File: {}\n+new",
+            updated.display(),
+            added.display(),
+            blocked.display()
+        ));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        let result = apply_patch(&patch, 0, &mut stdout, &mut stderr);
         assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&updated).unwrap(), "before\n");
+        assert!(!added.exists());
+        assert!(blocked.is_dir());
+        assert_eq!(
+            fs::read_dir(dir.path()).unwrap().count(),
+            2,
+            "no temporary files are left behind"
+        );
     }
 }
@@ -109,8 +109,75 @@ pub(crate) fn seek_sequence(
     None
 }
 
+/// Similarity below which a re-anchored hunk is rejected.
+const MIN_FUZZY_SIMILARITY: f64 = 0.8;
+/// How much better than the runner-up a re-anchored position must be.
+const MIN_FUZZY_MARGIN: f64 = 0.05;
+
+/// Where [`seek_fuzzy`] placed a hunk, and how similar the file's lines were to
+/// the hunk's context there (1.0 is identical).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct FuzzyMatch {
+    pub(crate) start: usize,
+    pub(crate) similarity: f64,
+}
+
+/// Fallback for a hunk whose lines are not in the file as written: looks for
+/// the position within `window` lines after `start` where the file's lines
+/// are most similar to `pattern`. Only context lines may differ; the lines the
+/// hunk removes (`changed[i]` set) must still match up to surrounding
+/// whitespace.
+///
+/// Returns `Ok` with a confident match, or `Err` with the best candidate (if
+/// any) when it is not similar enough or a different position is about as
+/// similar.
+pub(crate) fn seek_fuzzy(
+    lines: &[String],
+    pattern: &[String],
+    changed: &[bool],
+    start: usize,
+    window: usize,
+) -> Result<FuzzyMatch, Option<FuzzyMatch>> {
+    let context_lines = changed.iter().filter(|changed| !**changed).count();
+    if window == 0 || context_lines == 0 || pattern.len() > lines.len() {
+        return Err(None);
+    }
+    let last_start = (lines.len() - pattern.len()).min(start.saturating_add(window));
+    let mut candidates: Vec<FuzzyMatch> = (start..=last_start)
+        .filter_map(|candidate| {
+            let mut similarity = 0.0;
+            for (offset, (pat, changed)) in pattern.iter().zip(changed).enumerate() {
+                let line = lines[candidate + offset].trim();
+                if *changed {
+                    if line != pat.trim() {
+                        return None;
+                    }
+                } else {
+                    similarity +=
+                        f64::from(similar::TextDiff::from_chars(line, pat.trim()).ratio());
+                }
+            }
+            Some(FuzzyMatch {
+                start: candidate,
+                similarity: similarity / context_lines as f64,
+            })
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    match candidates.as_slice() {
+        [] => Err(None),
+        [best, ..] if best.similarity < MIN_FUZZY_SIMILARITY => Err(Some(*best)),
+        [best, runner_up, ..] if best.similarity - runner_up.similarity < MIN_FUZZY_MARGIN => {
+            Err(Some(*best))
+        }
+        [best, ..] => Ok(*best),
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use super::FuzzyMatch;
+    use super::seek_fuzzy;
     use super::seek_sequence;
     use std::string::ToString;
 
@@ -148,4 +215,44 @@ mod tests {
         // Should not panic – must return None when pattern cannot possibly fit.
         assert_eq!(seek_sequence(&lines, &pattern, 0, false), None);
     }
+
+    #[test]
+    fn test_fuzzy_match_reanchors_drifted_context() {
+        let lines = to_vec(&[
+            "fn main() {",
+            "    let count = items.len();",
+            "    println!(\"{count}\");",
+            "}",
+        ]);
+        let pattern = to_vec(&["    let count = item.len();", "    println!(\"{count}\");"]);
+        let changed = [false, true];
+
+        let found = seek_fuzzy(&lines, &pattern, &changed, 0, 10).expect("confident match");
+        assert_eq!(found.start, 1);
+        assert!(found.similarity > 0.9);
+
+        // The removed line itself must still be in the file.
+        let changed = [true, false];
+        assert_eq!(seek_fuzzy(&lines, &pattern, &changed, 0, 10), Err(None));
+        // Nothing is searched beyond the window.
+        assert_eq!(
+            seek_fuzzy(&lines, &pattern, &[false, true], 2, 10),
+            Err(None)
+        );
+    }
+
+    #[test]
+    fn test_fuzzy_match_rejects_low_confidence() {
+        let lines = to_vec(&["alpha", "remove me", "beta", "remove me"]);
+        let pattern = to_vec(&["something else entirely", "remove me"]);
+        assert!(matches!(
+            seek_fuzzy(&lines, &pattern, &[false, true], 0, 10),
+            Err(Some(FuzzyMatch { .. }))
+        ));
+
+        // Two equally good positions are ambiguous.
+        let pattern = to_vec(&["alphx", "remove me"]);
+        let lines = to_vec(&["alpha", "remove me", "alphb", "remove me"]);
+        assert!(seek_fuzzy(&lines, &pattern, &[false, true], 0, 10).is_err());
+    }
 }
@@ -5,6 +5,7 @@ use codex_core::auth::CLIENT_ID;
 use codex_core::auth::login_with_api_key;
 use codex_core::auth::logout;
 use codex_core::config::Config;
+use codex_core::default_client::set_default_client_http_network_config;
 use codex_login::ServerOptions;
 use codex_login::run_device_code_login;
 use codex_login::run_login_server;
@@ -281,7 +282,13 @@ async fn load_config_or_exit(cli_config_overrides: CliConfigOverrides) -> Config
     };
 
     match Config::load_with_cli_overrides(cli_overrides).await {
-        Ok(config) => config,
+        Ok(config) => {
+            if let Err(err) = set_default_client_http_network_config(&config.http) {
+                eprintln!("Error loading [http] settings: {err}");
+                std::process::exit(1);
+            }
+            config
+        }
         Err(e) => {
             eprintln!("Error loading configuration: {e}");
             std::process::exit(1);
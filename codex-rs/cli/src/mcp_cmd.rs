@@ -244,9 +244,12 @@ async fn run_add(config_overrides: &CliConfigOverrides, add_args: AddArgs) -> Re
         transport: transport.clone(),
         enabled: true,
         required: false,
+        lazy: false,
         disabled_reason: None,
         startup_timeout_sec: None,
         tool_timeout_sec: None,
+        elicitation: false,
+        elicitation_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
         scopes: None,
@@ -798,6 +801,12 @@ async fn run_get(config_overrides: &CliConfigOverrides, get_args: GetArgs) -> Re
     if let Some(timeout) = server.tool_timeout_sec {
         println!("  tool_timeout_sec: {}", timeout.as_secs_f64());
     }
+    if server.elicitation {
+        println!("  elicitation: true");
+    }
+    if let Some(timeout) = server.elicitation_timeout_sec {
+        println!("  elicitation_timeout_sec: {}", timeout.as_secs_f64());
+    }
     println!("  remove: codex mcp remove {}", get_args.name);
 
     Ok(())
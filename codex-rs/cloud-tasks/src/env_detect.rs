@@ -1,3 +1,4 @@
+use codex_core::default_client::build_reqwest_client;
 use reqwest::header::CONTENT_TYPE;
 use reqwest::header::HeaderMap;
 use std::collections::HashMap;
@@ -73,7 +74,7 @@ pub async fn autodetect_environment_id(
     };
     crate::append_error_log(format!("env: GET {list_url}"));
     // Fetch and log the full environments JSON for debugging
-    let http = reqwest::Client::builder().build()?;
+    let http = build_reqwest_client();
     let res = http.get(&list_url).headers(headers.clone()).send().await?;
     let status = res.status();
     let ct = res
@@ -147,7 +148,7 @@ async fn get_json<T: serde::de::DeserializeOwned>(
     url: &str,
     headers: &HeaderMap,
 ) -> anyhow::Result<T> {
-    let http = reqwest::Client::builder().build()?;
+    let http = build_reqwest_client();
     let res = http.get(url).headers(headers.clone()).send().await?;
     let status = res.status();
     let ct = res
@@ -54,6 +54,8 @@ async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext>
         });
     }
 
+    // Loading the config installs its `[http]` settings for the client below.
+    let auth_manager = util::load_auth_manager().await;
     let ua = codex_core::default_client::get_codex_user_agent();
     let mut http = codex_cloud_tasks_client::HttpClient::new(base_url.clone())?.with_user_agent(ua);
     let style = if base_url.contains("/backend-api") {
@@ -63,7 +65,6 @@ async fn init_backend(user_agent_suffix: &str) -> anyhow::Result<BackendContext>
     };
     append_error_log(format!("startup: base_url={base_url} path_style={style}"));
 
-    let auth_manager = util::load_auth_manager().await;
     let auth = match auth_manager.as_ref() {
         Some(manager) => manager.auth().await,
         None => None,
@@ -5,6 +5,7 @@ use chrono::Utc;
 use reqwest::header::HeaderMap;
 
 use codex_core::config::Config;
+use codex_core::default_client::set_default_client_http_network_config;
 use codex_login::AuthManager;
 
 pub fn set_user_agent_suffix(suffix: &str) {
@@ -62,6 +63,9 @@ pub fn extract_chatgpt_account_id(token: &str) -> Option<String> {
 pub async fn load_auth_manager() -> Option<AuthManager> {
     // TODO: pass in cli overrides once cloud tasks properly support them.
     let config = Config::load_with_cli_overrides(Vec::new()).await.ok()?;
+    if let Err(err) = set_default_client_http_network_config(&config.http) {
+        append_error_log(format!("failed to load [http] settings: {err}"));
+    }
     Some(AuthManager::new(
         config.codex_home,
         false,
@@ -1,6 +1,9 @@
+use async_trait::async_trait;
 use codex_client::Request;
+use codex_client::TransportError;
 use http::HeaderMap;
 use http::HeaderValue;
+use std::fmt::Debug;
 
 /// Provides bearer and account identity information for API requests.
 ///
@@ -14,7 +17,17 @@ pub trait AuthProvider: Send + Sync {
     }
 }
 
-pub(crate) fn add_auth_headers_to_header_map<A: AuthProvider>(auth: &A, headers: &mut HeaderMap) {
+/// Adds gateway-specific authentication to a request once it is fully built,
+/// e.g. a signature over its method, URL, and body.
+///
+/// Runs before every attempt, retries included. Unlike [`AuthProvider`],
+/// signers may do I/O.
+#[async_trait]
+pub trait RequestSigner: Debug + Send + Sync {
+    async fn sign(&self, request: &mut Request) -> Result<(), TransportError>;
+}
+
+pub fn add_auth_headers_to_header_map<A: AuthProvider>(auth: &A, headers: &mut HeaderMap) {
     if let Some(token) = auth.bearer_token()
         && let Ok(header) = HeaderValue::from_str(&format!("Bearer {token}"))
     {
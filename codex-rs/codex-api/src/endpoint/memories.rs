@@ -152,6 +152,7 @@ mod tests {
                 retry_transport: true,
             },
             stream_idle_timeout: Duration::from_secs(1),
+            signer: None,
         }
     }
 
@@ -3,12 +3,14 @@ use crate::auth::add_auth_headers_to_header_map;
 use crate::common::ResponseEvent;
 use crate::common::ResponseStream;
 use crate::common::ResponsesWsRequest;
+use crate::endpoint::websocket_connect::connect_websocket_stream;
 use crate::error::ApiError;
 use crate::provider::Provider;
 use crate::rate_limits::parse_rate_limit_event;
 use crate::sse::responses::ResponsesStreamEvent;
 use crate::sse::responses::process_responses_event;
 use crate::telemetry::WebsocketTelemetry;
+use codex_client::Request;
 use codex_client::TransportError;
 use codex_utils_rustls_provider::ensure_rustls_crypto_provider;
 use futures::SinkExt;
@@ -16,6 +18,7 @@ use futures::StreamExt;
 use http::HeaderMap;
 use http::HeaderName;
 use http::HeaderValue;
+use http::Method;
 use http::StatusCode;
 use serde::Deserialize;
 use serde_json::Value;
@@ -293,6 +296,12 @@ impl<A: AuthProvider> ResponsesWebsocketClient<A> {
         let mut headers =
             merge_request_headers(&self.provider.headers, extra_headers, default_headers);
         add_auth_headers_to_header_map(&self.auth, &mut headers);
+        if let Some(signer) = &self.provider.signer {
+            let mut handshake = Request::new(Method::GET, ws_url.to_string());
+            handshake.headers = headers;
+            signer.sign(&mut handshake).await?;
+            headers = handshake.headers;
+        }
 
         let (stream, server_reasoning_included, models_etag, server_model) =
             connect_websocket(ws_url, headers, turn_state.clone()).await?;
@@ -336,12 +345,7 @@ async fn connect_websocket(
         .map_err(|err| ApiError::Stream(format!("failed to build websocket request: {err}")))?;
     request.headers_mut().extend(headers);
 
-    let response = tokio_tungstenite::connect_async_with_config(
-        request,
-        Some(websocket_config()),
-        false, // `false` means "do not disable Nagle", which is tungstenite's recommended default.
-    )
-    .await;
+    let response = connect_websocket_stream(request, websocket_config()).await;
 
     let (stream, response) = match response {
         Ok((stream, response)) => {
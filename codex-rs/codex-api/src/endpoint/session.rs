@@ -8,6 +8,7 @@ use codex_client::Request;
 use codex_client::RequestTelemetry;
 use codex_client::Response;
 use codex_client::StreamResponse;
+use codex_client::TransportError;
 use http::HeaderMap;
 use http::Method;
 use serde_json::Value;
@@ -57,6 +58,13 @@ impl<T: HttpTransport, A: AuthProvider> EndpointSession<T, A> {
         add_auth_headers(&self.auth, req)
     }
 
+    async fn sign(&self, mut req: Request) -> Result<Request, TransportError> {
+        if let Some(signer) = &self.provider.signer {
+            signer.sign(&mut req).await?;
+        }
+        Ok(req)
+    }
+
     pub(crate) async fn execute(
         &self,
         method: Method,
@@ -89,7 +97,7 @@ impl<T: HttpTransport, A: AuthProvider> EndpointSession<T, A> {
             self.provider.retry.to_policy(),
             self.request_telemetry.clone(),
             make_request,
-            |req| self.transport.execute(req),
+            |req| async move { self.transport.execute(self.sign(req).await?).await },
         )
         .await?;
 
@@ -117,7 +125,7 @@ impl<T: HttpTransport, A: AuthProvider> EndpointSession<T, A> {
             self.provider.retry.to_policy(),
             self.request_telemetry.clone(),
             make_request,
-            |req| self.transport.stream(req),
+            |req| async move { self.transport.stream(self.sign(req).await?).await },
         )
         .await?;
 
@@ -0,0 +1,217 @@
+//! Opens WebSocket connections with the `[http]` proxy and TLS settings, so
+//! they reach the network the same way the HTTP clients do.
+//!
+//! A configured proxy is tunneled through with `CONNECT`; only `http://`
+//! proxies can do that. Extra certificate authorities and client
+//! certificates replace the default TLS connector.
+
+use std::io;
+use std::sync::Arc;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use codex_client::StreamNetworkSettings;
+use codex_client::stream_network_settings;
+use rustls::ClientConfig;
+use rustls::RootCertStore;
+use rustls::pki_types::CertificateDer;
+use rustls::pki_types::PrivateKeyDer;
+use rustls::pki_types::pem::PemObject;
+use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
+use tokio::net::TcpStream;
+use tokio_tungstenite::Connector;
+use tokio_tungstenite::MaybeTlsStream;
+use tokio_tungstenite::WebSocketStream;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use tokio_tungstenite::tungstenite::handshake::client::Request;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tungstenite::protocol::WebSocketConfig;
+use url::Url;
+
+/// Proxy responses with longer headers are refused.
+const MAX_PROXY_RESPONSE_LEN: usize = 8 * 1024;
+
+pub(crate) async fn connect_websocket_stream(
+    request: Request,
+    config: WebSocketConfig,
+) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response), WsError> {
+    let url = Url::parse(&request.uri().to_string())
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+    let settings = stream_network_settings(&url);
+    let connector = tls_connector(&settings)?;
+    match &settings.proxy {
+        Some(proxy) => {
+            let stream = tunnel(proxy, &url).await?;
+            tokio_tungstenite::client_async_tls_with_config(
+                request,
+                stream,
+                Some(config),
+                connector,
+            )
+            .await
+        }
+        None => {
+            tokio_tungstenite::connect_async_tls_with_config(
+                request,
+                Some(config),
+                false, // `false` means "do not disable Nagle", which is tungstenite's recommended default.
+                connector,
+            )
+            .await
+        }
+    }
+}
+
+/// A TLS connector trusting the configured certificate authorities and
+/// presenting the configured client certificate, or `None` for the default.
+fn tls_connector(settings: &StreamNetworkSettings) -> io::Result<Option<Connector>> {
+    if settings.ca_certificates_pem.is_none() && settings.client_identity_pem.is_none() {
+        return Ok(None);
+    }
+    let mut roots = RootCertStore::empty();
+    roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+    if let Some(pem) = &settings.ca_certificates_pem {
+        for certificate in CertificateDer::pem_slice_iter(pem) {
+            roots
+                .add(certificate.map_err(invalid_pem)?)
+                .map_err(invalid_pem)?;
+        }
+    }
+
+    let builder = ClientConfig::builder().with_root_certificates(roots);
+    let config = match &settings.client_identity_pem {
+        Some(pem) => {
+            let chain = CertificateDer::pem_slice_iter(pem)
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(invalid_pem)?;
+            let key = PrivateKeyDer::from_pem_slice(pem).map_err(invalid_pem)?;
+            builder
+                .with_client_auth_cert(chain, key)
+                .map_err(invalid_pem)?
+        }
+        None => builder.with_no_client_auth(),
+    };
+    Ok(Some(Connector::Rustls(Arc::new(config))))
+}
+
+fn invalid_pem(err: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("invalid [http] TLS settings: {err}"),
+    )
+}
+
+/// A connection to `target` through the HTTP proxy at `proxy`.
+async fn tunnel(proxy: &Url, target: &Url) -> io::Result<TcpStream> {
+    if proxy.scheme() != "http" {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "WebSocket connections can only be tunneled through http:// proxies, not {proxy}"
+            ),
+        ));
+    }
+    let (Some(proxy_host), Some(proxy_port)) = (proxy.host_str(), proxy.port_or_known_default())
+    else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid proxy URL {proxy}"),
+        ));
+    };
+    let (Some(host), Some(port)) = (target.host_str(), target.port_or_known_default()) else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid WebSocket URL {target}"),
+        ));
+    };
+
+    let mut stream = TcpStream::connect((
+        proxy_host.trim_start_matches('[').trim_end_matches(']'),
+        proxy_port,
+    ))
+    .await?;
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        let username = urlencoding::decode(proxy.username()).map_err(invalid_proxy_auth)?;
+        let password = urlencoding::decode(proxy.password().unwrap_or_default())
+            .map_err(invalid_proxy_auth)?;
+        let credentials = BASE64_STANDARD.encode(format!("{username}:{password}"));
+        request.push_str(&format!("Proxy-Authorization: Basic {credentials}\r\n"));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read byte by byte so nothing after the proxy's response is consumed.
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_PROXY_RESPONSE_LEN {
+            return Err(io::Error::other("proxy response headers are too long"));
+        }
+        response.push(stream.read_u8().await?);
+    }
+    let status_line = String::from_utf8_lossy(&response);
+    let status_line = status_line.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(io::Error::other(format!(
+            "proxy {proxy} refused to tunnel to {host}:{port}: {status_line}"
+        )));
+    }
+    Ok(stream)
+}
+
+fn invalid_proxy_auth(err: impl std::fmt::Display) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("invalid proxy credentials: {err}"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tokio::net::TcpListener;
+
+    #[tokio::test]
+    async fn tunnels_through_the_proxy_with_its_credentials() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let proxy = Url::parse(&format!(
+            "http://user:p%40ss@{}",
+            listener.local_addr().expect("local addr")
+        ))
+        .expect("proxy url");
+        let server = tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.expect("accept");
+            let mut request = Vec::new();
+            while !request.ends_with(b"\r\n\r\n") {
+                request.push(stream.read_u8().await.expect("read request"));
+            }
+            stream
+                .write_all(b"HTTP/1.1 200 Connection established\r\n\r\nhello")
+                .await
+                .expect("write response");
+            String::from_utf8(request).expect("utf-8 request")
+        });
+
+        let mut stream = tunnel(
+            &proxy,
+            &Url::parse("wss://api.example.com/v1/responses").expect("target url"),
+        )
+        .await
+        .expect("tunnel");
+        let mut greeting = [0; 5];
+        stream.read_exact(&mut greeting).await.expect("read tunnel");
+
+        assert_eq!(
+            (server.await.expect("proxy task"), &greeting),
+            (
+                format!(
+                    "CONNECT api.example.com:443 HTTP/1.1\r\nHost: api.example.com:443\r\nProxy-Authorization: Basic {}\r\n\r\n",
+                    BASE64_STANDARD.encode("user:p@ss")
+                ),
+                b"hello"
+            )
+        );
+    }
+}
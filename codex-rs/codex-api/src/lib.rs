@@ -14,6 +14,8 @@ pub use codex_client::ReqwestTransport;
 pub use codex_client::TransportError;
 
 pub use crate::auth::AuthProvider;
+pub use crate::auth::RequestSigner;
+pub use crate::auth::add_auth_headers_to_header_map;
 pub use crate::common::CompactionInput;
 pub use crate::common::MemorySummarizeInput;
 pub use crate::common::MemorySummarizeOutput;
@@ -1,3 +1,4 @@
+use crate::auth::RequestSigner;
 use codex_client::Request;
 use codex_client::RequestCompression;
 use codex_client::RetryOn;
@@ -5,6 +6,7 @@ use codex_client::RetryPolicy;
 use http::Method;
 use http::header::HeaderMap;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use url::Url;
 
@@ -37,8 +39,9 @@ impl RetryConfig {
 
 /// HTTP endpoint configuration used to talk to a concrete API deployment.
 ///
-/// Encapsulates base URL, default headers, query params, retry policy, and
-/// stream idle timeout, plus helper methods for building requests.
+/// Encapsulates base URL, default headers, query params, retry policy, stream
+/// idle timeout, and request signer, plus helper methods for building
+/// requests.
 #[derive(Debug, Clone)]
 pub struct Provider {
     pub name: String,
@@ -47,6 +50,7 @@ pub struct Provider {
     pub headers: HeaderMap,
     pub retry: RetryConfig,
     pub stream_idle_timeout: Duration,
+    pub signer: Option<Arc<dyn RequestSigner>>,
 }
 
 impl Provider {
@@ -170,6 +170,7 @@ pub struct ResponsesStreamEvent {
     headers: Option<Value>,
     response: Option<Value>,
     item: Option<Value>,
+    item_id: Option<String>,
     delta: Option<String>,
     summary_index: Option<i64>,
     content_index: Option<i64>,
@@ -375,6 +376,14 @@ pub fn process_responses_event(
                 }));
             }
         }
+        "response.function_call_arguments.delta" => {
+            if let (Some(item_id), Some(delta)) = (event.item_id, event.delta) {
+                return Ok(Some(ResponseEvent::ToolCallArgumentsDelta {
+                    item_id,
+                    delta,
+                }));
+            }
+        }
         _ => {
             trace!("unhandled responses event: {}", event.kind);
         }
@@ -134,6 +134,7 @@ fn provider(name: &str) -> Provider {
             retry_transport: true,
         },
         stream_idle_timeout: Duration::from_millis(10),
+        signer: None,
     }
 }
 
@@ -267,6 +268,9 @@ async fn streaming_client_retries_on_transport_error() -> Result<()> {
         include: Vec::new(),
         prompt_cache_key: None,
         text: None,
+        temperature: None,
+        top_p: None,
+        seed: None,
     };
     let client = ResponsesClient::new(transport.clone(), provider, NoAuth);
 
@@ -308,6 +312,9 @@ async fn azure_default_store_attaches_ids_and_headers() -> Result<()> {
         include: Vec::new(),
         prompt_cache_key: None,
         text: None,
+        temperature: None,
+        top_p: None,
+        seed: None,
     };
 
     let mut extra_headers = HeaderMap::new();
@@ -1,5 +1,6 @@
 mod default_client;
 mod error;
+mod network_config;
 mod request;
 mod retry;
 mod sse;
@@ -10,6 +11,14 @@ pub use crate::default_client::CodexHttpClient;
 pub use crate::default_client::CodexRequestBuilder;
 pub use crate::error::StreamError;
 pub use crate::error::TransportError;
+pub use crate::network_config::HttpDestinationConfig;
+pub use crate::network_config::HttpNetworkConfig;
+pub use crate::network_config::HttpNetworkConfigError;
+pub use crate::network_config::HttpTlsConfig;
+pub use crate::network_config::StreamNetworkSettings;
+pub use crate::network_config::apply_http_network_config;
+pub use crate::network_config::set_http_network_config;
+pub use crate::network_config::stream_network_settings;
 pub use crate::request::Request;
 pub use crate::request::RequestCompression;
 pub use crate::request::Response;
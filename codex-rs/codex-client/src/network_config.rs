@@ -0,0 +1,444 @@
+//! Proxy and TLS settings shared by every outbound HTTP client.
+//!
+//! The settings are installed once per process with
+//! [`set_http_network_config`], which reads and checks the certificate files,
+//! and every client then picks them up through [`apply_http_network_config`]
+//! as it is built. Requests go through `proxy` unless their host matches a
+//! `no_proxy` entry or the `NO_PROXY` environment variable; `destinations`
+//! entries override the proxy and TLS settings for the hosts they match.
+//! Connections reqwest does not make, such as WebSockets, get the same
+//! settings from [`stream_network_settings`].
+
+use std::collections::BTreeMap;
+use std::io;
+use std::net::IpAddr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use reqwest::Certificate;
+use reqwest::ClientBuilder;
+use reqwest::Identity;
+use reqwest::Proxy;
+use reqwest::Url;
+use thiserror::Error;
+
+/// Process-wide HTTP client settings, as configured.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpNetworkConfig {
+    /// Proxy for every scheme, e.g. `http://proxy.corp.example.com:8080`.
+    pub proxy: Option<String>,
+    /// Hosts reached without the proxy: host names, domains (`example.com`
+    /// and `.example.com` both cover the subdomains), IP addresses, CIDR
+    /// ranges such as `10.0.0.0/8`, or `*`. A `:port` suffix limits an entry
+    /// to that port.
+    pub no_proxy: Vec<String>,
+    pub tls: HttpTlsConfig,
+    /// Overrides keyed by host pattern, matched like `no_proxy` entries. The
+    /// longest matching pattern wins.
+    pub destinations: BTreeMap<String, HttpDestinationConfig>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpTlsConfig {
+    /// PEM bundle trusted in addition to the built-in roots.
+    pub ca_certificate: Option<PathBuf>,
+    /// PEM client certificate for mTLS; needs `client_private_key`.
+    pub client_certificate: Option<PathBuf>,
+    pub client_private_key: Option<PathBuf>,
+}
+
+/// Settings for the hosts matching one pattern. Unset fields fall back to
+/// the top-level ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpDestinationConfig {
+    pub proxy: Option<String>,
+    pub tls: HttpTlsConfig,
+}
+
+#[derive(Debug, Error)]
+pub enum HttpNetworkConfigError {
+    #[error("invalid proxy URL `{url}`: {message}")]
+    InvalidProxy { url: String, message: String },
+    #[error("failed to read {}: {source}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("invalid certificate or key in {}: {source}", path.display())]
+    InvalidPem {
+        path: PathBuf,
+        source: reqwest::Error,
+    },
+    #[error("client_certificate and client_private_key must be set together")]
+    IncompleteClientIdentity,
+}
+
+static HTTP_NETWORK_CONFIG: LazyLock<RwLock<Arc<LoadedConfig>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(LoadedConfig::default())));
+
+/// Installs `config` for every HTTP client built from now on.
+pub fn set_http_network_config(config: &HttpNetworkConfig) -> Result<(), HttpNetworkConfigError> {
+    let loaded = Arc::new(LoadedConfig::load(config)?);
+    match HTTP_NETWORK_CONFIG.write() {
+        Ok(mut guard) => *guard = loaded,
+        Err(poisoned) => *poisoned.into_inner() = loaded,
+    }
+    Ok(())
+}
+
+/// Applies the installed settings to `builder`, with the TLS settings for
+/// `destination` when the client only talks to that URL.
+pub fn apply_http_network_config(
+    mut builder: ClientBuilder,
+    destination: Option<&str>,
+) -> ClientBuilder {
+    let config = match HTTP_NETWORK_CONFIG.read() {
+        Ok(guard) => Arc::clone(&guard),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    };
+
+    if config.routes.uses_proxy() {
+        let routes = config.routes.clone();
+        builder = builder.proxy(Proxy::custom(move |url| routes.proxy_for(url)));
+    }
+
+    let host = destination
+        .and_then(|destination| Url::parse(destination).ok())
+        .and_then(|url| url.host_str().map(str::to_string));
+    let tls = host
+        .as_deref()
+        .and_then(|host| best_match(&config.destination_tls, host))
+        .unwrap_or(&config.tls);
+    for certificate in &tls.ca_certificates {
+        builder = builder.add_root_certificate(certificate.clone());
+    }
+    if let Some(identity) = &tls.identity {
+        builder = builder.use_rustls_tls().identity(identity.clone());
+    }
+    builder
+}
+
+/// The installed settings for a connection to `url` made without reqwest.
+pub fn stream_network_settings(url: &Url) -> StreamNetworkSettings {
+    let config = match HTTP_NETWORK_CONFIG.read() {
+        Ok(guard) => Arc::clone(&guard),
+        Err(poisoned) => Arc::clone(&poisoned.into_inner()),
+    };
+    let tls = url
+        .host_str()
+        .and_then(|host| best_match(&config.destination_tls, host))
+        .unwrap_or(&config.tls);
+    StreamNetworkSettings {
+        proxy: config.routes.proxy_for(url),
+        ca_certificates_pem: tls.ca_certificates_pem.clone(),
+        client_identity_pem: tls.identity_pem.clone(),
+    }
+}
+
+/// Proxy and TLS settings for a connection the caller makes itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StreamNetworkSettings {
+    /// Proxy to tunnel the connection through with `CONNECT`.
+    pub proxy: Option<Url>,
+    /// PEM bundle trusted in addition to the built-in roots.
+    pub ca_certificates_pem: Option<Vec<u8>>,
+    /// PEM client certificate chain followed by its private key.
+    pub client_identity_pem: Option<Vec<u8>>,
+}
+
+#[derive(Default)]
+struct LoadedConfig {
+    routes: ProxyRoutes,
+    tls: LoadedTls,
+    destination_tls: Vec<(String, LoadedTls)>,
+}
+
+#[derive(Default)]
+struct LoadedTls {
+    ca_certificates: Vec<Certificate>,
+    identity: Option<Identity>,
+    ca_certificates_pem: Option<Vec<u8>>,
+    identity_pem: Option<Vec<u8>>,
+}
+
+impl LoadedConfig {
+    fn load(config: &HttpNetworkConfig) -> Result<Self, HttpNetworkConfigError> {
+        let parse_proxy = |url: &str| {
+            Url::parse(url).map_err(|err| HttpNetworkConfigError::InvalidProxy {
+                url: url.to_string(),
+                message: err.to_string(),
+            })
+        };
+        let mut no_proxy = config.no_proxy.clone();
+        for var in ["NO_PROXY", "no_proxy"] {
+            if let Ok(value) = std::env::var(var) {
+                no_proxy.extend(
+                    value
+                        .split(',')
+                        .map(str::trim)
+                        .filter(|entry| !entry.is_empty())
+                        .map(str::to_string),
+                );
+            }
+        }
+        let mut destination_proxies = Vec::new();
+        let mut destination_tls = Vec::new();
+        for (pattern, destination) in &config.destinations {
+            if let Some(proxy) = &destination.proxy {
+                destination_proxies.push((pattern.clone(), parse_proxy(proxy)?));
+            }
+            let tls = HttpTlsConfig {
+                ca_certificate: destination
+                    .tls
+                    .ca_certificate
+                    .clone()
+                    .or_else(|| config.tls.ca_certificate.clone()),
+                client_certificate: destination
+                    .tls
+                    .client_certificate
+                    .clone()
+                    .or_else(|| config.tls.client_certificate.clone()),
+                client_private_key: destination
+                    .tls
+                    .client_private_key
+                    .clone()
+                    .or_else(|| config.tls.client_private_key.clone()),
+            };
+            destination_tls.push((pattern.clone(), LoadedTls::load(&tls)?));
+        }
+
+        Ok(Self {
+            routes: ProxyRoutes {
+                default: config.proxy.as_deref().map(parse_proxy).transpose()?,
+                no_proxy,
+                destinations: destination_proxies,
+            },
+            tls: LoadedTls::load(&config.tls)?,
+            destination_tls,
+        })
+    }
+}
+
+impl LoadedTls {
+    fn load(config: &HttpTlsConfig) -> Result<Self, HttpNetworkConfigError> {
+        let ca_certificates_pem = config.ca_certificate.as_deref().map(read).transpose()?;
+        let ca_certificates = match (&config.ca_certificate, &ca_certificates_pem) {
+            (Some(path), Some(pem)) => Certificate::from_pem_bundle(pem).map_err(|source| {
+                HttpNetworkConfigError::InvalidPem {
+                    path: path.clone(),
+                    source,
+                }
+            })?,
+            _ => Vec::new(),
+        };
+        let (identity, identity_pem) =
+            match (&config.client_certificate, &config.client_private_key) {
+                (Some(certificate), Some(key)) => {
+                    let mut pem = read(certificate)?;
+                    pem.extend_from_slice(&read(key)?);
+                    let identity = Identity::from_pem(&pem).map_err(|source| {
+                        HttpNetworkConfigError::InvalidPem {
+                            path: certificate.clone(),
+                            source,
+                        }
+                    })?;
+                    (Some(identity), Some(pem))
+                }
+                (None, None) => (None, None),
+                _ => return Err(HttpNetworkConfigError::IncompleteClientIdentity),
+            };
+        Ok(Self {
+            ca_certificates,
+            identity,
+            ca_certificates_pem,
+            identity_pem,
+        })
+    }
+}
+
+fn read(path: &Path) -> Result<Vec<u8>, HttpNetworkConfigError> {
+    std::fs::read(path).map_err(|source| HttpNetworkConfigError::Read {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Which proxy, if any, each request goes through.
+#[derive(Debug, Clone, Default)]
+struct ProxyRoutes {
+    default: Option<Url>,
+    no_proxy: Vec<String>,
+    destinations: Vec<(String, Url)>,
+}
+
+impl ProxyRoutes {
+    fn uses_proxy(&self) -> bool {
+        self.default.is_some() || !self.destinations.is_empty()
+    }
+
+    fn proxy_for(&self, url: &Url) -> Option<Url> {
+        let host = url.host_str()?;
+        if self
+            .no_proxy
+            .iter()
+            .any(|entry| no_proxy_matches(entry, url))
+        {
+            return None;
+        }
+        best_match(&self.destinations, host)
+            .or(self.default.as_ref())
+            .cloned()
+    }
+}
+
+/// The value of the longest pattern in `entries` that matches `host`.
+fn best_match<'a, T>(entries: &'a [(String, T)], host: &str) -> Option<&'a T> {
+    entries
+        .iter()
+        .filter(|(pattern, _)| host_matches(pattern, host))
+        .max_by_key(|(pattern, _)| pattern.len())
+        .map(|(_, value)| value)
+}
+
+/// Whether `url` skips the proxy under the `no_proxy` entry `entry`.
+fn no_proxy_matches(entry: &str, url: &Url) -> bool {
+    let entry = entry.trim();
+    let Some(host) = url.host_str() else {
+        return false;
+    };
+    if let Some((network, prefix)) = entry.split_once('/') {
+        let network = network.trim_start_matches('[').trim_end_matches(']');
+        let ip = host.trim_start_matches('[').trim_end_matches(']');
+        return match (network.parse(), ip.parse(), prefix.parse()) {
+            (Ok(network), Ok(ip), Ok(prefix)) => in_network(ip, network, prefix),
+            _ => false,
+        };
+    }
+
+    // `host:port` and `[v6]:port`; a bare IPv6 address has no port.
+    let (pattern, port) = match entry.strip_prefix('[') {
+        Some(rest) => match rest.split_once(']') {
+            Some((address, port)) => (address, port.strip_prefix(':')),
+            None => (rest, None),
+        },
+        None => match entry.split_once(':') {
+            Some((pattern, port)) if !port.contains(':') => (pattern, Some(port)),
+            _ => (entry, None),
+        },
+    };
+    if let Some(port) = port
+        && port.parse::<u16>().ok() != url.port_or_known_default()
+    {
+        return false;
+    }
+    host_matches(pattern, host)
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u32) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+fn host_matches(pattern: &str, host: &str) -> bool {
+    let pattern = pattern
+        .trim()
+        .trim_start_matches("*.")
+        .trim_start_matches('.');
+    if pattern == "*" {
+        return true;
+    }
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case(pattern)
+        || host
+            .to_ascii_lowercase()
+            .ends_with(&format!(".{}", pattern.to_ascii_lowercase()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn routes_requests_through_the_most_specific_proxy() {
+        let url = |url: &str| Url::parse(url).expect("url");
+        let routes = ProxyRoutes {
+            default: Some(url("http://proxy.corp.example:8080")),
+            no_proxy: vec![
+                "localhost".to_string(),
+                ".internal.example".to_string(),
+                "10.0.0.0/8".to_string(),
+                "fd00::/8".to_string(),
+                "registry.example:5000".to_string(),
+            ],
+            destinations: vec![
+                (
+                    "example.com".to_string(),
+                    url("http://edge.corp.example:3128"),
+                ),
+                (
+                    "api.example.com".to_string(),
+                    url("http://api.corp.example:3128"),
+                ),
+            ],
+        };
+
+        let proxies = [
+            "https://api.openai.com/v1/responses",
+            "https://docs.example.com/",
+            "https://api.example.com/v1",
+            "http://localhost:8080/mcp",
+            "https://mcp.internal.example/sse",
+            "https://notexample.com/",
+            "http://10.1.2.3:8080/",
+            "http://11.1.2.3/",
+            "http://[fd00::1]/",
+            "https://registry.example:5000/v2/",
+            "https://registry.example/v2/",
+        ]
+        .map(|target| {
+            routes
+                .proxy_for(&url(target))
+                .map(|proxy| proxy.to_string())
+        });
+        assert_eq!(
+            proxies,
+            [
+                Some("http://proxy.corp.example:8080/".to_string()),
+                Some("http://edge.corp.example:3128/".to_string()),
+                Some("http://api.corp.example:3128/".to_string()),
+                None,
+                None,
+                Some("http://proxy.corp.example:8080/".to_string()),
+                None,
+                Some("http://proxy.corp.example:8080/".to_string()),
+                None,
+                None,
+                Some("http://proxy.corp.example:8080/".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn client_certificate_requires_a_key() {
+        let err = LoadedTls::load(&HttpTlsConfig {
+            ca_certificate: None,
+            client_certificate: Some(PathBuf::from("/etc/codex/client.pem")),
+            client_private_key: None,
+        })
+        .err();
+        assert!(matches!(
+            err,
+            Some(HttpNetworkConfigError::IncompleteClientIdentity)
+        ));
+    }
+}
@@ -1,3 +1,4 @@
+use crate::error::TransportError;
 use bytes::Bytes;
 use http::Method;
 use reqwest::header::HeaderMap;
@@ -12,6 +13,18 @@ pub enum RequestCompression {
     Zstd,
 }
 
+impl RequestCompression {
+    /// Compresses a serialized request body. The output depends only on the
+    /// input, so a signature over it matches the bytes sent.
+    pub fn compress(self, body: Vec<u8>) -> Result<Vec<u8>, TransportError> {
+        match self {
+            RequestCompression::None => Ok(body),
+            RequestCompression::Zstd => zstd::stream::encode_all(std::io::Cursor::new(body), 3)
+                .map_err(|err| TransportError::Build(err.to_string())),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Request {
     pub method: Method,
@@ -73,13 +73,10 @@ impl ReqwestTransport {
                     .map_err(|err| TransportError::Build(err.to_string()))?;
                 let pre_compression_bytes = json.len();
                 let compression_start = std::time::Instant::now();
-                let (compressed, content_encoding) = match compression {
+                let compressed = compression.compress(json)?;
+                let content_encoding = match compression {
                     RequestCompression::None => unreachable!("guarded by compression != None"),
-                    RequestCompression::Zstd => (
-                        zstd::stream::encode_all(std::io::Cursor::new(json), 3)
-                            .map_err(|err| TransportError::Build(err.to_string()))?,
-                        http::HeaderValue::from_static("zstd"),
-                    ),
+                    RequestCompression::Zstd => http::HeaderValue::from_static("zstd"),
                 };
                 let post_compression_bytes = compressed.len();
                 let compression_duration = compression_start.elapsed();
@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use chrono::DateTime;
 use chrono::Utc;
 use codex_api::AuthProvider as ApiAuthProvider;
@@ -5,11 +7,13 @@ use codex_api::TransportError;
 use codex_api::error::ApiError;
 use codex_api::rate_limits::parse_promo_message;
 use codex_api::rate_limits::parse_rate_limit_for_limit;
+use codex_protocol::protocol::ProviderErrorKind;
 use http::HeaderMap;
 use serde::Deserialize;
 
 use crate::auth::CodexAuth;
 use crate::error::CodexErr;
+use crate::error::ProviderError;
 use crate::error::RetryLimitReachedError;
 use crate::error::UnexpectedResponseError;
 use crate::error::UsageLimitReachedError;
@@ -87,18 +91,49 @@ pub(crate) fn map_api_error(err: ApiError) -> CodexErr {
                         }
                     }
 
-                    CodexErr::RetryLimit(RetryLimitReachedError {
-                        status,
-                        request_id: extract_request_tracking_id(headers.as_ref()),
+                    CodexErr::Provider(ProviderError {
+                        kind: ProviderErrorKind::RateLimited,
+                        retry_after: extract_header(headers.as_ref(), RETRY_AFTER_HEADER)
+                            .and_then(|value| value.trim().parse().ok())
+                            .map(Duration::from_secs),
+                        response: UnexpectedResponseError {
+                            status,
+                            body: body_text,
+                            url,
+                            cf_ray: extract_header(headers.as_ref(), CF_RAY_HEADER),
+                            request_id: extract_request_id(headers.as_ref()),
+                        },
                     })
                 } else {
-                    CodexErr::UnexpectedStatus(UnexpectedResponseError {
+                    let kind = match status {
+                        http::StatusCode::UNAUTHORIZED => Some(ProviderErrorKind::Unauthorized),
+                        http::StatusCode::FORBIDDEN
+                            if body_text
+                                .to_ascii_lowercase()
+                                .contains("organization must be verified") =>
+                        {
+                            Some(ProviderErrorKind::OrganizationVerificationRequired)
+                        }
+                        http::StatusCode::BAD_GATEWAY
+                        | http::StatusCode::SERVICE_UNAVAILABLE
+                        | http::StatusCode::GATEWAY_TIMEOUT => Some(ProviderErrorKind::ServerError),
+                        _ => None,
+                    };
+                    let response = UnexpectedResponseError {
                         status,
                         body: body_text,
                         url,
                         cf_ray: extract_header(headers.as_ref(), CF_RAY_HEADER),
                         request_id: extract_request_id(headers.as_ref()),
-                    })
+                    };
+                    match kind {
+                        Some(kind) => CodexErr::Provider(ProviderError {
+                            kind,
+                            response,
+                            retry_after: None,
+                        }),
+                        None => CodexErr::UnexpectedStatus(response),
+                    }
                 }
             }
             TransportError::RetryLimit => CodexErr::RetryLimit(RetryLimitReachedError {
@@ -118,10 +153,12 @@ const ACTIVE_LIMIT_HEADER: &str = "x-codex-active-limit";
 const REQUEST_ID_HEADER: &str = "x-request-id";
 const OAI_REQUEST_ID_HEADER: &str = "x-oai-request-id";
 const CF_RAY_HEADER: &str = "cf-ray";
+const RETRY_AFTER_HEADER: &str = "retry-after";
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use codex_protocol::protocol::CodexErrorInfo;
     use pretty_assertions::assert_eq;
 
     #[test]
@@ -217,10 +254,74 @@ mod tests {
             None
         );
     }
-}
 
-fn extract_request_tracking_id(headers: Option<&HeaderMap>) -> Option<String> {
-    extract_request_id(headers).or_else(|| extract_header(headers, CF_RAY_HEADER))
+    #[test]
+    fn map_api_error_classifies_provider_errors() {
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER_HEADER, http::HeaderValue::from_static("30"));
+        let rate_limited = map_api_error(ApiError::Transport(TransportError::Http {
+            status: http::StatusCode::TOO_MANY_REQUESTS,
+            url: None,
+            headers: Some(headers),
+            body: Some(r#"{"error":{"message":"Rate limit reached"}}"#.to_string()),
+        }));
+        assert!(!rate_limited.is_retryable());
+        assert_eq!(
+            rate_limited.to_codex_protocol_error(),
+            CodexErrorInfo::ProviderError {
+                kind: ProviderErrorKind::RateLimited,
+                http_status_code: Some(429),
+                retry_after_seconds: Some(30),
+                remediation: "The provider is rate limiting requests. Try again in 30 seconds."
+                    .to_string(),
+            }
+        );
+
+        let unverified = map_api_error(ApiError::Transport(TransportError::Http {
+            status: http::StatusCode::FORBIDDEN,
+            url: None,
+            headers: None,
+            body: Some(
+                r#"{"error":{"message":"Your organization must be verified to use the model `o3`."}}"#
+                    .to_string(),
+            ),
+        }));
+        assert!(!unverified.is_retryable());
+        let CodexErr::Provider(unverified) = unverified else {
+            panic!("expected CodexErr::Provider, got {unverified:?}");
+        };
+        assert_eq!(
+            unverified.kind,
+            ProviderErrorKind::OrganizationVerificationRequired
+        );
+
+        let kinds = [
+            http::StatusCode::UNAUTHORIZED,
+            http::StatusCode::BAD_GATEWAY,
+            http::StatusCode::GATEWAY_TIMEOUT,
+            http::StatusCode::FORBIDDEN,
+        ]
+        .map(|status| {
+            match map_api_error(ApiError::Transport(TransportError::Http {
+                status,
+                url: None,
+                headers: None,
+                body: None,
+            })) {
+                CodexErr::Provider(err) => Some(err.kind),
+                _ => None,
+            }
+        });
+        assert_eq!(
+            kinds,
+            [
+                Some(ProviderErrorKind::Unauthorized),
+                Some(ProviderErrorKind::ServerError),
+                Some(ProviderErrorKind::ServerError),
+                None,
+            ]
+        );
+    }
 }
 
 fn extract_request_id(headers: Option<&HeaderMap>) -> Option<String> {
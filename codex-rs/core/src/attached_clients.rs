@@ -0,0 +1,295 @@
+//! Additional clients attached to a live thread, e.g. an IDE extension next
+//! to the TUI that started the session (see [`CodexThread::attach_client`]).
+//!
+//! Every event the session sends is also delivered to each attached client,
+//! as it is sent rather than when the primary client reads it. Attached clients submit ops through the thread's
+//! submission queue, so ops from all clients run in the order they arrive;
+//! the session remembers which client sent each one so it can report who
+//! answered an approval ([`EventMsg::ApprovalResolved`]). Each attached
+//! client gets a unique id, so two clients with the same name stay apart.
+//!
+//! A client attached as an [`AttachMode::Observer`] only watches: it gets the
+//! same events, but its ops are rejected until a driver promotes it with
+//! [`Op::PromoteObserver`].
+//!
+//! [`CodexThread::attach_client`]: crate::CodexThread::attach_client
+//! [`EventMsg::ApprovalResolved`]: codex_protocol::protocol::EventMsg::ApprovalResolved
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use async_channel::Receiver;
+use async_channel::Sender;
+use async_channel::TrySendError;
+use codex_protocol::protocol::Event;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::Submission;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::codex::Session;
+use crate::error::CodexErr;
+use crate::error::Result as CodexResult;
+
+/// Events buffered for an attached client. A client that falls further behind
+/// is detached rather than holding up the session.
+const CLIENT_EVENT_BUFFER: usize = 4096;
+
+/// What an attached client may do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttachMode {
+    /// Receives events and submits ops.
+    Driver,
+    /// Receives events only.
+    Observer,
+}
+
+#[derive(Debug)]
+struct Subscriber {
+    id: String,
+    client: String,
+    mode: AttachMode,
+    tx_event: Sender<Event>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct AttachedClients {
+    subscribers: Mutex<Vec<Subscriber>>,
+    /// The id of the attached client that sent each submission not yet
+    /// handled.
+    submitters: Mutex<HashMap<String, String>>,
+}
+
+impl AttachedClients {
+    /// Subscribes a client named `client`, returning its id and events.
+    fn subscribe(&self, client: String, mode: AttachMode) -> (String, Receiver<Event>) {
+        let id = Uuid::now_v7().to_string();
+        let (tx_event, rx_event) = async_channel::bounded(CLIENT_EVENT_BUFFER);
+        self.subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .push(Subscriber {
+                id: id.clone(),
+                client,
+                mode,
+                tx_event,
+            });
+        (id, rx_event)
+    }
+
+    /// The mode of the client with id `id`, or `None` once it is detached.
+    fn mode(&self, id: &str) -> Option<AttachMode> {
+        self.subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .find(|subscriber| subscriber.id == id)
+            .map(|subscriber| subscriber.mode)
+    }
+
+    /// Lets the observer with id `id` submit ops. Returns false if no such
+    /// observer is attached.
+    pub(crate) fn promote(&self, id: &str) -> bool {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        match subscribers
+            .iter_mut()
+            .find(|subscriber| subscriber.id == id && subscriber.mode == AttachMode::Observer)
+        {
+            Some(subscriber) => {
+                subscriber.mode = AttachMode::Driver;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Delivers `event` to every attached client.
+    pub(crate) fn broadcast(&self, event: &Event) {
+        let mut subscribers = self
+            .subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        subscribers.retain(
+            |subscriber| match subscriber.tx_event.try_send(event.clone()) {
+                Ok(()) => true,
+                Err(TrySendError::Full(_)) => {
+                    warn!(
+                        "detaching client {} ({}): it stopped reading events",
+                        subscriber.client, subscriber.id
+                    );
+                    false
+                }
+                Err(TrySendError::Closed(_)) => false,
+            },
+        );
+    }
+
+    fn record_submission(&self, sub_id: String, id: String) {
+        self.submitters
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(sub_id, id);
+    }
+
+    /// The id of the attached client that sent submission `sub_id`, or
+    /// `None` if the thread's primary client sent it.
+    pub(crate) fn take_submitter(&self, sub_id: &str) -> Option<String> {
+        self.submitters
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(sub_id)
+    }
+}
+
+/// A client attached to a live thread with
+/// [`CodexThread::attach_client`](crate::CodexThread::attach_client).
+/// Dropping it detaches the client.
+pub struct AttachedClient {
+    id: String,
+    client: String,
+    rx_event: Receiver<Event>,
+    tx_sub: Sender<Submission>,
+    session: Arc<Session>,
+}
+
+impl AttachedClient {
+    pub(crate) fn new(
+        client: String,
+        mode: AttachMode,
+        tx_sub: Sender<Submission>,
+        session: Arc<Session>,
+    ) -> Self {
+        let (id, rx_event) = session
+            .services
+            .attached_clients
+            .subscribe(client.clone(), mode);
+        Self {
+            id,
+            client,
+            rx_event,
+            tx_sub,
+            session,
+        }
+    }
+
+    /// The id that tells this client apart from others attached to the
+    /// thread, including ones with the same name. [`Op::PromoteObserver`]
+    /// and [`EventMsg::ApprovalResolved`] refer to clients by it.
+    ///
+    /// [`EventMsg::ApprovalResolved`]: codex_protocol::protocol::EventMsg::ApprovalResolved
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn client(&self) -> &str {
+        &self.client
+    }
+
+    /// Queues `op` behind ops already submitted by any client. Fails while
+    /// this client is an observer or after it was detached.
+    pub async fn submit(&self, op: Op) -> CodexResult<String> {
+        let attached_clients = &self.session.services.attached_clients;
+        match attached_clients.mode(&self.id) {
+            Some(AttachMode::Driver) => {}
+            Some(AttachMode::Observer) => {
+                return Err(CodexErr::UnsupportedOperation(format!(
+                    "client {} is attached as an observer and cannot submit ops",
+                    self.client
+                )));
+            }
+            None => {
+                return Err(CodexErr::UnsupportedOperation(format!(
+                    "client {} was detached and cannot submit ops",
+                    self.client
+                )));
+            }
+        }
+        let id = Uuid::now_v7().to_string();
+        attached_clients.record_submission(id.clone(), self.id.clone());
+        if self
+            .tx_sub
+            .send(Submission { id: id.clone(), op })
+            .await
+            .is_err()
+        {
+            attached_clients.take_submitter(&id);
+            return Err(CodexErr::InternalAgentDied);
+        }
+        Ok(id)
+    }
+
+    /// The next event of the thread. Fails once the session has ended or this
+    /// client was detached for falling behind.
+    pub async fn next_event(&self) -> CodexResult<Event> {
+        self.rx_event
+            .recv()
+            .await
+            .map_err(|_| CodexErr::InternalAgentDied)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::EventMsg;
+    use pretty_assertions::assert_eq;
+
+    fn event(id: &str) -> Event {
+        Event {
+            id: id.to_string(),
+            msg: EventMsg::ShutdownComplete,
+        }
+    }
+
+    #[test]
+    fn events_reach_every_client_and_closed_or_stalled_clients_are_dropped() {
+        let clients = AttachedClients::default();
+        let (_, ide) = clients.subscribe("ide".to_string(), AttachMode::Driver);
+        let (_, closed) = clients.subscribe("closed".to_string(), AttachMode::Driver);
+        drop(closed);
+
+        clients.broadcast(&event("1"));
+        assert_eq!(ide.try_recv().map(|event| event.id), Ok("1".to_string()));
+
+        for _ in 0..=CLIENT_EVENT_BUFFER {
+            clients.broadcast(&event("2"));
+        }
+        let subscribers = clients
+            .subscribers
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        assert!(subscribers.is_empty());
+    }
+
+    #[test]
+    fn observers_are_promoted_by_id() {
+        let clients = AttachedClients::default();
+        let (teammate, _teammate_events) =
+            clients.subscribe("teammate".to_string(), AttachMode::Observer);
+        let (namesake, _namesake_events) =
+            clients.subscribe("teammate".to_string(), AttachMode::Observer);
+
+        assert_eq!(clients.mode(&teammate), Some(AttachMode::Observer));
+        assert!(clients.promote(&teammate));
+        assert_eq!(
+            (clients.mode(&teammate), clients.mode(&namesake)),
+            (Some(AttachMode::Driver), Some(AttachMode::Observer))
+        );
+        assert!(!clients.promote(&teammate));
+        assert!(!clients.promote("teammate"));
+        assert_eq!(clients.mode("nobody"), None);
+    }
+
+    #[test]
+    fn submitters_are_taken_once() {
+        let clients = AttachedClients::default();
+        clients.record_submission("sub-1".to_string(), "ide".to_string());
+
+        assert_eq!(clients.take_submitter("sub-1"), Some("ide".to_string()));
+        assert_eq!(clients.take_submitter("sub-1"), None);
+    }
+}
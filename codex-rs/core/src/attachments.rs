@@ -0,0 +1,272 @@
+//! Content attached to the conversation via
+//! [`Op::AttachInput`](codex_protocol::protocol::Op::AttachInput), such as a
+//! diff, log, JSON document, or image piped into `codex exec`.
+//!
+//! Every attachment is registered on the session with an ID. Small text
+//! attachments enter the context whole, wrapped in an `<attached_input>` block
+//! tagged with their content type. Larger ones and images enter as a short
+//! summary, and the model pulls line ranges (or the image) on demand with the
+//! `read_attachment` tool, so a big log costs little until it is needed.
+
+use std::sync::Arc;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::ArchivedAttachment;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
+use crate::truncate::truncate_text;
+
+pub const ATTACHED_INPUT_OPEN: &str = "<attached_input";
+pub const ATTACHED_INPUT_CLOSE: &str = "</attached_input>";
+
+/// Text attachments up to this size enter the context whole.
+const INLINE_ATTACHMENT_TOKENS: usize = 2_000;
+/// Lines of a summarized text attachment shown up front.
+const PREVIEW_LINES: usize = 20;
+const PREVIEW_MAX_BYTES: usize = 4 * 1024;
+/// Attachments are held in memory for the whole session, so their combined
+/// size is capped.
+const MAX_SESSION_ATTACHMENT_BYTES: usize = 64 * 1024 * 1024;
+
+pub fn is_attached_input_text(text: &str) -> bool {
+    text.trim_start()
+        .to_ascii_lowercase()
+        .starts_with(ATTACHED_INPUT_OPEN)
+}
+
+/// An input registered with the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Attachment {
+    pub(crate) id: String,
+    pub(crate) content_type: String,
+    pub(crate) name: Option<String>,
+    pub(crate) content: Arc<str>,
+}
+
+impl Attachment {
+    pub(crate) fn is_image(&self) -> bool {
+        self.content_type.starts_with("image/")
+    }
+
+    /// The image as a data URL. Image content is either already a data URL
+    /// or base64-encoded bytes.
+    pub(crate) fn image_url(&self) -> String {
+        if self.content.starts_with("data:") {
+            self.content.to_string()
+        } else {
+            format!("data:{};base64,{}", self.content_type, self.content.trim())
+        }
+    }
+
+    /// Whether the model only sees a summary until it reads the attachment.
+    pub(crate) fn is_summarized(&self) -> bool {
+        self.is_image() || approx_token_count(&self.content) > INLINE_ATTACHMENT_TOKENS
+    }
+}
+
+/// Attachments registered with a session, in registration order. They live
+/// for the lifetime of the session and are not restored on resume.
+#[derive(Debug)]
+pub(crate) struct AttachmentRegistry {
+    attachments: Vec<Attachment>,
+    max_bytes: usize,
+}
+
+impl Default for AttachmentRegistry {
+    fn default() -> Self {
+        Self {
+            attachments: Vec::new(),
+            max_bytes: MAX_SESSION_ATTACHMENT_BYTES,
+        }
+    }
+}
+
+impl AttachmentRegistry {
+    /// Registers `content`, or explains why not when it would take the
+    /// session's attachments past their size cap.
+    pub(crate) fn register(
+        &mut self,
+        content_type: String,
+        name: Option<String>,
+        content: String,
+    ) -> Result<Attachment, String> {
+        let used: usize = self
+            .attachments
+            .iter()
+            .map(|attachment| attachment.content.len())
+            .sum();
+        if used + content.len() > self.max_bytes {
+            return Err(format!(
+                "attachment of {} bytes exceeds the session's remaining attachment space of {} bytes",
+                content.len(),
+                self.max_bytes.saturating_sub(used)
+            ));
+        }
+        let attachment = Attachment {
+            id: format!("att-{}", self.attachments.len() + 1),
+            content_type,
+            name,
+            content: content.into(),
+        };
+        self.attachments.push(attachment.clone());
+        Ok(attachment)
+    }
+
+    pub(crate) fn get(&self, id: &str) -> Option<&Attachment> {
+        self.attachments
+            .iter()
+            .find(|attachment| attachment.id == id)
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.attachments.is_empty()
+    }
+
+    /// The attachments without their content, for session archives.
+    pub(crate) fn manifest(&self) -> Vec<ArchivedAttachment> {
+        self.attachments
+            .iter()
+            .map(|attachment| ArchivedAttachment {
+                id: attachment.id.clone(),
+                content_type: attachment.content_type.clone(),
+                name: attachment.name.clone(),
+                bytes: attachment.content.len() as u64,
+            })
+            .collect()
+    }
+}
+
+/// Builds the `<attached_input>` message for `attachment`: the whole content
+/// for small text attachments, otherwise a summary pointing at
+/// `read_attachment`.
+pub(crate) fn attached_input_item(attachment: &Attachment) -> ResponseInputItem {
+    let escape = |value: &str| value.replace('&', "&amp;").replace('"', "&quot;");
+    let mut open = format!(
+        "{ATTACHED_INPUT_OPEN} id=\"{}\" content_type=\"{}\"",
+        attachment.id,
+        escape(&attachment.content_type)
+    );
+    if let Some(name) = &attachment.name {
+        open.push_str(&format!(" name=\"{}\"", escape(name)));
+    }
+
+    let body = if attachment.is_image() {
+        open.push_str(&format!(" bytes=\"{}\"", attachment.content.len()));
+        format!(
+            "Image attachment. Call `read_attachment` with id `{}` to view it.",
+            attachment.id
+        )
+    } else if attachment.is_summarized() {
+        let total_lines = attachment.content.lines().count();
+        open.push_str(&format!(
+            " lines=\"{total_lines}\" bytes=\"{}\"",
+            attachment.content.len()
+        ));
+        let preview = attachment
+            .content
+            .lines()
+            .take(PREVIEW_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+        let preview = truncate_text(&preview, TruncationPolicy::Bytes(PREVIEW_MAX_BYTES));
+        format!(
+            "First {} of {total_lines} lines:\n{preview}\n\nCall `read_attachment` with id `{}` to read more.",
+            PREVIEW_LINES.min(total_lines),
+            attachment.id
+        )
+    } else {
+        attachment.content.to_string()
+    };
+
+    ResponseInputItem::Message {
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!("{open}>\n{body}\n{ATTACHED_INPUT_CLOSE}"),
+        }],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn text(item: &ResponseInputItem) -> &str {
+        match item {
+            ResponseInputItem::Message { content, .. } => match content.as_slice() {
+                [ContentItem::InputText { text }] => text,
+                _ => panic!("unexpected content"),
+            },
+            _ => panic!("unexpected item"),
+        }
+    }
+
+    #[test]
+    fn small_text_attachments_enter_context_whole() {
+        let mut registry = AttachmentRegistry::default();
+        let attachment = registry
+            .register(
+                "text/x-diff".to_string(),
+                Some("changes \"v2\".diff".to_string()),
+                "+added".to_string(),
+            )
+            .expect("register");
+
+        assert!(!attachment.is_summarized());
+        let item = attached_input_item(&attachment);
+        assert_eq!(
+            text(&item),
+            "<attached_input id=\"att-1\" content_type=\"text/x-diff\" name=\"changes &quot;v2&quot;.diff\">\n+added\n</attached_input>"
+        );
+        assert!(is_attached_input_text(text(&item)));
+        assert_eq!(registry.get("att-1"), Some(&attachment));
+    }
+
+    #[test]
+    fn large_and_image_attachments_enter_context_as_summaries() {
+        let mut registry = AttachmentRegistry::default();
+        let log: String = (1..=5_000).map(|n| format!("log line {n}\n")).collect();
+        let log = registry
+            .register("text/plain".to_string(), None, log)
+            .expect("register log");
+        let image = registry
+            .register("image/png".to_string(), None, "aGVsbG8=".to_string())
+            .expect("register image");
+
+        let log_text = text(&attached_input_item(&log)).to_string();
+        assert!(log_text.starts_with(&format!(
+            "<attached_input id=\"att-1\" content_type=\"text/plain\" lines=\"5000\" bytes=\"{}\">\nFirst 20 of 5000 lines:\nlog line 1\n",
+            log.content.len()
+        )));
+        assert!(!log_text.contains("log line 21\n"));
+        assert!(log_text.contains("Call `read_attachment` with id `att-1` to read more."));
+
+        assert_eq!(
+            text(&attached_input_item(&image)),
+            "<attached_input id=\"att-2\" content_type=\"image/png\" bytes=\"8\">\nImage attachment. Call `read_attachment` with id `att-2` to view it.\n</attached_input>"
+        );
+        assert_eq!(image.image_url(), "data:image/png;base64,aGVsbG8=");
+    }
+
+    #[test]
+    fn attachments_past_the_session_cap_are_rejected() {
+        let mut registry = AttachmentRegistry {
+            attachments: Vec::new(),
+            max_bytes: 10,
+        };
+        registry
+            .register("text/plain".to_string(), None, "123456".to_string())
+            .expect("register");
+
+        assert_eq!(
+            registry.register("text/plain".to_string(), None, "12345".to_string()),
+            Err(
+                "attachment of 5 bytes exceeds the session's remaining attachment space of 4 bytes"
+                    .to_string()
+            )
+        );
+        assert_eq!(registry.manifest().len(), 1);
+    }
+}
@@ -78,6 +78,7 @@ use http::HeaderValue;
 use http::StatusCode as HttpStatusCode;
 use reqwest::StatusCode;
 use std::time::Duration;
+use std::time::Instant;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::error::TryRecvError;
@@ -93,7 +94,7 @@ use crate::client_common::Prompt;
 use crate::client_common::ResponseEvent;
 use crate::client_common::ResponseStream;
 use crate::config::Config;
-use crate::default_client::build_reqwest_client;
+use crate::default_client::build_pooled_reqwest_client;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::flags::CODEX_RS_SSE_FIXTURE;
@@ -108,6 +109,8 @@ pub const X_CODEX_TURN_METADATA_HEADER: &str = "x-codex-turn-metadata";
 pub const X_RESPONSESAPI_INCLUDE_TIMING_METRICS_HEADER: &str =
     "x-responsesapi-include-timing-metrics";
 const RESPONSES_WEBSOCKETS_V2_BETA_HEADER_VALUE: &str = "responses_websockets=2026-02-06";
+/// Upper bound on the connection warm-up request sent by [`ModelClient::preconnect`].
+const PRECONNECT_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ResponsesWebsocketVersion {
@@ -146,7 +149,12 @@ struct ModelClientState {
     include_timing_metrics: bool,
     beta_features_header: Option<String>,
     disable_websockets: AtomicBool,
+    /// Offline mode (`Op::SetOffline`): model requests fail fast.
+    offline: AtomicBool,
     cached_websocket_connection: StdMutex<Option<ApiWebSocketConnection>>,
+    /// HTTP clients keyed by provider base URL. Each owns a connection pool
+    /// that lives as long as the session.
+    http_clients: StdMutex<HashMap<String, reqwest::Client>>,
 }
 
 /// Resolved API client setup for a single request attempt.
@@ -248,7 +256,9 @@ impl ModelClient {
                 include_timing_metrics,
                 beta_features_header,
                 disable_websockets: AtomicBool::new(false),
+                offline: AtomicBool::new(false),
                 cached_websocket_connection: StdMutex::new(None),
+                http_clients: StdMutex::new(HashMap::new()),
             }),
         }
     }
@@ -283,6 +293,108 @@ impl ModelClient {
             .unwrap_or_else(std::sync::PoisonError::into_inner) = Some(connection);
     }
 
+    /// Returns the pooled HTTP client for `api_provider`'s endpoint, creating
+    /// it on first use. Clones share one connection pool, so a connection
+    /// opened by one request (or by [`Self::preconnect`]) serves the next.
+    fn http_client(
+        &self,
+        api_provider: &codex_api::Provider,
+        otel_manager: &OtelManager,
+    ) -> reqwest::Client {
+        let mut clients = self
+            .state
+            .http_clients
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let reused = clients.contains_key(&api_provider.base_url);
+        otel_manager.counter(
+            "codex.http_pool.client",
+            1,
+            &[("reused", if reused { "true" } else { "false" })],
+        );
+        clients
+            .entry(api_provider.base_url.clone())
+            .or_insert_with(|| build_pooled_reqwest_client(&api_provider.base_url))
+            .clone()
+    }
+
+    /// Opens a pooled connection to the provider endpoint so the first
+    /// request does not pay TCP, TLS, and HTTP/2 setup. Skipped when requests
+    /// go over websockets. Failures are only logged; requests then connect
+    /// as usual.
+    pub async fn preconnect(&self, otel_manager: &OtelManager, model_info: &ModelInfo) {
+        if CODEX_RS_SSE_FIXTURE.is_some()
+            || self.is_offline()
+            || self.active_ws_version(model_info).is_some()
+        {
+            return;
+        }
+        let client_setup = match self.current_client_setup().await {
+            Ok(client_setup) => client_setup,
+            Err(err) => {
+                warn!("failed to build preconnect client setup: {err}");
+                return;
+            }
+        };
+        let client = self.http_client(&client_setup.api_provider, otel_manager);
+        // Send the credentials real requests carry, so gateways that refuse
+        // unauthenticated traffic still complete the handshake.
+        let mut headers = client_setup.api_provider.headers.clone();
+        codex_api::add_auth_headers_to_header_map(&client_setup.api_auth, &mut headers);
+        let started = Instant::now();
+        // Any response, whatever its status, leaves a warm connection behind.
+        let result = client
+            .head(&client_setup.api_provider.base_url)
+            .headers(headers)
+            .timeout(PRECONNECT_TIMEOUT)
+            .send()
+            .await;
+        otel_manager.record_duration(
+            "codex.http_pool.preconnect",
+            started.elapsed(),
+            &[("success", if result.is_ok() { "true" } else { "false" })],
+        );
+        if let Err(err) = result {
+            warn!("model provider preconnect failed: {err}");
+        }
+    }
+
+    /// Enters or leaves offline mode and returns whether the client was
+    /// offline before. While offline, model requests fail with
+    /// [`CodexErr::Offline`] without touching the network.
+    pub fn set_offline(&self, offline: bool) -> bool {
+        self.state.offline.swap(offline, Ordering::Relaxed)
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.state.offline.load(Ordering::Relaxed)
+    }
+
+    fn ensure_online(&self) -> Result<()> {
+        if self.is_offline() {
+            return Err(CodexErr::Offline);
+        }
+        Ok(())
+    }
+
+    /// Whether the provider endpoint can be reached, checked with a `HEAD`
+    /// request like [`Self::preconnect`]'s. Any response counts, whatever its
+    /// status, and so does the SSE fixture used in tests.
+    pub async fn probe_connectivity(&self, otel_manager: &OtelManager) -> bool {
+        if CODEX_RS_SSE_FIXTURE.is_some() {
+            return true;
+        }
+        let Ok(client_setup) = self.current_client_setup().await else {
+            return false;
+        };
+        self.http_client(&client_setup.api_provider, otel_manager)
+            .head(&client_setup.api_provider.base_url)
+            .timeout(PRECONNECT_TIMEOUT)
+            .send()
+            .await
+            .is_ok()
+    }
+
     /// Compacts the current conversation history using the Compact endpoint.
     ///
     /// This is a unary call (no streaming) that returns a new list of
@@ -299,8 +411,10 @@ impl ModelClient {
         if prompt.input.is_empty() {
             return Ok(Vec::new());
         }
+        self.ensure_online()?;
         let client_setup = self.current_client_setup().await?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport =
+            ReqwestTransport::new(self.http_client(&client_setup.api_provider, otel_manager));
         let request_telemetry = Self::build_request_telemetry(otel_manager);
         let client =
             ApiCompactClient::new(transport, client_setup.api_provider, client_setup.api_auth)
@@ -336,9 +450,11 @@ impl ModelClient {
         if raw_memories.is_empty() {
             return Ok(Vec::new());
         }
+        self.ensure_online()?;
 
         let client_setup = self.current_client_setup().await?;
-        let transport = ReqwestTransport::new(build_reqwest_client());
+        let transport =
+            ReqwestTransport::new(self.http_client(&client_setup.api_provider, otel_manager));
         let request_telemetry = Self::build_request_telemetry(otel_manager);
         let client =
             ApiMemoriesClient::new(transport, client_setup.api_provider, client_setup.api_auth)
@@ -566,6 +682,9 @@ impl ModelClientSession {
             include,
             prompt_cache_key,
             text,
+            temperature: prompt.sampling.temperature,
+            top_p: prompt.sampling.top_p,
+            seed: prompt.sampling.seed,
         };
         Ok(request)
     }
@@ -693,7 +812,7 @@ impl ModelClientSession {
         let Some(ws_version) = self.client.active_ws_version(model_info) else {
             return Ok(());
         };
-        if self.connection.is_some() {
+        if self.connection.is_some() || self.client.is_offline() {
             return Ok(());
         }
 
@@ -801,7 +920,10 @@ impl ModelClientSession {
             .map(super::auth::AuthManager::unauthorized_recovery);
         loop {
             let client_setup = self.client.current_client_setup().await?;
-            let transport = ReqwestTransport::new(build_reqwest_client());
+            let transport = ReqwestTransport::new(
+                self.client
+                    .http_client(&client_setup.api_provider, otel_manager),
+            );
             let (request_telemetry, sse_telemetry) = Self::build_streaming_telemetry(otel_manager);
             let compression = self.responses_request_compression(client_setup.auth.as_ref());
             let options = self.build_responses_options(turn_metadata_header, compression);
@@ -952,6 +1074,7 @@ impl ModelClientSession {
         summary: ReasoningSummaryConfig,
         turn_metadata_header: Option<&str>,
     ) -> Result<ResponseStream> {
+        self.client.ensure_online()?;
         let wire_api = self.client.state.provider.wire_api;
         match wire_api {
             WireApi::Responses => {
@@ -1324,4 +1447,24 @@ mod tests {
             .expect("empty summarize request should succeed");
         assert_eq!(output.len(), 0);
     }
+
+    #[tokio::test]
+    async fn http_client_is_pooled_per_endpoint() {
+        let client = test_model_client(SessionSource::Cli);
+        let otel_manager = test_otel_manager();
+        let client_setup = client.current_client_setup().await.expect("client setup");
+
+        client.http_client(&client_setup.api_provider, &otel_manager);
+        client.http_client(&client_setup.api_provider, &otel_manager);
+
+        let endpoints: Vec<String> = client
+            .state
+            .http_clients
+            .lock()
+            .expect("http clients lock")
+            .keys()
+            .cloned()
+            .collect();
+        assert_eq!(endpoints, vec![client_setup.api_provider.base_url]);
+    }
 }
@@ -2,6 +2,7 @@ use crate::client_common::tools::ToolSpec;
 use crate::config::types::Personality;
 use crate::error::Result;
 pub use codex_api::common::ResponseEvent;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::FunctionCallOutputBody;
 use codex_protocol::models::ResponseItem;
@@ -42,6 +43,9 @@ pub struct Prompt {
 
     /// Optional the output schema for the model's response.
     pub output_schema: Option<Value>,
+
+    /// Sampling parameters for the request.
+    pub(crate) sampling: SamplingParams,
 }
 
 impl Prompt {
@@ -264,6 +268,9 @@ mod tests {
                 verbosity: Some(OpenAiVerbosity::Low),
                 format: None,
             }),
+            temperature: None,
+            top_p: None,
+            seed: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -302,6 +309,9 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: Some(text_controls),
+            temperature: None,
+            top_p: None,
+            seed: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -338,6 +348,9 @@ mod tests {
             include: vec![],
             prompt_cache_key: None,
             text: None,
+            temperature: None,
+            top_p: None,
+            seed: None,
         };
 
         let v = serde_json::to_value(&req).expect("json");
@@ -1,10 +1,15 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::atomic::AtomicU64;
+use std::time::Duration;
+use std::time::Instant;
 
 use crate::AuthManager;
 use crate::CodexAuth;
@@ -16,6 +21,10 @@ use crate::analytics_client::AnalyticsEventsClient;
 use crate::analytics_client::AppInvocation;
 use crate::analytics_client::build_track_events_context;
 use crate::apps::render_apps_section;
+use crate::attached_clients::AttachedClients;
+use crate::attachments::Attachment;
+use crate::command_risk::assess_command_risk;
+use crate::command_risk_model::refine_command_risk;
 use crate::commit_attribution::commit_message_trailer_instruction;
 use crate::compact;
 use crate::compact::InitialContextInjection;
@@ -44,6 +53,8 @@ use crate::stream_events_utils::last_assistant_message_from_item;
 use crate::terminal;
 use crate::truncate::TruncationPolicy;
 use crate::turn_metadata::TurnMetadataState;
+use crate::turn_timing::MAX_TURN_TIMINGS;
+use crate::turn_timing::TurnTimer;
 use crate::util::error_or_panic;
 use crate::ws_version_from_features;
 use async_channel::Receiver;
@@ -69,8 +80,11 @@ use codex_protocol::mcp::CallToolResult;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::format_allow_prefixes;
 use codex_protocol::openai_models::ModelInfo;
+use codex_protocol::protocol::ArchivedAttachment;
 use codex_protocol::protocol::FileChange;
 use codex_protocol::protocol::HasLegacyEvent;
+use codex_protocol::protocol::HistoryItemMetadata;
+use codex_protocol::protocol::HistoryMetadataRecordedEvent;
 use codex_protocol::protocol::ItemCompletedEvent;
 use codex_protocol::protocol::ItemStartedEvent;
 use codex_protocol::protocol::RawResponseItemEvent;
@@ -82,10 +96,12 @@ use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnContextItem;
 use codex_protocol::protocol::TurnContextNetworkItem;
 use codex_protocol::protocol::TurnStartedEvent;
+use codex_protocol::protocol::TurnTiming;
 use codex_protocol::request_user_input::RequestUserInputArgs;
 use codex_protocol::request_user_input::RequestUserInputResponse;
 use codex_rmcp_client::ElicitationResponse;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_rmcp_client::ToolCallNotification;
 use futures::future::BoxFuture;
 use futures::prelude::*;
 use futures::stream::FuturesOrdered;
@@ -99,6 +115,7 @@ use serde_json;
 use serde_json::Value;
 use tokio::sync::Mutex;
 use tokio::sync::RwLock;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 use tokio::sync::watch;
 use tokio_util::sync::CancellationToken;
@@ -144,14 +161,22 @@ pub enum SteerInputError {
     ExpectedTurnMismatch { expected: String, actual: String },
     EmptyInput,
 }
+use crate::context_providers::ContextRequest;
+use crate::context_providers::provide_context_items;
+use crate::exec_env::apply_env_policy_to_mcp_servers;
+use crate::exec_env::resolve_secret_from_store;
 use crate::exec_policy::ExecPolicyUpdateError;
+use crate::experiments::apply_experiment_tool_descriptions;
+use crate::experiments::assign_experiment_arms;
+use crate::experiments::experiment_instruction_layers;
 use crate::feedback_tags;
 use crate::file_watcher::FileWatcher;
 use crate::file_watcher::FileWatcherEvent;
 use crate::git_info::get_git_repo_root;
-use crate::instructions::UserInstructions;
+use crate::instructions::apply_instruction_layers;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::mcp::auth::compute_auth_statuses;
+use crate::mcp::dependency_decisions::McpDependencyDecision;
 use crate::mcp::effective_mcp_servers;
 use crate::mcp::maybe_prompt_and_install_mcp_dependencies;
 use crate::mcp::with_codex_apps_mcp;
@@ -165,6 +190,8 @@ use crate::mentions::build_connector_slug_counts;
 use crate::mentions::build_skill_name_counts;
 use crate::mentions::collect_explicit_app_ids;
 use crate::mentions::collect_tool_mentions_from_messages;
+use crate::notifier::NotificationDispatcher;
+use crate::patch_artifact::PatchArtifact;
 use crate::project_doc::get_user_instructions;
 use crate::proposed_plan_parser::ProposedPlanParser;
 use crate::proposed_plan_parser::ProposedPlanSegment;
@@ -180,6 +207,7 @@ use crate::protocol::Event;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecApprovalRequestEvent;
 use crate::protocol::McpServerRefreshConfig;
+use crate::protocol::ModelRequestUsage;
 use crate::protocol::ModelRerouteEvent;
 use crate::protocol::ModelRerouteReason;
 use crate::protocol::NetworkApprovalContext;
@@ -204,6 +232,7 @@ use crate::protocol::Submission;
 use crate::protocol::TokenCountEvent;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::protocol::ToolSelectionEvent;
 use crate::protocol::TurnDiffEvent;
 use crate::protocol::WarningEvent;
 use crate::rollout::RolloutRecorder;
@@ -211,6 +240,8 @@ use crate::rollout::RolloutRecorderParams;
 use crate::rollout::map_session_init_error;
 use crate::rollout::metadata;
 use crate::rollout::policy::EventPersistenceMode;
+use crate::rollout::retention::prune_sessions;
+use crate::shadow_workspace::ShadowWorkspace;
 use crate::shell;
 use crate::shell_snapshot::ShellSnapshot;
 use crate::skills::SkillError;
@@ -219,35 +250,49 @@ use crate::skills::SkillLoadOutcome;
 use crate::skills::SkillMetadata;
 use crate::skills::SkillsManager;
 use crate::skills::build_skill_injections;
+use crate::skills::collect_cli_dependencies;
 use crate::skills::collect_env_var_dependencies;
 use crate::skills::collect_explicit_skill_mentions;
 use crate::skills::injection::ToolMentionKind;
 use crate::skills::injection::app_id_from_path;
 use crate::skills::injection::tool_kind_for_path;
+use crate::skills::install_missing_cli_dependencies;
 use crate::skills::resolve_skill_dependencies_for_turn;
 use crate::state::ActiveTurn;
 use crate::state::SessionServices;
 use crate::state::SessionState;
 use crate::state_db;
+use crate::suggested_allowlist;
 use crate::tasks::GhostSnapshotTask;
 use crate::tasks::RegularTask;
+use crate::tasks::ResponseCandidates;
 use crate::tasks::ReviewTask;
 use crate::tasks::SessionTask;
 use crate::tasks::SessionTaskContext;
 use crate::tools::ToolRouter;
+use crate::tools::active_calls::ActiveToolCalls;
+use crate::tools::circuit_breaker::CIRCUIT_BREAKER_COOLDOWN;
+use crate::tools::circuit_breaker::CIRCUIT_BREAKER_FAILURE_THRESHOLD;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::findings_delta::FindingBaselines;
 use crate::tools::handlers::SEARCH_TOOL_BM25_TOOL_NAME;
 use crate::tools::js_repl::JsReplHandle;
 use crate::tools::network_approval::NetworkApprovalService;
 use crate::tools::network_approval::build_blocked_request_observer;
 use crate::tools::network_approval::build_network_policy_decider;
+use crate::tools::output_summary::ToolOutputSummaries;
 use crate::tools::parallel::ToolCallRuntime;
+use crate::tools::prefetch::ToolCallPrefetcher;
+use crate::tools::result_cache::ToolResultCache;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::ToolsConfigParams;
+use crate::tools::streaming_arguments::StreamingToolCalls;
 use crate::turn_diff_tracker::TurnDiffTracker;
 use crate::unified_exec::UnifiedExecProcessManager;
+use crate::usage_rollups;
 use crate::util::backoff;
+use crate::watch_mode::start_watch_mode;
 use crate::windows_sandbox::WindowsSandboxLevelExt;
 use crate::zsh_exec_bridge::ZshExecBridge;
 use codex_async_utils::OrCancelExt;
@@ -256,6 +301,7 @@ use codex_otel::TelemetryAuthMode;
 use codex_protocol::config_types::CollaborationMode;
 use codex_protocol::config_types::Personality;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::config_types::WindowsSandboxLevel;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::DeveloperInstructions;
@@ -264,6 +310,7 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::ReasoningEffort as ReasoningEffortConfig;
 use codex_protocol::protocol::CodexErrorInfo;
 use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::InstructionLayerMeta;
 use codex_protocol::user_input::UserInput;
 use codex_utils_absolute_path::AbsolutePathBuf;
 use codex_utils_readiness::Readiness;
@@ -310,7 +357,8 @@ impl Codex {
         persist_extended_history: bool,
     ) -> CodexResult<CodexSpawnOk> {
         let (tx_sub, rx_sub) = async_channel::bounded(SUBMISSION_CHANNEL_CAPACITY);
-        let (tx_event, rx_event) = async_channel::unbounded();
+        let (tx_event, rx_session_event) = async_channel::unbounded();
+        let (tx_primary_event, rx_event) = async_channel::unbounded();
 
         let loaded_skills = skills_manager.skills_for_config(&config);
 
@@ -352,12 +400,36 @@ impl Codex {
         // 1. config.base_instructions override
         // 2. conversation history => session_meta.base_instructions
         // 3. base_instructions for current model
+        // Instruction layers apply on top of 1 and 3; instructions restored
+        // from history already include the layers they were created with.
         let model_info = models_manager.get_model_info(model.as_str(), &config).await;
-        let base_instructions = config
-            .base_instructions
-            .clone()
-            .or_else(|| conversation_history.get_base_instructions().map(|s| s.text))
-            .unwrap_or_else(|| model_info.get_model_instructions(config.personality));
+        // New sessions are assigned experiment arms; resumed and forked
+        // sessions keep the arms recorded in their session metadata.
+        let experiments = match &conversation_history {
+            InitialHistory::New => assign_experiment_arms(&config.experiments),
+            InitialHistory::Resumed(_) | InitialHistory::Forked(_) => {
+                conversation_history.get_experiments().unwrap_or_default()
+            }
+        };
+        let (base_instructions, instruction_layers) = match (
+            config.base_instructions.clone(),
+            conversation_history.get_base_instructions(),
+        ) {
+            (None, Some(history_instructions)) => (history_instructions.text, Vec::new()),
+            (base_instructions, _) => apply_instruction_layers(
+                base_instructions
+                    .unwrap_or_else(|| model_info.get_model_instructions(config.personality)),
+                &config
+                    .instruction_layers
+                    .iter()
+                    .cloned()
+                    .chain(experiment_instruction_layers(
+                        &config.experiments,
+                        &experiments,
+                    ))
+                    .collect::<Vec<_>>(),
+            ),
+        };
 
         // Respect thread-start tools. When missing (resumed/forked threads), read from the db
         // first, then fall back to rollout-file tools.
@@ -398,6 +470,59 @@ impl Codex {
                 developer_instructions: None,
             },
         };
+        let shadow_workspace =
+            if config.shadow_workspace && !matches!(session_source, SessionSource::SubAgent(_)) {
+                // A resumed session keeps editing its own shadow; a fork
+                // starts from a copy of it.
+                let recorded = match conversation_history.session_cwd() {
+                    Some(cwd) => ShadowWorkspace::reopen(&config.codex_home, &cwd).await,
+                    None => Ok(None),
+                };
+                let shadow = match (recorded, &conversation_history) {
+                    (Ok(Some(shadow)), InitialHistory::Resumed(_)) => Ok(shadow),
+                    (Ok(Some(parent)), _) => parent.fork(&config.codex_home).await,
+                    (Ok(None), _) => ShadowWorkspace::create(&config.codex_home, &config.cwd).await,
+                    (Err(err), _) => Err(err),
+                }
+                .map_err(|err| {
+                    CodexErr::Fatal(format!("failed to create shadow workspace: {err:#}"))
+                })?;
+                Some(Arc::new(shadow))
+            } else {
+                None
+            };
+        if config.session_retention.is_enabled()
+            && !matches!(session_source, SessionSource::SubAgent(_))
+        {
+            let config = Arc::clone(&config);
+            let resume_path = match &conversation_history {
+                InitialHistory::Resumed(resumed) => Some(resumed.rollout_path.clone()),
+                InitialHistory::New | InitialHistory::Forked(_) => None,
+            };
+            tokio::spawn(async move {
+                let state_db = state_db::get_state_db(&config, None).await;
+                match prune_sessions(
+                    &config.codex_home,
+                    &config.session_retention,
+                    resume_path.as_deref(),
+                    state_db,
+                    false,
+                )
+                .await
+                {
+                    Ok(report) if !report.pruned.is_empty() => info!(
+                        "pruned {} stored sessions ({} bytes)",
+                        report.pruned.len(),
+                        report.freed_bytes
+                    ),
+                    Ok(_) => {}
+                    Err(err) => warn!("failed to prune stored sessions: {err}"),
+                }
+            });
+        }
+        let cwd = shadow_workspace
+            .as_ref()
+            .map_or_else(|| config.cwd.clone(), |shadow| shadow.cwd().to_path_buf());
         let session_configuration = SessionConfiguration {
             provider: config.model_provider.clone(),
             collaboration_mode,
@@ -405,17 +530,24 @@ impl Codex {
             developer_instructions: config.developer_instructions.clone(),
             user_instructions,
             personality: config.personality,
+            sampling: SamplingParams {
+                temperature: config.model_temperature,
+                top_p: config.model_top_p,
+                seed: config.model_seed,
+            },
             base_instructions,
             compact_prompt: config.compact_prompt.clone(),
             approval_policy: config.permissions.approval_policy.clone(),
             sandbox_policy: config.permissions.sandbox_policy.clone(),
             windows_sandbox_level: WindowsSandboxLevel::from_config(&config),
-            cwd: config.cwd.clone(),
+            cwd,
             codex_home: config.codex_home.clone(),
             thread_name: None,
             original_config_do_not_use: Arc::clone(&config),
             session_source,
             dynamic_tools,
+            instruction_layers,
+            experiments,
             persist_extended_history,
         };
 
@@ -437,6 +569,7 @@ impl Codex {
             skills_manager,
             file_watcher,
             agent_control,
+            shadow_workspace,
         )
         .instrument(session_init_span)
         .await
@@ -446,6 +579,21 @@ impl Codex {
         })?;
         let thread_id = session.conversation_id;
 
+        // Fan every event out to the attached clients as it is sent, whether or
+        // not the primary client is reading. Holds the session weakly so it
+        // ends once the session and its event senders are dropped.
+        let fan_out_session = Arc::downgrade(&session);
+        tokio::spawn(async move {
+            while let Ok(event) = rx_session_event.recv().await {
+                if let Some(session) = fan_out_session.upgrade() {
+                    session.services.attached_clients.broadcast(&event);
+                }
+                if tx_primary_event.send(event).await.is_err() {
+                    break;
+                }
+            }
+        });
+
         // This task will run until Op::Shutdown is received.
         let session_loop_span = info_span!("session_loop", thread_id = %thread_id);
         tokio::spawn(
@@ -533,6 +681,8 @@ pub(crate) struct Session {
     pending_mcp_server_refresh_config: Mutex<Option<McpServerRefreshConfig>>,
     pub(crate) conversation: Arc<RealtimeConversationManager>,
     pub(crate) active_turn: Mutex<Option<ActiveTurn>>,
+    /// When the session last became idle, or `None` while a turn is active.
+    pub(crate) idle_since: watch::Sender<Option<Instant>>,
     pub(crate) services: SessionServices,
     js_repl: Arc<JsReplHandle>,
     next_internal_sub_id: AtomicU64,
@@ -568,12 +718,14 @@ pub(crate) struct TurnContext {
     pub(crate) features: Features,
     pub(crate) ghost_snapshot: GhostSnapshotConfig,
     pub(crate) final_output_json_schema: Option<Value>,
+    pub(crate) sampling: SamplingParams,
     pub(crate) codex_linux_sandbox_exe: Option<PathBuf>,
     pub(crate) tool_call_gate: Arc<ReadinessFlag>,
     pub(crate) truncation_policy: TruncationPolicy,
     pub(crate) js_repl: Arc<JsReplHandle>,
     pub(crate) dynamic_tools: Vec<DynamicToolSpec>,
     pub(crate) turn_metadata_state: Arc<TurnMetadataState>,
+    pub(crate) timer: Arc<TurnTimer>,
 }
 impl TurnContext {
     pub(crate) fn model_context_window(&self) -> Option<i64> {
@@ -620,7 +772,9 @@ impl TurnContext {
             web_search_mode: self.tools_config.web_search_mode,
         })
         .with_allow_login_shell(self.tools_config.allow_login_shell)
-        .with_agent_roles(config.agent_roles.clone());
+        .with_agent_roles(config.agent_roles.clone())
+        .with_read_only(self.tools_config.read_only)
+        .with_patch_artifact(self.tools_config.patch_artifact);
 
         Self {
             sub_id: self.sub_id.clone(),
@@ -650,12 +804,14 @@ impl TurnContext {
             features,
             ghost_snapshot: self.ghost_snapshot.clone(),
             final_output_json_schema: self.final_output_json_schema.clone(),
+            sampling: self.sampling,
             codex_linux_sandbox_exe: self.codex_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy,
             js_repl: Arc::clone(&self.js_repl),
             dynamic_tools: self.dynamic_tools.clone(),
             turn_metadata_state: self.turn_metadata_state.clone(),
+            timer: Arc::clone(&self.timer),
         }
     }
 
@@ -687,6 +843,7 @@ impl TurnContext {
             developer_instructions: self.developer_instructions.clone(),
             final_output_json_schema: self.final_output_json_schema.clone(),
             truncation_policy: Some(self.truncation_policy.into()),
+            sampling: (!self.sampling.is_empty()).then_some(self.sampling),
         }
     }
 
@@ -721,6 +878,9 @@ pub(crate) struct SessionConfiguration {
     /// Personality preference for the model.
     personality: Option<Personality>,
 
+    /// Sampling parameters for model requests; turns may override them.
+    sampling: SamplingParams,
+
     /// Base instructions for the session.
     base_instructions: String,
 
@@ -751,6 +911,11 @@ pub(crate) struct SessionConfiguration {
     /// Source of the session (cli, vscode, exec, mcp, ...)
     session_source: SessionSource,
     dynamic_tools: Vec<DynamicToolSpec>,
+    /// Instruction layers applied to `base_instructions`, recorded in the
+    /// session metadata.
+    instruction_layers: Vec<InstructionLayerMeta>,
+    /// Prompt experiment arms assigned to the session, keyed by experiment.
+    experiments: BTreeMap<String, String>,
     persist_extended_history: bool,
 }
 
@@ -809,6 +974,8 @@ pub(crate) struct SessionSettingsUpdate {
     pub(crate) reasoning_summary: Option<ReasoningSummaryConfig>,
     pub(crate) final_output_json_schema: Option<Option<Value>>,
     pub(crate) personality: Option<Personality>,
+    /// Sampling overrides for this turn only.
+    pub(crate) sampling: Option<SamplingParams>,
 }
 
 impl Session {
@@ -953,7 +1120,9 @@ impl Session {
             web_search_mode: Some(per_turn_config.web_search_mode.value()),
         })
         .with_allow_login_shell(per_turn_config.permissions.allow_login_shell)
-        .with_agent_roles(per_turn_config.agent_roles.clone());
+        .with_agent_roles(per_turn_config.agent_roles.clone())
+        .with_read_only(per_turn_config.read_only_session)
+        .with_patch_artifact(per_turn_config.patch_artifact);
 
         let cwd = session_configuration.cwd.clone();
         let turn_metadata_state = Arc::new(TurnMetadataState::new(
@@ -990,12 +1159,14 @@ impl Session {
             features: per_turn_config.features.clone(),
             ghost_snapshot: per_turn_config.ghost_snapshot.clone(),
             final_output_json_schema: None,
+            sampling: session_configuration.sampling,
             codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
             tool_call_gate: Arc::new(ReadinessFlag::new()),
             truncation_policy: model_info.truncation_policy.into(),
             js_repl,
             dynamic_tools: session_configuration.dynamic_tools.clone(),
             turn_metadata_state,
+            timer: Arc::default(),
         }
     }
 
@@ -1013,6 +1184,7 @@ impl Session {
         skills_manager: Arc<SkillsManager>,
         file_watcher: Arc<FileWatcher>,
         agent_control: AgentControl,
+        shadow_workspace: Option<Arc<ShadowWorkspace>>,
     ) -> anyhow::Result<Arc<Self>> {
         debug!(
             "Configuring session: model={}; provider={:?}",
@@ -1046,7 +1218,9 @@ impl Session {
                         } else {
                             EventPersistenceMode::Limited
                         },
-                    ),
+                    )
+                    .with_instruction_layers(session_configuration.instruction_layers.clone())
+                    .with_experiments(session_configuration.experiments.clone()),
                 )
             }
             InitialHistory::Resumed(resumed_history) => (
@@ -1076,17 +1250,31 @@ impl Session {
         // - load history metadata
         let rollout_fut = async {
             if config.ephemeral {
-                Ok::<_, anyhow::Error>((None, None))
+                Ok::<_, anyhow::Error>((None, None, None))
+            } else if let Err(err) = crate::rollout::encryption::sealing_key(&config).await {
+                // Encryption is on without a key: save nothing rather than
+                // plaintext.
+                warn!("not recording session: {err}");
+                Ok((None, None, Some(err.to_string())))
             } else {
                 let state_db_ctx = state_db::init_if_enabled(&config, None).await;
-                let rollout_recorder = RolloutRecorder::new(
+                match RolloutRecorder::new(
                     &config,
                     rollout_params,
                     state_db_ctx.clone(),
                     state_builder.clone(),
                 )
-                .await?;
-                Ok((Some(rollout_recorder), state_db_ctx))
+                .await
+                {
+                    Ok(rollout_recorder) => Ok((Some(rollout_recorder), state_db_ctx, None)),
+                    // Another process is recording this session: continue
+                    // without recording rather than corrupt its rollout.
+                    Err(err) if err.kind() == std::io::ErrorKind::ResourceBusy => {
+                        warn!("not recording session: {err}");
+                        Ok((None, state_db_ctx, Some(err.to_string())))
+                    }
+                    Err(err) => Err(err.into()),
+                }
             }
         };
 
@@ -1111,10 +1299,11 @@ impl Session {
             (auth, mcp_servers, auth_statuses),
         ) = tokio::join!(rollout_fut, history_meta_fut, auth_and_mcp_fut);
 
-        let (rollout_recorder, state_db_ctx) = rollout_recorder_and_state_db.map_err(|e| {
-            error!("failed to initialize rollout recorder: {e:#}");
-            e
-        })?;
+        let (rollout_recorder, state_db_ctx, read_only_reason) = rollout_recorder_and_state_db
+            .map_err(|e| {
+                error!("failed to initialize rollout recorder: {e:#}");
+                e
+            })?;
         let rollout_path = rollout_recorder
             .as_ref()
             .map(|rec| rec.rollout_path.clone());
@@ -1151,6 +1340,16 @@ impl Session {
                 }),
             });
         }
+        if let Some(reason) = read_only_reason {
+            post_session_configured_events.push(Event {
+                id: "".to_owned(),
+                msg: EventMsg::Warning(WarningEvent {
+                    message: format!(
+                        "{reason}. This session is read-only here: nothing from this window will be saved to it."
+                    ),
+                }),
+            });
+        }
         maybe_push_unstable_features_warning(&config, &mut post_session_configured_events);
         if config.permissions.approval_policy.value() == AskForApproval::OnFailure {
             post_session_configured_events.push(Event {
@@ -1174,7 +1373,8 @@ impl Session {
             config.otel.log_user_prompt,
             terminal::user_agent(),
             session_configuration.session_source.clone(),
-        );
+        )
+        .with_experiments(&session_configuration.experiments);
         config.features.emit_metrics(&otel_manager);
         otel_manager.counter(
             "codex.thread.started",
@@ -1316,6 +1516,12 @@ impl Session {
             hooks: Hooks::new(HooksConfig {
                 legacy_notify_argv: config.notify.clone(),
             }),
+            notifications: NotificationDispatcher::new(&config.notifier),
+            attached_clients: AttachedClients::default(),
+            tool_output_summaries: Mutex::new(ToolOutputSummaries::default()),
+            tool_result_cache: Mutex::new(ToolResultCache::default()),
+            finding_baselines: Mutex::new(FindingBaselines::default()),
+            active_tool_calls: ActiveToolCalls::default(),
             rollout: Mutex::new(rollout_recorder),
             user_shell: Arc::new(default_shell),
             shell_snapshot_tx,
@@ -1331,6 +1537,22 @@ impl Session {
             network_proxy,
             network_approval: Arc::clone(&network_approval),
             state_db: state_db_ctx.clone(),
+            shadow_workspace,
+            patch_artifact: config.patch_artifact.then(|| {
+                let root = get_git_repo_root(&session_configuration.cwd)
+                    .unwrap_or_else(|| session_configuration.cwd.clone());
+                // Sub-agents keep their proposals in their own artifact rather
+                // than overwriting the parent's output file.
+                let output_path = if matches!(
+                    session_configuration.session_source,
+                    SessionSource::SubAgent(_)
+                ) {
+                    None
+                } else {
+                    config.patch_artifact_path.clone()
+                };
+                Mutex::new(PatchArtifact::new(root, output_path))
+            }),
             model_client: ModelClient::new(
                 Some(Arc::clone(&auth_manager)),
                 conversation_id,
@@ -1355,6 +1577,7 @@ impl Session {
             services.model_client.clone(),
             services.otel_manager.clone(),
             prewarm_model_info,
+            config.features.enabled(Feature::ModelPreconnect),
         );
         state.set_startup_regular_task(startup_regular_task);
 
@@ -1367,6 +1590,7 @@ impl Session {
             pending_mcp_server_refresh_config: Mutex::new(None),
             conversation: Arc::new(RealtimeConversationManager::new()),
             active_turn: Mutex::new(None),
+            idle_since: watch::Sender::new(Some(Instant::now())),
             services,
             js_repl,
             next_internal_sub_id: AtomicU64::new(0),
@@ -1405,6 +1629,14 @@ impl Session {
 
         // Start the watcher after SessionConfigured so it cannot emit earlier events.
         sess.start_file_watcher_listener();
+        if let Some(watch) = config.watch.as_ref()
+            && !matches!(
+                session_configuration.session_source,
+                SessionSource::SubAgent(_)
+            )
+        {
+            start_watch_mode(&sess, watch, &session_configuration.cwd);
+        }
 
         // Construct sandbox_state before MCP startup so it can be sent to each
         // MCP server immediately after it becomes ready (avoiding blocking).
@@ -1434,6 +1666,7 @@ impl Session {
             sandbox_state,
             config.codex_home.clone(),
             codex_apps_tools_cache_key(auth),
+            config.mcp_server_limits,
         )
         .await;
         {
@@ -1549,14 +1782,23 @@ impl Session {
         }
     }
 
-    pub(crate) async fn merge_mcp_tool_selection(&self, tool_names: Vec<String>) -> Vec<String> {
-        let mut state = self.state.lock().await;
-        state.merge_mcp_tool_selection(tool_names)
+    pub(crate) async fn experiment_arms(&self) -> BTreeMap<String, String> {
+        let state = self.state.lock().await;
+        state.session_configuration.experiments.clone()
     }
 
-    pub(crate) async fn set_mcp_tool_selection(&self, tool_names: Vec<String>) {
-        let mut state = self.state.lock().await;
-        state.set_mcp_tool_selection(tool_names);
+    pub(crate) async fn merge_mcp_tool_selection(&self, tool_names: Vec<String>) -> Vec<String> {
+        let (merged, selection) = {
+            let mut state = self.state.lock().await;
+            let previous = state.get_mcp_tool_selection();
+            let merged = state.merge_mcp_tool_selection(tool_names);
+            let changed = previous.as_ref() != Some(&merged);
+            (merged, changed.then(|| state.tool_selection()))
+        };
+        if let Some(selection) = selection {
+            self.persist_tool_selection(selection).await;
+        }
+        merged
     }
 
     pub(crate) async fn get_mcp_tool_selection(&self) -> Option<Vec<String>> {
@@ -1574,8 +1816,81 @@ impl Session {
         &self,
         connector_ids: HashSet<String>,
     ) -> HashSet<String> {
+        let (merged, selection) = {
+            let mut state = self.state.lock().await;
+            let previous_len = state.get_connector_selection().len();
+            let merged = state.merge_connector_selection(connector_ids);
+            let grew = merged.len() > previous_len;
+            (merged, grew.then(|| state.tool_selection()))
+        };
+        if let Some(selection) = selection {
+            self.persist_tool_selection(selection).await;
+        }
+        merged
+    }
+
+    /// Records the current connector and MCP tool selection in the rollout so
+    /// that resuming the session can restore it.
+    async fn persist_tool_selection(&self, selection: ToolSelectionEvent) {
+        self.persist_rollout_items(&[RolloutItem::EventMsg(EventMsg::ToolSelection(selection))])
+            .await;
+    }
+
+    /// Restores a selection recorded in a resumed or forked rollout and tells
+    /// the client what came back.
+    async fn restore_tool_selection(
+        &self,
+        turn_context: &TurnContext,
+        selection: ToolSelectionEvent,
+    ) {
+        {
+            let mut state = self.state.lock().await;
+            state.restore_tool_selection(selection.clone());
+        }
+        if !selection.connectors.is_empty() || !selection.mcp_tools.is_empty() {
+            self.send_event(turn_context, EventMsg::ToolSelectionRestored(selection))
+                .await;
+        }
+    }
+
+    pub(crate) async fn take_restored_connector_selection(&self) -> HashSet<String> {
+        let mut state = self.state.lock().await;
+        state.take_restored_connector_selection()
+    }
+
+    pub(crate) async fn register_attachment(
+        &self,
+        content_type: String,
+        name: Option<String>,
+        content: String,
+    ) -> Result<Attachment, String> {
+        let mut state = self.state.lock().await;
+        state.attachments.register(content_type, name, content)
+    }
+
+    pub(crate) async fn attachment(&self, id: &str) -> Option<Attachment> {
+        let state = self.state.lock().await;
+        state.attachments.get(id).cloned()
+    }
+
+    pub(crate) async fn disabled_tools(&self) -> BTreeSet<String> {
+        let state = self.state.lock().await;
+        state.disabled_tools()
+    }
+
+    pub(crate) async fn set_response_candidates(&self, candidates: ResponseCandidates) {
         let mut state = self.state.lock().await;
-        state.merge_connector_selection(connector_ids)
+        state.response_candidates = Some(candidates);
+    }
+
+    pub(crate) async fn has_attachments(&self) -> bool {
+        let state = self.state.lock().await;
+        !state.attachments.is_empty()
+    }
+
+    pub(crate) async fn attachment_manifest(&self) -> Vec<ArchivedAttachment> {
+        let state = self.state.lock().await;
+        state.attachments.manifest()
     }
 
     // Returns the connector IDs currently selected for this session.
@@ -1585,9 +1900,18 @@ impl Session {
     }
 
     // Clears connector IDs that were accumulated for explicit selection.
+    // The cleared selection is recorded too, so resuming does not bring back
+    // connectors from an earlier turn.
     pub(crate) async fn clear_connector_selection(&self) {
-        let mut state = self.state.lock().await;
-        state.clear_connector_selection();
+        let selection = {
+            let mut state = self.state.lock().await;
+            let had_connectors = !state.get_connector_selection().is_empty();
+            state.clear_connector_selection();
+            had_connectors.then(|| state.tool_selection())
+        };
+        if let Some(selection) = selection {
+            self.persist_tool_selection(selection).await;
+        }
     }
 
     async fn record_initial_history(&self, conversation_history: InitialHistory) {
@@ -1609,7 +1933,7 @@ impl Session {
             InitialHistory::Resumed(resumed_history) => {
                 let rollout_items = resumed_history.history;
                 let restored_tool_selection =
-                    Self::extract_mcp_tool_selection_from_rollout(&rollout_items);
+                    Self::extract_tool_selection_from_rollout(&rollout_items);
                 let (previous_regular_turn_context_item, crossed_compaction_after_turn) =
                     Self::last_rollout_regular_turn_context_lookup(&rollout_items);
                 let previous_model =
@@ -1647,10 +1971,11 @@ impl Session {
                 let reconstructed_history = self
                     .reconstruct_history_from_rollout(&turn_context, &rollout_items)
                     .await;
-                if !reconstructed_history.is_empty() {
-                    self.record_into_history(&reconstructed_history, &turn_context)
-                        .await;
-                }
+                self.state
+                    .lock()
+                    .await
+                    .history
+                    .extend(reconstructed_history);
 
                 // Seed usage info from the recorded rollout so UIs can show token counts
                 // immediately on resume/fork.
@@ -1658,8 +1983,10 @@ impl Session {
                     let mut state = self.state.lock().await;
                     state.set_token_info(Some(info));
                 }
-                if let Some(selected_tools) = restored_tool_selection {
-                    self.set_mcp_tool_selection(selected_tools).await;
+                self.state.lock().await.turn_timings =
+                    Self::turn_timings_from_rollout(&rollout_items);
+                if let Some(selection) = restored_tool_selection {
+                    self.restore_tool_selection(&turn_context, selection).await;
                 }
 
                 // Defer seeding the session's initial context until the first turn starts so
@@ -1668,7 +1995,7 @@ impl Session {
             }
             InitialHistory::Forked(rollout_items) => {
                 let restored_tool_selection =
-                    Self::extract_mcp_tool_selection_from_rollout(&rollout_items);
+                    Self::extract_tool_selection_from_rollout(&rollout_items);
                 let (previous_regular_turn_context_item, _) =
                     Self::last_rollout_regular_turn_context_lookup(&rollout_items);
                 let previous_model =
@@ -1679,10 +2006,11 @@ impl Session {
                 let reconstructed_history = self
                     .reconstruct_history_from_rollout(&turn_context, &rollout_items)
                     .await;
-                if !reconstructed_history.is_empty() {
-                    self.record_into_history(&reconstructed_history, &turn_context)
-                        .await;
-                }
+                self.state
+                    .lock()
+                    .await
+                    .history
+                    .extend(reconstructed_history);
 
                 // Seed usage info from the recorded rollout so UIs can show token counts
                 // immediately on resume/fork.
@@ -1690,8 +2018,10 @@ impl Session {
                     let mut state = self.state.lock().await;
                     state.set_token_info(Some(info));
                 }
-                if let Some(selected_tools) = restored_tool_selection {
-                    self.set_mcp_tool_selection(selected_tools).await;
+                self.state.lock().await.turn_timings =
+                    Self::turn_timings_from_rollout(&rollout_items);
+                if let Some(selection) = restored_tool_selection {
+                    self.restore_tool_selection(&turn_context, selection).await;
                 }
 
                 // If persisting, persist all rollout items as-is (recorder filters)
@@ -1868,6 +2198,47 @@ impl Session {
         })
     }
 
+    /// The latency breakdowns of the last [`MAX_TURN_TIMINGS`] turns recorded
+    /// in the rollout, oldest first.
+    fn turn_timings_from_rollout(rollout_items: &[RolloutItem]) -> VecDeque<TurnTiming> {
+        let mut turn_timings: Vec<TurnTiming> = rollout_items
+            .iter()
+            .rev()
+            .filter_map(|item| match item {
+                RolloutItem::EventMsg(EventMsg::TurnTimingRecorded(timing)) => Some(timing.clone()),
+                _ => None,
+            })
+            .take(MAX_TURN_TIMINGS)
+            .collect();
+        turn_timings.reverse();
+        turn_timings.into()
+    }
+
+    /// The last connector and MCP tool selection recorded in the rollout.
+    /// Rollouts written before selections were recorded only carry the MCP
+    /// tools, in the search tool's output.
+    fn extract_tool_selection_from_rollout(
+        rollout_items: &[RolloutItem],
+    ) -> Option<ToolSelectionEvent> {
+        rollout_items
+            .iter()
+            .rev()
+            .find_map(|item| match item {
+                RolloutItem::EventMsg(EventMsg::ToolSelection(selection)) => {
+                    Some(selection.clone())
+                }
+                _ => None,
+            })
+            .or_else(|| {
+                Self::extract_mcp_tool_selection_from_rollout(rollout_items).map(|mcp_tools| {
+                    ToolSelectionEvent {
+                        connectors: Vec::new(),
+                        mcp_tools,
+                    }
+                })
+            })
+    }
+
     fn extract_mcp_tool_selection_from_rollout(
         rollout_items: &[RolloutItem],
     ) -> Option<Vec<String>> {
@@ -1961,10 +2332,16 @@ impl Session {
                 let previous_cwd = state.session_configuration.cwd.clone();
                 let next_cwd = updated.cwd.clone();
                 let codex_home = updated.codex_home.clone();
-                state.session_configuration = updated;
+                let sandbox_state_changed = previous_cwd != next_cwd
+                    || state.session_configuration.sandbox_policy != updated.sandbox_policy;
+                state.session_configuration = updated.clone();
                 drop(state);
 
                 self.maybe_refresh_shell_snapshot_for_cwd(&previous_cwd, &next_cwd, &codex_home);
+                if sandbox_state_changed {
+                    self.notify_mcp_sandbox_state(&Self::build_per_turn_config(&updated))
+                        .await;
+                }
 
                 Ok(())
             }
@@ -1980,16 +2357,16 @@ impl Session {
         sub_id: String,
         updates: SessionSettingsUpdate,
     ) -> ConstraintResult<Arc<TurnContext>> {
-        let (session_configuration, sandbox_policy_changed, previous_cwd, codex_home) = {
+        let (session_configuration, sandbox_state_changed, previous_cwd, codex_home) = {
             let mut state = self.state.lock().await;
             match state.session_configuration.clone().apply(&updates) {
                 Ok(next) => {
                     let previous_cwd = state.session_configuration.cwd.clone();
-                    let sandbox_policy_changed =
-                        state.session_configuration.sandbox_policy != next.sandbox_policy;
+                    let sandbox_state_changed = previous_cwd != next.cwd
+                        || state.session_configuration.sandbox_policy != next.sandbox_policy;
                     let codex_home = next.codex_home.clone();
                     state.session_configuration = next.clone();
-                    (next, sandbox_policy_changed, previous_cwd, codex_home)
+                    (next, sandbox_state_changed, previous_cwd, codex_home)
                 }
                 Err(err) => {
                     drop(state);
@@ -2017,17 +2394,42 @@ impl Session {
                 sub_id,
                 session_configuration,
                 updates.final_output_json_schema,
-                sandbox_policy_changed,
+                updates.sampling,
+                sandbox_state_changed,
             )
             .await)
     }
 
+    /// Tells MCP servers the sandbox and workspace roots of
+    /// `per_turn_config`.
+    async fn notify_mcp_sandbox_state(&self, per_turn_config: &Config) {
+        let sandbox_state = SandboxState {
+            sandbox_policy: per_turn_config.permissions.sandbox_policy.get().clone(),
+            codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
+            sandbox_cwd: per_turn_config.cwd.clone(),
+            use_linux_sandbox_bwrap: per_turn_config
+                .features
+                .enabled(Feature::UseLinuxSandboxBwrap),
+        };
+        if let Err(e) = self
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .notify_sandbox_state_change(&sandbox_state)
+            .await
+        {
+            warn!("Failed to notify sandbox state change to MCP servers: {e:#}");
+        }
+    }
+
     async fn new_turn_from_configuration(
         &self,
         sub_id: String,
         session_configuration: SessionConfiguration,
         final_output_json_schema: Option<Option<Value>>,
-        sandbox_policy_changed: bool,
+        sampling: Option<SamplingParams>,
+        sandbox_state_changed: bool,
     ) -> Arc<TurnContext> {
         let per_turn_config = Self::build_per_turn_config(&session_configuration);
         self.services
@@ -2036,25 +2438,8 @@ impl Session {
             .await
             .set_approval_policy(&session_configuration.approval_policy);
 
-        if sandbox_policy_changed {
-            let sandbox_state = SandboxState {
-                sandbox_policy: per_turn_config.permissions.sandbox_policy.get().clone(),
-                codex_linux_sandbox_exe: per_turn_config.codex_linux_sandbox_exe.clone(),
-                sandbox_cwd: per_turn_config.cwd.clone(),
-                use_linux_sandbox_bwrap: per_turn_config
-                    .features
-                    .enabled(Feature::UseLinuxSandboxBwrap),
-            };
-            if let Err(e) = self
-                .services
-                .mcp_connection_manager
-                .read()
-                .await
-                .notify_sandbox_state_change(&sandbox_state)
-                .await
-            {
-                warn!("Failed to notify sandbox state change to MCP servers: {e:#}");
-            }
+        if sandbox_state_changed {
+            self.notify_mcp_sandbox_state(&per_turn_config).await;
         }
 
         let model_info = self
@@ -2083,6 +2468,9 @@ impl Session {
         if let Some(final_schema) = final_output_json_schema {
             turn_context.final_output_json_schema = final_schema;
         }
+        if let Some(sampling) = sampling {
+            turn_context.sampling = turn_context.sampling.with_overrides(sampling);
+        }
         let turn_context = Arc::new(turn_context);
         turn_context.turn_metadata_state.spawn_git_enrichment_task();
         turn_context
@@ -2173,7 +2561,7 @@ impl Session {
             let state = self.state.lock().await;
             state.session_configuration.clone()
         };
-        self.new_turn_from_configuration(sub_id, session_configuration, None, false)
+        self.new_turn_from_configuration(sub_id, session_configuration, None, None, false)
             .await
     }
 
@@ -2237,6 +2625,9 @@ impl Session {
         if let Some(status) = agent_status_from_event(&event.msg) {
             self.agent_status.send_replace(status);
         }
+        self.services
+            .notifications
+            .dispatch(self.conversation_id, &event.msg);
         // Persist the event into rollout (recorder filters as needed)
         let rollout_items = vec![RolloutItem::EventMsg(event.msg.clone())];
         self.persist_rollout_items(&rollout_items).await;
@@ -2398,7 +2789,18 @@ impl Session {
             warn!("Overwriting existing pending approval for call_id: {effective_approval_id}");
         }
 
+        let learned_commands = (turn_context.config.learn_approved_commands
+            && network_approval_context.is_none())
+        .then(|| {
+            suggested_allowlist::approved_commands(&command, proposed_execpolicy_amendment.as_ref())
+        });
         let parsed_cmd = parse_command(&command);
+        let mut risk_assessment = assess_command_risk(&command);
+        if let Some(model) = &turn_context.config.command_risk_model {
+            risk_assessment =
+                refine_command_risk(self, turn_context, model, &command, &cwd, risk_assessment)
+                    .await;
+        }
         let event = EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
             call_id,
             approval_id,
@@ -2409,9 +2811,28 @@ impl Session {
             network_approval_context,
             proposed_execpolicy_amendment,
             parsed_cmd,
+            risk_assessment: Some(risk_assessment),
         });
         self.send_event(turn_context, event).await;
-        rx_approve.await.unwrap_or_default()
+        let decision = rx_approve.await.unwrap_or_default();
+        if let Some(commands) = learned_commands
+            && matches!(
+                decision,
+                ReviewDecision::Approved | ReviewDecision::ApprovedForSession
+            )
+        {
+            let codex_home = turn_context.config.codex_home.clone();
+            let cwd = turn_context.cwd.clone();
+            tokio::task::spawn_blocking(move || {
+                let project_root = suggested_allowlist::project_root(&cwd);
+                if let Err(err) =
+                    suggested_allowlist::record_approvals(&codex_home, &project_root, commands)
+                {
+                    warn!("failed to record approved command: {err}");
+                }
+            });
+        }
+        decision
     }
 
     pub async fn request_patch_approval(
@@ -2528,7 +2949,9 @@ impl Session {
         }
     }
 
-    pub async fn notify_approval(&self, approval_id: &str, decision: ReviewDecision) {
+    /// Answers the pending approval `approval_id`. Returns false if it was
+    /// not pending, e.g. because another client answered it first.
+    pub async fn notify_approval(&self, approval_id: &str, decision: ReviewDecision) -> bool {
         let entry = {
             let mut active = self.active_turn.lock().await;
             match active.as_mut() {
@@ -2542,9 +2965,11 @@ impl Session {
         match entry {
             Some(tx_approve) => {
                 tx_approve.send(decision).ok();
+                true
             }
             None => {
                 warn!("No pending approval found for call_id: {approval_id}");
+                false
             }
         }
     }
@@ -2570,16 +2995,39 @@ impl Session {
         turn_context: &TurnContext,
         items: &[ResponseItem],
     ) {
-        self.record_into_history(items, turn_context).await;
+        let started = Instant::now();
+        let metadata = self.record_into_history(items, turn_context).await;
         self.persist_rollout_response_items(items).await;
+        if !metadata.is_empty() {
+            self.persist_rollout_items(&[RolloutItem::EventMsg(
+                EventMsg::HistoryMetadataRecorded(HistoryMetadataRecordedEvent { metadata }),
+            )])
+            .await;
+        }
+        turn_context.timer.record_serialization(started.elapsed());
         self.send_raw_response_items(turn_context, items).await;
     }
 
+    /// Keeps the latency breakdown of a finished or aborted turn for
+    /// `Op::GetTurnTimings`, in the session state and the rollout.
+    pub(crate) async fn keep_turn_timing(&self, turn_context: &TurnContext) {
+        let timing = turn_context.timer.timing(turn_context.sub_id.clone());
+        {
+            let mut state = self.state.lock().await;
+            if state.turn_timings.len() == MAX_TURN_TIMINGS {
+                state.turn_timings.pop_front();
+            }
+            state.turn_timings.push_back(timing.clone());
+        }
+        self.persist_rollout_items(&[RolloutItem::EventMsg(EventMsg::TurnTimingRecorded(timing))])
+            .await;
+    }
+
     async fn reconstruct_history_from_rollout(
         &self,
         turn_context: &TurnContext,
         rollout_items: &[RolloutItem],
-    ) -> Vec<ResponseItem> {
+    ) -> ContextManager {
         let mut history = ContextManager::new();
         for item in rollout_items {
             match item {
@@ -2605,10 +3053,23 @@ impl Session {
                 RolloutItem::EventMsg(EventMsg::ThreadRolledBack(rollback)) => {
                     history.drop_last_n_user_turns(rollback.num_turns);
                 }
+                RolloutItem::EventMsg(EventMsg::ResponseCandidateSelected(_)) => {
+                    let response_start = history.last_response_start();
+                    history.replace(history.raw_items()[..response_start].to_vec());
+                }
+                RolloutItem::EventMsg(EventMsg::ItemCompleted(ItemCompletedEvent {
+                    item: TurnItem::UserMessage(user_message),
+                    ..
+                })) => {
+                    history.set_last_user_message_id(user_message.id.clone());
+                }
+                RolloutItem::EventMsg(EventMsg::HistoryMetadataRecorded(recorded)) => {
+                    history.restore_metadata(recorded.metadata.clone());
+                }
                 _ => {}
             }
         }
-        history.raw_items().to_vec()
+        history
     }
 
     /// Append ResponseItems to the in-memory conversation history only.
@@ -2616,9 +3077,13 @@ impl Session {
         &self,
         items: &[ResponseItem],
         turn_context: &TurnContext,
-    ) {
+    ) -> Vec<HistoryItemMetadata> {
         let mut state = self.state.lock().await;
-        state.record_items(items.iter(), turn_context.truncation_policy);
+        state.record_items(
+            items.iter(),
+            turn_context.truncation_policy,
+            &turn_context.model_info.slug,
+        )
     }
 
     pub(crate) async fn record_model_warning(&self, message: impl Into<String>, ctx: &TurnContext) {
@@ -2738,13 +3203,15 @@ impl Session {
         if let Some(developer_instructions) = turn_context.developer_instructions.as_deref() {
             items.push(DeveloperInstructions::new(developer_instructions.to_string()).into());
         }
-        // Add developer instructions for memories.
-        if let Some(memory_prompt) =
-            build_memory_tool_developer_instructions(&turn_context.config.codex_home).await
-            && turn_context.features.enabled(Feature::MemoryTool)
-        {
-            items.push(DeveloperInstructions::new(memory_prompt).into());
-        }
+        // Memories, the codebase map, AGENTS.md, and other pluggable context sources.
+        let provided = provide_context_items(&ContextRequest {
+            config: &turn_context.config,
+            cwd: &turn_context.cwd,
+            features: &turn_context.features,
+            user_instructions: turn_context.user_instructions.as_deref(),
+        })
+        .await;
+        items.extend(provided.developer);
         // Add developer instructions from collaboration_mode if they exist and are non-empty
         let (collaboration_mode, base_instructions) = {
             let state = self.state.lock().await;
@@ -2786,15 +3253,7 @@ impl Session {
         {
             items.push(DeveloperInstructions::new(commit_message_instruction).into());
         }
-        if let Some(user_instructions) = turn_context.user_instructions.as_deref() {
-            items.push(
-                UserInstructions {
-                    text: user_instructions.to_string(),
-                    directory: turn_context.cwd.to_string_lossy().into_owned(),
-                }
-                .into(),
-            );
-        }
+        items.extend(provided.user_instructions);
         items.push(ResponseItem::from(EnvironmentContext::from_turn_context(
             turn_context,
             shell.as_ref(),
@@ -2880,6 +3339,7 @@ impl Session {
         &self,
         turn_context: &TurnContext,
         token_usage: Option<&TokenUsage>,
+        request: Option<ModelRequestUsage>,
     ) {
         {
             let mut state = self.state.lock().await;
@@ -2888,7 +3348,25 @@ impl Session {
                     .update_token_info_from_usage(token_usage, turn_context.model_context_window());
             }
         }
-        self.send_token_count_event(turn_context).await;
+        if let Some(token_usage) = token_usage {
+            let codex_home = turn_context.config.codex_home.clone();
+            let cwd = turn_context.cwd.clone();
+            let model = turn_context.model_info.slug.clone();
+            let token_usage = token_usage.clone();
+            tokio::task::spawn_blocking(move || {
+                let project_root = suggested_allowlist::project_root(&cwd);
+                if let Err(err) = usage_rollups::record(
+                    &codex_home,
+                    chrono::Local::now().date_naive(),
+                    &project_root,
+                    &model,
+                    &token_usage,
+                ) {
+                    warn!("failed to record usage: {err}");
+                }
+            });
+        }
+        self.send_token_count_event(turn_context, request).await;
     }
 
     pub(crate) async fn recompute_token_usage(&self, turn_context: &TurnContext) {
@@ -2921,7 +3399,7 @@ impl Session {
 
             state.set_token_info(Some(info));
         }
-        self.send_token_count_event(turn_context).await;
+        self.send_token_count_event(turn_context, None).await;
     }
 
     pub(crate) async fn update_rate_limits(
@@ -2929,24 +3407,75 @@ impl Session {
         turn_context: &TurnContext,
         new_rate_limits: RateLimitSnapshot,
     ) {
-        {
+        let change = {
             let mut state = self.state.lock().await;
-            state.set_rate_limits(new_rate_limits);
+            state.set_rate_limits(new_rate_limits)
+        };
+        if let Some(change) = change {
+            self.send_event(turn_context, EventMsg::PlanOrCreditsChanged(change))
+                .await;
         }
-        self.send_token_count_event(turn_context).await;
+        self.send_token_count_event(turn_context, None).await;
+    }
+
+    pub(crate) async fn mcp_dependency_decisions(&self) -> HashMap<String, McpDependencyDecision> {
+        let state = self.state.lock().await;
+        state.mcp_dependency_decisions()
+    }
+
+    pub(crate) async fn record_mcp_dependency_decisions<I>(&self, decisions: I)
+    where
+        I: IntoIterator<Item = (String, McpDependencyDecision)>,
+    {
+        let mut state = self.state.lock().await;
+        state.record_mcp_dependency_decisions(decisions);
     }
 
-    pub(crate) async fn mcp_dependency_prompted(&self) -> HashSet<String> {
+    pub(crate) async fn skipped_cli_dependencies(&self) -> HashSet<String> {
         let state = self.state.lock().await;
-        state.mcp_dependency_prompted()
+        state.skipped_cli_dependencies.clone()
     }
 
-    pub(crate) async fn record_mcp_dependency_prompted<I>(&self, names: I)
+    pub(crate) async fn record_skipped_cli_dependencies<I>(&self, names: I)
     where
         I: IntoIterator<Item = String>,
     {
         let mut state = self.state.lock().await;
-        state.record_mcp_dependency_prompted(names);
+        state.skipped_cli_dependencies.extend(names);
+    }
+
+    /// Remaining cooldown if the circuit breaker for `key` (a tool or MCP
+    /// server name) is open.
+    pub(crate) async fn tool_circuit_open_for(&self, key: &str) -> Option<Duration> {
+        let state = self.state.lock().await;
+        state.tool_circuit_breakers.open_for(key, Instant::now())
+    }
+
+    pub(crate) async fn record_tool_call_outcome(
+        &self,
+        turn_context: &TurnContext,
+        key: &str,
+        failed: bool,
+    ) {
+        let tripped = {
+            let mut state = self.state.lock().await;
+            if failed {
+                state
+                    .tool_circuit_breakers
+                    .record_failure(key, Instant::now())
+            } else {
+                state.tool_circuit_breakers.record_success(key);
+                false
+            }
+        };
+        if tripped {
+            let message = format!(
+                "`{key}` failed {CIRCUIT_BREAKER_FAILURE_THRESHOLD} times in a row; calls to it are paused for {}s.",
+                CIRCUIT_BREAKER_COOLDOWN.as_secs()
+            );
+            self.send_event(turn_context, EventMsg::Warning(WarningEvent { message }))
+                .await;
+        }
     }
 
     pub async fn dependency_env(&self) -> HashMap<String, String> {
@@ -2964,12 +3493,20 @@ impl Session {
         state.set_server_reasoning_included(included);
     }
 
-    async fn send_token_count_event(&self, turn_context: &TurnContext) {
+    async fn send_token_count_event(
+        &self,
+        turn_context: &TurnContext,
+        request: Option<ModelRequestUsage>,
+    ) {
         let (info, rate_limits) = {
             let state = self.state.lock().await;
             state.token_info_and_rate_limits()
         };
-        let event = EventMsg::TokenCount(TokenCountEvent { info, rate_limits });
+        let event = EventMsg::TokenCount(TokenCountEvent {
+            info,
+            rate_limits,
+            request,
+        });
         self.send_event(turn_context, event).await;
     }
 
@@ -2978,7 +3515,7 @@ impl Session {
             let mut state = self.state.lock().await;
             state.set_token_usage_full(context_window);
         }
-        self.send_token_count_event(turn_context).await;
+        self.send_token_count_event(turn_context, None).await;
     }
 
     pub(crate) async fn record_response_item_and_emit_turn_item(
@@ -3001,14 +3538,19 @@ impl Session {
         &self,
         turn_context: &TurnContext,
         input: &[UserInput],
-        response_item: ResponseItem,
+        mut response_item: ResponseItem,
     ) {
         // Persist the user message to history, but emit the turn item from `UserInput` so
         // UI-only `text_elements` are preserved. `ResponseItem::Message` does not carry
         // those spans, and `record_response_item_and_emit_turn_item` would drop them.
+        let user_message = UserMessageItem::new(input);
+        // Keep the item ID on the history entry so `Op::RetryFromUserMessage` can find it.
+        if let ResponseItem::Message { id: id @ None, .. } = &mut response_item {
+            *id = Some(user_message.id.clone());
+        }
         self.record_conversation_items(turn_context, std::slice::from_ref(&response_item))
             .await;
-        let turn_item = TurnItem::UserMessage(UserMessageItem::new(input));
+        let turn_item = TurnItem::UserMessage(user_message);
         self.emit_turn_item_started(turn_context, &turn_item).await;
         self.emit_turn_item_completed(turn_context, turn_item).await;
         self.ensure_rollout_materialized().await;
@@ -3124,6 +3666,23 @@ impl Session {
         }
     }
 
+    /// Records a tool call outcome for loop detection and returns how many
+    /// times in a row the call has failed with the same output.
+    pub(crate) async fn record_repeated_tool_failure(
+        &self,
+        key: String,
+        failure: Option<String>,
+    ) -> usize {
+        let active = self.active_turn.lock().await;
+        match active.as_ref() {
+            Some(at) => {
+                let mut ts = at.turn_state.lock().await;
+                ts.repeated_failures.record(key, failure)
+            }
+            None => 0,
+        }
+    }
+
     pub async fn get_pending_input(&self) -> Vec<ResponseInputItem> {
         let mut active = self.active_turn.lock().await;
         match active.as_mut() {
@@ -3190,12 +3749,13 @@ impl Session {
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
+        notifications: UnboundedSender<ToolCallNotification>,
     ) -> anyhow::Result<CallToolResult> {
         self.services
             .mcp_connection_manager
             .read()
             .await
-            .call_tool(server, tool, arguments)
+            .call_tool(server, tool, arguments, notifications)
             .await
     }
 
@@ -3234,12 +3794,19 @@ impl Session {
     ) {
         let auth = self.services.auth_manager.auth().await;
         let config = self.get_config().await;
-        let mcp_servers = with_codex_apps_mcp(
+        let mut mcp_servers = with_codex_apps_mcp(
             mcp_servers,
             self.features.enabled(Feature::Apps),
             auth.as_ref(),
             config.as_ref(),
         );
+        apply_env_policy_to_mcp_servers(
+            &mut mcp_servers,
+            &turn_context.shell_environment_policy,
+            |secret_name| {
+                resolve_secret_from_store(&config.codex_home, &turn_context.cwd, secret_name)
+            },
+        );
         let auth_statuses = compute_auth_statuses(mcp_servers.iter(), store_mode).await;
         let sandbox_state = SandboxState {
             sandbox_policy: turn_context.sandbox_policy.get().clone(),
@@ -3261,6 +3828,7 @@ impl Session {
             sandbox_state,
             config.codex_home.clone(),
             codex_apps_tools_cache_key(auth.as_ref()),
+            config.mcp_server_limits,
         )
         .await;
         {
@@ -3344,10 +3912,15 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
     // To break out of this loop, send Op::Shutdown.
     while let Ok(sub) = rx_sub.recv().await {
         debug!(?sub, "Submission");
+        // Taken for every submission so the map only holds unhandled ones.
+        let submitter = sess.services.attached_clients.take_submitter(&sub.id);
         match sub.op.clone() {
             Op::Interrupt => {
                 handlers::interrupt(&sess).await;
             }
+            Op::CancelToolCall { call_id } => {
+                handlers::cancel_tool_call(&sess, sub.id.clone(), call_id).await;
+            }
             Op::CleanBackgroundTerminals => {
                 handlers::clean_background_terminals(&sess).await;
             }
@@ -3419,10 +3992,10 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
                 turn_id,
                 decision,
             } => {
-                handlers::exec_approval(&sess, approval_id, turn_id, decision).await;
+                handlers::exec_approval(&sess, approval_id, turn_id, decision, submitter).await;
             }
             Op::PatchApproval { id, decision } => {
-                handlers::patch_approval(&sess, id, decision).await;
+                handlers::patch_approval(&sess, id, decision, submitter).await;
             }
             Op::UserInputAnswer { id, response } => {
                 handlers::request_user_input_response(&sess, id, response).await;
@@ -3443,6 +4016,9 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::RefreshMcpServers { config } => {
                 handlers::refresh_mcp_servers(&sess, config).await;
             }
+            Op::RefreshMcpTools { server } => {
+                handlers::refresh_mcp_tools(&sess, &config, sub.id.clone(), server).await;
+            }
             Op::ReloadUserConfig => {
                 handlers::reload_user_config(&sess).await;
             }
@@ -3485,29 +4061,151 @@ async fn submission_loop(sess: Arc<Session>, config: Arc<Config>, rx_sub: Receiv
             Op::ThreadRollback { num_turns } => {
                 handlers::thread_rollback(&sess, sub.id.clone(), num_turns).await;
             }
+            Op::RetryFromUserMessage { item_id, items } => {
+                handlers::retry_from_user_message(&sess, sub.id.clone(), item_id, items).await;
+            }
             Op::SetThreadName { name } => {
                 handlers::set_thread_name(&sess, sub.id.clone(), name).await;
             }
             Op::RunUserShellCommand { command } => {
                 handlers::run_user_shell_command(&sess, sub.id.clone(), command).await;
             }
-            Op::ResolveElicitation {
-                server_name,
-                request_id,
-                decision,
+            Op::InjectGitContext { source } => {
+                handlers::inject_git_context(&sess, sub.id.clone(), source).await;
+            }
+            Op::SetCwd { cwd } => {
+                handlers::set_cwd(&sess, sub.id.clone(), cwd).await;
+            }
+            Op::AttachInput {
+                content_type,
+                name,
+                content,
             } => {
-                handlers::resolve_elicitation(&sess, server_name, request_id, decision).await;
+                handlers::attach_input(&sess, sub.id.clone(), content_type, name, content).await;
             }
-            Op::Shutdown => {
-                if handlers::shutdown(&sess, sub.id.clone()).await {
-                    break;
-                }
+            Op::ApplyToRealWorkspace => {
+                handlers::apply_to_real_workspace(&sess, sub.id.clone()).await;
             }
-            Op::Review { review_request } => {
-                handlers::review(&sess, &config, sub.id.clone(), review_request).await;
+            Op::GetPatchArtifact => {
+                handlers::get_patch_artifact(&sess, sub.id.clone()).await;
             }
-            _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
-        }
+            Op::GenerateCodebaseMap => {
+                handlers::generate_codebase_map(&sess, sub.id.clone()).await;
+            }
+            Op::BenchmarkedTurn { items } => {
+                handlers::benchmarked_turn(&sess, &config, sub.id.clone(), items).await;
+            }
+            Op::AuditDependencies { upgrade } => {
+                handlers::audit_dependencies(&sess, sub.id.clone(), upgrade).await;
+            }
+            Op::ScanLicenses => {
+                handlers::scan_licenses(&sess, sub.id.clone()).await;
+            }
+            Op::PruneSessions { dry_run } => {
+                handlers::prune_sessions(&sess, &config, sub.id.clone(), dry_run).await;
+            }
+            Op::ExportSession { path } => {
+                handlers::export_session(&sess, &config, sub.id.clone(), path).await;
+            }
+            Op::InlineEdit {
+                path,
+                range,
+                instruction,
+            } => {
+                handlers::inline_edit(&sess, sub.id.clone(), path, range, instruction).await;
+            }
+            Op::Regenerate { n } => {
+                handlers::regenerate(&sess, sub.id.clone(), n).await;
+            }
+            Op::SelectCandidate { index } => {
+                handlers::select_candidate(&sess, sub.id.clone(), index).await;
+            }
+            Op::ForecastRateLimits => {
+                handlers::forecast_rate_limits(&sess, sub.id.clone()).await;
+            }
+            Op::SetOffline { offline } => {
+                handlers::set_offline(&sess, sub.id.clone(), offline).await;
+            }
+            Op::GetTurnTimings => {
+                handlers::get_turn_timings(&sess, sub.id.clone()).await;
+            }
+            Op::GetContextBreakdown => {
+                handlers::get_context_breakdown(&sess, sub.id.clone()).await;
+            }
+            Op::GetActiveToolCalls => {
+                handlers::get_active_tool_calls(&sess, sub.id.clone()).await;
+            }
+            Op::ListSessionTools => {
+                handlers::list_session_tools(&sess, sub.id.clone()).await;
+            }
+            Op::InvokeTool {
+                call_id,
+                tool,
+                arguments,
+            } => {
+                handlers::invoke_tool(&sess, sub.id.clone(), call_id, tool, arguments).await;
+            }
+            Op::QueryUsage {
+                project,
+                since,
+                until,
+            } => {
+                handlers::query_usage(&sess, &config, sub.id.clone(), project, since, until).await;
+            }
+            Op::Complete {
+                path,
+                cursor,
+                prefix_tokens,
+                suffix_tokens,
+            } => {
+                handlers::complete(
+                    &sess,
+                    sub.id.clone(),
+                    path,
+                    cursor,
+                    prefix_tokens,
+                    suffix_tokens,
+                )
+                .await;
+            }
+            Op::PromoteObserver { client } => {
+                handlers::promote_observer(&sess, sub.id.clone(), client).await;
+            }
+            Op::SetEnabledTools { allow, deny } => {
+                handlers::set_enabled_tools(&sess, sub.id.clone(), allow, deny).await;
+            }
+            Op::ReviewSuggestedAllowlist { promote, dismiss } => {
+                handlers::review_suggested_allowlist(&sess, sub.id.clone(), promote, dismiss).await;
+            }
+            Op::ImportSession { path } => {
+                handlers::import_session(&sess, &config, sub.id.clone(), path).await;
+            }
+            Op::ResolveElicitation {
+                server_name,
+                request_id,
+                decision,
+                content,
+            } => {
+                handlers::resolve_elicitation(
+                    &sess,
+                    sub.id.clone(),
+                    server_name,
+                    request_id,
+                    decision,
+                    content,
+                )
+                .await;
+            }
+            Op::Shutdown => {
+                if handlers::shutdown(&sess, sub.id.clone()).await {
+                    break;
+                }
+            }
+            Op::Review { review_request } => {
+                handlers::review(&sess, &config, sub.id.clone(), review_request).await;
+            }
+            _ => {} // Ignore unknown ops; enum is non_exhaustive to allow extensions.
+        }
     }
     debug!("Agent loop exited");
 }
@@ -3518,6 +4216,7 @@ mod handlers {
     use crate::codex::SessionSettingsUpdate;
     use crate::codex::SteerInputError;
 
+    use crate::codex::built_tools;
     use crate::codex::spawn_review_thread;
     use crate::config::Config;
 
@@ -3525,53 +4224,113 @@ mod handlers {
     use crate::mcp::collect_mcp_snapshot_from_manager;
     use crate::mcp::effective_mcp_servers;
     use crate::review_prompts::resolve_review_request;
+    use crate::rollout::retention;
     use crate::rollout::session_index;
+    use crate::session_archive;
+    use crate::suggested_allowlist;
+    use crate::tasks::ApplyShadowWorkspaceTask;
+    use crate::tasks::BenchmarkTask;
+    use crate::tasks::CodebaseMapTask;
     use crate::tasks::CompactTask;
+    use crate::tasks::DependencyAuditTask;
+    use crate::tasks::InlineEditTask;
+    use crate::tasks::InvokeToolTask;
+    use crate::tasks::LicenseScanTask;
+    use crate::tasks::MAX_RESPONSE_CANDIDATES;
+    use crate::tasks::RegenerateTask;
     use crate::tasks::UndoTask;
     use crate::tasks::UserShellCommandMode;
     use crate::tasks::UserShellCommandTask;
     use crate::tasks::execute_user_shell_command;
+    use crate::tasks::response_text;
+    use crate::usage_rollups;
+    use codex_protocol::approvals::ExecPolicyAmendment;
     use codex_protocol::custom_prompts::CustomPrompt;
+    use codex_protocol::protocol::ActiveToolCallsEvent;
+    use codex_protocol::protocol::ApprovalResolvedEvent;
     use codex_protocol::protocol::CodexErrorInfo;
+    use codex_protocol::protocol::ContextBreakdownEvent;
+    use codex_protocol::protocol::EnabledToolsUpdatedEvent;
     use codex_protocol::protocol::ErrorEvent;
     use codex_protocol::protocol::Event;
     use codex_protocol::protocol::EventMsg;
+    use codex_protocol::protocol::LineRange;
     use codex_protocol::protocol::ListCustomPromptsResponseEvent;
     use codex_protocol::protocol::ListRemoteSkillsResponseEvent;
     use codex_protocol::protocol::ListSkillsResponseEvent;
     use codex_protocol::protocol::McpServerRefreshConfig;
+    use codex_protocol::protocol::ObserverPromotedEvent;
+    use codex_protocol::protocol::OfflineStatusEvent;
     use codex_protocol::protocol::Op;
+    use codex_protocol::protocol::PatchArtifactEvent;
+    use codex_protocol::protocol::RateLimitForecastEvent;
     use codex_protocol::protocol::RemoteSkillDownloadedEvent;
     use codex_protocol::protocol::RemoteSkillHazelnutScope;
     use codex_protocol::protocol::RemoteSkillProductSurface;
     use codex_protocol::protocol::RemoteSkillSummary;
+    use codex_protocol::protocol::ResponseCandidateSelectedEvent;
     use codex_protocol::protocol::ReviewDecision;
     use codex_protocol::protocol::ReviewRequest;
+    use codex_protocol::protocol::RolloutItem;
+    use codex_protocol::protocol::SessionToolsEvent;
     use codex_protocol::protocol::SkillsListEntry;
+    use codex_protocol::protocol::SuggestedAllowlistEvent;
+    use codex_protocol::protocol::TextPosition;
     use codex_protocol::protocol::ThreadNameUpdatedEvent;
     use codex_protocol::protocol::ThreadRolledBackEvent;
     use codex_protocol::protocol::TurnAbortReason;
+    use codex_protocol::protocol::TurnTimingsEvent;
+    use codex_protocol::protocol::UsageRollupsEvent;
     use codex_protocol::protocol::WarningEvent;
     use codex_protocol::request_user_input::RequestUserInputResponse;
 
+    use crate::attachments::attached_input_item;
     use crate::context_manager::is_user_turn_boundary;
+    use crate::git_context::describe_git_context_source;
+    use crate::git_context::git_context_input_item;
+    use crate::path_utils::resolve_cwd_change;
+    use chrono::NaiveDate;
     use codex_protocol::config_types::CollaborationMode;
     use codex_protocol::config_types::ModeKind;
     use codex_protocol::config_types::Settings;
     use codex_protocol::dynamic_tools::DynamicToolResponse;
     use codex_protocol::mcp::RequestId as ProtocolRequestId;
+    use codex_protocol::models::ResponseItem;
+    use codex_protocol::protocol::GitContextSource;
     use codex_protocol::user_input::UserInput;
     use codex_rmcp_client::ElicitationAction;
     use codex_rmcp_client::ElicitationResponse;
+    use std::collections::HashSet;
+    use std::path::Path;
     use std::path::PathBuf;
     use std::sync::Arc;
+    use std::time::Duration;
+    use time::OffsetDateTime;
+    use tokio_util::sync::CancellationToken;
     use tracing::info;
     use tracing::warn;
 
+    /// How often an offline session probes the provider.
+    const OFFLINE_PROBE_INTERVAL: Duration = Duration::from_secs(30);
+
     pub async fn interrupt(sess: &Arc<Session>) {
         sess.interrupt_task().await;
     }
 
+    pub async fn cancel_tool_call(sess: &Arc<Session>, sub_id: String, call_id: String) {
+        if sess.services.active_tool_calls.cancel(&call_id) {
+            return;
+        }
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::Error(ErrorEvent {
+                message: format!("no tool call `{call_id}` is in flight"),
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+            }),
+        })
+        .await;
+    }
+
     pub async fn clean_background_terminals(sess: &Arc<Session>) {
         sess.close_unified_exec_processes().await;
     }
@@ -3594,6 +4353,15 @@ mod handlers {
     }
 
     pub async fn user_input_or_turn(sess: &Arc<Session>, sub_id: String, op: Op) {
+        if sess.services.model_client.is_offline() {
+            let queued_turns = {
+                let mut state = sess.state.lock().await;
+                state.offline_turns.push_back((sub_id.clone(), op));
+                state.offline_turns.len()
+            };
+            send_offline_status(sess, sub_id, true, queued_turns).await;
+            return;
+        }
         let (items, updates) = match op {
             Op::UserTurn {
                 cwd,
@@ -3606,6 +4374,7 @@ mod handlers {
                 items,
                 collaboration_mode,
                 personality,
+                sampling,
             } => {
                 let collaboration_mode = collaboration_mode.or_else(|| {
                     Some(CollaborationMode {
@@ -3628,6 +4397,7 @@ mod handlers {
                         reasoning_summary: Some(summary),
                         final_output_json_schema: Some(final_output_json_schema),
                         personality,
+                        sampling,
                     },
                 )
             }
@@ -3689,21 +4459,811 @@ mod handlers {
         .await;
     }
 
+    pub async fn inject_git_context(sess: &Arc<Session>, sub_id: String, source: GitContextSource) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+        let item = match git_context_input_item(&turn_context.cwd, &source).await {
+            Ok(item) => item,
+            Err(message) => {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: format!("failed to load git context: {message}"),
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                })
+                .await;
+                return;
+            }
+        };
+
+        // Mid-turn the summary is picked up with the next model request;
+        // otherwise it is recorded so the next turn sees it.
+        if let Err(items) = sess.inject_response_items(vec![item]).await {
+            let items: Vec<ResponseItem> = items.into_iter().map(ResponseItem::from).collect();
+            sess.record_conversation_items(&turn_context, &items).await;
+        }
+        sess.notify_background_event(
+            &turn_context,
+            format!(
+                "Added {} to the conversation context",
+                describe_git_context_source(&source)
+            ),
+        )
+        .await;
+    }
+
+    pub async fn set_cwd(sess: &Arc<Session>, sub_id: String, cwd: PathBuf) {
+        let send_error = |message: String| {
+            sess.send_event_raw(Event {
+                id: sub_id.clone(),
+                msg: EventMsg::Error(ErrorEvent {
+                    message,
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+        };
+        if sess.active_turn.lock().await.is_some() {
+            send_error("cannot change the working directory while a turn is running".to_string())
+                .await;
+            return;
+        }
+        let (sandbox_policy, current_cwd) = {
+            let state = sess.state.lock().await;
+            (
+                state.session_configuration.sandbox_policy.get().clone(),
+                state.session_configuration.cwd.clone(),
+            )
+        };
+        let cwd = match resolve_cwd_change(&sandbox_policy, &current_cwd, &cwd) {
+            Ok(cwd) => cwd,
+            Err(message) => {
+                send_error(message).await;
+                return;
+            }
+        };
+        if let Some(shadow) = &sess.services.shadow_workspace
+            && !shadow.contains(&cwd)
+        {
+            send_error(format!(
+                "{} is outside the shadow workspace; edits must stay in the shadow copy",
+                cwd.display()
+            ))
+            .await;
+            return;
+        }
+        if let Err(err) = sess
+            .update_settings(SessionSettingsUpdate {
+                cwd: Some(cwd.clone()),
+                ..Default::default()
+            })
+            .await
+        {
+            send_error(err.to_string()).await;
+            return;
+        }
+
+        // Record the new cwd right away so the history shows where later
+        // commands run. Before the first turn, the full initial context
+        // recorded by that turn already carries it.
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+        if sess.reference_context_item().await.is_some() {
+            let previous_model = sess.previous_model().await;
+            sess.record_context_updates_and_set_reference_context_item(
+                &turn_context,
+                previous_model.as_deref(),
+            )
+            .await;
+        }
+        sess.notify_background_event(
+            &turn_context,
+            format!("Working directory changed to {}", cwd.display()),
+        )
+        .await;
+    }
+
+    pub async fn attach_input(
+        sess: &Arc<Session>,
+        sub_id: String,
+        content_type: String,
+        name: Option<String>,
+        content: String,
+    ) {
+        let attachment = match sess.register_attachment(content_type, name, content).await {
+            Ok(attachment) => attachment,
+            Err(message) => {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message,
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                })
+                .await;
+                return;
+            }
+        };
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        let item = attached_input_item(&attachment);
+
+        // Like git context, mid-turn attachments are picked up with the next
+        // model request; otherwise they are recorded for the next turn.
+        if let Err(items) = sess.inject_response_items(vec![item]).await {
+            let items: Vec<ResponseItem> = items.into_iter().map(ResponseItem::from).collect();
+            sess.record_conversation_items(&turn_context, &items).await;
+        }
+        let label = attachment.name.as_deref().unwrap_or("input");
+        let summarized = if attachment.is_summarized() {
+            ", summarized"
+        } else {
+            ""
+        };
+        sess.notify_background_event(
+            &turn_context,
+            format!(
+                "Attached {label} as {} ({}, {} bytes{summarized}) to the conversation context",
+                attachment.id,
+                attachment.content_type,
+                attachment.content.len()
+            ),
+        )
+        .await;
+    }
+
+    pub async fn apply_to_real_workspace(sess: &Arc<Session>, sub_id: String) {
+        let Some(shadow) = sess.services.shadow_workspace.clone() else {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "this session is not using a shadow workspace".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+            return;
+        };
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            ApplyShadowWorkspaceTask::new(shadow),
+        )
+        .await;
+    }
+
+    pub async fn get_patch_artifact(sess: &Arc<Session>, sub_id: String) {
+        let msg = match &sess.services.patch_artifact {
+            Some(artifact) => {
+                let artifact = artifact.lock().await;
+                EventMsg::PatchArtifact(PatchArtifactEvent {
+                    patch: artifact.unified_diff(),
+                    path: artifact.output_path().map(Path::to_path_buf),
+                })
+            }
+            None => EventMsg::Error(ErrorEvent {
+                message: "this session is not in patch artifact mode".to_string(),
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn prune_sessions(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        dry_run: bool,
+    ) {
+        // This session's own rollout is kept through its lease.
+        let msg = match retention::prune_sessions(
+            &config.codex_home,
+            &config.session_retention,
+            None,
+            sess.state_db(),
+            dry_run,
+        )
+        .await
+        {
+            Ok(report) => EventMsg::SessionsPruned(report),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("failed to prune stored sessions: {err}"),
+                codex_error_info: Some(CodexErrorInfo::Other),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn export_session(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        path: PathBuf,
+    ) {
+        sess.flush_rollout().await;
+        let rollout_path = {
+            let recorder = sess.services.rollout.lock().await;
+            recorder
+                .as_ref()
+                .map(|recorder| recorder.rollout_path().to_path_buf())
+        };
+        let msg = match rollout_path {
+            Some(rollout_path) => match session_archive::export_session(
+                &config.codex_home,
+                &rollout_path,
+                sess.attachment_manifest().await,
+                sess.clone_history().await.breakdown(),
+                &path,
+            )
+            .await
+            {
+                Ok(exported) => EventMsg::SessionExported(exported),
+                Err(err) => EventMsg::Error(ErrorEvent {
+                    message: format!("failed to export session: {err}"),
+                    codex_error_info: Some(CodexErrorInfo::Other),
+                }),
+            },
+            None => EventMsg::Error(ErrorEvent {
+                message: "this session is not recorded and cannot be exported".to_string(),
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn import_session(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        path: PathBuf,
+    ) {
+        let msg = match session_archive::import_session(&config.codex_home, &path).await {
+            Ok(imported) => EventMsg::SessionImported(imported),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("failed to import session from {}: {err}", path.display()),
+                codex_error_info: Some(CodexErrorInfo::Other),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn inline_edit(
+        sess: &Arc<Session>,
+        sub_id: String,
+        path: PathBuf,
+        range: LineRange,
+        instruction: String,
+    ) {
+        if sess.active_turn.lock().await.is_some() {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "cannot run an inline edit while a turn is running".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+            return;
+        }
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            InlineEditTask {
+                path,
+                range,
+                instruction,
+            },
+        )
+        .await;
+    }
+
+    pub async fn regenerate(sess: &Arc<Session>, sub_id: String, n: u32) {
+        let message = if !(1..=MAX_RESPONSE_CANDIDATES).contains(&n) {
+            Some(format!("n must be between 1 and {MAX_RESPONSE_CANDIDATES}"))
+        } else if sess.active_turn.lock().await.is_some() {
+            Some("cannot regenerate while a turn is running".to_string())
+        } else {
+            None
+        };
+        if let Some(message) = message {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message,
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+            return;
+        }
+        // Sample with the settings of the turn that produced the response.
+        let reference = sess.reference_context_item().await;
+        let session_configuration = sess.state.lock().await.session_configuration.clone();
+        let turn_context = if let Some(reference) = reference
+            && let Ok(session_configuration) = session_configuration.apply(&SessionSettingsUpdate {
+                collaboration_mode: reference.collaboration_mode.clone(),
+                reasoning_summary: Some(reference.summary),
+                personality: reference.personality,
+                ..Default::default()
+            }) {
+            sess.new_turn_from_configuration(
+                sub_id,
+                session_configuration,
+                Some(reference.final_output_json_schema),
+                reference.sampling,
+                false,
+            )
+            .await
+        } else {
+            sess.new_default_turn_with_sub_id(sub_id).await
+        };
+        sess.spawn_task(turn_context, Vec::new(), RegenerateTask { n })
+            .await;
+    }
+
+    pub async fn select_candidate(sess: &Arc<Session>, sub_id: String, index: u32) {
+        let selected = if sess.active_turn.lock().await.is_some() {
+            Err("cannot select a candidate while a turn is running".to_string())
+        } else {
+            let mut state = sess.state.lock().await;
+            match state.response_candidates.clone() {
+                None => Err("there are no candidates; send Op::Regenerate first".to_string()),
+                Some(candidates) if candidates.history_len != state.history.raw_items().len() => {
+                    state.response_candidates = None;
+                    Err("the thread has changed since the candidates were sampled".to_string())
+                }
+                Some(candidates) => match usize::try_from(index)
+                    .ok()
+                    .and_then(|index| candidates.candidates.get(index))
+                {
+                    Some(items) => {
+                        let mut history =
+                            state.history.raw_items()[..candidates.response_start].to_vec();
+                        history.extend(items.iter().cloned());
+                        let reference_context_item = state.reference_context_item();
+                        state.replace_history(history, reference_context_item);
+                        state.response_candidates = None;
+                        Ok((items.clone(), response_text(items)))
+                    }
+                    None => Err(format!(
+                        "there is no candidate {index}; {} were sampled",
+                        candidates.candidates.len()
+                    )),
+                },
+            }
+        };
+        let (items, message) = match selected {
+            Ok(selected) => selected,
+            Err(message) => {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message,
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                })
+                .await;
+                return;
+            }
+        };
+
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.recompute_token_usage(turn_context.as_ref()).await;
+        // The recorded event drops the replaced response when history is
+        // rebuilt from the rollout; the candidate's items follow it.
+        sess.send_event_raw(Event {
+            id: turn_context.sub_id.clone(),
+            msg: EventMsg::ResponseCandidateSelected(ResponseCandidateSelectedEvent {
+                index,
+                message,
+            }),
+        })
+        .await;
+        let rollout_items: Vec<RolloutItem> =
+            items.into_iter().map(RolloutItem::ResponseItem).collect();
+        sess.persist_rollout_items(&rollout_items).await;
+    }
+
+    pub async fn forecast_rate_limits(sess: &Arc<Session>, sub_id: String) {
+        let windows = sess
+            .state
+            .lock()
+            .await
+            .rate_limit_samples
+            .forecast(OffsetDateTime::now_utc().unix_timestamp());
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::RateLimitForecast(RateLimitForecastEvent { windows }),
+        })
+        .await;
+    }
+
+    pub async fn set_offline(sess: &Arc<Session>, sub_id: String, offline: bool) {
+        let was_offline = sess.services.model_client.set_offline(offline);
+        let queued_turns = sess.state.lock().await.offline_turns.len();
+        send_offline_status(sess, sub_id, offline, queued_turns).await;
+        match (was_offline, offline) {
+            (false, true) => {
+                tokio::spawn(await_connectivity(Arc::clone(sess)));
+            }
+            (true, false) => {
+                tokio::spawn(run_offline_turns(Arc::clone(sess)));
+            }
+            _ => {}
+        }
+    }
+
+    pub async fn get_turn_timings(sess: &Arc<Session>, sub_id: String) {
+        let turns = sess
+            .state
+            .lock()
+            .await
+            .turn_timings
+            .iter()
+            .cloned()
+            .collect();
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::TurnTimings(TurnTimingsEvent { turns }),
+        })
+        .await;
+    }
+
+    pub async fn get_active_tool_calls(sess: &Arc<Session>, sub_id: String) {
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::ActiveToolCalls(ActiveToolCallsEvent {
+                calls: sess.services.active_tool_calls.snapshot(),
+            }),
+        })
+        .await;
+    }
+
+    pub async fn list_session_tools(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+        let msg = match built_tools(
+            sess,
+            &turn_context,
+            &[],
+            &HashSet::new(),
+            None,
+            &CancellationToken::new(),
+        )
+        .await
+        {
+            Ok(router) => EventMsg::SessionTools(SessionToolsEvent {
+                tools: router.session_tools(turn_context.approval_policy.value()),
+            }),
+            Err(err) => EventMsg::Error(err.to_error_event(None)),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn invoke_tool(
+        sess: &Arc<Session>,
+        sub_id: String,
+        call_id: String,
+        tool: String,
+        arguments: String,
+    ) {
+        if sess.active_turn.lock().await.is_some() {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "cannot invoke a tool while a turn is running".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+            return;
+        }
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(
+            turn_context,
+            Vec::new(),
+            InvokeToolTask {
+                call_id,
+                tool,
+                arguments,
+            },
+        )
+        .await;
+    }
+
+    pub async fn get_context_breakdown(sess: &Arc<Session>, sub_id: String) {
+        let items = sess.clone_history().await.breakdown();
+        let estimated_tokens = items.iter().map(|item| item.estimated_tokens).sum();
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::ContextBreakdown(ContextBreakdownEvent {
+                items,
+                estimated_tokens,
+            }),
+        })
+        .await;
+    }
+
+    pub async fn query_usage(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        project: Option<PathBuf>,
+        since: Option<String>,
+        until: Option<String>,
+    ) {
+        let parse_date = |date: Option<String>| {
+            date.map(|date| {
+                NaiveDate::parse_from_str(&date, "%Y-%m-%d")
+                    .map_err(|err| format!("invalid date `{date}`, expected YYYY-MM-DD: {err}"))
+            })
+            .transpose()
+        };
+        let (since, until) = match (parse_date(since), parse_date(until)) {
+            (Ok(since), Ok(until)) => (since, until),
+            (Err(message), _) | (_, Err(message)) => {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message,
+                        codex_error_info: Some(CodexErrorInfo::BadRequest),
+                    }),
+                })
+                .await;
+                return;
+            }
+        };
+        let cwd = sess.state.lock().await.session_configuration.cwd.clone();
+        let codex_home = config.codex_home.clone();
+        let pricing = config.model_pricing.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            let filter = usage_rollups::UsageFilter {
+                project_root: project
+                    .map(|project| suggested_allowlist::project_root(&cwd.join(project))),
+                since,
+                until,
+            };
+            usage_rollups::query(&codex_home, &filter, &pricing)
+        })
+        .await
+        .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+        let msg = match result {
+            Ok(rollups) => EventMsg::UsageRollups(UsageRollupsEvent { rollups }),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("failed to read usage rollups: {err}"),
+                codex_error_info: Some(CodexErrorInfo::Other),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    async fn send_offline_status(
+        sess: &Arc<Session>,
+        sub_id: String,
+        offline: bool,
+        queued_turns: usize,
+    ) {
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::OfflineStatus(OfflineStatusEvent {
+                offline,
+                queued_turns: u32::try_from(queued_turns).unwrap_or(u32::MAX),
+            }),
+        })
+        .await;
+    }
+
+    /// Probes the provider while the session is offline, and brings the
+    /// session back online with its queued turns once the provider answers.
+    async fn await_connectivity(sess: Arc<Session>) {
+        let model_client = &sess.services.model_client;
+        loop {
+            tokio::time::sleep(OFFLINE_PROBE_INTERVAL).await;
+            if !model_client.is_offline() {
+                return;
+            }
+            if !model_client
+                .probe_connectivity(&sess.services.otel_manager)
+                .await
+            {
+                continue;
+            }
+            if model_client.set_offline(false) {
+                info!("model provider reachable again; leaving offline mode");
+                let queued_turns = sess.state.lock().await.offline_turns.len();
+                send_offline_status(&sess, sess.next_internal_sub_id(), false, queued_turns).await;
+                run_offline_turns(Arc::clone(&sess)).await;
+            }
+            return;
+        }
+    }
+
+    /// Runs the turns queued while offline, one at a time and in the order
+    /// they were sent, stopping if the session goes offline again.
+    async fn run_offline_turns(sess: Arc<Session>) {
+        let mut idle_since = sess.idle_since.subscribe();
+        loop {
+            // Wait for the previous turn to finish without polling.
+            loop {
+                let idle = sess.active_turn.lock().await.is_none()
+                    && idle_since.borrow_and_update().is_some();
+                if idle {
+                    break;
+                }
+                if idle_since.changed().await.is_err() {
+                    return;
+                }
+            }
+            if sess.services.model_client.is_offline() {
+                return;
+            }
+            let Some((sub_id, op)) = sess.state.lock().await.offline_turns.pop_front() else {
+                return;
+            };
+            user_input_or_turn(&sess, sub_id, op).await;
+        }
+    }
+
+    pub async fn complete(
+        sess: &Arc<Session>,
+        sub_id: String,
+        path: PathBuf,
+        cursor: TextPosition,
+        prefix_tokens: Option<u32>,
+        suffix_tokens: Option<u32>,
+    ) {
+        let sess = Arc::clone(sess);
+        // Completions do not wait for the ops queued behind them.
+        tokio::spawn(async move {
+            let turn_context = sess.new_default_turn_with_sub_id(sub_id.clone()).await;
+            let msg = crate::completion::complete(
+                &sess,
+                &turn_context,
+                &path,
+                cursor,
+                prefix_tokens,
+                suffix_tokens,
+            )
+            .await;
+            sess.send_event_raw(Event { id: sub_id, msg }).await;
+        });
+    }
+
+    pub async fn promote_observer(sess: &Arc<Session>, sub_id: String, client: String) {
+        let msg = if sess.services.attached_clients.promote(&client) {
+            EventMsg::ObserverPromoted(ObserverPromotedEvent { client })
+        } else {
+            EventMsg::Error(ErrorEvent {
+                message: format!("no observer with id {client} is attached to this thread"),
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+            })
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn set_enabled_tools(
+        sess: &Arc<Session>,
+        sub_id: String,
+        allow: Vec<String>,
+        deny: Vec<String>,
+    ) {
+        let disabled = sess.state.lock().await.update_disabled_tools(allow, deny);
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::EnabledToolsUpdated(EnabledToolsUpdatedEvent { disabled }),
+        })
+        .await;
+    }
+
+    pub async fn review_suggested_allowlist(
+        sess: &Arc<Session>,
+        sub_id: String,
+        promote: Vec<Vec<String>>,
+        dismiss: Vec<Vec<String>>,
+    ) {
+        let codex_home = sess.codex_home().await;
+        let cwd = sess.state.lock().await.session_configuration.cwd.clone();
+        for command in &promote {
+            let amendment = ExecPolicyAmendment::new(command.clone());
+            if let Err(err) = sess.persist_execpolicy_amendment(&amendment).await {
+                sess.send_event_raw(Event {
+                    id: sub_id,
+                    msg: EventMsg::Error(ErrorEvent {
+                        message: format!("failed to promote {}: {err}", command.join(" ")),
+                        codex_error_info: Some(CodexErrorInfo::Other),
+                    }),
+                })
+                .await;
+                return;
+            }
+        }
+
+        let reviewed: Vec<Vec<String>> = promote.into_iter().chain(dismiss).collect();
+        let result = tokio::task::spawn_blocking(move || {
+            let project_root = suggested_allowlist::project_root(&cwd);
+            suggested_allowlist::remove(&codex_home, &project_root, &reviewed)
+                .map(|commands| (project_root, commands))
+        })
+        .await
+        .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+        let msg = match result {
+            Ok((project_root, commands)) => EventMsg::SuggestedAllowlist(SuggestedAllowlistEvent {
+                project_root,
+                commands,
+            }),
+            Err(err) => EventMsg::Error(ErrorEvent {
+                message: format!("failed to update the suggested allowlist: {err}"),
+                codex_error_info: Some(CodexErrorInfo::Other),
+            }),
+        };
+        sess.send_event_raw(Event { id: sub_id, msg }).await;
+    }
+
+    pub async fn generate_codebase_map(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), CodebaseMapTask)
+            .await;
+    }
+
+    pub async fn audit_dependencies(sess: &Arc<Session>, sub_id: String, upgrade: bool) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), DependencyAuditTask::new(upgrade))
+            .await;
+    }
+
+    pub async fn scan_licenses(sess: &Arc<Session>, sub_id: String) {
+        let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+        sess.spawn_task(turn_context, Vec::new(), LicenseScanTask)
+            .await;
+    }
+
+    pub async fn benchmarked_turn(
+        sess: &Arc<Session>,
+        config: &Arc<Config>,
+        sub_id: String,
+        items: Vec<UserInput>,
+    ) {
+        let turn_running = sess.active_turn.lock().await.is_some();
+        let message = match config
+            .benchmarks
+            .as_ref()
+            .filter(|benchmarks| !benchmarks.commands.is_empty())
+        {
+            None => "no benchmarks are configured; add them under `[benchmarks]` in config.toml",
+            Some(_) if turn_running => "cannot start a benchmarked turn while a turn is running",
+            Some(benchmarks) => {
+                let turn_context = sess.new_default_turn_with_sub_id(sub_id).await;
+                sess.spawn_task(turn_context, items, BenchmarkTask::new(benchmarks.clone()))
+                    .await;
+                return;
+            }
+        };
+        sess.send_event_raw(Event {
+            id: sub_id,
+            msg: EventMsg::Error(ErrorEvent {
+                message: message.to_string(),
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+            }),
+        })
+        .await;
+    }
+
     pub async fn resolve_elicitation(
         sess: &Arc<Session>,
+        sub_id: String,
         server_name: String,
         request_id: ProtocolRequestId,
         decision: codex_protocol::approvals::ElicitationAction,
+        content: Option<serde_json::Value>,
     ) {
         let action = match decision {
             codex_protocol::approvals::ElicitationAction::Accept => ElicitationAction::Accept,
             codex_protocol::approvals::ElicitationAction::Decline => ElicitationAction::Decline,
             codex_protocol::approvals::ElicitationAction::Cancel => ElicitationAction::Cancel,
         };
-        // When accepting, send an empty object as content to satisfy MCP servers
-        // that expect non-null content on Accept. For Decline/Cancel, content is None.
+        // When accepting without answers, send an empty object as content to
+        // satisfy MCP servers that expect non-null content on Accept. For
+        // Decline/Cancel, content is None.
         let content = match action {
-            ElicitationAction::Accept => Some(serde_json::json!({})),
+            ElicitationAction::Accept => Some(content.unwrap_or_else(|| serde_json::json!({}))),
             ElicitationAction::Decline | ElicitationAction::Cancel => None,
         };
         let response = ElicitationResponse { action, content };
@@ -3721,6 +5281,14 @@ mod handlers {
                 error = %err,
                 "failed to resolve elicitation request in session"
             );
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("failed to resolve elicitation request: {err:#}"),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
         }
     }
 
@@ -3731,6 +5299,7 @@ mod handlers {
         approval_id: String,
         turn_id: Option<String>,
         decision: ReviewDecision,
+        client: Option<String>,
     ) {
         let event_turn_id = turn_id.unwrap_or_else(|| approval_id.clone());
         if let ReviewDecision::ApprovedExecpolicyAmendment {
@@ -3760,20 +5329,40 @@ mod handlers {
                 }
             }
         }
-        match decision {
-            ReviewDecision::Abort => {
-                sess.interrupt_task().await;
-            }
-            other => sess.notify_approval(&approval_id, other).await,
-        }
+        resolve_approval(sess, approval_id, decision, client).await;
     }
 
-    pub async fn patch_approval(sess: &Arc<Session>, id: String, decision: ReviewDecision) {
-        match decision {
-            ReviewDecision::Abort => {
-                sess.interrupt_task().await;
-            }
-            other => sess.notify_approval(&id, other).await,
+    pub async fn patch_approval(
+        sess: &Arc<Session>,
+        id: String,
+        decision: ReviewDecision,
+        client: Option<String>,
+    ) {
+        resolve_approval(sess, id, decision, client).await;
+    }
+
+    /// Answers approval `id` and tells every client who answered it, so the
+    /// others can dismiss their prompt. Only the first answer counts.
+    async fn resolve_approval(
+        sess: &Arc<Session>,
+        id: String,
+        decision: ReviewDecision,
+        client: Option<String>,
+    ) {
+        if let ReviewDecision::Abort = decision {
+            sess.interrupt_task().await;
+            return;
+        }
+        if sess.notify_approval(&id, decision.clone()).await {
+            sess.send_event_raw(Event {
+                id: id.clone(),
+                msg: EventMsg::ApprovalResolved(ApprovalResolvedEvent {
+                    id,
+                    decision,
+                    client,
+                }),
+            })
+            .await;
         }
     }
 
@@ -3845,6 +5434,33 @@ mod handlers {
         *guard = Some(refresh_config);
     }
 
+    pub async fn refresh_mcp_tools(
+        sess: &Session,
+        config: &Arc<Config>,
+        sub_id: String,
+        server: Option<String>,
+    ) {
+        let refreshed = sess
+            .services
+            .mcp_connection_manager
+            .read()
+            .await
+            .refresh_tools(server.as_deref())
+            .await;
+        if let Err(err) = refreshed {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("{err:#}"),
+                    codex_error_info: Some(CodexErrorInfo::BadRequest),
+                }),
+            })
+            .await;
+            return;
+        }
+        list_mcp_tools(sess, config, sub_id).await;
+    }
+
     pub async fn reload_user_config(sess: &Arc<Session>) {
         sess.reload_user_config_layer().await;
     }
@@ -4139,6 +5755,50 @@ mod handlers {
         .await;
     }
 
+    /// Rolls history back to just before the user message `item_id` and starts
+    /// a new turn with `items` in its place.
+    pub async fn retry_from_user_message(
+        sess: &Arc<Session>,
+        sub_id: String,
+        item_id: String,
+        items: Vec<UserInput>,
+    ) {
+        let num_turns = sess.clone_history().await.user_turns_from(&item_id);
+        let Some(num_turns) = num_turns else {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: format!("no user message {item_id} in the thread history"),
+                    codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                }),
+            })
+            .await;
+            return;
+        };
+        if sess.active_turn.lock().await.is_some() {
+            sess.send_event_raw(Event {
+                id: sub_id,
+                msg: EventMsg::Error(ErrorEvent {
+                    message: "Cannot retry while a turn is in progress.".to_string(),
+                    codex_error_info: Some(CodexErrorInfo::ThreadRollbackFailed),
+                }),
+            })
+            .await;
+            return;
+        }
+
+        thread_rollback(sess, sub_id.clone(), num_turns).await;
+        user_input_or_turn(
+            sess,
+            sub_id,
+            Op::UserInput {
+                items,
+                final_output_json_schema: None,
+            },
+        )
+        .await;
+    }
+
     /// Persists the thread name in the session index, updates in-memory state, and emits
     /// a `ThreadNameUpdated` event on success.
     ///
@@ -4213,6 +5873,12 @@ mod handlers {
             .terminate_all_processes()
             .await;
         sess.services.zsh_exec_bridge.shutdown().await;
+        if let Some(shadow) = &sess.services.shadow_workspace {
+            shadow.cleanup().await;
+        }
+        if let Some(artifact) = &sess.services.patch_artifact {
+            artifact.lock().await.write().await;
+        }
         info!("Shutting down Codex instance");
         let history = sess.clone_history().await;
         let turn_count = history
@@ -4318,7 +5984,9 @@ async fn spawn_review_thread(
         web_search_mode: Some(review_web_search_mode),
     })
     .with_allow_login_shell(config.permissions.allow_login_shell)
-    .with_agent_roles(config.agent_roles.clone());
+    .with_agent_roles(config.agent_roles.clone())
+    .with_read_only(config.read_only_session)
+    .with_patch_artifact(config.patch_artifact);
 
     let review_prompt = resolved.prompt.clone();
     let provider = parent_turn_context.provider.clone();
@@ -4387,12 +6055,14 @@ async fn spawn_review_thread(
         shell_environment_policy: parent_turn_context.shell_environment_policy.clone(),
         cwd: parent_turn_context.cwd.clone(),
         final_output_json_schema: None,
+        sampling: parent_turn_context.sampling,
         codex_linux_sandbox_exe: parent_turn_context.codex_linux_sandbox_exe.clone(),
         tool_call_gate: Arc::new(ReadinessFlag::new()),
         js_repl: Arc::clone(&sess.js_repl),
         dynamic_tools: parent_turn_context.dynamic_tools.clone(),
         truncation_policy: model_info.truncation_policy.into(),
         turn_metadata_state,
+        timer: Arc::default(),
     };
 
     // Seed the child task with the review prompt as the initial user message.
@@ -4450,6 +6120,7 @@ fn skills_to_info(
                             transport: tool.transport,
                             command: tool.command,
                             url: tool.url,
+                            version: tool.version,
                         })
                         .collect(),
                 }
@@ -4492,7 +6163,9 @@ pub(crate) async fn run_turn(
     prewarmed_client_session: Option<ModelClientSession>,
     cancellation_token: CancellationToken,
 ) -> Option<String> {
-    if input.is_empty() {
+    // A turn can also start from pending input alone, as the follow-ups of a
+    // benchmarked turn do.
+    if input.is_empty() && !sess.has_pending_input().await {
         return None;
     }
 
@@ -4573,6 +6246,16 @@ pub(crate) async fn run_turn(
         let env_var_dependencies = collect_env_var_dependencies(&mentioned_skills);
         resolve_skill_dependencies_for_turn(&sess, &turn_context, &env_var_dependencies).await;
     }
+    if config.features.enabled(Feature::SkillCliDependencyInstall) {
+        let cli_dependencies = collect_cli_dependencies(&mentioned_skills);
+        install_missing_cli_dependencies(
+            &sess,
+            &turn_context,
+            &cancellation_token,
+            &cli_dependencies,
+        )
+        .await;
+    }
 
     maybe_prompt_and_install_mcp_dependencies(
         sess.as_ref(),
@@ -4628,13 +6311,22 @@ pub(crate) async fn run_turn(
     sess.services
         .analytics_events_client
         .track_app_mentioned(tracking.clone(), mentioned_app_invocations);
-    sess.merge_connector_selection(explicitly_enabled_connectors.clone())
-        .await;
+    let restored_connectors = sess.take_restored_connector_selection().await;
+    sess.merge_connector_selection(
+        explicitly_enabled_connectors
+            .iter()
+            .cloned()
+            .chain(restored_connectors)
+            .collect(),
+    )
+    .await;
 
-    let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input.clone());
-    let response_item: ResponseItem = initial_input_for_turn.clone().into();
-    sess.record_user_prompt_and_emit_turn_item(turn_context.as_ref(), &input, response_item)
-        .await;
+    if !input.is_empty() {
+        let initial_input_for_turn: ResponseInputItem = ResponseInputItem::from(input.clone());
+        let response_item: ResponseItem = initial_input_for_turn.clone().into();
+        sess.record_user_prompt_and_emit_turn_item(turn_context.as_ref(), &input, response_item)
+            .await;
+    }
     // Track the previous-model baseline from the regular user-turn path only so
     // standalone tasks (compact/shell/review/undo) cannot suppress future
     // `<model_switch>` injections.
@@ -4651,8 +6343,18 @@ pub(crate) async fn run_turn(
     let mut last_agent_message: Option<String> = None;
     // Although from the perspective of codex.rs, TurnDiffTracker has the lifecycle of a Task which contains
     // many turns, from the perspective of the user, it is a single turn.
-    let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+    let turn_diff_tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::with_limits(
+        turn_context.config.turn_diff_limits,
+    )));
     let mut server_model_warning_emitted_for_turn = false;
+    let task_limits = turn_context.config.task_limits;
+    let task_started = Instant::now();
+    let mut model_requests: u32 = 0;
+    let mut tool_calls: u32 = 0;
+    let mut tokens: u64 = 0;
+    // Last agent message of a request the model wants to follow up on, kept
+    // in case a limit stops the task before that follow-up.
+    let mut follow_up_agent_message = None;
 
     // `ModelClientSession` is turn-scoped and caches WebSocket + sticky routing state, so we reuse
     // one instance across retries within this turn.
@@ -4690,12 +6392,36 @@ pub(crate) async fn run_turn(
             }
         }
 
+        // Stop before the next request; everything recorded so far stays in
+        // history so the user can pick up from here.
+        if let Some(message) =
+            task_limits.reached(model_requests, tool_calls, tokens, task_started.elapsed())
+        {
+            last_agent_message = follow_up_agent_message;
+            sess.send_event(
+                &turn_context,
+                EventMsg::Error(ErrorEvent {
+                    message,
+                    codex_error_info: Some(CodexErrorInfo::TaskLimitReached),
+                }),
+            )
+            .await;
+            break;
+        }
+
+        // Deduplicate in history itself, so token accounting, compaction,
+        // and later requests all see the shorter outputs.
+        if turn_context.features.enabled(Feature::DedupToolOutputs) {
+            let mut state = sess.state.lock().await;
+            state.history.dedup_tool_outputs();
+        }
+
         // Construct the input that we will send to the model.
-        let sampling_request_input: Vec<ResponseItem> = {
-            sess.clone_history()
-                .await
-                .for_prompt(&turn_context.model_info.input_modalities)
-        };
+        let prompt_assembly_started = Instant::now();
+        let sampling_request_input: Vec<ResponseItem> = sess
+            .clone_history()
+            .await
+            .for_prompt(&turn_context.model_info.input_modalities);
 
         let sampling_request_input_messages = sampling_request_input
             .iter()
@@ -4705,6 +6431,9 @@ pub(crate) async fn run_turn(
             })
             .map(|user_message| user_message.message())
             .collect::<Vec<String>>();
+        turn_context
+            .timer
+            .record_prompt_assembly(prompt_assembly_started.elapsed());
         let turn_metadata_header = turn_context.turn_metadata_state.current_header_value();
         match run_sampling_request(
             Arc::clone(&sess),
@@ -4716,6 +6445,7 @@ pub(crate) async fn run_turn(
             &explicitly_enabled_connectors,
             skills_outcome.as_ref(),
             &mut server_model_warning_emitted_for_turn,
+            model_requests + 1,
             cancellation_token.child_token(),
         )
         .await
@@ -4724,7 +6454,16 @@ pub(crate) async fn run_turn(
                 let SamplingRequestResult {
                     needs_follow_up,
                     last_agent_message: sampling_request_last_agent_message,
+                    tool_calls: sampling_request_tool_calls,
+                    tokens: sampling_request_tokens,
                 } = sampling_request_output;
+                model_requests += 1;
+                tool_calls += sampling_request_tool_calls;
+                tokens += sampling_request_tokens;
+                if needs_follow_up {
+                    follow_up_agent_message = sampling_request_last_agent_message.clone();
+                }
+
                 let total_usage_tokens = sess.get_total_token_usage().await;
                 let token_limit_reached = total_usage_tokens >= auto_compact_limit;
 
@@ -5118,8 +6857,10 @@ async fn run_sampling_request(
     explicitly_enabled_connectors: &HashSet<String>,
     skills_outcome: Option<&SkillLoadOutcome>,
     server_model_warning_emitted_for_turn: &mut bool,
+    request_index: u32,
     cancellation_token: CancellationToken,
 ) -> CodexResult<SamplingRequestResult> {
+    let prompt_assembly_started = Instant::now();
     let router = built_tools(
         sess.as_ref(),
         turn_context.as_ref(),
@@ -5132,7 +6873,12 @@ async fn run_sampling_request(
 
     let model_supports_parallel = turn_context.model_info.supports_parallel_tool_calls;
 
-    let tools = router.specs();
+    let mut tools = router.specs();
+    apply_experiment_tool_descriptions(
+        &mut tools,
+        &turn_context.config.experiments,
+        &sess.experiment_arms().await,
+    );
     let base_instructions = sess.get_base_instructions().await;
 
     let prompt = Prompt {
@@ -5142,7 +6888,11 @@ async fn run_sampling_request(
         base_instructions,
         personality: turn_context.personality,
         output_schema: turn_context.final_output_json_schema.clone(),
+        sampling: turn_context.sampling,
     };
+    turn_context
+        .timer
+        .record_prompt_assembly(prompt_assembly_started.elapsed());
 
     let mut retries = 0;
     loop {
@@ -5155,6 +6905,7 @@ async fn run_sampling_request(
             Arc::clone(&turn_diff_tracker),
             server_model_warning_emitted_for_turn,
             &prompt,
+            request_index,
             cancellation_token.child_token(),
         )
         .await
@@ -5182,7 +6933,10 @@ async fn run_sampling_request(
 
         // Use the configured provider-specific stream retry budget.
         let max_retries = turn_context.provider.stream_max_retries();
+        // A provider error is the provider's answer, which another transport
+        // would get too.
         if retries >= max_retries
+            && !matches!(err, CodexErr::Provider(_))
             && client_session
                 .try_switch_fallback_transport(&turn_context.otel_manager, &turn_context.model_info)
         {
@@ -5236,7 +6990,7 @@ async fn run_sampling_request(
     }
 }
 
-async fn built_tools(
+pub(crate) async fn built_tools(
     sess: &Session,
     turn_context: &TurnContext,
     input: &[ResponseItem],
@@ -5295,8 +7049,13 @@ async fn built_tools(
             connectors::filter_codex_apps_tools_by_policy(selected_mcp_tools, &turn_context.config);
     }
 
+    let tools_config = turn_context
+        .tools_config
+        .clone()
+        .with_read_attachment_tool(sess.has_attachments().await)
+        .with_disabled_tools(sess.disabled_tools().await);
     Ok(Arc::new(ToolRouter::from_config(
-        &turn_context.tools_config,
+        &tools_config,
         has_mcp_servers.then(|| {
             mcp_tools
                 .into_iter()
@@ -5312,6 +7071,9 @@ async fn built_tools(
 struct SamplingRequestResult {
     needs_follow_up: bool,
     last_agent_message: Option<String>,
+    tool_calls: u32,
+    /// Input and output tokens the request used.
+    tokens: u64,
 }
 
 /// Ephemeral per-response state for streaming a single proposed plan.
@@ -5776,6 +7538,7 @@ async fn try_run_sampling_request(
     turn_diff_tracker: SharedTurnDiffTracker,
     server_model_warning_emitted_for_turn: &mut bool,
     prompt: &Prompt,
+    request_index: u32,
     cancellation_token: CancellationToken,
 ) -> CodexResult<SamplingRequestResult> {
     // Persist one TurnContext marker per sampling request (not just per user turn) so rollout
@@ -5793,7 +7556,9 @@ async fn try_run_sampling_request(
     );
 
     sess.persist_rollout_items(&[rollout_item]).await;
-    let mut stream = client_session
+    let request_started = Instant::now();
+    let mut time_to_first_token = None;
+    let stream = client_session
         .stream(
             prompt,
             &turn_context.model_info,
@@ -5804,7 +7569,19 @@ async fn try_run_sampling_request(
         )
         .instrument(trace_span!("stream_request"))
         .or_cancel(&cancellation_token)
-        .await??;
+        .await
+        .map_err(CodexErr::from)
+        .and_then(std::convert::identity);
+    let mut stream = match stream {
+        Ok(stream) => stream,
+        Err(err) => {
+            // Failed requests count toward the turn's timings too.
+            turn_context
+                .timer
+                .record_model_request(None, request_started.elapsed());
+            return Err(err);
+        }
+    };
 
     let tool_runtime = ToolCallRuntime::new(
         Arc::clone(&router),
@@ -5816,10 +7593,16 @@ async fn try_run_sampling_request(
         FuturesOrdered::new();
     let mut needs_follow_up = false;
     let mut last_agent_message: Option<String> = None;
+    let mut tool_calls: u32 = 0;
     let mut active_item: Option<TurnItem> = None;
     let mut should_emit_turn_diff = false;
     let plan_mode = turn_context.collaboration_mode.mode == ModeKind::Plan;
     let mut plan_mode_state = plan_mode.then(|| PlanModeStreamState::new(&turn_context.sub_id));
+    let mut streaming_tool_calls = StreamingToolCalls::default();
+    let mut prefetcher = turn_context
+        .features
+        .enabled(Feature::SpeculativeToolPrefetch)
+        .then(ToolCallPrefetcher::default);
     let receiving_span = trace_span!("receiving_stream");
     let outcome: CodexResult<SamplingRequestResult> = loop {
         let handle_responses = trace_span!(
@@ -5841,7 +7624,8 @@ async fn try_run_sampling_request(
         };
 
         let event = match event {
-            Some(res) => res?,
+            Some(Ok(event)) => event,
+            Some(Err(err)) => break Err(err),
             None => {
                 break Err(CodexErr::Stream(
                     "stream closed before response.completed".into(),
@@ -5853,6 +7637,18 @@ async fn try_run_sampling_request(
         sess.services
             .otel_manager
             .record_responses(&handle_responses, &event);
+        if time_to_first_token.is_none()
+            && !matches!(
+                event,
+                ResponseEvent::Created
+                    | ResponseEvent::ServerModel(_)
+                    | ResponseEvent::ServerReasoningIncluded(_)
+                    | ResponseEvent::RateLimits(_)
+                    | ResponseEvent::ModelsEtag(_)
+            )
+        {
+            time_to_first_token = Some(request_started.elapsed());
+        }
 
         match event {
             ResponseEvent::Created => {}
@@ -5892,11 +7688,17 @@ async fn try_run_sampling_request(
                     cancellation_token: cancellation_token.child_token(),
                 };
 
-                let output_result = handle_output_item_done(&mut ctx, item, previously_active_item)
-                    .instrument(handle_responses)
-                    .await?;
+                let output_result =
+                    match handle_output_item_done(&mut ctx, item, previously_active_item)
+                        .instrument(handle_responses)
+                        .await
+                    {
+                        Ok(output_result) => output_result,
+                        Err(err) => break Err(err),
+                    };
                 if let Some(tool_future) = output_result.tool_future {
                     in_flight.push_back(tool_future);
+                    tool_calls += 1;
                 }
                 if let Some(agent_message) = output_result.last_agent_message {
                     last_agent_message = Some(agent_message);
@@ -5904,6 +7706,10 @@ async fn try_run_sampling_request(
                 needs_follow_up |= output_result.needs_follow_up;
             }
             ResponseEvent::OutputItemAdded(item) => {
+                streaming_tool_calls.item_added(&item);
+                if let Some(prefetcher) = prefetcher.as_mut() {
+                    prefetcher.item_added(&item);
+                }
                 if let Some(turn_item) = handle_non_tool_response_item(&item, plan_mode).await {
                     if let Some(state) = plan_mode_state.as_mut()
                         && matches!(turn_item, TurnItem::AgentMessage(_))
@@ -5947,8 +7753,15 @@ async fn try_run_sampling_request(
                 if let Some(state) = plan_mode_state.as_mut() {
                     flush_proposed_plan_segments_all(&sess, &turn_context, state).await;
                 }
-                sess.update_token_usage_info(&turn_context, token_usage.as_ref())
-                    .await;
+                sess.update_token_usage_info(
+                    &turn_context,
+                    token_usage.as_ref(),
+                    Some(ModelRequestUsage {
+                        index: request_index,
+                        model: turn_context.model_info.slug.clone(),
+                    }),
+                )
+                .await;
                 should_emit_turn_diff = true;
 
                 needs_follow_up |= sess.has_pending_input().await;
@@ -5956,6 +7769,10 @@ async fn try_run_sampling_request(
                 break Ok(SamplingRequestResult {
                     needs_follow_up,
                     last_agent_message,
+                    tool_calls,
+                    tokens: token_usage.map_or(0, |usage| {
+                        u64::try_from(usage.total_tokens).unwrap_or_default()
+                    }),
                 });
             }
             ResponseEvent::OutputTextDelta(delta) => {
@@ -6015,6 +7832,24 @@ async fn try_run_sampling_request(
                     error_or_panic("ReasoningSummaryPartAdded without active item".to_string());
                 }
             }
+            ResponseEvent::ToolCallArgumentsDelta { item_id, delta } => {
+                if let Some(preview) = streaming_tool_calls.arguments_delta(&item_id, &delta) {
+                    if let Some(error) = &preview.error {
+                        warn!(
+                            "malformed arguments streaming for tool call {}: {error}",
+                            preview.call_id
+                        );
+                    }
+                    sess.send_event(&turn_context, EventMsg::ToolCallArgumentsPreview(preview))
+                        .await;
+                }
+                if let Some(prefetcher) = prefetcher.as_mut()
+                    && let Some(item) = prefetcher.arguments_delta(&item_id, &delta)
+                    && let Ok(Some(call)) = ToolRouter::build_tool_call(sess.as_ref(), item).await
+                {
+                    tool_runtime.prefetch(call, cancellation_token.child_token());
+                }
+            }
             ResponseEvent::ReasoningContentDelta {
                 delta,
                 content_index,
@@ -6035,16 +7870,22 @@ async fn try_run_sampling_request(
             }
         }
     };
+    turn_context
+        .timer
+        .record_model_request(time_to_first_token, request_started.elapsed());
 
     drain_in_flight(&mut in_flight, sess.clone(), turn_context.clone()).await?;
 
     if should_emit_turn_diff {
-        let unified_diff = {
+        let (unified_diff, anchors) = {
             let mut tracker = turn_diff_tracker.lock().await;
-            tracker.get_unified_diff()
+            (tracker.get_unified_diff(), tracker.anchors().to_vec())
         };
         if let Ok(Some(unified_diff)) = unified_diff {
-            let msg = EventMsg::TurnDiff(TurnDiffEvent { unified_diff });
+            let msg = EventMsg::TurnDiff(TurnDiffEvent {
+                unified_diff,
+                anchors,
+            });
             sess.clone().send_event(&turn_context, msg).await;
         }
     }
@@ -6072,7 +7913,6 @@ pub(super) fn get_last_assistant_message_from_turn(responses: &[ResponseItem]) -
     })
 }
 
-use crate::memories::prompts::build_memory_tool_developer_instructions;
 #[cfg(test)]
 pub(crate) use tests::make_session_and_context;
 #[cfg(test)]
@@ -6105,8 +7945,10 @@ mod tests {
     use crate::protocol::CompactedItem;
     use crate::protocol::CreditsSnapshot;
     use crate::protocol::InitialHistory;
+    use crate::protocol::OfflineStatusEvent;
     use crate::protocol::RateLimitSnapshot;
     use crate::protocol::RateLimitWindow;
+    use crate::protocol::ResponseCandidateSelectedEvent;
     use crate::protocol::ResumedHistory;
     use crate::protocol::TokenCountEvent;
     use crate::protocol::TokenUsage;
@@ -6212,6 +8054,7 @@ mod tests {
             },
             connector_id: connector_id.map(str::to_string),
             connector_name: connector_name.map(str::to_string),
+            connector_version: None,
         }
     }
 
@@ -6644,6 +8487,63 @@ mod tests {
         assert_eq!(selected, None);
     }
 
+    #[test]
+    fn extract_tool_selection_from_rollout_prefers_recorded_selection() {
+        let search_output = function_call_output_rollout_item(
+            "search-1",
+            &json!({ "active_selected_tools": ["mcp__rmcp__echo"] }).to_string(),
+        );
+        let recorded = ToolSelectionEvent {
+            connectors: vec!["calendar".to_string()],
+            mcp_tools: vec!["mcp__codex_apps__calendar_create_event".to_string()],
+        };
+        let rollout_items = vec![
+            RolloutItem::EventMsg(EventMsg::ToolSelection(ToolSelectionEvent::default())),
+            RolloutItem::EventMsg(EventMsg::ToolSelection(recorded.clone())),
+            function_call_rollout_item(SEARCH_TOOL_BM25_TOOL_NAME, "search-1"),
+            search_output.clone(),
+        ];
+        assert_eq!(
+            Session::extract_tool_selection_from_rollout(&rollout_items),
+            Some(recorded)
+        );
+
+        let legacy_rollout_items = vec![
+            function_call_rollout_item(SEARCH_TOOL_BM25_TOOL_NAME, "search-1"),
+            search_output,
+        ];
+        assert_eq!(
+            Session::extract_tool_selection_from_rollout(&legacy_rollout_items),
+            Some(ToolSelectionEvent {
+                connectors: Vec::new(),
+                mcp_tools: vec!["mcp__rmcp__echo".to_string()],
+            })
+        );
+    }
+
+    #[test]
+    fn turn_timings_from_rollout_keeps_the_most_recent_turns() {
+        let timing = |turn: usize| TurnTiming {
+            turn_id: format!("turn-{turn}"),
+            queue_wait_ms: 0,
+            prompt_assembly_ms: 0,
+            model_requests: Vec::new(),
+            tools: Vec::new(),
+            serialization_ms: 0,
+            total_ms: 0,
+        };
+        let rollout_items = (0..MAX_TURN_TIMINGS + 2)
+            .map(|turn| RolloutItem::EventMsg(EventMsg::TurnTimingRecorded(timing(turn))))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            Session::turn_timings_from_rollout(&rollout_items),
+            (2..MAX_TURN_TIMINGS + 2)
+                .map(timing)
+                .collect::<VecDeque<_>>()
+        );
+    }
+
     #[tokio::test]
     async fn reconstruct_history_matches_live_compactions() {
         let (session, turn_context) = make_session_and_context().await;
@@ -6654,7 +8554,7 @@ mod tests {
             .reconstruct_history_from_rollout(reconstruction_turn.as_ref(), &rollout_items)
             .await;
 
-        assert_eq!(expected, reconstructed);
+        assert_eq!(expected, reconstructed.raw_items());
     }
 
     #[tokio::test]
@@ -6690,7 +8590,7 @@ mod tests {
             .reconstruct_history_from_rollout(&turn_context, &rollout_items)
             .await;
 
-        assert_eq!(reconstructed, replacement_history);
+        assert_eq!(reconstructed.raw_items(), replacement_history);
     }
 
     #[tokio::test]
@@ -6729,6 +8629,7 @@ mod tests {
             developer_instructions: None,
             final_output_json_schema: None,
             truncation_policy: Some(turn_context.truncation_policy.into()),
+            sampling: None,
         };
         let rollout_items = vec![RolloutItem::TurnContext(previous_context_item)];
 
@@ -6766,6 +8667,7 @@ mod tests {
             developer_instructions: None,
             final_output_json_schema: None,
             truncation_policy: Some(turn_context.truncation_policy.into()),
+            sampling: None,
         };
         let turn_id = previous_context_item
             .turn_id
@@ -6995,24 +8897,28 @@ mod tests {
             TokenCountEvent {
                 info: Some(info1),
                 rate_limits: None,
+                request: None,
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                request: None,
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: Some(info2.clone()),
                 rate_limits: None,
+                request: None,
             },
         )));
         rollout_items.push(RolloutItem::EventMsg(EventMsg::TokenCount(
             TokenCountEvent {
                 info: None,
                 rate_limits: None,
+                request: None,
             },
         )));
 
@@ -7128,6 +9034,7 @@ mod tests {
             developer_instructions: None,
             final_output_json_schema: None,
             truncation_policy: Some(turn_context.truncation_policy.into()),
+            sampling: None,
         };
         let rollout_items = vec![RolloutItem::TurnContext(previous_context_item)];
 
@@ -7282,6 +9189,172 @@ mod tests {
         assert_eq!(initial_context, history.raw_items());
     }
 
+    #[tokio::test]
+    async fn retry_from_user_message_fails_for_unknown_item_id() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+
+        let initial_context = sess.build_initial_context(tc.as_ref()).await;
+        sess.record_into_history(&initial_context, tc.as_ref())
+            .await;
+
+        handlers::retry_from_user_message(
+            &sess,
+            "sub-1".to_string(),
+            "missing".to_string(),
+            vec![UserInput::Text {
+                text: "edited".to_string(),
+                text_elements: Vec::new(),
+            }],
+        )
+        .await;
+
+        let error_event = wait_for_thread_rollback_failed(&rx).await;
+        assert_eq!(
+            error_event.message,
+            "no user message missing in the thread history"
+        );
+
+        let history = sess.clone_history().await;
+        assert_eq!(initial_context, history.raw_items());
+    }
+
+    #[tokio::test]
+    async fn select_candidate_replaces_the_last_response() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+        let message = |role: &str, text: &str| ResponseItem::Message {
+            id: None,
+            role: role.to_string(),
+            content: vec![if role == "user" {
+                ContentItem::InputText {
+                    text: text.to_string(),
+                }
+            } else {
+                ContentItem::OutputText {
+                    text: text.to_string(),
+                }
+            }],
+            end_turn: None,
+            phase: None,
+        };
+
+        let mut expected = sess.build_initial_context(tc.as_ref()).await;
+        expected.push(message("user", "question"));
+        sess.record_into_history(&expected, tc.as_ref()).await;
+        sess.record_into_history(&[message("assistant", "original")], tc.as_ref())
+            .await;
+        sess.set_response_candidates(ResponseCandidates {
+            history_len: expected.len() + 1,
+            response_start: expected.len(),
+            candidates: vec![
+                vec![message("assistant", "first")],
+                vec![message("assistant", "second")],
+            ],
+        })
+        .await;
+
+        handlers::select_candidate(&sess, "sub-1".to_string(), 1).await;
+
+        let selected = tokio::time::timeout(StdDuration::from_secs(2), async {
+            loop {
+                if let EventMsg::ResponseCandidateSelected(event) =
+                    rx.recv().await.expect("event").msg
+                {
+                    return event;
+                }
+            }
+        })
+        .await
+        .expect("timeout waiting for event");
+        assert_eq!(
+            selected,
+            ResponseCandidateSelectedEvent {
+                index: 1,
+                message: "second".to_string(),
+            }
+        );
+        expected.push(message("assistant", "second"));
+        assert_eq!(expected, sess.clone_history().await.raw_items());
+
+        // Resuming drops the replaced response and keeps the recorded candidate.
+        let mut rollout_items: Vec<RolloutItem> = expected[..expected.len() - 1]
+            .iter()
+            .cloned()
+            .map(RolloutItem::ResponseItem)
+            .collect();
+        rollout_items.extend([
+            RolloutItem::ResponseItem(message("assistant", "original")),
+            RolloutItem::EventMsg(EventMsg::ResponseCandidateSelected(selected)),
+            RolloutItem::ResponseItem(message("assistant", "second")),
+        ]);
+        assert_eq!(
+            expected,
+            sess.reconstruct_history_from_rollout(tc.as_ref(), &rollout_items)
+                .await
+                .raw_items()
+        );
+    }
+
+    #[tokio::test]
+    async fn turns_are_queued_while_offline() {
+        let (sess, tc, rx) = make_session_and_context_with_rx().await;
+        let next_offline_status = || async {
+            tokio::time::timeout(StdDuration::from_secs(2), async {
+                loop {
+                    if let EventMsg::OfflineStatus(event) = rx.recv().await.expect("event").msg {
+                        return event;
+                    }
+                }
+            })
+            .await
+            .expect("timeout waiting for event")
+        };
+
+        handlers::set_offline(&sess, "sub-1".to_string(), true).await;
+        assert_eq!(
+            next_offline_status().await,
+            OfflineStatusEvent {
+                offline: true,
+                queued_turns: 0,
+            }
+        );
+
+        handlers::user_input_or_turn(
+            &sess,
+            "sub-2".to_string(),
+            Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: "hello".to_string(),
+                    text_elements: Vec::new(),
+                }],
+                final_output_json_schema: None,
+            },
+        )
+        .await;
+        assert_eq!(
+            next_offline_status().await,
+            OfflineStatusEvent {
+                offline: true,
+                queued_turns: 1,
+            }
+        );
+        assert!(sess.active_turn.lock().await.is_none());
+        assert!(matches!(
+            sess.services
+                .model_client
+                .new_session()
+                .stream(
+                    &Prompt::default(),
+                    &tc.model_info,
+                    &tc.otel_manager,
+                    None,
+                    tc.reasoning_summary,
+                    None,
+                )
+                .await,
+            Err(CodexErr::Offline)
+        ));
+    }
+
     #[tokio::test]
     async fn set_rate_limits_retains_previous_credits() {
         let codex_home = tempfile::tempdir().expect("create temp dir");
@@ -7320,7 +9393,10 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
             dynamic_tools: Vec::new(),
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             persist_extended_history: false,
+            sampling: SamplingParams::default(),
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -7411,7 +9487,10 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
             dynamic_tools: Vec::new(),
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             persist_extended_history: false,
+            sampling: SamplingParams::default(),
         };
 
         let mut state = SessionState::new(session_configuration);
@@ -7721,7 +9800,10 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
             dynamic_tools: Vec::new(),
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             persist_extended_history: false,
+            sampling: SamplingParams::default(),
         }
     }
 
@@ -7772,7 +9854,10 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
             dynamic_tools: Vec::new(),
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             persist_extended_history: false,
+            sampling: SamplingParams::default(),
         };
 
         let (tx_event, _rx_event) = async_channel::unbounded();
@@ -7790,6 +9875,7 @@ mod tests {
             Arc::new(SkillsManager::new(config.codex_home.clone())),
             Arc::new(FileWatcher::noop()),
             AgentControl::default(),
+            None,
         )
         .await;
 
@@ -7851,7 +9937,10 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
             dynamic_tools: Vec::new(),
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             persist_extended_history: false,
+            sampling: SamplingParams::default(),
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_info = ModelsManager::construct_model_info_offline_for_tests(
@@ -7888,6 +9977,12 @@ mod tests {
             hooks: Hooks::new(HooksConfig {
                 legacy_notify_argv: config.notify.clone(),
             }),
+            notifications: NotificationDispatcher::new(&config.notifier),
+            attached_clients: AttachedClients::default(),
+            tool_output_summaries: Mutex::new(ToolOutputSummaries::default()),
+            tool_result_cache: Mutex::new(ToolResultCache::default()),
+            finding_baselines: Mutex::new(FindingBaselines::default()),
+            active_tool_calls: ActiveToolCalls::default(),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             shell_snapshot_tx: watch::channel(None).0,
@@ -7903,6 +9998,8 @@ mod tests {
             network_proxy: None,
             network_approval: Arc::clone(&network_approval),
             state_db: None,
+            shadow_workspace: None,
+            patch_artifact: None,
             model_client: ModelClient::new(
                 Some(auth_manager.clone()),
                 conversation_id,
@@ -7941,6 +10038,7 @@ mod tests {
             pending_mcp_server_refresh_config: Mutex::new(None),
             conversation: Arc::new(RealtimeConversationManager::new()),
             active_turn: Mutex::new(None),
+            idle_since: watch::Sender::new(Some(Instant::now())),
             services,
             js_repl,
             next_internal_sub_id: AtomicU64::new(0),
@@ -8004,7 +10102,10 @@ mod tests {
             original_config_do_not_use: Arc::clone(&config),
             session_source: SessionSource::Exec,
             dynamic_tools: Vec::new(),
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             persist_extended_history: false,
+            sampling: SamplingParams::default(),
         };
         let per_turn_config = Session::build_per_turn_config(&session_configuration);
         let model_info = ModelsManager::construct_model_info_offline_for_tests(
@@ -8041,6 +10142,12 @@ mod tests {
             hooks: Hooks::new(HooksConfig {
                 legacy_notify_argv: config.notify.clone(),
             }),
+            notifications: NotificationDispatcher::new(&config.notifier),
+            attached_clients: AttachedClients::default(),
+            tool_output_summaries: Mutex::new(ToolOutputSummaries::default()),
+            tool_result_cache: Mutex::new(ToolResultCache::default()),
+            finding_baselines: Mutex::new(FindingBaselines::default()),
+            active_tool_calls: ActiveToolCalls::default(),
             rollout: Mutex::new(None),
             user_shell: Arc::new(default_user_shell()),
             shell_snapshot_tx: watch::channel(None).0,
@@ -8056,6 +10163,8 @@ mod tests {
             network_proxy: None,
             network_approval: Arc::clone(&network_approval),
             state_db: None,
+            shadow_workspace: None,
+            patch_artifact: None,
             model_client: ModelClient::new(
                 Some(Arc::clone(&auth_manager)),
                 conversation_id,
@@ -8094,6 +10203,7 @@ mod tests {
             pending_mcp_server_refresh_config: Mutex::new(None),
             conversation: Arc::new(RealtimeConversationManager::new()),
             active_turn: Mutex::new(None),
+            idle_since: watch::Sender::new(Some(Instant::now())),
             services,
             js_repl,
             next_internal_sub_id: AtomicU64::new(0),
@@ -1,4 +1,6 @@
 use crate::agent::AgentStatus;
+use crate::attached_clients::AttachMode;
+use crate::attached_clients::AttachedClient;
 use crate::codex::Codex;
 use crate::codex::SteerInputError;
 use crate::error::Result as CodexResult;
@@ -18,6 +20,7 @@ use codex_protocol::protocol::SessionSource;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::user_input::UserInput;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tokio::sync::watch;
 
 use crate::state_db::StateDbHandle;
@@ -76,6 +79,19 @@ impl CodexThread {
         self.codex.next_event().await
     }
 
+    /// Attaches another client, e.g. an IDE extension, to this live thread.
+    /// It receives every event from now on. As a driver it can also submit
+    /// ops, including answers to approvals the primary client was also asked
+    /// for; as an observer it only watches until promoted.
+    pub fn attach_client(&self, client: impl Into<String>, mode: AttachMode) -> AttachedClient {
+        AttachedClient::new(
+            client.into(),
+            mode,
+            self.codex.tx_sub.clone(),
+            Arc::clone(&self.codex.session),
+        )
+    }
+
     pub async fn agent_status(&self) -> AgentStatus {
         self.codex.agent_status().await
     }
@@ -106,6 +106,7 @@ async fn run_remote_compact_task_inner_impl(
         base_instructions,
         personality: turn_context.personality,
         output_schema: None,
+        sampling: turn_context.sampling,
     };
 
     let mut new_history = sess
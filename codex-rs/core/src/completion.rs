@@ -0,0 +1,199 @@
+//! Code completion at a cursor ([`Op::Complete`]), for editors that want
+//! Codex's auth and workspace context without the agent loop.
+//!
+//! The provider is sampled directly with the code around the cursor, cut to
+//! the requested token budgets, plus the repository's stored codebase map as
+//! workspace context when one has been generated. No turn starts and nothing
+//! is recorded.
+//!
+//! [`Op::Complete`]: codex_protocol::protocol::Op::Complete
+
+use std::path::Path;
+
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::CodexErrorInfo;
+use codex_protocol::protocol::CompletionResultEvent;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::TextPosition;
+use codex_utils_string::fill_template;
+use codex_utils_string::take_bytes_at_char_boundary;
+use codex_utils_string::take_last_bytes_at_char_boundary;
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::client_common::Prompt;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::memories::codebase_map::codebase_map_path;
+use crate::memories::codebase_map::codebase_map_root;
+use crate::stream_events_utils::sample_text;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_bytes_for_tokens;
+use crate::truncate::truncate_text;
+
+const INSTRUCTIONS: &str = include_str!("../templates/completion/instructions.md");
+const PROMPT_TEMPLATE: &str = include_str!("../templates/completion/prompt.md");
+const DEFAULT_PREFIX_TOKENS: u32 = 2_000;
+const DEFAULT_SUFFIX_TOKENS: u32 = 500;
+/// Share of the codebase map sent along; completions must stay fast.
+const CODEBASE_MAP_TOKENS: usize = 1_000;
+
+#[derive(Deserialize)]
+struct CompletionOutput {
+    completion: String,
+}
+
+/// Completes the code at `cursor` in the file at `path`.
+pub(crate) async fn complete(
+    sess: &Session,
+    turn_context: &TurnContext,
+    path: &Path,
+    cursor: TextPosition,
+    prefix_tokens: Option<u32>,
+    suffix_tokens: Option<u32>,
+) -> EventMsg {
+    let path = turn_context.cwd.join(path);
+    let error = |message: String, info: CodexErrorInfo| {
+        EventMsg::Error(ErrorEvent {
+            message,
+            codex_error_info: Some(info),
+        })
+    };
+    let content = match tokio::fs::read_to_string(&path).await {
+        Ok(content) => content,
+        Err(err) => {
+            return error(
+                format!("failed to read {}: {err}", path.display()),
+                CodexErrorInfo::BadRequest,
+            );
+        }
+    };
+    let Some((prefix, suffix)) = split_at_cursor(&content, cursor) else {
+        return error(
+            format!(
+                "line {} column {} is not within {}",
+                cursor.line,
+                cursor.column,
+                path.display()
+            ),
+            CodexErrorInfo::BadRequest,
+        );
+    };
+    let prefix = take_last_bytes_at_char_boundary(
+        prefix,
+        approx_bytes_for_tokens(prefix_tokens.unwrap_or(DEFAULT_PREFIX_TOKENS) as usize),
+    );
+    let suffix = take_bytes_at_char_boundary(
+        suffix,
+        approx_bytes_for_tokens(suffix_tokens.unwrap_or(DEFAULT_SUFFIX_TOKENS) as usize),
+    );
+
+    let repo_root = codebase_map_root(&turn_context.cwd);
+    let workspace = match tokio::fs::read_to_string(codebase_map_path(
+        &turn_context.config.codex_home,
+        &repo_root,
+    ))
+    .await
+    {
+        Ok(codebase_map) => format!(
+            "\nRepository overview:\n{}\n",
+            truncate_text(&codebase_map, TruncationPolicy::Tokens(CODEBASE_MAP_TOKENS))
+        ),
+        Err(_) => String::new(),
+    };
+    let display_path = path.strip_prefix(&repo_root).unwrap_or(&path);
+    let text = fill_template(
+        PROMPT_TEMPLATE,
+        &[
+            ("path", &display_path.display().to_string()),
+            ("workspace", &workspace),
+            ("suffix", suffix),
+            ("prefix", prefix),
+        ],
+    );
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text }],
+            end_turn: None,
+            phase: None,
+        }],
+        base_instructions: BaseInstructions {
+            text: INSTRUCTIONS.to_string(),
+        },
+        output_schema: Some(json!({
+            "type": "object",
+            "properties": {
+                "completion": { "type": "string" }
+            },
+            "required": ["completion"],
+            "additionalProperties": false
+        })),
+        sampling: turn_context.sampling,
+        ..Default::default()
+    };
+
+    let reply = match sample_text(sess, turn_context, &prompt).await {
+        Ok(reply) => reply,
+        Err(err) => return EventMsg::Error(err.to_error_event(None)),
+    };
+    match serde_json::from_str::<CompletionOutput>(&reply) {
+        Ok(output) => EventMsg::CompletionResult(CompletionResultEvent {
+            path,
+            cursor,
+            completion: output.completion,
+        }),
+        Err(err) => error(
+            format!("the model returned an invalid completion: {err}"),
+            CodexErrorInfo::Other,
+        ),
+    }
+}
+
+/// `content` split at `cursor`, or `None` if the cursor is not within it.
+fn split_at_cursor(content: &str, cursor: TextPosition) -> Option<(&str, &str)> {
+    if cursor.line == 0 {
+        return None;
+    }
+    let mut line_start = 0;
+    for _ in 1..cursor.line {
+        line_start += content[line_start..].find('\n')? + 1;
+    }
+    let line = content[line_start..]
+        .split_inclusive('\n')
+        .next()
+        .unwrap_or_default();
+    let line = line.strip_suffix('\n').unwrap_or(line);
+    let column = usize::try_from(cursor.column).ok()?.checked_sub(1)?;
+    let offset = line
+        .char_indices()
+        .map(|(offset, _)| offset)
+        .chain([line.len()])
+        .nth(column)?;
+    Some(content.split_at(line_start + offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn splits_content_at_the_cursor() {
+        let content = "fn main() {\n    let é = 1;\n}\n";
+        let at = |line, column| split_at_cursor(content, TextPosition { line, column });
+
+        assert_eq!(at(1, 1), Some(("", content)));
+        assert_eq!(at(2, 10), Some(("fn main() {\n    let é", " = 1;\n}\n")));
+        assert_eq!(at(2, 15), Some(("fn main() {\n    let é = 1;", "\n}\n")));
+        assert_eq!(at(4, 1), Some((content, "")));
+        assert_eq!(at(2, 16), None);
+        assert_eq!(at(5, 1), None);
+        assert_eq!(at(0, 1), None);
+        assert_eq!(at(1, 0), None);
+    }
+}
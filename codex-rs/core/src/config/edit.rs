@@ -174,12 +174,21 @@ mod document_helpers {
         if config.required {
             entry["required"] = value(true);
         }
+        if config.lazy {
+            entry["lazy"] = value(true);
+        }
         if let Some(timeout) = config.startup_timeout_sec {
             entry["startup_timeout_sec"] = value(timeout.as_secs_f64());
         }
         if let Some(timeout) = config.tool_timeout_sec {
             entry["tool_timeout_sec"] = value(timeout.as_secs_f64());
         }
+        if config.elicitation {
+            entry["elicitation"] = value(true);
+        }
+        if let Some(timeout) = config.elicitation_timeout_sec {
+            entry["elicitation_timeout_sec"] = value(timeout.as_secs_f64());
+        }
         if let Some(enabled_tools) = &config.enabled_tools
             && !enabled_tools.is_empty()
         {
@@ -1435,9 +1444,12 @@ gpt-5 = "gpt-5.1"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: Some(vec!["one".to_string(), "two".to_string()]),
                 disabled_tools: None,
                 scopes: None,
@@ -1459,9 +1471,12 @@ gpt-5 = "gpt-5.1"
                 },
                 enabled: false,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(std::time::Duration::from_secs(5)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: Some(vec!["forbidden".to_string()]),
                 scopes: None,
@@ -1526,9 +1541,12 @@ foo = { command = "cmd" }
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -1572,9 +1590,12 @@ foo = { command = "cmd" } # keep me
                 },
                 enabled: false,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -1617,9 +1638,12 @@ foo = { command = "cmd", args = ["--flag"] } # keep me
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -1663,9 +1687,12 @@ foo = { command = "cmd" }
                 },
                 enabled: false,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -2,25 +2,43 @@ use crate::auth::AuthCredentialsStoreMode;
 use crate::config::edit::ConfigEdit;
 use crate::config::edit::ConfigEditsBuilder;
 use crate::config::types::AppsConfigToml;
+use crate::config::types::BenchmarksConfig;
+use crate::config::types::CloudCliConfig;
+use crate::config::types::ContextProviderConfig;
 use crate::config::types::DEFAULT_OTEL_ENVIRONMENT;
+use crate::config::types::ExperimentConfig;
 use crate::config::types::History;
+use crate::config::types::InstructionLayer;
+use crate::config::types::InstructionLayerToml;
+use crate::config::types::LicensePolicyConfig;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerDisabledReason;
+use crate::config::types::McpServerLimits;
 use crate::config::types::McpServerTransportConfig;
 use crate::config::types::MemoriesConfig;
 use crate::config::types::MemoriesToml;
+use crate::config::types::ModelPricing;
 use crate::config::types::Notice;
 use crate::config::types::NotificationMethod;
 use crate::config::types::Notifications;
+use crate::config::types::NotifierConfig;
 use crate::config::types::OtelConfig;
 use crate::config::types::OtelConfigToml;
 use crate::config::types::OtelExporterKind;
+use crate::config::types::ReadFilterRule;
 use crate::config::types::SandboxWorkspaceWrite;
+use crate::config::types::SessionRetention;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyToml;
 use crate::config::types::SkillsConfig;
+use crate::config::types::TaskLimits;
+use crate::config::types::ToolFreshness;
+use crate::config::types::ToolLockWatchdog;
+use crate::config::types::ToolOutputSummaryConfig;
 use crate::config::types::Tui;
+use crate::config::types::TurnDiffLimits;
 use crate::config::types::UriBasedFileOpener;
+use crate::config::types::WatchConfig;
 use crate::config::types::WindowsSandboxModeToml;
 use crate::config::types::WindowsToml;
 use crate::config_loader::CloudRequirementsLoader;
@@ -33,11 +51,13 @@ use crate::config_loader::McpServerRequirement;
 use crate::config_loader::ResidencyRequirement;
 use crate::config_loader::Sourced;
 use crate::config_loader::load_config_layers_state;
+use crate::exec::EXEC_OUTPUT_MAX_BYTES;
 use crate::features::Feature;
 use crate::features::FeatureOverrides;
 use crate::features::Features;
 use crate::features::FeaturesToml;
 use crate::git_info::resolve_root_git_project_for_trust;
+use crate::localization::Locale;
 use crate::model_provider_info::LEGACY_OLLAMA_CHAT_PROVIDER_ID;
 use crate::model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 use crate::model_provider_info::ModelProviderInfo;
@@ -214,6 +234,10 @@ pub struct Config {
     /// Base instructions override.
     pub base_instructions: Option<String>,
 
+    /// Named layers appended to (or replacing) the base instructions of new
+    /// sessions. Applied by `order`, then name.
+    pub instruction_layers: Vec<InstructionLayer>,
+
     /// Developer instructions override injected as a separate message.
     pub developer_instructions: Option<String>,
 
@@ -328,6 +352,10 @@ pub struct Config {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Maximum number of bytes retained in memory per stream of a shell
+    /// command. Larger (or binary) output is spilled to a file on disk.
+    pub exec_output_max_bytes: usize,
+
     /// Maximum number of agent threads that can be open concurrently.
     pub agent_max_threads: Option<usize>,
 
@@ -350,6 +378,104 @@ pub struct Config {
     /// Settings that govern if and what will be written to `~/.codex/history.jsonl`.
     pub history: History,
 
+    /// Per-task limits on model requests, tool calls, and wall-clock time.
+    pub task_limits: TaskLimits,
+
+    /// When tool calls holding the exclusive tool lock are reported or
+    /// cancelled.
+    pub tool_lock_watchdog: ToolLockWatchdog,
+
+    /// Resource limits on spawned stdio MCP server processes.
+    pub mcp_server_limits: McpServerLimits,
+
+    /// Model prices, keyed by model slug, for the cost estimates of usage
+    /// rollups.
+    pub model_pricing: HashMap<String, ModelPricing>,
+
+    /// Summaries of truncated shell output by a cheap model, when set.
+    pub tool_output_summary: Option<ToolOutputSummaryConfig>,
+
+    /// Benchmarks that guard `Op::BenchmarkedTurn`, when set.
+    pub benchmarks: Option<BenchmarksConfig>,
+
+    /// Commands the `cloud_cli` tool may run beyond read-only ones.
+    pub cloud_cli: CloudCliConfig,
+
+    /// Policy that `Op::ScanLicenses` checks licenses against.
+    pub license_policy: LicensePolicyConfig,
+
+    /// Freshness classes of tools whose results are cached for the session,
+    /// keyed by tool name.
+    pub tool_freshness: HashMap<String, ToolFreshness>,
+
+    /// Whether results of immutable tools are shared with later sessions in
+    /// the same project through the state database.
+    pub shared_tool_result_cache: bool,
+
+    /// Memory bounds for tracking the diff of files edited during a turn.
+    pub turn_diff_limits: TurnDiffLimits,
+
+    /// Limits on the age and total size of stored sessions.
+    pub session_retention: SessionRetention,
+
+    /// Ordering, enablement, and token budgets of the context providers that
+    /// seed the initial context. Providers not listed keep their defaults.
+    pub context_providers: Vec<ContextProviderConfig>,
+
+    /// Prompt A/B experiments, keyed by experiment name.
+    pub experiments: BTreeMap<String, ExperimentConfig>,
+
+    /// Language of user-facing messages such as approval prompts and abort
+    /// messages.
+    pub locale: Locale,
+
+    /// When true, clients receiving raw events also receive semantic
+    /// annotations (`progress`, `needs-attention`, ...) for each event.
+    pub annotate_events: bool,
+
+    /// Channels used to notify the user about approvals, completed turns, and
+    /// failed tasks.
+    pub notifier: NotifierConfig,
+
+    /// Paths and prompt for watch mode. Only set when watch mode was enabled
+    /// by the embedder (`codex exec --watch`).
+    pub watch: Option<WatchConfig>,
+
+    /// When true, sessions edit a detached Git worktree copy of the
+    /// repository, and changes reach the real tree only through
+    /// `Op::ApplyToRealWorkspace`.
+    pub shadow_workspace: bool,
+
+    /// When true, the model is offered no tool that can modify anything:
+    /// shell, `apply_patch`, `js_repl`, sub-agents, dynamic tools, and MCP
+    /// tools not annotated as read-only are left out entirely.
+    pub read_only_session: bool,
+
+    /// When true, every command the user approves is counted in the
+    /// project's suggested allowlist, for review with
+    /// `Op::ReviewSuggestedAllowlist`.
+    pub learn_approved_commands: bool,
+
+    /// Model asked to refine the local risk assessment of commands awaiting
+    /// approval. `None` keeps the assessment local.
+    pub command_risk_model: Option<String>,
+
+    /// When true, `apply_patch` changes are collected into a patch artifact
+    /// instead of being written to the working tree, and shell commands run
+    /// read-only.
+    pub patch_artifact: bool,
+
+    /// Where the patch artifact is written after each recorded change.
+    pub patch_artifact_path: Option<PathBuf>,
+
+    /// How many lines past where a patch hunk was expected its drifted
+    /// context may be re-anchored. `0` turns re-anchoring off.
+    pub apply_patch_fuzz_window: usize,
+
+    /// Filters applied to `read_file` content before the model sees it, per
+    /// glob.
+    pub read_filters: Vec<ReadFilterRule>,
+
     /// When true, session is not persisted on disk. Default to `false`
     pub ephemeral: bool,
 
@@ -399,6 +525,16 @@ pub struct Config {
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
 
+    /// Sampling temperature sent with model requests (Responses API `temperature`).
+    pub model_temperature: Option<f64>,
+
+    /// Nucleus sampling cutoff sent with model requests (Responses API `top_p`).
+    pub model_top_p: Option<f64>,
+
+    /// Sampling seed sent with model requests, for providers that support
+    /// deterministic sampling.
+    pub model_seed: Option<u64>,
+
     /// Base URL for requests to ChatGPT (as opposed to the OpenAI API).
     pub chatgpt_base_url: String,
 
@@ -473,6 +609,9 @@ pub struct Config {
 
     /// OTEL configuration (exporter type, endpoint, headers, etc.).
     pub otel: crate::config::types::OtelConfig,
+
+    /// Proxy and TLS settings for every outbound HTTP client (`[http]`).
+    pub http: codex_client::HttpNetworkConfig,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -1074,6 +1213,11 @@ pub struct ConfigToml {
     /// Token budget applied when storing tool/function outputs in the context manager.
     pub tool_output_token_limit: Option<usize>,
 
+    /// Maximum number of bytes retained in memory per stream of a shell
+    /// command before the head/tail is kept and the rest spilled to disk.
+    /// Default: `1048576` (1 MiB).
+    pub exec_output_max_bytes: Option<usize>,
+
     /// Maximum poll window for background terminal output (`write_stdin`), in milliseconds.
     /// Default: `300000` (5 minutes).
     pub background_terminal_timeout: Option<u64>,
@@ -1098,6 +1242,137 @@ pub struct ConfigToml {
     #[serde(default)]
     pub history: Option<History>,
 
+    /// Per-task limits on model requests, tool calls, and wall-clock time.
+    #[serde(default)]
+    pub task_limits: Option<TaskLimits>,
+
+    /// Report, and optionally cancel, tool calls that keep the other tool
+    /// calls waiting by holding the exclusive tool lock too long.
+    #[serde(default)]
+    pub tool_lock_watchdog: Option<ToolLockWatchdog>,
+
+    /// Memory and CPU limits on the stdio MCP server processes Codex spawns.
+    #[serde(default)]
+    pub mcp_server_limits: Option<McpServerLimits>,
+
+    /// Prices in US dollars per million tokens, keyed by model slug, for the
+    /// cost estimates of usage rollups (`Op::QueryUsage`).
+    #[serde(default)]
+    pub model_pricing: Option<HashMap<String, ModelPricing>>,
+
+    /// Summarize shell output that is over the truncation budget with a
+    /// cheap model and send the summary along with the truncated output.
+    #[serde(default)]
+    pub tool_output_summary: Option<ToolOutputSummaryConfig>,
+
+    /// Benchmarks run before and after the agent's changes by
+    /// `Op::BenchmarkedTurn`, which reports or blocks on regressions.
+    #[serde(default)]
+    pub benchmarks: Option<BenchmarksConfig>,
+
+    /// Settings of the `cloud_cli` tool (feature `cloud_cli`), which runs
+    /// read-only kubectl, aws, and gcloud commands by default.
+    #[serde(default)]
+    pub cloud_cli: Option<CloudCliConfig>,
+
+    /// Allowed and denied licenses for `Op::ScanLicenses`, which inventories
+    /// dependency and source header licenses.
+    #[serde(default)]
+    pub license_policy: Option<LicensePolicyConfig>,
+
+    /// Cache the results of these tools for the session, for as long as
+    /// their freshness class allows. Keyed by tool name as the model sees it,
+    /// e.g. `mcp__weather__forecast`.
+    #[serde(default)]
+    pub tool_freshness: Option<HashMap<String, ToolFreshness>>,
+
+    /// Keep the results of `immutable` tools in the state database and serve
+    /// them to later sessions in the same project. Needs the `sqlite`
+    /// feature. Defaults to `false`.
+    #[serde(default)]
+    pub shared_tool_result_cache: Option<bool>,
+
+    /// Memory bounds for tracking the diff of files edited during a turn.
+    #[serde(default)]
+    pub turn_diff_limits: Option<TurnDiffLimits>,
+
+    /// Limits on the age and total size of stored sessions.
+    #[serde(default)]
+    pub session_retention: Option<SessionRetention>,
+
+    /// Named segments layered onto the model's base instructions, e.g.
+    /// organization policy, project conventions, or personal style.
+    #[serde(default)]
+    pub instruction_layers: Option<BTreeMap<String, InstructionLayerToml>>,
+
+    /// Ordered list of context providers (`agents_md`, `memory`,
+    /// `codebase_map`, `git_status`, `recent_sessions`, or ids registered by an
+    /// embedder) to enable, disable, or budget. Providers not listed keep
+    /// their default placement.
+    #[serde(default)]
+    pub context_providers: Option<Vec<ContextProviderConfig>>,
+
+    /// Prompt A/B experiments, keyed by experiment name. Each session is
+    /// assigned one arm, which can append instructions or replace tool
+    /// descriptions, and telemetry is tagged with the arm.
+    #[serde(default)]
+    pub experiments: Option<BTreeMap<String, ExperimentConfig>>,
+
+    /// Language of user-facing messages such as approval prompts and abort
+    /// messages: `en` (default), `es`, `fr`, `de`, `ja`, or `zh-CN`.
+    pub locale: Option<Locale>,
+
+    /// Attach semantic annotations (`progress`, `completed`,
+    /// `needs-attention`, `destructive-action-pending`, `error`) to events
+    /// forwarded to clients, for screen-reader friendly UIs. Defaults to
+    /// `false`.
+    pub annotate_events: Option<bool>,
+
+    /// Desktop, terminal bell, or webhook notifications for approval
+    /// requests, completed turns, and failed tasks, configured per event
+    /// type.
+    pub notifier: Option<NotifierConfig>,
+
+    /// Paths to watch and the prompt to run when they change, used by
+    /// `codex exec --watch`.
+    pub watch: Option<WatchConfig>,
+
+    /// Edit a copy of the repository instead of the live tree. Defaults to
+    /// `false`.
+    pub shadow_workspace: Option<bool>,
+
+    /// Offer the model only tools that cannot modify anything. Defaults to
+    /// `false`.
+    pub read_only_session: Option<bool>,
+
+    /// Record approved commands as suggested execpolicy rules for the
+    /// project. Defaults to `false`.
+    pub learn_approved_commands: Option<bool>,
+
+    /// Model (for example `gpt-5.1-codex-mini`) that refines the risk
+    /// assessment attached to command approval requests. It may describe a
+    /// command better or raise its risk, never lower it. Unset by default,
+    /// which keeps the assessment local.
+    pub command_risk_model: Option<String>,
+
+    /// Collect file changes into a patch artifact instead of modifying the
+    /// working tree. Defaults to `false`.
+    pub patch_artifact: Option<bool>,
+
+    /// File the patch artifact is written to. Setting it does not by itself
+    /// enable patch artifact mode.
+    pub patch_artifact_path: Option<AbsolutePathBuf>,
+
+    /// How many lines past where a patch hunk was expected its drifted
+    /// context may be re-anchored, reporting where it was applied. Defaults
+    /// to `0`, which turns re-anchoring off.
+    pub apply_patch_fuzz_window: Option<usize>,
+
+    /// Filters applied to file content read with `read_file` before the model
+    /// sees it, e.g. `{ glob = "*.lock", filters = ["collapse_lockfile"] }`.
+    /// Every matching rule contributes its filters, in order.
+    pub read_filters: Option<Vec<ReadFilterRule>>,
+
     /// Directory where Codex writes log files, for example `codex-tui.log`.
     /// Defaults to `$CODEX_HOME/log`.
     pub log_dir: Option<AbsolutePathBuf>,
@@ -1122,6 +1397,13 @@ pub struct ConfigToml {
     pub model_reasoning_summary: Option<ReasoningSummary>,
     /// Optional verbosity control for GPT-5 models (Responses API `text.verbosity`).
     pub model_verbosity: Option<Verbosity>,
+    /// Sampling temperature for model requests. Unset leaves the provider default.
+    pub model_temperature: Option<f64>,
+    /// Nucleus sampling cutoff (`top_p`) for model requests.
+    pub model_top_p: Option<f64>,
+    /// Sampling seed for model requests, honored by providers that support
+    /// deterministic sampling.
+    pub model_seed: Option<u64>,
 
     /// Override to force-enable reasoning summaries for the configured model.
     pub model_supports_reasoning_summaries: Option<bool>,
@@ -1204,6 +1486,9 @@ pub struct ConfigToml {
     /// OTEL configuration.
     pub otel: Option<crate::config::types::OtelConfigToml>,
 
+    /// Proxy and TLS settings for every outbound HTTP client.
+    pub http: Option<crate::config::types::HttpConfigToml>,
+
     /// Windows-specific configuration.
     #[serde(default)]
     pub windows: Option<WindowsToml>,
@@ -1495,6 +1780,13 @@ pub struct ConfigOverrides {
     pub ephemeral: Option<bool>,
     /// Additional directories that should be treated as writable roots for this session.
     pub additional_writable_roots: Vec<PathBuf>,
+    /// Instruction layers supplied by an embedder. They replace config layers
+    /// with the same name.
+    pub instruction_layers: Vec<InstructionLayer>,
+    /// Enables watch mode using the `[watch]` section of config.toml.
+    pub watch: Option<bool>,
+    /// Enables patch artifact mode and writes the artifact to this path.
+    pub patch_artifact_path: Option<PathBuf>,
 }
 
 /// Resolves the OSS provider from CLI override, profile config, or global config.
@@ -1623,6 +1915,9 @@ impl Config {
             tools_web_search_request: override_tools_web_search_request,
             ephemeral,
             additional_writable_roots,
+            instruction_layers: instruction_layer_overrides,
+            watch: watch_override,
+            patch_artifact_path: patch_artifact_path_override,
         } = overrides;
 
         let active_profile_name = config_profile_key
@@ -1693,6 +1988,19 @@ impl Config {
             &resolved_cwd,
             Some(&requirements.sandbox_policy),
         );
+        let patch_artifact =
+            patch_artifact_path_override.is_some() || cfg.patch_artifact.unwrap_or(false);
+        let patch_artifact_path = patch_artifact_path_override
+            .map(|path| resolved_cwd.join(path))
+            .or_else(|| {
+                cfg.patch_artifact_path
+                    .as_ref()
+                    .map(AbsolutePathBuf::to_path_buf)
+            });
+        if patch_artifact {
+            // Changes are only proposed; commands must not write to the tree.
+            sandbox_policy = SandboxPolicy::new_read_only_policy();
+        }
         if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &mut sandbox_policy {
             for path in additional_writable_roots {
                 if !writable_roots.iter().any(|existing| existing == &path) {
@@ -1871,6 +2179,48 @@ impl Config {
         let file_base_instructions =
             Self::try_read_non_empty_file(model_instructions_path, "model instructions file")?;
         let base_instructions = base_instructions.or(file_base_instructions);
+        let mut instruction_layers = Vec::new();
+        for (name, layer) in cfg.instruction_layers.unwrap_or_default() {
+            if instruction_layer_overrides
+                .iter()
+                .any(|override_layer| override_layer.name == name)
+            {
+                continue;
+            }
+            let text = match layer.text {
+                Some(text) => text,
+                None => Self::try_read_non_empty_file(
+                    layer.file.as_ref(),
+                    &format!("instruction layer `{name}` file"),
+                )?
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("instruction layer `{name}` must set `text` or `file`"),
+                    )
+                })?,
+            };
+            instruction_layers.push(InstructionLayer {
+                name,
+                text,
+                mode: layer.mode,
+                order: layer.order,
+                token_budget: layer.token_budget,
+            });
+        }
+        instruction_layers.extend(instruction_layer_overrides);
+        let watch = if watch_override.unwrap_or(false) {
+            let watch = cfg.watch.unwrap_or_default();
+            if watch.paths.is_empty() || watch.prompt.trim().is_empty() {
+                return Err(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "watch mode requires `paths` and `prompt` in the `[watch]` section of config.toml",
+                ));
+            }
+            Some(watch)
+        } else {
+            None
+        };
         let developer_instructions = developer_instructions.or(cfg.developer_instructions);
         let personality = personality
             .or(config_profile.personality)
@@ -2010,6 +2360,7 @@ impl Config {
             notify: cfg.notify,
             user_instructions,
             base_instructions,
+            instruction_layers,
             personality,
             developer_instructions,
             compact_prompt,
@@ -2039,6 +2390,7 @@ impl Config {
                 })
                 .collect(),
             tool_output_token_limit: cfg.tool_output_token_limit,
+            exec_output_max_bytes: cfg.exec_output_max_bytes.unwrap_or(EXEC_OUTPUT_MAX_BYTES),
             agent_max_threads,
             agent_max_depth,
             agent_roles,
@@ -2047,6 +2399,32 @@ impl Config {
             log_dir,
             config_layer_stack,
             history,
+            task_limits: cfg.task_limits.unwrap_or_default(),
+            tool_lock_watchdog: cfg.tool_lock_watchdog.unwrap_or_default(),
+            mcp_server_limits: cfg.mcp_server_limits.unwrap_or_default(),
+            model_pricing: cfg.model_pricing.unwrap_or_default(),
+            tool_output_summary: cfg.tool_output_summary,
+            benchmarks: cfg.benchmarks,
+            cloud_cli: cfg.cloud_cli.unwrap_or_default(),
+            license_policy: cfg.license_policy.unwrap_or_default(),
+            tool_freshness: cfg.tool_freshness.unwrap_or_default(),
+            shared_tool_result_cache: cfg.shared_tool_result_cache.unwrap_or(false),
+            turn_diff_limits: cfg.turn_diff_limits.unwrap_or_default(),
+            session_retention: cfg.session_retention.unwrap_or_default(),
+            context_providers: cfg.context_providers.unwrap_or_default(),
+            experiments: cfg.experiments.unwrap_or_default(),
+            locale: cfg.locale.unwrap_or_default(),
+            annotate_events: cfg.annotate_events.unwrap_or(false),
+            notifier: cfg.notifier.unwrap_or_default(),
+            watch,
+            shadow_workspace: cfg.shadow_workspace.unwrap_or(false),
+            read_only_session: cfg.read_only_session.unwrap_or(false),
+            learn_approved_commands: cfg.learn_approved_commands.unwrap_or(false),
+            command_risk_model: cfg.command_risk_model,
+            patch_artifact,
+            patch_artifact_path,
+            apply_patch_fuzz_window: cfg.apply_patch_fuzz_window.unwrap_or(0),
+            read_filters: cfg.read_filters.unwrap_or_default(),
             ephemeral: ephemeral.unwrap_or_default(),
             file_opener: cfg.file_opener.unwrap_or(UriBasedFileOpener::VsCode),
             codex_linux_sandbox_exe,
@@ -2072,6 +2450,9 @@ impl Config {
             model_supports_reasoning_summaries: cfg.model_supports_reasoning_summaries,
             model_catalog,
             model_verbosity: config_profile.model_verbosity.or(cfg.model_verbosity),
+            model_temperature: config_profile.model_temperature.or(cfg.model_temperature),
+            model_top_p: config_profile.model_top_p.or(cfg.model_top_p),
+            model_seed: config_profile.model_seed.or(cfg.model_seed),
             chatgpt_base_url: config_profile
                 .chatgpt_base_url
                 .or(cfg.chatgpt_base_url)
@@ -2141,6 +2522,7 @@ impl Config {
                     metrics_exporter,
                 }
             },
+            http: cfg.http.map(Into::into).unwrap_or_default(),
         };
         Ok(config)
     }
@@ -2331,9 +2713,12 @@ mod tests {
             },
             enabled: true,
             required: false,
+            lazy: false,
             disabled_reason: None,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
@@ -2350,9 +2735,12 @@ mod tests {
             },
             enabled: true,
             required: false,
+            lazy: false,
             disabled_reason: None,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
@@ -3367,9 +3755,12 @@ profile = "project"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(Duration::from_secs(3)),
                 tool_timeout_sec: Some(Duration::from_secs(5)),
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3524,9 +3915,12 @@ bearer_token = "secret"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3595,9 +3989,12 @@ ZIG_VAR = "3"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3646,9 +4043,12 @@ ZIG_VAR = "3"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3695,9 +4095,12 @@ ZIG_VAR = "3"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3760,9 +4163,12 @@ startup_timeout_sec = 2.0
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3837,9 +4243,12 @@ X-Auth = "DOCS_AUTH"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(Duration::from_secs(2)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3867,9 +4276,12 @@ X-Auth = "DOCS_AUTH"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -3935,9 +4347,12 @@ url = "https://example.com/mcp"
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(2)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -3955,9 +4370,12 @@ url = "https://example.com/mcp"
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: None,
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -4038,9 +4456,12 @@ url = "https://example.com/mcp"
                 },
                 enabled: false,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -4083,9 +4504,12 @@ url = "https://example.com/mcp"
                 },
                 enabled: true,
                 required: true,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -4128,9 +4552,12 @@ url = "https://example.com/mcp"
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: Some(vec!["allowed".to_string()]),
                 disabled_tools: Some(vec!["blocked".to_string()]),
                 scopes: None,
@@ -4537,6 +4964,7 @@ model_verbosity = "high"
             query_params: None,
             http_headers: None,
             env_http_headers: None,
+            request_signing: None,
             request_max_retries: Some(4),
             stream_max_retries: Some(10),
             stream_idle_timeout_ms: Some(300_000),
@@ -4621,6 +5049,7 @@ model_verbosity = "high"
                 project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
                 project_doc_fallback_filenames: Vec::new(),
                 tool_output_token_limit: None,
+                exec_output_max_bytes: EXEC_OUTPUT_MAX_BYTES,
                 agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
                 agent_max_depth: DEFAULT_AGENT_MAX_DEPTH,
                 agent_roles: BTreeMap::new(),
@@ -4630,6 +5059,32 @@ model_verbosity = "high"
                 config_layer_stack: Default::default(),
                 startup_warnings: Vec::new(),
                 history: History::default(),
+                task_limits: TaskLimits::default(),
+                tool_lock_watchdog: ToolLockWatchdog::default(),
+                mcp_server_limits: McpServerLimits::default(),
+                model_pricing: HashMap::new(),
+                tool_output_summary: None,
+                benchmarks: None,
+                cloud_cli: CloudCliConfig::default(),
+                license_policy: LicensePolicyConfig::default(),
+                tool_freshness: HashMap::new(),
+                shared_tool_result_cache: false,
+                turn_diff_limits: TurnDiffLimits::default(),
+                session_retention: SessionRetention::default(),
+                context_providers: Vec::new(),
+                experiments: BTreeMap::new(),
+                locale: Locale::default(),
+                annotate_events: false,
+                notifier: NotifierConfig::default(),
+                watch: None,
+                shadow_workspace: false,
+                read_only_session: false,
+                learn_approved_commands: false,
+                command_risk_model: None,
+                patch_artifact: false,
+                patch_artifact_path: None,
+                apply_patch_fuzz_window: 0,
+                read_filters: Vec::new(),
                 ephemeral: false,
                 file_opener: UriBasedFileOpener::VsCode,
                 codex_linux_sandbox_exe: None,
@@ -4644,11 +5099,15 @@ model_verbosity = "high"
                 model_supports_reasoning_summaries: None,
                 model_catalog: None,
                 model_verbosity: None,
+                model_temperature: None,
+                model_top_p: None,
+                model_seed: None,
                 personality: Some(Personality::Pragmatic),
                 chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
                 experimental_realtime_ws_base_url: None,
                 experimental_realtime_ws_backend_prompt: None,
                 base_instructions: None,
+                instruction_layers: Vec::new(),
                 developer_instructions: None,
                 compact_prompt: None,
                 commit_attribution: None,
@@ -4677,6 +5136,7 @@ model_verbosity = "high"
                 tui_status_line: None,
                 tui_theme: None,
                 otel: OtelConfig::default(),
+                http: codex_client::HttpNetworkConfig::default(),
             },
             o3_profile_config
         );
@@ -4744,6 +5204,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            exec_output_max_bytes: EXEC_OUTPUT_MAX_BYTES,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             agent_max_depth: DEFAULT_AGENT_MAX_DEPTH,
             agent_roles: BTreeMap::new(),
@@ -4753,6 +5214,32 @@ model_verbosity = "high"
             config_layer_stack: Default::default(),
             startup_warnings: Vec::new(),
             history: History::default(),
+            task_limits: TaskLimits::default(),
+            tool_lock_watchdog: ToolLockWatchdog::default(),
+            mcp_server_limits: McpServerLimits::default(),
+            model_pricing: HashMap::new(),
+            tool_output_summary: None,
+            benchmarks: None,
+            cloud_cli: CloudCliConfig::default(),
+            license_policy: LicensePolicyConfig::default(),
+            tool_freshness: HashMap::new(),
+            shared_tool_result_cache: false,
+            turn_diff_limits: TurnDiffLimits::default(),
+            session_retention: SessionRetention::default(),
+            context_providers: Vec::new(),
+            experiments: BTreeMap::new(),
+            locale: Locale::default(),
+            annotate_events: false,
+            notifier: NotifierConfig::default(),
+            watch: None,
+            shadow_workspace: false,
+            read_only_session: false,
+            learn_approved_commands: false,
+            command_risk_model: None,
+            patch_artifact: false,
+            patch_artifact_path: None,
+            apply_patch_fuzz_window: 0,
+            read_filters: Vec::new(),
             ephemeral: false,
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
@@ -4767,11 +5254,15 @@ model_verbosity = "high"
             model_supports_reasoning_summaries: None,
             model_catalog: None,
             model_verbosity: None,
+            model_temperature: None,
+            model_top_p: None,
+            model_seed: None,
             personality: Some(Personality::Pragmatic),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             experimental_realtime_ws_base_url: None,
             experimental_realtime_ws_backend_prompt: None,
             base_instructions: None,
+            instruction_layers: Vec::new(),
             developer_instructions: None,
             compact_prompt: None,
             commit_attribution: None,
@@ -4800,6 +5291,7 @@ model_verbosity = "high"
             tui_status_line: None,
             tui_theme: None,
             otel: OtelConfig::default(),
+            http: codex_client::HttpNetworkConfig::default(),
         };
 
         assert_eq!(expected_gpt3_profile_config, gpt3_profile_config);
@@ -4865,6 +5357,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            exec_output_max_bytes: EXEC_OUTPUT_MAX_BYTES,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             agent_max_depth: DEFAULT_AGENT_MAX_DEPTH,
             agent_roles: BTreeMap::new(),
@@ -4874,6 +5367,32 @@ model_verbosity = "high"
             config_layer_stack: Default::default(),
             startup_warnings: Vec::new(),
             history: History::default(),
+            task_limits: TaskLimits::default(),
+            tool_lock_watchdog: ToolLockWatchdog::default(),
+            mcp_server_limits: McpServerLimits::default(),
+            model_pricing: HashMap::new(),
+            tool_output_summary: None,
+            benchmarks: None,
+            cloud_cli: CloudCliConfig::default(),
+            license_policy: LicensePolicyConfig::default(),
+            tool_freshness: HashMap::new(),
+            shared_tool_result_cache: false,
+            turn_diff_limits: TurnDiffLimits::default(),
+            session_retention: SessionRetention::default(),
+            context_providers: Vec::new(),
+            experiments: BTreeMap::new(),
+            locale: Locale::default(),
+            annotate_events: false,
+            notifier: NotifierConfig::default(),
+            watch: None,
+            shadow_workspace: false,
+            read_only_session: false,
+            learn_approved_commands: false,
+            command_risk_model: None,
+            patch_artifact: false,
+            patch_artifact_path: None,
+            apply_patch_fuzz_window: 0,
+            read_filters: Vec::new(),
             ephemeral: false,
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
@@ -4888,11 +5407,15 @@ model_verbosity = "high"
             model_supports_reasoning_summaries: None,
             model_catalog: None,
             model_verbosity: None,
+            model_temperature: None,
+            model_top_p: None,
+            model_seed: None,
             personality: Some(Personality::Pragmatic),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             experimental_realtime_ws_base_url: None,
             experimental_realtime_ws_backend_prompt: None,
             base_instructions: None,
+            instruction_layers: Vec::new(),
             developer_instructions: None,
             compact_prompt: None,
             commit_attribution: None,
@@ -4921,6 +5444,7 @@ model_verbosity = "high"
             tui_status_line: None,
             tui_theme: None,
             otel: OtelConfig::default(),
+            http: codex_client::HttpNetworkConfig::default(),
         };
 
         assert_eq!(expected_zdr_profile_config, zdr_profile_config);
@@ -4972,6 +5496,7 @@ model_verbosity = "high"
             project_doc_max_bytes: PROJECT_DOC_MAX_BYTES,
             project_doc_fallback_filenames: Vec::new(),
             tool_output_token_limit: None,
+            exec_output_max_bytes: EXEC_OUTPUT_MAX_BYTES,
             agent_max_threads: DEFAULT_AGENT_MAX_THREADS,
             agent_max_depth: DEFAULT_AGENT_MAX_DEPTH,
             agent_roles: BTreeMap::new(),
@@ -4981,6 +5506,32 @@ model_verbosity = "high"
             config_layer_stack: Default::default(),
             startup_warnings: Vec::new(),
             history: History::default(),
+            task_limits: TaskLimits::default(),
+            tool_lock_watchdog: ToolLockWatchdog::default(),
+            mcp_server_limits: McpServerLimits::default(),
+            model_pricing: HashMap::new(),
+            tool_output_summary: None,
+            benchmarks: None,
+            cloud_cli: CloudCliConfig::default(),
+            license_policy: LicensePolicyConfig::default(),
+            tool_freshness: HashMap::new(),
+            shared_tool_result_cache: false,
+            turn_diff_limits: TurnDiffLimits::default(),
+            session_retention: SessionRetention::default(),
+            context_providers: Vec::new(),
+            experiments: BTreeMap::new(),
+            locale: Locale::default(),
+            annotate_events: false,
+            notifier: NotifierConfig::default(),
+            watch: None,
+            shadow_workspace: false,
+            read_only_session: false,
+            learn_approved_commands: false,
+            command_risk_model: None,
+            patch_artifact: false,
+            patch_artifact_path: None,
+            apply_patch_fuzz_window: 0,
+            read_filters: Vec::new(),
             ephemeral: false,
             file_opener: UriBasedFileOpener::VsCode,
             codex_linux_sandbox_exe: None,
@@ -4995,11 +5546,15 @@ model_verbosity = "high"
             model_supports_reasoning_summaries: None,
             model_catalog: None,
             model_verbosity: Some(Verbosity::High),
+            model_temperature: None,
+            model_top_p: None,
+            model_seed: None,
             personality: Some(Personality::Pragmatic),
             chatgpt_base_url: "https://chatgpt.com/backend-api/".to_string(),
             experimental_realtime_ws_base_url: None,
             experimental_realtime_ws_backend_prompt: None,
             base_instructions: None,
+            instruction_layers: Vec::new(),
             developer_instructions: None,
             compact_prompt: None,
             commit_attribution: None,
@@ -5028,6 +5583,7 @@ model_verbosity = "high"
             tui_status_line: None,
             tui_theme: None,
             otel: OtelConfig::default(),
+            http: codex_client::HttpNetworkConfig::default(),
         };
 
         assert_eq!(expected_gpt5_profile_config, gpt5_profile_config);
@@ -4,7 +4,11 @@
 // definitions that do not contain business logic.
 
 use crate::config_loader::RequirementSource;
+use codex_client::HttpDestinationConfig;
+use codex_client::HttpNetworkConfig;
+use codex_client::HttpTlsConfig;
 pub use codex_protocol::config_types::AltScreenMode;
+pub use codex_protocol::config_types::InstructionLayerMode;
 pub use codex_protocol::config_types::ModeKind;
 pub use codex_protocol::config_types::Personality;
 pub use codex_protocol::config_types::WebSearchMode;
@@ -71,6 +75,12 @@ pub struct McpServerConfig {
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub required: bool,
 
+    /// When `true`, Codex starts this MCP server the first time one of its tools is called
+    /// rather than at session start, advertising its tools from the listing cached when it
+    /// last ran. Ignored for required servers.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub lazy: bool,
+
     /// Reason this server was disabled after applying requirements.
     #[serde(skip)]
     pub disabled_reason: Option<McpServerDisabledReason>,
@@ -87,6 +97,21 @@ pub struct McpServerConfig {
     #[serde(default, with = "option_duration_secs")]
     pub tool_timeout_sec: Option<Duration>,
 
+    /// Whether this server may ask the user questions (MCP elicitation). Only
+    /// the built-in apps server may by default; other servers are not told
+    /// that questions are supported unless this is set.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub elicitation: bool,
+
+    /// How long a question this server asks the user (an MCP elicitation) waits for an
+    /// answer before it is cancelled. Defaults to 10 minutes.
+    #[serde(
+        default,
+        with = "option_duration_secs",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub elicitation_timeout_sec: Option<Duration>,
+
     /// Explicit allow-list of tools exposed from this server. When set, only these tools will be registered.
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub enabled_tools: Option<Vec<String>>,
@@ -133,10 +158,17 @@ pub(crate) struct RawMcpServerConfig {
     #[schemars(with = "Option<f64>")]
     pub tool_timeout_sec: Option<Duration>,
     #[serde(default)]
+    pub elicitation: Option<bool>,
+    #[serde(default, with = "option_duration_secs")]
+    #[schemars(with = "Option<f64>")]
+    pub elicitation_timeout_sec: Option<Duration>,
+    #[serde(default)]
     pub enabled: Option<bool>,
     #[serde(default)]
     pub required: Option<bool>,
     #[serde(default)]
+    pub lazy: Option<bool>,
+    #[serde(default)]
     pub enabled_tools: Option<Vec<String>>,
     #[serde(default)]
     pub disabled_tools: Option<Vec<String>>,
@@ -160,8 +192,11 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             (None, None) => None,
         };
         let tool_timeout_sec = raw.tool_timeout_sec;
+        let elicitation = raw.elicitation.unwrap_or_default();
+        let elicitation_timeout_sec = raw.elicitation_timeout_sec;
         let enabled = raw.enabled.unwrap_or_else(default_enabled);
         let required = raw.required.unwrap_or_default();
+        let lazy = raw.lazy.unwrap_or_default();
         let enabled_tools = raw.enabled_tools.clone();
         let disabled_tools = raw.disabled_tools.clone();
         let scopes = raw.scopes.clone();
@@ -215,8 +250,11 @@ impl<'de> Deserialize<'de> for McpServerConfig {
             transport,
             startup_timeout_sec,
             tool_timeout_sec,
+            elicitation,
+            elicitation_timeout_sec,
             enabled,
             required,
+            lazy,
             disabled_reason: None,
             enabled_tools,
             disabled_tools,
@@ -330,6 +368,390 @@ pub struct History {
     pub max_bytes: Option<usize>,
 }
 
+/// A named segment layered onto the model's base instructions (see
+/// `instruction_layers` in config.toml).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct InstructionLayerToml {
+    /// Inline layer text.
+    pub text: Option<String>,
+    /// File to read the layer text from, used when `text` is not set.
+    pub file: Option<AbsolutePathBuf>,
+    /// Whether the layer is appended (default) or replaces the instructions
+    /// assembled before it.
+    #[serde(default)]
+    pub mode: InstructionLayerMode,
+    /// Position among the layers; lower values apply first and ties are
+    /// broken by layer name.
+    #[serde(default)]
+    pub order: i32,
+    /// Maximum number of tokens the layer may contribute.
+    pub token_budget: Option<usize>,
+}
+
+/// An instruction layer ready to apply, from config or an embedder.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InstructionLayer {
+    pub name: String,
+    pub text: String,
+    pub mode: InstructionLayerMode,
+    pub order: i32,
+    pub token_budget: Option<usize>,
+}
+
+/// Placement of one context provider (see `context_providers` in config.toml).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ContextProviderConfig {
+    /// Provider id, e.g. `agents_md`, `memory`, `codebase_map`, `git_status`,
+    /// or `recent_sessions`.
+    pub id: String,
+    /// Whether the provider contributes context. Defaults to `true`.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Maximum number of tokens the provider may contribute.
+    pub token_budget: Option<usize>,
+}
+
+/// A channel user notifications can be delivered through.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationChannel {
+    /// Desktop notification through the platform's notification service.
+    Desktop,
+    /// Terminal bell.
+    Bell,
+    /// JSON POST to `webhook_url`.
+    Webhook,
+}
+
+/// Channels used to notify the user about session events, per event type
+/// (see `[notifier]` in config.toml). Event types without channels are not
+/// notified.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct NotifierConfig {
+    /// A command, patch, or question needs the user's approval or input.
+    #[serde(default)]
+    pub approval_requested: Vec<NotificationChannel>,
+    /// A turn completed.
+    #[serde(default)]
+    pub turn_complete: Vec<NotificationChannel>,
+    /// A task failed with an error.
+    #[serde(default)]
+    pub task_failed: Vec<NotificationChannel>,
+    /// URL the `webhook` channel POSTs notifications to.
+    pub webhook_url: Option<String>,
+}
+
+/// Watch mode (see `[watch]` in config.toml). When enabled with
+/// `codex exec --watch`, changes under `paths` start a new turn with `prompt`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct WatchConfig {
+    /// Files or directories to watch, relative to the session cwd unless
+    /// absolute. Directories are watched recursively.
+    #[serde(default)]
+    pub paths: Vec<PathBuf>,
+    /// Prompt sent when watched files change. `{changed_files}` is replaced
+    /// with the changed paths, one per line; when the placeholder is absent
+    /// the list is appended to the prompt.
+    pub prompt: String,
+    /// How long changes must settle before a run starts. Defaults to 1000 ms.
+    pub debounce_ms: Option<u64>,
+}
+
+/// A transformation applied to file content read with `read_file` before the
+/// model sees it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadFilter {
+    /// Drop comment-only lines, using the comment syntax of the file's
+    /// extension.
+    StripComments,
+    /// Replace a lockfile with its size and, for TOML lockfiles, the list of
+    /// locked packages.
+    CollapseLockfile,
+    /// Replace generated code with its top-level lines.
+    SummarizeGenerated,
+    /// Reformat JSON without insignificant whitespace.
+    MinifyJson,
+}
+
+/// Read filters for files matching a glob (see `read_filters` in
+/// config.toml).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ReadFilterRule {
+    /// Glob matched against the file path relative to the session cwd, e.g.
+    /// `*.lock` or `src/generated/*`.
+    pub glob: String,
+    /// Filters applied in order.
+    pub filters: Vec<ReadFilter>,
+}
+
+/// A prompt A/B experiment (see `experiments` in config.toml). Each session
+/// is assigned one arm, which is recorded in the session metadata and tagged
+/// on telemetry.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ExperimentConfig {
+    /// Pins every new session to this arm. When unset, new sessions pick an
+    /// arm uniformly at random.
+    pub arm: Option<String>,
+    /// Arms of the experiment, keyed by arm name.
+    #[serde(default)]
+    pub arms: BTreeMap<String, ExperimentArmConfig>,
+}
+
+/// Prompt changes applied by one experiment arm. An empty arm is a control.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ExperimentArmConfig {
+    /// Text appended to the base instructions after all instruction layers.
+    pub instructions: Option<String>,
+    /// Replacement descriptions for tools, keyed by tool name.
+    #[serde(default)]
+    pub tool_descriptions: BTreeMap<String, String>,
+}
+
+/// Per-task guardrails. Unset limits are not enforced. They are checked
+/// before each model request, so the tool calls of one request can take the
+/// task past `max_tool_calls`.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TaskLimits {
+    /// Maximum number of model requests (sampling round-trips) per task.
+    pub max_model_requests: Option<u32>,
+    /// Maximum number of tool calls per task.
+    pub max_tool_calls: Option<u32>,
+    /// Maximum number of tokens, input and output summed over the task's
+    /// model requests, per task.
+    pub max_tokens: Option<u64>,
+    /// Maximum wall-clock duration of a task, in seconds.
+    pub max_duration_secs: Option<u64>,
+}
+
+/// Watchdog over tool calls that hold the exclusive tool lock, which keeps
+/// every other tool call waiting.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ToolLockWatchdog {
+    /// Report a call that has held the lock this many seconds. Defaults to
+    /// 300.
+    pub report_after_secs: Option<u64>,
+    /// Cancel a call that has held the lock this many seconds. Calls are not
+    /// cancelled when unset.
+    pub cancel_after_secs: Option<u64>,
+}
+
+/// Resource limits on the stdio MCP server processes Codex spawns, applied
+/// where the platform supports them (`setrlimit` on Unix).
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct McpServerLimits {
+    /// Address space limit of each server process, in megabytes. Runtimes
+    /// that reserve large amounts of virtual memory up front, such as
+    /// Node.js, need a generous limit.
+    pub memory_limit_mb: Option<u64>,
+    /// CPU time limit of each server process, in seconds. A server past it
+    /// is killed.
+    pub cpu_time_limit_secs: Option<u64>,
+}
+
+impl TaskLimits {
+    /// Returns a description of the first limit the task has reached, if any.
+    pub fn reached(
+        &self,
+        model_requests: u32,
+        tool_calls: u32,
+        tokens: u64,
+        elapsed: std::time::Duration,
+    ) -> Option<String> {
+        if let Some(max) = self.max_model_requests
+            && model_requests >= max
+        {
+            return Some(format!(
+                "task stopped after reaching the limit of {max} model requests"
+            ));
+        }
+        if let Some(max) = self.max_tool_calls
+            && tool_calls >= max
+        {
+            return Some(format!(
+                "task stopped after reaching the limit of {max} tool calls"
+            ));
+        }
+        if let Some(max) = self.max_tokens
+            && tokens >= max
+        {
+            return Some(format!(
+                "task stopped after reaching the limit of {max} tokens"
+            ));
+        }
+        if let Some(max) = self.max_duration_secs
+            && elapsed.as_secs() >= max
+        {
+            return Some(format!("task stopped after running for longer than {max}s"));
+        }
+        None
+    }
+}
+
+/// Price of a model in US dollars per million tokens, for the cost estimates
+/// of usage rollups (`Op::QueryUsage`).
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ModelPricing {
+    pub input: f64,
+    /// Price of cached input tokens. Defaults to `input`.
+    pub cached_input: Option<f64>,
+    /// Price of output tokens, reasoning tokens included.
+    pub output: f64,
+}
+
+/// Summaries of shell output that is over the truncation budget, written by
+/// a cheap model and sent to the agent with the truncated output.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct ToolOutputSummaryConfig {
+    /// Model that writes the summaries, served by the session's provider.
+    pub model: String,
+    /// Most tokens of an output sent to the model; longer outputs are
+    /// truncated first. Defaults to 32000.
+    pub max_input_tokens: Option<u64>,
+    /// Tokens the summaries of one session may use in all, output included.
+    /// Defaults to 200000.
+    pub session_token_budget: Option<u64>,
+}
+
+/// Benchmarks that guard `Op::BenchmarkedTurn`: each runs against a snapshot
+/// of the workspace from before the turn and against the workspace after it,
+/// and a statistically significant slowdown is reported or sent back to the
+/// agent to fix.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BenchmarksConfig {
+    pub commands: Vec<BenchmarkCommand>,
+    /// Timed runs of each benchmark on each side, after one warm-up run.
+    /// Defaults to 5.
+    pub runs: Option<u32>,
+    /// Largest slowdown, in percent of the baseline mean, that is not a
+    /// regression. Defaults to 5.
+    pub max_regression_percent: Option<f64>,
+    /// What a regression does. Defaults to `report`.
+    pub on_regression: Option<BenchmarkRegressionAction>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct BenchmarkCommand {
+    pub name: String,
+    /// Program and arguments, run in the workspace directory.
+    pub command: Vec<String>,
+    /// Regex whose first capture group is the benchmark's metric in the
+    /// command's stdout, e.g. `time: ([0-9.]+) ms`. The wall time of the
+    /// command, in milliseconds, when unset.
+    pub metric_pattern: Option<String>,
+    /// Whether a larger metric is better, as for throughput. Defaults to
+    /// false.
+    pub higher_is_better: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BenchmarkRegressionAction {
+    /// Finish the turn and warn about the regressions.
+    #[default]
+    Report,
+    /// Send the regressions back to the agent and keep the turn going until
+    /// they are fixed, for at most two more attempts.
+    Block,
+}
+
+/// Settings of the `cloud_cli` tool, which runs read-only kubectl, aws, and
+/// gcloud commands unless a command is allowed here.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct CloudCliConfig {
+    /// Commands beyond the read-only ones that the tool may run, as their
+    /// leading words, e.g. `kubectl rollout restart` or `aws ecs
+    /// update-service`. Each run still asks for approval unless
+    /// `approval_policy` is `never`.
+    #[serde(default)]
+    pub allowed_commands: Vec<String>,
+}
+
+/// License policy that `Op::ScanLicenses` checks dependency and source file
+/// licenses against. License ids are SPDX identifiers, compared
+/// case-insensitively.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct LicensePolicyConfig {
+    /// When non-empty, the only licenses allowed. A dual-licensed package
+    /// (`MIT OR GPL-3.0-only`) passes when one of its choices is allowed.
+    #[serde(default)]
+    pub allowed: Vec<String>,
+    /// Licenses that are never allowed, e.g. `AGPL-3.0-only`.
+    #[serde(default)]
+    pub denied: Vec<String>,
+    /// Report dependencies without a declared license as violations.
+    #[serde(default)]
+    pub deny_unknown: bool,
+    /// Packages the policy does not apply to, e.g. internal ones.
+    #[serde(default)]
+    pub exempt_packages: Vec<String>,
+}
+
+/// How quickly the results of a tool go stale, which sets how long they are
+/// cached for the session (see `tool_freshness` in config.toml).
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolFreshness {
+    /// Data that changes within minutes, e.g. weather, prices, or scores.
+    /// Cached for 5 minutes.
+    Volatile,
+    /// Data that changes within days. Cached for an hour.
+    Stable,
+    /// Data that never changes, e.g. a released version's documentation.
+    /// Cached for the whole session.
+    Immutable,
+}
+
+/// Retention policy for stored session rollouts. When a limit is set,
+/// sessions are pruned at startup; `Op::PruneSessions` prunes on demand.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct SessionRetention {
+    /// Delete sessions last written more than this many days ago.
+    pub max_age_days: Option<u64>,
+    /// Delete the oldest sessions once the stored sessions together exceed
+    /// this many bytes.
+    pub max_total_bytes: Option<u64>,
+    /// Thread ids whose sessions are never deleted.
+    #[serde(default)]
+    pub pinned_threads: Vec<String>,
+}
+
+impl SessionRetention {
+    pub fn is_enabled(&self) -> bool {
+        self.max_age_days.is_some() || self.max_total_bytes.is_some()
+    }
+}
+
+/// Memory bounds for the per-turn diff of edited files. Unset values use the
+/// built-in defaults.
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct TurnDiffLimits {
+    /// Files larger than this many bytes are compared by hash only and are
+    /// not included as text in the turn diff.
+    pub max_file_bytes: Option<u64>,
+    /// Original contents larger than this many bytes are kept in a temporary
+    /// file instead of in memory.
+    pub spill_threshold_bytes: Option<u64>,
+}
+
 #[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Default, JsonSchema)]
 #[serde(rename_all = "kebab-case")]
 pub enum HistoryPersistence {
@@ -521,6 +943,78 @@ pub struct AppsConfigToml {
     pub apps: HashMap<String, AppConfig>,
 }
 
+// ===== HTTP client configuration =====
+
+/// Proxy and TLS settings for every outbound HTTP client: the model
+/// provider, MCP HTTP/SSE servers, connectors, and Codex's own services.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HttpConfigToml {
+    /// Proxy URL for all requests, e.g. `http://proxy.corp.example.com:8080`.
+    pub proxy: Option<String>,
+    /// Hosts reached without the proxy, in addition to the `NO_PROXY`
+    /// environment variable: host names, domains (`example.com` and
+    /// `.example.com` both cover subdomains), IP addresses, CIDR ranges, or
+    /// `*`. A `:port` suffix limits an entry to that port.
+    pub no_proxy: Option<Vec<String>>,
+    /// PEM bundle of certificate authorities to trust in addition to the
+    /// built-in roots.
+    pub ca_certificate: Option<AbsolutePathBuf>,
+    /// PEM client certificate for mutual TLS. Requires `client_private_key`.
+    pub client_certificate: Option<AbsolutePathBuf>,
+    pub client_private_key: Option<AbsolutePathBuf>,
+    /// Overrides for the hosts matching each pattern; the longest matching
+    /// pattern wins.
+    pub destinations: Option<BTreeMap<String, HttpDestinationToml>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct HttpDestinationToml {
+    pub proxy: Option<String>,
+    pub ca_certificate: Option<AbsolutePathBuf>,
+    pub client_certificate: Option<AbsolutePathBuf>,
+    pub client_private_key: Option<AbsolutePathBuf>,
+}
+
+impl From<HttpConfigToml> for HttpNetworkConfig {
+    fn from(toml: HttpConfigToml) -> Self {
+        Self {
+            proxy: toml.proxy,
+            no_proxy: toml.no_proxy.unwrap_or_default(),
+            tls: HttpTlsConfig {
+                ca_certificate: toml.ca_certificate.map(AbsolutePathBuf::into_path_buf),
+                client_certificate: toml.client_certificate.map(AbsolutePathBuf::into_path_buf),
+                client_private_key: toml.client_private_key.map(AbsolutePathBuf::into_path_buf),
+            },
+            destinations: toml
+                .destinations
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(pattern, destination)| {
+                    (
+                        pattern,
+                        HttpDestinationConfig {
+                            proxy: destination.proxy,
+                            tls: HttpTlsConfig {
+                                ca_certificate: destination
+                                    .ca_certificate
+                                    .map(AbsolutePathBuf::into_path_buf),
+                                client_certificate: destination
+                                    .client_certificate
+                                    .map(AbsolutePathBuf::into_path_buf),
+                                client_private_key: destination
+                                    .client_private_key
+                                    .map(AbsolutePathBuf::into_path_buf),
+                            },
+                        },
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
 // ===== OTEL configuration =====
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
@@ -774,7 +1268,7 @@ pub enum ShellEnvironmentPolicyInherit {
 }
 
 /// Policy for building the `env` when spawning a process via either the
-/// `shell` or `local_shell` tool.
+/// `shell` or `local_shell` tool, or launching a stdio MCP server.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default, JsonSchema)]
 #[schemars(deny_unknown_fields)]
 pub struct ShellEnvironmentPolicyToml {
@@ -790,6 +1284,12 @@ pub struct ShellEnvironmentPolicyToml {
     /// List of regular expressions.
     pub include_only: Option<Vec<String>>,
 
+    /// Environment variables whose values are read from the Codex secrets
+    /// store when a process is spawned, keyed by variable name with the
+    /// secret name as the value. A stdio MCP server only receives the ones it
+    /// names in its own `env_vars`.
+    pub secrets: Option<HashMap<String, String>>,
+
     pub experimental_use_profile: Option<bool>,
 }
 
@@ -802,6 +1302,12 @@ pub type EnvironmentVariablePattern = WildMatchPattern<'*', '?'>;
 /// 3. If `exclude` is not empty, filter the map using the provided patterns.
 /// 4. Insert any entries from `r#set` into the map.
 /// 5. If non-empty, filter the map using the `include_only` patterns.
+///
+/// Explicit injections (session-provided values and `secrets`) are applied
+/// after these steps at spawn time and are never filtered out.
+///
+/// Stdio MCP servers are launched with an environment derived the same way,
+/// plus the values set in their own `env`.
 #[derive(Debug, Clone, PartialEq)]
 pub struct ShellEnvironmentPolicy {
     /// Starting point when building the environment.
@@ -820,6 +1326,10 @@ pub struct ShellEnvironmentPolicy {
     /// Environment variable names to retain in the environment.
     pub include_only: Vec<EnvironmentVariablePattern>,
 
+    /// (variable name, secret name) pairs resolved from the Codex secrets
+    /// store when a process is spawned.
+    pub secrets: HashMap<String, String>,
+
     /// If true, the shell profile will be used to run the command.
     pub use_profile: bool,
 }
@@ -842,6 +1352,7 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             .into_iter()
             .map(|s| EnvironmentVariablePattern::new_case_insensitive(&s))
             .collect();
+        let secrets = toml.secrets.unwrap_or_default();
         let use_profile = toml.experimental_use_profile.unwrap_or(false);
 
         Self {
@@ -850,6 +1361,7 @@ impl From<ShellEnvironmentPolicyToml> for ShellEnvironmentPolicy {
             exclude,
             r#set,
             include_only,
+            secrets,
             use_profile,
         }
     }
@@ -863,6 +1375,7 @@ impl Default for ShellEnvironmentPolicy {
             exclude: Vec::new(),
             r#set: HashMap::new(),
             include_only: Vec::new(),
+            secrets: HashMap::new(),
             use_profile: false,
         }
     }
@@ -1155,4 +1668,35 @@ mod tests {
             "unexpected error: {err}"
         );
     }
+
+    #[test]
+    fn task_limits_report_the_first_limit_reached() {
+        let limits: TaskLimits = toml::from_str(
+            r#"
+            max_model_requests = 5
+            max_tool_calls = 10
+            max_tokens = 50000
+        "#,
+        )
+        .expect("should deserialize task limits");
+        let elapsed = std::time::Duration::from_secs(3600);
+
+        assert_eq!(limits.reached(4, 9, 49_999, elapsed), None);
+        assert_eq!(
+            limits.reached(4, 9, 50_000, elapsed),
+            Some("task stopped after reaching the limit of 50000 tokens".to_string())
+        );
+        assert_eq!(
+            limits.reached(4, 10, 50_000, elapsed),
+            Some("task stopped after reaching the limit of 10 tool calls".to_string())
+        );
+        assert_eq!(
+            limits.reached(5, 10, 50_000, elapsed),
+            Some("task stopped after reaching the limit of 5 model requests".to_string())
+        );
+        assert_eq!(
+            TaskLimits::default().reached(u32::MAX, u32::MAX, u64::MAX, elapsed),
+            None
+        );
+    }
 }
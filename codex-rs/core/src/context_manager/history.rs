@@ -1,7 +1,10 @@
+use crate::attachments::is_attached_input_text;
 use crate::codex::TurnContext;
 use crate::context_manager::normalize;
+use crate::git_context::is_git_context_text;
 use crate::instructions::SkillInstructions;
 use crate::instructions::UserInstructions;
+use crate::mcp::split_qualified_tool_name;
 use crate::session_prefix::is_session_prefix;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::approx_token_count;
@@ -9,6 +12,8 @@ use crate::truncate::approx_tokens_from_byte_count_i64;
 use crate::truncate::truncate_function_output_items_with_policy;
 use crate::truncate::truncate_text;
 use crate::user_shell_command::is_user_shell_command_text;
+use chrono::SecondsFormat;
+use chrono::Utc;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputBody;
@@ -16,6 +21,8 @@ use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::openai_models::InputModality;
+use codex_protocol::protocol::ContextItemBreakdown;
+use codex_protocol::protocol::HistoryItemMetadata;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
 use codex_protocol::protocol::TurnContextItem;
@@ -26,6 +33,8 @@ use std::ops::Deref;
 pub(crate) struct ContextManager {
     /// The oldest items are at the beginning of the vector.
     items: Vec<ResponseItem>,
+    /// Sidecar of `items`: the metadata of each item, at the same index.
+    metadata: Vec<HistoryItemMetadata>,
     token_info: Option<TokenUsageInfo>,
     /// Reference context snapshot used for diffing and producing model-visible
     /// settings update items.
@@ -50,6 +59,7 @@ impl ContextManager {
     pub(crate) fn new() -> Self {
         Self {
             items: Vec::new(),
+            metadata: Vec::new(),
             token_info: TokenUsageInfo::new_or_append(&None, &None, None),
             reference_context_item: None,
         }
@@ -86,6 +96,22 @@ impl ContextManager {
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
+        self.record_items_for_model(items, policy, None);
+    }
+
+    /// Like [`Self::record_items`], noting `model` as the model of the turn
+    /// recording the items. Returns the metadata of the recorded items.
+    pub(crate) fn record_items_for_model<I>(
+        &mut self,
+        items: I,
+        policy: TruncationPolicy,
+        model: Option<&str>,
+    ) -> Vec<HistoryItemMetadata>
+    where
+        I: IntoIterator,
+        I::Item: std::ops::Deref<Target = ResponseItem>,
+    {
+        let start = self.metadata.len();
         for item in items {
             let item_ref = item.deref();
             let is_ghost_snapshot = matches!(item_ref, ResponseItem::GhostSnapshot { .. });
@@ -94,7 +120,91 @@ impl ContextManager {
             }
 
             let processed = self.process_item(item_ref, policy);
+            let metadata = self.metadata_for(&processed, model);
             self.items.push(processed);
+            self.metadata.push(metadata);
+        }
+        self.metadata[start..].to_vec()
+    }
+
+    /// Restores the metadata recorded for the last `metadata.len()` items.
+    pub(crate) fn restore_metadata(&mut self, metadata: Vec<HistoryItemMetadata>) {
+        for (slot, metadata) in self
+            .metadata
+            .iter_mut()
+            .rev()
+            .zip(metadata.into_iter().rev())
+        {
+            *slot = metadata;
+        }
+    }
+
+    /// Appends the items of `history` with their metadata.
+    pub(crate) fn extend(&mut self, history: ContextManager) {
+        self.items.extend(history.items);
+        self.metadata.extend(history.metadata);
+    }
+
+    /// Each item with its estimated size and metadata, oldest first.
+    pub(crate) fn breakdown(&self) -> Vec<ContextItemBreakdown> {
+        self.items
+            .iter()
+            .zip(&self.metadata)
+            .map(|(item, metadata)| ContextItemBreakdown {
+                kind: serde_json::to_value(item)
+                    .ok()
+                    .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+                    .unwrap_or_default(),
+                role: match item {
+                    ResponseItem::Message { role, .. } => Some(role.clone()),
+                    _ => None,
+                },
+                estimated_tokens: estimate_item_token_count(item),
+                metadata: metadata.clone(),
+            })
+            .collect()
+    }
+
+    /// Metadata for `item`, recorded now after the current items.
+    fn metadata_for(&self, item: &ResponseItem, model: Option<&str>) -> HistoryItemMetadata {
+        let tool = match item {
+            ResponseItem::FunctionCall { name, .. } | ResponseItem::CustomToolCall { name, .. } => {
+                Some(name.clone())
+            }
+            ResponseItem::LocalShellCall { .. } => Some("local_shell".to_string()),
+            ResponseItem::WebSearchCall { .. } => Some("web_search".to_string()),
+            ResponseItem::FunctionCallOutput { call_id, .. }
+            | ResponseItem::CustomToolCallOutput { call_id, .. } => self
+                .items
+                .iter()
+                .rev()
+                .find_map(|candidate| match candidate {
+                    ResponseItem::FunctionCall {
+                        name,
+                        call_id: existing,
+                        ..
+                    }
+                    | ResponseItem::CustomToolCall {
+                        name,
+                        call_id: existing,
+                        ..
+                    } if existing == call_id => Some(name.clone()),
+                    ResponseItem::LocalShellCall {
+                        call_id: Some(existing),
+                        ..
+                    } if existing == call_id => Some("local_shell".to_string()),
+                    _ => None,
+                }),
+            _ => None,
+        };
+        HistoryItemMetadata {
+            recorded_at: Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true),
+            connector: tool
+                .as_deref()
+                .and_then(split_qualified_tool_name)
+                .map(|(server, _)| server),
+            tool,
+            model: model.map(str::to_string),
         }
     }
 
@@ -146,24 +256,74 @@ impl ContextManager {
             // Remove the oldest item (front of the list). Items are ordered from
             // oldest → newest, so index 0 is the first entry recorded.
             let removed = self.items.remove(0);
+            self.metadata.remove(0);
             // If the removed item participates in a call/output pair, also remove
             // its corresponding counterpart to keep the invariants intact without
             // running a full normalization pass.
-            normalize::remove_corresponding_for(&mut self.items, &removed);
+            if let Some(pos) = normalize::remove_corresponding_for(&mut self.items, &removed) {
+                self.metadata.remove(pos);
+            }
         }
     }
 
     pub(crate) fn remove_last_item(&mut self) -> bool {
         if let Some(removed) = self.items.pop() {
-            normalize::remove_corresponding_for(&mut self.items, &removed);
+            self.metadata.pop();
+            if let Some(pos) = normalize::remove_corresponding_for(&mut self.items, &removed) {
+                self.metadata.remove(pos);
+            }
             true
         } else {
             false
         }
     }
 
-    pub(crate) fn replace(&mut self, items: Vec<ResponseItem>) {
-        self.items = items;
+    /// Replaces the items. Items that were already in history keep their
+    /// metadata; the others are recorded now. User messages that compaction
+    /// carries over keep their IDs.
+    pub(crate) fn replace(&mut self, mut items: Vec<ResponseItem>) {
+        for item in &mut items {
+            if let ResponseItem::Message {
+                id: id @ None,
+                role,
+                content,
+                ..
+            } = item
+                && role == "user"
+            {
+                *id = self.items.iter().find_map(|previous| match previous {
+                    ResponseItem::Message {
+                        id: Some(previous_id),
+                        role: previous_role,
+                        content: previous_content,
+                        ..
+                    } if previous_role == "user" && previous_content == content => {
+                        Some(previous_id.clone())
+                    }
+                    _ => None,
+                });
+            }
+        }
+        let mut previous = std::mem::take(&mut self.items)
+            .into_iter()
+            .zip(std::mem::take(&mut self.metadata))
+            .map(Some)
+            .collect::<Vec<_>>();
+        for item in items {
+            let metadata = previous
+                .iter_mut()
+                .find(|entry| entry.as_ref().is_some_and(|(old, _)| *old == item))
+                .and_then(Option::take)
+                .map_or_else(|| self.metadata_for(&item, None), |(_, metadata)| metadata);
+            self.items.push(item);
+            self.metadata.push(metadata);
+        }
+    }
+
+    /// Replaces tool outputs that repeat an earlier one with a reference to
+    /// it. See [`normalize::dedup_tool_outputs`].
+    pub(crate) fn dedup_tool_outputs(&mut self) {
+        normalize::dedup_tool_outputs(&mut self.items);
     }
 
     /// Replace image content in the last turn if it originated from a tool output.
@@ -229,6 +389,41 @@ impl ContextManager {
         self.replace(snapshot[..cut_idx].to_vec());
     }
 
+    /// Index where the model's last response starts: the assistant messages
+    /// and reasoning at the end of history. This is the length of history
+    /// when it does not end with a response.
+    pub(crate) fn last_response_start(&self) -> usize {
+        self.items
+            .iter()
+            .rposition(|item| match item {
+                ResponseItem::Message { role, .. } => role != "assistant",
+                ResponseItem::Reasoning { .. } => false,
+                _ => true,
+            })
+            .map_or(0, |idx| idx + 1)
+    }
+
+    /// Sets the ID of the last user message, for rollouts that record it on
+    /// the message's `ItemCompleted` event instead of the message itself.
+    pub(crate) fn set_last_user_message_id(&mut self, item_id: String) {
+        if let Some(&idx) = user_message_positions(&self.items).last()
+            && let ResponseItem::Message { id: id @ None, .. } = &mut self.items[idx]
+        {
+            *id = Some(item_id);
+        }
+    }
+
+    /// How many user turns start at or after the user message with `item_id`,
+    /// i.e. the `num_turns` that rolls history back to just before it. `None`
+    /// if no user message in history has that ID.
+    pub(crate) fn user_turns_from(&self, item_id: &str) -> Option<u32> {
+        let user_positions = user_message_positions(&self.items);
+        let index = user_positions.iter().position(|&idx| {
+            matches!(&self.items[idx], ResponseItem::Message { id: Some(id), .. } if id == item_id)
+        })?;
+        u32::try_from(user_positions.len() - index).ok()
+    }
+
     pub(crate) fn update_token_info(
         &mut self,
         usage: &TokenUsage,
@@ -567,7 +762,11 @@ pub(crate) fn is_user_turn_boundary(item: &ResponseItem) -> bool {
     for content_item in content {
         match content_item {
             ContentItem::InputText { text } => {
-                if is_session_prefix(text) || is_user_shell_command_text(text) {
+                if is_session_prefix(text)
+                    || is_user_shell_command_text(text)
+                    || is_git_context_text(text)
+                    || is_attached_input_text(text)
+                {
                     return false;
                 }
             }
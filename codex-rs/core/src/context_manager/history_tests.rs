@@ -383,6 +383,35 @@ fn estimate_token_count_with_base_instructions_uses_provided_text() {
     assert_eq!(long_estimate - short_estimate, expected_delta);
 }
 
+#[test]
+fn dedup_tool_outputs_references_the_first_identical_output() {
+    let file = "fn main() {}\n".repeat(64);
+    let function_output = |call_id: &str, text: &str| ResponseItem::FunctionCallOutput {
+        call_id: call_id.to_string(),
+        output: FunctionCallOutputPayload::from_text(text.to_string()),
+    };
+    let mut history = create_history_with_items(vec![
+        function_output("call-1", &file),
+        function_output("call-2", "ok"),
+        custom_tool_call_output("call-3", &file),
+        function_output("call-4", "ok"),
+        function_output("call-5", &file),
+    ]);
+
+    history.dedup_tool_outputs();
+
+    assert_eq!(
+        history.raw_items(),
+        vec![
+            function_output("call-1", &file),
+            function_output("call-2", "ok"),
+            custom_tool_call_output("call-3", "[same as output of call call-1]"),
+            function_output("call-4", "ok"),
+            function_output("call-5", "[same as output of call call-1]"),
+        ]
+    );
+}
+
 #[test]
 fn remove_first_item_removes_matching_output_for_function_call() {
     let items = vec![
@@ -442,6 +471,92 @@ fn remove_last_item_removes_matching_call_for_output() {
     assert_eq!(h.raw_items(), vec![user_msg("before tool call")]);
 }
 
+#[test]
+fn breakdown_tags_items_with_their_origin() {
+    let mut h = create_history_with_items(vec![user_msg("open the issue")]);
+    h.record_items_for_model(
+        [
+            ResponseItem::FunctionCall {
+                id: None,
+                name: "mcp__github__get_issue".to_string(),
+                arguments: "{}".to_string(),
+                call_id: "call-1".to_string(),
+            },
+            ResponseItem::FunctionCallOutput {
+                call_id: "call-1".to_string(),
+                output: FunctionCallOutputPayload::from_text("issue".to_string()),
+            },
+            user_msg("thanks"),
+        ]
+        .iter(),
+        TruncationPolicy::Tokens(10_000),
+        Some("gpt-5"),
+    );
+    assert!(h.remove_last_item());
+    h.remove_first_item();
+
+    let origins: Vec<_> = h
+        .breakdown()
+        .into_iter()
+        .map(|item| {
+            (
+                item.kind,
+                item.metadata.tool,
+                item.metadata.connector,
+                item.metadata.model,
+            )
+        })
+        .collect();
+    let mcp_origin = |kind: &str| {
+        (
+            kind.to_string(),
+            Some("mcp__github__get_issue".to_string()),
+            Some("github".to_string()),
+            Some("gpt-5".to_string()),
+        )
+    };
+    assert_eq!(
+        origins,
+        vec![
+            mcp_origin("function_call"),
+            mcp_origin("function_call_output")
+        ]
+    );
+}
+
+#[test]
+fn replace_and_restore_keep_item_metadata() {
+    let mut h = ContextManager::new();
+    let recorded = h.record_items_for_model(
+        [
+            user_msg("first"),
+            assistant_msg("answer"),
+            user_msg("second"),
+        ]
+        .iter(),
+        TruncationPolicy::Tokens(10_000),
+        Some("gpt-5"),
+    );
+    let mut restored = recorded[2].clone();
+    restored.recorded_at = "2026-01-02T03:04:05.000Z".to_string();
+    h.restore_metadata(vec![restored.clone()]);
+
+    // Compaction carries the user messages over after a new summary.
+    h.replace(vec![
+        user_msg("summary"),
+        user_msg("first"),
+        user_msg("second"),
+    ]);
+
+    let metadata: Vec<_> = h
+        .breakdown()
+        .into_iter()
+        .map(|item| item.metadata)
+        .collect();
+    assert_eq!(metadata[1..], [recorded[0].clone(), restored]);
+    assert_eq!(metadata[0].model, None);
+}
+
 #[test]
 fn replace_last_turn_images_replaces_tool_output_images() {
     let items = vec![
@@ -559,6 +674,64 @@ fn drop_last_n_user_turns_preserves_prefix() {
     );
 }
 
+#[test]
+fn last_response_start_skips_trailing_assistant_output() {
+    let reasoning = ResponseItem::Reasoning {
+        id: String::new(),
+        summary: Vec::new(),
+        content: None,
+        encrypted_content: None,
+    };
+    let history =
+        create_history_with_items(vec![user_msg("u1"), reasoning.clone(), assistant_msg("a1")]);
+    assert_eq!(history.last_response_start(), 1);
+
+    let history =
+        create_history_with_items(vec![user_msg("u1"), assistant_msg("a1"), user_msg("u2")]);
+    assert_eq!(history.last_response_start(), 3);
+
+    let history = create_history_with_items(vec![reasoning, assistant_msg("a1")]);
+    assert_eq!(history.last_response_start(), 0);
+}
+
+#[test]
+fn user_turns_from_counts_turns_from_the_message_with_the_id() {
+    let user_msg_with_id = |text: &str, id: &str| {
+        let mut item = user_msg(text);
+        if let ResponseItem::Message { id: item_id, .. } = &mut item {
+            *item_id = Some(id.to_string());
+        }
+        item
+    };
+    let history = create_history_with_items(vec![
+        assistant_msg("session prefix item"),
+        user_msg_with_id("u1", "item-1"),
+        assistant_msg("a1"),
+        user_msg_with_id("u2", "item-2"),
+        assistant_msg("a2"),
+        user_msg_with_id("u3", "item-3"),
+    ]);
+
+    assert_eq!(
+        ["item-1", "item-2", "item-3", "missing"].map(|id| history.user_turns_from(id)),
+        [Some(3), Some(2), Some(1), None]
+    );
+}
+
+#[test]
+fn user_message_ids_survive_replacement() {
+    let mut history =
+        create_history_with_items(vec![user_msg("u1"), assistant_msg("a1"), user_msg("u2")]);
+    history.set_last_user_message_id("item-2".to_string());
+
+    history.replace(vec![user_msg("u2"), user_msg("summary")]);
+
+    assert_eq!(
+        ["item-2", "missing"].map(|id| history.user_turns_from(id)),
+        [Some(2), None]
+    );
+}
+
 #[test]
 fn drop_last_n_user_turns_ignores_session_prefix_user_messages() {
     let items = vec![
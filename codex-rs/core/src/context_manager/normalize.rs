@@ -1,4 +1,8 @@
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::hash::DefaultHasher;
+use std::hash::Hash;
+use std::hash::Hasher;
 
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::FunctionCallOutputBody;
@@ -13,6 +17,9 @@ use tracing::info;
 const IMAGE_CONTENT_OMITTED_PLACEHOLDER: &str =
     "image content omitted because you do not support image input";
 
+/// Tool outputs shorter than this are always sent in full.
+const DEDUP_MIN_OUTPUT_BYTES: usize = 512;
+
 pub(crate) fn ensure_call_outputs_present(items: &mut Vec<ResponseItem>) {
     // Collect synthetic outputs to insert immediately after their calls.
     // Store the insertion position (index of call) alongside the item so
@@ -153,7 +160,12 @@ pub(crate) fn remove_orphan_outputs(items: &mut Vec<ResponseItem>) {
     });
 }
 
-pub(crate) fn remove_corresponding_for(items: &mut Vec<ResponseItem>, item: &ResponseItem) {
+/// Removes the counterpart of `item` in a call/output pair, returning the
+/// position it had.
+pub(crate) fn remove_corresponding_for(
+    items: &mut Vec<ResponseItem>,
+    item: &ResponseItem,
+) -> Option<usize> {
     match item {
         ResponseItem::FunctionCall { call_id, .. } => {
             remove_first_matching(items, |i| {
@@ -163,17 +175,21 @@ pub(crate) fn remove_corresponding_for(items: &mut Vec<ResponseItem>, item: &Res
                         call_id: existing, ..
                     } if existing == call_id
                 )
-            });
+            })
         }
         ResponseItem::FunctionCallOutput { call_id, .. } => {
             if let Some(pos) = items.iter().position(|i| {
                 matches!(i, ResponseItem::FunctionCall { call_id: existing, .. } if existing == call_id)
             }) {
                 items.remove(pos);
+                Some(pos)
             } else if let Some(pos) = items.iter().position(|i| {
                 matches!(i, ResponseItem::LocalShellCall { call_id: Some(existing), .. } if existing == call_id)
             }) {
                 items.remove(pos);
+                Some(pos)
+            } else {
+                None
             }
         }
         ResponseItem::CustomToolCall { call_id, .. } => {
@@ -184,13 +200,13 @@ pub(crate) fn remove_corresponding_for(items: &mut Vec<ResponseItem>, item: &Res
                         call_id: existing, ..
                     } if existing == call_id
                 )
-            });
+            })
         }
         ResponseItem::CustomToolCallOutput { call_id, .. } => {
             remove_first_matching(
                 items,
                 |i| matches!(i, ResponseItem::CustomToolCall { call_id: existing, .. } if existing == call_id),
-            );
+            )
         }
         ResponseItem::LocalShellCall {
             call_id: Some(call_id),
@@ -203,19 +219,19 @@ pub(crate) fn remove_corresponding_for(items: &mut Vec<ResponseItem>, item: &Res
                         call_id: existing, ..
                     } if existing == call_id
                 )
-            });
+            })
         }
-        _ => {}
+        _ => None,
     }
 }
 
-fn remove_first_matching<F>(items: &mut Vec<ResponseItem>, predicate: F)
+fn remove_first_matching<F>(items: &mut Vec<ResponseItem>, predicate: F) -> Option<usize>
 where
     F: Fn(&ResponseItem) -> bool,
 {
-    if let Some(pos) = items.iter().position(predicate) {
-        items.remove(pos);
-    }
+    let pos = items.iter().position(predicate)?;
+    items.remove(pos);
+    Some(pos)
 }
 
 /// Strip image content from messages and tool outputs when the model does not support images.
@@ -267,3 +283,55 @@ pub(crate) fn strip_images_when_unsupported(
         }
     }
 }
+
+/// Replace each text tool output that repeats an earlier output in `items` with a reference to
+/// the earlier call, so re-reading an unchanged file does not spend the context window twice.
+/// Outputs are matched by content hash and confirmed by comparing the text.
+pub(crate) fn dedup_tool_outputs(items: &mut [ResponseItem]) {
+    let mut first_by_hash: HashMap<u64, usize> = HashMap::new();
+    for index in 0..items.len() {
+        let Some((_, text)) = tool_output_text(&items[index]) else {
+            continue;
+        };
+        if text.len() < DEDUP_MIN_OUTPUT_BYTES {
+            continue;
+        }
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        let hash = hasher.finish();
+        let marker = match first_by_hash.get(&hash) {
+            Some(&first) => match tool_output_text(&items[first]) {
+                Some((first_call_id, first_text)) if first_text == text => {
+                    format!("[same as output of call {first_call_id}]")
+                }
+                _ => continue,
+            },
+            None => {
+                first_by_hash.insert(hash, index);
+                continue;
+            }
+        };
+        match &mut items[index] {
+            ResponseItem::FunctionCallOutput { output, .. } => {
+                output.body = FunctionCallOutputBody::Text(marker);
+            }
+            ResponseItem::CustomToolCallOutput { output, .. } => *output = marker,
+            _ => {}
+        }
+    }
+}
+
+fn tool_output_text(item: &ResponseItem) -> Option<(&str, &str)> {
+    match item {
+        ResponseItem::FunctionCallOutput {
+            call_id,
+            output:
+                FunctionCallOutputPayload {
+                    body: FunctionCallOutputBody::Text(text),
+                    ..
+                },
+        } => Some((call_id, text)),
+        ResponseItem::CustomToolCallOutput { call_id, output } => Some((call_id, output)),
+        _ => None,
+    }
+}
@@ -0,0 +1,428 @@
+//! Pluggable sources of context seeded into a conversation.
+//!
+//! Whenever the full initial context is (re)built, every enabled
+//! [`ContextProvider`] is asked for a contribution, in order. Built-in
+//! providers cover AGENTS.md instructions, the memory summary, the codebase
+//! map, `git status`, and a summary of recent sessions in the workspace;
+//! embedders can add their own with [`register_context_provider`]. The
+//! `context_providers` config list enables, disables, orders, and budgets
+//! them by id; providers it does not mention keep their default placement.
+//!
+//! Developer contributions are seeded right after the session's developer
+//! instructions, ahead of collaboration-mode and personality guidance;
+//! user-instruction contributions take the place AGENTS.md instructions have
+//! always had, just before the environment context.
+
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::RwLock;
+
+use async_trait::async_trait;
+use codex_protocol::models::DeveloperInstructions;
+use codex_protocol::models::ResponseItem;
+use tracing::warn;
+
+use crate::config::Config;
+use crate::config::types::ContextProviderConfig;
+use crate::features::Feature;
+use crate::features::Features;
+use crate::git_info::status_short;
+use crate::instructions::UserInstructions;
+use crate::memories::codebase_map::build_codebase_map_developer_instructions;
+use crate::memories::prompts::build_memory_tool_developer_instructions;
+use crate::rollout::INTERACTIVE_SESSION_SOURCES;
+use crate::rollout::RolloutRecorder;
+use crate::rollout::list::ThreadSortKey;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+pub const AGENTS_MD_CONTEXT_PROVIDER_ID: &str = "agents_md";
+pub const MEMORY_CONTEXT_PROVIDER_ID: &str = "memory";
+pub const CODEBASE_MAP_CONTEXT_PROVIDER_ID: &str = "codebase_map";
+pub const GIT_STATUS_CONTEXT_PROVIDER_ID: &str = "git_status";
+pub const RECENT_SESSIONS_CONTEXT_PROVIDER_ID: &str = "recent_sessions";
+
+/// Number of recent sessions in the same workspace listed by the
+/// `recent_sessions` provider.
+const RECENT_SESSIONS_LIMIT: usize = 5;
+/// Threads scanned to find recent sessions in the same workspace.
+const RECENT_SESSIONS_SCAN_LIMIT: usize = 50;
+const RECENT_SESSION_PREVIEW_CHARS: usize = 200;
+
+/// Inputs available to a [`ContextProvider`].
+pub struct ContextRequest<'a> {
+    pub config: &'a Config,
+    pub cwd: &'a Path,
+    pub features: &'a Features,
+    /// Instructions loaded from AGENTS.md files (and the user's global
+    /// instructions), if any.
+    pub user_instructions: Option<&'a str>,
+}
+
+/// A piece of context supplied by a [`ContextProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProvidedContext {
+    /// Added to the conversation as a developer message.
+    Developer(String),
+    /// Added the same way as AGENTS.md instructions for the current directory.
+    UserInstructions(String),
+}
+
+impl ProvidedContext {
+    fn truncated(self, token_budget: usize) -> Self {
+        let policy = TruncationPolicy::Tokens(token_budget);
+        match self {
+            Self::Developer(text) => Self::Developer(truncate_text(&text, policy)),
+            Self::UserInstructions(text) => Self::UserInstructions(truncate_text(&text, policy)),
+        }
+    }
+
+    fn into_response_item(self, cwd: &Path) -> ResponseItem {
+        match self {
+            Self::Developer(text) => DeveloperInstructions::new(text).into(),
+            Self::UserInstructions(text) => UserInstructions {
+                text,
+                directory: cwd.to_string_lossy().into_owned(),
+            }
+            .into(),
+        }
+    }
+}
+
+#[async_trait]
+pub trait ContextProvider: Send + Sync {
+    /// Stable id used to reference the provider from config.
+    fn id(&self) -> &str;
+
+    /// Token budget applied when config does not set one. `None` means the
+    /// contribution is not truncated.
+    fn default_token_budget(&self) -> Option<usize> {
+        None
+    }
+
+    async fn provide(&self, request: &ContextRequest<'_>) -> Option<ProvidedContext>;
+}
+
+static REGISTERED_CONTEXT_PROVIDERS: LazyLock<RwLock<Vec<Arc<dyn ContextProvider>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Registers an additional provider for every session in this process.
+///
+/// Registered providers run after the built-in ones unless the
+/// `context_providers` config places them explicitly. Registering a provider
+/// with the id of an existing one replaces it.
+pub fn register_context_provider(provider: Arc<dyn ContextProvider>) {
+    let mut providers = REGISTERED_CONTEXT_PROVIDERS
+        .write()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    providers.retain(|existing| existing.id() != provider.id());
+    providers.push(provider);
+}
+
+/// Default ordering and enablement of the built-in providers. Matches the
+/// context Codex seeded before providers were configurable.
+fn default_provider_configs() -> Vec<ContextProviderConfig> {
+    [
+        (MEMORY_CONTEXT_PROVIDER_ID, true),
+        (CODEBASE_MAP_CONTEXT_PROVIDER_ID, true),
+        (AGENTS_MD_CONTEXT_PROVIDER_ID, true),
+        (GIT_STATUS_CONTEXT_PROVIDER_ID, false),
+        (RECENT_SESSIONS_CONTEXT_PROVIDER_ID, false),
+    ]
+    .into_iter()
+    .map(|(id, enabled)| ContextProviderConfig {
+        id: id.to_string(),
+        enabled,
+        token_budget: None,
+    })
+    .collect()
+}
+
+fn available_providers() -> Vec<Arc<dyn ContextProvider>> {
+    let mut providers: Vec<Arc<dyn ContextProvider>> = vec![
+        Arc::new(MemoryContextProvider),
+        Arc::new(CodebaseMapContextProvider),
+        Arc::new(AgentsMdContextProvider),
+        Arc::new(GitStatusContextProvider),
+        Arc::new(RecentSessionsContextProvider),
+    ];
+    let registered = REGISTERED_CONTEXT_PROVIDERS
+        .read()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    for provider in registered.iter() {
+        providers.retain(|existing| existing.id() != provider.id());
+        providers.push(Arc::clone(provider));
+    }
+    providers
+}
+
+/// Resolves which providers run and in what order: the configured entries
+/// first, then the built-in defaults the config does not mention, then any
+/// other available provider (i.e. ones registered by an embedder).
+fn resolve_providers(
+    configured: &[ContextProviderConfig],
+    available: Vec<Arc<dyn ContextProvider>>,
+) -> Vec<(Arc<dyn ContextProvider>, Option<usize>)> {
+    let mut entries = configured.to_vec();
+    for default in default_provider_configs() {
+        if !entries.iter().any(|entry| entry.id == default.id) {
+            entries.push(default);
+        }
+    }
+    for provider in &available {
+        if !entries.iter().any(|entry| entry.id == provider.id()) {
+            entries.push(ContextProviderConfig {
+                id: provider.id().to_string(),
+                enabled: true,
+                token_budget: None,
+            });
+        }
+    }
+
+    let mut resolved = Vec::new();
+    for entry in entries {
+        if !entry.enabled {
+            continue;
+        }
+        match available.iter().find(|provider| provider.id() == entry.id) {
+            Some(provider) => resolved.push((
+                Arc::clone(provider),
+                entry.token_budget.or(provider.default_token_budget()),
+            )),
+            None => warn!("unknown context provider `{}`", entry.id),
+        }
+    }
+    resolved
+}
+
+/// Contributions of the enabled providers, grouped by where they are seeded.
+#[derive(Debug, Default)]
+pub(crate) struct ProvidedContextItems {
+    pub(crate) developer: Vec<ResponseItem>,
+    pub(crate) user_instructions: Vec<ResponseItem>,
+}
+
+/// Collects the contributions of all enabled providers, in order.
+pub(crate) async fn provide_context_items(request: &ContextRequest<'_>) -> ProvidedContextItems {
+    let providers = resolve_providers(&request.config.context_providers, available_providers());
+    let mut items = ProvidedContextItems::default();
+    for (provider, token_budget) in providers {
+        let Some(context) = provider.provide(request).await else {
+            continue;
+        };
+        let context = match token_budget {
+            Some(budget) => context.truncated(budget),
+            None => context,
+        };
+        let group = match context {
+            ProvidedContext::Developer(_) => &mut items.developer,
+            ProvidedContext::UserInstructions(_) => &mut items.user_instructions,
+        };
+        group.push(context.into_response_item(request.cwd));
+    }
+    items
+}
+
+struct AgentsMdContextProvider;
+
+#[async_trait]
+impl ContextProvider for AgentsMdContextProvider {
+    fn id(&self) -> &str {
+        AGENTS_MD_CONTEXT_PROVIDER_ID
+    }
+
+    async fn provide(&self, request: &ContextRequest<'_>) -> Option<ProvidedContext> {
+        request
+            .user_instructions
+            .map(|text| ProvidedContext::UserInstructions(text.to_string()))
+    }
+}
+
+struct MemoryContextProvider;
+
+#[async_trait]
+impl ContextProvider for MemoryContextProvider {
+    fn id(&self) -> &str {
+        MEMORY_CONTEXT_PROVIDER_ID
+    }
+
+    async fn provide(&self, request: &ContextRequest<'_>) -> Option<ProvidedContext> {
+        if !request.features.enabled(Feature::MemoryTool) {
+            return None;
+        }
+        build_memory_tool_developer_instructions(&request.config.codex_home)
+            .await
+            .map(ProvidedContext::Developer)
+    }
+}
+
+struct CodebaseMapContextProvider;
+
+#[async_trait]
+impl ContextProvider for CodebaseMapContextProvider {
+    fn id(&self) -> &str {
+        CODEBASE_MAP_CONTEXT_PROVIDER_ID
+    }
+
+    async fn provide(&self, request: &ContextRequest<'_>) -> Option<ProvidedContext> {
+        build_codebase_map_developer_instructions(&request.config.codex_home, request.cwd)
+            .await
+            .map(ProvidedContext::Developer)
+    }
+}
+
+struct GitStatusContextProvider;
+
+#[async_trait]
+impl ContextProvider for GitStatusContextProvider {
+    fn id(&self) -> &str {
+        GIT_STATUS_CONTEXT_PROVIDER_ID
+    }
+
+    fn default_token_budget(&self) -> Option<usize> {
+        Some(1_000)
+    }
+
+    async fn provide(&self, request: &ContextRequest<'_>) -> Option<ProvidedContext> {
+        let status = status_short(request.cwd).await?;
+        Some(ProvidedContext::Developer(format!(
+            "`git status --short --branch` in the workspace when this context was captured:\n```\n{status}\n```"
+        )))
+    }
+}
+
+struct RecentSessionsContextProvider;
+
+#[async_trait]
+impl ContextProvider for RecentSessionsContextProvider {
+    fn id(&self) -> &str {
+        RECENT_SESSIONS_CONTEXT_PROVIDER_ID
+    }
+
+    fn default_token_budget(&self) -> Option<usize> {
+        Some(1_000)
+    }
+
+    async fn provide(&self, request: &ContextRequest<'_>) -> Option<ProvidedContext> {
+        let page = RolloutRecorder::list_threads(
+            request.config,
+            RECENT_SESSIONS_SCAN_LIMIT,
+            None,
+            ThreadSortKey::UpdatedAt,
+            INTERACTIVE_SESSION_SOURCES,
+            None,
+            &request.config.model_provider_id,
+        )
+        .await
+        .ok()?;
+        let lines: Vec<String> = page
+            .items
+            .into_iter()
+            .filter(|item| item.cwd.as_deref() == Some(request.cwd))
+            .filter_map(|item| {
+                let message = item.first_user_message?;
+                let preview: String = message
+                    .split_whitespace()
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .chars()
+                    .take(RECENT_SESSION_PREVIEW_CHARS)
+                    .collect();
+                let when = item.updated_at.or(item.created_at).unwrap_or_default();
+                Some(format!("- {when}: {preview}"))
+            })
+            .take(RECENT_SESSIONS_LIMIT)
+            .collect();
+        if lines.is_empty() {
+            return None;
+        }
+        Some(ProvidedContext::Developer(format!(
+            "Recent sessions in this workspace (newest first), by their first request:\n{}",
+            lines.join("\n")
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct StaticProvider(&'static str);
+
+    #[async_trait]
+    impl ContextProvider for StaticProvider {
+        fn id(&self) -> &str {
+            self.0
+        }
+
+        async fn provide(&self, _request: &ContextRequest<'_>) -> Option<ProvidedContext> {
+            Some(ProvidedContext::Developer(self.0.to_string()))
+        }
+    }
+
+    fn available() -> Vec<Arc<dyn ContextProvider>> {
+        vec![
+            Arc::new(StaticProvider(MEMORY_CONTEXT_PROVIDER_ID)),
+            Arc::new(StaticProvider(AGENTS_MD_CONTEXT_PROVIDER_ID)),
+            Arc::new(StaticProvider(GIT_STATUS_CONTEXT_PROVIDER_ID)),
+            Arc::new(StaticProvider("embedder")),
+        ]
+    }
+
+    fn resolved_ids(
+        resolved: &[(Arc<dyn ContextProvider>, Option<usize>)],
+    ) -> Vec<(&str, Option<usize>)> {
+        resolved
+            .iter()
+            .map(|(provider, budget)| (provider.id(), *budget))
+            .collect()
+    }
+
+    #[test]
+    fn defaults_keep_builtin_order_and_append_registered_providers() {
+        let resolved = resolve_providers(&[], available());
+
+        assert_eq!(
+            resolved_ids(&resolved),
+            vec![
+                (MEMORY_CONTEXT_PROVIDER_ID, None),
+                (AGENTS_MD_CONTEXT_PROVIDER_ID, None),
+                ("embedder", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn config_orders_disables_and_budgets_providers() {
+        let configured = [
+            ContextProviderConfig {
+                id: GIT_STATUS_CONTEXT_PROVIDER_ID.to_string(),
+                enabled: true,
+                token_budget: Some(200),
+            },
+            ContextProviderConfig {
+                id: AGENTS_MD_CONTEXT_PROVIDER_ID.to_string(),
+                enabled: true,
+                token_budget: None,
+            },
+            ContextProviderConfig {
+                id: "embedder".to_string(),
+                enabled: false,
+                token_budget: None,
+            },
+        ];
+
+        let resolved = resolve_providers(&configured, available());
+
+        assert_eq!(
+            resolved_ids(&resolved),
+            vec![
+                (GIT_STATUS_CONTEXT_PROVIDER_ID, Some(200)),
+                (AGENTS_MD_CONTEXT_PROVIDER_ID, None),
+                (MEMORY_CONTEXT_PROVIDER_ID, None),
+            ]
+        );
+    }
+}
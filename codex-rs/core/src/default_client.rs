@@ -2,11 +2,15 @@ use crate::config_loader::ResidencyRequirement;
 use crate::spawn::CODEX_SANDBOX_ENV_VAR;
 use codex_client::CodexHttpClient;
 pub use codex_client::CodexRequestBuilder;
+use codex_client::HttpNetworkConfig;
+use codex_client::HttpNetworkConfigError;
+use codex_client::apply_http_network_config;
 use reqwest::header::HeaderMap;
 use reqwest::header::HeaderValue;
 use std::sync::LazyLock;
 use std::sync::Mutex;
 use std::sync::RwLock;
+use std::time::Duration;
 
 /// Set this to add a suffix to the User-Agent string.
 ///
@@ -27,6 +31,10 @@ pub static USER_AGENT_SUFFIX: LazyLock<Mutex<Option<String>>> = LazyLock::new(||
 pub const DEFAULT_ORIGINATOR: &str = "codex_cli_rs";
 pub const CODEX_INTERNAL_ORIGINATOR_OVERRIDE_ENV_VAR: &str = "CODEX_INTERNAL_ORIGINATOR_OVERRIDE";
 pub const RESIDENCY_HEADER_NAME: &str = "x-openai-internal-codex-residency";
+/// How long an idle pooled connection is kept before it is closed.
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(300);
+/// Interval of the TCP and HTTP/2 keepalive probes on pooled connections.
+const POOL_KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30);
 
 #[derive(Debug, Clone)]
 pub struct Originator {
@@ -87,6 +95,14 @@ pub fn set_default_client_residency_requirement(enforce_residency: Option<Reside
     *guard = enforce_residency;
 }
 
+/// Installs the `[http]` proxy and TLS settings for every HTTP client built
+/// from now on, in this crate and in the MCP client.
+pub fn set_default_client_http_network_config(
+    config: &HttpNetworkConfig,
+) -> Result<(), HttpNetworkConfigError> {
+    codex_client::set_http_network_config(config)
+}
+
 pub fn originator() -> Originator {
     if let Ok(guard) = ORIGINATOR.read()
         && let Some(originator) = guard.as_ref()
@@ -183,17 +199,63 @@ pub fn create_client() -> CodexHttpClient {
 }
 
 pub fn build_reqwest_client() -> reqwest::Client {
+    reqwest_client_builder(None)
+        .build()
+        .unwrap_or_else(|_| reqwest::Client::new())
+}
+
+/// Like [`build_reqwest_client`], but meant to be kept and shared for the
+/// lifetime of a session: idle connections stay open and are kept alive
+/// with TCP and HTTP/2 pings, so later requests skip connection setup. The
+/// client only talks to `destination`, whose `[http.destinations]` TLS
+/// settings it uses.
+pub fn build_pooled_reqwest_client(destination: &str) -> reqwest::Client {
+    reqwest_client_builder(Some(destination))
+        .pool_idle_timeout(POOL_IDLE_TIMEOUT)
+        .tcp_keepalive(POOL_KEEPALIVE_INTERVAL)
+        .http2_keep_alive_interval(POOL_KEEPALIVE_INTERVAL)
+        .http2_keep_alive_while_idle(true)
+        .build()
+        .unwrap_or_else(|_| build_reqwest_client())
+}
+
+/// `response`'s body parsed as JSON. A body longer than `max_bytes` is
+/// refused instead of being buffered whole.
+pub(crate) async fn read_json_limited<T: serde::de::DeserializeOwned>(
+    mut response: reqwest::Response,
+    max_bytes: usize,
+) -> anyhow::Result<T> {
+    let url = response.url().clone();
+    let too_large = || anyhow::anyhow!("the response from {url} is larger than {max_bytes} bytes");
+    if response
+        .content_length()
+        .is_some_and(|len| len > max_bytes as u64)
+    {
+        return Err(too_large());
+    }
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > max_bytes {
+            return Err(too_large());
+        }
+        body.extend_from_slice(&chunk);
+    }
+    serde_json::from_slice(&body).map_err(|err| anyhow::anyhow!("invalid JSON from {url}: {err}"))
+}
+
+fn reqwest_client_builder(destination: Option<&str>) -> reqwest::ClientBuilder {
     let ua = get_codex_user_agent();
 
-    let mut builder = reqwest::Client::builder()
+    let builder = reqwest::Client::builder()
         // Set UA via dedicated helper to avoid header validation pitfalls
         .user_agent(ua)
         .default_headers(default_headers());
+    let mut builder = apply_http_network_config(builder, destination);
     if is_sandboxed() {
         builder = builder.no_proxy();
     }
 
-    builder.build().unwrap_or_else(|_| reqwest::Client::new())
+    builder
 }
 
 pub fn default_headers() -> HeaderMap {
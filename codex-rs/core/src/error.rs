@@ -1,4 +1,5 @@
 use crate::exec::ExecToolCallOutput;
+use crate::function_tool::ToolFailureKind;
 use crate::network_policy_decision::NetworkPolicyDecisionPayload;
 use crate::token_data::KnownPlan;
 use crate::token_data::PlanType;
@@ -12,6 +13,7 @@ use codex_async_utils::CancelErr;
 use codex_protocol::ThreadId;
 use codex_protocol::protocol::CodexErrorInfo;
 use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::ProviderErrorKind;
 use codex_protocol::protocol::RateLimitSnapshot;
 use reqwest::StatusCode;
 use serde_json;
@@ -106,6 +108,10 @@ pub enum CodexErr {
     #[error("{0}")]
     UnexpectedStatus(UnexpectedResponseError),
 
+    /// HTTP error from the model provider that the user can act on.
+    #[error("{0}")]
+    Provider(ProviderError),
+
     /// Invalid request.
     #[error("{0}")]
     InvalidRequest(String),
@@ -120,6 +126,13 @@ pub enum CodexErr {
     #[error("Selected model is at capacity. Please try a different model.")]
     ServerOverloaded,
 
+    /// Returned instead of making a model request while the session is in
+    /// offline mode.
+    #[error(
+        "Codex is offline, so no model request was made. Turns you send are queued until the provider is reachable again."
+    )]
+    Offline,
+
     #[error("{0}")]
     ResponseStreamFailed(ResponseStreamFailed),
 
@@ -213,7 +226,11 @@ impl CodexErr {
             | CodexErr::Spawn
             | CodexErr::SessionConfiguredNotFirstEvent
             | CodexErr::UsageLimitReached(_)
-            | CodexErr::ServerOverloaded => false,
+            | CodexErr::ServerOverloaded
+            | CodexErr::Offline => false,
+            // Rate limits are not retried, like the request retries
+            // (`retry_429: false`); the user gets the remediation instead.
+            CodexErr::Provider(err) => err.kind == ProviderErrorKind::ServerError,
             CodexErr::Stream(..)
             | CodexErr::Timeout
             | CodexErr::UnexpectedStatus(_)
@@ -375,6 +392,46 @@ impl std::fmt::Display for UnexpectedResponseError {
 
 impl std::error::Error for UnexpectedResponseError {}
 
+/// Provider HTTP error classified by what the user can do about it; the
+/// kind decides whether the request is retried.
+#[derive(Debug)]
+pub struct ProviderError {
+    pub kind: ProviderErrorKind,
+    pub response: UnexpectedResponseError,
+    /// Delay the provider asked for with `Retry-After`.
+    pub retry_after: Option<Duration>,
+}
+
+impl ProviderError {
+    /// What the user can do about the error.
+    pub fn remediation(&self) -> String {
+        match self.kind {
+            ProviderErrorKind::Unauthorized => {
+                "Your credentials were rejected. Run `codex login` to sign in again, or check that your API key is valid and not revoked.".to_string()
+            }
+            ProviderErrorKind::OrganizationVerificationRequired => {
+                "Your organization must be verified to use this model. Verify it in your provider's organization settings (for OpenAI, https://platform.openai.com/settings/organization/general), or switch to another model with /model.".to_string()
+            }
+            ProviderErrorKind::RateLimited => match self.retry_after {
+                Some(delay) => format!(
+                    "The provider is rate limiting requests. Try again in {} seconds.",
+                    delay.as_secs().max(1)
+                ),
+                None => "The provider is rate limiting requests. Wait a moment and try again, or reduce how many sessions run at once.".to_string(),
+            },
+            ProviderErrorKind::ServerError => {
+                "The provider is having trouble. Try again shortly, and check its status page if the problem persists.".to_string()
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}. {}", self.response, self.remediation())
+    }
+}
+
 fn truncate_with_ellipsis(text: &str, max_bytes: usize) -> String {
     if text.len() <= max_bytes {
         return text.to_string();
@@ -565,6 +622,52 @@ impl CodexErr {
         (self as &dyn std::any::Any).downcast_ref::<T>()
     }
 
+    /// Classify this error for tool outputs, when it maps onto one of the
+    /// [`ToolFailureKind`]s the model and clients know how to react to.
+    pub fn tool_failure_kind(&self) -> Option<ToolFailureKind> {
+        match self {
+            CodexErr::TurnAborted | CodexErr::Interrupted => Some(ToolFailureKind::UserCancelled),
+            CodexErr::Sandbox(SandboxErr::Timeout { .. }) | CodexErr::Timeout => {
+                Some(ToolFailureKind::Timeout)
+            }
+            CodexErr::Sandbox(SandboxErr::Denied { .. }) => Some(ToolFailureKind::SandboxDenied),
+            CodexErr::ThreadNotFound(_) => Some(ToolFailureKind::NotFound),
+            CodexErr::UsageLimitReached(_)
+            | CodexErr::QuotaExceeded
+            | CodexErr::UsageNotIncluded
+            | CodexErr::ServerOverloaded
+            | CodexErr::AgentLimitReached { .. } => Some(ToolFailureKind::ProviderLimit),
+            CodexErr::Provider(err) if err.kind == ProviderErrorKind::RateLimited => {
+                Some(ToolFailureKind::ProviderLimit)
+            }
+            CodexErr::Provider(_) => None,
+            CodexErr::Sandbox(_)
+            | CodexErr::Stream(..)
+            | CodexErr::ContextWindowExceeded
+            | CodexErr::SessionConfiguredNotFirstEvent
+            | CodexErr::Spawn
+            | CodexErr::UnexpectedStatus(_)
+            | CodexErr::InvalidRequest(_)
+            | CodexErr::InvalidImageRequest()
+            | CodexErr::ResponseStreamFailed(_)
+            | CodexErr::ConnectionFailed(_)
+            | CodexErr::InternalServerError
+            | CodexErr::RetryLimit(_)
+            | CodexErr::InternalAgentDied
+            | CodexErr::LandlockSandboxExecutableNotProvided
+            | CodexErr::UnsupportedOperation(_)
+            | CodexErr::RefreshTokenFailed(_)
+            | CodexErr::Offline
+            | CodexErr::Fatal(_)
+            | CodexErr::Io(_)
+            | CodexErr::Json(_)
+            | CodexErr::TokioJoin(_)
+            | CodexErr::EnvVar(_) => None,
+            #[cfg(target_os = "linux")]
+            CodexErr::LandlockRuleset(_) | CodexErr::LandlockPathFd(_) => None,
+        }
+    }
+
     /// Translate core error to client-facing protocol error.
     pub fn to_codex_protocol_error(&self) -> CodexErrorInfo {
         match self {
@@ -583,6 +686,13 @@ impl CodexErr {
                 http_status_code: self.http_status_code_value(),
             },
             CodexErr::RefreshTokenFailed(_) => CodexErrorInfo::Unauthorized,
+            CodexErr::Offline => CodexErrorInfo::Offline,
+            CodexErr::Provider(err) => CodexErrorInfo::ProviderError {
+                kind: err.kind,
+                http_status_code: self.http_status_code_value(),
+                retry_after_seconds: err.retry_after.map(|delay| delay.as_secs()),
+                remediation: err.remediation(),
+            },
             CodexErr::SessionConfiguredNotFirstEvent
             | CodexErr::InternalServerError
             | CodexErr::InternalAgentDied => CodexErrorInfo::InternalServerError,
@@ -610,6 +720,7 @@ impl CodexErr {
         let http_status_code = match self {
             CodexErr::RetryLimit(err) => Some(err.status),
             CodexErr::UnexpectedStatus(err) => Some(err.status),
+            CodexErr::Provider(err) => Some(err.response.status),
             CodexErr::ConnectionFailed(err) => err.source.status(),
             CodexErr::ResponseStreamFailed(err) => err.source.status(),
             _ => None,
@@ -18,6 +18,8 @@ use codex_protocol::user_input::UserInput;
 use tracing::warn;
 use uuid::Uuid;
 
+use crate::attachments::is_attached_input_text;
+use crate::git_context::is_git_context_text;
 use crate::instructions::SkillInstructions;
 use crate::instructions::UserInstructions;
 use crate::session_prefix::is_session_prefix;
@@ -44,7 +46,11 @@ fn parse_user_message(message: &[ContentItem]) -> Option<UserMessageItem> {
                 {
                     continue;
                 }
-                if is_session_prefix(text) || is_user_shell_command_text(text) {
+                if is_session_prefix(text)
+                    || is_user_shell_command_text(text)
+                    || is_git_context_text(text)
+                    || is_attached_input_text(text)
+                {
                     return None;
                 }
                 content.push(UserInput::Text {
@@ -12,6 +12,7 @@ use std::time::Instant;
 use async_channel::Sender;
 use tokio::io::AsyncRead;
 use tokio::io::AsyncReadExt;
+use tokio::io::AsyncWriteExt;
 use tokio::io::BufReader;
 use tokio::process::Child;
 use tokio_util::sync::CancellationToken;
@@ -33,6 +34,7 @@ use crate::spawn::SpawnChildRequest;
 use crate::spawn::StdioPolicy;
 use crate::spawn::spawn_child_async;
 use crate::text_encoding::bytes_to_string_smart;
+use crate::unified_exec::head_tail_buffer::HeadTailBuffer;
 use codex_network_proxy::NetworkProxy;
 use codex_utils_pty::process_group::kill_child_process_group;
 
@@ -47,13 +49,23 @@ const EXEC_TIMEOUT_EXIT_CODE: i32 = 124; // conventional timeout exit code
 
 // I/O buffer sizing
 const READ_CHUNK_SIZE: usize = 8192; // bytes per read
-const AGGREGATE_BUFFER_INITIAL_CAPACITY: usize = 8 * 1024; // 8 KiB
 
 /// Hard cap on bytes retained from exec stdout/stderr/aggregated output.
 ///
 /// This mirrors unified exec's output cap so a single runaway command cannot
 /// OOM the process by dumping huge amounts of data to stdout/stderr.
-const EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
+pub(crate) const EXEC_OUTPUT_MAX_BYTES: usize = 1024 * 1024; // 1 MiB
+
+/// Directory (under `codex_home`) that receives spilled exec output. It is
+/// private to the user because commands can print secrets.
+const EXEC_OUTPUT_SPILL_SUBDIR: &str = "exec_output";
+
+/// Spill files stay around after their command so the model can page
+/// through them, and are removed once older than this.
+const SPILL_FILE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Number of leading bytes inspected when deciding whether a stream is binary.
+const BINARY_SNIFF_BYTES: usize = 8 * 1024;
 
 /// Limit the number of ExecCommandOutputDelta events emitted per exec call.
 /// Aggregation still collects full output; only the live event stream is capped.
@@ -147,6 +159,36 @@ pub struct StdoutStream {
     pub sub_id: String,
     pub call_id: String,
     pub tx_event: Sender<Event>,
+    pub capture: ExecOutputCapture,
+}
+
+/// Limits applied while capturing a single command's stdout/stderr.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecOutputCapture {
+    /// Maximum number of bytes retained in memory per stream. Once exceeded,
+    /// only the head and tail of the stream are kept.
+    pub max_bytes: usize,
+    /// Directory that receives the complete stream when it is truncated or
+    /// looks binary. `None` disables spill files.
+    pub spill_dir: Option<PathBuf>,
+}
+
+impl Default for ExecOutputCapture {
+    fn default() -> Self {
+        Self {
+            max_bytes: EXEC_OUTPUT_MAX_BYTES,
+            spill_dir: None,
+        }
+    }
+}
+
+impl ExecOutputCapture {
+    pub(crate) fn from_config(config: &crate::config::Config) -> Self {
+        Self {
+            max_bytes: config.exec_output_max_bytes,
+            spill_dir: Some(config.codex_home.join(EXEC_OUTPUT_SPILL_SUBDIR)),
+        }
+    }
 }
 
 pub async fn process_exec_tool_call(
@@ -427,12 +469,14 @@ async fn exec_windows_sandbox(
     let stdout = StreamOutput {
         text: stdout_text,
         truncated_after_lines: None,
+        truncated: None,
     };
     let stderr = StreamOutput {
         text: stderr_text,
         truncated_after_lines: None,
+        truncated: None,
     };
-    let aggregated_output = aggregate_output(&stdout, &stderr);
+    let aggregated_output = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES);
 
     Ok(RawExecToolCallOutput {
         exit_status,
@@ -588,6 +632,19 @@ pub(crate) fn is_likely_sandbox_denied(
 pub struct StreamOutput<T: Clone> {
     pub text: T,
     pub truncated_after_lines: Option<u32>,
+    /// Set when the middle of the stream was dropped to stay within the
+    /// capture limit.
+    pub truncated: Option<StreamTruncation>,
+}
+
+/// Where a captured stream was cut: `text` holds the head of the stream up
+/// to `head_end`, its tail up to `tail_end`, then a truncation notice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamTruncation {
+    /// Length of the whole stream, in bytes.
+    pub total_bytes: usize,
+    pub head_end: usize,
+    pub tail_end: usize,
 }
 
 #[derive(Debug)]
@@ -604,35 +661,158 @@ impl StreamOutput<String> {
         Self {
             text,
             truncated_after_lines: None,
+            truncated: None,
         }
     }
 }
 
 impl StreamOutput<Vec<u8>> {
     pub fn from_utf8_lossy(&self) -> StreamOutput<String> {
+        let Some(truncated) = self.truncated else {
+            return StreamOutput {
+                text: bytes_to_string_smart(&self.text),
+                truncated_after_lines: self.truncated_after_lines,
+                truncated: None,
+            };
+        };
+        // Decoded part by part so the offsets still mark the cut.
+        let head = bytes_to_string_smart(&self.text[..truncated.head_end]);
+        let tail = bytes_to_string_smart(&self.text[truncated.head_end..truncated.tail_end]);
+        let notice = bytes_to_string_smart(&self.text[truncated.tail_end..]);
         StreamOutput {
-            text: bytes_to_string_smart(&self.text),
+            text: format!("{head}{tail}{notice}"),
             truncated_after_lines: self.truncated_after_lines,
+            truncated: Some(StreamTruncation {
+                total_bytes: truncated.total_bytes,
+                head_end: head.len(),
+                tail_end: head.len() + tail.len(),
+            }),
         }
     }
 }
 
-#[inline]
-fn append_capped(dst: &mut Vec<u8>, src: &[u8], max_bytes: usize) {
-    if dst.len() >= max_bytes {
-        return;
+/// A single captured stream plus the bookkeeping needed to explain what was
+/// dropped from it.
+#[derive(Debug)]
+struct CapturedStream {
+    output: StreamOutput<Vec<u8>>,
+    total_bytes: usize,
+    /// Bytes of `output` kept from the start of the stream.
+    head_bytes: usize,
+    binary: bool,
+    spill_path: Option<PathBuf>,
+}
+
+impl CapturedStream {
+    fn empty() -> Self {
+        Self {
+            output: StreamOutput {
+                text: Vec::new(),
+                truncated_after_lines: None,
+                truncated: None,
+            },
+            total_bytes: 0,
+            head_bytes: 0,
+            binary: false,
+            spill_path: None,
+        }
+    }
+
+    /// Replaces binary output with a short notice and appends a pointer to the
+    /// spill file when the retained bytes do not cover the whole stream.
+    fn into_stream_output(self) -> StreamOutput<Vec<u8>> {
+        let CapturedStream {
+            mut output,
+            total_bytes,
+            head_bytes,
+            binary,
+            spill_path,
+        } = self;
+        let pointer = spill_path
+            .map(|path| {
+                format!(
+                    " Full output saved to {} (page through it with a shell command such as `sed -n`).",
+                    path.display()
+                )
+            })
+            .unwrap_or_default();
+        let omitted = total_bytes.saturating_sub(output.text.len());
+        if binary {
+            output.text =
+                format!("[binary output omitted: {total_bytes} bytes.{pointer}]\n").into_bytes();
+        } else if omitted > 0 {
+            output.truncated = Some(StreamTruncation {
+                total_bytes,
+                head_end: head_bytes,
+                tail_end: output.text.len(),
+            });
+            output.text.extend_from_slice(
+                format!(
+                    "\n[output truncated: {omitted} of {total_bytes} bytes omitted from the middle.{pointer}]\n"
+                )
+                .as_bytes(),
+            );
+        }
+        output
+    }
+}
+
+/// Heuristic binary sniffing: a NUL byte in the leading bytes of a stream is a
+/// strong signal that the output is not meant to be read as text.
+fn looks_binary(sample: &[u8]) -> bool {
+    sample[..sample.len().min(BINARY_SNIFF_BYTES)].contains(&0)
+}
+
+async fn create_spill_file(
+    dir: &Path,
+    file_name: &str,
+    buffered: &[u8],
+) -> io::Result<(PathBuf, tokio::fs::File)> {
+    let mut builder = tokio::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    builder.mode(0o700);
+    builder.create(dir).await?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        tokio::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).await?;
+    }
+    if let Ok(mut entries) = tokio::fs::read_dir(dir).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if let Ok(metadata) = entry.metadata().await
+                && metadata.is_file()
+                && metadata
+                    .modified()
+                    .ok()
+                    .and_then(|modified| modified.elapsed().ok())
+                    .is_some_and(|age| age > SPILL_FILE_MAX_AGE)
+                && let Err(err) = tokio::fs::remove_file(entry.path()).await
+            {
+                tracing::warn!(
+                    "failed to remove old exec output spill file {}: {err}",
+                    entry.path().display()
+                );
+            }
+        }
     }
-    let remaining = max_bytes.saturating_sub(dst.len());
-    let take = remaining.min(src.len());
-    dst.extend_from_slice(&src[..take]);
+    let path = dir.join(file_name);
+    // `create_new` refuses to follow a planted symlink or reuse an existing file.
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&path).await?;
+    file.write_all(buffered).await?;
+    Ok((path, file))
 }
 
 fn aggregate_output(
     stdout: &StreamOutput<Vec<u8>>,
     stderr: &StreamOutput<Vec<u8>>,
+    max_bytes: usize,
 ) -> StreamOutput<Vec<u8>> {
     let total_len = stdout.text.len().saturating_add(stderr.text.len());
-    let max_bytes = EXEC_OUTPUT_MAX_BYTES;
     let mut aggregated = Vec::with_capacity(total_len.min(max_bytes));
 
     if total_len <= max_bytes {
@@ -641,6 +821,7 @@ fn aggregate_output(
         return StreamOutput {
             text: aggregated,
             truncated_after_lines: None,
+            truncated: None,
         };
     }
 
@@ -657,6 +838,7 @@ fn aggregate_output(
     StreamOutput {
         text: aggregated,
         truncated_after_lines: None,
+        truncated: None,
     }
 }
 
@@ -801,9 +983,9 @@ async fn consume_truncated_output(
     use tokio::task::JoinHandle;
 
     async fn await_with_timeout(
-        handle: &mut JoinHandle<std::io::Result<StreamOutput<Vec<u8>>>>,
+        handle: &mut JoinHandle<std::io::Result<CapturedStream>>,
         timeout: Duration,
-    ) -> std::io::Result<StreamOutput<Vec<u8>>> {
+    ) -> std::io::Result<CapturedStream> {
         match tokio::time::timeout(timeout, &mut *handle).await {
             Ok(join_res) => match join_res {
                 Ok(io_res) => io_res,
@@ -812,10 +994,7 @@ async fn consume_truncated_output(
             Err(_elapsed) => {
                 // Timeout: abort the task to avoid hanging on open pipes.
                 handle.abort();
-                Ok(StreamOutput {
-                    text: Vec::new(),
-                    truncated_after_lines: None,
-                })
+                Ok(CapturedStream::empty())
             }
         }
     }
@@ -827,13 +1006,18 @@ async fn consume_truncated_output(
         &mut stdout_handle,
         Duration::from_millis(IO_DRAIN_TIMEOUT_MS),
     )
-    .await?;
+    .await?
+    .into_stream_output();
     let stderr = await_with_timeout(
         &mut stderr_handle,
         Duration::from_millis(IO_DRAIN_TIMEOUT_MS),
     )
-    .await?;
-    let aggregated_output = aggregate_output(&stdout, &stderr);
+    .await?
+    .into_stream_output();
+    let max_bytes = stdout_stream
+        .as_ref()
+        .map_or(EXEC_OUTPUT_MAX_BYTES, |stream| stream.capture.max_bytes);
+    let aggregated_output = aggregate_output(&stdout, &stderr, max_bytes);
 
     Ok(RawExecToolCallOutput {
         exit_status,
@@ -844,25 +1028,41 @@ async fn consume_truncated_output(
     })
 }
 
+/// Streams a child pipe to EOF, retaining at most `capture.max_bytes` (head
+/// and tail) in memory. When the stream overflows that budget or looks binary,
+/// the complete stream is written to a spill file so nothing is lost.
 async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
     mut reader: R,
     stream: Option<StdoutStream>,
     is_stderr: bool,
-) -> io::Result<StreamOutput<Vec<u8>>> {
-    let mut buf = Vec::with_capacity(AGGREGATE_BUFFER_INITIAL_CAPACITY.min(EXEC_OUTPUT_MAX_BYTES));
+) -> io::Result<CapturedStream> {
+    let capture = stream
+        .as_ref()
+        .map(|stream| stream.capture.clone())
+        .unwrap_or_default();
+    let mut buf = HeadTailBuffer::new(capture.max_bytes);
     let mut tmp = [0u8; READ_CHUNK_SIZE];
     let mut emitted_deltas: usize = 0;
+    let mut total_bytes: usize = 0;
+    let mut binary = false;
+    // Taken on the first spill attempt so a failing directory is only tried once.
+    let mut spill_dir = capture.spill_dir;
+    let mut spill: Option<(PathBuf, tokio::fs::File)> = None;
 
     loop {
         let n = reader.read(&mut tmp).await?;
         if n == 0 {
             break;
         }
+        let chunk = &tmp[..n];
+        if total_bytes == 0 {
+            binary = looks_binary(chunk);
+        }
 
         if let Some(stream) = &stream
+            && !binary
             && emitted_deltas < MAX_EXEC_OUTPUT_DELTAS_PER_CALL
         {
-            let chunk = tmp[..n].to_vec();
             let msg = EventMsg::ExecCommandOutputDelta(ExecCommandOutputDeltaEvent {
                 call_id: stream.call_id.clone(),
                 stream: if is_stderr {
@@ -870,7 +1070,7 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
                 } else {
                     ExecOutputStream::Stdout
                 },
-                chunk,
+                chunk: chunk.to_vec(),
             });
             let event = Event {
                 id: stream.sub_id.clone(),
@@ -881,13 +1081,64 @@ async fn read_capped<R: AsyncRead + Unpin + Send + 'static>(
             emitted_deltas += 1;
         }
 
-        append_capped(&mut buf, &tmp[..n], EXEC_OUTPUT_MAX_BYTES);
+        let overflows = total_bytes.saturating_add(n) > capture.max_bytes;
+        if (binary || overflows)
+            && spill.is_none()
+            && let Some(dir) = spill_dir.take()
+        {
+            let call_id = stream.as_ref().map_or_else(
+                || uuid::Uuid::new_v4().to_string(),
+                |stream| stream.call_id.clone(),
+            );
+            let stream_name = if is_stderr { "stderr" } else { "stdout" };
+            // A sandbox denial re-runs the command under the same call id, so
+            // each attempt gets its own file.
+            let attempt = uuid::Uuid::new_v4().simple();
+            let file_name = format!("{call_id}.{stream_name}.{attempt}.log");
+            match create_spill_file(&dir, &file_name, &buf.to_bytes()).await {
+                Ok(created) => spill = Some(created),
+                Err(err) => tracing::warn!("failed to create exec output spill file: {err}"),
+            }
+        }
+        if let Some((path, file)) = spill.as_mut()
+            && let Err(err) = file.write_all(chunk).await
+        {
+            tracing::warn!(
+                "failed to write exec output spill file {}: {err}",
+                path.display()
+            );
+            spill = None;
+        }
+
+        buf.push_chunk(chunk.to_vec());
+        total_bytes = total_bytes.saturating_add(n);
         // Continue reading to EOF to avoid back-pressure
     }
 
-    Ok(StreamOutput {
-        text: buf,
-        truncated_after_lines: None,
+    let spill_path = match spill {
+        Some((path, mut file)) => match file.flush().await {
+            Ok(()) => Some(path),
+            Err(err) => {
+                tracing::warn!(
+                    "failed to flush exec output spill file {}: {err}",
+                    path.display()
+                );
+                None
+            }
+        },
+        None => None,
+    };
+
+    Ok(CapturedStream {
+        output: StreamOutput {
+            text: buf.to_bytes(),
+            truncated_after_lines: None,
+            truncated: None,
+        },
+        total_bytes,
+        head_bytes: buf.head_bytes(),
+        binary,
+        spill_path,
     })
 }
 
@@ -1007,7 +1258,94 @@ mod tests {
         });
 
         let out = read_capped(reader, None, false).await.expect("read");
-        assert_eq!(out.text.len(), EXEC_OUTPUT_MAX_BYTES);
+        assert_eq!(out.output.text.len(), EXEC_OUTPUT_MAX_BYTES);
+    }
+
+    #[tokio::test]
+    async fn read_capped_spills_full_output_when_over_cap_and_removes_old_spills() {
+        let spill_dir = tempfile::tempdir().expect("tempdir");
+        let old_spill_path = spill_dir.path().join("call-0.stdout.log");
+        std::fs::File::create(&old_spill_path)
+            .and_then(|file| {
+                file.set_modified(
+                    std::time::SystemTime::now() - SPILL_FILE_MAX_AGE - Duration::from_secs(60),
+                )
+            })
+            .expect("write old spill");
+        let (tx_event, _rx_event) = async_channel::unbounded();
+        let stream = StdoutStream {
+            sub_id: "sub".to_string(),
+            call_id: "call-1".to_string(),
+            tx_event,
+            capture: ExecOutputCapture {
+                max_bytes: 8,
+                spill_dir: Some(spill_dir.path().to_path_buf()),
+            },
+        };
+        let (mut writer, reader) = tokio::io::duplex(1024);
+        tokio::spawn(async move {
+            writer.write_all(b"0123456789abcdef").await.expect("write");
+        });
+
+        let out = read_capped(reader, Some(stream), false)
+            .await
+            .expect("read");
+        let spill_path = out.spill_path.clone().expect("spill path");
+
+        assert_eq!(out.output.text, b"0123cdef".to_vec());
+        assert_eq!(out.total_bytes, 16);
+        assert_eq!(spill_path.parent(), Some(spill_dir.path()));
+        assert!(
+            spill_path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("call-1.stdout."))
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |path: &Path| {
+                std::fs::metadata(path)
+                    .expect("metadata")
+                    .permissions()
+                    .mode()
+            };
+            assert_eq!(mode(&spill_path) & 0o777, 0o600);
+            assert_eq!(mode(spill_dir.path()) & 0o777, 0o700);
+        }
+        assert_eq!(
+            std::fs::read(spill_path).expect("read spill"),
+            b"0123456789abcdef".to_vec()
+        );
+        assert!(!old_spill_path.exists());
+        assert_eq!(
+            out.into_stream_output().truncated,
+            Some(StreamTruncation {
+                total_bytes: 16,
+                head_end: 4,
+                tail_end: 8,
+            })
+        );
+    }
+
+    #[test]
+    fn captured_binary_stream_is_replaced_with_notice() {
+        let captured = CapturedStream {
+            output: StreamOutput {
+                text: vec![0, 159, 146, 150],
+                truncated_after_lines: None,
+                truncated: None,
+            },
+            total_bytes: 4,
+            head_bytes: 4,
+            binary: looks_binary(&[0, 159, 146, 150]),
+            spill_path: None,
+        };
+
+        assert_eq!(
+            String::from_utf8(captured.into_stream_output().text).expect("utf8"),
+            "[binary output omitted: 4 bytes.]\n"
+        );
     }
 
     #[test]
@@ -1015,13 +1353,15 @@ mod tests {
         let stdout = StreamOutput {
             text: vec![b'a'; EXEC_OUTPUT_MAX_BYTES],
             truncated_after_lines: None,
+            truncated: None,
         };
         let stderr = StreamOutput {
             text: vec![b'b'; EXEC_OUTPUT_MAX_BYTES],
             truncated_after_lines: None,
+            truncated: None,
         };
 
-        let aggregated = aggregate_output(&stdout, &stderr);
+        let aggregated = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES);
         let stdout_cap = EXEC_OUTPUT_MAX_BYTES / 3;
         let stderr_cap = EXEC_OUTPUT_MAX_BYTES.saturating_sub(stdout_cap);
 
@@ -1036,13 +1376,15 @@ mod tests {
         let stdout = StreamOutput {
             text: vec![b'a'; stdout_len],
             truncated_after_lines: None,
+            truncated: None,
         };
         let stderr = StreamOutput {
             text: vec![b'b'; EXEC_OUTPUT_MAX_BYTES],
             truncated_after_lines: None,
+            truncated: None,
         };
 
-        let aggregated = aggregate_output(&stdout, &stderr);
+        let aggregated = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES);
         let stderr_cap = EXEC_OUTPUT_MAX_BYTES.saturating_sub(stdout_len);
 
         assert_eq!(aggregated.text.len(), EXEC_OUTPUT_MAX_BYTES);
@@ -1055,13 +1397,15 @@ mod tests {
         let stdout = StreamOutput {
             text: vec![b'a'; EXEC_OUTPUT_MAX_BYTES],
             truncated_after_lines: None,
+            truncated: None,
         };
         let stderr = StreamOutput {
             text: vec![b'b'; 1],
             truncated_after_lines: None,
+            truncated: None,
         };
 
-        let aggregated = aggregate_output(&stdout, &stderr);
+        let aggregated = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES);
         let stdout_len = EXEC_OUTPUT_MAX_BYTES.saturating_sub(1);
 
         assert_eq!(aggregated.text.len(), EXEC_OUTPUT_MAX_BYTES);
@@ -1074,13 +1418,15 @@ mod tests {
         let stdout = StreamOutput {
             text: vec![b'a'; 4],
             truncated_after_lines: None,
+            truncated: None,
         };
         let stderr = StreamOutput {
             text: vec![b'b'; 3],
             truncated_after_lines: None,
+            truncated: None,
         };
 
-        let aggregated = aggregate_output(&stdout, &stderr);
+        let aggregated = aggregate_output(&stdout, &stderr, EXEC_OUTPUT_MAX_BYTES);
         let mut expected = Vec::new();
         expected.extend_from_slice(&stdout.text);
         expected.extend_from_slice(&stderr.text);
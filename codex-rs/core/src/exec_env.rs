@@ -1,9 +1,18 @@
 use crate::config::types::EnvironmentVariablePattern;
+use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerTransportConfig;
 use crate::config::types::ShellEnvironmentPolicy;
 use crate::config::types::ShellEnvironmentPolicyInherit;
 use codex_protocol::ThreadId;
+use codex_secrets::SecretName;
+use codex_secrets::SecretScope;
+use codex_secrets::SecretsBackendKind;
+use codex_secrets::SecretsManager;
+use codex_secrets::environment_id_from_cwd;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
+use tracing::warn;
 
 pub const CODEX_THREAD_ID_ENV_VAR: &str = "CODEX_THREAD_ID";
 
@@ -93,6 +102,126 @@ where
     env_map
 }
 
+/// Applies the explicit injections for a spawned process on top of an
+/// environment built by [`create_env`]: `session_env` (for example skill
+/// env-var dependencies collected during the session), then the secret
+/// references in `policy.secrets`, resolved through `resolve_secret` at spawn
+/// time. Injections bypass the `inherit`/`include_only` filtering because they
+/// were requested explicitly.
+///
+/// Returns every explicitly provided variable, including `policy.set`, so the
+/// caller can re-export them on top of a restored shell snapshot.
+pub(crate) fn apply_env_injections(
+    env: &mut HashMap<String, String>,
+    policy: &ShellEnvironmentPolicy,
+    session_env: HashMap<String, String>,
+    resolve_secret: impl Fn(&str) -> Option<String>,
+) -> HashMap<String, String> {
+    let mut injected = session_env;
+    injected.extend(resolve_secret_env(policy, resolve_secret));
+    env.extend(injected.clone());
+
+    let mut explicit_overrides = policy.r#set.clone();
+    explicit_overrides.extend(injected);
+    explicit_overrides
+}
+
+/// Builds the environment of every stdio MCP server from `policy`, the same
+/// way as for shell commands (see [`create_env`]), so `inherit`, `exclude`,
+/// `set`, and `include_only` apply to MCP launches too. Values configured on
+/// the server itself take precedence.
+///
+/// Secrets are scoped per server: a server only receives the variables in
+/// `policy.secrets` that it names in its own `env_vars`.
+pub(crate) fn apply_env_policy_to_mcp_servers(
+    servers: &mut HashMap<String, McpServerConfig>,
+    policy: &ShellEnvironmentPolicy,
+    resolve_secret: impl Fn(&str) -> Option<String>,
+) {
+    apply_env_policy_to_mcp_servers_with_vars(servers, policy, std::env::vars(), resolve_secret);
+}
+
+fn apply_env_policy_to_mcp_servers_with_vars<I>(
+    servers: &mut HashMap<String, McpServerConfig>,
+    policy: &ShellEnvironmentPolicy,
+    vars: I,
+    resolve_secret: impl Fn(&str) -> Option<String>,
+) where
+    I: IntoIterator<Item = (String, String)>,
+{
+    let base = populate_env(vars, policy, None);
+    for server in servers.values_mut() {
+        match &mut server.transport {
+            McpServerTransportConfig::Stdio { env, env_vars, .. } => {
+                let mut server_env = base.clone();
+                for var in env_vars.iter() {
+                    let Some(secret_name) = policy.secrets.get(var) else {
+                        continue;
+                    };
+                    match resolve_secret(secret_name) {
+                        Some(value) => {
+                            server_env.insert(var.clone(), value);
+                        }
+                        None => {
+                            warn!(
+                                "secret `{secret_name}` for env var {var} was not found; skipping"
+                            );
+                        }
+                    }
+                }
+                server_env.extend(env.take().unwrap_or_default());
+                *env = Some(server_env);
+            }
+            McpServerTransportConfig::StreamableHttp { .. } => {}
+        }
+    }
+}
+
+/// Looks up `secret_name` in the Codex secrets store, preferring the scope of
+/// the environment rooted at `cwd` over the global scope.
+pub(crate) fn resolve_secret_from_store(
+    codex_home: &Path,
+    cwd: &Path,
+    secret_name: &str,
+) -> Option<String> {
+    let name = match SecretName::new(secret_name) {
+        Ok(name) => name,
+        Err(err) => {
+            warn!("invalid secret name `{secret_name}` in shell_environment_policy: {err}");
+            return None;
+        }
+    };
+    let manager = SecretsManager::new(codex_home.to_path_buf(), SecretsBackendKind::Local);
+    let scopes = SecretScope::environment(environment_id_from_cwd(cwd))
+        .into_iter()
+        .chain(std::iter::once(SecretScope::Global));
+    for scope in scopes {
+        match manager.get(&scope, &name) {
+            Ok(Some(value)) => return Some(value),
+            Ok(None) => {}
+            Err(err) => warn!("failed to read secret `{secret_name}`: {err}"),
+        }
+    }
+    None
+}
+
+fn resolve_secret_env(
+    policy: &ShellEnvironmentPolicy,
+    resolve_secret: impl Fn(&str) -> Option<String>,
+) -> HashMap<String, String> {
+    policy
+        .secrets
+        .iter()
+        .filter_map(|(var, secret_name)| {
+            let value = resolve_secret(secret_name);
+            if value.is_none() {
+                warn!("secret `{secret_name}` for env var {var} was not found; skipping");
+            }
+            value.map(|value| (var.clone(), value))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -310,4 +439,118 @@ mod tests {
         expected.insert(CODEX_THREAD_ID_ENV_VAR.to_string(), thread_id.to_string());
         assert_eq!(result, expected);
     }
+    #[test]
+    fn apply_env_injections_adds_session_values_and_secrets() {
+        let mut env = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+        };
+        let mut policy = ShellEnvironmentPolicy::default();
+        policy
+            .r#set
+            .insert("SET_VAR".to_string(), "set".to_string());
+        policy
+            .secrets
+            .insert("API_TOKEN".to_string(), "MY_TOKEN".to_string());
+        policy
+            .secrets
+            .insert("MISSING".to_string(), "NOT_STORED".to_string());
+        let session_env = hashmap! {
+            "DEP_VAR".to_string() => "dep".to_string(),
+        };
+
+        let explicit = apply_env_injections(&mut env, &policy, session_env, |name| {
+            (name == "MY_TOKEN").then(|| "s3cret".to_string())
+        });
+
+        let expected_env: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "DEP_VAR".to_string() => "dep".to_string(),
+            "API_TOKEN".to_string() => "s3cret".to_string(),
+        };
+        let expected_explicit: HashMap<String, String> = hashmap! {
+            "SET_VAR".to_string() => "set".to_string(),
+            "DEP_VAR".to_string() => "dep".to_string(),
+            "API_TOKEN".to_string() => "s3cret".to_string(),
+        };
+        assert_eq!(env, expected_env);
+        assert_eq!(explicit, expected_explicit);
+    }
+
+    fn stdio_server(env: Option<HashMap<String, String>>, env_vars: &[&str]) -> McpServerConfig {
+        McpServerConfig {
+            transport: McpServerTransportConfig::Stdio {
+                command: "docs-server".to_string(),
+                args: Vec::new(),
+                env,
+                env_vars: env_vars.iter().map(ToString::to_string).collect(),
+                cwd: None,
+            },
+            enabled: true,
+            required: false,
+            lazy: false,
+            disabled_reason: None,
+            startup_timeout_sec: None,
+            tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
+            enabled_tools: None,
+            disabled_tools: None,
+            scopes: None,
+        }
+    }
+
+    #[test]
+    fn mcp_server_env_follows_the_policy_and_scopes_secrets_per_server() {
+        let vars = make_vars(&[
+            ("PATH", "/usr/bin"),
+            ("HOME", "/home/user"),
+            ("AWS_PROFILE", "prod"),
+        ]);
+        let mut servers = hashmap! {
+            "docs".to_string() => stdio_server(
+                Some(hashmap! {
+                    "SHARED".to_string() => "server".to_string(),
+                }),
+                &["API_TOKEN"],
+            ),
+            "other".to_string() => stdio_server(None, &[]),
+        };
+        let mut policy = ShellEnvironmentPolicy {
+            inherit: ShellEnvironmentPolicyInherit::All,
+            exclude: vec![EnvironmentVariablePattern::new_case_insensitive("AWS_*")],
+            include_only: vec![
+                EnvironmentVariablePattern::new_case_insensitive("PATH"),
+                EnvironmentVariablePattern::new_case_insensitive("SHARED"),
+            ],
+            ..Default::default()
+        };
+        policy
+            .r#set
+            .insert("SHARED".to_string(), "policy".to_string());
+        policy
+            .secrets
+            .insert("API_TOKEN".to_string(), "MY_TOKEN".to_string());
+
+        apply_env_policy_to_mcp_servers_with_vars(&mut servers, &policy, vars, |_| {
+            Some("s3cret".to_string())
+        });
+
+        let server_env = |name: &str| {
+            let McpServerTransportConfig::Stdio { env, .. } = &servers[name].transport else {
+                panic!("expected stdio transport");
+            };
+            env.clone()
+        };
+        let expected_docs: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "SHARED".to_string() => "server".to_string(),
+            "API_TOKEN".to_string() => "s3cret".to_string(),
+        };
+        let expected_other: HashMap<String, String> = hashmap! {
+            "PATH".to_string() => "/usr/bin".to_string(),
+            "SHARED".to_string() => "policy".to_string(),
+        };
+        assert_eq!(server_env("docs"), Some(expected_docs));
+        assert_eq!(server_env("other"), Some(expected_other));
+    }
 }
@@ -0,0 +1,183 @@
+//! Prompt A/B experiments.
+//!
+//! Each new session is assigned one arm per configured experiment (see
+//! `experiments` in config.toml). An arm may append instructions to the base
+//! instructions and replace tool descriptions. Assignments are recorded in
+//! the session metadata so resumed and forked sessions keep their arms, and
+//! telemetry is tagged with them so arms can be compared.
+
+use std::collections::BTreeMap;
+
+use codex_protocol::config_types::InstructionLayerMode;
+use rand::prelude::IndexedRandom;
+use tracing::warn;
+
+use crate::client_common::tools::ToolSpec;
+use crate::config::types::ExperimentConfig;
+use crate::config::types::InstructionLayer;
+
+/// Assigns an arm to every experiment with at least one arm. Pinned arms win;
+/// otherwise the arm is picked uniformly at random.
+pub(crate) fn assign_experiment_arms(
+    experiments: &BTreeMap<String, ExperimentConfig>,
+) -> BTreeMap<String, String> {
+    let mut rng = rand::rng();
+    experiments
+        .iter()
+        .filter_map(|(name, experiment)| {
+            if let Some(arm) = experiment.arm.as_ref() {
+                if experiment.arms.contains_key(arm) {
+                    return Some((name.clone(), arm.clone()));
+                }
+                warn!("experiment `{name}` pins unknown arm `{arm}`; assigning randomly");
+            }
+            let arms: Vec<&String> = experiment.arms.keys().collect();
+            let arm = arms.choose(&mut rng)?;
+            Some((name.clone(), (*arm).clone()))
+        })
+        .collect()
+}
+
+/// Instruction layers for the assigned arms. They apply after all configured
+/// layers, in experiment name order.
+pub(crate) fn experiment_instruction_layers(
+    experiments: &BTreeMap<String, ExperimentConfig>,
+    assigned: &BTreeMap<String, String>,
+) -> Vec<InstructionLayer> {
+    assigned
+        .iter()
+        .filter_map(|(name, arm)| {
+            let instructions = experiments.get(name)?.arms.get(arm)?.instructions.clone()?;
+            Some(InstructionLayer {
+                name: format!("experiment.{name}"),
+                text: instructions,
+                mode: InstructionLayerMode::Append,
+                order: i32::MAX,
+                token_budget: None,
+            })
+        })
+        .collect()
+}
+
+/// Replaces the descriptions of tools overridden by the assigned arms. When
+/// several arms override the same tool, the last experiment by name wins.
+pub(crate) fn apply_experiment_tool_descriptions(
+    tools: &mut [ToolSpec],
+    experiments: &BTreeMap<String, ExperimentConfig>,
+    assigned: &BTreeMap<String, String>,
+) {
+    let overrides: BTreeMap<&str, &str> = assigned
+        .iter()
+        .filter_map(|(name, arm)| experiments.get(name)?.arms.get(arm))
+        .flat_map(|arm| &arm.tool_descriptions)
+        .map(|(tool, description)| (tool.as_str(), description.as_str()))
+        .collect();
+    if overrides.is_empty() {
+        return;
+    }
+    for tool in tools {
+        let (name, description) = match tool {
+            ToolSpec::Function(tool) => (&tool.name, &mut tool.description),
+            ToolSpec::Freeform(tool) => (&tool.name, &mut tool.description),
+            ToolSpec::LocalShell {} | ToolSpec::WebSearch { .. } => continue,
+        };
+        if let Some(replacement) = overrides.get(name.as_str()) {
+            *description = (*replacement).to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client_common::tools::ResponsesApiTool;
+    use crate::config::types::ExperimentArmConfig;
+    use crate::tools::spec::JsonSchema;
+    use pretty_assertions::assert_eq;
+
+    fn experiments() -> BTreeMap<String, ExperimentConfig> {
+        BTreeMap::from([
+            (
+                "terse".to_string(),
+                ExperimentConfig {
+                    arm: Some("treatment".to_string()),
+                    arms: BTreeMap::from([
+                        ("control".to_string(), ExperimentArmConfig::default()),
+                        (
+                            "treatment".to_string(),
+                            ExperimentArmConfig {
+                                instructions: Some("Be terse.".to_string()),
+                                tool_descriptions: BTreeMap::from([(
+                                    "shell".to_string(),
+                                    "Run a command.".to_string(),
+                                )]),
+                            },
+                        ),
+                    ]),
+                },
+            ),
+            ("empty".to_string(), ExperimentConfig::default()),
+        ])
+    }
+
+    #[test]
+    fn pinned_arm_drives_instructions_and_tool_descriptions() {
+        let experiments = experiments();
+        let assigned = assign_experiment_arms(&experiments);
+        assert_eq!(
+            assigned,
+            BTreeMap::from([("terse".to_string(), "treatment".to_string())])
+        );
+
+        assert_eq!(
+            experiment_instruction_layers(&experiments, &assigned),
+            vec![InstructionLayer {
+                name: "experiment.terse".to_string(),
+                text: "Be terse.".to_string(),
+                mode: InstructionLayerMode::Append,
+                order: i32::MAX,
+                token_budget: None,
+            }]
+        );
+
+        let tool = |name: &str| {
+            ToolSpec::Function(ResponsesApiTool {
+                name: name.to_string(),
+                description: "Original.".to_string(),
+                strict: false,
+                parameters: JsonSchema::Object {
+                    properties: BTreeMap::new(),
+                    required: None,
+                    additional_properties: None,
+                },
+            })
+        };
+        let mut tools = vec![tool("shell"), tool("apply_patch")];
+        apply_experiment_tool_descriptions(&mut tools, &experiments, &assigned);
+        let descriptions: Vec<(&str, &str)> = tools
+            .iter()
+            .filter_map(|tool| match tool {
+                ToolSpec::Function(tool) => Some((tool.name.as_str(), tool.description.as_str())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            descriptions,
+            vec![("shell", "Run a command."), ("apply_patch", "Original.")]
+        );
+    }
+
+    #[test]
+    fn unpinned_experiments_pick_a_configured_arm() {
+        let mut experiments = experiments();
+        if let Some(experiment) = experiments.get_mut("terse") {
+            experiment.arm = Some("missing".to_string());
+        }
+        let assigned = assign_experiment_arms(&experiments);
+        assert!(
+            assigned
+                .get("terse")
+                .is_some_and(|arm| arm == "control" || arm == "treatment")
+        );
+    }
+}
@@ -119,6 +119,9 @@ pub enum Feature {
     ChildAgentsMd,
     /// Enforce UTF8 output in Powershell.
     PowershellUtf8,
+    /// Translate common POSIX commands (`ls -la`, `rm -rf`, `&&` chains) when the
+    /// session shell is PowerShell.
+    PowershellPosixTranslation,
     /// Compress request bodies (zstd) when sending streaming requests to codex-backend.
     EnableRequestCompression,
     /// Enable collab tools.
@@ -131,6 +134,8 @@ pub enum Feature {
     SkillMcpDependencyInstall,
     /// Prompt for missing skill env var dependencies.
     SkillEnvVarDependencyPrompt,
+    /// Offer to install missing skill CLI dependencies in the sandbox.
+    SkillCliDependencyInstall,
     /// Steer feature flag - when enabled, Enter submits immediately instead of queuing.
     Steer,
     /// Enable collaboration modes (Plan, Default).
@@ -143,6 +148,29 @@ pub enum Feature {
     ResponsesWebsockets,
     /// Enable Responses API websocket v2 mode.
     ResponsesWebsocketsV2,
+    /// Start read-only tool calls while the model is still streaming them.
+    SpeculativeToolPrefetch,
+    /// Open a pooled connection to the model provider when a session starts.
+    ModelPreconnect,
+    /// Replace repeated identical tool outputs in history with a reference to the first one.
+    DedupToolOutputs,
+    /// Write new session rollouts as zstd-compressed frames.
+    RolloutCompression,
+    /// Encrypt new session rollouts with a key kept in the OS keychain.
+    SessionEncryption,
+    /// Report the resolved binary, cwd, and sandbox with every shell tool output.
+    CommandProvenance,
+    /// Offer the `cloud_cli` tool, which runs read-only kubectl, aws, and
+    /// gcloud commands outside the shell.
+    CloudCli,
+    /// Offer the `terraform_plan` and `terraform_apply` tools.
+    Terraform,
+    /// Offer the `build_image` and `run_in_image` tools.
+    ContainerImage,
+    /// Offer the `package_info` tool, which queries package registries.
+    PackageInfo,
+    /// Offer the `scaffold` tool, which creates files from template packs.
+    Scaffold,
 }
 
 impl Feature {
@@ -564,6 +592,12 @@ pub const FEATURES: &[FeatureSpec] = &[
         #[cfg(not(windows))]
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::PowershellPosixTranslation,
+        key: "powershell_posix_translation",
+        stage: Stage::Stable,
+        default_enabled: true,
+    },
     FeatureSpec {
         id: Feature::EnableRequestCompression,
         key: "enable_request_compression",
@@ -608,6 +642,12 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::UnderDevelopment,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::SkillCliDependencyInstall,
+        key: "skill_cli_dependency_install",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
     FeatureSpec {
         id: Feature::Steer,
         key: "steer",
@@ -652,6 +692,72 @@ pub const FEATURES: &[FeatureSpec] = &[
         stage: Stage::UnderDevelopment,
         default_enabled: false,
     },
+    FeatureSpec {
+        id: Feature::SpeculativeToolPrefetch,
+        key: "speculative_tool_prefetch",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ModelPreconnect,
+        key: "model_preconnect",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::DedupToolOutputs,
+        key: "dedup_tool_outputs",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::RolloutCompression,
+        key: "rollout_compression",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::SessionEncryption,
+        key: "session_encryption",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::CommandProvenance,
+        key: "command_provenance",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::CloudCli,
+        key: "cloud_cli",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Terraform,
+        key: "terraform",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::ContainerImage,
+        key: "container_image",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::PackageInfo,
+        key: "package_info",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
+    FeatureSpec {
+        id: Feature::Scaffold,
+        key: "scaffold",
+        stage: Stage::UnderDevelopment,
+        default_enabled: false,
+    },
 ];
 
 /// Push a warning event if any under-development features are enabled.
@@ -1,11 +1,129 @@
+use serde::Serialize;
 use thiserror::Error;
 
+/// Structured reason a tool call failed.
+///
+/// Typed failures are serialized into the tool output (see
+/// [`ToolFailureDetails`]) so the model and clients reading the transcript can
+/// tell a failure worth retrying from one that needs the user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ToolFailureKind {
+    /// The user declined or cancelled the call.
+    UserCancelled,
+    /// The sandbox blocked the call.
+    SandboxDenied,
+    /// The call did not finish in time.
+    Timeout,
+    /// The requested tool, thread, or resource does not exist.
+    NotFound,
+    /// A provider rate limit, quota, or capacity limit was hit.
+    ProviderLimit,
+    /// The tool kept failing and is paused by its circuit breaker.
+    Unavailable,
+    /// The connection to the tool's server broke before it answered.
+    ConnectionLost,
+}
+
+impl ToolFailureKind {
+    /// Whether retrying the same call unchanged may succeed.
+    pub fn is_retryable(self) -> bool {
+        match self {
+            Self::Timeout | Self::ProviderLimit | Self::ConnectionLost => true,
+            Self::UserCancelled | Self::SandboxDenied | Self::NotFound | Self::Unavailable => false,
+        }
+    }
+
+    fn retry_hint(self) -> &'static str {
+        match self {
+            Self::UserCancelled => {
+                "the user declined this call; do not retry it unchanged, ask the user how to proceed"
+            }
+            Self::SandboxDenied => {
+                "the sandbox blocked this call; request escalated permissions or choose another approach"
+            }
+            Self::Timeout => "the call timed out; retry with a longer timeout or a smaller scope",
+            Self::NotFound => "the target does not exist; check the name or path before retrying",
+            Self::ProviderLimit => "a provider limit was hit; wait before retrying",
+            Self::Unavailable => {
+                "the tool is temporarily unavailable after repeated failures; use another approach"
+            }
+            Self::ConnectionLost => "the connection to the tool's server was lost; retry the call",
+        }
+    }
+}
+
 #[derive(Debug, Error, PartialEq)]
 pub enum FunctionCallError {
     #[error("{0}")]
     RespondToModel(String),
+    /// Like [`FunctionCallError::RespondToModel`], but tagged with a
+    /// [`ToolFailureKind`] so callers can react to the failure. Build with
+    /// [`FunctionCallError::failed`] unless `message` already embeds the kind.
+    #[error("{message}")]
+    Failed {
+        kind: ToolFailureKind,
+        message: String,
+    },
     #[error("LocalShellCall without call_id or id")]
     MissingLocalShellCallId,
     #[error("Fatal error: {0}")]
     Fatal(String),
 }
+
+impl FunctionCallError {
+    /// Builds a typed failure whose model-facing text ends with a
+    /// `tool_error:` line holding the serialized [`ToolFailureDetails`].
+    pub(crate) fn failed(kind: ToolFailureKind, message: impl Into<String>) -> Self {
+        let message = message.into();
+        Self::Failed {
+            kind,
+            message: format!(
+                "{message}\n\ntool_error: {}",
+                ToolFailureDetails::from(kind)
+            ),
+        }
+    }
+}
+
+/// Wire form of a [`ToolFailureKind`] as it appears in tool outputs.
+#[derive(Debug, Serialize)]
+pub(crate) struct ToolFailureDetails {
+    kind: ToolFailureKind,
+    retryable: bool,
+    hint: &'static str,
+}
+
+impl From<ToolFailureKind> for ToolFailureDetails {
+    fn from(kind: ToolFailureKind) -> Self {
+        Self {
+            kind,
+            retryable: kind.is_retryable(),
+            hint: kind.retry_hint(),
+        }
+    }
+}
+
+impl std::fmt::Display for ToolFailureDetails {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn typed_failures_serialize_kind_into_model_output() {
+        assert_eq!(
+            FunctionCallError::failed(ToolFailureKind::NotFound, "unsupported call: missing"),
+            FunctionCallError::Failed {
+                kind: ToolFailureKind::NotFound,
+                message: "unsupported call: missing\n\ntool_error: {\"kind\":\"not_found\",\"retryable\":false,\"hint\":\"the target does not exist; check the name or path before retrying\"}".to_string(),
+            }
+        );
+    }
+}
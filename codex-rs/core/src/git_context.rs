@@ -0,0 +1,238 @@
+//! Summaries of commit ranges and pull requests injected into the conversation
+//! via [`Op::InjectGitContext`](codex_protocol::protocol::Op::InjectGitContext).
+//!
+//! The summary lists the commits (or the PR metadata) followed by the diff,
+//! where per-file diffs over [`MAX_FILE_DIFF_LINES`] are cut to their first
+//! lines and the headers of the hunks after them, with line counts, so a large
+//! change does not flood the context window.
+
+use std::path::Path;
+use std::time::Duration;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::GitContextSource;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+pub const GIT_CONTEXT_OPEN: &str = "<git_context>";
+pub const GIT_CONTEXT_CLOSE: &str = "</git_context>";
+
+/// Per-file diffs longer than this are summarized instead of included.
+const MAX_FILE_DIFF_LINES: usize = 200;
+/// Lines kept verbatim from the start of a summarized per-file diff.
+const LARGE_FILE_DIFF_EXCERPT_LINES: usize = 60;
+/// Upper bound on the whole summary.
+const MAX_GIT_CONTEXT_BYTES: usize = 64 * 1024;
+const GIT_CONTEXT_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub fn is_git_context_text(text: &str) -> bool {
+    text.trim_start()
+        .to_ascii_lowercase()
+        .starts_with(GIT_CONTEXT_OPEN)
+}
+
+pub(crate) fn describe_git_context_source(source: &GitContextSource) -> String {
+    match source {
+        GitContextSource::CommitRange { range } => format!("commits {range}"),
+        GitContextSource::PullRequest { pr } => format!("pull request {pr}"),
+    }
+}
+
+/// Builds the `<git_context>` message for `source`, or a user-facing error.
+pub(crate) async fn git_context_input_item(
+    cwd: &Path,
+    source: &GitContextSource,
+) -> Result<ResponseInputItem, String> {
+    let (header, diff) = match source {
+        GitContextSource::CommitRange { range } => {
+            let range = range.trim();
+            if !range.contains("..") || range.starts_with('-') {
+                return Err(format!(
+                    "expected a commit range such as `main..HEAD`, got `{range}`"
+                ));
+            }
+            let log = run(
+                "git",
+                &[
+                    "log",
+                    "--format=%h %s (%an, %ad)",
+                    "--date=short",
+                    "--end-of-options",
+                    range,
+                    "--",
+                ],
+                cwd,
+            )
+            .await?;
+            let diff = run("git", &["diff", "--end-of-options", range, "--"], cwd).await?;
+            (format!("Commits in {range}:\n{}", log.trim_end()), diff)
+        }
+        GitContextSource::PullRequest { pr } => {
+            let pr = pr.trim();
+            if pr.is_empty() || pr.starts_with('-') {
+                return Err(format!("expected a pull request URL or number, got `{pr}`"));
+            }
+            let view = run("gh", &["pr", "view", "--", pr], cwd).await?;
+            let diff = run("gh", &["pr", "diff", "--", pr], cwd).await?;
+            (format!("Pull request {pr}:\n{}", view.trim_end()), diff)
+        }
+    };
+
+    let body = format!("{header}\n\nChanges:\n{}", summarize_diff(&diff));
+    let body = truncate_text(&body, TruncationPolicy::Bytes(MAX_GIT_CONTEXT_BYTES));
+    Ok(ResponseInputItem::Message {
+        role: "user".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!("{GIT_CONTEXT_OPEN}\n{body}\n{GIT_CONTEXT_CLOSE}"),
+        }],
+    })
+}
+
+async fn run(program: &str, args: &[&str], cwd: &Path) -> Result<String, String> {
+    let mut command = Command::new(program);
+    command
+        .env("GIT_OPTIONAL_LOCKS", "0")
+        .args(args)
+        .current_dir(cwd)
+        .kill_on_drop(true);
+    let output = match timeout(GIT_CONTEXT_COMMAND_TIMEOUT, command.output()).await {
+        Ok(Ok(output)) => output,
+        Ok(Err(err)) => return Err(format!("failed to run `{program}`: {err}")),
+        Err(_) => return Err(format!("`{program} {}` timed out", args.join(" "))),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!(
+            "`{program} {}` failed: {}",
+            args.join(" "),
+            stderr.trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Keeps small per-file diffs verbatim and reduces large ones to their first
+/// lines, their line counts, and the headers of the hunks that follow.
+fn summarize_diff(diff: &str) -> String {
+    if diff.trim().is_empty() {
+        return "(no changes)".to_string();
+    }
+
+    let mut files: Vec<Vec<&str>> = Vec::new();
+    for line in diff.lines() {
+        match files.last_mut() {
+            Some(file) if !line.starts_with("diff --git ") => file.push(line),
+            _ => files.push(vec![line]),
+        }
+    }
+
+    let mut summary: Vec<String> = Vec::new();
+    for file in files {
+        if file.len() <= MAX_FILE_DIFF_LINES {
+            summary.extend(file.into_iter().map(str::to_string));
+            continue;
+        }
+        let added = file
+            .iter()
+            .filter(|line| line.starts_with('+') && !line.starts_with("+++"))
+            .count();
+        let removed = file
+            .iter()
+            .filter(|line| line.starts_with('-') && !line.starts_with("---"))
+            .count();
+        let (excerpt, rest) = file.split_at(LARGE_FILE_DIFF_EXCERPT_LINES);
+        summary.extend(excerpt.iter().map(|line| line.to_string()));
+        summary.push(format!(
+            "(large diff cut: +{added} -{removed} lines in total, only hunk headers below)"
+        ));
+        summary.extend(
+            rest.iter()
+                .filter(|line| line.starts_with("@@"))
+                .map(|line| line.to_string()),
+        );
+    }
+    summary.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn small_diffs_are_kept_and_large_diffs_summarized() {
+        let small = "diff --git a/a.rs b/a.rs\n--- a/a.rs\n+++ b/a.rs\n@@ -1 +1 @@\n-old\n+new";
+        let header = "diff --git a/b.rs b/b.rs\n--- a/b.rs\n+++ b/b.rs";
+        let hunk = |start: usize| {
+            let lines: String = (start..start + 100)
+                .map(|i| format!("\n+line {i}"))
+                .collect();
+            format!("\n@@ -0,0 +{start},100 @@{lines}")
+        };
+        let large = format!("{header}{}{}", hunk(1), hunk(101));
+        let excerpt = large
+            .lines()
+            .take(LARGE_FILE_DIFF_EXCERPT_LINES)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(
+            summarize_diff(&format!("{small}\n{large}")),
+            format!(
+                "{small}\n{excerpt}\n(large diff cut: +200 -0 lines in total, only hunk headers below)\n@@ -0,0 +101,100 @@"
+            )
+        );
+        assert_eq!(summarize_diff(""), "(no changes)");
+    }
+
+    #[tokio::test]
+    async fn commit_range_context_lists_the_commits_and_their_diff() {
+        let repo = tempfile::tempdir().expect("tempdir");
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .args(["-c", "user.name=Test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .current_dir(repo.path())
+                .status()
+                .expect("git");
+            assert!(status.success(), "git {args:?} failed");
+        };
+        git(&["init", "--quiet"]);
+        std::fs::write(repo.path().join("a.txt"), "one\n").expect("write a.txt");
+        git(&["add", "a.txt"]);
+        git(&["commit", "--quiet", "-m", "first"]);
+        std::fs::write(repo.path().join("a.txt"), "two\n").expect("write a.txt");
+        git(&["commit", "--quiet", "-am", "second"]);
+
+        let item = git_context_input_item(
+            repo.path(),
+            &GitContextSource::CommitRange {
+                range: "HEAD~1..HEAD".to_string(),
+            },
+        )
+        .await
+        .expect("git context");
+
+        let ResponseInputItem::Message { content, .. } = item else {
+            panic!("expected a message");
+        };
+        let [ContentItem::InputText { text }] = content.as_slice() else {
+            panic!("expected one text item");
+        };
+        assert!(text.contains(" second (Test, "), "{text}");
+        assert!(!text.contains(" first (Test, "), "{text}");
+        assert!(text.contains("-one\n+two"), "{text}");
+    }
+
+    #[test]
+    fn detects_git_context_text() {
+        assert!(is_git_context_text(
+            "<git_context>\nCommits in a..b:\n</git_context>"
+        ));
+        assert!(!is_git_context_text("please review <git_context>"));
+    }
+}
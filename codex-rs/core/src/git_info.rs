@@ -1,5 +1,6 @@
 use std::collections::BTreeMap;
 use std::collections::HashSet;
+use std::ops::RangeInclusive;
 use std::path::Path;
 use std::path::PathBuf;
 
@@ -159,6 +160,106 @@ pub async fn get_has_changes(cwd: &Path) -> Option<bool> {
     Some(!output.stdout.is_empty())
 }
 
+/// Short `git status` (branch line plus one line per changed path), or `None`
+/// outside a git repository or on error/timeout.
+pub async fn status_short(cwd: &Path) -> Option<String> {
+    let output = run_git_command_with_timeout(&["status", "--short", "--branch"], cwd).await?;
+    if !output.status.success() {
+        return None;
+    }
+
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .trim_end()
+            .to_string(),
+    )
+}
+
+/// Paths with uncommitted changes (including untracked files), relative to
+/// `cwd` and limited to it. Empty outside a git repository or on error/timeout.
+pub async fn changed_paths(cwd: &Path) -> Vec<PathBuf> {
+    let mut paths = Vec::new();
+    for args in [
+        &["diff", "--name-only", "--relative", "HEAD"][..],
+        &["ls-files", "--others", "--exclude-standard"][..],
+    ] {
+        if let Some(output) = run_git_command_with_timeout(args, cwd).await
+            && output.status.success()
+        {
+            paths.extend(
+                String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .filter(|line| !line.is_empty())
+                    .map(PathBuf::from),
+            );
+        }
+    }
+    paths
+}
+
+/// Lines added or changed since HEAD, as 1-based inclusive ranges keyed by
+/// path relative to `cwd`. Every line of an untracked file counts as changed.
+/// Empty outside a git repository or on error/timeout.
+pub async fn changed_line_ranges(cwd: &Path) -> BTreeMap<PathBuf, Vec<RangeInclusive<u32>>> {
+    let mut ranges = BTreeMap::new();
+    if let Some(output) = run_git_command_with_timeout(
+        &[
+            "diff",
+            "--unified=0",
+            "--relative",
+            "--no-color",
+            "--no-ext-diff",
+            // `diff.noprefix` and `diff.mnemonicPrefix` would change the
+            // `b/` prefix the paths are read with below.
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            "HEAD",
+        ],
+        cwd,
+    )
+    .await
+        && output.status.success()
+    {
+        let mut current: Option<PathBuf> = None;
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some(path) = line.strip_prefix("+++ ") {
+                // Deleted files diff against `/dev/null`.
+                current = path.strip_prefix("b/").map(PathBuf::from);
+            } else if let Some(hunk) = line.strip_prefix("@@ ")
+                && let Some(path) = &current
+                && let Some(added) = hunk
+                    .split_whitespace()
+                    .find_map(|part| part.strip_prefix('+'))
+            {
+                let (start, count) = added.split_once(',').unwrap_or((added, "1"));
+                if let (Ok(start), Ok(count)) = (start.parse::<u32>(), count.parse::<u32>())
+                    && count > 0
+                {
+                    ranges
+                        .entry(path.clone())
+                        .or_insert_with(Vec::new)
+                        .push(start..=start + count - 1);
+                }
+            }
+        }
+    }
+    if let Some(output) =
+        run_git_command_with_timeout(&["ls-files", "--others", "--exclude-standard"], cwd).await
+        && output.status.success()
+    {
+        for path in String::from_utf8_lossy(&output.stdout).lines() {
+            let Ok(contents) = tokio::fs::read_to_string(cwd.join(path)).await else {
+                continue;
+            };
+            let line_count = contents.lines().count() as u32;
+            if line_count > 0 {
+                ranges.insert(PathBuf::from(path), vec![1..=line_count]);
+            }
+        }
+    }
+    ranges
+}
+
 fn parse_git_remote_urls(stdout: &str) -> Option<BTreeMap<String, String>> {
     let mut remotes = BTreeMap::new();
     for line in stdout.lines() {
@@ -997,6 +1098,29 @@ mod tests {
         assert_eq!(get_has_changes(&repo_path).await, Some(true));
     }
 
+    #[tokio::test]
+    async fn test_changed_line_ranges_covers_edits_and_untracked_files() {
+        let temp_dir = TempDir::new().expect("Failed to create temp dir");
+        let repo_path = create_test_git_repo(&temp_dir).await;
+        Command::new("git")
+            .args(["config", "diff.noprefix", "true"])
+            .current_dir(&repo_path)
+            .output()
+            .await
+            .expect("Failed to set diff.noprefix");
+
+        fs::write(repo_path.join("test.txt"), "first\ntest content\nlast\n")
+            .expect("write tracked file");
+        fs::write(repo_path.join("new_file.txt"), "one\ntwo\n").expect("write untracked file");
+        assert_eq!(
+            changed_line_ranges(&repo_path).await,
+            BTreeMap::from([
+                (PathBuf::from("new_file.txt"), vec![1..=2]),
+                (PathBuf::from("test.txt"), vec![1..=3]),
+            ])
+        );
+    }
+
     #[tokio::test]
     async fn test_get_has_changes_with_untracked_change_returns_true() {
         let temp_dir = TempDir::new().expect("Failed to create temp dir");
@@ -0,0 +1,105 @@
+use codex_protocol::config_types::InstructionLayerMode;
+use codex_protocol::protocol::InstructionLayerMeta;
+
+use crate::config::types::InstructionLayer;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
+use crate::truncate::truncate_text;
+
+/// Applies `layers` to `base` in order (`order`, then name) and returns the
+/// resulting instructions along with a record of each applied layer.
+pub(crate) fn apply_instruction_layers(
+    base: String,
+    layers: &[InstructionLayer],
+) -> (String, Vec<InstructionLayerMeta>) {
+    let mut layers: Vec<&InstructionLayer> = layers.iter().collect();
+    layers.sort_by(|a, b| a.order.cmp(&b.order).then_with(|| a.name.cmp(&b.name)));
+
+    let mut instructions = base;
+    let mut applied = Vec::with_capacity(layers.len());
+    for layer in layers {
+        let text = layer.text.trim();
+        if text.is_empty() {
+            continue;
+        }
+        let (text, truncated) = match layer.token_budget {
+            Some(budget) => {
+                let truncated_text = truncate_text(text, TruncationPolicy::Tokens(budget));
+                let truncated = truncated_text != text;
+                (truncated_text, truncated)
+            }
+            None => (text.to_string(), false),
+        };
+        applied.push(InstructionLayerMeta {
+            name: layer.name.clone(),
+            mode: layer.mode,
+            tokens: approx_token_count(&text),
+            truncated,
+        });
+        instructions = match layer.mode {
+            InstructionLayerMode::Append => format!("{}\n\n{text}", instructions.trim_end()),
+            InstructionLayerMode::Replace => text,
+        };
+    }
+    (instructions, applied)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn layer(name: &str, text: &str, mode: InstructionLayerMode, order: i32) -> InstructionLayer {
+        InstructionLayer {
+            name: name.to_string(),
+            text: text.to_string(),
+            mode,
+            order,
+            token_budget: None,
+        }
+    }
+
+    #[test]
+    fn layers_apply_by_order_then_name() {
+        let layers = [
+            layer("user_style", "Be terse.", InstructionLayerMode::Append, 20),
+            layer("project", "Use tabs.", InstructionLayerMode::Append, 10),
+            layer("org_policy", "Org base.", InstructionLayerMode::Replace, 0),
+            layer("a_note", "Note.", InstructionLayerMode::Append, 10),
+        ];
+
+        let (instructions, applied) = apply_instruction_layers("Model base.".to_string(), &layers);
+
+        assert_eq!(instructions, "Org base.\n\nNote.\n\nUse tabs.\n\nBe terse.");
+        assert_eq!(
+            applied
+                .iter()
+                .map(|meta| (meta.name.as_str(), meta.mode, meta.truncated))
+                .collect::<Vec<_>>(),
+            vec![
+                ("org_policy", InstructionLayerMode::Replace, false),
+                ("a_note", InstructionLayerMode::Append, false),
+                ("project", InstructionLayerMode::Append, false),
+                ("user_style", InstructionLayerMode::Append, false),
+            ]
+        );
+    }
+
+    #[test]
+    fn token_budget_truncates_layer() {
+        let mut long = layer(
+            "project",
+            &"word ".repeat(2_000),
+            InstructionLayerMode::Append,
+            0,
+        );
+        long.token_budget = Some(50);
+
+        let (instructions, applied) = apply_instruction_layers("Base.".to_string(), &[long]);
+
+        assert!(instructions.starts_with("Base.\n\nword"));
+        assert!(instructions.contains("tokens truncated"));
+        assert_eq!(applied.len(), 1);
+        assert!(applied[0].truncated);
+    }
+}
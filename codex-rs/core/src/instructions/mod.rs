@@ -1,5 +1,8 @@
+mod layers;
 mod user_instructions;
 
+pub(crate) use layers::apply_instruction_layers;
+
 pub(crate) use user_instructions::SkillInstructions;
 pub use user_instructions::USER_INSTRUCTIONS_OPEN_TAG_LEGACY;
 pub use user_instructions::USER_INSTRUCTIONS_PREFIX;
@@ -9,24 +9,32 @@ mod analytics_client;
 pub mod api_bridge;
 mod apply_patch;
 mod apps;
+mod attached_clients;
+mod attachments;
 pub mod auth;
 mod client;
 mod client_common;
 pub mod codex;
+mod read_filters;
 mod realtime_conversation;
 pub use codex::SteerInputError;
 mod codex_thread;
 mod compact_remote;
+pub use attached_clients::AttachMode;
+pub use attached_clients::AttachedClient;
 pub use codex_thread::CodexThread;
 pub use codex_thread::ThreadConfigSnapshot;
 mod agent;
 mod codex_delegate;
 mod command_canonicalization;
+mod command_risk_model;
 mod commit_attribution;
+mod completion;
 pub mod config;
 pub mod config_loader;
 pub mod connectors;
 mod context_manager;
+pub mod context_providers;
 pub mod custom_prompts;
 pub mod env;
 mod environment_context;
@@ -34,17 +42,21 @@ pub mod error;
 pub mod exec;
 pub mod exec_env;
 mod exec_policy;
+mod experiments;
 pub mod features;
 mod file_watcher;
 mod flags;
+mod git_context;
 pub mod git_info;
 pub mod instructions;
 pub mod landlock;
+pub mod localization;
 pub mod mcp;
 mod mcp_connection_manager;
 pub mod models_manager;
 mod network_policy_decision;
 pub mod network_proxy_loader;
+pub mod notifier;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_CAPABILITY;
 pub use mcp_connection_manager::MCP_SANDBOX_STATE_METHOD;
 pub use mcp_connection_manager::SandboxState;
@@ -53,20 +65,27 @@ mod memories;
 mod mentions;
 mod message_history;
 mod model_provider_info;
+mod patch_artifact;
 pub mod path_utils;
 pub mod personality_migration;
 mod proposed_plan_parser;
+mod rate_limit_forecast;
+mod request_signing;
 mod sandbox_tags;
 pub mod sandboxing;
+mod session_archive;
 mod session_prefix;
+mod shadow_workspace;
 mod shell_detect;
 mod stream_events_utils;
+mod suggested_allowlist;
 mod tagged_block_parser;
 pub mod test_support;
 mod text_encoding;
 pub mod token_data;
 mod truncate;
 mod unified_exec;
+mod usage_rollups;
 pub mod windows_sandbox;
 pub use client::X_RESPONSESAPI_INCLUDE_TIMING_METRICS_HEADER;
 pub use model_provider_info::DEFAULT_LMSTUDIO_PORT;
@@ -74,6 +93,7 @@ pub use model_provider_info::DEFAULT_OLLAMA_PORT;
 pub use model_provider_info::LMSTUDIO_OSS_PROVIDER_ID;
 pub use model_provider_info::ModelProviderInfo;
 pub use model_provider_info::OLLAMA_OSS_PROVIDER_ID;
+pub use model_provider_info::RequestSigningConfig;
 pub use model_provider_info::WireApi;
 pub use model_provider_info::built_in_model_providers;
 pub use model_provider_info::create_oss_provider_with_base_url;
@@ -109,9 +129,11 @@ pub mod terminal;
 mod tools;
 pub mod turn_diff_tracker;
 mod turn_metadata;
+mod turn_timing;
 mod zsh_exec_bridge;
 pub use rollout::ARCHIVED_SESSIONS_SUBDIR;
 pub use rollout::INTERACTIVE_SESSION_SOURCES;
+pub use rollout::RolloutLines;
 pub use rollout::RolloutRecorder;
 pub use rollout::RolloutRecorderParams;
 pub use rollout::SESSIONS_SUBDIR;
@@ -133,15 +155,19 @@ pub use rollout::policy::EventPersistenceMode;
 pub use rollout::rollout_date_parts;
 pub use rollout::session_index::find_thread_names_by_ids;
 mod function_tool;
+pub use function_tool::ToolFailureKind;
 mod state;
 mod tasks;
 mod user_shell_command;
 pub mod util;
+mod watch_mode;
 pub(crate) use codex_protocol::protocol;
 pub(crate) use codex_shell_command::bash;
+pub(crate) use codex_shell_command::command_risk;
 pub(crate) use codex_shell_command::is_dangerous_command;
 pub(crate) use codex_shell_command::is_safe_command;
 pub(crate) use codex_shell_command::parse_command;
+pub(crate) use codex_shell_command::posix_translation;
 pub(crate) use codex_shell_command::powershell;
 
 pub use client::X_CODEX_TURN_METADATA_HEADER;
@@ -0,0 +1,297 @@
+//! Localized user-facing strings.
+//!
+//! Approval prompts, abort messages, and user-facing errors are looked up by
+//! [`Message`] in the session's [`Locale`] (`locale` in config.toml). Every
+//! locale must translate every message, which the exhaustive matches below
+//! enforce. Placeholders such as `{host}` are filled in by [`Locale::format`].
+
+use schemars::JsonSchema;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Language of user-facing agent and UI messages.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default, JsonSchema)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "fr")]
+    French,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "ja")]
+    Japanese,
+    #[serde(rename = "zh-CN")]
+    SimplifiedChinese,
+}
+
+/// A localizable user-facing message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Message {
+    /// Title of a command approval prompt.
+    RunCommandPrompt,
+    /// Title of a network access approval prompt. Placeholder: `{host}`.
+    NetworkAccessPrompt,
+    /// Title of a patch approval prompt.
+    EditFilesPrompt,
+    /// Title of an MCP elicitation prompt. Placeholder: `{server}`.
+    ElicitationPrompt,
+    /// Label preceding the reason given for an approval request.
+    ApprovalReason,
+    /// Label preceding the MCP server name of an elicitation.
+    ElicitationServer,
+    ApproveOnce,
+    ApproveHostForSession,
+    ApproveProceed,
+    /// Placeholder: `{prefix}`.
+    ApproveCommandPrefix,
+    ApproveFilesForSession,
+    DenyAndExplain,
+    ProvideRequestedInfo,
+    ContinueWithoutInfo,
+    CancelRequest,
+    /// Output of an interrupted shell tool call. Placeholder: `{secs}`.
+    ShellAborted,
+    /// Output of any other interrupted tool call. Placeholder: `{secs}`.
+    ToolAborted,
+    /// Output of an interrupted user shell command.
+    CommandAborted,
+    ExecRejected,
+    PatchRejected,
+    ReviewInterrupted,
+}
+
+impl Locale {
+    /// The template for `message` in this locale, placeholders included.
+    pub fn text(self, message: Message) -> &'static str {
+        match self {
+            Locale::English => english(message),
+            Locale::Spanish => spanish(message),
+            Locale::French => french(message),
+            Locale::German => german(message),
+            Locale::Japanese => japanese(message),
+            Locale::SimplifiedChinese => simplified_chinese(message),
+        }
+    }
+
+    /// `message` with each `{name}` placeholder replaced by its value in
+    /// `args`.
+    pub fn format(self, message: Message, args: &[(&str, &str)]) -> String {
+        args.iter()
+            .fold(self.text(message).to_string(), |text, (name, value)| {
+                text.replace(&format!("{{{name}}}"), value)
+            })
+    }
+}
+
+fn english(message: Message) -> &'static str {
+    match message {
+        Message::RunCommandPrompt => "Would you like to run the following command?",
+        Message::NetworkAccessPrompt => "Do you want to approve network access to \"{host}\"?",
+        Message::EditFilesPrompt => "Would you like to make the following edits?",
+        Message::ElicitationPrompt => "{server} needs your approval.",
+        Message::ApprovalReason => "Reason: ",
+        Message::ElicitationServer => "Server: ",
+        Message::ApproveOnce => "Yes, just this once",
+        Message::ApproveHostForSession => "Yes, and allow this host for this session",
+        Message::ApproveProceed => "Yes, proceed",
+        Message::ApproveCommandPrefix => {
+            "Yes, and don't ask again for commands that start with `{prefix}`"
+        }
+        Message::ApproveFilesForSession => "Yes, and don't ask again for these files",
+        Message::DenyAndExplain => "No, and tell Codex what to do differently",
+        Message::ProvideRequestedInfo => "Yes, provide the requested info",
+        Message::ContinueWithoutInfo => "No, but continue without it",
+        Message::CancelRequest => "Cancel this request",
+        Message::ShellAborted => "Wall time: {secs} seconds\naborted by user",
+        Message::ToolAborted => "aborted by user after {secs}s",
+        Message::CommandAborted => "command aborted by user",
+        Message::ExecRejected => "exec command rejected by user",
+        Message::PatchRejected => "patch rejected by user",
+        Message::ReviewInterrupted => {
+            "Review was interrupted. Please re-run /review and wait for it to complete."
+        }
+    }
+}
+
+fn spanish(message: Message) -> &'static str {
+    match message {
+        Message::RunCommandPrompt => "¿Quieres ejecutar el siguiente comando?",
+        Message::NetworkAccessPrompt => "¿Quieres aprobar el acceso de red a \"{host}\"?",
+        Message::EditFilesPrompt => "¿Quieres realizar las siguientes ediciones?",
+        Message::ElicitationPrompt => "{server} necesita tu aprobación.",
+        Message::ApprovalReason => "Motivo: ",
+        Message::ElicitationServer => "Servidor: ",
+        Message::ApproveOnce => "Sí, solo esta vez",
+        Message::ApproveHostForSession => "Sí, y permitir este host durante esta sesión",
+        Message::ApproveProceed => "Sí, continuar",
+        Message::ApproveCommandPrefix => {
+            "Sí, y no volver a preguntar por comandos que empiecen por `{prefix}`"
+        }
+        Message::ApproveFilesForSession => "Sí, y no volver a preguntar por estos archivos",
+        Message::DenyAndExplain => "No, y decirle a Codex qué hacer de otra forma",
+        Message::ProvideRequestedInfo => "Sí, proporcionar la información solicitada",
+        Message::ContinueWithoutInfo => "No, pero continuar sin ella",
+        Message::CancelRequest => "Cancelar esta solicitud",
+        Message::ShellAborted => "Tiempo transcurrido: {secs} segundos\ncancelado por el usuario",
+        Message::ToolAborted => "cancelado por el usuario tras {secs} s",
+        Message::CommandAborted => "comando cancelado por el usuario",
+        Message::ExecRejected => "ejecución del comando rechazada por el usuario",
+        Message::PatchRejected => "parche rechazado por el usuario",
+        Message::ReviewInterrupted => {
+            "La revisión se interrumpió. Vuelve a ejecutar /review y espera a que termine."
+        }
+    }
+}
+
+fn french(message: Message) -> &'static str {
+    match message {
+        Message::RunCommandPrompt => "Voulez-vous exécuter la commande suivante ?",
+        Message::NetworkAccessPrompt => "Voulez-vous autoriser l'accès réseau à « {host} » ?",
+        Message::EditFilesPrompt => "Voulez-vous effectuer les modifications suivantes ?",
+        Message::ElicitationPrompt => "{server} a besoin de votre approbation.",
+        Message::ApprovalReason => "Raison : ",
+        Message::ElicitationServer => "Serveur : ",
+        Message::ApproveOnce => "Oui, juste cette fois",
+        Message::ApproveHostForSession => "Oui, et autoriser cet hôte pour cette session",
+        Message::ApproveProceed => "Oui, continuer",
+        Message::ApproveCommandPrefix => {
+            "Oui, et ne plus demander pour les commandes commençant par `{prefix}`"
+        }
+        Message::ApproveFilesForSession => "Oui, et ne plus demander pour ces fichiers",
+        Message::DenyAndExplain => "Non, et indiquer à Codex quoi faire autrement",
+        Message::ProvideRequestedInfo => "Oui, fournir les informations demandées",
+        Message::ContinueWithoutInfo => "Non, mais continuer sans",
+        Message::CancelRequest => "Annuler cette demande",
+        Message::ShellAborted => "Durée : {secs} secondes\ninterrompu par l'utilisateur",
+        Message::ToolAborted => "interrompu par l'utilisateur après {secs} s",
+        Message::CommandAborted => "commande interrompue par l'utilisateur",
+        Message::ExecRejected => "exécution de la commande refusée par l'utilisateur",
+        Message::PatchRejected => "correctif refusé par l'utilisateur",
+        Message::ReviewInterrupted => {
+            "La revue a été interrompue. Relancez /review et attendez qu'elle se termine."
+        }
+    }
+}
+
+fn german(message: Message) -> &'static str {
+    match message {
+        Message::RunCommandPrompt => "Möchten Sie den folgenden Befehl ausführen?",
+        Message::NetworkAccessPrompt => "Möchten Sie den Netzwerkzugriff auf „{host}“ erlauben?",
+        Message::EditFilesPrompt => "Möchten Sie die folgenden Änderungen vornehmen?",
+        Message::ElicitationPrompt => "{server} benötigt Ihre Zustimmung.",
+        Message::ApprovalReason => "Grund: ",
+        Message::ElicitationServer => "Server: ",
+        Message::ApproveOnce => "Ja, nur dieses Mal",
+        Message::ApproveHostForSession => "Ja, und diesen Host für diese Sitzung erlauben",
+        Message::ApproveProceed => "Ja, fortfahren",
+        Message::ApproveCommandPrefix => {
+            "Ja, und bei Befehlen, die mit `{prefix}` beginnen, nicht mehr fragen"
+        }
+        Message::ApproveFilesForSession => "Ja, und bei diesen Dateien nicht mehr fragen",
+        Message::DenyAndExplain => "Nein, und Codex sagen, was anders gemacht werden soll",
+        Message::ProvideRequestedInfo => "Ja, die angeforderten Informationen bereitstellen",
+        Message::ContinueWithoutInfo => "Nein, aber ohne sie fortfahren",
+        Message::CancelRequest => "Diese Anfrage abbrechen",
+        Message::ShellAborted => "Laufzeit: {secs} Sekunden\nvom Benutzer abgebrochen",
+        Message::ToolAborted => "nach {secs} s vom Benutzer abgebrochen",
+        Message::CommandAborted => "Befehl vom Benutzer abgebrochen",
+        Message::ExecRejected => "Befehlsausführung vom Benutzer abgelehnt",
+        Message::PatchRejected => "Patch vom Benutzer abgelehnt",
+        Message::ReviewInterrupted => {
+            "Das Review wurde unterbrochen. Führen Sie /review erneut aus und warten Sie, bis es abgeschlossen ist."
+        }
+    }
+}
+
+fn japanese(message: Message) -> &'static str {
+    match message {
+        Message::RunCommandPrompt => "次のコマンドを実行しますか？",
+        Message::NetworkAccessPrompt => "\"{host}\" へのネットワークアクセスを許可しますか？",
+        Message::EditFilesPrompt => "次の編集を行いますか？",
+        Message::ElicitationPrompt => "{server} があなたの承認を求めています。",
+        Message::ApprovalReason => "理由: ",
+        Message::ElicitationServer => "サーバー: ",
+        Message::ApproveOnce => "はい、今回のみ",
+        Message::ApproveHostForSession => "はい、このセッション中はこのホストを許可",
+        Message::ApproveProceed => "はい、続行",
+        Message::ApproveCommandPrefix => "はい、`{prefix}` で始まるコマンドは今後確認しない",
+        Message::ApproveFilesForSession => "はい、これらのファイルは今後確認しない",
+        Message::DenyAndExplain => "いいえ、Codex に別のやり方を伝える",
+        Message::ProvideRequestedInfo => "はい、要求された情報を提供",
+        Message::ContinueWithoutInfo => "いいえ、情報なしで続行",
+        Message::CancelRequest => "このリクエストをキャンセル",
+        Message::ShellAborted => "経過時間: {secs} 秒\nユーザーにより中止されました",
+        Message::ToolAborted => "{secs} 秒後にユーザーにより中止されました",
+        Message::CommandAborted => "コマンドはユーザーにより中止されました",
+        Message::ExecRejected => "コマンドの実行はユーザーにより拒否されました",
+        Message::PatchRejected => "パッチはユーザーにより拒否されました",
+        Message::ReviewInterrupted => {
+            "レビューが中断されました。/review を再実行し、完了するまでお待ちください。"
+        }
+    }
+}
+
+fn simplified_chinese(message: Message) -> &'static str {
+    match message {
+        Message::RunCommandPrompt => "要运行以下命令吗？",
+        Message::NetworkAccessPrompt => "是否允许访问 \"{host}\" 的网络？",
+        Message::EditFilesPrompt => "要进行以下编辑吗？",
+        Message::ElicitationPrompt => "{server} 需要你的批准。",
+        Message::ApprovalReason => "原因：",
+        Message::ElicitationServer => "服务器：",
+        Message::ApproveOnce => "是，仅此一次",
+        Message::ApproveHostForSession => "是，并在本次会话中允许此主机",
+        Message::ApproveProceed => "是，继续",
+        Message::ApproveCommandPrefix => "是，以 `{prefix}` 开头的命令不再询问",
+        Message::ApproveFilesForSession => "是，这些文件不再询问",
+        Message::DenyAndExplain => "否，并告诉 Codex 应该怎么做",
+        Message::ProvideRequestedInfo => "是，提供所请求的信息",
+        Message::ContinueWithoutInfo => "否，但不提供信息继续",
+        Message::CancelRequest => "取消此请求",
+        Message::ShellAborted => "运行时间：{secs} 秒\n已被用户中止",
+        Message::ToolAborted => "{secs} 秒后被用户中止",
+        Message::CommandAborted => "命令已被用户中止",
+        Message::ExecRejected => "命令执行被用户拒绝",
+        Message::PatchRejected => "补丁被用户拒绝",
+        Message::ReviewInterrupted => "审查已中断。请重新运行 /review 并等待其完成。",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn format_fills_placeholders_in_the_selected_locale() {
+        assert_eq!(
+            Locale::English.format(Message::NetworkAccessPrompt, &[("host", "example.com")]),
+            "Do you want to approve network access to \"example.com\"?"
+        );
+        assert_eq!(
+            Locale::Spanish.format(Message::ToolAborted, &[("secs", "1.5")]),
+            "cancelado por el usuario tras 1.5 s"
+        );
+    }
+
+    #[test]
+    fn locale_deserializes_from_language_tags() {
+        let locales: Vec<Locale> =
+            serde_json::from_str(r#"["en", "es", "fr", "de", "ja", "zh-CN"]"#)
+                .expect("valid locales");
+        assert_eq!(
+            locales,
+            vec![
+                Locale::English,
+                Locale::Spanish,
+                Locale::French,
+                Locale::German,
+                Locale::Japanese,
+                Locale::SimplifiedChinese,
+            ]
+        );
+    }
+}
@@ -0,0 +1,163 @@
+//! Remembered answers to the prompt offering to install the MCP servers that
+//! mentioned skills depend on.
+//!
+//! Each answer is kept per project under
+//! `<codex_home>/mcp_dependency_decisions`, keyed by the dependency's
+//! canonical key, along with the version the skill declared at the time. A
+//! dependency is offered again only once its version changes, unless the user
+//! asked never to be asked about it.
+//!
+//! Each update holds a lock on a sidecar `.lock` file for its
+//! read-modify-write, so concurrent sessions do not lose each other's
+//! answers, and replaces the file atomically, so a crash mid-write cannot
+//! leave it truncated.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::path_utils::write_atomically;
+use crate::suggested_allowlist::project_file_path;
+
+const MCP_DEPENDENCY_DECISIONS_SUBDIR: &str = "mcp_dependency_decisions";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum McpDependencyChoice {
+    Install,
+    Skip,
+    NeverAsk,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct McpDependencyDecision {
+    pub(crate) choice: McpDependencyChoice,
+    /// Version of the dependency the user answered for, if the skill declared
+    /// one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) version: Option<String>,
+}
+
+impl McpDependencyDecision {
+    /// Whether this answer still stands for the dependency at `version`.
+    pub(crate) fn covers(&self, version: Option<&str>) -> bool {
+        self.choice == McpDependencyChoice::NeverAsk || self.version.as_deref() == version
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct McpDependencyDecisionsFile {
+    decisions: BTreeMap<String, McpDependencyDecision>,
+}
+
+/// The answers remembered for `project_root`. Blocks on the file system.
+pub(crate) fn load(
+    codex_home: &Path,
+    project_root: &Path,
+) -> io::Result<HashMap<String, McpDependencyDecision>> {
+    let path = project_file_path(codex_home, MCP_DEPENDENCY_DECISIONS_SUBDIR, project_root);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(HashMap::new()),
+        Err(err) => return Err(err),
+    };
+    if contents.trim().is_empty() {
+        return Ok(HashMap::new());
+    }
+    let file: McpDependencyDecisionsFile = serde_json::from_str(&contents)?;
+    Ok(file.decisions.into_iter().collect())
+}
+
+/// Remembers `decisions` for `project_root`, replacing earlier answers for
+/// the same dependencies. Blocks on the file system.
+pub(crate) fn record(
+    codex_home: &Path,
+    project_root: &Path,
+    decisions: HashMap<String, McpDependencyDecision>,
+) -> io::Result<()> {
+    let path = project_file_path(codex_home, MCP_DEPENDENCY_DECISIONS_SUBDIR, project_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let lock = File::options()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(path.with_extension("lock"))?;
+    lock.lock()?;
+    let mut remembered = McpDependencyDecisionsFile {
+        decisions: load(codex_home, project_root)?.into_iter().collect(),
+    };
+    remembered.decisions.extend(decisions);
+    write_atomically(&path, &serde_json::to_string_pretty(&remembered)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn decisions_are_remembered_per_project() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let project = Path::new("/work/project");
+        let decision = |choice, version: Option<&str>| McpDependencyDecision {
+            choice,
+            version: version.map(str::to_string),
+        };
+
+        record(
+            codex_home.path(),
+            project,
+            HashMap::from([
+                (
+                    "mcp__stdio__gh-mcp".to_string(),
+                    decision(McpDependencyChoice::Skip, Some("1.0.0")),
+                ),
+                (
+                    "mcp__streamable_http__https://example.com/mcp".to_string(),
+                    decision(McpDependencyChoice::NeverAsk, None),
+                ),
+            ]),
+        )
+        .expect("record");
+        record(
+            codex_home.path(),
+            project,
+            HashMap::from([(
+                "mcp__stdio__gh-mcp".to_string(),
+                decision(McpDependencyChoice::Install, Some("1.1.0")),
+            )]),
+        )
+        .expect("record");
+
+        let decisions = load(codex_home.path(), project).expect("load");
+        assert_eq!(
+            decisions,
+            HashMap::from([
+                (
+                    "mcp__stdio__gh-mcp".to_string(),
+                    decision(McpDependencyChoice::Install, Some("1.1.0")),
+                ),
+                (
+                    "mcp__streamable_http__https://example.com/mcp".to_string(),
+                    decision(McpDependencyChoice::NeverAsk, None),
+                ),
+            ])
+        );
+        assert_eq!(
+            load(codex_home.path(), Path::new("/work/other")).expect("load"),
+            HashMap::new()
+        );
+
+        let installed = &decisions["mcp__stdio__gh-mcp"];
+        assert!(installed.covers(Some("1.1.0")));
+        assert!(!installed.covers(Some("2.0.0")));
+        assert!(decisions["mcp__streamable_http__https://example.com/mcp"].covers(Some("2.0.0")));
+    }
+}
@@ -1,4 +1,6 @@
 pub mod auth;
+pub(crate) mod dependency_decisions;
+pub(crate) mod server_processes;
 mod skill_dependencies;
 pub(crate) use skill_dependencies::maybe_prompt_and_install_mcp_dependencies;
 
@@ -20,6 +22,8 @@ use crate::CodexAuth;
 use crate::config::Config;
 use crate::config::types::McpServerConfig;
 use crate::config::types::McpServerTransportConfig;
+use crate::exec_env::apply_env_policy_to_mcp_servers;
+use crate::exec_env::resolve_secret_from_store;
 use crate::features::Feature;
 use crate::mcp::auth::compute_auth_statuses;
 use crate::mcp_connection_manager::McpConnectionManager;
@@ -133,9 +137,12 @@ fn codex_apps_mcp_server_config(config: &Config, auth: Option<&CodexAuth>) -> Mc
         },
         enabled: true,
         required: false,
+        lazy: false,
         disabled_reason: None,
         startup_timeout_sec: Some(Duration::from_secs(30)),
         tool_timeout_sec: None,
+        elicitation: false,
+        elicitation_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
         scopes: None,
@@ -163,12 +170,18 @@ pub(crate) fn effective_mcp_servers(
     config: &Config,
     auth: Option<&CodexAuth>,
 ) -> HashMap<String, McpServerConfig> {
-    with_codex_apps_mcp(
+    let mut servers = with_codex_apps_mcp(
         config.mcp_servers.get().clone(),
         config.features.enabled(Feature::Apps),
         auth,
         config,
-    )
+    );
+    apply_env_policy_to_mcp_servers(
+        &mut servers,
+        &config.permissions.shell_environment_policy,
+        |secret_name| resolve_secret_from_store(&config.codex_home, &config.cwd, secret_name),
+    );
+    servers
 }
 
 pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent {
@@ -211,6 +224,7 @@ pub async fn collect_mcp_snapshot(config: &Config) -> McpListToolsResponseEvent
         sandbox_state,
         config.codex_home.clone(),
         codex_apps_tools_cache_key(auth.as_ref()),
+        config.mcp_server_limits,
     )
     .await;
 
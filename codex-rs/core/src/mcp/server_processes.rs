@@ -0,0 +1,243 @@
+//! Records of the stdio MCP server processes sessions have spawned, so
+//! servers orphaned by a crashed session can be cleaned up.
+//!
+//! Each server process on Unix, where it leads a process group of its own,
+//! gets a record in `<codex_home>/mcp_processes/` naming the Codex process
+//! that owns it and its process group, which the owner removes once the
+//! server stops. A record that outlives its owner means the owner died
+//! without stopping the server: the next session to start kills what is left
+//! of the server's process group and removes the record.
+//!
+//! A process group is only killed when it is certainly the server's. A group
+//! whose leader has exited cannot have been reused, since process IDs are not
+//! reallocated while a group of that ID exists. A live leader is checked
+//! against the start time recorded for it, which is only known on Linux;
+//! elsewhere a server whose leader is still running is left alone.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::info;
+use tracing::warn;
+
+const SERVER_PROCESSES_DIR: &str = "mcp_processes";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct ServerProcessRecord {
+    server: String,
+    owner_pid: u32,
+    process_group_id: u32,
+    /// Start time of the group leader, in clock ticks since boot (Linux).
+    #[serde(default)]
+    leader_start_ticks: Option<u64>,
+}
+
+/// Removes the record of a server process when dropped, once the server has
+/// stopped.
+#[derive(Debug)]
+pub(crate) struct ServerProcessGuard {
+    path: PathBuf,
+}
+
+impl Drop for ServerProcessGuard {
+    fn drop(&mut self) {
+        if let Err(err) = fs::remove_file(&self.path)
+            && err.kind() != io::ErrorKind::NotFound
+        {
+            warn!("failed to remove {}: {err}", self.path.display());
+        }
+    }
+}
+
+/// Records that this process spawned MCP server `server`, the leader of
+/// process group `process_group_id`. Blocks on the file system.
+pub(crate) fn record_server_process(
+    codex_home: &Path,
+    server: &str,
+    process_group_id: u32,
+) -> io::Result<ServerProcessGuard> {
+    let dir = codex_home.join(SERVER_PROCESSES_DIR);
+    fs::create_dir_all(&dir)?;
+    let path = dir.join(format!("{process_group_id}.json"));
+    let record = ServerProcessRecord {
+        server: server.to_string(),
+        owner_pid: std::process::id(),
+        process_group_id,
+        leader_start_ticks: process_start_ticks(process_group_id),
+    };
+    fs::write(&path, serde_json::to_vec(&record)?)?;
+    Ok(ServerProcessGuard { path })
+}
+
+/// Kills the servers whose owning session is gone and removes their records.
+/// Blocks on the file system.
+pub(crate) fn clean_up_orphaned_servers(codex_home: &Path) {
+    let entries = match fs::read_dir(codex_home.join(SERVER_PROCESSES_DIR)) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return,
+        Err(err) => {
+            warn!("failed to list MCP server process records: {err}");
+            return;
+        }
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Some(record) = fs::read(&path)
+            .ok()
+            .and_then(|contents| serde_json::from_slice::<ServerProcessRecord>(&contents).ok())
+        else {
+            warn!(
+                "ignoring unreadable MCP server process record {}",
+                path.display()
+            );
+            continue;
+        };
+        if process_exists(record.owner_pid) {
+            continue;
+        }
+        if server_still_running(&record) {
+            info!(
+                "killing MCP server `{}` (process group {}) orphaned by session process {}",
+                record.server, record.process_group_id, record.owner_pid
+            );
+            if let Err(err) =
+                codex_utils_pty::process_group::kill_process_group(record.process_group_id)
+            {
+                warn!(
+                    "failed to kill orphaned MCP server `{}`: {err}",
+                    record.server
+                );
+                continue;
+            }
+        }
+        if let Err(err) = fs::remove_file(&path) {
+            warn!("failed to remove {}: {err}", path.display());
+        }
+    }
+}
+
+/// Whether any process of the server's group is still running.
+fn server_still_running(record: &ServerProcessRecord) -> bool {
+    if !process_group_exists(record.process_group_id) {
+        return false;
+    }
+    if !process_exists(record.process_group_id) {
+        return true;
+    }
+    match (
+        record.leader_start_ticks,
+        process_start_ticks(record.process_group_id),
+    ) {
+        (Some(recorded), Some(current)) => recorded == current,
+        _ => false,
+    }
+}
+
+#[cfg(unix)]
+fn process_exists(pid: u32) -> bool {
+    signal_zero(pid as libc::pid_t)
+}
+
+#[cfg(unix)]
+fn process_group_exists(process_group_id: u32) -> bool {
+    signal_zero(-(process_group_id as libc::pid_t))
+}
+
+/// Whether `kill(pid, 0)` finds a process, including one this process may
+/// not signal.
+#[cfg(unix)]
+fn signal_zero(pid: libc::pid_t) -> bool {
+    let result = unsafe { libc::kill(pid, 0) };
+    result == 0 || io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+fn process_exists(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(not(unix))]
+fn process_group_exists(_process_group_id: u32) -> bool {
+    false
+}
+
+#[cfg(target_os = "linux")]
+fn process_start_ticks(pid: u32) -> Option<u64> {
+    let stat = fs::read_to_string(format!("/proc/{pid}/stat")).ok()?;
+    // The command name in parentheses may contain spaces; `starttime` is
+    // the 20th field after it.
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(19)?.parse().ok()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn process_start_ticks(_pid: u32) -> Option<u64> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn records_server_processes_until_their_guard_drops() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let guard = record_server_process(codex_home.path(), "docs", 4242).expect("record");
+
+        let path = codex_home
+            .path()
+            .join(SERVER_PROCESSES_DIR)
+            .join("4242.json");
+        let record: ServerProcessRecord =
+            serde_json::from_slice(&fs::read(&path).expect("read")).expect("parse");
+        assert_eq!(
+            record,
+            ServerProcessRecord {
+                server: "docs".to_string(),
+                owner_pid: std::process::id(),
+                process_group_id: 4242,
+                leader_start_ticks: process_start_ticks(4242),
+            }
+        );
+
+        // Records of a live owner are left alone.
+        clean_up_orphaned_servers(codex_home.path());
+        assert!(path.exists());
+
+        drop(guard);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn cleans_up_records_of_dead_owners() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let exited_pid = || {
+            let mut child = std::process::Command::new("true").spawn().expect("spawn");
+            child.wait().expect("wait");
+            child.id()
+        };
+        let dir = codex_home.path().join(SERVER_PROCESSES_DIR);
+        fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("1.json");
+        fs::write(
+            &path,
+            serde_json::to_vec(&ServerProcessRecord {
+                server: "docs".to_string(),
+                owner_pid: exited_pid(),
+                process_group_id: exited_pid(),
+                leader_start_ticks: None,
+            })
+            .expect("serialize"),
+        )
+        .expect("write");
+
+        clean_up_orphaned_servers(codex_home.path());
+        assert!(!path.exists());
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::path::Path;
 
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::SandboxPolicy;
@@ -13,6 +14,9 @@ use tracing::warn;
 
 use super::auth::McpOAuthLoginSupport;
 use super::auth::oauth_login_support;
+use super::dependency_decisions;
+use super::dependency_decisions::McpDependencyChoice;
+use super::dependency_decisions::McpDependencyDecision;
 use super::effective_mcp_servers;
 use crate::codex::Session;
 use crate::codex::TurnContext;
@@ -26,10 +30,20 @@ use crate::default_client::originator;
 use crate::features::Feature;
 use crate::skills::SkillMetadata;
 use crate::skills::model::SkillToolDependency;
+use crate::suggested_allowlist::project_root;
 
 const SKILL_MCP_DEPENDENCY_PROMPT_ID: &str = "skill_mcp_dependency_install";
 const MCP_DEPENDENCY_OPTION_INSTALL: &str = "Install";
 const MCP_DEPENDENCY_OPTION_SKIP: &str = "Continue anyway";
+const MCP_DEPENDENCY_OPTION_NEVER_ASK: &str = "Never ask";
+
+/// An MCP server a mentioned skill depends on that is not installed.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct MissingMcpDependency {
+    pub(crate) config: McpServerConfig,
+    /// Version of the server the skill declares, if any.
+    pub(crate) version: Option<String>,
+}
 
 fn is_full_access_mode(turn_context: &TurnContext) -> bool {
     matches!(turn_context.approval_policy.value(), AskForApproval::Never)
@@ -39,64 +53,88 @@ fn is_full_access_mode(turn_context: &TurnContext) -> bool {
         )
 }
 
-fn format_missing_mcp_dependencies(missing: &HashMap<String, McpServerConfig>) -> String {
-    let mut names = missing.keys().cloned().collect::<Vec<_>>();
-    names.sort();
-    names.join(", ")
-}
-
-async fn filter_prompted_mcp_dependencies(
+/// The missing dependencies the user has not answered the install prompt
+/// for, in this session or earlier in this project, at their current version.
+async fn undecided_mcp_dependencies(
     sess: &Session,
-    missing: &HashMap<String, McpServerConfig>,
-) -> HashMap<String, McpServerConfig> {
-    let prompted = sess.mcp_dependency_prompted().await;
-    if prompted.is_empty() {
-        return missing.clone();
-    }
+    codex_home: &Path,
+    project_root: &Path,
+    missing: &HashMap<String, MissingMcpDependency>,
+) -> HashMap<String, MissingMcpDependency> {
+    let load = {
+        let codex_home = codex_home.to_path_buf();
+        let project_root = project_root.to_path_buf();
+        tokio::task::spawn_blocking(move || dependency_decisions::load(&codex_home, &project_root))
+    };
+    let mut decisions = load
+        .await
+        .unwrap_or_else(|err| Err(std::io::Error::other(err)))
+        .unwrap_or_else(|err| {
+            warn!("failed to load remembered MCP dependency decisions: {err}");
+            HashMap::new()
+        });
+    decisions.extend(sess.mcp_dependency_decisions().await);
 
     missing
         .iter()
-        .filter(|(name, config)| !prompted.contains(&canonical_mcp_server_key(name, config)))
-        .map(|(name, config)| (name.clone(), config.clone()))
+        .filter(|(name, dependency)| {
+            !decisions
+                .get(&canonical_mcp_server_key(name, &dependency.config))
+                .is_some_and(|decision| decision.covers(dependency.version.as_deref()))
+        })
+        .map(|(name, dependency)| (name.clone(), dependency.clone()))
         .collect()
 }
 
-async fn should_install_mcp_dependencies(
+/// Asks about all of `undecided` in one prompt, one question per server, and
+/// returns the answers given.
+async fn prompt_for_mcp_dependencies(
     sess: &Session,
     turn_context: &TurnContext,
-    missing: &HashMap<String, McpServerConfig>,
+    undecided: &HashMap<String, MissingMcpDependency>,
     cancellation_token: &CancellationToken,
-) -> bool {
-    if is_full_access_mode(turn_context) {
-        return true;
-    }
-
-    let server_list = format_missing_mcp_dependencies(missing);
-    let question = RequestUserInputQuestion {
-        id: SKILL_MCP_DEPENDENCY_PROMPT_ID.to_string(),
-        header: "Install MCP servers?".to_string(),
-        question: format!(
-            "The following MCP servers are required by the selected skills but are not installed yet: {server_list}. Install them now?"
-        ),
-        is_other: false,
-        is_secret: false,
-        options: Some(vec![
-            RequestUserInputQuestionOption {
-                label: MCP_DEPENDENCY_OPTION_INSTALL.to_string(),
-                description:
-                    "Install and enable the missing MCP servers in your global config."
-                        .to_string(),
-            },
-            RequestUserInputQuestionOption {
-                label: MCP_DEPENDENCY_OPTION_SKIP.to_string(),
-                description: "Skip installation for now and do not show again for these MCP servers in this session."
-                    .to_string(),
-            },
-        ]),
-    };
-    let args = RequestUserInputArgs {
-        questions: vec![question],
-    };
+) -> HashMap<String, McpDependencyChoice> {
+    let mut names = undecided.keys().cloned().collect::<Vec<_>>();
+    names.sort();
+    let questions = names
+        .iter()
+        .map(|name| {
+            let version = undecided
+                .get(name)
+                .and_then(|dependency| dependency.version.as_deref())
+                .map(|version| format!(" ({version})"))
+                .unwrap_or_default();
+            RequestUserInputQuestion {
+                id: format!("{SKILL_MCP_DEPENDENCY_PROMPT_ID}:{name}"),
+                header: "Install MCP server?".to_string(),
+                question: format!(
+                    "The MCP server {name}{version} is required by the selected skills but is not installed yet. Install it now?"
+                ),
+                is_other: false,
+                is_secret: false,
+                options: Some(vec![
+                    RequestUserInputQuestionOption {
+                        label: MCP_DEPENDENCY_OPTION_INSTALL.to_string(),
+                        description:
+                            "Install and enable this MCP server in your global config."
+                                .to_string(),
+                    },
+                    RequestUserInputQuestionOption {
+                        label: MCP_DEPENDENCY_OPTION_SKIP.to_string(),
+                        description: "Skip installation and do not ask again in this project until the skills need another version."
+                            .to_string(),
+                    },
+                    RequestUserInputQuestionOption {
+                        label: MCP_DEPENDENCY_OPTION_NEVER_ASK.to_string(),
+                        description:
+                            "Skip installation and never ask again about this MCP server in this project."
+                                .to_string(),
+                    },
+                ]),
+            }
+        })
+        .collect();
+    let args = RequestUserInputArgs { questions };
     let sub_id = &turn_context.sub_id;
     let call_id = format!("mcp-deps-{sub_id}");
     let response_fut = sess.request_user_input(turn_context, call_id, args);
@@ -114,22 +152,24 @@ async fn should_install_mcp_dependencies(
         }),
     };
 
-    let install = response
-        .answers
-        .get(SKILL_MCP_DEPENDENCY_PROMPT_ID)
-        .is_some_and(|answer| {
-            answer
+    names
+        .into_iter()
+        .filter_map(|name| {
+            let answer = response
+                .answers
+                .get(&format!("{SKILL_MCP_DEPENDENCY_PROMPT_ID}:{name}"))?;
+            let choice = answer
                 .answers
                 .iter()
-                .any(|entry| entry == MCP_DEPENDENCY_OPTION_INSTALL)
-        });
-
-    let prompted_keys = missing
-        .iter()
-        .map(|(name, config)| canonical_mcp_server_key(name, config));
-    sess.record_mcp_dependency_prompted(prompted_keys).await;
-
-    install
+                .find_map(|entry| match entry.as_str() {
+                    MCP_DEPENDENCY_OPTION_INSTALL => Some(McpDependencyChoice::Install),
+                    MCP_DEPENDENCY_OPTION_SKIP => Some(McpDependencyChoice::Skip),
+                    MCP_DEPENDENCY_OPTION_NEVER_ASK => Some(McpDependencyChoice::NeverAsk),
+                    _ => None,
+                })?;
+            Some((name, choice))
+        })
+        .collect()
 }
 
 pub(crate) async fn maybe_prompt_and_install_mcp_dependencies(
@@ -155,56 +195,111 @@ pub(crate) async fn maybe_prompt_and_install_mcp_dependencies(
         return;
     }
 
-    let unprompted_missing = filter_prompted_mcp_dependencies(sess, &missing).await;
-    if unprompted_missing.is_empty() {
+    let project_root = project_root(&turn_context.cwd);
+    let undecided =
+        undecided_mcp_dependencies(sess, &config.codex_home, &project_root, &missing).await;
+    if undecided.is_empty() {
         return;
     }
 
-    if should_install_mcp_dependencies(sess, turn_context, &unprompted_missing, cancellation_token)
+    if is_full_access_mode(turn_context) {
+        let to_install = undecided
+            .into_iter()
+            .map(|(name, dependency)| (name, dependency.config))
+            .collect();
+        install_mcp_dependencies(sess, turn_context, config.as_ref(), to_install).await;
+        return;
+    }
+
+    let choices =
+        prompt_for_mcp_dependencies(sess, turn_context, &undecided, cancellation_token).await;
+    // Questions left unanswered are not asked again in this session, but are
+    // not remembered for the project either.
+    let decision = |name: &str, choice: McpDependencyChoice| {
+        let dependency = &undecided[name];
+        (
+            canonical_mcp_server_key(name, &dependency.config),
+            McpDependencyDecision {
+                choice,
+                version: dependency.version.clone(),
+            },
+        )
+    };
+    sess.record_mcp_dependency_decisions(undecided.keys().map(|name| {
+        decision(
+            name,
+            choices
+                .get(name)
+                .copied()
+                .unwrap_or(McpDependencyChoice::Skip),
+        )
+    }))
+    .await;
+
+    let to_install: HashMap<String, McpServerConfig> = undecided
+        .iter()
+        .filter(|(name, _)| choices.get(*name) == Some(&McpDependencyChoice::Install))
+        .map(|(name, dependency)| (name.clone(), dependency.config.clone()))
+        .collect();
+    let installed = if to_install.is_empty() {
+        HashSet::new()
+    } else {
+        install_mcp_dependencies(sess, turn_context, config.as_ref(), to_install).await
+    };
+
+    // An install that failed is only remembered for this session, so the user
+    // is offered it again next time.
+    let decisions: HashMap<String, McpDependencyDecision> = choices
+        .iter()
+        .filter(|(name, choice)| {
+            **choice != McpDependencyChoice::Install || installed.contains(*name)
+        })
+        .map(|(name, choice)| decision(name, *choice))
+        .collect();
+    if !decisions.is_empty() {
+        let codex_home = config.codex_home.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            dependency_decisions::record(&codex_home, &project_root, decisions)
+        })
         .await
-    {
-        maybe_install_mcp_dependencies(sess, turn_context, config.as_ref(), mentioned_skills).await;
+        .unwrap_or_else(|err| Err(std::io::Error::other(err)));
+        if let Err(err) = result {
+            warn!("failed to remember MCP dependency decisions: {err}");
+        }
     }
 }
 
-pub(crate) async fn maybe_install_mcp_dependencies(
+/// Adds the servers of `missing` that are not configured yet to the global
+/// config, logs into those that need it, and restarts the session's servers.
+/// Returns the names of the servers that ended up installed.
+async fn install_mcp_dependencies(
     sess: &Session,
     turn_context: &TurnContext,
     config: &Config,
-    mentioned_skills: &[SkillMetadata],
-) {
-    if mentioned_skills.is_empty() || !config.features.enabled(Feature::SkillMcpDependencyInstall) {
-        return;
-    }
-
+    missing: HashMap<String, McpServerConfig>,
+) -> HashSet<String> {
     let codex_home = config.codex_home.clone();
-    let installed = config.mcp_servers.get().clone();
-    let missing = collect_missing_mcp_dependencies(mentioned_skills, &installed);
-    if missing.is_empty() {
-        return;
-    }
-
     let mut servers = match load_global_mcp_servers(&codex_home).await {
         Ok(servers) => servers,
         Err(err) => {
             warn!("failed to load MCP servers while installing skill dependencies: {err}");
-            return;
+            return HashSet::new();
         }
     };
 
-    let mut updated = false;
+    let mut installed = HashSet::new();
     let mut added = Vec::new();
     for (name, config) in missing {
         if servers.contains_key(&name) {
+            installed.insert(name);
             continue;
         }
         servers.insert(name.clone(), config.clone());
         added.push((name, config));
-        updated = true;
     }
 
-    if !updated {
-        return;
+    if added.is_empty() {
+        return installed;
     }
 
     if let Err(err) = ConfigEditsBuilder::new(&codex_home)
@@ -213,15 +308,19 @@ pub(crate) async fn maybe_install_mcp_dependencies(
         .await
     {
         warn!("failed to persist MCP dependencies for mentioned skills: {err}");
-        return;
+        return installed;
     }
 
     for (name, server_config) in added {
         let oauth_config = match oauth_login_support(&server_config.transport).await {
             McpOAuthLoginSupport::Supported(config) => config,
-            McpOAuthLoginSupport::Unsupported => continue,
+            McpOAuthLoginSupport::Unsupported => {
+                installed.insert(name);
+                continue;
+            }
             McpOAuthLoginSupport::Unknown(err) => {
                 warn!("MCP server may or may not require login for dependency {name}: {err}");
+                installed.insert(name);
                 continue;
             }
         };
@@ -247,6 +346,8 @@ pub(crate) async fn maybe_install_mcp_dependencies(
         .await
         {
             warn!("failed to login to MCP dependency {name}: {err}");
+        } else {
+            installed.insert(name);
         }
     }
 
@@ -265,6 +366,7 @@ pub(crate) async fn maybe_install_mcp_dependencies(
         config.mcp_oauth_credentials_store_mode,
     )
     .await;
+    installed
 }
 
 fn canonical_mcp_key(transport: &str, identifier: &str, fallback: &str) -> String {
@@ -309,7 +411,7 @@ fn canonical_mcp_dependency_key(dependency: &SkillToolDependency) -> Result<Stri
 pub(crate) fn collect_missing_mcp_dependencies(
     mentioned_skills: &[SkillMetadata],
     installed: &HashMap<String, McpServerConfig>,
-) -> HashMap<String, McpServerConfig> {
+) -> HashMap<String, MissingMcpDependency> {
     let mut missing = HashMap::new();
     let installed_keys: HashSet<String> = installed
         .iter()
@@ -355,7 +457,13 @@ pub(crate) fn collect_missing_mcp_dependencies(
                 }
             };
 
-            missing.insert(tool.value.clone(), config);
+            missing.insert(
+                tool.value.clone(),
+                MissingMcpDependency {
+                    config,
+                    version: tool.version.clone(),
+                },
+            );
             seen_canonical_keys.insert(dependency_key);
         }
     }
@@ -381,9 +489,12 @@ fn mcp_dependency_to_server_config(
             },
             enabled: true,
             required: false,
+            lazy: false,
             disabled_reason: None,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
@@ -405,9 +516,12 @@ fn mcp_dependency_to_server_config(
             },
             enabled: true,
             required: false,
+            lazy: false,
             disabled_reason: None,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
@@ -449,6 +563,9 @@ mod tests {
             transport: Some("streamable_http".to_string()),
             command: None,
             url: Some(url.clone()),
+            version: None,
+            install: None,
+            check: None,
         }])];
         let installed = HashMap::from([(
             "alias".to_string(),
@@ -461,9 +578,12 @@ mod tests {
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -487,6 +607,9 @@ mod tests {
                 transport: Some("streamable_http".to_string()),
                 command: None,
                 url: Some(url.clone()),
+                version: Some("1.0.0".to_string()),
+                install: None,
+                check: None,
             },
             SkillToolDependency {
                 r#type: "mcp".to_string(),
@@ -495,26 +618,35 @@ mod tests {
                 transport: Some("streamable_http".to_string()),
                 command: None,
                 url: Some(url.clone()),
+                version: None,
+                install: None,
+                check: None,
             },
         ])];
 
         let expected = HashMap::from([(
             "alias-one".to_string(),
-            McpServerConfig {
-                transport: McpServerTransportConfig::StreamableHttp {
-                    url,
-                    bearer_token_env_var: None,
-                    http_headers: None,
-                    env_http_headers: None,
+            MissingMcpDependency {
+                config: McpServerConfig {
+                    transport: McpServerTransportConfig::StreamableHttp {
+                        url,
+                        bearer_token_env_var: None,
+                        http_headers: None,
+                        env_http_headers: None,
+                    },
+                    enabled: true,
+                    required: false,
+                    lazy: false,
+                    disabled_reason: None,
+                    startup_timeout_sec: None,
+                    tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    scopes: None,
                 },
-                enabled: true,
-                required: false,
-                disabled_reason: None,
-                startup_timeout_sec: None,
-                tool_timeout_sec: None,
-                enabled_tools: None,
-                disabled_tools: None,
-                scopes: None,
+                version: Some("1.0.0".to_string()),
             },
         )]);
 
@@ -6,10 +6,12 @@
 //! in a single aggregated map using the fully-qualified tool name
 //! `"<server><MCP_TOOL_NAME_DELIMITER><tool>"` as the key.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::env;
 use std::ffi::OsString;
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 use std::sync::Mutex as StdMutex;
@@ -20,6 +22,9 @@ use std::time::Instant;
 
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::mcp::auth::McpAuthStatusEntry;
+use crate::mcp::server_processes::ServerProcessGuard;
+use crate::mcp::server_processes::clean_up_orphaned_servers;
+use crate::mcp::server_processes::record_server_process;
 use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
@@ -28,20 +33,25 @@ use codex_async_utils::CancelErr;
 use codex_async_utils::OrCancelExt;
 use codex_config::Constrained;
 use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::approvals::ElicitationTimedOutEvent;
 use codex_protocol::mcp::CallToolResult;
 use codex_protocol::mcp::RequestId as ProtocolRequestId;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::Event;
 use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::McpAuthStatus;
 use codex_protocol::protocol::McpStartupCompleteEvent;
 use codex_protocol::protocol::McpStartupFailure;
 use codex_protocol::protocol::McpStartupStatus;
 use codex_protocol::protocol::McpStartupUpdateEvent;
 use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::WarningEvent;
 use codex_rmcp_client::ElicitationResponse;
 use codex_rmcp_client::OAuthCredentialsStoreMode;
 use codex_rmcp_client::RmcpClient;
 use codex_rmcp_client::SendElicitation;
+use codex_rmcp_client::StdioServerLimits;
+use codex_rmcp_client::ToolCallNotification;
 use futures::future::BoxFuture;
 use futures::future::FutureExt;
 use futures::future::Shared;
@@ -61,6 +71,8 @@ use rmcp::model::ReadResourceResult;
 use rmcp::model::RequestId;
 use rmcp::model::Resource;
 use rmcp::model::ResourceTemplate;
+use rmcp::model::Root;
+use rmcp::model::RootsCapabilities;
 use rmcp::model::Tool;
 
 use serde::Deserialize;
@@ -68,15 +80,18 @@ use serde::Serialize;
 use sha1::Digest;
 use sha1::Sha1;
 use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::oneshot;
 use tokio::task::JoinSet;
 use tokio_util::sync::CancellationToken;
+use tracing::info;
 use tracing::instrument;
 use tracing::warn;
 use url::Url;
 
 use crate::codex::INITIAL_SUBMIT_ID;
 use crate::config::types::McpServerConfig;
+use crate::config::types::McpServerLimits;
 use crate::config::types::McpServerTransportConfig;
 use crate::connectors::is_connector_id_allowed;
 
@@ -94,8 +109,13 @@ pub const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for individual tool calls.
 const DEFAULT_TOOL_TIMEOUT: Duration = Duration::from_secs(120);
 
-const CODEX_APPS_TOOLS_CACHE_SCHEMA_VERSION: u8 = 1;
+const CODEX_APPS_TOOLS_CACHE_SCHEMA_VERSION: u8 = 2;
 const CODEX_APPS_TOOLS_CACHE_DIR: &str = "cache/codex_apps_tools";
+const MCP_TOOLS_CACHE_SCHEMA_VERSION: u8 = 2;
+const MCP_TOOLS_CACHE_DIR: &str = "cache/mcp_tools";
+/// Age past which a server's cached tool listing is re-fetched when the server
+/// starts, and a lazy server is started in the background to re-fetch it.
+const MCP_TOOLS_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
 const MCP_TOOLS_LIST_DURATION_METRIC: &str = "codex.mcp.tools.list.duration_ms";
 const MCP_TOOLS_FETCH_UNCACHED_DURATION_METRIC: &str = "codex.mcp.tools.fetch_uncached.duration_ms";
 const MCP_TOOLS_CACHE_WRITE_DURATION_METRIC: &str = "codex.mcp.tools.cache_write.duration_ms";
@@ -197,6 +217,8 @@ pub(crate) struct ToolInfo {
     pub(crate) tool: Tool,
     pub(crate) connector_id: Option<String>,
     pub(crate) connector_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) connector_version: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -222,9 +244,175 @@ impl CodexAppsToolsCacheContext {
     }
 }
 
+/// Where a server's tool listing is cached between sessions, so starting
+/// the server again skips `tools/list` and a lazy server can advertise its
+/// tools before it starts. The cache is keyed by the server's name and
+/// transport, the directory it runs in and the credentials it is reached
+/// with, so changing how the server is launched or reached, or who it is
+/// reached as, invalidates it.
+#[derive(Clone)]
+struct McpToolsCacheContext {
+    cache_path: PathBuf,
+}
+
+impl McpToolsCacheContext {
+    fn new(
+        codex_home: &Path,
+        server_name: &str,
+        transport: &McpServerTransportConfig,
+        session_cwd: &Path,
+        auth_status: Option<McpAuthStatus>,
+    ) -> Self {
+        // A stdio server without its own `cwd` runs in the session's, and
+        // may list different tools per project. Credentials read from the
+        // environment are hashed into the key, never stored.
+        let (cwd, credential_env_vars) = match transport {
+            McpServerTransportConfig::Stdio { cwd, env_vars, .. } => (
+                Some(cwd.as_deref().unwrap_or(session_cwd).to_path_buf()),
+                env_vars.iter().map(String::as_str).collect::<Vec<_>>(),
+            ),
+            McpServerTransportConfig::StreamableHttp {
+                bearer_token_env_var,
+                env_http_headers,
+                ..
+            } => {
+                let mut env_vars = bearer_token_env_var
+                    .iter()
+                    .map(String::as_str)
+                    .collect::<Vec<_>>();
+                env_vars.extend(
+                    env_http_headers
+                        .iter()
+                        .flat_map(|headers| headers.values().map(String::as_str)),
+                );
+                env_vars.sort_unstable();
+                (None, env_vars)
+            }
+        };
+        let credentials = credential_env_vars
+            .iter()
+            .map(|name| format!("{name}={}", std::env::var(name).unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut transport = serde_json::to_value(transport).unwrap_or_default();
+        // Maps such as `env` serialize in arbitrary order.
+        transport.sort_all_objects();
+        let auth = serde_json::json!({
+            "cwd": cwd,
+            "auth_status": auth_status,
+            "credentials": sha1_hex(&credentials),
+        });
+        let key = sha1_hex(&format!("{server_name}\n{transport}\n{auth}"));
+        Self {
+            cache_path: codex_home
+                .join(MCP_TOOLS_CACHE_DIR)
+                .join(format!("{key}.json")),
+        }
+    }
+
+    /// The cached listing, and whether it is old enough to need a refresh.
+    fn load(&self) -> Option<(Vec<ToolInfo>, bool)> {
+        Some((self.load_entry()?.tools, self.is_stale()))
+    }
+
+    fn load_entry(&self) -> Option<McpToolsDiskCache> {
+        let bytes = std::fs::read(&self.cache_path).ok()?;
+        let cache: McpToolsDiskCache = serde_json::from_slice(&bytes).ok()?;
+        (cache.schema_version == MCP_TOOLS_CACHE_SCHEMA_VERSION).then_some(cache)
+    }
+
+    /// Whether the listing was last confirmed too long ago to be trusted.
+    fn is_stale(&self) -> bool {
+        std::fs::metadata(&self.cache_path)
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.elapsed().ok())
+            .is_none_or(|age| age > MCP_TOOLS_CACHE_MAX_AGE)
+    }
+
+    /// Stores the tools `server_version` listed. A listing whose schemas did
+    /// not change only has its cache entry marked as confirmed now.
+    fn write(&self, server_version: &str, tools: &[ToolInfo]) {
+        let tools_hash = sha1_hex(&serde_json::to_string(tools).unwrap_or_default());
+        if let Some(cached) = self.load_entry()
+            && cached.server_version == server_version
+        {
+            if cached.tools_hash == tools_hash {
+                if let Err(err) = std::fs::File::options()
+                    .append(true)
+                    .open(&self.cache_path)
+                    .and_then(|file| file.set_modified(std::time::SystemTime::now()))
+                {
+                    warn!(
+                        "failed to touch MCP tools cache {}: {err}",
+                        self.cache_path.display()
+                    );
+                }
+                return;
+            }
+            info!(
+                "tool schemas of MCP server `{server_version}` changed; updating {}",
+                self.cache_path.display()
+            );
+        }
+        if let Some(parent) = self.cache_path.parent()
+            && std::fs::create_dir_all(parent).is_err()
+        {
+            return;
+        }
+        let Ok(bytes) = serde_json::to_vec_pretty(&McpToolsDiskCache {
+            schema_version: MCP_TOOLS_CACHE_SCHEMA_VERSION,
+            server_version: server_version.to_string(),
+            tools_hash,
+            tools: tools.to_vec(),
+        }) else {
+            return;
+        };
+        if let Err(err) = std::fs::write(&self.cache_path, bytes) {
+            warn!(
+                "failed to write MCP tools cache {}: {err}",
+                self.cache_path.display()
+            );
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct CodexAppsToolsDiskCache {
     schema_version: u8,
+    /// Version and schema hash of each connector's actions in `tools`.
+    #[serde(default)]
+    connectors: BTreeMap<String, CachedConnectorSchemas>,
+    tools: Vec<ToolInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct CachedConnectorSchemas {
+    /// Version the connector reported, if any.
+    version: Option<String>,
+    /// Hash of the connector's tools, schemas included.
+    tools_hash: String,
+}
+
+/// How the connectors in a fresh codex_apps listing differ from the cached
+/// listing.
+#[derive(Debug, Default, PartialEq, Eq)]
+struct ConnectorSchemaChanges {
+    added: Vec<String>,
+    removed: Vec<String>,
+    /// Connectors that report a new version.
+    updated: Vec<String>,
+    /// Connectors whose schemas changed while their version did not.
+    changed_without_version: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct McpToolsDiskCache {
+    schema_version: u8,
+    /// `<name>@<version>` the server reported when it listed the tools.
+    server_version: String,
+    /// Hash of the listed tools, schemas included.
+    tools_hash: String,
     tools: Vec<ToolInfo>,
 }
 
@@ -234,7 +422,16 @@ enum CachedCodexAppsToolsLoad {
     Invalid,
 }
 
-type ResponderMap = HashMap<(String, RequestId), oneshot::Sender<ElicitationResponse>>;
+type ResponderMap = HashMap<(String, RequestId), PendingElicitation>;
+
+/// How long an elicitation waits for the user's answer by default.
+const DEFAULT_ELICITATION_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+
+struct PendingElicitation {
+    responder: oneshot::Sender<ElicitationResponse>,
+    /// Schema the answers of a form request must match.
+    requested_schema: Option<serde_json::Value>,
+}
 
 fn elicitation_is_rejected_by_policy(approval_policy: AskForApproval) -> bool {
     match approval_policy {
@@ -246,6 +443,55 @@ fn elicitation_is_rejected_by_policy(approval_policy: AskForApproval) -> bool {
     }
 }
 
+/// Checks answers to a form elicitation against the form's schema: an object
+/// with every required field, no unknown fields, and values of the declared
+/// primitive type (and among the allowed values, for enums). A schema without
+/// `properties` leaves the fields unconstrained.
+fn validate_elicitation_content(
+    schema: &serde_json::Value,
+    content: Option<&serde_json::Value>,
+) -> Result<()> {
+    let Some(content) = content.and_then(serde_json::Value::as_object) else {
+        return Err(anyhow!("answers must be an object"));
+    };
+    let properties = schema
+        .get("properties")
+        .and_then(serde_json::Value::as_object);
+    if let Some(required) = schema.get("required").and_then(serde_json::Value::as_array) {
+        for field in required.iter().filter_map(serde_json::Value::as_str) {
+            if !content.contains_key(field) {
+                return Err(anyhow!("missing required field `{field}`"));
+            }
+        }
+    }
+    let Some(properties) = properties else {
+        return Ok(());
+    };
+    for (field, value) in content {
+        let Some(property) = properties.get(field) else {
+            return Err(anyhow!("unknown field `{field}`"));
+        };
+        if let Some(kind) = property.get("type").and_then(serde_json::Value::as_str) {
+            let matches = match kind {
+                "string" => value.is_string(),
+                "number" => value.is_number(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "boolean" => value.is_boolean(),
+                _ => true,
+            };
+            if !matches {
+                return Err(anyhow!("field `{field}` must be of type {kind}"));
+            }
+        }
+        if let Some(allowed) = property.get("enum").and_then(serde_json::Value::as_array)
+            && !allowed.contains(value)
+        {
+            return Err(anyhow!("field `{field}` must be one of the allowed values"));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 struct ElicitationRequestManager {
     requests: Arc<Mutex<ResponderMap>>,
@@ -266,16 +512,33 @@ impl ElicitationRequestManager {
         id: RequestId,
         response: ElicitationResponse,
     ) -> Result<()> {
-        self.requests
-            .lock()
-            .await
-            .remove(&(server_name, id))
+        let mut requests = self.requests.lock().await;
+        let key = (server_name, id);
+        let pending = requests
+            .get(&key)
+            .ok_or_else(|| anyhow!("elicitation request not found"))?;
+        // Answers that do not match the form leave the request open, so the
+        // user can correct them.
+        if response.action == ElicitationAction::Accept
+            && let Some(schema) = &pending.requested_schema
+        {
+            validate_elicitation_content(schema, response.content.as_ref())
+                .map_err(|err| anyhow!("invalid elicitation answers: {err}"))?;
+        }
+        requests
+            .remove(&key)
             .ok_or_else(|| anyhow!("elicitation request not found"))?
+            .responder
             .send(response)
             .map_err(|e| anyhow!("failed to send elicitation response: {e:?}"))
     }
 
-    fn make_sender(&self, server_name: String, tx_event: Sender<Event>) -> SendElicitation {
+    fn make_sender(
+        &self,
+        server_name: String,
+        tx_event: Sender<Event>,
+        timeout: Duration,
+    ) -> SendElicitation {
         let elicitation_requests = self.requests.clone();
         let approval_policy = self.approval_policy.clone();
         Box::new(move |id, elicitation| {
@@ -294,39 +557,70 @@ impl ElicitationRequestManager {
                     });
                 }
 
+                let (message, requested_schema, url) = match elicitation {
+                    CreateElicitationRequestParams::FormElicitationParams {
+                        message,
+                        requested_schema,
+                        ..
+                    } => (message, serde_json::to_value(requested_schema).ok(), None),
+                    CreateElicitationRequestParams::UrlElicitationParams {
+                        message, url, ..
+                    } => (message, None, Some(url)),
+                };
+                let protocol_id = match id.clone() {
+                    rmcp::model::NumberOrString::String(value) => {
+                        ProtocolRequestId::String(value.to_string())
+                    }
+                    rmcp::model::NumberOrString::Number(value) => ProtocolRequestId::Integer(value),
+                };
                 let (tx, rx) = oneshot::channel();
                 {
                     let mut lock = elicitation_requests.lock().await;
-                    lock.insert((server_name.clone(), id.clone()), tx);
+                    lock.insert(
+                        (server_name.clone(), id.clone()),
+                        PendingElicitation {
+                            responder: tx,
+                            requested_schema: requested_schema.clone(),
+                        },
+                    );
                 }
                 let _ = tx_event
                     .send(Event {
                         id: "mcp_elicitation_request".to_string(),
                         msg: EventMsg::ElicitationRequest(ElicitationRequestEvent {
-                            server_name,
-                            id: match id.clone() {
-                                rmcp::model::NumberOrString::String(value) => {
-                                    ProtocolRequestId::String(value.to_string())
-                                }
-                                rmcp::model::NumberOrString::Number(value) => {
-                                    ProtocolRequestId::Integer(value)
-                                }
-                            },
-                            message: match elicitation {
-                                CreateElicitationRequestParams::FormElicitationParams {
-                                    message,
-                                    ..
-                                }
-                                | CreateElicitationRequestParams::UrlElicitationParams {
-                                    message,
-                                    ..
-                                } => message,
-                            },
+                            server_name: server_name.clone(),
+                            id: protocol_id.clone(),
+                            message,
+                            requested_schema,
+                            url,
+                            timeout_ms: u64::try_from(timeout.as_millis()).ok(),
                         }),
                     })
                     .await;
-                rx.await
-                    .context("elicitation request channel closed unexpectedly")
+                match tokio::time::timeout(timeout, rx).await {
+                    Ok(response) => {
+                        response.context("elicitation request channel closed unexpectedly")
+                    }
+                    Err(_) => {
+                        elicitation_requests
+                            .lock()
+                            .await
+                            .remove(&(server_name.clone(), id));
+                        let _ = tx_event
+                            .send(Event {
+                                id: "mcp_elicitation_request".to_string(),
+                                msg: EventMsg::ElicitationTimedOut(ElicitationTimedOutEvent {
+                                    server_name,
+                                    id: protocol_id,
+                                }),
+                            })
+                            .await;
+                        Ok(ElicitationResponse {
+                            action: ElicitationAction::Cancel,
+                            content: None,
+                        })
+                    }
+                }
             }
             .boxed()
         })
@@ -335,12 +629,19 @@ impl ElicitationRequestManager {
 
 #[derive(Clone)]
 struct ManagedClient {
+    server_name: String,
     client: Arc<RmcpClient>,
-    tools: Vec<ToolInfo>,
+    /// `<name>@<version>` the server reported when it initialized.
+    server_version: String,
+    tools: Arc<StdMutex<Vec<ToolInfo>>>,
     tool_filter: ToolFilter,
     tool_timeout: Option<Duration>,
     server_supports_sandbox_state_capability: bool,
     codex_apps_tools_cache_context: Option<CodexAppsToolsCacheContext>,
+    tools_cache: Option<McpToolsCacheContext>,
+    tx_event: Sender<Event>,
+    /// Record of the server process, removed once the client is dropped.
+    _server_process: Option<Arc<ServerProcessGuard>>,
 }
 
 impl ManagedClient {
@@ -366,23 +667,56 @@ impl ManagedClient {
             );
         }
 
-        self.tools.clone()
+        self.tools
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone()
+    }
+
+    /// Re-fetches the server's tools, bypassing the listing caches.
+    async fn refresh_tools(&self) -> Result<()> {
+        let tools =
+            list_tools_for_client_uncached(&self.server_name, &self.client, self.tool_timeout)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed to refresh tools for MCP server '{}'",
+                        self.server_name
+                    )
+                })?;
+        write_cached_codex_apps_tools_if_needed(
+            &self.server_name,
+            self.codex_apps_tools_cache_context.as_ref(),
+            &tools,
+            &self.tx_event,
+        )
+        .await;
+        if let Some(tools_cache) = &self.tools_cache {
+            tools_cache.write(&self.server_version, &tools);
+        }
+        *self
+            .tools
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner) =
+            filter_tools(tools, &self.tool_filter);
+        Ok(())
     }
 
     /// Returns once the server has ack'd the sandbox state update.
     async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
-        if !self.server_supports_sandbox_state_capability {
-            return Ok(());
+        // A server failing to take the roots change still gets the sandbox
+        // state.
+        let roots_result = self.client.set_roots(sandbox_state.roots()).await;
+        if self.server_supports_sandbox_state_capability {
+            let _response = self
+                .client
+                .send_session_request(
+                    MCP_SANDBOX_STATE_METHOD,
+                    Some(serde_json::to_value(sandbox_state)?),
+                )
+                .await?;
         }
-
-        let _response = self
-            .client
-            .send_custom_request(
-                MCP_SANDBOX_STATE_METHOD,
-                Some(serde_json::to_value(sandbox_state)?),
-            )
-            .await?;
-        Ok(())
+        roots_result
     }
 }
 
@@ -391,9 +725,14 @@ struct AsyncManagedClient {
     client: Shared<BoxFuture<'static, Result<ManagedClient, StartupOutcomeError>>>,
     startup_snapshot: Option<Vec<ToolInfo>>,
     startup_complete: Arc<AtomicBool>,
+    /// Whether the server waits for its first use to start (`lazy`).
+    deferred: bool,
+    /// The sandbox state to start the server with, until it starts.
+    sandbox_state_until_start: Arc<StdMutex<Option<SandboxState>>>,
 }
 
 impl AsyncManagedClient {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         server_name: String,
         config: McpServerConfig,
@@ -402,44 +741,97 @@ impl AsyncManagedClient {
         tx_event: Sender<Event>,
         elicitation_requests: ElicitationRequestManager,
         codex_apps_tools_cache_context: Option<CodexAppsToolsCacheContext>,
+        sandbox_state: SandboxState,
+        codex_home: PathBuf,
+        server_limits: McpServerLimits,
+        auth_status: Option<McpAuthStatus>,
     ) -> Self {
         let tool_filter = ToolFilter::from_config(&config);
+        let tools_cache = (server_name != CODEX_APPS_MCP_SERVER_NAME).then(|| {
+            McpToolsCacheContext::new(
+                &codex_home,
+                &server_name,
+                &config.transport,
+                &sandbox_state.sandbox_cwd,
+                auth_status,
+            )
+        });
+        let lazy_cached_tools = if config.lazy && !config.required {
+            tools_cache.as_ref().and_then(McpToolsCacheContext::load)
+        } else {
+            None
+        };
+        // A lazy server whose cached listing is missing or stale starts right
+        // away to refresh it, advertising the stale listing meanwhile.
+        let deferred = matches!(lazy_cached_tools, Some((_, false)));
         let startup_snapshot = load_startup_cached_codex_apps_tools_snapshot(
             &server_name,
             codex_apps_tools_cache_context.as_ref(),
         )
+        .or(lazy_cached_tools.map(|(tools, _)| tools))
         .map(|tools| filter_tools(tools, &tool_filter));
         let startup_tool_filter = tool_filter;
         let startup_complete = Arc::new(AtomicBool::new(false));
         let startup_complete_for_fut = Arc::clone(&startup_complete);
+        let sandbox_state_until_start = Arc::new(StdMutex::new(Some(sandbox_state.clone())));
+        let sandbox_state_for_fut = Arc::clone(&sandbox_state_until_start);
         let fut = async move {
+            let sandbox_state = sandbox_state_for_fut
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .take()
+                .unwrap_or(sandbox_state);
             let outcome = async {
                 if let Err(error) = validate_mcp_server_name(&server_name) {
                     return Err(error.into());
                 }
 
-                let client =
-                    Arc::new(make_rmcp_client(&server_name, config.transport, store_mode).await?);
-                match start_server_task(
-                    server_name,
+                let client = Arc::new(
+                    make_rmcp_client(&server_name, config.transport, store_mode, server_limits)
+                        .await?,
+                );
+                let server_process = client.process_group_id().and_then(|process_group_id| {
+                    record_server_process(&codex_home, &server_name, process_group_id)
+                        .inspect_err(|err| {
+                            warn!("failed to record MCP server `{server_name}` process: {err}");
+                        })
+                        .ok()
+                        .map(Arc::new)
+                });
+                let managed = match start_server_task(
+                    server_name.clone(),
                     client,
                     StartServerTaskParams {
                         startup_timeout: config
                             .startup_timeout_sec
                             .or(Some(DEFAULT_STARTUP_TIMEOUT)),
                         tool_timeout: config.tool_timeout_sec.unwrap_or(DEFAULT_TOOL_TIMEOUT),
+                        elicitation: elicitation_capability_for_server(
+                            &server_name,
+                            config.elicitation,
+                        ),
+                        elicitation_timeout: config
+                            .elicitation_timeout_sec
+                            .unwrap_or(DEFAULT_ELICITATION_TIMEOUT),
                         tool_filter: startup_tool_filter,
                         tx_event,
                         elicitation_requests,
                         codex_apps_tools_cache_context,
+                        tools_cache,
+                        roots: sandbox_state.roots(),
+                        server_process,
                     },
                 )
                 .or_cancel(&cancel_token)
                 .await
                 {
-                    Ok(result) => result,
-                    Err(CancelErr::Cancelled) => Err(StartupOutcomeError::Cancelled),
+                    Ok(result) => result?,
+                    Err(CancelErr::Cancelled) => return Err(StartupOutcomeError::Cancelled),
+                };
+                if let Err(e) = managed.notify_sandbox_state_change(&sandbox_state).await {
+                    warn!("Failed to notify sandbox state to MCP server {server_name}: {e:#}");
                 }
+                Ok(managed)
             }
             .await;
 
@@ -447,7 +839,7 @@ impl AsyncManagedClient {
             outcome
         };
         let client = fut.boxed().shared();
-        if startup_snapshot.is_some() {
+        if startup_snapshot.is_some() && !deferred {
             let startup_task = client.clone();
             tokio::spawn(async move {
                 let _ = startup_task.await;
@@ -458,6 +850,8 @@ impl AsyncManagedClient {
             client,
             startup_snapshot,
             startup_complete,
+            deferred,
+            sandbox_state_until_start,
         }
     }
 
@@ -478,12 +872,39 @@ impl AsyncManagedClient {
         }
 
         match self.client().await {
-            Ok(client) => Some(client.listed_tools()),
+            Ok(client) => {
+                if client.client.take_tool_list_changed()
+                    && let Err(err) = client.refresh_tools().await
+                {
+                    warn!("{err:#}");
+                }
+                Some(client.listed_tools())
+            }
             Err(_) => self.startup_snapshot.clone(),
         }
     }
 
+    /// Whether this is a lazy server that has not been used yet.
+    fn waiting_for_first_use(&self) -> bool {
+        self.deferred
+            && self
+                .sandbox_state_until_start
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .is_some()
+    }
+
     async fn notify_sandbox_state_change(&self, sandbox_state: &SandboxState) -> Result<()> {
+        {
+            let mut sandbox_state_until_start = self
+                .sandbox_state_until_start
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if let Some(pending) = sandbox_state_until_start.as_mut() {
+                *pending = sandbox_state.clone();
+                return Ok(());
+            }
+        }
         let managed = self.client().await?;
         managed.notify_sandbox_state_change(sandbox_state).await
     }
@@ -505,6 +926,34 @@ pub struct SandboxState {
     pub use_linux_sandbox_bwrap: bool,
 }
 
+impl SandboxState {
+    /// The workspace roots MCP servers are told about (`roots/list`): the
+    /// sandbox cwd and any extra writable roots.
+    fn roots(&self) -> Vec<Root> {
+        let mut paths = vec![self.sandbox_cwd.clone()];
+        if let SandboxPolicy::WorkspaceWrite { writable_roots, .. } = &self.sandbox_policy {
+            for root in writable_roots {
+                let root = root.to_path_buf();
+                if !paths.contains(&root) {
+                    paths.push(root);
+                }
+            }
+        }
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let uri = Url::from_file_path(&path).ok()?;
+                Some(Root {
+                    uri: uri.to_string(),
+                    name: path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned()),
+                })
+            })
+            .collect()
+    }
+}
+
 /// A thin wrapper around a set of running [`RmcpClient`] instances.
 pub(crate) struct McpConnectionManager {
     clients: HashMap<String, AsyncManagedClient>,
@@ -552,7 +1001,10 @@ impl McpConnectionManager {
         initial_sandbox_state: SandboxState,
         codex_home: PathBuf,
         codex_apps_tools_cache_key: CodexAppsToolsCacheKey,
+        server_limits: McpServerLimits,
     ) -> (Self, CancellationToken) {
+        let orphans_codex_home = codex_home.clone();
+        tokio::task::spawn_blocking(move || clean_up_orphaned_servers(&orphans_codex_home));
         let cancel_token = CancellationToken::new();
         let mut clients = HashMap::new();
         let mut server_origins = HashMap::new();
@@ -564,14 +1016,6 @@ impl McpConnectionManager {
                 server_origins.insert(server_name.clone(), origin);
             }
             let cancel_token = cancel_token.child_token();
-            let _ = emit_update(
-                &tx_event,
-                McpStartupUpdateEvent {
-                    server: server_name.clone(),
-                    status: McpStartupStatus::Starting,
-                },
-            )
-            .await;
             let codex_apps_tools_cache_context = if server_name == CODEX_APPS_MCP_SERVER_NAME {
                 Some(CodexAppsToolsCacheContext {
                     codex_home: codex_home.clone(),
@@ -588,29 +1032,34 @@ impl McpConnectionManager {
                 tx_event.clone(),
                 elicitation_requests.clone(),
                 codex_apps_tools_cache_context,
+                initial_sandbox_state.clone(),
+                codex_home.clone(),
+                server_limits,
+                auth_entries
+                    .get(&server_name)
+                    .map(|auth_entry| auth_entry.auth_status),
             );
             clients.insert(server_name.clone(), async_managed_client.clone());
+            if async_managed_client.deferred {
+                continue;
+            }
+            let _ = emit_update(
+                &tx_event,
+                McpStartupUpdateEvent {
+                    server: server_name.clone(),
+                    status: McpStartupStatus::Starting,
+                },
+            )
+            .await;
             let tx_event = tx_event.clone();
             let auth_entry = auth_entries.get(&server_name).cloned();
-            let sandbox_state = initial_sandbox_state.clone();
             join_set.spawn(async move {
                 let outcome = async_managed_client.client().await;
                 if cancel_token.is_cancelled() {
                     return (server_name, Err(StartupOutcomeError::Cancelled));
                 }
                 let status = match &outcome {
-                    Ok(_) => {
-                        // Send sandbox state notification immediately after Ready
-                        if let Err(e) = async_managed_client
-                            .notify_sandbox_state_change(&sandbox_state)
-                            .await
-                        {
-                            warn!(
-                                "Failed to notify sandbox state to MCP server {server_name}: {e:#}",
-                            );
-                        }
-                        McpStartupStatus::Ready
-                    }
+                    Ok(_) => McpStartupStatus::Ready,
                     Err(error) => {
                         let error_str = mcp_init_error_display(
                             server_name.as_str(),
@@ -767,7 +1216,9 @@ impl McpConnectionManager {
             CODEX_APPS_MCP_SERVER_NAME,
             managed_client.codex_apps_tools_cache_context.as_ref(),
             &tools,
-        );
+            &managed_client.tx_event,
+        )
+        .await;
         emit_duration(
             MCP_TOOLS_LIST_DURATION_METRIC,
             list_start.elapsed(),
@@ -776,6 +1227,26 @@ impl McpConnectionManager {
         Ok(())
     }
 
+    /// Re-fetches the tools of `server`, or of every started server, bypassing
+    /// the listing caches.
+    pub(crate) async fn refresh_tools(&self, server: Option<&str>) -> Result<()> {
+        if let Some(server) = server {
+            return self.client_by_name(server).await?.refresh_tools().await;
+        }
+        for async_managed_client in self.clients.values() {
+            if async_managed_client.waiting_for_first_use() {
+                continue;
+            }
+            let Ok(managed_client) = async_managed_client.client().await else {
+                continue;
+            };
+            if let Err(err) = managed_client.refresh_tools().await {
+                warn!("{err:#}");
+            }
+        }
+        Ok(())
+    }
+
     /// Returns a single map that contains all resources. Each key is the
     /// server name and the value is a vector of resources.
     pub async fn list_all_resources(&self) -> HashMap<String, Vec<Resource>> {
@@ -784,6 +1255,9 @@ impl McpConnectionManager {
         let clients_snapshot = &self.clients;
 
         for (server_name, async_managed_client) in clients_snapshot {
+            if async_managed_client.waiting_for_first_use() {
+                continue;
+            }
             let server_name = server_name.clone();
             let Ok(managed_client) = async_managed_client.client().await else {
                 continue;
@@ -850,6 +1324,9 @@ impl McpConnectionManager {
         let clients_snapshot = &self.clients;
 
         for (server_name, async_managed_client) in clients_snapshot {
+            if async_managed_client.waiting_for_first_use() {
+                continue;
+            }
             let server_name_cloned = server_name.clone();
             let Ok(managed_client) = async_managed_client.client().await else {
                 continue;
@@ -912,12 +1389,14 @@ impl McpConnectionManager {
         aggregated
     }
 
-    /// Invoke the tool indicated by the (server, tool) pair.
+    /// Invoke the tool indicated by the (server, tool) pair, sending the
+    /// notifications the server sends about the call to `notifications`.
     pub async fn call_tool(
         &self,
         server: &str,
         tool: &str,
         arguments: Option<serde_json::Value>,
+        notifications: UnboundedSender<ToolCallNotification>,
     ) -> Result<CallToolResult> {
         let client = self.client_by_name(server).await?;
         if !client.tool_filter.allows(tool) {
@@ -928,7 +1407,12 @@ impl McpConnectionManager {
 
         let result: rmcp::model::CallToolResult = client
             .client
-            .call_tool(tool.to_string(), arguments, client.tool_timeout)
+            .call_tool(
+                tool.to_string(),
+                arguments,
+                client.tool_timeout,
+                notifications,
+            )
             .await
             .with_context(|| format!("tool call failed for `{server}/{tool}`"))?;
 
@@ -1206,19 +1690,22 @@ impl From<anyhow::Error> for StartupOutcomeError {
     }
 }
 
-fn elicitation_capability_for_server(server_name: &str) -> Option<ElicitationCapability> {
-    if server_name == CODEX_APPS_MCP_SERVER_NAME {
+/// Servers are only told that they may ask the user questions when they are
+/// the built-in apps server or their config opts in with `elicitation`.
+fn elicitation_capability_for_server(
+    server_name: &str,
+    enabled: bool,
+) -> Option<ElicitationCapability> {
+    (enabled || server_name == CODEX_APPS_MCP_SERVER_NAME).then_some(
         // https://modelcontextprotocol.io/specification/2025-06-18/client/elicitation#capabilities
         // indicates this should be an empty object.
-        Some(ElicitationCapability {
+        ElicitationCapability {
             form: Some(FormElicitationCapability {
                 schema_validation: None,
             }),
             url: None,
-        })
-    } else {
-        None
-    }
+        },
+    )
 }
 
 async fn start_server_task(
@@ -1229,18 +1716,24 @@ async fn start_server_task(
     let StartServerTaskParams {
         startup_timeout,
         tool_timeout,
+        elicitation,
+        elicitation_timeout,
         tool_filter,
         tx_event,
         elicitation_requests,
         codex_apps_tools_cache_context,
+        tools_cache,
+        roots,
+        server_process,
     } = params;
-    let elicitation = elicitation_capability_for_server(&server_name);
     let params = InitializeRequestParams {
         meta: None,
         capabilities: ClientCapabilities {
             experimental: None,
             extensions: None,
-            roots: None,
+            roots: Some(RootsCapabilities {
+                list_changed: Some(true),
+            }),
             sampling: None,
             elicitation,
             tasks: None,
@@ -1256,35 +1749,67 @@ async fn start_server_task(
         protocol_version: ProtocolVersion::V_2025_06_18,
     };
 
-    let send_elicitation = elicitation_requests.make_sender(server_name.clone(), tx_event);
+    let send_elicitation = elicitation_requests.make_sender(
+        server_name.clone(),
+        tx_event.clone(),
+        elicitation_timeout,
+    );
+    client
+        .set_roots(roots)
+        .await
+        .map_err(StartupOutcomeError::from)?;
 
     let initialize_result = client
         .initialize(params, startup_timeout, send_elicitation)
         .await
         .map_err(StartupOutcomeError::from)?;
 
-    let list_start = Instant::now();
-    let fetch_start = Instant::now();
-    let tools = list_tools_for_client_uncached(&server_name, &client, startup_timeout)
-        .await
-        .map_err(StartupOutcomeError::from)?;
-    emit_duration(
-        MCP_TOOLS_FETCH_UNCACHED_DURATION_METRIC,
-        fetch_start.elapsed(),
-        &[],
-    );
-    write_cached_codex_apps_tools_if_needed(
-        &server_name,
-        codex_apps_tools_cache_context.as_ref(),
-        &tools,
+    let server_version = format!(
+        "{}@{}",
+        initialize_result.server_info.name, initialize_result.server_info.version
     );
-    if server_name == CODEX_APPS_MCP_SERVER_NAME {
+    let list_start = Instant::now();
+    // A recent listing by the same server version is trusted; changes during
+    // the session arrive as `notifications/tools/list_changed`.
+    let cached_tools = tools_cache
+        .as_ref()
+        .filter(|tools_cache| !tools_cache.is_stale())
+        .and_then(McpToolsCacheContext::load_entry)
+        .filter(|cached| cached.server_version == server_version);
+    let tools = if let Some(cached) = cached_tools {
+        emit_duration(
+            MCP_TOOLS_LIST_DURATION_METRIC,
+            list_start.elapsed(),
+            &[("cache", "hit")],
+        );
+        cached.tools
+    } else {
+        let fetch_start = Instant::now();
+        let tools = list_tools_for_client_uncached(&server_name, &client, startup_timeout)
+            .await
+            .map_err(StartupOutcomeError::from)?;
+        emit_duration(
+            MCP_TOOLS_FETCH_UNCACHED_DURATION_METRIC,
+            fetch_start.elapsed(),
+            &[],
+        );
+        write_cached_codex_apps_tools_if_needed(
+            &server_name,
+            codex_apps_tools_cache_context.as_ref(),
+            &tools,
+            &tx_event,
+        )
+        .await;
+        if let Some(tools_cache) = &tools_cache {
+            tools_cache.write(&server_version, &tools);
+        }
         emit_duration(
             MCP_TOOLS_LIST_DURATION_METRIC,
             list_start.elapsed(),
             &[("cache", "miss")],
         );
-    }
+        tools
+    };
     let tools = filter_tools(tools, &tool_filter);
 
     let server_supports_sandbox_state_capability = initialize_result
@@ -1295,12 +1820,17 @@ async fn start_server_task(
         .is_some();
 
     let managed = ManagedClient {
+        server_name,
         client: Arc::clone(&client),
-        tools,
+        server_version,
+        tools: Arc::new(StdMutex::new(tools)),
         tool_timeout: Some(tool_timeout),
         tool_filter,
         server_supports_sandbox_state_capability,
         codex_apps_tools_cache_context,
+        tools_cache,
+        tx_event,
+        _server_process: server_process,
     };
 
     Ok(managed)
@@ -1309,16 +1839,22 @@ async fn start_server_task(
 struct StartServerTaskParams {
     startup_timeout: Option<Duration>, // TODO: cancel_token should handle this.
     tool_timeout: Duration,
+    elicitation: Option<ElicitationCapability>,
+    elicitation_timeout: Duration,
     tool_filter: ToolFilter,
     tx_event: Sender<Event>,
     elicitation_requests: ElicitationRequestManager,
     codex_apps_tools_cache_context: Option<CodexAppsToolsCacheContext>,
+    tools_cache: Option<McpToolsCacheContext>,
+    roots: Vec<Root>,
+    server_process: Option<Arc<ServerProcessGuard>>,
 }
 
 async fn make_rmcp_client(
     server_name: &str,
     transport: McpServerTransportConfig,
     store_mode: OAuthCredentialsStoreMode,
+    server_limits: McpServerLimits,
 ) -> Result<RmcpClient, StartupOutcomeError> {
     match transport {
         McpServerTransportConfig::Stdio {
@@ -1330,7 +1866,16 @@ async fn make_rmcp_client(
         } => {
             let command_os: OsString = command.into();
             let args_os: Vec<OsString> = args.into_iter().map(Into::into).collect();
-            RmcpClient::new_stdio_client(command_os, args_os, env, &env_vars, cwd)
+            let limits = StdioServerLimits {
+                memory_bytes: server_limits
+                    .memory_limit_mb
+                    .map(|megabytes| megabytes.saturating_mul(1024 * 1024)),
+                cpu_secs: server_limits.cpu_time_limit_secs,
+            };
+            // `env` already holds the environment built from the shell
+            // environment policy (see `apply_env_policy_to_mcp_servers`), so
+            // the client adds no defaults the policy may have filtered out.
+            RmcpClient::new_stdio_client(command_os, args_os, env, &env_vars, false, cwd, limits)
                 .await
                 .map_err(|err| StartupOutcomeError::from(anyhow!(err)))
         }
@@ -1359,24 +1904,58 @@ async fn make_rmcp_client(
     }
 }
 
-fn write_cached_codex_apps_tools_if_needed(
+/// Caches a fresh codex_apps listing and warns the user about connectors
+/// whose actions changed since the cached one.
+async fn write_cached_codex_apps_tools_if_needed(
     server_name: &str,
     cache_context: Option<&CodexAppsToolsCacheContext>,
     tools: &[ToolInfo],
+    tx_event: &Sender<Event>,
 ) {
     if server_name != CODEX_APPS_MCP_SERVER_NAME {
         return;
     }
 
-    if let Some(cache_context) = cache_context {
-        let cache_write_start = Instant::now();
-        write_cached_codex_apps_tools(cache_context, tools);
-        emit_duration(
-            MCP_TOOLS_CACHE_WRITE_DURATION_METRIC,
-            cache_write_start.elapsed(),
-            &[],
-        );
+    let Some(cache_context) = cache_context else {
+        return;
+    };
+    let cache_write_start = Instant::now();
+    let changes = write_cached_codex_apps_tools(cache_context, tools);
+    emit_duration(
+        MCP_TOOLS_CACHE_WRITE_DURATION_METRIC,
+        cache_write_start.elapsed(),
+        &[],
+    );
+
+    let mut summary = Vec::new();
+    for (label, connectors) in [
+        ("added", &changes.added),
+        ("removed", &changes.removed),
+        ("updated", &changes.updated),
+        (
+            "changed without a new version",
+            &changes.changed_without_version,
+        ),
+    ] {
+        if !connectors.is_empty() {
+            summary.push(format!("{label}: {}", connectors.join(", ")));
+        }
+    }
+    if summary.is_empty() {
+        return;
     }
+    let summary = summary.join("; ");
+    info!("connector schemas changed since the cached listing: {summary}");
+    let _ = tx_event
+        .send(Event {
+            id: INITIAL_SUBMIT_ID.to_owned(),
+            msg: EventMsg::Warning(WarningEvent {
+                message: format!(
+                    "Connector actions changed since they were last listed ({summary})."
+                ),
+            }),
+        })
+        .await;
 }
 
 fn load_startup_cached_codex_apps_tools_snapshot(
@@ -1426,21 +2005,87 @@ fn load_cached_codex_apps_tools(
     CachedCodexAppsToolsLoad::Hit(filter_disallowed_codex_apps_tools(cache.tools))
 }
 
-fn write_cached_codex_apps_tools(cache_context: &CodexAppsToolsCacheContext, tools: &[ToolInfo]) {
+/// Caches `tools` and returns how their connectors differ from the
+/// previously cached listing, if there was one.
+fn write_cached_codex_apps_tools(
+    cache_context: &CodexAppsToolsCacheContext,
+    tools: &[ToolInfo],
+) -> ConnectorSchemaChanges {
     let cache_path = cache_context.cache_path();
     if let Some(parent) = cache_path.parent()
         && std::fs::create_dir_all(parent).is_err()
     {
-        return;
+        return ConnectorSchemaChanges::default();
     }
     let tools = filter_disallowed_codex_apps_tools(tools.to_vec());
+    let connectors = connector_schemas(&tools);
+    let changes = match std::fs::read(&cache_path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice::<CodexAppsToolsDiskCache>(&bytes).ok())
+    {
+        Some(cached) if cached.schema_version == CODEX_APPS_TOOLS_CACHE_SCHEMA_VERSION => {
+            diff_connector_schemas(&cached.connectors, &connectors)
+        }
+        _ => ConnectorSchemaChanges::default(),
+    };
     let Ok(bytes) = serde_json::to_vec_pretty(&CodexAppsToolsDiskCache {
         schema_version: CODEX_APPS_TOOLS_CACHE_SCHEMA_VERSION,
+        connectors,
         tools,
     }) else {
-        return;
+        return changes;
     };
     let _ = std::fs::write(cache_path, bytes);
+    changes
+}
+
+/// The version and schema hash of each connector's actions in `tools`.
+fn connector_schemas(tools: &[ToolInfo]) -> BTreeMap<String, CachedConnectorSchemas> {
+    let mut tools_by_connector: BTreeMap<&str, Vec<&ToolInfo>> = BTreeMap::new();
+    for tool in tools {
+        if let Some(connector_id) = tool.connector_id.as_deref() {
+            tools_by_connector
+                .entry(connector_id)
+                .or_default()
+                .push(tool);
+        }
+    }
+    tools_by_connector
+        .into_iter()
+        .map(|(connector_id, mut tools)| {
+            tools.sort_by(|a, b| a.tool_name.cmp(&b.tool_name));
+            let schemas = CachedConnectorSchemas {
+                version: tools.iter().find_map(|tool| tool.connector_version.clone()),
+                tools_hash: sha1_hex(&serde_json::to_string(&tools).unwrap_or_default()),
+            };
+            (connector_id.to_string(), schemas)
+        })
+        .collect()
+}
+
+fn diff_connector_schemas(
+    cached: &BTreeMap<String, CachedConnectorSchemas>,
+    current: &BTreeMap<String, CachedConnectorSchemas>,
+) -> ConnectorSchemaChanges {
+    let mut changes = ConnectorSchemaChanges::default();
+    for (connector_id, schemas) in current {
+        match cached.get(connector_id) {
+            None => changes.added.push(connector_id.clone()),
+            Some(cached) if cached.version != schemas.version => {
+                changes.updated.push(connector_id.clone());
+            }
+            Some(cached) if cached.tools_hash != schemas.tools_hash => {
+                changes.changed_without_version.push(connector_id.clone());
+            }
+            Some(_) => {}
+        }
+    }
+    changes.removed = cached
+        .keys()
+        .filter(|connector_id| !current.contains_key(*connector_id))
+        .cloned()
+        .collect();
+    changes
 }
 
 fn filter_disallowed_codex_apps_tools(tools: Vec<ToolInfo>) -> Vec<ToolInfo> {
@@ -1495,6 +2140,7 @@ async fn list_tools_for_client_uncached(
                 tool: tool_def,
                 connector_id: tool.connector_id,
                 connector_name,
+                connector_version: tool.connector_version,
             }
         })
         .collect();
@@ -1586,11 +2232,47 @@ mod tests {
     use super::*;
     use codex_protocol::protocol::McpAuthStatus;
     use codex_protocol::protocol::RejectConfig;
+    use pretty_assertions::assert_eq;
     use rmcp::model::JsonObject;
     use std::collections::HashSet;
     use std::sync::Arc;
     use tempfile::tempdir;
 
+    #[cfg(unix)]
+    #[test]
+    fn sandbox_state_lists_cwd_and_writable_roots_as_mcp_roots() {
+        let writable_root = |path: &str| {
+            codex_utils_absolute_path::AbsolutePathBuf::from_absolute_path(path)
+                .expect("absolute path")
+        };
+        let sandbox_state = SandboxState {
+            sandbox_policy: SandboxPolicy::WorkspaceWrite {
+                writable_roots: vec![writable_root("/work/repo"), writable_root("/work/shared")],
+                read_only_access: Default::default(),
+                network_access: false,
+                exclude_tmpdir_env_var: false,
+                exclude_slash_tmp: false,
+            },
+            codex_linux_sandbox_exe: None,
+            sandbox_cwd: PathBuf::from("/work/repo"),
+            use_linux_sandbox_bwrap: false,
+        };
+
+        assert_eq!(
+            sandbox_state.roots(),
+            vec![
+                Root {
+                    uri: "file:///work/repo".to_string(),
+                    name: Some("repo".to_string()),
+                },
+                Root {
+                    uri: "file:///work/shared".to_string(),
+                    name: Some("shared".to_string()),
+                },
+            ]
+        );
+    }
+
     fn create_test_tool(server_name: &str, tool_name: &str) -> ToolInfo {
         ToolInfo {
             server_name: server_name.to_string(),
@@ -1608,6 +2290,7 @@ mod tests {
             },
             connector_id: None,
             connector_name: None,
+            connector_version: None,
         }
     }
 
@@ -1844,6 +2527,72 @@ mod tests {
         assert_eq!(cached_gateway_2[0].tool_name, "two");
     }
 
+    #[test]
+    fn codex_apps_tools_cache_diffs_connectors_by_version() {
+        let versioned_tool = |tool_name: &str, connector_id: &str, version: &str| {
+            let mut tool = create_test_tool_with_connector(
+                CODEX_APPS_MCP_SERVER_NAME,
+                tool_name,
+                connector_id,
+                None,
+            );
+            tool.connector_version = Some(version.to_string());
+            tool
+        };
+        let cached = connector_schemas(&[
+            versioned_tool("calendar_search", "calendar", "1"),
+            versioned_tool("drive_search", "drive", "1"),
+            versioned_tool("mail_search", "mail", "1"),
+            versioned_tool("notes_search", "notes", "1"),
+        ]);
+        let mut changed_mail_tool = versioned_tool("mail_search", "mail", "1");
+        changed_mail_tool.tool.description = Some("Search mail".into());
+        let current = connector_schemas(&[
+            versioned_tool("calendar_search", "calendar", "1"),
+            versioned_tool("drive_search", "drive", "2"),
+            changed_mail_tool,
+            versioned_tool("slack_search", "slack", "1"),
+        ]);
+
+        assert_eq!(
+            diff_connector_schemas(&cached, &current),
+            ConnectorSchemaChanges {
+                added: vec!["slack".to_string()],
+                removed: vec!["notes".to_string()],
+                updated: vec!["drive".to_string()],
+                changed_without_version: vec!["mail".to_string()],
+            }
+        );
+
+        let codex_home = tempdir().expect("tempdir");
+        let cache_context = create_codex_apps_tools_cache_context(
+            codex_home.path().to_path_buf(),
+            Some("account-one"),
+            Some("user-one"),
+        );
+        write_cached_codex_apps_tools(
+            &cache_context,
+            &[versioned_tool("drive_search", "drive", "1")],
+        );
+        assert_eq!(
+            write_cached_codex_apps_tools(
+                &cache_context,
+                &[versioned_tool("drive_search", "drive", "2")],
+            ),
+            ConnectorSchemaChanges {
+                updated: vec!["drive".to_string()],
+                ..Default::default()
+            }
+        );
+        let disk_cache: CodexAppsToolsDiskCache =
+            serde_json::from_slice(&std::fs::read(cache_context.cache_path()).expect("read cache"))
+                .expect("parse cache");
+        assert_eq!(
+            disk_cache.connectors,
+            BTreeMap::from([("drive".to_string(), current["drive"].clone())])
+        );
+    }
+
     #[test]
     fn codex_apps_tools_cache_is_scoped_per_user() {
         let codex_home = tempdir().expect("tempdir");
@@ -1991,6 +2740,8 @@ mod tests {
                 client: pending_client,
                 startup_snapshot: Some(startup_tools),
                 startup_complete: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                deferred: false,
+                sandbox_state_until_start: Default::default(),
             },
         );
 
@@ -2016,6 +2767,8 @@ mod tests {
                 client: pending_client,
                 startup_snapshot: None,
                 startup_complete: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                deferred: false,
+                sandbox_state_until_start: Default::default(),
             },
         );
 
@@ -2038,6 +2791,8 @@ mod tests {
                 client: pending_client,
                 startup_snapshot: Some(Vec::new()),
                 startup_complete: Arc::new(std::sync::atomic::AtomicBool::new(false)),
+                deferred: false,
+                sandbox_state_until_start: Default::default(),
             },
         );
 
@@ -2069,6 +2824,8 @@ mod tests {
                 client: failed_client,
                 startup_snapshot: Some(startup_tools),
                 startup_complete,
+                deferred: false,
+                sandbox_state_until_start: Default::default(),
             },
         );
 
@@ -2080,20 +2837,205 @@ mod tests {
         assert_eq!(tool.tool_name, "calendar_create_event");
     }
 
+    #[tokio::test]
+    async fn lazy_server_advertises_cached_tools_until_first_use() {
+        let codex_home = tempdir().expect("tempdir");
+        let transport = |command: &str| McpServerTransportConfig::Stdio {
+            command: command.to_string(),
+            args: Vec::new(),
+            env: None,
+            env_vars: Vec::new(),
+            cwd: None,
+        };
+        let cache_for = |command: &str, cwd: &Path, auth_status| {
+            McpToolsCacheContext::new(
+                codex_home.path(),
+                "docs",
+                &transport(command),
+                cwd,
+                auth_status,
+            )
+        };
+        let cache = cache_for("docs-mcp", codex_home.path(), None);
+        let cached_tools = vec![create_test_tool("docs", "search")];
+        cache.write("docs@1.0.0", &cached_tools);
+        assert_eq!(
+            cache.load().map(|(tools, stale)| (tools.len(), stale)),
+            Some((1, false))
+        );
+        // Launching the server differently, in another project or as another
+        // user invalidates its listing.
+        assert!(
+            cache_for("docs-mcp-v2", codex_home.path(), None)
+                .load()
+                .is_none()
+        );
+        assert!(
+            cache_for("docs-mcp", &codex_home.path().join("other"), None)
+                .load()
+                .is_none()
+        );
+        assert!(
+            cache_for("docs-mcp", codex_home.path(), Some(McpAuthStatus::OAuth))
+                .load()
+                .is_none()
+        );
+
+        let client = AsyncManagedClient::new(
+            "docs".to_string(),
+            McpServerConfig {
+                transport: transport("docs-mcp"),
+                enabled: true,
+                required: false,
+                lazy: true,
+                disabled_reason: None,
+                startup_timeout_sec: None,
+                tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
+                enabled_tools: None,
+                disabled_tools: None,
+                scopes: None,
+            },
+            OAuthCredentialsStoreMode::default(),
+            CancellationToken::new(),
+            async_channel::unbounded().0,
+            ElicitationRequestManager::new(AskForApproval::OnFailure),
+            None,
+            SandboxState {
+                sandbox_policy: SandboxPolicy::DangerFullAccess,
+                codex_linux_sandbox_exe: None,
+                sandbox_cwd: codex_home.path().to_path_buf(),
+                use_linux_sandbox_bwrap: false,
+            },
+            codex_home.path().to_path_buf(),
+            McpServerLimits::default(),
+            None,
+        );
+        assert!(client.deferred);
+        assert!(client.waiting_for_first_use());
+        let tools = client.listed_tools().await.expect("cached tools");
+        assert_eq!(
+            tools
+                .iter()
+                .map(|tool| tool.tool_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["search"]
+        );
+        assert!(client.waiting_for_first_use());
+    }
+
     #[test]
-    fn elicitation_capability_enabled_only_for_codex_apps() {
-        let codex_apps_capability = elicitation_capability_for_server(CODEX_APPS_MCP_SERVER_NAME);
-        assert!(matches!(
-            codex_apps_capability,
-            Some(ElicitationCapability {
-                form: Some(FormElicitationCapability {
-                    schema_validation: None
-                }),
-                url: None,
-            })
-        ));
+    fn tools_cache_detects_schema_changes() {
+        let codex_home = tempdir().expect("tempdir");
+        let cache = McpToolsCacheContext::new(
+            codex_home.path(),
+            "docs",
+            &McpServerTransportConfig::StreamableHttp {
+                url: "https://docs.example.com/mcp".to_string(),
+                bearer_token_env_var: None,
+                http_headers: None,
+                env_http_headers: None,
+            },
+            codex_home.path(),
+            None,
+        );
+        assert!(cache.load().is_none());
+        assert!(cache.is_stale());
 
-        assert!(elicitation_capability_for_server("custom_mcp").is_none());
+        let search = create_test_tool("docs", "search");
+        cache.write("docs@1.0.0", std::slice::from_ref(&search));
+        let first = cache.load_entry().expect("cached listing");
+        cache.write("docs@1.0.0", std::slice::from_ref(&search));
+        assert_eq!(
+            cache.load_entry().expect("cached listing").tools_hash,
+            first.tools_hash
+        );
+
+        let mut changed_search = search;
+        changed_search.tool.description = Some("Search the docs".into());
+        cache.write("docs@1.0.0", std::slice::from_ref(&changed_search));
+        let changed = cache.load_entry().expect("cached listing");
+        assert_ne!(changed.tools_hash, first.tools_hash);
+        assert_eq!(
+            changed
+                .tools
+                .iter()
+                .map(|tool| tool.tool.description.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("Search the docs")]
+        );
+        assert_eq!(
+            (changed.schema_version, changed.server_version),
+            (MCP_TOOLS_CACHE_SCHEMA_VERSION, "docs@1.0.0".to_string())
+        );
+    }
+
+    #[test]
+    fn elicitation_capability_only_for_codex_apps_and_opted_in_servers() {
+        assert_eq!(
+            (
+                elicitation_capability_for_server(CODEX_APPS_MCP_SERVER_NAME, false).is_some(),
+                elicitation_capability_for_server("docs", false).is_some(),
+                elicitation_capability_for_server("docs", true).is_some(),
+            ),
+            (true, false, true)
+        );
+    }
+
+    #[test]
+    fn validates_elicitation_answers_against_the_form() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "name": { "type": "string" },
+                "count": { "type": "integer" },
+                "color": { "type": "string", "enum": ["red", "blue"] },
+                "confirm": { "type": "boolean" }
+            },
+            "required": ["name"]
+        });
+        let validate = |content: serde_json::Value| {
+            validate_elicitation_content(&schema, Some(&content)).map_err(|err| err.to_string())
+        };
+
+        assert_eq!(
+            validate(serde_json::json!({
+                "name": "docs",
+                "count": 3,
+                "color": "blue",
+                "confirm": true
+            })),
+            Ok(())
+        );
+        assert_eq!(
+            validate_elicitation_content(&schema, None).map_err(|err| err.to_string()),
+            Err("answers must be an object".to_string())
+        );
+        assert_eq!(
+            validate(serde_json::json!({ "count": 3 })),
+            Err("missing required field `name`".to_string())
+        );
+        assert_eq!(
+            validate(serde_json::json!({ "name": "docs", "size": 3 })),
+            Err("unknown field `size`".to_string())
+        );
+        assert_eq!(
+            validate(serde_json::json!({ "name": "docs", "count": 1.5 })),
+            Err("field `count` must be of type integer".to_string())
+        );
+        assert_eq!(
+            validate(serde_json::json!({ "name": "docs", "color": "green" })),
+            Err("field `color` must be one of the allowed values".to_string())
+        );
+        assert_eq!(
+            validate_elicitation_content(
+                &serde_json::json!({ "type": "object" }),
+                Some(&serde_json::json!({ "note": "anything" })),
+            )
+            .map_err(|err| err.to_string()),
+            Ok(())
+        );
     }
 
     #[test]
@@ -2109,9 +3051,12 @@ mod tests {
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -2156,9 +3101,12 @@ mod tests {
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: None,
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -9,11 +9,16 @@ use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::config::types::AppToolApproval;
 use crate::connectors;
+use crate::function_tool::FunctionCallError;
+use crate::function_tool::ToolFailureKind;
 use crate::mcp::CODEX_APPS_MCP_SERVER_NAME;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
+use crate::protocol::McpLogLevel;
 use crate::protocol::McpToolCallBeginEvent;
 use crate::protocol::McpToolCallEndEvent;
+use crate::protocol::McpToolCallLogEvent;
+use crate::protocol::McpToolCallProgressEvent;
 use codex_protocol::mcp::CallToolResult;
 use codex_protocol::models::FunctionCallOutputBody;
 use codex_protocol::models::FunctionCallOutputPayload;
@@ -26,9 +31,13 @@ use codex_protocol::request_user_input::RequestUserInputArgs;
 use codex_protocol::request_user_input::RequestUserInputQuestion;
 use codex_protocol::request_user_input::RequestUserInputQuestionOption;
 use codex_protocol::request_user_input::RequestUserInputResponse;
+use codex_rmcp_client::ToolCallNotification;
+use codex_rmcp_client::TransportError;
+use rmcp::model::LoggingLevel;
 use rmcp::model::ToolAnnotations;
 use serde::Serialize;
 use std::sync::Arc;
+use tokio::sync::mpsc;
 
 /// Handles the specified tool call dispatches the appropriate
 /// `McpToolCallBegin` and `McpToolCallEnd` events to the `Session`.
@@ -39,7 +48,7 @@ pub(crate) async fn handle_mcp_tool_call(
     server: String,
     tool_name: String,
     arguments: String,
-) -> ResponseInputItem {
+) -> Result<ResponseInputItem, FunctionCallError> {
     // Parse the `arguments` as JSON. An empty string is OK, but invalid JSON
     // is not.
     let arguments_value = if arguments.trim().is_empty() {
@@ -49,13 +58,13 @@ pub(crate) async fn handle_mcp_tool_call(
             Ok(value) => Some(value),
             Err(e) => {
                 error!("failed to parse tool call arguments: {e}");
-                return ResponseInputItem::FunctionCallOutput {
+                return Ok(ResponseInputItem::FunctionCallOutput {
                     call_id: call_id.clone(),
                     output: FunctionCallOutputPayload {
                         body: FunctionCallOutputBody::Text(format!("err: {e}")),
                         success: Some(false),
                     },
-                };
+                });
             }
         }
     };
@@ -98,7 +107,7 @@ pub(crate) async fn handle_mcp_tool_call(
         turn_context
             .otel_manager
             .counter("codex.mcp.call", 1, &[("status", status)]);
-        return ResponseInputItem::McpToolCallOutput { call_id, result };
+        return Ok(ResponseInputItem::McpToolCallOutput { call_id, result });
     }
 
     if let Some(decision) = maybe_request_mcp_tool_approval(
@@ -112,6 +121,7 @@ pub(crate) async fn handle_mcp_tool_call(
     )
     .await
     {
+        let mut failure_kind = None;
         let result = match decision {
             McpToolApprovalDecision::Accept | McpToolApprovalDecision::AcceptAndRemember => {
                 let tool_call_begin_event = EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
@@ -122,10 +132,17 @@ pub(crate) async fn handle_mcp_tool_call(
                     .await;
 
                 let start = Instant::now();
-                let result = sess
-                    .call_tool(&server, &tool_name, arguments_value.clone())
-                    .await
-                    .map_err(|e| format!("tool call error: {e:?}"));
+                let result = call_tool_forwarding_notifications(
+                    sess.as_ref(),
+                    turn_context,
+                    &call_id,
+                    &server,
+                    &tool_name,
+                    arguments_value.clone(),
+                )
+                .await;
+                failure_kind = result.as_ref().err().and_then(call_failure_kind);
+                let result = result.map_err(|e| format!("tool call error: {e:?}"));
                 let result = sanitize_mcp_tool_result_for_model(
                     turn_context
                         .model_info
@@ -180,7 +197,7 @@ pub(crate) async fn handle_mcp_tool_call(
             .otel_manager
             .counter("codex.mcp.call", 1, &[("status", status)]);
 
-        return ResponseInputItem::McpToolCallOutput { call_id, result };
+        return call_output(call_id, result, failure_kind);
     }
 
     let tool_call_begin_event = EventMsg::McpToolCallBegin(McpToolCallBeginEvent {
@@ -191,10 +208,17 @@ pub(crate) async fn handle_mcp_tool_call(
 
     let start = Instant::now();
     // Perform the tool call.
-    let result = sess
-        .call_tool(&server, &tool_name, arguments_value.clone())
-        .await
-        .map_err(|e| format!("tool call error: {e:?}"));
+    let result = call_tool_forwarding_notifications(
+        sess.as_ref(),
+        turn_context,
+        &call_id,
+        &server,
+        &tool_name,
+        arguments_value.clone(),
+    )
+    .await;
+    let failure_kind = result.as_ref().err().and_then(call_failure_kind);
+    let result = result.map_err(|e| format!("tool call error: {e:?}"));
     let result = sanitize_mcp_tool_result_for_model(
         turn_context
             .model_info
@@ -220,7 +244,34 @@ pub(crate) async fn handle_mcp_tool_call(
         .otel_manager
         .counter("codex.mcp.call", 1, &[("status", status)]);
 
-    ResponseInputItem::McpToolCallOutput { call_id, result }
+    call_output(call_id, result, failure_kind)
+}
+
+/// Calls that got no answer from the server fail with a typed error so the
+/// router can retry them; any other outcome is the call's output.
+fn call_output(
+    call_id: String,
+    result: Result<CallToolResult, String>,
+    failure_kind: Option<ToolFailureKind>,
+) -> Result<ResponseInputItem, FunctionCallError> {
+    match (result, failure_kind) {
+        (Err(message), Some(kind)) => Err(FunctionCallError::failed(kind, message)),
+        (result, _) => Ok(ResponseInputItem::McpToolCallOutput { call_id, result }),
+    }
+}
+
+/// Whether a failed call timed out or lost its connection, as opposed to
+/// being rejected, which retrying would not change.
+fn call_failure_kind(err: &anyhow::Error) -> Option<ToolFailureKind> {
+    err.chain().find_map(|cause| {
+        if cause.is::<tokio::time::error::Elapsed>() {
+            Some(ToolFailureKind::Timeout)
+        } else if cause.is::<TransportError>() {
+            Some(ToolFailureKind::ConnectionLost)
+        } else {
+            None
+        }
+    })
 }
 
 fn sanitize_mcp_tool_result_for_model(
@@ -258,6 +309,68 @@ async fn notify_mcp_tool_call_event(sess: &Session, turn_context: &TurnContext,
     sess.send_event(turn_context, event).await;
 }
 
+/// Calls the tool, forwarding the progress and log notifications the server
+/// sends about it as events for `call_id` until it returns.
+async fn call_tool_forwarding_notifications(
+    sess: &Session,
+    turn_context: &TurnContext,
+    call_id: &str,
+    server: &str,
+    tool_name: &str,
+    arguments: Option<serde_json::Value>,
+) -> anyhow::Result<CallToolResult> {
+    let (notifications_tx, mut notifications_rx) = mpsc::unbounded_channel();
+    let call = sess.call_tool(server, tool_name, arguments, notifications_tx);
+    tokio::pin!(call);
+    loop {
+        tokio::select! {
+            // Forward what the server sent before its result first.
+            biased;
+            Some(notification) = notifications_rx.recv() => {
+                let event = match notification {
+                    ToolCallNotification::Progress {
+                        progress,
+                        total,
+                        message,
+                    } => EventMsg::McpToolCallProgress(McpToolCallProgressEvent {
+                        call_id: call_id.to_string(),
+                        progress,
+                        total,
+                        message,
+                    }),
+                    ToolCallNotification::Log {
+                        level,
+                        logger,
+                        data,
+                    } => EventMsg::McpToolCallLog(McpToolCallLogEvent {
+                        call_id: call_id.to_string(),
+                        level: match level {
+                            LoggingLevel::Debug => McpLogLevel::Debug,
+                            LoggingLevel::Info => McpLogLevel::Info,
+                            LoggingLevel::Notice => McpLogLevel::Notice,
+                            LoggingLevel::Warning => McpLogLevel::Warning,
+                            LoggingLevel::Error => McpLogLevel::Error,
+                            LoggingLevel::Critical => McpLogLevel::Critical,
+                            LoggingLevel::Alert => McpLogLevel::Alert,
+                            LoggingLevel::Emergency => McpLogLevel::Emergency,
+                        },
+                        logger,
+                        data,
+                    }),
+                };
+                notify_mcp_tool_call_event(sess, turn_context, event).await;
+            }
+            result = &mut call => {
+                // Only failing to reach the server or get its response counts
+                // against the server's circuit breaker, not tool errors.
+                sess.record_tool_call_outcome(turn_context, server, result.is_err())
+                    .await;
+                return result;
+            }
+        }
+    }
+}
+
 struct McpAppUsageMetadata {
     connector_id: Option<String>,
     app_name: Option<String>,
@@ -0,0 +1,158 @@
+//! Per-repository codebase maps.
+//!
+//! A map is generated on demand (see `Op::GenerateCodebaseMap`) by asking the
+//! model to explore the repository and summarize its modules, entry points,
+//! build/test commands, and conventions. The result is stored under the
+//! memory root, keyed by repository, and injected into the developer
+//! instructions of later sessions in the same repository.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::DateTime;
+use chrono::Utc;
+use sha2::Digest;
+use sha2::Sha256;
+use tokio::fs;
+
+use crate::git_info::get_git_repo_root;
+use crate::memories::memory_root;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::truncate_text;
+
+const CODEBASE_MAPS_SUBDIR: &str = "codebase_maps";
+/// Maximum number of tokens of a codebase map injected into a new session.
+const CODEBASE_MAP_TOKEN_LIMIT: usize = 6_000;
+
+const PROMPT_TEMPLATE: &str = include_str!("../../templates/memories/codebase_map_prompt.md");
+const READ_PATH_TEMPLATE: &str = include_str!("../../templates/memories/codebase_map_read_path.md");
+
+/// The repository a codebase map for `cwd` belongs to: the enclosing git
+/// repository, or `cwd` itself outside of one.
+pub(crate) fn codebase_map_root(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// Location of the stored map for `repo_root`, e.g.
+/// `<codex_home>/memories/codebase_maps/codex-1a2b3c4d5e6f7a8b.md`.
+pub(crate) fn codebase_map_path(codex_home: &Path, repo_root: &Path) -> PathBuf {
+    let digest = Sha256::digest(repo_root.to_string_lossy().as_bytes());
+    let hex = format!("{digest:x}");
+    let short_hash = hex.get(..16).unwrap_or(&hex);
+    let name: String = repo_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    memory_root(codex_home)
+        .join(CODEBASE_MAPS_SUBDIR)
+        .join(format!("{name}-{short_hash}.md"))
+}
+
+/// User prompt for the codebase map generation turn.
+pub(crate) fn build_codebase_map_prompt(repo_root: &Path) -> String {
+    PROMPT_TEMPLATE.replace("{{ repo_root }}", &repo_root.display().to_string())
+}
+
+/// Persists a generated map and returns where it was written.
+pub(crate) async fn write_codebase_map(
+    codex_home: &Path,
+    repo_root: &Path,
+    codebase_map: &str,
+) -> std::io::Result<PathBuf> {
+    let path = codebase_map_path(codex_home, repo_root);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await?;
+    }
+    fs::write(&path, format!("{}\n", codebase_map.trim())).await?;
+    Ok(path)
+}
+
+/// Developer instructions carrying the stored map for the repository
+/// containing `cwd`, truncated to [`CODEBASE_MAP_TOKEN_LIMIT`]. Returns `None`
+/// when no map has been generated.
+pub(crate) async fn build_codebase_map_developer_instructions(
+    codex_home: &Path,
+    cwd: &Path,
+) -> Option<String> {
+    let repo_root = codebase_map_root(cwd);
+    let path = codebase_map_path(codex_home, &repo_root);
+    let codebase_map = fs::read_to_string(&path).await.ok()?;
+    let codebase_map = codebase_map.trim();
+    if codebase_map.is_empty() {
+        return None;
+    }
+    let generated_at = fs::metadata(&path)
+        .await
+        .and_then(|metadata| metadata.modified())
+        .map(|modified| {
+            DateTime::<Utc>::from(modified)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_else(|_| "an unknown date".to_string());
+    let codebase_map = truncate_text(
+        codebase_map,
+        TruncationPolicy::Tokens(CODEBASE_MAP_TOKEN_LIMIT),
+    );
+    Some(
+        READ_PATH_TEMPLATE
+            .replace("{{ repo_root }}", &repo_root.display().to_string())
+            .replace("{{ generated_at }}", &generated_at)
+            .replace("{{ codebase_map }}", &codebase_map),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::TempDir;
+
+    #[tokio::test]
+    async fn stored_map_is_injected_for_the_same_repository_only() {
+        let codex_home = TempDir::new().expect("codex home");
+        let workspace = TempDir::new().expect("workspace");
+        let repo_root = workspace.path().join("my repo");
+        let other_root = workspace.path().join("other");
+        std::fs::create_dir_all(repo_root.join(".git")).expect("create repo");
+        std::fs::create_dir_all(repo_root.join("src")).expect("create src");
+        std::fs::create_dir_all(&other_root).expect("create other");
+
+        let path = write_codebase_map(codex_home.path(), &repo_root, "## Overview\nA tool.\n\n")
+            .await
+            .expect("write map");
+
+        assert_eq!(
+            path.parent(),
+            Some(
+                memory_root(codex_home.path())
+                    .join(CODEBASE_MAPS_SUBDIR)
+                    .as_path()
+            )
+        );
+        assert!(
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.starts_with("my-repo-"))
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).expect("read map"),
+            "## Overview\nA tool.\n"
+        );
+
+        let instructions =
+            build_codebase_map_developer_instructions(codex_home.path(), &repo_root.join("src"))
+                .await
+                .expect("map for nested cwd");
+        assert!(instructions.starts_with("## Codebase map"));
+        assert!(instructions.ends_with("## Overview\nA tool.\n"));
+
+        assert_eq!(
+            build_codebase_map_developer_instructions(codex_home.path(), &other_root).await,
+            None
+        );
+    }
+}
@@ -4,6 +4,7 @@
 //! - Phase 1: select rollouts, extract stage-1 raw memories, persist stage-1 outputs, and enqueue consolidation.
 //! - Phase 2: claim a global consolidation lock, materialize consolidation inputs, and dispatch one consolidation agent.
 
+pub(crate) mod codebase_map;
 mod phase1;
 mod phase2;
 pub(crate) mod prompts;
@@ -97,7 +98,7 @@ pub fn memory_root(codex_home: &Path) -> PathBuf {
     codex_home.join("memories")
 }
 
-fn rollout_summaries_dir(root: &Path) -> PathBuf {
+pub(crate) fn rollout_summaries_dir(root: &Path) -> PathBuf {
     root.join(artifacts::ROLLOUT_SUMMARIES_SUBDIR)
 }
 
@@ -13,6 +13,7 @@ use crate::rollout::policy::should_persist_response_item_for_memories;
 use codex_api::ResponseEvent;
 use codex_otel::OtelManager;
 use codex_protocol::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use codex_protocol::config_types::SamplingParams;
 use codex_protocol::models::BaseInstructions;
 use codex_protocol::models::ContentItem;
 use codex_protocol::models::ResponseItem;
@@ -312,6 +313,7 @@ mod job {
             },
             personality: None,
             output_schema: Some(output_schema()),
+            sampling: SamplingParams::default(),
         };
 
         let mut client_session = session.services.model_client.new_session();
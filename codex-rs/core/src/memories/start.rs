@@ -9,8 +9,9 @@ use tracing::warn;
 
 /// Starts the asynchronous startup memory pipeline for an eligible root session.
 ///
-/// The pipeline is skipped for ephemeral sessions, disabled feature flags, and
-/// subagent sessions.
+/// The pipeline is skipped for ephemeral sessions, disabled feature flags,
+/// subagent sessions, and while `session_encryption` is on, since memories are
+/// kept as plain files for the consolidation agent to read.
 pub(crate) fn start_memories_startup_task(
     session: &Arc<Session>,
     config: Arc<Config>,
@@ -18,6 +19,7 @@ pub(crate) fn start_memories_startup_task(
 ) {
     if config.ephemeral
         || !config.features.enabled(Feature::MemoryTool)
+        || config.features.enabled(Feature::SessionEncryption)
         || matches!(source, SessionSource::SubAgent(_))
     {
         return;
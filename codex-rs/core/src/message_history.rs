@@ -15,6 +15,8 @@
 //! that writes up to `PIPE_BUF` bytes are atomic in that case.
 //! Note: `conversation_id` stores the thread id; the field name is preserved for
 //! backwards compatibility with existing history files.
+//!
+//! With `session_encryption` on, `text` is sealed with the session store key.
 
 use std::fs::File;
 use std::fs::OpenOptions;
@@ -37,6 +39,7 @@ use tokio::io::AsyncReadExt;
 
 use crate::config::Config;
 use crate::config::types::HistoryPersistence;
+use crate::rollout::encryption;
 
 use codex_protocol::ThreadId;
 #[cfg(unix)]
@@ -98,11 +101,18 @@ pub(crate) async fn append_entry(
         .map_err(|e| std::io::Error::other(format!("system clock before Unix epoch: {e}")))?
         .as_secs();
 
+    // With `session_encryption` on, only the text is sealed so entries can
+    // still be found by line; without a key the entry is not saved.
+    let text = match encryption::sealing_key(config).await? {
+        Some(key) => key.seal_text(text)?,
+        None => text.to_string(),
+    };
+
     // Construct the JSON line first so we can write it in a single syscall.
     let entry = HistoryEntry {
         session_id: conversation_id.to_string(),
         ts,
-        text: text.to_string(),
+        text,
     };
     let mut line = serde_json::to_string(&entry)
         .map_err(|e| std::io::Error::other(format!("failed to serialise history entry: {e}")))?;
@@ -359,7 +369,15 @@ fn lookup_history_entry(path: &Path, log_id: u64, offset: usize) -> Option<Histo
 
                     if idx == offset {
                         match serde_json::from_str::<HistoryEntry>(&line) {
-                            Ok(entry) => return Some(entry),
+                            Ok(entry) => {
+                                return match encryption::open_text(&entry.text) {
+                                    Ok(text) => Some(HistoryEntry { text, ..entry }),
+                                    Err(e) => {
+                                        tracing::warn!(error = %e, "failed to decrypt history entry");
+                                        None
+                                    }
+                                };
+                            }
                             Err(e) => {
                                 tracing::warn!(error = %e, "failed to parse history entry");
                                 return None;
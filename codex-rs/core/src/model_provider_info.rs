@@ -7,6 +7,7 @@
 
 use crate::auth::AuthMode;
 use crate::error::EnvVarError;
+use crate::request_signing::request_signer;
 use codex_api::Provider as ApiProvider;
 use codex_api::provider::RetryConfig as ApiRetryConfig;
 use http::HeaderMap;
@@ -54,6 +55,35 @@ impl<'de> Deserialize<'de> for WireApi {
     }
 }
 
+/// How requests to a provider are signed.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum RequestSigningConfig {
+    /// HMAC-SHA256 of `"{timestamp}\n{method}\n{path_and_query}\n{body}"`,
+    /// hex-encoded, where the body is the bytes sent: the JSON, zstd-compressed
+    /// when request compression is on.
+    Hmac {
+        /// Account under the `codex` service in the OS keychain whose value
+        /// is the signing key.
+        keychain_account: String,
+        /// Header carrying the signature. Defaults to `X-Codex-Signature`.
+        signature_header: Option<String>,
+        /// Header carrying the signed unix timestamp. Defaults to
+        /// `X-Codex-Timestamp`.
+        timestamp_header: Option<String>,
+    },
+    /// Runs `command` with `args` for every request. It receives the request
+    /// as JSON (`method`, `url`, `headers`, `body`, the body as JSON before any
+    /// compression) on stdin and prints a JSON object of headers to add.
+    Command {
+        command: String,
+        #[serde(default)]
+        args: Vec<String>,
+        /// Defaults to 10 seconds.
+        timeout_ms: Option<u64>,
+    },
+}
+
 /// Serializable representation of a provider definition.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema)]
 #[schemars(deny_unknown_fields)]
@@ -91,6 +121,11 @@ pub struct ModelProviderInfo {
     /// value is empty, the header will not be included in the request.
     pub env_http_headers: Option<HashMap<String, String>>,
 
+    /// Signs every request to this provider, for enterprise gateways that
+    /// require their own authentication on top of (or instead of) the API
+    /// key.
+    pub request_signing: Option<RequestSigningConfig>,
+
     /// Maximum number of times to retry a failed HTTP request to this provider.
     pub request_max_retries: Option<u64>,
 
@@ -171,6 +206,7 @@ impl ModelProviderInfo {
             headers,
             retry,
             stream_idle_timeout: self.stream_idle_timeout(),
+            signer: self.request_signing.as_ref().map(request_signer),
         })
     }
 
@@ -248,6 +284,7 @@ impl ModelProviderInfo {
                 .collect(),
             ),
             // Use global defaults for retry/timeout unless overridden in config.toml.
+            request_signing: None,
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
@@ -321,6 +358,7 @@ pub fn create_oss_provider_with_base_url(base_url: &str, wire_api: WireApi) -> M
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
@@ -350,6 +388,7 @@ base_url = "http://localhost:11434/v1"
             query_params: None,
             http_headers: None,
             env_http_headers: None,
+            request_signing: None,
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
@@ -381,6 +420,7 @@ query_params = { api-version = "2025-04-01-preview" }
             }),
             http_headers: None,
             env_http_headers: None,
+            request_signing: None,
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
@@ -415,6 +455,7 @@ env_http_headers = { "X-Example-Env-Header" = "EXAMPLE_ENV_VAR" }
             env_http_headers: Some(maplit::hashmap! {
                 "X-Example-Env-Header".to_string() => "EXAMPLE_ENV_VAR".to_string(),
             }),
+            request_signing: None,
             request_max_retries: None,
             stream_max_retries: None,
             stream_idle_timeout_ms: None,
@@ -460,6 +460,7 @@ mod tests {
             query_params: None,
             http_headers: None,
             env_http_headers: None,
+            request_signing: None,
             request_max_retries: Some(0),
             stream_max_retries: Some(0),
             stream_idle_timeout_ms: Some(5_000),
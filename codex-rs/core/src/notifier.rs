@@ -0,0 +1,266 @@
+//! User notifications for sessions running in the background.
+//!
+//! Independent of any UI, a session can ping the user when an approval is
+//! needed, a turn completes, or a task fails. Each event type is routed to
+//! the channels listed for it under `[notifier]` in config.toml: desktop
+//! notifications, the terminal bell, or a webhook. Delivery is best effort
+//! and never blocks the session.
+
+use std::io::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::EventMsg;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::types::NotificationChannel;
+use crate::config::types::NotifierConfig;
+use crate::default_client::build_reqwest_client;
+
+const WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+const BODY_MAX_CHARS: usize = 200;
+
+/// Kind of event a notification is sent for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationKind {
+    ApprovalRequested,
+    TurnComplete,
+    TaskFailed,
+}
+
+/// A notification ready to deliver.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct UserNotification {
+    #[serde(rename = "type")]
+    pub kind: NotificationKind,
+    pub thread_id: ThreadId,
+    pub title: String,
+    pub body: String,
+}
+
+impl UserNotification {
+    /// The notification to send for `msg`, if the event is one users can be
+    /// notified about.
+    pub fn from_event(thread_id: ThreadId, msg: &EventMsg) -> Option<Self> {
+        let (kind, title, body) = match msg {
+            EventMsg::ExecApprovalRequest(event) => (
+                NotificationKind::ApprovalRequested,
+                "Codex needs approval to run a command",
+                event.command.join(" "),
+            ),
+            EventMsg::ApplyPatchApprovalRequest(event) => (
+                NotificationKind::ApprovalRequested,
+                "Codex needs approval to edit files",
+                format!("{} file(s) changed", event.changes.len()),
+            ),
+            EventMsg::ElicitationRequest(event) => (
+                NotificationKind::ApprovalRequested,
+                "Codex needs your input",
+                event.message.clone(),
+            ),
+            EventMsg::RequestUserInput(_) => (
+                NotificationKind::ApprovalRequested,
+                "Codex has a question",
+                String::new(),
+            ),
+            EventMsg::TurnComplete(event) => (
+                NotificationKind::TurnComplete,
+                "Codex finished a turn",
+                event.last_agent_message.clone().unwrap_or_default(),
+            ),
+            EventMsg::Error(event) => (
+                NotificationKind::TaskFailed,
+                "Codex task failed",
+                event.message.clone(),
+            ),
+            _ => return None,
+        };
+        let body = if body.chars().count() > BODY_MAX_CHARS {
+            let truncated: String = body.chars().take(BODY_MAX_CHARS).collect();
+            format!("{truncated}…")
+        } else {
+            body
+        };
+        Some(Self {
+            kind,
+            thread_id,
+            title: title.to_string(),
+            body,
+        })
+    }
+}
+
+/// A channel notifications can be delivered through.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, notification: &UserNotification) -> anyhow::Result<()>;
+}
+
+/// Desktop notifications through the platform's notification service.
+pub struct DesktopNotifier;
+
+#[async_trait]
+impl Notifier for DesktopNotifier {
+    async fn notify(&self, notification: &UserNotification) -> anyhow::Result<()> {
+        let mut desktop_notification = notify_rust::Notification::new();
+        desktop_notification
+            .appname("Codex")
+            .summary(&notification.title)
+            .body(&notification.body);
+        tokio::task::spawn_blocking(move || desktop_notification.show().map(drop))
+            .await?
+            .context("failed to show desktop notification")
+    }
+}
+
+/// Rings the terminal bell on stderr.
+pub struct BellNotifier;
+
+#[async_trait]
+impl Notifier for BellNotifier {
+    async fn notify(&self, _notification: &UserNotification) -> anyhow::Result<()> {
+        let mut stderr = std::io::stderr();
+        stderr.write_all(b"\x07")?;
+        stderr.flush()?;
+        Ok(())
+    }
+}
+
+/// POSTs each notification as JSON to a URL.
+pub struct WebhookNotifier {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(url: String) -> Self {
+        Self {
+            url,
+            client: build_reqwest_client(),
+        }
+    }
+}
+
+#[async_trait]
+impl Notifier for WebhookNotifier {
+    async fn notify(&self, notification: &UserNotification) -> anyhow::Result<()> {
+        self.client
+            .post(&self.url)
+            .timeout(WEBHOOK_TIMEOUT)
+            .json(notification)
+            .send()
+            .await
+            .with_context(|| format!("failed to send notification to {}", self.url))?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Routes session events to the notifiers configured for their kind.
+#[derive(Default, Clone)]
+pub(crate) struct NotificationDispatcher {
+    approval_requested: Vec<Arc<dyn Notifier>>,
+    turn_complete: Vec<Arc<dyn Notifier>>,
+    task_failed: Vec<Arc<dyn Notifier>>,
+}
+
+impl NotificationDispatcher {
+    pub(crate) fn new(config: &NotifierConfig) -> Self {
+        let desktop: Arc<dyn Notifier> = Arc::new(DesktopNotifier);
+        let bell: Arc<dyn Notifier> = Arc::new(BellNotifier);
+        let webhook: Option<Arc<dyn Notifier>> = config
+            .webhook_url
+            .clone()
+            .map(|url| Arc::new(WebhookNotifier::new(url)) as Arc<dyn Notifier>);
+        let resolve = |channels: &[NotificationChannel]| {
+            channels
+                .iter()
+                .filter_map(|channel| match channel {
+                    NotificationChannel::Desktop => Some(Arc::clone(&desktop)),
+                    NotificationChannel::Bell => Some(Arc::clone(&bell)),
+                    NotificationChannel::Webhook => {
+                        if webhook.is_none() {
+                            warn!("notifier: `webhook` channel configured without `webhook_url`");
+                        }
+                        webhook.clone()
+                    }
+                })
+                .collect()
+        };
+        Self {
+            approval_requested: resolve(&config.approval_requested),
+            turn_complete: resolve(&config.turn_complete),
+            task_failed: resolve(&config.task_failed),
+        }
+    }
+
+    fn notifiers(&self, kind: NotificationKind) -> &[Arc<dyn Notifier>] {
+        match kind {
+            NotificationKind::ApprovalRequested => &self.approval_requested,
+            NotificationKind::TurnComplete => &self.turn_complete,
+            NotificationKind::TaskFailed => &self.task_failed,
+        }
+    }
+
+    /// Delivers the notification for `msg`, if any, in the background.
+    pub(crate) fn dispatch(&self, thread_id: ThreadId, msg: &EventMsg) {
+        if self.approval_requested.is_empty()
+            && self.turn_complete.is_empty()
+            && self.task_failed.is_empty()
+        {
+            return;
+        }
+        let Some(notification) = UserNotification::from_event(thread_id, msg) else {
+            return;
+        };
+        let notifiers = self.notifiers(notification.kind).to_vec();
+        if notifiers.is_empty() {
+            return;
+        }
+        tokio::spawn(async move {
+            for notifier in notifiers {
+                if let Err(err) = notifier.notify(&notification).await {
+                    warn!("failed to deliver notification: {err:#}");
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::ErrorEvent;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn error_events_become_task_failed_notifications() {
+        let thread_id = ThreadId::new();
+        let notification = UserNotification::from_event(
+            thread_id,
+            &EventMsg::Error(ErrorEvent {
+                message: "x".repeat(250),
+                codex_error_info: None,
+            }),
+        );
+
+        assert_eq!(
+            notification,
+            Some(UserNotification {
+                kind: NotificationKind::TaskFailed,
+                thread_id,
+                title: "Codex task failed".to_string(),
+                body: format!("{}…", "x".repeat(BODY_MAX_CHARS)),
+            })
+        );
+        assert_eq!(
+            UserNotification::from_event(thread_id, &EventMsg::SkillsUpdateAvailable),
+            None
+        );
+    }
+}
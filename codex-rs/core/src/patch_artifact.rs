@@ -0,0 +1,235 @@
+//! Patch artifact mode: propose changes without touching the working tree.
+//!
+//! With `patch_artifact = true` (or `codex exec --patch-output <PATH>`),
+//! `apply_patch` calls are recorded against an in-memory overlay of the
+//! workspace instead of being written to disk, and the shell sandbox is forced
+//! to read-only. Later patches see the proposed contents from earlier ones.
+//! The accumulated changes are available as one unified diff through
+//! `Op::GetPatchArtifact`, and are rewritten to the configured path after
+//! every recorded patch.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_apply_patch::Hunk;
+use codex_apply_patch::apply_chunks_to_contents;
+use similar::TextDiff;
+use tracing::warn;
+
+/// Context lines around each hunk of the artifact, as in `git diff`.
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ProposedFile {
+    /// Contents on disk when the file was first touched, `None` if it did not
+    /// exist.
+    original: Option<String>,
+    /// Proposed contents, `None` if the file is deleted.
+    proposed: Option<String>,
+}
+
+/// The changes proposed so far in a patch artifact session.
+#[derive(Debug)]
+pub(crate) struct PatchArtifact {
+    /// Paths in the artifact are shown relative to this directory.
+    root: PathBuf,
+    output_path: Option<PathBuf>,
+    files: BTreeMap<PathBuf, ProposedFile>,
+}
+
+impl PatchArtifact {
+    pub(crate) fn new(root: PathBuf, output_path: Option<PathBuf>) -> Self {
+        Self {
+            root,
+            output_path,
+            files: BTreeMap::new(),
+        }
+    }
+
+    pub(crate) fn output_path(&self) -> Option<&Path> {
+        self.output_path.as_deref()
+    }
+
+    /// Records `hunks`, resolved against `cwd`, on top of the changes proposed
+    /// so far, re-anchoring drifted context within `fuzz_window` lines. Either
+    /// every hunk is recorded or none is. Returns a summary in the format
+    /// `apply_patch` prints.
+    pub(crate) fn record(
+        &mut self,
+        hunks: &[Hunk],
+        cwd: &Path,
+        fuzz_window: usize,
+    ) -> Result<String, String> {
+        if hunks.is_empty() {
+            return Err("No files were modified.".to_string());
+        }
+        let mut files = self.files.clone();
+        let mut summary =
+            vec!["Recorded in patch artifact; the working tree is unchanged:".to_string()];
+        for hunk in hunks {
+            let path = hunk.resolve_path(cwd);
+            match hunk {
+                Hunk::AddFile { contents, .. } => {
+                    set_proposed(&mut files, &path, Some(contents.clone()));
+                    summary.push(format!("A {}", path.display()));
+                }
+                Hunk::DeleteFile { .. } => {
+                    if current_contents(&files, &path).is_none() {
+                        return Err(format!(
+                            "Failed to delete file {}: not found",
+                            path.display()
+                        ));
+                    }
+                    set_proposed(&mut files, &path, None);
+                    summary.push(format!("D {}", path.display()));
+                }
+                Hunk::UpdateFile {
+                    move_path, chunks, ..
+                } => {
+                    let Some(current) = current_contents(&files, &path) else {
+                        return Err(format!(
+                            "Failed to read file to update {}: not found",
+                            path.display()
+                        ));
+                    };
+                    let new_contents =
+                        apply_chunks_to_contents(&path, &current, chunks, fuzz_window)
+                            .map_err(|err| err.to_string())?;
+                    match move_path {
+                        Some(dest) => {
+                            let dest = cwd.join(dest);
+                            set_proposed(&mut files, &path, None);
+                            set_proposed(&mut files, &dest, Some(new_contents));
+                            summary.push(format!("M {}", dest.display()));
+                        }
+                        None => {
+                            set_proposed(&mut files, &path, Some(new_contents));
+                            summary.push(format!("M {}", path.display()));
+                        }
+                    }
+                }
+            }
+        }
+        self.files = files;
+        Ok(summary.join("\n"))
+    }
+
+    /// Writes the artifact to the configured output path, if any.
+    pub(crate) async fn write(&self) {
+        if let Some(path) = &self.output_path
+            && let Err(err) = tokio::fs::write(path, self.unified_diff()).await
+        {
+            warn!(
+                "failed to write patch artifact to {}: {err}",
+                path.display()
+            );
+        }
+    }
+
+    /// All proposed changes as one unified diff, in `git diff` format.
+    pub(crate) fn unified_diff(&self) -> String {
+        let mut diff = String::new();
+        for (path, file) in &self.files {
+            if file.original == file.proposed {
+                continue;
+            }
+            let display = path
+                .strip_prefix(&self.root)
+                .unwrap_or(path)
+                .to_string_lossy()
+                .replace('\\', "/");
+            let old = file.original.as_deref().unwrap_or_default();
+            let new = file.proposed.as_deref().unwrap_or_default();
+            diff.push_str(&format!("diff --git a/{display} b/{display}\n"));
+            let old_header = match file.original {
+                Some(_) => format!("a/{display}"),
+                None => {
+                    diff.push_str("new file mode 100644\n");
+                    "/dev/null".to_string()
+                }
+            };
+            let new_header = match file.proposed {
+                Some(_) => format!("b/{display}"),
+                None => {
+                    diff.push_str("deleted file mode 100644\n");
+                    "/dev/null".to_string()
+                }
+            };
+            diff.push_str(
+                &TextDiff::from_lines(old, new)
+                    .unified_diff()
+                    .context_radius(CONTEXT_LINES)
+                    .header(&old_header, &new_header)
+                    .to_string(),
+            );
+        }
+        diff
+    }
+}
+
+fn current_contents(files: &BTreeMap<PathBuf, ProposedFile>, path: &Path) -> Option<String> {
+    match files.get(path) {
+        Some(file) => file.proposed.clone(),
+        None => std::fs::read_to_string(path).ok(),
+    }
+}
+
+fn set_proposed(
+    files: &mut BTreeMap<PathBuf, ProposedFile>,
+    path: &Path,
+    proposed: Option<String>,
+) {
+    files
+        .entry(path.to_path_buf())
+        .or_insert_with(|| ProposedFile {
+            original: std::fs::read_to_string(path).ok(),
+            proposed: None,
+        })
+        .proposed = proposed;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_apply_patch::parse_patch;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn records_patches_without_touching_the_tree() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let root = dir.path();
+        std::fs::write(root.join("a.txt"), "one\ntwo\nthree\n").expect("write a.txt");
+        let mut artifact = PatchArtifact::new(root.to_path_buf(), None);
+
+        let first = parse_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n one\n-two\n+TWO\n*** Add File: b.txt\n+new\n*** End Patch",
+        )
+        .expect("parse first patch");
+        artifact
+            .record(&first.hunks, root, 0)
+            .expect("record first patch");
+        // The second patch builds on the proposed contents of the first.
+        let second = parse_patch(
+            "*** Begin Patch\n*** Update File: a.txt\n@@\n TWO\n-three\n+THREE\n*** Delete File: b.txt\n*** End Patch",
+        )
+        .expect("parse second patch");
+        artifact
+            .record(&second.hunks, root, 0)
+            .expect("record second patch");
+
+        assert_eq!(
+            artifact.unified_diff(),
+            "diff --git a/a.txt b/a.txt\n--- a/a.txt\n+++ b/a.txt\n@@ -1,3 +1,3 @@\n one\n-two\n-three\n+TWO\n+THREE\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("a.txt")).expect("read a.txt"),
+            "one\ntwo\nthree\n"
+        );
+        assert!(!root.join("b.txt").exists());
+
+        let missing = parse_patch("*** Begin Patch\n*** Delete File: b.txt\n*** End Patch")
+            .expect("parse delete");
+        assert!(artifact.record(&missing.hunks, root, 0).is_err());
+    }
+}
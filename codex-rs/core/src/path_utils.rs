@@ -6,12 +6,41 @@ use std::path::PathBuf;
 use tempfile::NamedTempFile;
 
 use crate::env;
+use crate::protocol::SandboxPolicy;
 
 pub fn normalize_for_path_comparison(path: impl AsRef<Path>) -> std::io::Result<PathBuf> {
     let canonical = path.as_ref().canonicalize()?;
     Ok(normalize_for_wsl(canonical))
 }
 
+/// Resolves a working directory requested mid-session against the current
+/// one. Under a workspace-write sandbox the new directory must already be
+/// writable, read-only subpaths like `.git` excluded, so moving the cwd
+/// cannot widen what the agent may modify.
+pub(crate) fn resolve_cwd_change(
+    sandbox_policy: &SandboxPolicy,
+    current_cwd: &Path,
+    requested: &Path,
+) -> Result<PathBuf, String> {
+    let cwd = dunce::canonicalize(current_cwd.join(requested))
+        .map_err(|err| format!("cannot change directory to {}: {err}", requested.display()))?;
+    if !cwd.is_dir() {
+        return Err(format!("{} is not a directory", cwd.display()));
+    }
+    if matches!(sandbox_policy, SandboxPolicy::WorkspaceWrite { .. })
+        && !sandbox_policy
+            .get_writable_roots_with_cwd(current_cwd)
+            .iter()
+            .any(|root| root.is_path_writable(&cwd))
+    {
+        return Err(format!(
+            "{} is outside the sandbox's writable roots; update the sandbox policy first",
+            cwd.display()
+        ));
+    }
+    Ok(cwd)
+}
+
 pub struct SymlinkWritePaths {
     pub read_path: Option<PathBuf>,
     pub write_path: PathBuf,
@@ -188,6 +217,44 @@ fn lower_ascii_path(path: PathBuf) -> PathBuf {
 
 #[cfg(test)]
 mod tests {
+    use super::resolve_cwd_change;
+    use crate::protocol::SandboxPolicy;
+    use pretty_assertions::assert_eq;
+    use std::path::Path;
+
+    #[test]
+    fn cwd_changes_stay_within_workspace_write_roots() -> std::io::Result<()> {
+        let workspace = tempfile::tempdir()?;
+        let outside = tempfile::tempdir()?;
+        std::fs::create_dir(workspace.path().join("crate"))?;
+        std::fs::create_dir(workspace.path().join(".git"))?;
+        let workspace_path = dunce::canonicalize(workspace.path())?;
+        let policy = SandboxPolicy::WorkspaceWrite {
+            writable_roots: Vec::new(),
+            read_only_access: Default::default(),
+            network_access: false,
+            exclude_tmpdir_env_var: true,
+            exclude_slash_tmp: true,
+        };
+
+        assert_eq!(
+            resolve_cwd_change(&policy, &workspace_path, Path::new("crate")),
+            Ok(workspace_path.join("crate"))
+        );
+        assert!(resolve_cwd_change(&policy, &workspace_path, outside.path()).is_err());
+        assert!(resolve_cwd_change(&policy, &workspace_path, Path::new("missing")).is_err());
+        assert!(resolve_cwd_change(&policy, &workspace_path, Path::new(".git")).is_err());
+        assert_eq!(
+            resolve_cwd_change(
+                &SandboxPolicy::DangerFullAccess,
+                &workspace_path,
+                outside.path()
+            ),
+            Ok(dunce::canonicalize(outside.path())?)
+        );
+        Ok(())
+    }
+
     #[cfg(unix)]
     mod symlinks {
         use super::super::resolve_symlink_write_paths;
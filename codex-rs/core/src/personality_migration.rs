@@ -177,6 +177,8 @@ mod tests {
                 model_provider: None,
                 base_instructions: None,
                 dynamic_tools: None,
+                instruction_layers: None,
+                experiments: None,
             },
             git: None,
         };
@@ -0,0 +1,198 @@
+//! Rate-limit forecasts ([`Op::ForecastRateLimits`]), for planning long
+//! autonomous runs.
+//!
+//! The session keeps every rate-limit snapshot it receives together with the
+//! time it arrived. A window's snapshots since it last reset give its burn
+//! rate. A reset shows as usage dropping, or as a snapshot arriving after the
+//! reset time of the one before it, which catches resets that usage has
+//! already grown past; at that rate the forecast says when the window runs out, and how
+//! fast usage may go while still lasting until the reset.
+//!
+//! [`Op::ForecastRateLimits`]: codex_protocol::protocol::Op::ForecastRateLimits
+
+use std::collections::VecDeque;
+
+use codex_protocol::protocol::RateLimitSnapshot;
+use codex_protocol::protocol::RateLimitWindow;
+use codex_protocol::protocol::RateLimitWindowForecast;
+use codex_protocol::protocol::RateLimitWindowKind;
+
+/// Snapshots kept; the oldest are dropped first.
+const MAX_SAMPLES: usize = 256;
+const SECONDS_PER_HOUR: f64 = 3600.0;
+
+/// Rate-limit snapshots received this session, oldest first.
+#[derive(Debug, Default)]
+pub(crate) struct RateLimitSamples {
+    samples: VecDeque<(i64, RateLimitSnapshot)>,
+}
+
+impl RateLimitSamples {
+    /// Keeps `snapshot`, received at unix time `observed_at`.
+    pub(crate) fn record(&mut self, observed_at: i64, snapshot: RateLimitSnapshot) {
+        if self.samples.len() == MAX_SAMPLES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((observed_at, snapshot));
+    }
+
+    /// Forecasts every window of the latest snapshot of each limit.
+    pub(crate) fn forecast(&self, now: i64) -> Vec<RateLimitWindowForecast> {
+        let mut limit_ids: Vec<Option<&str>> = Vec::new();
+        for (_, snapshot) in &self.samples {
+            let limit_id = snapshot.limit_id.as_deref();
+            if !limit_ids.contains(&limit_id) {
+                limit_ids.push(limit_id);
+            }
+        }
+
+        let mut forecasts = Vec::new();
+        for limit_id in limit_ids {
+            let samples: Vec<&(i64, RateLimitSnapshot)> = self
+                .samples
+                .iter()
+                .filter(|(_, snapshot)| snapshot.limit_id.as_deref() == limit_id)
+                .collect();
+            for kind in [RateLimitWindowKind::Primary, RateLimitWindowKind::Secondary] {
+                let window_samples: Vec<(i64, &RateLimitWindow)> = samples
+                    .iter()
+                    .filter_map(|(observed_at, snapshot)| {
+                        let window = match kind {
+                            RateLimitWindowKind::Primary => snapshot.primary.as_ref(),
+                            RateLimitWindowKind::Secondary => snapshot.secondary.as_ref(),
+                        };
+                        window.map(|window| (*observed_at, window))
+                    })
+                    .collect();
+                let Some(&(latest_at, latest)) = window_samples.last() else {
+                    continue;
+                };
+                // Only samples since the last reset describe the current
+                // burn rate.
+                let mut start = window_samples.len() - 1;
+                while start > 0 {
+                    let (_, earlier) = window_samples[start - 1];
+                    let (later_at, later) = window_samples[start];
+                    let same_window = earlier.used_percent <= later.used_percent
+                        && earlier
+                            .resets_at
+                            .is_none_or(|resets_at| later_at < resets_at);
+                    if !same_window {
+                        break;
+                    }
+                    start -= 1;
+                }
+                let (first_at, first) = window_samples[start];
+
+                let burn_percent_per_hour = (latest_at > first_at).then(|| {
+                    (latest.used_percent - first.used_percent).max(0.0)
+                        / ((latest_at - first_at) as f64 / SECONDS_PER_HOUR)
+                });
+                let remaining_percent = (100.0 - latest.used_percent).max(0.0);
+                let exhausted_at = burn_percent_per_hour
+                    .filter(|rate| *rate > 0.0)
+                    .map(|rate| {
+                        latest_at + (remaining_percent / rate * SECONDS_PER_HOUR).round() as i64
+                    })
+                    .filter(|exhausted_at| {
+                        latest
+                            .resets_at
+                            .is_none_or(|resets_at| *exhausted_at < resets_at)
+                    });
+                let sustainable_percent_per_hour = latest.resets_at.map(|resets_at| {
+                    let hours_left = (resets_at - now).max(1) as f64 / SECONDS_PER_HOUR;
+                    remaining_percent / hours_left
+                });
+
+                forecasts.push(RateLimitWindowForecast {
+                    limit_id: limit_id.unwrap_or("codex").to_string(),
+                    window: kind,
+                    used_percent: latest.used_percent,
+                    resets_at: latest.resets_at,
+                    burn_percent_per_hour,
+                    exhausted_at,
+                    sustainable_percent_per_hour,
+                });
+            }
+        }
+        forecasts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn snapshot(used_percent: f64, resets_at: i64) -> RateLimitSnapshot {
+        RateLimitSnapshot {
+            limit_id: Some("codex".to_string()),
+            limit_name: None,
+            primary: Some(RateLimitWindow {
+                used_percent,
+                window_minutes: Some(300),
+                resets_at: Some(resets_at),
+            }),
+            secondary: None,
+            credits: None,
+            plan_type: None,
+        }
+    }
+
+    #[test]
+    fn forecasts_exhaustion_from_the_burn_rate_since_the_last_reset() {
+        let mut samples = RateLimitSamples::default();
+        // The previous window, which must not count towards the burn rate.
+        samples.record(0, snapshot(90.0, 3_600));
+        samples.record(3_600, snapshot(10.0, 36_000));
+        samples.record(7_200, snapshot(30.0, 36_000));
+
+        assert_eq!(
+            samples.forecast(7_200),
+            vec![RateLimitWindowForecast {
+                limit_id: "codex".to_string(),
+                window: RateLimitWindowKind::Primary,
+                used_percent: 30.0,
+                resets_at: Some(36_000),
+                burn_percent_per_hour: Some(20.0),
+                exhausted_at: Some(7_200 + 3 * 3_600 + 1_800),
+                sustainable_percent_per_hour: Some(8.75),
+            }]
+        );
+    }
+
+    #[test]
+    fn detects_resets_that_usage_has_grown_past() {
+        let mut samples = RateLimitSamples::default();
+        // The previous window reset at 3_600; by the next snapshot usage is
+        // already higher than before the reset.
+        samples.record(0, snapshot(10.0, 3_600));
+        samples.record(5_400, snapshot(20.0, 21_600));
+        samples.record(9_000, snapshot(30.0, 21_600));
+
+        assert_eq!(
+            samples.forecast(9_000),
+            vec![RateLimitWindowForecast {
+                limit_id: "codex".to_string(),
+                window: RateLimitWindowKind::Primary,
+                used_percent: 30.0,
+                resets_at: Some(21_600),
+                burn_percent_per_hour: Some(10.0),
+                exhausted_at: None,
+                sustainable_percent_per_hour: Some(20.0),
+            }]
+        );
+    }
+
+    #[test]
+    fn windows_that_reset_first_are_not_exhausted() {
+        let mut samples = RateLimitSamples::default();
+        samples.record(0, snapshot(10.0, 7_200));
+        assert_eq!(samples.forecast(0)[0].burn_percent_per_hour, None);
+
+        samples.record(3_600, snapshot(20.0, 7_200));
+        let forecast = &samples.forecast(3_600)[0];
+        assert_eq!(forecast.burn_percent_per_hour, Some(10.0));
+        assert_eq!(forecast.exhausted_at, None);
+    }
+}
@@ -0,0 +1,238 @@
+//! Filters applied to file content read with `read_file` before it reaches
+//! the model (see `read_filters` in config.toml).
+//!
+//! Each rule pairs a glob with a list of filters. Every rule matching a path
+//! contributes its filters, in config order, and they run as a pipeline. The
+//! filters are lossy on purpose: they trade exact content for tokens on files
+//! whose details rarely matter, and leave content unchanged when they do not
+//! apply (for example, `minify_json` on invalid JSON).
+
+use std::path::Path;
+
+use codex_utils_string::take_bytes_at_char_boundary;
+use wildmatch::WildMatch;
+
+use crate::config::types::ReadFilter;
+use crate::config::types::ReadFilterRule;
+
+/// Minified JSON is wrapped at this width so `read_file`'s line length cap
+/// does not cut it.
+const MINIFIED_LINE_BYTES: usize = 400;
+/// Top-level lines kept when summarizing generated code.
+const GENERATED_SUMMARY_LINES: usize = 200;
+
+/// The filters that apply to `path`, from every rule whose glob matches it.
+pub(crate) fn filters_for_path(
+    rules: &[ReadFilterRule],
+    path: &Path,
+    cwd: &Path,
+) -> Vec<ReadFilter> {
+    let relative = path
+        .strip_prefix(cwd)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    rules
+        .iter()
+        .filter(|rule| {
+            let glob = WildMatch::new(&rule.glob);
+            glob.matches(&relative) || glob.matches(&file_name)
+        })
+        .flat_map(|rule| rule.filters.iter().copied())
+        .collect()
+}
+
+/// Runs `filters` over `content`, the contents of `path`, in order.
+pub(crate) fn apply_read_filters(filters: &[ReadFilter], path: &Path, content: &str) -> String {
+    filters
+        .iter()
+        .fold(content.to_string(), |content, filter| match filter {
+            ReadFilter::StripComments => strip_comments(path, &content),
+            ReadFilter::CollapseLockfile => collapse_lockfile(&content),
+            ReadFilter::SummarizeGenerated => summarize_generated(&content),
+            ReadFilter::MinifyJson => minify_json(&content),
+        })
+}
+
+pub(crate) fn read_filter_name(filter: ReadFilter) -> &'static str {
+    match filter {
+        ReadFilter::StripComments => "strip_comments",
+        ReadFilter::CollapseLockfile => "collapse_lockfile",
+        ReadFilter::SummarizeGenerated => "summarize_generated",
+        ReadFilter::MinifyJson => "minify_json",
+    }
+}
+
+fn strip_comments(path: &Path, content: &str) -> String {
+    let extension = path
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let (line_prefix, block) = match extension.as_str() {
+        "rs" | "c" | "h" | "cc" | "cpp" | "hpp" | "java" | "kt" | "swift" | "go" | "js" | "jsx"
+        | "ts" | "tsx" | "cs" | "scala" | "dart" => ("//", Some(("/*", "*/"))),
+        "py" | "sh" | "bash" | "zsh" | "rb" | "pl" | "toml" | "yaml" | "yml" | "r" => ("#", None),
+        "sql" | "lua" | "hs" => ("--", None),
+        _ => return content.to_string(),
+    };
+
+    let mut kept = Vec::new();
+    let mut block_close = None;
+    for line in content.lines() {
+        let trimmed = line.trim_start();
+        if let Some(close) = block_close {
+            if trimmed.contains(close) {
+                block_close = None;
+            }
+            continue;
+        }
+        if trimmed.starts_with(line_prefix) {
+            continue;
+        }
+        if let Some((open, close)) = block
+            && let Some(rest) = trimmed.strip_prefix(open)
+        {
+            match rest.find(close) {
+                // Keep code that follows a closed block comment.
+                Some(end) if !rest[end + close.len()..].trim().is_empty() => {}
+                Some(_) => continue,
+                None => {
+                    block_close = Some(close);
+                    continue;
+                }
+            }
+        }
+        kept.push(line);
+    }
+    kept.join("\n")
+}
+
+fn collapse_lockfile(content: &str) -> String {
+    let mut summary = format!(
+        "[lockfile collapsed by read filter: {} lines, {} bytes]",
+        content.lines().count(),
+        content.len()
+    );
+    // TOML lockfiles (Cargo.lock, poetry.lock, uv.lock) list one
+    // `[[package]]` table per locked package.
+    let mut packages = Vec::new();
+    let mut in_package = false;
+    let mut name = None;
+    for line in content.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_package = line == "[[package]]";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        if let Some(value) = line.strip_prefix("name = ") {
+            name = Some(value.trim_matches('"').to_string());
+        } else if let Some(value) = line.strip_prefix("version = ")
+            && let Some(name) = name.take()
+        {
+            packages.push(format!("{name} {}", value.trim_matches('"')));
+        }
+    }
+    if !packages.is_empty() {
+        summary.push_str(&format!(
+            "\n{} packages:\n{}",
+            packages.len(),
+            packages.join("\n")
+        ));
+    }
+    summary
+}
+
+fn summarize_generated(content: &str) -> String {
+    let total = content.lines().count();
+    let top_level: Vec<&str> = content
+        .lines()
+        .filter(|line| {
+            !line.trim().is_empty()
+                && !line.starts_with(char::is_whitespace)
+                && !line.starts_with(['}', ')', ']'])
+        })
+        .take(GENERATED_SUMMARY_LINES)
+        .collect();
+    format!(
+        "[generated file summarized by read filter: {total} lines; top-level lines shown]\n{}",
+        top_level.join("\n")
+    )
+}
+
+fn minify_json(content: &str) -> String {
+    let Ok(value) = serde_json::from_str::<serde_json::Value>(content) else {
+        return content.to_string();
+    };
+    let mut rest = value.to_string();
+    let mut lines = Vec::new();
+    while !rest.is_empty() {
+        let line = take_bytes_at_char_boundary(&rest, MINIFIED_LINE_BYTES).to_string();
+        rest = rest.split_off(line.len());
+        lines.push(line);
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn matching_rules_compose_in_order() {
+        let rules = vec![
+            ReadFilterRule {
+                glob: "*.json".to_string(),
+                filters: vec![ReadFilter::MinifyJson],
+            },
+            ReadFilterRule {
+                glob: "generated/*".to_string(),
+                filters: vec![ReadFilter::SummarizeGenerated],
+            },
+        ];
+        let cwd = Path::new("/repo");
+
+        assert_eq!(
+            filters_for_path(&rules, Path::new("/repo/generated/schema.json"), cwd),
+            vec![ReadFilter::MinifyJson, ReadFilter::SummarizeGenerated]
+        );
+        assert_eq!(
+            filters_for_path(&rules, Path::new("/repo/src/lib.rs"), cwd),
+            Vec::new()
+        );
+    }
+
+    #[test]
+    fn filters_shrink_content() {
+        assert_eq!(
+            apply_read_filters(
+                &[ReadFilter::StripComments],
+                Path::new("lib.rs"),
+                "// header\n#[derive(Debug)]\n/* block\n   comment */\nstruct A; // trailing\n",
+            ),
+            "#[derive(Debug)]\nstruct A; // trailing"
+        );
+        assert_eq!(
+            apply_read_filters(
+                &[ReadFilter::MinifyJson],
+                Path::new("a.json"),
+                "{\n  \"a\": [1, 2]\n}\n"
+            ),
+            "{\"a\":[1,2]}"
+        );
+        assert_eq!(
+            apply_read_filters(
+                &[ReadFilter::CollapseLockfile],
+                Path::new("Cargo.lock"),
+                "version = 4\n\n[[package]]\nname = \"anyhow\"\nversion = \"1.0.0\"\n",
+            ),
+            "[lockfile collapsed by read filter: 5 lines, 59 bytes]\n1 packages:\nanyhow 1.0.0"
+        );
+    }
+}
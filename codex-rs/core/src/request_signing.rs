@@ -0,0 +1,321 @@
+//! Request signing for providers behind enterprise LLM gateways
+//! (`request_signing` in a `[model_providers.<id>]` table).
+//!
+//! The signer sees every HTTP request to the provider once its static and
+//! environment headers and the auth headers are in place, and the handshake
+//! of its WebSocket connections. Gateways that only want extra static
+//! headers need no signer: `http_headers` and `env_http_headers` cover them.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::time::Duration;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use async_trait::async_trait;
+use codex_api::RequestSigner;
+use codex_api::TransportError;
+use codex_client::Request;
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use hmac::Hmac;
+use hmac::Mac;
+use http::HeaderMap;
+use http::HeaderName;
+use http::HeaderValue;
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+use url::Url;
+
+use crate::model_provider_info::RequestSigningConfig;
+
+const KEYRING_SERVICE: &str = "codex";
+const DEFAULT_SIGNATURE_HEADER: &str = "X-Codex-Signature";
+const DEFAULT_TIMESTAMP_HEADER: &str = "X-Codex-Timestamp";
+const DEFAULT_COMMAND_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// HMAC keys read from the keychain by this process, by account, so the
+/// keychain is not asked on every request.
+static HMAC_KEYS: LazyLock<Mutex<HashMap<String, Arc<[u8]>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The signer `config` describes.
+pub(crate) fn request_signer(config: &RequestSigningConfig) -> Arc<dyn RequestSigner> {
+    match config.clone() {
+        RequestSigningConfig::Hmac {
+            keychain_account,
+            signature_header,
+            timestamp_header,
+        } => Arc::new(HmacSigner {
+            keychain_account,
+            signature_header: signature_header
+                .unwrap_or_else(|| DEFAULT_SIGNATURE_HEADER.to_string()),
+            timestamp_header: timestamp_header
+                .unwrap_or_else(|| DEFAULT_TIMESTAMP_HEADER.to_string()),
+        }),
+        RequestSigningConfig::Command {
+            command,
+            args,
+            timeout_ms,
+        } => Arc::new(CommandSigner {
+            command,
+            args,
+            timeout: timeout_ms
+                .map(Duration::from_millis)
+                .unwrap_or(DEFAULT_COMMAND_TIMEOUT),
+        }),
+    }
+}
+
+#[derive(Debug)]
+struct HmacSigner {
+    keychain_account: String,
+    signature_header: String,
+    timestamp_header: String,
+}
+
+impl HmacSigner {
+    async fn key(&self) -> Result<Arc<[u8]>, TransportError> {
+        if let Some(key) = lock_keys().get(&self.keychain_account) {
+            return Ok(Arc::clone(key));
+        }
+        let account = self.keychain_account.clone();
+        let stored = tokio::task::spawn_blocking(move || {
+            DefaultKeyringStore.load(KEYRING_SERVICE, &account)
+        })
+        .await
+        .map_err(signing_error)?
+        .map_err(|err| {
+            signing_error(format!(
+                "failed to load `{}` from the OS keychain: {}",
+                self.keychain_account,
+                err.message()
+            ))
+        })?;
+        let Some(stored) = stored else {
+            return Err(signing_error(format!(
+                "the OS keychain has no `{}` key",
+                self.keychain_account
+            )));
+        };
+        let key: Arc<[u8]> = Arc::from(stored.trim().as_bytes());
+        lock_keys().insert(self.keychain_account.clone(), Arc::clone(&key));
+        Ok(key)
+    }
+}
+
+#[async_trait]
+impl RequestSigner for HmacSigner {
+    async fn sign(&self, request: &mut Request) -> Result<(), TransportError> {
+        let key = self.key().await?;
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .unwrap_or_default()
+            .to_string();
+        let signature = hmac_signature(&key, &timestamp, request)?;
+        insert_header(&mut request.headers, &self.timestamp_header, &timestamp)?;
+        insert_header(&mut request.headers, &self.signature_header, &signature)
+    }
+}
+
+/// Hex HMAC-SHA256 of `"{timestamp}\n{method}\n{path_and_query}\n{body}"`,
+/// where the body is the bytes sent, compressed when the request is.
+fn hmac_signature(
+    key: &[u8],
+    timestamp: &str,
+    request: &Request,
+) -> Result<String, TransportError> {
+    let url = Url::parse(&request.url).map_err(signing_error)?;
+    let path_and_query = match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    };
+    // The transport serializes and compresses the body the same way before
+    // sending it.
+    let body = match &request.body {
+        Some(body) => request
+            .compression
+            .compress(serde_json::to_vec(body).map_err(signing_error)?)
+            .map_err(signing_error)?,
+        None => Vec::new(),
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).map_err(signing_error)?;
+    mac.update(format!("{timestamp}\n{}\n{path_and_query}\n", request.method).as_bytes());
+    mac.update(&body);
+    Ok(format!("{:x}", mac.finalize().into_bytes()))
+}
+
+#[derive(Debug)]
+struct CommandSigner {
+    command: String,
+    args: Vec<String>,
+    timeout: Duration,
+}
+
+/// What the signer command receives on stdin.
+#[derive(Serialize)]
+struct CommandSignerInput<'a> {
+    method: &'a str,
+    url: &'a str,
+    headers: BTreeMap<&'a str, &'a str>,
+    body: Option<&'a Value>,
+}
+
+#[async_trait]
+impl RequestSigner for CommandSigner {
+    async fn sign(&self, request: &mut Request) -> Result<(), TransportError> {
+        let input = serde_json::to_vec(&CommandSignerInput {
+            method: request.method.as_str(),
+            url: &request.url,
+            headers: request
+                .headers
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)))
+                .collect(),
+            body: request.body.as_ref(),
+        })
+        .map_err(signing_error)?;
+
+        let mut child = Command::new(&self.command)
+            .args(&self.args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| signing_error(format!("failed to run `{}`: {err}", self.command)))?;
+        let output = tokio::time::timeout(self.timeout, async move {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(&input).await?;
+            }
+            child.wait_with_output().await
+        })
+        .await
+        .map_err(|_| {
+            signing_error(format!(
+                "`{}` timed out after {}ms",
+                self.command,
+                self.timeout.as_millis()
+            ))
+        })?
+        .map_err(|err| signing_error(format!("`{}` failed: {err}", self.command)))?;
+        if !output.status.success() {
+            return Err(signing_error(format!(
+                "`{}` exited with {}: {}",
+                self.command,
+                output.status,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+
+        let headers: HashMap<String, String> =
+            serde_json::from_slice(&output.stdout).map_err(|err| {
+                signing_error(format!(
+                    "`{}` did not print a JSON object of headers: {err}",
+                    self.command
+                ))
+            })?;
+        for (name, value) in headers {
+            insert_header(&mut request.headers, &name, &value)?;
+        }
+        Ok(())
+    }
+}
+
+fn insert_header(headers: &mut HeaderMap, name: &str, value: &str) -> Result<(), TransportError> {
+    let name = HeaderName::try_from(name)
+        .map_err(|err| signing_error(format!("invalid header name `{name}`: {err}")))?;
+    let value = HeaderValue::try_from(value)
+        .map_err(|err| signing_error(format!("invalid value for header `{name}`: {err}")))?;
+    headers.insert(name, value);
+    Ok(())
+}
+
+fn signing_error(message: impl Display) -> TransportError {
+    TransportError::Build(format!("request signing failed: {message}"))
+}
+
+fn lock_keys() -> std::sync::MutexGuard<'static, HashMap<String, Arc<[u8]>>> {
+    match HMAC_KEYS.lock() {
+        Ok(guard) => guard,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_client::RequestCompression;
+    use http::Method;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn hmac_signs_method_path_and_body() {
+        let mut request = Request::new(
+            Method::POST,
+            "https://gateway.example.com/v1/responses?api-version=2".to_string(),
+        );
+        request.body = Some(json!({"model": "gpt-5"}));
+
+        let mut expected = Hmac::<Sha256>::new_from_slice(b"secret").expect("key");
+        expected.update(b"1700000000\nPOST\n/v1/responses?api-version=2\n{\"model\":\"gpt-5\"}");
+        assert_eq!(
+            hmac_signature(b"secret", "1700000000", &request).expect("signature"),
+            format!("{:x}", expected.finalize().into_bytes())
+        );
+    }
+
+    #[test]
+    fn hmac_signs_the_compressed_body_when_the_request_is_compressed() {
+        let mut request = Request::new(
+            Method::POST,
+            "https://gateway.example.com/v1/responses".to_string(),
+        )
+        .with_compression(RequestCompression::Zstd);
+        request.body = Some(json!({"model": "gpt-5"}));
+
+        let compressed =
+            zstd::stream::encode_all(std::io::Cursor::new(br#"{"model":"gpt-5"}"#.to_vec()), 3)
+                .expect("compress");
+        let mut expected = Hmac::<Sha256>::new_from_slice(b"secret").expect("key");
+        expected.update(b"1700000000\nPOST\n/v1/responses\n");
+        expected.update(&compressed);
+        assert_eq!(
+            hmac_signature(b"secret", "1700000000", &request).expect("signature"),
+            format!("{:x}", expected.finalize().into_bytes())
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn command_signer_adds_the_printed_headers() {
+        let signer = CommandSigner {
+            command: "sh".to_string(),
+            args: vec![
+                "-c".to_string(),
+                r#"cat > /dev/null; echo '{"X-Gateway-Auth": "signed"}'"#.to_string(),
+            ],
+            timeout: DEFAULT_COMMAND_TIMEOUT,
+        };
+        let mut request = Request::new(Method::GET, "https://gateway.example.com/v1".to_string());
+        signer.sign(&mut request).await.expect("sign");
+
+        assert_eq!(
+            request
+                .headers
+                .get("x-gateway-auth")
+                .and_then(|value| value.to_str().ok()),
+            Some("signed")
+        );
+    }
+}
@@ -1,3 +1,8 @@
+use std::path::Path;
+
+use serde_json::Value;
+use serde_json::json;
+
 use crate::protocol::ReviewFinding;
 use crate::protocol::ReviewOutputEvent;
 
@@ -80,3 +85,129 @@ pub fn render_review_output_text(output: &ReviewOutputEvent) -> String {
         sections.join("\n\n")
     }
 }
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_RULE_ID: &str = "codex-review-finding";
+
+/// Convert review findings into a SARIF 2.1.0 log so they can be uploaded to
+/// code scanning services or shown in IDE problem panes.
+///
+/// Paths under `root` are emitted relative to it (against the `%SRCROOT%`
+/// base), which is what GitHub code scanning expects. Finding priorities map
+/// to SARIF levels: P0/P1 are errors, P2 warnings, and anything lower notes.
+pub fn review_output_to_sarif(output: &ReviewOutputEvent, root: &Path) -> Value {
+    let results: Vec<Value> = output
+        .findings
+        .iter()
+        .map(|finding| {
+            let path = &finding.code_location.absolute_file_path;
+            let artifact_location = match path.strip_prefix(root) {
+                Ok(relative) => json!({
+                    "uri": relative.to_string_lossy().replace('\\', "/"),
+                    "uriBaseId": "%SRCROOT%",
+                }),
+                Err(_) => json!({ "uri": path.to_string_lossy().replace('\\', "/") }),
+            };
+            let start = finding.code_location.line_range.start.max(1);
+            let end = finding.code_location.line_range.end.max(start);
+            let level = match finding.priority {
+                i32::MIN..=1 => "error",
+                2 => "warning",
+                _ => "note",
+            };
+            json!({
+                "ruleId": SARIF_RULE_ID,
+                "level": level,
+                "message": { "text": format!("{}\n\n{}", finding.title, finding.body.trim()) },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": artifact_location,
+                        "region": { "startLine": start, "endLine": end },
+                    },
+                }],
+                "properties": {
+                    "priority": finding.priority,
+                    "confidence": finding.confidence_score,
+                },
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "codex",
+                    "informationUri": "https://github.com/openai/codex",
+                    "rules": [{
+                        "id": SARIF_RULE_ID,
+                        "shortDescription": { "text": "Issue reported by a Codex review" },
+                    }],
+                },
+            },
+            "results": results,
+        }],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::protocol::ReviewCodeLocation;
+    use crate::protocol::ReviewLineRange;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    #[test]
+    fn sarif_uses_repo_relative_paths_and_priority_levels() {
+        let finding = |path: &str, priority| ReviewFinding {
+            title: "[P1] SQL injection (CWE-89)".to_string(),
+            body: "User input reaches the query unescaped.\n".to_string(),
+            confidence_score: 0.9,
+            priority,
+            code_location: ReviewCodeLocation {
+                absolute_file_path: PathBuf::from(path),
+                line_range: ReviewLineRange { start: 10, end: 12 },
+            },
+        };
+        let output = ReviewOutputEvent {
+            findings: vec![finding("/repo/src/db.rs", 1), finding("/elsewhere/a.rs", 3)],
+            ..Default::default()
+        };
+
+        let sarif = review_output_to_sarif(&output, Path::new("/repo"));
+
+        let message = "[P1] SQL injection (CWE-89)\n\nUser input reaches the query unescaped.";
+        assert_eq!(
+            sarif["runs"][0]["results"],
+            json!([
+                {
+                    "ruleId": SARIF_RULE_ID,
+                    "level": "error",
+                    "message": { "text": message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "src/db.rs", "uriBaseId": "%SRCROOT%" },
+                            "region": { "startLine": 10, "endLine": 12 },
+                        },
+                    }],
+                    "properties": { "priority": 1, "confidence": 0.9f32 },
+                },
+                {
+                    "ruleId": SARIF_RULE_ID,
+                    "level": "note",
+                    "message": { "text": message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "/elsewhere/a.rs" },
+                            "region": { "startLine": 10, "endLine": 12 },
+                        },
+                    }],
+                    "properties": { "priority": 3, "confidence": 0.9f32 },
+                },
+            ])
+        );
+    }
+}
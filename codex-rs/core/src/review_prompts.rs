@@ -19,6 +19,53 @@ const COMMIT_PROMPT_WITH_TITLE: &str = "Review the code changes introduced by co
 const COMMIT_PROMPT: &str =
     "Review the code changes introduced by commit {sha}. Provide prioritized, actionable findings.";
 
+const PULL_REQUEST_PROMPT: &str = "Review the code changes in the pull request {url}. Fetch its diff with `gh pr diff {quotedUrl}` (or the hosting service's API if `gh` is unavailable) and read the surrounding code in this repository as needed. Provide prioritized, actionable findings.";
+
+const SECURITY_AUDIT_PROMPT: &str = "Perform a security audit of {scope}. Look for exploitable vulnerabilities such as injection, path traversal, unsafe deserialization, authentication or authorization flaws, secrets committed to the repository, insecure cryptography, and memory-safety bugs. If local scanners such as `semgrep`, `gitleaks`, `cargo audit`, `npm audit`, or `bandit` are already installed, run the ones relevant to this repository and triage their results; do not install new tools. Report only security issues you have confirmed in the code, each with the exact file and line range, a title naming the vulnerability class (with its CWE id when known), and a priority reflecting severity: P0 critical, P1 high, P2 medium, P3 low.";
+
+/// Which files a security audit covers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SecurityAuditScope {
+    /// Staged, unstaged, and untracked changes.
+    UncommittedChanges,
+    /// Changes on the current branch relative to `branch`.
+    BaseBranch { branch: String },
+    /// Every file tracked by the repository.
+    AllFiles,
+}
+
+/// Builds the review request for the built-in security audit preset. The
+/// audit runs as a review so its findings can be exported (e.g. as SARIF).
+pub fn security_audit_request(scope: &SecurityAuditScope) -> ReviewRequest {
+    let (scope_text, hint) = match scope {
+        SecurityAuditScope::UncommittedChanges => (
+            "the current code changes (staged, unstaged, and untracked files)".to_string(),
+            "security audit of current changes".to_string(),
+        ),
+        SecurityAuditScope::BaseBranch { branch } => {
+            // The branch name goes into a shell command, so it is quoted; git
+            // rejects names with the NUL byte quoting fails on.
+            let quoted = shlex::try_quote(branch).unwrap_or_default();
+            (
+                format!(
+                    "the code changes against the base branch '{branch}' (run `git diff \"$(git merge-base HEAD {quoted})\"` to see them)"
+                ),
+                format!("security audit of changes against '{branch}'"),
+            )
+        }
+        SecurityAuditScope::AllFiles => (
+            "all files tracked in this repository (list them with `git ls-files`)".to_string(),
+            "security audit of all files".to_string(),
+        ),
+    };
+    ReviewRequest {
+        target: ReviewTarget::Custom {
+            instructions: SECURITY_AUDIT_PROMPT.replace("{scope}", &scope_text),
+        },
+        user_facing_hint: Some(hint),
+    }
+}
+
 pub fn resolve_review_request(
     request: ReviewRequest,
     cwd: &Path,
@@ -57,6 +104,20 @@ pub fn review_prompt(target: &ReviewTarget, cwd: &Path) -> anyhow::Result<String
                 Ok(COMMIT_PROMPT.replace("{sha}", sha))
             }
         }
+        ReviewTarget::PullRequest { url } => {
+            // The URL goes into a shell command, so it must be a web URL and
+            // is quoted.
+            let is_web_url = url::Url::parse(url).is_ok_and(|parsed| {
+                matches!(parsed.scheme(), "http" | "https") && parsed.host_str().is_some()
+            });
+            if !is_web_url {
+                anyhow::bail!("Pull request URL must be an http(s) URL, got {url:?}");
+            }
+            let quoted = shlex::try_quote(url)?;
+            Ok(PULL_REQUEST_PROMPT
+                .replace("{quotedUrl}", &quoted)
+                .replace("{url}", url))
+        }
         ReviewTarget::Custom { instructions } => {
             let prompt = instructions.trim();
             if prompt.is_empty() {
@@ -79,6 +140,7 @@ pub fn user_facing_hint(target: &ReviewTarget) -> String {
                 format!("commit {short_sha}")
             }
         }
+        ReviewTarget::PullRequest { url } => format!("pull request {url}"),
         ReviewTarget::Custom { instructions } => instructions.trim().to_string(),
     }
 }
@@ -91,3 +153,47 @@ impl From<ResolvedReviewRequest> for ReviewRequest {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn security_audit_quotes_the_base_branch_in_commands() {
+        let request = security_audit_request(&SecurityAuditScope::BaseBranch {
+            branch: "main;rm -rf ~".to_string(),
+        });
+
+        let ReviewTarget::Custom { instructions } = request.target else {
+            panic!("expected custom instructions, got {:?}", request.target);
+        };
+        assert_eq!(
+            instructions
+                .split('`')
+                .nth(1)
+                .expect("instructions contain a command"),
+            "git diff \"$(git merge-base HEAD 'main;rm -rf ~')\""
+        );
+    }
+
+    #[test]
+    fn pull_request_prompt_quotes_the_url_and_rejects_other_schemes() {
+        let prompt = |url: &str| {
+            review_prompt(
+                &ReviewTarget::PullRequest {
+                    url: url.to_string(),
+                },
+                Path::new("."),
+            )
+        };
+
+        let quoted = prompt("https://github.com/o/r/pull/1?x=$(id)").expect("https url");
+        assert_eq!(
+            quoted.split('`').nth(1),
+            Some("gh pr diff 'https://github.com/o/r/pull/1?x=$(id)'")
+        );
+        assert!(prompt("file:///etc/passwd").is_err());
+        assert!(prompt("1; rm -rf ~").is_err());
+    }
+}
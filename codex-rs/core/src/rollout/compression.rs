@@ -0,0 +1,285 @@
+//! zstd compression of rollout files (`rollout_compression` feature).
+//!
+//! A compressed rollout is a sequence of zstd frames, one per line written,
+//! so the recorder can keep appending to it and a crash loses at most the
+//! frame being written. Frames are small, so they are compressed against a
+//! raw-content dictionary of typical rollout lines (`zstd_dictionary.txt`);
+//! to inspect a file by hand, run `zstd -dc -D zstd_dictionary.txt <file>`.
+//!
+//! Readers ([`RolloutLines`]) detect compression from the zstd magic number at
+//! the start of the file, so plain and compressed rollouts can coexist, and
+//! decompress as lines are consumed instead of inflating the whole file
+//! first. Encrypted rollouts (see [`super::encryption`]) are decrypted first;
+//! their plaintext is either form.
+
+use std::fs::File;
+use std::fs::OpenOptions;
+use std::io;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::io::Write;
+use std::path::Path;
+
+use tokio::sync::mpsc;
+use tracing::warn;
+
+use super::encryption;
+use super::encryption::DecryptingReader;
+use super::encryption::RecordSealer;
+use super::encryption::SessionKey;
+use super::list::parse_timestamp_uuid_from_filename;
+
+/// Raw-content dictionary shared by the writer and readers. Changing it makes
+/// existing compressed rollouts unreadable.
+const DICTIONARY: &[u8] = include_bytes!("zstd_dictionary.txt");
+/// Favors write latency; rollout lines are written as the session runs.
+const COMPRESSION_LEVEL: i32 = 3;
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+/// Lines decompressed ahead of the reader.
+const LINE_BUFFER: usize = 64;
+
+/// Compresses `data` into one self-contained zstd frame.
+pub(crate) fn compress_frame(data: &[u8]) -> io::Result<Vec<u8>> {
+    let mut encoder =
+        zstd::stream::write::Encoder::with_dictionary(Vec::new(), COMPRESSION_LEVEL, DICTIONARY)?;
+    encoder.write_all(data)?;
+    encoder.finish()
+}
+
+/// How the lines of a rollout file are encoded.
+#[derive(Clone, Default)]
+pub(crate) struct RolloutEncoding {
+    /// Each line is its own zstd frame.
+    pub(crate) compress: bool,
+    /// Each (possibly compressed) line is a record sealed by this.
+    pub(crate) sealer: Option<RecordSealer>,
+}
+
+/// Readies the rollout at `path` for appending and returns its encoding, so
+/// appended lines can match it. A line a crash left incomplete is cut off
+/// first; anything appended after it would be unreadable. Empty files are
+/// plain.
+pub(crate) async fn prepare_append(path: &Path) -> io::Result<RolloutEncoding> {
+    let path = path.to_path_buf();
+    tokio::task::spawn_blocking(move || {
+        let (mut reader, key) = open_plaintext(&path)?;
+        let compress = reader.fill_buf()?.starts_with(&ZSTD_MAGIC);
+        drop(reader);
+
+        let data = std::fs::read(&path)?;
+        let mut sealer = None;
+        let complete = if let Some((key, thread_id)) = key {
+            let (len, records) = encryption::complete_records(&data);
+            sealer = Some(RecordSealer::new(key, thread_id, records));
+            len
+        } else if compress {
+            complete_frames_len(&data)
+        } else {
+            data.iter()
+                .rposition(|&byte| byte == b'\n')
+                .map_or(0, |pos| pos + 1)
+        };
+        if complete < data.len() {
+            warn!(
+                "dropping {} bytes of an incomplete line at the end of rollout {}",
+                data.len() - complete,
+                path.display()
+            );
+            OpenOptions::new()
+                .write(true)
+                .open(&path)?
+                .set_len(complete as u64)?;
+        }
+        Ok(RolloutEncoding { compress, sealer })
+    })
+    .await
+    .map_err(io::Error::other)?
+}
+
+/// Length of the whole zstd frames at the start of `data`.
+fn complete_frames_len(data: &[u8]) -> usize {
+    let mut len = 0;
+    while len < data.len()
+        && let Ok(frame) = zstd::zstd_safe::find_frame_compressed_size(&data[len..])
+        && frame > 0
+        && len + frame <= data.len()
+    {
+        len += frame;
+    }
+    len
+}
+
+/// The rollout at `path` with any encryption removed, and the key and
+/// thread id it was encrypted with. Blocks on the keychain for encrypted
+/// rollouts.
+fn open_plaintext(
+    path: &Path,
+) -> io::Result<(Box<dyn BufRead + Send>, Option<(SessionKey, String)>)> {
+    let mut reader = BufReader::new(File::open(path)?);
+    if !encryption::is_encrypted(reader.fill_buf()?) {
+        return Ok((Box::new(reader), None));
+    }
+    // Records are bound to the thread named in the file name.
+    let thread_id = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .and_then(parse_timestamp_uuid_from_filename)
+        .map(|(_, uuid)| uuid.to_string())
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "encrypted rollout {} is not named after its thread",
+                    path.display()
+                ),
+            )
+        })?;
+    let key = SessionKey::load()?;
+    Ok((
+        Box::new(DecryptingReader::new(
+            reader,
+            key.clone(),
+            thread_id.clone(),
+        )),
+        Some((key, thread_id)),
+    ))
+}
+
+/// Lines of a rollout file, decompressed on a blocking thread as they are
+/// consumed. A truncated or corrupt tail ends the lines with a warning so
+/// everything before it can still be resumed.
+pub struct RolloutLines {
+    rx: mpsc::Receiver<io::Result<String>>,
+}
+
+impl RolloutLines {
+    pub async fn open(path: &Path) -> io::Result<Self> {
+        let path = path.to_path_buf();
+        let (reader, path) = tokio::task::spawn_blocking(move || {
+            let (mut reader, _) = open_plaintext(&path)?;
+            let reader: Box<dyn BufRead + Send> = if reader.fill_buf()?.starts_with(&ZSTD_MAGIC) {
+                Box::new(BufReader::new(
+                    zstd::stream::read::Decoder::with_dictionary(reader, DICTIONARY)?,
+                ))
+            } else {
+                Box::new(reader)
+            };
+            io::Result::Ok((reader, path))
+        })
+        .await
+        .map_err(io::Error::other)??;
+
+        let (tx, rx) = mpsc::channel(LINE_BUFFER);
+        tokio::task::spawn_blocking(move || {
+            let mut read_any = false;
+            for line in reader.lines() {
+                let line = match line {
+                    Ok(line) => line,
+                    Err(err) if read_any => {
+                        warn!(
+                            "stopped reading rollout {} at a damaged line: {err}",
+                            path.display()
+                        );
+                        break;
+                    }
+                    Err(err) => {
+                        let _ = tx.blocking_send(Err(err));
+                        break;
+                    }
+                };
+                read_any = true;
+                // The receiver was dropped: the caller has read enough.
+                if tx.blocking_send(Ok(line)).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(Self { rx })
+    }
+
+    pub async fn next_line(&mut self) -> io::Result<Option<String>> {
+        self.rx.recv().await.transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    async fn read_all(path: &Path) -> Vec<String> {
+        let mut lines = RolloutLines::open(path).await.expect("open rollout");
+        let mut all = Vec::new();
+        while let Some(line) = lines.next_line().await.expect("read line") {
+            all.push(line);
+        }
+        all
+    }
+
+    #[tokio::test]
+    async fn reads_appended_frames_and_plain_files() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let compressed = dir.path().join("compressed.jsonl");
+        let mut file = File::create(&compressed).expect("create compressed rollout");
+        for line in ["{\"a\":1}\n", "{\"b\":2}\n", "{\"c\":3}\n"] {
+            file.write_all(&compress_frame(line.as_bytes()).expect("compress"))
+                .expect("write frame");
+        }
+        // A frame cut short by a crash does not hide the lines before it.
+        file.write_all(&compress_frame(b"{\"d\":4}\n").expect("compress")[..6])
+            .expect("write partial frame");
+        let plain = dir.path().join("plain.jsonl");
+        std::fs::write(&plain, "{\"a\":1}\n\n{\"b\":2}\n").expect("write plain rollout");
+
+        assert_eq!(
+            read_all(&compressed).await,
+            vec!["{\"a\":1}", "{\"b\":2}", "{\"c\":3}"]
+        );
+        assert_eq!(read_all(&plain).await, vec!["{\"a\":1}", "", "{\"b\":2}"]);
+    }
+
+    #[tokio::test]
+    async fn appending_drops_an_incomplete_trailing_line() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let compressed = dir.path().join("compressed.jsonl");
+        let mut frames = compress_frame(b"{\"a\":1}\n").expect("compress");
+        frames.extend_from_slice(&compress_frame(b"{\"b\":2}\n").expect("compress")[..6]);
+        std::fs::write(&compressed, frames).expect("write compressed rollout");
+        let plain = dir.path().join("plain.jsonl");
+        std::fs::write(&plain, "{\"a\":1}\n{\"b\":").expect("write plain rollout");
+
+        assert!(
+            prepare_append(&compressed)
+                .await
+                .expect("prepare compressed")
+                .compress
+        );
+        assert!(
+            !prepare_append(&plain)
+                .await
+                .expect("prepare plain")
+                .compress
+        );
+        for (path, line) in [
+            (
+                &compressed,
+                compress_frame(b"{\"c\":3}\n").expect("compress"),
+            ),
+            (&plain, b"{\"c\":3}\n".to_vec()),
+        ] {
+            OpenOptions::new()
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(&line))
+                .expect("append line");
+        }
+
+        assert_eq!(
+            (read_all(&compressed).await, read_all(&plain).await),
+            (
+                vec!["{\"a\":1}".to_string(), "{\"c\":3}".to_string()],
+                vec!["{\"a\":1}".to_string(), "{\"c\":3}".to_string()],
+            )
+        );
+    }
+}
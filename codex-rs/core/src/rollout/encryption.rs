@@ -0,0 +1,502 @@
+//! At-rest encryption of the session store (`session_encryption` feature).
+//!
+//! Each line written to an encrypted rollout becomes one record: a magic
+//! number, the length of the rest of the record, a random nonce, and the
+//! line sealed with ChaCha20-Poly1305. Records are independent, so the
+//! recorder can keep appending and a crash loses at most the record being
+//! written. Each record is authenticated together with its rollout's thread
+//! id and its position in the rollout, so records moved between rollouts,
+//! reordered, or removed from the middle fail to decrypt. Compression, when
+//! enabled, happens before encryption.
+//!
+//! Text stored inside other formats (history entries, the state DB's
+//! conversation columns) is sealed the same way into one record, base64
+//! encoded behind [`SEALED_TEXT_PREFIX`]. Memories are not generated while
+//! encryption is on, since the consolidation agent reads them as plain files.
+//!
+//! The key is generated on first use and kept in the OS keychain, never on
+//! disk. Readers only fetch it when they open encrypted data, so plain and
+//! encrypted data can coexist and reading plain data never touches the
+//! keychain. When the keychain can't provide a key, nothing new is saved:
+//! the session runs without recording and history entries are dropped.
+
+use std::io;
+use std::io::BufRead;
+use std::io::Read;
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::OnceLock;
+
+use base64::Engine as _;
+use base64::engine::general_purpose::STANDARD as BASE64_STANDARD;
+use chacha20poly1305::ChaCha20Poly1305;
+use chacha20poly1305::Key;
+use chacha20poly1305::KeyInit;
+use chacha20poly1305::Nonce;
+use chacha20poly1305::aead::Aead;
+use chacha20poly1305::aead::Payload;
+use codex_keyring_store::DefaultKeyringStore;
+use codex_keyring_store::KeyringStore;
+use rand::TryRngCore;
+use rand::rngs::OsRng;
+
+use crate::config::Config;
+use crate::features::Feature;
+
+const RECORD_MAGIC: [u8; 4] = *b"CXE1";
+const SEALED_TEXT_PREFIX: &str = "cxe1:";
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+/// Larger records can only come from corruption.
+const MAX_RECORD_LEN: usize = 256 * 1024 * 1024;
+const KEYRING_SERVICE: &str = "codex";
+const KEYRING_ACCOUNT: &str = "session-store";
+/// Held while a process creates the key, so concurrent sessions agree on it.
+const KEY_LOCK_FILE: &str = ".session-store-key.lock";
+/// Stands in for the thread id of sealed text, which is not part of a
+/// rollout; thread ids are UUIDs, so it can't collide with one.
+const SEALED_TEXT_CONTEXT: &str = "text";
+
+static SESSION_KEY: OnceLock<SessionKey> = OnceLock::new();
+static KEY_CREATION: Mutex<()> = Mutex::new(());
+
+/// Whether a rollout starting with `prefix` is encrypted.
+pub(crate) fn is_encrypted(prefix: &[u8]) -> bool {
+    prefix.starts_with(&RECORD_MAGIC)
+}
+
+/// Length and number of the whole records at the start of `data`.
+pub(crate) fn complete_records(data: &[u8]) -> (usize, u64) {
+    let header_len = RECORD_MAGIC.len() + 4;
+    let mut len = 0;
+    let mut count = 0;
+    while let Some(header) = data.get(len..len + header_len)
+        && header.starts_with(&RECORD_MAGIC)
+    {
+        let record_len = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+        if !(NONCE_LEN..=MAX_RECORD_LEN).contains(&record_len)
+            || len + header_len + record_len > data.len()
+        {
+            break;
+        }
+        len += header_len + record_len;
+        count += 1;
+    }
+    (len, count)
+}
+
+/// The key to seal new session data with, or `None` when
+/// `session_encryption` is off. Fails when encryption is on but the keychain
+/// can't provide a key; callers must then not save anything.
+pub(crate) async fn sealing_key(config: &Config) -> io::Result<Option<SessionKey>> {
+    if !config.features.enabled(Feature::SessionEncryption) {
+        return Ok(None);
+    }
+    let codex_home = config.codex_home.clone();
+    tokio::task::spawn_blocking(move || SessionKey::load_or_create(&codex_home))
+        .await
+        .map_err(io::Error::other)
+        .and_then(|key| key)
+        .map(Some)
+        .map_err(|err| {
+            io::Error::new(
+                err.kind(),
+                format!("Session encryption is on but its key is unavailable: {err}"),
+            )
+        })
+}
+
+/// Data each record is authenticated with: the thread whose rollout it
+/// belongs to and its position there.
+fn record_aad(thread_id: &str, index: u64) -> Vec<u8> {
+    let mut aad = Vec::with_capacity(RECORD_MAGIC.len() + thread_id.len() + 8);
+    aad.extend_from_slice(&RECORD_MAGIC);
+    aad.extend_from_slice(thread_id.as_bytes());
+    aad.extend_from_slice(&index.to_le_bytes());
+    aad
+}
+
+/// `stored` as written by [`SessionKey::seal_text`], or as is when it was
+/// not sealed. Blocks on the keychain for sealed text.
+pub(crate) fn open_text(stored: &str) -> io::Result<String> {
+    let Some(encoded) = stored.strip_prefix(SEALED_TEXT_PREFIX) else {
+        return Ok(stored.to_string());
+    };
+    let record = BASE64_STANDARD
+        .decode(encoded)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    let mut text = String::new();
+    DecryptingReader::new(record.as_slice(), SessionKey::load()?, SEALED_TEXT_CONTEXT)
+        .read_to_string(&mut text)?;
+    Ok(text)
+}
+
+/// Seals the state DB's conversation columns with the session store key.
+pub(crate) struct StateDbCipher {
+    /// `None` keeps new text in plaintext; sealed text is still opened.
+    key: Option<SessionKey>,
+}
+
+impl StateDbCipher {
+    pub(crate) fn new(key: Option<SessionKey>) -> Arc<Self> {
+        Arc::new(Self { key })
+    }
+}
+
+impl codex_state::TextCipher for StateDbCipher {
+    fn seal(&self, plaintext: &str) -> anyhow::Result<String> {
+        match &self.key {
+            // Empty columns mean "unset" to the state DB's queries.
+            Some(key) if !plaintext.is_empty() => Ok(key.seal_text(plaintext)?),
+            _ => Ok(plaintext.to_string()),
+        }
+    }
+
+    fn open(&self, stored: &str) -> anyhow::Result<String> {
+        Ok(open_text(stored)?)
+    }
+}
+
+/// The key rollouts are encrypted with.
+#[derive(Clone)]
+pub(crate) struct SessionKey {
+    cipher: ChaCha20Poly1305,
+}
+
+impl SessionKey {
+    fn from_bytes(bytes: &[u8; KEY_LEN]) -> Self {
+        Self {
+            cipher: ChaCha20Poly1305::new(Key::from_slice(bytes)),
+        }
+    }
+
+    /// The key from the OS keychain, generating and storing one if there is
+    /// none yet. Blocks on the keychain and on other processes creating it.
+    pub(crate) fn load_or_create(codex_home: &Path) -> io::Result<Self> {
+        if let Some(key) = Self::from_keychain()? {
+            return Ok(key);
+        }
+        // Another session may be creating the key right now; whichever
+        // stores one first, both must use that one.
+        let _guard = KEY_CREATION
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        std::fs::create_dir_all(codex_home)?;
+        let lock_file = std::fs::File::options()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(codex_home.join(KEY_LOCK_FILE))?;
+        lock_file.lock()?;
+        if let Some(key) = Self::from_keychain()? {
+            return Ok(key);
+        }
+        let mut bytes = [0; KEY_LEN];
+        OsRng.try_fill_bytes(&mut bytes).map_err(io::Error::other)?;
+        DefaultKeyringStore
+            .save(
+                KEYRING_SERVICE,
+                KEYRING_ACCOUNT,
+                &BASE64_STANDARD.encode(bytes),
+            )
+            .map_err(|err| {
+                io::Error::other(format!(
+                    "failed to store the session store key in the OS keychain: {}",
+                    err.message()
+                ))
+            })?;
+        // Use what the keychain holds, in case something else replaced it.
+        Self::from_keychain()?
+            .ok_or_else(|| io::Error::other("the OS keychain did not keep the session store key"))
+    }
+
+    /// The key from the OS keychain, for reading encrypted rollouts. Blocks
+    /// on the keychain.
+    pub(crate) fn load() -> io::Result<Self> {
+        Self::from_keychain()?.ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::NotFound,
+                "rollout is encrypted but the OS keychain has no session store key",
+            )
+        })
+    }
+
+    fn from_keychain() -> io::Result<Option<Self>> {
+        if let Some(key) = SESSION_KEY.get() {
+            return Ok(Some(key.clone()));
+        }
+        let stored = DefaultKeyringStore
+            .load(KEYRING_SERVICE, KEYRING_ACCOUNT)
+            .map_err(|err| {
+                io::Error::other(format!(
+                    "failed to load the session store key from the OS keychain: {}",
+                    err.message()
+                ))
+            })?;
+        let Some(stored) = stored else {
+            return Ok(None);
+        };
+        let bytes: [u8; KEY_LEN] = BASE64_STANDARD
+            .decode(stored.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the session store key in the OS keychain is malformed",
+                )
+            })?;
+        Ok(Some(
+            SESSION_KEY.get_or_init(|| Self::from_bytes(&bytes)).clone(),
+        ))
+    }
+
+    /// Seals `plaintext` into one record authenticated with `aad`.
+    fn seal(&self, plaintext: &[u8], aad: &[u8]) -> io::Result<Vec<u8>> {
+        let mut nonce = [0; NONCE_LEN];
+        OsRng.try_fill_bytes(&mut nonce).map_err(io::Error::other)?;
+        let ciphertext = self
+            .cipher
+            .encrypt(
+                Nonce::from_slice(&nonce),
+                Payload {
+                    msg: plaintext,
+                    aad,
+                },
+            )
+            .map_err(|_| io::Error::other("failed to encrypt rollout line"))?;
+        let len = u32::try_from(NONCE_LEN + ciphertext.len())
+            .map_err(|_| io::Error::other("rollout line is too large to encrypt"))?;
+        let mut record = Vec::with_capacity(RECORD_MAGIC.len() + 4 + len as usize);
+        record.extend_from_slice(&RECORD_MAGIC);
+        record.extend_from_slice(&len.to_le_bytes());
+        record.extend_from_slice(&nonce);
+        record.extend_from_slice(&ciphertext);
+        Ok(record)
+    }
+
+    /// Seals `plaintext` into text that can be stored inside other formats;
+    /// [`open_text`] reverses it.
+    pub(crate) fn seal_text(&self, plaintext: &str) -> io::Result<String> {
+        let record = self.seal(plaintext.as_bytes(), &record_aad(SEALED_TEXT_CONTEXT, 0))?;
+        Ok(format!(
+            "{SEALED_TEXT_PREFIX}{}",
+            BASE64_STANDARD.encode(record)
+        ))
+    }
+}
+
+/// Seals the lines of one thread's rollout, numbering its records.
+#[derive(Clone)]
+pub(crate) struct RecordSealer {
+    key: SessionKey,
+    thread_id: String,
+    next_record: u64,
+}
+
+impl RecordSealer {
+    /// Seals records of `thread_id`'s rollout, which already holds
+    /// `next_record` records.
+    pub(crate) fn new(key: SessionKey, thread_id: String, next_record: u64) -> Self {
+        Self {
+            key,
+            thread_id,
+            next_record,
+        }
+    }
+
+    /// Seals `plaintext` into the rollout's next record.
+    pub(crate) fn seal(&mut self, plaintext: &[u8]) -> io::Result<Vec<u8>> {
+        let record = self
+            .key
+            .seal(plaintext, &record_aad(&self.thread_id, self.next_record))?;
+        self.next_record += 1;
+        Ok(record)
+    }
+}
+
+/// The plaintext of an encrypted rollout, decrypted one record at a time.
+pub(crate) struct DecryptingReader<R> {
+    inner: R,
+    key: SessionKey,
+    thread_id: String,
+    next_record: u64,
+    plaintext: Vec<u8>,
+    pos: usize,
+}
+
+impl<R: Read> DecryptingReader<R> {
+    /// Decrypts the records of `thread_id`'s rollout.
+    pub(crate) fn new(inner: R, key: SessionKey, thread_id: impl Into<String>) -> Self {
+        Self {
+            inner,
+            key,
+            thread_id: thread_id.into(),
+            next_record: 0,
+            plaintext: Vec::new(),
+            pos: 0,
+        }
+    }
+
+    /// Reads the next record into `plaintext`. Returns false at the end of
+    /// the input.
+    fn next_record(&mut self) -> io::Result<bool> {
+        let mut header = [0; RECORD_MAGIC.len() + 4];
+        let mut filled = 0;
+        while filled < header.len() {
+            match self.inner.read(&mut header[filled..])? {
+                0 if filled == 0 => return Ok(false),
+                0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+                read => filled += read,
+            }
+        }
+        let (magic, len) = header.split_at(RECORD_MAGIC.len());
+        let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        if magic != RECORD_MAGIC || !(NONCE_LEN..=MAX_RECORD_LEN).contains(&len) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "malformed encrypted rollout record",
+            ));
+        }
+        let mut record = vec![0; len];
+        self.inner.read_exact(&mut record)?;
+        let (nonce, ciphertext) = record.split_at(NONCE_LEN);
+        let aad = record_aad(&self.thread_id, self.next_record);
+        self.plaintext = self
+            .key
+            .cipher
+            .decrypt(
+                Nonce::from_slice(nonce),
+                Payload {
+                    msg: ciphertext,
+                    aad: &aad,
+                },
+            )
+            .map_err(|_| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "failed to decrypt rollout record; the session store key may have changed or the record was moved",
+                )
+            })?;
+        self.next_record += 1;
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+impl<R: Read> Read for DecryptingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.consume(read);
+        Ok(read)
+    }
+}
+
+impl<R: Read> BufRead for DecryptingReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while self.pos >= self.plaintext.len() {
+            if !self.next_record()? {
+                return Ok(&[]);
+            }
+        }
+        Ok(&self.plaintext[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.plaintext.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const THREAD_ID: &str = "0199a213-81c0-7800-8aa1-bbab2a035a53";
+
+    fn seal_lines(key: &SessionKey, thread_id: &str, lines: &[&str]) -> Vec<Vec<u8>> {
+        let mut sealer = RecordSealer::new(key.clone(), thread_id.to_string(), 0);
+        lines
+            .iter()
+            .map(|line| sealer.seal(line.as_bytes()).expect("seal"))
+            .collect()
+    }
+
+    #[test]
+    fn records_round_trip_and_a_truncated_tail_fails() {
+        let key = SessionKey::from_bytes(&[7; KEY_LEN]);
+        let sealed = seal_lines(&key, THREAD_ID, &["{\"a\":1}\n", "{\"b\":2}\n"]).concat();
+        assert!(is_encrypted(&sealed));
+        assert!(!sealed.windows(7).any(|window| window == b"{\"a\":1}"));
+
+        let lines = DecryptingReader::new(sealed.as_slice(), key.clone(), THREAD_ID)
+            .lines()
+            .collect::<io::Result<Vec<_>>>()
+            .expect("decrypt");
+        assert_eq!(lines, vec!["{\"a\":1}", "{\"b\":2}"]);
+
+        let sealed_text = key.seal_text("{\"a\":1}").expect("seal text");
+        assert!(sealed_text.starts_with(SEALED_TEXT_PREFIX) && !sealed_text.contains("\"a\""));
+        assert_eq!(
+            open_text("{\"a\":1}").expect("open plain text"),
+            "{\"a\":1}"
+        );
+
+        let cut = &sealed[..sealed.len() - 3];
+        assert_eq!(
+            complete_records(cut),
+            (seal_lines(&key, THREAD_ID, &["{\"a\":1}\n"])[0].len(), 1)
+        );
+        let mut lines = DecryptingReader::new(cut, key, THREAD_ID).lines();
+        assert_eq!(
+            lines.next().transpose().expect("first line"),
+            Some("{\"a\":1}".to_string())
+        );
+        assert!(lines.next().expect("second line").is_err());
+
+        let other_key = SessionKey::from_bytes(&[8; KEY_LEN]);
+        let err = DecryptingReader::new(sealed.as_slice(), other_key, THREAD_ID)
+            .fill_buf()
+            .map(<[u8]>::to_vec)
+            .expect_err("wrong key");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn records_are_bound_to_their_thread_and_position() {
+        let key = SessionKey::from_bytes(&[7; KEY_LEN]);
+        let records = seal_lines(&key, THREAD_ID, &["{\"a\":1}\n", "{\"b\":2}\n"]);
+        let read_first = |data: Vec<u8>, thread_id: &str| {
+            DecryptingReader::new(data.as_slice(), key.clone(), thread_id)
+                .fill_buf()
+                .map(<[u8]>::to_vec)
+        };
+
+        let other_thread = "0199a213-81c0-7800-8aa1-bbab2a035a54";
+        let err = read_first(records.concat(), other_thread).expect_err("other thread");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let reordered = [records[1].clone(), records[0].clone()].concat();
+        let err = read_first(reordered, THREAD_ID).expect_err("reordered records");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let err = read_first(records[1].clone(), THREAD_ID).expect_err("dropped first record");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let resumed = RecordSealer::new(key.clone(), THREAD_ID.to_string(), 1)
+            .seal(b"{\"b\":2}\n")
+            .expect("seal appended record");
+        let lines = DecryptingReader::new(
+            [records[0].clone(), resumed].concat().as_slice(),
+            key.clone(),
+            THREAD_ID,
+        )
+        .lines()
+        .collect::<io::Result<Vec<_>>>()
+        .expect("decrypt appended record");
+        assert_eq!(lines, vec!["{\"a\":1}", "{\"b\":2}"]);
+    }
+}
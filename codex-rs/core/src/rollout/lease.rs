@@ -0,0 +1,245 @@
+//! Ownership leases for session rollouts.
+//!
+//! Two processes appending to the same rollout interleave lines and corrupt
+//! it, so the recorder holds a lease on its session while it writes. A lease
+//! is an advisory lock on `session_leases/<thread id>.lease` under the codex
+//! home, which records the owner for error messages. The OS releases the lock
+//! when the owner exits, however it exits; an owner that shuts down cleanly
+//! also deletes the lease file, so a record found on an unlocked lease means
+//! the previous owner died and the lease is stale. Recorders of the same
+//! session within one process share its lease.
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+use std::sync::Mutex;
+use std::sync::Weak;
+
+use codex_protocol::ThreadId;
+use serde::Deserialize;
+use serde::Serialize;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+use tracing::warn;
+
+const LEASES_SUBDIR: &str = "session_leases";
+
+/// Leases this process holds, by lease path.
+static HELD_LEASES: LazyLock<Mutex<HashMap<PathBuf, Weak<LeaseFile>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// The process holding a lease.
+#[derive(Debug, Serialize, Deserialize)]
+struct LeaseOwner {
+    pid: u32,
+    acquired_at: String,
+}
+
+/// A held lease. The session is released once every recorder in this
+/// process holding it is dropped.
+#[derive(Debug)]
+pub(crate) struct SessionLease {
+    _file: Arc<LeaseFile>,
+}
+
+#[derive(Debug)]
+struct LeaseFile {
+    file: File,
+    path: PathBuf,
+}
+
+/// Attempts at locking a lease file that its owner deletes in between.
+const MAX_LOCK_ATTEMPTS: usize = 5;
+
+impl SessionLease {
+    /// Takes the lease on `thread_id`, failing with
+    /// [`io::ErrorKind::ResourceBusy`] while another live process holds it.
+    /// Blocks on the file system.
+    pub(crate) fn acquire(codex_home: &Path, thread_id: &ThreadId) -> io::Result<Self> {
+        let path = lease_path(codex_home, thread_id);
+        let mut held = HELD_LEASES
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        held.retain(|_, lease| lease.strong_count() > 0);
+        if let Some(file) = held.get(&path).and_then(Weak::upgrade) {
+            return Ok(Self { _file: file });
+        }
+
+        std::fs::create_dir_all(codex_home.join(LEASES_SUBDIR))?;
+        let mut attempts = 0;
+        let mut file = loop {
+            let mut file = File::options()
+                .read(true)
+                .write(true)
+                .create(true)
+                .truncate(false)
+                .open(&path)?;
+            match file.try_lock() {
+                Ok(()) => {}
+                Err(std::fs::TryLockError::WouldBlock) => {
+                    let mut message =
+                        format!("session {thread_id} is open in another Codex process");
+                    if let Some(owner) = read_owner(&mut file) {
+                        message.push_str(&format!(
+                            " (pid {}, since {})",
+                            owner.pid, owner.acquired_at
+                        ));
+                    }
+                    return Err(io::Error::new(io::ErrorKind::ResourceBusy, message));
+                }
+                Err(std::fs::TryLockError::Error(err)) => return Err(err),
+            }
+            // The previous owner deletes the file on release; a lock taken on
+            // the deleted file guards nothing, so open the path again.
+            attempts += 1;
+            if is_linked(&file, &path) {
+                break file;
+            }
+            if attempts == MAX_LOCK_ATTEMPTS {
+                return Err(io::Error::other(format!(
+                    "lease on session {thread_id} kept changing while being taken"
+                )));
+            }
+        };
+
+        if let Some(stale) = read_owner(&mut file) {
+            warn!(
+                "taking over the lease on session {thread_id} from process {}, which exited without releasing it",
+                stale.pid
+            );
+        }
+        let owner = LeaseOwner {
+            pid: std::process::id(),
+            acquired_at: OffsetDateTime::now_utc()
+                .format(&Rfc3339)
+                .map_err(io::Error::other)?,
+        };
+        file.set_len(0)?;
+        file.seek(SeekFrom::Start(0))?;
+        file.write_all(&serde_json::to_vec(&owner)?)?;
+        let file = Arc::new(LeaseFile {
+            file,
+            path: path.clone(),
+        });
+        held.insert(path, Arc::downgrade(&file));
+        Ok(Self { _file: file })
+    }
+}
+
+impl Drop for LeaseFile {
+    fn drop(&mut self) {
+        // Delete the lease while still holding the lock; the lock itself is
+        // released when the file is closed. Where open files cannot be
+        // deleted (Windows), clear the record instead.
+        if std::fs::remove_file(&self.path).is_err()
+            && let Err(err) = self.file.set_len(0)
+        {
+            warn!("failed to release session lease: {err}");
+        }
+    }
+}
+
+/// Whether a live process, this one included, holds the lease on
+/// `thread_id`. Blocks on the file system.
+pub(crate) fn is_held(codex_home: &Path, thread_id: &ThreadId) -> bool {
+    let path = lease_path(codex_home, thread_id);
+    // Hold the table while probing so this process does not take the lease
+    // in the meantime and find it busy.
+    let held = HELD_LEASES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner);
+    if held
+        .get(&path)
+        .is_some_and(|lease| lease.strong_count() > 0)
+    {
+        return true;
+    }
+    // A cleanly released lease leaves no file behind.
+    let Ok(file) = File::open(&path) else {
+        return false;
+    };
+    matches!(
+        file.try_lock_shared(),
+        Err(std::fs::TryLockError::WouldBlock)
+    )
+}
+
+/// Whether `path` still names the open `file`.
+#[cfg(unix)]
+fn is_linked(file: &File, path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    match (file.metadata(), std::fs::metadata(path)) {
+        (Ok(open), Ok(linked)) => open.dev() == linked.dev() && open.ino() == linked.ino(),
+        _ => false,
+    }
+}
+
+/// Whether `path` still names the open `file`. Files cannot be deleted while
+/// open here, so it always does.
+#[cfg(not(unix))]
+fn is_linked(_file: &File, path: &Path) -> bool {
+    path.exists()
+}
+
+fn lease_path(codex_home: &Path, thread_id: &ThreadId) -> PathBuf {
+    codex_home
+        .join(LEASES_SUBDIR)
+        .join(format!("{thread_id}.lease"))
+}
+
+fn read_owner(file: &mut File) -> Option<LeaseOwner> {
+    let mut contents = Vec::new();
+    file.seek(SeekFrom::Start(0)).ok()?;
+    file.read_to_end(&mut contents).ok()?;
+    serde_json::from_slice(&contents).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn other_owners_are_refused_and_stale_leases_are_taken_over() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let thread_id = ThreadId::new();
+        let path = lease_path(codex_home.path(), &thread_id);
+        std::fs::create_dir_all(path.parent().expect("lease dir")).expect("create lease dir");
+
+        // Another process: a separate lock on the lease file.
+        let mut other = File::create(&path).expect("create lease");
+        other.try_lock().expect("lock lease");
+        other
+            .write_all(br#"{"pid":1,"acquired_at":"2025-01-01T00:00:00Z"}"#)
+            .expect("write owner");
+        let err = SessionLease::acquire(codex_home.path(), &thread_id).expect_err("held");
+        assert_eq!(err.kind(), io::ErrorKind::ResourceBusy);
+        assert!(err.to_string().starts_with(&format!(
+            "session {thread_id} is open in another Codex process"
+        )));
+
+        // The other process died without clearing its record.
+        drop(other);
+        let lease = SessionLease::acquire(codex_home.path(), &thread_id).expect("take over");
+        let shared = SessionLease::acquire(codex_home.path(), &thread_id).expect("share");
+        drop(lease);
+        assert!(path.exists());
+        assert!(is_held(codex_home.path(), &thread_id));
+        drop(shared);
+        assert!(!is_held(codex_home.path(), &thread_id));
+        if cfg!(unix) {
+            assert!(!path.exists());
+        } else {
+            assert_eq!(std::fs::read(&path).expect("read lease"), Vec::<u8>::new());
+        }
+    }
+}
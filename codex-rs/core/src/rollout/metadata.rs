@@ -519,6 +519,8 @@ mod tests {
             model_provider: Some("openai".to_string()),
             base_instructions: None,
             dynamic_tools: None,
+            instruction_layers: None,
+            experiments: None,
         };
         let session_meta_line = SessionMetaLine {
             meta: session_meta,
@@ -669,6 +671,8 @@ mod tests {
             model_provider: Some("test-provider".to_string()),
             base_instructions: None,
             dynamic_tools: None,
+            instruction_layers: None,
+            experiments: None,
         };
         let session_meta_line = SessionMetaLine {
             meta: session_meta,
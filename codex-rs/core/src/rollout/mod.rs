@@ -7,15 +7,20 @@ pub const ARCHIVED_SESSIONS_SUBDIR: &str = "archived_sessions";
 pub const INTERACTIVE_SESSION_SOURCES: &[SessionSource] =
     &[SessionSource::Cli, SessionSource::VSCode];
 
+pub(crate) mod compression;
+pub(crate) mod encryption;
 pub(crate) mod error;
+pub(crate) mod lease;
 pub mod list;
 pub(crate) mod metadata;
 pub(crate) mod policy;
 pub mod recorder;
+pub(crate) mod retention;
 pub(crate) mod session_index;
 pub(crate) mod truncation;
 
 pub use codex_protocol::protocol::SessionMeta;
+pub use compression::RolloutLines;
 pub(crate) use error::map_session_init_error;
 pub use list::find_archived_thread_path_by_id_str;
 pub use list::find_thread_path_by_id_str;
@@ -97,6 +97,10 @@ fn event_msg_persistence_mode(ev: &EventMsg) -> Option<EventPersistenceMode> {
         | EventMsg::ExitedReviewMode(_)
         | EventMsg::ThreadRolledBack(_)
         | EventMsg::UndoCompleted(_)
+        | EventMsg::ToolSelection(_)
+        | EventMsg::ResponseCandidateSelected(_)
+        | EventMsg::HistoryMetadataRecorded(_)
+        | EventMsg::TurnTimingRecorded(_)
         | EventMsg::TurnAborted(_)
         | EventMsg::TurnStarted(_)
         | EventMsg::TurnComplete(_) => Some(EventPersistenceMode::Limited),
@@ -104,7 +108,13 @@ fn event_msg_persistence_mode(ev: &EventMsg) -> Option<EventPersistenceMode> {
             // Plan items are derived from streaming tags and are not part of the
             // raw ResponseItem history, so we persist their completion to replay
             // them on resume without bloating rollouts with every item lifecycle.
-            if matches!(event.item, codex_protocol::items::TurnItem::Plan(_)) {
+            // User messages are persisted for their item ID, which the
+            // recorded `ResponseItem` does not serialize.
+            if matches!(
+                event.item,
+                codex_protocol::items::TurnItem::Plan(_)
+                    | codex_protocol::items::TurnItem::UserMessage(_)
+            ) {
                 Some(EventPersistenceMode::Limited)
             } else {
                 None
@@ -156,6 +166,35 @@ fn event_msg_persistence_mode(ev: &EventMsg) -> Option<EventPersistenceMode> {
         | EventMsg::ListSkillsResponse(_)
         | EventMsg::ListRemoteSkillsResponse(_)
         | EventMsg::RemoteSkillDownloaded(_)
+        | EventMsg::PatchArtifact(_)
+        | EventMsg::ToolCallArgumentsPreview(_)
+        | EventMsg::SessionsPruned(_)
+        | EventMsg::SessionExported(_)
+        | EventMsg::SessionImported(_)
+        | EventMsg::ApprovalResolved(_)
+        | EventMsg::ObserverPromoted(_)
+        | EventMsg::InlineEditResult(_)
+        | EventMsg::SessionTools(_)
+        | EventMsg::ToolSelectionRestored(_)
+        | EventMsg::ElicitationTimedOut(_)
+        | EventMsg::ToolInvocationResult(_)
+        | EventMsg::CompletionResult(_)
+        | EventMsg::EnabledToolsUpdated(_)
+        | EventMsg::SuggestedAllowlist(_)
+        | EventMsg::DependencyAudit(_)
+        | EventMsg::LicenseReport(_)
+        | EventMsg::ResponseCandidates(_)
+        | EventMsg::RateLimitForecast(_)
+        | EventMsg::PlanOrCreditsChanged(_)
+        | EventMsg::OfflineStatus(_)
+        | EventMsg::UsageRollups(_)
+        | EventMsg::TurnTimings(_)
+        | EventMsg::ContextBreakdown(_)
+        | EventMsg::ActiveToolCalls(_)
+        | EventMsg::ToolLockHeld(_)
+        | EventMsg::ToolProgress(_)
+        | EventMsg::McpToolCallProgress(_)
+        | EventMsg::McpToolCallLog(_)
         | EventMsg::PlanUpdate(_)
         | EventMsg::ShutdownComplete
         | EventMsg::DeprecationNotice(_)
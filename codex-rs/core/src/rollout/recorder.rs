@@ -1,5 +1,6 @@
 //! Persist Codex session rollouts (.jsonl) so sessions can be replayed or inspected later.
 
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::fs::{self};
 use std::io::Error as IoError;
@@ -25,6 +26,12 @@ use tracing::warn;
 
 use super::ARCHIVED_SESSIONS_SUBDIR;
 use super::SESSIONS_SUBDIR;
+use super::compression;
+use super::compression::RolloutEncoding;
+use super::compression::RolloutLines;
+use super::encryption;
+use super::encryption::RecordSealer;
+use super::lease::SessionLease;
 use super::list::Cursor;
 use super::list::ThreadItem;
 use super::list::ThreadListConfig;
@@ -40,6 +47,7 @@ use super::policy::EventPersistenceMode;
 use super::policy::is_persisted_response_item;
 use crate::config::Config;
 use crate::default_client::originator;
+use crate::features::Feature;
 use crate::git_info::collect_git_info;
 use crate::path_utils;
 use crate::state_db;
@@ -48,6 +56,7 @@ use crate::truncate::TruncationPolicy;
 use crate::truncate::truncate_text;
 use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::InitialHistory;
+use codex_protocol::protocol::InstructionLayerMeta;
 use codex_protocol::protocol::ResumedHistory;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::protocol::RolloutLine;
@@ -82,6 +91,8 @@ pub enum RolloutRecorderParams {
         source: SessionSource,
         base_instructions: BaseInstructions,
         dynamic_tools: Vec<DynamicToolSpec>,
+        instruction_layers: Vec<InstructionLayerMeta>,
+        experiments: BTreeMap<String, String>,
         event_persistence_mode: EventPersistenceMode,
     },
     Resume {
@@ -119,10 +130,33 @@ impl RolloutRecorderParams {
             source,
             base_instructions,
             dynamic_tools,
+            instruction_layers: Vec::new(),
+            experiments: BTreeMap::new(),
             event_persistence_mode,
         }
     }
 
+    /// Records the instruction layers applied to `base_instructions` in the
+    /// session metadata. Has no effect when resuming.
+    pub fn with_instruction_layers(mut self, layers: Vec<InstructionLayerMeta>) -> Self {
+        if let Self::Create {
+            instruction_layers, ..
+        } = &mut self
+        {
+            *instruction_layers = layers;
+        }
+        self
+    }
+
+    /// Records the prompt experiment arms assigned to the session. Has no
+    /// effect when resuming.
+    pub fn with_experiments(mut self, arms: BTreeMap<String, String>) -> Self {
+        if let Self::Create { experiments, .. } = &mut self {
+            *experiments = arms;
+        }
+        self
+    }
+
     pub fn resume(path: PathBuf, event_persistence_mode: EventPersistenceMode) -> Self {
         Self::Resume {
             path,
@@ -361,60 +395,98 @@ impl RolloutRecorder {
         state_db_ctx: Option<StateDbHandle>,
         state_builder: Option<ThreadMetadataBuilder>,
     ) -> std::io::Result<Self> {
-        let (file, deferred_log_file_info, rollout_path, meta, event_persistence_mode) =
-            match params {
-                RolloutRecorderParams::Create {
-                    conversation_id,
+        let (
+            file,
+            deferred_log_file_info,
+            rollout_path,
+            meta,
+            event_persistence_mode,
+            encoding,
+            lease,
+        ) = match params {
+            RolloutRecorderParams::Create {
+                conversation_id,
+                forked_from_id,
+                source,
+                base_instructions,
+                dynamic_tools,
+                instruction_layers,
+                experiments,
+                event_persistence_mode,
+            } => {
+                let log_file_info = precompute_log_file_info(config, conversation_id)?;
+                let path = log_file_info.path.clone();
+                let session_id = log_file_info.conversation_id;
+                let started_at = log_file_info.timestamp;
+
+                let timestamp_format: &[FormatItem] = format_description!(
+                    "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
+                );
+                let timestamp = started_at
+                    .to_offset(time::UtcOffset::UTC)
+                    .format(timestamp_format)
+                    .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
+
+                let session_meta = SessionMeta {
+                    id: session_id,
                     forked_from_id,
+                    timestamp,
+                    cwd: config.cwd.clone(),
+                    originator: originator().value,
+                    cli_version: env!("CARGO_PKG_VERSION").to_string(),
+                    agent_nickname: source.get_nickname(),
+                    agent_role: source.get_agent_role(),
                     source,
-                    base_instructions,
-                    dynamic_tools,
-                    event_persistence_mode,
-                } => {
-                    let log_file_info = precompute_log_file_info(config, conversation_id)?;
-                    let path = log_file_info.path.clone();
-                    let session_id = log_file_info.conversation_id;
-                    let started_at = log_file_info.timestamp;
-
-                    let timestamp_format: &[FormatItem] = format_description!(
-                        "[year]-[month]-[day]T[hour]:[minute]:[second].[subsecond digits:3]Z"
-                    );
-                    let timestamp = started_at
-                        .to_offset(time::UtcOffset::UTC)
-                        .format(timestamp_format)
-                        .map_err(|e| IoError::other(format!("failed to format timestamp: {e}")))?;
-
-                    let session_meta = SessionMeta {
-                        id: session_id,
-                        forked_from_id,
-                        timestamp,
-                        cwd: config.cwd.clone(),
-                        originator: originator().value,
-                        cli_version: env!("CARGO_PKG_VERSION").to_string(),
-                        agent_nickname: source.get_nickname(),
-                        agent_role: source.get_agent_role(),
-                        source,
-                        model_provider: Some(config.model_provider_id.clone()),
-                        base_instructions: Some(base_instructions),
-                        dynamic_tools: if dynamic_tools.is_empty() {
-                            None
-                        } else {
-                            Some(dynamic_tools)
-                        },
-                    };
-
-                    (
-                        None,
-                        Some(log_file_info),
-                        path,
-                        Some(session_meta),
-                        event_persistence_mode,
-                    )
-                }
-                RolloutRecorderParams::Resume {
+                    model_provider: Some(config.model_provider_id.clone()),
+                    base_instructions: Some(base_instructions),
+                    dynamic_tools: if dynamic_tools.is_empty() {
+                        None
+                    } else {
+                        Some(dynamic_tools)
+                    },
+                    instruction_layers: if instruction_layers.is_empty() {
+                        None
+                    } else {
+                        Some(instruction_layers)
+                    },
+                    experiments: if experiments.is_empty() {
+                        None
+                    } else {
+                        Some(experiments)
+                    },
+                };
+
+                (
+                    None,
+                    Some(log_file_info),
                     path,
+                    Some(session_meta),
                     event_persistence_mode,
-                } => (
+                    RolloutEncoding {
+                        compress: config.features.enabled(Feature::RolloutCompression),
+                        sealer: encryption::sealing_key(config)
+                            .await?
+                            .map(|key| RecordSealer::new(key, session_id.to_string(), 0)),
+                    },
+                    Some(SessionLease::acquire(&config.codex_home, &session_id)?),
+                )
+            }
+            RolloutRecorderParams::Resume {
+                path,
+                event_persistence_mode,
+            } => {
+                // Another process appending to the same rollout would
+                // corrupt it; fail with `ResourceBusy` while one holds it.
+                let lease = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(parse_timestamp_uuid_from_filename)
+                    .and_then(|(_, uuid)| ThreadId::from_string(&uuid.to_string()).ok())
+                    .map(|thread_id| SessionLease::acquire(&config.codex_home, &thread_id))
+                    .transpose()?;
+                // Keep appending in the format the file was started in.
+                let encoding = compression::prepare_append(&path).await?;
+                (
                     Some(
                         tokio::fs::OpenOptions::new()
                             .append(true)
@@ -425,8 +497,11 @@ impl RolloutRecorder {
                     path,
                     None,
                     event_persistence_mode,
-                ),
-            };
+                    encoding,
+                    lease,
+                )
+            }
+        };
 
         // Clone the cwd for the spawned task to collect git info asynchronously
         let cwd = config.cwd.clone();
@@ -449,6 +524,8 @@ impl RolloutRecorder {
             state_db_ctx.clone(),
             state_builder,
             config.model_provider_id.clone(),
+            encoding,
+            lease,
         ));
 
         Ok(Self {
@@ -517,19 +594,18 @@ impl RolloutRecorder {
         path: &Path,
     ) -> std::io::Result<(Vec<RolloutItem>, Option<ThreadId>, usize)> {
         trace!("Resuming rollout from {path:?}");
-        let text = tokio::fs::read_to_string(path).await?;
-        if text.trim().is_empty() {
-            return Err(IoError::other("empty session file"));
-        }
+        let mut lines = RolloutLines::open(path).await?;
+        let mut empty = true;
 
         let mut items: Vec<RolloutItem> = Vec::new();
         let mut thread_id: Option<ThreadId> = None;
         let mut parse_errors = 0usize;
-        for line in text.lines() {
+        while let Some(line) = lines.next_line().await? {
             if line.trim().is_empty() {
                 continue;
             }
-            let v: Value = match serde_json::from_str(line) {
+            empty = false;
+            let v: Value = match serde_json::from_str(&line) {
                 Ok(v) => v,
                 Err(e) => {
                     warn!("failed to parse line as JSON: {line:?}, error: {e}");
@@ -568,6 +644,9 @@ impl RolloutRecorder {
                 }
             }
         }
+        if empty {
+            return Err(IoError::other("empty session file"));
+        }
 
         tracing::debug!(
             "Resumed rollout with {} items, thread ID: {:?}, parse errors: {}",
@@ -700,8 +779,14 @@ async fn rollout_writer(
     state_db_ctx: Option<StateDbHandle>,
     mut state_builder: Option<ThreadMetadataBuilder>,
     default_provider: String,
+    encoding: RolloutEncoding,
+    // Held until the writer stops.
+    _lease: Option<SessionLease>,
 ) -> std::io::Result<()> {
-    let mut writer = file.map(|file| JsonlWriter { file });
+    let mut writer = file.map(|file| JsonlWriter {
+        file,
+        encoding: encoding.clone(),
+    });
     let mut buffered_items = Vec::<RolloutItem>::new();
     if let Some(builder) = state_builder.as_mut() {
         builder.rollout_path = rollout_path.clone();
@@ -762,6 +847,7 @@ async fn rollout_writer(
                         let file = open_log_file(log_file_info.path.as_path())?;
                         writer = Some(JsonlWriter {
                             file: tokio::fs::File::from_std(file),
+                            encoding: encoding.clone(),
                         });
 
                         if let Some(session_meta) = meta.take() {
@@ -884,6 +970,9 @@ async fn write_and_reconcile_items(
 
 struct JsonlWriter {
     file: tokio::fs::File,
+    /// Compression and encryption applied to each line (see
+    /// [`compression`] and [`super::encryption`]).
+    encoding: RolloutEncoding,
 }
 
 #[derive(serde::Serialize)]
@@ -911,7 +1000,14 @@ impl JsonlWriter {
     async fn write_line(&mut self, item: &impl serde::Serialize) -> std::io::Result<()> {
         let mut json = serde_json::to_string(item)?;
         json.push('\n');
-        self.file.write_all(json.as_bytes()).await?;
+        let mut bytes = json.into_bytes();
+        if self.encoding.compress {
+            bytes = compression::compress_frame(&bytes)?;
+        }
+        if let Some(sealer) = &mut self.encoding.sealer {
+            bytes = sealer.seal(&bytes)?;
+        }
+        self.file.write_all(&bytes).await?;
         self.file.flush().await?;
         Ok(())
     }
@@ -0,0 +1,307 @@
+//! Pruning of stored session rollouts (`[session_retention]` in config.toml).
+//!
+//! Rollouts under `sessions/` and `archived_sessions/` are visited newest
+//! first by modification time. Pinned threads, the session being resumed,
+//! sessions some process holds the lease on and rollouts written in the last
+//! hour are always kept; any other rollout is pruned when it is older than
+//! `max_age_days` or when keeping it would push the kept rollouts over
+//! `max_total_bytes`. A pruned session's state DB row and session index
+//! entries go with its rollout.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::PrunedSession;
+use codex_protocol::protocol::SessionsPrunedEvent;
+use tracing::warn;
+
+use super::ARCHIVED_SESSIONS_SUBDIR;
+use super::SESSIONS_SUBDIR;
+use super::lease;
+use super::list::parse_timestamp_uuid_from_filename;
+use super::session_index::remove_session_index_entries;
+use crate::config::types::SessionRetention;
+use crate::state_db::StateDbHandle;
+
+/// Rollouts modified more recently than this may belong to a live session.
+const RECENT_WRITE_GRACE: Duration = Duration::from_secs(60 * 60);
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+struct StoredSession {
+    path: PathBuf,
+    thread_id: ThreadId,
+    bytes: u64,
+    modified: SystemTime,
+}
+
+/// Applies `retention` to the rollouts under `codex_home`, never pruning the
+/// rollout at `resume_path`. With `dry_run`, nothing is deleted and the result
+/// lists what would be.
+pub(crate) async fn prune_sessions(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    resume_path: Option<&Path>,
+    state_db: Option<StateDbHandle>,
+    dry_run: bool,
+) -> io::Result<SessionsPrunedEvent> {
+    let codex_home = codex_home.to_path_buf();
+    let retention = retention.clone();
+    let resume_path = resume_path.map(Path::to_path_buf);
+    let report = tokio::task::spawn_blocking(move || {
+        prune_sessions_blocking(
+            &codex_home,
+            &retention,
+            resume_path.as_deref(),
+            dry_run,
+            SystemTime::now(),
+        )
+    })
+    .await
+    .map_err(io::Error::other)??;
+    if !dry_run && let Some(state_db) = state_db {
+        for session in &report.pruned {
+            if let Err(err) = state_db.delete_thread(session.thread_id).await {
+                warn!(
+                    "failed to delete pruned session {} from the state db: {err}",
+                    session.thread_id
+                );
+            }
+        }
+    }
+    Ok(report)
+}
+
+fn prune_sessions_blocking(
+    codex_home: &Path,
+    retention: &SessionRetention,
+    resume_path: Option<&Path>,
+    dry_run: bool,
+    now: SystemTime,
+) -> io::Result<SessionsPrunedEvent> {
+    let mut sessions = Vec::new();
+    for subdir in [SESSIONS_SUBDIR, ARCHIVED_SESSIONS_SUBDIR] {
+        collect_sessions(&codex_home.join(subdir), &mut sessions)?;
+    }
+    sessions.sort_by(|a, b| b.modified.cmp(&a.modified));
+
+    let mut pruned = Vec::new();
+    let mut freed_bytes = 0;
+    let mut kept_bytes = 0;
+    for session in sessions {
+        let age = now.duration_since(session.modified).unwrap_or_default();
+        let reason = if age < RECENT_WRITE_GRACE
+            || retention
+                .pinned_threads
+                .contains(&session.thread_id.to_string())
+            || resume_path == Some(session.path.as_path())
+            || lease::is_held(codex_home, &session.thread_id)
+        {
+            None
+        } else if let Some(days) = retention.max_age_days
+            && age > Duration::from_secs(days.saturating_mul(SECONDS_PER_DAY))
+        {
+            Some(format!("older than {days} days"))
+        } else if let Some(max_total_bytes) = retention.max_total_bytes
+            && kept_bytes + session.bytes > max_total_bytes
+        {
+            Some(format!("over the {max_total_bytes} byte total"))
+        } else {
+            None
+        };
+        let Some(reason) = reason else {
+            kept_bytes += session.bytes;
+            continue;
+        };
+        if !dry_run && let Err(err) = fs::remove_file(&session.path) {
+            warn!("failed to prune session {}: {err}", session.path.display());
+            kept_bytes += session.bytes;
+            continue;
+        }
+        freed_bytes += session.bytes;
+        pruned.push(PrunedSession {
+            thread_id: session.thread_id,
+            path: session.path,
+            bytes: session.bytes,
+            reason,
+        });
+    }
+
+    if !dry_run && !pruned.is_empty() {
+        let thread_ids: HashSet<ThreadId> =
+            pruned.iter().map(|session| session.thread_id).collect();
+        if let Err(err) = remove_session_index_entries(codex_home, &thread_ids) {
+            warn!("failed to drop pruned sessions from the session index: {err}");
+        }
+    }
+
+    Ok(SessionsPrunedEvent {
+        dry_run,
+        pruned,
+        freed_bytes,
+        kept_bytes,
+    })
+}
+
+fn collect_sessions(dir: &Path, sessions: &mut Vec<StoredSession>) -> io::Result<()> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    for entry in entries {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            collect_sessions(&entry.path(), sessions)?;
+            continue;
+        }
+        let name = entry.file_name();
+        let Some(thread_id) = name
+            .to_str()
+            .and_then(parse_timestamp_uuid_from_filename)
+            .and_then(|(_, uuid)| ThreadId::from_string(&uuid.to_string()).ok())
+        else {
+            continue;
+        };
+        if !file_type.is_file() {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        sessions.push(StoredSession {
+            path: entry.path(),
+            thread_id,
+            bytes: metadata.len(),
+            modified: metadata.modified()?,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rollout::lease::SessionLease;
+    use crate::rollout::session_index::SessionIndexEntry;
+    use crate::rollout::session_index::append_session_index_entry;
+    use pretty_assertions::assert_eq;
+
+    const DAY: Duration = Duration::from_secs(SECONDS_PER_DAY);
+
+    fn thread_id(n: u8) -> ThreadId {
+        ThreadId::from_string(&format!("00000000-0000-0000-0000-00000000000{n}"))
+            .expect("thread id")
+    }
+
+    fn write_rollout(dir: &Path, n: u8, bytes: usize, modified: SystemTime) -> PathBuf {
+        fs::create_dir_all(dir).expect("create sessions dir");
+        let path = dir.join(format!(
+            "rollout-2025-01-01T00-00-00-{}.jsonl",
+            thread_id(n)
+        ));
+        fs::write(&path, "x".repeat(bytes)).expect("write rollout");
+        fs::File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(modified))
+            .expect("set mtime");
+        path
+    }
+
+    #[tokio::test]
+    async fn prunes_by_age_and_total_size_keeping_pinned_recent_resumed_and_leased() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let sessions = codex_home.path().join(SESSIONS_SUBDIR).join("2025/01/01");
+        let archived = codex_home.path().join(ARCHIVED_SESSIONS_SUBDIR);
+        let now = SystemTime::now();
+        let live = write_rollout(&sessions, 1, 400, now);
+        let recent = write_rollout(&sessions, 2, 300, now - DAY);
+        let over_total = write_rollout(&archived, 3, 200, now - 2 * DAY);
+        let pinned = write_rollout(&archived, 4, 100, now - 60 * DAY);
+        let old = write_rollout(&sessions, 5, 10, now - 40 * DAY);
+        let resumed = write_rollout(&sessions, 6, 10, now - 50 * DAY);
+        let leased = write_rollout(&sessions, 7, 10, now - 50 * DAY);
+        fs::write(sessions.join("notes.txt"), "not a rollout").expect("write other file");
+        let _lease = SessionLease::acquire(codex_home.path(), &thread_id(7)).expect("lease");
+        for n in [3, 4, 5] {
+            append_session_index_entry(
+                codex_home.path(),
+                &SessionIndexEntry {
+                    id: thread_id(n),
+                    thread_name: format!("thread {n}"),
+                    updated_at: "2025-01-01T00:00:00Z".to_string(),
+                },
+            )
+            .await
+            .expect("append index entry");
+        }
+        let retention = SessionRetention {
+            max_age_days: Some(30),
+            max_total_bytes: Some(820),
+            pinned_threads: vec![thread_id(4).to_string()],
+        };
+        let expected = |dry_run| SessionsPrunedEvent {
+            dry_run,
+            pruned: vec![
+                PrunedSession {
+                    thread_id: thread_id(3),
+                    path: over_total.clone(),
+                    bytes: 200,
+                    reason: "over the 820 byte total".to_string(),
+                },
+                PrunedSession {
+                    thread_id: thread_id(5),
+                    path: old.clone(),
+                    bytes: 10,
+                    reason: "older than 30 days".to_string(),
+                },
+            ],
+            freed_bytes: 210,
+            kept_bytes: 820,
+        };
+
+        assert_eq!(
+            prune_sessions(codex_home.path(), &retention, Some(&resumed), None, true)
+                .await
+                .expect("dry run"),
+            expected(true)
+        );
+        assert!(over_total.exists() && old.exists());
+
+        assert_eq!(
+            prune_sessions(codex_home.path(), &retention, Some(&resumed), None, false)
+                .await
+                .expect("prune"),
+            expected(false)
+        );
+        assert_eq!(
+            [
+                &live,
+                &recent,
+                &over_total,
+                &pinned,
+                &old,
+                &resumed,
+                &leased
+            ]
+            .map(|path| path.exists()),
+            [true, true, false, true, false, true, true]
+        );
+        let index =
+            fs::read_to_string(codex_home.path().join("session_index.jsonl")).expect("read index");
+        let indexed: Vec<ThreadId> = index
+            .lines()
+            .map(|line| {
+                serde_json::from_str::<SessionIndexEntry>(line)
+                    .expect("index entry")
+                    .id
+            })
+            .collect();
+        assert_eq!(indexed, vec![thread_id(4)]);
+    }
+}
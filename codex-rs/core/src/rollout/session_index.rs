@@ -63,6 +63,32 @@ pub async fn append_session_index_entry(
     Ok(())
 }
 
+/// Drops every entry for `thread_ids` from the session index. Blocks on the
+/// file system.
+pub(crate) fn remove_session_index_entries(
+    codex_home: &Path,
+    thread_ids: &HashSet<ThreadId>,
+) -> std::io::Result<()> {
+    let path = session_index_path(codex_home);
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+    let kept: String = contents
+        .lines()
+        .filter(|line| {
+            serde_json::from_str::<SessionIndexEntry>(line)
+                .map_or(true, |entry| !thread_ids.contains(&entry.id))
+        })
+        .map(|line| format!("{line}\n"))
+        .collect();
+    if kept.len() == contents.len() {
+        return Ok(());
+    }
+    crate::path_utils::write_atomically(&path, &kept)
+}
+
 /// Find the latest thread name for a thread id, if any.
 pub async fn find_thread_name_by_id(
     codex_home: &Path,
@@ -1109,6 +1109,8 @@ async fn test_updated_at_uses_file_mtime() -> Result<()> {
                 model_provider: Some("test-provider".into()),
                 base_instructions: None,
                 dynamic_tools: None,
+                instruction_layers: None,
+                experiments: None,
             },
             git: None,
         }),
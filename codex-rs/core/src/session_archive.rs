@@ -0,0 +1,317 @@
+//! Portable session archives for moving a session between machines
+//! ([`Op::ExportSession`](codex_protocol::protocol::Op::ExportSession) and
+//! [`Op::ImportSession`](codex_protocol::protocol::Op::ImportSession)).
+//!
+//! An archive is one JSON document holding the session's rollout lines
+//! (decompressed, so either side may use `rollout_compression`), the metadata
+//! needed to place them, the thread's memory summaries, and a manifest of its
+//! attachments. Attachment content is not archived: attachments are not
+//! restored on resume either, so the manifest tells the user what to attach
+//! again. The archive also carries the breakdown of the live context, with
+//! when and where each item came from, for auditing. Importing stores the
+//! rollout with the local sessions under its original file name, after which
+//! the session resumes like any other, and merges the memory summaries into
+//! the local ones.
+
+use std::ffi::OsStr;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::ThreadId;
+use codex_protocol::protocol::ArchivedAttachment;
+use codex_protocol::protocol::ContextItemBreakdown;
+use codex_protocol::protocol::RolloutItem;
+use codex_protocol::protocol::RolloutLine;
+use codex_protocol::protocol::SessionExportedEvent;
+use codex_protocol::protocol::SessionImportedEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncWriteExt;
+
+use crate::memories::memory_root;
+use crate::memories::rollout_summaries_dir;
+use crate::rollout::RolloutLines;
+use crate::rollout::SESSIONS_SUBDIR;
+use crate::rollout::find_thread_path_by_id_str;
+use crate::rollout::list::parse_timestamp_uuid_from_filename;
+use crate::rollout::rollout_date_parts;
+
+const ARCHIVE_VERSION: u32 = 1;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SessionArchive {
+    version: u32,
+    thread_id: ThreadId,
+    source_cwd: PathBuf,
+    rollout_file_name: String,
+    rollout: Vec<String>,
+    memory: Vec<ArchivedFile>,
+    attachments: Vec<ArchivedAttachment>,
+    #[serde(default)]
+    context: Vec<ContextItemBreakdown>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ArchivedFile {
+    name: String,
+    content: String,
+}
+
+/// Writes the session recorded at `rollout_path`, whose live context is
+/// `context`, to an archive at `path`.
+pub(crate) async fn export_session(
+    codex_home: &Path,
+    rollout_path: &Path,
+    attachments: Vec<ArchivedAttachment>,
+    context: Vec<ContextItemBreakdown>,
+    path: &Path,
+) -> io::Result<SessionExportedEvent> {
+    let rollout_file_name = rollout_path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| io::Error::other("session has no rollout file"))?
+        .to_string();
+    let mut lines = RolloutLines::open(rollout_path).await?;
+    let mut rollout = Vec::new();
+    let mut meta = None;
+    while let Some(line) = lines.next_line().await? {
+        if meta.is_none()
+            && let Ok(RolloutLine {
+                item: RolloutItem::SessionMeta(session_meta),
+                ..
+            }) = serde_json::from_str::<RolloutLine>(&line)
+        {
+            meta = Some(session_meta.meta);
+        }
+        rollout.push(line);
+    }
+    let meta = meta.ok_or_else(|| io::Error::other("rollout has no session metadata"))?;
+
+    let archive = SessionArchive {
+        version: ARCHIVE_VERSION,
+        thread_id: meta.id,
+        source_cwd: meta.cwd,
+        rollout_file_name,
+        rollout,
+        memory: memory_summaries(codex_home, &meta.id).await?,
+        attachments,
+        context,
+    };
+    tokio::fs::write(path, serde_json::to_vec(&archive)?).await?;
+    Ok(SessionExportedEvent {
+        thread_id: archive.thread_id,
+        path: path.to_path_buf(),
+        attachments: archive.attachments,
+    })
+}
+
+/// Stores the session in the archive at `path` with the sessions under
+/// `codex_home`. Fails if a session with the same thread id already exists.
+pub(crate) async fn import_session(
+    codex_home: &Path,
+    path: &Path,
+) -> io::Result<SessionImportedEvent> {
+    let archive: SessionArchive = serde_json::from_slice(&tokio::fs::read(path).await?)
+        .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+    if archive.version != ARCHIVE_VERSION {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unsupported session archive version {}", archive.version),
+        ));
+    }
+    // The file name must be a plain rollout name for this thread: it is
+    // joined onto the sessions directory.
+    let file_name = archive.rollout_file_name.as_str();
+    let date_parts = parse_timestamp_uuid_from_filename(file_name)
+        .filter(|(_, uuid)| uuid.to_string() == archive.thread_id.to_string())
+        .and_then(|_| rollout_date_parts(OsStr::new(file_name)));
+    let Some((year, month, day)) = date_parts else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("invalid rollout file name `{file_name}` in session archive"),
+        ));
+    };
+    let thread_id = archive.thread_id.to_string();
+    if let Some(existing) = find_thread_path_by_id_str(codex_home, &thread_id).await? {
+        return Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "session {thread_id} already exists at {}",
+                existing.display()
+            ),
+        ));
+    }
+
+    let dir = codex_home
+        .join(SESSIONS_SUBDIR)
+        .join(year)
+        .join(month)
+        .join(day);
+    tokio::fs::create_dir_all(&dir).await?;
+    let rollout_path = dir.join(file_name);
+    let mut file = tokio::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&rollout_path)
+        .await?;
+    for line in &archive.rollout {
+        file.write_all(line.as_bytes()).await?;
+        file.write_all(b"\n").await?;
+    }
+    file.flush().await?;
+
+    let summaries_dir = rollout_summaries_dir(&memory_root(codex_home));
+    tokio::fs::create_dir_all(&summaries_dir).await?;
+    for summary in &archive.memory {
+        if Path::new(&summary.name).file_name() != Some(OsStr::new(&summary.name)) {
+            continue;
+        }
+        // Memories already on this machine are kept: an imported summary
+        // whose name is taken is appended to the existing one unless it is
+        // already there.
+        let summary_path = summaries_dir.join(&summary.name);
+        let content = match tokio::fs::read_to_string(&summary_path).await {
+            Ok(existing) if existing.contains(&summary.content) => continue,
+            Ok(existing) => format!("{}\n\n{}", existing.trim_end(), summary.content),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => summary.content.clone(),
+            Err(err) => return Err(err),
+        };
+        tokio::fs::write(summary_path, content).await?;
+    }
+
+    Ok(SessionImportedEvent {
+        thread_id: archive.thread_id,
+        rollout_path,
+        source_cwd: archive.source_cwd,
+        attachments: archive.attachments,
+    })
+}
+
+/// The memory summaries written for `thread_id`, which start with a
+/// `thread_id:` header.
+async fn memory_summaries(
+    codex_home: &Path,
+    thread_id: &ThreadId,
+) -> io::Result<Vec<ArchivedFile>> {
+    let header = format!("thread_id: {thread_id}\n");
+    let mut entries =
+        match tokio::fs::read_dir(rollout_summaries_dir(&memory_root(codex_home))).await {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err),
+        };
+    let mut summaries = Vec::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let Some(name) = entry.file_name().to_str().map(str::to_string) else {
+            continue;
+        };
+        let Ok(content) = tokio::fs::read_to_string(entry.path()).await else {
+            continue;
+        };
+        if content.starts_with(&header) {
+            summaries.push(ArchivedFile { name, content });
+        }
+    }
+    summaries.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::protocol::SessionMeta;
+    use codex_protocol::protocol::SessionMetaLine;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn exported_session_imports_on_another_machine() {
+        let laptop = tempfile::tempdir().expect("tempdir");
+        let desktop = tempfile::tempdir().expect("tempdir");
+        let thread_id = ThreadId::new();
+        let file_name = format!("rollout-2025-03-04T05-06-07-{thread_id}.jsonl");
+        let meta_line = serde_json::to_string(&RolloutLine {
+            timestamp: "2025-03-04T05:06:07.000Z".to_string(),
+            item: RolloutItem::SessionMeta(SessionMetaLine {
+                meta: SessionMeta {
+                    id: thread_id,
+                    cwd: PathBuf::from("/home/me/project"),
+                    ..SessionMeta::default()
+                },
+                git: None,
+            }),
+        })
+        .expect("serialize session meta");
+        let rollout = format!("{meta_line}\n{{\"timestamp\":\"t\",\"type\":\"future_item\"}}\n");
+        let source_dir = laptop.path().join(SESSIONS_SUBDIR).join("2025/03/04");
+        std::fs::create_dir_all(&source_dir).expect("create sessions dir");
+        std::fs::write(source_dir.join(&file_name), &rollout).expect("write rollout");
+        let summaries = rollout_summaries_dir(&memory_root(laptop.path()));
+        std::fs::create_dir_all(&summaries).expect("create memory dir");
+        let summary =
+            format!("thread_id: {thread_id}\ncwd: /home/me/project\n\nFixed the build.\n");
+        std::fs::write(summaries.join("mine.md"), &summary).expect("write summary");
+        std::fs::write(summaries.join("other.md"), "thread_id: other\n").expect("write summary");
+        let attachments = vec![ArchivedAttachment {
+            id: "att-1".to_string(),
+            content_type: "text/x-diff".to_string(),
+            name: Some("fix.diff".to_string()),
+            bytes: 120,
+        }];
+        let archive = laptop.path().join("session.json");
+
+        let exported = export_session(
+            laptop.path(),
+            &source_dir.join(&file_name),
+            attachments.clone(),
+            Vec::new(),
+            &archive,
+        )
+        .await
+        .expect("export");
+        assert_eq!(
+            exported,
+            SessionExportedEvent {
+                thread_id,
+                path: archive.clone(),
+                attachments: attachments.clone(),
+            }
+        );
+
+        let imported_summaries = rollout_summaries_dir(&memory_root(desktop.path()));
+        std::fs::create_dir_all(&imported_summaries).expect("create memory dir");
+        let desktop_summary = "thread_id: desktop\ncwd: /work\n\nRan the tests.\n";
+        std::fs::write(imported_summaries.join("mine.md"), desktop_summary).expect("write summary");
+
+        let rollout_path = desktop
+            .path()
+            .join(SESSIONS_SUBDIR)
+            .join("2025/03/04")
+            .join(&file_name);
+        assert_eq!(
+            import_session(desktop.path(), &archive)
+                .await
+                .expect("import"),
+            SessionImportedEvent {
+                thread_id,
+                rollout_path: rollout_path.clone(),
+                source_cwd: PathBuf::from("/home/me/project"),
+                attachments,
+            }
+        );
+        assert_eq!(
+            std::fs::read_to_string(&rollout_path).expect("read imported rollout"),
+            rollout
+        );
+        assert_eq!(
+            std::fs::read_to_string(imported_summaries.join("mine.md")).expect("read summary"),
+            format!("{}\n\n{summary}", desktop_summary.trim_end())
+        );
+        assert!(!imported_summaries.join("other.md").exists());
+
+        let err = import_session(desktop.path(), &archive)
+            .await
+            .expect_err("second import");
+        assert_eq!(err.kind(), io::ErrorKind::AlreadyExists);
+    }
+}
@@ -0,0 +1,447 @@
+//! Shadow workspaces: sessions that edit a copy of the repository.
+//!
+//! With `shadow_workspace = true`, a new session snapshots the repository
+//! (including uncommitted and untracked files, via a ghost commit) and checks
+//! the snapshot out as a detached `git worktree` under
+//! `$CODEX_HOME/shadow-workspaces`. The session's cwd points into that copy,
+//! so every command and patch lands there. `Op::ApplyToRealWorkspace` turns
+//! everything changed since the snapshot into a single patch, asks the user
+//! to review it, and applies it to the real repository with `git apply`.
+//! Ignored files (build outputs, dependencies) are not copied.
+//!
+//! The worktree's `HEAD` is the commit its changes are diffed against, so a
+//! resumed session reopens the same worktree and a forked one snapshots its
+//! parent's worktree while keeping the parent's unapplied changes.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use codex_git::ApplyGitRequest;
+use codex_git::CreateGhostCommitOptions;
+use codex_git::apply_git_patch;
+use codex_git::create_ghost_commit;
+use codex_protocol::protocol::FileChange;
+use tokio::process::Command;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::git_info::get_git_repo_root;
+
+/// Identity of the commits that advance the base, the same as for ghost
+/// commits, so applying works without a configured git identity.
+const COMMIT_USER_NAME: &str = "user.name=Codex Snapshot";
+const COMMIT_USER_EMAIL: &str = "user.email=snapshot@codex.local";
+
+pub(crate) struct ShadowWorkspace {
+    real_root: PathBuf,
+    shadow_root: PathBuf,
+    cwd: PathBuf,
+}
+
+impl ShadowWorkspace {
+    /// Snapshots the repository containing `cwd` into a new worktree.
+    pub(crate) async fn create(codex_home: &Path, cwd: &Path) -> anyhow::Result<Self> {
+        let real_root = get_git_repo_root(cwd)
+            .with_context(|| format!("{} is not inside a Git repository", cwd.display()))?;
+        let relative_cwd = cwd.strip_prefix(&real_root).unwrap_or(Path::new(""));
+        Self::snapshot(
+            codex_home,
+            real_root.clone(),
+            &real_root,
+            relative_cwd,
+            None,
+        )
+        .await
+    }
+
+    /// Reopens the worktree `cwd` lies in, if it is a shadow workspace under
+    /// `codex_home` that still exists.
+    pub(crate) async fn reopen(codex_home: &Path, cwd: &Path) -> anyhow::Result<Option<Self>> {
+        let workspaces = dunce::canonicalize(codex_home.join("shadow-workspaces"))
+            .unwrap_or_else(|_| codex_home.join("shadow-workspaces"));
+        let Some(name) = cwd
+            .strip_prefix(&workspaces)
+            .ok()
+            .and_then(|relative| relative.components().next())
+        else {
+            return Ok(None);
+        };
+        let shadow_root = workspaces.join(name);
+        if !shadow_root.join(".git").exists() {
+            return Ok(None);
+        }
+        let common_dir = run_git(
+            &shadow_root,
+            &["rev-parse", "--path-format=absolute", "--git-common-dir"],
+        )
+        .await?;
+        let real_root = Path::new(common_dir.trim())
+            .parent()
+            .context("shadow workspace has no parent repository")?
+            .to_path_buf();
+        Ok(Some(Self {
+            real_root,
+            shadow_root,
+            cwd: cwd.to_path_buf(),
+        }))
+    }
+
+    /// Snapshots this worktree into a new one for a forked session. The fork
+    /// starts from the same base, so changes not yet applied carry over.
+    pub(crate) async fn fork(&self, codex_home: &Path) -> anyhow::Result<Self> {
+        run_git(&self.shadow_root, &["add", "-A"]).await?;
+        let base = self.base().await?;
+        let relative_cwd = self
+            .cwd
+            .strip_prefix(&self.shadow_root)
+            .unwrap_or(Path::new(""));
+        Self::snapshot(
+            codex_home,
+            self.real_root.clone(),
+            &self.shadow_root,
+            relative_cwd,
+            Some(&base),
+        )
+        .await
+    }
+
+    /// Checks a snapshot of `source` out as a new worktree whose changes
+    /// apply to `real_root`, diffed against `base` (the snapshot itself when
+    /// `None`).
+    async fn snapshot(
+        codex_home: &Path,
+        real_root: PathBuf,
+        source: &Path,
+        relative_cwd: &Path,
+        base: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let snapshot_root = source.to_path_buf();
+        let snapshot = tokio::task::spawn_blocking(move || {
+            create_ghost_commit(
+                &CreateGhostCommitOptions::new(&snapshot_root).message("codex shadow workspace"),
+            )
+        })
+        .await??;
+
+        let shadow_root = codex_home
+            .join("shadow-workspaces")
+            .join(Uuid::new_v4().to_string());
+        tokio::fs::create_dir_all(&shadow_root).await?;
+        let shadow_arg = shadow_root.to_string_lossy().to_string();
+        run_git(
+            &real_root,
+            &["worktree", "add", "--detach", &shadow_arg, snapshot.id()],
+        )
+        .await?;
+        if let Some(base) = base {
+            run_git(&shadow_root, &["reset", "--soft", base]).await?;
+        }
+
+        // Canonical, so `contains` agrees with canonicalized cwd changes.
+        let shadow_root = dunce::canonicalize(&shadow_root)?;
+        let cwd = shadow_root.join(relative_cwd);
+        Ok(Self {
+            real_root,
+            shadow_root,
+            cwd,
+        })
+    }
+
+    /// The session's working directory inside the shadow.
+    pub(crate) fn cwd(&self) -> &Path {
+        &self.cwd
+    }
+
+    pub(crate) fn real_root(&self) -> &Path {
+        &self.real_root
+    }
+
+    /// Whether `path` lies inside the shadow copy.
+    pub(crate) fn contains(&self, path: &Path) -> bool {
+        path.starts_with(&self.shadow_root)
+    }
+
+    /// The commit the shadow's changes are diffed against: the initial
+    /// snapshot, then the state of the shadow as of the last apply.
+    async fn base(&self) -> anyhow::Result<String> {
+        let head = run_git(&self.shadow_root, &["rev-parse", "HEAD"]).await?;
+        Ok(head.trim().to_string())
+    }
+
+    /// Everything changed in the shadow since the last apply, as a git patch.
+    pub(crate) async fn diff(&self) -> anyhow::Result<String> {
+        run_git(&self.shadow_root, &["add", "-A"]).await?;
+        run_git(
+            &self.shadow_root,
+            &["diff", "--cached", "--binary", "--no-renames", "HEAD"],
+        )
+        .await
+    }
+
+    /// The files touched by `diff`, keyed by their path in the real
+    /// repository, for the approval request.
+    pub(crate) async fn changes(&self, diff: &str) -> anyhow::Result<HashMap<PathBuf, FileChange>> {
+        // Paths come from `--name-status -z`, which lists the files in the
+        // same order as the patch without quoting them.
+        let name_status = run_git(
+            &self.shadow_root,
+            &[
+                "diff",
+                "--cached",
+                "--name-status",
+                "-z",
+                "--no-renames",
+                "HEAD",
+            ],
+        )
+        .await?;
+        let fields: Vec<&str> = name_status.split('\0').collect();
+        let mut changes = HashMap::new();
+        for (entry, block) in fields.chunks_exact(2).zip(split_file_diffs(diff)) {
+            let [status, path] = entry else {
+                continue;
+            };
+            let path = PathBuf::from(*path);
+            let change = if status.starts_with('A') {
+                FileChange::Add {
+                    content: std::fs::read_to_string(self.shadow_root.join(&path))
+                        .unwrap_or_default(),
+                }
+            } else if status.starts_with('D') {
+                FileChange::Delete {
+                    content: std::fs::read_to_string(self.real_root.join(&path))
+                        .unwrap_or_default(),
+                }
+            } else {
+                let hunks = block
+                    .find("\n@@")
+                    .map_or(block, |start| &block[start + 1..]);
+                FileChange::Update {
+                    unified_diff: hunks.to_string(),
+                    move_path: None,
+                }
+            };
+            changes.insert(self.real_root.join(path), change);
+        }
+        Ok(changes)
+    }
+
+    /// Applies `diff` to the real repository and makes the current shadow
+    /// state the base for the next apply.
+    pub(crate) async fn apply(&self, diff: String) -> anyhow::Result<Vec<String>> {
+        // Commit the shadow state before touching the real repository, so a
+        // failure here leaves both unchanged.
+        let tree = run_git(&self.shadow_root, &["write-tree"]).await?;
+        let base = self.base().await?;
+        let commit = run_git(
+            &self.shadow_root,
+            &[
+                "-c",
+                COMMIT_USER_NAME,
+                "-c",
+                COMMIT_USER_EMAIL,
+                "commit-tree",
+                tree.trim(),
+                "-p",
+                &base,
+                "-m",
+                "codex shadow workspace apply",
+            ],
+        )
+        .await?;
+
+        let request = ApplyGitRequest {
+            cwd: self.real_root.clone(),
+            diff,
+            revert: false,
+            preflight: false,
+        };
+        let result = tokio::task::spawn_blocking(move || apply_git_patch(&request)).await??;
+        anyhow::ensure!(
+            result.exit_code == 0,
+            "git apply failed: {}",
+            result.stderr.trim()
+        );
+        run_git(&self.shadow_root, &["reset", "--soft", commit.trim()]).await?;
+        Ok(result.applied_paths)
+    }
+
+    /// Removes the worktree unless it holds changes that were never applied.
+    pub(crate) async fn cleanup(&self) {
+        match self.diff().await {
+            Ok(diff) if diff.is_empty() => {
+                let shadow_arg = self.shadow_root.to_string_lossy().to_string();
+                if let Err(err) = run_git(
+                    &self.real_root,
+                    &["worktree", "remove", "--force", &shadow_arg],
+                )
+                .await
+                {
+                    warn!("failed to remove shadow workspace: {err:#}");
+                }
+            }
+            Ok(_) => warn!(
+                "keeping shadow workspace {} with unapplied changes",
+                self.shadow_root.display()
+            ),
+            Err(err) => warn!("failed to inspect shadow workspace: {err:#}"),
+        }
+    }
+}
+
+/// Splits a `git diff` into one block per file.
+fn split_file_diffs(diff: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = diff
+        .match_indices("diff --git ")
+        .filter(|(index, _)| *index == 0 || diff.as_bytes()[index - 1] == b'\n')
+        .map(|(index, _)| index)
+        .collect();
+    starts.push(diff.len());
+    starts
+        .windows(2)
+        .map(|window| &diff[window[0]..window[1]])
+        .collect()
+}
+
+async fn run_git(dir: &Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .context("failed to run git")?;
+    anyhow::ensure!(
+        output.status.success(),
+        "git {} failed: {}",
+        args.first().copied().unwrap_or_default(),
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serial_test::serial;
+
+    #[test]
+    fn split_file_diffs_returns_one_block_per_file() {
+        let diff = "diff --git a/a.txt b/a.txt\n@@ -1 +1 @@\n-a\n+b\ndiff --git a/b.txt b/b.txt\nnew file mode 100644\n@@ -0,0 +1 @@\n+diff --git inside\n";
+
+        assert_eq!(
+            split_file_diffs(diff),
+            vec![
+                "diff --git a/a.txt b/a.txt\n@@ -1 +1 @@\n-a\n+b\n",
+                "diff --git a/b.txt b/b.txt\nnew file mode 100644\n@@ -0,0 +1 @@\n+diff --git inside\n",
+            ]
+        );
+    }
+
+    /// Points git at a global config that requires an explicit identity, as
+    /// on a machine where `user.name` and `user.email` were never set.
+    struct NoGitIdentityGuard {
+        _config: tempfile::NamedTempFile,
+        original: Option<std::ffi::OsString>,
+    }
+
+    impl NoGitIdentityGuard {
+        fn set() -> Self {
+            let config = tempfile::NamedTempFile::new().expect("git config");
+            std::fs::write(config.path(), "[user]\n\tuseConfigOnly = true\n")
+                .expect("write git config");
+            let original = std::env::var_os("GIT_CONFIG_GLOBAL");
+            unsafe {
+                std::env::set_var("GIT_CONFIG_GLOBAL", config.path());
+            }
+            Self {
+                _config: config,
+                original,
+            }
+        }
+    }
+
+    impl Drop for NoGitIdentityGuard {
+        fn drop(&mut self) {
+            unsafe {
+                match &self.original {
+                    Some(value) => std::env::set_var("GIT_CONFIG_GLOBAL", value),
+                    None => std::env::remove_var("GIT_CONFIG_GLOBAL"),
+                }
+            }
+        }
+    }
+
+    async fn init_repo(repo: &Path) {
+        run_git(repo, &["init"]).await.expect("git init");
+        run_git(repo, &["add", "-A"]).await.expect("git add");
+        run_git(
+            repo,
+            &[
+                "-c",
+                "user.name=Test",
+                "-c",
+                "user.email=test@example.com",
+                "commit",
+                "-m",
+                "init",
+            ],
+        )
+        .await
+        .expect("git commit");
+    }
+
+    #[tokio::test]
+    #[serial(git_config_global)]
+    async fn apply_works_without_a_git_identity_and_advances_the_base() {
+        let _guard = NoGitIdentityGuard::set();
+        let temp = tempfile::tempdir().expect("tempdir");
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(&repo).expect("create repo");
+        std::fs::write(repo.join("a.txt"), "one\n").expect("write a.txt");
+        init_repo(&repo).await;
+        let shadow = ShadowWorkspace::create(&temp.path().join("home"), &repo)
+            .await
+            .expect("create shadow");
+        std::fs::write(shadow.cwd().join("a.txt"), "two\n").expect("edit a.txt");
+
+        let diff = shadow.diff().await.expect("diff");
+        shadow.apply(diff).await.expect("apply");
+
+        assert_eq!(
+            std::fs::read_to_string(repo.join("a.txt")).expect("read a.txt"),
+            "two\n"
+        );
+        assert_eq!(shadow.diff().await.expect("diff after apply"), "");
+    }
+
+    #[tokio::test]
+    async fn reopened_workspace_lists_changes_by_path() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let repo = temp.path().join("repo");
+        std::fs::create_dir_all(repo.join("dir b")).expect("create dir");
+        std::fs::write(repo.join("dir b/a.txt"), "one\n").expect("write a.txt");
+        init_repo(&repo).await;
+        let codex_home = temp.path().join("home");
+        let shadow = ShadowWorkspace::create(&codex_home, &repo)
+            .await
+            .expect("create shadow");
+        std::fs::write(shadow.cwd().join("dir b/a.txt"), "two\n").expect("edit a.txt");
+
+        let reopened = ShadowWorkspace::reopen(&codex_home, shadow.cwd())
+            .await
+            .expect("reopen shadow")
+            .expect("shadow workspace");
+        let diff = reopened.diff().await.expect("diff");
+        let changes = reopened.changes(&diff).await.expect("changes");
+
+        let real_root = dunce::canonicalize(&repo).expect("canonical repo");
+        assert_eq!(reopened.real_root(), real_root);
+        assert_eq!(
+            changes.into_keys().collect::<Vec<_>>(),
+            vec![real_root.join("dir b/a.txt")]
+        );
+    }
+}
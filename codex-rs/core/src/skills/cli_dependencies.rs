@@ -0,0 +1,435 @@
+//! Installing the command-line tools mentioned skills depend on.
+//!
+//! A skill's `cli` dependency may declare the command that installs it and a
+//! health check. When such a tool is not on the `PATH`, the user is shown the
+//! exact install command of every missing tool in one prompt; a tool the
+//! user skips is not offered again in the session. Approved
+//! installs run in the sandbox with network access, writing only to
+//! `<codex_home>/tools/<name>` (`$CODEX_DEPENDENCY_PREFIX`), and are expected
+//! to leave the tool's executables in its `bin` directory. The health check
+//! then runs read-only against that `PATH`; once it passes, the directory is
+//! put on the `PATH` of the session's commands through the dependency
+//! environment.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::env;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_async_utils::OrCancelExt;
+use codex_protocol::request_user_input::RequestUserInputArgs;
+use codex_protocol::request_user_input::RequestUserInputQuestion;
+use codex_protocol::request_user_input::RequestUserInputQuestionOption;
+use codex_protocol::request_user_input::RequestUserInputResponse;
+use tokio_util::sync::CancellationToken;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::exec::ExecExpiration;
+use crate::exec::ExecParams;
+use crate::exec::process_exec_tool_call;
+use crate::exec_env::create_env;
+use crate::features::Feature;
+use crate::protocol::ReadOnlyAccess;
+use crate::protocol::SandboxPolicy;
+use crate::sandboxing::SandboxPermissions;
+use crate::skills::SkillMetadata;
+
+const SKILL_CLI_DEPENDENCY_PROMPT_ID: &str = "skill_cli_dependency_install";
+const CLI_DEPENDENCY_OPTION_INSTALL: &str = "Install";
+const CLI_DEPENDENCY_OPTION_SKIP: &str = "Skip";
+const CLI_DEPENDENCY_TOOLS_SUBDIR: &str = "tools";
+const CLI_DEPENDENCY_PREFIX_ENV_VAR: &str = "CODEX_DEPENDENCY_PREFIX";
+const CLI_DEPENDENCY_INSTALL_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const CLI_DEPENDENCY_CHECK_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CliDependency {
+    pub(crate) skill_name: String,
+    pub(crate) name: String,
+    pub(crate) install: Option<String>,
+    pub(crate) check: Option<String>,
+}
+
+/// The `cli` dependencies of `mentioned_skills`, the first declaration of
+/// each tool winning.
+pub(crate) fn collect_cli_dependencies(mentioned_skills: &[SkillMetadata]) -> Vec<CliDependency> {
+    let mut dependencies = Vec::new();
+    let mut seen_names = HashSet::new();
+    for skill in mentioned_skills {
+        let Some(skill_dependencies) = &skill.dependencies else {
+            continue;
+        };
+        for tool in &skill_dependencies.tools {
+            if !tool.r#type.eq_ignore_ascii_case("cli") || tool.value.is_empty() {
+                continue;
+            }
+            if !seen_names.insert(tool.value.clone()) {
+                continue;
+            }
+            dependencies.push(CliDependency {
+                skill_name: skill.name.clone(),
+                name: tool.value.clone(),
+                install: tool.install.clone(),
+                check: tool.check.clone(),
+            });
+        }
+    }
+    dependencies
+}
+
+/// Offers to install the tools of `dependencies` missing from the `PATH` and
+/// installs those the user approves.
+pub(crate) async fn install_missing_cli_dependencies(
+    sess: &Arc<Session>,
+    turn_context: &Arc<TurnContext>,
+    cancellation_token: &CancellationToken,
+    dependencies: &[CliDependency],
+) {
+    if dependencies.is_empty() {
+        return;
+    }
+
+    let mut path = sess
+        .dependency_env()
+        .await
+        .get("PATH")
+        .cloned()
+        .or_else(|| env::var("PATH").ok())
+        .unwrap_or_default();
+    let skipped = sess.skipped_cli_dependencies().await;
+    let mut installable = Vec::new();
+    for dependency in dependencies {
+        if skipped.contains(&dependency.name)
+            || which::which_in(&dependency.name, Some(&path), &turn_context.cwd).is_ok()
+        {
+            continue;
+        }
+        let name = dependency.name.as_str();
+        let skill_name = dependency.skill_name.as_str();
+        let is_plain_name = name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+            && !name.starts_with('.');
+        if dependency.install.is_none() || !is_plain_name {
+            sess.notify_background_event(
+                turn_context.as_ref(),
+                format!("The skill \"{skill_name}\" requires `{name}`, which is not installed."),
+            )
+            .await;
+            continue;
+        }
+        installable.push(dependency);
+    }
+    if installable.is_empty() {
+        return;
+    }
+
+    let tools_dir = turn_context
+        .config
+        .codex_home
+        .join(CLI_DEPENDENCY_TOOLS_SUBDIR);
+    let questions = installable
+        .iter()
+        .map(|dependency| {
+            let name = dependency.name.as_str();
+            let install = dependency.install.as_deref().unwrap_or_default();
+            let prefix = tools_dir.join(name);
+            RequestUserInputQuestion {
+                id: format!("{SKILL_CLI_DEPENDENCY_PROMPT_ID}:{name}"),
+                header: "Install tool?".to_string(),
+                question: format!(
+                    "The skill \"{}\" requires `{name}`, which is not installed. Install it by running `{install}`? It runs in the sandbox with network access and may only write to {}.",
+                    dependency.skill_name,
+                    prefix.display()
+                ),
+                is_other: false,
+                is_secret: false,
+                options: Some(vec![
+                    RequestUserInputQuestionOption {
+                        label: CLI_DEPENDENCY_OPTION_INSTALL.to_string(),
+                        description: "Run the install command, then check that the tool works."
+                            .to_string(),
+                    },
+                    RequestUserInputQuestionOption {
+                        label: CLI_DEPENDENCY_OPTION_SKIP.to_string(),
+                        description:
+                            "Continue without installing the tool, and do not ask again in this session."
+                                .to_string(),
+                    },
+                ]),
+            }
+        })
+        .collect();
+    let args = RequestUserInputArgs { questions };
+    let sub_id = &turn_context.sub_id;
+    let call_id = format!("cli-deps-{sub_id}");
+    let response_fut = sess.request_user_input(turn_context, call_id, args);
+    let response = tokio::select! {
+        biased;
+        _ = cancellation_token.cancelled() => {
+            let empty = RequestUserInputResponse {
+                answers: HashMap::new(),
+            };
+            sess.notify_user_input_response(sub_id, empty.clone()).await;
+            empty
+        }
+        response = response_fut => response.unwrap_or_else(|| RequestUserInputResponse {
+            answers: HashMap::new(),
+        }),
+    };
+
+    if cancellation_token.is_cancelled() {
+        return;
+    }
+    let (approved, skipped): (Vec<_>, Vec<_>) = installable.into_iter().partition(|dependency| {
+        response
+            .answers
+            .get(&format!(
+                "{SKILL_CLI_DEPENDENCY_PROMPT_ID}:{}",
+                dependency.name
+            ))
+            .is_some_and(|answer| {
+                answer
+                    .answers
+                    .iter()
+                    .any(|entry| entry == CLI_DEPENDENCY_OPTION_INSTALL)
+            })
+    });
+    sess.record_skipped_cli_dependencies(
+        skipped
+            .into_iter()
+            .map(|dependency| dependency.name.clone()),
+    )
+    .await;
+
+    for dependency in approved {
+        let name = dependency.name.as_str();
+        if cancellation_token.is_cancelled() {
+            return;
+        }
+
+        sess.notify_background_event(turn_context.as_ref(), format!("Installing `{name}`..."))
+            .await;
+        match install_cli_dependency(
+            sess,
+            turn_context,
+            cancellation_token,
+            dependency,
+            &tools_dir.join(name),
+            &path,
+        )
+        .await
+        {
+            Ok(updated_path) => {
+                path = updated_path;
+                sess.set_dependency_env(HashMap::from([("PATH".to_string(), path.clone())]))
+                    .await;
+                sess.notify_background_event(turn_context.as_ref(), format!("Installed `{name}`."))
+                    .await;
+            }
+            Err(err) => {
+                sess.notify_background_event(
+                    turn_context.as_ref(),
+                    format!("Could not install `{name}`: {err}"),
+                )
+                .await;
+            }
+        }
+    }
+}
+
+/// Runs the install command of `dependency` into `prefix` and its health
+/// check, and returns `path` with the tool's `bin` directory in front.
+async fn install_cli_dependency(
+    sess: &Session,
+    turn_context: &TurnContext,
+    cancellation_token: &CancellationToken,
+    dependency: &CliDependency,
+    prefix: &Path,
+    path: &str,
+) -> Result<String, String> {
+    let install = dependency.install.as_deref().unwrap_or_default();
+    tokio::fs::create_dir_all(prefix)
+        .await
+        .map_err(|err| format!("failed to create {}: {err}", prefix.display()))?;
+    let bin_dir = prefix.join("bin");
+    let updated_path = env::join_paths(std::iter::once(bin_dir).chain(env::split_paths(path)))
+        .map_err(|err| format!("failed to extend PATH: {err}"))?
+        .to_string_lossy()
+        .into_owned();
+
+    let mut env = create_env(
+        &turn_context.shell_environment_policy,
+        Some(sess.conversation_id),
+    );
+    env.insert("PATH".to_string(), path.to_string());
+    env.insert(
+        CLI_DEPENDENCY_PREFIX_ENV_VAR.to_string(),
+        prefix.to_string_lossy().into_owned(),
+    );
+    // The prefix is the install's workspace, so it is the only place outside
+    // the temporary directories it may write to.
+    let install_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: Vec::new(),
+        read_only_access: ReadOnlyAccess::FullAccess,
+        network_access: true,
+        exclude_tmpdir_env_var: false,
+        exclude_slash_tmp: false,
+    };
+    run_sandboxed(
+        sess,
+        turn_context,
+        cancellation_token,
+        install,
+        prefix,
+        env.clone(),
+        &install_policy,
+        CLI_DEPENDENCY_INSTALL_TIMEOUT,
+    )
+    .await
+    .map_err(|err| format!("`{install}` failed: {err}"))?;
+
+    let check = dependency
+        .check
+        .clone()
+        .unwrap_or_else(|| format!("{} --version", dependency.name));
+    env.insert("PATH".to_string(), updated_path.clone());
+    env.remove(CLI_DEPENDENCY_PREFIX_ENV_VAR);
+    run_sandboxed(
+        sess,
+        turn_context,
+        cancellation_token,
+        &check,
+        &turn_context.cwd,
+        env,
+        &SandboxPolicy::new_read_only_policy(),
+        CLI_DEPENDENCY_CHECK_TIMEOUT,
+    )
+    .await
+    .map_err(|err| format!("health check `{check}` failed: {err}"))?;
+
+    Ok(updated_path)
+}
+
+/// Runs `command` in the user's shell under `sandbox_policy`, treating a
+/// non-zero exit as a failure.
+#[allow(clippy::too_many_arguments)]
+async fn run_sandboxed(
+    sess: &Session,
+    turn_context: &TurnContext,
+    cancellation_token: &CancellationToken,
+    command: &str,
+    cwd: &Path,
+    env: HashMap<String, String>,
+    sandbox_policy: &SandboxPolicy,
+    timeout: Duration,
+) -> Result<(), String> {
+    let params = ExecParams {
+        command: sess.user_shell().derive_exec_args(command, false),
+        cwd: cwd.to_path_buf(),
+        expiration: ExecExpiration::Timeout(timeout),
+        env,
+        network: turn_context.network.clone(),
+        sandbox_permissions: SandboxPermissions::UseDefault,
+        windows_sandbox_level: turn_context.windows_sandbox_level,
+        justification: None,
+        arg0: None,
+    };
+    let output = process_exec_tool_call(
+        params,
+        sandbox_policy,
+        cwd,
+        &turn_context.codex_linux_sandbox_exe,
+        turn_context.features.enabled(Feature::UseLinuxSandboxBwrap),
+        None,
+    )
+    .or_cancel(cancellation_token)
+    .await
+    .map_err(|_| "cancelled".to_string())?
+    .map_err(|err| err.to_string())?;
+    if output.exit_code == 0 {
+        return Ok(());
+    }
+    let stderr = output.stderr.text.trim();
+    if stderr.is_empty() {
+        Err(format!("exited with code {}", output.exit_code))
+    } else {
+        Err(format!("exited with code {}: {stderr}", output.exit_code))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::skills::model::SkillDependencies;
+    use crate::skills::model::SkillToolDependency;
+    use codex_protocol::protocol::SkillScope;
+    use pretty_assertions::assert_eq;
+    use std::path::PathBuf;
+
+    fn skill(name: &str, tools: Vec<SkillToolDependency>) -> SkillMetadata {
+        SkillMetadata {
+            name: name.to_string(),
+            description: name.to_string(),
+            short_description: None,
+            interface: None,
+            dependencies: Some(SkillDependencies { tools }),
+            policy: None,
+            permissions: None,
+            path: PathBuf::from(name),
+            scope: SkillScope::User,
+        }
+    }
+
+    fn tool(kind: &str, value: &str, install: Option<&str>) -> SkillToolDependency {
+        SkillToolDependency {
+            r#type: kind.to_string(),
+            value: value.to_string(),
+            description: None,
+            transport: None,
+            command: None,
+            url: None,
+            version: None,
+            install: install.map(str::to_string),
+            check: None,
+        }
+    }
+
+    #[test]
+    fn collects_each_cli_dependency_once() {
+        let skills = vec![
+            skill(
+                "github",
+                vec![
+                    tool("cli", "gh", Some("install-gh")),
+                    tool("env_var", "GITHUB_TOKEN", None),
+                ],
+            ),
+            skill(
+                "release",
+                vec![tool("CLI", "gh", None), tool("cli", "jq", None)],
+            ),
+        ];
+
+        assert_eq!(
+            collect_cli_dependencies(&skills),
+            vec![
+                CliDependency {
+                    skill_name: "github".to_string(),
+                    name: "gh".to_string(),
+                    install: Some("install-gh".to_string()),
+                    check: None,
+                },
+                CliDependency {
+                    skill_name: "release".to_string(),
+                    name: "jq".to_string(),
+                    install: None,
+                    check: None,
+                },
+            ]
+        );
+    }
+}
@@ -88,6 +88,9 @@ struct DependencyTool {
     transport: Option<String>,
     command: Option<String>,
     url: Option<String>,
+    version: Option<String>,
+    install: Option<String>,
+    check: Option<String>,
 }
 
 const SKILLS_FILENAME: &str = "SKILL.md";
@@ -105,6 +108,9 @@ const MAX_DEPENDENCY_VALUE_LEN: usize = MAX_DESCRIPTION_LEN;
 const MAX_DEPENDENCY_DESCRIPTION_LEN: usize = MAX_DESCRIPTION_LEN;
 const MAX_DEPENDENCY_COMMAND_LEN: usize = MAX_DESCRIPTION_LEN;
 const MAX_DEPENDENCY_URL_LEN: usize = MAX_DESCRIPTION_LEN;
+const MAX_DEPENDENCY_VERSION_LEN: usize = MAX_NAME_LEN;
+const MAX_DEPENDENCY_INSTALL_LEN: usize = MAX_DESCRIPTION_LEN;
+const MAX_DEPENDENCY_CHECK_LEN: usize = MAX_DESCRIPTION_LEN;
 // Traversal depth from the skills root.
 const MAX_SCAN_DEPTH: usize = 6;
 const MAX_SKILLS_DIRS_PER_ROOT: usize = 2000;
@@ -669,6 +675,21 @@ fn resolve_dependency_tool(tool: DependencyTool) -> Option<SkillToolDependency>
         "dependencies.tools.command",
     );
     let url = resolve_str(tool.url, MAX_DEPENDENCY_URL_LEN, "dependencies.tools.url");
+    let version = resolve_str(
+        tool.version,
+        MAX_DEPENDENCY_VERSION_LEN,
+        "dependencies.tools.version",
+    );
+    let install = resolve_str(
+        tool.install,
+        MAX_DEPENDENCY_INSTALL_LEN,
+        "dependencies.tools.install",
+    );
+    let check = resolve_str(
+        tool.check,
+        MAX_DEPENDENCY_CHECK_LEN,
+        "dependencies.tools.check",
+    );
 
     Some(SkillToolDependency {
         r#type,
@@ -677,6 +698,9 @@ fn resolve_dependency_tool(tool: DependencyTool) -> Option<SkillToolDependency>
         transport,
         command,
         url,
+        version,
+        install,
+        check,
     })
 }
 
@@ -1124,12 +1148,15 @@ mod tests {
         "value": "github",
         "description": "GitHub MCP server",
         "transport": "streamable_http",
-        "url": "https://example.com/mcp"
+        "url": "https://example.com/mcp",
+        "version": "1.2.0"
       },
       {
         "type": "cli",
         "value": "gh",
-        "description": "GitHub CLI"
+        "description": "GitHub CLI",
+        "install": "curl -fsSL https://example.com/gh.tar.gz | tar -xz -C \"$CODEX_DEPENDENCY_PREFIX\"",
+        "check": "gh --version"
       },
       {
         "type": "mcp",
@@ -1168,6 +1195,9 @@ mod tests {
                             transport: None,
                             command: None,
                             url: None,
+                            version: None,
+                            install: None,
+                            check: None,
                         },
                         SkillToolDependency {
                             r#type: "mcp".to_string(),
@@ -1176,6 +1206,9 @@ mod tests {
                             transport: Some("streamable_http".to_string()),
                             command: None,
                             url: Some("https://example.com/mcp".to_string()),
+                            version: Some("1.2.0".to_string()),
+                            install: None,
+                            check: None,
                         },
                         SkillToolDependency {
                             r#type: "cli".to_string(),
@@ -1184,6 +1217,12 @@ mod tests {
                             transport: None,
                             command: None,
                             url: None,
+                            version: None,
+                            install: Some(
+                                "curl -fsSL https://example.com/gh.tar.gz | tar -xz -C \"$CODEX_DEPENDENCY_PREFIX\""
+                                    .to_string()
+                            ),
+                            check: Some("gh --version".to_string()),
                         },
                         SkillToolDependency {
                             r#type: "mcp".to_string(),
@@ -1192,6 +1231,9 @@ mod tests {
                             transport: Some("stdio".to_string()),
                             command: Some("gh-mcp".to_string()),
                             url: None,
+                            version: None,
+                            install: None,
+                            check: None,
                         },
                     ],
                 }),
@@ -1,3 +1,4 @@
+mod cli_dependencies;
 mod env_var_dependencies;
 pub mod injection;
 pub mod loader;
@@ -8,6 +9,8 @@ pub mod remote;
 pub mod render;
 pub mod system;
 
+pub(crate) use cli_dependencies::collect_cli_dependencies;
+pub(crate) use cli_dependencies::install_missing_cli_dependencies;
 pub(crate) use env_var_dependencies::collect_env_var_dependencies;
 pub(crate) use env_var_dependencies::resolve_skill_dependencies_for_turn;
 pub(crate) use injection::SkillInjections;
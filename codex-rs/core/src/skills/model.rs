@@ -55,6 +55,16 @@ pub struct SkillToolDependency {
     pub transport: Option<String>,
     pub command: Option<String>,
     pub url: Option<String>,
+    /// Version of the dependency the skill expects. The prompt to install a
+    /// missing MCP server is shown again when it changes.
+    pub version: Option<String>,
+    /// Shell command that installs a `cli` dependency into
+    /// `$CODEX_DEPENDENCY_PREFIX`, with its executables in the `bin`
+    /// directory there.
+    pub install: Option<String>,
+    /// Shell command that exits successfully once a `cli` dependency works.
+    /// Defaults to `<value> --version`.
+    pub check: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
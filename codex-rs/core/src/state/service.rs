@@ -4,15 +4,23 @@ use crate::AuthManager;
 use crate::RolloutRecorder;
 use crate::agent::AgentControl;
 use crate::analytics_client::AnalyticsEventsClient;
+use crate::attached_clients::AttachedClients;
 use crate::client::ModelClient;
 use crate::config::StartedNetworkProxy;
 use crate::exec_policy::ExecPolicyManager;
 use crate::file_watcher::FileWatcher;
 use crate::mcp_connection_manager::McpConnectionManager;
 use crate::models_manager::manager::ModelsManager;
+use crate::notifier::NotificationDispatcher;
+use crate::patch_artifact::PatchArtifact;
+use crate::shadow_workspace::ShadowWorkspace;
 use crate::skills::SkillsManager;
 use crate::state_db::StateDbHandle;
+use crate::tools::active_calls::ActiveToolCalls;
+use crate::tools::findings_delta::FindingBaselines;
 use crate::tools::network_approval::NetworkApprovalService;
+use crate::tools::output_summary::ToolOutputSummaries;
+use crate::tools::result_cache::ToolResultCache;
 use crate::tools::sandboxing::ApprovalStore;
 use crate::unified_exec::UnifiedExecProcessManager;
 use crate::zsh_exec_bridge::ZshExecBridge;
@@ -30,6 +38,7 @@ pub(crate) struct SessionServices {
     pub(crate) zsh_exec_bridge: ZshExecBridge,
     pub(crate) analytics_events_client: AnalyticsEventsClient,
     pub(crate) hooks: Hooks,
+    pub(crate) notifications: NotificationDispatcher,
     pub(crate) rollout: Mutex<Option<RolloutRecorder>>,
     pub(crate) user_shell: Arc<crate::shell::Shell>,
     pub(crate) shell_snapshot_tx: watch::Sender<Option<Arc<crate::shell_snapshot::ShellSnapshot>>>,
@@ -45,6 +54,17 @@ pub(crate) struct SessionServices {
     pub(crate) network_proxy: Option<StartedNetworkProxy>,
     pub(crate) network_approval: Arc<NetworkApprovalService>,
     pub(crate) state_db: Option<StateDbHandle>,
+    /// Set when the session edits a shadow copy of the repository.
+    pub(crate) shadow_workspace: Option<Arc<ShadowWorkspace>>,
+    /// Set in patch artifact mode, where file changes are only proposed.
+    pub(crate) patch_artifact: Option<Mutex<PatchArtifact>>,
     /// Session-scoped model client shared across turns.
     pub(crate) model_client: ModelClient,
+    /// Clients attached to the thread besides its primary client.
+    pub(crate) attached_clients: AttachedClients,
+    pub(crate) tool_output_summaries: Mutex<ToolOutputSummaries>,
+    pub(crate) tool_result_cache: Mutex<ToolResultCache>,
+    /// Latest findings of each `check_build` and `run_tests` command.
+    pub(crate) finding_baselines: Mutex<FindingBaselines>,
+    pub(crate) active_tool_calls: ActiveToolCalls,
 }
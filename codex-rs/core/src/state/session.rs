@@ -1,15 +1,28 @@
 //! Session-wide mutable state.
 
 use codex_protocol::models::ResponseItem;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
+use time::OffsetDateTime;
 
+use crate::attachments::AttachmentRegistry;
 use crate::codex::SessionConfiguration;
 use crate::context_manager::ContextManager;
+use crate::mcp::dependency_decisions::McpDependencyDecision;
+use crate::protocol::HistoryItemMetadata;
+use crate::protocol::Op;
+use crate::protocol::PlanOrCreditsChangedEvent;
 use crate::protocol::RateLimitSnapshot;
 use crate::protocol::TokenUsage;
 use crate::protocol::TokenUsageInfo;
+use crate::protocol::ToolSelectionEvent;
+use crate::protocol::TurnTiming;
+use crate::rate_limit_forecast::RateLimitSamples;
 use crate::tasks::RegularTask;
+use crate::tasks::ResponseCandidates;
+use crate::tools::circuit_breaker::ToolCircuitBreakers;
 use crate::truncate::TruncationPolicy;
 use codex_protocol::protocol::TurnContextItem;
 
@@ -18,9 +31,15 @@ pub(crate) struct SessionState {
     pub(crate) session_configuration: SessionConfiguration,
     pub(crate) history: ContextManager,
     pub(crate) latest_rate_limits: Option<RateLimitSnapshot>,
+    /// Every merged snapshot, for `Op::ForecastRateLimits`.
+    pub(crate) rate_limit_samples: RateLimitSamples,
     pub(crate) server_reasoning_included: bool,
     pub(crate) dependency_env: HashMap<String, String>,
-    pub(crate) mcp_dependency_prompted: HashSet<String>,
+    /// Answers to the MCP dependency install prompt given in this session,
+    /// by canonical dependency key.
+    pub(crate) mcp_dependency_decisions: HashMap<String, McpDependencyDecision>,
+    /// CLI tools the user declined to install in this session.
+    pub(crate) skipped_cli_dependencies: HashSet<String>,
     /// Model used by the latest regular user turn, used for model-switch handling
     /// on subsequent regular turns (including full-context reinjection after
     /// resume or `/compact`).
@@ -29,6 +48,22 @@ pub(crate) struct SessionState {
     pub(crate) startup_regular_task: Option<RegularTask>,
     pub(crate) active_mcp_tool_selection: Option<Vec<String>>,
     pub(crate) active_connector_selection: HashSet<String>,
+    /// Connectors selected when a resumed session was last saved, carried
+    /// into its first turn.
+    restored_connector_selection: HashSet<String>,
+    /// Consecutive-failure tracking per tool or MCP server.
+    pub(crate) tool_circuit_breakers: ToolCircuitBreakers,
+    /// Inputs attached via `Op::AttachInput`, readable with `read_attachment`.
+    pub(crate) attachments: AttachmentRegistry,
+    /// Tools and MCP servers turned off with `Op::SetEnabledTools`.
+    disabled_tools: BTreeSet<String>,
+    /// Alternatives to the last response sampled by `Op::Regenerate`.
+    pub(crate) response_candidates: Option<ResponseCandidates>,
+    /// Turns sent while offline, with their submission ids, to run once the
+    /// session is back online.
+    pub(crate) offline_turns: VecDeque<(String, Op)>,
+    /// Latency breakdowns of the most recent turns, oldest first.
+    pub(crate) turn_timings: VecDeque<TurnTiming>,
 }
 
 impl SessionState {
@@ -39,23 +74,38 @@ impl SessionState {
             session_configuration,
             history,
             latest_rate_limits: None,
+            rate_limit_samples: RateLimitSamples::default(),
             server_reasoning_included: false,
             dependency_env: HashMap::new(),
-            mcp_dependency_prompted: HashSet::new(),
+            mcp_dependency_decisions: HashMap::new(),
+            skipped_cli_dependencies: HashSet::new(),
             previous_model: None,
             startup_regular_task: None,
             active_mcp_tool_selection: None,
             active_connector_selection: HashSet::new(),
+            restored_connector_selection: HashSet::new(),
+            tool_circuit_breakers: ToolCircuitBreakers::default(),
+            attachments: AttachmentRegistry::default(),
+            disabled_tools: BTreeSet::new(),
+            response_candidates: None,
+            offline_turns: VecDeque::new(),
+            turn_timings: VecDeque::new(),
         }
     }
 
     // History helpers
-    pub(crate) fn record_items<I>(&mut self, items: I, policy: TruncationPolicy)
+    pub(crate) fn record_items<I>(
+        &mut self,
+        items: I,
+        policy: TruncationPolicy,
+        model: &str,
+    ) -> Vec<HistoryItemMetadata>
     where
         I: IntoIterator,
         I::Item: std::ops::Deref<Target = ResponseItem>,
     {
-        self.history.record_items(items, policy);
+        self.history
+            .record_items_for_model(items, policy, Some(model))
     }
 
     pub(crate) fn previous_model(&self) -> Option<String> {
@@ -104,11 +154,37 @@ impl SessionState {
         self.history.token_info()
     }
 
-    pub(crate) fn set_rate_limits(&mut self, snapshot: RateLimitSnapshot) {
-        self.latest_rate_limits = Some(merge_rate_limit_fields(
-            self.latest_rate_limits.as_ref(),
-            snapshot,
-        ));
+    /// Records `snapshot`. Returns the change when it moves the plan, or
+    /// whether there are credits at all, off what an earlier snapshot
+    /// reported. The balance alone drops with every request, so it is not a
+    /// change.
+    pub(crate) fn set_rate_limits(
+        &mut self,
+        snapshot: RateLimitSnapshot,
+    ) -> Option<PlanOrCreditsChangedEvent> {
+        let snapshot = merge_rate_limit_fields(self.latest_rate_limits.as_ref(), snapshot);
+        self.rate_limit_samples
+            .record(OffsetDateTime::now_utc().unix_timestamp(), snapshot.clone());
+        let change = self.latest_rate_limits.as_ref().and_then(|previous| {
+            let plan_changed =
+                previous.plan_type.is_some() && previous.plan_type != snapshot.plan_type;
+            let availability = |snapshot: &RateLimitSnapshot| {
+                snapshot
+                    .credits
+                    .as_ref()
+                    .map(|credits| (credits.has_credits, credits.unlimited))
+            };
+            let credits_changed =
+                previous.credits.is_some() && availability(previous) != availability(&snapshot);
+            (plan_changed || credits_changed).then(|| PlanOrCreditsChangedEvent {
+                previous_plan_type: previous.plan_type,
+                plan_type: snapshot.plan_type,
+                previous_credits: previous.credits.clone(),
+                credits: snapshot.credits.clone(),
+            })
+        });
+        self.latest_rate_limits = Some(snapshot);
+        change
     }
 
     pub(crate) fn token_info_and_rate_limits(
@@ -134,15 +210,15 @@ impl SessionState {
         self.server_reasoning_included
     }
 
-    pub(crate) fn record_mcp_dependency_prompted<I>(&mut self, names: I)
+    pub(crate) fn record_mcp_dependency_decisions<I>(&mut self, decisions: I)
     where
-        I: IntoIterator<Item = String>,
+        I: IntoIterator<Item = (String, McpDependencyDecision)>,
     {
-        self.mcp_dependency_prompted.extend(names);
+        self.mcp_dependency_decisions.extend(decisions);
     }
 
-    pub(crate) fn mcp_dependency_prompted(&self) -> HashSet<String> {
-        self.mcp_dependency_prompted.clone()
+    pub(crate) fn mcp_dependency_decisions(&self) -> HashMap<String, McpDependencyDecision> {
+        self.mcp_dependency_decisions.clone()
     }
 
     pub(crate) fn set_dependency_env(&mut self, values: HashMap<String, String>) {
@@ -163,6 +239,24 @@ impl SessionState {
         self.startup_regular_task.take()
     }
 
+    /// Re-enables `allow`, then disables `deny`, and returns everything now
+    /// disabled.
+    pub(crate) fn update_disabled_tools(
+        &mut self,
+        allow: Vec<String>,
+        deny: Vec<String>,
+    ) -> Vec<String> {
+        for name in &allow {
+            self.disabled_tools.remove(name);
+        }
+        self.disabled_tools.extend(deny);
+        self.disabled_tools.iter().cloned().collect()
+    }
+
+    pub(crate) fn disabled_tools(&self) -> BTreeSet<String> {
+        self.disabled_tools.clone()
+    }
+
     pub(crate) fn merge_mcp_tool_selection(&mut self, tool_names: Vec<String>) -> Vec<String> {
         if tool_names.is_empty() {
             return self.active_mcp_tool_selection.clone().unwrap_or_default();
@@ -228,6 +322,27 @@ impl SessionState {
     pub(crate) fn clear_connector_selection(&mut self) {
         self.active_connector_selection.clear();
     }
+
+    /// The current connector and MCP tool selection, as recorded in the rollout.
+    pub(crate) fn tool_selection(&self) -> ToolSelectionEvent {
+        let mut connectors: Vec<String> = self.active_connector_selection.iter().cloned().collect();
+        connectors.sort();
+        ToolSelectionEvent {
+            connectors,
+            mcp_tools: self.active_mcp_tool_selection.clone().unwrap_or_default(),
+        }
+    }
+
+    /// Restores a selection recorded in the rollout. The MCP tools are
+    /// selected right away; the connectors join the next turn's selection.
+    pub(crate) fn restore_tool_selection(&mut self, selection: ToolSelectionEvent) {
+        self.set_mcp_tool_selection(selection.mcp_tools);
+        self.restored_connector_selection = selection.connectors.into_iter().collect();
+    }
+
+    pub(crate) fn take_restored_connector_selection(&mut self) -> HashSet<String> {
+        std::mem::take(&mut self.restored_connector_selection)
+    }
 }
 
 // Sometimes new snapshots don't include credits or plan information.
@@ -256,6 +371,25 @@ mod tests {
     use crate::protocol::RateLimitWindow;
     use pretty_assertions::assert_eq;
 
+    #[tokio::test]
+    async fn update_disabled_tools_applies_deny_after_allow() {
+        let session_configuration = make_session_configuration_for_tests().await;
+        let mut state = SessionState::new(session_configuration);
+
+        let disabled = state.update_disabled_tools(
+            Vec::new(),
+            vec!["web_search".to_string(), "docs".to_string()],
+        );
+        assert_eq!(disabled, vec!["docs".to_string(), "web_search".to_string()]);
+
+        let disabled = state.update_disabled_tools(
+            vec!["web_search".to_string(), "docs".to_string()],
+            vec!["docs".to_string()],
+        );
+        assert_eq!(disabled, vec!["docs".to_string()]);
+        assert_eq!(state.disabled_tools(), BTreeSet::from(["docs".to_string()]));
+    }
+
     #[tokio::test]
     async fn merge_mcp_tool_selection_deduplicates_and_preserves_order() {
         let session_configuration = make_session_configuration_for_tests().await;
@@ -509,4 +643,55 @@ mod tests {
             })
         );
     }
+
+    #[tokio::test]
+    async fn set_rate_limits_reports_plan_and_credit_changes() {
+        let session_configuration = make_session_configuration_for_tests().await;
+        let mut state = SessionState::new(session_configuration);
+        let snapshot = |balance: &str, plan_type| RateLimitSnapshot {
+            limit_id: None,
+            limit_name: None,
+            primary: None,
+            secondary: None,
+            credits: Some(crate::protocol::CreditsSnapshot {
+                has_credits: balance != "0",
+                unlimited: false,
+                balance: Some(balance.to_string()),
+            }),
+            plan_type: Some(plan_type),
+        };
+        let credits = |balance: &str| {
+            Some(crate::protocol::CreditsSnapshot {
+                has_credits: balance != "0",
+                unlimited: false,
+                balance: Some(balance.to_string()),
+            })
+        };
+        let plus = codex_protocol::account::PlanType::Plus;
+        let free = codex_protocol::account::PlanType::Free;
+
+        assert_eq!(state.set_rate_limits(snapshot("50", plus)), None);
+        assert_eq!(state.set_rate_limits(snapshot("49", plus)), None);
+        assert_eq!(
+            state.set_rate_limits(snapshot("0", plus)),
+            Some(PlanOrCreditsChangedEvent {
+                previous_plan_type: Some(plus),
+                plan_type: Some(plus),
+                previous_credits: credits("49"),
+                credits: credits("0"),
+            })
+        );
+        assert_eq!(
+            state.set_rate_limits(RateLimitSnapshot {
+                credits: None,
+                ..snapshot("0", free)
+            }),
+            Some(PlanOrCreditsChangedEvent {
+                previous_plan_type: Some(plus),
+                plan_type: Some(free),
+                previous_credits: credits("0"),
+                credits: credits("0"),
+            })
+        );
+    }
 }
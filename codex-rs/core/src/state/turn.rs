@@ -16,6 +16,7 @@ use tokio::sync::oneshot;
 use crate::codex::TurnContext;
 use crate::protocol::ReviewDecision;
 use crate::tasks::SessionTask;
+use crate::tools::loop_detection::RepeatedFailureTracker;
 
 /// Metadata about the currently running turn.
 pub(crate) struct ActiveTurn {
@@ -73,6 +74,7 @@ pub(crate) struct TurnState {
     pending_user_input: HashMap<String, oneshot::Sender<RequestUserInputResponse>>,
     pending_dynamic_tools: HashMap<String, oneshot::Sender<DynamicToolResponse>>,
     pending_input: Vec<ResponseInputItem>,
+    pub(crate) repeated_failures: RepeatedFailureTracker,
 }
 
 impl TurnState {
@@ -1,6 +1,8 @@
 use crate::config::Config;
 use crate::features::Feature;
 use crate::path_utils::normalize_for_path_comparison;
+use crate::rollout::encryption;
+use crate::rollout::encryption::StateDbCipher;
 use crate::rollout::list::Cursor;
 use crate::rollout::list::ThreadSortKey;
 use crate::rollout::metadata;
@@ -55,6 +57,15 @@ pub(crate) async fn init_if_enabled(
             return None;
         }
     };
+    // Without the key the DB would get plaintext; leave it closed.
+    let key = match encryption::sealing_key(config).await {
+        Ok(key) => key,
+        Err(err) => {
+            warn!("not opening the state DB: {err}");
+            return None;
+        }
+    };
+    runtime.set_text_cipher(StateDbCipher::new(key));
     let backfill_state = match runtime.get_backfill_state().await {
         Ok(state) => state,
         Err(err) => {
@@ -92,6 +103,8 @@ pub async fn get_state_db(config: &Config, otel: Option<&OtelManager>) -> Option
     )
     .await
     .ok()?;
+    let key = encryption::sealing_key(config).await.ok()?;
+    runtime.set_text_cipher(StateDbCipher::new(key));
     require_backfill_complete(runtime, config.codex_home.as_path()).await
 }
 
@@ -110,6 +123,7 @@ pub async fn open_if_present(codex_home: &Path, default_provider: &str) -> Optio
     )
     .await
     .ok()?;
+    runtime.set_text_cipher(StateDbCipher::new(None));
     require_backfill_complete(runtime, codex_home).await
 }
 
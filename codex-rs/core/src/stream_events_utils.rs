@@ -5,8 +5,11 @@ use codex_protocol::config_types::ModeKind;
 use codex_protocol::items::TurnItem;
 use tokio_util::sync::CancellationToken;
 
+use crate::client_common::Prompt;
+use crate::client_common::ResponseEvent;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::compact::content_items_to_text;
 use crate::error::CodexErr;
 use crate::error::Result;
 use crate::function_tool::FunctionCallError;
@@ -19,6 +22,7 @@ use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use futures::Future;
+use futures::StreamExt;
 use tracing::debug;
 use tracing::instrument;
 
@@ -127,7 +131,9 @@ pub(crate) async fn handle_output_item_done(
             output.needs_follow_up = true;
         }
         // The tool request should be answered directly (or was denied); push that response into the transcript.
-        Err(FunctionCallError::RespondToModel(message)) => {
+        Err(
+            FunctionCallError::RespondToModel(message) | FunctionCallError::Failed { message, .. },
+        ) => {
             let response = ResponseInputItem::FunctionCallOutput {
                 call_id: String::new(),
                 output: FunctionCallOutputPayload {
@@ -191,6 +197,78 @@ pub(crate) async fn handle_non_tool_response_item(
     }
 }
 
+/// Samples `prompt` outside of any turn, so nothing is recorded, and returns
+/// the text of the model's reply.
+pub(crate) async fn sample_text(
+    sess: &Session,
+    turn_context: &TurnContext,
+    prompt: &Prompt,
+) -> Result<String> {
+    let turn_metadata_header = turn_context.turn_metadata_state.current_header_value();
+    let mut client_session = sess.services.model_client.new_session();
+    let mut stream = client_session
+        .stream(
+            prompt,
+            &turn_context.model_info,
+            &turn_context.otel_manager,
+            turn_context.reasoning_effort,
+            turn_context.reasoning_summary,
+            turn_metadata_header.as_deref(),
+        )
+        .await?;
+    let mut text = String::new();
+    while let Some(event) = stream.next().await.transpose()? {
+        match event {
+            ResponseEvent::OutputTextDelta(delta) => text.push_str(&delta),
+            ResponseEvent::OutputItemDone(item) => {
+                if text.is_empty()
+                    && let ResponseItem::Message { content, .. } = item
+                    && let Some(item_text) = content_items_to_text(&content)
+                {
+                    text.push_str(&item_text);
+                }
+            }
+            ResponseEvent::Completed { .. } => break,
+            _ => {}
+        }
+    }
+    Ok(text)
+}
+
+/// Samples `prompt` outside of any turn, so only its token usage is
+/// recorded, and returns the items of the model's reply.
+pub(crate) async fn sample_items(
+    sess: &Session,
+    turn_context: &TurnContext,
+    prompt: &Prompt,
+) -> Result<Vec<ResponseItem>> {
+    let turn_metadata_header = turn_context.turn_metadata_state.current_header_value();
+    let mut client_session = sess.services.model_client.new_session();
+    let mut stream = client_session
+        .stream(
+            prompt,
+            &turn_context.model_info,
+            &turn_context.otel_manager,
+            turn_context.reasoning_effort,
+            turn_context.reasoning_summary,
+            turn_metadata_header.as_deref(),
+        )
+        .await?;
+    let mut items = Vec::new();
+    while let Some(event) = stream.next().await.transpose()? {
+        match event {
+            ResponseEvent::OutputItemDone(item) => items.push(item),
+            ResponseEvent::Completed { token_usage, .. } => {
+                sess.update_token_usage_info(turn_context, token_usage.as_ref(), None)
+                    .await;
+                break;
+            }
+            _ => {}
+        }
+    }
+    Ok(items)
+}
+
 pub(crate) fn last_assistant_message_from_item(
     item: &ResponseItem,
     plan_mode: bool,
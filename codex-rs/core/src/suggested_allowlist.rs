@@ -0,0 +1,205 @@
+//! Suggested allowlists learned from approvals (`learn_approved_commands`).
+//!
+//! Every command the user approves once or for the session is counted in a
+//! per-project file under `<codex_home>/suggested_allowlists`, shared by all
+//! sessions in the project. The user reviews the list with
+//! `Op::ReviewSuggestedAllowlist` and promotes the commands worth keeping to
+//! execpolicy allow rules, so approval rules grow out of real usage rather
+//! than guesses.
+//!
+//! Each update locks the file for its read-modify-write, so concurrent
+//! sessions do not lose each other's approvals.
+
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+use std::path::Path;
+use std::path::PathBuf;
+
+use codex_protocol::approvals::ExecPolicyAmendment;
+use codex_protocol::protocol::SuggestedCommand;
+use serde::Deserialize;
+use serde::Serialize;
+use sha2::Digest;
+use sha2::Sha256;
+use time::OffsetDateTime;
+use time::format_description::well_known::Rfc3339;
+
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::git_info::get_git_repo_root;
+
+const SUGGESTED_ALLOWLISTS_SUBDIR: &str = "suggested_allowlists";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SuggestedAllowlistFile {
+    commands: Vec<SuggestedCommand>,
+}
+
+/// The project a session in `cwd` learns commands for.
+pub(crate) fn project_root(cwd: &Path) -> PathBuf {
+    get_git_repo_root(cwd).unwrap_or_else(|| cwd.to_path_buf())
+}
+
+/// The commands an approval of `command` vouches for: the rule the approval
+/// prompt proposed, else each command of a plain shell script, else the
+/// command itself.
+pub(crate) fn approved_commands(
+    command: &[String],
+    proposed_execpolicy_amendment: Option<&ExecPolicyAmendment>,
+) -> Vec<Vec<String>> {
+    if let Some(amendment) = proposed_execpolicy_amendment {
+        return vec![amendment.command.clone()];
+    }
+    parse_shell_lc_plain_commands(command).unwrap_or_else(|| vec![command.to_vec()])
+}
+
+/// Counts one approval of each of `commands`. Blocks on the file system.
+pub(crate) fn record_approvals(
+    codex_home: &Path,
+    project_root: &Path,
+    commands: Vec<Vec<String>>,
+) -> io::Result<()> {
+    let now = OffsetDateTime::now_utc()
+        .format(&Rfc3339)
+        .map_err(io::Error::other)?;
+    update(codex_home, project_root, |suggestions| {
+        for command in commands {
+            match suggestions
+                .iter_mut()
+                .find(|entry| entry.command == command)
+            {
+                Some(entry) => {
+                    entry.approvals = entry.approvals.saturating_add(1);
+                    entry.last_approved_at = now.clone();
+                }
+                None => suggestions.push(SuggestedCommand {
+                    command,
+                    approvals: 1,
+                    last_approved_at: now.clone(),
+                }),
+            }
+        }
+    })?;
+    Ok(())
+}
+
+/// Drops `commands` from the suggestions and returns the rest, most approved
+/// first. Blocks on the file system.
+pub(crate) fn remove(
+    codex_home: &Path,
+    project_root: &Path,
+    commands: &[Vec<String>],
+) -> io::Result<Vec<SuggestedCommand>> {
+    update(codex_home, project_root, |suggestions| {
+        suggestions.retain(|entry| !commands.contains(&entry.command));
+    })
+}
+
+fn update(
+    codex_home: &Path,
+    project_root: &Path,
+    apply: impl FnOnce(&mut Vec<SuggestedCommand>),
+) -> io::Result<Vec<SuggestedCommand>> {
+    let path = project_file_path(codex_home, SUGGESTED_ALLOWLISTS_SUBDIR, project_root);
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let mut file = File::options()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(&path)?;
+    file.lock()?;
+    let mut contents = String::new();
+    file.read_to_string(&mut contents)?;
+    let mut allowlist: SuggestedAllowlistFile = if contents.trim().is_empty() {
+        SuggestedAllowlistFile::default()
+    } else {
+        serde_json::from_str(&contents)?
+    };
+    apply(&mut allowlist.commands);
+    allowlist.commands.sort_by(|a, b| {
+        b.approvals
+            .cmp(&a.approvals)
+            .then_with(|| a.command.cmp(&b.command))
+    });
+    file.set_len(0)?;
+    file.seek(SeekFrom::Start(0))?;
+    file.write_all(&serde_json::to_vec_pretty(&allowlist)?)?;
+    Ok(allowlist.commands)
+}
+
+/// Location of the state kept in `subdir` for `project_root`, e.g.
+/// `<codex_home>/suggested_allowlists/codex-1a2b3c4d5e6f7a8b.json`.
+pub(crate) fn project_file_path(codex_home: &Path, subdir: &str, project_root: &Path) -> PathBuf {
+    let digest = Sha256::digest(project_root.to_string_lossy().as_bytes());
+    let hex = format!("{digest:x}");
+    let short_hash = hex.get(..16).unwrap_or(&hex);
+    let name: String = project_root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect();
+    codex_home
+        .join(subdir)
+        .join(format!("{name}-{short_hash}.json"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn command(words: &[&str]) -> Vec<String> {
+        words.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn approvals_are_counted_per_project_and_can_be_removed() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let project = Path::new("/work/project");
+        let other_project = Path::new("/work/other");
+
+        record_approvals(
+            codex_home.path(),
+            project,
+            approved_commands(&command(&["bash", "-lc", "cargo test && git status"]), None),
+        )
+        .expect("record");
+        record_approvals(
+            codex_home.path(),
+            project,
+            approved_commands(
+                &command(&["cargo", "test", "-p", "codex-core"]),
+                Some(&ExecPolicyAmendment::new(command(&["cargo", "test"]))),
+            ),
+        )
+        .expect("record");
+        record_approvals(codex_home.path(), other_project, vec![command(&["make"])])
+            .expect("record");
+
+        let suggestions =
+            remove(codex_home.path(), project, &[command(&["git", "status"])]).expect("remove");
+        assert_eq!(
+            suggestions
+                .into_iter()
+                .map(|entry| (entry.command, entry.approvals))
+                .collect::<Vec<_>>(),
+            vec![(command(&["cargo", "test"]), 2)]
+        );
+        let other = remove(codex_home.path(), other_project, &[]).expect("list");
+        assert_eq!(
+            other
+                .into_iter()
+                .map(|entry| entry.command)
+                .collect::<Vec<_>>(),
+            vec![command(&["make"])]
+        );
+    }
+}
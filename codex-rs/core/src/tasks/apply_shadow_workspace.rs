@@ -0,0 +1,126 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::codex::TurnContext;
+use crate::protocol::EventMsg;
+use crate::shadow_workspace::ShadowWorkspace;
+use crate::state::TaskKind;
+
+/// Reviews everything changed in the shadow workspace as one patch and
+/// applies it to the real repository once approved.
+pub(crate) struct ApplyShadowWorkspaceTask {
+    shadow: Arc<ShadowWorkspace>,
+}
+
+impl ApplyShadowWorkspaceTask {
+    pub(crate) fn new(shadow: Arc<ShadowWorkspace>) -> Self {
+        Self { shadow }
+    }
+}
+
+#[async_trait]
+impl SessionTask for ApplyShadowWorkspaceTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let warn = |message: String| {
+            sess.send_event(ctx.as_ref(), EventMsg::Warning(WarningEvent { message }))
+        };
+
+        let diff = match self.shadow.diff().await {
+            Ok(diff) => diff,
+            Err(err) => {
+                warn(format!("Failed to diff the shadow workspace: {err:#}")).await;
+                return None;
+            }
+        };
+        if diff.is_empty() {
+            sess.notify_background_event(
+                ctx.as_ref(),
+                "The shadow workspace has no changes to apply.",
+            )
+            .await;
+            return None;
+        }
+
+        // Nobody answers approval requests under `never`; apply directly.
+        let decision = if matches!(ctx.approval_policy.value(), AskForApproval::Never) {
+            ReviewDecision::Approved
+        } else {
+            let changes = match self.shadow.changes(&diff).await {
+                Ok(changes) => changes,
+                Err(err) => {
+                    warn(format!(
+                        "Failed to list the shadow workspace changes: {err:#}"
+                    ))
+                    .await;
+                    return None;
+                }
+            };
+            let rx_approve = sess
+                .request_patch_approval(
+                    ctx.as_ref(),
+                    ctx.sub_id.clone(),
+                    changes,
+                    Some(format!(
+                        "Apply the shadow workspace changes to {}",
+                        self.shadow.real_root().display()
+                    )),
+                    None,
+                )
+                .await;
+            tokio::select! {
+                decision = rx_approve => decision.unwrap_or_default(),
+                _ = cancellation_token.cancelled() => return None,
+            }
+        };
+
+        match decision {
+            ReviewDecision::Approved
+            | ReviewDecision::ApprovedForSession
+            | ReviewDecision::ApprovedExecpolicyAmendment { .. } => {
+                match self.shadow.apply(diff).await {
+                    Ok(applied_paths) => {
+                        sess.notify_background_event(
+                            ctx.as_ref(),
+                            format!(
+                                "Applied shadow workspace changes to {} file(s) in {}.",
+                                applied_paths.len(),
+                                self.shadow.real_root().display()
+                            ),
+                        )
+                        .await;
+                    }
+                    Err(err) => {
+                        warn(format!("Failed to apply shadow workspace changes: {err:#}")).await;
+                    }
+                }
+            }
+            ReviewDecision::Denied | ReviewDecision::Abort => {
+                sess.notify_background_event(
+                    ctx.as_ref(),
+                    "Shadow workspace changes were not applied.",
+                )
+                .await;
+            }
+        }
+        None
+    }
+}
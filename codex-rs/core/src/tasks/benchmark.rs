@@ -0,0 +1,463 @@
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use regex_lite::Regex;
+use tokio::process::Command;
+use tokio::sync::Mutex;
+use tokio::time::timeout;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+use tracing::trace_span;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::codex::TurnContext;
+use crate::codex::run_turn;
+use crate::config::types::BenchmarkCommand;
+use crate::config::types::BenchmarkRegressionAction;
+use crate::config::types::BenchmarksConfig;
+use crate::protocol::EventMsg;
+use crate::shadow_workspace::ShadowWorkspace;
+use crate::state::TaskKind;
+
+const DEFAULT_RUNS: u32 = 5;
+const DEFAULT_MAX_REGRESSION_PERCENT: f64 = 5.0;
+/// Follow-up turns a blocking regression gets before it is reported.
+const MAX_FIX_ATTEMPTS: usize = 2;
+const RUN_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Welch's t statistic a slowdown must exceed to count as more than noise;
+/// about 95% one-sided confidence at the default five runs a side.
+const MIN_T_STATISTIC: f64 = 2.0;
+
+/// Runs a user turn guarded by the configured benchmarks. The workspace is
+/// snapshotted into a worktree before the turn; afterwards each benchmark
+/// runs alternately in the snapshot and in the workspace, and the two sets
+/// of samples are compared with Welch's t-test. Regressions are reported as
+/// a warning or, when blocking, sent back to the agent to fix as developer
+/// instructions.
+pub(crate) struct BenchmarkTask {
+    config: BenchmarksConfig,
+    /// The snapshot, kept here so an aborted task still removes it.
+    baseline: Mutex<Option<ShadowWorkspace>>,
+}
+
+impl BenchmarkTask {
+    pub(crate) fn new(config: BenchmarksConfig) -> Self {
+        Self {
+            config,
+            baseline: Mutex::new(None),
+        }
+    }
+
+    /// Removes the snapshot. It is only forgotten once removed, so an abort
+    /// during the removal retries it.
+    async fn cleanup_baseline(&self) {
+        let mut baseline = self.baseline.lock().await;
+        if let Some(snapshot) = baseline.as_ref() {
+            snapshot.cleanup().await;
+        }
+        *baseline = None;
+    }
+}
+
+#[async_trait]
+impl SessionTask for BenchmarkTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let warn = |message: String| {
+            sess.send_event(ctx.as_ref(), EventMsg::Warning(WarningEvent { message }))
+        };
+
+        let baseline_cwd = match ShadowWorkspace::create(&ctx.config.codex_home, &ctx.cwd).await {
+            Ok(baseline) => {
+                let baseline_cwd = baseline.cwd().to_path_buf();
+                *self.baseline.lock().await = Some(baseline);
+                baseline_cwd
+            }
+            Err(err) => {
+                warn(format!(
+                    "Failed to snapshot the workspace for benchmarks: {err:#}"
+                ))
+                .await;
+                return None;
+            }
+        };
+        let max_regression_percent = self
+            .config
+            .max_regression_percent
+            .unwrap_or(DEFAULT_MAX_REGRESSION_PERCENT);
+
+        let mut input = input;
+        let mut fix_attempts = 0;
+        let last_agent_message = loop {
+            let last_agent_message = run_turn(
+                Arc::clone(&sess),
+                Arc::clone(&ctx),
+                input,
+                None,
+                cancellation_token.clone(),
+            )
+            .instrument(trace_span!("benchmarked_turn"))
+            .await;
+            if cancellation_token.is_cancelled() {
+                break last_agent_message;
+            }
+
+            sess.notify_background_event(
+                ctx.as_ref(),
+                "Running benchmarks before and after the changes.",
+            )
+            .await;
+            let comparisons = match compare_benchmarks(
+                &self.config,
+                &baseline_cwd,
+                &ctx.cwd,
+                &cancellation_token,
+            )
+            .await
+            {
+                Ok(comparisons) => comparisons,
+                Err(err) => {
+                    warn(format!("Failed to run benchmarks: {err:#}")).await;
+                    break last_agent_message;
+                }
+            };
+            let report = comparisons
+                .iter()
+                .map(|comparison| comparison.describe(max_regression_percent))
+                .collect::<Vec<_>>()
+                .join("\n");
+            if !comparisons
+                .iter()
+                .any(|comparison| comparison.is_regression(max_regression_percent))
+            {
+                sess.notify_background_event(
+                    ctx.as_ref(),
+                    format!("No benchmark regressions:\n{report}"),
+                )
+                .await;
+                break last_agent_message;
+            }
+
+            let blocking =
+                self.config.on_regression.unwrap_or_default() == BenchmarkRegressionAction::Block;
+            if !blocking || fix_attempts == MAX_FIX_ATTEMPTS {
+                warn(format!("Benchmarks regressed:\n{report}")).await;
+                break last_agent_message;
+            }
+            fix_attempts += 1;
+            // The follow-up turn starts from this pending instruction alone,
+            // so it is not shown or recorded as something the user said.
+            let fix_request = ResponseInputItem::Message {
+                role: "developer".to_string(),
+                content: vec![ContentItem::InputText {
+                    text: format!(
+                        "The changes made these benchmarks worse than before the turn \
+                         (mean ± standard deviation, before → after):\n{report}\n\n\
+                         Find and fix the cause of each REGRESSION while keeping the requested \
+                         change. The benchmarks run again when you finish."
+                    ),
+                }],
+            };
+            if sess.inject_response_items(vec![fix_request]).await.is_err() {
+                break last_agent_message;
+            }
+            input = Vec::new();
+        };
+
+        self.cleanup_baseline().await;
+        last_agent_message
+    }
+
+    async fn abort(&self, _session: Arc<SessionTaskContext>, _ctx: Arc<TurnContext>) {
+        self.cleanup_baseline().await;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Stats {
+    mean: f64,
+    variance: f64,
+    samples: usize,
+}
+
+impl Stats {
+    fn of(samples: &[f64]) -> Self {
+        let n = samples.len() as f64;
+        let mean = samples.iter().sum::<f64>() / n;
+        let variance = if samples.len() > 1 {
+            samples
+                .iter()
+                .map(|sample| (sample - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1.0)
+        } else {
+            0.0
+        };
+        Self {
+            mean,
+            variance,
+            samples: samples.len(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Comparison {
+    name: String,
+    before: Stats,
+    after: Stats,
+    higher_is_better: bool,
+}
+
+impl Comparison {
+    /// How much worse the mean got, in percent of the baseline mean.
+    fn regression_percent(&self) -> f64 {
+        if self.before.mean == 0.0 {
+            return 0.0;
+        }
+        let change = (self.after.mean - self.before.mean) / self.before.mean.abs() * 100.0;
+        if self.higher_is_better {
+            -change
+        } else {
+            change
+        }
+    }
+
+    /// Welch's t statistic of the change, positive when it is for the worse.
+    fn t_statistic(&self) -> f64 {
+        let difference = if self.higher_is_better {
+            self.before.mean - self.after.mean
+        } else {
+            self.after.mean - self.before.mean
+        };
+        let standard_error = (self.before.variance / self.before.samples as f64
+            + self.after.variance / self.after.samples as f64)
+            .sqrt();
+        if standard_error == 0.0 {
+            return if difference == 0.0 {
+                0.0
+            } else {
+                f64::INFINITY.copysign(difference)
+            };
+        }
+        difference / standard_error
+    }
+
+    fn is_regression(&self, max_regression_percent: f64) -> bool {
+        self.regression_percent() > max_regression_percent && self.t_statistic() > MIN_T_STATISTIC
+    }
+
+    fn describe(&self, max_regression_percent: f64) -> String {
+        let verdict = if self.is_regression(max_regression_percent) {
+            " REGRESSION"
+        } else {
+            ""
+        };
+        format!(
+            "{}: {:.2} ± {:.2} → {:.2} ± {:.2} ({:+.1}% worse, t = {:.1}){verdict}",
+            self.name,
+            self.before.mean,
+            self.before.variance.sqrt(),
+            self.after.mean,
+            self.after.variance.sqrt(),
+            self.regression_percent(),
+            self.t_statistic(),
+        )
+    }
+}
+
+/// Runs every benchmark in `baseline_dir` and `dir` in turn, one warm-up run
+/// each and then alternating timed runs, so drift in the machine's load hits
+/// both sides alike.
+async fn compare_benchmarks(
+    config: &BenchmarksConfig,
+    baseline_dir: &Path,
+    dir: &Path,
+    cancellation_token: &CancellationToken,
+) -> anyhow::Result<Vec<Comparison>> {
+    let runs = config.runs.unwrap_or(DEFAULT_RUNS).max(1);
+    let mut comparisons = Vec::new();
+    for benchmark in &config.commands {
+        let metric_pattern = benchmark
+            .metric_pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .with_context(|| format!("invalid metric_pattern of benchmark `{}`", benchmark.name))?;
+        let mut before = Vec::new();
+        let mut after = Vec::new();
+        for run in 0..=runs {
+            for (side, samples) in [(baseline_dir, &mut before), (dir, &mut after)] {
+                let sample = tokio::select! {
+                    sample = run_benchmark(benchmark, metric_pattern.as_ref(), side) => sample?,
+                    _ = cancellation_token.cancelled() => anyhow::bail!("cancelled"),
+                };
+                if run > 0 {
+                    samples.push(sample);
+                }
+            }
+        }
+        comparisons.push(Comparison {
+            name: benchmark.name.clone(),
+            before: Stats::of(&before),
+            after: Stats::of(&after),
+            higher_is_better: benchmark.higher_is_better.unwrap_or(false),
+        });
+    }
+    Ok(comparisons)
+}
+
+/// Runs `benchmark` once in `dir` and returns its metric.
+async fn run_benchmark(
+    benchmark: &BenchmarkCommand,
+    metric_pattern: Option<&Regex>,
+    dir: &Path,
+) -> anyhow::Result<f64> {
+    let Some((program, args)) = benchmark.command.split_first() else {
+        anyhow::bail!("benchmark `{}` has an empty command", benchmark.name);
+    };
+    let started = Instant::now();
+    let output = timeout(
+        RUN_TIMEOUT,
+        Command::new(program)
+            .args(args)
+            .current_dir(dir)
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .with_context(|| format!("benchmark `{}` timed out", benchmark.name))?
+    .with_context(|| format!("failed to run benchmark `{}`", benchmark.name))?;
+    let elapsed = started.elapsed();
+    anyhow::ensure!(
+        output.status.success(),
+        "benchmark `{}` failed in {} with {}: {}",
+        benchmark.name,
+        dir.display(),
+        output.status,
+        String::from_utf8_lossy(&output.stderr).trim()
+    );
+
+    let Some(metric_pattern) = metric_pattern else {
+        return Ok(elapsed.as_secs_f64() * 1000.0);
+    };
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let metric = metric_pattern
+        .captures(&stdout)
+        .and_then(|captures| captures.get(1))
+        .with_context(|| {
+            format!(
+                "metric_pattern of benchmark `{}` matched nothing in its output",
+                benchmark.name
+            )
+        })?;
+    metric.as_str().trim().parse().with_context(|| {
+        format!(
+            "benchmark `{}` reported `{}`, which is not a number",
+            benchmark.name,
+            metric.as_str()
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn comparison(before: &[f64], after: &[f64], higher_is_better: bool) -> Comparison {
+        Comparison {
+            name: "parse".to_string(),
+            before: Stats::of(before),
+            after: Stats::of(after),
+            higher_is_better,
+        }
+    }
+
+    #[test]
+    fn only_significant_slowdowns_past_the_threshold_regress() {
+        let consistent = comparison(
+            &[100.0, 101.0, 99.0, 100.0, 100.0],
+            &[110.0, 111.0, 109.0, 110.0, 110.0],
+            false,
+        );
+        let noisy = comparison(
+            &[80.0, 120.0, 100.0, 90.0, 110.0],
+            &[130.0, 85.0, 125.0, 95.0, 115.0],
+            false,
+        );
+        let small = comparison(
+            &[100.0, 101.0, 99.0, 100.0, 100.0],
+            &[103.0, 104.0, 102.0, 103.0, 103.0],
+            false,
+        );
+        let faster_throughput = comparison(
+            &[100.0, 101.0, 99.0, 100.0, 100.0],
+            &[110.0, 111.0, 109.0, 110.0, 110.0],
+            true,
+        );
+        assert_eq!(
+            [&consistent, &noisy, &small, &faster_throughput]
+                .map(|comparison| comparison.is_regression(DEFAULT_MAX_REGRESSION_PERCENT)),
+            [true, false, false, false]
+        );
+        assert_eq!(
+            consistent.describe(DEFAULT_MAX_REGRESSION_PERCENT),
+            "parse: 100.00 ± 0.71 → 110.00 ± 0.71 (+10.0% worse, t = 22.4) REGRESSION"
+        );
+    }
+
+    #[tokio::test]
+    async fn compares_the_metric_of_each_side() {
+        let baseline = tempfile::tempdir().expect("tempdir");
+        let workspace = tempfile::tempdir().expect("tempdir");
+        std::fs::write(baseline.path().join("metric.txt"), "time: 100 ms\n").expect("write");
+        std::fs::write(workspace.path().join("metric.txt"), "time: 120 ms\n").expect("write");
+        let config = BenchmarksConfig {
+            commands: vec![BenchmarkCommand {
+                name: "parse".to_string(),
+                command: vec!["cat".to_string(), "metric.txt".to_string()],
+                metric_pattern: Some("time: ([0-9.]+) ms".to_string()),
+                higher_is_better: None,
+            }],
+            runs: Some(2),
+            max_regression_percent: None,
+            on_regression: None,
+        };
+
+        let comparisons = compare_benchmarks(
+            &config,
+            baseline.path(),
+            workspace.path(),
+            &CancellationToken::new(),
+        )
+        .await
+        .expect("compare benchmarks");
+        assert_eq!(
+            comparisons,
+            vec![comparison(&[100.0, 100.0], &[120.0, 120.0], false)]
+        );
+        assert!(comparisons[0].is_regression(DEFAULT_MAX_REGRESSION_PERCENT));
+    }
+}
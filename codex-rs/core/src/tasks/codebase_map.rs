@@ -0,0 +1,107 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::codex::TurnContext;
+use crate::codex_delegate::run_codex_thread_one_shot;
+use crate::config::Constrained;
+use crate::memories::codebase_map::build_codebase_map_prompt;
+use crate::memories::codebase_map::codebase_map_root;
+use crate::memories::codebase_map::write_codebase_map;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+
+/// Explores the workspace in a separate conversation, so the exploration
+/// stays out of this session's history, and stores the resulting codebase
+/// map in memory so later sessions in the same repository start with it.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct CodebaseMapTask;
+
+#[async_trait]
+impl SessionTask for CodebaseMapTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let repo_root = codebase_map_root(&ctx.cwd);
+        let input = vec![UserInput::Text {
+            text: build_codebase_map_prompt(&repo_root),
+            text_elements: Vec::new(),
+        }];
+        let mut sub_agent_config = ctx.config.as_ref().clone();
+        sub_agent_config.permissions.approval_policy =
+            Constrained::allow_only(AskForApproval::Never);
+        let io = match run_codex_thread_one_shot(
+            sub_agent_config,
+            session.auth_manager(),
+            session.models_manager(),
+            input,
+            Arc::clone(&sess),
+            Arc::clone(&ctx),
+            cancellation_token,
+            None,
+        )
+        .await
+        {
+            Ok(io) => io,
+            Err(err) => {
+                sess.send_event(
+                    ctx.as_ref(),
+                    EventMsg::Warning(WarningEvent {
+                        message: format!("Failed to start the codebase map turn: {err}"),
+                    }),
+                )
+                .await;
+                return None;
+            }
+        };
+
+        // Show the exploration as it happens; only the map itself is kept.
+        let mut codebase_map = None;
+        while let Ok(event) = io.rx_event.recv().await {
+            match event.msg {
+                EventMsg::TurnComplete(turn_complete) => {
+                    codebase_map = turn_complete.last_agent_message;
+                    break;
+                }
+                EventMsg::TurnAborted(_) => break,
+                msg => sess.send_event(ctx.as_ref(), msg).await,
+            }
+        }
+        let codebase_map = codebase_map?;
+
+        match write_codebase_map(&ctx.config.codex_home, &repo_root, &codebase_map).await {
+            Ok(path) => {
+                sess.notify_background_event(
+                    ctx.as_ref(),
+                    format!("Saved codebase map to {}", path.display()),
+                )
+                .await;
+            }
+            Err(err) => {
+                sess.send_event(
+                    ctx.as_ref(),
+                    EventMsg::Warning(WarningEvent {
+                        message: format!("Failed to save codebase map: {err}"),
+                    }),
+                )
+                .await;
+            }
+        }
+        Some(codebase_map)
+    }
+}
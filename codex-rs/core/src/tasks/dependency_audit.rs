@@ -0,0 +1,683 @@
+use std::cmp::Ordering;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use codex_protocol::protocol::DependencyAdvisoryFinding;
+use codex_protocol::protocol::DependencyAuditEvent;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use futures::StreamExt;
+use futures::stream;
+use serde_json::Value;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+use tracing::Instrument;
+use tracing::trace_span;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::codex::TurnContext;
+use crate::codex::run_turn;
+use crate::default_client::build_reqwest_client;
+use crate::default_client::read_json_limited;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+
+const OSV_QUERY_BATCH_URL: &str = "https://api.osv.dev/v1/querybatch";
+const OSV_VULN_URL: &str = "https://api.osv.dev/v1/vulns/";
+/// Queries per `querybatch` request, OSV's own limit.
+const QUERY_BATCH_SIZE: usize = 1000;
+const MAX_ADVISORIES: usize = 200;
+/// OSV answers larger than this are refused.
+pub(super) const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+const CONCURRENT_REQUESTS: usize = 8;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const MAX_LOCKFILE_DEPTH: usize = 4;
+pub(crate) const SKIPPED_DIRS: &[&str] =
+    &["node_modules", "target", "vendor", "venv", "dist", "build"];
+
+/// Reads the workspace's lockfiles, looks up every pinned package version in
+/// OSV (which includes RustSec), and reports the advisories affecting them
+/// with the lowest fixed version to bump to. With `upgrade`, a turn then
+/// makes those bumps.
+pub(crate) struct DependencyAuditTask {
+    upgrade: bool,
+}
+
+impl DependencyAuditTask {
+    pub(crate) fn new(upgrade: bool) -> Self {
+        Self { upgrade }
+    }
+}
+
+/// A package version pinned by a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(crate) struct PinnedPackage {
+    pub(crate) ecosystem: &'static str,
+    pub(crate) name: String,
+    pub(crate) version: String,
+}
+
+#[async_trait]
+impl SessionTask for DependencyAuditTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let audit = tokio::select! {
+            audit = audit(&ctx.cwd) => audit,
+            _ = cancellation_token.cancelled() => return None,
+        };
+        let audit = match audit {
+            Ok(audit) => audit,
+            Err(err) => {
+                sess.send_event(
+                    ctx.as_ref(),
+                    EventMsg::Warning(WarningEvent {
+                        message: format!("Failed to audit dependencies: {err:#}"),
+                    }),
+                )
+                .await;
+                return None;
+            }
+        };
+        let findings = audit.findings.clone();
+        sess.send_event(ctx.as_ref(), EventMsg::DependencyAudit(audit))
+            .await;
+        if !self.upgrade || findings.is_empty() {
+            return None;
+        }
+
+        let input = vec![UserInput::Text {
+            text: upgrade_prompt(&findings),
+            text_elements: Vec::new(),
+        }];
+        run_turn(sess, ctx, input, None, cancellation_token)
+            .instrument(trace_span!("dependency_upgrade_turn"))
+            .await
+    }
+}
+
+async fn audit(cwd: &Path) -> anyhow::Result<DependencyAuditEvent> {
+    let root = cwd.to_path_buf();
+    let lockfiles = tokio::task::spawn_blocking(move || read_lockfiles(&root))
+        .await
+        .context("lockfile reader panicked")?;
+
+    let packages = lockfiles
+        .iter()
+        .flat_map(|(_, packages)| packages.iter().cloned())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect::<Vec<_>>();
+    let client = build_reqwest_client();
+    let mut errors = Vec::new();
+    let mut advisory_ids = HashMap::<PinnedPackage, Vec<String>>::new();
+    // OSV pages the advisories of packages with many; those are queried
+    // again with the page token until they are exhausted.
+    let mut pending = packages
+        .iter()
+        .map(|package| (package, None::<String>))
+        .collect::<Vec<_>>();
+    while !pending.is_empty() {
+        let mut next_pages = Vec::new();
+        for batch in pending.chunks(QUERY_BATCH_SIZE) {
+            let queries = batch
+                .iter()
+                .map(|(package, page_token)| {
+                    let mut query = json!({
+                        "package": { "name": package.name, "ecosystem": package.ecosystem },
+                        "version": package.version,
+                    });
+                    if let Some(page_token) = page_token {
+                        query["page_token"] = json!(page_token);
+                    }
+                    query
+                })
+                .collect::<Vec<_>>();
+            let response = client
+                .post(OSV_QUERY_BATCH_URL)
+                .timeout(REQUEST_TIMEOUT)
+                .json(&json!({ "queries": queries }))
+                .send()
+                .await
+                .and_then(reqwest::Response::error_for_status)
+                .context("failed to query OSV")?;
+            let answer: Value = read_json_limited(response, MAX_RESPONSE_BYTES)
+                .await
+                .context("failed to read the OSV answer")?;
+            for ((package, _), result) in batch
+                .iter()
+                .zip(answer["results"].as_array().into_iter().flatten())
+            {
+                advisory_ids.entry((*package).clone()).or_default().extend(
+                    result["vulns"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|vuln| vuln["id"].as_str().map(str::to_string)),
+                );
+                if let Some(page_token) = result["next_page_token"].as_str() {
+                    next_pages.push((*package, Some(page_token.to_string())));
+                }
+            }
+        }
+        pending = next_pages;
+    }
+    advisory_ids.retain(|_, ids| !ids.is_empty());
+
+    let unique_ids = advisory_ids
+        .values()
+        .flatten()
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    if unique_ids.len() > MAX_ADVISORIES {
+        errors.push(format!(
+            "{} advisories were not looked up; only the first {MAX_ADVISORIES} are",
+            unique_ids.len() - MAX_ADVISORIES
+        ));
+    }
+    let fetched = stream::iter(unique_ids.into_iter().take(MAX_ADVISORIES))
+        .map(|id| {
+            let client = client.clone();
+            async move {
+                let advisory = async {
+                    let response = client
+                        .get(format!("{OSV_VULN_URL}{id}"))
+                        .timeout(REQUEST_TIMEOUT)
+                        .send()
+                        .await
+                        .and_then(reqwest::Response::error_for_status)?;
+                    read_json_limited::<Value>(response, MAX_RESPONSE_BYTES).await
+                }
+                .await;
+                (id, advisory)
+            }
+        })
+        .buffer_unordered(CONCURRENT_REQUESTS)
+        .collect::<Vec<_>>()
+        .await;
+    let mut advisories = HashMap::new();
+    for (id, advisory) in fetched {
+        match advisory {
+            // Withdrawn advisories were published in error.
+            Ok(advisory) if advisory["withdrawn"].is_string() => {}
+            Ok(advisory) => {
+                advisories.insert(id, advisory);
+            }
+            Err(err) => errors.push(format!("failed to fetch advisory {id}: {err:#}")),
+        }
+    }
+    errors.sort();
+
+    let mut findings = Vec::new();
+    for (lockfile, packages) in &lockfiles {
+        for package in packages {
+            for id in advisory_ids.get(package).into_iter().flatten() {
+                let Some(advisory) = advisories.get(id) else {
+                    continue;
+                };
+                findings.push(finding(lockfile, package, id, advisory));
+            }
+        }
+    }
+
+    Ok(DependencyAuditEvent {
+        lockfiles: lockfiles.into_iter().map(|(path, _)| path).collect(),
+        packages_checked: packages.len() as u64,
+        findings,
+        errors,
+    })
+}
+
+fn finding(
+    lockfile: &Path,
+    package: &PinnedPackage,
+    id: &str,
+    advisory: &Value,
+) -> DependencyAdvisoryFinding {
+    let text = |value: &Value| value.as_str().map(str::to_string);
+    let suggested_version = advisory["affected"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|affected| {
+            affected["package"]["name"].as_str().is_some_and(|name| {
+                if package.ecosystem == "PyPI" {
+                    normalize_pypi_name(name) == package.name
+                } else {
+                    name == package.name
+                }
+            })
+        })
+        .flat_map(|affected| affected["ranges"].as_array().into_iter().flatten())
+        .flat_map(|range| range["events"].as_array().into_iter().flatten())
+        .filter_map(|event| event["fixed"].as_str())
+        .filter(|fixed| compare_versions(fixed, &package.version) == Ordering::Greater)
+        .min_by(|a, b| compare_versions(a, b))
+        .map(str::to_string);
+    DependencyAdvisoryFinding {
+        ecosystem: package.ecosystem.to_string(),
+        package: package.name.clone(),
+        version: package.version.clone(),
+        lockfile: lockfile.to_path_buf(),
+        advisory_id: id.to_string(),
+        aliases: advisory["aliases"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(text)
+            .collect(),
+        summary: text(&advisory["summary"])
+            .or_else(|| text(&advisory["details"]))
+            .unwrap_or_default(),
+        severity: text(&advisory["database_specific"]["severity"]),
+        suggested_version,
+    }
+}
+
+/// Orders versions by their numeric release parts, then ranks a
+/// prerelease (`1.0.0-rc.1`, `1.0rc1`) below its release and a PyPI
+/// post-release above it. Good enough for picking among fixed versions.
+fn compare_versions(a: &str, b: &str) -> Ordering {
+    let split = |version: &str| {
+        let version = version.trim_start_matches('v');
+        let version = version.split('+').next().unwrap_or(version);
+        let end = version
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(version.len());
+        let release = version[..end]
+            .split('.')
+            .filter_map(|part| part.parse::<u64>().ok())
+            .collect::<Vec<_>>();
+        let suffix = version[end..]
+            .trim_start_matches(['-', '.', '_'])
+            .to_string();
+        (release, suffix)
+    };
+    let ((a_release, a_suffix), (b_release, b_suffix)) = (split(a), split(b));
+    for idx in 0..a_release.len().max(b_release.len()) {
+        let ordering = a_release
+            .get(idx)
+            .unwrap_or(&0)
+            .cmp(b_release.get(idx).unwrap_or(&0));
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    // Releases rank between their prereleases and post-releases.
+    let rank = |suffix: &str| match suffix {
+        "" => 1,
+        suffix if suffix.starts_with("post") => 2,
+        _ => 0,
+    };
+    rank(&a_suffix)
+        .cmp(&rank(&b_suffix))
+        .then_with(|| compare_suffixes(&a_suffix, &b_suffix))
+}
+
+/// Orders prerelease identifiers part by part, numerically where both parts
+/// are numbers.
+fn compare_suffixes(a: &str, b: &str) -> Ordering {
+    let (a, b) = (
+        a.split('.').collect::<Vec<_>>(),
+        b.split('.').collect::<Vec<_>>(),
+    );
+    for (a, b) in a.iter().zip(&b) {
+        let ordering = match (a.parse::<u64>(), b.parse::<u64>()) {
+            (Ok(a), Ok(b)) => a.cmp(&b),
+            _ => a.cmp(b),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a.len().cmp(&b.len())
+}
+
+/// A PyPI project name in its normalized form, e.g. `Foo_Bar` as `foo-bar`,
+/// which is how OSV records it.
+fn normalize_pypi_name(name: &str) -> String {
+    let mut normalized = String::with_capacity(name.len());
+    for part in name.split(['-', '_', '.']).filter(|part| !part.is_empty()) {
+        if !normalized.is_empty() {
+            normalized.push('-');
+        }
+        normalized.push_str(&part.to_ascii_lowercase());
+    }
+    normalized
+}
+
+fn upgrade_prompt(findings: &[DependencyAdvisoryFinding]) -> String {
+    let bumps = findings
+        .iter()
+        .map(|finding| {
+            let target = finding
+                .suggested_version
+                .as_deref()
+                .map_or("no fixed release yet".to_string(), |version| {
+                    format!("bump to {version}")
+                });
+            format!(
+                "- {} {} ({}, {}): {} — {target}",
+                finding.package,
+                finding.version,
+                finding.lockfile.display(),
+                finding.advisory_id,
+                finding.summary
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!(
+        "A dependency audit found these advisories affecting versions pinned by the \
+         workspace's lockfiles:\n{bumps}\n\n\
+         Upgrade each affected package to at least the suggested version with the project's \
+         package manager (e.g. `cargo update -p`, `npm install`, `uv lock --upgrade-package`), \
+         changing manifests only when their version requirements forbid the fix. Then build \
+         and run the tests. For advisories without a fixed release, explain the exposure \
+         instead of changing anything."
+    )
+}
+
+/// Lockfiles under `root`, relative to it, with the packages each pins.
+pub(crate) fn read_lockfiles(root: &Path) -> Vec<(PathBuf, Vec<PinnedPackage>)> {
+    let mut lockfiles = Vec::new();
+    let mut dirs = vec![(root.to_path_buf(), 0)];
+    while let Some((dir, depth)) = dirs.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut entries = entries
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .collect::<Vec<_>>();
+        entries.sort();
+        for path in entries {
+            let Some(name) = path.file_name().and_then(|name| name.to_str()) else {
+                continue;
+            };
+            if path.is_dir() {
+                if depth < MAX_LOCKFILE_DEPTH
+                    && !name.starts_with('.')
+                    && !SKIPPED_DIRS.contains(&name)
+                {
+                    dirs.push((path.clone(), depth + 1));
+                }
+                continue;
+            }
+            let Ok(contents) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let mut packages = match name {
+                "Cargo.lock" => parse_cargo_lock(&contents),
+                "package-lock.json" => parse_package_lock(&contents),
+                "poetry.lock" | "uv.lock" => parse_python_lock(&contents),
+                "requirements.txt" => parse_requirements(&contents),
+                _ => continue,
+            };
+            packages.sort();
+            packages.dedup();
+            let relative = path.strip_prefix(root).unwrap_or(&path).to_path_buf();
+            lockfiles.push((relative, packages));
+        }
+    }
+    lockfiles.sort();
+    lockfiles
+}
+
+fn toml_packages(contents: &str) -> Vec<toml::Value> {
+    toml::from_str::<toml::Value>(contents)
+        .ok()
+        .and_then(|lock| lock.get("package")?.as_array().cloned())
+        .unwrap_or_default()
+}
+
+/// Registry packages of a `Cargo.lock`; path and git dependencies have no
+/// advisories of their own.
+fn parse_cargo_lock(contents: &str) -> Vec<PinnedPackage> {
+    toml_packages(contents)
+        .iter()
+        .filter(|package| {
+            package
+                .get("source")
+                .and_then(toml::Value::as_str)
+                .is_some_and(|source| {
+                    source.starts_with("registry+") || source.starts_with("sparse+")
+                })
+        })
+        .filter_map(|package| {
+            Some(PinnedPackage {
+                ecosystem: "crates.io",
+                name: package.get("name")?.as_str()?.to_string(),
+                version: package.get("version")?.as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Packages of a `poetry.lock` or `uv.lock`, leaving out the workspace's own
+/// editable, path, and git packages.
+fn parse_python_lock(contents: &str) -> Vec<PinnedPackage> {
+    toml_packages(contents)
+        .iter()
+        .filter(|package| {
+            package
+                .get("source")
+                .and_then(toml::Value::as_table)
+                .is_none_or(|source| {
+                    !["editable", "virtual", "path", "directory", "git"]
+                        .iter()
+                        .any(|kind| source.contains_key(*kind))
+                        && source.get("type").and_then(toml::Value::as_str) != Some("directory")
+                })
+        })
+        .filter_map(|package| {
+            Some(PinnedPackage {
+                ecosystem: "PyPI",
+                name: normalize_pypi_name(package.get("name")?.as_str()?),
+                version: package.get("version")?.as_str()?.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Packages of a `package-lock.json`, from its `packages` map (lockfile
+/// version 2 and later) or nested `dependencies` (version 1).
+fn parse_package_lock(contents: &str) -> Vec<PinnedPackage> {
+    let Ok(lock) = serde_json::from_str::<Value>(contents) else {
+        return Vec::new();
+    };
+    let npm = |name: &str, entry: &Value| {
+        Some(PinnedPackage {
+            ecosystem: "npm",
+            name: name.to_string(),
+            version: entry["version"].as_str()?.to_string(),
+        })
+    };
+    if let Some(packages) = lock["packages"].as_object() {
+        return packages
+            .iter()
+            .filter(|(_, entry)| entry["link"] != true)
+            .filter_map(|(path, entry)| {
+                let (_, name) = path.rsplit_once("node_modules/")?;
+                npm(name, entry)
+            })
+            .collect();
+    }
+
+    let mut packages = Vec::new();
+    let mut pending = vec![&lock["dependencies"]];
+    while let Some(dependencies) = pending.pop() {
+        for (name, entry) in dependencies.as_object().into_iter().flatten() {
+            packages.extend(npm(name, entry));
+            pending.push(&entry["dependencies"]);
+        }
+    }
+    packages
+}
+
+/// Exact pins (`name==version`) of a `requirements.txt`.
+fn parse_requirements(contents: &str) -> Vec<PinnedPackage> {
+    contents
+        .lines()
+        .filter_map(|line| {
+            let line = line.split(['#', ';']).next()?.trim();
+            let (name, version) = line.split_once("==")?;
+            let name = name.split('[').next()?.trim();
+            let version = version.split(',').next()?.trim();
+            (!name.is_empty() && !version.is_empty()).then(|| PinnedPackage {
+                ecosystem: "PyPI",
+                name: normalize_pypi_name(name),
+                version: version.to_string(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn pinned(ecosystem: &'static str, name: &str, version: &str) -> PinnedPackage {
+        PinnedPackage {
+            ecosystem,
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_pinned_packages_from_lockfiles() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("Cargo.lock"),
+            r#"
+version = 4
+
+[[package]]
+name = "app"
+version = "0.1.0"
+
+[[package]]
+name = "time"
+version = "0.1.45"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .expect("write Cargo.lock");
+        std::fs::create_dir_all(dir.path().join("web/node_modules")).expect("mkdir");
+        std::fs::write(
+            dir.path().join("web/package-lock.json"),
+            r#"{"lockfileVersion": 3, "packages": {
+                "": {"name": "web"},
+                "node_modules/lodash": {"version": "4.17.20"},
+                "node_modules/a/node_modules/@types/node": {"version": "20.1.0"},
+                "node_modules/shared": {"link": true}
+            }}"#,
+        )
+        .expect("write package-lock.json");
+        std::fs::write(
+            dir.path().join("requirements.txt"),
+            "# pinned\nrequests[socks]==2.19.0 ; python_version >= '3.8'\nflask>=2\nZope.Interface==5.0\n",
+        )
+        .expect("write requirements.txt");
+
+        assert_eq!(
+            read_lockfiles(dir.path()),
+            vec![
+                (
+                    PathBuf::from("Cargo.lock"),
+                    vec![pinned("crates.io", "time", "0.1.45")]
+                ),
+                (
+                    PathBuf::from("requirements.txt"),
+                    vec![
+                        pinned("PyPI", "requests", "2.19.0"),
+                        pinned("PyPI", "zope-interface", "5.0"),
+                    ]
+                ),
+                (
+                    PathBuf::from("web/package-lock.json"),
+                    vec![
+                        pinned("npm", "@types/node", "20.1.0"),
+                        pinned("npm", "lodash", "4.17.20"),
+                    ]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn suggests_the_lowest_newer_fixed_version() {
+        let advisory = json!({
+            "summary": "Segfault in localtime_r",
+            "aliases": ["CVE-2020-26235"],
+            "affected": [{
+                "package": { "name": "time", "ecosystem": "crates.io" },
+                "ranges": [{ "type": "SEMVER", "events": [
+                    { "introduced": "0.0.0" }, { "fixed": "0.1.10" },
+                    { "introduced": "0.2.0" }, { "fixed": "0.2.23" },
+                    { "introduced": "0.1.40" }, { "fixed": "0.1.46" },
+                ]}],
+            }],
+        });
+        assert_eq!(
+            finding(
+                Path::new("Cargo.lock"),
+                &pinned("crates.io", "time", "0.1.45"),
+                "RUSTSEC-2020-0071",
+                &advisory
+            ),
+            DependencyAdvisoryFinding {
+                ecosystem: "crates.io".to_string(),
+                package: "time".to_string(),
+                version: "0.1.45".to_string(),
+                lockfile: PathBuf::from("Cargo.lock"),
+                advisory_id: "RUSTSEC-2020-0071".to_string(),
+                aliases: vec!["CVE-2020-26235".to_string()],
+                summary: "Segfault in localtime_r".to_string(),
+                severity: None,
+                suggested_version: Some("0.1.46".to_string()),
+            }
+        );
+    }
+
+    #[test]
+    fn versions_rank_prereleases_below_their_release() {
+        assert_eq!(
+            [
+                ("1.0.0-rc.1", "1.0.0"),
+                ("1.0rc1", "1.0"),
+                ("1.0.0-rc.2", "1.0.0-rc.10"),
+                ("1.0.post1", "1.0"),
+                ("1.10", "1.9"),
+                ("1.0", "1.0.0"),
+            ]
+            .map(|(a, b)| compare_versions(a, b)),
+            [
+                Ordering::Less,
+                Ordering::Less,
+                Ordering::Less,
+                Ordering::Greater,
+                Ordering::Greater,
+                Ordering::Equal,
+            ]
+        );
+    }
+}
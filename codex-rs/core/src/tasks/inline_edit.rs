@@ -0,0 +1,207 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_async_utils::OrCancelExt;
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::CodexErrorInfo;
+use codex_protocol::protocol::ErrorEvent;
+use codex_protocol::protocol::InlineEditResultEvent;
+use codex_protocol::protocol::LineRange;
+use codex_protocol::user_input::UserInput;
+use codex_utils_string::fill_template;
+use serde::Deserialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio_util::sync::CancellationToken;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::client_common::Prompt;
+use crate::codex::TurnContext;
+use crate::error::Result as CodexResult;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+use crate::stream_events_utils::sample_text;
+
+const INSTRUCTIONS: &str = include_str!("../../templates/inline_edit/instructions.md");
+const PROMPT_TEMPLATE: &str = include_str!("../../templates/inline_edit/prompt.md");
+/// Lines of the file shown on each side of the selection.
+const CONTEXT_LINES: usize = 200;
+
+/// Rewrites a range of lines as instructed ([`Op::InlineEdit`]). The turn has
+/// no tools and no thread history, and is not recorded in either: the model
+/// can only answer with the replacement, which is reported to the client to
+/// apply.
+///
+/// [`Op::InlineEdit`]: codex_protocol::protocol::Op::InlineEdit
+pub(crate) struct InlineEditTask {
+    pub(crate) path: PathBuf,
+    pub(crate) range: LineRange,
+    pub(crate) instruction: String,
+}
+
+#[derive(Deserialize)]
+struct InlineEditOutput {
+    replacement: String,
+}
+
+/// The lines of `content` before, in, and after `range`, with their line
+/// endings. `None` if the range is not within the file.
+fn split_at_range(content: &str, range: LineRange) -> Option<(Vec<&str>, String, Vec<&str>)> {
+    let lines: Vec<&str> = content.split_inclusive('\n').collect();
+    let start = usize::try_from(range.start).ok()?;
+    let end = usize::try_from(range.end).ok()?;
+    if start == 0 || start > end || end > lines.len() {
+        return None;
+    }
+    let before = lines[start.saturating_sub(CONTEXT_LINES + 1)..start - 1].to_vec();
+    let after = lines[end..lines.len().min(end + CONTEXT_LINES)].to_vec();
+    Some((before, lines[start - 1..end].concat(), after))
+}
+
+fn output_schema() -> Value {
+    json!({
+        "type": "object",
+        "properties": {
+            "replacement": { "type": "string" }
+        },
+        "required": ["replacement"],
+        "additionalProperties": false
+    })
+}
+
+impl InlineEditTask {
+    async fn edit(&self, sess: &SessionTaskContext, ctx: &TurnContext) -> CodexResult<EventMsg> {
+        let path = ctx.cwd.join(&self.path);
+        let bad_request = |message: String| {
+            EventMsg::Error(ErrorEvent {
+                message,
+                codex_error_info: Some(CodexErrorInfo::BadRequest),
+            })
+        };
+        let content = match tokio::fs::read_to_string(&path).await {
+            Ok(content) => content,
+            Err(err) => {
+                return Ok(bad_request(format!(
+                    "failed to read {}: {err}",
+                    path.display()
+                )));
+            }
+        };
+        let Some((before, original, after)) = split_at_range(&content, self.range) else {
+            return Ok(bad_request(format!(
+                "lines {}-{} are not within {}",
+                self.range.start,
+                self.range.end,
+                path.display()
+            )));
+        };
+
+        let text = fill_template(
+            PROMPT_TEMPLATE,
+            &[
+                ("path", &path.display().to_string()),
+                ("start", &self.range.start.to_string()),
+                ("end", &self.range.end.to_string()),
+                ("before", &before.concat()),
+                ("after", &after.concat()),
+                ("instruction", &self.instruction),
+                ("selection", &original),
+            ],
+        );
+        let prompt = Prompt {
+            input: vec![ResponseItem::Message {
+                id: None,
+                role: "user".to_string(),
+                content: vec![ContentItem::InputText { text }],
+                end_turn: None,
+                phase: None,
+            }],
+            base_instructions: BaseInstructions {
+                text: INSTRUCTIONS.to_string(),
+            },
+            output_schema: Some(output_schema()),
+            sampling: ctx.sampling,
+            ..Default::default()
+        };
+
+        let result = sample_text(&sess.clone_session(), ctx, &prompt).await?;
+        let output: InlineEditOutput = match serde_json::from_str(&result) {
+            Ok(output) => output,
+            Err(err) => {
+                return Ok(EventMsg::Error(ErrorEvent {
+                    message: format!("the model returned an invalid inline edit: {err}"),
+                    codex_error_info: Some(CodexErrorInfo::Other),
+                }));
+            }
+        };
+
+        Ok(EventMsg::InlineEditResult(InlineEditResultEvent {
+            path,
+            range: self.range,
+            original,
+            replacement: output.replacement,
+        }))
+    }
+}
+
+#[async_trait]
+impl SessionTask for InlineEditTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let msg = match self
+            .edit(&session, &ctx)
+            .or_cancel(&cancellation_token)
+            .await
+        {
+            Ok(Ok(msg)) => msg,
+            Ok(Err(err)) => EventMsg::Error(err.to_error_event(None)),
+            Err(_) => return None,
+        };
+        session.clone_session().send_event(&ctx, msg).await;
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn splits_content_around_the_range() {
+        let content = "a\nb\nc\nd";
+        assert_eq!(
+            split_at_range(content, LineRange { start: 2, end: 3 }),
+            Some((vec!["a\n"], "b\nc\n".to_string(), vec!["d"]))
+        );
+        assert_eq!(
+            split_at_range(content, LineRange { start: 4, end: 4 }),
+            Some((vec!["a\n", "b\n", "c\n"], "d".to_string(), Vec::new()))
+        );
+        assert_eq!(
+            split_at_range(content, LineRange { start: 0, end: 1 }),
+            None
+        );
+        assert_eq!(
+            split_at_range(content, LineRange { start: 3, end: 2 }),
+            None
+        );
+        assert_eq!(
+            split_at_range(content, LineRange { start: 4, end: 5 }),
+            None
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_async_utils::OrCancelExt;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::models::FunctionCallOutputPayload;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::ToolInvocationResultEvent;
+use codex_protocol::user_input::UserInput;
+use tokio_util::sync::CancellationToken;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::codex::TurnContext;
+use crate::codex::built_tools;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+use crate::tools::router::ToolCallSource;
+use crate::turn_diff_tracker::TurnDiffTracker;
+
+/// Runs one of the session's tools for a client ([`Op::InvokeTool`]). The
+/// call goes through the same router as the model's calls, so approvals,
+/// sandboxing, hooks, and events are the same; nothing is recorded in the
+/// thread history. Failures are reported as the output of the call, the way
+/// the model would see them.
+///
+/// [`Op::InvokeTool`]: codex_protocol::protocol::Op::InvokeTool
+pub(crate) struct InvokeToolTask {
+    pub(crate) call_id: String,
+    pub(crate) tool: String,
+    pub(crate) arguments: String,
+}
+
+#[async_trait]
+impl SessionTask for InvokeToolTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let failure = |message: String| ResponseInputItem::FunctionCallOutput {
+            call_id: self.call_id.clone(),
+            output: FunctionCallOutputPayload {
+                body: FunctionCallOutputBody::Text(message),
+                success: Some(false),
+            },
+        };
+        let output =
+            match built_tools(&sess, &ctx, &[], &HashSet::new(), None, &cancellation_token).await {
+                Ok(router) => {
+                    let call = router
+                        .build_call_by_name(
+                            &sess,
+                            self.tool.clone(),
+                            self.call_id.clone(),
+                            self.arguments.clone(),
+                        )
+                        .await;
+                    let tracker = Arc::new(tokio::sync::Mutex::new(TurnDiffTracker::new()));
+                    match router
+                        .dispatch_tool_call(
+                            Arc::clone(&sess),
+                            Arc::clone(&ctx),
+                            tracker,
+                            call,
+                            ToolCallSource::Client,
+                        )
+                        .or_cancel(&cancellation_token)
+                        .await
+                    {
+                        Ok(Ok(output)) => output,
+                        Ok(Err(err)) => failure(err.to_string()),
+                        Err(_) => return None,
+                    }
+                }
+                Err(err) => failure(err.to_string()),
+            };
+        sess.send_event(
+            &ctx,
+            EventMsg::ToolInvocationResult(ToolInvocationResultEvent {
+                call_id: self.call_id.clone(),
+                output,
+            }),
+        )
+        .await;
+        None
+    }
+}
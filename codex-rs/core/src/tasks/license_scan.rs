@@ -0,0 +1,647 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::io::Read;
+use std::iter::Peekable;
+use std::path::Path;
+use std::path::PathBuf;
+use std::str::SplitWhitespace;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Context;
+use async_trait::async_trait;
+use codex_protocol::protocol::DependencyLicense;
+use codex_protocol::protocol::LicenseReportEvent;
+use codex_protocol::protocol::LicenseViolation;
+use codex_protocol::protocol::SourceHeaderLicense;
+use codex_protocol::protocol::WarningEvent;
+use codex_protocol::user_input::UserInput;
+use futures::StreamExt;
+use futures::stream;
+use ignore::WalkBuilder;
+use serde_json::Value;
+use tokio_util::sync::CancellationToken;
+use url::Url;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use super::dependency_audit::MAX_RESPONSE_BYTES;
+use super::dependency_audit::PinnedPackage;
+use super::dependency_audit::SKIPPED_DIRS;
+use super::dependency_audit::read_lockfiles;
+use crate::codex::TurnContext;
+use crate::config::types::LicensePolicyConfig;
+use crate::default_client::build_reqwest_client;
+use crate::default_client::read_json_limited;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+
+/// Packages whose license is looked up in their registry when no local
+/// metadata declares it.
+const MAX_REGISTRY_LOOKUPS: usize = 500;
+const CONCURRENT_REQUESTS: usize = 8;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+const MAX_SOURCE_FILES: usize = 20_000;
+/// Bytes read from the top of each source file when looking for its header.
+const HEADER_BYTES: u64 = 4096;
+const SPDX_TAG: &str = "SPDX-License-Identifier:";
+/// SPDX ids of the license names PyPI metadata gives instead of one, in its
+/// `license` field or its `License ::` classifiers.
+const PYPI_LICENSE_NAMES: &[(&str, &str)] = &[
+    ("MIT License", "MIT"),
+    ("Apache Software License", "Apache-2.0"),
+    ("Apache 2.0", "Apache-2.0"),
+    ("Apache License 2.0", "Apache-2.0"),
+    ("Apache License, Version 2.0", "Apache-2.0"),
+    ("BSD 3-Clause", "BSD-3-Clause"),
+    ("BSD 3-Clause License", "BSD-3-Clause"),
+    ("New BSD License", "BSD-3-Clause"),
+    ("BSD 2-Clause", "BSD-2-Clause"),
+    ("Simplified BSD License", "BSD-2-Clause"),
+    ("ISC License", "ISC"),
+    ("ISC License (ISCL)", "ISC"),
+    ("Python Software Foundation License", "PSF-2.0"),
+    ("Mozilla Public License 2.0 (MPL 2.0)", "MPL-2.0"),
+    ("Eclipse Public License 2.0 (EPL-2.0)", "EPL-2.0"),
+    ("Boost Software License 1.0 (BSL-1.0)", "BSL-1.0"),
+    ("The Unlicense (Unlicense)", "Unlicense"),
+    ("GNU General Public License v2 (GPLv2)", "GPL-2.0-only"),
+    (
+        "GNU General Public License v2 or later (GPLv2+)",
+        "GPL-2.0-or-later",
+    ),
+    ("GNU General Public License v3 (GPLv3)", "GPL-3.0-only"),
+    (
+        "GNU General Public License v3 or later (GPLv3+)",
+        "GPL-3.0-or-later",
+    ),
+    (
+        "GNU Lesser General Public License v2 (LGPLv2)",
+        "LGPL-2.0-only",
+    ),
+    (
+        "GNU Lesser General Public License v2 or later (LGPLv2+)",
+        "LGPL-2.0-or-later",
+    ),
+    (
+        "GNU Lesser General Public License v3 (LGPLv3)",
+        "LGPL-3.0-only",
+    ),
+    (
+        "GNU Lesser General Public License v3 or later (LGPLv3+)",
+        "LGPL-3.0-or-later",
+    ),
+    ("GNU Affero General Public License v3", "AGPL-3.0-only"),
+    (
+        "GNU Affero General Public License v3 or later (AGPLv3+)",
+        "AGPL-3.0-or-later",
+    ),
+];
+
+/// Inventories the licenses of the packages pinned by the workspace's
+/// lockfiles and of its source file headers, and reports those that break
+/// `[license_policy]`. Dependency licenses come from the local Cargo
+/// registry cache and `node_modules` first, then from the package registry.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct LicenseScanTask;
+
+#[async_trait]
+impl SessionTask for LicenseScanTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let report = tokio::select! {
+            report = scan(&ctx.cwd, &ctx.config.license_policy) => report,
+            _ = cancellation_token.cancelled() => return None,
+        };
+        let msg = match report {
+            Ok(report) => EventMsg::LicenseReport(report),
+            Err(err) => EventMsg::Warning(WarningEvent {
+                message: format!("Failed to scan licenses: {err:#}"),
+            }),
+        };
+        sess.send_event(ctx.as_ref(), msg).await;
+        None
+    }
+}
+
+async fn scan(cwd: &Path, policy: &LicensePolicyConfig) -> anyhow::Result<LicenseReportEvent> {
+    let root = cwd.to_path_buf();
+    let (lockfiles, mut licenses, source_headers) = tokio::task::spawn_blocking(move || {
+        let lockfiles = read_lockfiles(&root);
+        let cargo_sources = cargo_registry_sources();
+        let mut licenses = HashMap::new();
+        for (lockfile, packages) in &lockfiles {
+            for package in packages {
+                if let Some(license) = local_license(&root, lockfile, package, &cargo_sources) {
+                    licenses.insert(package.clone(), license);
+                }
+            }
+        }
+        (lockfiles, licenses, read_source_headers(&root))
+    })
+    .await
+    .context("license reader panicked")?;
+
+    let missing = lockfiles
+        .iter()
+        .flat_map(|(_, packages)| packages)
+        .filter(|package| !licenses.contains_key(*package))
+        .cloned()
+        .collect::<BTreeSet<_>>();
+    let mut errors = Vec::new();
+    if missing.len() > MAX_REGISTRY_LOOKUPS {
+        errors.push(format!(
+            "{} packages were not looked up in their registry; only {MAX_REGISTRY_LOOKUPS} are",
+            missing.len() - MAX_REGISTRY_LOOKUPS
+        ));
+    }
+    let client = build_reqwest_client();
+    let lookups = stream::iter(missing.into_iter().take(MAX_REGISTRY_LOOKUPS))
+        .map(|package| {
+            let client = client.clone();
+            async move {
+                let lookup = async {
+                    let mut url = Url::parse(match package.ecosystem {
+                        "crates.io" => "https://crates.io/api/v1/crates/",
+                        "npm" => "https://registry.npmjs.org/",
+                        _ => "https://pypi.org/pypi/",
+                    })?;
+                    if let Ok(mut segments) = url.path_segments_mut() {
+                        segments
+                            .pop_if_empty()
+                            .push(&package.name)
+                            .push(&package.version);
+                        if package.ecosystem == "PyPI" {
+                            segments.push("json");
+                        }
+                    }
+                    let response = client
+                        .get(url)
+                        .timeout(REQUEST_TIMEOUT)
+                        .send()
+                        .await?
+                        .error_for_status()?;
+                    let metadata: Value = read_json_limited(response, MAX_RESPONSE_BYTES).await?;
+                    anyhow::Ok(match package.ecosystem {
+                        "crates.io" => metadata["version"]["license"].as_str().map(str::to_string),
+                        "npm" => npm_license(&metadata),
+                        _ => pypi_license(&metadata["info"]),
+                    })
+                };
+                (lookup.await, package)
+            }
+        })
+        .buffer_unordered(CONCURRENT_REQUESTS)
+        .collect::<Vec<_>>()
+        .await;
+    for (lookup, package) in lookups {
+        match lookup {
+            Ok(Some(license)) => {
+                licenses.insert(package, license);
+            }
+            Ok(None) => {}
+            Err(err) => errors.push(format!(
+                "failed to look up the license of {}@{}: {err:#}",
+                package.name, package.version
+            )),
+        }
+    }
+
+    let mut dependencies = Vec::new();
+    let mut violations = Vec::new();
+    for (lockfile, packages) in lockfiles {
+        for package in packages {
+            let license = licenses.get(&package).cloned();
+            if !policy.exempt_packages.contains(&package.name)
+                && let Some(reason) = violation_reason(policy, license.as_deref())
+            {
+                violations.push(LicenseViolation {
+                    subject: format!(
+                        "{}@{} ({})",
+                        package.name,
+                        package.version,
+                        lockfile.display()
+                    ),
+                    license: license.clone(),
+                    reason,
+                });
+            }
+            dependencies.push(DependencyLicense {
+                ecosystem: package.ecosystem.to_string(),
+                package: package.name,
+                version: package.version,
+                lockfile: lockfile.clone(),
+                license,
+            });
+        }
+    }
+    for header in &source_headers {
+        if let Some(reason) = violation_reason(policy, Some(&header.license))
+            && let Some(first) = header.files.first()
+        {
+            let subject = match header.files.len() {
+                1 => first.display().to_string(),
+                count => format!("{} and {} other source files", first.display(), count - 1),
+            };
+            violations.push(LicenseViolation {
+                subject,
+                license: Some(header.license.clone()),
+                reason,
+            });
+        }
+    }
+
+    Ok(LicenseReportEvent {
+        dependencies,
+        source_headers,
+        violations,
+        errors,
+    })
+}
+
+/// Why `license` breaks `policy`, or `None` when it complies.
+fn violation_reason(policy: &LicensePolicyConfig, license: Option<&str>) -> Option<String> {
+    let Some(license) = license.filter(|license| !license.trim().is_empty()) else {
+        return policy
+            .deny_unknown
+            .then(|| "no license declared".to_string());
+    };
+    let listed =
+        |list: &[String], id: &str| list.iter().any(|entry| entry.eq_ignore_ascii_case(id));
+    let choices = license_choices(license);
+    let compliant = choices.iter().any(|choice| {
+        choice.iter().all(|id| {
+            !listed(&policy.denied, id)
+                && (policy.allowed.is_empty() || listed(&policy.allowed, id))
+        })
+    });
+    if compliant {
+        return None;
+    }
+    Some(
+        match choices
+            .iter()
+            .flatten()
+            .find(|id| listed(&policy.denied, id))
+        {
+            Some(id) => format!("{id} is denied"),
+            None => "not among the allowed licenses".to_string(),
+        },
+    )
+}
+
+/// The ways to satisfy an SPDX license expression, each the licenses that
+/// must all be accepted: `MIT OR (Apache-2.0 AND BSD-3-Clause)` gives
+/// `[[MIT], [Apache-2.0, BSD-3-Clause]]`. License exceptions (`WITH ...`)
+/// are dropped and the legacy `/` separator of older crates reads as `OR`.
+fn license_choices(expression: &str) -> Vec<Vec<String>> {
+    let spaced = expression
+        .replace('(', " ( ")
+        .replace(')', " ) ")
+        .replace('/', " OR ");
+    parse_or(&mut spaced.split_whitespace().peekable())
+}
+
+fn parse_or(tokens: &mut Peekable<SplitWhitespace<'_>>) -> Vec<Vec<String>> {
+    let mut choices = parse_and(tokens);
+    while tokens
+        .next_if(|token| token.eq_ignore_ascii_case("OR"))
+        .is_some()
+    {
+        choices.extend(parse_and(tokens));
+    }
+    choices
+}
+
+fn parse_and(tokens: &mut Peekable<SplitWhitespace<'_>>) -> Vec<Vec<String>> {
+    let mut choices = parse_term(tokens);
+    while tokens
+        .next_if(|token| token.eq_ignore_ascii_case("AND"))
+        .is_some()
+    {
+        let right = parse_term(tokens);
+        choices = choices
+            .iter()
+            .flat_map(|left| {
+                right
+                    .iter()
+                    .map(move |term| [left.clone(), term.clone()].concat())
+            })
+            .collect();
+    }
+    choices
+}
+
+fn parse_term(tokens: &mut Peekable<SplitWhitespace<'_>>) -> Vec<Vec<String>> {
+    let choices = match tokens.next() {
+        Some("(") => {
+            let choices = parse_or(tokens);
+            tokens.next_if_eq(&")");
+            choices
+        }
+        Some(id) => vec![vec![id.to_string()]],
+        None => Vec::new(),
+    };
+    if tokens
+        .next_if(|token| token.eq_ignore_ascii_case("WITH"))
+        .is_some()
+    {
+        tokens.next();
+    }
+    choices
+}
+
+/// Unpacked crate sources of the local Cargo registry cache, one directory
+/// per registry.
+fn cargo_registry_sources() -> Vec<PathBuf> {
+    let cargo_home = std::env::var_os("CARGO_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".cargo")));
+    let Some(Ok(entries)) = cargo_home.map(|home| std::fs::read_dir(home.join("registry/src")))
+    else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect()
+}
+
+/// License declared by the package's own manifest when it is on disk: in the
+/// Cargo registry cache for crates, or next to an npm lockfile in
+/// `node_modules`.
+fn local_license(
+    root: &Path,
+    lockfile: &Path,
+    package: &PinnedPackage,
+    cargo_sources: &[PathBuf],
+) -> Option<String> {
+    match package.ecosystem {
+        "crates.io" => cargo_sources.iter().find_map(|source| {
+            let manifest_path = source
+                .join(format!("{}-{}", package.name, package.version))
+                .join("Cargo.toml");
+            let manifest = std::fs::read_to_string(manifest_path).ok()?;
+            toml::from_str::<toml::Value>(&manifest)
+                .ok()?
+                .get("package")?
+                .get("license")?
+                .as_str()
+                .map(str::to_string)
+        }),
+        "npm" => {
+            let manifest_path = root
+                .join(lockfile)
+                .parent()?
+                .join("node_modules")
+                .join(&package.name)
+                .join("package.json");
+            let manifest =
+                serde_json::from_str::<Value>(&std::fs::read_to_string(manifest_path).ok()?)
+                    .ok()?;
+            if manifest["version"] != package.version.as_str() {
+                return None;
+            }
+            npm_license(&manifest)
+        }
+        _ => None,
+    }
+}
+
+/// `license` of an npm manifest, which older packages give as an object.
+fn npm_license(manifest: &Value) -> Option<String> {
+    manifest["license"]
+        .as_str()
+        .or_else(|| manifest["license"]["type"].as_str())
+        .map(str::to_string)
+}
+
+/// License of a PyPI release: its SPDX `license_expression`, else a short
+/// `license` field (many hold the full license text instead), else its
+/// `License ::` classifiers, any of which may be chosen. License names are
+/// mapped to their SPDX ids.
+fn pypi_license(info: &Value) -> Option<String> {
+    let spdx_id = |name: &str| {
+        PYPI_LICENSE_NAMES
+            .iter()
+            .find(|(alias, _)| alias.eq_ignore_ascii_case(name))
+            .map_or(name, |(_, id)| id)
+            .to_string()
+    };
+    let short = |field: &str| {
+        info[field]
+            .as_str()
+            .map(str::trim)
+            .filter(|text| !text.is_empty() && text.len() <= 64 && !text.contains('\n'))
+    };
+    if let Some(expression) = short("license_expression") {
+        return Some(expression.to_string());
+    }
+    if let Some(license) = short("license") {
+        return Some(spdx_id(license));
+    }
+    let classifiers = info["classifiers"]
+        .as_array()?
+        .iter()
+        .filter_map(Value::as_str)
+        .filter_map(|classifier| classifier.strip_prefix("License :: "))
+        .filter(|classifier| *classifier != "OSI Approved")
+        .map(|classifier| spdx_id(classifier.rsplit(" :: ").next().unwrap_or(classifier)))
+        .collect::<Vec<_>>();
+    (!classifiers.is_empty()).then(|| classifiers.join(" OR "))
+}
+
+/// Licenses declared by `SPDX-License-Identifier` headers of the files under
+/// `root` that are not hidden or ignored, with paths relative to it.
+fn read_source_headers(root: &Path) -> Vec<SourceHeaderLicense> {
+    let mut headers = BTreeMap::<String, Vec<PathBuf>>::new();
+    let files = WalkBuilder::new(root)
+        // Apply .gitignore rules outside of git repositories too.
+        .require_git(false)
+        .filter_entry(|entry| {
+            !entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_dir())
+                || !entry
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| SKIPPED_DIRS.contains(&name))
+        })
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| {
+            entry
+                .file_type()
+                .is_some_and(|file_type| file_type.is_file())
+        })
+        .take(MAX_SOURCE_FILES);
+    for entry in files {
+        let path = entry.path();
+        let mut head = Vec::new();
+        let Ok(file) = std::fs::File::open(path) else {
+            continue;
+        };
+        if file.take(HEADER_BYTES).read_to_end(&mut head).is_err() {
+            continue;
+        }
+        let head = String::from_utf8_lossy(&head);
+        let Some((_, rest)) = head.split_once(SPDX_TAG) else {
+            continue;
+        };
+        let license = rest
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .trim_end_matches("*/")
+            .trim_end_matches("-->")
+            .trim();
+        if !license.is_empty() {
+            let relative = path.strip_prefix(root).unwrap_or(path).to_path_buf();
+            headers
+                .entry(license.to_string())
+                .or_default()
+                .push(relative);
+        }
+    }
+    headers
+        .into_iter()
+        .map(|(license, mut files)| {
+            files.sort();
+            SourceHeaderLicense { license, files }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn checks_license_expressions_against_the_policy() {
+        let policy = LicensePolicyConfig {
+            allowed: vec![
+                "MIT".to_string(),
+                "Apache-2.0".to_string(),
+                "BSD-3-Clause".to_string(),
+            ],
+            denied: vec!["GPL-3.0-only".to_string()],
+            deny_unknown: true,
+            exempt_packages: Vec::new(),
+        };
+        let reasons = [
+            None,
+            Some("MIT OR Apache-2.0"),
+            Some("MIT/Apache-2.0"),
+            Some("(MIT OR GPL-3.0-only) AND BSD-3-Clause"),
+            Some("Apache-2.0 WITH LLVM-exception"),
+            Some("GPL-3.0-only AND MIT"),
+            Some("MPL-2.0"),
+        ]
+        .map(|license| violation_reason(&policy, license));
+
+        assert_eq!(
+            reasons,
+            [
+                Some("no license declared".to_string()),
+                None,
+                None,
+                None,
+                None,
+                Some("GPL-3.0-only is denied".to_string()),
+                Some("not among the allowed licenses".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn maps_pypi_license_names_to_spdx_ids() {
+        let licenses = [
+            serde_json::json!({ "license_expression": "MIT OR Apache-2.0", "license": "MIT License" }),
+            serde_json::json!({ "license": "Apache License 2.0" }),
+            serde_json::json!({
+                "license": "",
+                "classifiers": [
+                    "License :: OSI Approved",
+                    "License :: OSI Approved :: MIT License",
+                    "License :: OSI Approved :: GNU General Public License v3 (GPLv3)",
+                    "Programming Language :: Python :: 3",
+                ],
+            }),
+            serde_json::json!({ "license": "Custom" }),
+        ]
+        .map(|info| pypi_license(&info));
+
+        assert_eq!(
+            licenses,
+            [
+                Some("MIT OR Apache-2.0".to_string()),
+                Some("Apache-2.0".to_string()),
+                Some("MIT OR GPL-3.0-only".to_string()),
+                Some("Custom".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn groups_source_files_by_spdx_header() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("src/vendored")).expect("mkdir");
+        std::fs::create_dir_all(dir.path().join("node_modules/dep")).expect("mkdir");
+        std::fs::write(
+            dir.path().join("src/main.rs"),
+            "// SPDX-License-Identifier: MIT OR Apache-2.0\nfn main() {}\n",
+        )
+        .expect("write main.rs");
+        std::fs::write(
+            dir.path().join("src/vendored/zlib.c"),
+            "/* SPDX-License-Identifier: Zlib */\n",
+        )
+        .expect("write zlib.c");
+        std::fs::write(
+            dir.path().join("src/lib.rs"),
+            "// SPDX-License-Identifier: MIT OR Apache-2.0\n",
+        )
+        .expect("write lib.rs");
+        std::fs::write(dir.path().join("src/util.rs"), "fn util() {}\n").expect("write util.rs");
+        std::fs::create_dir_all(dir.path().join("generated")).expect("mkdir");
+        std::fs::write(dir.path().join(".gitignore"), "generated/\n").expect("write .gitignore");
+        std::fs::write(
+            dir.path().join("generated/bindings.rs"),
+            "// SPDX-License-Identifier: GPL-3.0-only\n",
+        )
+        .expect("write bindings.rs");
+        std::fs::write(
+            dir.path().join("node_modules/dep/index.js"),
+            "// SPDX-License-Identifier: GPL-3.0-only\n",
+        )
+        .expect("write index.js");
+
+        assert_eq!(
+            read_source_headers(dir.path()),
+            vec![
+                SourceHeaderLicense {
+                    license: "MIT OR Apache-2.0".to_string(),
+                    files: vec![PathBuf::from("src/lib.rs"), PathBuf::from("src/main.rs")],
+                },
+                SourceHeaderLicense {
+                    license: "Zlib".to_string(),
+                    files: vec![PathBuf::from("src/vendored/zlib.c")],
+                },
+            ]
+        );
+    }
+}
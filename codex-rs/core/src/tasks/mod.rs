@@ -1,5 +1,13 @@
+mod apply_shadow_workspace;
+mod benchmark;
+mod codebase_map;
 mod compact;
+mod dependency_audit;
 mod ghost_snapshot;
+mod inline_edit;
+mod invoke_tool;
+mod license_scan;
+mod regenerate;
 mod regular;
 mod review;
 mod undo;
@@ -7,6 +15,7 @@ mod user_shell;
 
 use std::sync::Arc;
 use std::time::Duration;
+use std::time::Instant;
 
 use async_trait::async_trait;
 use tokio::select;
@@ -36,8 +45,19 @@ use codex_protocol::models::ResponseItem;
 use codex_protocol::protocol::RolloutItem;
 use codex_protocol::user_input::UserInput;
 
+pub(crate) use apply_shadow_workspace::ApplyShadowWorkspaceTask;
+pub(crate) use benchmark::BenchmarkTask;
+pub(crate) use codebase_map::CodebaseMapTask;
 pub(crate) use compact::CompactTask;
+pub(crate) use dependency_audit::DependencyAuditTask;
 pub(crate) use ghost_snapshot::GhostSnapshotTask;
+pub(crate) use inline_edit::InlineEditTask;
+pub(crate) use invoke_tool::InvokeToolTask;
+pub(crate) use license_scan::LicenseScanTask;
+pub(crate) use regenerate::MAX_RESPONSE_CANDIDATES;
+pub(crate) use regenerate::RegenerateTask;
+pub(crate) use regenerate::ResponseCandidates;
+pub(crate) use regenerate::response_text;
 pub(crate) use regular::RegularTask;
 pub(crate) use review::ReviewTask;
 pub(crate) use undo::UndoTask;
@@ -138,6 +158,7 @@ impl Session {
             tokio::spawn(
                 async move {
                     let ctx_for_finish = Arc::clone(&ctx);
+                    ctx.timer.start();
                     let last_agent_message = task_for_run
                         .run(
                             Arc::clone(&session_ctx),
@@ -147,7 +168,11 @@ impl Session {
                         )
                         .await;
                     let sess = session_ctx.clone_session();
+                    let flush_started = Instant::now();
                     sess.flush_rollout().await;
+                    ctx_for_finish
+                        .timer
+                        .record_serialization(flush_started.elapsed());
                     if !task_cancellation_token.is_cancelled() {
                         // Emit completion uniformly from spawn site so all tasks share the same lifecycle.
                         sess.on_task_finished(Arc::clone(&ctx_for_finish), last_agent_message)
@@ -206,6 +231,7 @@ impl Session {
         }
         if should_clear_active_turn {
             *active = None;
+            self.idle_since.send_replace(Some(Instant::now()));
         }
         drop(active);
         if !pending_input.is_empty() {
@@ -220,6 +246,7 @@ impl Session {
             turn_id: turn_context.sub_id.clone(),
             last_agent_message,
         });
+        self.keep_turn_timing(&turn_context).await;
         self.send_event(turn_context.as_ref(), event).await;
     }
 
@@ -228,6 +255,7 @@ impl Session {
         let mut turn = ActiveTurn::default();
         turn.add_task(task);
         *active = Some(turn);
+        self.idle_since.send_replace(None);
     }
 
     async fn take_all_running_tasks(&self) -> Vec<RunningTask> {
@@ -235,6 +263,7 @@ impl Session {
         match active.take() {
             Some(mut at) => {
                 at.clear_pending().await;
+                self.idle_since.send_replace(Some(Instant::now()));
 
                 at.drain_tasks()
             }
@@ -302,6 +331,7 @@ impl Session {
             turn_id: Some(task.turn_context.sub_id.clone()),
             reason,
         });
+        self.keep_turn_timing(&task.turn_context).await;
         self.send_event(task.turn_context.as_ref(), event).await;
     }
 }
@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_async_utils::OrCancelExt;
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ResponseCandidatesEvent;
+use codex_protocol::user_input::UserInput;
+use futures::future::try_join_all;
+use tokio_util::sync::CancellationToken;
+
+use super::SessionTask;
+use super::SessionTaskContext;
+use crate::client_common::Prompt;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::codex::built_tools;
+use crate::error::Result as CodexResult;
+use crate::experiments::apply_experiment_tool_descriptions;
+use crate::protocol::EventMsg;
+use crate::state::TaskKind;
+use crate::stream_events_utils::last_assistant_message_from_item;
+use crate::stream_events_utils::sample_items;
+
+/// Most candidates one `Op::Regenerate` may ask for.
+pub(crate) const MAX_RESPONSE_CANDIDATES: u32 = 8;
+
+/// Samples alternatives to the model's last response ([`Op::Regenerate`]).
+/// The request is the one that produced that response, with the same tools
+/// and turn settings, but the candidates' tool calls are not run; they are
+/// held in the session state until [`Op::SelectCandidate`] puts one in the
+/// history.
+///
+/// [`Op::Regenerate`]: codex_protocol::protocol::Op::Regenerate
+/// [`Op::SelectCandidate`]: codex_protocol::protocol::Op::SelectCandidate
+pub(crate) struct RegenerateTask {
+    pub(crate) n: u32,
+}
+
+/// Candidates sampled by the latest [`RegenerateTask`].
+#[derive(Debug, Clone)]
+pub(crate) struct ResponseCandidates {
+    /// Length of the history they were sampled against; a different length
+    /// means the thread has moved on and they no longer apply.
+    pub(crate) history_len: usize,
+    /// Where the response they replace starts in that history.
+    pub(crate) response_start: usize,
+    pub(crate) candidates: Vec<Vec<ResponseItem>>,
+}
+
+/// The text a client shows for a response.
+pub(crate) fn response_text(items: &[ResponseItem]) -> String {
+    items
+        .iter()
+        .filter_map(|item| last_assistant_message_from_item(item, false))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+impl RegenerateTask {
+    async fn regenerate(
+        &self,
+        sess: &Session,
+        ctx: &TurnContext,
+        cancellation_token: &CancellationToken,
+    ) -> CodexResult<EventMsg> {
+        let mut history = sess.clone_history().await;
+        let history_len = history.raw_items().len();
+        let response_start = history.last_response_start();
+        history.replace(history.raw_items()[..response_start].to_vec());
+        let input = history.for_prompt(&ctx.model_info.input_modalities);
+        let router =
+            built_tools(sess, ctx, &input, &HashSet::new(), None, cancellation_token).await?;
+        let mut tools = router.specs();
+        apply_experiment_tool_descriptions(
+            &mut tools,
+            &ctx.config.experiments,
+            &sess.experiment_arms().await,
+        );
+        let prompt = Prompt {
+            input,
+            tools,
+            parallel_tool_calls: ctx.model_info.supports_parallel_tool_calls,
+            base_instructions: sess.get_base_instructions().await,
+            personality: ctx.personality,
+            output_schema: ctx.final_output_json_schema.clone(),
+            sampling: ctx.sampling,
+        };
+
+        let candidates =
+            try_join_all((0..self.n).map(|_| sample_items(sess, ctx, &prompt))).await?;
+        let texts = candidates
+            .iter()
+            .map(|items| response_text(items))
+            .collect();
+        sess.set_response_candidates(ResponseCandidates {
+            history_len,
+            response_start,
+            candidates,
+        })
+        .await;
+        Ok(EventMsg::ResponseCandidates(ResponseCandidatesEvent {
+            candidates: texts,
+        }))
+    }
+}
+
+#[async_trait]
+impl SessionTask for RegenerateTask {
+    fn kind(&self) -> TaskKind {
+        TaskKind::Regular
+    }
+
+    async fn run(
+        self: Arc<Self>,
+        session: Arc<SessionTaskContext>,
+        ctx: Arc<TurnContext>,
+        _input: Vec<UserInput>,
+        cancellation_token: CancellationToken,
+    ) -> Option<String> {
+        let sess = session.clone_session();
+        let msg = match self
+            .regenerate(&sess, &ctx, &cancellation_token)
+            .or_cancel(&cancellation_token)
+            .await
+        {
+            Ok(Ok(msg)) => msg,
+            Ok(Err(err)) => EventMsg::Error(err.to_error_event(None)),
+            Err(_) => return None,
+        };
+        sess.send_event(&ctx, msg).await;
+        None
+    }
+}
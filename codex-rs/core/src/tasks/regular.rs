@@ -38,8 +38,12 @@ impl RegularTask {
         model_client: ModelClient,
         otel_manager: OtelManager,
         model_info: ModelInfo,
+        preconnect: bool,
     ) -> Self {
         let prewarmed_session_task = tokio::spawn(async move {
+            if preconnect {
+                model_client.preconnect(&otel_manager, &model_info).await;
+            }
             let mut client_session = model_client.new_session();
             match client_session
                 .prewarm_websocket(&otel_manager, &model_info)
@@ -20,6 +20,7 @@ use crate::codex::TurnContext;
 use crate::codex_delegate::run_codex_thread_one_shot;
 use crate::config::Constrained;
 use crate::features::Feature;
+use crate::localization::Message;
 use crate::review_format::format_review_findings_block;
 use crate::review_format::render_review_output_text;
 use crate::state::TaskKind;
@@ -218,9 +219,11 @@ pub(crate) async fn exit_review_mode(
         (rendered, assistant_message)
     } else {
         let rendered = crate::client_common::REVIEW_EXIT_INTERRUPTED_TMPL.to_string();
-        let assistant_message =
-            "Review was interrupted. Please re-run /review and wait for it to complete."
-                .to_string();
+        let assistant_message = ctx
+            .config
+            .locale
+            .text(Message::ReviewInterrupted)
+            .to_string();
         (rendered, assistant_message)
     };
 
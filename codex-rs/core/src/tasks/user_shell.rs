@@ -10,12 +10,14 @@ use tracing::error;
 use uuid::Uuid;
 
 use crate::codex::TurnContext;
+use crate::exec::ExecOutputCapture;
 use crate::exec::ExecToolCallOutput;
 use crate::exec::SandboxType;
 use crate::exec::StdoutStream;
 use crate::exec::StreamOutput;
 use crate::exec::execute_exec_env;
 use crate::exec_env::create_env;
+use crate::localization::Message;
 use crate::parse_command::parse_command;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandBeginEvent;
@@ -169,6 +171,7 @@ pub(crate) async fn execute_user_shell_command(
         sub_id: turn_context.sub_id.clone(),
         call_id: call_id.clone(),
         tx_event: session.get_tx_event(),
+        capture: ExecOutputCapture::from_config(&turn_context.config),
     });
 
     let sandbox_policy = SandboxPolicy::DangerFullAccess;
@@ -178,7 +181,11 @@ pub(crate) async fn execute_user_shell_command(
 
     match exec_result {
         Err(CancelErr::Cancelled) => {
-            let aborted_message = "command aborted by user".to_string();
+            let aborted_message = turn_context
+                .config
+                .locale
+                .text(Message::CommandAborted)
+                .to_string();
             let exec_output = ExecToolCallOutput {
                 exit_code: -1,
                 stdout: StreamOutput::new(String::new()),
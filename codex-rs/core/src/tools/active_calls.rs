@@ -0,0 +1,228 @@
+//! Tool calls in flight ([`Op::GetActiveToolCalls`]).
+//!
+//! Every dispatched call is registered from the moment it is spawned until
+//! its task ends, with what it is doing: waiting for the parallel execution
+//! lock, waiting for an identical call in flight, waiting for approval, or
+//! running. Each call also keeps its own cancellation token, so it can be
+//! aborted alone ([`Op::CancelToolCall`]).
+//!
+//! [`Op::GetActiveToolCalls`]: codex_protocol::protocol::Op::GetActiveToolCalls
+//! [`Op::CancelToolCall`]: codex_protocol::protocol::Op::CancelToolCall
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::ActiveToolCall;
+use codex_protocol::protocol::ActiveToolCallStatus;
+use tokio_util::sync::CancellationToken;
+
+#[derive(Debug, Default)]
+pub(crate) struct ActiveToolCalls {
+    calls: Arc<Mutex<HashMap<String, ActiveCall>>>,
+}
+
+#[derive(Debug)]
+struct ActiveCall {
+    tool: String,
+    started_at: Instant,
+    status: ActiveToolCallStatus,
+    cancellation_token: CancellationToken,
+    /// Time spent waiting for approval, not counting a wait in progress.
+    approval_wait: Duration,
+    /// When the wait for approval in progress began.
+    approval_started_at: Option<Instant>,
+}
+
+impl ActiveToolCalls {
+    /// Registers call `call_id` of `tool`, which `cancellation_token`
+    /// aborts, until the returned guard drops.
+    pub(crate) fn start(
+        &self,
+        call_id: &str,
+        tool: &str,
+        status: ActiveToolCallStatus,
+        cancellation_token: CancellationToken,
+    ) -> ActiveCallGuard {
+        lock(&self.calls).insert(
+            call_id.to_string(),
+            ActiveCall {
+                tool: tool.to_string(),
+                started_at: Instant::now(),
+                status,
+                cancellation_token,
+                approval_wait: Duration::ZERO,
+                approval_started_at: None,
+            },
+        );
+        ActiveCallGuard {
+            calls: Arc::clone(&self.calls),
+            call_id: call_id.to_string(),
+        }
+    }
+
+    /// Updates the status of call `call_id`, if it is registered.
+    pub(crate) fn set_status(&self, call_id: &str, status: ActiveToolCallStatus) {
+        if let Some(call) = lock(&self.calls).get_mut(call_id) {
+            if status == ActiveToolCallStatus::WaitingForApproval {
+                call.approval_started_at.get_or_insert_with(Instant::now);
+            } else if let Some(approval_started_at) = call.approval_started_at.take() {
+                call.approval_wait += approval_started_at.elapsed();
+            }
+            call.status = status;
+        }
+    }
+
+    /// How long call `call_id` has waited for approval so far, and whether it
+    /// is waiting now.
+    pub(crate) fn approval_wait(&self, call_id: &str) -> (Duration, bool) {
+        match lock(&self.calls).get(call_id) {
+            Some(call) => (
+                call.approval_wait
+                    + call
+                        .approval_started_at
+                        .map_or(Duration::ZERO, |started_at| started_at.elapsed()),
+                call.approval_started_at.is_some(),
+            ),
+            None => (Duration::ZERO, false),
+        }
+    }
+
+    /// Aborts call `call_id`. Returns `false` if it is not in flight.
+    pub(crate) fn cancel(&self, call_id: &str) -> bool {
+        match lock(&self.calls).get(call_id) {
+            Some(call) => {
+                call.cancellation_token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Vec<ActiveToolCall> {
+        let calls = lock(&self.calls);
+        let mut active: Vec<(Instant, ActiveToolCall)> = calls
+            .iter()
+            .map(|(call_id, call)| {
+                (
+                    call.started_at,
+                    ActiveToolCall {
+                        call_id: call_id.clone(),
+                        tool: call.tool.clone(),
+                        elapsed_ms: u64::try_from(call.started_at.elapsed().as_millis())
+                            .unwrap_or(u64::MAX),
+                        status: call.status,
+                    },
+                )
+            })
+            .collect();
+        active.sort_by(|(a_started, a), (b_started, b)| {
+            a_started
+                .cmp(b_started)
+                .then_with(|| a.call_id.cmp(&b.call_id))
+        });
+        active.into_iter().map(|(_, call)| call).collect()
+    }
+}
+
+/// Unregisters its call when dropped, including when the call is aborted.
+pub(crate) struct ActiveCallGuard {
+    calls: Arc<Mutex<HashMap<String, ActiveCall>>>,
+    call_id: String,
+}
+
+impl Drop for ActiveCallGuard {
+    fn drop(&mut self) {
+        lock(&self.calls).remove(&self.call_id);
+    }
+}
+
+fn lock(calls: &Mutex<HashMap<String, ActiveCall>>) -> MutexGuard<'_, HashMap<String, ActiveCall>> {
+    calls
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lists_calls_until_their_guard_drops() {
+        let active = ActiveToolCalls::default();
+        let shell_token = CancellationToken::new();
+        let shell = active.start(
+            "call-1",
+            "shell",
+            ActiveToolCallStatus::Running,
+            shell_token.clone(),
+        );
+        let read = active.start(
+            "call-2",
+            "read_file",
+            ActiveToolCallStatus::WaitingForParallelLock,
+            CancellationToken::new(),
+        );
+        active.set_status("call-2", ActiveToolCallStatus::WaitingForDuplicate);
+
+        let summary = |active: &ActiveToolCalls| {
+            active
+                .snapshot()
+                .into_iter()
+                .map(|call| (call.call_id, call.tool, call.status))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            summary(&active),
+            vec![
+                (
+                    "call-1".to_string(),
+                    "shell".to_string(),
+                    ActiveToolCallStatus::Running
+                ),
+                (
+                    "call-2".to_string(),
+                    "read_file".to_string(),
+                    ActiveToolCallStatus::WaitingForDuplicate
+                ),
+            ]
+        );
+
+        assert!(active.cancel("call-1"));
+        assert!(shell_token.is_cancelled());
+
+        drop(shell);
+        drop(read);
+        assert_eq!(summary(&active), vec![]);
+        assert!(!active.cancel("call-1"));
+    }
+
+    #[test]
+    fn counts_time_spent_waiting_for_approval() {
+        let active = ActiveToolCalls::default();
+        let _call = active.start(
+            "call-1",
+            "shell",
+            ActiveToolCallStatus::Running,
+            CancellationToken::new(),
+        );
+        assert_eq!(active.approval_wait("call-1"), (Duration::ZERO, false));
+
+        active.set_status("call-1", ActiveToolCallStatus::WaitingForApproval);
+        std::thread::sleep(Duration::from_millis(20));
+        let (waiting, waiting_now) = active.approval_wait("call-1");
+        assert!(waiting >= Duration::from_millis(20));
+        assert!(waiting_now);
+
+        active.set_status("call-1", ActiveToolCallStatus::Running);
+        let (waited, waiting_now) = active.approval_wait("call-1");
+        std::thread::sleep(Duration::from_millis(20));
+        assert_eq!(active.approval_wait("call-1"), (waited, false));
+        assert!(waited >= waiting);
+        assert!(!waiting_now);
+    }
+}
@@ -0,0 +1,119 @@
+//! Per-tool circuit breakers.
+//!
+//! A tool (or, for MCP tools, the whole server) that keeps failing with
+//! timeouts or provider limits is short-circuited for a cooldown period so the
+//! model gets an immediate "tool unavailable" result instead of waiting on the
+//! same failure again.
+
+use std::collections::HashMap;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::models::ResponseInputItem;
+
+use crate::function_tool::FunctionCallError;
+use crate::function_tool::ToolFailureKind;
+
+/// Consecutive failures after which a breaker trips.
+pub(crate) const CIRCUIT_BREAKER_FAILURE_THRESHOLD: u32 = 3;
+/// How long a tripped breaker rejects calls before letting one through again.
+pub(crate) const CIRCUIT_BREAKER_COOLDOWN: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ToolCircuitBreakers {
+    circuits: HashMap<String, CircuitState>,
+}
+
+impl ToolCircuitBreakers {
+    /// Returns the remaining cooldown if the breaker for `key` is open.
+    pub(crate) fn open_for(&self, key: &str, now: Instant) -> Option<Duration> {
+        let open_until = self.circuits.get(key)?.open_until?;
+        (open_until > now).then(|| open_until - now)
+    }
+
+    pub(crate) fn record_success(&mut self, key: &str) {
+        self.circuits.remove(key);
+    }
+
+    /// Records a failure and returns `true` when it trips the breaker.
+    ///
+    /// A failure of the probe call let through after a cooldown re-opens the
+    /// breaker immediately.
+    pub(crate) fn record_failure(&mut self, key: &str, now: Instant) -> bool {
+        let circuit = self.circuits.entry(key.to_string()).or_default();
+        circuit.consecutive_failures += 1;
+        if circuit.consecutive_failures < CIRCUIT_BREAKER_FAILURE_THRESHOLD {
+            return false;
+        }
+        circuit.open_until = Some(now + CIRCUIT_BREAKER_COOLDOWN);
+        true
+    }
+}
+
+/// Whether a dispatched call counts as a failure (`Some(true)`), a success
+/// (`Some(false)`), or neither for circuit breaking. Only failures of the tool
+/// itself count; bad arguments or user declines leave the breaker untouched.
+pub(crate) fn call_failed(result: &Result<ResponseInputItem, FunctionCallError>) -> Option<bool> {
+    match result {
+        Ok(_) => Some(false),
+        Err(FunctionCallError::Failed {
+            kind: ToolFailureKind::Timeout | ToolFailureKind::ProviderLimit,
+            ..
+        }) => Some(true),
+        Err(
+            FunctionCallError::Failed { .. }
+            | FunctionCallError::RespondToModel(_)
+            | FunctionCallError::MissingLocalShellCallId
+            | FunctionCallError::Fatal(_),
+        ) => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn trips_after_consecutive_failures_and_recovers() {
+        let mut breakers = ToolCircuitBreakers::default();
+        let now = Instant::now();
+
+        assert!(!breakers.record_failure("docs", now));
+        assert!(!breakers.record_failure("docs", now));
+        assert_eq!(breakers.open_for("docs", now), None);
+        assert!(breakers.record_failure("docs", now));
+        assert_eq!(
+            breakers.open_for("docs", now),
+            Some(CIRCUIT_BREAKER_COOLDOWN)
+        );
+        assert_eq!(breakers.open_for("other", now), None);
+
+        let after_cooldown = now + CIRCUIT_BREAKER_COOLDOWN;
+        assert_eq!(breakers.open_for("docs", after_cooldown), None);
+        // The probe call fails again: re-open right away.
+        assert!(breakers.record_failure("docs", after_cooldown));
+
+        breakers.record_success("docs");
+        assert_eq!(breakers.open_for("docs", after_cooldown), None);
+        assert!(!breakers.record_failure("docs", after_cooldown));
+    }
+
+    #[test]
+    fn success_resets_the_failure_count() {
+        let mut breakers = ToolCircuitBreakers::default();
+        let now = Instant::now();
+
+        breakers.record_failure("docs", now);
+        breakers.record_failure("docs", now);
+        breakers.record_success("docs");
+
+        assert!(!breakers.record_failure("docs", now));
+    }
+}
@@ -4,6 +4,8 @@ use crate::error::CodexErr;
 use crate::error::SandboxErr;
 use crate::exec::ExecToolCallOutput;
 use crate::function_tool::FunctionCallError;
+use crate::function_tool::ToolFailureKind;
+use crate::localization::Message;
 use crate::parse_command::parse_command;
 use crate::protocol::EventMsg;
 use crate::protocol::ExecCommandBeginEvent;
@@ -16,6 +18,7 @@ use crate::protocol::PatchApplyEndEvent;
 use crate::protocol::PatchApplyStatus;
 use crate::protocol::TurnDiffEvent;
 use crate::tools::context::SharedTurnDiffTracker;
+use crate::tools::output_summary::summarize_truncated_output;
 use crate::tools::sandboxing::ToolError;
 use codex_protocol::parse_command::ParsedCommand;
 use std::collections::HashMap;
@@ -23,6 +26,7 @@ use std::path::Path;
 use std::path::PathBuf;
 use std::time::Duration;
 
+use super::ExecProvenance;
 use super::format_exec_output_str;
 
 #[derive(Clone, Copy)]
@@ -94,6 +98,7 @@ pub(crate) enum ToolEmitter {
         source: ExecCommandSource,
         parsed_cmd: Vec<ParsedCommand>,
         freeform: bool,
+        provenance: Option<ExecProvenance>,
     },
     ApplyPatch {
         changes: HashMap<PathBuf, FileChange>,
@@ -122,9 +127,21 @@ impl ToolEmitter {
             source,
             parsed_cmd,
             freeform,
+            provenance: None,
         }
     }
 
+    /// Reports `provenance` with the output of a shell command.
+    pub fn with_provenance(mut self, provenance: ExecProvenance) -> Self {
+        if let Self::Shell {
+            provenance: slot, ..
+        } = &mut self
+        {
+            *slot = Some(provenance);
+        }
+        self
+    }
+
     pub fn apply_patch(changes: HashMap<PathBuf, FileChange>, auto_approved: bool) -> Self {
         Self::ApplyPatch {
             changes,
@@ -283,16 +300,42 @@ impl ToolEmitter {
         self.emit(ctx, ToolEventStage::Begin).await;
     }
 
-    fn format_exec_output_for_model(
+    async fn format_exec_output_for_model(
         &self,
         output: &ExecToolCallOutput,
         ctx: ToolEventCtx<'_>,
+        failure: Option<ToolFailureKind>,
     ) -> String {
-        match self {
-            Self::Shell { freeform: true, .. } => {
-                super::format_exec_output_for_model_freeform(output, ctx.turn.truncation_policy)
+        let provenance = match self {
+            Self::Shell { provenance, .. } => provenance.as_ref(),
+            _ => None,
+        };
+        let summary = match self {
+            Self::Shell { .. } => {
+                let content = super::build_content_with_timeout(output);
+                if content.len() > ctx.turn.truncation_policy.byte_budget() {
+                    summarize_truncated_output(ctx.session, ctx.turn, &content).await
+                } else {
+                    None
+                }
             }
-            _ => super::format_exec_output_for_model_structured(output, ctx.turn.truncation_policy),
+            Self::ApplyPatch { .. } | Self::UnifiedExec { .. } => None,
+        };
+        match self {
+            Self::Shell { freeform: true, .. } => super::format_exec_output_for_model_freeform(
+                output,
+                ctx.turn.truncation_policy,
+                failure,
+                provenance,
+                summary.as_deref(),
+            ),
+            _ => super::format_exec_output_for_model_structured(
+                output,
+                ctx.turn.truncation_policy,
+                failure,
+                provenance,
+                summary.as_deref(),
+            ),
         }
     }
 
@@ -303,7 +346,7 @@ impl ToolEmitter {
     ) -> Result<String, FunctionCallError> {
         let (event, result) = match out {
             Ok(output) => {
-                let content = self.format_exec_output_for_model(&output, ctx);
+                let content = self.format_exec_output_for_model(&output, ctx, None).await;
                 let exit_code = output.exit_code;
                 let event = ToolEventStage::Success(output);
                 let result = if exit_code == 0 {
@@ -313,17 +356,32 @@ impl ToolEmitter {
                 };
                 (event, result)
             }
-            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output })))
-            | Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output, .. }))) => {
-                let response = self.format_exec_output_for_model(&output, ctx);
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Timeout { output }))) => {
+                let kind = ToolFailureKind::Timeout;
+                // The exec formatters embed the failure details themselves.
+                let message = self
+                    .format_exec_output_for_model(&output, ctx, Some(kind))
+                    .await;
+                let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
+                let result = Err(FunctionCallError::Failed { kind, message });
+                (event, result)
+            }
+            Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied { output, .. }))) => {
+                let kind = ToolFailureKind::SandboxDenied;
+                let message = self
+                    .format_exec_output_for_model(&output, ctx, Some(kind))
+                    .await;
                 let event = ToolEventStage::Failure(ToolEventFailure::Output(*output));
-                let result = Err(FunctionCallError::RespondToModel(response));
+                let result = Err(FunctionCallError::Failed { kind, message });
                 (event, result)
             }
             Err(ToolError::Codex(err)) => {
                 let message = format!("execution error: {err:?}");
                 let event = ToolEventStage::Failure(ToolEventFailure::Message(message.clone()));
-                let result = Err(FunctionCallError::RespondToModel(message));
+                let result = Err(match err.tool_failure_kind() {
+                    Some(kind) => FunctionCallError::failed(kind, message),
+                    None => FunctionCallError::RespondToModel(message),
+                });
                 (event, result)
             }
             Err(ToolError::Rejected(msg)) => {
@@ -336,18 +394,22 @@ impl ToolEmitter {
                 // which means a subset of non-user failures may be reported as Declined.
                 //
                 // TODO: We should add a new ToolError variant for user-declined approvals.
-                let normalized = if msg == "rejected by user" {
-                    match self {
-                        Self::Shell { .. } | Self::UnifiedExec { .. } => {
-                            "exec command rejected by user".to_string()
-                        }
-                        Self::ApplyPatch { .. } => "patch rejected by user".to_string(),
-                    }
+                let declined_by_user = msg == "rejected by user";
+                let normalized = if declined_by_user {
+                    let message = match self {
+                        Self::Shell { .. } | Self::UnifiedExec { .. } => Message::ExecRejected,
+                        Self::ApplyPatch { .. } => Message::PatchRejected,
+                    };
+                    ctx.turn.config.locale.text(message).to_string()
                 } else {
                     msg
                 };
                 let event = ToolEventStage::Failure(ToolEventFailure::Rejected(normalized.clone()));
-                let result = Err(FunctionCallError::RespondToModel(normalized));
+                let result = Err(if declined_by_user {
+                    FunctionCallError::failed(ToolFailureKind::UserCancelled, normalized)
+                } else {
+                    FunctionCallError::RespondToModel(normalized)
+                });
                 (event, result)
             }
         };
@@ -496,6 +558,12 @@ async fn emit_patch_end(
     success: bool,
     status: PatchApplyStatus,
 ) {
+    let anchors = match ctx.turn_diff_tracker {
+        Some(tracker) if status == PatchApplyStatus::Completed => {
+            tracker.lock().await.on_patch_end(&changes)
+        }
+        _ => Vec::new(),
+    };
     ctx.session
         .send_event(
             ctx.turn,
@@ -507,18 +575,25 @@ async fn emit_patch_end(
                 success,
                 changes,
                 status,
+                anchors,
             }),
         )
         .await;
 
     if let Some(tracker) = ctx.turn_diff_tracker {
-        let unified_diff = {
+        let (unified_diff, anchors) = {
             let mut guard = tracker.lock().await;
-            guard.get_unified_diff()
+            (guard.get_unified_diff(), guard.anchors().to_vec())
         };
         if let Ok(Some(unified_diff)) = unified_diff {
             ctx.session
-                .send_event(ctx.turn, EventMsg::TurnDiff(TurnDiffEvent { unified_diff }))
+                .send_event(
+                    ctx.turn,
+                    EventMsg::TurnDiff(TurnDiffEvent {
+                        unified_diff,
+                        anchors,
+                    }),
+                )
                 .await;
         }
     }
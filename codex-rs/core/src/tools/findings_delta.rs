@@ -0,0 +1,168 @@
+//! Deltas between successive runs of `check_build` and `run_tests`.
+//!
+//! The findings of each run (compiler diagnostics, failing tests) are kept per
+//! tool, directory, and command for the rest of the session. A later run of
+//! the same command reports what changed since the previous one instead of
+//! listing every finding once: the findings that went away, the new ones
+//! (split by whether the agent's edits this turn likely introduced them), and
+//! the unchanged ones.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub(crate) struct Finding {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) file: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) line: Option<u32>,
+    /// Severity of a diagnostic, or `failed` for a failing test.
+    pub(crate) kind: String,
+    pub(crate) message: String,
+}
+
+impl Finding {
+    /// What identifies a finding across runs. The line is left out, since
+    /// edits above a finding move it.
+    fn key(&self) -> (&Option<String>, &str, &str) {
+        (&self.file, &self.kind, &self.message)
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+pub(crate) struct FindingsDelta {
+    /// Findings of the previous run that are gone.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) fixed: Vec<Finding>,
+    /// New findings in files the agent edited this turn, and new findings
+    /// without a file (failing tests) when the agent edited any.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) introduced_by_edits: Vec<Finding>,
+    /// New findings elsewhere.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) new: Vec<Finding>,
+    /// Findings of the previous run still present.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub(crate) unchanged: Vec<Finding>,
+}
+
+/// The latest findings of each check or test command run this session.
+#[derive(Debug, Default)]
+pub(crate) struct FindingBaselines {
+    by_source: HashMap<String, Vec<Finding>>,
+}
+
+impl FindingBaselines {
+    /// Records `findings` as the latest from `source` and returns how they
+    /// differ from its previous run, or `None` on its first run. `edited` are
+    /// the files the agent changed this turn.
+    pub(crate) fn record(
+        &mut self,
+        source: String,
+        findings: &[Finding],
+        edited: &[PathBuf],
+    ) -> Option<FindingsDelta> {
+        let previous = self.by_source.insert(source, findings.to_vec())?;
+
+        let mut unmatched: Vec<&Finding> = previous.iter().collect();
+        let mut delta = FindingsDelta::default();
+        for finding in findings {
+            if let Some(index) = unmatched
+                .iter()
+                .position(|previous| previous.key() == finding.key())
+            {
+                unmatched.remove(index);
+                delta.unchanged.push(finding.clone());
+            } else if match &finding.file {
+                Some(file) => edited.iter().any(|path| path.ends_with(file)),
+                None => !edited.is_empty(),
+            } {
+                delta.introduced_by_edits.push(finding.clone());
+            } else {
+                delta.new.push(finding.clone());
+            }
+        }
+        delta.fixed = unmatched.into_iter().cloned().collect();
+        Some(delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn error(file: &str, line: u32, message: &str) -> Finding {
+        Finding {
+            file: Some(file.to_string()),
+            line: Some(line),
+            kind: "error".to_string(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn second_run_reports_fixed_and_introduced_findings() {
+        let mut baselines = FindingBaselines::default();
+        let source = "check_build:/work:cargo check".to_string();
+        let first = [
+            error("src/lib.rs", 10, "mismatched types"),
+            error("src/main.rs", 4, "unresolved import"),
+        ];
+        assert_eq!(baselines.record(source.clone(), &first, &[]), None);
+
+        let second = [
+            error("src/lib.rs", 12, "mismatched types"),
+            error("src/parse.rs", 7, "cannot find value `x`"),
+            error("src/other.rs", 1, "unused variable"),
+        ];
+        let edited = [PathBuf::from("/work/src/parse.rs")];
+        assert_eq!(
+            baselines.record(source, &second, &edited),
+            Some(FindingsDelta {
+                fixed: vec![error("src/main.rs", 4, "unresolved import")],
+                introduced_by_edits: vec![error("src/parse.rs", 7, "cannot find value `x`")],
+                new: vec![error("src/other.rs", 1, "unused variable")],
+                unchanged: vec![error("src/lib.rs", 12, "mismatched types")],
+            })
+        );
+    }
+
+    #[test]
+    fn new_test_failures_are_attributed_to_edits_made_this_turn() {
+        let failed = |name: &str| Finding {
+            file: None,
+            line: None,
+            kind: "failed".to_string(),
+            message: name.to_string(),
+        };
+        let mut baselines = FindingBaselines::default();
+        let source = "run_tests:/work:cargo test".to_string();
+        assert_eq!(baselines.record(source.clone(), &[], &[]), None);
+        assert_eq!(
+            baselines.record(
+                source.clone(),
+                &[failed("parser::tests::parses_empty")],
+                &[PathBuf::from("/work/src/parser.rs")],
+            ),
+            Some(FindingsDelta {
+                introduced_by_edits: vec![failed("parser::tests::parses_empty")],
+                ..FindingsDelta::default()
+            })
+        );
+        assert_eq!(
+            baselines.record(
+                source,
+                &[failed("parser::tests::parses_empty"), failed("flaky")],
+                &[]
+            ),
+            Some(FindingsDelta {
+                new: vec![failed("flaky")],
+                unchanged: vec![failed("parser::tests::parses_empty")],
+                ..FindingsDelta::default()
+            })
+        );
+    }
+}
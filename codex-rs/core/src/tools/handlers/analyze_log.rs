@@ -0,0 +1,477 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::BufRead;
+use std::io::BufReader;
+use std::sync::LazyLock;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_utils_string::take_bytes_at_char_boundary;
+use flate2::read::GzDecoder;
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::read_file::format_line;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Reads a log file of any size outside the context window and returns a
+/// compact JSON report: line counts by level, the time range, warnings and
+/// errors clustered by message template, and deduplicated stack traces.
+pub struct AnalyzeLogHandler;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+const MAX_TRACES: usize = 10;
+/// Frames shown of each stack trace.
+const MAX_TRACE_FRAMES: usize = 8;
+/// Frames kept while a stack trace is read; the rest are only counted.
+const MAX_COLLECTED_FRAMES: usize = 200;
+const MAX_MATCH_LINES: usize = 10;
+/// Distinct clusters and traces tracked before further ones are only
+/// counted, so a log of unique messages cannot exhaust memory.
+const MAX_TRACKED: usize = 10_000;
+/// How far into a line its level and timestamp are looked for.
+const LEVEL_SCAN_BYTES: usize = 200;
+const TIMESTAMP_SCAN_BYTES: usize = 64;
+
+static LEVEL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)\b(trace|debug|info|notice|warn|warning|error|err|fatal|critical|crit|panic)\b",
+    )
+    .unwrap_or_else(|err| panic!("invalid level regex: {err}"))
+});
+static TIMESTAMP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:[.,]\d+)?(?:Z|[+-]\d{2}:?\d{2})?|\b(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec) [ \d]\d \d{2}:\d{2}:\d{2}\b",
+    )
+    .unwrap_or_else(|err| panic!("invalid timestamp regex: {err}"))
+});
+/// Lines that continue a stack trace: Java, JavaScript, Python, and Rust
+/// frames, file:line locations, and the headers between them.
+static TRACE_LINE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^(?:\s+(?:at\s|File "|\d+:\s|\.\.\.\s\d+\smore|\S+\.\w+:\d+)|Caused by:|Traceback \(most recent call last\):|stack backtrace:)"#,
+    )
+    .unwrap_or_else(|err| panic!("invalid trace regex: {err}"))
+});
+/// The variable parts of a message, replaced to get its template.
+static VARIABLES: LazyLock<[(Regex, &'static str); 4]> = LazyLock::new(|| {
+    [
+        (
+            r"[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}",
+            "<uuid>",
+        ),
+        (r"0x[0-9a-fA-F]+", "<hex>"),
+        (r#""[^"]*"|'[^']*'"#, "<str>"),
+        (r"\d+(?:\.\d+)*", "<n>"),
+    ]
+    .map(|(pattern, placeholder)| {
+        (
+            Regex::new(pattern).unwrap_or_else(|err| panic!("invalid regex {pattern}: {err}")),
+            placeholder,
+        )
+    })
+});
+static HEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"0x[0-9a-fA-F]+").unwrap_or_else(|err| panic!("invalid hex regex: {err}"))
+});
+
+#[derive(Deserialize)]
+struct AnalyzeLogArgs {
+    path: String,
+    #[serde(default)]
+    pattern: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct LogReport {
+    lines: u64,
+    bytes: u64,
+    /// Lines by level; lines without a recognizable level are not counted.
+    levels: BTreeMap<&'static str, u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    first_timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_timestamp: Option<String>,
+    /// Warnings and errors grouped by message with numbers, ids, and quoted
+    /// strings replaced, most severe and most frequent first.
+    clusters: Vec<Cluster>,
+    #[serde(skip_serializing_if = "is_zero")]
+    more_clusters: usize,
+    stack_traces: Vec<StackTrace>,
+    #[serde(skip_serializing_if = "is_zero")]
+    more_stack_traces: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    matches: Option<PatternMatches>,
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)] // serde passes fields by reference
+fn is_zero(count: &usize) -> bool {
+    *count == 0
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Cluster {
+    level: &'static str,
+    template: String,
+    count: u64,
+    first_line: u64,
+    last_line: u64,
+    example: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct StackTrace {
+    count: u64,
+    first_line: u64,
+    /// The line the first occurrence followed, usually the error it belongs to.
+    head: String,
+    frames: Vec<String>,
+    #[serde(skip_serializing_if = "is_zero")]
+    more_frames: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct PatternMatches {
+    count: u64,
+    lines: Vec<MatchedLine>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct MatchedLine {
+    line: u64,
+    text: String,
+}
+
+#[async_trait]
+impl ToolHandler for AnalyzeLogHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { turn, payload, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "analyze_log handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: AnalyzeLogArgs = parse_arguments(&arguments)?;
+        if args.limit == 0 || args.limit > MAX_LIMIT {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "limit must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+        let pattern = args
+            .pattern
+            .as_deref()
+            .map(Regex::new)
+            .transpose()
+            .map_err(|err| FunctionCallError::RespondToModel(format!("invalid pattern: {err}")))?;
+
+        let path = turn.resolve_path(Some(args.path));
+        let report = tokio::task::spawn_blocking(move || {
+            let file = File::open(&path)?;
+            if path.extension().is_some_and(|extension| extension == "gz") {
+                analyze_log(
+                    BufReader::new(GzDecoder::new(file)),
+                    pattern.as_ref(),
+                    args.limit,
+                )
+            } else {
+                analyze_log(BufReader::new(file), pattern.as_ref(), args.limit)
+            }
+        })
+        .await
+        .map_err(|err| FunctionCallError::RespondToModel(format!("log analysis failed: {err}")))?
+        .map_err(|err| FunctionCallError::RespondToModel(format!("failed to read log: {err}")))?;
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(serde_json::to_string(&report).unwrap_or_default()),
+            success: Some(true),
+        })
+    }
+}
+
+/// A stack trace being read: the line it follows and its frames so far.
+struct OpenTrace {
+    head_line: u64,
+    head: String,
+    frames: Vec<String>,
+    frame_count: usize,
+}
+
+fn analyze_log(
+    mut reader: impl BufRead,
+    pattern: Option<&Regex>,
+    limit: usize,
+) -> std::io::Result<LogReport> {
+    let mut report = LogReport::default();
+    let mut clusters: HashMap<(&'static str, String), Cluster> = HashMap::new();
+    let mut traces: HashMap<String, StackTrace> = HashMap::new();
+    let mut untracked_clusters = 0;
+    let mut untracked_traces = 0;
+    let mut matches = pattern.map(|_| PatternMatches {
+        count: 0,
+        lines: Vec::new(),
+    });
+    let mut head: Option<(u64, String)> = None;
+    let mut open_trace: Option<OpenTrace> = None;
+
+    let mut finish_trace = |trace: OpenTrace, traces: &mut HashMap<String, StackTrace>| {
+        if trace.frame_count < 2 {
+            return;
+        }
+        let key = trace
+            .frames
+            .iter()
+            .map(|frame| HEX.replace_all(frame, "<hex>"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        if let Some(existing) = traces.get_mut(&key) {
+            existing.count += 1;
+        } else if traces.len() < MAX_TRACKED {
+            traces.insert(
+                key,
+                StackTrace {
+                    count: 1,
+                    first_line: trace.head_line,
+                    head: trace.head,
+                    more_frames: trace.frame_count.saturating_sub(MAX_TRACE_FRAMES),
+                    frames: trace.frames.into_iter().take(MAX_TRACE_FRAMES).collect(),
+                },
+            );
+        } else {
+            untracked_traces += 1;
+        }
+    };
+
+    let mut buffer = Vec::new();
+    loop {
+        buffer.clear();
+        let read = reader.read_until(b'\n', &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        report.lines += 1;
+        report.bytes += read as u64;
+        let line_number = report.lines;
+        let line = String::from_utf8_lossy(&buffer);
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        if let (Some(pattern), Some(matches)) = (pattern, matches.as_mut())
+            && pattern.is_match(line)
+        {
+            matches.count += 1;
+            if matches.lines.len() < MAX_MATCH_LINES {
+                matches.lines.push(MatchedLine {
+                    line: line_number,
+                    text: format_line(line.as_bytes()),
+                });
+            }
+        }
+
+        if TRACE_LINE.is_match(line)
+            && let Some((head_line, head)) = head.as_ref()
+        {
+            let trace = open_trace.get_or_insert_with(|| OpenTrace {
+                head_line: *head_line,
+                head: head.clone(),
+                frames: Vec::new(),
+                frame_count: 0,
+            });
+            trace.frame_count += 1;
+            if trace.frames.len() < MAX_COLLECTED_FRAMES {
+                trace.frames.push(format_line(line.trim().as_bytes()));
+            }
+            continue;
+        }
+        if let Some(trace) = open_trace.take() {
+            finish_trace(trace, &mut traces);
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        head = Some((line_number, format_line(line.as_bytes())));
+
+        if let Some(timestamp) =
+            TIMESTAMP.find(take_bytes_at_char_boundary(line, TIMESTAMP_SCAN_BYTES))
+        {
+            if report.first_timestamp.is_none() {
+                report.first_timestamp = Some(timestamp.as_str().to_string());
+            }
+            report.last_timestamp = Some(timestamp.as_str().to_string());
+        }
+
+        let Some(level_match) = LEVEL.find(take_bytes_at_char_boundary(line, LEVEL_SCAN_BYTES))
+        else {
+            continue;
+        };
+        let level = match level_match.as_str().to_ascii_lowercase().as_str() {
+            "trace" => "trace",
+            "debug" => "debug",
+            "info" | "notice" => "info",
+            "warn" | "warning" => "warn",
+            "error" | "err" => "error",
+            _ => "fatal",
+        };
+        *report.levels.entry(level).or_default() += 1;
+        if !matches!(level, "warn" | "error" | "fatal") {
+            continue;
+        }
+
+        let message = line[level_match.end()..]
+            .trim_start_matches(|c: char| !c.is_alphanumeric() && c != '<' && c != '"');
+        let template =
+            VARIABLES
+                .iter()
+                .fold(message.to_string(), |template, (regex, placeholder)| {
+                    regex.replace_all(&template, *placeholder).into_owned()
+                });
+        let key = (level, format_line(template.as_bytes()));
+        if let Some(cluster) = clusters.get_mut(&key) {
+            cluster.count += 1;
+            cluster.last_line = line_number;
+        } else if clusters.len() < MAX_TRACKED {
+            clusters.insert(
+                key.clone(),
+                Cluster {
+                    level,
+                    template: key.1,
+                    count: 1,
+                    first_line: line_number,
+                    last_line: line_number,
+                    example: format_line(line.as_bytes()),
+                },
+            );
+        } else {
+            untracked_clusters += 1;
+        }
+    }
+    if let Some(trace) = open_trace.take() {
+        finish_trace(trace, &mut traces);
+    }
+
+    let severity = |level: &str| match level {
+        "fatal" => 0,
+        "error" => 1,
+        _ => 2,
+    };
+    let mut clusters: Vec<Cluster> = clusters.into_values().collect();
+    clusters.sort_by(|a, b| {
+        severity(a.level)
+            .cmp(&severity(b.level))
+            .then(b.count.cmp(&a.count))
+            .then(a.first_line.cmp(&b.first_line))
+    });
+    report.more_clusters = clusters.len().saturating_sub(limit) + untracked_clusters;
+    clusters.truncate(limit);
+    report.clusters = clusters;
+
+    let mut traces: Vec<StackTrace> = traces.into_values().collect();
+    traces.sort_by(|a, b| b.count.cmp(&a.count).then(a.first_line.cmp(&b.first_line)));
+    report.more_stack_traces = traces.len().saturating_sub(MAX_TRACES) + untracked_traces;
+    traces.truncate(MAX_TRACES);
+    report.stack_traces = traces;
+    report.matches = matches;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn report_clusters_errors_and_deduplicates_stack_traces() {
+        let log = "\
+2026-03-01T10:00:00Z INFO server started on port 8080
+2026-03-01T10:00:01Z ERROR request 41 failed: user \"ann\" not found
+\tat com.example.Users.find(Users.java:42)
+\tat com.example.Api.handle(Api.java:17)
+2026-03-01T10:00:02Z WARN slow query took 1200 ms
+2026-03-01T10:00:03Z ERROR request 42 failed: user \"bob\" not found
+\tat com.example.Users.find(Users.java:42)
+\tat com.example.Api.handle(Api.java:17)
+2026-03-01T10:00:04Z DEBUG cache hit
+";
+        let pattern = Regex::new("request \\d+").expect("pattern");
+        let report = analyze_log(log.as_bytes(), Some(&pattern), 5).expect("analyze");
+
+        assert_eq!(
+            report,
+            LogReport {
+                lines: 9,
+                bytes: log.len() as u64,
+                levels: BTreeMap::from([("debug", 1), ("error", 2), ("info", 1), ("warn", 1)]),
+                first_timestamp: Some("2026-03-01T10:00:00Z".to_string()),
+                last_timestamp: Some("2026-03-01T10:00:04Z".to_string()),
+                clusters: vec![
+                    Cluster {
+                        level: "error",
+                        template: "request <n> failed: user <str> not found".to_string(),
+                        count: 2,
+                        first_line: 2,
+                        last_line: 6,
+                        example: "2026-03-01T10:00:01Z ERROR request 41 failed: user \"ann\" not found"
+                            .to_string(),
+                    },
+                    Cluster {
+                        level: "warn",
+                        template: "slow query took <n> ms".to_string(),
+                        count: 1,
+                        first_line: 5,
+                        last_line: 5,
+                        example: "2026-03-01T10:00:02Z WARN slow query took 1200 ms".to_string(),
+                    },
+                ],
+                more_clusters: 0,
+                stack_traces: vec![StackTrace {
+                    count: 2,
+                    first_line: 2,
+                    head: "2026-03-01T10:00:01Z ERROR request 41 failed: user \"ann\" not found"
+                        .to_string(),
+                    frames: vec![
+                        "at com.example.Users.find(Users.java:42)".to_string(),
+                        "at com.example.Api.handle(Api.java:17)".to_string(),
+                    ],
+                    more_frames: 0,
+                }],
+                more_stack_traces: 0,
+                matches: Some(PatternMatches {
+                    count: 2,
+                    lines: vec![
+                        MatchedLine {
+                            line: 2,
+                            text: "2026-03-01T10:00:01Z ERROR request 41 failed: user \"ann\" not found"
+                                .to_string(),
+                        },
+                        MatchedLine {
+                            line: 6,
+                            text: "2026-03-01T10:00:03Z ERROR request 42 failed: user \"bob\" not found"
+                                .to_string(),
+                        },
+                    ],
+                }),
+            }
+        );
+    }
+}
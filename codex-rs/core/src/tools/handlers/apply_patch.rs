@@ -20,6 +20,7 @@ use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::orchestrator::ToolOrchestrator;
+use crate::tools::progress::ToolProgress;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use crate::tools::runtimes::apply_patch::ApplyPatchRequest;
@@ -29,7 +30,9 @@ use crate::tools::spec::ApplyPatchToolArgs;
 use crate::tools::spec::JsonSchema;
 use async_trait::async_trait;
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::ApplyPatchArgs;
 use codex_apply_patch::ApplyPatchFileChange;
+use codex_apply_patch::MaybeApplyPatch;
 use codex_utils_absolute_path::AbsolutePathBuf;
 
 pub struct ApplyPatchHandler;
@@ -60,6 +63,28 @@ fn to_abs_path(cwd: &Path, path: &Path) -> Option<AbsolutePathBuf> {
     AbsolutePathBuf::resolve_path_against_base(path, cwd).ok()
 }
 
+/// In a shadow workspace, refuses patches that would write outside the
+/// shadow copy, such as absolute paths into the real repository.
+fn check_shadow_workspace(
+    session: &Session,
+    action: &ApplyPatchAction,
+) -> Result<(), FunctionCallError> {
+    let Some(shadow) = &session.services.shadow_workspace else {
+        return Ok(());
+    };
+    match file_paths_for_action(action)
+        .into_iter()
+        .find(|path| !shadow.contains(path.as_path()))
+    {
+        Some(path) => Err(FunctionCallError::RespondToModel(format!(
+            "{} is outside the shadow workspace; edits must stay in the shadow copy under {}",
+            path.display(),
+            shadow.cwd().display()
+        ))),
+        None => Ok(()),
+    }
+}
+
 #[async_trait]
 impl ToolHandler for ApplyPatchHandler {
     fn kind(&self) -> ToolKind {
@@ -78,6 +103,7 @@ impl ToolHandler for ApplyPatchHandler {
     }
 
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let progress = ToolProgress::new(&invocation);
         let ToolInvocation {
             session,
             turn,
@@ -104,8 +130,15 @@ impl ToolHandler for ApplyPatchHandler {
         // Avoid building temporary ExecParams/command vectors; derive directly from inputs.
         let cwd = turn.cwd.clone();
         let command = vec!["apply_patch".to_string(), patch_input.clone()];
-        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd) {
+        let fuzz_window = turn.config.apply_patch_fuzz_window;
+        if let Some(output) =
+            record_in_patch_artifact(session.as_ref(), &command, &cwd, fuzz_window).await
+        {
+            return output;
+        }
+        match codex_apply_patch::maybe_parse_apply_patch_verified(&command, &cwd, fuzz_window) {
             codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
+                check_shadow_workspace(session.as_ref(), &changes)?;
                 match apply_patch::apply_patch(turn.as_ref(), changes).await {
                     InternalApplyPatchInvocation::Output(item) => {
                         let content = item?;
@@ -126,6 +159,10 @@ impl ToolHandler for ApplyPatchHandler {
                             Some(&tracker),
                         );
                         emitter.begin(event_ctx).await;
+                        let files = u64::try_from(changes.len()).unwrap_or(u64::MAX);
+                        progress
+                            .report(0, Some(files), Some(format!("Patching {files} files")))
+                            .await;
 
                         let req = ApplyPatchRequest {
                             action: apply.action,
@@ -134,6 +171,7 @@ impl ToolHandler for ApplyPatchHandler {
                             exec_approval_requirement: apply.exec_approval_requirement,
                             timeout_ms: None,
                             codex_exe: turn.codex_linux_sandbox_exe.clone(),
+                            fuzz_window,
                         };
 
                         let mut orchestrator = ToolOrchestrator::new();
@@ -154,6 +192,9 @@ impl ToolHandler for ApplyPatchHandler {
                             )
                             .await
                             .map(|result| result.output);
+                        if out.is_ok() {
+                            progress.report(files, Some(files), None).await;
+                        }
                         let event_ctx = ToolEventCtx::new(
                             session.as_ref(),
                             turn.as_ref(),
@@ -188,6 +229,43 @@ impl ToolHandler for ApplyPatchHandler {
     }
 }
 
+/// In patch artifact mode, records the patch in `command` instead of applying
+/// it. Returns `None` when the session writes to the tree or `command` is not
+/// an `apply_patch` invocation.
+async fn record_in_patch_artifact(
+    session: &Session,
+    command: &[String],
+    cwd: &Path,
+    fuzz_window: usize,
+) -> Option<Result<ToolOutput, FunctionCallError>> {
+    let artifact = session.services.patch_artifact.as_ref()?;
+    let ApplyPatchArgs { hunks, workdir, .. } =
+        match codex_apply_patch::maybe_parse_apply_patch(command) {
+            MaybeApplyPatch::Body(args) => args,
+            MaybeApplyPatch::PatchParseError(err) => {
+                return Some(Err(FunctionCallError::RespondToModel(format!(
+                    "apply_patch verification failed: {err}"
+                ))));
+            }
+            MaybeApplyPatch::ShellParseError(_) | MaybeApplyPatch::NotApplyPatch => return None,
+        };
+    let cwd = workdir.map_or_else(|| cwd.to_path_buf(), |dir| cwd.join(dir));
+
+    let mut artifact = artifact.lock().await;
+    Some(match artifact.record(&hunks, &cwd, fuzz_window) {
+        Ok(summary) => {
+            artifact.write().await;
+            Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(summary),
+                success: Some(true),
+            })
+        }
+        Err(message) => Err(FunctionCallError::RespondToModel(format!(
+            "apply_patch verification failed: {message}"
+        ))),
+    })
+}
+
 #[allow(clippy::too_many_arguments)]
 pub(crate) async fn intercept_apply_patch(
     command: &[String],
@@ -199,8 +277,13 @@ pub(crate) async fn intercept_apply_patch(
     call_id: &str,
     tool_name: &str,
 ) -> Result<Option<ToolOutput>, FunctionCallError> {
-    match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd) {
+    let fuzz_window = turn.config.apply_patch_fuzz_window;
+    if let Some(output) = record_in_patch_artifact(session, command, cwd, fuzz_window).await {
+        return output.map(Some);
+    }
+    match codex_apply_patch::maybe_parse_apply_patch_verified(command, cwd, fuzz_window) {
         codex_apply_patch::MaybeApplyPatchVerified::Body(changes) => {
+            check_shadow_workspace(session, &changes)?;
             session
                 .record_model_warning(
                     format!("apply_patch was requested via {tool_name}. Use the apply_patch tool instead of exec_command."),
@@ -230,6 +313,7 @@ pub(crate) async fn intercept_apply_patch(
                         exec_approval_requirement: apply.exec_approval_requirement,
                         timeout_ms,
                         codex_exe: turn.codex_linux_sandbox_exe.clone(),
+                        fuzz_window,
                     };
 
                     let mut orchestrator = ToolOrchestrator::new();
@@ -399,7 +483,7 @@ mod tests {
 +new content
 *** End Patch"#;
         let argv = vec!["apply_patch".to_string(), patch.to_string()];
-        let action = match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd) {
+        let action = match codex_apply_patch::maybe_parse_apply_patch_verified(&argv, cwd, 0) {
             MaybeApplyPatchVerified::Body(action) => action,
             other => panic!("expected patch body, got: {other:?}"),
         };
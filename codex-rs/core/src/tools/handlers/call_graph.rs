@@ -0,0 +1,472 @@
+use codex_protocol::models::FunctionCallOutputBody;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+use tree_sitter::Node;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::read_file::format_line;
+use crate::tools::handlers::read_symbol::SourceGrammar;
+use crate::tools::handlers::read_symbol::go_receiver_type;
+use crate::tools::handlers::read_symbol::is_qualified_by;
+use crate::tools::handlers::read_symbol::split_symbol;
+use crate::tools::handlers::read_symbol::walk_definitions;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::registry::ToolPriority;
+
+/// Answers `who_calls` and `what_does_this_call` from the syntax trees of the
+/// workspace's source files, so the model can see the callers and callees of a
+/// definition without grepping for a name that collides across modules.
+///
+/// Calls are resolved syntactically: a call through a type path
+/// (`Parser::parse`) must name the symbol's qualifiers, but a call on a value
+/// (`parser.parse()`) is matched by name alone, since its type is unknown.
+pub struct CallGraphHandler;
+
+pub(crate) const WHO_CALLS_TOOL_NAME: &str = "who_calls";
+pub(crate) const WHAT_DOES_THIS_CALL_TOOL_NAME: &str = "what_does_this_call";
+
+/// Most files `who_calls` parses.
+const MAX_FILES: usize = 500;
+/// Most call sites `who_calls` returns.
+const MAX_CALL_SITES: usize = 50;
+/// Most definitions `what_does_this_call` describes.
+const MAX_DEFINITIONS: usize = 5;
+/// Most callees listed for one definition.
+const MAX_CALLEES: usize = 100;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const SOURCE_GLOBS: &[&str] = &[
+    "*.rs", "*.py", "*.pyi", "*.js", "*.jsx", "*.mjs", "*.cjs", "*.ts", "*.mts", "*.cts", "*.tsx",
+    "*.go",
+];
+/// Receivers that refer to the type enclosing the call.
+const SELF_RECEIVERS: &[&str] = &["Self", "self", "this", "cls"];
+
+#[derive(Deserialize)]
+struct WhoCallsArgs {
+    symbol: String,
+    #[serde(default)]
+    path: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct WhatDoesThisCallArgs {
+    path: String,
+    symbol: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct CallSite {
+    line: usize,
+    caller: String,
+    text: String,
+}
+
+#[async_trait]
+impl ToolHandler for CallGraphHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_cache_eligible(&self) -> bool {
+        true
+    }
+
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Interactive
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            turn,
+            tool_name,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "{tool_name} handler received unsupported payload"
+                )));
+            }
+        };
+
+        let body = match tool_name.as_str() {
+            WHO_CALLS_TOOL_NAME => {
+                let args: WhoCallsArgs = parse_arguments(&arguments)?;
+                let search_path = turn.resolve_path(args.path);
+                who_calls(&search_path, &turn.cwd, args.symbol.trim()).await?
+            }
+            WHAT_DOES_THIS_CALL_TOOL_NAME => {
+                let args: WhatDoesThisCallArgs = parse_arguments(&arguments)?;
+                let path = turn.resolve_path(Some(args.path));
+                let source = tokio::fs::read_to_string(&path).await.map_err(|err| {
+                    FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+                })?;
+                what_does_this_call(&path, &source, args.symbol.trim())?
+            }
+            other => {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "unsupported call graph tool {other}"
+                )));
+            }
+        };
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(body),
+            success: Some(true),
+        })
+    }
+}
+
+async fn who_calls(
+    search_path: &Path,
+    cwd: &Path,
+    symbol: &str,
+) -> Result<String, FunctionCallError> {
+    let (name, qualifiers) = split_symbol(symbol)?;
+    let files = files_mentioning(name, search_path, cwd).await?;
+    let truncated_files = files.len() > MAX_FILES;
+
+    let mut found = Vec::new();
+    for file in files.into_iter().take(MAX_FILES) {
+        let Ok(source) = tokio::fs::read_to_string(&file).await else {
+            continue;
+        };
+        let Ok(sites) = call_sites(&file, &source, name, &qualifiers) else {
+            continue;
+        };
+        let display = file
+            .strip_prefix(cwd)
+            .unwrap_or(&file)
+            .display()
+            .to_string();
+        found.extend(sites.into_iter().map(|site| (display.clone(), site)));
+    }
+
+    if found.is_empty() {
+        return Ok(format!("No call sites of `{symbol}` found."));
+    }
+    let mut output = vec![format!("{} call sites of `{symbol}`:", found.len())];
+    for (path, site) in found.iter().take(MAX_CALL_SITES) {
+        let caller = if site.caller.is_empty() {
+            String::new()
+        } else {
+            format!(" in {}", site.caller)
+        };
+        output.push(format!("{path}:{}{caller}: {}", site.line, site.text));
+    }
+    if found.len() > MAX_CALL_SITES {
+        output.push(format!(
+            "[{} more call sites omitted; qualify the symbol or narrow `path`]",
+            found.len() - MAX_CALL_SITES
+        ));
+    }
+    if truncated_files {
+        output.push(format!(
+            "[only the first {MAX_FILES} files mentioning `{name}` were searched; narrow `path`]"
+        ));
+    }
+    Ok(output.join("\n"))
+}
+
+/// The supported source files under `search_path` that mention `name` as a
+/// word.
+async fn files_mentioning(
+    name: &str,
+    search_path: &Path,
+    cwd: &Path,
+) -> Result<Vec<PathBuf>, FunctionCallError> {
+    let mut command = Command::new("rg");
+    command
+        .current_dir(cwd)
+        .arg("--files-with-matches")
+        .arg("--word-regexp")
+        .arg("--fixed-strings")
+        .arg("--sort=path")
+        .arg("--no-messages");
+    for glob in SOURCE_GLOBS {
+        command.arg("--glob").arg(glob);
+    }
+    command.arg("--regexp").arg(name).arg("--").arg(search_path);
+
+    let output = timeout(COMMAND_TIMEOUT, command.output())
+        .await
+        .map_err(|_| {
+            FunctionCallError::RespondToModel("rg timed out after 30 seconds".to_string())
+        })?
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to launch rg: {err}. Ensure ripgrep is installed and on PATH."
+            ))
+        })?;
+
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(|line| cwd.join(line))
+            .collect()),
+        Some(1) => Ok(Vec::new()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(FunctionCallError::RespondToModel(format!(
+                "rg failed: {stderr}"
+            )))
+        }
+    }
+}
+
+/// The calls in `source` that may reach `qualifiers::name`, in source order.
+fn call_sites(
+    path: &Path,
+    source: &str,
+    name: &str,
+    qualifiers: &[&str],
+) -> Result<Vec<CallSite>, FunctionCallError> {
+    let grammar = SourceGrammar::for_path(path)?;
+    let tree = grammar.parse(path, source)?;
+    let separator = separator_for(path);
+    let lines: Vec<&str> = source.lines().collect();
+
+    let mut sites = Vec::new();
+    walk_definitions(&tree, source, &grammar, |node, _, containers| {
+        let Some(callee) = callee(node, source, &grammar) else {
+            return;
+        };
+        let mut segments = callee_segments(&callee);
+        if segments.pop() != Some(name) {
+            return;
+        }
+        let scope: Vec<&str> = containers
+            .iter()
+            .map(|(container, _)| container.as_str())
+            .collect();
+        let in_qualifying_scope = qualifiers.is_empty()
+            || scope
+                .windows(qualifiers.len())
+                .any(|window| window == qualifiers);
+        let may_call = match segments.as_slice() {
+            [] => in_qualifying_scope,
+            [receiver] if SELF_RECEIVERS.contains(receiver) => in_qualifying_scope,
+            [first, ..] if first.starts_with(char::is_uppercase) => segments.ends_with(qualifiers),
+            _ => true,
+        };
+        if may_call {
+            let row = node.start_position().row;
+            sites.push(CallSite {
+                line: row + 1,
+                caller: qualified_name(containers, source, separator),
+                text: format_line(lines.get(row).unwrap_or(&"").trim().as_bytes()),
+            });
+        }
+    });
+    Ok(sites)
+}
+
+/// A definition of the symbol, its qualified name, and the calls in it with
+/// their lines.
+type Definition<'tree> = (Node<'tree>, String, Vec<(usize, String)>);
+
+fn what_does_this_call(
+    path: &Path,
+    source: &str,
+    symbol: &str,
+) -> Result<String, FunctionCallError> {
+    let (name, qualifiers) = split_symbol(symbol)?;
+    let grammar = SourceGrammar::for_path(path)?;
+    let tree = grammar.parse(path, source)?;
+    let separator = separator_for(path);
+
+    let mut definitions: Vec<Definition> = Vec::new();
+    walk_definitions(&tree, source, &grammar, |node, defined, containers| {
+        if defined == Some(name) && is_qualified_by(node, source, containers, &qualifiers) {
+            let mut chain = containers.to_vec();
+            chain.push((name.to_string(), node));
+            definitions.push((node, qualified_name(&chain, source, separator), Vec::new()));
+        }
+        let Some(callee) = callee(node, source, &grammar) else {
+            return;
+        };
+        for (_, _, callees) in definitions.iter_mut().filter(|(definition, _, _)| {
+            containers
+                .iter()
+                .any(|(_, container)| container.id() == definition.id())
+        }) {
+            if !callees.iter().any(|(_, seen)| *seen == callee) {
+                callees.push((node.start_position().row + 1, callee.clone()));
+            }
+        }
+    });
+
+    if definitions.is_empty() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "no definition of `{symbol}` found in `{}`",
+            path.display()
+        )));
+    }
+    let mut output = Vec::new();
+    for (definition, qualified, callees) in definitions.iter().take(MAX_DEFINITIONS) {
+        let mut section = vec![format!(
+            "{}:{}-{} {qualified} calls:",
+            path.display(),
+            definition.start_position().row + 1,
+            definition.end_position().row + 1
+        )];
+        if callees.is_empty() {
+            section.push("(nothing)".to_string());
+        }
+        section.extend(
+            callees
+                .iter()
+                .take(MAX_CALLEES)
+                .map(|(line, callee)| format!("L{line}: {callee}")),
+        );
+        if callees.len() > MAX_CALLEES {
+            section.push(format!(
+                "[{} more callees omitted]",
+                callees.len() - MAX_CALLEES
+            ));
+        }
+        output.push(section.join("\n"));
+    }
+    if definitions.len() > MAX_DEFINITIONS {
+        output.push(format!(
+            "[{} more definitions of `{symbol}` omitted; qualify the symbol to narrow it down]",
+            definitions.len() - MAX_DEFINITIONS
+        ));
+    }
+    Ok(output.join("\n\n"))
+}
+
+/// The callee of `node` if it is a call, with whitespace removed so chained
+/// calls split across lines read as one expression.
+fn callee(node: Node, source: &str, grammar: &SourceGrammar) -> Option<String> {
+    let (_, callee_field) = grammar
+        .calls
+        .iter()
+        .find(|(kind, _)| *kind == node.kind())?;
+    let text = node
+        .child_by_field_name(callee_field)?
+        .utf8_text(source.as_bytes())
+        .ok()?;
+    Some(format_line(
+        text.split_whitespace().collect::<String>().as_bytes(),
+    ))
+}
+
+/// The path segments of a callee, so `self.items.iter().map` becomes
+/// `[self, items, iter, map]` and `Vec::<u8>::new` becomes `[Vec, new]`.
+fn callee_segments(callee: &str) -> Vec<&str> {
+    callee
+        .split("::")
+        .flat_map(|segment| segment.split('.'))
+        .map(|segment| {
+            segment
+                .split(['(', '<', '[', '?'])
+                .next()
+                .unwrap_or(segment)
+        })
+        .filter(|segment| !segment.is_empty())
+        .collect()
+}
+
+/// The name of the innermost definition in `containers`, qualified by the ones
+/// enclosing it and, for a Go method, by its receiver type.
+fn qualified_name(containers: &[(String, Node)], source: &str, separator: &str) -> String {
+    let mut names = Vec::new();
+    for (name, node) in containers {
+        names.extend(go_receiver_type(*node, source));
+        names.push(name.clone());
+    }
+    names.join(separator)
+}
+
+fn separator_for(path: &Path) -> &'static str {
+    if path.extension().is_some_and(|extension| extension == "rs") {
+        "::"
+    } else {
+        "."
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SOURCE: &str = "\
+struct Parser;
+
+impl Parser {
+    fn parse(&self) {}
+
+    fn parse_twice(&self) {
+        self.parse();
+        Self::parse(self);
+    }
+}
+
+fn main() {
+    let parser = Parser;
+    parser.parse();
+    Parser::parse(&parser);
+    Other::parse();
+    parse();
+}
+";
+
+    #[test]
+    fn who_calls_skips_calls_through_other_types() {
+        let site = |line, caller: &str, text: &str| CallSite {
+            line,
+            caller: caller.to_string(),
+            text: text.to_string(),
+        };
+        assert_eq!(
+            call_sites(Path::new("main.rs"), SOURCE, "parse", &["Parser"]).expect("parse"),
+            vec![
+                site(7, "Parser::parse_twice", "self.parse();"),
+                site(8, "Parser::parse_twice", "Self::parse(self);"),
+                site(14, "main", "parser.parse();"),
+                site(15, "main", "Parser::parse(&parser);"),
+            ]
+        );
+        assert_eq!(
+            call_sites(Path::new("main.rs"), SOURCE, "parse", &[])
+                .expect("parse")
+                .len(),
+            6
+        );
+    }
+
+    #[test]
+    fn what_does_this_call_lists_each_callee_once() {
+        assert_eq!(
+            what_does_this_call(Path::new("main.rs"), SOURCE, "main").expect("found"),
+            "main.rs:12-18 main calls:
+L14: parser.parse
+L15: Parser::parse
+L16: Other::parse
+L17: parse"
+        );
+        assert_eq!(
+            what_does_this_call(Path::new("main.rs"), SOURCE, "Parser::parse_twice")
+                .expect("found"),
+            "main.rs:6-9 Parser::parse_twice calls:
+L7: self.parse
+L8: Self::parse"
+        );
+    }
+}
@@ -0,0 +1,418 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::models::ShellCommandToolCallParams;
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::exec::ExecToolCallOutput;
+use crate::function_tool::FunctionCallError;
+use crate::git_info::changed_paths;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::findings_delta::Finding;
+use crate::tools::findings_delta::FindingsDelta;
+use crate::tools::handlers::ShellCommandHandler;
+use crate::tools::handlers::ShellHandler;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::run_tests::cargo_packages;
+use crate::tools::handlers::shell::OutputSummary;
+use crate::tools::handlers::shell::RunExecLikeArgs;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Type-checks the code affected by the workspace's uncommitted changes with
+/// the project's own checker and returns its diagnostics as structured data
+/// instead of the checker's output. The command goes through the same approval
+/// and sandboxing as `shell_command`.
+pub struct CheckBuildHandler;
+
+/// Most diagnostics listed; the counts cover all of them.
+const MAX_DIAGNOSTICS: usize = 200;
+
+#[derive(Deserialize)]
+struct CheckBuildArgs {
+    #[serde(default)]
+    build_system: Option<BuildSystem>,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum BuildSystem {
+    Cargo,
+    Tsc,
+    Mypy,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Diagnostic {
+    file: String,
+    line: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<u32>,
+    severity: Severity,
+    message: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct CheckReport {
+    build_system: BuildSystem,
+    command: String,
+    errors: usize,
+    warnings: usize,
+    /// Empty when `delta` is set.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    diagnostics: Vec<Diagnostic>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    /// Changes since the previous run of the same command this session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delta: Option<FindingsDelta>,
+}
+
+#[async_trait]
+impl ToolHandler for CheckBuildHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            call_id,
+            tool_name,
+            payload,
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(
+                "check_build handler received unsupported payload".to_string(),
+            ));
+        };
+        let args: CheckBuildArgs = parse_arguments(&arguments)?;
+
+        let dir = turn.resolve_path(args.workdir.clone());
+        let build_system = match args.build_system {
+            Some(build_system) => build_system,
+            None => BuildSystem::detect(&dir).ok_or_else(|| {
+                FunctionCallError::RespondToModel(format!(
+                    "could not detect a build system in `{}`; pass `build_system` (cargo, \
+                     tsc, or mypy)",
+                    dir.display()
+                ))
+            })?,
+        };
+        let argv = build_system.command(&dir, &changed_paths(&dir).await);
+        let command = shlex::try_join(argv.iter().map(String::as_str)).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to quote check command: {err}"))
+        })?;
+        let findings_source = format!("check_build:{}:{command}", dir.display());
+        let edited = tracker.lock().await.edited_paths();
+        let baseline_session = Arc::clone(&session);
+
+        let params = ShellCommandToolCallParams {
+            command: command.clone(),
+            workdir: args.workdir,
+            login: None,
+            timeout_ms: args.timeout_ms,
+            sandbox_permissions: None,
+            prefix_rule: None,
+            justification: None,
+        };
+        let exec_params = ShellCommandHandler::to_exec_params(
+            &params,
+            session.as_ref(),
+            turn.as_ref(),
+            session.conversation_id,
+            turn.tools_config.allow_login_shell,
+        )?;
+        ShellHandler::run_exec_like(RunExecLikeArgs {
+            tool_name,
+            exec_params,
+            prefix_rule: None,
+            session,
+            turn,
+            tracker,
+            call_id,
+            freeform: true,
+            summarize_output: Some(Box::new(move |output: &ExecToolCallOutput| {
+                let mut report = build_system.report(command, &output.aggregated_output.text);
+                let findings: Vec<Finding> = report
+                    .diagnostics
+                    .iter()
+                    .map(|diagnostic| Finding {
+                        file: Some(diagnostic.file.clone()),
+                        line: Some(diagnostic.line),
+                        kind: match diagnostic.severity {
+                            Severity::Error => "error",
+                            Severity::Warning => "warning",
+                            Severity::Note => "note",
+                        }
+                        .to_string(),
+                        message: diagnostic.message.clone(),
+                    })
+                    .collect();
+                if !report.truncated
+                    // Both tools are mutating, so they run one at a time
+                    // and the baselines are never locked here already.
+                    && let Ok(mut baselines) = baseline_session.services.finding_baselines.try_lock()
+                    && let Some(delta) = baselines.record(findings_source, &findings, &edited)
+                {
+                    report.diagnostics.clear();
+                    report.delta = Some(delta);
+                }
+                // A failed check with no diagnostics failed for some other
+                // reason (a missing tool, a broken manifest), which only its
+                // output explains.
+                let include_output = findings.is_empty() && output.exit_code != 0;
+                OutputSummary {
+                    summary: serde_json::to_string(&report).unwrap_or_default(),
+                    include_output,
+                }
+            })),
+        })
+        .await
+    }
+}
+
+impl BuildSystem {
+    /// The build system of the project rooted at `dir`, judged by its
+    /// manifests.
+    fn detect(dir: &Path) -> Option<Self> {
+        if dir.join("Cargo.toml").is_file() {
+            return Some(Self::Cargo);
+        }
+        if dir.join("tsconfig.json").is_file() {
+            return Some(Self::Tsc);
+        }
+        let configures_mypy = |file: &str, section: &str| {
+            std::fs::read_to_string(dir.join(file)).is_ok_and(|config| config.contains(section))
+        };
+        (dir.join("mypy.ini").is_file()
+            || configures_mypy("pyproject.toml", "[tool.mypy")
+            || configures_mypy("setup.cfg", "[mypy"))
+        .then_some(Self::Mypy)
+    }
+
+    /// The check command for the code owning `changed` (paths relative to
+    /// `dir`). `tsc` always checks the whole project, since a change can break
+    /// any file importing it.
+    fn command(self, dir: &Path, changed: &[PathBuf]) -> Vec<String> {
+        let mut argv: Vec<String> = match self {
+            Self::Cargo => vec!["cargo", "check", "--all-targets", "--message-format=short"],
+            Self::Tsc => vec!["npx", "tsc", "--noEmit", "--pretty", "false"],
+            Self::Mypy => vec![
+                "mypy",
+                "--show-column-numbers",
+                "--no-error-summary",
+                "--no-color-output",
+            ],
+        }
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        match self {
+            Self::Cargo => {
+                for package in cargo_packages(dir, changed) {
+                    argv.extend(["-p".to_string(), package]);
+                }
+            }
+            Self::Tsc => {}
+            Self::Mypy => {
+                let files: Vec<String> = changed
+                    .iter()
+                    .filter(|path| {
+                        path.extension()
+                            .is_some_and(|extension| extension == "py" || extension == "pyi")
+                            && dir.join(path).is_file()
+                    })
+                    .map(|path| path.display().to_string())
+                    .collect();
+                if files.is_empty() {
+                    argv.push(".".to_string());
+                } else {
+                    argv.extend(files);
+                }
+            }
+        }
+        argv
+    }
+
+    /// The diagnostics the checker printed in `output`.
+    fn report(self, command: String, output: &str) -> CheckReport {
+        #[expect(clippy::expect_used)]
+        let line = Regex::new(match self {
+            // src/lib.rs:10:5: error[E0308]: mismatched types
+            Self::Cargo => r"^(.+?):(\d+):(\d+): (error|warning)(?:\[\w+\])?: (.+)$",
+            // src/app.ts(10,5): error TS2322: Type 'string' is not assignable ...
+            Self::Tsc => r"^(.+?)\((\d+),(\d+)\): (error|warning) (TS\d+: .+)$",
+            // app/models.py:10:5: error: Incompatible types  [assignment]
+            Self::Mypy => r"^(.+?):(\d+):(?:(\d+):)? (error|warning|note): (.+)$",
+        })
+        .expect("diagnostic pattern is valid");
+
+        let mut diagnostics: Vec<Diagnostic> = output
+            .lines()
+            .filter_map(|text| line.captures(text))
+            .filter_map(|captures| {
+                Some(Diagnostic {
+                    file: captures[1].to_string(),
+                    line: captures[2].parse().ok()?,
+                    column: captures
+                        .get(3)
+                        .and_then(|column| column.as_str().parse().ok()),
+                    severity: match &captures[4] {
+                        "error" => Severity::Error,
+                        "warning" => Severity::Warning,
+                        _ => Severity::Note,
+                    },
+                    message: captures[5].trim().to_string(),
+                })
+            })
+            .collect();
+
+        // `cargo check --all-targets` repeats a diagnostic for every target
+        // compiling the same file.
+        let mut seen = HashSet::new();
+        diagnostics.retain(|diagnostic| {
+            seen.insert((
+                diagnostic.file.clone(),
+                diagnostic.line,
+                diagnostic.column,
+                diagnostic.severity,
+                diagnostic.message.clone(),
+            ))
+        });
+
+        let count = |severity| {
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity == severity)
+                .count()
+        };
+        let (errors, warnings) = (count(Severity::Error), count(Severity::Warning));
+        let truncated = diagnostics.len() > MAX_DIAGNOSTICS;
+        if truncated {
+            // Keep errors over warnings and notes when trimming the list.
+            diagnostics.sort_by_key(|diagnostic| diagnostic.severity != Severity::Error);
+            diagnostics.truncate(MAX_DIAGNOSTICS);
+        }
+        CheckReport {
+            build_system: self,
+            command,
+            errors,
+            warnings,
+            diagnostics,
+            truncated,
+            delta: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_diagnostics_from_each_checker() {
+        let diagnostic = |file: &str, line, column, severity, message: &str| Diagnostic {
+            file: file.to_string(),
+            line,
+            column,
+            severity,
+            message: message.to_string(),
+        };
+
+        let output = "\
+    Checking codex-core v0.0.0 (/work/core)
+core/src/lib.rs:10:5: error[E0308]: mismatched types
+core/src/lib.rs:3:1: warning: unused import: `std::fmt`
+core/src/lib.rs:3:1: warning: unused import: `std::fmt`
+error: could not compile `codex-core` (lib) due to 1 previous error; 1 warning emitted
+";
+        assert_eq!(
+            BuildSystem::Cargo.report("cargo check".to_string(), output),
+            CheckReport {
+                build_system: BuildSystem::Cargo,
+                command: "cargo check".to_string(),
+                errors: 1,
+                warnings: 1,
+                diagnostics: vec![
+                    diagnostic(
+                        "core/src/lib.rs",
+                        10,
+                        Some(5),
+                        Severity::Error,
+                        "mismatched types"
+                    ),
+                    diagnostic(
+                        "core/src/lib.rs",
+                        3,
+                        Some(1),
+                        Severity::Warning,
+                        "unused import: `std::fmt`"
+                    ),
+                ],
+                truncated: false,
+                delta: None,
+            }
+        );
+
+        let output = "src/app.ts(4,7): error TS2322: Type 'string' is not assignable to type \
+                      'number'.\n";
+        assert_eq!(
+            BuildSystem::Tsc
+                .report("npx tsc".to_string(), output)
+                .diagnostics,
+            vec![diagnostic(
+                "src/app.ts",
+                4,
+                Some(7),
+                Severity::Error,
+                "TS2322: Type 'string' is not assignable to type 'number'."
+            )]
+        );
+
+        let output = "app/models.py:12: error: Missing return statement  [return]\n\
+                      app/models.py:12: note: See docs\n";
+        assert_eq!(
+            BuildSystem::Mypy
+                .report("mypy".to_string(), output)
+                .diagnostics,
+            vec![
+                diagnostic(
+                    "app/models.py",
+                    12,
+                    None,
+                    Severity::Error,
+                    "Missing return statement  [return]"
+                ),
+                diagnostic("app/models.py", 12, None, Severity::Note, "See docs"),
+            ]
+        );
+    }
+}
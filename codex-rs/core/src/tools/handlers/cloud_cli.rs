@@ -0,0 +1,797 @@
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
+use serde::Deserialize;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+
+/// Runs `kubectl`, `aws`, and `gcloud` commands for infra debugging without
+/// giving the model a shell next to the user's cloud credentials. Commands
+/// whose positional command path is on a read-only allowlist and whose flags
+/// are all on the CLI's flag allowlist run as is, with log reads capped;
+/// commands that print credentials never run; anything else counts as
+/// mutating and needs an entry in `cloud_cli.allowed_commands`
+/// and, unless `approval_policy` is `never`, the user's approval.
+pub struct CloudCliHandler;
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(60);
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+const DEFAULT_LOG_LINES: u64 = 200;
+const MAX_LOG_LINES: u64 = 2000;
+
+/// Read-only kubectl command paths, with whether operands (resource names)
+/// may follow them.
+const KUBECTL_READ_COMMANDS: &[(&[&str], bool)] = &[
+    (&["api-resources"], false),
+    (&["api-versions"], false),
+    (&["auth", "can-i"], true),
+    (&["auth", "whoami"], false),
+    (&["cluster-info"], false),
+    (&["config", "current-context"], false),
+    (&["config", "get-clusters"], false),
+    (&["config", "get-contexts"], true),
+    (&["config", "view"], false),
+    (&["describe"], true),
+    (&["events"], false),
+    (&["explain"], true),
+    (&["get"], true),
+    (&["logs"], true),
+    (&["rollout", "history"], true),
+    (&["rollout", "status"], true),
+    (&["top"], true),
+    (&["version"], false),
+];
+/// Flags a read-only kubectl command may use. Anything else, like `--server`,
+/// `--token`, `--kubeconfig`, or `--context`, can point kubectl at other
+/// credentials or clusters, so the command needs approval.
+const KUBECTL_READ_FLAGS: &[&str] = &[
+    "-A",
+    "-L",
+    "-c",
+    "-l",
+    "-n",
+    "-o",
+    "-p",
+    "--all-containers",
+    "--all-namespaces",
+    "--api-group",
+    "--chunk-size",
+    "--client",
+    "--container",
+    "--containers",
+    "--field-selector",
+    "--for",
+    "--ignore-not-found",
+    "--label-columns",
+    "--limit-bytes",
+    "--list",
+    "--minify",
+    "--namespace",
+    "--namespaced",
+    "--no-headers",
+    "--output",
+    "--prefix",
+    "--previous",
+    "--recursive",
+    "--revision",
+    "--selector",
+    "--show-kind",
+    "--show-labels",
+    "--since",
+    "--since-time",
+    "--sort-by",
+    "--tail",
+    "--timestamps",
+    "--types",
+    "--verbs",
+];
+/// Read-only AWS operations by service. An entry ending in `-` matches the
+/// operations it prefixes; operations that stream their result into a local
+/// file (e.g. `s3api get-object`) are deliberately absent.
+const AWS_READ_OPERATIONS: &[(&str, &[&str])] = &[
+    ("autoscaling", &["describe-"]),
+    ("cloudformation", &["describe-", "get-template", "list-"]),
+    (
+        "cloudwatch",
+        &[
+            "describe-",
+            "get-metric-data",
+            "get-metric-statistics",
+            "list-",
+        ],
+    ),
+    ("dynamodb", &["describe-", "list-"]),
+    ("ec2", &["describe-"]),
+    ("ecr", &["describe-", "list-"]),
+    ("ecs", &["describe-", "list-"]),
+    ("eks", &["describe-", "list-"]),
+    ("elb", &["describe-"]),
+    ("elbv2", &["describe-"]),
+    (
+        "iam",
+        &[
+            "get-account-summary",
+            "get-group",
+            "get-policy",
+            "get-policy-version",
+            "get-role",
+            "get-role-policy",
+            "get-user",
+            "list-",
+        ],
+    ),
+    ("lambda", &["get-function-configuration", "list-"]),
+    (
+        "logs",
+        &["describe-", "filter-log-events", "get-log-events", "tail"],
+    ),
+    ("rds", &["describe-"]),
+    ("route53", &["get-hosted-zone", "list-"]),
+    ("s3", &["ls"]),
+    (
+        "s3api",
+        &["get-bucket-", "head-bucket", "head-object", "list-"],
+    ),
+    ("sns", &["get-topic-attributes", "list-"]),
+    ("sqs", &["get-queue-attributes", "get-queue-url", "list-"]),
+    ("sts", &["get-caller-identity"]),
+];
+/// Flags a read-only AWS operation may use: output shaping, paging, and the
+/// parameters that pick what the allowed operations read. Anything else,
+/// like `--endpoint-url` or `--profile`, needs approval.
+const AWS_READ_FLAGS: &[&str] = &[
+    "--alarm-names",
+    "--auto-scaling-group-names",
+    "--bucket",
+    "--cluster",
+    "--clusters",
+    "--db-cluster-identifier",
+    "--db-instance-identifier",
+    "--dimensions",
+    "--end-time",
+    "--filter",
+    "--filter-pattern",
+    "--filters",
+    "--follow",
+    "--format",
+    "--function-name",
+    "--group-ids",
+    "--group-name",
+    "--hosted-zone-id",
+    "--human-readable",
+    "--id",
+    "--image-ids",
+    "--instance-ids",
+    "--key",
+    "--load-balancer-arns",
+    "--log-group-name",
+    "--log-group-name-prefix",
+    "--log-stream-name",
+    "--log-stream-name-prefix",
+    "--log-stream-names",
+    "--max-items",
+    "--max-results",
+    "--metric-data-queries",
+    "--metric-name",
+    "--name",
+    "--names",
+    "--namespace",
+    "--next-token",
+    "--no-cli-pager",
+    "--no-paginate",
+    "--nodegroup-name",
+    "--output",
+    "--page-size",
+    "--period",
+    "--policy-arn",
+    "--policy-name",
+    "--prefix",
+    "--query",
+    "--queue-url",
+    "--recursive",
+    "--region",
+    "--repository-name",
+    "--role-name",
+    "--service",
+    "--services",
+    "--since",
+    "--stack-name",
+    "--start-time",
+    "--starting-token",
+    "--statistics",
+    "--subnet-ids",
+    "--summarize",
+    "--table-name",
+    "--target-group-arns",
+    "--task-definition",
+    "--tasks",
+    "--topic-arn",
+    "--user-name",
+    "--version-id",
+    "--vpc-ids",
+];
+const AWS_SECRET_OPERATIONS: &[&str] = &[
+    "get-authorization-token",
+    "get-federation-token",
+    "get-login",
+    "get-login-password",
+    "get-password-data",
+    "get-secret-value",
+    "get-session-token",
+];
+/// gcloud command groups a read-only command path may go through before its
+/// verb.
+const GCLOUD_READ_GROUPS: &[&str] = &[
+    "artifacts",
+    "buckets",
+    "builds",
+    "clusters",
+    "compute",
+    "config",
+    "configurations",
+    "container",
+    "disks",
+    "dns",
+    "docker",
+    "firewall-rules",
+    "functions",
+    "iam",
+    "images",
+    "instances",
+    "logging",
+    "logs",
+    "managed-zones",
+    "networks",
+    "node-pools",
+    "operations",
+    "projects",
+    "pubsub",
+    "record-sets",
+    "regions",
+    "repositories",
+    "revisions",
+    "roles",
+    "run",
+    "service-accounts",
+    "services",
+    "sql",
+    "storage",
+    "subscriptions",
+    "topics",
+    "zones",
+];
+const GCLOUD_READ_VERBS: &[&str] = &["describe", "get-iam-policy", "list", "ls", "read"];
+/// Flags a read-only gcloud command may use. Anything else, like
+/// `--impersonate-service-account` or `--account`, needs approval.
+const GCLOUD_READ_FLAGS: &[&str] = &[
+    "--cluster",
+    "--filter",
+    "--flatten",
+    "--format",
+    "--freshness",
+    "--instance",
+    "--limit",
+    "--location",
+    "--order",
+    "--page-size",
+    "--project",
+    "--region",
+    "--sort-by",
+    "--uri",
+    "--zone",
+];
+const GCLOUD_SECRET_VERBS: &[&str] = &[
+    "config-helper",
+    "print-access-token",
+    "print-identity-token",
+];
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Cli {
+    Kubectl,
+    Aws,
+    Gcloud,
+}
+
+impl Cli {
+    fn program(self) -> &'static str {
+        match self {
+            Cli::Kubectl => "kubectl",
+            Cli::Aws => "aws",
+            Cli::Gcloud => "gcloud",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CloudCliArgs {
+    cli: Cli,
+    args: Vec<String>,
+}
+
+/// What a command may do, decided from its arguments alone.
+#[derive(Debug, PartialEq)]
+enum Access {
+    /// Only reads; carries the arguments with log reads capped.
+    ReadOnly(Vec<String>),
+    Mutating,
+    /// Never runs, e.g. because it prints credentials.
+    Refused(String),
+}
+
+#[async_trait]
+impl ToolHandler for CloudCliHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        match &invocation.payload {
+            ToolPayload::Function { arguments } => !matches!(
+                parse_arguments::<CloudCliArgs>(arguments),
+                Ok(args) if matches!(classify(args.cli, &args.args), Access::ReadOnly(_))
+            ),
+            _ => true,
+        }
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "cloud_cli handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let CloudCliArgs { cli, args } = parse_arguments(&arguments)?;
+        if args.first().is_none_or(|arg| arg.starts_with('-')) {
+            return Err(FunctionCallError::RespondToModel(
+                "args must start with the subcommand, e.g. `get` or `ec2`".to_string(),
+            ));
+        }
+
+        let program = cli.program();
+        let args = match classify(cli, &args) {
+            Access::ReadOnly(args) => args,
+            Access::Refused(reason) => return Err(FunctionCallError::RespondToModel(reason)),
+            Access::Mutating => {
+                let command = [program.to_string()]
+                    .into_iter()
+                    .chain(args.iter().cloned())
+                    .collect::<Vec<_>>();
+                if !is_allowed(&turn.config.cloud_cli.allowed_commands, &command) {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "`{}` is not read-only and no `cloud_cli.allowed_commands` entry in \
+                         config.toml allows it; ask the user to run it or to allow it",
+                        command.join(" ")
+                    )));
+                }
+                if !matches!(turn.approval_policy.value(), AskForApproval::Never) {
+                    let decision = session
+                        .request_command_approval(
+                            turn.as_ref(),
+                            call_id,
+                            None,
+                            command.clone(),
+                            turn.cwd.clone(),
+                            Some(format!("Allowed mutating {program} command")),
+                            None,
+                            None,
+                        )
+                        .await;
+                    match decision {
+                        ReviewDecision::Approved
+                        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                        | ReviewDecision::ApprovedForSession => {}
+                        ReviewDecision::Denied | ReviewDecision::Abort => {
+                            return Err(FunctionCallError::RespondToModel(format!(
+                                "the user declined `{}`",
+                                command.join(" ")
+                            )));
+                        }
+                    }
+                }
+                args
+            }
+        };
+
+        let output = timeout(
+            COMMAND_TIMEOUT,
+            Command::new(program)
+                .args(&args)
+                .current_dir(&turn.cwd)
+                .env("AWS_PAGER", "")
+                .env("CLOUDSDK_CORE_DISABLE_PROMPTS", "1")
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await
+        .map_err(|_| {
+            FunctionCallError::RespondToModel(format!(
+                "{program} timed out after {}s",
+                COMMAND_TIMEOUT.as_secs()
+            ))
+        })?
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to run {program}: {err}"))
+        })?;
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let mut content = if output.status.success() {
+            stdout.into_owned()
+        } else {
+            format!("{program} exited with {}\n{stdout}", output.status)
+        };
+        if !stderr.trim().is_empty() {
+            content.push_str(&format!("\nstderr:\n{}", stderr.trim_end()));
+        }
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(formatted_truncate_text(
+                &content,
+                TruncationPolicy::Bytes(MAX_OUTPUT_BYTES),
+            )),
+            success: Some(output.status.success()),
+        })
+    }
+}
+
+fn classify(cli: Cli, args: &[String]) -> Access {
+    // The positional words, which start with the command path; flag values
+    // show up here too, so a flag between path words breaks the match and
+    // the command counts as mutating.
+    let words = args
+        .iter()
+        .map(String::as_str)
+        .filter(|arg| !arg.starts_with('-'))
+        .collect::<Vec<_>>();
+    // Negative numbers are values, e.g. `--tail -1`.
+    let flags = args
+        .iter()
+        .filter(|arg| arg.starts_with('-') && arg[1..].parse::<i64>().is_err())
+        .map(|arg| arg.split_once('=').map_or(arg.as_str(), |(name, _)| name))
+        .collect::<Vec<_>>();
+    let has_flag = |names: &[&str]| flags.iter().any(|flag| names.contains(flag));
+    let only_flags = |allowed: &[&str]| flags.iter().all(|flag| allowed.contains(flag));
+
+    match cli {
+        Cli::Kubectl => {
+            let read_only = KUBECTL_READ_COMMANDS.iter().any(|(path, operands)| {
+                words.starts_with(path) && (*operands || words.len() == path.len())
+            });
+            if !read_only {
+                return Access::Mutating;
+            }
+            let verb = words[0];
+            if has_flag(&["--raw"]) {
+                return Access::Refused(
+                    "`--raw` reaches any API path, secrets and credentials included, and is \
+                     never run"
+                        .to_string(),
+                );
+            }
+            if matches!(verb, "get" | "describe")
+                && words[1..].iter().any(|word| {
+                    word.split(',').any(|resource| {
+                        // `secret`, `Secrets`, `secret.v1`, `secrets.v1.`, ...
+                        let kind = resource.split(['/', '.']).next().unwrap_or_default();
+                        kind.eq_ignore_ascii_case("secret") || kind.eq_ignore_ascii_case("secrets")
+                    })
+                })
+            {
+                return Access::Refused(
+                    "reading Kubernetes secrets is never allowed through cloud_cli".to_string(),
+                );
+            }
+            if has_flag(&["-w", "--watch", "--watch-only"])
+                || (verb == "logs" && has_flag(&["-f", "--follow"]))
+            {
+                return Access::Refused(
+                    "commands that stream until interrupted cannot run; drop the watch or \
+                     follow flag"
+                        .to_string(),
+                );
+            }
+            if !only_flags(KUBECTL_READ_FLAGS) {
+                return Access::Mutating;
+            }
+            if verb == "logs" {
+                return Access::ReadOnly(cap_flag(args, "--tail"));
+            }
+            Access::ReadOnly(args.to_vec())
+        }
+        Cli::Aws => {
+            let (Some(service), Some(operation)) = (words.first(), words.get(1)) else {
+                return Access::Mutating;
+            };
+            if AWS_SECRET_OPERATIONS.contains(operation)
+                || (operation.starts_with("get-parameter") && has_flag(&["--with-decryption"]))
+            {
+                return Access::Refused(format!(
+                    "`aws {service} {operation}` returns credentials or secrets and is never run"
+                ));
+            }
+            let read_only = AWS_READ_OPERATIONS
+                .iter()
+                .filter(|(read_service, _)| read_service == service)
+                .flat_map(|(_, operations)| operations.iter())
+                .any(|allowed| {
+                    if allowed.ends_with('-') {
+                        operation.starts_with(allowed)
+                    } else {
+                        allowed == operation
+                    }
+                });
+            if !read_only {
+                return Access::Mutating;
+            }
+            if *service == "logs" && has_flag(&["--follow"]) {
+                return Access::Refused(
+                    "`aws logs tail --follow` streams until interrupted; drop --follow".to_string(),
+                );
+            }
+            if !only_flags(AWS_READ_FLAGS) {
+                return Access::Mutating;
+            }
+            Access::ReadOnly(args.to_vec())
+        }
+        Cli::Gcloud => {
+            if let Some(verb) = words.iter().find(|word| GCLOUD_SECRET_VERBS.contains(word)) {
+                return Access::Refused(format!(
+                    "`gcloud ... {verb}` prints credentials and is never run"
+                ));
+            }
+            if words
+                .windows(3)
+                .any(|window| window == ["secrets", "versions", "access"])
+            {
+                return Access::Refused(
+                    "reading secret values is never allowed through cloud_cli".to_string(),
+                );
+            }
+            // The command path is groups followed by the verb; whatever
+            // comes after the verb is its operands.
+            let verb_index = words
+                .iter()
+                .position(|word| !GCLOUD_READ_GROUPS.contains(word));
+            let Some(verb_index) =
+                verb_index.filter(|index| *index > 0 && GCLOUD_READ_VERBS.contains(&words[*index]))
+            else {
+                return Access::Mutating;
+            };
+            if !only_flags(GCLOUD_READ_FLAGS) {
+                return Access::Mutating;
+            }
+            if words[verb_index - 1..=verb_index] == ["logging", "read"] {
+                return Access::ReadOnly(cap_flag(args, "--limit"));
+            }
+            Access::ReadOnly(args.to_vec())
+        }
+    }
+}
+
+/// Caps the line count `flag` asks for at [`MAX_LOG_LINES`], adding it with
+/// [`DEFAULT_LOG_LINES`] when absent. Negative counts (all lines) get the cap.
+fn cap_flag(args: &[String], flag: &str) -> Vec<String> {
+    let capped = |value: &str| {
+        value
+            .parse::<i64>()
+            .ok()
+            .and_then(|lines| u64::try_from(lines).ok())
+            .map_or(MAX_LOG_LINES, |lines| lines.min(MAX_LOG_LINES))
+    };
+    let mut capped_args = Vec::with_capacity(args.len() + 1);
+    let mut found = false;
+    let mut args_iter = args.iter();
+    while let Some(arg) = args_iter.next() {
+        if arg == flag {
+            found = true;
+            let lines = args_iter
+                .next()
+                .map_or(DEFAULT_LOG_LINES, |value| capped(value));
+            capped_args.push(format!("{flag}={lines}"));
+        } else if let Some(value) = arg.strip_prefix(&format!("{flag}=")) {
+            found = true;
+            capped_args.push(format!("{flag}={}", capped(value)));
+        } else {
+            capped_args.push(arg.clone());
+        }
+    }
+    if !found {
+        capped_args.push(format!("{flag}={DEFAULT_LOG_LINES}"));
+    }
+    capped_args
+}
+
+/// Whether an `allowed_commands` entry's words start `command`.
+fn is_allowed(allowed_commands: &[String], command: &[String]) -> bool {
+    allowed_commands.iter().any(|allowed| {
+        let words = allowed.split_whitespace().collect::<Vec<_>>();
+        !words.is_empty()
+            && words.len() <= command.len()
+            && words.iter().zip(command).all(|(word, arg)| word == arg)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn args(command: &str) -> Vec<String> {
+        command.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn classifies_reads_writes_and_credential_commands() {
+        let cases = [
+            (Cli::Kubectl, "get pods -n prod"),
+            (Cli::Kubectl, "rollout restart deploy/api"),
+            (Cli::Kubectl, "get secrets,configmaps"),
+            (Cli::Kubectl, "get pods --watch"),
+            (Cli::Aws, "ec2 describe-instances"),
+            (Cli::Aws, "ecs update-service --service api"),
+            (Cli::Aws, "ssm get-parameter --name db --with-decryption"),
+            (Cli::Gcloud, "compute instances list"),
+            (Cli::Gcloud, "compute instances delete describe"),
+            (Cli::Gcloud, "auth print-access-token"),
+        ];
+        let access = cases
+            .iter()
+            .map(|(cli, command)| match classify(*cli, &args(command)) {
+                Access::ReadOnly(_) => "read",
+                Access::Mutating => "mutating",
+                Access::Refused(_) => "refused",
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            access,
+            vec![
+                "read", "mutating", "refused", "refused", "read", "mutating", "refused", "read",
+                "mutating", "refused",
+            ]
+        );
+        assert!(is_allowed(
+            &["kubectl rollout restart".to_string()],
+            &args("kubectl rollout restart deploy/api")
+        ));
+    }
+
+    #[test]
+    fn reads_outside_the_allowlist_are_not_read_only() {
+        let cases = [
+            (
+                Cli::Aws,
+                "s3api get-object --bucket b --key k /home/user/.bashrc",
+            ),
+            (Cli::Aws, "s3api list-objects-v2 --bucket b"),
+            (Cli::Aws, "ecr get-login --no-include-email"),
+            (Cli::Aws, "lambda invoke --function-name f out.json"),
+            (Cli::Kubectl, "get --raw /api/v1/namespaces/x/secrets/y"),
+            (
+                Cli::Kubectl,
+                "cluster-info dump --output-directory /tmp/dump",
+            ),
+            (
+                Cli::Gcloud,
+                "compute instances add-metadata vm --metadata k=v",
+            ),
+            (Cli::Gcloud, "compute instances remove-metadata vm --keys k"),
+            (Cli::Gcloud, "compute instances add-labels vm --labels k=v"),
+            (Cli::Gcloud, "compute instances attach-disk vm --disk d"),
+            (Cli::Gcloud, "sql users set-password root --instance db"),
+            (Cli::Gcloud, "storage cp gs://b/o list"),
+            (Cli::Gcloud, "storage mv gs://b/o gs://b/p"),
+            (Cli::Gcloud, "storage rm gs://b/o"),
+            (Cli::Gcloud, "compute instances describe list"),
+            (Cli::Gcloud, "storage ls gs://b"),
+        ];
+        let access = cases
+            .iter()
+            .map(|(cli, command)| match classify(*cli, &args(command)) {
+                Access::ReadOnly(_) => "read",
+                Access::Mutating => "mutating",
+                Access::Refused(_) => "refused",
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            access,
+            vec![
+                "mutating", "read", "refused", "mutating", "refused", "mutating", "mutating",
+                "mutating", "mutating", "mutating", "mutating", "mutating", "mutating", "mutating",
+                "read", "read",
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_off_the_allowlist_need_approval() {
+        let cases = [
+            (Cli::Kubectl, "get pods -n prod -o wide"),
+            (Cli::Kubectl, "get pods --server https://attacker.example"),
+            (Cli::Kubectl, "get pods --token=abc"),
+            (Cli::Kubectl, "get pods --kubeconfig /tmp/config"),
+            (Cli::Kubectl, "get pods --context prod"),
+            (Cli::Kubectl, "get secret.v1/db"),
+            (Cli::Kubectl, "describe Secrets"),
+            (Cli::Kubectl, "get pods,secrets.v1./db"),
+            (
+                Cli::Aws,
+                "ec2 describe-instances --region us-east-1 --output json",
+            ),
+            (
+                Cli::Aws,
+                "ec2 describe-instances --endpoint-url https://attacker.example",
+            ),
+            (Cli::Aws, "sts get-caller-identity --profile prod"),
+            (
+                Cli::Gcloud,
+                "compute instances list --project p --format=json",
+            ),
+            (
+                Cli::Gcloud,
+                "compute instances list --impersonate-service-account sa@p.iam.gserviceaccount.com",
+            ),
+        ];
+        let access = cases
+            .iter()
+            .map(|(cli, command)| match classify(*cli, &args(command)) {
+                Access::ReadOnly(_) => "read",
+                Access::Mutating => "mutating",
+                Access::Refused(_) => "refused",
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            access,
+            vec![
+                "read", "mutating", "mutating", "mutating", "mutating", "refused", "refused",
+                "refused", "read", "mutating", "mutating", "read", "mutating",
+            ]
+        );
+    }
+
+    #[test]
+    fn caps_log_reads() {
+        assert_eq!(
+            [
+                classify(Cli::Kubectl, &args("logs api-0")),
+                classify(Cli::Kubectl, &args("logs api-0 --tail -1")),
+                classify(
+                    Cli::Gcloud,
+                    &args("logging read severity>=ERROR --limit=50")
+                ),
+            ],
+            [
+                Access::ReadOnly(args("logs api-0 --tail=200")),
+                Access::ReadOnly(args("logs api-0 --tail=2000")),
+                Access::ReadOnly(args("logging read severity>=ERROR --limit=50")),
+            ]
+        );
+    }
+}
@@ -0,0 +1,671 @@
+use std::collections::BTreeMap;
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::LazyLock;
+use std::time::Duration;
+use std::time::Instant;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxPolicy;
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::json;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::AsyncRead;
+use tokio::io::BufReader;
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::progress::ToolProgress;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::sandboxing::with_cached_approval;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+
+/// Builds a container image with docker or podman, reporting each build step
+/// as tool progress and returning how many steps came from the layer cache.
+pub struct BuildImageHandler;
+
+/// Runs a verification command in an image, with no network and, unless
+/// asked, without the workspace mounted.
+pub struct RunInImageHandler;
+
+const BUILD_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+const DEFAULT_RUN_TIMEOUT_MS: u64 = 10 * 60 * 1000;
+const MAX_RUN_TIMEOUT_MS: u64 = 30 * 60 * 1000;
+/// Minimum time between build progress reports.
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(250);
+const LOG_TAIL_LINES: usize = 60;
+const SLOWEST_STEPS: usize = 5;
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+const WORKSPACE_MOUNT: &str = "/workspace";
+
+/// A BuildKit `--progress=plain` step header: `#7 [build 2/5] RUN make`.
+static BUILDKIT_STEP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^#(\d+) \[(.*?)\s*(\d+)/(\d+)\] (.+)$")
+        .unwrap_or_else(|err| panic!("invalid buildkit step regex: {err}"))
+});
+/// A BuildKit vertex status: `#7 CACHED`, `#7 DONE 12.3s`, `#7 ERROR: ...`.
+static BUILDKIT_STATUS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^#(\d+) (CACHED|DONE (\d+(?:\.\d+)?)s|ERROR: .*)$")
+        .unwrap_or_else(|err| panic!("invalid buildkit status regex: {err}"))
+});
+/// A buildah (podman) step header: `[1/2] STEP 2/5: RUN make`.
+static BUILDAH_STEP: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:\[(\d+)/\d+\] )?STEP (\d+)/(\d+): (.+)$")
+        .unwrap_or_else(|err| panic!("invalid buildah step regex: {err}"))
+});
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+enum Engine {
+    Docker,
+    Podman,
+}
+
+impl Engine {
+    fn program(self) -> &'static str {
+        match self {
+            Engine::Docker => "docker",
+            Engine::Podman => "podman",
+        }
+    }
+
+    fn resolve(engine: Option<Engine>) -> Result<Engine, FunctionCallError> {
+        if let Some(engine) = engine {
+            return Ok(engine);
+        }
+        [Engine::Docker, Engine::Podman]
+            .into_iter()
+            .find(|engine| which::which(engine.program()).is_ok())
+            .ok_or_else(|| {
+                FunctionCallError::RespondToModel(
+                    "neither docker nor podman is installed".to_string(),
+                )
+            })
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct BuildImageArgs {
+    #[serde(default)]
+    context: Option<String>,
+    #[serde(default)]
+    dockerfile: Option<String>,
+    #[serde(default)]
+    tag: Option<String>,
+    #[serde(default)]
+    target: Option<String>,
+    #[serde(default)]
+    build_args: BTreeMap<String, String>,
+    #[serde(default)]
+    engine: Option<Engine>,
+}
+
+#[derive(Deserialize)]
+struct RunInImageArgs {
+    image: String,
+    command: Vec<String>,
+    #[serde(default)]
+    mount_workspace: bool,
+    #[serde(default)]
+    network: bool,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(default)]
+    engine: Option<Engine>,
+}
+
+/// What the session remembers when the user approves a build or run for
+/// the rest of the session.
+#[derive(Serialize)]
+#[serde(tag = "tool", rename_all = "snake_case")]
+enum ContainerApprovalKey {
+    /// A build is approved only for the exact same request.
+    BuildImage {
+        engine: Engine,
+        context: String,
+        request: BuildImageArgs,
+    },
+    /// So is a run: the command can write to a mounted workspace or reach
+    /// the network, so approving one command must not approve others.
+    RunInImage {
+        engine: Engine,
+        image: String,
+        command: Vec<String>,
+        mount_workspace: bool,
+        network: bool,
+    },
+}
+
+impl ContainerApprovalKey {
+    fn tool(&self) -> &'static str {
+        match self {
+            ContainerApprovalKey::BuildImage { .. } => "build_image",
+            ContainerApprovalKey::RunInImage { .. } => "run_in_image",
+        }
+    }
+}
+
+/// Build steps seen so far in the engine's output.
+#[derive(Debug, Default, PartialEq)]
+struct BuildSteps {
+    /// Step count of each stage.
+    totals: BTreeMap<String, u64>,
+    /// BuildKit vertex ids of steps, with their instructions.
+    vertices: BTreeMap<u64, String>,
+    finished: u64,
+    cached: u64,
+    /// Instructions and seconds of the steps that ran.
+    timings: Vec<(String, f64)>,
+    current: Option<String>,
+    errors: Vec<String>,
+}
+
+impl BuildSteps {
+    fn observe(&mut self, line: &str) {
+        let line = line.trim_end();
+        if let Some(captures) = BUILDKIT_STEP.captures(line) {
+            let (Ok(vertex), Ok(total)) = (captures[1].parse(), captures[4].parse()) else {
+                return;
+            };
+            self.totals.insert(captures[2].to_string(), total);
+            self.vertices.insert(vertex, captures[5].to_string());
+            self.current = Some(captures[5].to_string());
+        } else if let Some(captures) = BUILDKIT_STATUS.captures(line) {
+            let Some(step) = captures[1]
+                .parse()
+                .ok()
+                .and_then(|vertex: u64| self.vertices.get(&vertex).cloned())
+            else {
+                return;
+            };
+            let status = &captures[2];
+            if status == "CACHED" {
+                self.cached += 1;
+                self.finished += 1;
+            } else if let Some(seconds) = captures.get(3) {
+                self.finished += 1;
+                self.timings
+                    .push((step, seconds.as_str().parse().unwrap_or_default()));
+            } else {
+                self.errors.push(format!("{step}: {status}"));
+            }
+        } else if let Some(captures) = BUILDAH_STEP.captures(line) {
+            let stage = captures.get(1).map_or("", |stage| stage.as_str());
+            let Ok(total) = captures[3].parse() else {
+                return;
+            };
+            if self.current.is_some() {
+                self.finished += 1;
+            }
+            self.totals.insert(stage.to_string(), total);
+            self.current = Some(captures[4].to_string());
+        } else if line.starts_with("--> Using cache") {
+            self.cached += 1;
+        } else if let Some(error) = line.strip_prefix("Error: ") {
+            self.errors.push(error.to_string());
+        }
+    }
+
+    fn total(&self) -> u64 {
+        self.totals.values().sum()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for BuildImageHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let progress = ToolProgress::new(&invocation);
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "build_image handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: BuildImageArgs = parse_arguments(&arguments)?;
+        let engine = Engine::resolve(args.engine)?;
+        let context = turn.resolve_path(args.context.clone());
+        let tag = args.tag.clone().unwrap_or_else(|| {
+            let id = Uuid::new_v4().simple().to_string();
+            format!("codex-build:{}", &id[..12])
+        });
+
+        let mut command = vec![engine.program().to_string(), "build".to_string()];
+        if engine == Engine::Docker {
+            command.push("--progress=plain".to_string());
+        }
+        command.extend(["--tag".to_string(), tag.clone()]);
+        if let Some(dockerfile) = &args.dockerfile {
+            command.extend(["--file".to_string(), dockerfile.clone()]);
+        }
+        if let Some(target) = &args.target {
+            command.extend(["--target".to_string(), target.clone()]);
+        }
+        for (name, value) in &args.build_args {
+            command.extend(["--build-arg".to_string(), format!("{name}={value}")]);
+        }
+        command.push(context.display().to_string());
+
+        let key = ContainerApprovalKey::BuildImage {
+            engine,
+            context: context.display().to_string(),
+            request: args,
+        };
+        request_approval(
+            &session,
+            &turn,
+            call_id,
+            &command,
+            key,
+            "Build a container image",
+        )
+        .await?;
+
+        let mut child = Command::new(&command[0])
+            .args(&command[1..])
+            .current_dir(&context)
+            .env("DOCKER_BUILDKIT", "1")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to run {}: {err}",
+                    engine.program()
+                ))
+            })?;
+        let (tx, mut lines) = mpsc::unbounded_channel();
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(forward_lines(stdout, tx.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(forward_lines(stderr, tx));
+        }
+
+        let mut steps = BuildSteps::default();
+        let mut tail = VecDeque::with_capacity(LOG_TAIL_LINES);
+        let build = async {
+            let mut last_report: Option<Instant> = None;
+            while let Some(line) = lines.recv().await {
+                steps.observe(&line);
+                if tail.len() == LOG_TAIL_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+                if last_report.is_some_and(|reported| reported.elapsed() < PROGRESS_INTERVAL) {
+                    continue;
+                }
+                last_report = Some(Instant::now());
+                let total = steps.total();
+                progress
+                    .report(
+                        steps.finished,
+                        (total > 0).then_some(total),
+                        steps.current.clone(),
+                    )
+                    .await;
+            }
+            child.wait().await
+        };
+        let status = timeout(BUILD_TIMEOUT, build)
+            .await
+            .map_err(|_| {
+                FunctionCallError::RespondToModel(format!(
+                    "{} build timed out after {}s",
+                    engine.program(),
+                    BUILD_TIMEOUT.as_secs()
+                ))
+            })?
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to wait for {}: {err}",
+                    engine.program()
+                ))
+            })?;
+
+        let mut slowest = steps.timings.clone();
+        slowest.sort_by(|a, b| b.1.total_cmp(&a.1));
+        slowest.truncate(SLOWEST_STEPS);
+        // Neither engine marks the last step done, so a successful build
+        // counts every step.
+        let finished = if status.success() {
+            steps.total().max(steps.finished)
+        } else {
+            steps.finished
+        }
+        .max(steps.cached);
+        let mut report = json!({
+            "engine": engine,
+            "image": tag,
+            "success": status.success(),
+            "steps": {
+                "total": steps.total(),
+                "cached": steps.cached,
+                "built": finished - steps.cached,
+            },
+            "cache_hit_rate": if finished == 0 {
+                0.0
+            } else {
+                steps.cached as f64 / finished as f64
+            },
+            "slowest_steps": slowest
+                .into_iter()
+                .map(|(step, seconds)| json!({"step": step, "seconds": seconds}))
+                .collect::<Vec<_>>(),
+        });
+        if !status.success() {
+            report["errors"] = json!(steps.errors);
+            report["log_tail"] = json!(Vec::from(tail).join("\n"));
+        }
+        let content = serde_json::to_string_pretty(&report).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to serialize build report: {err}"))
+        })?;
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(formatted_truncate_text(
+                &content,
+                TruncationPolicy::Bytes(MAX_OUTPUT_BYTES),
+            )),
+            success: Some(status.success()),
+        })
+    }
+}
+
+#[async_trait]
+impl ToolHandler for RunInImageHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        match &invocation.payload {
+            ToolPayload::Function { arguments } => !matches!(
+                parse_arguments::<RunInImageArgs>(arguments),
+                Ok(args) if !args.mount_workspace
+            ),
+            _ => true,
+        }
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "run_in_image handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: RunInImageArgs = parse_arguments(&arguments)?;
+        if args.command.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "command must not be empty".to_string(),
+            ));
+        }
+        let engine = Engine::resolve(args.engine)?;
+        // Named so that a run that times out can be removed; killing the
+        // client leaves the container running.
+        let name = format!("codex-run-{}", Uuid::new_v4().simple());
+
+        let mut command = vec![
+            engine.program().to_string(),
+            "run".to_string(),
+            "--rm".to_string(),
+            "--name".to_string(),
+            name.clone(),
+        ];
+        if !args.network {
+            command.push("--network=none".to_string());
+        }
+        if args.mount_workspace {
+            command.extend([
+                "--volume".to_string(),
+                format!("{}:{WORKSPACE_MOUNT}", turn.cwd.display()),
+                "--workdir".to_string(),
+                WORKSPACE_MOUNT.to_string(),
+            ]);
+        }
+        command.push(args.image.clone());
+        command.extend(args.command.iter().cloned());
+
+        let key = ContainerApprovalKey::RunInImage {
+            engine,
+            image: args.image,
+            command: args.command,
+            mount_workspace: args.mount_workspace,
+            network: args.network,
+        };
+        request_approval(
+            &session,
+            &turn,
+            call_id,
+            &command,
+            key,
+            "Run a command in a container image",
+        )
+        .await?;
+
+        let limit = Duration::from_millis(
+            args.timeout_ms
+                .unwrap_or(DEFAULT_RUN_TIMEOUT_MS)
+                .min(MAX_RUN_TIMEOUT_MS),
+        );
+        let output = match timeout(
+            limit,
+            Command::new(&command[0])
+                .args(&command[1..])
+                .current_dir(&turn.cwd)
+                .stdin(Stdio::null())
+                .kill_on_drop(true)
+                .output(),
+        )
+        .await
+        {
+            Ok(output) => output.map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to run {}: {err}", command[0]))
+            })?,
+            Err(_) => {
+                let removed = Command::new(engine.program())
+                    .args(["rm", "--force", &name])
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::null())
+                    .stderr(Stdio::null())
+                    .status()
+                    .await;
+                if !removed.is_ok_and(|status| status.success()) {
+                    warn!("failed to remove timed out container {name}");
+                }
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "command timed out after {}ms",
+                    limit.as_millis()
+                )));
+            }
+        };
+        let mut content = format!(
+            "exit code: {}\n{}",
+            output.status.code().unwrap_or(-1),
+            String::from_utf8_lossy(&output.stdout)
+        );
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            content.push_str(&format!("\nstderr:\n{}", stderr.trim_end()));
+        }
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(formatted_truncate_text(
+                &content,
+                TruncationPolicy::Bytes(MAX_OUTPUT_BYTES),
+            )),
+            success: Some(output.status.success()),
+        })
+    }
+}
+
+/// Asks the user to approve `command` unless they approved the same build or
+/// run for the session. The engine's daemon works outside the sandbox, so
+/// with approval_policy `never` the command runs only when the sandbox
+/// allows full access.
+async fn request_approval(
+    session: &Session,
+    turn: &TurnContext,
+    call_id: String,
+    command: &[String],
+    key: ContainerApprovalKey,
+    reason: &str,
+) -> Result<(), FunctionCallError> {
+    if matches!(turn.approval_policy.value(), AskForApproval::Never) {
+        return if matches!(
+            turn.sandbox_policy.get(),
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
+        ) {
+            Ok(())
+        } else {
+            Err(FunctionCallError::RespondToModel(format!(
+                "`{}` runs outside the sandbox, which needs the user's approval; \
+                 approval_policy `never` cannot give it",
+                command.join(" ")
+            )))
+        };
+    }
+    let decision = with_cached_approval(&session.services, key.tool(), vec![key], || {
+        session.request_command_approval(
+            turn,
+            call_id,
+            None,
+            command.to_vec(),
+            turn.cwd.clone(),
+            Some(reason.to_string()),
+            None,
+            None,
+        )
+    })
+    .await;
+    match decision {
+        ReviewDecision::Approved
+        | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+        | ReviewDecision::ApprovedForSession => Ok(()),
+        ReviewDecision::Denied | ReviewDecision::Abort => Err(FunctionCallError::RespondToModel(
+            format!("the user declined `{}`", command.join(" ")),
+        )),
+    }
+}
+
+async fn forward_lines(stream: impl AsyncRead + Unpin, tx: mpsc::UnboundedSender<String>) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if tx.send(line).is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn observe(output: &str) -> BuildSteps {
+        let mut steps = BuildSteps::default();
+        for line in output.lines() {
+            steps.observe(line);
+        }
+        steps
+    }
+
+    #[test]
+    fn counts_buildkit_steps_and_cache_hits() {
+        let steps = observe(
+            "#1 [internal] load build definition from Dockerfile\n\
+             #1 DONE 0.0s\n\
+             #5 [build 1/3] FROM docker.io/library/rust:1.93\n\
+             #5 CACHED\n\
+             #6 [build 2/3] COPY . .\n\
+             #6 DONE 0.4s\n\
+             #7 [build 3/3] RUN cargo build --release\n\
+             #7 DONE 84.2s\n",
+        );
+        assert_eq!(
+            steps,
+            BuildSteps {
+                totals: BTreeMap::from([("build".to_string(), 3)]),
+                vertices: BTreeMap::from([
+                    (5, "FROM docker.io/library/rust:1.93".to_string()),
+                    (6, "COPY . .".to_string()),
+                    (7, "RUN cargo build --release".to_string()),
+                ]),
+                finished: 3,
+                cached: 1,
+                timings: vec![
+                    ("COPY . .".to_string(), 0.4),
+                    ("RUN cargo build --release".to_string(), 84.2),
+                ],
+                current: Some("RUN cargo build --release".to_string()),
+                errors: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn counts_buildah_steps_and_cache_hits() {
+        let steps = observe(
+            "STEP 1/3: FROM alpine:3.20\n\
+             STEP 2/3: COPY . /src\n\
+             --> Using cache 4f1a2b\n\
+             STEP 3/3: RUN make -C /src\n\
+             Error: building at STEP \"RUN make -C /src\": exit status 2\n",
+        );
+        assert_eq!(
+            (steps.total(), steps.finished, steps.cached, steps.errors),
+            (
+                3,
+                2,
+                1,
+                vec!["building at STEP \"RUN make -C /src\": exit status 2".to_string()]
+            )
+        );
+    }
+}
@@ -14,6 +14,7 @@ use crate::tools::context::ToolPayload;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use crate::tools::registry::ToolPriority;
 
 pub struct GrepFilesHandler;
 
@@ -42,6 +43,14 @@ impl ToolHandler for GrepFilesHandler {
         ToolKind::Function
     }
 
+    fn is_cache_eligible(&self) -> bool {
+        true
+    }
+
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Interactive
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation { payload, turn, .. } = invocation;
 
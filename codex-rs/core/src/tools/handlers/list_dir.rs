@@ -17,6 +17,7 @@ use crate::tools::context::ToolPayload;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use crate::tools::registry::ToolPriority;
 
 pub struct ListDirHandler;
 
@@ -52,6 +53,14 @@ impl ToolHandler for ListDirHandler {
         ToolKind::Function
     }
 
+    fn is_cache_eligible(&self) -> bool {
+        true
+    }
+
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Interactive
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation { payload, .. } = invocation;
 
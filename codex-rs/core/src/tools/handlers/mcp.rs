@@ -10,7 +10,10 @@ use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use codex_protocol::models::ResponseInputItem;
 
-pub struct McpHandler;
+pub struct McpHandler {
+    /// Whether the server annotates the tool as read-only.
+    pub read_only: bool,
+}
 
 #[async_trait]
 impl ToolHandler for McpHandler {
@@ -18,6 +21,10 @@ impl ToolHandler for McpHandler {
         ToolKind::Mcp
     }
 
+    fn is_idempotent(&self) -> bool {
+        self.read_only
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -51,7 +58,7 @@ impl ToolHandler for McpHandler {
             tool,
             arguments_str,
         )
-        .await;
+        .await?;
 
         match response {
             ResponseInputItem::McpToolCallOutput { result, .. } => Ok(ToolOutput::Mcp { result }),
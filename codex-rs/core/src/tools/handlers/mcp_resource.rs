@@ -21,6 +21,7 @@ use serde_json::Value;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::function_tool::FunctionCallError;
+use crate::function_tool::ToolFailureKind;
 use crate::protocol::EventMsg;
 use crate::protocol::McpInvocation;
 use crate::protocol::McpToolCallBeginEvent;
@@ -184,6 +185,10 @@ impl ToolHandler for McpResourceHandler {
         ToolKind::Function
     }
 
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
         let ToolInvocation {
             session,
@@ -269,9 +274,7 @@ async fn handle_list_resources(
             let result = session
                 .list_resources(&server_name, params)
                 .await
-                .map_err(|err| {
-                    FunctionCallError::RespondToModel(format!("resources/list failed: {err:#}"))
-                })?;
+                .map_err(|err| resource_request_error("resources/list", err))?;
             Ok(ListResourcesPayload::from_single_server(
                 server_name,
                 result,
@@ -375,11 +378,7 @@ async fn handle_list_resource_templates(
             let result = session
                 .list_resource_templates(&server_name, params)
                 .await
-                .map_err(|err| {
-                    FunctionCallError::RespondToModel(format!(
-                        "resources/templates/list failed: {err:#}"
-                    ))
-                })?;
+                .map_err(|err| resource_request_error("resources/templates/list", err))?;
             Ok(ListResourceTemplatesPayload::from_single_server(
                 server_name,
                 result,
@@ -484,9 +483,7 @@ async fn handle_read_resource(
                 },
             )
             .await
-            .map_err(|err| {
-                FunctionCallError::RespondToModel(format!("resources/read failed: {err:#}"))
-            })?;
+            .map_err(|err| resource_request_error("resources/read", err))?;
 
         Ok(ReadResourcePayload {
             server,
@@ -594,6 +591,20 @@ async fn emit_tool_call_end(
         .await;
 }
 
+/// Timed-out requests are reported as [`ToolFailureKind::Timeout`] so the
+/// router can retry them.
+fn resource_request_error(method: &str, err: anyhow::Error) -> FunctionCallError {
+    let message = format!("{method} failed: {err:#}");
+    if err
+        .chain()
+        .any(<dyn std::error::Error>::is::<tokio::time::error::Elapsed>)
+    {
+        FunctionCallError::failed(ToolFailureKind::Timeout, message)
+    } else {
+        FunctionCallError::RespondToModel(message)
+    }
+}
+
 fn normalize_optional_string(input: Option<String>) -> Option<String> {
     input.and_then(|value| {
         let trimmed = value.trim().to_string();
@@ -782,6 +793,28 @@ mod tests {
         assert_eq!(value["server"], json!("figma"));
     }
 
+    #[tokio::test]
+    async fn resource_request_timeouts_are_retryable() {
+        let elapsed = tokio::time::timeout(Duration::ZERO, std::future::pending::<()>())
+            .await
+            .expect_err("pending future should time out");
+        let err = anyhow::Error::new(elapsed).context("timed out awaiting resources/read");
+        assert_eq!(
+            resource_request_error("resources/read", err),
+            FunctionCallError::failed(
+                ToolFailureKind::Timeout,
+                "resources/read failed: timed out awaiting resources/read: deadline has elapsed",
+            )
+        );
+
+        assert_eq!(
+            resource_request_error("resources/read", anyhow::anyhow!("unknown resource")),
+            FunctionCallError::RespondToModel(
+                "resources/read failed: unknown resource".to_string()
+            )
+        );
+    }
+
     #[test]
     fn template_with_server_serializes_server_field() {
         let entry =
@@ -1,4 +1,9 @@
+mod analyze_log;
 pub mod apply_patch;
+mod call_graph;
+mod check_build;
+mod cloud_cli;
+mod container_image;
 mod dynamic;
 mod grep_files;
 mod js_repl;
@@ -6,11 +11,20 @@ mod list_dir;
 mod mcp;
 mod mcp_resource;
 pub(crate) mod multi_agents;
+mod package_info;
 mod plan;
+mod read_attachment;
 mod read_file;
+mod read_symbol;
+mod replace_in_files;
 mod request_user_input;
+mod run_tests;
+mod scaffold;
 mod search_tool_bm25;
 mod shell;
+mod summarize_profile;
+mod symbolicate_backtrace;
+mod terraform;
 mod test_sync;
 pub(crate) mod unified_exec;
 mod view_image;
@@ -19,7 +33,15 @@ pub use plan::PLAN_TOOL;
 use serde::Deserialize;
 
 use crate::function_tool::FunctionCallError;
+pub use analyze_log::AnalyzeLogHandler;
 pub use apply_patch::ApplyPatchHandler;
+pub use call_graph::CallGraphHandler;
+pub(crate) use call_graph::WHAT_DOES_THIS_CALL_TOOL_NAME;
+pub(crate) use call_graph::WHO_CALLS_TOOL_NAME;
+pub use check_build::CheckBuildHandler;
+pub use cloud_cli::CloudCliHandler;
+pub use container_image::BuildImageHandler;
+pub use container_image::RunInImageHandler;
 pub use dynamic::DynamicToolHandler;
 pub use grep_files::GrepFilesHandler;
 pub use js_repl::JsReplHandler;
@@ -28,15 +50,25 @@ pub use list_dir::ListDirHandler;
 pub use mcp::McpHandler;
 pub use mcp_resource::McpResourceHandler;
 pub use multi_agents::MultiAgentHandler;
+pub use package_info::PackageInfoHandler;
 pub use plan::PlanHandler;
+pub use read_attachment::ReadAttachmentHandler;
 pub use read_file::ReadFileHandler;
+pub use read_symbol::ReadSymbolHandler;
+pub use replace_in_files::ReplaceInFilesHandler;
 pub use request_user_input::RequestUserInputHandler;
 pub(crate) use request_user_input::request_user_input_tool_description;
+pub use run_tests::RunTestsHandler;
+pub use scaffold::ScaffoldHandler;
 pub(crate) use search_tool_bm25::DEFAULT_LIMIT as SEARCH_TOOL_BM25_DEFAULT_LIMIT;
 pub(crate) use search_tool_bm25::SEARCH_TOOL_BM25_TOOL_NAME;
 pub use search_tool_bm25::SearchToolBm25Handler;
 pub use shell::ShellCommandHandler;
 pub use shell::ShellHandler;
+pub use summarize_profile::SummarizeProfileHandler;
+pub use symbolicate_backtrace::SymbolicateBacktraceHandler;
+pub use terraform::TerraformApplyHandler;
+pub use terraform::TerraformPlanHandler;
 pub use test_sync::TestSyncHandler;
 pub use unified_exec::UnifiedExecHandler;
 pub use view_image::ViewImageHandler;
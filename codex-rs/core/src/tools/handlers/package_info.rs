@@ -0,0 +1,481 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use url::Url;
+
+use crate::config::types::ToolFreshness;
+use crate::default_client::build_reqwest_client;
+use crate::default_client::read_json_limited;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Looks up a package on crates.io, npm, or PyPI: its latest version, the
+/// yanked or deprecated state of the version asked about, recent releases,
+/// and the OSV advisories affecting that version.
+pub struct PackageInfoHandler;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const RECENT_VERSIONS: usize = 10;
+/// Registry and OSV answers longer than this are refused.
+const MAX_RESPONSE_BYTES: usize = 16 * 1024 * 1024;
+const OSV_QUERY_URL: &str = "https://api.osv.dev/v1/query";
+
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+enum Ecosystem {
+    #[serde(rename = "crates.io", alias = "cargo")]
+    CratesIo,
+    #[serde(rename = "npm")]
+    Npm,
+    #[serde(rename = "pypi", alias = "PyPI")]
+    PyPi,
+}
+
+impl Ecosystem {
+    /// The ecosystem's name in OSV.
+    fn osv_name(self) -> &'static str {
+        match self {
+            Ecosystem::CratesIo => "crates.io",
+            Ecosystem::Npm => "npm",
+            Ecosystem::PyPi => "PyPI",
+        }
+    }
+
+    fn metadata_url(self, name: &str) -> Url {
+        let (base, suffix): (&str, &[&str]) = match self {
+            Ecosystem::CratesIo => ("https://crates.io/api/v1/crates/", &[]),
+            Ecosystem::Npm => ("https://registry.npmjs.org/", &[]),
+            Ecosystem::PyPi => ("https://pypi.org/pypi/", &["json"]),
+        };
+        let mut url = Url::parse(base).unwrap_or_else(|err| panic!("invalid registry url: {err}"));
+        if let Ok(mut segments) = url.path_segments_mut() {
+            segments.pop_if_empty().push(name).extend(suffix);
+        }
+        url
+    }
+}
+
+#[derive(Deserialize)]
+struct PackageInfoArgs {
+    ecosystem: Ecosystem,
+    name: String,
+    #[serde(default)]
+    version: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct PackageSummary {
+    latest: Option<String>,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    dist_tags: BTreeMap<String, String>,
+    /// Why the package as a whole should not be used, when its registry
+    /// says so.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecated: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    requested: Option<VersionInfo>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    recent_versions: Vec<VersionInfo>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+struct VersionInfo {
+    version: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    published: Option<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    yanked: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    deprecated: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct Advisory {
+    id: String,
+    summary: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    aliases: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    severity: Option<String>,
+    fixed_in: Vec<String>,
+}
+
+#[async_trait]
+impl ToolHandler for PackageInfoHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_idempotent(&self) -> bool {
+        true
+    }
+
+    fn freshness(&self) -> Option<ToolFreshness> {
+        Some(ToolFreshness::Stable)
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        if !invocation.turn.sandbox_policy.has_full_network_access() {
+            return Err(FunctionCallError::RespondToModel(
+                "package_info needs network access, which the sandbox policy does not allow"
+                    .to_string(),
+            ));
+        }
+        let arguments = match invocation.payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "package_info handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: PackageInfoArgs = parse_arguments(&arguments)?;
+        let name = args.name.trim();
+        if name.is_empty()
+            || !name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '@' | '/' | '.' | '_' | '-'))
+        {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`{name}` is not a package name"
+            )));
+        }
+
+        let client = build_reqwest_client();
+        let mut request = client
+            .get(args.ecosystem.metadata_url(name))
+            .timeout(REQUEST_TIMEOUT);
+        if args.ecosystem == Ecosystem::Npm {
+            // The abbreviated document, which leaves out readmes of every
+            // version.
+            request = request.header("Accept", "application/vnd.npm.install-v1+json");
+        }
+        let response = request.send().await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to query the registry: {err}"))
+        })?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "`{name}` does not exist on {}",
+                args.ecosystem.osv_name()
+            )));
+        }
+        if !response.status().is_success() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "the registry answered {}",
+                response.status()
+            )));
+        }
+        let metadata: Value = read_json_limited(response, MAX_RESPONSE_BYTES)
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to read the registry answer: {err}"
+                ))
+            })?;
+        let version = args.version.as_deref();
+        let summary = match args.ecosystem {
+            Ecosystem::CratesIo => summarize_crates_io(&metadata, version),
+            Ecosystem::Npm => summarize_npm(&metadata, version),
+            Ecosystem::PyPi => summarize_pypi(&metadata, version),
+        };
+
+        let advisory_version = version.or(summary.latest.as_deref());
+        let advisories = match advisory_version {
+            Some(advisory_version) => {
+                let query = json!({
+                    "version": advisory_version,
+                    "package": { "name": name, "ecosystem": args.ecosystem.osv_name() },
+                });
+                let response = client
+                    .post(OSV_QUERY_URL)
+                    .timeout(REQUEST_TIMEOUT)
+                    .json(&query)
+                    .send()
+                    .await
+                    .and_then(reqwest::Response::error_for_status);
+                match response {
+                    Ok(response) => read_json_limited::<Value>(response, MAX_RESPONSE_BYTES)
+                        .await
+                        .map(|osv| json!(advisories(&osv)))
+                        .unwrap_or_else(|err| json!(format!("unavailable: {err}"))),
+                    Err(err) => json!(format!("unavailable: {err}")),
+                }
+            }
+            None => json!([]),
+        };
+
+        let mut report = serde_json::to_value(&summary).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to serialize package info: {err}"))
+        })?;
+        report["ecosystem"] = json!(args.ecosystem.osv_name());
+        report["name"] = json!(name);
+        report["advisories_for"] = json!(advisory_version);
+        report["advisories"] = advisories;
+        let content = serde_json::to_string_pretty(&report).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to serialize package info: {err}"))
+        })?;
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(content),
+            success: Some(true),
+        })
+    }
+}
+
+fn text(value: &Value) -> Option<String> {
+    value.as_str().map(str::to_string)
+}
+
+/// Summarizes `GET /api/v1/crates/{name}`, whose versions are newest first.
+fn summarize_crates_io(metadata: &Value, version: Option<&str>) -> PackageSummary {
+    let versions = metadata["versions"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|entry| VersionInfo {
+            version: text(&entry["num"]).unwrap_or_default(),
+            published: text(&entry["created_at"]),
+            yanked: entry["yanked"].as_bool().unwrap_or(false),
+            deprecated: text(&entry["yank_message"]),
+        })
+        .collect::<Vec<_>>();
+    let krate = &metadata["crate"];
+    PackageSummary {
+        latest: text(&krate["max_stable_version"]).or_else(|| text(&krate["max_version"])),
+        requested: version.and_then(|version| {
+            versions
+                .iter()
+                .find(|info| info.version == version)
+                .cloned()
+        }),
+        recent_versions: versions.into_iter().take(RECENT_VERSIONS).collect(),
+        ..Default::default()
+    }
+}
+
+/// Summarizes npm's abbreviated package document, which has no publish
+/// times, so no recent versions either.
+fn summarize_npm(metadata: &Value, version: Option<&str>) -> PackageSummary {
+    let dist_tags = metadata["dist-tags"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(tag, version)| Some((tag.clone(), text(version)?)))
+        .collect::<BTreeMap<_, _>>();
+    let latest = dist_tags.get("latest").cloned();
+    let info = |version: &str| {
+        let entry = metadata["versions"].get(version)?;
+        Some(VersionInfo {
+            version: version.to_string(),
+            deprecated: text(&entry["deprecated"]),
+            ..Default::default()
+        })
+    };
+    PackageSummary {
+        deprecated: latest
+            .as_deref()
+            .and_then(info)
+            .and_then(|latest| latest.deprecated),
+        requested: version.and_then(info),
+        latest,
+        dist_tags,
+        ..Default::default()
+    }
+}
+
+/// Summarizes `GET /pypi/{name}/json`. A release is yanked when all its
+/// files are.
+fn summarize_pypi(metadata: &Value, version: Option<&str>) -> PackageSummary {
+    let mut versions = metadata["releases"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter_map(|(version, files)| {
+            let files = files.as_array().filter(|files| !files.is_empty())?;
+            Some(VersionInfo {
+                version: version.clone(),
+                published: files
+                    .iter()
+                    .filter_map(|file| file["upload_time_iso_8601"].as_str())
+                    .min()
+                    .map(str::to_string),
+                yanked: files.iter().all(|file| file["yanked"] == true),
+                deprecated: files.iter().find_map(|file| text(&file["yanked_reason"])),
+            })
+        })
+        .collect::<Vec<_>>();
+    versions.sort_by(|a, b| b.published.cmp(&a.published));
+    let info = &metadata["info"];
+    let inactive = info["classifiers"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .any(|classifier| classifier == "Development Status :: 7 - Inactive");
+    let requested_index =
+        version.and_then(|version| versions.iter().position(|info| info.version == version));
+    let requested = requested_index.map(|index| versions.remove(index));
+    let mut recent_versions = versions;
+    recent_versions.truncate(RECENT_VERSIONS);
+    PackageSummary {
+        latest: text(&info["version"]),
+        deprecated: inactive.then(|| "marked inactive by its maintainers".to_string()),
+        requested,
+        recent_versions,
+        ..Default::default()
+    }
+}
+
+/// The advisories of an OSV `/v1/query` answer, with the versions that fix
+/// each.
+fn advisories(osv: &Value) -> Vec<Advisory> {
+    osv["vulns"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .map(|vuln| {
+            let mut fixed_in = vuln["affected"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .flat_map(|affected| affected["ranges"].as_array().into_iter().flatten())
+                .flat_map(|range| range["events"].as_array().into_iter().flatten())
+                .filter_map(|event| text(&event["fixed"]))
+                .collect::<Vec<_>>();
+            fixed_in.dedup();
+            Advisory {
+                id: text(&vuln["id"]).unwrap_or_default(),
+                summary: text(&vuln["summary"])
+                    .or_else(|| text(&vuln["details"]))
+                    .unwrap_or_default(),
+                aliases: vuln["aliases"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(text)
+                    .collect(),
+                severity: text(&vuln["database_specific"]["severity"]),
+                fixed_in,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn summarizes_registry_metadata() {
+        let crates_io = json!({
+            "crate": { "max_stable_version": "1.2.0", "max_version": "2.0.0-rc.1" },
+            "versions": [
+                { "num": "2.0.0-rc.1", "created_at": "2026-09-01T00:00:00Z", "yanked": false },
+                { "num": "1.2.0", "created_at": "2026-06-01T00:00:00Z", "yanked": false },
+                { "num": "1.1.0", "created_at": "2026-01-01T00:00:00Z", "yanked": true,
+                  "yank_message": "unsound" },
+            ],
+        });
+        assert_eq!(
+            summarize_crates_io(&crates_io, Some("1.1.0")).requested,
+            Some(VersionInfo {
+                version: "1.1.0".to_string(),
+                published: Some("2026-01-01T00:00:00Z".to_string()),
+                yanked: true,
+                deprecated: Some("unsound".to_string()),
+            })
+        );
+
+        let npm = json!({
+            "dist-tags": { "latest": "4.0.0", "next": "5.0.0-beta.0" },
+            "versions": {
+                "3.0.0": { "deprecated": "Use 4.x" },
+                "4.0.0": {},
+                "5.0.0-beta.0": {},
+            },
+        });
+        assert_eq!(
+            summarize_npm(&npm, Some("3.0.0")),
+            PackageSummary {
+                latest: Some("4.0.0".to_string()),
+                dist_tags: BTreeMap::from([
+                    ("latest".to_string(), "4.0.0".to_string()),
+                    ("next".to_string(), "5.0.0-beta.0".to_string()),
+                ]),
+                deprecated: None,
+                requested: Some(VersionInfo {
+                    version: "3.0.0".to_string(),
+                    deprecated: Some("Use 4.x".to_string()),
+                    ..Default::default()
+                }),
+                recent_versions: Vec::new(),
+            }
+        );
+
+        let pypi = json!({
+            "info": { "version": "2.1", "classifiers": ["Development Status :: 7 - Inactive"] },
+            "releases": {
+                "2.0": [{ "upload_time_iso_8601": "2025-01-01T00:00:00Z", "yanked": true,
+                          "yanked_reason": "broken wheel" }],
+                "2.1": [{ "upload_time_iso_8601": "2025-02-01T00:00:00Z", "yanked": false }],
+                "0.1": [],
+            },
+        });
+        assert_eq!(
+            summarize_pypi(&pypi, None),
+            PackageSummary {
+                latest: Some("2.1".to_string()),
+                deprecated: Some("marked inactive by its maintainers".to_string()),
+                recent_versions: vec![
+                    VersionInfo {
+                        version: "2.1".to_string(),
+                        published: Some("2025-02-01T00:00:00Z".to_string()),
+                        ..Default::default()
+                    },
+                    VersionInfo {
+                        version: "2.0".to_string(),
+                        published: Some("2025-01-01T00:00:00Z".to_string()),
+                        yanked: true,
+                        deprecated: Some("broken wheel".to_string()),
+                    },
+                ],
+                ..Default::default()
+            }
+        );
+    }
+
+    #[test]
+    fn extracts_osv_advisories_with_fixed_versions() {
+        let osv = json!({
+            "vulns": [{
+                "id": "RUSTSEC-2026-0001",
+                "summary": "Out-of-bounds read in parser",
+                "aliases": ["CVE-2026-1234"],
+                "database_specific": { "severity": "HIGH" },
+                "affected": [{
+                    "ranges": [{ "events": [{ "introduced": "0" }, { "fixed": "1.2.0" }] }],
+                }],
+            }],
+        });
+        assert_eq!(
+            advisories(&osv),
+            vec![Advisory {
+                id: "RUSTSEC-2026-0001".to_string(),
+                summary: "Out-of-bounds read in parser".to_string(),
+                aliases: vec!["CVE-2026-1234".to_string()],
+                severity: Some("HIGH".to_string()),
+                fixed_in: vec!["1.2.0".to_string()],
+            }]
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::openai_models::InputModality;
+use codex_utils_string::take_bytes_at_char_boundary;
+use serde::Deserialize;
+
+use crate::attachments::Attachment;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::registry::ToolPriority;
+
+pub struct ReadAttachmentHandler;
+
+const MAX_LINE_LENGTH: usize = 500;
+const MAX_LIMIT: usize = 2_000;
+
+fn default_offset() -> usize {
+    1
+}
+
+fn default_limit() -> usize {
+    200
+}
+
+#[derive(Deserialize)]
+struct ReadAttachmentArgs {
+    id: String,
+    #[serde(default = "default_offset")]
+    offset: usize,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+#[async_trait]
+impl ToolHandler for ReadAttachmentHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_cache_eligible(&self) -> bool {
+        true
+    }
+
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Interactive
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "read_attachment handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let ReadAttachmentArgs { id, offset, limit } = parse_arguments(&arguments)?;
+        if offset == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "offset must be a 1-indexed line number".to_string(),
+            ));
+        }
+        if limit == 0 {
+            return Err(FunctionCallError::RespondToModel(
+                "limit must be greater than zero".to_string(),
+            ));
+        }
+
+        let Some(attachment) = session.attachment(&id).await else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "no attachment with id `{id}`; attachments are not kept when a session is resumed"
+            )));
+        };
+
+        if attachment.is_image() {
+            if !turn
+                .model_info
+                .input_modalities
+                .contains(&InputModality::Image)
+            {
+                return Err(FunctionCallError::RespondToModel(
+                    "attachment is an image, but you do not support image inputs".to_string(),
+                ));
+            }
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::ContentItems(vec![
+                    FunctionCallOutputContentItem::InputImage {
+                        image_url: attachment.image_url(),
+                    },
+                ]),
+                success: Some(true),
+            });
+        }
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(read_lines(&attachment, offset, limit)?),
+            success: Some(true),
+        })
+    }
+}
+
+fn read_lines(
+    attachment: &Attachment,
+    offset: usize,
+    limit: usize,
+) -> Result<String, FunctionCallError> {
+    let total_lines = attachment.content.lines().count();
+    if offset > total_lines {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "offset {offset} exceeds the attachment length of {total_lines} lines"
+        )));
+    }
+    let limit = limit.min(MAX_LIMIT);
+    let lines: Vec<String> = attachment
+        .content
+        .lines()
+        .enumerate()
+        .skip(offset - 1)
+        .take(limit)
+        .map(|(index, line)| {
+            format!(
+                "L{}: {}",
+                index + 1,
+                take_bytes_at_char_boundary(line, MAX_LINE_LENGTH)
+            )
+        })
+        .collect();
+    let last = offset + lines.len() - 1;
+    let mut output = format!(
+        "Lines {offset}-{last} of {total_lines} from {}:\n{}",
+        attachment.id,
+        lines.join("\n")
+    );
+    if last < total_lines {
+        output.push_str(&format!(
+            "\n\n{} more lines; continue with offset {}.",
+            total_lines - last,
+            last + 1
+        ));
+    }
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    fn attachment(content: &str) -> Attachment {
+        Attachment {
+            id: "att-1".to_string(),
+            content_type: "text/plain".to_string(),
+            name: None,
+            content: Arc::from(content),
+        }
+    }
+
+    #[test]
+    fn reads_the_requested_range() {
+        let attachment = attachment("one\ntwo\nthree\nfour\n");
+
+        assert_eq!(
+            read_lines(&attachment, 2, 2).expect("read lines"),
+            "Lines 2-3 of 4 from att-1:\nL2: two\nL3: three\n\n1 more lines; continue with offset 4."
+        );
+        assert_eq!(
+            read_lines(&attachment, 4, 10).expect("read lines"),
+            "Lines 4-4 of 4 from att-1:\nL4: four"
+        );
+        assert!(read_lines(&attachment, 5, 10).is_err());
+    }
+}
@@ -7,17 +7,24 @@ use codex_utils_string::take_bytes_at_char_boundary;
 use serde::Deserialize;
 
 use crate::function_tool::FunctionCallError;
+use crate::read_filters::apply_read_filters;
+use crate::read_filters::filters_for_path;
+use crate::read_filters::read_filter_name;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
 use crate::tools::handlers::parse_arguments;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
+use crate::tools::registry::ToolPriority;
 
 pub struct ReadFileHandler;
 
 const MAX_LINE_LENGTH: usize = 500;
 const TAB_WIDTH: usize = 4;
+/// Largest file read filters are applied to. They need the whole file in memory, so larger
+/// files are read as a plain slice.
+const MAX_FILTERED_FILE_BYTES: u64 = 4 * 1024 * 1024;
 
 // TODO(jif) add support for block comments
 const COMMENT_PREFIXES: &[&str] = &["#", "//", "--"];
@@ -98,8 +105,16 @@ impl ToolHandler for ReadFileHandler {
         ToolKind::Function
     }
 
+    fn is_cache_eligible(&self) -> bool {
+        true
+    }
+
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Interactive
+    }
+
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
-        let ToolInvocation { payload, .. } = invocation;
+        let ToolInvocation { turn, payload, .. } = invocation;
 
         let arguments = match payload {
             ToolPayload::Function { arguments } => arguments,
@@ -139,7 +154,28 @@ impl ToolHandler for ReadFileHandler {
             ));
         }
 
+        let filters = filters_for_path(&turn.config.read_filters, &path, &turn.cwd);
         let collected = match mode {
+            ReadMode::Slice if !filters.is_empty() => {
+                match slice::read_whole(&path, MAX_FILTERED_FILE_BYTES).await? {
+                    Some(content) => {
+                        let filtered =
+                            apply_read_filters(&filters, &path, &String::from_utf8_lossy(&content));
+                        let names = filters
+                            .iter()
+                            .map(|filter| read_filter_name(*filter))
+                            .collect::<Vec<_>>()
+                            .join(", ");
+                        let mut collected = vec![format!(
+                            "[read filters applied: {names}; line numbers refer to the filtered content]"
+                        )];
+                        collected
+                            .extend(slice::read_lines(filtered.as_bytes(), offset, limit).await?);
+                        collected
+                    }
+                    None => slice::read(&path, offset, limit).await?,
+                }
+            }
             ReadMode::Slice => slice::read(&path, offset, limit).await?,
             ReadMode::Indentation => {
                 let indentation = indentation.unwrap_or_default();
@@ -158,7 +194,9 @@ mod slice {
     use crate::tools::handlers::read_file::format_line;
     use std::path::Path;
     use tokio::fs::File;
+    use tokio::io::AsyncBufRead;
     use tokio::io::AsyncBufReadExt;
+    use tokio::io::AsyncReadExt;
     use tokio::io::BufReader;
 
     pub async fn read(
@@ -169,8 +207,34 @@ mod slice {
         let file = File::open(path).await.map_err(|err| {
             FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
         })?;
+        read_lines(BufReader::new(file), offset, limit).await
+    }
 
-        let mut reader = BufReader::new(file);
+    /// Reads all of `path`, or returns `None` without buffering more than `max_bytes` when the
+    /// file is larger than that.
+    pub async fn read_whole(
+        path: &Path,
+        max_bytes: u64,
+    ) -> Result<Option<Vec<u8>>, FunctionCallError> {
+        let file = File::open(path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+        })?;
+        let mut content = Vec::new();
+        file.take(max_bytes.saturating_add(1))
+            .read_to_end(&mut content)
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+            })?;
+        Ok((content.len() as u64 <= max_bytes).then_some(content))
+    }
+
+    /// Like [`read`], over content that was already loaded (and filtered).
+    pub async fn read_lines(
+        mut reader: impl AsyncBufRead + Unpin,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<String>, FunctionCallError> {
         let mut collected = Vec::new();
         let mut seen = 0usize;
         let mut buffer = Vec::new();
@@ -429,7 +493,7 @@ mod indentation {
     }
 }
 
-fn format_line(bytes: &[u8]) -> String {
+pub(super) fn format_line(bytes: &[u8]) -> String {
     let decoded = String::from_utf8_lossy(bytes);
     if decoded.len() > MAX_LINE_LENGTH {
         take_bytes_at_char_boundary(&decoded, MAX_LINE_LENGTH).to_string()
@@ -487,6 +551,7 @@ mod defaults {
 mod tests {
     use super::indentation::read_block;
     use super::slice::read;
+    use super::slice::read_whole;
     use super::*;
     use pretty_assertions::assert_eq;
     use tempfile::NamedTempFile;
@@ -508,6 +573,20 @@ gamma
         Ok(())
     }
 
+    #[tokio::test]
+    async fn reads_whole_file_only_up_to_the_cap() -> anyhow::Result<()> {
+        let mut temp = NamedTempFile::new()?;
+        use std::io::Write as _;
+        write!(temp, "alpha\nbeta\n")?;
+
+        assert_eq!(
+            read_whole(temp.path(), 11).await?,
+            Some(b"alpha\nbeta\n".to_vec())
+        );
+        assert_eq!(read_whole(temp.path(), 10).await?, None);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn errors_when_offset_exceeds_length() -> anyhow::Result<()> {
         let mut temp = NamedTempFile::new()?;
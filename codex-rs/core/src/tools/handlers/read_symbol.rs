@@ -0,0 +1,455 @@
+use codex_protocol::models::FunctionCallOutputBody;
+use std::path::Path;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tree_sitter::Language;
+use tree_sitter::Node;
+use tree_sitter::Parser;
+use tree_sitter::Tree;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::read_file::format_line;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::tools::registry::ToolPriority;
+
+/// Returns the definition of one symbol in a source file, with its doc
+/// comments and the first line of each enclosing definition, so the model can
+/// read one function without reading the whole file.
+pub struct ReadSymbolHandler;
+
+/// Most definitions returned for one symbol.
+const MAX_MATCHES: usize = 5;
+/// Most lines returned for one definition.
+const MAX_DEFINITION_LINES: usize = 400;
+
+/// Node kinds that define a symbol, with the field holding the symbol's name.
+const RUST_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_item", "name"),
+    ("function_signature_item", "name"),
+    ("struct_item", "name"),
+    ("enum_item", "name"),
+    ("union_item", "name"),
+    ("trait_item", "name"),
+    ("impl_item", "type"),
+    ("type_item", "name"),
+    ("const_item", "name"),
+    ("static_item", "name"),
+    ("mod_item", "name"),
+    ("macro_definition", "name"),
+];
+const PYTHON_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_definition", "name"),
+    ("class_definition", "name"),
+];
+const JAVASCRIPT_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "name"),
+    ("generator_function_declaration", "name"),
+    ("class_declaration", "name"),
+    ("abstract_class_declaration", "name"),
+    ("method_definition", "name"),
+    ("interface_declaration", "name"),
+    ("type_alias_declaration", "name"),
+    ("enum_declaration", "name"),
+    ("variable_declarator", "name"),
+];
+const GO_DEFINITIONS: &[(&str, &str)] = &[
+    ("function_declaration", "name"),
+    ("method_declaration", "name"),
+    ("type_spec", "name"),
+];
+
+/// Node kinds that call a function, with the field holding the callee.
+const RUST_CALLS: &[(&str, &str)] = &[("call_expression", "function")];
+const PYTHON_CALLS: &[(&str, &str)] = &[("call", "function")];
+const JAVASCRIPT_CALLS: &[(&str, &str)] = &[
+    ("call_expression", "function"),
+    ("new_expression", "constructor"),
+];
+const GO_CALLS: &[(&str, &str)] = &[("call_expression", "function")];
+
+/// Nodes that wrap a definition and belong in its excerpt (decorators,
+/// `export`, the `const` of a declarator, Go's `type`).
+const WRAPPER_KINDS: &[&str] = &[
+    "decorated_definition",
+    "export_statement",
+    "lexical_declaration",
+    "variable_declaration",
+    "type_declaration",
+];
+/// Siblings directly above a definition that document it.
+const DOC_KINDS: &[&str] = &["line_comment", "block_comment", "comment", "attribute_item"];
+
+#[derive(Deserialize)]
+struct ReadSymbolArgs {
+    path: String,
+    symbol: String,
+}
+
+#[async_trait]
+impl ToolHandler for ReadSymbolHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    fn is_cache_eligible(&self) -> bool {
+        true
+    }
+
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Interactive
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation { turn, payload, .. } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "read_symbol handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: ReadSymbolArgs = parse_arguments(&arguments)?;
+
+        let path = turn.resolve_path(Some(args.path));
+        let source = tokio::fs::read_to_string(&path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to read file: {err}"))
+        })?;
+        let excerpts = read_symbol(&path, &source, args.symbol.trim())?;
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(excerpts.join("\n\n")),
+            success: Some(true),
+        })
+    }
+}
+
+/// The excerpts defining `symbol` in `source`. `symbol` may be qualified by
+/// its enclosing definitions, as in `Type::method` or `Class.method`.
+fn read_symbol(path: &Path, source: &str, symbol: &str) -> Result<Vec<String>, FunctionCallError> {
+    let (name, qualifiers) = split_symbol(symbol)?;
+    let grammar = SourceGrammar::for_path(path)?;
+    let tree = grammar.parse(path, source)?;
+
+    let lines: Vec<&str> = source.lines().collect();
+    let mut excerpts = Vec::new();
+    walk_definitions(&tree, source, &grammar, |node, defined, containers| {
+        if defined == Some(name) && is_qualified_by(node, source, containers, &qualifiers) {
+            excerpts.push(excerpt(path, &lines, node, containers));
+        }
+    });
+
+    if excerpts.is_empty() {
+        return Err(FunctionCallError::RespondToModel(format!(
+            "no definition of `{symbol}` found in `{}`",
+            path.display()
+        )));
+    }
+    let total = excerpts.len();
+    if total > MAX_MATCHES {
+        excerpts.truncate(MAX_MATCHES);
+        excerpts.push(format!(
+            "[{} more definitions of `{symbol}` omitted; qualify the symbol to narrow it down]",
+            total - MAX_MATCHES
+        ));
+    }
+    Ok(excerpts)
+}
+
+/// The tree-sitter grammar for a source file, with the node kinds that define
+/// and call symbols in it.
+pub(super) struct SourceGrammar {
+    language: Language,
+    definitions: &'static [(&'static str, &'static str)],
+    pub(super) calls: &'static [(&'static str, &'static str)],
+}
+
+impl SourceGrammar {
+    pub(super) fn for_path(path: &Path) -> Result<Self, FunctionCallError> {
+        let (language, definitions, calls): (Language, _, _) =
+            match path.extension().and_then(|extension| extension.to_str()) {
+                Some("rs") => (
+                    tree_sitter_rust::LANGUAGE.into(),
+                    RUST_DEFINITIONS,
+                    RUST_CALLS,
+                ),
+                Some("py" | "pyi") => (
+                    tree_sitter_python::LANGUAGE.into(),
+                    PYTHON_DEFINITIONS,
+                    PYTHON_CALLS,
+                ),
+                Some("js" | "jsx" | "mjs" | "cjs") => (
+                    tree_sitter_javascript::LANGUAGE.into(),
+                    JAVASCRIPT_DEFINITIONS,
+                    JAVASCRIPT_CALLS,
+                ),
+                Some("ts" | "mts" | "cts") => (
+                    tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+                    JAVASCRIPT_DEFINITIONS,
+                    JAVASCRIPT_CALLS,
+                ),
+                Some("tsx") => (
+                    tree_sitter_typescript::LANGUAGE_TSX.into(),
+                    JAVASCRIPT_DEFINITIONS,
+                    JAVASCRIPT_CALLS,
+                ),
+                Some("go") => (tree_sitter_go::LANGUAGE.into(), GO_DEFINITIONS, GO_CALLS),
+                _ => {
+                    return Err(FunctionCallError::RespondToModel(format!(
+                        "`{}` is not a supported source file; supported languages are Rust, \
+                         Python, JavaScript, TypeScript, and Go",
+                        path.display()
+                    )));
+                }
+            };
+        Ok(Self {
+            language,
+            definitions,
+            calls,
+        })
+    }
+
+    pub(super) fn parse(&self, path: &Path, source: &str) -> Result<Tree, FunctionCallError> {
+        let mut parser = Parser::new();
+        parser.set_language(&self.language).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to load grammar: {err}"))
+        })?;
+        parser.parse(source, None).ok_or_else(|| {
+            FunctionCallError::RespondToModel(format!("failed to parse `{}`", path.display()))
+        })
+    }
+}
+
+/// Splits a possibly qualified symbol (`Type::method`, `Class.method`) into its
+/// name and qualifiers.
+pub(super) fn split_symbol(symbol: &str) -> Result<(&str, Vec<&str>), FunctionCallError> {
+    let mut qualifiers: Vec<&str> = symbol
+        .split("::")
+        .flat_map(|segment| segment.split('.'))
+        .map(str::trim)
+        .filter(|segment| !segment.is_empty())
+        .collect();
+    let name = qualifiers
+        .pop()
+        .ok_or_else(|| FunctionCallError::RespondToModel("symbol must not be empty".to_string()))?;
+    Ok((name, qualifiers))
+}
+
+/// Visits every node in `tree` in source order, with the name it defines (if it
+/// is a definition) and the names and nodes of the definitions enclosing it.
+pub(super) fn walk_definitions<'tree>(
+    tree: &'tree Tree,
+    source: &str,
+    grammar: &SourceGrammar,
+    mut visit: impl FnMut(Node<'tree>, Option<&str>, &[(String, Node<'tree>)]),
+) {
+    let mut stack = vec![(tree.root_node(), Vec::<(String, Node)>::new())];
+    while let Some((node, containers)) = stack.pop() {
+        let defined = definition_name(node, source, grammar.definitions);
+        visit(node, defined.as_deref(), &containers);
+
+        let mut child_containers = containers;
+        if let Some(defined) = defined {
+            child_containers.push((defined, node));
+        }
+        let mut cursor = node.walk();
+        let first_child = stack.len();
+        stack.extend(
+            node.named_children(&mut cursor)
+                .map(|child| (child, child_containers.clone())),
+        );
+        stack[first_child..].reverse();
+    }
+}
+
+/// Whether the definition at `node` sits inside definitions named by
+/// `qualifiers`, innermost last. A Go method is qualified by its receiver type.
+pub(super) fn is_qualified_by(
+    node: Node,
+    source: &str,
+    containers: &[(String, Node)],
+    qualifiers: &[&str],
+) -> bool {
+    let mut qualified_by: Vec<&str> = containers
+        .iter()
+        .map(|(container, _)| container.as_str())
+        .collect();
+    let receiver = go_receiver_type(node, source);
+    qualified_by.extend(receiver.as_deref());
+    qualified_by.ends_with(qualifiers)
+}
+
+/// The name `node` defines, if it is a definition. Generic parameters are
+/// dropped, so `impl<T> Foo<T>` defines `Foo`.
+fn definition_name(node: Node, source: &str, definitions: &[(&str, &str)]) -> Option<String> {
+    let (_, name_field) = definitions.iter().find(|(kind, _)| *kind == node.kind())?;
+    let name = node
+        .child_by_field_name(name_field)?
+        .utf8_text(source.as_bytes())
+        .ok()?;
+    let name = name.split('<').next().unwrap_or(name).trim();
+    Some(name.rsplit("::").next().unwrap_or(name).to_string())
+}
+
+/// The type a Go method is declared on, which qualifies it like an enclosing
+/// definition would.
+pub(super) fn go_receiver_type(node: Node, source: &str) -> Option<String> {
+    if node.kind() != "method_declaration" {
+        return None;
+    }
+    let receiver = node
+        .child_by_field_name("receiver")?
+        .utf8_text(source.as_bytes())
+        .ok()?;
+    let receiver = receiver
+        .trim_matches(|c| c == '(' || c == ')')
+        .split_whitespace()
+        .last()?
+        .trim_start_matches('*');
+    Some(receiver.split('[').next().unwrap_or(receiver).to_string())
+}
+
+/// The definition at `node`, preceded by its doc comments and the first line of
+/// each enclosing definition.
+fn excerpt(path: &Path, lines: &[&str], node: Node, containers: &[(String, Node)]) -> String {
+    let mut node = node;
+    while let Some(parent) = node.parent()
+        && WRAPPER_KINDS.contains(&parent.kind())
+    {
+        node = parent;
+    }
+    let mut start = node.start_position().row;
+    let mut sibling = node.prev_named_sibling();
+    while let Some(doc) = sibling
+        && DOC_KINDS.contains(&doc.kind())
+        && doc.end_position().row + 1 >= start
+    {
+        start = doc.start_position().row;
+        sibling = doc.prev_named_sibling();
+    }
+    let end = node.end_position().row;
+
+    let numbered = |row: usize| {
+        format!(
+            "L{}: {}",
+            row + 1,
+            format_line(lines.get(row).unwrap_or(&"").as_bytes())
+        )
+    };
+    let mut excerpt = vec![format!("{}:{}-{}", path.display(), start + 1, end + 1)];
+    for (_, container) in containers {
+        excerpt.push(numbered(container.start_position().row));
+        excerpt.push("...".to_string());
+    }
+    excerpt.extend((start..=end.min(start + MAX_DEFINITION_LINES - 1)).map(numbered));
+    if end >= start + MAX_DEFINITION_LINES {
+        excerpt.push(format!(
+            "[definition continues to line {}; use read_file for the rest]",
+            end + 1
+        ));
+    }
+    excerpt.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn reads_rust_method_with_docs_and_enclosing_impl() {
+        let source = "\
+struct Counter {
+    count: usize,
+}
+
+impl Counter {
+    fn new() -> Self {
+        Self { count: 0 }
+    }
+
+    /// Adds one.
+    #[inline]
+    fn increment(&mut self) {
+        self.count += 1;
+    }
+}
+
+fn increment() {}
+";
+        let excerpts =
+            read_symbol(Path::new("counter.rs"), source, "Counter::increment").expect("found");
+        assert_eq!(
+            excerpts,
+            vec![
+                "counter.rs:10-14
+L5: impl Counter {
+...
+L10:     /// Adds one.
+L11:     #[inline]
+L12:     fn increment(&mut self) {
+L13:         self.count += 1;
+L14:     }"
+                    .to_string()
+            ]
+        );
+
+        let unqualified = read_symbol(Path::new("counter.rs"), source, "increment").expect("found");
+        assert_eq!(unqualified.len(), 2);
+        assert_eq!(unqualified[1], "counter.rs:17-17\nL17: fn increment() {}");
+    }
+
+    #[test]
+    fn reads_python_decorated_method_and_go_method() {
+        let python = "\
+class Greeter:
+    @staticmethod
+    def greet(name):
+        return f\"hi {name}\"
+";
+        assert_eq!(
+            read_symbol(Path::new("greeter.py"), python, "Greeter.greet").expect("found"),
+            vec![
+                "greeter.py:2-4
+L1: class Greeter:
+...
+L2:     @staticmethod
+L3:     def greet(name):
+L4:         return f\"hi {name}\""
+                    .to_string()
+            ]
+        );
+
+        let go = "\
+package main
+
+// Greet says hi.
+func (g *Greeter) Greet() string {
+\treturn \"hi\"
+}
+";
+        assert_eq!(
+            read_symbol(Path::new("greeter.go"), go, "Greeter.Greet").expect("found"),
+            vec![
+                "greeter.go:3-6
+L3: // Greet says hi.
+L4: func (g *Greeter) Greet() string {
+L5: \treturn \"hi\"
+L6: }"
+                    .to_string()
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_missing_symbols_and_unsupported_files() {
+        assert!(read_symbol(Path::new("lib.rs"), "fn present() {}\n", "absent").is_err());
+        assert!(read_symbol(Path::new("notes.txt"), "present\n", "present").is_err());
+    }
+}
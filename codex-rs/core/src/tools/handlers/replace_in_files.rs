@@ -0,0 +1,409 @@
+use codex_protocol::models::FunctionCallOutputBody;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use regex::NoExpand;
+use regex::Regex;
+use regex::RegexBuilder;
+use serde::Deserialize;
+use sha2::Digest;
+use sha2::Sha256;
+use similar::ChangeTag;
+use similar::TextDiff;
+use tokio::process::Command;
+use tokio::time::timeout;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ApplyPatchHandler;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::progress::ToolProgress;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Replaces a regex or literal across the files matching a set of globs.
+/// Without `apply` the call only previews the change as a unified diff,
+/// identified by a preview id. With it, the replacement is re-run against the
+/// files' current contents and applied as a patch through
+/// [`ApplyPatchHandler`] only if it still has the previewed id, so approvals,
+/// sandboxing, and patch events are the same as for a patch the model wrote.
+///
+/// Files are found with `rg`, and the pattern is compiled with the `regex`
+/// crate `rg` uses, with `^` and `$` matching at line boundaries as in `rg`.
+pub struct ReplaceInFilesHandler;
+
+/// Most files one call may change.
+const MAX_FILES: usize = 200;
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+const CONTEXT_LINES: usize = 3;
+
+#[derive(Deserialize)]
+struct ReplaceInFilesArgs {
+    pattern: String,
+    replacement: String,
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    path: Option<String>,
+    #[serde(default)]
+    literal: bool,
+    #[serde(default)]
+    apply: bool,
+    #[serde(default)]
+    preview_id: Option<String>,
+}
+
+struct FileReplacement {
+    path: PathBuf,
+    original: String,
+    replaced: String,
+    count: usize,
+}
+
+#[async_trait]
+impl ToolHandler for ReplaceInFilesHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        match &invocation.payload {
+            ToolPayload::Function { arguments } => !matches!(
+                parse_arguments::<ReplaceInFilesArgs>(arguments),
+                Ok(args) if !args.apply
+            ),
+            _ => true,
+        }
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let args: ReplaceInFilesArgs = match &invocation.payload {
+            ToolPayload::Function { arguments } => parse_arguments(arguments)?,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "replace_in_files handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        if args.pattern.is_empty() {
+            return Err(FunctionCallError::RespondToModel(
+                "pattern must not be empty".to_string(),
+            ));
+        }
+        let pattern = if args.literal {
+            regex::escape(&args.pattern)
+        } else {
+            args.pattern.clone()
+        };
+        let regex = RegexBuilder::new(&pattern)
+            .multi_line(true)
+            .build()
+            .map_err(|err| FunctionCallError::RespondToModel(format!("invalid pattern: {err}")))?;
+
+        let turn = &invocation.turn;
+        let search_path = turn.resolve_path(args.path.clone());
+        tokio::fs::metadata(&search_path).await.map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "unable to access `{}`: {err}",
+                search_path.display()
+            ))
+        })?;
+
+        let files = list_matching_files(&args, &search_path, &turn.cwd).await?;
+        if files.len() > MAX_FILES {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "pattern matches more than {MAX_FILES} files; narrow `include` or `path`"
+            )));
+        }
+        let progress = ToolProgress::new(&invocation);
+        let (replacements, not_utf8) = replace_in_files(
+            files,
+            &regex,
+            &args.replacement,
+            args.literal,
+            Some(&progress),
+        )
+        .await;
+        if replacements.is_empty() && not_utf8.is_empty() {
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text("No matches found.".to_string()),
+                success: Some(false),
+            });
+        }
+
+        let patch = patch_for(&replacements, &turn.cwd);
+        let preview_id = format!("{:x}", Sha256::digest(patch.as_bytes()))[..16].to_string();
+        if !args.apply || replacements.is_empty() {
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(preview(
+                    &replacements,
+                    &not_utf8,
+                    &preview_id,
+                    &turn.cwd,
+                )),
+                success: Some(!replacements.is_empty()),
+            });
+        }
+        if args.preview_id.as_deref() != Some(preview_id.as_str()) {
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(format!(
+                    "Nothing was changed: the replacement no longer matches the previewed one, \
+                     or no `preview_id` was given. Review this preview instead.\n\n{}",
+                    preview(&replacements, &not_utf8, &preview_id, &turn.cwd)
+                )),
+                success: Some(false),
+            });
+        }
+
+        ApplyPatchHandler
+            .handle(ToolInvocation {
+                payload: ToolPayload::Custom { input: patch },
+                ..invocation
+            })
+            .await
+    }
+}
+
+async fn list_matching_files(
+    args: &ReplaceInFilesArgs,
+    search_path: &Path,
+    cwd: &Path,
+) -> Result<Vec<PathBuf>, FunctionCallError> {
+    let mut command = Command::new("rg");
+    command
+        .current_dir(cwd)
+        .arg("--files-with-matches")
+        .arg("--multiline")
+        .arg("--sort=path")
+        .arg("--no-messages");
+    if args.literal {
+        command.arg("--fixed-strings");
+    }
+    command.arg("--regexp").arg(&args.pattern);
+    for glob in &args.include {
+        command.arg("--glob").arg(glob);
+    }
+    command.arg("--").arg(search_path);
+
+    let output = timeout(COMMAND_TIMEOUT, command.output())
+        .await
+        .map_err(|_| {
+            FunctionCallError::RespondToModel("rg timed out after 30 seconds".to_string())
+        })?
+        .map_err(|err| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to launch rg: {err}. Ensure ripgrep is installed and on PATH."
+            ))
+        })?;
+
+    match output.status.code() {
+        Some(0) => Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect()),
+        Some(1) => Ok(Vec::new()),
+        _ => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            Err(FunctionCallError::RespondToModel(format!(
+                "rg failed: {stderr}"
+            )))
+        }
+    }
+}
+
+/// Runs the replacement over each file, skipping files that it leaves
+/// unchanged. Files that are not UTF-8 text cannot be changed and are
+/// returned separately.
+async fn replace_in_files(
+    files: Vec<PathBuf>,
+    regex: &Regex,
+    replacement: &str,
+    literal: bool,
+    progress: Option<&ToolProgress>,
+) -> (Vec<FileReplacement>, Vec<PathBuf>) {
+    let mut replacements = Vec::new();
+    let mut not_utf8 = Vec::new();
+    let total = u64::try_from(files.len()).unwrap_or(u64::MAX);
+    for (read, path) in (0u64..).zip(files) {
+        if let Some(progress) = progress {
+            progress
+                .report(read, Some(total), Some(format!("Reading {total} files")))
+                .await;
+        }
+        let Ok(bytes) = tokio::fs::read(&path).await else {
+            continue;
+        };
+        let Ok(original) = String::from_utf8(bytes) else {
+            not_utf8.push(path);
+            continue;
+        };
+        let count = regex.find_iter(&original).count();
+        if count == 0 {
+            continue;
+        }
+        let replaced = if literal {
+            regex.replace_all(&original, NoExpand(replacement))
+        } else {
+            regex.replace_all(&original, replacement)
+        }
+        .into_owned();
+        if replaced != original {
+            replacements.push(FileReplacement {
+                path,
+                original,
+                replaced,
+                count,
+            });
+        }
+    }
+    (replacements, not_utf8)
+}
+
+fn display_path<'a>(path: &'a Path, cwd: &Path) -> &'a Path {
+    path.strip_prefix(cwd).unwrap_or(path)
+}
+
+fn preview(
+    replacements: &[FileReplacement],
+    not_utf8: &[PathBuf],
+    preview_id: &str,
+    cwd: &Path,
+) -> String {
+    let total: usize = replacements
+        .iter()
+        .map(|replacement| replacement.count)
+        .sum();
+    let mut preview = format!(
+        "{total} replacements in {files} files. Nothing was changed yet: call replace_in_files \
+         again with the same arguments, `apply: true`, and `preview_id: \"{preview_id}\"` to \
+         apply exactly these changes.\n",
+        files = replacements.len()
+    );
+    if !not_utf8.is_empty() {
+        preview.push_str("These matching files are not UTF-8 text and will not be changed:\n");
+        for path in not_utf8 {
+            preview.push_str(&format!("  {}\n", display_path(path, cwd).display()));
+        }
+    }
+    preview.push('\n');
+    for replacement in replacements {
+        let path = display_path(&replacement.path, cwd).display();
+        preview.push_str(
+            &TextDiff::from_lines(&replacement.original, &replacement.replaced)
+                .unified_diff()
+                .context_radius(CONTEXT_LINES)
+                .header(&format!("a/{path}"), &format!("b/{path}"))
+                .to_string(),
+        );
+    }
+    preview
+}
+
+/// The replacements as `apply_patch` input.
+fn patch_for(replacements: &[FileReplacement], cwd: &Path) -> String {
+    let mut patch = "*** Begin Patch\n".to_string();
+    for replacement in replacements {
+        let path = display_path(&replacement.path, cwd).display();
+        patch.push_str(&format!("*** Update File: {path}\n"));
+        let diff = TextDiff::from_lines(&replacement.original, &replacement.replaced);
+        for group in diff.grouped_ops(CONTEXT_LINES) {
+            patch.push_str("@@\n");
+            for op in &group {
+                for change in diff.iter_changes(op) {
+                    patch.push(match change.tag() {
+                        ChangeTag::Equal => ' ',
+                        ChangeTag::Delete => '-',
+                        ChangeTag::Insert => '+',
+                    });
+                    patch.push_str(change.value());
+                    if change.missing_newline() {
+                        patch.push('\n');
+                    }
+                }
+            }
+        }
+    }
+    patch.push_str("*** End Patch\n");
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn replacement_patch_applies_to_the_previewed_files() {
+        let temp = tempdir().expect("create temp dir");
+        let dir = temp.path();
+        let lib = dir.join("lib.rs");
+        let main = dir.join("main.rs");
+        let lib_original = (1..=12)
+            .map(|line| format!("let value_{line} = old_name({line});\n"))
+            .collect::<String>();
+        std::fs::write(&lib, &lib_original).expect("write lib.rs");
+        std::fs::write(&main, "fn main() {\n    old_name(0);\n}\n").expect("write main.rs");
+
+        let binary = dir.join("data.bin");
+        std::fs::write(&binary, b"old_name(7)\xff").expect("write data.bin");
+
+        let regex = Regex::new(r"old_name\((\d+)\)").expect("regex");
+        let (replacements, not_utf8) = replace_in_files(
+            vec![lib.clone(), main.clone(), binary.clone()],
+            &regex,
+            "new_name($1)",
+            false,
+            None,
+        )
+        .await;
+        assert_eq!(not_utf8, vec![binary]);
+        assert_eq!(
+            replacements
+                .iter()
+                .map(|replacement| replacement.count)
+                .collect::<Vec<_>>(),
+            vec![12, 1]
+        );
+        let preview = preview(&replacements, &not_utf8, "0123456789abcdef", dir);
+        assert!(preview.starts_with("13 replacements in 2 files."));
+        assert!(preview.contains("`preview_id: \"0123456789abcdef\"`"));
+        assert!(preview.contains("will not be changed:\n  data.bin\n"));
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        codex_apply_patch::apply_patch(
+            &patch_for(&replacements, Path::new("/nonexistent")),
+            0,
+            &mut stdout,
+            &mut stderr,
+        )
+        .expect("apply patch");
+        assert_eq!(
+            std::fs::read_to_string(&lib).expect("read lib.rs"),
+            lib_original.replace("old_name", "new_name")
+        );
+        assert_eq!(
+            std::fs::read_to_string(&main).expect("read main.rs"),
+            "fn main() {\n    new_name(0);\n}\n"
+        );
+    }
+
+    #[tokio::test]
+    async fn literal_replacement_does_not_expand_captures() {
+        let temp = tempdir().expect("create temp dir");
+        let file = temp.path().join("prices.txt");
+        std::fs::write(&file, "cost: a.b\ncost: axb\n").expect("write file");
+
+        let regex = Regex::new(&regex::escape("a.b")).expect("regex");
+        let (replacements, _) = replace_in_files(vec![file], &regex, "$1.00", true, None).await;
+        assert_eq!(replacements.len(), 1);
+        assert_eq!(replacements[0].replaced, "cost: $1.00\ncost: axb\n");
+    }
+}
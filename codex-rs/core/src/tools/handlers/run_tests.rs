@@ -0,0 +1,743 @@
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use codex_protocol::models::ShellCommandToolCallParams;
+use regex_lite::Regex;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::exec::ExecToolCallOutput;
+use crate::function_tool::FunctionCallError;
+use crate::git_info::changed_line_ranges;
+use crate::git_info::changed_paths;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::findings_delta::Finding;
+use crate::tools::findings_delta::FindingsDelta;
+use crate::tools::handlers::ShellCommandHandler;
+use crate::tools::handlers::ShellHandler;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::shell::OutputSummary;
+use crate::tools::handlers::shell::RunExecLikeArgs;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Runs the tests most likely affected by the workspace's uncommitted changes
+/// with the project's test framework, and reports each test case's outcome
+/// ahead of the command output. The command goes through the same approval
+/// and sandboxing as `shell_command`.
+pub struct RunTestsHandler;
+
+/// Most test cases listed in the report; the counts cover all of them.
+const MAX_TEST_CASES: usize = 500;
+
+#[derive(Deserialize)]
+struct RunTestsArgs {
+    #[serde(default)]
+    filter: Option<String>,
+    #[serde(default)]
+    framework: Option<TestFramework>,
+    #[serde(default)]
+    workdir: Option<String>,
+    #[serde(default)]
+    timeout_ms: Option<u64>,
+    #[serde(default)]
+    coverage: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum TestFramework {
+    Cargo,
+    Pytest,
+    Jest,
+    Go,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum TestOutcome {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct TestCaseResult {
+    name: String,
+    outcome: TestOutcome,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct TestReport {
+    framework: TestFramework,
+    command: String,
+    passed: usize,
+    failed: usize,
+    skipped: usize,
+    /// Empty when `delta` is set.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    tests: Vec<TestCaseResult>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+    /// Failing tests that changed since the previous run of the same command
+    /// this session.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    delta: Option<FindingsDelta>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    coverage: Option<CoverageSummary>,
+}
+
+/// How well the run covered the lines changed since HEAD.
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct CoverageSummary {
+    /// Changed lines no test executed, as `start-end` ranges per file.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    uncovered_changed_lines: BTreeMap<String, Vec<String>>,
+    /// Changed files the coverage report does not mention, usually because
+    /// no test loaded them.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    unmeasured_files: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for RunTestsHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            call_id,
+            tool_name,
+            payload,
+        } = invocation;
+
+        let ToolPayload::Function { arguments } = payload else {
+            return Err(FunctionCallError::RespondToModel(
+                "run_tests handler received unsupported payload".to_string(),
+            ));
+        };
+        let args: RunTestsArgs = parse_arguments(&arguments)?;
+
+        let dir = turn.resolve_path(args.workdir.clone());
+        let framework = match args.framework {
+            Some(framework) => framework,
+            None => TestFramework::detect(&dir).ok_or_else(|| {
+                FunctionCallError::RespondToModel(format!(
+                    "could not detect a test framework in `{}`; pass `framework` (cargo, \
+                     pytest, jest, or go)",
+                    dir.display()
+                ))
+            })?,
+        };
+        let targets = framework.targets(&dir, &changed_paths(&dir).await);
+        let baseline_session = Arc::clone(&session);
+        let coverage = if args.coverage {
+            Some((
+                std::env::temp_dir().join(format!("codex-coverage-{call_id}")),
+                changed_line_ranges(&dir).await,
+            ))
+        } else {
+            None
+        };
+        let argv = framework.command(
+            args.filter.as_deref(),
+            &targets,
+            coverage.as_ref().map(|(artifact, _)| artifact.as_path()),
+        );
+        let command = shlex::try_join(argv.iter().map(String::as_str)).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to quote test command: {err}"))
+        })?;
+
+        let findings_source = format!("run_tests:{}:{command}", dir.display());
+        let edited = tracker.lock().await.edited_paths();
+
+        let params = ShellCommandToolCallParams {
+            command: command.clone(),
+            workdir: args.workdir,
+            login: None,
+            timeout_ms: args.timeout_ms,
+            sandbox_permissions: None,
+            prefix_rule: None,
+            justification: None,
+        };
+        let exec_params = ShellCommandHandler::to_exec_params(
+            &params,
+            session.as_ref(),
+            turn.as_ref(),
+            session.conversation_id,
+            turn.tools_config.allow_login_shell,
+        )?;
+        ShellHandler::run_exec_like(RunExecLikeArgs {
+            tool_name,
+            exec_params,
+            prefix_rule: None,
+            session,
+            turn,
+            tracker,
+            call_id,
+            freeform: true,
+            summarize_output: Some(Box::new(move |output: &ExecToolCallOutput| {
+                let mut report = framework.report(command, &output.aggregated_output.text);
+                let failures: Vec<Finding> = report
+                    .tests
+                    .iter()
+                    .filter(|test| test.outcome == TestOutcome::Failed)
+                    .map(|test| Finding {
+                        file: None,
+                        line: None,
+                        kind: "failed".to_string(),
+                        message: test.name.clone(),
+                    })
+                    .collect();
+                if !report.truncated
+                    // Both tools are mutating, so they run one at a time
+                    // and the baselines are never locked here already.
+                    && let Ok(mut baselines) = baseline_session.services.finding_baselines.try_lock()
+                    && let Some(delta) = baselines.record(findings_source, &failures, &edited)
+                {
+                    report.tests.clear();
+                    report.delta = Some(delta);
+                }
+                if let Some((artifact, changed)) = coverage {
+                    report.coverage = Some(framework.summarize_coverage(&artifact, &changed));
+                }
+                OutputSummary {
+                    summary: serde_json::to_string(&report).unwrap_or_default(),
+                    include_output: true,
+                }
+            })),
+        })
+        .await
+    }
+}
+
+/// The Cargo packages owning the `changed` Rust sources and manifests (paths
+/// relative to `dir`).
+pub(super) fn cargo_packages(dir: &Path, changed: &[PathBuf]) -> BTreeSet<String> {
+    changed
+        .iter()
+        .filter(|path| {
+            path.extension().is_some_and(|extension| extension == "rs")
+                || path.file_name().is_some_and(|name| name == "Cargo.toml")
+        })
+        .filter_map(|path| {
+            dir.join(path)
+                .ancestors()
+                .skip(1)
+                .take_while(|ancestor| ancestor.starts_with(dir))
+                .find_map(|ancestor| {
+                    let manifest = std::fs::read_to_string(ancestor.join("Cargo.toml")).ok()?;
+                    let manifest: toml::Value = toml::from_str(&manifest).ok()?;
+                    manifest
+                        .get("package")?
+                        .get("name")?
+                        .as_str()
+                        .map(str::to_string)
+                })
+        })
+        .collect()
+}
+
+impl TestFramework {
+    /// The framework of the project rooted at `dir`, judged by its manifests.
+    fn detect(dir: &Path) -> Option<Self> {
+        if dir.join("Cargo.toml").is_file() {
+            return Some(Self::Cargo);
+        }
+        if dir.join("go.mod").is_file() {
+            return Some(Self::Go);
+        }
+        if std::fs::read_to_string(dir.join("package.json"))
+            .is_ok_and(|package| package.contains("\"jest\""))
+        {
+            return Some(Self::Jest);
+        }
+        [
+            "pytest.ini",
+            "pyproject.toml",
+            "setup.cfg",
+            "tox.ini",
+            "conftest.py",
+        ]
+        .iter()
+        .any(|marker| dir.join(marker).is_file())
+        .then_some(Self::Pytest)
+    }
+
+    /// Whether `path` is a source file this framework tests.
+    fn is_source(self, path: &Path) -> bool {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        match self {
+            Self::Cargo => extension == "rs",
+            Self::Pytest => extension == "py",
+            Self::Jest => matches!(extension, "js" | "jsx" | "mjs" | "cjs" | "ts" | "tsx"),
+            Self::Go => extension == "go",
+        }
+    }
+
+    /// The test targets covering `changed` (paths relative to `dir`): the
+    /// crates, packages, or test files that own them. Empty when none can be
+    /// found, in which case the whole suite runs.
+    fn targets(self, dir: &Path, changed: &[PathBuf]) -> Vec<String> {
+        let targets: BTreeSet<String> = match self {
+            Self::Cargo => cargo_packages(dir, changed),
+            Self::Go => changed
+                .iter()
+                .filter(|path| self.is_source(path))
+                .map(|path| match path.parent() {
+                    Some(parent) if !parent.as_os_str().is_empty() => {
+                        format!("./{}", parent.display())
+                    }
+                    _ => ".".to_string(),
+                })
+                .collect(),
+            Self::Jest => changed
+                .iter()
+                .filter(|path| self.is_source(path))
+                .map(|path| path.display().to_string())
+                .collect(),
+            Self::Pytest => changed
+                .iter()
+                .filter(|path| self.is_source(path))
+                .flat_map(|path| {
+                    let Some(stem) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                        return Vec::new();
+                    };
+                    if stem.starts_with("test_") || stem.ends_with("_test") {
+                        return vec![path.clone()];
+                    }
+                    let parent = path.parent().unwrap_or(Path::new(""));
+                    let test_file = format!("test_{stem}.py");
+                    [
+                        parent.join(&test_file),
+                        parent.join("tests").join(&test_file),
+                        Path::new("tests").join(&test_file),
+                    ]
+                    .into_iter()
+                    .filter(|candidate| dir.join(candidate).is_file())
+                    .collect()
+                })
+                .map(|path| path.display().to_string())
+                .collect(),
+        };
+        targets.into_iter().collect()
+    }
+
+    /// The command running `targets`. With `coverage`, the run also writes a
+    /// coverage report there (a directory holding `lcov.info` for jest).
+    fn command(
+        self,
+        filter: Option<&str>,
+        targets: &[String],
+        coverage: Option<&Path>,
+    ) -> Vec<String> {
+        let mut argv: Vec<String> = match (self, coverage) {
+            (Self::Cargo, None) => vec!["cargo", "test", "--no-fail-fast"],
+            (Self::Cargo, Some(_)) => vec!["cargo", "llvm-cov", "--no-fail-fast", "--lcov"],
+            (Self::Pytest, _) => vec!["pytest", "-v"],
+            (Self::Jest, _) => vec!["npx", "jest", "--verbose"],
+            (Self::Go, _) => vec!["go", "test", "-v"],
+        }
+        .into_iter()
+        .map(str::to_string)
+        .collect();
+        if let Some(coverage) = coverage {
+            let coverage = coverage.display();
+            argv.extend(match self {
+                Self::Cargo => vec!["--output-path".to_string(), coverage.to_string()],
+                Self::Pytest => vec!["--cov".to_string(), format!("--cov-report=lcov:{coverage}")],
+                Self::Jest => vec![
+                    "--coverage".to_string(),
+                    "--coverageReporters=lcov".to_string(),
+                    format!("--coverageDirectory={coverage}"),
+                ],
+                Self::Go => vec![format!("-coverprofile={coverage}")],
+            });
+        }
+        match self {
+            Self::Cargo => {
+                for package in targets {
+                    argv.extend(["-p".to_string(), package.clone()]);
+                }
+                argv.extend(filter.map(str::to_string));
+            }
+            Self::Pytest => {
+                if let Some(filter) = filter {
+                    argv.extend(["-k".to_string(), filter.to_string()]);
+                }
+                argv.extend(targets.iter().cloned());
+            }
+            Self::Jest => {
+                if let Some(filter) = filter {
+                    argv.extend(["-t".to_string(), filter.to_string()]);
+                }
+                if !targets.is_empty() {
+                    argv.push("--findRelatedTests".to_string());
+                    argv.extend(targets.iter().cloned());
+                }
+            }
+            Self::Go => {
+                if let Some(filter) = filter {
+                    argv.extend(["-run".to_string(), filter.to_string()]);
+                }
+                if targets.is_empty() {
+                    argv.push("./...".to_string());
+                } else {
+                    argv.extend(targets.iter().cloned());
+                }
+            }
+        }
+        argv
+    }
+
+    /// The outcome of each test case the framework reported in `output`.
+    fn report(self, command: String, output: &str) -> TestReport {
+        #[expect(clippy::expect_used)]
+        let line = Regex::new(match self {
+            Self::Cargo => r"^test (\S+) \.\.\. (ok|FAILED|ignored)",
+            Self::Pytest => r"^(\S+::\S+) (PASSED|FAILED|ERROR|SKIPPED|XFAIL|XPASS)",
+            Self::Jest => r"^\s*(✓|✕|○)(?: skipped)? (.+?)(?: \(\d+ ms\))?$",
+            Self::Go => r"^\s*--- (PASS|FAIL|SKIP): (\S+)",
+        })
+        .expect("test result pattern is valid");
+
+        let mut tests = Vec::new();
+        for captures in output.lines().filter_map(|text| line.captures(text)) {
+            let (name, outcome) = match self {
+                Self::Jest | Self::Go => (&captures[2], &captures[1]),
+                Self::Cargo | Self::Pytest => (&captures[1], &captures[2]),
+            };
+            let outcome = match outcome {
+                "ok" | "PASSED" | "XFAIL" | "✓" | "PASS" => TestOutcome::Passed,
+                "ignored" | "SKIPPED" | "○" | "SKIP" => TestOutcome::Skipped,
+                _ => TestOutcome::Failed,
+            };
+            tests.push(TestCaseResult {
+                name: name.trim().to_string(),
+                outcome,
+            });
+        }
+
+        let count = |outcome| tests.iter().filter(|test| test.outcome == outcome).count();
+        let (passed, failed, skipped) = (
+            count(TestOutcome::Passed),
+            count(TestOutcome::Failed),
+            count(TestOutcome::Skipped),
+        );
+        let truncated = tests.len() > MAX_TEST_CASES;
+        if truncated {
+            // Keep failures over passes when trimming the list.
+            tests.sort_by_key(|test| test.outcome != TestOutcome::Failed);
+            tests.truncate(MAX_TEST_CASES);
+        }
+        TestReport {
+            framework: self,
+            command,
+            passed,
+            failed,
+            skipped,
+            tests,
+            truncated,
+            coverage: None,
+            delta: None,
+        }
+    }
+
+    /// Which of the `changed` lines the coverage report at `artifact` shows
+    /// no test executing. Removes the report afterwards.
+    fn summarize_coverage(
+        self,
+        artifact: &Path,
+        changed: &BTreeMap<PathBuf, Vec<RangeInclusive<u32>>>,
+    ) -> CoverageSummary {
+        let contents = if self == Self::Jest {
+            let contents = std::fs::read_to_string(artifact.join("lcov.info"));
+            let _ = std::fs::remove_dir_all(artifact);
+            contents
+        } else {
+            let contents = std::fs::read_to_string(artifact);
+            let _ = std::fs::remove_file(artifact);
+            contents
+        };
+        let Ok(contents) = contents else {
+            let hint = match self {
+                Self::Cargo => "is cargo-llvm-cov installed?",
+                Self::Pytest => "is pytest-cov installed?",
+                Self::Jest | Self::Go => "did the run stop before collecting it?",
+            };
+            return CoverageSummary {
+                error: Some(format!("no coverage report was written; {hint}")),
+                ..Default::default()
+            };
+        };
+
+        let coverage = self.parse_coverage(&contents);
+        let mut summary = CoverageSummary::default();
+        for (path, ranges) in changed.iter().filter(|(path, _)| self.is_source(path)) {
+            let Some(lines) = coverage
+                .iter()
+                .find_map(|(measured, lines)| measured.ends_with(path).then_some(lines))
+            else {
+                summary.unmeasured_files.push(path.display().to_string());
+                continue;
+            };
+            let mut spans: Vec<(u32, u32)> = Vec::new();
+            for line in ranges
+                .iter()
+                .cloned()
+                .flatten()
+                .filter(|line| lines.get(line) == Some(&false))
+            {
+                match spans.last_mut() {
+                    Some((_, end)) if *end + 1 == line => *end = line,
+                    _ => spans.push((line, line)),
+                }
+            }
+            if !spans.is_empty() {
+                summary.uncovered_changed_lines.insert(
+                    path.display().to_string(),
+                    spans
+                        .into_iter()
+                        .map(|(start, end)| {
+                            if start == end {
+                                start.to_string()
+                            } else {
+                                format!("{start}-{end}")
+                            }
+                        })
+                        .collect(),
+                );
+            }
+        }
+        summary
+    }
+
+    /// Whether each instrumented line was executed, per file the coverage
+    /// report names. Go writes its own profile format; the rest write lcov.
+    fn parse_coverage(self, contents: &str) -> HashMap<PathBuf, BTreeMap<u32, bool>> {
+        let mut files: HashMap<PathBuf, BTreeMap<u32, bool>> = HashMap::new();
+        if self == Self::Go {
+            // `path/to/file.go:12.34,15.2 3 1`: a block spanning lines 12-15,
+            // with 3 statements, executed once.
+            for block in contents.lines().skip(1) {
+                let Some((location, counts)) = block.split_once(' ') else {
+                    continue;
+                };
+                let Some((path, span)) = location.rsplit_once(':') else {
+                    continue;
+                };
+                let line_of = |position: &str| {
+                    position
+                        .split('.')
+                        .next()
+                        .and_then(|line| line.parse::<u32>().ok())
+                };
+                let Some((Some(start), Some(end))) = span
+                    .split_once(',')
+                    .map(|(start, end)| (line_of(start), line_of(end)))
+                else {
+                    continue;
+                };
+                let executed = counts.rsplit(' ').next().is_some_and(|count| count != "0");
+                let lines = files.entry(PathBuf::from(path)).or_default();
+                for line in start..=end {
+                    *lines.entry(line).or_default() |= executed;
+                }
+            }
+        } else {
+            let mut current: Option<PathBuf> = None;
+            for record in contents.lines() {
+                if let Some(path) = record.strip_prefix("SF:") {
+                    current = Some(PathBuf::from(path));
+                } else if let Some(hit) = record.strip_prefix("DA:")
+                    && let Some(path) = &current
+                    && let Some((line, hits)) = hit.split_once(',')
+                    && let Ok(line) = line.parse::<u32>()
+                {
+                    let hits = hits.split(',').next().unwrap_or(hits);
+                    *files
+                        .entry(path.clone())
+                        .or_default()
+                        .entry(line)
+                        .or_default() |= hits != "0";
+                }
+            }
+        }
+        files
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn changed_files_map_to_their_crates_and_tests() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let dir = temp.path();
+        std::fs::create_dir_all(dir.join("core/src")).expect("create core");
+        std::fs::write(
+            dir.join("Cargo.toml"),
+            "[workspace]\nmembers = [\"core\"]\n",
+        )
+        .expect("write workspace manifest");
+        std::fs::write(
+            dir.join("core/Cargo.toml"),
+            "[package]\nname = \"codex-core\"\n",
+        )
+        .expect("write crate manifest");
+        std::fs::create_dir_all(dir.join("tests")).expect("create tests");
+        std::fs::write(dir.join("tests/test_parser.py"), "").expect("write test");
+
+        let changed = [
+            PathBuf::from("core/src/lib.rs"),
+            PathBuf::from("parser.py"),
+            PathBuf::from("README.md"),
+        ];
+        assert_eq!(
+            TestFramework::Cargo.targets(dir, &changed),
+            vec!["codex-core".to_string()]
+        );
+        assert_eq!(
+            TestFramework::Pytest.targets(dir, &changed),
+            vec!["tests/test_parser.py".to_string()]
+        );
+        assert_eq!(
+            TestFramework::Cargo.command(Some("parse"), &["codex-core".to_string()], None),
+            [
+                "cargo",
+                "test",
+                "--no-fail-fast",
+                "-p",
+                "codex-core",
+                "parse"
+            ]
+            .map(str::to_string)
+            .to_vec()
+        );
+    }
+
+    #[test]
+    fn reports_each_test_case() {
+        let case = |name: &str, outcome| TestCaseResult {
+            name: name.to_string(),
+            outcome,
+        };
+        let output = "\
+running 3 tests
+test parser::parses ... ok
+test parser::rejects ... FAILED
+test parser::slow ... ignored, needs network
+";
+        assert_eq!(
+            TestFramework::Cargo.report("cargo test".to_string(), output),
+            TestReport {
+                framework: TestFramework::Cargo,
+                command: "cargo test".to_string(),
+                passed: 1,
+                failed: 1,
+                skipped: 1,
+                tests: vec![
+                    case("parser::parses", TestOutcome::Passed),
+                    case("parser::rejects", TestOutcome::Failed),
+                    case("parser::slow", TestOutcome::Skipped),
+                ],
+                truncated: false,
+                coverage: None,
+                delta: None,
+            }
+        );
+
+        let output = "\
+=== RUN   TestParse
+--- PASS: TestParse (0.00s)
+    --- FAIL: TestParse/empty (0.00s)
+";
+        assert_eq!(
+            TestFramework::Go
+                .report("go test".to_string(), output)
+                .tests,
+            vec![
+                case("TestParse", TestOutcome::Passed),
+                case("TestParse/empty", TestOutcome::Failed),
+            ]
+        );
+
+        let output = "  ✓ parses input (3 ms)\n  ✕ rejects input (1 ms)\n  ○ skipped later\n";
+        assert_eq!(
+            TestFramework::Jest
+                .report("npx jest".to_string(), output)
+                .tests,
+            vec![
+                case("parses input", TestOutcome::Passed),
+                case("rejects input", TestOutcome::Failed),
+                case("later", TestOutcome::Skipped),
+            ]
+        );
+    }
+
+    #[test]
+    fn coverage_reports_uncovered_changed_lines() {
+        let temp = tempfile::tempdir().expect("tempdir");
+        let changed = BTreeMap::from([
+            (PathBuf::from("src/lib.rs"), vec![2..=6]),
+            (PathBuf::from("src/unused.rs"), vec![1..=3]),
+            (PathBuf::from("README.md"), vec![1..=1]),
+        ]);
+        let lcov = temp.path().join("lcov");
+        std::fs::write(
+            &lcov,
+            "SF:/work/src/lib.rs\nDA:2,1\nDA:3,0\nDA:4,0\nDA:6,0\nend_of_record\n",
+        )
+        .expect("write lcov");
+        assert_eq!(
+            TestFramework::Cargo.summarize_coverage(&lcov, &changed),
+            CoverageSummary {
+                uncovered_changed_lines: BTreeMap::from([(
+                    "src/lib.rs".to_string(),
+                    vec!["3-4".to_string(), "6".to_string()],
+                )]),
+                unmeasured_files: vec!["src/unused.rs".to_string()],
+                error: None,
+            }
+        );
+        assert!(!lcov.exists());
+
+        let changed = BTreeMap::from([(PathBuf::from("pkg/parse.go"), vec![10..=20])]);
+        let profile = temp.path().join("profile");
+        std::fs::write(
+            &profile,
+            "mode: set\nexample.com/mod/pkg/parse.go:10.20,12.3 2 1\n\
+             example.com/mod/pkg/parse.go:12.3,15.2 2 0\n",
+        )
+        .expect("write profile");
+        assert_eq!(
+            TestFramework::Go
+                .summarize_coverage(&profile, &changed)
+                .uncovered_changed_lines,
+            BTreeMap::from([("pkg/parse.go".to_string(), vec!["13-15".to_string()])])
+        );
+    }
+}
@@ -0,0 +1,602 @@
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::LazyLock;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use regex_lite::Captures;
+use regex_lite::Regex;
+use serde::Deserialize;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ApplyPatchHandler;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Instantiates a template pack: a directory under `.codex/templates` in the
+/// workspace or `$CODEX_HOME/templates` whose files, and file names, contain
+/// `{{variable}}` placeholders. An optional `template.toml` at its root
+/// describes the pack and declares its variables with their defaults.
+/// Without `apply` the call only lists the files it would create. With it,
+/// the files are added as a patch through [`ApplyPatchHandler`], so
+/// approvals, sandboxing, and patch events are the same as for a patch the
+/// model wrote; once the patch has created them, they get the template's
+/// exact bytes, which a patch cannot carry for CRLF text or binary assets.
+pub struct ScaffoldHandler;
+
+const MANIFEST_FILE: &str = "template.toml";
+/// Most files one template may create.
+const MAX_FILES: usize = 200;
+const MAX_TEMPLATE_BYTES: usize = 1024 * 1024;
+
+static PLACEHOLDER: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\{\{\s*([A-Za-z0-9_-]+)\s*\}\}")
+        .unwrap_or_else(|err| panic!("invalid placeholder regex: {err}"))
+});
+
+#[derive(Deserialize)]
+struct ScaffoldArgs {
+    #[serde(default)]
+    template: Option<String>,
+    #[serde(default)]
+    variables: HashMap<String, String>,
+    #[serde(default)]
+    destination: Option<String>,
+    #[serde(default)]
+    apply: bool,
+}
+
+#[derive(Deserialize, Default)]
+struct TemplateManifest {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    variables: BTreeMap<String, TemplateVariable>,
+}
+
+#[derive(Deserialize, Default)]
+struct TemplateVariable {
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    default: Option<String>,
+}
+
+#[async_trait]
+impl ToolHandler for ScaffoldHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, invocation: &ToolInvocation) -> bool {
+        match &invocation.payload {
+            ToolPayload::Function { arguments } => !matches!(
+                parse_arguments::<ScaffoldArgs>(arguments),
+                Ok(args) if !args.apply
+            ),
+            _ => true,
+        }
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let args: ScaffoldArgs = match &invocation.payload {
+            ToolPayload::Function { arguments } => parse_arguments(arguments)?,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "scaffold handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+
+        let turn = &invocation.turn;
+        let template_roots = [
+            turn.cwd.join(".codex").join("templates"),
+            turn.config.codex_home.join("templates"),
+        ];
+        let Some(template) = args.template else {
+            let listing = tokio::task::spawn_blocking(move || list_templates(&template_roots))
+                .await
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!("failed to list templates: {err}"))
+                })?;
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(listing),
+                success: Some(true),
+            });
+        };
+        if Path::new(&template)
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_)))
+            || template.contains(['/', '\\'])
+        {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "invalid template name `{template}`"
+            )));
+        }
+        let Some(pack) = template_roots
+            .iter()
+            .map(|root| root.join(&template))
+            .find(|pack| pack.is_dir())
+        else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "no template named `{template}`; call scaffold without `template` to list them"
+            )));
+        };
+
+        let variables = args.variables;
+        let files = tokio::task::spawn_blocking(move || render_pack(&pack, variables))
+            .await
+            .map_err(|err| {
+                FunctionCallError::RespondToModel(format!("failed to render template: {err}"))
+            })?
+            .map_err(FunctionCallError::RespondToModel)?;
+
+        let destination = turn.resolve_path(args.destination);
+        let files = files
+            .into_iter()
+            .map(|(path, contents)| (destination.join(path), contents))
+            .collect::<Vec<_>>();
+        let existing = files
+            .iter()
+            .filter(|(path, _)| path.exists())
+            .map(|(path, _)| display_path(path, &turn.cwd).display().to_string())
+            .collect::<Vec<_>>();
+        if !existing.is_empty() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "refusing to overwrite existing files: {}",
+                existing.join(", ")
+            )));
+        }
+
+        if !args.apply {
+            let mut preview = format!(
+                "Template `{template}` creates {count} files. Nothing was created yet: call \
+                 scaffold again with the same arguments and `apply: true` to create them.\n",
+                count = files.len()
+            );
+            for (path, contents) in &files {
+                let size = match std::str::from_utf8(contents) {
+                    Ok(text) => format!("{} lines", text.lines().count()),
+                    Err(_) => format!("binary, {} bytes", contents.len()),
+                };
+                preview.push_str(&format!(
+                    "\n{} ({size})",
+                    display_path(path, &turn.cwd).display()
+                ));
+            }
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(preview),
+                success: Some(true),
+            });
+        }
+
+        let patch = patch_for(&files, &turn.cwd);
+        let session = Arc::clone(&invocation.session);
+        let output = ApplyPatchHandler
+            .handle(ToolInvocation {
+                payload: ToolPayload::Custom { input: patch },
+                ..invocation
+            })
+            .await?;
+        // In patch artifact mode nothing was created; the artifact keeps the
+        // patch.
+        if session.services.patch_artifact.is_none() {
+            for (path, contents) in &files {
+                tokio::fs::write(path, contents).await.map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to write `{}`: {err}",
+                        path.display()
+                    ))
+                })?;
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn read_manifest(pack: &Path) -> Result<TemplateManifest, String> {
+    match std::fs::read_to_string(pack.join(MANIFEST_FILE)) {
+        Ok(contents) => toml::from_str(&contents)
+            .map_err(|err| format!("invalid {MANIFEST_FILE} in `{}`: {err}", pack.display())),
+        Err(_) => Ok(TemplateManifest::default()),
+    }
+}
+
+/// The template packs under `roots`, with their descriptions and variables.
+/// A pack name found under several roots is listed once, from the first.
+fn list_templates(roots: &[PathBuf]) -> String {
+    let mut packs = BTreeMap::new();
+    for root in roots {
+        let Ok(entries) = std::fs::read_dir(root) else {
+            continue;
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            if path.is_dir()
+                && let Some(name) = path.file_name().and_then(|name| name.to_str())
+            {
+                packs.entry(name.to_string()).or_insert(path);
+            }
+        }
+    }
+    if packs.is_empty() {
+        return format!(
+            "No templates found. Template packs are directories under {}.",
+            roots
+                .iter()
+                .map(|root| format!("`{}`", root.display()))
+                .collect::<Vec<_>>()
+                .join(" or ")
+        );
+    }
+
+    let mut listing = String::new();
+    for (name, path) in packs {
+        listing.push_str(&format!("- {name}"));
+        let manifest = match read_manifest(&path) {
+            Ok(manifest) => manifest,
+            Err(err) => {
+                listing.push_str(&format!(" ({err})\n"));
+                continue;
+            }
+        };
+        if let Some(description) = manifest.description {
+            listing.push_str(&format!(": {description}"));
+        }
+        listing.push('\n');
+        for (variable, spec) in manifest.variables {
+            listing.push_str(&format!("  - {variable}"));
+            if let Some(description) = spec.description {
+                listing.push_str(&format!(": {description}"));
+            }
+            if let Some(default) = spec.default {
+                listing.push_str(&format!(" (default `{default}`)"));
+            }
+            listing.push('\n');
+        }
+    }
+    listing
+}
+
+/// The files of `pack` with their placeholders filled in, as paths relative
+/// to the destination. Placeholders naming no variable are left as written,
+/// so templates can contain other `{{...}}` syntax. Files that are not UTF-8
+/// are assets and are copied as is; symlinks are skipped.
+fn render_pack(
+    pack: &Path,
+    mut variables: HashMap<String, String>,
+) -> Result<Vec<(PathBuf, Vec<u8>)>, String> {
+    let manifest = read_manifest(pack)?;
+    if !manifest.variables.is_empty()
+        && let Some(unknown) = variables
+            .keys()
+            .find(|name| !manifest.variables.contains_key(*name))
+    {
+        return Err(format!("template does not declare a variable `{unknown}`"));
+    }
+    let mut missing = Vec::new();
+    for (name, spec) in manifest.variables {
+        if variables.contains_key(&name) {
+            continue;
+        }
+        match spec.default {
+            Some(default) => {
+                variables.insert(name, default);
+            }
+            None => missing.push(name),
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!("missing variables: {}", missing.join(", ")));
+    }
+    let render = |text: &str| {
+        PLACEHOLDER
+            .replace_all(text, |captures: &Captures<'_>| {
+                variables
+                    .get(&captures[1])
+                    .cloned()
+                    .unwrap_or_else(|| captures[0].to_string())
+            })
+            .into_owned()
+    };
+
+    let mut files = Vec::new();
+    let mut sources = HashMap::new();
+    let mut total_bytes = 0;
+    let mut dirs = vec![pack.to_path_buf()];
+    while let Some(dir) = dirs.pop() {
+        let entries = std::fs::read_dir(&dir)
+            .map_err(|err| format!("failed to read `{}`: {err}", dir.display()))?;
+        for entry in entries.filter_map(Result::ok) {
+            let path = entry.path();
+            let file_type = std::fs::symlink_metadata(&path)
+                .map_err(|err| format!("failed to read `{}`: {err}", path.display()))?
+                .file_type();
+            if file_type.is_symlink() {
+                continue;
+            }
+            if file_type.is_dir() {
+                dirs.push(path);
+                continue;
+            }
+            let relative = path.strip_prefix(pack).unwrap_or(&path);
+            if relative == Path::new(MANIFEST_FILE) {
+                continue;
+            }
+            let rendered = PathBuf::from(render(&relative.to_string_lossy()));
+            if !rendered
+                .components()
+                .all(|component| matches!(component, Component::Normal(_)))
+            {
+                return Err(format!(
+                    "`{}` renders to `{}`, which leaves the destination",
+                    relative.display(),
+                    rendered.display()
+                ));
+            }
+            if let Some(other) = sources.insert(rendered.clone(), relative.to_path_buf()) {
+                return Err(format!(
+                    "`{}` and `{}` both render to `{}`",
+                    other.display(),
+                    relative.display(),
+                    rendered.display()
+                ));
+            }
+            let contents = std::fs::read(&path)
+                .map_err(|err| format!("failed to read `{}`: {err}", relative.display()))?;
+            total_bytes += contents.len();
+            let contents = match String::from_utf8(contents) {
+                Ok(text) => render(&text).into_bytes(),
+                Err(err) => err.into_bytes(),
+            };
+            files.push((rendered, contents));
+            if files.len() > MAX_FILES || total_bytes > MAX_TEMPLATE_BYTES {
+                return Err(format!(
+                    "template has more than {MAX_FILES} files or {MAX_TEMPLATE_BYTES} bytes"
+                ));
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+fn display_path<'a>(path: &'a Path, cwd: &Path) -> &'a Path {
+    path.strip_prefix(cwd).unwrap_or(path)
+}
+
+/// The files as `apply_patch` input: text as `\n`-terminated lines, and
+/// binary assets as empty files.
+fn patch_for(files: &[(PathBuf, Vec<u8>)], cwd: &Path) -> String {
+    let mut patch = "*** Begin Patch\n".to_string();
+    for (path, contents) in files {
+        let path = display_path(path, cwd).display();
+        patch.push_str(&format!("*** Add File: {path}\n"));
+        for line in std::str::from_utf8(contents).unwrap_or_default().lines() {
+            patch.push('+');
+            patch.push_str(line);
+            patch.push('\n');
+        }
+    }
+    patch.push_str("*** End Patch\n");
+    patch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::make_session_and_context;
+    use crate::turn_diff_tracker::TurnDiffTracker;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tempfile::tempdir;
+    use tokio::sync::Mutex;
+
+    fn write_pack(pack: &Path) {
+        std::fs::create_dir_all(pack.join("src")).expect("create pack");
+        std::fs::write(
+            pack.join(MANIFEST_FILE),
+            r#"
+description = "Rust binary crate"
+
+[variables.name]
+description = "Crate name"
+
+[variables.edition]
+default = "2024"
+"#,
+        )
+        .expect("write manifest");
+        std::fs::write(
+            pack.join("Cargo.toml"),
+            "[package]\nname = \"{{ name }}\"\nedition = \"{{edition}}\"\n",
+        )
+        .expect("write Cargo.toml");
+        std::fs::write(
+            pack.join("src/{{name}}.rs"),
+            "// ${{ secrets.TOKEN }} stays as written\n",
+        )
+        .expect("write source");
+    }
+
+    #[test]
+    fn renders_placeholders_in_paths_and_contents() {
+        let temp = tempdir().expect("create temp dir");
+        write_pack(temp.path());
+
+        assert_eq!(
+            render_pack(temp.path(), HashMap::new()),
+            Err("missing variables: name".to_string())
+        );
+        assert_eq!(
+            render_pack(
+                temp.path(),
+                HashMap::from([("name".to_string(), "demo".to_string())])
+            ),
+            Ok(vec![
+                (
+                    PathBuf::from("Cargo.toml"),
+                    b"[package]\nname = \"demo\"\nedition = \"2024\"\n".to_vec()
+                ),
+                (
+                    PathBuf::from("src/demo.rs"),
+                    b"// ${{ secrets.TOKEN }} stays as written\n".to_vec()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn keeps_template_bytes_and_rejects_colliding_names() {
+        let temp = tempdir().expect("create temp dir");
+        let pack = temp.path().join("pack");
+        std::fs::create_dir_all(&pack).expect("create pack");
+        std::fs::write(pack.join("run.bat"), "echo {{name}}\r\npause").expect("write run.bat");
+        std::fs::write(pack.join("logo.png"), [0x89, b'P', b'N', b'G', 0xff, 0x00])
+            .expect("write logo.png");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink("/etc/passwd", pack.join("passwd")).expect("create symlink");
+        let variables = HashMap::from([("name".to_string(), "demo".to_string())]);
+
+        assert_eq!(
+            render_pack(&pack, variables.clone()),
+            Ok(vec![
+                (
+                    PathBuf::from("logo.png"),
+                    vec![0x89, b'P', b'N', b'G', 0xff, 0x00]
+                ),
+                (PathBuf::from("run.bat"), b"echo demo\r\npause".to_vec()),
+            ])
+        );
+
+        std::fs::write(pack.join("demo.txt"), "").expect("write demo.txt");
+        std::fs::write(pack.join("{{name}}.txt"), "").expect("write {{name}}.txt");
+        assert!(
+            render_pack(&pack, variables)
+                .is_err_and(|err| err.ends_with("both render to `demo.txt`"))
+        );
+    }
+
+    #[test]
+    fn scaffold_patch_adds_the_rendered_files() {
+        let temp = tempdir().expect("create temp dir");
+        let pack = temp.path().join("pack");
+        write_pack(&pack);
+        let destination = temp.path().join("demo");
+        let files = render_pack(
+            &pack,
+            HashMap::from([
+                ("name".to_string(), "demo".to_string()),
+                ("edition".to_string(), "2021".to_string()),
+            ]),
+        )
+        .expect("render pack")
+        .into_iter()
+        .map(|(path, contents)| (destination.join(path), contents))
+        .collect::<Vec<_>>();
+
+        let mut stdout = Vec::new();
+        let mut stderr = Vec::new();
+        codex_apply_patch::apply_patch(
+            &patch_for(&files, Path::new("/nonexistent")),
+            0,
+            &mut stdout,
+            &mut stderr,
+        )
+        .expect("apply patch");
+        assert_eq!(
+            std::fs::read_to_string(destination.join("Cargo.toml")).expect("read Cargo.toml"),
+            "[package]\nname = \"demo\"\nedition = \"2021\"\n"
+        );
+        assert_eq!(
+            std::fs::read_to_string(destination.join("src/demo.rs")).expect("read demo.rs"),
+            "// ${{ secrets.TOKEN }} stays as written\n"
+        );
+    }
+
+    async fn call_scaffold(
+        cwd: &Path,
+        arguments: serde_json::Value,
+    ) -> Result<String, FunctionCallError> {
+        let (session, mut turn) = make_session_and_context().await;
+        turn.cwd = cwd.to_path_buf();
+        let output = ScaffoldHandler
+            .handle(ToolInvocation {
+                session: Arc::new(session),
+                turn: Arc::new(turn),
+                tracker: Arc::new(Mutex::new(TurnDiffTracker::default())),
+                call_id: "call-1".to_string(),
+                tool_name: "scaffold".to_string(),
+                payload: ToolPayload::Function {
+                    arguments: arguments.to_string(),
+                },
+            })
+            .await?;
+        let ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(text),
+            ..
+        } = output
+        else {
+            panic!("unexpected scaffold output");
+        };
+        Ok(text)
+    }
+
+    #[tokio::test]
+    async fn handler_lists_packs_and_refuses_escapes_and_overwrites() {
+        let temp = tempdir().expect("create temp dir");
+        write_pack(&temp.path().join(".codex/templates/rust-bin"));
+
+        assert_eq!(
+            call_scaffold(temp.path(), json!({})).await,
+            Ok(
+                "- rust-bin: Rust binary crate\n  - edition (default `2024`)\n  - name: Crate \
+                name\n"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            call_scaffold(temp.path(), json!({"template": ".."})).await,
+            Err(FunctionCallError::RespondToModel(
+                "invalid template name `..`".to_string()
+            ))
+        );
+        assert_eq!(
+            call_scaffold(
+                temp.path(),
+                json!({"template": "rust-bin", "variables": {"name": "../../escape"}})
+            )
+            .await,
+            Err(FunctionCallError::RespondToModel(
+                "`src/{{name}}.rs` renders to `src/../../escape.rs`, which leaves the destination"
+                    .to_string()
+            ))
+        );
+
+        std::fs::create_dir_all(temp.path().join("demo")).expect("create destination");
+        std::fs::write(temp.path().join("demo/Cargo.toml"), "").expect("write Cargo.toml");
+        assert_eq!(
+            call_scaffold(
+                temp.path(),
+                json!({
+                    "template": "rust-bin",
+                    "variables": {"name": "demo"},
+                    "destination": "demo",
+                    "apply": true,
+                })
+            )
+            .await,
+            Err(FunctionCallError::RespondToModel(
+                "refusing to overwrite existing files: demo/Cargo.toml".to_string()
+            ))
+        );
+    }
+}
@@ -295,6 +295,7 @@ mod tests {
                 },
                 connector_id: connector_id.map(str::to_string),
                 connector_name: connector_id.map(str::to_string),
+                connector_version: None,
             },
         )
     }
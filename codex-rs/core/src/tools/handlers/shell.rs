@@ -7,12 +7,18 @@ use std::sync::Arc;
 
 use crate::codex::TurnContext;
 use crate::exec::ExecParams;
+use crate::exec::ExecToolCallOutput;
+use crate::exec_env::apply_env_injections;
 use crate::exec_env::create_env;
+use crate::exec_env::resolve_secret_from_store;
 use crate::exec_policy::ExecApprovalRequest;
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
+use crate::posix_translation::translate_posix_in_powershell_command;
 use crate::protocol::ExecCommandSource;
 use crate::shell::Shell;
+use crate::tools::ExecProvenance;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
@@ -31,15 +37,27 @@ pub struct ShellHandler;
 
 pub struct ShellCommandHandler;
 
-struct RunExecLikeArgs {
-    tool_name: String,
-    exec_params: ExecParams,
-    prefix_rule: Option<Vec<String>>,
-    session: Arc<crate::codex::Session>,
-    turn: Arc<TurnContext>,
-    tracker: crate::tools::context::SharedTurnDiffTracker,
-    call_id: String,
-    freeform: bool,
+pub(super) struct RunExecLikeArgs {
+    pub(super) tool_name: String,
+    pub(super) exec_params: ExecParams,
+    pub(super) prefix_rule: Option<Vec<String>>,
+    pub(super) session: Arc<crate::codex::Session>,
+    pub(super) turn: Arc<TurnContext>,
+    pub(super) tracker: crate::tools::context::SharedTurnDiffTracker,
+    pub(super) call_id: String,
+    pub(super) freeform: bool,
+    /// Summarizes a completed command's output for the model. When set, a
+    /// command that exits non-zero is reported as an unsuccessful result
+    /// rather than an error.
+    pub(super) summarize_output: Option<OutputSummarizer>,
+}
+
+pub(super) type OutputSummarizer = Box<dyn FnOnce(&ExecToolCallOutput) -> OutputSummary + Send>;
+
+pub(super) struct OutputSummary {
+    pub(super) summary: String,
+    /// Whether the command's own output follows the summary.
+    pub(super) include_output: bool,
 }
 
 impl ShellHandler {
@@ -80,7 +98,7 @@ impl ShellCommandHandler {
         shell.derive_exec_args(command, use_login_shell)
     }
 
-    fn to_exec_params(
+    pub(super) fn to_exec_params(
         params: &ShellCommandToolCallParams,
         session: &crate::codex::Session,
         turn_context: &TurnContext,
@@ -155,6 +173,7 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     freeform: false,
+                    summarize_output: None,
                 })
                 .await
             }
@@ -170,6 +189,7 @@ impl ToolHandler for ShellHandler {
                     tracker,
                     call_id,
                     freeform: false,
+                    summarize_output: None,
                 })
                 .await
             }
@@ -245,13 +265,16 @@ impl ToolHandler for ShellCommandHandler {
             tracker,
             call_id,
             freeform: true,
+            summarize_output: None,
         })
         .await
     }
 }
 
 impl ShellHandler {
-    async fn run_exec_like(args: RunExecLikeArgs) -> Result<ToolOutput, FunctionCallError> {
+    pub(super) async fn run_exec_like(
+        args: RunExecLikeArgs,
+    ) -> Result<ToolOutput, FunctionCallError> {
         let RunExecLikeArgs {
             tool_name,
             exec_params,
@@ -261,20 +284,26 @@ impl ShellHandler {
             tracker,
             call_id,
             freeform,
+            summarize_output,
         } = args;
 
         let mut exec_params = exec_params;
-        let dependency_env = session.dependency_env().await;
-        if !dependency_env.is_empty() {
-            exec_params.env.extend(dependency_env.clone());
-        }
-
-        let mut explicit_env_overrides = turn.shell_environment_policy.r#set.clone();
-        for key in dependency_env.keys() {
-            if let Some(value) = exec_params.env.get(key) {
-                explicit_env_overrides.insert(key.clone(), value.clone());
-            }
+        // Translated before exec policy and approval see the command, so the
+        // command that runs is the one that was approved.
+        if session
+            .features()
+            .enabled(Feature::PowershellPosixTranslation)
+        {
+            exec_params.command = translate_posix_in_powershell_command(&exec_params.command);
         }
+        let explicit_env_overrides = apply_env_injections(
+            &mut exec_params.env,
+            &turn.shell_environment_policy,
+            session.dependency_env().await,
+            |secret_name| {
+                resolve_secret_from_store(&turn.config.codex_home, &exec_params.cwd, secret_name)
+            },
+        );
 
         // Approval policy guard for explicit escalation in non-OnRequest modes.
         if exec_params
@@ -358,8 +387,39 @@ impl ShellHandler {
             )
             .await
             .map(|result| result.output);
+        let emitter = match orchestrator.sandbox_used() {
+            Some(sandbox) if turn.features.enabled(Feature::CommandProvenance) => emitter
+                .with_provenance(ExecProvenance::new(
+                    &exec_params.command,
+                    exec_params.env.get("PATH").map(String::as_str),
+                    exec_params.cwd.clone(),
+                    sandbox,
+                )),
+            _ => emitter,
+        };
+        let summary = match (summarize_output, &out) {
+            (Some(summarize), Ok(output)) => Some((summarize(output), output.exit_code == 0)),
+            _ => None,
+        };
         let event_ctx = ToolEventCtx::new(session.as_ref(), turn.as_ref(), &call_id, None);
-        let content = emitter.finish(event_ctx, out).await?;
+        let content = emitter.finish(event_ctx, out).await;
+        if let Some((summary, succeeded)) = summary
+            && let Ok(content) | Err(FunctionCallError::RespondToModel(content)) = content
+        {
+            let OutputSummary {
+                summary,
+                include_output,
+            } = summary;
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(if include_output {
+                    format!("{summary}\n\n{content}")
+                } else {
+                    summary
+                }),
+                success: Some(succeeded),
+            });
+        }
+        let content = content?;
         Ok(ToolOutput::Function {
             body: FunctionCallOutputBody::Text(content),
             success: Some(true),
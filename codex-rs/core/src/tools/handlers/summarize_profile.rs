@@ -0,0 +1,960 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::io::Read;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::models::ShellCommandToolCallParams;
+use flate2::read::GzDecoder;
+use serde::Deserialize;
+use serde::Serialize;
+use tokio::io::AsyncReadExt;
+
+use crate::exec::ExecToolCallOutput;
+use crate::exec::StreamOutput;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ShellCommandHandler;
+use crate::tools::handlers::ShellHandler;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::read_file::format_line;
+use crate::tools::handlers::shell::OutputSummary;
+use crate::tools::handlers::shell::RunExecLikeArgs;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Reads a CPU profile recorded outside the session (`perf record`,
+/// `cargo flamegraph`, pprof) and returns its hot spots as JSON, so
+/// performance work starts from measurements.
+pub struct SummarizeProfileHandler;
+
+const DEFAULT_LIMIT: usize = 20;
+const MAX_LIMIT: usize = 100;
+const MAX_HOT_STACKS: usize = 5;
+/// Leaf-most frames shown of each hot stack.
+const MAX_STACK_FRAMES: usize = 12;
+const PERF_SCRIPT_TIMEOUT_MS: u64 = 120_000;
+/// Bytes read from the start of a profile to detect its format.
+const HEADER_BYTES: usize = 4096;
+/// Largest decompressed pprof profile read.
+const MAX_PPROF_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct SummarizeProfileArgs {
+    path: String,
+    #[serde(default)]
+    format: Option<ProfileFormat>,
+    #[serde(default)]
+    focus: Option<String>,
+    #[serde(default = "default_limit")]
+    limit: usize,
+}
+
+fn default_limit() -> usize {
+    DEFAULT_LIMIT
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum ProfileFormat {
+    /// A `perf.data` file, read through `perf script`.
+    PerfData,
+    /// The text output of `perf script`.
+    PerfScript,
+    /// Collapsed stacks, one `frame;frame;frame count` per line, as written
+    /// by `stackcollapse-*` and `inferno-collapse-*`.
+    Folded,
+    /// A flame graph drawn by `flamegraph.pl`, inferno, or
+    /// `cargo flamegraph`.
+    FlamegraphSvg,
+    /// A pprof protobuf, gzipped or not.
+    Pprof,
+}
+
+/// Frames from the root to the leaf, and the samples spent in the leaf.
+type Stack = (Vec<String>, u64);
+
+#[derive(Debug, PartialEq, Serialize)]
+struct ProfileSummary {
+    format: ProfileFormat,
+    sample_type: String,
+    total: u64,
+    /// Samples in stacks through a frame matching `focus`, which the rest of
+    /// the summary is limited to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    focused: Option<u64>,
+    /// Functions by the samples spent in their own code.
+    top_self: Vec<HotFunction>,
+    /// Functions by the samples spent in them and their callees.
+    top_total: Vec<HotFunction>,
+    /// The most sampled stacks, leaf first.
+    hot_stacks: Vec<HotStack>,
+    truncated: bool,
+    /// Whether the middle of `perf script`'s output was dropped, because it
+    /// exceeded the configured output limit, so only the samples at its start
+    /// and end were counted.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    incomplete: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct HotFunction {
+    function: String,
+    self_samples: u64,
+    self_percent: f64,
+    total_samples: u64,
+    total_percent: f64,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct HotStack {
+    frames: Vec<String>,
+    samples: u64,
+    percent: f64,
+}
+
+#[async_trait]
+impl ToolHandler for SummarizeProfileHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            call_id,
+            tool_name,
+            payload,
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "summarize_profile handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: SummarizeProfileArgs = parse_arguments(&arguments)?;
+        if args.limit == 0 || args.limit > MAX_LIMIT {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "limit must be between 1 and {MAX_LIMIT}"
+            )));
+        }
+
+        let path = turn.resolve_path(Some(args.path.clone()));
+        let read_error = |err: std::io::Error| {
+            FunctionCallError::RespondToModel(format!(
+                "failed to read profile {}: {err}",
+                path.display()
+            ))
+        };
+        let format = match args.format {
+            Some(format) => format,
+            None => {
+                let mut header = Vec::with_capacity(HEADER_BYTES);
+                tokio::fs::File::open(&path)
+                    .await
+                    .map_err(read_error)?
+                    .take(HEADER_BYTES as u64)
+                    .read_to_end(&mut header)
+                    .await
+                    .map_err(read_error)?;
+                detect_format(&header)
+            }
+        };
+
+        if format == ProfileFormat::PerfData {
+            // perf reads the profile itself, in the sandbox and under the
+            // approval policy like any other command.
+            let command = shlex::try_join(["perf", "script", "--input", args.path.as_str()])
+                .map_err(|err| {
+                    FunctionCallError::RespondToModel(format!(
+                        "failed to quote perf command: {err}"
+                    ))
+                })?;
+            let params = ShellCommandToolCallParams {
+                command,
+                workdir: None,
+                login: None,
+                timeout_ms: Some(PERF_SCRIPT_TIMEOUT_MS),
+                sandbox_permissions: None,
+                prefix_rule: None,
+                justification: None,
+            };
+            let exec_params = ShellCommandHandler::to_exec_params(
+                &params,
+                session.as_ref(),
+                turn.as_ref(),
+                session.conversation_id,
+                turn.tools_config.allow_login_shell,
+            )?;
+            let display_path = path.display().to_string();
+            return ShellHandler::run_exec_like(RunExecLikeArgs {
+                tool_name,
+                exec_params,
+                prefix_rule: None,
+                session,
+                turn,
+                tracker,
+                call_id,
+                freeform: true,
+                summarize_output: Some(Box::new(move |output: &ExecToolCallOutput| {
+                    let stacks = parse_captured_perf_script(&output.stdout);
+                    if output.exit_code != 0 || stacks.is_empty() {
+                        return OutputSummary {
+                            summary: format!(
+                                "no samples read from {display_path} through `perf script`"
+                            ),
+                            include_output: true,
+                        };
+                    }
+                    let mut summary = summarize(
+                        format,
+                        "samples".to_string(),
+                        stacks,
+                        args.focus.as_deref(),
+                        args.limit,
+                    );
+                    summary.incomplete = output.stdout.truncated.is_some();
+                    OutputSummary {
+                        summary: serde_json::to_string(&summary).unwrap_or_default(),
+                        include_output: false,
+                    }
+                })),
+            })
+            .await;
+        }
+
+        let bytes = tokio::fs::read(&path).await.map_err(read_error)?;
+        let (sample_type, stacks) = match format {
+            // perf.data was read through perf above.
+            ProfileFormat::PerfData | ProfileFormat::PerfScript => (
+                "samples".to_string(),
+                parse_perf_script(&String::from_utf8_lossy(&bytes)),
+            ),
+            ProfileFormat::Folded => (
+                "samples".to_string(),
+                parse_folded(&String::from_utf8_lossy(&bytes))?,
+            ),
+            ProfileFormat::FlamegraphSvg => (
+                "samples".to_string(),
+                parse_flamegraph_svg(&String::from_utf8_lossy(&bytes)),
+            ),
+            ProfileFormat::Pprof => parse_pprof(&bytes)?,
+        };
+        if stacks.is_empty() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "no samples found in {} read as {}",
+                path.display(),
+                serde_json::to_string(&format).unwrap_or_default()
+            )));
+        }
+
+        let summary = summarize(
+            format,
+            sample_type,
+            stacks,
+            args.focus.as_deref(),
+            args.limit,
+        );
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(serde_json::to_string(&summary).unwrap_or_default()),
+            success: Some(true),
+        })
+    }
+}
+
+/// Detects the format of a profile from its first `HEADER_BYTES`.
+fn detect_format(header: &[u8]) -> ProfileFormat {
+    if header.starts_with(b"PERFILE2") {
+        return ProfileFormat::PerfData;
+    }
+    if header.starts_with(&[0x1f, 0x8b]) {
+        return ProfileFormat::Pprof;
+    }
+    // The header may end inside a character or a line.
+    let text = match std::str::from_utf8(header) {
+        Ok(text) => text,
+        Err(err) if err.error_len().is_none() => {
+            std::str::from_utf8(&header[..err.valid_up_to()]).unwrap_or_default()
+        }
+        Err(_) => return ProfileFormat::Pprof,
+    };
+    let complete_lines = if header.len() < HEADER_BYTES {
+        text
+    } else {
+        text.rsplit_once('\n').map_or("", |(lines, _)| lines)
+    };
+    if text.contains("<svg") {
+        ProfileFormat::FlamegraphSvg
+    } else if complete_lines
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(100)
+        .all(|line| folded_line(line).is_some())
+    {
+        ProfileFormat::Folded
+    } else {
+        ProfileFormat::PerfScript
+    }
+}
+
+fn folded_line(line: &str) -> Option<(&str, u64)> {
+    let (stack, count) = line.trim_end().rsplit_once(' ')?;
+    Some((stack, count.parse().ok()?))
+}
+
+fn parse_folded(text: &str) -> Result<Vec<Stack>, FunctionCallError> {
+    let mut stacks = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let Some((stack, count)) = folded_line(line) else {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "line {} is not a collapsed stack (`frame;frame count`)",
+                index + 1
+            )));
+        };
+        stacks.push((stack.split(';').map(str::to_string).collect(), count));
+    }
+    Ok(stacks)
+}
+
+/// Reads `perf script` output: one sample per block, a header line followed
+/// by one `address symbol+offset (dso)` line per frame, leaf first.
+fn parse_perf_script(text: &str) -> Vec<Stack> {
+    let mut stacks = Vec::new();
+    for event in text.split("\n\n") {
+        let mut lines = event
+            .lines()
+            .filter(|line| !line.trim().is_empty() && !line.starts_with('#'));
+        let Some(header) = lines.next() else {
+            continue;
+        };
+        let mut frames: Vec<String> = lines.map(perf_frame).collect();
+        if frames.is_empty() {
+            // Recorded without call graphs; charge the sample to the command.
+            frames.push(
+                header
+                    .split_whitespace()
+                    .next()
+                    .unwrap_or("[unknown]")
+                    .to_string(),
+            );
+        }
+        frames.reverse();
+        stacks.push((frames, 1));
+    }
+    stacks
+}
+
+/// Reads `perf script` output captured from a command. When the middle of
+/// the output was dropped, the events cut at either side of the gap are left
+/// out, and so is the truncation notice after the tail.
+fn parse_captured_perf_script(output: &StreamOutput<String>) -> Vec<Stack> {
+    let Some(truncated) = output.truncated else {
+        return parse_perf_script(&output.text);
+    };
+    let head = &output.text[..truncated.head_end];
+    let tail = &output.text[truncated.head_end..truncated.tail_end];
+    let head = head.rsplit_once("\n\n").map_or("", |(events, _)| events);
+    let tail = tail.split_once("\n\n").map_or("", |(_, events)| events);
+    let mut stacks = parse_perf_script(head);
+    stacks.extend(parse_perf_script(tail));
+    stacks
+}
+
+fn perf_frame(line: &str) -> String {
+    let line = line.trim();
+    let symbol = line
+        .split_once(char::is_whitespace)
+        .map_or(line, |(_, symbol)| symbol.trim_start());
+    // Unknown symbols keep their object, which is all there is to go on.
+    if symbol.starts_with("[unknown]") {
+        return symbol.to_string();
+    }
+    let symbol = match symbol.rfind(" (") {
+        Some(object_start) if symbol.ends_with(')') => &symbol[..object_start],
+        _ => symbol,
+    };
+    symbol
+        .rsplit_once("+0x")
+        .map_or(symbol, |(symbol, _)| symbol)
+        .to_string()
+}
+
+struct SvgFrame {
+    name: String,
+    samples: u64,
+    x: f64,
+    y: f64,
+    width: f64,
+}
+
+/// Rebuilds the stacks of a flame graph from the position of its frames:
+/// a frame's parent is the frame one level closer to the root that spans
+/// its midpoint, and the samples not covered by its children are its own.
+fn parse_flamegraph_svg(text: &str) -> Vec<Stack> {
+    let mut frames = Vec::new();
+    let mut rest = text;
+    while let Some(title_start) = rest.find("<title>") {
+        rest = &rest[title_start + "<title>".len()..];
+        let Some(title_end) = rest.find("</title>") else {
+            break;
+        };
+        let title = rest[..title_end]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&");
+        rest = &rest[title_end..];
+        let Some(rect_start) = rest.find("<rect") else {
+            break;
+        };
+        if rest
+            .find("<title>")
+            .is_some_and(|next_title| next_title < rect_start)
+        {
+            continue;
+        }
+        let rect = &rest[rect_start..];
+        let rect = &rect[..rect.find('>').unwrap_or(rect.len())];
+        let Some((name, samples)) = title.rsplit_once(" (").and_then(|(name, counts)| {
+            let (samples, _) = counts.split_once(" sample")?;
+            Some((name, samples.replace(',', "").parse().ok()?))
+        }) else {
+            continue;
+        };
+        let attribute = |name: &str| {
+            let value = &rect[rect.find(&format!(" {name}=\""))? + name.len() + 3..];
+            value[..value.find('"')?].trim_end_matches('%').parse().ok()
+        };
+        let (Some(x), Some(y), Some(width)) = (attribute("x"), attribute("y"), attribute("width"))
+        else {
+            continue;
+        };
+        frames.push(SvgFrame {
+            name: name.to_string(),
+            samples,
+            x,
+            y,
+            width,
+        });
+    }
+    // Levels from the root outwards. The root (`all`) sits at the bottom of a
+    // flame graph and at the top of an icicle graph.
+    let mut level_ys: Vec<f64> = frames.iter().map(|frame| frame.y).collect();
+    level_ys.sort_by(f64::total_cmp);
+    level_ys.dedup();
+    let icicle = frames
+        .iter()
+        .find(|frame| frame.name == "all")
+        .is_some_and(|root| level_ys.first() == Some(&root.y));
+    if !icicle {
+        level_ys.reverse();
+    }
+    let mut levels = vec![Vec::new(); level_ys.len()];
+    for (index, frame) in frames.iter().enumerate() {
+        if let Some(depth) = level_ys.iter().position(|y| *y == frame.y) {
+            levels[depth].push(index);
+        }
+    }
+    for level in &mut levels {
+        level.sort_by(|a, b| frames[*a].x.total_cmp(&frames[*b].x));
+    }
+
+    let mut parents = vec![None; frames.len()];
+    let mut child_samples = vec![0; frames.len()];
+    for depth in 1..levels.len() {
+        for &child in &levels[depth] {
+            let midpoint = frames[child].x + frames[child].width / 2.0;
+            let above = &levels[depth - 1];
+            let index = above.partition_point(|parent| frames[*parent].x <= midpoint);
+            if let Some(&parent) = index.checked_sub(1).and_then(|index| above.get(index))
+                && midpoint < frames[parent].x + frames[parent].width
+            {
+                parents[child] = Some(parent);
+                child_samples[parent] += frames[child].samples;
+            }
+        }
+    }
+
+    let mut stacks = Vec::new();
+    for (index, frame) in frames.iter().enumerate() {
+        let own_samples = frame.samples.saturating_sub(child_samples[index]);
+        if own_samples == 0 {
+            continue;
+        }
+        let mut chain = vec![frame.name.clone()];
+        let mut current = index;
+        while let Some(parent) = parents[current] {
+            chain.push(frames[parent].name.clone());
+            current = parent;
+        }
+        // flamegraph.pl and inferno add a root frame for the whole profile.
+        if chain.len() > 1 && chain.last().is_some_and(|name| name == "all") {
+            chain.pop();
+        }
+        chain.reverse();
+        stacks.push((chain, own_samples));
+    }
+    stacks
+}
+
+/// Reads a pprof `Profile` message, weighting each stack by the default
+/// sample type (the last one, unless the profile names another).
+fn parse_pprof(bytes: &[u8]) -> Result<(String, Vec<Stack>), FunctionCallError> {
+    let malformed =
+        |what: &str| FunctionCallError::RespondToModel(format!("malformed pprof profile: {what}"));
+    let data = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut data = Vec::new();
+        GzDecoder::new(bytes)
+            .take(MAX_PPROF_BYTES + 1)
+            .read_to_end(&mut data)
+            .map_err(|err| malformed(&err.to_string()))?;
+        if data.len() as u64 > MAX_PPROF_BYTES {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "pprof profile decompresses to more than {MAX_PPROF_BYTES} bytes"
+            )));
+        }
+        Cow::Owned(data)
+    } else {
+        Cow::Borrowed(bytes)
+    };
+
+    let mut sample_types = Vec::new();
+    let mut samples = Vec::new();
+    let mut locations: HashMap<u64, Vec<u64>> = HashMap::new();
+    let mut functions: HashMap<u64, u64> = HashMap::new();
+    let mut strings = Vec::new();
+    let mut default_sample_type = None;
+    for field in proto_fields(&data) {
+        match field.ok_or_else(|| malformed("truncated message"))? {
+            (1, ProtoValue::Bytes(value_type)) => {
+                let (mut kind, mut unit) = (0, 0);
+                for field in proto_fields(value_type) {
+                    match field.ok_or_else(|| malformed("truncated sample type"))? {
+                        (1, ProtoValue::Varint(index)) => kind = index,
+                        (2, ProtoValue::Varint(index)) => unit = index,
+                        _ => {}
+                    }
+                }
+                sample_types.push((kind, unit));
+            }
+            (2, ProtoValue::Bytes(sample)) => {
+                let (mut location_ids, mut values) = (Vec::new(), Vec::new());
+                for field in proto_fields(sample) {
+                    match field.ok_or_else(|| malformed("truncated sample"))? {
+                        (1, value) => location_ids.extend(packed_varints(value)),
+                        (2, value) => values.extend(packed_varints(value)),
+                        _ => {}
+                    }
+                }
+                samples.push((location_ids, values));
+            }
+            (4, ProtoValue::Bytes(location)) => {
+                let (mut id, mut function_ids) = (0, Vec::new());
+                for field in proto_fields(location) {
+                    match field.ok_or_else(|| malformed("truncated location"))? {
+                        (1, ProtoValue::Varint(value)) => id = value,
+                        (4, ProtoValue::Bytes(line)) => {
+                            for field in proto_fields(line) {
+                                if let (1, ProtoValue::Varint(function_id)) =
+                                    field.ok_or_else(|| malformed("truncated line"))?
+                                {
+                                    function_ids.push(function_id);
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                locations.insert(id, function_ids);
+            }
+            (5, ProtoValue::Bytes(function)) => {
+                let (mut id, mut name) = (0, 0);
+                for field in proto_fields(function) {
+                    match field.ok_or_else(|| malformed("truncated function"))? {
+                        (1, ProtoValue::Varint(value)) => id = value,
+                        (2, ProtoValue::Varint(value)) => name = value,
+                        _ => {}
+                    }
+                }
+                functions.insert(id, name);
+            }
+            (6, ProtoValue::Bytes(string)) => {
+                strings.push(String::from_utf8_lossy(string).into_owned());
+            }
+            (14, ProtoValue::Varint(kind)) => default_sample_type = Some(kind),
+            _ => {}
+        }
+    }
+
+    let string = |index: u64| {
+        strings
+            .get(index as usize)
+            .map_or("[unknown]", String::as_str)
+    };
+    let value_index = default_sample_type
+        .and_then(|kind| {
+            sample_types
+                .iter()
+                .position(|(sample_kind, _)| *sample_kind == kind)
+        })
+        .unwrap_or(sample_types.len().saturating_sub(1));
+    let sample_type = sample_types.get(value_index).map_or_else(
+        || "samples".to_string(),
+        |(kind, unit)| format!("{}/{}", string(*kind), string(*unit)),
+    );
+
+    let mut stacks = Vec::new();
+    for (location_ids, values) in samples {
+        // Values are int64; negative ones (diff profiles) are not hot spots.
+        let Some(&value) = values.get(value_index) else {
+            continue;
+        };
+        if value as i64 <= 0 {
+            continue;
+        }
+        // Locations run leaf first, as do the inlined lines of each one.
+        let mut frames: Vec<String> = location_ids
+            .iter()
+            .flat_map(|id| locations.get(id).map(Vec::as_slice).unwrap_or(&[0][..]))
+            .map(|function_id| {
+                functions
+                    .get(function_id)
+                    .map_or("[unknown]", |name| string(*name))
+                    .to_string()
+            })
+            .collect();
+        frames.reverse();
+        stacks.push((frames, value));
+    }
+    Ok((sample_type, stacks))
+}
+
+enum ProtoValue<'a> {
+    Varint(u64),
+    Bytes(&'a [u8]),
+    Fixed,
+}
+
+/// The fields of a protobuf message as `(number, value)`, with `None` for a
+/// field cut short.
+fn proto_fields(mut data: &[u8]) -> impl Iterator<Item = Option<(u64, ProtoValue<'_>)>> {
+    std::iter::from_fn(move || {
+        if data.is_empty() {
+            return None;
+        }
+        let field = (|| {
+            let key = read_varint(&mut data)?;
+            let value = match key & 7 {
+                0 => ProtoValue::Varint(read_varint(&mut data)?),
+                1 | 5 => {
+                    let width = if key & 7 == 1 { 8 } else { 4 };
+                    data = data.get(width..)?;
+                    ProtoValue::Fixed
+                }
+                2 => {
+                    let len = usize::try_from(read_varint(&mut data)?).ok()?;
+                    let (bytes, rest) = data.split_at_checked(len)?;
+                    data = rest;
+                    ProtoValue::Bytes(bytes)
+                }
+                _ => return None,
+            };
+            Some((key >> 3, value))
+        })();
+        if field.is_none() {
+            data = &[];
+        }
+        Some(field)
+    })
+}
+
+fn read_varint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first()?;
+        *data = rest;
+        value |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// A repeated integer field, which may be packed into one length-delimited
+/// value or spread over several varints.
+fn packed_varints(value: ProtoValue<'_>) -> Vec<u64> {
+    match value {
+        ProtoValue::Varint(value) => vec![value],
+        ProtoValue::Bytes(mut data) => std::iter::from_fn(|| read_varint(&mut data)).collect(),
+        ProtoValue::Fixed => Vec::new(),
+    }
+}
+
+fn summarize(
+    format: ProfileFormat,
+    sample_type: String,
+    stacks: Vec<Stack>,
+    focus: Option<&str>,
+    limit: usize,
+) -> ProfileSummary {
+    let total: u64 = stacks.iter().map(|(_, samples)| samples).sum();
+    let stacks: Vec<Stack> = stacks
+        .into_iter()
+        .filter(|(frames, _)| {
+            focus.is_none_or(|focus| frames.iter().any(|frame| frame.contains(focus)))
+        })
+        .collect();
+    let focused = focus.map(|_| stacks.iter().map(|(_, samples)| samples).sum());
+    let percent = |samples: u64| {
+        if total == 0 {
+            0.0
+        } else {
+            (samples as f64 * 1000.0 / total as f64).round() / 10.0
+        }
+    };
+
+    let mut functions: HashMap<&str, (u64, u64)> = HashMap::new();
+    let mut merged: HashMap<&[String], u64> = HashMap::new();
+    for (frames, samples) in &stacks {
+        if let Some(leaf) = frames.last() {
+            functions.entry(leaf.as_str()).or_default().0 += samples;
+        }
+        // Recursive frames count once toward the total.
+        let mut seen = HashSet::new();
+        for frame in frames {
+            if seen.insert(frame.as_str()) {
+                functions.entry(frame.as_str()).or_default().1 += samples;
+            }
+        }
+        *merged.entry(frames.as_slice()).or_default() += samples;
+    }
+
+    let mut functions: Vec<HotFunction> = functions
+        .into_iter()
+        .map(|(function, (self_samples, total_samples))| HotFunction {
+            function: format_line(function.as_bytes()),
+            self_samples,
+            self_percent: percent(self_samples),
+            total_samples,
+            total_percent: percent(total_samples),
+        })
+        .collect();
+    let truncated = functions.len() > limit;
+    functions.sort_by(|a, b| {
+        b.self_samples
+            .cmp(&a.self_samples)
+            .then(b.total_samples.cmp(&a.total_samples))
+            .then(a.function.cmp(&b.function))
+    });
+    let top_self: Vec<HotFunction> = functions
+        .iter()
+        .take(limit)
+        .filter(|function| function.self_samples > 0)
+        .cloned()
+        .collect();
+    functions.sort_by(|a, b| {
+        b.total_samples
+            .cmp(&a.total_samples)
+            .then(b.self_samples.cmp(&a.self_samples))
+            .then(a.function.cmp(&b.function))
+    });
+    functions.truncate(limit);
+
+    let mut hot_stacks: Vec<(&[String], u64)> = merged.into_iter().collect();
+    hot_stacks.sort_by(|(a_frames, a), (b_frames, b)| b.cmp(a).then(a_frames.cmp(b_frames)));
+    let hot_stacks = hot_stacks
+        .into_iter()
+        .take(MAX_HOT_STACKS)
+        .map(|(frames, samples)| {
+            let mut leaf_first: Vec<String> = frames
+                .iter()
+                .rev()
+                .take(MAX_STACK_FRAMES)
+                .map(|frame| format_line(frame.as_bytes()))
+                .collect();
+            if frames.len() > MAX_STACK_FRAMES {
+                leaf_first.push(format!("[{} more frames]", frames.len() - MAX_STACK_FRAMES));
+            }
+            HotStack {
+                frames: leaf_first,
+                samples,
+                percent: percent(samples),
+            }
+        })
+        .collect();
+
+    ProfileSummary {
+        format,
+        sample_type,
+        total,
+        focused,
+        top_self,
+        top_total: functions,
+        hot_stacks,
+        truncated,
+        incomplete: false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::StreamTruncation;
+    use pretty_assertions::assert_eq;
+
+    fn stack(frames: &[&str], samples: u64) -> Stack {
+        (
+            frames.iter().map(|frame| frame.to_string()).collect(),
+            samples,
+        )
+    }
+
+    #[test]
+    fn perf_script_samples_become_root_first_stacks() {
+        let script = "\
+# ========
+# captured on: Thu Oct  1 10:00:00 2026
+# ========
+#
+app 4242 100.000001: 250000 cpu-clock:
+\t    55d0c0a1b2c3 app::parse::tokenize+0x1f (/work/target/release/app)
+\t    55d0c0a1b400 app::main+0x42 (/work/target/release/app)
+\t    7f0000001000 [unknown] (/usr/lib/libc.so.6)
+
+app 4242 100.000251: 250000 cpu-clock:
+\t    55d0c0a1b400 app::main+0x10 (/work/target/release/app)
+";
+        assert_eq!(detect_format(script.as_bytes()), ProfileFormat::PerfScript);
+        assert_eq!(
+            parse_perf_script(script),
+            vec![
+                stack(
+                    &[
+                        "[unknown] (/usr/lib/libc.so.6)",
+                        "app::main",
+                        "app::parse::tokenize",
+                    ],
+                    1
+                ),
+                stack(&["app::main"], 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_perf_script_skips_the_events_cut_at_the_gap() {
+        let head = "\
+app 1 1.0: cpu-clock:
+\t    1 app::a+0x1 (/app)
+
+app 1 2.0: cpu-clock:
+\t    2 app::b+0x1 (/app)
+\t    3 app::";
+        let tail = "\
+main+0x1 (/app)
+
+app 1 9.0: cpu-clock:
+\t    4 app::c+0x1 (/app)
+";
+        let notice = "\n[output truncated: 100 of 150 bytes omitted from the middle.]\n";
+        let output = StreamOutput {
+            text: format!("{head}{tail}{notice}"),
+            truncated_after_lines: None,
+            truncated: Some(StreamTruncation {
+                total_bytes: 150,
+                head_end: head.len(),
+                tail_end: head.len() + tail.len(),
+            }),
+        };
+
+        assert_eq!(
+            parse_captured_perf_script(&output),
+            vec![stack(&["app::a"], 1), stack(&["app::c"], 1)]
+        );
+    }
+
+    #[test]
+    fn flamegraph_svg_frames_keep_only_their_own_samples() {
+        let svg = r#"<?xml version="1.0" standalone="no"?>
+<svg version="1.1" width="1200" height="86" xmlns="http://www.w3.org/2000/svg">
+<g id="frames">
+<g><title>all (10 samples, 100%)</title><rect x="0.0000%" y="53" width="100.0000%" height="15" fill="rgb(1,1,1)" fg:x="0" fg:w="10"/><text x="0.2500%" y="63.50">all</text></g>
+<g><title>main (10 samples, 100.00%)</title><rect x="0.0000%" y="37" width="100.0000%" height="15" fill="rgb(1,1,1)" fg:x="0" fg:w="10"/><text x="0.2500%" y="47.50">main</text></g>
+<g><title>parse&lt;T&gt; (6 samples, 60.00%)</title><rect x="0.0000%" y="21" width="60.0000%" height="15" fill="rgb(1,1,1)" fg:x="0" fg:w="6"/><text x="0.2500%" y="31.50">parse&lt;T&gt;</text></g>
+<g><title>render (3 samples, 30.00%)</title><rect x="60.0000%" y="21" width="30.0000%" height="15" fill="rgb(1,1,1)" fg:x="6" fg:w="3"/><text x="60.2500%" y="31.50">render</text></g>
+</g>
+</svg>"#;
+        assert_eq!(detect_format(svg.as_bytes()), ProfileFormat::FlamegraphSvg);
+        assert_eq!(
+            parse_flamegraph_svg(svg),
+            vec![
+                stack(&["main"], 1),
+                stack(&["main", "parse<T>"], 6),
+                stack(&["main", "render"], 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn summary_ranks_functions_by_self_and_total_samples() {
+        let folded = "main;parse;tokenize 6\nmain;parse 2\nmain;render 2\n";
+        assert_eq!(detect_format(folded.as_bytes()), ProfileFormat::Folded);
+        let stacks = parse_folded(folded).expect("folded stacks");
+
+        let function = |function: &str, self_samples: u64, total_samples: u64| HotFunction {
+            function: function.to_string(),
+            self_samples,
+            self_percent: self_samples as f64 * 10.0,
+            total_samples,
+            total_percent: total_samples as f64 * 10.0,
+        };
+        assert_eq!(
+            summarize(
+                ProfileFormat::Folded,
+                "samples".to_string(),
+                stacks,
+                Some("parse"),
+                2
+            ),
+            ProfileSummary {
+                format: ProfileFormat::Folded,
+                sample_type: "samples".to_string(),
+                total: 10,
+                focused: Some(8),
+                top_self: vec![function("tokenize", 6, 6), function("parse", 2, 8)],
+                top_total: vec![function("parse", 2, 8), function("main", 0, 8)],
+                hot_stacks: vec![
+                    HotStack {
+                        frames: vec![
+                            "tokenize".to_string(),
+                            "parse".to_string(),
+                            "main".to_string(),
+                        ],
+                        samples: 6,
+                        percent: 60.0,
+                    },
+                    HotStack {
+                        frames: vec!["parse".to_string(), "main".to_string()],
+                        samples: 2,
+                        percent: 20.0,
+                    },
+                ],
+                truncated: true,
+                incomplete: false,
+            }
+        );
+    }
+}
@@ -0,0 +1,628 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::LazyLock;
+
+use async_trait::async_trait;
+use codex_protocol::models::ShellCommandToolCallParams;
+use regex_lite::Regex;
+use serde::Deserialize;
+
+use crate::exec::ExecToolCallOutput;
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::ShellCommandHandler;
+use crate::tools::handlers::ShellHandler;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::handlers::read_file::format_line;
+use crate::tools::handlers::shell::OutputSummary;
+use crate::tools::handlers::shell::RunExecLikeArgs;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+
+/// Turns a raw backtrace or a core dump into frames with function names,
+/// source locations, and the source line of each frame found in the
+/// workspace. Raw addresses are resolved against the build artifact with
+/// `addr2line` (or `atos` for a dSYM without LLVM tools); core dumps are
+/// read with `gdb` or `lldb`. The symbolizer runs like any other command,
+/// in the sandbox and under the approval policy.
+pub struct SymbolicateBacktraceHandler;
+
+const MAX_FRAMES: usize = 200;
+const COMMAND_TIMEOUT_MS: u64 = 60_000;
+
+/// The module of a `backtrace_symbols` frame: `./app(+0x1a2b) [0x...]`.
+static GLIBC_MODULE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([^\s(\[]+)\(").unwrap_or_else(|err| panic!("invalid glibc module regex: {err}"))
+});
+/// The module of a macOS crash report frame:
+/// `3   app   0x0000000100003f50 0x100000000 + 16208`.
+static CRASH_REPORT_MODULE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d+\s+(\S+)\s+0x[0-9a-fA-F]+\s+0x[0-9a-fA-F]+ \+ \d+")
+        .unwrap_or_else(|err| panic!("invalid crash report module regex: {err}"))
+});
+/// `module(+0x1a2b)`, as printed by glibc's `backtrace_symbols`.
+static MODULE_OFFSET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\(\+0x([0-9a-fA-F]+)\)")
+        .unwrap_or_else(|err| panic!("invalid module offset regex: {err}"))
+});
+/// `0x100000000 + 16208`, the load address and offset of a macOS crash
+/// report.
+static LOAD_OFFSET: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"0x[0-9a-fA-F]+ \+ (\d+)")
+        .unwrap_or_else(|err| panic!("invalid load offset regex: {err}"))
+});
+/// `[0x55d0c0a1ba2b]`, the absolute address `backtrace_symbols` prints last.
+static BRACKETED_ADDRESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\[0x([0-9a-fA-F]+)\]")
+        .unwrap_or_else(|err| panic!("invalid bracketed address regex: {err}"))
+});
+static ADDRESS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"0x([0-9a-fA-F]+)").unwrap_or_else(|err| panic!("invalid address regex: {err}"))
+});
+/// A frame of `gdb`'s `bt` (`#3  0x... in main () at main.c:12`) or
+/// `lldb`'s `thread backtrace` (`frame #3: 0x... app`main at main.c:12:5`).
+static DEBUGGER_FRAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*(?:\*\s*)?(?:frame\s+)?#\d+")
+        .unwrap_or_else(|err| panic!("invalid frame regex: {err}"))
+});
+
+#[derive(Deserialize)]
+struct SymbolicateBacktraceArgs {
+    binary: String,
+    #[serde(default)]
+    backtrace: Option<String>,
+    #[serde(default)]
+    core_dump: Option<String>,
+    #[serde(default)]
+    load_address: Option<String>,
+}
+
+/// A frame of a raw backtrace.
+#[derive(Debug, PartialEq)]
+struct RawFrame {
+    text: String,
+    /// Absolute address in the crashed process.
+    address: Option<u64>,
+    /// Offset into the binary, when the backtrace gives it or the load
+    /// address is known.
+    offset: Option<u64>,
+    /// Whether the frame is in the binary rather than in another module
+    /// the backtrace names, such as a shared library.
+    in_binary: bool,
+}
+
+/// One function at an address; an address inside inlined code resolves to
+/// several, innermost first.
+#[derive(Debug, PartialEq)]
+struct Symbol {
+    function: String,
+    location: Option<(String, u32)>,
+}
+
+#[async_trait]
+impl ToolHandler for SymbolicateBacktraceHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            tracker,
+            call_id,
+            tool_name,
+            payload,
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "symbolicate_backtrace handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: SymbolicateBacktraceArgs = parse_arguments(&arguments)?;
+        let binary = turn.resolve_path(Some(args.binary));
+        if !binary.exists() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "binary {} does not exist",
+                binary.display()
+            )));
+        }
+        let load_address = args
+            .load_address
+            .as_deref()
+            .map(|address| {
+                u64::from_str_radix(address.trim().trim_start_matches("0x"), 16).map_err(|_| {
+                    FunctionCallError::RespondToModel(format!(
+                        "load_address `{address}` is not a hex address"
+                    ))
+                })
+            })
+            .transpose()?;
+
+        type ParseOutput = Box<dyn FnOnce(&str) -> Result<Vec<String>, String> + Send>;
+        let (argv, parse): (Vec<String>, ParseOutput) = match (args.backtrace, args.core_dump) {
+            (Some(backtrace), None) => {
+                // A dSYM bundle holds the debug info of the binary it is named
+                // after.
+                let module = if binary.extension().is_some_and(|ext| ext == "dSYM") {
+                    binary.file_stem()
+                } else {
+                    binary.file_name()
+                };
+                let module = module.map(|name| name.to_string_lossy().into_owned());
+                let frames = parse_raw_backtrace(&backtrace, module.as_deref(), load_address);
+                if !frames
+                    .iter()
+                    .any(|frame| frame.in_binary && frame.address.is_some())
+                {
+                    return Err(FunctionCallError::RespondToModel(
+                        "no addresses in the binary found in the backtrace".to_string(),
+                    ));
+                }
+                let (tool, argv) = symbolizer_command(&binary, &frames, load_address)?;
+                (
+                    argv,
+                    Box::new(move |stdout: &str| {
+                        let symbols = if tool == "atos" {
+                            parse_atos(stdout)
+                        } else {
+                            parse_addr2line(stdout)
+                        };
+                        let mut lines = vec![format!("Symbolicated with {tool}:")];
+                        lines.extend(format_raw_frames(&frames, &symbols));
+                        Ok(lines)
+                    }),
+                )
+            }
+            (None, Some(core_dump)) => {
+                let core_dump = turn.resolve_path(Some(core_dump));
+                (
+                    debugger_command(&binary, &core_dump)?,
+                    Box::new(debugger_frames),
+                )
+            }
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "pass exactly one of `backtrace` and `core_dump`".to_string(),
+                ));
+            }
+        };
+
+        let command = shlex::try_join(argv.iter().map(String::as_str)).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to quote {}: {err}", argv[0]))
+        })?;
+        let params = ShellCommandToolCallParams {
+            command,
+            workdir: None,
+            login: None,
+            timeout_ms: Some(COMMAND_TIMEOUT_MS),
+            sandbox_permissions: None,
+            prefix_rule: None,
+            justification: None,
+        };
+        let exec_params = ShellCommandHandler::to_exec_params(
+            &params,
+            session.as_ref(),
+            turn.as_ref(),
+            session.conversation_id,
+            turn.tools_config.allow_login_shell,
+        )?;
+        let cwd = turn.cwd.clone();
+        let tool = argv[0].clone();
+        ShellHandler::run_exec_like(RunExecLikeArgs {
+            tool_name,
+            exec_params,
+            prefix_rule: None,
+            session,
+            turn,
+            tracker,
+            call_id,
+            freeform: true,
+            summarize_output: Some(Box::new(move |output: &ExecToolCallOutput| {
+                if output.exit_code != 0 {
+                    return OutputSummary {
+                        summary: format!("{tool} failed"),
+                        include_output: true,
+                    };
+                }
+                match parse(&output.stdout.text) {
+                    Ok(lines) => {
+                        let mut sources = HashMap::new();
+                        let mut annotated = Vec::new();
+                        for line in lines {
+                            let source = source_location(&line).and_then(|(file, line)| {
+                                source_line(&cwd, &file, line, &mut sources)
+                            });
+                            annotated.push(line);
+                            annotated.extend(source);
+                        }
+                        OutputSummary {
+                            summary: annotated.join("\n"),
+                            include_output: false,
+                        }
+                    }
+                    Err(message) => OutputSummary {
+                        summary: message,
+                        include_output: true,
+                    },
+                }
+            })),
+        })
+        .await
+    }
+}
+
+/// Reads the frames of a raw backtrace of the binary named `module`. Frames
+/// that name another module are kept for context but not resolved.
+fn parse_raw_backtrace(
+    backtrace: &str,
+    module: Option<&str>,
+    load_address: Option<u64>,
+) -> Vec<RawFrame> {
+    backtrace
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .take(MAX_FRAMES)
+        .map(|line| {
+            let hex = |captures: Option<regex_lite::Captures>| {
+                u64::from_str_radix(captures?.get(1)?.as_str(), 16).ok()
+            };
+            let frame_module = GLIBC_MODULE
+                .captures(line)
+                .or_else(|| CRASH_REPORT_MODULE.captures(line))
+                .and_then(|captures| captures.get(1))
+                .map(|frame_module| frame_module.as_str());
+            let in_binary = match (frame_module, module) {
+                (Some(frame_module), Some(module)) => {
+                    Path::new(frame_module).file_name() == Some(module.as_ref())
+                }
+                _ => true,
+            };
+            let address =
+                hex(BRACKETED_ADDRESS.captures(line)).or_else(|| hex(ADDRESS.captures(line)));
+            if !in_binary {
+                return RawFrame {
+                    text: line.to_string(),
+                    address,
+                    offset: None,
+                    in_binary,
+                };
+            }
+            let offset = hex(MODULE_OFFSET.captures(line))
+                .or_else(|| {
+                    LOAD_OFFSET
+                        .captures(line)
+                        .and_then(|captures| captures.get(1)?.as_str().parse().ok())
+                })
+                .or_else(|| address?.checked_sub(load_address?));
+            RawFrame {
+                text: line.to_string(),
+                address,
+                offset,
+                in_binary,
+            }
+        })
+        .collect()
+}
+
+/// The command resolving the frames of the binary that have an address,
+/// with the symbolizer it runs.
+fn symbolizer_command(
+    binary: &Path,
+    frames: &[RawFrame],
+    load_address: Option<u64>,
+) -> Result<(&'static str, Vec<String>), FunctionCallError> {
+    let resolvable = frames
+        .iter()
+        .filter(|frame| frame.in_binary && frame.address.is_some());
+    if let Some(tool) = ["llvm-addr2line", "addr2line"]
+        .into_iter()
+        .find(|tool| which::which(tool).is_ok())
+    {
+        let dwarf = dsym_dwarf_file(binary).unwrap_or_else(|| binary.to_path_buf());
+        let mut argv = vec![
+            tool.to_string(),
+            "--exe".to_string(),
+            dwarf.display().to_string(),
+        ];
+        argv.extend(["--functions", "--demangle", "--inlines", "--addresses"].map(str::to_string));
+        argv.extend(
+            resolvable
+                .filter_map(|frame| frame.offset.or(frame.address))
+                .map(|address| format!("{address:#x}")),
+        );
+        return Ok((tool, argv));
+    }
+
+    // macOS ships atos rather than LLVM's tools; it wants the addresses as
+    // they were in the process.
+    if which::which("atos").is_err() {
+        return Err(FunctionCallError::RespondToModel(
+            "no symbolizer found; install llvm-addr2line or binutils' addr2line".to_string(),
+        ));
+    }
+    let mut argv = vec![
+        "atos".to_string(),
+        "-o".to_string(),
+        binary.display().to_string(),
+        "-i".to_string(),
+    ];
+    if let Some(load_address) = load_address {
+        argv.extend(["-l".to_string(), format!("{load_address:#x}")]);
+    }
+    argv.extend(
+        resolvable
+            .filter_map(|frame| frame.address)
+            .map(|address| format!("{address:#x}")),
+    );
+    Ok(("atos", argv))
+}
+
+/// The DWARF file inside `path` when it is a `.dSYM` bundle.
+fn dsym_dwarf_file(path: &Path) -> Option<PathBuf> {
+    if path.extension()? != "dSYM" {
+        return None;
+    }
+    std::fs::read_dir(path.join("Contents/Resources/DWARF"))
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .find(|path| path.is_file())
+}
+
+/// Reads `addr2line --functions --inlines --addresses` output: each address
+/// on its own line, followed by a function and `file:line` pair per inlined
+/// frame.
+fn parse_addr2line(output: &str) -> Vec<Vec<Symbol>> {
+    let mut resolved: Vec<Vec<Symbol>> = Vec::new();
+    let mut lines = output.lines();
+    while let Some(line) = lines.next() {
+        if line.starts_with("0x") {
+            resolved.push(Vec::new());
+            continue;
+        }
+        let Some(symbols) = resolved.last_mut() else {
+            continue;
+        };
+        let location = lines.next().and_then(|location| {
+            let location = location.split(" (discriminator").next().unwrap_or(location);
+            let (file, line) = location.rsplit_once(':')?;
+            let line = line.parse().ok().filter(|line| *line > 0)?;
+            (file != "??").then(|| (file.to_string(), line))
+        });
+        if line != "??" || location.is_some() {
+            symbols.push(Symbol {
+                function: line.to_string(),
+                location,
+            });
+        }
+    }
+    resolved
+}
+
+/// Reads `atos -i` output: one line per frame, `function (in Module)
+/// (file:line)`, with the inlined frames of an address on consecutive lines
+/// and a blank line after each address.
+fn parse_atos(output: &str) -> Vec<Vec<Symbol>> {
+    output
+        .split("\n\n")
+        .filter(|block| !block.trim().is_empty())
+        .map(|block| {
+            block
+                .lines()
+                .filter(|line| !line.starts_with("0x"))
+                .map(|line| {
+                    let function = line.split(" (in ").next().unwrap_or(line).to_string();
+                    let location = line
+                        .rsplit_once(" (")
+                        .and_then(|(_, location)| location.strip_suffix(')'))
+                        .and_then(|location| location.rsplit_once(':'))
+                        .and_then(|(file, line)| Some((file.to_string(), line.parse().ok()?)));
+                    Symbol { function, location }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn format_raw_frames(frames: &[RawFrame], symbols: &[Vec<Symbol>]) -> Vec<String> {
+    let mut symbols = symbols.iter();
+    let mut lines = Vec::new();
+    for (index, frame) in frames.iter().enumerate() {
+        let resolved = match frame.address {
+            Some(_) if frame.in_binary => symbols.next().map(Vec::as_slice).unwrap_or_default(),
+            _ => &[],
+        };
+        let Some(address) = frame.address.filter(|_| !resolved.is_empty()) else {
+            lines.push(format!("#{index} {}", frame.text));
+            continue;
+        };
+        for (depth, symbol) in resolved.iter().enumerate() {
+            let location = symbol
+                .location
+                .as_ref()
+                .map(|(file, line)| format!(" at {file}:{line}"))
+                .unwrap_or_default();
+            let inlined = if depth + 1 < resolved.len() {
+                " (inlined)"
+            } else {
+                ""
+            };
+            lines.push(format!(
+                "#{index} {address:#x} {}{location}{inlined}",
+                symbol.function
+            ));
+        }
+    }
+    lines
+}
+
+/// The command printing the frames of every thread of `core_dump`, with
+/// `gdb` or else `lldb`. Neither loads scripts from the user's or the
+/// binary's directories, which could run arbitrary code.
+fn debugger_command(binary: &Path, core_dump: &Path) -> Result<Vec<String>, FunctionCallError> {
+    let binary = binary.display().to_string();
+    let core_dump = core_dump.display().to_string();
+    if which::which("gdb").is_ok() {
+        return Ok([
+            "gdb",
+            "--batch",
+            "-nx",
+            "-iex",
+            "set auto-load off",
+            "-ex",
+            "set pagination off",
+            "-ex",
+            "thread apply all bt",
+            &binary,
+            &core_dump,
+        ]
+        .map(str::to_string)
+        .to_vec());
+    }
+    if which::which("lldb").is_ok() {
+        return Ok([
+            "lldb",
+            "--batch",
+            "--no-lldbinit",
+            "--core",
+            &core_dump,
+            "-o",
+            "thread backtrace all",
+            &binary,
+        ]
+        .map(str::to_string)
+        .to_vec());
+    }
+    Err(FunctionCallError::RespondToModel(
+        "reading a core dump needs gdb or lldb, and neither is installed".to_string(),
+    ))
+}
+
+/// The frame lines of a debugger's backtrace output.
+fn debugger_frames(stdout: &str) -> Result<Vec<String>, String> {
+    let lines: Vec<String> = stdout
+        .lines()
+        .filter(|line| {
+            DEBUGGER_FRAME.is_match(line)
+                || line.starts_with("Thread ")
+                || line.trim_start_matches([' ', '*']).starts_with("thread #")
+        })
+        .take(MAX_FRAMES)
+        .map(|line| format_line(line.trim_end().as_bytes()))
+        .collect();
+    if lines.is_empty() {
+        return Err("no frames found in the core dump".to_string());
+    }
+    Ok(lines)
+}
+
+/// The `file:line` a frame is `at`, as `gdb`, `lldb`, and the frames built
+/// here print it; `lldb` adds a column.
+fn source_location(frame: &str) -> Option<(String, u32)> {
+    let (_, location) = frame.rsplit_once(" at ")?;
+    let location = location.split_whitespace().next()?;
+    let (rest, last) = location.rsplit_once(':')?;
+    let last: u32 = last.parse().ok()?;
+    match rest.rsplit_once(':') {
+        Some((file, line)) if line.parse::<u32>().is_ok() => {
+            Some((file.to_string(), line.parse().ok()?))
+        }
+        _ => Some((rest.to_string(), last)),
+    }
+}
+
+/// Line `line` of `file`, if the file is in the workspace or at an absolute
+/// path that exists, formatted to follow its frame.
+fn source_line(
+    cwd: &Path,
+    file: &str,
+    line: u32,
+    sources: &mut HashMap<PathBuf, Option<String>>,
+) -> Option<String> {
+    let path = cwd.join(file);
+    let source = sources
+        .entry(path.clone())
+        .or_insert_with(|| std::fs::read_to_string(&path).ok())
+        .as_deref()?;
+    let text = source
+        .lines()
+        .nth(usize::try_from(line).ok()?.checked_sub(1)?)?;
+    Some(format!(
+        "    {line} | {}",
+        format_line(text.trim().as_bytes())
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn raw_frames_resolve_to_inlined_symbols_with_locations() {
+        let backtrace = "\
+./app(+0x1a2b) [0x55d0c0a1ba2b]
+  1: 0x55d0c0a1c000 - <unknown>
+libc.so.6(__libc_start_main+0x80) [0x7f0000002000]
+";
+        let frames = parse_raw_backtrace(backtrace, Some("app"), Some(0x55d0c0a00000));
+        assert_eq!(
+            frames
+                .iter()
+                .map(|frame| (frame.address, frame.offset, frame.in_binary))
+                .collect::<Vec<_>>(),
+            vec![
+                (Some(0x55d0c0a1ba2b), Some(0x1a2b), true),
+                (Some(0x55d0c0a1c000), Some(0x1c000), true),
+                (Some(0x7f0000002000), None, false),
+            ]
+        );
+
+        let symbols = parse_addr2line(
+            "\
+0x1a2b
+app::parse::tokenize
+/work/src/parse.rs:42
+app::parse::run
+/work/src/parse.rs:10 (discriminator 2)
+0x1c000
+app::main
+src/main.rs:5
+",
+        );
+        assert_eq!(
+            format_raw_frames(&frames, &symbols),
+            vec![
+                "#0 0x55d0c0a1ba2b app::parse::tokenize at /work/src/parse.rs:42 (inlined)",
+                "#0 0x55d0c0a1ba2b app::parse::run at /work/src/parse.rs:10",
+                "#1 0x55d0c0a1c000 app::main at src/main.rs:5",
+                "#2 libc.so.6(__libc_start_main+0x80) [0x7f0000002000]",
+            ]
+        );
+    }
+
+    #[test]
+    fn source_location_reads_debugger_frames() {
+        assert_eq!(
+            source_location("#3  0x000055d0c0a1b2c3 in parse (input=0x0) at src/parse.c:42"),
+            Some(("src/parse.c".to_string(), 42))
+        );
+        assert_eq!(
+            source_location("  frame #1: 0x0000000100003f50 app`main at main.c:12:5"),
+            Some(("main.c".to_string(), 12))
+        );
+        assert_eq!(
+            source_location("#4  0x00007f0000002000 in __libc_start_main () from /lib/libc.so.6"),
+            None
+        );
+    }
+}
@@ -0,0 +1,655 @@
+use std::ffi::OsStr;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Output;
+use std::process::Stdio;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::ReviewCodeLocation;
+use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::ReviewFinding;
+use codex_protocol::protocol::ReviewLineRange;
+use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::protocol::WarningEvent;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::process::Command;
+use tokio::time::timeout;
+use uuid::Uuid;
+
+use crate::function_tool::FunctionCallError;
+use crate::protocol::EventMsg;
+use crate::tools::context::ToolInvocation;
+use crate::tools::context::ToolOutput;
+use crate::tools::context::ToolPayload;
+use crate::tools::handlers::parse_arguments;
+use crate::tools::registry::ToolHandler;
+use crate::tools::registry::ToolKind;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::formatted_truncate_text;
+
+/// Runs `terraform plan -json` into a saved plan, and reports the planned
+/// resource changes grouped by risk, with every destroyed or replaced
+/// resource as a review finding. Planning runs provider plugins and
+/// `data "external"` programs outside the sandbox, so unless the sandbox
+/// policy already grants full access it needs the user's approval.
+pub struct TerraformPlanHandler;
+
+/// Applies a plan saved by [`TerraformPlanHandler`]. Every apply needs the
+/// user's approval; one that destroys or replaces resources is refused
+/// outright when `approval_policy` is `never`, since nobody can approve it.
+pub struct TerraformApplyHandler;
+
+const PLAN_TIMEOUT: Duration = Duration::from_secs(10 * 60);
+const APPLY_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+/// Where plans are saved, relative to the Terraform working directory;
+/// `.terraform` is already ignored by Terraform projects.
+const PLANS_DIR: &str = ".terraform/codex-plans";
+const MAX_CHANGES_PER_CLASS: usize = 100;
+const MAX_OUTPUT_BYTES: usize = 64 * 1024;
+
+#[derive(Deserialize)]
+struct TerraformPlanArgs {
+    #[serde(default)]
+    dir: Option<String>,
+    #[serde(default)]
+    var_files: Vec<String>,
+    #[serde(default)]
+    targets: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TerraformApplyArgs {
+    #[serde(default)]
+    dir: Option<String>,
+    plan_id: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Risk {
+    /// Deletes or replaces existing infrastructure.
+    Destructive,
+    /// Changes existing infrastructure or state in place.
+    Modifying,
+    /// Only adds infrastructure or reads data.
+    Additive,
+}
+
+impl Risk {
+    fn of(action: &str) -> Option<Self> {
+        match action {
+            "delete" | "replace" => Some(Risk::Destructive),
+            "update" | "move" | "remove" => Some(Risk::Modifying),
+            "create" | "import" | "read" => Some(Risk::Additive),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct PlannedChange {
+    address: String,
+    action: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reason: Option<String>,
+    #[serde(skip)]
+    resource_type: String,
+    #[serde(skip)]
+    resource_name: String,
+    #[serde(skip)]
+    module: String,
+}
+
+/// What `terraform plan -json` reported, line by line.
+#[derive(Debug, Default, PartialEq)]
+struct PlanReport {
+    changes: Vec<(Risk, PlannedChange)>,
+    drifted: usize,
+    summary: Option<Value>,
+    errors: Vec<String>,
+}
+
+impl PlanReport {
+    fn parse(stream: &str) -> Self {
+        let mut report = PlanReport::default();
+        for message in stream
+            .lines()
+            .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        {
+            match message["type"].as_str() {
+                Some("planned_change") => {
+                    let change = &message["change"];
+                    let action = change["action"].as_str().unwrap_or_default();
+                    let Some(risk) = Risk::of(action) else {
+                        continue;
+                    };
+                    let resource = &change["resource"];
+                    let text = |value: &Value| value.as_str().unwrap_or_default().to_string();
+                    report.changes.push((
+                        risk,
+                        PlannedChange {
+                            address: text(&resource["addr"]),
+                            action: action.to_string(),
+                            reason: change["reason"].as_str().map(str::to_string),
+                            resource_type: text(&resource["resource_type"]),
+                            resource_name: text(&resource["resource_name"]),
+                            module: text(&resource["module"]),
+                        },
+                    ));
+                }
+                Some("resource_drift") => report.drifted += 1,
+                Some("change_summary") => report.summary = Some(message["changes"].clone()),
+                Some("diagnostic") if message["diagnostic"]["severity"] == "error" => {
+                    let diagnostic = &message["diagnostic"];
+                    let summary = diagnostic["summary"].as_str().unwrap_or_default();
+                    let detail = diagnostic["detail"].as_str().unwrap_or_default();
+                    report.errors.push(format!("{summary}: {detail}"));
+                }
+                _ => {}
+            }
+        }
+        report
+    }
+
+    fn destructive(&self) -> impl Iterator<Item = &PlannedChange> {
+        self.changes
+            .iter()
+            .filter(|(risk, _)| *risk == Risk::Destructive)
+            .map(|(_, change)| change)
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerraformPlanHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "terraform_plan handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: TerraformPlanArgs = parse_arguments(&arguments)?;
+        let dir = turn.resolve_path(args.dir);
+        let plan_id = Uuid::new_v4().to_string();
+        let plan_file = dir.join(PLANS_DIR).join(format!("{plan_id}.tfplan"));
+        let mut command = vec![
+            "terraform".to_string(),
+            "plan".to_string(),
+            "-json".to_string(),
+            "-input=false".to_string(),
+            format!("-out={}", plan_file.display()),
+        ];
+        command.extend(
+            args.var_files
+                .iter()
+                .map(|file| format!("-var-file={file}")),
+        );
+        command.extend(
+            args.targets
+                .iter()
+                .map(|target| format!("-target={target}")),
+        );
+
+        if !matches!(
+            turn.sandbox_policy.get(),
+            SandboxPolicy::DangerFullAccess | SandboxPolicy::ExternalSandbox { .. }
+        ) {
+            if matches!(turn.approval_policy.value(), AskForApproval::Never) {
+                return Err(FunctionCallError::RespondToModel(
+                    "terraform plan runs provider code outside the sandbox, which needs the \
+                     user's approval; approval_policy `never` cannot give it"
+                        .to_string(),
+                ));
+            }
+            let decision = session
+                .request_command_approval(
+                    turn.as_ref(),
+                    call_id,
+                    None,
+                    command.clone(),
+                    dir.clone(),
+                    Some(
+                        "Plan Terraform changes; providers and external data sources run \
+                         outside the sandbox"
+                            .to_string(),
+                    ),
+                    None,
+                    None,
+                )
+                .await;
+            match decision {
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                | ReviewDecision::ApprovedForSession => {}
+                ReviewDecision::Denied | ReviewDecision::Abort => {
+                    return Err(FunctionCallError::RespondToModel(
+                        "the user declined to run terraform plan".to_string(),
+                    ));
+                }
+            }
+        }
+
+        if let Some(parent) = plan_file.parent() {
+            tokio::fs::create_dir_all(parent).await.map_err(|err| {
+                FunctionCallError::RespondToModel(format!(
+                    "failed to create {}: {err}",
+                    parent.display()
+                ))
+            })?;
+        }
+        let output = run_terraform(&dir, &command[1..], PLAN_TIMEOUT).await?;
+        let report = PlanReport::parse(&String::from_utf8_lossy(&output.stdout));
+        if !output.status.success() {
+            let errors = if report.errors.is_empty() {
+                String::from_utf8_lossy(&output.stderr).trim().to_string()
+            } else {
+                report.errors.join("\n")
+            };
+            return Ok(ToolOutput::Function {
+                body: FunctionCallOutputBody::Text(formatted_truncate_text(
+                    &format!("terraform plan failed with {}:\n{errors}", output.status),
+                    TruncationPolicy::Bytes(MAX_OUTPUT_BYTES),
+                )),
+                success: Some(false),
+            });
+        }
+
+        let findings = report
+            .destructive()
+            .map(|change| destructive_finding(&dir, change))
+            .collect::<Vec<_>>();
+        if !findings.is_empty() {
+            let addresses = report
+                .destructive()
+                .map(|change| format!("{} ({})", change.address, change.action))
+                .collect::<Vec<_>>()
+                .join(", ");
+            session
+                .send_event(
+                    turn.as_ref(),
+                    EventMsg::Warning(WarningEvent {
+                        message: format!(
+                            "Terraform plan in {} destroys or replaces {} resource(s): \
+                             {addresses}. Applying it needs your explicit approval.",
+                            dir.display(),
+                            findings.len()
+                        ),
+                    }),
+                )
+                .await;
+        }
+
+        let by_risk = |risk: Risk| {
+            let changes = report
+                .changes
+                .iter()
+                .filter(|(change_risk, _)| *change_risk == risk)
+                .map(|(_, change)| change)
+                .collect::<Vec<_>>();
+            json!({
+                "count": changes.len(),
+                "resources": changes.into_iter().take(MAX_CHANGES_PER_CLASS).collect::<Vec<_>>(),
+            })
+        };
+        let summary = json!({
+            "plan_id": plan_id,
+            "summary": report.summary,
+            "destructive": by_risk(Risk::Destructive),
+            "modifying": by_risk(Risk::Modifying),
+            "additive": by_risk(Risk::Additive),
+            "drifted_resources": report.drifted,
+            "findings": findings,
+            "apply": "Call terraform_apply with this plan_id to apply exactly this plan; the user \
+                      is asked to approve it.",
+        });
+        let content = serde_json::to_string_pretty(&summary).map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to serialize plan summary: {err}"))
+        })?;
+
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(content),
+            success: Some(true),
+        })
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerraformApplyHandler {
+    fn kind(&self) -> ToolKind {
+        ToolKind::Function
+    }
+
+    async fn is_mutating(&self, _invocation: &ToolInvocation) -> bool {
+        true
+    }
+
+    async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError> {
+        let ToolInvocation {
+            session,
+            turn,
+            call_id,
+            payload,
+            ..
+        } = invocation;
+
+        let arguments = match payload {
+            ToolPayload::Function { arguments } => arguments,
+            _ => {
+                return Err(FunctionCallError::RespondToModel(
+                    "terraform_apply handler received unsupported payload".to_string(),
+                ));
+            }
+        };
+        let args: TerraformApplyArgs = parse_arguments(&arguments)?;
+        if Uuid::parse_str(&args.plan_id).is_err() {
+            return Err(FunctionCallError::RespondToModel(
+                "plan_id must be the plan_id terraform_plan returned".to_string(),
+            ));
+        }
+        let dir = turn.resolve_path(args.dir);
+        let plan_file = dir.join(PLANS_DIR).join(format!("{}.tfplan", args.plan_id));
+        if !plan_file.exists() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "no saved plan {} in {}; run terraform_plan first",
+                args.plan_id,
+                dir.display()
+            )));
+        }
+
+        let plan_file_arg = plan_file.display().to_string();
+        let shown = run_terraform(
+            &dir,
+            &["show", "-json", plan_file_arg.as_str()],
+            PLAN_TIMEOUT,
+        )
+        .await?;
+        if !shown.status.success() {
+            return Err(FunctionCallError::RespondToModel(format!(
+                "failed to read saved plan: {}",
+                String::from_utf8_lossy(&shown.stderr).trim()
+            )));
+        }
+        let destructive = saved_plan_destructive_changes(&String::from_utf8_lossy(&shown.stdout))
+            .map_err(|err| {
+            FunctionCallError::RespondToModel(format!("failed to parse saved plan: {err}"))
+        })?;
+
+        let command = vec![
+            "terraform".to_string(),
+            "apply".to_string(),
+            "-input=false".to_string(),
+            plan_file_arg,
+        ];
+        if matches!(turn.approval_policy.value(), AskForApproval::Never) {
+            if !destructive.is_empty() {
+                return Err(FunctionCallError::RespondToModel(format!(
+                    "the plan destroys or replaces {}; that needs the user's explicit approval, \
+                     which approval_policy `never` cannot give",
+                    destructive.join(", ")
+                )));
+            }
+        } else {
+            let reason = if destructive.is_empty() {
+                "Apply the reviewed Terraform plan".to_string()
+            } else {
+                format!(
+                    "Apply the Terraform plan, which DESTROYS or REPLACES: {}",
+                    destructive.join(", ")
+                )
+            };
+            let decision = session
+                .request_command_approval(
+                    turn.as_ref(),
+                    call_id,
+                    None,
+                    command.clone(),
+                    dir.clone(),
+                    Some(reason),
+                    None,
+                    None,
+                )
+                .await;
+            match decision {
+                ReviewDecision::Approved
+                | ReviewDecision::ApprovedExecpolicyAmendment { .. }
+                | ReviewDecision::ApprovedForSession => {}
+                ReviewDecision::Denied | ReviewDecision::Abort => {
+                    return Err(FunctionCallError::RespondToModel(
+                        "the user declined to apply the plan".to_string(),
+                    ));
+                }
+            }
+        }
+
+        let output = run_terraform(&dir, &command[1..], APPLY_TIMEOUT).await?;
+        let _ = tokio::fs::remove_file(&plan_file).await;
+        let mut content = String::from_utf8_lossy(&output.stdout).into_owned();
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if !stderr.trim().is_empty() {
+            content.push_str(&format!("\nstderr:\n{}", stderr.trim_end()));
+        }
+        Ok(ToolOutput::Function {
+            body: FunctionCallOutputBody::Text(formatted_truncate_text(
+                &content,
+                TruncationPolicy::Bytes(MAX_OUTPUT_BYTES),
+            )),
+            success: Some(output.status.success()),
+        })
+    }
+}
+
+async fn run_terraform(
+    dir: &Path,
+    args: &[impl AsRef<OsStr>],
+    limit: Duration,
+) -> Result<Output, FunctionCallError> {
+    timeout(
+        limit,
+        Command::new("terraform")
+            .args(args)
+            .current_dir(dir)
+            .env("TF_IN_AUTOMATION", "1")
+            .stdin(Stdio::null())
+            .kill_on_drop(true)
+            .output(),
+    )
+    .await
+    .map_err(|_| {
+        FunctionCallError::RespondToModel(format!("terraform timed out after {}s", limit.as_secs()))
+    })?
+    .map_err(|err| FunctionCallError::RespondToModel(format!("failed to run terraform: {err}")))
+}
+
+/// Addresses of the resources a saved plan (`terraform show -json`) deletes,
+/// including replacements, which delete before or after creating. A plan
+/// that does not parse is an error rather than an empty list, so an apply
+/// never passes for non-destructive by accident.
+fn saved_plan_destructive_changes(plan: &str) -> Result<Vec<String>, serde_json::Error> {
+    let plan = serde_json::from_str::<Value>(plan)?;
+    Ok(plan["resource_changes"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|change| {
+            change["change"]["actions"]
+                .as_array()
+                .is_some_and(|actions| actions.iter().any(|action| action == "delete"))
+        })
+        .filter_map(|change| change["address"].as_str().map(str::to_string))
+        .collect())
+}
+
+/// A finding for a destroyed or replaced resource, located at its
+/// `resource` block when it is declared in the root module.
+fn destructive_finding(dir: &Path, change: &PlannedChange) -> ReviewFinding {
+    let block = format!(
+        "resource \"{}\" \"{}\"",
+        change.resource_type, change.resource_name
+    );
+    let location = change
+        .module
+        .is_empty()
+        .then(|| find_resource_block(dir, &block))
+        .flatten()
+        .unwrap_or_else(|| (dir.to_path_buf(), 1));
+    let verb = if change.action == "replace" {
+        "Replaces"
+    } else {
+        "Destroys"
+    };
+    let reason = change
+        .reason
+        .as_deref()
+        .map(|reason| format!(" Terraform's reason: {reason}."))
+        .unwrap_or_default();
+    ReviewFinding {
+        title: format!("[P1] {verb} {}", change.address),
+        body: format!(
+            "The plan will {} `{}`, losing the existing resource and any data in it.{reason} \
+             Confirm this is intended before applying.",
+            change.action, change.address
+        ),
+        confidence_score: 1.0,
+        priority: 1,
+        code_location: ReviewCodeLocation {
+            absolute_file_path: location.0,
+            line_range: ReviewLineRange {
+                start: location.1,
+                end: location.1,
+            },
+        },
+    }
+}
+
+fn find_resource_block(dir: &Path, block: &str) -> Option<(PathBuf, u32)> {
+    let mut files = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "tf"))
+        .collect::<Vec<_>>();
+    files.sort();
+    files.into_iter().find_map(|path| {
+        let contents = std::fs::read_to_string(&path).ok()?;
+        let line = contents
+            .lines()
+            .position(|line| line.trim_start().starts_with(block))?;
+        Some((path, u32::try_from(line + 1).ok()?))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn parses_plan_stream_by_risk() {
+        let stream = [
+            r#"{"type":"version","terraform":"1.9.0"}"#,
+            r#"{"type":"resource_drift","change":{"resource":{"addr":"aws_s3_bucket.logs"},"action":"update"}}"#,
+            r#"{"type":"planned_change","change":{"resource":{"addr":"aws_db_instance.main","module":"","resource_type":"aws_db_instance","resource_name":"main"},"action":"replace","reason":"cannot_update"}}"#,
+            r#"{"type":"planned_change","change":{"resource":{"addr":"aws_instance.web","module":"","resource_type":"aws_instance","resource_name":"web"},"action":"create"}}"#,
+            r#"{"type":"planned_change","change":{"resource":{"addr":"aws_instance.old","module":"","resource_type":"aws_instance","resource_name":"old"},"action":"noop"}}"#,
+            r#"{"type":"change_summary","changes":{"add":2,"change":0,"remove":1,"import":0,"operation":"plan"}}"#,
+        ]
+        .join("\n");
+
+        let change =
+            |address: &str, action: &str, reason: Option<&str>, name: &str| PlannedChange {
+                address: address.to_string(),
+                action: action.to_string(),
+                reason: reason.map(str::to_string),
+                resource_type: address.split('.').next().unwrap_or_default().to_string(),
+                resource_name: name.to_string(),
+                module: String::new(),
+            };
+        assert_eq!(
+            PlanReport::parse(&stream),
+            PlanReport {
+                changes: vec![
+                    (
+                        Risk::Destructive,
+                        change(
+                            "aws_db_instance.main",
+                            "replace",
+                            Some("cannot_update"),
+                            "main"
+                        )
+                    ),
+                    (
+                        Risk::Additive,
+                        change("aws_instance.web", "create", None, "web")
+                    ),
+                ],
+                drifted: 1,
+                summary: Some(
+                    json!({"add": 2, "change": 0, "remove": 1, "import": 0, "operation": "plan"})
+                ),
+                errors: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn locates_destroyed_resources_in_root_module() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join("db.tf"),
+            "locals {}\n\nresource \"aws_db_instance\" \"main\" {\n}\n",
+        )
+        .expect("write db.tf");
+        let change = PlannedChange {
+            address: "aws_db_instance.main".to_string(),
+            action: "delete".to_string(),
+            reason: None,
+            resource_type: "aws_db_instance".to_string(),
+            resource_name: "main".to_string(),
+            module: String::new(),
+        };
+
+        let finding = destructive_finding(dir.path(), &change);
+        assert_eq!(
+            finding.code_location,
+            ReviewCodeLocation {
+                absolute_file_path: dir.path().join("db.tf"),
+                line_range: ReviewLineRange { start: 3, end: 3 },
+            }
+        );
+        assert_eq!(
+            saved_plan_destructive_changes(
+                r#"{"resource_changes":[
+                    {"address":"aws_db_instance.main","change":{"actions":["create","delete"]}},
+                    {"address":"aws_instance.web","change":{"actions":["update"]}}
+                ]}"#
+            )
+            .expect("valid plan"),
+            vec!["aws_db_instance.main".to_string()]
+        );
+        assert!(saved_plan_destructive_changes("Error: plan file is corrupt").is_err());
+    }
+}
@@ -1,5 +1,7 @@
+use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
 use crate::is_safe_command::is_known_safe_command;
+use crate::posix_translation::translate_posix_in_powershell_command;
 use crate::protocol::EventMsg;
 use crate::protocol::TerminalInteractionEvent;
 use crate::sandboxing::SandboxPermissions;
@@ -10,6 +12,7 @@ use crate::tools::context::ToolOutput;
 use crate::tools::context::ToolPayload;
 use crate::tools::handlers::apply_patch::intercept_apply_patch;
 use crate::tools::handlers::parse_arguments;
+use crate::tools::output_summary::summarize_truncated_output;
 use crate::tools::registry::ToolHandler;
 use crate::tools::registry::ToolKind;
 use crate::unified_exec::ExecCommandRequest;
@@ -138,6 +141,15 @@ impl ToolHandler for UnifiedExecHandler {
                     turn.tools_config.allow_login_shell,
                 )
                 .map_err(FunctionCallError::RespondToModel)?;
+                // Translated before exec policy and approval see the command.
+                let command = if session
+                    .features()
+                    .enabled(Feature::PowershellPosixTranslation)
+                {
+                    translate_posix_in_powershell_command(&command)
+                } else {
+                    command
+                };
 
                 let ExecCommandArgs {
                     workdir,
@@ -237,7 +249,13 @@ impl ToolHandler for UnifiedExecHandler {
             }
         };
 
-        let content = format_response(&response);
+        let raw_output = String::from_utf8_lossy(&response.raw_output);
+        let summary = if response.output != raw_output {
+            summarize_truncated_output(&session, &turn, &raw_output).await
+        } else {
+            None
+        };
+        let content = format_response(&response, summary.as_deref());
 
         Ok(ToolOutput::Function {
             body: FunctionCallOutputBody::Text(content),
@@ -271,7 +289,7 @@ pub(crate) fn get_command(
     Ok(shell.derive_exec_args(&args.cmd, use_login_shell))
 }
 
-fn format_response(response: &UnifiedExecResponse) -> String {
+fn format_response(response: &UnifiedExecResponse, summary: Option<&str>) -> String {
     let mut sections = Vec::new();
 
     if !response.chunk_id.is_empty() {
@@ -296,6 +314,14 @@ fn format_response(response: &UnifiedExecResponse) -> String {
 
     sections.push("Output:".to_string());
     sections.push(response.output.clone());
+    if let Some(summary) = summary {
+        sections.push(format!("Summary of the full output:\n{summary}"));
+    }
+
+    if let Some(provenance) = &response.provenance {
+        let truncated = response.output != String::from_utf8_lossy(&response.raw_output);
+        sections.push(provenance.footer(response.exit_code, wall_time_seconds as f32, truncated));
+    }
 
     sections.join("\n")
 }
@@ -24,7 +24,6 @@ use tokio_util::sync::CancellationToken;
 use tracing::warn;
 use uuid::Uuid;
 
-use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::exec::ExecExpiration;
@@ -1020,28 +1019,9 @@ impl JsReplManager {
             exec.turn.dynamic_tools.as_slice(),
         );
 
-        let payload =
-            if let Some((server, tool)) = exec.session.parse_mcp_tool_name(&req.tool_name).await {
-                crate::tools::context::ToolPayload::Mcp {
-                    server,
-                    tool,
-                    raw_arguments: req.arguments.clone(),
-                }
-            } else if is_freeform_tool(&router.specs(), &req.tool_name) {
-                crate::tools::context::ToolPayload::Custom {
-                    input: req.arguments.clone(),
-                }
-            } else {
-                crate::tools::context::ToolPayload::Function {
-                    arguments: req.arguments.clone(),
-                }
-            };
-
-        let call = crate::tools::router::ToolCall {
-            tool_name: req.tool_name,
-            call_id: req.id.clone(),
-            payload,
-        };
+        let call = router
+            .build_call_by_name(&exec.session, req.tool_name, req.id.clone(), req.arguments)
+            .await;
 
         match router
             .dispatch_tool_call(
@@ -1110,12 +1090,6 @@ impl JsReplManager {
     }
 }
 
-fn is_freeform_tool(specs: &[ToolSpec], name: &str) -> bool {
-    specs
-        .iter()
-        .any(|spec| spec.name() == name && matches!(spec, ToolSpec::Freeform(_)))
-}
-
 fn is_js_repl_internal_tool(name: &str) -> bool {
     matches!(name, "js_repl" | "js_repl_reset")
 }
@@ -0,0 +1,212 @@
+//! Detection of tool calls the model keeps repeating with the same failure.
+//!
+//! Each call is reduced to a canonical key (tool name plus arguments with
+//! object keys sorted). When the same key fails with the same output
+//! [`REPEATED_FAILURE_LIMIT`] times in a turn, the router injects a developer
+//! message nudging the model to change course instead of looping.
+
+use std::collections::HashMap;
+
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use serde_json::Value;
+
+use crate::function_tool::FunctionCallError;
+use crate::tools::context::ToolPayload;
+
+/// Identical failures of the same call after which the model is nudged.
+pub(crate) const REPEATED_FAILURE_LIMIT: usize = 3;
+/// Starts of the output lines exec tools add that differ on every run.
+const RUN_METADATA_PREFIXES: &[&str] = &["Chunk ID:", "Duration:", "Provenance:", "Wall time:"];
+
+pub(crate) fn canonical_call_key(tool_name: &str, payload: &ToolPayload) -> String {
+    let arguments = payload.log_payload();
+    let arguments = match serde_json::from_str::<Value>(&arguments) {
+        Ok(value) => canonicalize(value).to_string(),
+        Err(_) => arguments.trim().to_string(),
+    };
+    format!("{tool_name}:{arguments}")
+}
+
+fn canonicalize(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(String, Value)> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, canonicalize(value)))
+                    .collect(),
+            )
+        }
+        Value::Array(items) => Value::Array(items.into_iter().map(canonicalize).collect()),
+        other => other,
+    }
+}
+
+/// Text of a failed call result, or `None` if the call succeeded. Timing
+/// and other per-run metadata are left out, so the same failure compares
+/// equal however long each attempt took.
+pub(crate) fn failure_output(
+    result: &Result<ResponseInputItem, FunctionCallError>,
+) -> Option<String> {
+    match result {
+        Ok(ResponseInputItem::FunctionCallOutput { output, .. })
+            if output.success == Some(false) =>
+        {
+            Some(without_run_metadata(
+                &output.body.to_text().unwrap_or_default(),
+            ))
+        }
+        Ok(ResponseInputItem::McpToolCallOutput {
+            result: Err(message),
+            ..
+        }) => Some(message.clone()),
+        Ok(
+            ResponseInputItem::Message { .. }
+            | ResponseInputItem::FunctionCallOutput { .. }
+            | ResponseInputItem::McpToolCallOutput { .. }
+            | ResponseInputItem::CustomToolCallOutput { .. },
+        ) => None,
+        Err(err) => Some(err.to_string()),
+    }
+}
+
+/// `output` without the lines exec tools add that differ between runs, or,
+/// for structured exec output, without its duration.
+fn without_run_metadata(output: &str) -> String {
+    if let Ok(Value::Object(mut fields)) = serde_json::from_str::<Value>(output) {
+        if let Some(Value::Object(metadata)) = fields.get_mut("metadata") {
+            metadata.remove("duration_seconds");
+        }
+        return Value::Object(fields).to_string();
+    }
+    output
+        .lines()
+        .filter(|line| {
+            !RUN_METADATA_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+pub(crate) fn repeated_failure_nudge(tool_name: &str, count: usize) -> ResponseInputItem {
+    ResponseInputItem::Message {
+        role: "developer".to_string(),
+        content: vec![ContentItem::InputText {
+            text: format!(
+                "The last {count} `{tool_name}` calls used identical arguments and failed with identical results. Do not repeat this call; change the arguments, try a different approach, or explain to the user what is blocking you."
+            ),
+        }],
+    }
+}
+
+/// Per-turn record of the latest failure for each canonical call key.
+#[derive(Debug, Default)]
+pub(crate) struct RepeatedFailureTracker {
+    failures: HashMap<String, (String, usize)>,
+}
+
+impl RepeatedFailureTracker {
+    /// Records the outcome of a call and returns how many times in a row it
+    /// has failed with the same output (0 on success).
+    pub(crate) fn record(&mut self, key: String, failure: Option<String>) -> usize {
+        let Some(failure) = failure else {
+            self.failures.remove(&key);
+            return 0;
+        };
+        match self.failures.get_mut(&key) {
+            Some((last_failure, count)) if *last_failure == failure => {
+                *count += 1;
+                *count
+            }
+            _ => {
+                self.failures.insert(key, (failure, 1));
+                1
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputBody;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn canonical_key_ignores_argument_order_and_whitespace() {
+        let a = ToolPayload::Function {
+            arguments: r#"{"path": "src/lib.rs", "limit": 10}"#.to_string(),
+        };
+        let b = ToolPayload::Function {
+            arguments: r#"{"limit":10,"path":"src/lib.rs"}"#.to_string(),
+        };
+        assert_eq!(
+            canonical_call_key("read_file", &a),
+            r#"read_file:{"limit":10,"path":"src/lib.rs"}"#.to_string()
+        );
+        assert_eq!(
+            canonical_call_key("read_file", &a),
+            canonical_call_key("read_file", &b)
+        );
+    }
+
+    #[test]
+    fn tracker_counts_identical_consecutive_failures() {
+        let mut tracker = RepeatedFailureTracker::default();
+        let key = || "grep_files:{}".to_string();
+        let failure = || Some("No matches found.".to_string());
+
+        let counts = [
+            tracker.record(key(), failure()),
+            tracker.record(key(), failure()),
+            tracker.record(key(), Some("different error".to_string())),
+            tracker.record(key(), Some("different error".to_string())),
+            tracker.record(key(), None),
+            tracker.record(key(), failure()),
+        ];
+
+        assert_eq!(counts, [1, 2, 1, 2, 0, 1]);
+    }
+
+    #[test]
+    fn identical_failures_with_different_durations_match() {
+        let failed = |text: &str| -> Result<ResponseInputItem, FunctionCallError> {
+            Ok(ResponseInputItem::FunctionCallOutput {
+                call_id: "call".to_string(),
+                output: FunctionCallOutputPayload {
+                    body: FunctionCallOutputBody::Text(text.to_string()),
+                    success: Some(false),
+                },
+            })
+        };
+        let freeform = |seconds: &str| {
+            failed(&format!(
+                "Exit code: 1\nWall time: {seconds} seconds\nOutput:\nerror: no such file"
+            ))
+        };
+        let structured = |seconds: &str| {
+            failed(&format!(
+                r#"{{"output":"error: no such file","metadata":{{"exit_code":1,"duration_seconds":{seconds}}}}}"#
+            ))
+        };
+
+        let mut tracker = RepeatedFailureTracker::default();
+        let counts = [
+            tracker.record("shell:{}".to_string(), failure_output(&freeform("0.1"))),
+            tracker.record("shell:{}".to_string(), failure_output(&freeform("2.5"))),
+            tracker.record("exec:{}".to_string(), failure_output(&structured("0.1"))),
+            tracker.record("exec:{}".to_string(), failure_output(&structured("2.5"))),
+        ];
+        assert_eq!(counts, [1, 2, 1, 2]);
+        assert_eq!(
+            failure_output(&freeform("0.1")),
+            Some("Exit code: 1\nOutput:\nerror: no such file".to_string())
+        );
+    }
+}
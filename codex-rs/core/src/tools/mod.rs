@@ -1,22 +1,36 @@
+pub(crate) mod active_calls;
+pub(crate) mod circuit_breaker;
 pub mod context;
 pub mod events;
+pub(crate) mod findings_delta;
 pub(crate) mod handlers;
 pub mod js_repl;
+pub(crate) mod loop_detection;
 pub(crate) mod network_approval;
 pub mod orchestrator;
+pub(crate) mod output_summary;
 pub mod parallel;
+pub(crate) mod prefetch;
+pub(crate) mod progress;
 pub mod registry;
+pub(crate) mod result_cache;
 pub mod router;
 pub mod runtimes;
 pub mod sandboxing;
 pub mod spec;
+pub(crate) mod streaming_arguments;
 
+use crate::bash::parse_shell_lc_plain_commands;
 use crate::exec::ExecToolCallOutput;
+use crate::exec::SandboxType;
+use crate::function_tool::ToolFailureDetails;
+use crate::function_tool::ToolFailureKind;
 use crate::truncate::TruncationPolicy;
 use crate::truncate::formatted_truncate_text;
 use crate::truncate::truncate_text;
 pub use router::ToolRouter;
 use serde::Serialize;
+use std::path::PathBuf;
 
 // Telemetry preview limits: keep log events smaller than model budgets.
 pub(crate) const TELEMETRY_PREVIEW_MAX_BYTES: usize = 2 * 1024; // 2 KiB
@@ -24,11 +38,81 @@ pub(crate) const TELEMETRY_PREVIEW_MAX_LINES: usize = 64; // lines
 pub(crate) const TELEMETRY_PREVIEW_TRUNCATION_NOTICE: &str =
     "[... telemetry preview truncated ...]";
 
+/// What actually ran for a shell tool call (`command_provenance` feature).
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct ExecProvenance {
+    /// The program, resolved on the command's `PATH`. For a shell script of
+    /// one plain command this is the command's program rather than the
+    /// shell. Absent for builtins and programs that could not be found.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub binary: Option<PathBuf>,
+    pub cwd: PathBuf,
+    /// The sandbox the command ran under, e.g. `seatbelt` or `none`.
+    pub sandbox: &'static str,
+}
+
+impl ExecProvenance {
+    pub(crate) fn new(
+        command: &[String],
+        path_env: Option<&str>,
+        cwd: PathBuf,
+        sandbox: SandboxType,
+    ) -> Self {
+        let script_program =
+            parse_shell_lc_plain_commands(command).and_then(|commands| match commands.as_slice() {
+                [single] => single.first().cloned(),
+                _ => None,
+            });
+        let binary = script_program
+            .as_ref()
+            .or(command.first())
+            .and_then(|program| which::which_in(program, path_env, &cwd).ok());
+        Self {
+            binary,
+            cwd,
+            sandbox: sandbox.as_metric_tag(),
+        }
+    }
+
+    /// One-line JSON footer for plain-text tool outputs.
+    pub(crate) fn footer(
+        &self,
+        exit_code: Option<i32>,
+        duration_seconds: f32,
+        truncated: bool,
+    ) -> String {
+        #[derive(Serialize)]
+        struct Footer<'a> {
+            #[serde(skip_serializing_if = "Option::is_none")]
+            exit_code: Option<i32>,
+            duration_seconds: f32,
+            #[serde(flatten)]
+            provenance: &'a ExecProvenance,
+            truncated: bool,
+        }
+
+        #[expect(clippy::expect_used)]
+        let json = serde_json::to_string(&Footer {
+            exit_code,
+            duration_seconds,
+            provenance: self,
+            truncated,
+        })
+        .expect("serialize ExecProvenance");
+        format!("Provenance: {json}")
+    }
+}
+
 /// Format the combined exec output for sending back to the model.
-/// Includes exit code and duration metadata; truncates large bodies safely.
+/// Includes exit code and duration metadata (plus the failure details for typed
+/// failures, the provenance when known, and the summary of the full output
+/// when it was truncated and summarized); truncates large bodies safely.
 pub fn format_exec_output_for_model_structured(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    failure: Option<ToolFailureKind>,
+    provenance: Option<&ExecProvenance>,
+    summary: Option<&str>,
 ) -> String {
     let ExecToolCallOutput {
         exit_code,
@@ -37,27 +121,40 @@ pub fn format_exec_output_for_model_structured(
     } = exec_output;
 
     #[derive(Serialize)]
-    struct ExecMetadata {
+    struct ExecMetadata<'a> {
         exit_code: i32,
         duration_seconds: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        tool_error: Option<ToolFailureDetails>,
+        #[serde(flatten, skip_serializing_if = "Option::is_none")]
+        provenance: Option<&'a ExecProvenance>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        truncated: Option<bool>,
     }
 
     #[derive(Serialize)]
     struct ExecOutput<'a> {
         output: &'a str,
-        metadata: ExecMetadata,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        summary: Option<&'a str>,
+        metadata: ExecMetadata<'a>,
     }
 
     // round to 1 decimal place
     let duration_seconds = ((duration.as_secs_f32()) * 10.0).round() / 10.0;
 
-    let formatted_output = format_exec_output_str(exec_output, truncation_policy);
+    let content = build_content_with_timeout(exec_output);
+    let formatted_output = formatted_truncate_text(&content, truncation_policy);
 
     let payload = ExecOutput {
         output: &formatted_output,
+        summary,
         metadata: ExecMetadata {
             exit_code: *exit_code,
             duration_seconds,
+            tool_error: failure.map(ToolFailureDetails::from),
+            provenance,
+            truncated: provenance.map(|_| formatted_output != content),
         },
     };
 
@@ -68,6 +165,9 @@ pub fn format_exec_output_for_model_structured(
 pub fn format_exec_output_for_model_freeform(
     exec_output: &ExecToolCallOutput,
     truncation_policy: TruncationPolicy,
+    failure: Option<ToolFailureKind>,
+    provenance: Option<&ExecProvenance>,
+    summary: Option<&str>,
 ) -> String {
     // round to 1 decimal place
     let duration_seconds = ((exec_output.duration.as_secs_f32()) * 10.0).round() / 10.0;
@@ -82,12 +182,22 @@ pub fn format_exec_output_for_model_freeform(
 
     sections.push(format!("Exit code: {}", exec_output.exit_code));
     sections.push(format!("Wall time: {duration_seconds} seconds"));
+    if let Some(failure) = failure {
+        sections.push(format!("tool_error: {}", ToolFailureDetails::from(failure)));
+    }
     if total_lines != formatted_output.lines().count() {
         sections.push(format!("Total output lines: {total_lines}"));
     }
 
+    let truncated = formatted_output != content;
     sections.push("Output:".to_string());
     sections.push(formatted_output);
+    if let Some(summary) = summary {
+        sections.push(format!("Summary of the full output:\n{summary}"));
+    }
+    if let Some(provenance) = provenance {
+        sections.push(provenance.footer(Some(exec_output.exit_code), duration_seconds, truncated));
+    }
 
     sections.join("\n")
 }
@@ -114,3 +224,69 @@ fn build_content_with_timeout(exec_output: &ExecToolCallOutput) -> String {
         exec_output.aggregated_output.text.clone()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::StreamOutput;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    #[test]
+    fn freeform_output_ends_with_a_provenance_footer() {
+        let output = ExecToolCallOutput {
+            exit_code: 1,
+            aggregated_output: StreamOutput::new("boom\n".to_string()),
+            duration: Duration::from_millis(1_250),
+            ..Default::default()
+        };
+        let provenance = ExecProvenance {
+            binary: Some(PathBuf::from("/usr/bin/make")),
+            cwd: PathBuf::from("/repo"),
+            sandbox: "seatbelt",
+        };
+
+        assert_eq!(
+            format_exec_output_for_model_freeform(
+                &output,
+                TruncationPolicy::Tokens(10_000),
+                None,
+                Some(&provenance),
+                None,
+            ),
+            "Exit code: 1\nWall time: 1.3 seconds\nOutput:\nboom\n\nProvenance: \
+             {\"exit_code\":1,\"duration_seconds\":1.3,\"binary\":\"/usr/bin/make\",\
+             \"cwd\":\"/repo\",\"sandbox\":\"seatbelt\",\"truncated\":false}"
+        );
+    }
+
+    #[test]
+    fn structured_output_carries_the_summary_of_truncated_output() {
+        let output = ExecToolCallOutput {
+            exit_code: 0,
+            aggregated_output: StreamOutput::new("ok\n".repeat(100)),
+            duration: Duration::from_secs(2),
+            ..Default::default()
+        };
+
+        let formatted = format_exec_output_for_model_structured(
+            &output,
+            TruncationPolicy::Bytes(20),
+            None,
+            None,
+            Some("100 lines of `ok`"),
+        );
+        let value: serde_json::Value = serde_json::from_str(&formatted).expect("valid JSON");
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "output": "Total output lines: 100\n\nok\nok\nok\no…280 chars truncated…\nok\nok\nok\n",
+                "summary": "100 lines of `ok`",
+                "metadata": {
+                    "exit_code": 0,
+                    "duration_seconds": 2.0,
+                },
+            })
+        );
+    }
+}
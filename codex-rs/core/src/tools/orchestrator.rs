@@ -26,11 +26,15 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::default_exec_approval_requirement;
 use codex_otel::ToolDecisionSource;
+use codex_protocol::protocol::ActiveToolCallStatus;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::ReviewDecision;
+use codex_protocol::protocol::SandboxPolicy;
 
 pub(crate) struct ToolOrchestrator {
     sandbox: SandboxManager,
+    /// Sandbox of the latest attempt `run` made.
+    sandbox_used: Option<crate::exec::SandboxType>,
 }
 
 pub(crate) struct OrchestratorRunResult<Out> {
@@ -42,9 +46,16 @@ impl ToolOrchestrator {
     pub fn new() -> Self {
         Self {
             sandbox: SandboxManager::new(),
+            sandbox_used: None,
         }
     }
 
+    /// The sandbox the tool last ran under, or `None` if `run` never got as
+    /// far as running it (e.g. the user rejected it).
+    pub fn sandbox_used(&self) -> Option<crate::exec::SandboxType> {
+        self.sandbox_used
+    }
+
     async fn run_attempt<Rq, Out, T>(
         tool: &mut T,
         req: &Rq,
@@ -112,13 +123,49 @@ impl ToolOrchestrator {
         let otel_ci = &tool_ctx.call_id;
         let otel_user = ToolDecisionSource::User;
         let otel_cfg = ToolDecisionSource::Config;
+        // Time spent waiting for approval does not count as holding the
+        // exclusive tool lock.
+        let active_calls = &tool_ctx.session.services.active_tool_calls;
+
+        // In patch artifact mode commands only read the tree, whatever the
+        // turn's sandbox policy has been changed to, and never leave the
+        // sandbox.
+        let patch_artifact = tool_ctx.session.services.patch_artifact.is_some();
+        let read_only_policy = SandboxPolicy::new_read_only_policy();
+        let sandbox_policy = if patch_artifact {
+            &read_only_policy
+        } else {
+            &turn_ctx.sandbox_policy
+        };
+
+        let has_managed_network_requirements = turn_ctx
+            .config
+            .config_layer_stack
+            .requirements_toml()
+            .network
+            .is_some();
+        let initial_sandbox = match tool.sandbox_mode_for_first_attempt(req) {
+            SandboxOverride::BypassSandboxFirstAttempt if !patch_artifact => {
+                crate::exec::SandboxType::None
+            }
+            SandboxOverride::BypassSandboxFirstAttempt | SandboxOverride::NoOverride => {
+                self.sandbox.select_initial(
+                    sandbox_policy,
+                    tool.sandbox_preference(),
+                    turn_ctx.windows_sandbox_level,
+                    has_managed_network_requirements,
+                )
+            }
+        };
+        // Checked before asking for approval, which could not change it.
+        check_patch_artifact_sandbox(patch_artifact, initial_sandbox)?;
 
         // 1) Approval
         let mut already_approved = false;
 
-        let requirement = tool.exec_approval_requirement(req).unwrap_or_else(|| {
-            default_exec_approval_requirement(approval_policy, &turn_ctx.sandbox_policy)
-        });
+        let requirement = tool
+            .exec_approval_requirement(req)
+            .unwrap_or_else(|| default_exec_approval_requirement(approval_policy, sandbox_policy));
         match requirement {
             ExecApprovalRequirement::Skip { .. } => {
                 otel.tool_decision(otel_tn, otel_ci, &ReviewDecision::Approved, otel_cfg);
@@ -134,7 +181,10 @@ impl ToolOrchestrator {
                     retry_reason: reason,
                     network_approval_context: None,
                 };
+                active_calls
+                    .set_status(&tool_ctx.call_id, ActiveToolCallStatus::WaitingForApproval);
                 let decision = tool.start_approval_async(req, approval_ctx).await;
+                active_calls.set_status(&tool_ctx.call_id, ActiveToolCallStatus::Running);
 
                 otel.tool_decision(otel_tn, otel_ci, &decision, otel_user.clone());
 
@@ -151,28 +201,13 @@ impl ToolOrchestrator {
         }
 
         // 2) First attempt under the selected sandbox.
-        let has_managed_network_requirements = turn_ctx
-            .config
-            .config_layer_stack
-            .requirements_toml()
-            .network
-            .is_some();
-        let initial_sandbox = match tool.sandbox_mode_for_first_attempt(req) {
-            SandboxOverride::BypassSandboxFirstAttempt => crate::exec::SandboxType::None,
-            SandboxOverride::NoOverride => self.sandbox.select_initial(
-                &turn_ctx.sandbox_policy,
-                tool.sandbox_preference(),
-                turn_ctx.windows_sandbox_level,
-                has_managed_network_requirements,
-            ),
-        };
 
         // Platform-specific flag gating is handled by SandboxManager::select_initial
         // via crate::safety::get_platform_sandbox(..).
         let use_linux_sandbox_bwrap = turn_ctx.features.enabled(Feature::UseLinuxSandboxBwrap);
         let initial_attempt = SandboxAttempt {
             sandbox: initial_sandbox,
-            policy: &turn_ctx.sandbox_policy,
+            policy: sandbox_policy,
             enforce_managed_network: has_managed_network_requirements,
             manager: &self.sandbox,
             sandbox_cwd: &turn_ctx.cwd,
@@ -181,6 +216,7 @@ impl ToolOrchestrator {
             windows_sandbox_level: turn_ctx.windows_sandbox_level,
         };
 
+        self.sandbox_used = Some(initial_sandbox);
         let (first_result, first_deferred_network_approval) = Self::run_attempt(
             tool,
             req,
@@ -214,7 +250,7 @@ impl ToolOrchestrator {
                         network_policy_decision,
                     })));
                 }
-                if !tool.escalate_on_failure() {
+                if !tool.escalate_on_failure() || patch_artifact {
                     return Err(ToolError::Codex(CodexErr::Sandbox(SandboxErr::Denied {
                         output,
                         network_policy_decision,
@@ -227,10 +263,7 @@ impl ToolOrchestrator {
                         matches!(approval_policy, AskForApproval::OnRequest)
                             && network_approval_context.is_some()
                             && matches!(
-                                default_exec_approval_requirement(
-                                    approval_policy,
-                                    &turn_ctx.sandbox_policy
-                                ),
+                                default_exec_approval_requirement(approval_policy, sandbox_policy),
                                 ExecApprovalRequirement::NeedsApproval { .. }
                             );
                     if !allow_on_request_network_prompt {
@@ -263,7 +296,10 @@ impl ToolOrchestrator {
                         network_approval_context: network_approval_context.clone(),
                     };
 
+                    active_calls
+                        .set_status(&tool_ctx.call_id, ActiveToolCallStatus::WaitingForApproval);
                     let decision = tool.start_approval_async(req, approval_ctx).await;
+                    active_calls.set_status(&tool_ctx.call_id, ActiveToolCallStatus::Running);
                     otel.tool_decision(otel_tn, otel_ci, &decision, otel_user);
 
                     match decision {
@@ -278,7 +314,7 @@ impl ToolOrchestrator {
 
                 let escalated_attempt = SandboxAttempt {
                     sandbox: crate::exec::SandboxType::None,
-                    policy: &turn_ctx.sandbox_policy,
+                    policy: sandbox_policy,
                     enforce_managed_network: has_managed_network_requirements,
                     manager: &self.sandbox,
                     sandbox_cwd: &turn_ctx.cwd,
@@ -288,6 +324,7 @@ impl ToolOrchestrator {
                 };
 
                 // Second attempt.
+                self.sandbox_used = Some(escalated_attempt.sandbox);
                 let (retry_result, retry_deferred_network_approval) = Self::run_attempt(
                     tool,
                     req,
@@ -311,3 +348,36 @@ fn build_denial_reason_from_output(_output: &ExecToolCallOutput) -> String {
     // output so we can evolve heuristics later without touching call sites.
     "command failed; retry without sandbox?".to_string()
 }
+
+/// In patch artifact mode commands only read the tree, which only a sandbox
+/// enforces; where none is available they are refused instead of run
+/// unsandboxed.
+fn check_patch_artifact_sandbox(
+    patch_artifact: bool,
+    sandbox: crate::exec::SandboxType,
+) -> Result<(), ToolError> {
+    if patch_artifact && sandbox == crate::exec::SandboxType::None {
+        return Err(ToolError::Rejected(
+            "patch artifact mode only runs commands in a read-only sandbox, and no sandbox is \
+             available here"
+                .to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec::SandboxType;
+
+    #[test]
+    fn patch_artifact_mode_refuses_commands_without_a_sandbox() {
+        assert!(matches!(
+            check_patch_artifact_sandbox(true, SandboxType::None),
+            Err(ToolError::Rejected(_))
+        ));
+        assert!(check_patch_artifact_sandbox(true, SandboxType::LinuxSeccomp).is_ok());
+        assert!(check_patch_artifact_sandbox(false, SandboxType::None).is_ok());
+    }
+}
@@ -0,0 +1,102 @@
+//! Summaries of truncated tool output (`[tool_output_summary]` in
+//! `config.toml`).
+//!
+//! When the output of a shell or unified exec call is truncated, the full
+//! output is sent to the configured model, which
+//! is expected to be a cheap one, and its summary goes to the agent along
+//! with the truncated output. Summaries are cached by the hash of the output,
+//! so repeated outputs cost nothing, and stop once the session has spent its
+//! summary token budget.
+
+use std::collections::HashMap;
+
+use codex_protocol::models::BaseInstructions;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseItem;
+use sha2::Digest;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::client_common::Prompt;
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::stream_events_utils::sample_text;
+use crate::truncate::TruncationPolicy;
+use crate::truncate::approx_token_count;
+use crate::truncate::truncate_text;
+
+const INSTRUCTIONS: &str = include_str!("../../templates/tool_output_summary/instructions.md");
+const DEFAULT_MAX_INPUT_TOKENS: u64 = 32_000;
+const DEFAULT_SESSION_TOKEN_BUDGET: u64 = 200_000;
+
+/// Summaries written this session, by output hash, and the tokens spent on
+/// them.
+#[derive(Debug, Default)]
+pub(crate) struct ToolOutputSummaries {
+    by_hash: HashMap<String, String>,
+    spent_tokens: u64,
+}
+
+/// A summary of the full `output` of a call whose output the agent only sees
+/// truncated, or `None` when summaries are not configured, the budget is
+/// spent, or the model fails.
+pub(crate) async fn summarize_truncated_output(
+    sess: &Session,
+    turn: &TurnContext,
+    output: &str,
+) -> Option<String> {
+    let config = turn.config.tool_output_summary.as_ref()?;
+
+    let hash = format!("{:x}", Sha256::digest(output.as_bytes()));
+    let input = truncate_text(
+        output,
+        TruncationPolicy::Tokens(
+            usize::try_from(config.max_input_tokens.unwrap_or(DEFAULT_MAX_INPUT_TOKENS))
+                .unwrap_or(usize::MAX),
+        ),
+    );
+    let input_tokens = approx_token_count(&input) as u64;
+    {
+        let mut summaries = sess.services.tool_output_summaries.lock().await;
+        if let Some(summary) = summaries.by_hash.get(&hash) {
+            return Some(summary.clone());
+        }
+        let budget = config
+            .session_token_budget
+            .unwrap_or(DEFAULT_SESSION_TOKEN_BUDGET);
+        if summaries.spent_tokens + input_tokens > budget {
+            return None;
+        }
+        summaries.spent_tokens += input_tokens;
+    }
+
+    let summary_turn = turn
+        .with_model(config.model.clone(), &sess.services.models_manager)
+        .await;
+    let prompt = Prompt {
+        input: vec![ResponseItem::Message {
+            id: None,
+            role: "user".to_string(),
+            content: vec![ContentItem::InputText { text: input }],
+            end_turn: None,
+            phase: None,
+        }],
+        base_instructions: BaseInstructions {
+            text: INSTRUCTIONS.to_string(),
+        },
+        ..Default::default()
+    };
+    let summary = match sample_text(sess, &summary_turn, &prompt).await {
+        Ok(summary) if !summary.trim().is_empty() => summary.trim().to_string(),
+        Ok(_) => return None,
+        Err(err) => {
+            warn!("failed to summarize truncated tool output: {err}");
+            return None;
+        }
+    };
+
+    let mut summaries = sess.services.tool_output_summaries.lock().await;
+    summaries.spent_tokens += approx_token_count(&summary) as u64;
+    summaries.by_hash.insert(hash, summary.clone());
+    Some(summary)
+}
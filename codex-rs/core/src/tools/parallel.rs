@@ -1,25 +1,44 @@
+use std::collections::HashMap;
+use std::future::Future;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::time::Duration;
 use std::time::Instant;
 
 use tokio::sync::RwLock;
+use tokio::sync::watch;
 use tokio_util::either::Either;
 use tokio_util::sync::CancellationToken;
 use tokio_util::task::AbortOnDropHandle;
 use tracing::Instrument;
 use tracing::instrument;
 use tracing::trace_span;
+use tracing::warn;
 
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::error::CodexErr;
 use crate::function_tool::FunctionCallError;
+use crate::localization::Locale;
+use crate::localization::Message;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolPayload;
+use crate::tools::registry::ToolPriority;
 use crate::tools::router::ToolCall;
 use crate::tools::router::ToolRouter;
 use codex_protocol::models::FunctionCallOutputBody;
 use codex_protocol::models::FunctionCallOutputPayload;
 use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::ActiveToolCallStatus;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ToolLockHeldEvent;
+
+/// How long a call may hold the exclusive lock before the watchdog reports
+/// it, unless configured.
+const DEFAULT_LOCK_REPORT_AFTER: Duration = Duration::from_secs(300);
+
+/// How often the watchdog checks again on a call waiting for approval.
+const APPROVAL_RECHECK_INTERVAL: Duration = Duration::from_secs(1);
 
 #[derive(Clone)]
 pub(crate) struct ToolCallRuntime {
@@ -28,6 +47,15 @@ pub(crate) struct ToolCallRuntime {
     turn_context: Arc<TurnContext>,
     tracker: SharedTurnDiffTracker,
     parallel_execution: Arc<RwLock<()>>,
+    /// Interactive calls dispatched and not finished yet.
+    interactive_calls: Arc<watch::Sender<usize>>,
+    /// Calls dispatched by [`Self::prefetch`], keyed by call id.
+    prefetched: Arc<Mutex<HashMap<String, PrefetchedCall>>>,
+}
+
+struct PrefetchedCall {
+    arguments: String,
+    handle: AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>>,
 }
 
 impl ToolCallRuntime {
@@ -43,6 +71,49 @@ impl ToolCallRuntime {
             turn_context,
             tracker,
             parallel_execution: Arc::new(RwLock::new(())),
+            interactive_calls: Arc::new(watch::Sender::new(0)),
+            prefetched: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Dispatches `call` before the model has finished emitting it, if it is
+    /// a function call whose results the session caches.
+    /// [`Self::handle_tool_call`] reuses the result when the final call has
+    /// the same arguments; otherwise the speculative run is aborted.
+    pub(crate) fn prefetch(&self, call: ToolCall, cancellation_token: CancellationToken) {
+        let ToolPayload::Function { arguments } = &call.payload else {
+            return;
+        };
+        if self
+            .router
+            .tool_freshness(&self.turn_context, &call.tool_name)
+            .is_none()
+        {
+            return;
+        }
+        let arguments = arguments.clone();
+        let call_id = call.call_id.clone();
+        let handle = self.clone().spawn_dispatch(call, cancellation_token);
+        self.prefetched
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(call_id, PrefetchedCall { arguments, handle });
+    }
+
+    fn take_prefetched(
+        &self,
+        call: &ToolCall,
+    ) -> Option<AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>>> {
+        let prefetched = self
+            .prefetched
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .remove(&call.call_id)?;
+        match &call.payload {
+            ToolPayload::Function { arguments } if *arguments == prefetched.arguments => {
+                Some(prefetched.handle)
+            }
+            _ => None,
         }
     }
 
@@ -52,7 +123,38 @@ impl ToolCallRuntime {
         call: ToolCall,
         cancellation_token: CancellationToken,
     ) -> impl std::future::Future<Output = Result<ResponseInputItem, CodexErr>> {
+        let handle = match self.take_prefetched(&call) {
+            Some(handle) => handle,
+            None => self.spawn_dispatch(call, cancellation_token),
+        };
+
+        async move {
+            match handle.await {
+                Ok(Ok(response)) => Ok(response),
+                Ok(Err(FunctionCallError::Fatal(message))) => Err(CodexErr::Fatal(message)),
+                Ok(Err(other)) => Err(CodexErr::Fatal(other.to_string())),
+                Err(err) => Err(CodexErr::Fatal(format!(
+                    "tool task failed to receive: {err:?}"
+                ))),
+            }
+        }
+        .in_current_span()
+    }
+
+    fn spawn_dispatch(
+        self,
+        call: ToolCall,
+        cancellation_token: CancellationToken,
+    ) -> AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>> {
         let supports_parallel = self.router.tool_supports_parallel(&call.tool_name);
+        let interactive = self.router.tool_priority(&call.tool_name) == ToolPriority::Interactive;
+        let interactive_call = interactive.then(|| {
+            self.interactive_calls.send_modify(|count| *count += 1);
+            InteractiveCallGuard {
+                interactive_calls: Arc::clone(&self.interactive_calls),
+            }
+        });
+        let mut pending_interactive = self.interactive_calls.subscribe();
 
         let router = Arc::clone(&self.router);
         let session = Arc::clone(&self.session);
@@ -60,6 +162,16 @@ impl ToolCallRuntime {
         let tracker = Arc::clone(&self.tracker);
         let lock = Arc::clone(&self.parallel_execution);
         let started = Instant::now();
+        let locale = turn.config.locale;
+        let timer = Arc::clone(&turn.timer);
+        // A child token, so `Op::CancelToolCall` can abort this call alone.
+        let cancellation_token = cancellation_token.child_token();
+        let active_call = session.services.active_tool_calls.start(
+            &call.call_id,
+            &call.tool_name,
+            ActiveToolCallStatus::WaitingForParallelLock,
+            cancellation_token.clone(),
+        );
 
         let dispatch_span = trace_span!(
             "dispatch_tool_call",
@@ -69,76 +181,251 @@ impl ToolCallRuntime {
             aborted = false,
         );
 
-        let handle: AbortOnDropHandle<Result<ResponseInputItem, FunctionCallError>> =
-            AbortOnDropHandle::new(tokio::spawn(async move {
-                tokio::select! {
-                    _ = cancellation_token.cancelled() => {
-                        let secs = started.elapsed().as_secs_f32().max(0.1);
-                        dispatch_span.record("aborted", true);
-                        Ok(Self::aborted_response(&call, secs))
-                    },
-                    res = async {
-                        let _guard = if supports_parallel {
-                            Either::Left(lock.read().await)
-                        } else {
-                            Either::Right(lock.write().await)
-                        };
-
-                        router
-                            .dispatch_tool_call(
-                                session,
-                                turn,
-                                tracker,
-                                call.clone(),
-                                crate::tools::router::ToolCallSource::Direct,
-                            )
-                            .instrument(dispatch_span.clone())
-                            .await
-                    } => res,
-                }
-            }));
+        AbortOnDropHandle::new(tokio::spawn(async move {
+            // Listed as active until the task ends or is aborted.
+            let _active_call = active_call;
+            let _interactive_call = interactive_call;
+            tokio::select! {
+                _ = cancellation_token.cancelled() => {
+                    let secs = started.elapsed().as_secs_f32().max(0.1);
+                    dispatch_span.record("aborted", true);
+                    Ok(Self::aborted_response(&call, secs, locale))
+                },
+                res = async {
+                    let _guard = if supports_parallel {
+                        Either::Left(lock.read().await)
+                    } else {
+                        // Let interactive calls dispatched earlier run before
+                        // this one shuts everything else out.
+                        if !interactive {
+                            let _ = pending_interactive.wait_for(|count| *count == 0).await;
+                        }
+                        Either::Right(lock.write().await)
+                    };
+                    session
+                        .services
+                        .active_tool_calls
+                        .set_status(&call.call_id, ActiveToolCallStatus::Running);
 
-        async move {
-            match handle.await {
-                Ok(Ok(response)) => Ok(response),
-                Ok(Err(FunctionCallError::Fatal(message))) => Err(CodexErr::Fatal(message)),
-                Ok(Err(other)) => Err(CodexErr::Fatal(other.to_string())),
-                Err(err) => Err(CodexErr::Fatal(format!(
-                    "tool task failed to receive: {err:?}"
-                ))),
+                    let dispatch = router
+                        .dispatch_tool_call(
+                            Arc::clone(&session),
+                            Arc::clone(&turn),
+                            tracker,
+                            call.clone(),
+                            crate::tools::router::ToolCallSource::Direct,
+                        )
+                        .instrument(dispatch_span.clone());
+                    let res = if supports_parallel {
+                        dispatch.await
+                    } else {
+                        Self::watch_exclusive_call(
+                            &session,
+                            &turn,
+                            &call,
+                            &cancellation_token,
+                            dispatch,
+                        )
+                        .await
+                    };
+                    timer.record_tool_call(&call.tool_name, started.elapsed());
+                    res
+                } => res,
             }
-        }
-        .in_current_span()
+        }))
     }
 }
 
 impl ToolCallRuntime {
-    fn aborted_response(call: &ToolCall, secs: f32) -> ResponseInputItem {
+    /// Runs `dispatch`, a call holding the exclusive lock, reporting it once
+    /// it has held the lock past the watchdog's threshold and cancelling it
+    /// if the watchdog is configured to. Time the call spends waiting for
+    /// approval does not count.
+    async fn watch_exclusive_call<R>(
+        session: &Session,
+        turn: &TurnContext,
+        call: &ToolCall,
+        cancellation_token: &CancellationToken,
+        dispatch: impl Future<Output = R>,
+    ) -> R {
+        let watchdog = turn.config.tool_lock_watchdog;
+        let held_since = Instant::now();
+        tokio::pin!(dispatch);
+        let report_after = watchdog
+            .report_after_secs
+            .map_or(DEFAULT_LOCK_REPORT_AFTER, Duration::from_secs);
+        let mut reported = false;
+        loop {
+            let threshold = match (reported, watchdog.cancel_after_secs) {
+                (false, _) => report_after,
+                (true, Some(cancel_after)) => Duration::from_secs(cancel_after),
+                (true, None) => return dispatch.await,
+            };
+            let (approval_wait, waiting_for_approval) = session
+                .services
+                .active_tool_calls
+                .approval_wait(&call.call_id);
+            let held = held_since.elapsed().saturating_sub(approval_wait);
+            if held >= threshold && !waiting_for_approval {
+                Self::report_lock_held(session, turn, call, held, reported).await;
+                if !reported {
+                    reported = true;
+                    continue;
+                }
+                // The task answers with the aborted response of the call.
+                cancellation_token.cancel();
+                return std::future::pending().await;
+            }
+            let recheck_after = if waiting_for_approval {
+                APPROVAL_RECHECK_INTERVAL
+            } else {
+                threshold - held
+            };
+            tokio::select! {
+                res = &mut dispatch => return res,
+                _ = tokio::time::sleep(recheck_after) => {}
+            }
+        }
+    }
+
+    async fn report_lock_held(
+        session: &Session,
+        turn: &TurnContext,
+        call: &ToolCall,
+        held: Duration,
+        cancelled: bool,
+    ) {
+        let held_ms = u64::try_from(held.as_millis()).unwrap_or(u64::MAX);
+        let waiting_call_ids: Vec<String> = session
+            .services
+            .active_tool_calls
+            .snapshot()
+            .into_iter()
+            .filter(|active| active.status == ActiveToolCallStatus::WaitingForParallelLock)
+            .map(|active| active.call_id)
+            .collect();
+        warn!(
+            "tool call {} ({}) has held the exclusive tool lock for {held_ms}ms with {} calls waiting",
+            call.call_id,
+            call.tool_name,
+            waiting_call_ids.len()
+        );
+        session
+            .send_event(
+                turn,
+                EventMsg::ToolLockHeld(ToolLockHeldEvent {
+                    call_id: call.call_id.clone(),
+                    tool: call.tool_name.clone(),
+                    held_ms,
+                    waiting_call_ids,
+                    cancelled,
+                }),
+            )
+            .await;
+    }
+
+    fn aborted_response(call: &ToolCall, secs: f32, locale: Locale) -> ResponseInputItem {
         match &call.payload {
             ToolPayload::Custom { .. } => ResponseInputItem::CustomToolCallOutput {
                 call_id: call.call_id.clone(),
-                output: Self::abort_message(call, secs),
+                output: Self::abort_message(call, secs, locale),
             },
             ToolPayload::Mcp { .. } => ResponseInputItem::McpToolCallOutput {
                 call_id: call.call_id.clone(),
-                result: Err(Self::abort_message(call, secs)),
+                result: Err(Self::abort_message(call, secs, locale)),
             },
             _ => ResponseInputItem::FunctionCallOutput {
                 call_id: call.call_id.clone(),
                 output: FunctionCallOutputPayload {
-                    body: FunctionCallOutputBody::Text(Self::abort_message(call, secs)),
+                    body: FunctionCallOutputBody::Text(Self::abort_message(call, secs, locale)),
                     ..Default::default()
                 },
             },
         }
     }
 
-    fn abort_message(call: &ToolCall, secs: f32) -> String {
-        match call.tool_name.as_str() {
+    fn abort_message(call: &ToolCall, secs: f32, locale: Locale) -> String {
+        let message = match call.tool_name.as_str() {
             "shell" | "container.exec" | "local_shell" | "shell_command" | "unified_exec" => {
-                format!("Wall time: {secs:.1} seconds\naborted by user")
+                Message::ShellAborted
             }
-            _ => format!("aborted by user after {secs:.1}s"),
-        }
+            _ => Message::ToolAborted,
+        };
+        locale.format(message, &[("secs", &format!("{secs:.1}"))])
+    }
+}
+
+/// Counts an interactive call as pending until it finishes or is aborted.
+struct InteractiveCallGuard {
+    interactive_calls: Arc<watch::Sender<usize>>,
+}
+
+impl Drop for InteractiveCallGuard {
+    fn drop(&mut self) {
+        self.interactive_calls
+            .send_modify(|count| *count = count.saturating_sub(1));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::make_session_and_context_with_rx;
+    use crate::config::Config;
+    use crate::config::types::ToolLockWatchdog;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn time_waiting_for_approval_does_not_count_as_holding_the_lock() {
+        let (session, turn, rx) = make_session_and_context_with_rx().await;
+        let mut turn = Arc::into_inner(turn).expect("unshared turn context");
+        turn.config = Arc::new(Config {
+            tool_lock_watchdog: ToolLockWatchdog {
+                report_after_secs: Some(1),
+                cancel_after_secs: Some(1),
+            },
+            ..(*turn.config).clone()
+        });
+        let call = ToolCall {
+            tool_name: "shell".to_string(),
+            call_id: "call-1".to_string(),
+            payload: ToolPayload::Function {
+                arguments: "{}".to_string(),
+            },
+        };
+        let active_calls = &session.services.active_tool_calls;
+        let _active_call = active_calls.start(
+            &call.call_id,
+            &call.tool_name,
+            ActiveToolCallStatus::Running,
+            CancellationToken::new(),
+        );
+        let cancellation_token = CancellationToken::new();
+
+        let result = ToolCallRuntime::watch_exclusive_call(
+            &session,
+            &turn,
+            &call,
+            &cancellation_token,
+            async {
+                active_calls.set_status(&call.call_id, ActiveToolCallStatus::WaitingForApproval);
+                tokio::time::sleep(Duration::from_millis(1500)).await;
+                active_calls.set_status(&call.call_id, ActiveToolCallStatus::Running);
+                tokio::time::sleep(Duration::from_millis(500)).await;
+                "done"
+            },
+        )
+        .await;
+
+        let lock_reports: Vec<ToolLockHeldEvent> = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|event| match event.msg {
+                EventMsg::ToolLockHeld(report) => Some(report),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            (result, lock_reports, cancellation_token.is_cancelled()),
+            ("done", Vec::new(), false)
+        );
     }
 }
@@ -0,0 +1,106 @@
+//! Speculative prefetch of tool calls (`speculative_tool_prefetch` feature).
+//!
+//! Function call arguments stream in as JSON fragments before the call's
+//! `OutputItemDone`. [`ToolCallPrefetcher`] accumulates them per output item
+//! and hands the call back as soon as the arguments form a complete JSON
+//! object, so a call whose results are cached can be dispatched early (see
+//! [`crate::tools::parallel::ToolCallRuntime::prefetch`]). The result is used
+//! only if the completed call carries the same arguments.
+
+use std::collections::HashMap;
+
+use codex_protocol::models::ResponseItem;
+
+use crate::tools::streaming_arguments::PartialJson;
+
+#[derive(Debug)]
+struct StreamingCall {
+    name: String,
+    call_id: String,
+    arguments: PartialJson,
+}
+
+/// Function calls whose arguments are still streaming, keyed by item id.
+#[derive(Debug, Default)]
+pub(crate) struct ToolCallPrefetcher {
+    streaming: HashMap<String, StreamingCall>,
+}
+
+impl ToolCallPrefetcher {
+    /// Starts tracking `item` if it is a function call.
+    pub(crate) fn item_added(&mut self, item: &ResponseItem) {
+        if let ResponseItem::FunctionCall {
+            id: Some(id),
+            name,
+            arguments,
+            call_id,
+        } = item
+        {
+            let mut partial = PartialJson::default();
+            if partial.push(arguments).is_ok() {
+                self.streaming.insert(
+                    id.clone(),
+                    StreamingCall {
+                        name: name.clone(),
+                        call_id: call_id.clone(),
+                        arguments: partial,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Appends `delta` to the arguments of `item_id`. Returns the call once its
+    /// arguments form a complete object; it is not tracked after that, nor
+    /// once its arguments turn out to be malformed.
+    pub(crate) fn arguments_delta(&mut self, item_id: &str, delta: &str) -> Option<ResponseItem> {
+        let call = self.streaming.get_mut(item_id)?;
+        let malformed = call.arguments.push(delta).is_err();
+        if !malformed && !call.arguments.is_complete() {
+            return None;
+        }
+        let StreamingCall {
+            name,
+            call_id,
+            arguments,
+        } = self.streaming.remove(item_id)?;
+        (!malformed).then(|| ResponseItem::FunctionCall {
+            id: Some(item_id.to_string()),
+            name,
+            arguments: arguments.into_string(),
+            call_id,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn returns_the_call_once_arguments_are_complete() {
+        let mut prefetcher = ToolCallPrefetcher::default();
+        prefetcher.item_added(&ResponseItem::FunctionCall {
+            id: Some("fc_1".to_string()),
+            name: "read_file".to_string(),
+            arguments: String::new(),
+            call_id: "call_1".to_string(),
+        });
+
+        assert_eq!(
+            prefetcher.arguments_delta("fc_1", r#"{"file_path":"/repo/"#),
+            None
+        );
+        assert_eq!(
+            prefetcher.arguments_delta("fc_1", r#"src/lib.rs"}"#),
+            Some(ResponseItem::FunctionCall {
+                id: Some("fc_1".to_string()),
+                name: "read_file".to_string(),
+                arguments: r#"{"file_path":"/repo/src/lib.rs"}"#.to_string(),
+                call_id: "call_1".to_string(),
+            })
+        );
+        assert_eq!(prefetcher.arguments_delta("fc_1", "}"), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! Progress of long-running tool calls ([`EventMsg::ToolProgress`]).
+//!
+//! A handler takes a [`ToolProgress`] for its call and reports each step it
+//! completes, out of a total when it knows one, so clients can show a
+//! determinate progress bar next to the call. Reports that change neither
+//! the whole percentage nor the message are dropped, so a handler can report
+//! every step of a large job without flooding clients.
+//!
+//! [`EventMsg::ToolProgress`]: codex_protocol::protocol::EventMsg::ToolProgress
+
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::ToolProgressEvent;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::tools::context::ToolInvocation;
+
+pub(crate) struct ToolProgress {
+    session: Arc<Session>,
+    turn: Arc<TurnContext>,
+    call_id: String,
+    last: Mutex<Option<ToolProgressEvent>>,
+}
+
+impl ToolProgress {
+    pub(crate) fn new(invocation: &ToolInvocation) -> Self {
+        Self {
+            session: Arc::clone(&invocation.session),
+            turn: Arc::clone(&invocation.turn),
+            call_id: invocation.call_id.clone(),
+            last: Mutex::new(None),
+        }
+    }
+
+    /// Reports `completed` steps of `total`, doing `message` now.
+    pub(crate) async fn report(&self, completed: u64, total: Option<u64>, message: Option<String>) {
+        let event = ToolProgressEvent {
+            call_id: self.call_id.clone(),
+            completed,
+            total,
+            message,
+        };
+        {
+            let mut last = self
+                .last
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if last
+                .as_ref()
+                .is_some_and(|last| !changes_visibly(last, &event))
+            {
+                return;
+            }
+            *last = Some(event.clone());
+        }
+        self.session
+            .send_event(self.turn.as_ref(), EventMsg::ToolProgress(event))
+            .await;
+    }
+}
+
+/// Whether `next` would show differently from `last`: another whole
+/// percentage, another message, or another step count when there is no
+/// total.
+fn changes_visibly(last: &ToolProgressEvent, next: &ToolProgressEvent) -> bool {
+    if last.message != next.message || last.total != next.total {
+        return true;
+    }
+    match next.total {
+        Some(total) if total > 0 => {
+            last.completed.saturating_mul(100) / total != next.completed.saturating_mul(100) / total
+        }
+        _ => last.completed != next.completed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::codex::make_session_and_context_with_rx;
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    async fn reports_only_visible_changes() {
+        let (session, turn, rx) = make_session_and_context_with_rx().await;
+        let progress = ToolProgress::new(&ToolInvocation {
+            session,
+            turn,
+            tracker: Default::default(),
+            call_id: "call-1".to_string(),
+            tool_name: "apply_patch".to_string(),
+            payload: crate::tools::context::ToolPayload::Custom {
+                input: String::new(),
+            },
+        });
+        let patching = Some("patching".to_string());
+        progress.report(0, Some(1_000), patching.clone()).await;
+        progress.report(1, Some(1_000), patching.clone()).await;
+        progress.report(10, Some(1_000), patching.clone()).await;
+        progress.report(10, Some(1_000), None).await;
+        progress.report(1_000, Some(1_000), None).await;
+
+        let mut reported = Vec::new();
+        while let Ok(event) = rx.try_recv() {
+            if let EventMsg::ToolProgress(event) = event.msg {
+                reported.push(event);
+            }
+        }
+        let event = |completed, message: &Option<String>| ToolProgressEvent {
+            call_id: "call-1".to_string(),
+            completed,
+            total: Some(1_000),
+            message: message.clone(),
+        };
+        assert_eq!(
+            reported,
+            vec![
+                event(0, &patching),
+                event(10, &patching),
+                event(10, &None),
+                event(1_000, &None),
+            ]
+        );
+    }
+}
@@ -4,8 +4,10 @@ use std::time::Duration;
 use std::time::Instant;
 
 use crate::client_common::tools::ToolSpec;
+use crate::config::types::ToolFreshness;
 use crate::features::Feature;
 use crate::function_tool::FunctionCallError;
+use crate::function_tool::ToolFailureKind;
 use crate::memories::usage::emit_metric_for_tool_read;
 use crate::protocol::SandboxPolicy;
 use crate::sandbox_tags::sandbox_tag;
@@ -30,6 +32,15 @@ pub enum ToolKind {
     Mcp,
 }
 
+/// How urgently a tool's calls are scheduled against each other.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ToolPriority {
+    /// Quick calls the model waits on, such as reads. Calls that need the
+    /// exclusive lock wait for pending interactive calls to finish first.
+    Interactive,
+    Normal,
+}
+
 #[async_trait]
 pub trait ToolHandler: Send + Sync {
     fn kind(&self) -> ToolKind;
@@ -50,6 +61,33 @@ pub trait ToolHandler: Send + Sync {
         false
     }
 
+    /// Returns `true` if repeating a call has no effect beyond the first one
+    /// (e.g. reads). The router retries calls that fail with a retryable
+    /// [`ToolFailureKind`] when the tool is idempotent or the session caches
+    /// its results.
+    fn is_idempotent(&self) -> bool {
+        false
+    }
+
+    /// Returns `true` if the call only reads local state and its output
+    /// depends on nothing but its arguments and that state, so the session
+    /// may cache its results.
+    fn is_cache_eligible(&self) -> bool {
+        false
+    }
+
+    /// How long the session caches the tool's results when `tool_freshness`
+    /// does not configure it, or `None` to not cache them. Tools that only
+    /// read local state are volatile.
+    fn freshness(&self) -> Option<ToolFreshness> {
+        self.is_cache_eligible().then_some(ToolFreshness::Volatile)
+    }
+
+    /// Scheduling priority of the tool's calls.
+    fn priority(&self) -> ToolPriority {
+        ToolPriority::Normal
+    }
+
     /// Perform the actual [ToolInvocation] and returns a [ToolOutput] containing
     /// the final output to return to the model.
     async fn handle(&self, invocation: ToolInvocation) -> Result<ToolOutput, FunctionCallError>;
@@ -134,7 +172,10 @@ impl ToolRegistry {
                     mcp_server_ref,
                     mcp_server_origin_ref,
                 );
-                return Err(FunctionCallError::RespondToModel(message));
+                return Err(FunctionCallError::failed(
+                    ToolFailureKind::NotFound,
+                    message,
+                ));
             }
         };
 
@@ -275,6 +316,12 @@ impl ToolRegistryBuilder {
         }
     }
 
+    /// Drops the specs and handlers of every tool `disabled` matches.
+    pub fn remove_tools(&mut self, disabled: impl Fn(&str) -> bool) {
+        self.specs.retain(|spec| !disabled(spec.spec.name()));
+        self.handlers.retain(|name, _| !disabled(name));
+    }
+
     // TODO(jif) for dynamic tools.
     // pub fn register_many<I>(&mut self, names: I, handler: Arc<dyn ToolHandler>)
     // where
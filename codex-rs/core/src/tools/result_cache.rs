@@ -0,0 +1,505 @@
+//! Session cache of tool results (`tool_freshness` in `config.toml`).
+//!
+//! Tools with a freshness class have their successful results cached for the
+//! session, for as long as the class allows: a few minutes for volatile data
+//! such as weather or prices, an hour for stable data, and the whole session
+//! for immutable data. Built-in tools that only read local state are volatile
+//! and `package_info` is stable unless configured otherwise, and mutating
+//! calls drop the volatile entries.
+//! Results served from the cache tell the model how old they are.
+//!
+//! Results of file tools are keyed by the resolved path, the session cwd, and
+//! the file's modification time and size (for `list_dir`, also the
+//! modification times of the subdirectories the listing descends into), so
+//! they are not served after the file changes, whoever changed it.
+//!
+//! With `shared_tool_result_cache` set, results of immutable tools are also
+//! kept in the state database, under a hash of the project and the call, so
+//! new sessions in the same project start with them.
+//!
+//! A call identical to one in flight waits for it and reuses its result.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use codex_protocol::models::FunctionCallOutputBody;
+use codex_protocol::models::FunctionCallOutputContentItem;
+use codex_protocol::models::ResponseInputItem;
+use serde_json::Value;
+use serde_json::json;
+use sha2::Digest;
+use sha2::Sha256;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::codex::TurnContext;
+use crate::config::types::ToolFreshness;
+use crate::suggested_allowlist::project_root;
+use crate::tools::context::ToolPayload;
+use crate::tools::loop_detection::canonical_call_key;
+
+/// Entries kept; the oldest are dropped first.
+const MAX_ENTRIES: usize = 256;
+/// Most subdirectories fingerprinted for one `list_dir` call; deeper
+/// listings are not cached.
+const MAX_FINGERPRINTED_DIRS: usize = 256;
+
+impl ToolFreshness {
+    /// How long results stay cached, or `None` for the whole session.
+    pub(crate) fn ttl(self) -> Option<Duration> {
+        match self {
+            ToolFreshness::Volatile => Some(Duration::from_secs(5 * 60)),
+            ToolFreshness::Stable => Some(Duration::from_secs(60 * 60)),
+            ToolFreshness::Immutable => None,
+        }
+    }
+}
+
+/// Key of the results of `tool_name` called with `payload` from `cwd`, or
+/// `None` when they cannot be cached: the file a file tool reads is missing,
+/// or the tool reads more files than can be fingerprinted.
+pub(crate) async fn tool_call_cache_key(
+    tool_name: &str,
+    payload: &ToolPayload,
+    cwd: &Path,
+) -> Option<String> {
+    let call_key = canonical_call_key(tool_name, payload);
+    let path_argument = match tool_name {
+        "read_file" => "file_path",
+        "read_symbol" => "path",
+        "list_dir" => "dir_path",
+        // Matches depend on the content of every file searched.
+        "grep_files" | "who_calls" | "what_does_this_call" => return None,
+        _ => return Some(call_key),
+    };
+    let ToolPayload::Function { arguments } = payload else {
+        return None;
+    };
+    let arguments: Value = serde_json::from_str(arguments).ok()?;
+    let path = cwd.join(arguments.get(path_argument)?.as_str()?);
+    let path = tokio::fs::canonicalize(&path).await.ok()?;
+    let metadata = tokio::fs::metadata(&path).await.ok()?;
+    let modified = metadata
+        .modified()
+        .ok()?
+        .duration_since(UNIX_EPOCH)
+        .ok()?
+        .as_nanos();
+    let mut key = format!(
+        "{call_key}@{}:{}:{modified}:{}",
+        cwd.display(),
+        path.display(),
+        metadata.len()
+    );
+    if tool_name == "list_dir" {
+        let depth = arguments.get("depth").and_then(Value::as_u64).unwrap_or(2);
+        let mut subdirs = Vec::new();
+        let mut pending = vec![(path, depth)];
+        while let Some((dir, depth)) = pending.pop() {
+            if depth <= 1 {
+                continue;
+            }
+            let mut entries = tokio::fs::read_dir(&dir).await.ok()?;
+            while let Some(entry) = entries.next_entry().await.ok()? {
+                if !entry.file_type().await.ok()?.is_dir() {
+                    continue;
+                }
+                let modified = entry
+                    .metadata()
+                    .await
+                    .ok()?
+                    .modified()
+                    .ok()?
+                    .duration_since(UNIX_EPOCH)
+                    .ok()?
+                    .as_nanos();
+                subdirs.push((entry.path(), modified));
+                if subdirs.len() > MAX_FINGERPRINTED_DIRS {
+                    return None;
+                }
+                pending.push((entry.path(), depth - 1));
+            }
+        }
+        subdirs.sort();
+        for (dir, modified) in subdirs {
+            key.push_str(&format!(";{}:{modified}", dir.display()));
+        }
+    }
+    Some(key)
+}
+
+/// The cached result of the call keyed `cache_key`, as the output of call
+/// `call_id`, from this session or, for immutable tools, the shared cache.
+pub(crate) async fn cached_result(
+    sess: &Session,
+    turn: &TurnContext,
+    cache_key: &str,
+    freshness: ToolFreshness,
+    call_id: &str,
+) -> Option<ResponseInputItem> {
+    if let Some(cached) =
+        sess.services
+            .tool_result_cache
+            .lock()
+            .await
+            .get(cache_key, call_id, Instant::now())
+    {
+        return Some(cached);
+    }
+    if !is_shared(freshness) || !turn.config.shared_tool_result_cache {
+        return None;
+    }
+    let state_db = sess.services.state_db.as_ref()?;
+    let (output, stored_at) = match state_db
+        .get_shared_tool_result(&shared_key(turn, cache_key))
+        .await
+    {
+        Ok(stored) => stored?,
+        Err(err) => {
+            warn!("failed to read the shared tool result cache: {err}");
+            return None;
+        }
+    };
+    let output: ResponseInputItem = serde_json::from_str(&output).ok()?;
+    let age = Duration::from_secs(u64::try_from(unix_now() - stored_at).unwrap_or_default());
+    let now = Instant::now();
+    let mut cache = sess.services.tool_result_cache.lock().await;
+    cache.insert(
+        cache_key.to_string(),
+        output,
+        freshness,
+        now.checked_sub(age).unwrap_or(now),
+    );
+    cache.get(cache_key, call_id, now)
+}
+
+/// Caches `output`, the result of the call keyed `cache_key`.
+pub(crate) async fn store_result(
+    sess: &Session,
+    turn: &TurnContext,
+    cache_key: String,
+    freshness: ToolFreshness,
+    output: ResponseInputItem,
+) {
+    if is_shared(freshness)
+        && turn.config.shared_tool_result_cache
+        && let Some(state_db) = sess.services.state_db.as_ref()
+        && let Ok(serialized) = serde_json::to_string(&output)
+        && let Err(err) = state_db
+            .put_shared_tool_result(&shared_key(turn, &cache_key), &serialized, unix_now())
+            .await
+    {
+        warn!("failed to write the shared tool result cache: {err}");
+    }
+    sess.services.tool_result_cache.lock().await.insert(
+        cache_key,
+        output,
+        freshness,
+        Instant::now(),
+    );
+}
+
+/// Whether results of this class go to the shared cache. Only immutable
+/// results do: another session can't tell when a stable one went stale.
+fn is_shared(freshness: ToolFreshness) -> bool {
+    freshness == ToolFreshness::Immutable
+}
+
+/// Content address of the call keyed `cache_key` in the project of `turn`.
+fn shared_key(turn: &TurnContext, cache_key: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(project_root(&turn.cwd).to_string_lossy().as_bytes());
+    hasher.update([0]);
+    hasher.update(cache_key.as_bytes());
+    format!("{:x}", hasher.finalize())
+}
+
+fn unix_now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| {
+            i64::try_from(elapsed.as_secs()).unwrap_or(i64::MAX)
+        })
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ToolResultCache {
+    entries: HashMap<String, CachedResult>,
+    /// Held by the call computing the result of each key.
+    in_flight: HashMap<String, Arc<tokio::sync::Mutex<()>>>,
+}
+
+#[derive(Debug)]
+struct CachedResult {
+    output: ResponseInputItem,
+    freshness: ToolFreshness,
+    stored_at: Instant,
+}
+
+impl CachedResult {
+    fn is_fresh(&self, now: Instant) -> bool {
+        self.freshness
+            .ttl()
+            .is_none_or(|ttl| now.duration_since(self.stored_at) < ttl)
+    }
+}
+
+impl ToolResultCache {
+    /// The cached result under `key`, as the output of call `call_id` and
+    /// annotated with its age.
+    pub(crate) fn get(
+        &mut self,
+        key: &str,
+        call_id: &str,
+        now: Instant,
+    ) -> Option<ResponseInputItem> {
+        let entry = self.entries.get(key)?;
+        if !entry.is_fresh(now) {
+            self.entries.remove(key);
+            return None;
+        }
+        let note = format!(
+            "[Cached result from {}; the underlying data may have changed since.]",
+            describe_age(now.duration_since(entry.stored_at))
+        );
+        Some(with_note(entry.output.clone(), call_id, &note))
+    }
+
+    pub(crate) fn insert(
+        &mut self,
+        key: String,
+        output: ResponseInputItem,
+        freshness: ToolFreshness,
+        now: Instant,
+    ) {
+        self.entries.retain(|_, entry| entry.is_fresh(now));
+        if self.entries.len() >= MAX_ENTRIES
+            && let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(key, _)| key.clone())
+        {
+            self.entries.remove(&oldest);
+        }
+        self.entries.insert(
+            key,
+            CachedResult {
+                output,
+                freshness,
+                stored_at: now,
+            },
+        );
+    }
+
+    /// The lock held while the result of `key` is computed.
+    pub(crate) fn in_flight_slot(&mut self, key: &str) -> Arc<tokio::sync::Mutex<()>> {
+        Arc::clone(self.in_flight.entry(key.to_string()).or_default())
+    }
+
+    /// Forgets the lock of `key` once no call holds or waits for it.
+    pub(crate) fn release_in_flight(&mut self, key: &str) {
+        if self
+            .in_flight
+            .get(key)
+            .is_some_and(|slot| Arc::strong_count(slot) == 1)
+        {
+            self.in_flight.remove(key);
+        }
+    }
+
+    /// Drops the volatile entries, after a call that may have changed what
+    /// they describe.
+    pub(crate) fn invalidate_volatile(&mut self) {
+        self.entries
+            .retain(|_, entry| entry.freshness != ToolFreshness::Volatile);
+    }
+}
+
+fn describe_age(age: Duration) -> String {
+    let minutes = age.as_secs() / 60;
+    match minutes {
+        0 => "less than a minute ago".to_string(),
+        1 => "1 minute ago".to_string(),
+        2..120 => format!("{minutes} minutes ago"),
+        _ => format!("{} hours ago", minutes / 60),
+    }
+}
+
+/// `output` re-addressed to `call_id`, with `note` before its content.
+fn with_note(output: ResponseInputItem, call_id: &str, note: &str) -> ResponseInputItem {
+    let call_id = call_id.to_string();
+    match output {
+        ResponseInputItem::FunctionCallOutput { mut output, .. } => {
+            output.body = match output.body {
+                FunctionCallOutputBody::Text(text) => {
+                    FunctionCallOutputBody::Text(format!("{note}\n{text}"))
+                }
+                FunctionCallOutputBody::ContentItems(mut items) => {
+                    items.insert(
+                        0,
+                        FunctionCallOutputContentItem::InputText {
+                            text: note.to_string(),
+                        },
+                    );
+                    FunctionCallOutputBody::ContentItems(items)
+                }
+            };
+            ResponseInputItem::FunctionCallOutput { call_id, output }
+        }
+        ResponseInputItem::CustomToolCallOutput { output, .. } => {
+            ResponseInputItem::CustomToolCallOutput {
+                call_id,
+                output: format!("{note}\n{output}"),
+            }
+        }
+        ResponseInputItem::McpToolCallOutput { result, .. } => {
+            ResponseInputItem::McpToolCallOutput {
+                call_id,
+                result: result.map(|mut result| {
+                    result
+                        .content
+                        .insert(0, json!({"type": "text", "text": note}));
+                    result
+                }),
+            }
+        }
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    fn output(call_id: &str, text: &str) -> ResponseInputItem {
+        ResponseInputItem::FunctionCallOutput {
+            call_id: call_id.to_string(),
+            output: FunctionCallOutputPayload::from_text(text.to_string()),
+        }
+    }
+
+    #[test]
+    fn serves_results_annotated_with_their_age_until_they_expire() {
+        let mut cache = ToolResultCache::default();
+        let stored_at = Instant::now();
+        cache.insert(
+            "weather".to_string(),
+            output("call-1", "sunny"),
+            ToolFreshness::Volatile,
+            stored_at,
+        );
+        cache.insert(
+            "docs".to_string(),
+            output("call-2", "v1.0"),
+            ToolFreshness::Immutable,
+            stored_at,
+        );
+
+        assert_eq!(
+            cache.get("weather", "call-3", stored_at + Duration::from_secs(3 * 60)),
+            Some(output(
+                "call-3",
+                "[Cached result from 3 minutes ago; the underlying data may have changed since.]\nsunny"
+            ))
+        );
+        let later = stored_at + Duration::from_secs(3 * 60 * 60);
+        assert_eq!(cache.get("weather", "call-4", later), None);
+        assert_eq!(
+            cache.get("docs", "call-5", later),
+            Some(output(
+                "call-5",
+                "[Cached result from 3 hours ago; the underlying data may have changed since.]\nv1.0"
+            ))
+        );
+    }
+
+    #[test]
+    fn only_immutable_results_are_shared() {
+        assert_eq!(
+            [
+                ToolFreshness::Volatile,
+                ToolFreshness::Stable,
+                ToolFreshness::Immutable,
+            ]
+            .map(is_shared),
+            [false, false, true]
+        );
+    }
+
+    #[tokio::test]
+    async fn file_tool_keys_change_with_the_file() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let file = dir.path().join("notes.txt");
+        std::fs::write(&file, "one").expect("write");
+        let payload = ToolPayload::Function {
+            arguments: json!({"file_path": file}).to_string(),
+        };
+        let key = tool_call_cache_key("read_file", &payload, dir.path())
+            .await
+            .expect("key");
+
+        let other_cwd = tempfile::tempdir().expect("tempdir");
+        assert_ne!(
+            tool_call_cache_key("read_file", &payload, other_cwd.path()).await,
+            Some(key.clone())
+        );
+        std::fs::write(&file, "one more").expect("write");
+        assert_ne!(
+            tool_call_cache_key("read_file", &payload, dir.path()).await,
+            Some(key)
+        );
+        std::fs::remove_file(&file).expect("remove");
+        assert_eq!(
+            tool_call_cache_key("read_file", &payload, dir.path()).await,
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn list_dir_keys_change_with_nested_directories() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::create_dir_all(dir.path().join("src/nested")).expect("create dirs");
+        let payload = ToolPayload::Function {
+            arguments: json!({"dir_path": dir.path(), "depth": 3}).to_string(),
+        };
+        let key = tool_call_cache_key("list_dir", &payload, dir.path())
+            .await
+            .expect("key");
+
+        std::fs::write(dir.path().join("src/nested/lib.rs"), "").expect("write");
+        assert_ne!(
+            tool_call_cache_key("list_dir", &payload, dir.path()).await,
+            Some(key)
+        );
+    }
+
+    #[test]
+    fn invalidating_keeps_results_that_cannot_go_stale() {
+        let mut cache = ToolResultCache::default();
+        let now = Instant::now();
+        cache.insert(
+            "read".to_string(),
+            output("call-1", "fn main() {}"),
+            ToolFreshness::Volatile,
+            now,
+        );
+        cache.insert(
+            "release".to_string(),
+            output("call-2", "1.2.3"),
+            ToolFreshness::Stable,
+            now,
+        );
+
+        cache.invalidate_volatile();
+        assert_eq!(cache.get("read", "call-3", now), None);
+        assert!(cache.get("release", "call-3", now).is_some());
+    }
+}
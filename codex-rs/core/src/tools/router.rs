@@ -1,26 +1,46 @@
 use crate::client_common::tools::ToolSpec;
 use crate::codex::Session;
 use crate::codex::TurnContext;
+use crate::config::types::ToolFreshness;
 use crate::function_tool::FunctionCallError;
+use crate::function_tool::ToolFailureKind;
 use crate::mcp_connection_manager::ToolInfo;
 use crate::sandboxing::SandboxPermissions;
+use crate::tools::circuit_breaker::call_failed;
 use crate::tools::context::SharedTurnDiffTracker;
 use crate::tools::context::ToolInvocation;
 use crate::tools::context::ToolPayload;
+use crate::tools::loop_detection::REPEATED_FAILURE_LIMIT;
+use crate::tools::loop_detection::canonical_call_key;
+use crate::tools::loop_detection::failure_output;
+use crate::tools::loop_detection::repeated_failure_nudge;
 use crate::tools::registry::ConfiguredToolSpec;
+use crate::tools::registry::ToolPriority;
 use crate::tools::registry::ToolRegistry;
+use crate::tools::result_cache::cached_result;
+use crate::tools::result_cache::store_result;
+use crate::tools::result_cache::tool_call_cache_key;
 use crate::tools::spec::ToolsConfig;
 use crate::tools::spec::build_specs;
+use crate::util::backoff;
 use codex_protocol::dynamic_tools::DynamicToolSpec;
 use codex_protocol::models::FunctionCallOutputBody;
 use codex_protocol::models::LocalShellAction;
 use codex_protocol::models::ResponseInputItem;
 use codex_protocol::models::ResponseItem;
 use codex_protocol::models::ShellToolCallParams;
+use codex_protocol::protocol::ActiveToolCallStatus;
+use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::SessionTool;
 use rmcp::model::Tool;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tracing::instrument;
+use tracing::warn;
+
+/// How many times a transient failure of an idempotent tool is retried before
+/// the error is returned to the model.
+const MAX_IDEMPOTENT_TOOL_RETRIES: u64 = 2;
 
 #[derive(Clone, Debug)]
 pub struct ToolCall {
@@ -33,6 +53,10 @@ pub struct ToolCall {
 pub enum ToolCallSource {
     Direct,
     JsRepl,
+    /// A client calling the tool itself ([`Op::InvokeTool`]).
+    ///
+    /// [`Op::InvokeTool`]: codex_protocol::protocol::Op::InvokeTool
+    Client,
 }
 
 pub struct ToolRouter {
@@ -67,6 +91,90 @@ impl ToolRouter {
             .any(|config| config.spec.name() == tool_name)
     }
 
+    pub fn tool_priority(&self, tool_name: &str) -> ToolPriority {
+        self.registry
+            .handler(tool_name)
+            .map_or(ToolPriority::Normal, |handler| handler.priority())
+    }
+
+    /// How the session caches results of `tool_name`: as `tool_freshness`
+    /// configures, else as the tool declares. `None` when they are not
+    /// cached.
+    pub fn tool_freshness(&self, turn: &TurnContext, tool_name: &str) -> Option<ToolFreshness> {
+        turn.config
+            .tool_freshness
+            .get(tool_name)
+            .copied()
+            .or_else(|| {
+                self.registry
+                    .handler(tool_name)
+                    .and_then(|handler| handler.freshness())
+            })
+    }
+
+    /// The tools a client may call itself, as [`Op::ListSessionTools`] lists
+    /// them. Tools the Responses API runs or shapes itself, such as
+    /// `web_search` and `local_shell`, are left out.
+    ///
+    /// [`Op::ListSessionTools`]: codex_protocol::protocol::Op::ListSessionTools
+    pub fn session_tools(&self, approval_policy: AskForApproval) -> Vec<SessionTool> {
+        self.specs
+            .iter()
+            .filter_map(|config| {
+                let (name, description, input_schema) = match &config.spec {
+                    ToolSpec::Function(tool) => (
+                        &tool.name,
+                        &tool.description,
+                        serde_json::to_value(&tool.parameters).ok(),
+                    ),
+                    ToolSpec::Freeform(tool) => (&tool.name, &tool.description, None),
+                    ToolSpec::LocalShell {} | ToolSpec::WebSearch { .. } => return None,
+                };
+                Some(SessionTool {
+                    name: name.clone(),
+                    description: description.clone(),
+                    input_schema,
+                    // Only tools known to just read local state never ask;
+                    // any other call may, e.g. to run outside the sandbox.
+                    requires_approval: approval_policy != AskForApproval::Never
+                        && !self
+                            .registry
+                            .handler(name)
+                            .is_some_and(|handler| handler.is_cache_eligible()),
+                })
+            })
+            .collect()
+    }
+
+    /// The call of `tool_name` with `arguments` as the model would make it,
+    /// for callers other than the model.
+    pub async fn build_call_by_name(
+        &self,
+        session: &Session,
+        tool_name: String,
+        call_id: String,
+        arguments: String,
+    ) -> ToolCall {
+        let payload = if let Some((server, tool)) = session.parse_mcp_tool_name(&tool_name).await {
+            ToolPayload::Mcp {
+                server,
+                tool,
+                raw_arguments: arguments,
+            }
+        } else if self.specs.iter().any(|config| {
+            config.spec.name() == tool_name && matches!(config.spec, ToolSpec::Freeform(_))
+        }) {
+            ToolPayload::Custom { input: arguments }
+        } else {
+            ToolPayload::Function { arguments }
+        };
+        ToolCall {
+            tool_name,
+            call_id,
+            payload,
+        }
+    }
+
     #[instrument(level = "trace", skip_all, err)]
     pub async fn build_tool_call(
         session: &Session,
@@ -171,6 +279,83 @@ impl ToolRouter {
             ));
         }
 
+        let freshness = self.tool_freshness(&turn, &tool_name);
+        let cache = match freshness {
+            Some(freshness) => tool_call_cache_key(&tool_name, &payload, &turn.cwd)
+                .await
+                .map(|cache_key| (cache_key, freshness)),
+            None => None,
+        };
+        let mut in_flight = None;
+        if let Some((cache_key, freshness)) = &cache {
+            if let Some(cached) =
+                cached_result(&session, &turn, cache_key, *freshness, &call_id).await
+            {
+                return Ok(cached);
+            }
+            let slot = session
+                .services
+                .tool_result_cache
+                .lock()
+                .await
+                .in_flight_slot(cache_key);
+            let guard = match Arc::clone(&slot).try_lock_owned() {
+                Ok(guard) => guard,
+                Err(_) => {
+                    let active_calls = &session.services.active_tool_calls;
+                    active_calls.set_status(&call_id, ActiveToolCallStatus::WaitingForDuplicate);
+                    let guard = slot.lock_owned().await;
+                    active_calls.set_status(&call_id, ActiveToolCallStatus::Running);
+                    let cached =
+                        cached_result(&session, &turn, cache_key, *freshness, &call_id).await;
+                    if let Some(cached) = cached {
+                        drop(guard);
+                        session
+                            .services
+                            .tool_result_cache
+                            .lock()
+                            .await
+                            .release_in_flight(cache_key);
+                        return Ok(cached);
+                    }
+                    guard
+                }
+            };
+            in_flight = Some(guard);
+        }
+
+        let handler = self.registry.handler(&tool_name);
+        // Calls whose results the session caches are as safe to repeat.
+        let idempotent = freshness.is_some()
+            || handler
+                .as_ref()
+                .is_some_and(|handler| handler.is_idempotent());
+        // Only calls that depend on a remote service are circuit broken; MCP
+        // tools share their server's breaker since they usually fail together,
+        // and record their outcome where the server is called.
+        let circuit_key = match &payload {
+            ToolPayload::Mcp { server, .. } => Some(server.clone()),
+            _ if idempotent => Some(tool_name.clone()),
+            _ => None,
+        };
+        if let Some(circuit_key) = &circuit_key
+            && let Some(remaining) = session.tool_circuit_open_for(circuit_key).await
+        {
+            let err = FunctionCallError::failed(
+                ToolFailureKind::Unavailable,
+                format!(
+                    "tool unavailable: `{circuit_key}` failed repeatedly; calls are paused for another {}s",
+                    remaining.as_secs().max(1)
+                ),
+            );
+            return Ok(Self::failure_response(
+                failure_call_id,
+                payload_outputs_custom,
+                err,
+            ));
+        }
+
+        let call_key = canonical_call_key(&tool_name, &payload);
         let invocation = ToolInvocation {
             session,
             turn,
@@ -180,7 +365,89 @@ impl ToolRouter {
             payload,
         };
 
-        match self.registry.dispatch(invocation).await {
+        let mut retries = 0;
+        let result = loop {
+            match self.registry.dispatch(invocation.clone()).await {
+                Err(FunctionCallError::Failed { kind, message })
+                    if idempotent
+                        && kind.is_retryable()
+                        && retries < MAX_IDEMPOTENT_TOOL_RETRIES =>
+                {
+                    retries += 1;
+                    let delay = backoff(retries);
+                    warn!(
+                        "retrying idempotent tool {} in {delay:?} ({retries}/{MAX_IDEMPOTENT_TOOL_RETRIES}): {message}",
+                        invocation.tool_name
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => break result,
+            }
+        };
+
+        match cache {
+            Some((cache_key, freshness)) => {
+                if let Ok(output) = &result
+                    && failure_output(&result).is_none()
+                {
+                    store_result(
+                        &invocation.session,
+                        &invocation.turn,
+                        cache_key.clone(),
+                        freshness,
+                        output.clone(),
+                    )
+                    .await;
+                }
+                drop(in_flight);
+                invocation
+                    .session
+                    .services
+                    .tool_result_cache
+                    .lock()
+                    .await
+                    .release_in_flight(&cache_key);
+            }
+            None => {
+                if let Some(handler) = &handler
+                    && handler.is_mutating(&invocation).await
+                {
+                    invocation
+                        .session
+                        .services
+                        .tool_result_cache
+                        .lock()
+                        .await
+                        .invalidate_volatile();
+                }
+            }
+        }
+
+        if let Some(circuit_key) = &circuit_key
+            && !matches!(invocation.payload, ToolPayload::Mcp { .. })
+            && let Some(failed) = call_failed(&result)
+        {
+            invocation
+                .session
+                .record_tool_call_outcome(&invocation.turn, circuit_key, failed)
+                .await;
+        }
+
+        let repeated_failures = invocation
+            .session
+            .record_repeated_tool_failure(call_key, failure_output(&result))
+            .await;
+        if repeated_failures >= REPEATED_FAILURE_LIMIT {
+            warn!(
+                "tool {} failed identically {repeated_failures} times in a row",
+                invocation.tool_name
+            );
+            let nudge = repeated_failure_nudge(&invocation.tool_name, repeated_failures);
+            // Without an active turn there is no follow-up to nudge.
+            let _ = invocation.session.inject_response_items(vec![nudge]).await;
+        }
+
+        match result {
             Ok(response) => Ok(response),
             Err(FunctionCallError::Fatal(message)) => Err(FunctionCallError::Fatal(message)),
             Err(err) => Ok(Self::failure_response(
@@ -219,8 +486,10 @@ mod tests {
 
     use crate::codex::make_session_and_context;
     use crate::tools::context::ToolPayload;
+    use crate::tools::registry::ToolPriority;
     use crate::turn_diff_tracker::TurnDiffTracker;
     use codex_protocol::models::ResponseInputItem;
+    use pretty_assertions::assert_eq;
 
     use super::ToolCall;
     use super::ToolCallSource;
@@ -331,4 +600,24 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn only_read_tools_are_interactive() {
+        let (_session, mut turn) = make_session_and_context().await;
+        turn.tools_config.experimental_supported_tools =
+            vec!["grep_files".to_string(), "read_file".to_string()];
+        let router = ToolRouter::from_config(&turn.tools_config, None, None, &[]);
+
+        assert_eq!(
+            ["grep_files", "read_file", "shell", "apply_patch", "unknown"]
+                .map(|tool| router.tool_priority(tool)),
+            [
+                ToolPriority::Interactive,
+                ToolPriority::Interactive,
+                ToolPriority::Normal,
+                ToolPriority::Normal,
+                ToolPriority::Normal,
+            ]
+        );
+    }
 }
@@ -19,6 +19,7 @@ use crate::tools::sandboxing::ToolError;
 use crate::tools::sandboxing::ToolRuntime;
 use crate::tools::sandboxing::with_cached_approval;
 use codex_apply_patch::ApplyPatchAction;
+use codex_apply_patch::CODEX_APPLY_PATCH_FUZZ_WINDOW_ENV_VAR;
 use codex_apply_patch::CODEX_CORE_APPLY_PATCH_ARG1;
 use codex_protocol::protocol::AskForApproval;
 use codex_protocol::protocol::FileChange;
@@ -36,6 +37,8 @@ pub struct ApplyPatchRequest {
     pub exec_approval_requirement: ExecApprovalRequirement,
     pub timeout_ms: Option<u64>,
     pub codex_exe: Option<PathBuf>,
+    /// `apply_patch_fuzz_window` of the turn the patch was verified in.
+    pub fuzz_window: usize,
 }
 
 #[derive(Default)]
@@ -64,7 +67,11 @@ impl ApplyPatchRuntime {
             cwd: req.action.cwd.clone(),
             expiration: req.timeout_ms.into(),
             // Run apply_patch with a minimal environment for determinism and to avoid leaks.
-            env: HashMap::new(),
+            // The fuzz window is forwarded so the patch applies the way it was verified.
+            env: HashMap::from([(
+                CODEX_APPLY_PATCH_FUZZ_WINDOW_ENV_VAR.to_string(),
+                req.fuzz_window.to_string(),
+            )]),
             sandbox_permissions: SandboxPermissions::UseDefault,
             justification: None,
         })
@@ -75,6 +82,7 @@ impl ApplyPatchRuntime {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            capture: crate::exec::ExecOutputCapture::default(),
         })
     }
 }
@@ -65,6 +65,7 @@ impl ShellRuntime {
             sub_id: ctx.turn.sub_id.clone(),
             call_id: ctx.call_id.clone(),
             tx_event: ctx.session.get_tx_event(),
+            capture: crate::exec::ExecOutputCapture::from_config(&ctx.turn.config),
         })
     }
 }
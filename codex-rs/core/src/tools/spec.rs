@@ -5,10 +5,13 @@ use crate::client_common::tools::ToolSpec;
 use crate::config::AgentRoleConfig;
 use crate::features::Feature;
 use crate::features::Features;
+use crate::mcp::split_qualified_tool_name;
 use crate::mcp_connection_manager::ToolInfo;
 use crate::tools::handlers::PLAN_TOOL;
 use crate::tools::handlers::SEARCH_TOOL_BM25_DEFAULT_LIMIT;
 use crate::tools::handlers::SEARCH_TOOL_BM25_TOOL_NAME;
+use crate::tools::handlers::WHAT_DOES_THIS_CALL_TOOL_NAME;
+use crate::tools::handlers::WHO_CALLS_TOOL_NAME;
 use crate::tools::handlers::apply_patch::create_apply_patch_freeform_tool;
 use crate::tools::handlers::apply_patch::create_apply_patch_json_tool;
 use crate::tools::handlers::multi_agents::DEFAULT_WAIT_TIMEOUT_MS;
@@ -27,6 +30,7 @@ use serde::Serialize;
 use serde_json::Value as JsonValue;
 use serde_json::json;
 use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::collections::HashMap;
 
 const SEARCH_TOOL_BM25_DESCRIPTION_TEMPLATE: &str =
@@ -45,8 +49,27 @@ pub(crate) struct ToolsConfig {
     pub collab_tools: bool,
     pub collaboration_modes_tools: bool,
     pub experimental_supported_tools: Vec<String>,
+    pub read_attachment_tool: bool,
+    pub cloud_cli_tool: bool,
+    pub terraform_tools: bool,
+    pub container_image_tools: bool,
+    pub package_info_tool: bool,
+    pub scaffold_tool: bool,
+    pub read_only: bool,
+    pub patch_artifact: bool,
+    pub disabled_tools: BTreeSet<String>,
 }
 
+/// Experimental tools that write files or run external commands (builds,
+/// tests, profilers, debuggers), left out of read-only sessions.
+const MUTATING_EXPERIMENTAL_TOOLS: &[&str] = &[
+    "check_build",
+    "replace_in_files",
+    "run_tests",
+    "summarize_profile",
+    "symbolicate_backtrace",
+];
+
 pub(crate) struct ToolsConfigParams<'a> {
     pub(crate) model_info: &'a ModelInfo,
     pub(crate) features: &'a Features,
@@ -67,6 +90,11 @@ impl ToolsConfig {
         let include_collab_tools = features.enabled(Feature::Collab);
         let include_collaboration_modes_tools = features.enabled(Feature::CollaborationModes);
         let include_search_tool = features.enabled(Feature::Apps);
+        let include_cloud_cli_tool = features.enabled(Feature::CloudCli);
+        let include_terraform_tools = features.enabled(Feature::Terraform);
+        let include_container_image_tools = features.enabled(Feature::ContainerImage);
+        let include_package_info_tool = features.enabled(Feature::PackageInfo);
+        let include_scaffold_tool = features.enabled(Feature::Scaffold);
 
         let shell_type = if !features.enabled(Feature::ShellTool) {
             ConfigShellToolType::Disabled
@@ -107,6 +135,15 @@ impl ToolsConfig {
             collab_tools: include_collab_tools,
             collaboration_modes_tools: include_collaboration_modes_tools,
             experimental_supported_tools: model_info.experimental_supported_tools.clone(),
+            read_attachment_tool: false,
+            cloud_cli_tool: include_cloud_cli_tool,
+            terraform_tools: include_terraform_tools,
+            container_image_tools: include_container_image_tools,
+            package_info_tool: include_package_info_tool,
+            scaffold_tool: include_scaffold_tool,
+            read_only: false,
+            patch_artifact: false,
+            disabled_tools: BTreeSet::new(),
         }
     }
 
@@ -119,6 +156,56 @@ impl ToolsConfig {
         self.allow_login_shell = allow_login_shell;
         self
     }
+
+    /// Offers `read_attachment`, used once the session has attachments.
+    pub fn with_read_attachment_tool(mut self, read_attachment_tool: bool) -> Self {
+        self.read_attachment_tool = read_attachment_tool;
+        self
+    }
+
+    /// Leaves out the tools and MCP servers turned off with
+    /// `Op::SetEnabledTools`.
+    pub fn with_disabled_tools(mut self, disabled_tools: BTreeSet<String>) -> Self {
+        self.disabled_tools = disabled_tools;
+        self
+    }
+
+    /// Leaves out every tool that can modify anything: shell, `apply_patch`,
+    /// `js_repl`, sub-agents, the tools that write files or run commands,
+    /// dynamic tools, and MCP tools not annotated as read-only.
+    pub fn with_read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        if read_only {
+            self.shell_type = ConfigShellToolType::Disabled;
+            self.apply_patch_tool_type = None;
+            self.js_repl_enabled = false;
+            self.js_repl_tools_only = false;
+            self.collab_tools = false;
+            self.cloud_cli_tool = false;
+            self.terraform_tools = false;
+            self.container_image_tools = false;
+            self.scaffold_tool = false;
+            self.experimental_supported_tools
+                .retain(|tool| !MUTATING_EXPERIMENTAL_TOOLS.contains(&tool.as_str()));
+        }
+        self
+    }
+
+    /// Leaves out the tools that would change the workspace or external state
+    /// outside the read-only sandbox patch artifact mode forces: `js_repl`,
+    /// the cloud CLI, Terraform, and container image tools. Shell commands and
+    /// `apply_patch` stay, sandboxed and recorded into the artifact.
+    pub fn with_patch_artifact(mut self, patch_artifact: bool) -> Self {
+        self.patch_artifact = patch_artifact;
+        if patch_artifact {
+            self.js_repl_enabled = false;
+            self.js_repl_tools_only = false;
+            self.cloud_cli_tool = false;
+            self.terraform_tools = false;
+            self.container_image_tools = false;
+        }
+        self
+    }
 }
 
 /// Generic JSON‑Schema subset needed for our tool definitions
@@ -804,31 +891,808 @@ fn create_test_sync_tool() -> ToolSpec {
             "sleep_before_ms".to_string(),
             JsonSchema::Number {
                 description: Some(
-                    "Optional delay in milliseconds before any other action".to_string(),
+                    "Optional delay in milliseconds before any other action".to_string(),
+                ),
+            },
+        ),
+        (
+            "sleep_after_ms".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Optional delay in milliseconds after completing the barrier".to_string(),
+                ),
+            },
+        ),
+        (
+            "barrier".to_string(),
+            JsonSchema::Object {
+                properties: barrier_properties,
+                required: Some(vec!["id".to_string(), "participants".to_string()]),
+                additional_properties: Some(false.into()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "test_sync_tool".to_string(),
+        description: "Internal synchronization helper used by Codex integration tests.".to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_grep_files_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "pattern".to_string(),
+            JsonSchema::String {
+                description: Some("Regular expression pattern to search for.".to_string()),
+            },
+        ),
+        (
+            "include".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Optional glob that limits which files are searched (e.g. \"*.rs\" or \
+                     \"*.{ts,tsx}\")."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Directory or file path to search. Defaults to the session's working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Maximum number of file paths to return (defaults to 100).".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "grep_files".to_string(),
+        description: "Finds files whose contents match the pattern and lists them by modification \
+                      time."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["pattern".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_read_symbol_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some("Path to the source file.".to_string()),
+            },
+        ),
+        (
+            "symbol".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Name of the function, type, or other definition to read. Qualify it with \
+                     its enclosing definitions (e.g. \"Parser::parse\" or \"Client.send\") to \
+                     pick one of several definitions with the same name."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_symbol".to_string(),
+        description: "Reads one definition (function, method, type, impl, class, ...) from a \
+                      Rust, Python, JavaScript, TypeScript, or Go file, with its doc comments \
+                      and the signatures of the definitions enclosing it. Prefer this over \
+                      reading the whole file when you only need one definition."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string(), "symbol".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_who_calls_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "symbol".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Function or method whose callers to find. Qualify it with its type or \
+                     module (e.g. \"Parser::parse\" or \"Client.send\") to skip calls through \
+                     other types with the same method name."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Directory or file to search. Defaults to the session's working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: WHO_CALLS_TOOL_NAME.to_string(),
+        description: "Lists the call sites of a function or method in Rust, Python, JavaScript, \
+                      TypeScript, and Go files, with the definition each call is made from. \
+                      Calls on values (e.g. `parser.parse()`) are matched by method name, since \
+                      their type is not known."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["symbol".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_what_does_this_call_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some("Path to the source file defining the symbol.".to_string()),
+            },
+        ),
+        (
+            "symbol".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Function or method whose calls to list, optionally qualified by its \
+                     enclosing definitions (e.g. \"Parser::parse\")."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: WHAT_DOES_THIS_CALL_TOOL_NAME.to_string(),
+        description: "Lists the functions and methods a definition calls, each once with the \
+                      line of its first call."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string(), "symbol".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_run_tests_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "filter".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Only run tests whose names match this filter, as understood by the \
+                     framework (e.g. a substring for cargo test, a `-k` expression for pytest)."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "framework".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "One of \"cargo\", \"pytest\", \"jest\", or \"go\". Detected from the \
+                     project's manifests when omitted."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "workdir".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Project directory to run the tests in. Defaults to the session's working \
+                     directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some("The timeout for the test run in milliseconds.".to_string()),
+            },
+        ),
+        (
+            "coverage".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Also collect coverage (cargo-llvm-cov, pytest-cov, jest, or go test \
+                     -coverprofile) and report which lines changed since HEAD no test executed."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "run_tests".to_string(),
+        description: "Runs the tests covering the workspace's uncommitted changes (the crates, \
+                      packages, or test files that own the changed files), or the whole suite \
+                      when none can be mapped. Returns a JSON report with the outcome of each \
+                      test case, followed by the command output."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_check_build_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "build_system".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "One of \"cargo\", \"tsc\", or \"mypy\". Detected from the project's \
+                     manifests when omitted."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "workdir".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Project directory to check. Defaults to the session's working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some("The timeout for the check in milliseconds.".to_string()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "check_build".to_string(),
+        description: "Type-checks the code affected by the workspace's uncommitted changes \
+                      (cargo check on the owning crates, tsc --noEmit, or mypy on the changed \
+                      files) and returns a JSON report of diagnostics with file, line, column, \
+                      severity, and message."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_summarize_profile_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Profile to read: a perf.data file, `perf script` output, collapsed stacks, \
+                     a flame graph SVG, or a pprof protobuf."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "format".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "One of \"perf_data\", \"perf_script\", \"folded\", \"flamegraph_svg\", or \
+                     \"pprof\". Detected from the file's contents when omitted."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "focus".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Only count stacks with a frame whose name contains this text, to drill \
+                     into one code path."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Most functions listed in each ranking (defaults to 20, at most 100)."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "summarize_profile".to_string(),
+        description: "Summarizes a CPU profile recorded with perf, cargo flamegraph, or pprof as \
+                      JSON: the functions with the most samples in their own code and including \
+                      their callees, with percentages of the total, and the hottest stacks. Use \
+                      it to decide what to optimize from a measured profile."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_analyze_log_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "path".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Log file to analyze; files ending in .gz are decompressed.".to_string(),
+                ),
+            },
+        ),
+        (
+            "pattern".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Regex to count and show the first matching lines of, e.g. a request id."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "Most warning and error clusters returned (defaults to 20, at most 100)."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "analyze_log".to_string(),
+        description: "Reads a log file of any size without loading it into context and returns \
+                      a compact JSON report: line counts by level, the first and last \
+                      timestamps, warnings and errors grouped by message template with counts \
+                      and line numbers, and deduplicated stack traces. Use it before reading \
+                      any part of a large log, then read_file around the reported lines."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["path".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_symbolicate_backtrace_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "binary".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Build artifact with debug info that the addresses belong to: an \
+                     executable, shared library, or .dSYM bundle."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "backtrace".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Raw backtrace text with hex addresses, one frame per line.".to_string(),
+                ),
+            },
+        ),
+        (
+            "core_dump".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Core dump of the crashed process, read with gdb or lldb. Pass either this \
+                     or `backtrace`."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "load_address".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Hex address `binary` was loaded at in the crashed process, needed to \
+                     resolve absolute addresses of position-independent binaries."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "symbolicate_backtrace".to_string(),
+        description: "Symbolicates a raw backtrace or core dump against a build artifact and \
+                      returns each frame with its function (inlined frames included), \
+                      file:line, and the source line when the file is in the workspace."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["binary".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_cloud_cli_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "cli".to_string(),
+            JsonSchema::String {
+                description: Some("One of `kubectl`, `aws`, or `gcloud`.".to_string()),
+            },
+        ),
+        (
+            "args".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some(
+                    "Arguments after the program name, starting with the subcommand, e.g. \
+                     [\"logs\", \"api-0\", \"-n\", \"prod\"]."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "cloud_cli".to_string(),
+        description: "Runs a kubectl, aws, or gcloud command with the user's credentials. \
+                      Read-only commands (get, describe, logs, list, ...) run directly, with log \
+                      reads capped at 2000 lines; commands that print credentials or secrets \
+                      never run. Other commands run only when the user has allowed them in \
+                      config and approves the call."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["cli".to_string(), "args".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_terraform_plan_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "dir".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Terraform working directory. Defaults to the turn's working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "var_files".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some("Variable files passed as `-var-file`.".to_string()),
+            },
+        ),
+        (
+            "targets".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some(
+                    "Resource addresses to limit the plan to, passed as `-target`.".to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "terraform_plan".to_string(),
+        description: "Runs `terraform plan` and saves the plan. Returns its plan_id and the \
+                      resource changes grouped as destructive, modifying, or additive, with a \
+                      review finding for each resource the plan destroys or replaces."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_terraform_apply_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "dir".to_string(),
+            JsonSchema::String {
+                description: Some("Terraform working directory the plan was made in.".to_string()),
+            },
+        ),
+        (
+            "plan_id".to_string(),
+            JsonSchema::String {
+                description: Some("plan_id returned by terraform_plan.".to_string()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "terraform_apply".to_string(),
+        description: "Applies a plan saved by terraform_plan, exactly as planned, after the \
+                      user approves it. Never apply Terraform changes any other way."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["plan_id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_build_image_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "context".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Build context directory. Defaults to the turn's working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "dockerfile".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Dockerfile or Containerfile to build, relative to the working directory."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "tag".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Tag for the image. A unique `codex-build:` tag is used when omitted."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "target".to_string(),
+            JsonSchema::String {
+                description: Some("Build stage to stop at.".to_string()),
+            },
+        ),
+        (
+            "build_args".to_string(),
+            JsonSchema::Object {
+                properties: BTreeMap::new(),
+                required: None,
+                additional_properties: Some(JsonSchema::String { description: None }.into()),
+            },
+        ),
+        (
+            "engine".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "`docker` or `podman`. Defaults to whichever is installed, docker first."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "build_image".to_string(),
+        description: "Builds a container image with docker or podman. Returns the image tag, \
+                      step and layer cache counts, the slowest steps, and on failure the \
+                      errors and the end of the build log. Verify the image with run_in_image."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: None,
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_run_in_image_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "image".to_string(),
+            JsonSchema::String {
+                description: Some("Image to run, e.g. the tag build_image returned.".to_string()),
+            },
+        ),
+        (
+            "command".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some("Command and arguments to run in the image.".to_string()),
+            },
+        ),
+        (
+            "mount_workspace".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Mount the working directory at /workspace and run there. Defaults to false."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "network".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Give the container network access. Defaults to false.".to_string(),
+                ),
+            },
+        ),
+        (
+            "timeout_ms".to_string(),
+            JsonSchema::Number {
+                description: Some("Timeout in milliseconds. Defaults to 10 minutes.".to_string()),
+            },
+        ),
+        (
+            "engine".to_string(),
+            JsonSchema::String {
+                description: Some("`docker` or `podman`, as for build_image.".to_string()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "run_in_image".to_string(),
+        description: "Runs a command in a container image, removing the container afterwards, \
+                      and returns its exit code and output."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["image".to_string(), "command".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_package_info_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "ecosystem".to_string(),
+            JsonSchema::String {
+                description: Some("One of `crates.io`, `npm`, or `pypi`.".to_string()),
+            },
+        ),
+        (
+            "name".to_string(),
+            JsonSchema::String {
+                description: Some("Package name, e.g. `serde` or `@types/node`.".to_string()),
+            },
+        ),
+        (
+            "version".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Version to check for yanks, deprecation, and advisories. Defaults to the \
+                     latest version."
+                        .to_string(),
+                ),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "package_info".to_string(),
+        description: "Looks up a package on crates.io, npm, or PyPI and returns its latest \
+                      version, recent releases, whether the given version is yanked or \
+                      deprecated, and the security advisories affecting it. Use it instead of \
+                      recalling versions from memory when adding or upgrading dependencies."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["ecosystem".to_string(), "name".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
+fn create_scaffold_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "template".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Template pack to instantiate. Omit to list the available packs and their \
+                     variables."
+                        .to_string(),
                 ),
             },
         ),
         (
-            "sleep_after_ms".to_string(),
-            JsonSchema::Number {
+            "variables".to_string(),
+            JsonSchema::Object {
+                properties: BTreeMap::new(),
+                required: None,
+                additional_properties: Some(JsonSchema::String { description: None }.into()),
+            },
+        ),
+        (
+            "destination".to_string(),
+            JsonSchema::String {
                 description: Some(
-                    "Optional delay in milliseconds after completing the barrier".to_string(),
+                    "Directory to create the files in. Defaults to the session's working \
+                     directory."
+                        .to_string(),
                 ),
             },
         ),
         (
-            "barrier".to_string(),
-            JsonSchema::Object {
-                properties: barrier_properties,
-                required: Some(vec!["id".to_string(), "participants".to_string()]),
-                additional_properties: Some(false.into()),
+            "apply".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Create the files. Leave unset first to list the files the template \
+                     produces, then call again with `apply: true`."
+                        .to_string(),
+                ),
             },
         ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "test_sync_tool".to_string(),
-        description: "Internal synchronization helper used by Codex integration tests.".to_string(),
+        name: "scaffold".to_string(),
+        description: "Creates files from a user-defined template pack, filling in its \
+                      `{{variable}}` placeholders. Prefer it over writing boilerplate by hand \
+                      when a pack fits; the files are added as a patch, so they are reviewed \
+                      and sandboxed like any other edit."
+            .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
@@ -838,20 +1702,35 @@ fn create_test_sync_tool() -> ToolSpec {
     })
 }
 
-fn create_grep_files_tool() -> ToolSpec {
+fn create_replace_in_files_tool() -> ToolSpec {
     let properties = BTreeMap::from([
         (
             "pattern".to_string(),
             JsonSchema::String {
-                description: Some("Regular expression pattern to search for.".to_string()),
+                description: Some(
+                    "Regular expression to replace, in ripgrep syntax with `^` and `$` matching \
+                     at line boundaries, or the exact text when `literal` is set."
+                        .to_string(),
+                ),
             },
         ),
         (
-            "include".to_string(),
+            "replacement".to_string(),
             JsonSchema::String {
                 description: Some(
-                    "Optional glob that limits which files are searched (e.g. \"*.rs\" or \
-                     \"*.{ts,tsx}\")."
+                    "Replacement text. Unless `literal` is set, `$1` or `${name}` insert capture \
+                     groups."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "include".to_string(),
+            JsonSchema::Array {
+                items: Box::new(JsonSchema::String { description: None }),
+                description: Some(
+                    "Optional globs that limit which files are changed (e.g. \"*.rs\" or \
+                     \"src/**/*.{ts,tsx}\")."
                         .to_string(),
                 ),
             },
@@ -860,30 +1739,53 @@ fn create_grep_files_tool() -> ToolSpec {
             "path".to_string(),
             JsonSchema::String {
                 description: Some(
-                    "Directory or file path to search. Defaults to the session's working directory."
+                    "Directory or file to search. Defaults to the session's working directory."
                         .to_string(),
                 ),
             },
         ),
         (
-            "limit".to_string(),
-            JsonSchema::Number {
+            "literal".to_string(),
+            JsonSchema::Boolean {
                 description: Some(
-                    "Maximum number of file paths to return (defaults to 100).".to_string(),
+                    "Treat `pattern` and `replacement` as plain text (defaults to false)."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "apply".to_string(),
+            JsonSchema::Boolean {
+                description: Some(
+                    "Apply the replacement. Leave unset first to get a preview diff, then call \
+                     again with `apply: true` and the preview's `preview_id` once the preview \
+                     looks right."
+                        .to_string(),
+                ),
+            },
+        ),
+        (
+            "preview_id".to_string(),
+            JsonSchema::String {
+                description: Some(
+                    "Id of the preview being applied. Nothing is changed when the replacement \
+                     no longer matches that preview."
+                        .to_string(),
                 ),
             },
         ),
     ]);
 
     ToolSpec::Function(ResponsesApiTool {
-        name: "grep_files".to_string(),
-        description: "Finds files whose contents match the pattern and lists them by modification \
-                      time."
+        name: "replace_in_files".to_string(),
+        description: "Replaces a regex or literal across many files at once. Returns a preview \
+                      diff unless `apply` is true; applying goes through the same approval as \
+                      apply_patch."
             .to_string(),
         strict: false,
         parameters: JsonSchema::Object {
             properties,
-            required: Some(vec!["pattern".to_string()]),
+            required: Some(vec!["pattern".to_string(), "replacement".to_string()]),
             additional_properties: Some(false.into()),
         },
     })
@@ -1078,6 +1980,43 @@ fn create_list_dir_tool() -> ToolSpec {
     })
 }
 
+fn create_read_attachment_tool() -> ToolSpec {
+    let properties = BTreeMap::from([
+        (
+            "id".to_string(),
+            JsonSchema::String {
+                description: Some("ID of the attachment, e.g. `att-1`.".to_string()),
+            },
+        ),
+        (
+            "offset".to_string(),
+            JsonSchema::Number {
+                description: Some(
+                    "The line number to start reading from. Must be 1 or greater.".to_string(),
+                ),
+            },
+        ),
+        (
+            "limit".to_string(),
+            JsonSchema::Number {
+                description: Some("The maximum number of lines to return.".to_string()),
+            },
+        ),
+    ]);
+
+    ToolSpec::Function(ResponsesApiTool {
+        name: "read_attachment".to_string(),
+        description: "Reads a range of lines from an attachment added to the conversation, or views an image attachment. Attachments too large to include up front are shown as summaries with their ID."
+            .to_string(),
+        strict: false,
+        parameters: JsonSchema::Object {
+            properties,
+            required: Some(vec!["id".to_string()]),
+            additional_properties: Some(false.into()),
+        },
+    })
+}
+
 fn create_js_repl_tool() -> ToolSpec {
     // Keep JS input freeform, but block the most common malformed payload shapes
     // (JSON wrappers, quoted strings, and markdown fences) before they reach the
@@ -1429,7 +2368,12 @@ pub(crate) fn build_specs(
     app_tools: Option<HashMap<String, ToolInfo>>,
     dynamic_tools: &[DynamicToolSpec],
 ) -> ToolRegistryBuilder {
+    use crate::tools::handlers::AnalyzeLogHandler;
     use crate::tools::handlers::ApplyPatchHandler;
+    use crate::tools::handlers::BuildImageHandler;
+    use crate::tools::handlers::CallGraphHandler;
+    use crate::tools::handlers::CheckBuildHandler;
+    use crate::tools::handlers::CloudCliHandler;
     use crate::tools::handlers::DynamicToolHandler;
     use crate::tools::handlers::GrepFilesHandler;
     use crate::tools::handlers::JsReplHandler;
@@ -1438,12 +2382,23 @@ pub(crate) fn build_specs(
     use crate::tools::handlers::McpHandler;
     use crate::tools::handlers::McpResourceHandler;
     use crate::tools::handlers::MultiAgentHandler;
+    use crate::tools::handlers::PackageInfoHandler;
     use crate::tools::handlers::PlanHandler;
+    use crate::tools::handlers::ReadAttachmentHandler;
     use crate::tools::handlers::ReadFileHandler;
+    use crate::tools::handlers::ReadSymbolHandler;
+    use crate::tools::handlers::ReplaceInFilesHandler;
     use crate::tools::handlers::RequestUserInputHandler;
+    use crate::tools::handlers::RunInImageHandler;
+    use crate::tools::handlers::RunTestsHandler;
+    use crate::tools::handlers::ScaffoldHandler;
     use crate::tools::handlers::SearchToolBm25Handler;
     use crate::tools::handlers::ShellCommandHandler;
     use crate::tools::handlers::ShellHandler;
+    use crate::tools::handlers::SummarizeProfileHandler;
+    use crate::tools::handlers::SymbolicateBacktraceHandler;
+    use crate::tools::handlers::TerraformApplyHandler;
+    use crate::tools::handlers::TerraformPlanHandler;
     use crate::tools::handlers::TestSyncHandler;
     use crate::tools::handlers::UnifiedExecHandler;
     use crate::tools::handlers::ViewImageHandler;
@@ -1457,7 +2412,8 @@ pub(crate) fn build_specs(
     let apply_patch_handler = Arc::new(ApplyPatchHandler);
     let dynamic_tool_handler = Arc::new(DynamicToolHandler);
     let view_image_handler = Arc::new(ViewImageHandler);
-    let mcp_handler = Arc::new(McpHandler);
+    let mcp_handler = Arc::new(McpHandler { read_only: false });
+    let read_only_mcp_handler = Arc::new(McpHandler { read_only: true });
     let mcp_resource_handler = Arc::new(McpResourceHandler);
     let shell_command_handler = Arc::new(ShellCommandHandler);
     let request_user_input_handler = Arc::new(RequestUserInputHandler);
@@ -1561,6 +2517,87 @@ pub(crate) fn build_specs(
         builder.register_handler("read_file", read_file_handler);
     }
 
+    if config
+        .experimental_supported_tools
+        .contains(&"read_symbol".to_string())
+    {
+        let read_symbol_handler = Arc::new(ReadSymbolHandler);
+        builder.push_spec_with_parallel_support(create_read_symbol_tool(), true);
+        builder.register_handler("read_symbol", read_symbol_handler);
+    }
+
+    let who_calls = config
+        .experimental_supported_tools
+        .contains(&WHO_CALLS_TOOL_NAME.to_string());
+    let what_does_this_call = config
+        .experimental_supported_tools
+        .contains(&WHAT_DOES_THIS_CALL_TOOL_NAME.to_string());
+    if who_calls || what_does_this_call {
+        let call_graph_handler = Arc::new(CallGraphHandler);
+        if who_calls {
+            builder.push_spec_with_parallel_support(create_who_calls_tool(), true);
+            builder.register_handler(WHO_CALLS_TOOL_NAME, call_graph_handler.clone());
+        }
+        if what_does_this_call {
+            builder.push_spec_with_parallel_support(create_what_does_this_call_tool(), true);
+            builder.register_handler(WHAT_DOES_THIS_CALL_TOOL_NAME, call_graph_handler);
+        }
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"replace_in_files".to_string())
+    {
+        let replace_in_files_handler = Arc::new(ReplaceInFilesHandler);
+        builder.push_spec(create_replace_in_files_tool());
+        builder.register_handler("replace_in_files", replace_in_files_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"run_tests".to_string())
+    {
+        let run_tests_handler = Arc::new(RunTestsHandler);
+        builder.push_spec(create_run_tests_tool());
+        builder.register_handler("run_tests", run_tests_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"check_build".to_string())
+    {
+        let check_build_handler = Arc::new(CheckBuildHandler);
+        builder.push_spec(create_check_build_tool());
+        builder.register_handler("check_build", check_build_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"summarize_profile".to_string())
+    {
+        let summarize_profile_handler = Arc::new(SummarizeProfileHandler);
+        builder.push_spec_with_parallel_support(create_summarize_profile_tool(), true);
+        builder.register_handler("summarize_profile", summarize_profile_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"analyze_log".to_string())
+    {
+        let analyze_log_handler = Arc::new(AnalyzeLogHandler);
+        builder.push_spec_with_parallel_support(create_analyze_log_tool(), true);
+        builder.register_handler("analyze_log", analyze_log_handler);
+    }
+
+    if config
+        .experimental_supported_tools
+        .contains(&"symbolicate_backtrace".to_string())
+    {
+        let symbolicate_backtrace_handler = Arc::new(SymbolicateBacktraceHandler);
+        builder.push_spec_with_parallel_support(create_symbolicate_backtrace_tool(), true);
+        builder.register_handler("symbolicate_backtrace", symbolicate_backtrace_handler);
+    }
+
     if config
         .experimental_supported_tools
         .iter()
@@ -1597,6 +2634,42 @@ pub(crate) fn build_specs(
     builder.push_spec_with_parallel_support(create_view_image_tool(), true);
     builder.register_handler("view_image", view_image_handler);
 
+    if config.read_attachment_tool {
+        let read_attachment_handler = Arc::new(ReadAttachmentHandler);
+        builder.push_spec_with_parallel_support(create_read_attachment_tool(), true);
+        builder.register_handler("read_attachment", read_attachment_handler);
+    }
+
+    if config.cloud_cli_tool {
+        let cloud_cli_handler = Arc::new(CloudCliHandler);
+        builder.push_spec(create_cloud_cli_tool());
+        builder.register_handler("cloud_cli", cloud_cli_handler);
+    }
+
+    if config.terraform_tools {
+        builder.push_spec(create_terraform_plan_tool());
+        builder.push_spec(create_terraform_apply_tool());
+        builder.register_handler("terraform_plan", Arc::new(TerraformPlanHandler));
+        builder.register_handler("terraform_apply", Arc::new(TerraformApplyHandler));
+    }
+
+    if config.container_image_tools {
+        builder.push_spec(create_build_image_tool());
+        builder.push_spec(create_run_in_image_tool());
+        builder.register_handler("build_image", Arc::new(BuildImageHandler));
+        builder.register_handler("run_in_image", Arc::new(RunInImageHandler));
+    }
+
+    if config.package_info_tool {
+        builder.push_spec_with_parallel_support(create_package_info_tool(), true);
+        builder.register_handler("package_info", Arc::new(PackageInfoHandler));
+    }
+
+    if config.scaffold_tool {
+        builder.push_spec(create_scaffold_tool());
+        builder.register_handler("scaffold", Arc::new(ScaffoldHandler));
+    }
+
     if config.collab_tools {
         let multi_agent_handler = Arc::new(MultiAgentHandler);
         builder.push_spec(create_spawn_agent_tool(config));
@@ -1616,10 +2689,23 @@ pub(crate) fn build_specs(
         entries.sort_by(|a, b| a.0.cmp(&b.0));
 
         for (name, tool) in entries.into_iter() {
+            let read_only = tool
+                .annotations
+                .as_ref()
+                .and_then(|annotations| annotations.read_only_hint)
+                == Some(true);
+            if config.read_only && !read_only {
+                continue;
+            }
             match mcp_tool_to_openai_tool(name.clone(), tool.clone()) {
                 Ok(converted_tool) => {
                     builder.push_spec(ToolSpec::Function(converted_tool));
-                    builder.register_handler(name, mcp_handler.clone());
+                    let handler = if read_only {
+                        read_only_mcp_handler.clone()
+                    } else {
+                        mcp_handler.clone()
+                    };
+                    builder.register_handler(name, handler);
                 }
                 Err(e) => {
                     tracing::error!("Failed to convert {name:?} MCP tool to OpenAI tool: {e:?}");
@@ -1628,7 +2714,7 @@ pub(crate) fn build_specs(
         }
     }
 
-    if !dynamic_tools.is_empty() {
+    if !config.read_only && !dynamic_tools.is_empty() {
         for tool in dynamic_tools {
             match dynamic_tool_to_openai_tool(tool) {
                 Ok(converted_tool) => {
@@ -1645,6 +2731,14 @@ pub(crate) fn build_specs(
         }
     }
 
+    if !config.disabled_tools.is_empty() {
+        builder.remove_tools(|name| {
+            config.disabled_tools.contains(name)
+                || split_qualified_tool_name(name)
+                    .is_some_and(|(server, _)| config.disabled_tools.contains(&server))
+        });
+    }
+
     builder
 }
 
@@ -1887,6 +2981,155 @@ mod tests {
         );
     }
 
+    #[test]
+    fn read_only_sessions_offer_no_tools_that_modify_anything() {
+        let config = test_config();
+        let model_info =
+            ModelsManager::construct_model_info_offline_for_tests("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        for spec in crate::features::FEATURES {
+            features.enable(spec.id);
+        }
+        let mut tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        });
+        tools_config.experimental_supported_tools = [
+            "analyze_log",
+            "check_build",
+            "grep_files",
+            "list_dir",
+            "read_file",
+            "read_symbol",
+            "replace_in_files",
+            "run_tests",
+            "summarize_profile",
+            "symbolicate_backtrace",
+            WHAT_DOES_THIS_CALL_TOOL_NAME,
+            WHO_CALLS_TOOL_NAME,
+        ]
+        .map(str::to_string)
+        .to_vec();
+        let tools_config = tools_config.with_read_only(true);
+        let mut lookup = mcp_tool("lookup", "Look something up", json!({"type": "object"}));
+        lookup.annotations = Some(rmcp::model::ToolAnnotations {
+            destructive_hint: None,
+            idempotent_hint: None,
+            open_world_hint: None,
+            read_only_hint: Some(true),
+            title: None,
+        });
+        let mcp_tools = HashMap::from([
+            ("server/lookup".to_string(), lookup),
+            (
+                "server/deploy".to_string(),
+                mcp_tool("deploy", "Deploy", json!({"type": "object"})),
+            ),
+        ]);
+        let dynamic_tools = [DynamicToolSpec {
+            name: "edit_ticket".to_string(),
+            description: "Edit a ticket".to_string(),
+            input_schema: json!({"type": "object"}),
+        }];
+
+        let (tools, _) = build_specs(&tools_config, Some(mcp_tools), None, &dynamic_tools).build();
+
+        let mut names: Vec<&str> = tools.iter().map(|tool| tool_name(&tool.spec)).collect();
+        names.sort_unstable();
+        assert_eq!(
+            names,
+            vec![
+                "analyze_log",
+                "grep_files",
+                "list_dir",
+                "list_mcp_resource_templates",
+                "list_mcp_resources",
+                "package_info",
+                "read_file",
+                "read_mcp_resource",
+                "read_symbol",
+                "request_user_input",
+                "server/lookup",
+                "update_plan",
+                "view_image",
+                "web_search",
+                "what_does_this_call",
+                "who_calls",
+            ]
+        );
+    }
+
+    #[test]
+    fn patch_artifact_sessions_offer_no_tools_that_bypass_the_sandbox() {
+        let config = test_config();
+        let model_info =
+            ModelsManager::construct_model_info_offline_for_tests("gpt-5-codex", &config);
+        let mut features = Features::with_defaults();
+        for spec in crate::features::FEATURES {
+            features.enable(spec.id);
+        }
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &features,
+            web_search_mode: Some(WebSearchMode::Cached),
+        })
+        .with_patch_artifact(true);
+
+        let (tools, _) = build_specs(&tools_config, None, None, &[]).build();
+
+        let names: Vec<&str> = tools.iter().map(|tool| tool_name(&tool.spec)).collect();
+        for excluded in [
+            "build_image",
+            "cloud_cli",
+            "js_repl",
+            "js_repl_reset",
+            "run_in_image",
+            "terraform_apply",
+            "terraform_plan",
+        ] {
+            assert!(
+                !names.contains(&excluded),
+                "{excluded} should not be offered: {names:?}"
+            );
+        }
+        assert!(names.contains(&"apply_patch"), "{names:?}");
+    }
+
+    #[test]
+    fn disabled_tools_and_mcp_servers_are_left_out() {
+        let config = test_config();
+        let model_info =
+            ModelsManager::construct_model_info_offline_for_tests("gpt-5-codex", &config);
+        let tools_config = ToolsConfig::new(&ToolsConfigParams {
+            model_info: &model_info,
+            features: &Features::with_defaults(),
+            web_search_mode: Some(WebSearchMode::Cached),
+        })
+        .with_disabled_tools(BTreeSet::from([
+            "web_search".to_string(),
+            "noisy".to_string(),
+        ]));
+        let mcp_tools = HashMap::from([
+            (
+                "mcp__docs__search".to_string(),
+                mcp_tool("search", "Search docs", json!({"type": "object"})),
+            ),
+            (
+                "mcp__noisy__ping".to_string(),
+                mcp_tool("ping", "Ping", json!({"type": "object"})),
+            ),
+        ]);
+
+        let (tools, registry) = build_specs(&tools_config, Some(mcp_tools), None, &[]).build();
+
+        let names: Vec<&str> = tools.iter().map(|tool| tool_name(&tool.spec)).collect();
+        assert!(names.contains(&"mcp__docs__search"));
+        assert!(!names.contains(&"mcp__noisy__ping"));
+        assert!(!names.contains(&"web_search"));
+        assert!(registry.handler("mcp__noisy__ping").is_none());
+    }
+
     #[test]
     fn request_user_input_requires_collaboration_modes_feature() {
         let config = test_config();
@@ -2341,6 +3584,15 @@ mod tests {
             "read_file".to_string(),
             "grep_files".to_string(),
             "list_dir".to_string(),
+            "read_symbol".to_string(),
+            "replace_in_files".to_string(),
+            "run_tests".to_string(),
+            "check_build".to_string(),
+            "summarize_profile".to_string(),
+            "analyze_log".to_string(),
+            "symbolicate_backtrace".to_string(),
+            "who_calls".to_string(),
+            "what_does_this_call".to_string(),
         ];
         let features = Features::with_defaults();
         let tools_config = ToolsConfig::new(&ToolsConfigParams {
@@ -2366,6 +3618,51 @@ mod tests {
                 .any(|tool| tool_name(&tool.spec) == "grep_files")
         );
         assert!(tools.iter().any(|tool| tool_name(&tool.spec) == "list_dir"));
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "read_symbol")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "replace_in_files")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "run_tests")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "check_build")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "summarize_profile")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "analyze_log")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "symbolicate_backtrace")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "who_calls")
+        );
+        assert!(
+            tools
+                .iter()
+                .any(|tool| tool_name(&tool.spec) == "what_does_this_call")
+        );
     }
 
     #[test]
@@ -2540,6 +3837,7 @@ mod tests {
                         ),
                         connector_id: Some("calendar".to_string()),
                         connector_name: Some("Calendar".to_string()),
+                        connector_version: None,
                     },
                 ),
                 (
@@ -2550,6 +3848,7 @@ mod tests {
                         tool: mcp_tool("echo", "Echo", serde_json::json!({"type": "object"})),
                         connector_id: None,
                         connector_name: None,
+                        connector_version: None,
                     },
                 ),
             ])),
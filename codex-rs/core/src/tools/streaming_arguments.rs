@@ -0,0 +1,295 @@
+//! Incremental parsing of function call arguments while they stream.
+//!
+//! Arguments arrive as JSON fragments before the call's `OutputItemDone`.
+//! [`PartialJson`] scans each fragment once, tracking open strings and
+//! containers, so that:
+//!
+//! - malformed arguments are reported as soon as they can no longer form a
+//!   JSON object, instead of after the model finishes a large payload;
+//! - a best-effort value of the arguments so far can be shown to clients
+//!   ([`ToolCallArgumentsPreviewEvent`]), e.g. a long command as it is
+//!   written;
+//! - the completed call is known without re-parsing the whole buffer on every
+//!   fragment (see [`crate::tools::prefetch`]).
+
+use std::collections::HashMap;
+
+use codex_protocol::models::ResponseItem;
+use codex_protocol::protocol::ToolCallArgumentsPreviewEvent;
+use serde_json::Value;
+
+/// Minimum arguments received since the last preview before another one is
+/// sent. The interval also grows with the arguments, so previewing a huge
+/// payload stays linear.
+const PREVIEW_INTERVAL_BYTES: usize = 256;
+
+/// A JSON object received in fragments.
+#[derive(Debug, Default)]
+pub(crate) struct PartialJson {
+    buffer: String,
+    /// Open containers, innermost last, as their closing character and the
+    /// offset just past their opening character.
+    open: Vec<(char, usize)>,
+    in_string: bool,
+    escaped: bool,
+    /// Offset of the last member separator and the nesting depth it was at,
+    /// while that container is still open.
+    last_comma: Option<(usize, usize)>,
+    complete: bool,
+}
+
+impl PartialJson {
+    /// Appends `delta`. Fails once the input can no longer be the prefix of a
+    /// JSON object.
+    pub(crate) fn push(&mut self, delta: &str) -> Result<(), String> {
+        for ch in delta.chars() {
+            let offset = self.buffer.len();
+            self.buffer.push(ch);
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if ch == '\\' {
+                    self.escaped = true;
+                } else if ch == '"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+            if ch.is_whitespace() {
+                continue;
+            }
+            if self.complete {
+                return Err(format!("unexpected `{ch}` after the end of the arguments"));
+            }
+            if self.open.is_empty() && ch != '{' {
+                return Err("arguments must be a JSON object".to_string());
+            }
+            match ch {
+                '"' => self.in_string = true,
+                '{' => self.open.push(('}', offset + 1)),
+                '[' => self.open.push((']', offset + 1)),
+                '}' | ']' => {
+                    if self.open.pop().map(|(closer, _)| closer) != Some(ch) {
+                        return Err(format!("unexpected `{ch}`"));
+                    }
+                    if let Some((_, depth)) = self.last_comma
+                        && depth > self.open.len()
+                    {
+                        self.last_comma = None;
+                    }
+                    self.complete = self.open.is_empty();
+                }
+                ',' => self.last_comma = Some((offset, self.open.len())),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the top-level object has been closed.
+    pub(crate) fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    pub(crate) fn received_bytes(&self) -> usize {
+        self.buffer.len()
+    }
+
+    pub(crate) fn into_string(self) -> String {
+        self.buffer
+    }
+
+    /// The arguments so far as a value: an unterminated string is closed,
+    /// a trailing incomplete member is dropped, and open containers are
+    /// closed. `None` before the object has started.
+    pub(crate) fn value(&self) -> Option<Value> {
+        if self.complete {
+            return serde_json::from_str(&self.buffer).ok();
+        }
+        let (_, innermost) = *self.open.last()?;
+        let closers = |depth: usize| {
+            self.open[..depth]
+                .iter()
+                .rev()
+                .map(|(closer, _)| *closer)
+                .collect::<String>()
+        };
+
+        let mut closed = self.buffer.clone();
+        if self.in_string {
+            if self.escaped {
+                closed.pop();
+            }
+            closed.push('"');
+        }
+        closed.push_str(&closers(self.open.len()));
+        let mut candidates = vec![closed];
+        if let Some((offset, depth)) = self.last_comma {
+            candidates.push(format!("{}{}", &self.buffer[..offset], closers(depth)));
+        }
+        candidates.push(format!(
+            "{}{}",
+            &self.buffer[..innermost],
+            closers(self.open.len())
+        ));
+        candidates
+            .iter()
+            .find_map(|candidate| serde_json::from_str(candidate).ok())
+    }
+}
+
+#[derive(Debug)]
+struct StreamingCall {
+    name: String,
+    call_id: String,
+    arguments: PartialJson,
+    /// Length of the arguments when the last preview was sent.
+    previewed_len: usize,
+}
+
+/// Function calls whose arguments are still streaming, keyed by item id.
+#[derive(Debug, Default)]
+pub(crate) struct StreamingToolCalls {
+    calls: HashMap<String, StreamingCall>,
+}
+
+impl StreamingToolCalls {
+    /// Starts tracking `item` if it is a function call.
+    pub(crate) fn item_added(&mut self, item: &ResponseItem) {
+        if let ResponseItem::FunctionCall {
+            id: Some(id),
+            name,
+            arguments,
+            call_id,
+        } = item
+        {
+            let mut partial = PartialJson::default();
+            if partial.push(arguments).is_ok() {
+                self.calls.insert(
+                    id.clone(),
+                    StreamingCall {
+                        name: name.clone(),
+                        call_id: call_id.clone(),
+                        arguments: partial,
+                        previewed_len: 0,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Appends `delta` to the arguments of `item_id`, returning a preview of
+    /// them when enough has arrived since the last one, or the error once
+    /// they turn out to be malformed. A call stops being tracked once its
+    /// arguments complete or turn out to be malformed.
+    pub(crate) fn arguments_delta(
+        &mut self,
+        item_id: &str,
+        delta: &str,
+    ) -> Option<ToolCallArgumentsPreviewEvent> {
+        let call = self.calls.get_mut(item_id)?;
+        let result = call.arguments.push(delta);
+        if result.is_err() || call.arguments.is_complete() {
+            let StreamingCall { name, call_id, .. } = self.calls.remove(item_id)?;
+            return result.err().map(|error| ToolCallArgumentsPreviewEvent {
+                call_id,
+                tool_name: name,
+                arguments: None,
+                error: Some(error),
+            });
+        }
+
+        if call.arguments.received_bytes()
+            < call.previewed_len + PREVIEW_INTERVAL_BYTES.max(call.previewed_len)
+        {
+            return None;
+        }
+        let arguments = call.arguments.value()?;
+        call.previewed_len = call.arguments.received_bytes();
+        Some(ToolCallArgumentsPreviewEvent {
+            call_id: call.call_id.clone(),
+            tool_name: call.name.clone(),
+            arguments: Some(arguments),
+            error: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    fn partial(input: &str) -> PartialJson {
+        let mut partial = PartialJson::default();
+        partial.push(input).expect("valid prefix");
+        partial
+    }
+
+    #[test]
+    fn partial_values_close_open_strings_and_containers() {
+        assert_eq!(partial("").value(), None);
+        assert_eq!(partial(r#"{"cm"#).value(), Some(json!({})));
+        assert_eq!(
+            partial(r#"{"command":["bash","-lc","echo hi && ls -"#).value(),
+            Some(json!({"command": ["bash", "-lc", "echo hi && ls -"]}))
+        );
+        assert_eq!(
+            partial(r#"{"path":"a\"#).value(),
+            Some(json!({"path": "a"}))
+        );
+        assert_eq!(
+            partial(r#"{"a":{"b":1},"timeout_ms":"#).value(),
+            Some(json!({"a": {"b": 1}}))
+        );
+        assert_eq!(
+            partial(r#"{"a":1,"b":[1,tr"#).value(),
+            Some(json!({"a": 1, "b": [1]}))
+        );
+    }
+
+    #[test]
+    fn malformed_arguments_fail_early() {
+        assert_eq!(
+            PartialJson::default().push("[1, 2]"),
+            Err("arguments must be a JSON object".to_string())
+        );
+        assert_eq!(
+            PartialJson::default().push(r#"{"a":[1}"#),
+            Err("unexpected `}`".to_string())
+        );
+        assert_eq!(
+            PartialJson::default().push(r#"{"a":1} x"#),
+            Err("unexpected `x` after the end of the arguments".to_string())
+        );
+    }
+
+    #[test]
+    fn returns_previews_until_the_arguments_complete() {
+        let mut calls = StreamingToolCalls::default();
+        calls.item_added(&ResponseItem::FunctionCall {
+            id: Some("fc_1".to_string()),
+            name: "shell".to_string(),
+            arguments: String::new(),
+            call_id: "call_1".to_string(),
+        });
+        let script = "x".repeat(PREVIEW_INTERVAL_BYTES);
+
+        assert_eq!(
+            calls.arguments_delta("fc_1", r#"{"command":["bash","-lc","#),
+            None
+        );
+        assert_eq!(
+            calls.arguments_delta("fc_1", &format!("\"{script}")),
+            Some(ToolCallArgumentsPreviewEvent {
+                call_id: "call_1".to_string(),
+                tool_name: "shell".to_string(),
+                arguments: Some(json!({"command": ["bash", "-lc", script]})),
+                error: None,
+            })
+        );
+        assert_eq!(calls.arguments_delta("fc_1", r#""]}"#), None);
+        assert_eq!(calls.arguments_delta("fc_1", "}"), None);
+    }
+}
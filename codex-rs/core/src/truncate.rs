@@ -1,13 +1,72 @@
 //! Utilities for truncating large chunks of output while preserving a prefix
 //! and suffix on UTF-8 boundaries, and helpers for line/token‑based truncation
 //! used across the core crate.
+//!
+//! What survives truncation depends on the content: JSON keeps its structure
+//! with long strings and arrays elided, unified diffs keep their file and
+//! hunk headers, source code keeps its imports and signatures, and anything
+//! else (logs included) keeps its head and tail.
 
 use codex_protocol::models::FunctionCallOutputContentItem;
 use codex_protocol::openai_models::TruncationMode;
 use codex_protocol::openai_models::TruncationPolicyConfig;
 use codex_protocol::protocol::TruncationPolicy as ProtocolTruncationPolicy;
+use serde_json::Value;
 
 const APPROX_BYTES_PER_TOKEN: usize = 4;
+/// `(max string bytes, max array items)` tried in turn until elided JSON fits.
+const JSON_ELISION_LEVELS: [(usize, usize); 4] = [(512, 20), (128, 5), (32, 2), (8, 1)];
+/// Room left for the marker of each elided run of diff or code lines.
+const LINE_MARKER_RESERVE_BYTES: usize = 32;
+/// Modifiers skipped before looking for a signature keyword.
+const SIGNATURE_MODIFIERS: [&str; 13] = [
+    "pub(crate) ",
+    "pub(super) ",
+    "pub ",
+    "export ",
+    "default ",
+    "public ",
+    "private ",
+    "protected ",
+    "static ",
+    "abstract ",
+    "async ",
+    "unsafe ",
+    "const ",
+];
+const SIGNATURE_KEYWORDS: [&str; 13] = [
+    "fn ",
+    "def ",
+    "class ",
+    "struct ",
+    "enum ",
+    "trait ",
+    "impl ",
+    "impl<",
+    "interface ",
+    "function ",
+    "func ",
+    "type ",
+    "mod ",
+];
+const IMPORT_PREFIXES: [&str; 7] = [
+    "use ",
+    "pub use ",
+    "import ",
+    "from ",
+    "#include ",
+    "using ",
+    "package ",
+];
+const DIFF_HEADER_PREFIXES: [&str; 7] = [
+    "diff ",
+    "index ",
+    "--- a/",
+    "+++ b/",
+    "--- /dev/null",
+    "+++ /dev/null",
+    "@@ ",
+];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum TruncationPolicy {
@@ -204,6 +263,10 @@ fn truncate_with_byte_estimate(s: &str, policy: TruncationPolicy) -> String {
         return s.to_string();
     }
 
+    if let Some(truncated) = truncate_by_content_type(s, policy) {
+        return truncated;
+    }
+
     let total_bytes = s.len();
 
     let (left_budget, right_budget) = split_budget(max_bytes);
@@ -218,6 +281,204 @@ fn truncate_with_byte_estimate(s: &str, policy: TruncationPolicy) -> String {
     assemble_truncated_output(left, right, &marker)
 }
 
+/// Truncates JSON, diffs and code so their structure survives, or returns
+/// `None` for other content and when the structure alone is over budget.
+fn truncate_by_content_type(s: &str, policy: TruncationPolicy) -> Option<String> {
+    let trimmed = s.trim();
+    if (trimmed.starts_with('{') || trimmed.starts_with('['))
+        && let Ok(value) = serde_json::from_str::<Value>(trimmed)
+    {
+        let pretty = trimmed.contains('\n');
+        return JSON_ELISION_LEVELS
+            .iter()
+            .find_map(|&(max_string_bytes, max_array_items)| {
+                let elided = elide_json(&value, policy, max_string_bytes, max_array_items);
+                let rendered = if pretty {
+                    serde_json::to_string_pretty(&elided)
+                } else {
+                    serde_json::to_string(&elided)
+                }
+                .ok()?;
+                (rendered.len() <= policy.byte_budget()).then_some(rendered)
+            });
+    }
+
+    let lines: Vec<&str> = s.lines().collect();
+    if lines.iter().any(|line| line.starts_with("@@ "))
+        && lines
+            .iter()
+            .any(|line| line.starts_with("+++ ") || line.starts_with("diff "))
+    {
+        return keep_lines(&lines, policy, |line| {
+            DIFF_HEADER_PREFIXES
+                .iter()
+                .any(|prefix| line.starts_with(prefix))
+        });
+    }
+
+    let is_code_skeleton = |line: &str| {
+        let line = line.trim_start();
+        if IMPORT_PREFIXES
+            .iter()
+            .any(|prefix| line.starts_with(prefix))
+        {
+            return true;
+        }
+        let mut rest = line;
+        while let Some(modifier) = SIGNATURE_MODIFIERS
+            .iter()
+            .find(|modifier| rest.starts_with(*modifier))
+        {
+            rest = &rest[modifier.len()..];
+        }
+        SIGNATURE_KEYWORDS
+            .iter()
+            .any(|keyword| rest.starts_with(keyword))
+    };
+    let skeleton_lines = lines
+        .iter()
+        .copied()
+        .filter(|line| is_code_skeleton(line))
+        .count();
+    if skeleton_lines >= 3 && skeleton_lines * 20 >= lines.len() {
+        return keep_lines(&lines, policy, is_code_skeleton);
+    }
+    None
+}
+
+/// `value` with strings over `max_string_bytes` cut in the middle and arrays
+/// past `max_array_items` items cut short.
+fn elide_json(
+    value: &Value,
+    policy: TruncationPolicy,
+    max_string_bytes: usize,
+    max_array_items: usize,
+) -> Value {
+    match value {
+        Value::String(text) if text.len() > max_string_bytes => {
+            let (left_budget, right_budget) = split_budget(max_string_bytes);
+            let (removed_chars, left, right) = split_string(text, left_budget, right_budget);
+            let removed_bytes = text.len() - left.len() - right.len();
+            let marker = format_truncation_marker(
+                policy,
+                removed_units_for_source(policy, removed_bytes, removed_chars),
+            );
+            Value::String(assemble_truncated_output(left, right, &marker))
+        }
+        Value::Array(items) => {
+            let mut elided: Vec<Value> = items
+                .iter()
+                .take(max_array_items)
+                .map(|item| elide_json(item, policy, max_string_bytes, max_array_items))
+                .collect();
+            if items.len() > max_array_items {
+                elided.push(Value::String(format!(
+                    "…{} more items…",
+                    items.len() - max_array_items
+                )));
+            }
+            Value::Array(elided)
+        }
+        Value::Object(fields) => Value::Object(
+            fields
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.clone(),
+                        elide_json(value, policy, max_string_bytes, max_array_items),
+                    )
+                })
+                .collect(),
+        ),
+        other => other.clone(),
+    }
+}
+
+/// Keeps every line `is_structure` accepts and, of each run of other lines,
+/// the leading lines that fit its share of the remaining budget. Runs smaller
+/// than their share are kept whole and leave the rest to the larger ones.
+/// Returns `None` when the result, markers included, is over budget, as when
+/// the structure lines alone are or the runs are too many to mark.
+fn keep_lines(
+    lines: &[&str],
+    policy: TruncationPolicy,
+    is_structure: impl Fn(&str) -> bool,
+) -> Option<String> {
+    let line_bytes = |range: &std::ops::Range<usize>| -> usize {
+        lines[range.clone()].iter().map(|line| line.len() + 1).sum()
+    };
+    let mut structure_bytes = 0usize;
+    let mut runs: Vec<std::ops::Range<usize>> = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        if is_structure(line) {
+            structure_bytes += line.len() + 1;
+        } else if let Some(run) = runs.last_mut()
+            && run.end == idx
+        {
+            run.end += 1;
+        } else {
+            runs.push(idx..idx + 1);
+        }
+    }
+
+    let mut remaining = policy.byte_budget().checked_sub(structure_bytes)?;
+    let mut by_size: Vec<usize> = (0..runs.len()).collect();
+    by_size.sort_by_key(|&run| line_bytes(&runs[run]));
+    let mut run_budgets = vec![0usize; runs.len()];
+    for (sized, &run) in by_size.iter().enumerate() {
+        let share = remaining / (runs.len() - sized);
+        let size = line_bytes(&runs[run]);
+        if size <= share {
+            run_budgets[run] = size;
+            remaining -= size;
+        } else {
+            run_budgets[run] = share.saturating_sub(LINE_MARKER_RESERVE_BYTES);
+            remaining -= share;
+        }
+    }
+
+    let mut out = String::new();
+    let mut next_line = 0usize;
+    for (run, run_budget) in runs.iter().zip(run_budgets) {
+        for line in &lines[next_line..run.start] {
+            out.push_str(line);
+            out.push('\n');
+        }
+        let mut kept_bytes = 0usize;
+        let mut removed = run.end;
+        for idx in run.clone() {
+            if kept_bytes + lines[idx].len() + 1 > run_budget {
+                removed = idx;
+                break;
+            }
+            kept_bytes += lines[idx].len() + 1;
+            out.push_str(lines[idx]);
+            out.push('\n');
+        }
+        if removed < run.end {
+            let removed_lines = &lines[removed..run.end];
+            out.push_str(&format_truncation_marker(
+                policy,
+                removed_units_for_source(
+                    policy,
+                    removed_lines.iter().map(|line| line.len() + 1).sum(),
+                    removed_lines
+                        .iter()
+                        .map(|line| line.chars().count() + 1)
+                        .sum(),
+                ),
+            ));
+            out.push('\n');
+        }
+        next_line = run.end;
+    }
+    for line in &lines[next_line..] {
+        out.push_str(line);
+        out.push('\n');
+    }
+    (out.len() <= policy.byte_budget()).then_some(out)
+}
+
 fn split_string(s: &str, beginning_bytes: usize, end_bytes: usize) -> (usize, &str, &str) {
     if s.is_empty() {
         return (0, "", "");
@@ -324,6 +585,8 @@ mod tests {
     use super::truncate_with_token_budget;
     use codex_protocol::models::FunctionCallOutputContentItem;
     use pretty_assertions::assert_eq;
+    use serde_json::Value;
+    use serde_json::json;
 
     #[test]
     fn split_string_works() {
@@ -537,4 +800,88 @@ mod tests {
         };
         assert!(summary_text.contains("omitted 2 text items"));
     }
+
+    #[test]
+    fn truncate_json_keeps_structure_and_elides_long_values() {
+        let content = serde_json::to_string(&json!({
+            "id": 7,
+            "log": "x".repeat(2_000),
+            "items": (0..50).collect::<Vec<_>>(),
+        }))
+        .expect("serialize");
+
+        let truncated = truncate_text(&content, TruncationPolicy::Bytes(1_000));
+
+        let mut items: Vec<Value> = (0..20).map(Value::from).collect();
+        items.push(json!("…30 more items…"));
+        assert_eq!(
+            serde_json::from_str::<Value>(&truncated).expect("truncated output is JSON"),
+            json!({
+                "id": 7,
+                "log": format!("{}…1488 chars truncated…{}", "x".repeat(256), "x".repeat(256)),
+                "items": items,
+            })
+        );
+    }
+
+    #[test]
+    fn truncate_diff_keeps_file_and_hunk_headers() {
+        let added = (0..40)
+            .map(|line| format!("+added line {line:02}\n"))
+            .collect::<String>();
+        let content = format!(
+            "diff --git a/src/lib.rs b/src/lib.rs\nindex 1111111..2222222 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,40 @@\n{added}@@ -90,2 +127,2 @@ fn tail()\n-old\n+new"
+        );
+
+        let kept = (0..14)
+            .map(|line| format!("+added line {line:02}\n"))
+            .collect::<String>();
+        assert_eq!(
+            truncate_text(&content, TruncationPolicy::Bytes(400)),
+            format!(
+                "diff --git a/src/lib.rs b/src/lib.rs\nindex 1111111..2222222 100644\n--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1,3 +1,40 @@\n{kept}…390 chars truncated…\n@@ -90,2 +127,2 @@ fn tail()\n-old\n+new\n"
+            )
+        );
+    }
+
+    #[test]
+    fn truncate_diff_with_too_many_hunks_to_mark_falls_back_to_head_and_tail() {
+        let hunks = (0..30)
+            .map(|hunk| {
+                format!("@@ -{hunk} +{hunk} @@\n-old {hunk:02}\n+new {hunk:02}\n+more {hunk:02}\n")
+            })
+            .collect::<String>();
+        let content = format!("--- a/src/lib.rs\n+++ b/src/lib.rs\n{hunks}");
+
+        assert_eq!(
+            truncate_text(&content, TruncationPolicy::Bytes(600)),
+            format!(
+                "{}…{} chars truncated…{}",
+                &content[..300],
+                content.len() - 600,
+                &content[content.len() - 300..]
+            )
+        );
+    }
+
+    #[test]
+    fn truncate_code_keeps_imports_and_signatures() {
+        let body = |lines: std::ops::Range<usize>| {
+            lines
+                .map(|line| format!("        let v{line} = x + {line};\n"))
+                .collect::<String>()
+        };
+        let content = format!(
+            "use std::fmt;\n\npub struct Point {{\n    x: i64,\n}}\n\nimpl Point {{\n    pub fn new(x: i64) -> Self {{\n{}        Self {{ x }}\n    }}\n\n    pub fn x(&self) -> i64 {{\n        self.x\n    }}\n}}",
+            body(0..30)
+        );
+
+        assert_eq!(
+            truncate_text(&content, TruncationPolicy::Bytes(400)),
+            format!(
+                "use std::fmt;\n\npub struct Point {{\n    x: i64,\n}}\n\nimpl Point {{\n    pub fn new(x: i64) -> Self {{\n{}…570 chars truncated…\n    pub fn x(&self) -> i64 {{\n        self.x\n    }}\n}}\n",
+                body(0..9)
+            )
+        );
+    }
 }
@@ -1,5 +1,9 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs;
+use std::io::Read;
+use std::io::Seek;
+use std::io::Write;
 use std::path::Path;
 use std::path::PathBuf;
 use std::process::Command;
@@ -10,18 +14,65 @@ use anyhow::anyhow;
 use sha1::digest::Output;
 use uuid::Uuid;
 
+use crate::config::types::TurnDiffLimits;
+use crate::protocol::FileAnchor;
 use crate::protocol::FileChange;
 
 const ZERO_OID: &str = "0000000000000000000000000000000000000000";
 const DEV_NULL: &str = "/dev/null";
+/// Files larger than this are tracked by blob id only and are not diffed.
+const DEFAULT_MAX_FILE_BYTES: u64 = 32 * 1024 * 1024;
+/// Baselines larger than this are kept in a temporary file instead of memory.
+const DEFAULT_SPILL_THRESHOLD_BYTES: u64 = 1024 * 1024;
+/// Chunk size used when hashing files too large to read at once.
+const HASH_CHUNK_BYTES: usize = 64 * 1024;
 
 struct BaselineFileInfo {
     path: PathBuf,
-    content: Vec<u8>,
+    content: BaselineContent,
     mode: FileMode,
     oid: String,
 }
 
+/// Where the baseline bytes of a tracked file live.
+enum BaselineContent {
+    InMemory(Vec<u8>),
+    /// Spilled to an anonymous temporary file, removed when dropped.
+    Spilled(fs::File),
+    /// Over the per-file cap: only the blob id is kept.
+    TooLarge,
+}
+
+impl BaselineContent {
+    fn new(content: Vec<u8>, spill_threshold_bytes: u64) -> Self {
+        if content.len() as u64 <= spill_threshold_bytes {
+            return Self::InMemory(content);
+        }
+        let spilled = tempfile::tempfile().and_then(|mut file| {
+            file.write_all(&content)?;
+            Ok(file)
+        });
+        match spilled {
+            Ok(file) => Self::Spilled(file),
+            Err(_) => Self::InMemory(content),
+        }
+    }
+
+    fn bytes(&self) -> Option<Cow<'_, [u8]>> {
+        match self {
+            Self::InMemory(content) => Some(Cow::Borrowed(content)),
+            Self::Spilled(file) => {
+                let mut file: &fs::File = file;
+                let mut content = Vec::new();
+                file.rewind().ok()?;
+                file.read_to_end(&mut content).ok()?;
+                Some(Cow::Owned(content))
+            }
+            Self::TooLarge => None,
+        }
+    }
+}
+
 /// Tracks sets of changes to files and exposes the overall unified diff.
 /// Internally, the way this works is now:
 /// 1. Maintain an in-memory baseline snapshot of files when they are first seen.
@@ -29,7 +80,12 @@ struct BaselineFileInfo {
 /// 2. Keep a stable internal filename (uuid) per external path for rename tracking.
 /// 3. To compute the aggregated unified diff, compare each baseline snapshot to the current file on disk entirely in-memory
 ///    using the `similar` crate and emit unified diffs with rewritten external paths.
-#[derive(Default)]
+///
+/// To bound memory when large files are rewritten, baselines above the spill threshold are kept in
+/// temporary files, and files above the per-file cap are compared by blob id only.
+///
+/// The tracker also keeps an anchor for the lines each applied patch wrote, and moves it through
+/// the hunks of every later patch to the same file so clients can keep pointing at those lines.
 pub struct TurnDiffTracker {
     /// Map external path -> internal filename (uuid).
     external_to_temp_name: HashMap<PathBuf, String>,
@@ -40,6 +96,16 @@ pub struct TurnDiffTracker {
     temp_name_to_current_path: HashMap<String, PathBuf>,
     /// Cache of known git worktree roots to avoid repeated filesystem walks.
     git_root_cache: Vec<PathBuf>,
+    max_file_bytes: u64,
+    spill_threshold_bytes: u64,
+    /// Lines written by the patches applied so far, at their current positions.
+    anchors: Vec<FileAnchor>,
+}
+
+impl Default for TurnDiffTracker {
+    fn default() -> Self {
+        Self::with_limits(TurnDiffLimits::default())
+    }
 }
 
 impl TurnDiffTracker {
@@ -47,6 +113,33 @@ impl TurnDiffTracker {
         Self::default()
     }
 
+    pub fn with_limits(limits: TurnDiffLimits) -> Self {
+        Self {
+            external_to_temp_name: HashMap::new(),
+            baseline_file_info: HashMap::new(),
+            temp_name_to_current_path: HashMap::new(),
+            git_root_cache: Vec::new(),
+            max_file_bytes: limits.max_file_bytes.unwrap_or(DEFAULT_MAX_FILE_BYTES),
+            spill_threshold_bytes: limits
+                .spill_threshold_bytes
+                .unwrap_or(DEFAULT_SPILL_THRESHOLD_BYTES),
+            anchors: Vec::new(),
+        }
+    }
+
+    /// Whether `path` exceeds the per-file cap. Symlinks are never too large.
+    fn is_too_large(&self, path: &Path, mode: FileMode) -> bool {
+        mode != FileMode::Symlink
+            && fs::metadata(path).is_ok_and(|meta| meta.len() > self.max_file_bytes)
+    }
+
+    /// Blob id of a file over the per-file cap, hashed in chunks when git cannot be used.
+    fn large_file_oid(&mut self, path: &Path) -> String {
+        self.git_blob_oid_for_path(path)
+            .or_else(|| git_blob_sha1_hex_file(path).map(|oid| format!("{oid:x}")))
+            .unwrap_or_else(|| ZERO_OID.to_string())
+    }
+
     /// Front-run apply patch calls to track the starting contents of any modified files.
     /// - Creates an in-memory baseline snapshot for files that already exist on disk when first seen.
     /// - For additions, we intentionally do not create a baseline snapshot so that diffs are proper additions.
@@ -65,23 +158,33 @@ impl TurnDiffTracker {
                 let baseline_file_info = if path.exists() {
                     let mode = file_mode_for_path(path);
                     let mode_val = mode.unwrap_or(FileMode::Regular);
-                    let content = blob_bytes(path, mode_val).unwrap_or_default();
-                    let oid = if mode == Some(FileMode::Symlink) {
-                        format!("{:x}", git_blob_sha1_hex_bytes(&content))
+                    if self.is_too_large(path, mode_val) {
+                        Some(BaselineFileInfo {
+                            path: path.clone(),
+                            content: BaselineContent::TooLarge,
+                            mode: mode_val,
+                            oid: self.large_file_oid(path),
+                        })
                     } else {
-                        self.git_blob_oid_for_path(path)
-                            .unwrap_or_else(|| format!("{:x}", git_blob_sha1_hex_bytes(&content)))
-                    };
-                    Some(BaselineFileInfo {
-                        path: path.clone(),
-                        content,
-                        mode: mode_val,
-                        oid,
-                    })
+                        let content = blob_bytes(path, mode_val).unwrap_or_default();
+                        let oid = if mode == Some(FileMode::Symlink) {
+                            format!("{:x}", git_blob_sha1_hex_bytes(&content))
+                        } else {
+                            self.git_blob_oid_for_path(path).unwrap_or_else(|| {
+                                format!("{:x}", git_blob_sha1_hex_bytes(&content))
+                            })
+                        };
+                        Some(BaselineFileInfo {
+                            path: path.clone(),
+                            content: BaselineContent::new(content, self.spill_threshold_bytes),
+                            mode: mode_val,
+                            oid,
+                        })
+                    }
                 } else {
                     Some(BaselineFileInfo {
                         path: path.clone(),
-                        content: vec![],
+                        content: BaselineContent::InMemory(Vec::new()),
                         mode: FileMode::Regular,
                         oid: ZERO_OID.to_string(),
                     })
@@ -108,7 +211,7 @@ impl TurnDiffTracker {
                             i.clone(),
                             BaselineFileInfo {
                                 path: path.clone(),
-                                content: vec![],
+                                content: BaselineContent::InMemory(Vec::new()),
                                 mode: FileMode::Regular,
                                 oid: ZERO_OID.to_string(),
                             },
@@ -127,6 +230,57 @@ impl TurnDiffTracker {
         }
     }
 
+    /// Records a successfully applied patch: moves the anchors of earlier patches to where their
+    /// lines are now and returns anchors for the lines this patch wrote.
+    pub fn on_patch_end(&mut self, changes: &HashMap<PathBuf, FileChange>) -> Vec<FileAnchor> {
+        let mut written = Vec::new();
+        for (path, change) in changes {
+            match change {
+                FileChange::Add { content } => {
+                    self.anchors.retain(|anchor| &anchor.path != path);
+                    written.push(FileAnchor {
+                        path: path.clone(),
+                        start_line: 1,
+                        end_line: u32::try_from(content.lines().count().max(1)).unwrap_or(u32::MAX),
+                    });
+                }
+                FileChange::Delete { .. } => {
+                    self.anchors.retain(|anchor| &anchor.path != path);
+                }
+                FileChange::Update {
+                    unified_diff,
+                    move_path,
+                } => {
+                    let hunks = parse_hunks(unified_diff);
+                    let dest = move_path.as_ref().unwrap_or(path);
+                    for anchor in self
+                        .anchors
+                        .iter_mut()
+                        .filter(|anchor| &anchor.path == path)
+                    {
+                        anchor.path = dest.clone();
+                        anchor.start_line = map_line(&hunks, anchor.start_line);
+                        anchor.end_line = map_line(&hunks, anchor.end_line).max(anchor.start_line);
+                    }
+                    written.extend(hunks.iter().map(|hunk| hunk.written_lines(dest)));
+                }
+            }
+        }
+        written.sort_by(|a, b| (&a.path, a.start_line).cmp(&(&b.path, b.start_line)));
+        self.anchors.extend(written.iter().cloned());
+        written
+    }
+
+    /// Current paths of the files this turn's patches changed.
+    pub fn edited_paths(&self) -> Vec<PathBuf> {
+        self.temp_name_to_current_path.values().cloned().collect()
+    }
+
+    /// Anchors for the lines written by this turn's patches, at their current positions.
+    pub fn anchors(&self) -> &[FileAnchor] {
+        &self.anchors
+    }
+
     fn get_path_for_internal(&self, internal: &str) -> Option<PathBuf> {
         self.temp_name_to_current_path
             .get(internal)
@@ -266,14 +420,22 @@ impl TurnDiffTracker {
         };
 
         let current_mode = file_mode_for_path(&current_external_path).unwrap_or(FileMode::Regular);
-        let right_bytes = blob_bytes(&current_external_path, current_mode);
+        let right_too_large = self.is_too_large(&current_external_path, current_mode);
+        let right_bytes = if right_too_large {
+            None
+        } else {
+            blob_bytes(&current_external_path, current_mode)
+        };
+        let right_present = right_too_large || right_bytes.is_some();
 
         // Compute displays with &mut self before borrowing any baseline content.
         let left_display = self.relative_to_git_root_str(&baseline_external_path);
         let right_display = self.relative_to_git_root_str(&current_external_path);
 
         // Compute right oid before borrowing baseline content.
-        let right_oid = if let Some(b) = right_bytes.as_ref() {
+        let right_oid = if right_too_large {
+            self.large_file_oid(&current_external_path)
+        } else if let Some(b) = right_bytes.as_ref() {
             if current_mode == FileMode::Symlink {
                 format!("{:x}", git_blob_sha1_hex_bytes(b))
             } else {
@@ -286,23 +448,32 @@ impl TurnDiffTracker {
 
         // Borrow baseline content only after all &mut self uses are done.
         let left_present = left_oid.as_str() != ZERO_OID;
-        let left_bytes: Option<&[u8]> = if left_present {
-            self.baseline_file_info
-                .get(internal_file_name)
-                .map(|i| i.content.as_slice())
+        let left_content = self
+            .baseline_file_info
+            .get(internal_file_name)
+            .map(|info| &info.content);
+        let left_too_large =
+            left_present && matches!(left_content, Some(BaselineContent::TooLarge));
+        let left_bytes = if left_present {
+            left_content.and_then(BaselineContent::bytes)
         } else {
             None
         };
 
-        // Fast path: identical bytes or both missing.
-        if left_bytes == right_bytes.as_deref() {
+        // Fast path: identical contents or both missing. Files over the cap are compared by blob
+        // id.
+        if left_too_large || right_too_large {
+            if left_present == right_present && left_oid == right_oid {
+                return aggregated;
+            }
+        } else if left_bytes.as_deref() == right_bytes.as_deref() {
             return aggregated;
         }
 
         aggregated.push_str(&format!("diff --git a/{left_display} b/{right_display}\n"));
 
-        let is_add = !left_present && right_bytes.is_some();
-        let is_delete = left_present && right_bytes.is_none();
+        let is_add = !left_present && right_present;
+        let is_delete = left_present && !right_present;
 
         if is_add {
             aggregated.push_str(&format!("new file mode {current_mode}\n"));
@@ -313,15 +484,19 @@ impl TurnDiffTracker {
             aggregated.push_str(&format!("new mode {current_mode}\n"));
         }
 
-        let left_text = left_bytes.and_then(|b| std::str::from_utf8(b).ok());
+        let left_text = left_bytes
+            .as_deref()
+            .and_then(|b| std::str::from_utf8(b).ok());
         let right_text = right_bytes
             .as_deref()
             .and_then(|b| std::str::from_utf8(b).ok());
 
-        let can_text_diff = matches!(
-            (left_text, right_text, is_add, is_delete),
-            (Some(_), Some(_), _, _) | (_, Some(_), true, _) | (Some(_), _, _, true)
-        );
+        let can_text_diff = !left_too_large
+            && !right_too_large
+            && matches!(
+                (left_text, right_text, is_add, is_delete),
+                (Some(_), Some(_), _, _) | (_, Some(_), true, _) | (Some(_), _, _, true)
+            );
 
         if can_text_diff {
             let l = left_text.unwrap_or("");
@@ -334,7 +509,7 @@ impl TurnDiffTracker {
             } else {
                 DEV_NULL.to_string()
             };
-            let new_header = if right_bytes.is_some() {
+            let new_header = if right_present {
                 format!("b/{right_display}")
             } else {
                 DEV_NULL.to_string()
@@ -355,19 +530,137 @@ impl TurnDiffTracker {
             } else {
                 DEV_NULL.to_string()
             };
-            let new_header = if right_bytes.is_some() {
+            let new_header = if right_present {
                 format!("b/{right_display}")
             } else {
                 DEV_NULL.to_string()
             };
             aggregated.push_str(&format!("--- {old_header}\n"));
             aggregated.push_str(&format!("+++ {new_header}\n"));
-            aggregated.push_str("Binary files differ\n");
+            if left_too_large || right_too_large {
+                aggregated.push_str(&format!(
+                    "File too large to diff (over {} bytes)\n",
+                    self.max_file_bytes
+                ));
+            } else {
+                aggregated.push_str("Binary files differ\n");
+            }
         }
         aggregated
     }
 }
 
+/// One hunk of a file change's unified diff.
+struct DiffHunk {
+    old_start: u32,
+    new_start: u32,
+    lines: Vec<DiffLine>,
+}
+
+#[derive(Clone, Copy)]
+enum DiffLine {
+    Context,
+    Removed,
+    Added,
+}
+
+impl DiffHunk {
+    /// The lines of the new file this hunk added, or the line now in place of the lines it only
+    /// removed.
+    fn written_lines(&self, path: &Path) -> FileAnchor {
+        let mut new = self.new_start;
+        let mut span: Option<(u32, u32)> = None;
+        for diff_line in &self.lines {
+            let touched = match diff_line {
+                DiffLine::Context => {
+                    new += 1;
+                    continue;
+                }
+                DiffLine::Removed => new,
+                DiffLine::Added => {
+                    new += 1;
+                    new - 1
+                }
+            };
+            span = Some(span.map_or((touched, touched), |(start, end)| {
+                (start.min(touched), end.max(touched))
+            }));
+        }
+        let (start, end) = span.unwrap_or((self.new_start, self.new_start));
+        let start_line = start.max(1);
+        FileAnchor {
+            path: path.to_path_buf(),
+            start_line,
+            end_line: end.max(start_line),
+        }
+    }
+}
+
+fn parse_hunks(unified_diff: &str) -> Vec<DiffHunk> {
+    // A hunk header is `@@ -<old>[,<len>] +<new>[,<len>] @@`. An empty range starts at the line
+    // before it, so it is moved to the line after for the walk below.
+    fn range_start(range: &str) -> Option<u32> {
+        let (start, len) = range.split_once(',').unwrap_or((range, "1"));
+        let start: u32 = start.parse().ok()?;
+        Some(if len == "0" { start + 1 } else { start })
+    }
+
+    let mut hunks: Vec<DiffHunk> = Vec::new();
+    for line in unified_diff.lines() {
+        if let Some(header) = line.strip_prefix("@@ -") {
+            let mut ranges = header.split(' ');
+            let old_start = ranges.next().and_then(range_start);
+            let new_start = ranges
+                .next()
+                .and_then(|range| range.strip_prefix('+'))
+                .and_then(range_start);
+            if let (Some(old_start), Some(new_start)) = (old_start, new_start) {
+                hunks.push(DiffHunk {
+                    old_start,
+                    new_start,
+                    lines: Vec::new(),
+                });
+            }
+            continue;
+        }
+        let Some(hunk) = hunks.last_mut() else {
+            continue;
+        };
+        match line.as_bytes().first() {
+            Some(b' ') => hunk.lines.push(DiffLine::Context),
+            Some(b'-') => hunk.lines.push(DiffLine::Removed),
+            Some(b'+') => hunk.lines.push(DiffLine::Added),
+            _ => {}
+        }
+    }
+    hunks
+}
+
+/// Where `line` of a file is after the change described by `hunks`. A removed line maps to the
+/// line now in its place.
+fn map_line(hunks: &[DiffHunk], line: u32) -> u32 {
+    let mut shift = 0i64;
+    for hunk in hunks {
+        if line < hunk.old_start {
+            break;
+        }
+        let (mut old, mut new) = (hunk.old_start, hunk.new_start);
+        for diff_line in &hunk.lines {
+            match diff_line {
+                DiffLine::Context | DiffLine::Removed if old == line => return new,
+                DiffLine::Context => {
+                    old += 1;
+                    new += 1;
+                }
+                DiffLine::Removed => old += 1,
+                DiffLine::Added => new += 1,
+            }
+        }
+        shift = i64::from(new) - i64::from(old);
+    }
+    u32::try_from((i64::from(line) + shift).max(1)).unwrap_or(u32::MAX)
+}
+
 /// Compute the Git SHA-1 blob object ID for the given content (bytes).
 fn git_blob_sha1_hex_bytes(data: &[u8]) -> Output<sha1::Sha1> {
     // Git blob hash is sha1 of: "blob <len>\0<data>"
@@ -379,6 +672,24 @@ fn git_blob_sha1_hex_bytes(data: &[u8]) -> Output<sha1::Sha1> {
     hasher.finalize()
 }
 
+/// Compute the Git SHA-1 blob object ID of the file at `path`, reading it in chunks.
+fn git_blob_sha1_hex_file(path: &Path) -> Option<Output<sha1::Sha1>> {
+    use sha1::Digest;
+    let mut file = fs::File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    let mut hasher = sha1::Sha1::new();
+    hasher.update(format!("blob {len}\0").as_bytes());
+    let mut chunk = vec![0; HASH_CHUNK_BYTES];
+    loop {
+        let read = file.read(&mut chunk).ok()?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&chunk[..read]);
+    }
+    Some(hasher.finalize())
+}
+
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum FileMode {
     Regular,
@@ -826,6 +1137,61 @@ Binary files differ
         assert_eq!(diff, expected);
     }
 
+    #[test]
+    fn large_files_are_spilled_or_compared_by_blob_id() {
+        let dir = tempdir().unwrap();
+        let small = dir.path().join("small.txt");
+        let big = dir.path().join("big.txt");
+        fs::write(&small, "one\ntwo\n").unwrap();
+        fs::write(&big, "x".repeat(20)).unwrap();
+
+        let mut acc = TurnDiffTracker::with_limits(TurnDiffLimits {
+            max_file_bytes: Some(16),
+            spill_threshold_bytes: Some(4),
+        });
+        let update = || FileChange::Update {
+            unified_diff: "".to_owned(),
+            move_path: None,
+        };
+        acc.on_patch_begin(&HashMap::from([
+            (small.clone(), update()),
+            (big.clone(), update()),
+        ]));
+        assert!(matches!(
+            acc.baseline_file_info
+                .get(&acc.external_to_temp_name[&small])
+                .map(|info| &info.content),
+            Some(BaselineContent::Spilled(_))
+        ));
+
+        fs::write(&small, "one\nTWO\n").unwrap();
+        fs::write(&big, "y".repeat(20)).unwrap();
+
+        let diff = acc.get_unified_diff().unwrap().unwrap();
+        let diff = normalize_diff_for_test(&diff, dir.path());
+        let expected = format!(
+            r#"diff --git a/<TMP>/big.txt b/<TMP>/big.txt
+index {big_left}..{big_right}
+--- a/<TMP>/big.txt
++++ b/<TMP>/big.txt
+File too large to diff (over 16 bytes)
+diff --git a/<TMP>/small.txt b/<TMP>/small.txt
+index {small_left}..{small_right}
+--- a/<TMP>/small.txt
++++ b/<TMP>/small.txt
+@@ -1,2 +1,2 @@
+ one
+-two
++TWO
+"#,
+            big_left = git_blob_sha1_hex(&"x".repeat(20)),
+            big_right = git_blob_sha1_hex(&"y".repeat(20)),
+            small_left = git_blob_sha1_hex("one\ntwo\n"),
+            small_right = git_blob_sha1_hex("one\nTWO\n"),
+        );
+        assert_eq!(diff, expected);
+    }
+
     #[test]
     fn filenames_with_spaces_add_and_update() {
         let mut acc = TurnDiffTracker::new();
@@ -893,4 +1259,54 @@ index {ZERO_OID}..{right_oid}
         };
         assert_eq!(combined, expected_combined);
     }
+
+    #[test]
+    fn anchors_follow_later_edits_and_renames() {
+        let mut acc = TurnDiffTracker::new();
+        let a = PathBuf::from("/repo/a.rs");
+        let b = PathBuf::from("/repo/b.rs");
+        let update = |unified_diff: &str, move_path: Option<&PathBuf>| FileChange::Update {
+            unified_diff: unified_diff.to_string(),
+            move_path: move_path.cloned(),
+        };
+        let anchor = |path: &PathBuf, start_line, end_line| FileAnchor {
+            path: path.clone(),
+            start_line,
+            end_line,
+        };
+
+        // a, b, c -> a, B, B2, c
+        let written = acc.on_patch_end(&HashMap::from([(
+            a.clone(),
+            update("@@ -1,3 +1,4 @@\n a\n-b\n+B\n+B2\n c\n", None),
+        )]));
+        assert_eq!(written, vec![anchor(&a, 2, 3)]);
+
+        // -> x, y, a, B, B2, c
+        let written = acc.on_patch_end(&HashMap::from([(
+            a.clone(),
+            update("@@ -1 +1,3 @@\n+x\n+y\n a\n", None),
+        )]));
+        assert_eq!(written, vec![anchor(&a, 1, 2)]);
+        assert_eq!(acc.anchors(), [anchor(&a, 4, 5), anchor(&a, 1, 2)]);
+
+        // -> x, y, a, B2, c, renamed to b.rs
+        let written = acc.on_patch_end(&HashMap::from([(
+            a.clone(),
+            update("@@ -3,3 +3,2 @@\n a\n-B\n B2\n", Some(&b)),
+        )]));
+        assert_eq!(written, vec![anchor(&b, 4, 4)]);
+        assert_eq!(
+            acc.anchors(),
+            [anchor(&b, 4, 4), anchor(&b, 1, 2), anchor(&b, 4, 4)]
+        );
+
+        acc.on_patch_end(&HashMap::from([(
+            b.clone(),
+            FileChange::Delete {
+                content: String::new(),
+            },
+        )]));
+        assert_eq!(acc.anchors(), Vec::<FileAnchor>::new());
+    }
 }
@@ -0,0 +1,181 @@
+//! Latency breakdown of each turn ([`Op::GetTurnTimings`]).
+//!
+//! Every [`TurnContext`] carries a [`TurnTimer`] that the stages of the turn
+//! add to as they run: prompt assembly, each model request, each tool call,
+//! and recording items. When the turn finishes or is aborted, the breakdown
+//! is kept in the session state for the most recent [`MAX_TURN_TIMINGS`]
+//! turns.
+//!
+//! [`Op::GetTurnTimings`]: codex_protocol::protocol::Op::GetTurnTimings
+//! [`TurnContext`]: crate::codex::TurnContext
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::time::Duration;
+use std::time::Instant;
+
+use codex_protocol::protocol::ModelRequestTiming;
+use codex_protocol::protocol::ToolTiming;
+use codex_protocol::protocol::TurnTiming;
+
+/// Turns whose breakdown the session keeps.
+pub(crate) const MAX_TURN_TIMINGS: usize = 50;
+
+#[derive(Debug)]
+pub(crate) struct TurnTimer {
+    created_at: Instant,
+    state: Mutex<TimerState>,
+}
+
+#[derive(Debug, Default)]
+struct TimerState {
+    started_at: Option<Instant>,
+    prompt_assembly: Duration,
+    model_requests: Vec<ModelRequestTiming>,
+    tools: HashMap<String, (u32, Duration)>,
+    serialization: Duration,
+}
+
+impl Default for TurnTimer {
+    fn default() -> Self {
+        Self {
+            created_at: Instant::now(),
+            state: Mutex::new(TimerState::default()),
+        }
+    }
+}
+
+impl TurnTimer {
+    /// Marks the end of the queue wait: the turn's task starts running.
+    pub(crate) fn start(&self) {
+        self.state().started_at.get_or_insert_with(Instant::now);
+    }
+
+    pub(crate) fn record_prompt_assembly(&self, elapsed: Duration) {
+        self.state().prompt_assembly += elapsed;
+    }
+
+    /// Records a model request that took `elapsed` in all, the first
+    /// `time_to_first_token` of it waiting for output.
+    pub(crate) fn record_model_request(
+        &self,
+        time_to_first_token: Option<Duration>,
+        elapsed: Duration,
+    ) {
+        self.state().model_requests.push(ModelRequestTiming {
+            time_to_first_token_ms: time_to_first_token.map(millis),
+            streaming_ms: millis(elapsed.saturating_sub(time_to_first_token.unwrap_or(elapsed))),
+        });
+    }
+
+    pub(crate) fn record_tool_call(&self, tool: &str, elapsed: Duration) {
+        let mut state = self.state();
+        let (calls, wall_time) = state.tools.entry(tool.to_string()).or_default();
+        *calls += 1;
+        *wall_time += elapsed;
+    }
+
+    pub(crate) fn record_serialization(&self, elapsed: Duration) {
+        self.state().serialization += elapsed;
+    }
+
+    /// The breakdown of turn `turn_id` so far.
+    pub(crate) fn timing(&self, turn_id: String) -> TurnTiming {
+        let state = self.state();
+        let mut tools: Vec<ToolTiming> = state
+            .tools
+            .iter()
+            .map(|(tool, (calls, wall_time))| ToolTiming {
+                tool: tool.clone(),
+                calls: *calls,
+                wall_time_ms: millis(*wall_time),
+            })
+            .collect();
+        tools.sort_by(|a, b| {
+            b.wall_time_ms
+                .cmp(&a.wall_time_ms)
+                .then_with(|| a.tool.cmp(&b.tool))
+        });
+        TurnTiming {
+            turn_id,
+            queue_wait_ms: millis(
+                state
+                    .started_at
+                    .map_or(Duration::ZERO, |started_at| started_at - self.created_at),
+            ),
+            prompt_assembly_ms: millis(state.prompt_assembly),
+            model_requests: state.model_requests.clone(),
+            tools,
+            serialization_ms: millis(state.serialization),
+            total_ms: millis(self.created_at.elapsed()),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, TimerState> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn breaks_a_turn_down_by_stage() {
+        let timer = TurnTimer::default();
+        timer.record_prompt_assembly(Duration::from_millis(20));
+        timer.record_prompt_assembly(Duration::from_millis(5));
+        timer.record_model_request(Some(Duration::from_millis(800)), Duration::from_secs(3));
+        timer.record_model_request(None, Duration::from_secs(1));
+        timer.record_tool_call("shell", Duration::from_secs(2));
+        timer.record_tool_call("read_file", Duration::from_millis(10));
+        timer.record_tool_call("shell", Duration::from_secs(1));
+        timer.record_serialization(Duration::from_millis(3));
+
+        let timing = timer.timing("turn-1".to_string());
+        assert_eq!(
+            TurnTiming {
+                queue_wait_ms: 0,
+                total_ms: 0,
+                ..timing
+            },
+            TurnTiming {
+                turn_id: "turn-1".to_string(),
+                queue_wait_ms: 0,
+                prompt_assembly_ms: 25,
+                model_requests: vec![
+                    ModelRequestTiming {
+                        time_to_first_token_ms: Some(800),
+                        streaming_ms: 2_200,
+                    },
+                    ModelRequestTiming {
+                        time_to_first_token_ms: None,
+                        streaming_ms: 0,
+                    },
+                ],
+                tools: vec![
+                    ToolTiming {
+                        tool: "shell".to_string(),
+                        calls: 2,
+                        wall_time_ms: 3_000,
+                    },
+                    ToolTiming {
+                        tool: "read_file".to_string(),
+                        calls: 1,
+                        wall_time_ms: 10,
+                    },
+                ],
+                serialization_ms: 3,
+                total_ms: 0,
+            }
+        );
+    }
+}
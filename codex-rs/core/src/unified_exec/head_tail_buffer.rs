@@ -50,6 +50,11 @@ impl HeadTailBuffer {
         self.head_bytes.saturating_add(self.tail_bytes)
     }
 
+    /// Bytes retained from the start of the output.
+    pub(crate) fn head_bytes(&self) -> usize {
+        self.head_bytes
+    }
+
     // Used for tests.
     #[allow(dead_code)]
     /// Total bytes that were dropped from the middle due to the size cap.
@@ -36,10 +36,11 @@ use tokio::sync::Mutex;
 use crate::codex::Session;
 use crate::codex::TurnContext;
 use crate::sandboxing::SandboxPermissions;
+use crate::tools::ExecProvenance;
 
 mod async_watcher;
 mod errors;
-mod head_tail_buffer;
+pub(crate) mod head_tail_buffer;
 mod process;
 mod process_manager;
 
@@ -113,6 +114,9 @@ pub(crate) struct UnifiedExecResponse {
     pub exit_code: Option<i32>,
     pub original_token_count: Option<usize>,
     pub session_command: Option<Vec<String>>,
+    /// What the command ran as, on the `exec_command` call that started it
+    /// (`command_provenance` feature).
+    pub provenance: Option<ExecProvenance>,
 }
 
 #[derive(Default)]
@@ -12,10 +12,14 @@ use tokio::time::Duration;
 use tokio::time::Instant;
 use tokio_util::sync::CancellationToken;
 
+use crate::exec_env::apply_env_injections;
 use crate::exec_env::create_env;
+use crate::exec_env::resolve_secret_from_store;
 use crate::exec_policy::ExecApprovalRequest;
+use crate::features::Feature;
 use crate::protocol::ExecCommandSource;
 use crate::sandboxing::ExecRequest;
+use crate::tools::ExecProvenance;
 use crate::tools::events::ToolEmitter;
 use crate::tools::events::ToolEventCtx;
 use crate::tools::events::ToolEventStage;
@@ -167,9 +171,9 @@ impl UnifiedExecProcessManager {
             .open_session_with_sandbox(&request, cwd.clone(), context)
             .await;
 
-        let (process, mut deferred_network_approval) = match process {
-            Ok((process, deferred_network_approval)) => {
-                (Arc::new(process), deferred_network_approval)
+        let (process, mut deferred_network_approval, provenance) = match process {
+            Ok((process, deferred_network_approval, provenance)) => {
+                (Arc::new(process), deferred_network_approval, provenance)
             }
             Err(err) => {
                 self.release_process_id(&request.process_id).await;
@@ -289,6 +293,7 @@ impl UnifiedExecProcessManager {
             exit_code,
             original_token_count: Some(original_token_count),
             session_command: Some(request.command.clone()),
+            provenance,
         };
 
         Ok(response)
@@ -384,6 +389,7 @@ impl UnifiedExecProcessManager {
             exit_code,
             original_token_count: Some(original_token_count),
             session_command: Some(session_command.clone()),
+            provenance: None,
         };
 
         Ok(response)
@@ -563,11 +569,26 @@ impl UnifiedExecProcessManager {
         request: &ExecCommandRequest,
         cwd: PathBuf,
         context: &UnifiedExecContext,
-    ) -> Result<(UnifiedExecProcess, Option<DeferredNetworkApproval>), UnifiedExecError> {
-        let env = apply_unified_exec_env(create_env(
+    ) -> Result<
+        (
+            UnifiedExecProcess,
+            Option<DeferredNetworkApproval>,
+            Option<ExecProvenance>,
+        ),
+        UnifiedExecError,
+    > {
+        let mut env = apply_unified_exec_env(create_env(
             &context.turn.shell_environment_policy,
             Some(context.session.conversation_id),
         ));
+        let explicit_env_overrides = apply_env_injections(
+            &mut env,
+            &context.turn.shell_environment_policy,
+            context.session.dependency_env().await,
+            |secret_name| {
+                resolve_secret_from_store(&context.turn.config.codex_home, &cwd, secret_name)
+            },
+        );
         let mut orchestrator = ToolOrchestrator::new();
         let mut runtime = UnifiedExecRuntime::new(self);
         let exec_approval_requirement = context
@@ -586,7 +607,7 @@ impl UnifiedExecProcessManager {
             command: request.command.clone(),
             cwd,
             env,
-            explicit_env_overrides: context.turn.shell_environment_policy.r#set.clone(),
+            explicit_env_overrides,
             network: request.network.clone(),
             tty: request.tty,
             sandbox_permissions: request.sandbox_permissions,
@@ -599,7 +620,7 @@ impl UnifiedExecProcessManager {
             call_id: context.call_id.clone(),
             tool_name: "exec_command".to_string(),
         };
-        orchestrator
+        let result = orchestrator
             .run(
                 &mut runtime,
                 &req,
@@ -608,8 +629,20 @@ impl UnifiedExecProcessManager {
                 context.turn.approval_policy.value(),
             )
             .await
-            .map(|result| (result.output, result.deferred_network_approval))
-            .map_err(|e| UnifiedExecError::create_process(format!("{e:?}")))
+            .map_err(|e| UnifiedExecError::create_process(format!("{e:?}")))?;
+        let provenance = context
+            .turn
+            .features
+            .enabled(Feature::CommandProvenance)
+            .then(|| {
+                ExecProvenance::new(
+                    &req.command,
+                    req.env.get("PATH").map(String::as_str),
+                    req.cwd.clone(),
+                    result.output.sandbox_type(),
+                )
+            });
+        Ok((result.output, result.deferred_network_approval, provenance))
     }
 
     pub(super) async fn collect_output_until_deadline(
@@ -0,0 +1,293 @@
+//! Local usage rollups ([`Op::QueryUsage`]).
+//!
+//! The token usage of every model request is added to a per-project,
+//! per-day, per-model total in `<codex_home>/usage_rollups.json`, shared by
+//! all sessions on the machine, so spend can be reported without external
+//! telemetry. Costs are estimated when the rollups are queried, from the
+//! `model_pricing` configured at that time.
+//!
+//! Each update holds a lock on `usage_rollups.json.lock` for its
+//! read-modify-write, so concurrent sessions do not lose each other's usage,
+//! and replaces the file atomically, so a crash cannot leave it half
+//! written. A file that cannot be parsed is moved aside to
+//! `usage_rollups.json.corrupt` and counting starts over. Rollups older than
+//! [`RETENTION_DAYS`] are dropped on each update.
+//!
+//! [`Op::QueryUsage`]: codex_protocol::protocol::Op::QueryUsage
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use codex_protocol::protocol::TokenUsage;
+use codex_protocol::protocol::UsageRollup;
+use serde::Deserialize;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::config::types::ModelPricing;
+use crate::path_utils::write_atomically;
+
+const USAGE_ROLLUPS_FILE: &str = "usage_rollups.json";
+const USAGE_ROLLUPS_LOCK_FILE: &str = "usage_rollups.json.lock";
+const USAGE_ROLLUPS_CORRUPT_FILE: &str = "usage_rollups.json.corrupt";
+/// Rollups of days this long before the recorded one are dropped.
+const RETENTION_DAYS: i64 = 400;
+const TOKENS_PER_PRICE_UNIT: f64 = 1_000_000.0;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct UsageRollupsFile {
+    rollups: Vec<StoredRollup>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct StoredRollup {
+    date: NaiveDate,
+    project_root: PathBuf,
+    model: String,
+    requests: u64,
+    token_usage: TokenUsage,
+}
+
+/// Which rollups a query returns.
+#[derive(Debug, Default)]
+pub(crate) struct UsageFilter {
+    pub(crate) project_root: Option<PathBuf>,
+    pub(crate) since: Option<NaiveDate>,
+    pub(crate) until: Option<NaiveDate>,
+}
+
+/// Adds one request's `usage` of `model` to the total of `project_root` on
+/// `date`. Blocks on the file system.
+pub(crate) fn record(
+    codex_home: &Path,
+    date: NaiveDate,
+    project_root: &Path,
+    model: &str,
+    usage: &TokenUsage,
+) -> io::Result<()> {
+    let lock = File::options()
+        .write(true)
+        .create(true)
+        .truncate(false)
+        .open(codex_home.join(USAGE_ROLLUPS_LOCK_FILE))?;
+    lock.lock()?;
+    let path = codex_home.join(USAGE_ROLLUPS_FILE);
+    let mut store = match read_store(&path) {
+        Ok(store) => store,
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            let corrupt_path = codex_home.join(USAGE_ROLLUPS_CORRUPT_FILE);
+            warn!(
+                "moving unreadable {} to {}: {err}",
+                path.display(),
+                corrupt_path.display()
+            );
+            std::fs::rename(&path, corrupt_path)?;
+            UsageRollupsFile::default()
+        }
+        Err(err) => return Err(err),
+    };
+    let oldest = date - chrono::Duration::days(RETENTION_DAYS);
+    store.rollups.retain(|rollup| rollup.date >= oldest);
+    match store.rollups.iter_mut().find(|rollup| {
+        rollup.date == date && rollup.project_root == project_root && rollup.model == model
+    }) {
+        Some(rollup) => {
+            rollup.requests += 1;
+            rollup.token_usage.add_assign(usage);
+        }
+        None => store.rollups.push(StoredRollup {
+            date,
+            project_root: project_root.to_path_buf(),
+            model: model.to_string(),
+            requests: 1,
+            token_usage: usage.clone(),
+        }),
+    }
+    store.rollups.sort_by(|a, b| {
+        (a.date, &a.project_root, &a.model).cmp(&(b.date, &b.project_root, &b.model))
+    });
+    write_atomically(&path, &serde_json::to_string_pretty(&store)?)
+}
+
+/// The rollups `filter` selects, with costs from `pricing`. Blocks on the
+/// file system.
+pub(crate) fn query(
+    codex_home: &Path,
+    filter: &UsageFilter,
+    pricing: &HashMap<String, ModelPricing>,
+) -> io::Result<Vec<UsageRollup>> {
+    let path = codex_home.join(USAGE_ROLLUPS_FILE);
+    let store = match read_store(&path) {
+        Ok(store) => store,
+        Err(err) if err.kind() == io::ErrorKind::InvalidData => {
+            warn!("ignoring unreadable {}: {err}", path.display());
+            UsageRollupsFile::default()
+        }
+        Err(err) => return Err(err),
+    };
+    Ok(store
+        .rollups
+        .into_iter()
+        .filter(|rollup| {
+            filter
+                .project_root
+                .as_ref()
+                .is_none_or(|project_root| &rollup.project_root == project_root)
+                && filter.since.is_none_or(|since| rollup.date >= since)
+                && filter.until.is_none_or(|until| rollup.date <= until)
+        })
+        .map(|rollup| UsageRollup {
+            date: rollup.date.to_string(),
+            cost_usd: pricing.get(&rollup.model).map(|price| {
+                let usage = &rollup.token_usage;
+                (usage.non_cached_input() as f64 * price.input
+                    + usage.cached_input() as f64 * price.cached_input.unwrap_or(price.input)
+                    + usage.output_tokens.max(0) as f64 * price.output)
+                    / TOKENS_PER_PRICE_UNIT
+            }),
+            project_root: rollup.project_root,
+            model: rollup.model,
+            requests: rollup.requests,
+            token_usage: rollup.token_usage,
+        })
+        .collect())
+}
+
+/// The rollups in `path`, none if it does not exist. Fails with
+/// [`io::ErrorKind::InvalidData`] if it cannot be parsed.
+fn read_store(path: &Path) -> io::Result<UsageRollupsFile> {
+    let contents = match std::fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(UsageRollupsFile::default()),
+        Err(err) => return Err(err),
+    };
+    if contents.trim().is_empty() {
+        return Ok(UsageRollupsFile::default());
+    }
+    serde_json::from_str(&contents).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn usage(input_tokens: i64, cached_input_tokens: i64, output_tokens: i64) -> TokenUsage {
+        TokenUsage {
+            input_tokens,
+            cached_input_tokens,
+            output_tokens,
+            reasoning_output_tokens: 0,
+            total_tokens: input_tokens + output_tokens,
+        }
+    }
+
+    #[test]
+    fn rolls_usage_up_per_project_day_and_model() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let repo = Path::new("/work/repo");
+        let other = Path::new("/work/other");
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).expect("date");
+        let tuesday = NaiveDate::from_ymd_opt(2026, 3, 3).expect("date");
+        for (date, project_root) in [
+            (monday, repo),
+            (tuesday, repo),
+            (tuesday, repo),
+            (tuesday, other),
+        ] {
+            record(
+                codex_home.path(),
+                date,
+                project_root,
+                "gpt-5",
+                &usage(1_000_000, 500_000, 100_000),
+            )
+            .expect("record");
+        }
+
+        let pricing = HashMap::from([(
+            "gpt-5".to_string(),
+            ModelPricing {
+                input: 2.0,
+                cached_input: Some(0.5),
+                output: 10.0,
+            },
+        )]);
+        let rollups = query(
+            codex_home.path(),
+            &UsageFilter {
+                project_root: Some(repo.to_path_buf()),
+                since: Some(tuesday),
+                until: None,
+            },
+            &pricing,
+        )
+        .expect("query");
+        assert_eq!(
+            rollups,
+            vec![UsageRollup {
+                date: "2026-03-03".to_string(),
+                project_root: repo.to_path_buf(),
+                model: "gpt-5".to_string(),
+                requests: 2,
+                token_usage: usage(2_000_000, 1_000_000, 200_000),
+                cost_usd: Some(2.0 * (1.0 + 0.25 + 1.0)),
+            }]
+        );
+
+        let all =
+            query(codex_home.path(), &UsageFilter::default(), &HashMap::new()).expect("query");
+        assert_eq!(
+            all.iter()
+                .map(|rollup| (rollup.date.as_str(), rollup.requests, rollup.cost_usd))
+                .collect::<Vec<_>>(),
+            vec![
+                ("2026-03-02", 1, None),
+                ("2026-03-03", 1, None),
+                ("2026-03-03", 2, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn sets_a_corrupt_file_aside_and_drops_old_rollups() {
+        let codex_home = tempfile::tempdir().expect("tempdir");
+        let repo = Path::new("/work/repo");
+        std::fs::write(
+            codex_home.path().join(USAGE_ROLLUPS_FILE),
+            "{\"rollups\": [",
+        )
+        .expect("write corrupt file");
+        assert_eq!(
+            query(codex_home.path(), &UsageFilter::default(), &HashMap::new()).expect("query"),
+            Vec::new()
+        );
+
+        let old = NaiveDate::from_ymd_opt(2025, 1, 1).expect("date");
+        let today = NaiveDate::from_ymd_opt(2026, 3, 2).expect("date");
+        for date in [old, today] {
+            record(codex_home.path(), date, repo, "gpt-5", &usage(10, 0, 1)).expect("record");
+        }
+
+        let dates = query(codex_home.path(), &UsageFilter::default(), &HashMap::new())
+            .expect("query")
+            .into_iter()
+            .map(|rollup| rollup.date)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            (
+                dates,
+                std::fs::read_to_string(codex_home.path().join(USAGE_ROLLUPS_CORRUPT_FILE))
+                    .expect("corrupt file"),
+            ),
+            (
+                vec!["2026-03-02".to_string()],
+                "{\"rollups\": [".to_string()
+            )
+        );
+    }
+}
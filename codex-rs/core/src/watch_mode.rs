@@ -0,0 +1,171 @@
+//! Headless watch mode: a local, CI-like agent loop.
+//!
+//! When watch mode is enabled (`codex exec --watch`), the session watches the
+//! paths configured under `[watch]` and, once changes have settled for the
+//! debounce interval, starts a regular turn with the configured prompt. Runs
+//! never overlap: changes observed while a turn is active, or within the
+//! debounce interval after it ends, are dropped. The grace period covers the
+//! agent's own edits whose events are delivered after its turn ended, which
+//! would otherwise retrigger it.
+
+use std::collections::BTreeSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use codex_protocol::user_input::UserInput;
+use notify::EventKind;
+use notify::RecursiveMode;
+use notify::Watcher;
+use tokio::sync::mpsc;
+use tracing::debug;
+use tracing::info;
+use tracing::warn;
+
+use crate::codex::Session;
+use crate::config::types::WatchConfig;
+use crate::tasks::RegularTask;
+
+const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(1000);
+const CHANGED_FILES_PLACEHOLDER: &str = "{changed_files}";
+
+/// Starts watching the configured paths for the lifetime of `sess`.
+pub(crate) fn start_watch_mode(sess: &Arc<Session>, config: &WatchConfig, cwd: &Path) {
+    let (raw_tx, mut raw_rx) = mpsc::unbounded_channel();
+    let mut watcher = match notify::recommended_watcher(move |res| {
+        let _ = raw_tx.send(res);
+    }) {
+        Ok(watcher) => watcher,
+        Err(err) => {
+            warn!("watch mode disabled: failed to create file watcher: {err}");
+            return;
+        }
+    };
+    for path in &config.paths {
+        let path = cwd.join(path);
+        if let Err(err) = watcher.watch(&path, RecursiveMode::Recursive) {
+            warn!("watch mode: failed to watch {}: {err}", path.display());
+        }
+    }
+
+    let debounce = config
+        .debounce_ms
+        .map_or(DEFAULT_DEBOUNCE, Duration::from_millis);
+    let template = config.prompt.clone();
+    let cwd = cwd.to_path_buf();
+    let weak_sess = Arc::downgrade(sess);
+    tokio::spawn(async move {
+        // Keep the watcher alive for as long as the loop runs.
+        let _watcher = watcher;
+        let mut pending: BTreeSet<PathBuf> = BTreeSet::new();
+        // Whether the session has been idle for the debounce interval.
+        let settled = |sess: &Session| {
+            sess.idle_since
+                .borrow()
+                .is_some_and(|idle_since| idle_since.elapsed() >= debounce)
+        };
+        loop {
+            let res = if pending.is_empty() {
+                raw_rx.recv().await
+            } else {
+                match tokio::time::timeout(debounce, raw_rx.recv()).await {
+                    Ok(res) => res,
+                    Err(_) => {
+                        let Some(sess) = weak_sess.upgrade() else {
+                            break;
+                        };
+                        let changed = std::mem::take(&mut pending);
+                        if !settled(&sess) {
+                            debug!(
+                                "watch mode: turn in progress or just finished; dropping {} change(s)",
+                                changed.len()
+                            );
+                            continue;
+                        }
+                        let changed: Vec<PathBuf> = changed.into_iter().collect();
+                        info!(
+                            "watch mode: starting run for {} changed path(s)",
+                            changed.len()
+                        );
+                        let text = render_watch_prompt(&template, &changed, &cwd);
+                        let turn_context = sess.new_default_turn().await;
+                        sess.spawn_task(
+                            turn_context,
+                            vec![UserInput::Text {
+                                text,
+                                text_elements: Vec::new(),
+                            }],
+                            RegularTask::default(),
+                        )
+                        .await;
+                        continue;
+                    }
+                }
+            };
+            match res {
+                Some(Ok(event)) => {
+                    if !matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        continue;
+                    }
+                    let Some(sess) = weak_sess.upgrade() else {
+                        break;
+                    };
+                    if !settled(&sess) {
+                        continue;
+                    }
+                    pending.extend(
+                        event
+                            .paths
+                            .into_iter()
+                            .filter(|path| !path.components().any(|c| c.as_os_str() == ".git")),
+                    );
+                }
+                Some(Err(err)) => warn!("watch mode: file watcher error: {err}"),
+                None => break,
+            }
+        }
+    });
+}
+
+/// Fills `{changed_files}` in `template` with the changed paths, relative to
+/// `cwd` where possible. Without the placeholder the list is appended.
+fn render_watch_prompt(template: &str, changed: &[PathBuf], cwd: &Path) -> String {
+    let changed_files = changed
+        .iter()
+        .map(|path| path.strip_prefix(cwd).unwrap_or(path).display().to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    if template.contains(CHANGED_FILES_PLACEHOLDER) {
+        template.replace(CHANGED_FILES_PLACEHOLDER, &changed_files)
+    } else {
+        format!("{template}\n\nChanged files:\n{changed_files}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn render_watch_prompt_lists_changed_files() {
+        let cwd = PathBuf::from("/repo");
+        let changed = vec![
+            PathBuf::from("/repo/src/lib.rs"),
+            PathBuf::from("/elsewhere/notes.md"),
+        ];
+
+        assert_eq!(
+            render_watch_prompt("Fix failures in:\n{changed_files}", &changed, &cwd),
+            "Fix failures in:\nsrc/lib.rs\n/elsewhere/notes.md"
+        );
+        assert_eq!(
+            render_watch_prompt("Run tests.", &changed, &cwd),
+            "Run tests.\n\nChanged files:\nsrc/lib.rs\n/elsewhere/notes.md"
+        );
+    }
+}
@@ -51,6 +51,7 @@ async fn responses_stream_includes_subagent_header_on_review() {
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
@@ -155,6 +156,7 @@ async fn responses_stream_includes_subagent_header_on_other() {
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
@@ -254,6 +256,7 @@ async fn responses_respects_model_info_overrides_from_config() {
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
@@ -314,6 +314,7 @@ async fn apply_patch_cli_move_without_content_change_has_no_turn_diff(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -909,6 +910,7 @@ async fn apply_patch_shell_command_heredoc_with_cd_emits_turn_diff() -> Result<(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -989,6 +991,7 @@ async fn apply_patch_shell_command_failure_propagates_error_and_skips_diff() ->
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1139,6 +1142,7 @@ async fn apply_patch_emits_turn_diff_event_with_unified_diff(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1202,6 +1206,7 @@ async fn apply_patch_turn_diff_for_rename_with_content_change(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1273,6 +1278,7 @@ async fn apply_patch_aggregates_diff_across_multiple_tool_calls() -> Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1344,6 +1350,7 @@ async fn apply_patch_aggregates_diff_preserves_success_after_failure() -> Result
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -516,6 +516,7 @@ async fn submit_turn(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
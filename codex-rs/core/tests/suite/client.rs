@@ -990,6 +990,7 @@ async fn user_turn_collaboration_mode_overrides_model_and_effort() -> anyhow::Re
             collaboration_mode: Some(collaboration_mode),
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1314,6 +1315,7 @@ async fn azure_responses_request_includes_store_and_reasoning_ids() {
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
@@ -1906,6 +1908,7 @@ async fn azure_overrides_assign_properties_used_for_responses_url() {
             "Value".to_string(),
         )])),
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
@@ -1990,6 +1993,7 @@ async fn env_var_overrides_loaded_auth() {
             "Value".to_string(),
         )])),
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: None,
         stream_max_retries: None,
         stream_idle_timeout_ms: None,
@@ -1227,6 +1227,7 @@ fn websocket_provider(server: &WebSocketTestServer) -> ModelProviderInfo {
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: Some(0),
         stream_max_retries: Some(0),
         stream_idle_timeout_ms: Some(5_000),
@@ -173,6 +173,7 @@ async fn collaboration_instructions_added_on_user_turn() -> Result<()> {
             collaboration_mode: Some(collaboration_mode),
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
@@ -279,6 +280,7 @@ async fn user_turn_overrides_collaboration_instructions_after_override() -> Resu
             collaboration_mode: Some(turn_mode),
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&test.codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
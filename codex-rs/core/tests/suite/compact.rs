@@ -1636,6 +1636,7 @@ async fn auto_compact_runs_after_resume_when_token_usage_is_over_limit() {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .unwrap();
@@ -1725,6 +1726,7 @@ async fn pre_sampling_compact_runs_on_switch_to_smaller_context_model() {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .expect("submit first user turn");
@@ -1748,6 +1750,7 @@ async fn pre_sampling_compact_runs_on_switch_to_smaller_context_model() {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .expect("submit second user turn");
@@ -1857,6 +1860,7 @@ async fn pre_sampling_compact_runs_after_resume_and_switch_to_smaller_model() {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .expect("submit pre-resume turn");
@@ -1904,6 +1908,7 @@ async fn pre_sampling_compact_runs_after_resume_and_switch_to_smaller_model() {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .expect("submit resumed user turn");
@@ -3105,6 +3110,7 @@ async fn snapshot_request_shape_pre_turn_compaction_strips_incoming_model_switch
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .expect("submit first user turn");
@@ -3128,6 +3134,7 @@ async fn snapshot_request_shape_pre_turn_compaction_strips_incoming_model_switch
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await
         .expect("submit second user turn");
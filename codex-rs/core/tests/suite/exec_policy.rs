@@ -58,6 +58,7 @@ async fn submit_user_turn(
             summary: ReasoningSummary::Auto,
             collaboration_mode,
             personality: None,
+            sampling: None,
         })
         .await?;
     Ok(())
@@ -137,6 +138,7 @@ async fn execpolicy_blocks_shell_invocation() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
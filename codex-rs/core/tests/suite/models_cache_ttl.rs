@@ -102,6 +102,7 @@ async fn renews_cache_ttl_on_matching_models_etag() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -100,6 +100,7 @@ async fn user_turn_personality_none_does_not_add_update_message() -> anyhow::Res
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -146,6 +147,7 @@ async fn config_personality_some_sets_instructions_template() -> anyhow::Result<
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -199,6 +201,7 @@ async fn config_personality_none_sends_no_personality() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -258,6 +261,7 @@ async fn default_personality_is_pragmatic_without_config_toml() -> anyhow::Resul
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -305,6 +309,7 @@ async fn user_turn_personality_some_adds_update_message() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -339,6 +344,7 @@ async fn user_turn_personality_some_adds_update_message() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -401,6 +407,7 @@ async fn user_turn_personality_same_value_does_not_add_update_message() -> anyho
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -435,6 +442,7 @@ async fn user_turn_personality_same_value_does_not_add_update_message() -> anyho
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -507,6 +515,7 @@ async fn user_turn_personality_skips_if_feature_disabled() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -541,6 +550,7 @@ async fn user_turn_personality_skips_if_feature_disabled() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -649,6 +659,7 @@ async fn remote_model_friendly_personality_instructions_with_feature() -> anyhow
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: Some(Personality::Friendly),
+            sampling: None,
         })
         .await?;
 
@@ -759,6 +770,7 @@ async fn user_turn_personality_remote_model_template_includes_update_message() -
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -793,6 +805,7 @@ async fn user_turn_personality_remote_model_template_includes_update_message() -
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -71,6 +71,8 @@ async fn write_rollout_with_user_event(dir: &Path, thread_id: ThreadId) -> io::R
             model_provider: None,
             base_instructions: None,
             dynamic_tools: None,
+            instruction_layers: None,
+            experiments: None,
         },
         git: None,
     };
@@ -114,6 +116,8 @@ async fn write_rollout_with_meta_only(dir: &Path, thread_id: ThreadId) -> io::Re
             model_provider: None,
             base_instructions: None,
             dynamic_tools: None,
+            instruction_layers: None,
+            experiments: None,
         },
         git: None,
     };
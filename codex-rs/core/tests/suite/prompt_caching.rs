@@ -635,6 +635,7 @@ async fn per_turn_overrides_keep_cached_prefix_and_key_constant() -> anyhow::Res
             collaboration_mode: None,
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
@@ -750,6 +751,7 @@ async fn send_user_turn_with_no_changes_does_not_send_environment_context() -> a
             collaboration_mode: None,
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
@@ -769,6 +771,7 @@ async fn send_user_turn_with_no_changes_does_not_send_environment_context() -> a
             collaboration_mode: None,
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
@@ -858,6 +861,7 @@ async fn send_user_turn_with_changes_sends_environment_context() -> anyhow::Resu
             collaboration_mode: None,
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
@@ -877,6 +881,7 @@ async fn send_user_turn_with_changes_sends_environment_context() -> anyhow::Resu
             collaboration_mode: None,
             final_output_json_schema: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     wait_for_event(&codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
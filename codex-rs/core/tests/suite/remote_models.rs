@@ -178,6 +178,7 @@ async fn remote_models_long_model_slug_is_sent_with_high_reasoning() -> Result<(
             summary: config.model_reasoning_summary,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -323,6 +324,7 @@ async fn remote_models_remote_model_uses_unified_exec() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -534,6 +536,7 @@ async fn remote_models_apply_remote_base_instructions() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -98,9 +98,12 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -131,6 +134,7 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -188,6 +192,133 @@ async fn stdio_server_round_trip() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 1)]
+#[serial(mcp_test_value)]
+async fn stdio_read_only_tool_call_is_retried_after_timeout() -> anyhow::Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = responses::start_mock_server().await;
+
+    let call_id = "call-stall";
+    let server_name = "rmcp";
+    let tool_name = format!("mcp__{server_name}__stall_once");
+
+    mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_response_created("resp-1"),
+            responses::ev_function_call(call_id, &tool_name, "{}"),
+            responses::ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+    let final_mock = mount_sse_once(
+        &server,
+        responses::sse(vec![
+            responses::ev_assistant_message("msg-1", "rmcp stall_once tool completed."),
+            responses::ev_completed("resp-2"),
+        ]),
+    )
+    .await;
+
+    let rmcp_test_server_bin = stdio_server_bin()?;
+
+    let fixture = test_codex()
+        .with_config(move |config| {
+            let mut servers = config.mcp_servers.get().clone();
+            servers.insert(
+                server_name.to_string(),
+                McpServerConfig {
+                    transport: McpServerTransportConfig::Stdio {
+                        command: rmcp_test_server_bin,
+                        args: Vec::new(),
+                        env: None,
+                        env_vars: Vec::new(),
+                        cwd: None,
+                    },
+                    enabled: true,
+                    required: false,
+                    lazy: false,
+                    disabled_reason: None,
+                    startup_timeout_sec: Some(Duration::from_secs(10)),
+                    tool_timeout_sec: Some(Duration::from_secs(1)),
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
+                    enabled_tools: None,
+                    disabled_tools: None,
+                    scopes: None,
+                },
+            );
+            config
+                .mcp_servers
+                .set(servers)
+                .expect("test mcp servers should accept any configuration");
+        })
+        .build(&server)
+        .await?;
+    let session_model = fixture.session_configured.model.clone();
+
+    fixture
+        .codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Text {
+                text: "call the rmcp stall_once tool".into(),
+                text_elements: Vec::new(),
+            }],
+            final_output_json_schema: None,
+            cwd: fixture.cwd.path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::new_read_only_policy(),
+            model: session_model,
+            effort: None,
+            summary: ReasoningSummary::Auto,
+            collaboration_mode: None,
+            personality: None,
+            sampling: None,
+        })
+        .await?;
+
+    // The first call times out and the router retries the read-only tool.
+    let mut end_results = Vec::new();
+    while end_results.len() < 2 {
+        let event = wait_for_event(&fixture.codex, |ev| {
+            matches!(ev, EventMsg::McpToolCallEnd(_))
+        })
+        .await;
+        let EventMsg::McpToolCallEnd(end) = event else {
+            unreachable!("event guard guarantees McpToolCallEnd");
+        };
+        assert_eq!(end.call_id, call_id);
+        end_results.push(end.result);
+    }
+    let first_error = end_results[0]
+        .as_ref()
+        .expect_err("first stall_once call should time out");
+    assert!(
+        first_error.contains("timed out"),
+        "unexpected first call error: {first_error}"
+    );
+    let retried = end_results[1]
+        .as_ref()
+        .expect("retried stall_once call should succeed");
+    assert_eq!(retried.structured_content, Some(json!({ "stalled": true })));
+
+    wait_for_event(&fixture.codex, |ev| matches!(ev, EventMsg::TurnComplete(_))).await;
+
+    let output = final_mock
+        .single_request()
+        .function_call_output_text(call_id)
+        .expect("stall_once output should be sent to the model");
+    assert!(
+        output.contains("stalled"),
+        "model should see the retried result: {output}"
+    );
+
+    server.verify().await;
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 1)]
 #[serial(mcp_test_value)]
 async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
@@ -240,9 +371,12 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -298,6 +432,7 @@ async fn stdio_image_responses_round_trip() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -456,9 +591,12 @@ async fn stdio_image_responses_are_sanitized_for_text_only_model() -> anyhow::Re
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -495,6 +633,7 @@ async fn stdio_image_responses_are_sanitized_for_text_only_model() -> anyhow::Re
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -574,9 +713,12 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -607,6 +749,7 @@ async fn stdio_server_propagates_whitelisted_env_vars() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -733,9 +876,12 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -766,6 +912,7 @@ async fn streamable_http_tool_call_round_trip() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -952,9 +1099,12 @@ async fn streamable_http_with_oauth_round_trip_impl() -> anyhow::Result<()> {
                     },
                     enabled: true,
                     required: false,
+                    lazy: false,
                     disabled_reason: None,
                     startup_timeout_sec: Some(Duration::from_secs(10)),
                     tool_timeout_sec: None,
+                    elicitation: false,
+                    elicitation_timeout_sec: None,
                     enabled_tools: None,
                     disabled_tools: None,
                     scopes: None,
@@ -985,6 +1135,7 @@ async fn streamable_http_with_oauth_round_trip_impl() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -127,9 +127,12 @@ fn rmcp_server_config(command: String) -> McpServerConfig {
         },
         enabled: true,
         required: false,
+        lazy: false,
         disabled_reason: None,
         startup_timeout_sec: Some(Duration::from_secs(10)),
         tool_timeout_sec: None,
+        elicitation: false,
+        elicitation_timeout_sec: None,
         enabled_tools: None,
         disabled_tools: None,
         scopes: None,
@@ -159,6 +159,7 @@ async fn run_snapshot_command_with_options(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -245,6 +246,7 @@ async fn run_shell_command_snapshot_with_options(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -314,6 +316,7 @@ async fn run_tool_turn_on_harness(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -532,6 +535,7 @@ async fn shell_command_snapshot_still_intercepts_apply_patch() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
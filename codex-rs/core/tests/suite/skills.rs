@@ -2,17 +2,23 @@
 #![allow(clippy::unwrap_used, clippy::expect_used)]
 
 use anyhow::Result;
+use codex_core::features::Feature;
 use codex_protocol::protocol::AskForApproval;
+use codex_protocol::protocol::EventMsg;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::SandboxPolicy;
+use codex_protocol::request_user_input::RequestUserInputAnswer;
+use codex_protocol::request_user_input::RequestUserInputResponse;
 use codex_protocol::user_input::UserInput;
 use core_test_support::responses::ev_assistant_message;
 use core_test_support::responses::ev_completed;
 use core_test_support::responses::ev_response_created;
 use core_test_support::responses::mount_sse_once;
+use core_test_support::responses::mount_sse_sequence;
 use core_test_support::responses::sse;
 use core_test_support::responses::start_mock_server;
 use core_test_support::skip_if_no_network;
+use core_test_support::skip_if_sandbox;
 use core_test_support::test_codex::test_codex;
 use std::fs;
 use std::path::Path;
@@ -80,6 +86,7 @@ async fn user_turn_includes_skill_instructions() -> Result<()> {
             summary: codex_protocol::config_types::ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -223,3 +230,166 @@ async fn list_skills_includes_system_cache_entries() -> Result<()> {
 
     Ok(())
 }
+
+/// Writes a skill that depends on the CLI tool `codex-fake-tool`, whose
+/// install command puts a script printing its version into the prefix.
+fn write_skill_with_cli_dependency(home: &Path) {
+    write_skill(home, "fake", "needs a tool", "use codex-fake-tool");
+    let metadata_dir = home.join("skills/fake/agents");
+    fs::create_dir_all(&metadata_dir).unwrap();
+    fs::write(
+        metadata_dir.join("openai.yaml"),
+        r#"dependencies:
+  tools:
+    - type: cli
+      value: codex-fake-tool
+      install: >-
+        mkdir -p "$CODEX_DEPENDENCY_PREFIX/bin" &&
+        printf '#!/bin/sh\necho codex-fake-tool 1.0\n' > "$CODEX_DEPENDENCY_PREFIX/bin/codex-fake-tool" &&
+        chmod +x "$CODEX_DEPENDENCY_PREFIX/bin/codex-fake-tool"
+"#,
+    )
+    .unwrap();
+}
+
+/// Runs a turn mentioning the `fake` skill, answering the CLI dependency
+/// prompt with `answer` if it is shown, and returns the questions asked.
+async fn run_turn_with_cli_dependency(
+    test: &core_test_support::test_codex::TestCodex,
+    answer: &str,
+) -> Result<Vec<String>> {
+    let skill_path = fs::canonicalize(test.codex_home_path().join("skills/fake/SKILL.md"))?;
+    test.codex
+        .submit(Op::UserTurn {
+            items: vec![UserInput::Skill {
+                name: "fake".to_string(),
+                path: skill_path,
+            }],
+            final_output_json_schema: None,
+            cwd: test.cwd_path().to_path_buf(),
+            approval_policy: AskForApproval::Never,
+            sandbox_policy: SandboxPolicy::DangerFullAccess,
+            model: test.session_configured.model.clone(),
+            effort: None,
+            summary: codex_protocol::config_types::ReasoningSummary::Auto,
+            collaboration_mode: None,
+            personality: None,
+            sampling: None,
+        })
+        .await?;
+
+    let mut questions = Vec::new();
+    loop {
+        let event = core_test_support::wait_for_event(test.codex.as_ref(), |event| {
+            matches!(
+                event,
+                EventMsg::RequestUserInput(_) | EventMsg::TurnComplete(_)
+            )
+        })
+        .await;
+        let EventMsg::RequestUserInput(request) = event else {
+            return Ok(questions);
+        };
+        let answers = request
+            .questions
+            .iter()
+            .map(|question| {
+                questions.push(question.id.clone());
+                (
+                    question.id.clone(),
+                    RequestUserInputAnswer {
+                        answers: vec![answer.to_string()],
+                    },
+                )
+            })
+            .collect();
+        test.codex
+            .submit(Op::UserInputAnswer {
+                id: request.turn_id.clone(),
+                response: RequestUserInputResponse { answers },
+            })
+            .await?;
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn approved_cli_dependency_is_installed_and_verified() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+    skip_if_sandbox!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex()
+        .with_pre_build_hook(write_skill_with_cli_dependency)
+        .with_config(|config| {
+            config.features.enable(Feature::SkillCliDependencyInstall);
+        });
+    let test = builder.build(&server).await?;
+    mount_sse_once(
+        &server,
+        sse(vec![
+            ev_response_created("resp-1"),
+            ev_assistant_message("msg-1", "done"),
+            ev_completed("resp-1"),
+        ]),
+    )
+    .await;
+
+    let questions = run_turn_with_cli_dependency(&test, "Install").await?;
+
+    assert_eq!(
+        questions,
+        vec!["skill_cli_dependency_install:codex-fake-tool".to_string()]
+    );
+    let installed = test
+        .codex_home_path()
+        .join("tools/codex-fake-tool/bin/codex-fake-tool");
+    assert!(installed.exists(), "expected {installed:?} to be installed");
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn skipped_cli_dependency_is_not_offered_again() -> Result<()> {
+    skip_if_no_network!(Ok(()));
+
+    let server = start_mock_server().await;
+    let mut builder = test_codex()
+        .with_pre_build_hook(write_skill_with_cli_dependency)
+        .with_config(|config| {
+            config.features.enable(Feature::SkillCliDependencyInstall);
+        });
+    let test = builder.build(&server).await?;
+    mount_sse_sequence(
+        &server,
+        vec![
+            sse(vec![
+                ev_response_created("resp-1"),
+                ev_assistant_message("msg-1", "done"),
+                ev_completed("resp-1"),
+            ]),
+            sse(vec![
+                ev_response_created("resp-2"),
+                ev_assistant_message("msg-2", "done"),
+                ev_completed("resp-2"),
+            ]),
+        ],
+    )
+    .await;
+
+    let first = run_turn_with_cli_dependency(&test, "Skip").await?;
+    let second = run_turn_with_cli_dependency(&test, "Skip").await?;
+
+    assert_eq!(
+        (first, second),
+        (
+            vec!["skill_cli_dependency_install:codex-fake-tool".to_string()],
+            Vec::new()
+        )
+    );
+    assert!(
+        !test
+            .codex_home_path()
+            .join("tools/codex-fake-tool")
+            .exists()
+    );
+    Ok(())
+}
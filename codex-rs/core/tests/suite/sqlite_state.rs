@@ -128,6 +128,8 @@ async fn backfill_scans_existing_rollouts() -> Result<()> {
                     model_provider: None,
                     base_instructions: None,
                     dynamic_tools: Some(dynamic_tools_for_hook),
+                    instruction_layers: None,
+                    experiments: None,
                 },
                 git: None,
             };
@@ -73,6 +73,7 @@ async fn continue_after_stream_error() {
         query_params: None,
         http_headers: None,
         env_http_headers: None,
+        request_signing: None,
         request_max_retries: Some(1),
         stream_max_retries: Some(1),
         stream_idle_timeout_ms: Some(2_000),
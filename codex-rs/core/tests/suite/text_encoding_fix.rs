@@ -71,6 +71,7 @@ fn decode_shell_output(bytes: &[u8]) -> String {
     StreamOutput {
         text: bytes.to_vec(),
         truncated_after_lines: None,
+        truncated: None,
     }
     .from_utf8_lossy()
     .text
@@ -365,9 +365,12 @@ async fn mcp_tool_call_output_exceeds_limit_truncated_for_model() -> Result<()>
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(std::time::Duration::from_secs(10)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -459,9 +462,12 @@ async fn mcp_image_output_preserves_image_and_no_text_summary() -> Result<()> {
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(Duration::from_secs(10)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
@@ -491,6 +497,7 @@ async fn mcp_image_output_preserves_image_and_no_text_summary() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -724,9 +731,12 @@ async fn mcp_tool_call_output_not_truncated_with_custom_limit() -> Result<()> {
                 },
                 enabled: true,
                 required: false,
+                lazy: false,
                 disabled_reason: None,
                 startup_timeout_sec: Some(std::time::Duration::from_secs(10)),
                 tool_timeout_sec: None,
+                elicitation: false,
+                elicitation_timeout_sec: None,
                 enabled_tools: None,
                 disabled_tools: None,
                 scopes: None,
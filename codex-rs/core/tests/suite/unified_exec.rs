@@ -212,6 +212,7 @@ async fn unified_exec_intercepts_apply_patch_exec_command() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -341,6 +342,7 @@ async fn unified_exec_emits_exec_command_begin_event() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -419,6 +421,7 @@ async fn unified_exec_resolves_relative_workdir() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -500,6 +503,7 @@ async fn unified_exec_respects_workdir_override() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -593,6 +597,7 @@ async fn unified_exec_emits_exec_command_end_event() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -668,6 +673,7 @@ async fn unified_exec_emits_output_delta_for_exec_command() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -744,6 +750,7 @@ async fn unified_exec_full_lifecycle_with_background_end_event() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -874,6 +881,7 @@ async fn unified_exec_emits_terminal_interaction_for_write_stdin() -> Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1011,6 +1019,7 @@ async fn unified_exec_terminal_interaction_captures_delayed_output() -> Result<(
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1171,6 +1180,7 @@ async fn unified_exec_emits_one_begin_and_one_end_event() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1269,6 +1279,7 @@ async fn exec_command_reports_chunk_and_exit_metadata() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1387,6 +1398,7 @@ async fn unified_exec_defaults_to_pipe() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1477,6 +1489,7 @@ async fn unified_exec_can_enable_tty() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1558,6 +1571,7 @@ async fn unified_exec_respects_early_exit_notifications() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1689,6 +1703,7 @@ async fn write_stdin_returns_exit_metadata_and_clears_session() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1857,6 +1872,7 @@ async fn unified_exec_emits_end_event_when_session_dies_via_stdin() -> Result<()
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -1934,6 +1950,7 @@ async fn unified_exec_keeps_long_running_session_after_turn_end() -> Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2022,6 +2039,7 @@ async fn unified_exec_interrupt_terminates_long_running_session() -> Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2119,6 +2137,7 @@ async fn unified_exec_reuses_session_via_stdin() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2254,6 +2273,7 @@ PY
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
     // This is a worst case scenario for the truncate logic.
@@ -2368,6 +2388,7 @@ async fn unified_exec_timeout_and_followup_poll() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2464,6 +2485,7 @@ PY
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2546,6 +2568,7 @@ async fn unified_exec_runs_under_sandbox() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2650,6 +2673,7 @@ async fn unified_exec_python_prompt_under_seatbelt() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2745,6 +2769,7 @@ async fn unified_exec_runs_on_all_platforms() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -2880,6 +2905,7 @@ async fn unified_exec_prunes_exited_sessions_first() -> Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -181,6 +181,7 @@ async fn user_shell_command_does_not_replace_active_turn() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
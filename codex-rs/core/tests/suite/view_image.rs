@@ -106,6 +106,7 @@ async fn user_turn_with_local_image_attaches_image() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -208,6 +209,7 @@ async fn view_image_tool_attaches_local_image() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -349,6 +351,7 @@ console.log(out.output?.body?.text ?? "");
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -450,6 +453,7 @@ async fn view_image_tool_errors_when_path_is_directory() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -525,6 +529,7 @@ async fn view_image_tool_placeholder_for_non_image_files() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -619,6 +624,7 @@ async fn view_image_tool_errors_when_file_missing() -> anyhow::Result<()> {
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -741,6 +747,7 @@ async fn view_image_tool_returns_unsupported_message_for_text_only_model() -> an
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -817,6 +824,7 @@ async fn replaces_invalid_local_image_after_bad_request() -> anyhow::Result<()>
             summary: ReasoningSummary::Auto,
             collaboration_mode: None,
             personality: None,
+            sampling: None,
         })
         .await?;
 
@@ -79,6 +79,33 @@ pub struct Cli {
     #[arg(long = "output-schema", value_name = "FILE")]
     pub output_schema: Option<PathBuf>,
 
+    /// Read stdin and attach it to the session as content of type
+    /// CONTENT_TYPE (default `text/plain`), e.g. a diff or a log, instead of
+    /// reading the prompt from it. The prompt must be passed as an argument.
+    #[arg(
+        long = "attach-stdin",
+        value_name = "CONTENT_TYPE",
+        num_args = 0..=1,
+        default_missing_value = "text/plain"
+    )]
+    pub attach_stdin: Option<String>,
+
+    /// Keep running and start a new turn whenever the paths configured under
+    /// `[watch]` in config.toml change. The prompt is optional in this mode.
+    #[arg(long = "watch", default_value_t = false)]
+    pub watch: bool,
+
+    /// Propose changes instead of applying them: file edits are collected into
+    /// a unified diff written to PATH, and the working tree is left untouched.
+    #[arg(long = "patch-output", value_name = "PATH")]
+    pub patch_output: Option<PathBuf>,
+
+    /// Run the prompt as a benchmarked turn: the benchmarks configured under
+    /// `[benchmarks]` in config.toml run before and after the changes, and
+    /// regressions are reported or sent back to the agent to fix.
+    #[arg(long = "benchmark", default_value_t = false)]
+    pub benchmark: bool,
+
     #[clap(skip)]
     pub config_overrides: CliConfigOverrides,
 
@@ -112,6 +139,9 @@ pub enum Command {
 
     /// Run a code review against the current repository.
     Review(ReviewArgs),
+
+    /// Run a security audit of the current repository.
+    Audit(AuditArgs),
 }
 
 #[derive(Args, Debug)]
@@ -211,7 +241,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "uncommitted",
         default_value_t = false,
-        conflicts_with_all = ["base", "commit", "prompt"]
+        conflicts_with_all = ["base", "commit", "pull_request", "prompt"]
     )]
     pub uncommitted: bool,
 
@@ -219,7 +249,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "base",
         value_name = "BRANCH",
-        conflicts_with_all = ["uncommitted", "commit", "prompt"]
+        conflicts_with_all = ["uncommitted", "commit", "pull_request", "prompt"]
     )]
     pub base: Option<String>,
 
@@ -227,7 +257,7 @@ pub struct ReviewArgs {
     #[arg(
         long = "commit",
         value_name = "SHA",
-        conflicts_with_all = ["uncommitted", "base", "prompt"]
+        conflicts_with_all = ["uncommitted", "base", "pull_request", "prompt"]
     )]
     pub commit: Option<String>,
 
@@ -235,11 +265,35 @@ pub struct ReviewArgs {
     #[arg(long = "title", value_name = "TITLE", requires = "commit")]
     pub commit_title: Option<String>,
 
+    /// Review the changes in a pull request, given by its URL.
+    #[arg(
+        long = "pr",
+        value_name = "URL",
+        conflicts_with_all = ["uncommitted", "base", "commit", "prompt"]
+    )]
+    pub pull_request: Option<String>,
+
     /// Custom review instructions. If `-` is used, read from stdin.
     #[arg(value_name = "PROMPT", value_hint = clap::ValueHint::Other)]
     pub prompt: Option<String>,
 }
 
+#[derive(Parser, Debug)]
+pub struct AuditArgs {
+    /// Audit the changes against the given base branch instead of the
+    /// uncommitted changes.
+    #[arg(long = "base", value_name = "BRANCH", conflicts_with = "all")]
+    pub base: Option<String>,
+
+    /// Audit every file tracked by the repository.
+    #[arg(long = "all", default_value_t = false)]
+    pub all: bool,
+
+    /// Write the findings to this file as SARIF 2.1.0.
+    #[arg(long = "sarif", value_name = "FILE")]
+    pub sarif: Option<PathBuf>,
+}
+
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
 #[value(rename_all = "kebab-case")]
 pub enum Color {
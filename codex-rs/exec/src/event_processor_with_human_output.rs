@@ -29,6 +29,7 @@ use codex_protocol::protocol::PatchApplyBeginEvent;
 use codex_protocol::protocol::PatchApplyEndEvent;
 use codex_protocol::protocol::SessionConfiguredEvent;
 use codex_protocol::protocol::StreamErrorEvent;
+use codex_protocol::protocol::ToolProgressEvent;
 use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnCompleteEvent;
 use codex_protocol::protocol::TurnDiffEvent;
@@ -240,6 +241,24 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 ts_msg!(self, "{}", message.style(self.dimmed));
             }
+            EventMsg::ToolProgress(ToolProgressEvent {
+                completed,
+                total,
+                message,
+                ..
+            }) => {
+                let steps = match total {
+                    Some(total) if total > 0 => {
+                        format!("{}%", completed.saturating_mul(100) / total)
+                    }
+                    _ => format!("{completed} done"),
+                };
+                let progress = match message {
+                    Some(message) => format!("{message} · {steps}"),
+                    None => steps,
+                };
+                ts_msg!(self, "{}", progress.style(self.dimmed));
+            }
             EventMsg::StreamError(StreamErrorEvent {
                 message,
                 additional_details,
@@ -522,7 +541,7 @@ impl EventProcessor for EventProcessorWithHumanOutput {
                     eprintln!("{}", line.style(self.dimmed));
                 }
             }
-            EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => {
+            EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) => {
                 ts_msg!(
                     self,
                     "{}",
@@ -788,6 +807,38 @@ impl EventProcessor for EventProcessorWithHumanOutput {
             | EventMsg::ListSkillsResponse(_)
             | EventMsg::ListRemoteSkillsResponse(_)
             | EventMsg::RemoteSkillDownloaded(_)
+            | EventMsg::PatchArtifact(_)
+            | EventMsg::ToolCallArgumentsPreview(_)
+            | EventMsg::SessionsPruned(_)
+            | EventMsg::SessionExported(_)
+            | EventMsg::SessionImported(_)
+            | EventMsg::ApprovalResolved(_)
+            | EventMsg::ObserverPromoted(_)
+            | EventMsg::InlineEditResult(_)
+            | EventMsg::SessionTools(_)
+            | EventMsg::ToolSelection(_)
+            | EventMsg::ToolSelectionRestored(_)
+            | EventMsg::ElicitationTimedOut(_)
+            | EventMsg::ToolInvocationResult(_)
+            | EventMsg::CompletionResult(_)
+            | EventMsg::EnabledToolsUpdated(_)
+            | EventMsg::SuggestedAllowlist(_)
+            | EventMsg::DependencyAudit(_)
+            | EventMsg::LicenseReport(_)
+            | EventMsg::ResponseCandidates(_)
+            | EventMsg::ResponseCandidateSelected(_)
+            | EventMsg::RateLimitForecast(_)
+            | EventMsg::PlanOrCreditsChanged(_)
+            | EventMsg::OfflineStatus(_)
+            | EventMsg::UsageRollups(_)
+            | EventMsg::TurnTimings(_)
+            | EventMsg::TurnTimingRecorded(_)
+            | EventMsg::ContextBreakdown(_)
+            | EventMsg::HistoryMetadataRecorded(_)
+            | EventMsg::ActiveToolCalls(_)
+            | EventMsg::ToolLockHeld(_)
+            | EventMsg::McpToolCallProgress(_)
+            | EventMsg::McpToolCallLog(_)
             | EventMsg::RawResponseItem(_)
             | EventMsg::UserMessage(_)
             | EventMsg::EnteredReviewMode(_)
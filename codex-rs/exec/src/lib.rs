@@ -10,6 +10,7 @@ mod event_processor_with_human_output;
 pub mod event_processor_with_jsonl_output;
 pub mod exec_events;
 
+pub use cli::AuditArgs;
 pub use cli::Cli;
 pub use cli::Command;
 pub use cli::ReviewArgs;
@@ -66,10 +67,14 @@ use uuid::Uuid;
 use crate::cli::Command as ExecCommand;
 use crate::event_processor::CodexStatus;
 use crate::event_processor::EventProcessor;
+use codex_core::default_client::set_default_client_http_network_config;
 use codex_core::default_client::set_default_client_residency_requirement;
 use codex_core::default_client::set_default_originator;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::find_thread_path_by_name_str;
+use codex_core::review_format::review_output_to_sarif;
+use codex_core::review_prompts::SecurityAuditScope;
+use codex_core::review_prompts::security_audit_request;
 
 enum InitialOperation {
     UserTurn {
@@ -79,6 +84,11 @@ enum InitialOperation {
     Review {
         review_request: ReviewRequest,
     },
+    BenchmarkedTurn {
+        items: Vec<UserInput>,
+    },
+    /// Watch mode without an initial prompt: wait for the first change.
+    Watch,
 }
 
 #[derive(Clone)]
@@ -112,6 +122,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         sandbox_mode: sandbox_mode_cli_arg,
         prompt,
         output_schema: output_schema_path,
+        attach_stdin,
+        watch,
+        patch_output,
+        benchmark,
         config_overrides,
     } = cli;
 
@@ -263,6 +277,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         tools_web_search_request: None,
         ephemeral: ephemeral.then_some(true),
         additional_writable_roots: add_dir,
+        instruction_layers: Vec::new(),
+        watch: watch.then_some(true),
+        patch_artifact_path: patch_output,
     };
 
     let config = ConfigBuilder::default()
@@ -285,6 +302,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     }
 
     set_default_client_residency_requirement(config.enforce_residency.value());
+    if let Err(err) = set_default_client_http_network_config(&config.http) {
+        eprintln!("Error loading [http] settings: {err}");
+        std::process::exit(1);
+    }
 
     if let Err(err) = enforce_login_restrictions(&config) {
         eprintln!("{err}");
@@ -398,12 +419,47 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
     } else {
         thread_manager.start_thread(config.clone()).await?
     };
+    let sarif_path = match &command {
+        Some(ExecCommand::Audit(args)) => args.sarif.clone(),
+        Some(ExecCommand::Review(_) | ExecCommand::Resume(_)) | None => None,
+    };
+    let stdin_attachment = match attach_stdin {
+        Some(content_type) => {
+            // Stdin can't carry both the attachment and the prompt.
+            let prompt_from_stdin = match &command {
+                Some(ExecCommand::Review(_) | ExecCommand::Audit(_)) => false,
+                Some(ExecCommand::Resume(args)) => args
+                    .prompt
+                    .as_ref()
+                    .or(args.session_id.as_ref().filter(|_| args.last))
+                    .or(prompt.as_ref())
+                    .is_none_or(|prompt| prompt == "-"),
+                None => prompt.as_deref().map_or(!watch, |prompt| prompt == "-"),
+            };
+            if prompt_from_stdin {
+                anyhow::bail!(
+                    "--attach-stdin reads stdin as an attachment; pass the prompt as an argument"
+                );
+            }
+            let mut bytes = Vec::new();
+            std::io::stdin().read_to_end(&mut bytes)?;
+            let content = decode_prompt_bytes(&bytes)
+                .map_err(|err| anyhow::anyhow!("failed to read stdin attachment: {err}"))?;
+            Some((content_type, content))
+        }
+        None => None,
+    };
     let (initial_operation, prompt_summary) = match (command, prompt, images) {
         (Some(ExecCommand::Review(review_cli)), _, _) => {
             let review_request = build_review_request(review_cli)?;
             let summary = codex_core::review_prompts::user_facing_hint(&review_request.target);
             (InitialOperation::Review { review_request }, summary)
         }
+        (Some(ExecCommand::Audit(audit_cli)), _, _) => {
+            let review_request = build_audit_request(&audit_cli);
+            let summary = review_request.user_facing_hint.clone().unwrap_or_default();
+            (InitialOperation::Review { review_request }, summary)
+        }
         (Some(ExecCommand::Resume(args)), root_prompt, imgs) => {
             let prompt_arg = args
                 .prompt
@@ -436,6 +492,9 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 prompt_text,
             )
         }
+        (None, None, imgs) if watch && imgs.is_empty() => {
+            (InitialOperation::Watch, "Watching for changes".to_string())
+        }
         (None, root_prompt, imgs) => {
             let prompt_text = resolve_prompt(root_prompt);
             let mut items: Vec<UserInput> = imgs
@@ -447,14 +506,18 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 // CLI input doesn't track UI element ranges, so none are available here.
                 text_elements: Vec::new(),
             });
-            let output_schema = load_output_schema(output_schema_path);
-            (
-                InitialOperation::UserTurn {
-                    items,
-                    output_schema,
-                },
-                prompt_text,
-            )
+            if benchmark {
+                (InitialOperation::BenchmarkedTurn { items }, prompt_text)
+            } else {
+                let output_schema = load_output_schema(output_schema_path);
+                (
+                    InitialOperation::UserTurn {
+                        items,
+                        output_schema,
+                    },
+                    prompt_text,
+                )
+            }
         }
     };
 
@@ -475,6 +538,10 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                 tracing::debug!("Keyboard interrupt");
                 // Immediately notify Codex to abort any in-flight task.
                 thread.submit(Op::Interrupt).await.ok();
+                // Watch mode only ends on interrupt.
+                if watch {
+                    thread.submit(Op::Shutdown).await.ok();
+                }
             }
         });
     }
@@ -510,6 +577,16 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         });
     }
 
+    if let Some((content_type, content)) = stdin_attachment {
+        thread
+            .submit(Op::AttachInput {
+                content_type,
+                name: Some("stdin".to_string()),
+                content,
+            })
+            .await?;
+    }
+
     match initial_operation {
         InitialOperation::UserTurn {
             items,
@@ -527,6 +604,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                     final_output_json_schema: output_schema,
                     collaboration_mode: None,
                     personality: None,
+                    sampling: None,
                 })
                 .await?;
             info!("Sent prompt with event ID: {task_id}");
@@ -537,6 +615,15 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
             info!("Sent review request with event ID: {task_id}");
             task_id
         }
+        InitialOperation::BenchmarkedTurn { items } => {
+            let task_id = thread.submit(Op::BenchmarkedTurn { items }).await?;
+            info!("Sent benchmarked turn with event ID: {task_id}");
+            task_id
+        }
+        InitialOperation::Watch => {
+            info!("Waiting for watched paths to change");
+            String::new()
+        }
     };
 
     // Run the loop until the task is complete.
@@ -558,6 +645,23 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         {
             continue;
         }
+        if let Some(path) = &sarif_path
+            && let EventMsg::ExitedReviewMode(ev) = &event.msg
+        {
+            match &ev.review_output {
+                Some(output) => {
+                    let sarif = review_output_to_sarif(output, config.cwd.as_path());
+                    if let Err(err) = std::fs::write(path, format!("{sarif:#}\n")) {
+                        error_seen = true;
+                        eprintln!("Failed to write SARIF output to {}: {err}", path.display());
+                    }
+                }
+                None => {
+                    error_seen = true;
+                    eprintln!("Audit produced no findings output; SARIF file not written.");
+                }
+            }
+        }
         if let EventMsg::ElicitationRequest(ev) = &event.msg {
             // Automatically cancel elicitation requests in exec mode.
             thread
@@ -565,6 +669,7 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
                     server_name: ev.server_name.clone(),
                     request_id: ev.id.clone(),
                     decision: ElicitationAction::Cancel,
+                    content: None,
                 })
                 .await?;
         }
@@ -591,6 +696,8 @@ pub async fn run_main(cli: Cli, codex_linux_sandbox_exe: Option<PathBuf>) -> any
         }
         match shutdown {
             CodexStatus::Running => continue,
+            // In watch mode, completed turns wait for the next change.
+            CodexStatus::InitiateShutdown if watch => continue,
             CodexStatus::InitiateShutdown => {
                 if !shutdown_requested {
                     thread.submit(Op::Shutdown).await?;
@@ -836,6 +943,8 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
             sha,
             title: args.commit_title,
         }
+    } else if let Some(url) = args.pull_request {
+        ReviewTarget::PullRequest { url }
     } else if let Some(prompt_arg) = args.prompt {
         let prompt = resolve_prompt(Some(prompt_arg)).trim().to_string();
         if prompt.is_empty() {
@@ -846,7 +955,7 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
         }
     } else {
         anyhow::bail!(
-            "Specify --uncommitted, --base, --commit, or provide custom review instructions"
+            "Specify --uncommitted, --base, --commit, --pr, or provide custom review instructions"
         );
     };
 
@@ -856,11 +965,44 @@ fn build_review_request(args: ReviewArgs) -> anyhow::Result<ReviewRequest> {
     })
 }
 
+fn build_audit_request(args: &AuditArgs) -> ReviewRequest {
+    let scope = if args.all {
+        SecurityAuditScope::AllFiles
+    } else if let Some(branch) = &args.base {
+        SecurityAuditScope::BaseBranch {
+            branch: branch.clone(),
+        }
+    } else {
+        SecurityAuditScope::UncommittedChanges
+    };
+    security_audit_request(&scope)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn builds_audit_request_for_base_branch() {
+        let request = build_audit_request(&AuditArgs {
+            base: Some("main".to_string()),
+            all: false,
+            sarif: None,
+        });
+
+        assert_eq!(
+            request,
+            security_audit_request(&SecurityAuditScope::BaseBranch {
+                branch: "main".to_string(),
+            })
+        );
+        assert_eq!(
+            request.user_facing_hint.as_deref(),
+            Some("security audit of changes against 'main'")
+        );
+    }
+
     #[test]
     fn builds_uncommitted_review_request() {
         let request = build_review_request(ReviewArgs {
@@ -868,6 +1010,7 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            pull_request: None,
             prompt: None,
         })
         .expect("builds uncommitted review request");
@@ -887,6 +1030,7 @@ mod tests {
             base: None,
             commit: Some("123456789".to_string()),
             commit_title: Some("Add review command".to_string()),
+            pull_request: None,
             prompt: None,
         })
         .expect("builds commit review request");
@@ -902,6 +1046,28 @@ mod tests {
         assert_eq!(request, expected);
     }
 
+    #[test]
+    fn builds_pull_request_review_request() {
+        let request = build_review_request(ReviewArgs {
+            uncommitted: false,
+            base: None,
+            commit: None,
+            commit_title: None,
+            pull_request: Some("https://github.com/openai/codex/pull/1".to_string()),
+            prompt: None,
+        })
+        .expect("builds pull request review request");
+
+        let expected = ReviewRequest {
+            target: ReviewTarget::PullRequest {
+                url: "https://github.com/openai/codex/pull/1".to_string(),
+            },
+            user_facing_hint: None,
+        };
+
+        assert_eq!(request, expected);
+    }
+
     #[test]
     fn builds_custom_review_request_trims_prompt() {
         let request = build_review_request(ReviewArgs {
@@ -909,6 +1075,7 @@ mod tests {
             base: None,
             commit: None,
             commit_title: None,
+            pull_request: None,
             prompt: Some("  custom review instructions  ".to_string()),
         })
         .expect("builds custom review request");
@@ -1168,6 +1168,7 @@ fn patch_apply_success_produces_item_completed_patchapply() {
             success: true,
             changes: changes.clone(),
             status: CorePatchApplyStatus::Completed,
+            anchors: Vec::new(),
         }),
     );
     let out_end = ep.collect_thread_events(&end);
@@ -1240,6 +1241,7 @@ fn patch_apply_failure_produces_item_completed_patchapply_failed() {
             success: false,
             changes: changes.clone(),
             status: CorePatchApplyStatus::Failed,
+            anchors: Vec::new(),
         }),
     );
     let out_end = ep.collect_thread_events(&end);
@@ -1284,6 +1286,7 @@ fn task_complete_produces_turn_completed_with_usage() {
         EventMsg::TokenCount(codex_protocol::protocol::TokenCountEvent {
             info: Some(info),
             rate_limits: None,
+            request: None,
         }),
     );
     assert!(ep.collect_thread_events(&token_count_event).is_empty());
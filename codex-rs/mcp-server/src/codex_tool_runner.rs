@@ -6,6 +6,7 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use crate::exec_approval::handle_exec_approval_request;
+use crate::mcp_elicitation::handle_mcp_elicitation_request;
 use crate::outgoing_message::OutgoingMessageSender;
 use crate::outgoing_message::OutgoingNotificationMeta;
 use crate::patch_approval::handle_patch_approval_request;
@@ -225,6 +226,7 @@ async fn run_codex_tool_session_inner(
                             proposed_execpolicy_amendment: _,
                             parsed_cmd,
                             network_approval_context: _,
+                            risk_assessment: _,
                         } = ev;
                         handle_exec_approval_request(
                             command,
@@ -258,8 +260,17 @@ async fn run_codex_tool_session_inner(
                     EventMsg::Warning(_) => {
                         continue;
                     }
-                    EventMsg::ElicitationRequest(_) => {
-                        // TODO: forward elicitation requests to the client?
+                    EventMsg::ElicitationRequest(ev) => {
+                        handle_mcp_elicitation_request(
+                            ev,
+                            outgoing.clone(),
+                            thread.clone(),
+                            request_id.clone(),
+                            request_id_str.clone(),
+                            event.id.clone(),
+                            thread_id,
+                        )
+                        .await;
                         continue;
                     }
                     EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
@@ -331,6 +342,39 @@ async fn run_codex_tool_session_inner(
                     | EventMsg::ListSkillsResponse(_)
                     | EventMsg::ListRemoteSkillsResponse(_)
                     | EventMsg::RemoteSkillDownloaded(_)
+                    | EventMsg::PatchArtifact(_)
+                    | EventMsg::ToolCallArgumentsPreview(_)
+                    | EventMsg::SessionsPruned(_)
+                    | EventMsg::SessionExported(_)
+                    | EventMsg::SessionImported(_)
+                    | EventMsg::ApprovalResolved(_)
+                    | EventMsg::ObserverPromoted(_)
+                    | EventMsg::InlineEditResult(_)
+                    | EventMsg::SessionTools(_)
+                    | EventMsg::ToolSelection(_)
+                    | EventMsg::ToolSelectionRestored(_)
+                    | EventMsg::ElicitationTimedOut(_)
+                    | EventMsg::ToolInvocationResult(_)
+                    | EventMsg::CompletionResult(_)
+                    | EventMsg::EnabledToolsUpdated(_)
+                    | EventMsg::SuggestedAllowlist(_)
+                    | EventMsg::DependencyAudit(_)
+                    | EventMsg::LicenseReport(_)
+                    | EventMsg::ResponseCandidates(_)
+                    | EventMsg::ResponseCandidateSelected(_)
+                    | EventMsg::RateLimitForecast(_)
+                    | EventMsg::PlanOrCreditsChanged(_)
+                    | EventMsg::OfflineStatus(_)
+                    | EventMsg::UsageRollups(_)
+                    | EventMsg::TurnTimings(_)
+                    | EventMsg::TurnTimingRecorded(_)
+                    | EventMsg::ContextBreakdown(_)
+                    | EventMsg::HistoryMetadataRecorded(_)
+                    | EventMsg::ActiveToolCalls(_)
+                    | EventMsg::ToolLockHeld(_)
+                    | EventMsg::ToolProgress(_)
+                    | EventMsg::McpToolCallProgress(_)
+                    | EventMsg::McpToolCallLog(_)
                     | EventMsg::ExecCommandBegin(_)
                     | EventMsg::TerminalInteraction(_)
                     | EventMsg::ExecCommandOutputDelta(_)
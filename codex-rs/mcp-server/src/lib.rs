@@ -6,6 +6,7 @@ use std::io::Result as IoResult;
 use std::path::PathBuf;
 
 use codex_core::config::Config;
+use codex_core::default_client::set_default_client_http_network_config;
 use codex_utils_cli::CliConfigOverrides;
 
 use rmcp::model::ClientNotification;
@@ -25,9 +26,11 @@ use tracing_subscriber::EnvFilter;
 mod codex_tool_config;
 mod codex_tool_runner;
 mod exec_approval;
+mod mcp_elicitation;
 pub(crate) mod message_processor;
 mod outgoing_message;
 mod patch_approval;
+mod session_tools;
 
 use crate::message_processor::MessageProcessor;
 use crate::outgoing_message::OutgoingJsonRpcMessage;
@@ -38,8 +41,13 @@ pub use crate::codex_tool_config::CodexToolCallParam;
 pub use crate::codex_tool_config::CodexToolCallReplyParam;
 pub use crate::exec_approval::ExecApprovalElicitRequestParams;
 pub use crate::exec_approval::ExecApprovalResponse;
+pub use crate::mcp_elicitation::McpElicitationElicitRequestParams;
+pub use crate::mcp_elicitation::McpElicitationResponse;
 pub use crate::patch_approval::PatchApprovalElicitRequestParams;
 pub use crate::patch_approval::PatchApprovalResponse;
+pub use crate::session_tools::CodexEndSessionParam;
+pub use crate::session_tools::CodexStartSessionParam;
+pub use crate::session_tools::CodexToolParam;
 
 /// Size of the bounded channels used to communicate between tasks. The value
 /// is a balance between throughput and memory usage – 128 messages should be
@@ -99,6 +107,12 @@ pub async fn run_main(
         .map_err(|e| {
             std::io::Error::new(ErrorKind::InvalidData, format!("error loading config: {e}"))
         })?;
+    set_default_client_http_network_config(&config.http).map_err(|e| {
+        std::io::Error::new(
+            ErrorKind::InvalidData,
+            format!("error loading [http] settings: {e}"),
+        )
+    })?;
 
     // Task: process incoming messages.
     let processor_handle = tokio::spawn({
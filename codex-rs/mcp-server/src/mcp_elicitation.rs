@@ -0,0 +1,136 @@
+use std::sync::Arc;
+
+use codex_core::CodexThread;
+use codex_protocol::ThreadId;
+use codex_protocol::approvals::ElicitationAction;
+use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::mcp::RequestId as ProtocolRequestId;
+use codex_protocol::protocol::Op;
+use rmcp::model::ErrorData;
+use rmcp::model::RequestId;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tracing::error;
+
+/// Conforms to the MCP elicitation request params shape, so it can be used as
+/// the `params` field of an `elicitation/create` request. Carries a question
+/// one of the session's own MCP servers asked the user.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct McpElicitationElicitRequestParams {
+    pub message: String,
+
+    #[serde(rename = "requestedSchema")]
+    pub requested_schema: Value,
+
+    // These are additional fields the client can use to
+    // correlate the request with the codex tool call.
+    #[serde(rename = "threadId")]
+    pub thread_id: ThreadId,
+    pub codex_elicitation: String,
+    pub codex_mcp_tool_call_id: String,
+    pub codex_event_id: String,
+    pub codex_server_name: String,
+    pub codex_url: Option<String>,
+    pub codex_timeout_ms: Option<u64>,
+}
+
+/// The client's answer, an MCP `ElicitResult`.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct McpElicitationResponse {
+    pub action: ElicitationAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub content: Option<Value>,
+}
+
+pub(crate) async fn handle_mcp_elicitation_request(
+    event: ElicitationRequestEvent,
+    outgoing: Arc<crate::outgoing_message::OutgoingMessageSender>,
+    codex: Arc<CodexThread>,
+    request_id: RequestId,
+    tool_call_id: String,
+    event_id: String,
+    thread_id: ThreadId,
+) {
+    let ElicitationRequestEvent {
+        server_name,
+        id,
+        message,
+        requested_schema,
+        url,
+        timeout_ms,
+    } = event;
+    let params = McpElicitationElicitRequestParams {
+        message,
+        requested_schema: requested_schema
+            .unwrap_or_else(|| json!({"type":"object","properties":{}})),
+        thread_id,
+        codex_elicitation: "mcp-elicitation".to_string(),
+        codex_mcp_tool_call_id: tool_call_id,
+        codex_event_id: event_id,
+        codex_server_name: server_name.clone(),
+        codex_url: url,
+        codex_timeout_ms: timeout_ms,
+    };
+    let params_json = match serde_json::to_value(&params) {
+        Ok(value) => value,
+        Err(err) => {
+            let message = format!("Failed to serialize McpElicitationElicitRequestParams: {err}");
+            error!("{message}");
+
+            outgoing
+                .send_error(request_id.clone(), ErrorData::invalid_params(message, None))
+                .await;
+
+            return;
+        }
+    };
+
+    let on_response = outgoing
+        .send_request("elicitation/create", Some(params_json))
+        .await;
+
+    // Listen for the response on a separate task so we don't block the main agent loop.
+    tokio::spawn(async move {
+        on_mcp_elicitation_response(server_name, id, on_response, codex).await;
+    });
+}
+
+async fn on_mcp_elicitation_response(
+    server_name: String,
+    id: ProtocolRequestId,
+    receiver: tokio::sync::oneshot::Receiver<serde_json::Value>,
+    codex: Arc<CodexThread>,
+) {
+    let response = receiver.await;
+    let value = match response {
+        Ok(value) => value,
+        Err(err) => {
+            error!("request failed: {err:?}");
+            return;
+        }
+    };
+
+    let response = serde_json::from_value::<McpElicitationResponse>(value).unwrap_or_else(|err| {
+        error!("failed to deserialize McpElicitationResponse: {err}");
+        // If we cannot deserialize the response, we decline the request to be
+        // conservative.
+        McpElicitationResponse {
+            action: ElicitationAction::Decline,
+            content: None,
+        }
+    });
+
+    if let Err(err) = codex
+        .submit(Op::ResolveElicitation {
+            server_name,
+            request_id: id,
+            decision: response.action,
+            content: response.content,
+        })
+        .await
+    {
+        error!("failed to submit ResolveElicitation: {err}");
+    }
+}
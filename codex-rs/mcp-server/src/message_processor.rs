@@ -1,7 +1,10 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
+use std::collections::HashSet;
 use std::path::PathBuf;
 
 use codex_core::AuthManager;
+use codex_core::CodexThread;
 use codex_core::ThreadManager;
 use codex_core::config::Config;
 use codex_core::default_client::USER_AGENT_SUFFIX;
@@ -17,6 +20,7 @@ use rmcp::model::ErrorCode;
 use rmcp::model::ErrorData;
 use rmcp::model::Implementation;
 use rmcp::model::InitializeResult;
+use rmcp::model::JsonObject;
 use rmcp::model::JsonRpcError;
 use rmcp::model::JsonRpcNotification;
 use rmcp::model::JsonRpcRequest;
@@ -34,6 +38,12 @@ use crate::codex_tool_config::CodexToolCallReplyParam;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_param;
 use crate::codex_tool_config::create_tool_for_codex_tool_call_reply_param;
 use crate::outgoing_message::OutgoingMessageSender;
+use crate::session_tools::CodexEndSessionParam;
+use crate::session_tools::CodexStartSessionParam;
+use crate::session_tools::CodexToolParam;
+use crate::session_tools::create_tool_for_codex_end_session_param;
+use crate::session_tools::create_tool_for_codex_start_session_param;
+use crate::session_tools::create_tool_for_codex_tool_param;
 
 pub(crate) struct MessageProcessor {
     outgoing: Arc<OutgoingMessageSender>,
@@ -41,6 +51,9 @@ pub(crate) struct MessageProcessor {
     codex_linux_sandbox_exe: Option<PathBuf>,
     thread_manager: Arc<ThreadManager>,
     running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ThreadId>>>,
+    /// Sessions started with `codex-start-session`, whose tools `codex-tool`
+    /// may call.
+    session_tool_threads: Arc<Mutex<HashSet<ThreadId>>>,
 }
 
 impl MessageProcessor {
@@ -69,6 +82,7 @@ impl MessageProcessor {
             codex_linux_sandbox_exe,
             thread_manager,
             running_requests_id_to_codex_uuid: Arc::new(Mutex::new(HashMap::new())),
+            session_tool_threads: Arc::new(Mutex::new(HashSet::new())),
         }
     }
 
@@ -240,6 +254,20 @@ impl MessageProcessor {
                 tools: Some(ToolsCapability {
                     list_changed: Some(true),
                 }),
+                // Commands and patches that need the user's approval, and
+                // questions the session's MCP servers ask the user, are put to
+                // the client as `elicitation/create` requests, which
+                // `codex_elicitation` tells apart.
+                experimental: Some(BTreeMap::from([(
+                    "codex/approvals".to_string(),
+                    JsonObject::from_iter([
+                        ("elicitation".to_string(), json!(true)),
+                        (
+                            "codexElicitation".to_string(),
+                            json!(["exec-approval", "patch-approval", "mcp-elicitation"]),
+                        ),
+                    ]),
+                )])),
                 ..Default::default()
             },
             instructions: None,
@@ -313,6 +341,9 @@ impl MessageProcessor {
             tools: vec![
                 create_tool_for_codex_tool_call_param(),
                 create_tool_for_codex_tool_call_reply_param(),
+                create_tool_for_codex_start_session_param(),
+                create_tool_for_codex_tool_param(),
+                create_tool_for_codex_end_session_param(),
             ],
             next_cursor: None,
         };
@@ -332,6 +363,9 @@ impl MessageProcessor {
                 self.handle_tool_call_codex_session_reply(id, arguments)
                     .await
             }
+            "codex-start-session" => self.handle_tool_call_start_session(id, arguments).await,
+            "codex-tool" => self.handle_tool_call_session_tool(id, arguments).await,
+            "codex-end-session" => self.handle_tool_call_end_session(id, arguments).await,
             _ => {
                 let result = CallToolResult {
                     content: vec![rmcp::model::Content::text(format!("Unknown tool '{name}'"))],
@@ -516,6 +550,160 @@ impl MessageProcessor {
         });
     }
 
+    async fn handle_tool_call_start_session(
+        &self,
+        id: RequestId,
+        arguments: Option<rmcp::model::JsonObject>,
+    ) {
+        let param = match serde_json::from_value::<CodexStartSessionParam>(
+            arguments.map_or(json!({}), serde_json::Value::Object),
+        ) {
+            Ok(param) => param,
+            Err(e) => {
+                self.send_tool_call_error(
+                    id,
+                    format!("Failed to parse configuration for Codex session: {e}"),
+                )
+                .await;
+                return;
+            }
+        };
+        task::spawn(crate::session_tools::run_start_session(
+            id,
+            param,
+            self.codex_linux_sandbox_exe.clone(),
+            self.outgoing.clone(),
+            self.thread_manager.clone(),
+            self.session_tool_threads.clone(),
+        ));
+    }
+
+    async fn handle_tool_call_session_tool(
+        &self,
+        id: RequestId,
+        arguments: Option<rmcp::model::JsonObject>,
+    ) {
+        let param = match arguments
+            .map(|arguments| serde_json::from_value::<CodexToolParam>(arguments.into()))
+        {
+            Some(Ok(param)) => param,
+            Some(Err(e)) => {
+                self.send_tool_call_error(id, format!("Failed to parse codex-tool arguments: {e}"))
+                    .await;
+                return;
+            }
+            None => {
+                self.send_tool_call_error(
+                    id,
+                    "Missing arguments for codex-tool; the `threadId` and `tool` fields are required."
+                        .to_string(),
+                )
+                .await;
+                return;
+            }
+        };
+        let Some((thread_id, thread)) = self.find_thread(&id, &param.thread_id).await else {
+            return;
+        };
+        // A `codex` session's events are read by its prompts' runner.
+        if !self.session_tool_threads.lock().await.contains(&thread_id) {
+            let result = crate::codex_tool_runner::create_call_tool_result_with_thread_id(
+                thread_id,
+                format!("Session {thread_id} was not started with codex-start-session"),
+                Some(true),
+            );
+            self.outgoing.send_response(id, result).await;
+            return;
+        }
+        task::spawn(crate::session_tools::run_tool(
+            id,
+            thread_id,
+            thread,
+            param.tool,
+            param.arguments,
+            self.outgoing.clone(),
+            self.running_requests_id_to_codex_uuid.clone(),
+        ));
+    }
+
+    async fn handle_tool_call_end_session(
+        &self,
+        id: RequestId,
+        arguments: Option<rmcp::model::JsonObject>,
+    ) {
+        let param = match arguments
+            .map(|arguments| serde_json::from_value::<CodexEndSessionParam>(arguments.into()))
+        {
+            Some(Ok(param)) => param,
+            Some(Err(e)) => {
+                self.send_tool_call_error(
+                    id,
+                    format!("Failed to parse codex-end-session arguments: {e}"),
+                )
+                .await;
+                return;
+            }
+            None => {
+                self.send_tool_call_error(
+                    id,
+                    "Missing arguments for codex-end-session; the `threadId` field is required."
+                        .to_string(),
+                )
+                .await;
+                return;
+            }
+        };
+        let Some((thread_id, _)) = self.find_thread(&id, &param.thread_id).await else {
+            return;
+        };
+        task::spawn(crate::session_tools::run_end_session(
+            id,
+            thread_id,
+            self.outgoing.clone(),
+            self.thread_manager.clone(),
+            self.session_tool_threads.clone(),
+        ));
+    }
+
+    /// The session of `thread_id`, or `None` after responding to `id` with
+    /// the error.
+    async fn find_thread(
+        &self,
+        id: &RequestId,
+        thread_id: &str,
+    ) -> Option<(ThreadId, Arc<CodexThread>)> {
+        let thread_id = match ThreadId::from_string(thread_id) {
+            Ok(thread_id) => thread_id,
+            Err(e) => {
+                self.send_tool_call_error(id.clone(), format!("Failed to parse threadId: {e}"))
+                    .await;
+                return None;
+            }
+        };
+        match self.thread_manager.get_thread(thread_id).await {
+            Ok(thread) => Some((thread_id, thread)),
+            Err(_) => {
+                let result = crate::codex_tool_runner::create_call_tool_result_with_thread_id(
+                    thread_id,
+                    format!("Session not found for thread_id: {thread_id}"),
+                    Some(true),
+                );
+                self.outgoing.send_response(id.clone(), result).await;
+                None
+            }
+        }
+    }
+
+    async fn send_tool_call_error(&self, id: RequestId, message: String) {
+        let result = CallToolResult {
+            content: vec![rmcp::model::Content::text(message)],
+            structured_content: None,
+            is_error: Some(true),
+            meta: None,
+        };
+        self.outgoing.send_response(id, result).await;
+    }
+
     fn handle_set_level(&self, params: rmcp::model::SetLevelRequestParams) {
         tracing::info!("logging/setLevel -> params: {:?}", params);
     }
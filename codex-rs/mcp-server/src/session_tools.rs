@@ -0,0 +1,584 @@
+//! Tools that let an MCP client drive a Codex session's own tools: start a
+//! session without a prompt, call its shell, file, search, and plan tools
+//! directly, and end it. Calls run under the session's approval and sandbox
+//! policies, and approval requests reach the client as elicitations, as they
+//! do for the `codex` tool.
+//!
+//! Each call reads the session's events through its own attached client and
+//! only handles the events of the submission it made, so calls in flight at
+//! the same time never read each other's events. Nothing else reads the
+//! session's primary event stream, which is drained for the session's life.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use codex_core::AttachMode;
+use codex_core::AttachedClient;
+use codex_core::CodexThread;
+use codex_core::NewThread;
+use codex_core::ThreadManager;
+use codex_protocol::ThreadId;
+use codex_protocol::models::ContentItem;
+use codex_protocol::models::ResponseInputItem;
+use codex_protocol::protocol::ApplyPatchApprovalRequestEvent;
+use codex_protocol::protocol::EventMsg;
+use codex_protocol::protocol::Op;
+use codex_protocol::protocol::SessionTool;
+use rmcp::model::CallToolResult;
+use rmcp::model::Content;
+use rmcp::model::RequestId;
+use rmcp::model::Tool;
+use schemars::JsonSchema;
+use schemars::r#gen::SchemaSettings;
+use serde::Deserialize;
+use serde::Serialize;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::Mutex;
+
+use crate::codex_tool_config::CodexToolCallApprovalPolicy;
+use crate::codex_tool_config::CodexToolCallParam;
+use crate::codex_tool_config::CodexToolCallSandboxMode;
+use crate::codex_tool_config::create_tool_input_schema;
+use crate::codex_tool_runner::create_call_tool_result_with_thread_id;
+use crate::exec_approval::handle_exec_approval_request;
+use crate::mcp_elicitation::handle_mcp_elicitation_request;
+use crate::outgoing_message::OutgoingMessageSender;
+use crate::outgoing_message::OutgoingNotificationMeta;
+use crate::patch_approval::handle_patch_approval_request;
+
+/// Arguments of the `codex-start-session` tool: the configuration of the
+/// `codex` tool, without a prompt.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, Default)]
+#[serde(rename_all = "kebab-case")]
+pub struct CodexStartSessionParam {
+    /// Optional override for the model name (e.g. 'gpt-5.2', 'gpt-5.2-codex').
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+
+    /// Configuration profile from config.toml to specify default options.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+
+    /// Working directory for the session. If relative, it is resolved against
+    /// the server process's current working directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+
+    /// Approval policy for shell commands and patches: `untrusted`,
+    /// `on-failure`, `on-request`, `never`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval_policy: Option<CodexToolCallApprovalPolicy>,
+
+    /// Sandbox mode: `read-only`, `workspace-write`, or `danger-full-access`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sandbox: Option<CodexToolCallSandboxMode>,
+
+    /// Individual config settings that will override what is in
+    /// CODEX_HOME/config.toml.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<HashMap<String, serde_json::Value>>,
+}
+
+/// Name the calls of the session tools attach to a session under.
+const SESSION_TOOLS_CLIENT: &str = "mcp-session-tools";
+
+/// Arguments of the `codex-tool` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexToolParam {
+    /// The thread id of the session, from `codex-start-session`.
+    pub thread_id: String,
+
+    /// Name of the session tool to call, as listed by `codex-start-session`.
+    pub tool: String,
+
+    /// Arguments of the call: an object matching the tool's input schema, or
+    /// a string for tools that take raw input, such as `apply_patch`.
+    #[serde(default)]
+    pub arguments: Value,
+}
+
+/// Arguments of the `codex-end-session` tool.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexEndSessionParam {
+    /// The thread id of the session to end.
+    pub thread_id: String,
+}
+
+/// Builds the `Tool` definition of `codex-start-session`.
+pub(crate) fn create_tool_for_codex_start_session_param() -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<CodexStartSessionParam>();
+
+    Tool {
+        name: "codex-start-session".into(),
+        title: Some("Codex Start Session".to_string()),
+        input_schema: create_tool_input_schema(
+            schema,
+            "Codex start session tool schema should serialize",
+        ),
+        output_schema: None,
+        description: Some(
+            "Start a Codex session without a prompt and list the tools it offers, for use \
+             with `codex-tool`. Tools with `requires_approval` may ask for approval through \
+             elicitation."
+                .into(),
+        ),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+    }
+}
+
+/// Builds the `Tool` definition of `codex-tool`.
+pub(crate) fn create_tool_for_codex_tool_param() -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<CodexToolParam>();
+
+    Tool {
+        name: "codex-tool".into(),
+        title: Some("Codex Tool".to_string()),
+        input_schema: create_tool_input_schema(schema, "Codex tool-call schema should serialize"),
+        output_schema: None,
+        description: Some(
+            "Call one of a Codex session's tools directly, such as its shell, file, search, \
+             and plan tools, under the session's approval and sandbox policies."
+                .into(),
+        ),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+    }
+}
+
+/// Builds the `Tool` definition of `codex-end-session`.
+pub(crate) fn create_tool_for_codex_end_session_param() -> Tool {
+    let schema = SchemaSettings::draft2019_09()
+        .with(|s| {
+            s.inline_subschemas = true;
+            s.option_add_null_type = false;
+        })
+        .into_generator()
+        .into_root_schema_for::<CodexEndSessionParam>();
+
+    Tool {
+        name: "codex-end-session".into(),
+        title: Some("Codex End Session".to_string()),
+        input_schema: create_tool_input_schema(
+            schema,
+            "Codex end session tool schema should serialize",
+        ),
+        output_schema: None,
+        description: Some("End a Codex session and release its resources.".into()),
+        annotations: None,
+        execution: None,
+        icons: None,
+        meta: None,
+    }
+}
+
+/// Starts a session for `codex-start-session` and responds with its thread id
+/// and tools.
+pub(crate) async fn run_start_session(
+    id: RequestId,
+    param: CodexStartSessionParam,
+    codex_linux_sandbox_exe: Option<PathBuf>,
+    outgoing: Arc<OutgoingMessageSender>,
+    thread_manager: Arc<ThreadManager>,
+    session_tool_threads: Arc<Mutex<HashSet<ThreadId>>>,
+) {
+    let CodexStartSessionParam {
+        model,
+        profile,
+        cwd,
+        approval_policy,
+        sandbox,
+        config,
+    } = param;
+    let config = match (CodexToolCallParam {
+        model,
+        profile,
+        cwd,
+        approval_policy,
+        sandbox,
+        config,
+        ..Default::default()
+    })
+    .into_config(codex_linux_sandbox_exe)
+    .await
+    {
+        Ok((_, config)) => config,
+        Err(err) => {
+            outgoing
+                .send_response(
+                    id,
+                    error_result(format!(
+                        "Failed to load Codex configuration from overrides: {err}"
+                    )),
+                )
+                .await;
+            return;
+        }
+    };
+    let NewThread {
+        thread_id, thread, ..
+    } = match thread_manager.start_thread(config).await {
+        Ok(new_thread) => new_thread,
+        Err(err) => {
+            outgoing
+                .send_response(
+                    id,
+                    error_result(format!("Failed to start Codex session: {err}")),
+                )
+                .await;
+            return;
+        }
+    };
+
+    session_tool_threads.lock().await.insert(thread_id);
+    // The calls read their events through attached clients; drop the copies
+    // on the primary stream until the session ends.
+    let primary = Arc::clone(&thread);
+    tokio::spawn(async move { while primary.next_event().await.is_ok() {} });
+
+    let client = thread.attach_client(SESSION_TOOLS_CLIENT, AttachMode::Driver);
+    let tools = match list_session_tools(&client).await {
+        Ok(tools) => tools,
+        Err(message) => {
+            outgoing
+                .send_response(
+                    id,
+                    create_call_tool_result_with_thread_id(thread_id, message, Some(true)),
+                )
+                .await;
+            return;
+        }
+    };
+    let names: Vec<&str> = tools.iter().map(|tool| tool.name.as_str()).collect();
+    let text = format!(
+        "Started session {thread_id} with tools: {}",
+        names.join(", ")
+    );
+    let result = CallToolResult {
+        content: vec![Content::text(text)],
+        structured_content: Some(json!({
+            "threadId": thread_id,
+            "tools": tools,
+        })),
+        is_error: None,
+        meta: None,
+    };
+    outgoing.send_response(id, result).await;
+}
+
+/// The session's tools, read from the events of `client`'s submission.
+async fn list_session_tools(client: &AttachedClient) -> Result<Vec<SessionTool>, String> {
+    let sub_id = client
+        .submit(Op::ListSessionTools)
+        .await
+        .map_err(|err| format!("Failed to list session tools: {err}"))?;
+    loop {
+        let event = client
+            .next_event()
+            .await
+            .map_err(|err| format!("Codex runtime error: {err}"))?;
+        if event.id != sub_id {
+            continue;
+        }
+        match event.msg {
+            EventMsg::SessionTools(event) => return Ok(event.tools),
+            EventMsg::Error(event) => return Err(event.message),
+            _ => {}
+        }
+    }
+}
+
+/// Runs a call of `codex-tool` and responds with its output once the call's
+/// turn ends, forwarding the session's events as notifications meanwhile.
+pub(crate) async fn run_tool(
+    id: RequestId,
+    thread_id: ThreadId,
+    thread: Arc<CodexThread>,
+    tool: String,
+    arguments: Value,
+    outgoing: Arc<OutgoingMessageSender>,
+    running_requests_id_to_codex_uuid: Arc<Mutex<HashMap<RequestId, ThreadId>>>,
+) {
+    let call_id = id.to_string();
+    let arguments = match arguments {
+        Value::String(input) => input,
+        Value::Null => "{}".to_string(),
+        arguments => arguments.to_string(),
+    };
+    running_requests_id_to_codex_uuid
+        .lock()
+        .await
+        .insert(id.clone(), thread_id);
+    let client = thread.attach_client(SESSION_TOOLS_CLIENT, AttachMode::Driver);
+    let sub_id = match client
+        .submit(Op::InvokeTool {
+            call_id: call_id.clone(),
+            tool,
+            arguments,
+        })
+        .await
+    {
+        Ok(sub_id) => sub_id,
+        Err(err) => {
+            outgoing
+                .send_response(
+                    id.clone(),
+                    create_call_tool_result_with_thread_id(
+                        thread_id,
+                        format!("Failed to submit tool call: {err}"),
+                        Some(true),
+                    ),
+                )
+                .await;
+            running_requests_id_to_codex_uuid.lock().await.remove(&id);
+            return;
+        }
+    };
+
+    let mut output = None;
+    let result = loop {
+        let event = match client.next_event().await {
+            Ok(event) if event.id != sub_id => continue,
+            Ok(event) => event,
+            Err(err) => {
+                break create_call_tool_result_with_thread_id(
+                    thread_id,
+                    format!("Codex runtime error: {err}"),
+                    Some(true),
+                );
+            }
+        };
+        outgoing
+            .send_event_as_notification(
+                &event,
+                Some(OutgoingNotificationMeta {
+                    request_id: Some(id.clone()),
+                    thread_id: Some(thread_id),
+                }),
+            )
+            .await;
+        match event.msg {
+            EventMsg::ExecApprovalRequest(ev) => {
+                let approval_id = ev.effective_approval_id();
+                handle_exec_approval_request(
+                    ev.command,
+                    ev.cwd,
+                    outgoing.clone(),
+                    thread.clone(),
+                    id.clone(),
+                    call_id.clone(),
+                    event.id,
+                    ev.call_id,
+                    approval_id,
+                    ev.parsed_cmd,
+                    thread_id,
+                )
+                .await;
+            }
+            EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                call_id: patch_call_id,
+                turn_id: _,
+                reason,
+                grant_root,
+                changes,
+            }) => {
+                handle_patch_approval_request(
+                    patch_call_id,
+                    reason,
+                    grant_root,
+                    changes,
+                    outgoing.clone(),
+                    thread.clone(),
+                    id.clone(),
+                    call_id.clone(),
+                    event.id,
+                    thread_id,
+                )
+                .await;
+            }
+            EventMsg::ElicitationRequest(ev) => {
+                handle_mcp_elicitation_request(
+                    ev,
+                    outgoing.clone(),
+                    thread.clone(),
+                    id.clone(),
+                    call_id.clone(),
+                    event.id,
+                    thread_id,
+                )
+                .await;
+            }
+            EventMsg::ToolInvocationResult(ev) => {
+                output = Some(call_tool_result_for_output(thread_id, ev.output));
+            }
+            // Sent instead of running the call, so no turn follows.
+            EventMsg::Error(ev) => {
+                break create_call_tool_result_with_thread_id(thread_id, ev.message, Some(true));
+            }
+            EventMsg::TurnComplete(_) | EventMsg::TurnAborted(_) => {
+                break output.unwrap_or_else(|| {
+                    create_call_tool_result_with_thread_id(
+                        thread_id,
+                        "The tool call was aborted".to_string(),
+                        Some(true),
+                    )
+                });
+            }
+            _ => {}
+        }
+    };
+    outgoing.send_response(id.clone(), result).await;
+    running_requests_id_to_codex_uuid.lock().await.remove(&id);
+}
+
+/// Ends a session for `codex-end-session`.
+pub(crate) async fn run_end_session(
+    id: RequestId,
+    thread_id: ThreadId,
+    outgoing: Arc<OutgoingMessageSender>,
+    thread_manager: Arc<ThreadManager>,
+    session_tool_threads: Arc<Mutex<HashSet<ThreadId>>>,
+) {
+    session_tool_threads.lock().await.remove(&thread_id);
+    let result = match thread_manager.remove_thread(&thread_id).await {
+        Some(thread) => match thread.submit(Op::Shutdown).await {
+            Ok(_) => create_call_tool_result_with_thread_id(
+                thread_id,
+                format!("Ended session {thread_id}"),
+                None,
+            ),
+            Err(err) => create_call_tool_result_with_thread_id(
+                thread_id,
+                format!("Failed to end session: {err}"),
+                Some(true),
+            ),
+        },
+        None => create_call_tool_result_with_thread_id(
+            thread_id,
+            format!("Session not found for thread_id: {thread_id}"),
+            Some(true),
+        ),
+    };
+    outgoing.send_response(id, result).await;
+}
+
+fn error_result(message: String) -> CallToolResult {
+    CallToolResult {
+        content: vec![Content::text(message)],
+        structured_content: None,
+        is_error: Some(true),
+        meta: None,
+    }
+}
+
+/// The `tools/call` result of a session tool's output: MCP tools' results
+/// as they are, and the text the model would have read otherwise.
+fn call_tool_result_for_output(thread_id: ThreadId, output: ResponseInputItem) -> CallToolResult {
+    match output {
+        ResponseInputItem::McpToolCallOutput {
+            result: Ok(result), ..
+        } => serde_json::to_value(result)
+            .and_then(serde_json::from_value)
+            .unwrap_or_else(|err| {
+                create_call_tool_result_with_thread_id(
+                    thread_id,
+                    format!("Failed to convert the tool result: {err}"),
+                    Some(true),
+                )
+            }),
+        ResponseInputItem::McpToolCallOutput {
+            result: Err(message),
+            ..
+        } => create_call_tool_result_with_thread_id(thread_id, message, Some(true)),
+        ResponseInputItem::FunctionCallOutput { output, .. } => {
+            create_call_tool_result_with_thread_id(
+                thread_id,
+                output.body.to_text().unwrap_or_default(),
+                (output.success == Some(false)).then_some(true),
+            )
+        }
+        ResponseInputItem::CustomToolCallOutput { output, .. } => {
+            create_call_tool_result_with_thread_id(thread_id, output, None)
+        }
+        ResponseInputItem::Message { content, .. } => {
+            let text = content
+                .into_iter()
+                .filter_map(|item| match item {
+                    ContentItem::InputText { text } | ContentItem::OutputText { text } => {
+                        Some(text)
+                    }
+                    ContentItem::InputImage { .. } => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            create_call_tool_result_with_thread_id(thread_id, text, None)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codex_protocol::models::FunctionCallOutputBody;
+    use codex_protocol::models::FunctionCallOutputPayload;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn tool_output_becomes_a_call_tool_result() {
+        let thread_id = ThreadId::new();
+        let output = |success| ResponseInputItem::FunctionCallOutput {
+            call_id: "call-1".to_string(),
+            output: FunctionCallOutputPayload {
+                body: FunctionCallOutputBody::Text("exit code 1".to_string()),
+                success,
+            },
+        };
+        assert_eq!(
+            call_tool_result_for_output(thread_id, output(Some(false))),
+            create_call_tool_result_with_thread_id(
+                thread_id,
+                "exit code 1".to_string(),
+                Some(true)
+            )
+        );
+        assert_eq!(
+            call_tool_result_for_output(thread_id, output(Some(true))),
+            create_call_tool_result_with_thread_id(thread_id, "exit code 1".to_string(), None)
+        );
+        assert_eq!(
+            call_tool_result_for_output(
+                thread_id,
+                ResponseInputItem::CustomToolCallOutput {
+                    call_id: "call-2".to_string(),
+                    output: "Success. Updated the following files:\nM a.txt".to_string(),
+                }
+            ),
+            create_call_tool_result_with_thread_id(
+                thread_id,
+                "Success. Updated the following files:\nM a.txt".to_string(),
+                None
+            )
+        );
+    }
+}
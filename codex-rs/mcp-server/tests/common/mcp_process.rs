@@ -175,6 +175,16 @@ impl McpProcess {
                     "tools": {
                         "listChanged": true
                     },
+                    "experimental": {
+                        "codex/approvals": {
+                            "elicitation": true,
+                            "codexElicitation": [
+                                "exec-approval",
+                                "patch-approval",
+                                "mcp-elicitation"
+                            ]
+                        }
+                    },
                 },
                 "serverInfo": {
                     "name": "codex-mcp-server",
@@ -218,6 +228,25 @@ impl McpProcess {
         .await
     }
 
+    /// Calls the server's tool `name`; returns the id of the request.
+    pub async fn send_tool_call(
+        &mut self,
+        name: &str,
+        arguments: serde_json::Value,
+    ) -> anyhow::Result<i64> {
+        let serde_json::Value::Object(arguments) = arguments else {
+            anyhow::bail!("tool arguments must be an object: {arguments}");
+        };
+        let params = CallToolRequestParams {
+            meta: None,
+            name: name.to_string().into(),
+            arguments: Some(arguments),
+            task: None,
+        };
+        self.send_request("tools/call", Some(serde_json::to_value(params)?))
+            .await
+    }
+
     async fn send_request(
         &mut self,
         method: &str,
@@ -0,0 +1,147 @@
+use std::env;
+use std::path::Path;
+
+use codex_core::spawn::CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR;
+use codex_mcp_server::ExecApprovalElicitRequestParams;
+use codex_mcp_server::ExecApprovalResponse;
+use codex_protocol::protocol::ReviewDecision;
+use pretty_assertions::assert_eq;
+use rmcp::model::RequestId;
+use serde_json::Value;
+use serde_json::json;
+use tempfile::TempDir;
+use tokio::time::timeout;
+
+use mcp_test_support::McpProcess;
+use mcp_test_support::create_mock_responses_server;
+
+const DEFAULT_READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(20);
+
+/// Starts a session, runs its shell tool through an approval elicitation
+/// without a model turn, and ends the session.
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn session_tool_call_asks_for_approval_and_runs() -> anyhow::Result<()> {
+    if env::var(CODEX_SANDBOX_NETWORK_DISABLED_ENV_VAR).is_ok() {
+        println!(
+            "Skipping test because it cannot execute when network is disabled in a Codex sandbox."
+        );
+        return Ok(());
+    }
+
+    let server = create_mock_responses_server(Vec::new()).await;
+    let codex_home = TempDir::new()?;
+    create_config_toml(codex_home.path(), &server.uri())?;
+    let workdir = TempDir::new()?;
+    let mut mcp = McpProcess::new(codex_home.path()).await?;
+    timeout(DEFAULT_READ_TIMEOUT, mcp.initialize()).await??;
+
+    let start_id = mcp
+        .send_tool_call(
+            "codex-start-session",
+            json!({ "cwd": workdir.path().to_string_lossy() }),
+        )
+        .await?;
+    let started = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Number(start_id)),
+    )
+    .await??;
+    let structured = &started.result["structuredContent"];
+    let thread_id = structured["threadId"]
+        .as_str()
+        .ok_or_else(|| anyhow::anyhow!("missing threadId: {structured}"))?
+        .to_string();
+    let shell_requires_approval = structured["tools"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .find(|tool| tool["name"] == "exec_command")
+        .map(|tool| tool["requires_approval"].clone());
+    assert_eq!(shell_requires_approval, Some(Value::Bool(true)));
+
+    let created = workdir.path().join("created_by_session_tool.txt");
+    let tool_id = mcp
+        .send_tool_call(
+            "codex-tool",
+            json!({
+                "threadId": thread_id,
+                "tool": "exec_command",
+                "arguments": {
+                    "cmd": "python3 -c \"import pathlib; pathlib.Path('created_by_session_tool.txt').touch()\"",
+                    "workdir": workdir.path().to_string_lossy(),
+                },
+            }),
+        )
+        .await?;
+    let elicitation = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_request_message(),
+    )
+    .await??;
+    let params = serde_json::from_value::<ExecApprovalElicitRequestParams>(
+        elicitation
+            .request
+            .params
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("elicitation params must be set"))?,
+    )?;
+    assert_eq!(
+        (
+            elicitation.request.method.as_str(),
+            params.codex_elicitation.as_str(),
+            params.codex_mcp_tool_call_id,
+        ),
+        ("elicitation/create", "exec-approval", tool_id.to_string())
+    );
+    mcp.send_response(
+        elicitation.id,
+        serde_json::to_value(ExecApprovalResponse {
+            decision: ReviewDecision::Approved,
+        })?,
+    )
+    .await?;
+
+    let called = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Number(tool_id)),
+    )
+    .await??;
+    assert_eq!(called.result.get("isError"), None);
+    assert!(created.is_file(), "the approved command should have run");
+
+    let end_id = mcp
+        .send_tool_call("codex-end-session", json!({ "threadId": thread_id }))
+        .await?;
+    let ended = timeout(
+        DEFAULT_READ_TIMEOUT,
+        mcp.read_stream_until_response_message(RequestId::Number(end_id)),
+    )
+    .await??;
+    assert_eq!(
+        ended.result["content"][0]["text"],
+        json!(format!("Ended session {thread_id}"))
+    );
+    Ok(())
+}
+
+fn create_config_toml(codex_home: &Path, server_uri: &str) -> std::io::Result<()> {
+    std::fs::write(
+        codex_home.join("config.toml"),
+        format!(
+            r#"
+model = "mock-model"
+approval_policy = "untrusted"
+sandbox_mode = "danger-full-access"
+
+model_provider = "mock_provider"
+
+[model_providers.mock_provider]
+name = "Mock provider for test"
+base_url = "{server_uri}/v1"
+wire_api = "responses"
+request_max_retries = 0
+stream_max_retries = 0
+"#
+        ),
+    )
+}
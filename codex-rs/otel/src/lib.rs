@@ -17,6 +17,7 @@ use codex_protocol::ThreadId;
 pub use codex_utils_string::sanitize_metric_tag_value;
 use opentelemetry_sdk::metrics::data::ResourceMetrics;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::time::Duration;
 use strum_macros::Display;
 use tracing::debug;
@@ -51,6 +52,10 @@ pub struct OtelEventMetadata {
     pub(crate) log_user_prompts: bool,
     pub(crate) app_version: &'static str,
     pub(crate) terminal_type: String,
+    /// Prompt experiment arms as `(experiment, arm)`, sorted by experiment.
+    pub(crate) experiments: Vec<(String, String)>,
+    /// `experiment=arm` pairs joined by `,`, attached to log events.
+    pub(crate) experiments_label: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -67,6 +72,28 @@ impl OtelManager {
         self
     }
 
+    /// Tags telemetry with the prompt experiment arms assigned to the session.
+    /// Metrics get one `experiment.<name>` tag per experiment.
+    pub fn with_experiments(mut self, experiments: &BTreeMap<String, String>) -> Self {
+        self.metadata.experiments = experiments
+            .iter()
+            .map(|(experiment, arm)| {
+                (
+                    format!("experiment.{}", sanitize_metric_tag_value(experiment)),
+                    sanitize_metric_tag_value(arm),
+                )
+            })
+            .collect();
+        self.metadata.experiments_label = (!experiments.is_empty()).then(|| {
+            experiments
+                .iter()
+                .map(|(experiment, arm)| format!("{experiment}={arm}"))
+                .collect::<Vec<_>>()
+                .join(",")
+        });
+        self
+    }
+
     pub fn with_metrics(mut self, metrics: MetricsClient) -> Self {
         self.metrics = Some(metrics);
         self.metrics_use_metadata_tags = true;
@@ -211,6 +238,11 @@ impl OtelManager {
         )?;
         Self::push_metadata_tag(&mut tags, "model", Some(self.metadata.model.as_str()))?;
         Self::push_metadata_tag(&mut tags, "app.version", Some(self.metadata.app_version))?;
+        for (key, arm) in &self.metadata.experiments {
+            validate_tag_key(key)?;
+            validate_tag_value(arm)?;
+            tags.push((key.as_str(), arm.as_str()));
+        }
         Ok(tags)
     }
 
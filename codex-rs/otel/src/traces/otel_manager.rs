@@ -85,6 +85,8 @@ impl OtelManager {
                 log_user_prompts,
                 app_version: env!("CARGO_PKG_VERSION"),
                 terminal_type,
+                experiments: Vec::new(),
+                experiments_label: None,
             },
             metrics: crate::metrics::global(),
             metrics_use_metadata_tags: true,
@@ -143,6 +145,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             provider_name = %provider_name,
             reasoning_effort = reasoning_effort.map(|e| e.to_string()),
             reasoning_summary = %reasoning_summary,
@@ -208,6 +211,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             duration_ms = %duration.as_millis(),
             http.response.status_code = status,
             error.message = error,
@@ -240,6 +244,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             duration_ms = %duration.as_millis(),
             success = success_str,
             error.message = error,
@@ -342,6 +347,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             duration_ms = %duration.as_millis(),
             success = success_str,
             error.message = error_message.as_deref(),
@@ -420,6 +426,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             duration_ms = %duration.as_millis(),
         );
     }
@@ -454,6 +461,7 @@ impl OtelManager {
                 terminal.type = %self.metadata.terminal_type,
                 model = %self.metadata.model,
                 slug = %self.metadata.slug,
+                experiments = self.metadata.experiments_label,
                 duration_ms = %duration.as_millis(),
                 error.message = %error,
             ),
@@ -470,6 +478,7 @@ impl OtelManager {
                 terminal.type = %self.metadata.terminal_type,
                 model = %self.metadata.model,
                 slug = %self.metadata.slug,
+                experiments = self.metadata.experiments_label,
                 duration_ms = %duration.as_millis(),
                 error.message = %error,
             ),
@@ -494,6 +503,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             error.message = %error,
         )
     }
@@ -520,6 +530,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             input_token_count = %input_token_count,
             output_token_count = %output_token_count,
             cached_token_count = cached_token_count,
@@ -556,6 +567,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             prompt_length = %prompt.chars().count(),
             prompt = %prompt_to_log,
         );
@@ -581,6 +593,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             tool_name = %tool_name,
             call_id = %call_id,
             decision = %decision.clone().to_string().to_lowercase(),
@@ -642,6 +655,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             tool_name = %tool_name,
             duration_ms = %Duration::ZERO.as_millis(),
             success = %false,
@@ -686,6 +700,7 @@ impl OtelManager {
             terminal.type = %self.metadata.terminal_type,
             model = %self.metadata.model,
             slug = %self.metadata.slug,
+            experiments = self.metadata.experiments_label,
             tool_name = %tool_name,
             call_id = %call_id,
             arguments = %arguments,
@@ -761,6 +776,7 @@ impl OtelManager {
             ResponseEvent::ReasoningSummaryPartAdded { .. } => {
                 "reasoning_summary_part_added".into()
             }
+            ResponseEvent::ToolCallArgumentsDelta { .. } => "tool_call_arguments_delta".into(),
             ResponseEvent::ServerModel(_) => "server_model".into(),
             ResponseEvent::ServerReasoningIncluded(_) => "server_reasoning_included".into(),
             ResponseEvent::RateLimits(_) => "rate_limits".into(),
@@ -109,3 +109,61 @@ fn manager_allows_disabling_metadata_tags() -> Result<()> {
 
     Ok(())
 }
+
+// Ensures prompt experiment arms are attached to metrics as tags.
+#[test]
+fn manager_attaches_experiment_tags_to_metrics() -> Result<()> {
+    let (metrics, exporter) = build_metrics_with_defaults(&[])?;
+    let manager = OtelManager::new(
+        ThreadId::new(),
+        "gpt-5.1",
+        "gpt-5.1",
+        None,
+        None,
+        None,
+        "test_originator".to_string(),
+        true,
+        "tty".to_string(),
+        SessionSource::Cli,
+    )
+    .with_metrics(metrics)
+    .with_experiments(&BTreeMap::from([(
+        "terse prompt".to_string(),
+        "treatment".to_string(),
+    )]));
+
+    manager.counter("codex.session_started", 1, &[]);
+    manager.shutdown_metrics()?;
+
+    let resource_metrics = latest_metrics(&exporter);
+    let metric =
+        find_metric(&resource_metrics, "codex.session_started").expect("counter metric missing");
+    let attrs = match metric.data() {
+        AggregatedMetrics::U64(data) => match data {
+            MetricData::Sum(sum) => {
+                let points: Vec<_> = sum.data_points().collect();
+                assert_eq!(points.len(), 1);
+                attributes_to_map(points[0].attributes())
+            }
+            _ => panic!("unexpected counter aggregation"),
+        },
+        _ => panic!("unexpected counter data type"),
+    };
+
+    let expected = BTreeMap::from([
+        (
+            "app.version".to_string(),
+            env!("CARGO_PKG_VERSION").to_string(),
+        ),
+        (
+            "experiment.terse_prompt".to_string(),
+            "treatment".to_string(),
+        ),
+        ("model".to_string(), "gpt-5.1".to_string()),
+        ("originator".to_string(), "test_originator".to_string()),
+        ("session_source".to_string(), "cli".to_string()),
+    ]);
+    assert_eq!(attrs, expected);
+
+    Ok(())
+}
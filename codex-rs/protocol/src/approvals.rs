@@ -55,6 +55,32 @@ pub struct NetworkApprovalContext {
     pub protocol: NetworkApprovalProtocol,
 }
 
+/// Coarse risk level assigned to a command before it is shown for approval.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum CommandRiskLevel {
+    /// Only reads files or inspects state.
+    Low,
+    /// Writes files, touches the network, or does something we cannot classify.
+    Medium,
+    /// Deletes or overwrites data, rewrites history, or escalates privileges.
+    High,
+}
+
+/// Best-effort explanation of a command awaiting approval, computed locally
+/// and optionally refined by the `command_risk_model`.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct CommandRiskAssessment {
+    /// Short human-readable description of what the command does.
+    pub summary: String,
+    pub level: CommandRiskLevel,
+    /// Whether the command deletes or overwrites existing data.
+    pub destructive: bool,
+    /// Paths the command reads or writes, as written in the command (relative
+    /// paths are relative to the approval request's `cwd`).
+    pub paths: Vec<String>,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct ExecApprovalRequestEvent {
     /// Identifier for the associated command execution item.
@@ -86,6 +112,10 @@ pub struct ExecApprovalRequestEvent {
     #[ts(optional)]
     pub proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     pub parsed_cmd: Vec<ParsedCommand>,
+    /// Local risk classification of `command`, computed before the request is emitted.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub risk_assessment: Option<CommandRiskAssessment>,
 }
 
 impl ExecApprovalRequestEvent {
@@ -102,9 +132,27 @@ pub struct ElicitationRequestEvent {
     #[ts(type = "string | number")]
     pub id: RequestId,
     pub message: String,
-    // TODO: MCP servers can request we fill out a schema for the elicitation. We don't support
-    // this yet.
-    // pub requested_schema: ElicitRequestParamsRequestedSchema,
+    /// JSON schema of the answers a form request asks for: an object of
+    /// primitive properties. The answers go back as the `content` of
+    /// `Op::ResolveElicitation`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub requested_schema: Option<serde_json::Value>,
+    /// Page a URL request asks the user to open.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub url: Option<String>,
+    /// How long the request waits for an answer before it is cancelled.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub timeout_ms: Option<u64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct ElicitationTimedOutEvent {
+    pub server_name: String,
+    #[ts(type = "string | number")]
+    pub id: RequestId,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
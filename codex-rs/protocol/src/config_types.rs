@@ -24,6 +24,20 @@ pub enum ReasoningSummary {
     None,
 }
 
+/// How an instruction layer combines with the instructions assembled before it.
+#[derive(
+    Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, Eq, Display, JsonSchema, TS,
+)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum InstructionLayerMode {
+    /// Appended after the instructions assembled so far.
+    #[default]
+    Append,
+    /// Replaces the instructions assembled so far.
+    Replace,
+}
+
 /// Controls output length/detail on GPT-5 models via the Responses API.
 /// Serialized with lowercase values to match the OpenAI API.
 #[derive(
@@ -49,6 +63,36 @@ pub enum Verbosity {
     High,
 }
 
+/// Sampling parameters for model requests. Unset values are left to the
+/// provider's defaults; `seed` is only honored by providers that support
+/// deterministic sampling.
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, PartialEq, JsonSchema, TS)]
+pub struct SamplingParams {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(type = "number | null")]
+    pub seed: Option<u64>,
+}
+
+impl SamplingParams {
+    /// Returns these parameters with every value set in `overrides` taking
+    /// precedence.
+    pub fn with_overrides(self, overrides: SamplingParams) -> Self {
+        Self {
+            temperature: overrides.temperature.or(self.temperature),
+            top_p: overrides.top_p.or(self.top_p),
+            seed: overrides.seed.or(self.seed),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
 #[derive(
     Deserialize, Debug, Clone, Copy, PartialEq, Default, Serialize, Display, JsonSchema, TS,
 )]
@@ -358,4 +402,28 @@ mod tests {
         assert!(!ModeKind::PairProgramming.is_tui_visible());
         assert!(!ModeKind::Execute.is_tui_visible());
     }
+
+    #[test]
+    fn sampling_overrides_replace_only_set_values() {
+        let configured = SamplingParams {
+            temperature: Some(0.2),
+            top_p: Some(0.9),
+            seed: None,
+        };
+        let overrides = SamplingParams {
+            temperature: Some(0.0),
+            top_p: None,
+            seed: Some(7),
+        };
+
+        assert_eq!(
+            configured.with_overrides(overrides),
+            SamplingParams {
+                temperature: Some(0.0),
+                top_p: Some(0.9),
+                seed: Some(7),
+            }
+        );
+        assert!(SamplingParams::default().is_empty());
+    }
 }
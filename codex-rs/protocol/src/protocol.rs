@@ -3,6 +3,7 @@
 //! Uses a SQ (Submission Queue) / EQ (Event Queue) pattern to asynchronously communicate
 //! between user and agent.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::ffi::OsStr;
@@ -14,10 +15,13 @@ use std::time::Duration;
 
 use crate::ThreadId;
 use crate::approvals::ElicitationRequestEvent;
+use crate::approvals::ElicitationTimedOutEvent;
 use crate::config_types::CollaborationMode;
+use crate::config_types::InstructionLayerMode;
 use crate::config_types::ModeKind;
 use crate::config_types::Personality;
 use crate::config_types::ReasoningSummary as ReasoningSummaryConfig;
+use crate::config_types::SamplingParams;
 use crate::config_types::WindowsSandboxLevel;
 use crate::custom_prompts::CustomPrompt;
 use crate::dynamic_tools::DynamicToolCallRequest;
@@ -33,6 +37,7 @@ use crate::message_history::HistoryEntry;
 use crate::models::BaseInstructions;
 use crate::models::ContentItem;
 use crate::models::MessagePhase;
+use crate::models::ResponseInputItem;
 use crate::models::ResponseItem;
 use crate::models::WebSearchAction;
 use crate::num_format::format_with_separators;
@@ -52,6 +57,8 @@ use tracing::error;
 use ts_rs::TS;
 
 pub use crate::approvals::ApplyPatchApprovalRequestEvent;
+pub use crate::approvals::CommandRiskAssessment;
+pub use crate::approvals::CommandRiskLevel;
 pub use crate::approvals::ElicitationAction;
 pub use crate::approvals::ExecApprovalRequestEvent;
 pub use crate::approvals::ExecPolicyAmendment;
@@ -130,6 +137,10 @@ pub enum Op {
     /// This server sends [`EventMsg::TurnAborted`] in response.
     Interrupt,
 
+    /// Abort one tool call in flight while the rest of the turn continues.
+    /// The model receives the aborted output of that call.
+    CancelToolCall { call_id: String },
+
     /// Terminate all running background terminal processes for this thread.
     CleanBackgroundTerminals,
 
@@ -194,6 +205,11 @@ pub enum Op {
         /// Optional personality override for this turn.
         #[serde(skip_serializing_if = "Option::is_none")]
         personality: Option<Personality>,
+
+        /// Sampling overrides for this turn only, applied on top of the
+        /// session's configured sampling parameters.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        sampling: Option<SamplingParams>,
     },
 
     /// Override parts of the persistent turn context for subsequent turns.
@@ -272,6 +288,10 @@ pub enum Op {
         request_id: RequestId,
         /// User's decision for the request.
         decision: ElicitationAction,
+        /// Answers to a form request, matching its `requested_schema`, when
+        /// accepting it.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<Value>,
     },
 
     /// Resolve a request_user_input tool call.
@@ -310,6 +330,14 @@ pub enum Op {
     /// Request MCP servers to reinitialize and refresh cached tool lists.
     RefreshMcpServers { config: McpServerRefreshConfig },
 
+    /// Re-fetch the tool listings of the running MCP servers, or only of
+    /// `server`, bypassing the listing cache.
+    /// Reply is delivered via `EventMsg::McpListToolsResponse`.
+    RefreshMcpTools {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        server: Option<String>,
+    },
+
     /// Reload user config layer overrides for the active session.
     ///
     /// This updates runtime config-derived behavior (for example app
@@ -367,6 +395,17 @@ pub enum Op {
     /// responsible for undoing any edits on disk.
     ThreadRollback { num_turns: u32 },
 
+    /// Edit and regenerate: roll history back to just before the user message
+    /// with item ID `item_id` (from its `ItemStarted`/`ItemCompleted` events)
+    /// and start a new turn with `items` in its place.
+    ///
+    /// Emits `ThreadRolledBack` for the dropped turns before the new turn
+    /// starts. Like `ThreadRollback`, this does not revert changes on disk.
+    RetryFromUserMessage {
+        item_id: String,
+        items: Vec<UserInput>,
+    },
+
     /// Request a code review from the agent.
     Review { review_request: ReviewRequest },
 
@@ -383,10 +422,220 @@ pub enum Op {
         command: String,
     },
 
+    /// Inject a summary of a commit range or pull request (commit metadata
+    /// plus the diff, with large per-file diffs summarized) into the
+    /// conversation context without starting a turn.
+    InjectGitContext { source: GitContextSource },
+
+    /// Change the session's working directory between turns. Relative paths
+    /// resolve against the current cwd. Rejected while a turn is running, and
+    /// under a workspace-write sandbox when the directory is outside the
+    /// writable roots. The change is recorded in the conversation history.
+    SetCwd { cwd: PathBuf },
+
+    /// Attach content such as a diff, log, or JSON document to the
+    /// conversation context without starting a turn. The content is
+    /// registered with the session: small text enters the context whole,
+    /// larger text and images enter as a summary the model reads further with
+    /// the `read_attachment` tool. Rejected with an error once the session's
+    /// attachments would exceed their size cap.
+    AttachInput {
+        /// MIME type of the content, e.g. `text/x-diff` or `application/json`.
+        content_type: String,
+        /// Optional label shown to the model, e.g. the source file name.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        name: Option<String>,
+        content: String,
+    },
+
+    /// In a shadow-workspace session, collect every change made to the shadow
+    /// copy since the last apply into one patch, request approval for it, and
+    /// apply it to the real repository once approved.
+    ApplyToRealWorkspace,
+
+    /// Request the patch artifact of a patch-artifact-mode session: every
+    /// change proposed so far as one unified diff. Reply is delivered via
+    /// `EventMsg::PatchArtifact`.
+    GetPatchArtifact,
+
+    /// Explore the workspace and store a codebase map (modules, entry points,
+    /// build/test commands, conventions) in memory. Stored maps are added to
+    /// the context of new sessions in the same repository.
+    GenerateCodebaseMap,
+
+    /// Run `items` as a user turn guarded by `[benchmarks]` in config.toml.
+    /// The benchmarks run against a snapshot of the workspace from before the
+    /// turn and against the workspace after it; regressions are reported as
+    /// a warning or, with `on_regression = "block"`, sent back to the agent
+    /// to fix before the turn completes.
+    BenchmarkedTurn { items: Vec<UserInput> },
+
+    /// Check the packages pinned by the workspace's lockfiles against OSV
+    /// advisories, which include RustSec. Reply is delivered via
+    /// `EventMsg::DependencyAudit`. With `upgrade`, a turn then bumps the
+    /// affected dependencies to the suggested versions.
+    AuditDependencies { upgrade: bool },
+
+    /// Inventory the licenses of the packages pinned by the workspace's
+    /// lockfiles and of the SPDX headers in its source files, and check
+    /// them against `[license_policy]`. Reply is delivered via
+    /// `EventMsg::LicenseReport`.
+    ScanLicenses,
+
+    /// Delete stored sessions according to `[session_retention]`. With
+    /// `dry_run`, nothing is deleted and the reply lists what would be.
+    /// Reply is delivered via `EventMsg::SessionsPruned`.
+    PruneSessions { dry_run: bool },
+
+    /// Write this session's history, metadata, memory summaries, and
+    /// attachment manifest to a portable archive at `path`, so it can be
+    /// imported and resumed on another machine.
+    /// Reply is delivered via `EventMsg::SessionExported`.
+    ExportSession { path: PathBuf },
+
+    /// Store the session in the archive at `path` (written by
+    /// `Op::ExportSession`) with the local sessions so it can be resumed.
+    /// Reply is delivered via `EventMsg::SessionImported`.
+    ImportSession { path: PathBuf },
+
+    /// Let the observer attached to this thread with id `client` (see
+    /// `AttachedClient::id`) submit ops.
+    /// Reply is delivered via `EventMsg::ObserverPromoted`.
+    PromoteObserver { client: String },
+
+    /// Turn tools off and back on for the rest of the session, starting with
+    /// the next model request. Entries name a tool as the model sees it (e.g.
+    /// `web_search`, `mcp__docs__search`) or an MCP server, which covers all
+    /// of its tools. `deny` is applied after `allow`; `allow` only re-enables
+    /// tools turned off this way, never ones the config leaves out.
+    /// Reply is delivered via `EventMsg::EnabledToolsUpdated`.
+    SetEnabledTools {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        allow: Vec<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        deny: Vec<String>,
+    },
+
+    /// Review the commands approved in this project while
+    /// `learn_approved_commands` is on. Commands in `promote` are saved as
+    /// execpolicy allow rules, like `ApprovedExecpolicyAmendment`; commands in
+    /// `dismiss` are dropped from the suggestions. Send both empty to list
+    /// the suggestions. Reply is delivered via `EventMsg::SuggestedAllowlist`.
+    ReviewSuggestedAllowlist {
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        promote: Vec<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        dismiss: Vec<Vec<String>>,
+    },
+
+    /// Complete the code at `cursor` in the file at `path` (relative paths
+    /// resolve against the cwd), for editor completions. Bypasses the agent
+    /// loop: no turn starts and nothing is recorded. The code sent around the
+    /// cursor is cut to the prefix and suffix budgets, in tokens.
+    /// Reply is delivered via `EventMsg::CompletionResult`.
+    Complete {
+        path: PathBuf,
+        cursor: TextPosition,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix_tokens: Option<u32>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        suffix_tokens: Option<u32>,
+    },
+
+    /// Rewrite lines `range` of the file at `path` (relative paths resolve
+    /// against the cwd) as `instruction` asks. Runs a turn without tools or
+    /// thread history, so the model can only produce the replacement; no file
+    /// is written. Reply is delivered via `EventMsg::InlineEditResult`.
+    InlineEdit {
+        path: PathBuf,
+        range: LineRange,
+        instruction: String,
+    },
+
+    /// Sample `n` alternatives to the model's last response from the same
+    /// context. The candidates are kept out of the thread history and offer
+    /// no tools, so nothing runs until one is chosen with
+    /// `Op::SelectCandidate`. Reply is delivered via
+    /// `EventMsg::ResponseCandidates`.
+    Regenerate { n: u32 },
+
+    /// Replace the model's last response with candidate `index` of the latest
+    /// `EventMsg::ResponseCandidates`. Reply is delivered via
+    /// `EventMsg::ResponseCandidateSelected`.
+    SelectCandidate { index: u32 },
+
+    /// Forecast when each rate-limit window runs out at the current burn
+    /// rate, from the snapshots received this session. Reply is delivered via
+    /// `EventMsg::RateLimitForecast`.
+    ForecastRateLimits,
+
+    /// Enter or leave offline mode. While offline, model requests fail fast
+    /// with `CodexErrorInfo::Offline` and new turns are queued instead of
+    /// started; everything that does not need the provider keeps working.
+    /// The provider is probed periodically, and once it answers the session
+    /// goes back online and runs the queued turns in order. Reply is
+    /// delivered via `EventMsg::OfflineStatus`.
+    SetOffline { offline: bool },
+
+    /// Token usage and estimated cost rolled up per project, day, and model,
+    /// across every session on this machine. `project` (relative paths
+    /// resolve against the cwd) keeps only the project containing it;
+    /// `since` and `until` are inclusive local dates (`YYYY-MM-DD`). Reply is
+    /// delivered via `EventMsg::UsageRollups`.
+    QueryUsage {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        project: Option<PathBuf>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        since: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<String>,
+    },
+
+    /// Report where the time of the session's recent turns went, for
+    /// diagnosing slow ones. Reply is delivered via `EventMsg::TurnTimings`.
+    GetTurnTimings,
+
+    /// Report what the model's context holds, item by item, with when and
+    /// where each item came from. Reply is delivered via
+    /// `EventMsg::ContextBreakdown`.
+    GetContextBreakdown,
+
+    /// Report the tool calls executing right now, for a live view of what
+    /// the agent is doing. Reply is delivered via `EventMsg::ActiveToolCalls`.
+    GetActiveToolCalls,
+
+    /// List the session's tools as the model is offered them, for a client
+    /// that calls them itself with `Op::InvokeTool`. Reply is delivered via
+    /// `EventMsg::SessionTools`.
+    ListSessionTools,
+
+    /// Run one of the session's tools directly, as if the model had called
+    /// it, under the session's approval and sandbox policies. Approval
+    /// requests are raised as for the model's calls. Reply is delivered via
+    /// `EventMsg::ToolInvocationResult`.
+    InvokeTool {
+        call_id: String,
+        tool: String,
+        /// The arguments as the model would pass them: a JSON object for
+        /// function tools, the raw input for freeform ones.
+        arguments: String,
+    },
+
     /// Request the list of available models.
     ListModels,
 }
 
+/// What [`Op::InjectGitContext`] should summarize.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, JsonSchema, TS)]
+#[serde(tag = "type", rename_all = "camelCase")]
+#[ts(tag = "type")]
+pub enum GitContextSource {
+    /// A revision range understood by `git log`, e.g. `main..HEAD`.
+    CommitRange { range: String },
+    /// A pull request URL or number, fetched with the GitHub CLI (`gh`).
+    PullRequest { pr: String },
+}
+
 /// Determines the conditions under which the user is consulted to approve
 /// running the command proposed by Codex.
 #[derive(
@@ -1017,6 +1266,18 @@ pub enum EventMsg {
 
     McpToolCallEnd(McpToolCallEndEvent),
 
+    /// Progress an MCP server reported for a tool call in flight
+    /// (`notifications/progress`).
+    McpToolCallProgress(McpToolCallProgressEvent),
+
+    /// A log message an MCP server sent while running a tool call
+    /// (`notifications/message`).
+    McpToolCallLog(McpToolCallLogEvent),
+
+    /// Best-effort view of a function call's arguments while the model is
+    /// still streaming them.
+    ToolCallArgumentsPreview(ToolCallArgumentsPreviewEvent),
+
     WebSearchBegin(WebSearchBeginEvent),
 
     WebSearchEnd(WebSearchEndEvent),
@@ -1043,6 +1304,10 @@ pub enum EventMsg {
 
     ElicitationRequest(ElicitationRequestEvent),
 
+    /// An MCP elicitation went unanswered past its timeout and was
+    /// cancelled, so clients can dismiss it.
+    ElicitationTimedOut(ElicitationTimedOutEvent),
+
     ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent),
 
     /// Notification advising the user that something they are using has been
@@ -1086,6 +1351,86 @@ pub enum EventMsg {
     /// Remote skill downloaded to local cache.
     RemoteSkillDownloaded(RemoteSkillDownloadedEvent),
 
+    /// Changes proposed in a patch-artifact-mode session.
+    PatchArtifact(PatchArtifactEvent),
+
+    /// Stored sessions deleted (or, in a dry run, selected for deletion).
+    SessionsPruned(SessionsPrunedEvent),
+
+    SessionExported(SessionExportedEvent),
+
+    SessionImported(SessionImportedEvent),
+
+    /// A pending exec or patch approval was answered. Clients attached to the
+    /// same thread use it to dismiss their own prompt for the approval.
+    ApprovalResolved(ApprovalResolvedEvent),
+
+    /// An observer attached to the thread was promoted to a driver.
+    ObserverPromoted(ObserverPromotedEvent),
+
+    InlineEditResult(InlineEditResultEvent),
+
+    CompletionResult(CompletionResultEvent),
+
+    EnabledToolsUpdated(EnabledToolsUpdatedEvent),
+
+    SuggestedAllowlist(SuggestedAllowlistEvent),
+
+    DependencyAudit(DependencyAuditEvent),
+
+    LicenseReport(LicenseReportEvent),
+
+    ResponseCandidates(ResponseCandidatesEvent),
+
+    ResponseCandidateSelected(ResponseCandidateSelectedEvent),
+
+    RateLimitForecast(RateLimitForecastEvent),
+
+    /// The account's plan changed or its credits ran out or came back; a
+    /// balance that merely drops is not reported. The latest `TokenCount`
+    /// carries the same data.
+    PlanOrCreditsChanged(PlanOrCreditsChangedEvent),
+
+    /// The session entered or left offline mode, or queued a turn for when it
+    /// is back online.
+    OfflineStatus(OfflineStatusEvent),
+
+    UsageRollups(UsageRollupsEvent),
+
+    TurnTimings(TurnTimingsEvent),
+
+    /// Latency breakdown of a finished or aborted turn. Only written to the
+    /// rollout, so `Op::GetTurnTimings` still covers the turn after a resume.
+    TurnTimingRecorded(TurnTiming),
+
+    ContextBreakdown(ContextBreakdownEvent),
+
+    /// Metadata of the history items recorded from the response items just
+    /// before it in the rollout, so resuming restores it with them.
+    HistoryMetadataRecorded(HistoryMetadataRecordedEvent),
+
+    ActiveToolCalls(ActiveToolCallsEvent),
+
+    /// A tool call has held the exclusive tool lock, which keeps every other
+    /// tool call waiting, past the watchdog's threshold.
+    ToolLockHeld(ToolLockHeldEvent),
+
+    /// Progress of a long-running built-in tool call, so clients can show a
+    /// determinate progress bar.
+    ToolProgress(ToolProgressEvent),
+
+    SessionTools(SessionToolsEvent),
+
+    ToolInvocationResult(ToolInvocationResultEvent),
+
+    /// The connectors and MCP tools selected in the session, recorded in the
+    /// rollout whenever the selection grows so it can be restored on resume.
+    ToolSelection(ToolSelectionEvent),
+
+    /// The connector and MCP tool selection a resumed session picked up from
+    /// its rollout.
+    ToolSelectionRestored(ToolSelectionEvent),
+
     /// Notification that skill data may have been updated and clients may want to reload.
     SkillsUpdateAvailable,
 
@@ -1210,6 +1555,166 @@ impl From<CollabResumeEndEvent> for EventMsg {
     }
 }
 
+/// Semantic hint attached to an event so assistive clients (e.g. screen
+/// readers) can prioritize announcements without parsing free text.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "kebab-case")]
+#[ts(rename_all = "kebab-case")]
+pub enum EventAnnotation {
+    /// Work has started or is ongoing.
+    Progress,
+    /// A unit of work has finished.
+    Completed,
+    /// The user must act or should be told promptly.
+    NeedsAttention,
+    /// An approval request for an action that deletes or overwrites data.
+    DestructiveActionPending,
+    /// Something failed.
+    Error,
+}
+
+impl EventMsg {
+    /// Semantic annotations for this event, most urgent first. Streaming
+    /// deltas and bookkeeping events have none.
+    pub fn annotations(&self) -> Vec<EventAnnotation> {
+        match self {
+            EventMsg::ExecApprovalRequest(event) => {
+                if event
+                    .risk_assessment
+                    .as_ref()
+                    .is_some_and(|risk| risk.destructive)
+                {
+                    vec![
+                        EventAnnotation::DestructiveActionPending,
+                        EventAnnotation::NeedsAttention,
+                    ]
+                } else {
+                    vec![EventAnnotation::NeedsAttention]
+                }
+            }
+            EventMsg::ApplyPatchApprovalRequest(event) => {
+                if event
+                    .changes
+                    .values()
+                    .any(|change| matches!(change, FileChange::Delete { .. }))
+                {
+                    vec![
+                        EventAnnotation::DestructiveActionPending,
+                        EventAnnotation::NeedsAttention,
+                    ]
+                } else {
+                    vec![EventAnnotation::NeedsAttention]
+                }
+            }
+            EventMsg::Error(_) => vec![EventAnnotation::Error, EventAnnotation::NeedsAttention],
+            EventMsg::RequestUserInput(_)
+            | EventMsg::DynamicToolCallRequest(_)
+            | EventMsg::ElicitationRequest(_)
+            | EventMsg::Warning(_)
+            | EventMsg::DeprecationNotice(_)
+            | EventMsg::TurnAborted(_) => vec![EventAnnotation::NeedsAttention],
+            EventMsg::TurnStarted(_)
+            | EventMsg::McpStartupUpdate(_)
+            | EventMsg::McpToolCallBegin(_)
+            | EventMsg::WebSearchBegin(_)
+            | EventMsg::ExecCommandBegin(_)
+            | EventMsg::PatchApplyBegin(_)
+            | EventMsg::BackgroundEvent(_)
+            | EventMsg::UndoStarted(_)
+            | EventMsg::StreamError(_)
+            | EventMsg::PlanUpdate(_)
+            | EventMsg::ContextCompacted(_)
+            | EventMsg::EnteredReviewMode(_)
+            | EventMsg::ItemStarted(_)
+            | EventMsg::CollabAgentSpawnBegin(_)
+            | EventMsg::CollabAgentInteractionBegin(_)
+            | EventMsg::CollabWaitingBegin(_)
+            | EventMsg::CollabCloseBegin(_)
+            | EventMsg::CollabResumeBegin(_) => vec![EventAnnotation::Progress],
+            EventMsg::TurnComplete(_)
+            | EventMsg::McpStartupComplete(_)
+            | EventMsg::McpToolCallEnd(_)
+            | EventMsg::WebSearchEnd(_)
+            | EventMsg::ExecCommandEnd(_)
+            | EventMsg::PatchApplyEnd(_)
+            | EventMsg::UndoCompleted(_)
+            | EventMsg::ExitedReviewMode(_)
+            | EventMsg::ItemCompleted(_)
+            | EventMsg::CollabAgentSpawnEnd(_)
+            | EventMsg::CollabAgentInteractionEnd(_)
+            | EventMsg::CollabWaitingEnd(_)
+            | EventMsg::CollabCloseEnd(_)
+            | EventMsg::CollabResumeEnd(_)
+            | EventMsg::ShutdownComplete => vec![EventAnnotation::Completed],
+            EventMsg::RealtimeConversationStarted(_)
+            | EventMsg::RealtimeConversationRealtime(_)
+            | EventMsg::RealtimeConversationClosed(_)
+            | EventMsg::ModelReroute(_)
+            | EventMsg::ThreadRolledBack(_)
+            | EventMsg::TokenCount(_)
+            | EventMsg::AgentMessage(_)
+            | EventMsg::UserMessage(_)
+            | EventMsg::AgentMessageDelta(_)
+            | EventMsg::AgentReasoning(_)
+            | EventMsg::AgentReasoningDelta(_)
+            | EventMsg::AgentReasoningRawContent(_)
+            | EventMsg::AgentReasoningRawContentDelta(_)
+            | EventMsg::AgentReasoningSectionBreak(_)
+            | EventMsg::SessionConfigured(_)
+            | EventMsg::ThreadNameUpdated(_)
+            | EventMsg::ExecCommandOutputDelta(_)
+            | EventMsg::TerminalInteraction(_)
+            | EventMsg::ViewImageToolCall(_)
+            | EventMsg::TurnDiff(_)
+            | EventMsg::GetHistoryEntryResponse(_)
+            | EventMsg::McpListToolsResponse(_)
+            | EventMsg::ListCustomPromptsResponse(_)
+            | EventMsg::ListSkillsResponse(_)
+            | EventMsg::ListRemoteSkillsResponse(_)
+            | EventMsg::RemoteSkillDownloaded(_)
+            | EventMsg::PatchArtifact(_)
+            | EventMsg::ToolCallArgumentsPreview(_)
+            | EventMsg::SessionsPruned(_)
+            | EventMsg::SessionExported(_)
+            | EventMsg::SessionImported(_)
+            | EventMsg::ApprovalResolved(_)
+            | EventMsg::ObserverPromoted(_)
+            | EventMsg::InlineEditResult(_)
+            | EventMsg::SessionTools(_)
+            | EventMsg::ToolSelection(_)
+            | EventMsg::ToolSelectionRestored(_)
+            | EventMsg::ElicitationTimedOut(_)
+            | EventMsg::ToolInvocationResult(_)
+            | EventMsg::CompletionResult(_)
+            | EventMsg::EnabledToolsUpdated(_)
+            | EventMsg::SuggestedAllowlist(_)
+            | EventMsg::DependencyAudit(_)
+            | EventMsg::LicenseReport(_)
+            | EventMsg::ResponseCandidates(_)
+            | EventMsg::ResponseCandidateSelected(_)
+            | EventMsg::RateLimitForecast(_)
+            | EventMsg::PlanOrCreditsChanged(_)
+            | EventMsg::OfflineStatus(_)
+            | EventMsg::UsageRollups(_)
+            | EventMsg::TurnTimings(_)
+            | EventMsg::TurnTimingRecorded(_)
+            | EventMsg::ContextBreakdown(_)
+            | EventMsg::HistoryMetadataRecorded(_)
+            | EventMsg::ActiveToolCalls(_)
+            | EventMsg::ToolLockHeld(_)
+            | EventMsg::ToolProgress(_)
+            | EventMsg::McpToolCallProgress(_)
+            | EventMsg::McpToolCallLog(_)
+            | EventMsg::SkillsUpdateAvailable
+            | EventMsg::RawResponseItem(_)
+            | EventMsg::AgentMessageContentDelta(_)
+            | EventMsg::PlanDelta(_)
+            | EventMsg::ReasoningContentDelta(_)
+            | EventMsg::ReasoningRawContentDelta(_) => Vec::new(),
+        }
+    }
+}
+
 /// Agent lifecycle status, derived from emitted events.
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS, Default)]
 #[serde(rename_all = "snake_case")]
@@ -1258,9 +1763,36 @@ pub enum CodexErrorInfo {
         http_status_code: Option<u16>,
     },
     ThreadRollbackFailed,
+    TaskLimitReached,
+    /// The session is offline, so no model request was made.
+    Offline,
+    /// The model provider rejected the request for a reason the user can act on.
+    ProviderError {
+        kind: ProviderErrorKind,
+        http_status_code: Option<u16>,
+        /// Delay the provider asked for before retrying, from `Retry-After`.
+        retry_after_seconds: Option<u64>,
+        /// What the user can do about it.
+        remediation: String,
+    },
     Other,
 }
 
+/// Why a model provider rejected a request, classified from its HTTP response.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, TS)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum ProviderErrorKind {
+    /// 401: the credentials are missing, expired, or revoked.
+    Unauthorized,
+    /// 403: the organization must be verified before it can use the model.
+    OrganizationVerificationRequired,
+    /// 429 that is not a usage limit: too many requests for now.
+    RateLimited,
+    /// 502, 503, or 504: the provider is failing or unavailable.
+    ServerError,
+}
+
 impl CodexErrorInfo {
     /// Whether this error should mark the current turn as failed when replaying history.
     pub fn affects_turn_status(&self) -> bool {
@@ -1277,6 +1809,9 @@ impl CodexErrorInfo {
             | Self::SandboxError
             | Self::ResponseStreamDisconnected { .. }
             | Self::ResponseTooManyFailedAttempts { .. }
+            | Self::TaskLimitReached
+            | Self::Offline
+            | Self::ProviderError { .. }
             | Self::Other => true,
         }
     }
@@ -1550,6 +2085,21 @@ impl TokenUsageInfo {
 pub struct TokenCountEvent {
     pub info: Option<TokenUsageInfo>,
     pub rate_limits: Option<RateLimitSnapshot>,
+    /// The model request `info.last_token_usage` belongs to, when the event
+    /// follows one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub request: Option<ModelRequestUsage>,
+}
+
+/// A model request within a turn, identifying the usage a [`TokenCountEvent`]
+/// reports.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ModelRequestUsage {
+    /// 1-based position of the request in its turn; tool-call follow-ups
+    /// count as new requests.
+    pub index: u32,
+    pub model: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize, JsonSchema, TS)]
@@ -1776,6 +2326,43 @@ impl McpToolCallEndEvent {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct McpToolCallProgressEvent {
+    /// Identifier of the McpToolCallBegin the progress is for.
+    pub call_id: String,
+    /// Progress so far, in the server's units. Increases with every update.
+    pub progress: f64,
+    /// Progress at completion, when the server knows it.
+    pub total: Option<f64>,
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct McpToolCallLogEvent {
+    /// Identifier of the McpToolCallBegin the server was running.
+    pub call_id: String,
+    pub level: McpLogLevel,
+    /// Name of the server's logger, if it sent one.
+    pub logger: Option<String>,
+    /// The message: a string or any JSON value.
+    pub data: serde_json::Value,
+}
+
+/// Severity of an MCP log message, as in RFC 5424.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+#[ts(rename_all = "snake_case")]
+pub enum McpLogLevel {
+    Debug,
+    Info,
+    Notice,
+    Warning,
+    Error,
+    Critical,
+    Alert,
+    Emergency,
+}
+
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct WebSearchBeginEvent {
     pub call_id: String,
@@ -1901,6 +2488,22 @@ impl InitialHistory {
             }),
         }
     }
+
+    pub fn get_experiments(&self) -> Option<BTreeMap<String, String>> {
+        match self {
+            InitialHistory::New => None,
+            InitialHistory::Resumed(resumed) => {
+                resumed.history.iter().find_map(|item| match item {
+                    RolloutItem::SessionMeta(meta_line) => meta_line.meta.experiments.clone(),
+                    _ => None,
+                })
+            }
+            InitialHistory::Forked(items) => items.iter().find_map(|item| match item {
+                RolloutItem::SessionMeta(meta_line) => meta_line.meta.experiments.clone(),
+                _ => None,
+            }),
+        }
+    }
 }
 
 fn session_cwd_from_items(items: &[RolloutItem]) -> Option<PathBuf> {
@@ -2022,6 +2625,26 @@ pub struct SessionMeta {
     pub base_instructions: Option<BaseInstructions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub dynamic_tools: Option<Vec<DynamicToolSpec>>,
+    /// Instruction layers applied on top of the model's base instructions,
+    /// in the order they were applied. Recorded for debugging only; the
+    /// layered text is already part of `base_instructions`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instruction_layers: Option<Vec<InstructionLayerMeta>>,
+    /// Prompt experiment arms assigned to the session, keyed by experiment.
+    /// Resumed and forked sessions keep these arms.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub experiments: Option<BTreeMap<String, String>>,
+}
+
+/// Record of one instruction layer applied to a session's base instructions.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, JsonSchema, TS)]
+pub struct InstructionLayerMeta {
+    pub name: String,
+    pub mode: InstructionLayerMode,
+    /// Approximate number of tokens the layer contributed.
+    pub tokens: usize,
+    /// Whether the layer was cut down to fit its token budget.
+    pub truncated: bool,
 }
 
 impl Default for SessionMeta {
@@ -2039,6 +2662,8 @@ impl Default for SessionMeta {
             model_provider: None,
             base_instructions: None,
             dynamic_tools: None,
+            instruction_layers: None,
+            experiments: None,
         }
     }
 }
@@ -2117,6 +2742,9 @@ pub struct TurnContextItem {
     pub final_output_json_schema: Option<Value>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub truncation_policy: Option<TruncationPolicy>,
+    /// Sampling parameters the turn's requests were sent with.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sampling: Option<SamplingParams>,
 }
 
 #[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
@@ -2174,6 +2802,11 @@ pub enum ReviewTarget {
         title: Option<String>,
     },
 
+    /// Review the changes in a pull request, identified by its URL.
+    #[serde(rename_all = "camelCase")]
+    #[ts(rename_all = "camelCase")]
+    PullRequest { url: String },
+
     /// Arbitrary instructions provided by the user.
     #[serde(rename_all = "camelCase")]
     #[ts(rename_all = "camelCase")]
@@ -2441,6 +3074,33 @@ pub struct PatchApplyEndEvent {
     pub changes: HashMap<PathBuf, FileChange>,
     /// Completion status for this patch application.
     pub status: PatchApplyStatus,
+    /// The lines this patch wrote, for clients that link to them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub anchors: Vec<FileAnchor>,
+}
+
+/// Lines of a file, 1-based and inclusive.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct LineRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// A position in a text file. Both fields are 1-based; `column` counts
+/// characters, so column 1 is before the first character of the line.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct TextPosition {
+    pub line: u32,
+    pub column: u32,
+}
+
+/// A span of lines in a file, 1-based and inclusive, that clients can jump
+/// to or decorate.
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
+pub struct FileAnchor {
+    pub path: PathBuf,
+    pub start_line: u32,
+    pub end_line: u32,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Eq, JsonSchema, TS)]
@@ -2454,6 +3114,10 @@ pub enum PatchApplyStatus {
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
 pub struct TurnDiffEvent {
     pub unified_diff: String,
+    /// Where the lines written by this turn's patches are now, after any
+    /// later edits in the turn moved them.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub anchors: Vec<FileAnchor>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -2583,6 +3247,511 @@ pub struct RemoteSkillDownloadedEvent {
     pub path: PathBuf,
 }
 
+/// Sent periodically while a function call's arguments stream in, so clients
+/// can preview large calls (for example a long command) before they run.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ToolCallArgumentsPreviewEvent {
+    pub call_id: String,
+    pub tool_name: String,
+    /// The arguments received so far, with unterminated strings and
+    /// containers closed and any trailing incomplete member dropped.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub arguments: Option<serde_json::Value>,
+    /// Set when the arguments can no longer form a valid JSON object; no
+    /// further previews follow for the call.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub error: Option<String>,
+}
+
+/// Response payload for `Op::GetPatchArtifact`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
+pub struct PatchArtifactEvent {
+    /// Every proposed change as one unified diff, in `git diff` format.
+    pub patch: String,
+    /// Where the artifact is also written, if configured.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub path: Option<PathBuf>,
+}
+
+/// Response payload for `Op::AuditDependencies`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct DependencyAuditEvent {
+    /// Lockfiles read, relative to the session cwd.
+    pub lockfiles: Vec<PathBuf>,
+    /// Distinct packages checked.
+    pub packages_checked: u64,
+    pub findings: Vec<DependencyAdvisoryFinding>,
+    /// Advisories that could not be looked up. Findings are incomplete when
+    /// this is not empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// An advisory affecting a package version pinned by a lockfile.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct DependencyAdvisoryFinding {
+    /// OSV ecosystem of the package: `crates.io`, `npm`, or `PyPI`.
+    pub ecosystem: String,
+    pub package: String,
+    pub version: String,
+    pub lockfile: PathBuf,
+    /// OSV id of the advisory, e.g. `RUSTSEC-2024-0001` or `GHSA-...`.
+    pub advisory_id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    pub summary: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub severity: Option<String>,
+    /// Lowest version newer than `version` that fixes the advisory, if one
+    /// is released.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub suggested_version: Option<String>,
+}
+
+/// Response payload for `Op::ScanLicenses`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct LicenseReportEvent {
+    pub dependencies: Vec<DependencyLicense>,
+    /// Licenses declared by `SPDX-License-Identifier` source file headers.
+    pub source_headers: Vec<SourceHeaderLicense>,
+    pub violations: Vec<LicenseViolation>,
+    /// Licenses that could not be looked up. The report is incomplete when
+    /// this is not empty.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<String>,
+}
+
+/// License of a package version pinned by a lockfile.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct DependencyLicense {
+    /// OSV ecosystem of the package: `crates.io`, `npm`, or `PyPI`.
+    pub ecosystem: String,
+    pub package: String,
+    pub version: String,
+    pub lockfile: PathBuf,
+    /// SPDX expression, e.g. `MIT OR Apache-2.0`; `None` when neither local
+    /// package metadata nor the registry declares one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub license: Option<String>,
+}
+
+/// A license declared by source file headers.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SourceHeaderLicense {
+    pub license: String,
+    /// Files declaring the license, relative to the session cwd.
+    pub files: Vec<PathBuf>,
+}
+
+/// A dependency or source file whose license breaks the license policy.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct LicenseViolation {
+    /// `package@version (lockfile)` for dependencies, or a file path for
+    /// source headers.
+    pub subject: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub license: Option<String>,
+    pub reason: String,
+}
+
+/// Response payload for `Op::PruneSessions`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SessionsPrunedEvent {
+    /// When true, nothing was deleted and `pruned` lists what would be.
+    pub dry_run: bool,
+    pub pruned: Vec<PrunedSession>,
+    /// Total size of the pruned rollouts.
+    pub freed_bytes: u64,
+    /// Total size of the rollouts that remain.
+    pub kept_bytes: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct PrunedSession {
+    pub thread_id: ThreadId,
+    pub path: PathBuf,
+    pub bytes: u64,
+    /// Which retention limit selected the session.
+    pub reason: String,
+}
+
+/// Response payload for `Op::ExportSession`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SessionExportedEvent {
+    pub thread_id: ThreadId,
+    /// Where the archive was written.
+    pub path: PathBuf,
+    pub attachments: Vec<ArchivedAttachment>,
+}
+
+/// Response payload for `Op::ImportSession`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SessionImportedEvent {
+    pub thread_id: ThreadId,
+    /// The imported rollout; resume the session from this path.
+    pub rollout_path: PathBuf,
+    /// Working directory of the session on the machine it was exported from.
+    pub source_cwd: PathBuf,
+    /// Attachments the session had when it was exported. Their content is
+    /// not archived; attach them again to make them readable.
+    pub attachments: Vec<ArchivedAttachment>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ApprovalResolvedEvent {
+    /// Id of the approval request that was answered.
+    pub id: String,
+    pub decision: ReviewDecision,
+    /// Id of the attached client that answered, or `None` for the thread's
+    /// primary client.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub client: Option<String>,
+}
+
+/// Response payload for `Op::InlineEdit`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct InlineEditResultEvent {
+    pub path: PathBuf,
+    pub range: LineRange,
+    /// The text of the range the edit was made against.
+    pub original: String,
+    /// Text to put in place of `original`.
+    pub replacement: String,
+}
+
+/// Response payload for `Op::Complete`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct CompletionResultEvent {
+    pub path: PathBuf,
+    pub cursor: TextPosition,
+    /// Text to insert at the cursor; empty when the model has nothing to add.
+    pub completion: String,
+}
+
+/// Response payload for `Op::SetEnabledTools`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct EnabledToolsUpdatedEvent {
+    /// Every tool and MCP server now turned off, sorted.
+    pub disabled: Vec<String>,
+}
+
+/// A command approved while learning approved commands.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct SuggestedCommand {
+    /// The command as an execpolicy prefix rule would match it.
+    pub command: Vec<String>,
+    /// How many times it was approved.
+    pub approvals: u32,
+    /// RFC 3339 timestamp of the latest approval.
+    pub last_approved_at: String,
+}
+
+/// Response payload for `Op::ReviewSuggestedAllowlist`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SuggestedAllowlistEvent {
+    pub project_root: PathBuf,
+    /// The remaining suggestions, most approved first.
+    pub commands: Vec<SuggestedCommand>,
+}
+
+/// Response payload for `Op::Regenerate`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ResponseCandidatesEvent {
+    /// The text of each candidate response, in the order `Op::SelectCandidate`
+    /// indexes them.
+    pub candidates: Vec<String>,
+}
+
+/// Response payload for `Op::ForecastRateLimits`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct RateLimitForecastEvent {
+    pub windows: Vec<RateLimitWindowForecast>,
+}
+
+/// Which window of a [`RateLimitSnapshot`] a forecast is for.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitWindowKind {
+    Primary,
+    Secondary,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct RateLimitWindowForecast {
+    pub limit_id: String,
+    pub window: RateLimitWindowKind,
+    /// Percentage (0-100) of the window consumed at the latest snapshot.
+    pub used_percent: f64,
+    /// Unix timestamp (seconds since epoch) when the window resets.
+    #[ts(type = "number | null")]
+    pub resets_at: Option<i64>,
+    /// Percentage of the window consumed per hour since it last reset, or
+    /// `None` until two snapshots of it have been seen.
+    pub burn_percent_per_hour: Option<f64>,
+    /// Unix timestamp when the window runs out at the current burn rate, or
+    /// `None` if it resets first.
+    #[ts(type = "number | null")]
+    pub exhausted_at: Option<i64>,
+    /// The highest burn rate, in percent per hour, that lasts until the
+    /// window resets.
+    pub sustainable_percent_per_hour: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct PlanOrCreditsChangedEvent {
+    pub previous_plan_type: Option<crate::account::PlanType>,
+    pub plan_type: Option<crate::account::PlanType>,
+    pub previous_credits: Option<CreditsSnapshot>,
+    pub credits: Option<CreditsSnapshot>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct OfflineStatusEvent {
+    pub offline: bool,
+    /// Turns waiting to run once the session is back online.
+    pub queued_turns: u32,
+}
+
+/// Response payload for `Op::QueryUsage`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct UsageRollupsEvent {
+    /// Oldest day first, then by project and model.
+    pub rollups: Vec<UsageRollup>,
+}
+
+/// Usage of one model in one project on one day.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct UsageRollup {
+    /// Local date, `YYYY-MM-DD`.
+    pub date: String,
+    pub project_root: PathBuf,
+    pub model: String,
+    /// Number of model requests.
+    #[ts(type = "number")]
+    pub requests: u64,
+    pub token_usage: TokenUsage,
+    /// Estimated cost in US dollars, or `None` if `model_pricing` has no
+    /// price for the model.
+    pub cost_usd: Option<f64>,
+}
+
+/// Response payload for `Op::GetTurnTimings`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct TurnTimingsEvent {
+    /// The session's most recent finished or aborted turns, oldest first.
+    pub turns: Vec<TurnTiming>,
+}
+
+/// Where the time of one turn went. Durations are in milliseconds.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct TurnTiming {
+    pub turn_id: String,
+    /// From accepting the submission to the turn starting, including waiting
+    /// for the previous turn to stop.
+    #[ts(type = "number")]
+    pub queue_wait_ms: u64,
+    /// Building the prompts: history, tools, and instructions.
+    #[ts(type = "number")]
+    pub prompt_assembly_ms: u64,
+    /// One entry per model request, retries included, in order.
+    pub model_requests: Vec<ModelRequestTiming>,
+    /// Tool calls by tool, slowest first. Parallel calls overlap, so these
+    /// can add up to more than the turn took.
+    pub tools: Vec<ToolTiming>,
+    /// Recording items into the history and the rollout.
+    #[ts(type = "number")]
+    pub serialization_ms: u64,
+    #[ts(type = "number")]
+    pub total_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ModelRequestTiming {
+    /// From sending the request to the first output from the model, or
+    /// `None` if no output arrived.
+    #[ts(type = "number | null")]
+    pub time_to_first_token_ms: Option<u64>,
+    /// From the first output to the end of the response.
+    #[ts(type = "number")]
+    pub streaming_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ToolTiming {
+    pub tool: String,
+    pub calls: u32,
+    /// Wall time of the calls summed, including waiting for exclusive access.
+    #[ts(type = "number")]
+    pub wall_time_ms: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ToolLockHeldEvent {
+    /// The call holding the lock.
+    pub call_id: String,
+    pub tool: String,
+    pub held_ms: u64,
+    /// Calls waiting for the lock.
+    pub waiting_call_ids: Vec<String>,
+    /// Whether the watchdog cancelled the call.
+    pub cancelled: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ToolProgressEvent {
+    /// The call reporting progress.
+    pub call_id: String,
+    /// Steps done so far.
+    pub completed: u64,
+    /// Steps in all, when known.
+    pub total: Option<u64>,
+    /// What the call is doing now, e.g. the file being patched.
+    pub message: Option<String>,
+}
+
+/// Response payload for `Op::GetActiveToolCalls`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ActiveToolCallsEvent {
+    /// The executing calls, the longest-running first.
+    pub calls: Vec<ActiveToolCall>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ActiveToolCall {
+    pub call_id: String,
+    pub tool: String,
+    pub elapsed_ms: u64,
+    pub status: ActiveToolCallStatus,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ActiveToolCallStatus {
+    Running,
+    /// Waiting for calls that cannot run in parallel with it to finish.
+    WaitingForParallelLock,
+    /// Waiting for an identical call already in flight, whose result it will
+    /// reuse.
+    WaitingForDuplicate,
+    /// Waiting for the user to approve it.
+    WaitingForApproval,
+}
+
+/// Response payload for `Op::ListSessionTools`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SessionToolsEvent {
+    pub tools: Vec<SessionTool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct SessionTool {
+    pub name: String,
+    pub description: String,
+    /// JSON schema of the arguments of a function tool. `None` for tools
+    /// that take raw input, such as the freeform `apply_patch`.
+    pub input_schema: Option<Value>,
+    /// Whether calls may stop for the user's approval under the session's
+    /// approval policy.
+    pub requires_approval: bool,
+}
+
+/// Response payload for `Op::InvokeTool`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ToolInvocationResultEvent {
+    pub call_id: String,
+    /// The output as the model would have received it.
+    pub output: ResponseInputItem,
+}
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ToolSelectionEvent {
+    /// IDs of the connectors explicitly selected, sorted.
+    #[serde(default)]
+    pub connectors: Vec<String>,
+    /// Fully-qualified names of the MCP tools selected through tool search,
+    /// in the order they were selected.
+    #[serde(default)]
+    pub mcp_tools: Vec<String>,
+}
+
+/// Response payload for `Op::GetContextBreakdown`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ContextBreakdownEvent {
+    /// The items of the context, oldest first.
+    pub items: Vec<ContextItemBreakdown>,
+    /// The estimated tokens of the items summed.
+    #[ts(type = "number")]
+    pub estimated_tokens: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ContextItemBreakdown {
+    /// Item type as sent to the model, e.g. `message` or
+    /// `function_call_output`.
+    pub kind: String,
+    /// Role of `message` items.
+    pub role: Option<String>,
+    #[ts(type = "number")]
+    pub estimated_tokens: i64,
+    pub metadata: HistoryItemMetadata,
+}
+
+/// When and where an item of the model's context came from.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct HistoryItemMetadata {
+    /// When the item was recorded, in RFC 3339. Items restored from a
+    /// rollout carry the time they were restored.
+    pub recorded_at: String,
+    /// Tool behind tool calls and their outputs, as the model names it.
+    pub tool: Option<String>,
+    /// MCP server of that tool.
+    pub connector: Option<String>,
+    /// Model of the turn that recorded the item.
+    pub model: Option<String>,
+}
+
+/// Payload of `EventMsg::HistoryMetadataRecorded`, which is only written to
+/// the rollout.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct HistoryMetadataRecordedEvent {
+    /// One entry per recorded item, oldest first.
+    pub metadata: Vec<HistoryItemMetadata>,
+}
+
+/// Response payload for `Op::SelectCandidate`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ResponseCandidateSelectedEvent {
+    pub index: u32,
+    /// The text of the response now in the thread history.
+    pub message: String,
+}
+
+/// Response payload for `Op::PromoteObserver`.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq)]
+pub struct ObserverPromotedEvent {
+    /// Id of the promoted client.
+    pub client: String,
+}
+
+/// An attachment listed in a session archive's manifest.
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS, PartialEq, Eq)]
+pub struct ArchivedAttachment {
+    pub id: String,
+    pub content_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub name: Option<String>,
+    pub bytes: u64,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, TS)]
 #[serde(rename_all = "snake_case")]
 #[ts(rename_all = "snake_case")]
@@ -2651,6 +3820,9 @@ pub struct SkillToolDependency {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     #[ts(optional)]
     pub url: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub version: Option<String>,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize, JsonSchema, TS)]
@@ -3004,6 +4176,39 @@ mod tests {
     use serde_json::json;
     use tempfile::NamedTempFile;
 
+    #[test]
+    fn patch_approval_with_deletions_is_annotated_destructive() -> Result<()> {
+        let patch_approval = |change: FileChange| {
+            EventMsg::ApplyPatchApprovalRequest(ApplyPatchApprovalRequestEvent {
+                call_id: "call-1".to_string(),
+                turn_id: "turn-1".to_string(),
+                changes: HashMap::from([(PathBuf::from("notes.txt"), change)]),
+                reason: None,
+                grant_root: None,
+            })
+        };
+
+        let deletion = patch_approval(FileChange::Delete {
+            content: "old".to_string(),
+        });
+        assert_eq!(
+            serde_json::to_value(deletion.annotations())?,
+            json!(["destructive-action-pending", "needs-attention"])
+        );
+        let addition = patch_approval(FileChange::Add {
+            content: "new".to_string(),
+        });
+        assert_eq!(
+            addition.annotations(),
+            vec![EventAnnotation::NeedsAttention]
+        );
+        assert_eq!(
+            EventMsg::SkillsUpdateAvailable.annotations(),
+            Vec::<EventAnnotation>::new()
+        );
+        Ok(())
+    }
+
     #[test]
     fn external_sandbox_reports_full_access_flags() {
         let restricted = SandboxPolicy::ExternalSandbox {
@@ -3328,6 +4533,7 @@ mod tests {
             developer_instructions: None,
             final_output_json_schema: None,
             truncation_policy: None,
+            sampling: None,
         };
 
         let value = serde_json::to_value(item)?;
@@ -3,6 +3,7 @@ use std::time::Duration;
 
 use anyhow::Error;
 use anyhow::Result;
+use codex_client::apply_http_network_config;
 use codex_protocol::protocol::McpAuthStatus;
 use reqwest::Client;
 use reqwest::StatusCode;
@@ -64,8 +65,11 @@ async fn supports_oauth_login_with_headers(url: &str, default_headers: &HeaderMa
     let base_url = Url::parse(url)?;
 
     // Use no_proxy to avoid a bug in the system-configuration crate that
-    // can result in a panic. See #8912.
-    let builder = Client::builder().timeout(DISCOVERY_TIMEOUT).no_proxy();
+    // can result in a panic. See #8912. The `[http]` proxy still applies.
+    let builder = apply_http_network_config(
+        Client::builder().timeout(DISCOVERY_TIMEOUT).no_proxy(),
+        Some(url),
+    );
     let client = apply_default_headers(builder, default_headers).build()?;
 
     let mut last_error: Option<Error> = None;
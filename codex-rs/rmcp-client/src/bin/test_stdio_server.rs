@@ -1,6 +1,8 @@
 use std::borrow::Cow;
 use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 
 use rmcp::ErrorData as McpError;
 use rmcp::ServiceExt;
@@ -22,6 +24,7 @@ use rmcp::model::ResourceTemplate;
 use rmcp::model::ServerCapabilities;
 use rmcp::model::ServerInfo;
 use rmcp::model::Tool;
+use rmcp::model::ToolAnnotations;
 use serde::Deserialize;
 use serde_json::json;
 use tokio::task;
@@ -31,6 +34,7 @@ struct TestToolServer {
     tools: Arc<Vec<Tool>>,
     resources: Arc<Vec<Resource>>,
     resource_templates: Arc<Vec<ResourceTemplate>>,
+    stalled: Arc<AtomicBool>,
 }
 
 const MEMO_URI: &str = "memo://codex/example-note";
@@ -47,6 +51,7 @@ impl TestToolServer {
             Self::echo_tool(),
             Self::image_tool(),
             Self::image_scenario_tool(),
+            Self::stall_once_tool(),
         ];
         let resources = vec![Self::memo_resource()];
         let resource_templates = vec![Self::memo_template()];
@@ -54,6 +59,7 @@ impl TestToolServer {
             tools: Arc::new(tools),
             resources: Arc::new(resources),
             resource_templates: Arc::new(resource_templates),
+            stalled: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -148,6 +154,26 @@ impl TestToolServer {
         )
     }
 
+    /// Read-only tool whose first call never answers, so tests can exercise
+    /// retrying a timed-out call.
+    fn stall_once_tool() -> Tool {
+        #[expect(clippy::expect_used)]
+        let schema: JsonObject = serde_json::from_value(json!({
+            "type": "object",
+            "properties": {},
+            "additionalProperties": false
+        }))
+        .expect("stall_once tool schema should deserialize");
+
+        let mut tool = Tool::new(
+            Cow::Borrowed("stall_once"),
+            Cow::Borrowed("Hang on the first call and answer every later one."),
+            Arc::new(schema),
+        );
+        tool.annotations = Some(ToolAnnotations::new().read_only(true));
+        tool
+    }
+
     fn memo_resource() -> Resource {
         let raw = RawResource {
             uri: MEMO_URI.to_string(),
@@ -348,6 +374,17 @@ impl ServerHandler for TestToolServer {
                 let args = Self::parse_call_args::<ImageScenarioArgs>(&request, "image_scenario")?;
                 Self::image_scenario_result(args)
             }
+            "stall_once" => {
+                if !self.stalled.swap(true, Ordering::SeqCst) {
+                    std::future::pending::<()>().await;
+                }
+                Ok(CallToolResult {
+                    content: Vec::new(),
+                    structured_content: Some(json!({ "stalled": true })),
+                    is_error: Some(false),
+                    meta: None,
+                })
+            }
             other => Err(McpError::invalid_params(
                 format!("unknown tool: {other}"),
                 None,
@@ -24,4 +24,7 @@ pub use rmcp_client::ElicitationResponse;
 pub use rmcp_client::ListToolsWithConnectorIdResult;
 pub use rmcp_client::RmcpClient;
 pub use rmcp_client::SendElicitation;
+pub use rmcp_client::StdioServerLimits;
+pub use rmcp_client::ToolCallNotification;
 pub use rmcp_client::ToolWithConnectorId;
+pub use utils::TransportError;
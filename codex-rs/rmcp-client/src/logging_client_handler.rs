@@ -1,4 +1,10 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::AtomicU64;
+use std::sync::atomic::Ordering;
 
 use rmcp::ClientHandler;
 use rmcp::RoleClient;
@@ -6,32 +12,130 @@ use rmcp::model::CancelledNotificationParam;
 use rmcp::model::ClientInfo;
 use rmcp::model::CreateElicitationRequestParams;
 use rmcp::model::CreateElicitationResult;
+use rmcp::model::ListRootsResult;
 use rmcp::model::LoggingLevel;
 use rmcp::model::LoggingMessageNotificationParam;
+use rmcp::model::NumberOrString;
 use rmcp::model::ProgressNotificationParam;
+use rmcp::model::ProgressToken;
 use rmcp::model::ResourceUpdatedNotificationParam;
+use rmcp::model::Root;
 use rmcp::service::NotificationContext;
 use rmcp::service::RequestContext;
+use tokio::sync::mpsc::UnboundedSender;
 use tracing::debug;
 use tracing::error;
 use tracing::info;
 use tracing::warn;
 
 use crate::rmcp_client::SendElicitation;
+use crate::rmcp_client::ToolCallNotification;
 
 #[derive(Clone)]
 pub(crate) struct LoggingClientHandler {
     client_info: ClientInfo,
     send_elicitation: Arc<SendElicitation>,
+    tool_calls: Arc<ToolCallRoutes>,
+    roots: SharedRoots,
+    tool_list_changed: Arc<AtomicBool>,
 }
 
+/// The workspace roots the client lists to the server.
+pub(crate) type SharedRoots = Arc<Mutex<Vec<Root>>>;
+
 impl LoggingClientHandler {
-    pub(crate) fn new(client_info: ClientInfo, send_elicitation: SendElicitation) -> Self {
+    pub(crate) fn new(
+        client_info: ClientInfo,
+        send_elicitation: SendElicitation,
+        tool_calls: Arc<ToolCallRoutes>,
+        roots: SharedRoots,
+        tool_list_changed: Arc<AtomicBool>,
+    ) -> Self {
         Self {
             client_info,
             send_elicitation: Arc::new(send_elicitation),
+            tool_calls,
+            roots,
+            tool_list_changed,
+        }
+    }
+}
+
+/// Tool calls in flight, by the progress token sent with each, so the
+/// notifications the server sends about them reach their caller.
+#[derive(Debug, Default)]
+pub(crate) struct ToolCallRoutes {
+    next_token: AtomicU64,
+    calls: Mutex<HashMap<String, UnboundedSender<ToolCallNotification>>>,
+}
+
+impl ToolCallRoutes {
+    /// Routes the notifications for a new call to `notifications` until the
+    /// returned route drops.
+    pub(crate) fn register(
+        self: &Arc<Self>,
+        notifications: UnboundedSender<ToolCallNotification>,
+    ) -> ToolCallRoute {
+        let token = format!(
+            "codex-tool-call-{}",
+            self.next_token.fetch_add(1, Ordering::Relaxed)
+        );
+        self.calls().insert(token.clone(), notifications);
+        ToolCallRoute {
+            routes: Arc::clone(self),
+            token,
         }
     }
+
+    fn send_progress(&self, params: ProgressNotificationParam) {
+        let NumberOrString::String(token) = &params.progress_token.0 else {
+            return;
+        };
+        if let Some(notifications) = self.calls().get(token.as_ref()) {
+            let _ = notifications.send(ToolCallNotification::Progress {
+                progress: params.progress,
+                total: params.total,
+                message: params.message,
+            });
+        }
+    }
+
+    fn send_log(&self, params: LoggingMessageNotificationParam) {
+        let calls = self.calls();
+        if calls.len() == 1
+            && let Some(notifications) = calls.values().next()
+        {
+            let _ = notifications.send(ToolCallNotification::Log {
+                level: params.level,
+                logger: params.logger,
+                data: params.data,
+            });
+        }
+    }
+
+    fn calls(&self) -> MutexGuard<'_, HashMap<String, UnboundedSender<ToolCallNotification>>> {
+        self.calls
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+/// Stops routing notifications to its call when dropped.
+pub(crate) struct ToolCallRoute {
+    routes: Arc<ToolCallRoutes>,
+    token: String,
+}
+
+impl ToolCallRoute {
+    pub(crate) fn progress_token(&self) -> ProgressToken {
+        ProgressToken(NumberOrString::String(self.token.as_str().into()))
+    }
+}
+
+impl Drop for ToolCallRoute {
+    fn drop(&mut self) {
+        self.routes.calls().remove(&self.token);
+    }
 }
 
 impl ClientHandler for LoggingClientHandler {
@@ -45,6 +149,18 @@ impl ClientHandler for LoggingClientHandler {
             .map_err(|err| rmcp::ErrorData::internal_error(err.to_string(), None))
     }
 
+    async fn list_roots(
+        &self,
+        _context: RequestContext<RoleClient>,
+    ) -> Result<ListRootsResult, rmcp::ErrorData> {
+        let roots = self
+            .roots
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        Ok(ListRootsResult { roots })
+    }
+
     async fn on_cancelled(
         &self,
         params: CancelledNotificationParam,
@@ -65,6 +181,7 @@ impl ClientHandler for LoggingClientHandler {
             "MCP server progress notification (token: {:?}, progress: {}, total: {:?}, message: {:?})",
             params.progress_token, params.progress, params.total, params.message
         );
+        self.tool_calls.send_progress(params);
     }
 
     async fn on_resource_updated(
@@ -81,6 +198,7 @@ impl ClientHandler for LoggingClientHandler {
 
     async fn on_tool_list_changed(&self, _context: NotificationContext<RoleClient>) {
         info!("MCP server tool list changed");
+        self.tool_list_changed.store(true, Ordering::Release);
     }
 
     async fn on_prompt_list_changed(&self, _context: NotificationContext<RoleClient>) {
@@ -100,7 +218,7 @@ impl ClientHandler for LoggingClientHandler {
             level,
             logger,
             data,
-        } = params;
+        } = &params;
         let logger = logger.as_deref();
         match level {
             LoggingLevel::Emergency
@@ -131,5 +249,66 @@ impl ClientHandler for LoggingClientHandler {
                 );
             }
         }
+        self.tool_calls.send_log(params);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    fn log(data: &str) -> LoggingMessageNotificationParam {
+        LoggingMessageNotificationParam {
+            level: LoggingLevel::Info,
+            logger: None,
+            data: json!(data),
+        }
+    }
+
+    #[test]
+    fn routes_notifications_to_their_call() {
+        let routes = Arc::new(ToolCallRoutes::default());
+        let (build_tx, mut build_rx) = unbounded_channel();
+        let build = routes.register(build_tx);
+
+        routes.send_progress(ProgressNotificationParam {
+            progress_token: build.progress_token(),
+            progress: 1.0,
+            total: Some(4.0),
+            message: Some("compiling".to_string()),
+        });
+        routes.send_log(log("building"));
+
+        let (deploy_tx, mut deploy_rx) = unbounded_channel();
+        let deploy = routes.register(deploy_tx);
+        routes.send_log(log("not attributable"));
+        drop(build);
+        routes.send_log(log("deploying"));
+        drop(deploy);
+        routes.send_log(log("idle"));
+
+        let drain = |rx: &mut tokio::sync::mpsc::UnboundedReceiver<ToolCallNotification>| {
+            std::iter::from_fn(|| rx.try_recv().ok()).collect::<Vec<_>>()
+        };
+        let info = |data: &str| ToolCallNotification::Log {
+            level: LoggingLevel::Info,
+            logger: None,
+            data: json!(data),
+        };
+        assert_eq!(
+            drain(&mut build_rx),
+            vec![
+                ToolCallNotification::Progress {
+                    progress: 1.0,
+                    total: Some(4.0),
+                    message: Some("compiling".to_string()),
+                },
+                info("building"),
+            ]
+        );
+        assert_eq!(drain(&mut deploy_rx), vec![info("deploying")]);
     }
 }
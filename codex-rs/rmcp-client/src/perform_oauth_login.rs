@@ -7,6 +7,7 @@ use anyhow::Context;
 use anyhow::Result;
 use anyhow::anyhow;
 use anyhow::bail;
+use codex_client::apply_http_network_config;
 use reqwest::ClientBuilder;
 use reqwest::Url;
 use rmcp::transport::auth::OAuthState;
@@ -333,7 +334,11 @@ impl OauthLoginFlow {
             env_http_headers,
         } = headers;
         let default_headers = build_default_headers(http_headers, env_http_headers)?;
-        let http_client = apply_default_headers(ClientBuilder::new(), &default_headers).build()?;
+        let http_client = apply_default_headers(
+            apply_http_network_config(ClientBuilder::new(), Some(server_url)),
+            &default_headers,
+        )
+        .build()?;
 
         let mut oauth_state = OAuthState::new(server_url, Some(http_client)).await?;
         let scope_refs: Vec<&str> = scopes.iter().map(String::as_str).collect();
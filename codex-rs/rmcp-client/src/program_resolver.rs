@@ -165,7 +165,7 @@ mod tests {
             #[cfg(windows)]
             extra_env.insert("PATHEXT".to_string(), Self::ensure_cmd_extension());
 
-            let mcp_env = create_env_for_mcp_server(Some(extra_env), &[]);
+            let mcp_env = create_env_for_mcp_server(Some(extra_env), &[], true);
 
             Ok(Self {
                 _temp_dir: temp_dir,
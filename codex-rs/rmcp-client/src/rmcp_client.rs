@@ -1,13 +1,18 @@
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::ffi::OsString;
 use std::io;
 use std::path::PathBuf;
 use std::process::Stdio;
 use std::sync::Arc;
+use std::sync::Mutex as StdMutex;
+use std::sync::atomic::AtomicBool;
+use std::sync::atomic::Ordering;
 use std::time::Duration;
 
 use anyhow::Result;
 use anyhow::anyhow;
+use codex_client::apply_http_network_config;
 use futures::FutureExt;
 use futures::future::BoxFuture;
 use oauth2::TokenResponse;
@@ -27,10 +32,13 @@ use rmcp::model::InitializeResult;
 use rmcp::model::ListResourceTemplatesResult;
 use rmcp::model::ListResourcesResult;
 use rmcp::model::ListToolsResult;
+use rmcp::model::LoggingLevel;
+use rmcp::model::Meta;
 use rmcp::model::PaginatedRequestParams;
 use rmcp::model::ReadResourceRequestParams;
 use rmcp::model::ReadResourceResult;
 use rmcp::model::RequestId;
+use rmcp::model::Root;
 use rmcp::model::ServerResult;
 use rmcp::model::Tool;
 use rmcp::service::RoleClient;
@@ -41,18 +49,22 @@ use rmcp::transport::auth::AuthClient;
 use rmcp::transport::auth::AuthError;
 use rmcp::transport::auth::OAuthState;
 use rmcp::transport::child_process::TokioChildProcess;
+use rmcp::transport::common::client_side_sse::ExponentialBackoff;
 use rmcp::transport::streamable_http_client::StreamableHttpClientTransportConfig;
 use serde_json::Value;
 use tokio::io::AsyncBufReadExt;
 use tokio::io::BufReader;
 use tokio::process::Command;
 use tokio::sync::Mutex;
+use tokio::sync::mpsc::UnboundedSender;
 use tokio::time;
 use tracing::info;
 use tracing::warn;
 
 use crate::load_oauth_tokens;
 use crate::logging_client_handler::LoggingClientHandler;
+use crate::logging_client_handler::SharedRoots;
+use crate::logging_client_handler::ToolCallRoutes;
 use crate::oauth::OAuthCredentialsStoreMode;
 use crate::oauth::OAuthPersistor;
 use crate::oauth::StoredOAuthTokens;
@@ -76,6 +88,16 @@ enum PendingTransport {
     },
 }
 
+/// How to reach a streamable HTTP server, kept to open a new session when
+/// the current one ends.
+struct StreamableHttpTarget {
+    server_name: String,
+    url: String,
+    bearer_token: Option<String>,
+    default_headers: HeaderMap,
+    store_mode: OAuthCredentialsStoreMode,
+}
+
 enum ClientState {
     Connecting {
         transport: Option<PendingTransport>,
@@ -84,11 +106,16 @@ enum ClientState {
         _process_group_guard: Option<ProcessGroupGuard>,
         service: Arc<RunningService<RoleClient, LoggingClientHandler>>,
         oauth: Option<OAuthPersistor>,
+        handler: Box<LoggingClientHandler>,
+        handshake_timeout: Option<Duration>,
     },
 }
 
 #[cfg(unix)]
 const PROCESS_GROUP_TERM_GRACE_PERIOD: Duration = Duration::from_secs(2);
+/// How many times a dropped event stream is resumed before the session is
+/// given up.
+const STREAM_RESUME_ATTEMPTS: usize = 5;
 
 #[cfg(unix)]
 struct ProcessGroupGuard {
@@ -154,10 +181,41 @@ pub type SendElicitation = Box<
     dyn Fn(RequestId, Elicitation) -> BoxFuture<'static, Result<ElicitationResponse>> + Send + Sync,
 >;
 
+/// A notification a server sent about a tool call while it ran.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ToolCallNotification {
+    /// `notifications/progress` for the call.
+    Progress {
+        progress: f64,
+        total: Option<f64>,
+        message: Option<String>,
+    },
+    /// `notifications/message` sent while the call was the server's only
+    /// tool call in flight. Log messages carry no request id, so they are
+    /// not forwarded while calls run concurrently.
+    Log {
+        level: LoggingLevel,
+        logger: Option<String>,
+        data: Value,
+    },
+}
+
+/// Resource limits on a stdio server process, applied where the platform
+/// supports them (`setrlimit` on Unix).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StdioServerLimits {
+    /// Address space limit, in bytes.
+    pub memory_bytes: Option<u64>,
+    /// CPU time limit, in seconds.
+    pub cpu_secs: Option<u64>,
+}
+
 pub struct ToolWithConnectorId {
     pub tool: Tool,
     pub connector_id: Option<String>,
     pub connector_name: Option<String>,
+    /// Version of the connector's actions, when the server reports one.
+    pub connector_version: Option<String>,
 }
 
 pub struct ListToolsWithConnectorIdResult {
@@ -169,20 +227,38 @@ pub struct ListToolsWithConnectorIdResult {
 /// https://github.com/modelcontextprotocol/rust-sdk
 pub struct RmcpClient {
     state: Mutex<ClientState>,
+    tool_calls: Arc<ToolCallRoutes>,
+    roots: SharedRoots,
+    tool_list_changed: Arc<AtomicBool>,
+    process_group_id: Option<u32>,
+    /// Set for streamable HTTP servers, to reconnect once a session ends.
+    http_target: Option<StreamableHttpTarget>,
+    /// Held while a new session is started, so requests racing to reconnect
+    /// start one session between them without holding `state`.
+    reconnecting: Mutex<()>,
+    /// Custom requests sent with [`Self::send_session_request`], by method,
+    /// re-sent to every new session.
+    session_requests: StdMutex<BTreeMap<String, Option<Value>>>,
 }
 
 impl RmcpClient {
+    /// Launches a stdio MCP server. Its environment holds `env` on top of the
+    /// variables named in `env_vars`, copied from this process; with
+    /// `inherit_default_env`, basic variables such as `PATH` and `HOME` are
+    /// copied as well.
     pub async fn new_stdio_client(
         program: OsString,
         args: Vec<OsString>,
         env: Option<HashMap<String, String>>,
         env_vars: &[String],
+        inherit_default_env: bool,
         cwd: Option<PathBuf>,
+        limits: StdioServerLimits,
     ) -> io::Result<Self> {
         let program_name = program.to_string_lossy().into_owned();
 
         // Build environment for program resolution and subprocess
-        let envs = create_env_for_mcp_server(env, env_vars);
+        let envs = create_env_for_mcp_server(env, env_vars, inherit_default_env);
 
         // Resolve program to executable path (platform-specific)
         let resolved_program = program_resolver::resolve(program, &envs)?;
@@ -196,7 +272,22 @@ impl RmcpClient {
             .envs(envs)
             .args(&args);
         #[cfg(unix)]
-        command.process_group(0);
+        {
+            command.process_group(0);
+            // No parent death signal: it would fire when the runtime thread
+            // spawning the server exits, not when this process does. A server
+            // left behind by a crash is cleaned up by the next session.
+            unsafe {
+                command.pre_exec(move || {
+                    codex_utils_pty::process_group::set_resource_limits(
+                        limits.memory_bytes,
+                        limits.cpu_secs,
+                    )
+                });
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = limits;
         if let Some(cwd) = cwd {
             command.current_dir(cwd);
         }
@@ -204,7 +295,10 @@ impl RmcpClient {
         let (transport, stderr) = TokioChildProcess::builder(command)
             .stderr(Stdio::piped())
             .spawn()?;
-        let process_group_guard = transport.id().map(ProcessGroupGuard::new);
+        let pid = transport.id();
+        let process_group_guard = pid.map(ProcessGroupGuard::new);
+        // Only on Unix does the server lead a process group of its own.
+        let process_group_id = if cfg!(unix) { pid } else { None };
 
         if let Some(stderr) = stderr {
             tokio::spawn(async move {
@@ -231,6 +325,13 @@ impl RmcpClient {
                     process_group_guard,
                 }),
             }),
+            tool_calls: Arc::default(),
+            roots: SharedRoots::default(),
+            tool_list_changed: Arc::default(),
+            process_group_id,
+            http_target: None,
+            reconnecting: Mutex::new(()),
+            session_requests: StdMutex::default(),
         })
     }
 
@@ -243,80 +344,33 @@ impl RmcpClient {
         env_http_headers: Option<HashMap<String, String>>,
         store_mode: OAuthCredentialsStoreMode,
     ) -> Result<Self> {
-        let default_headers = build_default_headers(http_headers, env_http_headers)?;
-
-        let initial_oauth_tokens =
-            if bearer_token.is_none() && !default_headers.contains_key(AUTHORIZATION) {
-                match load_oauth_tokens(server_name, url, store_mode) {
-                    Ok(tokens) => tokens,
-                    Err(err) => {
-                        warn!("failed to read tokens for server `{server_name}`: {err}");
-                        None
-                    }
-                }
-            } else {
-                None
-            };
-
-        let transport = if let Some(initial_tokens) = initial_oauth_tokens.clone() {
-            match create_oauth_transport_and_runtime(
-                server_name,
-                url,
-                initial_tokens.clone(),
-                store_mode,
-                default_headers.clone(),
-            )
-            .await
-            {
-                Ok((transport, oauth_persistor)) => PendingTransport::StreamableHttpWithOAuth {
-                    transport,
-                    oauth_persistor,
-                },
-                Err(err)
-                    if err.downcast_ref::<AuthError>().is_some_and(|auth_err| {
-                        matches!(auth_err, AuthError::NoAuthorizationSupport)
-                    }) =>
-                {
-                    let access_token = initial_tokens
-                        .token_response
-                        .0
-                        .access_token()
-                        .secret()
-                        .to_string();
-                    warn!(
-                        "OAuth metadata discovery is unavailable for MCP server `{server_name}`; falling back to stored bearer token authentication"
-                    );
-                    let http_config =
-                        StreamableHttpClientTransportConfig::with_uri(url.to_string())
-                            .auth_header(access_token);
-                    let http_client =
-                        apply_default_headers(reqwest::Client::builder(), &default_headers)
-                            .build()?;
-                    let transport =
-                        StreamableHttpClientTransport::with_client(http_client, http_config);
-                    PendingTransport::StreamableHttp { transport }
-                }
-                Err(err) => return Err(err),
-            }
-        } else {
-            let mut http_config = StreamableHttpClientTransportConfig::with_uri(url.to_string());
-            if let Some(bearer_token) = bearer_token.clone() {
-                http_config = http_config.auth_header(bearer_token);
-            }
-
-            let http_client =
-                apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
-
-            let transport = StreamableHttpClientTransport::with_client(http_client, http_config);
-            PendingTransport::StreamableHttp { transport }
+        let target = StreamableHttpTarget {
+            server_name: server_name.to_string(),
+            url: url.to_string(),
+            bearer_token,
+            default_headers: build_default_headers(http_headers, env_http_headers)?,
+            store_mode,
         };
+        let transport = connect_streamable_http(&target).await?;
         Ok(Self {
             state: Mutex::new(ClientState::Connecting {
                 transport: Some(transport),
             }),
+            tool_calls: Arc::default(),
+            roots: SharedRoots::default(),
+            tool_list_changed: Arc::default(),
+            process_group_id: None,
+            http_target: Some(target),
+            reconnecting: Mutex::new(()),
+            session_requests: StdMutex::default(),
         })
     }
 
+    /// Process group the server process leads, for stdio servers on Unix.
+    pub fn process_group_id(&self) -> Option<u32> {
+        self.process_group_id
+    }
+
     /// Perform the initialization handshake with the MCP server.
     /// https://modelcontextprotocol.io/specification/2025-06-18/basic/lifecycle#initialization
     pub async fn initialize(
@@ -325,48 +379,25 @@ impl RmcpClient {
         timeout: Option<Duration>,
         send_elicitation: SendElicitation,
     ) -> Result<InitializeResult> {
-        let client_handler = LoggingClientHandler::new(params.clone(), send_elicitation);
-
-        let (transport, oauth_persistor, process_group_guard) = {
+        let client_handler = LoggingClientHandler::new(
+            params.clone(),
+            send_elicitation,
+            Arc::clone(&self.tool_calls),
+            Arc::clone(&self.roots),
+            Arc::clone(&self.tool_list_changed),
+        );
+
+        let transport = {
             let mut guard = self.state.lock().await;
             match &mut *guard {
-                ClientState::Connecting { transport } => match transport.take() {
-                    Some(PendingTransport::ChildProcess {
-                        transport,
-                        process_group_guard,
-                    }) => (
-                        service::serve_client(client_handler.clone(), transport).boxed(),
-                        None,
-                        process_group_guard,
-                    ),
-                    Some(PendingTransport::StreamableHttp { transport }) => (
-                        service::serve_client(client_handler.clone(), transport).boxed(),
-                        None,
-                        None,
-                    ),
-                    Some(PendingTransport::StreamableHttpWithOAuth {
-                        transport,
-                        oauth_persistor,
-                    }) => (
-                        service::serve_client(client_handler.clone(), transport).boxed(),
-                        Some(oauth_persistor),
-                        None,
-                    ),
-                    None => return Err(anyhow!("client already initializing")),
-                },
+                ClientState::Connecting { transport } => transport
+                    .take()
+                    .ok_or_else(|| anyhow!("client already initializing"))?,
                 ClientState::Ready { .. } => return Err(anyhow!("client already initialized")),
             }
         };
-
-        let service = match timeout {
-            Some(duration) => time::timeout(duration, transport)
-                .await
-                .map_err(|_| anyhow!("timed out handshaking with MCP server after {duration:?}"))?
-                .map_err(|err| anyhow!("handshaking with MCP server failed: {err}"))?,
-            None => transport
-                .await
-                .map_err(|err| anyhow!("handshaking with MCP server failed: {err}"))?,
-        };
+        let (service, oauth_persistor, process_group_guard) =
+            serve(client_handler.clone(), transport, timeout).await?;
 
         let initialize_result_rmcp = service
             .peer()
@@ -380,6 +411,8 @@ impl RmcpClient {
                 _process_group_guard: process_group_guard,
                 service: Arc::new(service),
                 oauth: oauth_persistor.clone(),
+                handler: Box::new(client_handler),
+                handshake_timeout: timeout,
             };
         }
 
@@ -423,10 +456,12 @@ impl RmcpClient {
                 let connector_id = Self::meta_string(meta, "connector_id");
                 let connector_name = Self::meta_string(meta, "connector_name")
                     .or_else(|| Self::meta_string(meta, "connector_display_name"));
+                let connector_version = Self::meta_string(meta, "connector_version");
                 Ok(ToolWithConnectorId {
                     tool,
                     connector_id,
                     connector_name,
+                    connector_version,
                 })
             })
             .collect::<Result<Vec<_>>>()?;
@@ -486,11 +521,14 @@ impl RmcpClient {
         Ok(result)
     }
 
+    /// Calls tool `name`, sending the progress and log notifications the
+    /// server sends about it to `notifications` until it returns.
     pub async fn call_tool(
         &self,
         name: String,
         arguments: Option<serde_json::Value>,
         timeout: Option<Duration>,
+        notifications: UnboundedSender<ToolCallNotification>,
     ) -> Result<CallToolResult> {
         self.refresh_oauth_if_needed().await;
         let service = self.service().await?;
@@ -503,8 +541,11 @@ impl RmcpClient {
             }
             None => None,
         };
+        let route = self.tool_calls.register(notifications);
+        let mut meta = Meta::new();
+        meta.set_progress_token(route.progress_token());
         let rmcp_params = CallToolRequestParams {
-            meta: None,
+            meta: Some(meta),
             name: name.into(),
             arguments,
             task: None,
@@ -515,6 +556,34 @@ impl RmcpClient {
         Ok(result)
     }
 
+    /// Whether the server sent `notifications/tools/list_changed` since the
+    /// last call.
+    pub fn take_tool_list_changed(&self) -> bool {
+        self.tool_list_changed.swap(false, Ordering::AcqRel)
+    }
+
+    /// Sets the workspace roots the server lists with `roots/list`. Once
+    /// the client is initialized, a change is announced to the server with
+    /// `notifications/roots/list_changed`.
+    pub async fn set_roots(&self, roots: Vec<Root>) -> Result<()> {
+        {
+            let mut current = self
+                .roots
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner);
+            if *current == roots {
+                return Ok(());
+            }
+            *current = roots;
+        }
+        let service = match &*self.state.lock().await {
+            ClientState::Ready { service, .. } => Arc::clone(service),
+            ClientState::Connecting { .. } => return Ok(()),
+        };
+        service.notify_roots_list_changed().await?;
+        Ok(())
+    }
+
     pub async fn send_custom_notification(
         &self,
         method: &str,
@@ -545,14 +614,94 @@ impl RmcpClient {
         Ok(response)
     }
 
-    async fn service(&self) -> Result<Arc<RunningService<RoleClient, LoggingClientHandler>>> {
-        let guard = self.state.lock().await;
-        match &*guard {
-            ClientState::Ready { service, .. } => Ok(Arc::clone(service)),
+    /// Sends a custom request that configures the session, such as the
+    /// sandbox state. The latest params of each method are re-sent whenever
+    /// a new session is started.
+    pub async fn send_session_request(
+        &self,
+        method: &str,
+        params: Option<serde_json::Value>,
+    ) -> Result<ServerResult> {
+        self.session_requests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .insert(method.to_string(), params.clone());
+        self.send_custom_request(method, params).await
+    }
+
+    async fn ready_service(
+        &self,
+    ) -> Result<(
+        Arc<RunningService<RoleClient, LoggingClientHandler>>,
+        LoggingClientHandler,
+        Option<Duration>,
+    )> {
+        match &*self.state.lock().await {
+            ClientState::Ready {
+                service,
+                handler,
+                handshake_timeout,
+                ..
+            } => Ok((
+                Arc::clone(service),
+                handler.as_ref().clone(),
+                *handshake_timeout,
+            )),
             ClientState::Connecting { .. } => Err(anyhow!("MCP client not initialized")),
         }
     }
 
+    async fn service(&self) -> Result<Arc<RunningService<RoleClient, LoggingClientHandler>>> {
+        let (service, handler, handshake_timeout) = self.ready_service().await?;
+        // A streamable HTTP server may end the session or drop the connection
+        // for good; start a new session rather than failing every later
+        // request. A dropped event stream is first resumed within the session
+        // (see `streamable_http_config`).
+        let Some(target) = &self.http_target else {
+            return Ok(service);
+        };
+        if !service.peer().is_transport_closed() {
+            return Ok(service);
+        }
+        let _reconnecting = self.reconnecting.lock().await;
+        let (current, ..) = self.ready_service().await?;
+        if !Arc::ptr_eq(&current, &service) {
+            // Another request reconnected while this one waited.
+            return Ok(current);
+        }
+        info!(
+            "MCP server `{}` closed its session; reconnecting",
+            target.server_name
+        );
+        let transport = connect_streamable_http(target).await?;
+        let (new_service, new_oauth, _) = serve(handler, transport, handshake_timeout).await?;
+        let new_service = Arc::new(new_service);
+        if let ClientState::Ready { service, oauth, .. } = &mut *self.state.lock().await {
+            *service = Arc::clone(&new_service);
+            *oauth = new_oauth;
+        }
+        let session_requests = self
+            .session_requests
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .clone();
+        for (method, params) in session_requests {
+            if let Err(err) = new_service
+                .send_request(ClientRequest::CustomRequest(CustomRequest::new(
+                    method.as_str(),
+                    params,
+                )))
+                .await
+            {
+                warn!(
+                    "failed to re-send `{method}` to MCP server `{}`: {err}",
+                    target.server_name
+                );
+            }
+        }
+        Ok(new_service)
+    }
+
     async fn oauth_persistor(&self) -> Option<OAuthPersistor> {
         let guard = self.state.lock().await;
         match &*guard {
@@ -583,6 +732,143 @@ impl RmcpClient {
     }
 }
 
+/// Performs the MCP handshake over `transport`.
+async fn serve(
+    handler: LoggingClientHandler,
+    transport: PendingTransport,
+    timeout: Option<Duration>,
+) -> Result<(
+    RunningService<RoleClient, LoggingClientHandler>,
+    Option<OAuthPersistor>,
+    Option<ProcessGroupGuard>,
+)> {
+    let (transport, oauth_persistor, process_group_guard) = match transport {
+        PendingTransport::ChildProcess {
+            transport,
+            process_group_guard,
+        } => (
+            service::serve_client(handler, transport).boxed(),
+            None,
+            process_group_guard,
+        ),
+        PendingTransport::StreamableHttp { transport } => (
+            service::serve_client(handler, transport).boxed(),
+            None,
+            None,
+        ),
+        PendingTransport::StreamableHttpWithOAuth {
+            transport,
+            oauth_persistor,
+        } => (
+            service::serve_client(handler, transport).boxed(),
+            Some(oauth_persistor),
+            None,
+        ),
+    };
+    let service = match timeout {
+        Some(duration) => time::timeout(duration, transport)
+            .await
+            .map_err(|_| anyhow!("timed out handshaking with MCP server after {duration:?}"))?
+            .map_err(|err| anyhow!("handshaking with MCP server failed: {err}"))?,
+        None => transport
+            .await
+            .map_err(|err| anyhow!("handshaking with MCP server failed: {err}"))?,
+    };
+    Ok((service, oauth_persistor, process_group_guard))
+}
+
+/// Transport settings for `url`. An event stream that drops mid-session is
+/// re-requested with `Last-Event-ID`, so the server can replay the events
+/// this client missed, a bounded number of times.
+fn streamable_http_config(url: &str) -> StreamableHttpClientTransportConfig {
+    StreamableHttpClientTransportConfig {
+        retry_config: Arc::new(ExponentialBackoff {
+            max_times: Some(STREAM_RESUME_ATTEMPTS),
+            base_duration: ExponentialBackoff::DEFAULT_DURATION,
+        }),
+        ..StreamableHttpClientTransportConfig::with_uri(url.to_string())
+    }
+}
+
+/// Opens a transport to a streamable HTTP server, authenticating with the
+/// configured bearer token or else with the stored OAuth tokens.
+async fn connect_streamable_http(target: &StreamableHttpTarget) -> Result<PendingTransport> {
+    let server_name = target.server_name.as_str();
+    let url = target.url.as_str();
+    let bearer_token = &target.bearer_token;
+    let default_headers = &target.default_headers;
+    let store_mode = target.store_mode;
+    let initial_oauth_tokens =
+        if bearer_token.is_none() && !default_headers.contains_key(AUTHORIZATION) {
+            match load_oauth_tokens(server_name, url, store_mode) {
+                Ok(tokens) => tokens,
+                Err(err) => {
+                    warn!("failed to read tokens for server `{server_name}`: {err}");
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+    let transport = if let Some(initial_tokens) = initial_oauth_tokens.clone() {
+        match create_oauth_transport_and_runtime(
+            server_name,
+            url,
+            initial_tokens.clone(),
+            store_mode,
+            default_headers.clone(),
+        )
+        .await
+        {
+            Ok((transport, oauth_persistor)) => PendingTransport::StreamableHttpWithOAuth {
+                transport,
+                oauth_persistor,
+            },
+            Err(err)
+                if err.downcast_ref::<AuthError>().is_some_and(|auth_err| {
+                    matches!(auth_err, AuthError::NoAuthorizationSupport)
+                }) =>
+            {
+                let access_token = initial_tokens
+                    .token_response
+                    .0
+                    .access_token()
+                    .secret()
+                    .to_string();
+                warn!(
+                    "OAuth metadata discovery is unavailable for MCP server `{server_name}`; falling back to stored bearer token authentication"
+                );
+                let http_config = streamable_http_config(url).auth_header(access_token);
+                let http_client = apply_default_headers(
+                    apply_http_network_config(reqwest::Client::builder(), Some(url)),
+                    default_headers,
+                )
+                .build()?;
+                let transport =
+                    StreamableHttpClientTransport::with_client(http_client, http_config);
+                PendingTransport::StreamableHttp { transport }
+            }
+            Err(err) => return Err(err),
+        }
+    } else {
+        let mut http_config = streamable_http_config(url);
+        if let Some(bearer_token) = bearer_token.clone() {
+            http_config = http_config.auth_header(bearer_token);
+        }
+
+        let http_client = apply_default_headers(
+            apply_http_network_config(reqwest::Client::builder(), Some(url)),
+            default_headers,
+        )
+        .build()?;
+
+        let transport = StreamableHttpClientTransport::with_client(http_client, http_config);
+        PendingTransport::StreamableHttp { transport }
+    };
+    Ok(transport)
+}
+
 async fn create_oauth_transport_and_runtime(
     server_name: &str,
     url: &str,
@@ -593,8 +879,11 @@ async fn create_oauth_transport_and_runtime(
     StreamableHttpClientTransport<AuthClient<reqwest::Client>>,
     OAuthPersistor,
 )> {
-    let http_client =
-        apply_default_headers(reqwest::Client::builder(), &default_headers).build()?;
+    let http_client = apply_default_headers(
+        apply_http_network_config(reqwest::Client::builder(), Some(url)),
+        &default_headers,
+    )
+    .build()?;
     let mut oauth_state = OAuthState::new(url.to_string(), Some(http_client.clone())).await?;
 
     oauth_state
@@ -615,10 +904,8 @@ async fn create_oauth_transport_and_runtime(
     let auth_client = AuthClient::new(http_client, manager);
     let auth_manager = auth_client.auth_manager.clone();
 
-    let transport = StreamableHttpClientTransport::with_client(
-        auth_client,
-        StreamableHttpClientTransportConfig::with_uri(url.to_string()),
-    );
+    let transport =
+        StreamableHttpClientTransport::with_client(auth_client, streamable_http_config(url));
 
     let runtime = OAuthPersistor::new(
         server_name.to_string(),
@@ -24,17 +24,50 @@ where
         let result = time::timeout(duration, fut)
             .await
             .with_context(|| anyhow!("timed out awaiting {label} after {duration:?}"))?;
-        result.map_err(|err| anyhow!("{label} failed: {err}"))
+        result.map_err(|err| request_failed(label, err))
     } else {
-        fut.await.map_err(|err| anyhow!("{label} failed: {err}"))
+        fut.await.map_err(|err| request_failed(label, err))
     }
 }
 
+/// The connection to an MCP server broke while a request was in flight, as
+/// opposed to the server answering with an error. The same request may
+/// succeed once the client reconnects.
+#[derive(Debug)]
+pub struct TransportError(String);
+
+impl std::fmt::Display for TransportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TransportError {}
+
+fn request_failed(label: &str, err: ServiceError) -> anyhow::Error {
+    let message = format!("{label} failed: {err}");
+    match err {
+        ServiceError::TransportSend(_) | ServiceError::TransportClosed => {
+            TransportError(message).into()
+        }
+        _ => anyhow!(message),
+    }
+}
+
+/// The environment a stdio MCP server is launched with: the variables named in
+/// `env_vars` (and, with `inherit_default_env`, the basic ones in
+/// [`DEFAULT_ENV_VARS`]) copied from this process, overridden by `extra_env`.
 pub(crate) fn create_env_for_mcp_server(
     extra_env: Option<HashMap<String, String>>,
     env_vars: &[String],
+    inherit_default_env: bool,
 ) -> HashMap<String, String> {
-    DEFAULT_ENV_VARS
+    let default_env_vars = if inherit_default_env {
+        DEFAULT_ENV_VARS
+    } else {
+        &[]
+    };
+    default_env_vars
         .iter()
         .copied()
         .chain(env_vars.iter().map(String::as_str))
@@ -201,8 +234,11 @@ mod tests {
     #[tokio::test]
     async fn create_env_honors_overrides() {
         let value = "custom".to_string();
-        let env =
-            create_env_for_mcp_server(Some(HashMap::from([("TZ".into(), value.clone())])), &[]);
+        let env = create_env_for_mcp_server(
+            Some(HashMap::from([("TZ".into(), value.clone())])),
+            &[],
+            true,
+        );
         assert_eq!(env.get("TZ"), Some(&value));
     }
 
@@ -212,7 +248,26 @@ mod tests {
         let custom_var = "EXTRA_RMCP_ENV";
         let value = "from-env";
         let _guard = EnvVarGuard::set(custom_var, value);
-        let env = create_env_for_mcp_server(None, &[custom_var.to_string()]);
+        let env = create_env_for_mcp_server(None, &[custom_var.to_string()], true);
         assert_eq!(env.get(custom_var), Some(&value.to_string()));
     }
+
+    #[test]
+    #[serial(extra_rmcp_env)]
+    fn create_env_without_defaults_keeps_only_requested_variables() {
+        let custom_var = "EXTRA_RMCP_ENV";
+        let _guard = EnvVarGuard::set(custom_var, "from-env");
+        let env = create_env_for_mcp_server(
+            Some(HashMap::from([("FOO".to_string(), "bar".to_string())])),
+            &[custom_var.to_string()],
+            false,
+        );
+        assert_eq!(
+            env,
+            HashMap::from([
+                (custom_var.to_string(), "from-env".to_string()),
+                ("FOO".to_string(), "bar".to_string()),
+            ])
+        );
+    }
 }
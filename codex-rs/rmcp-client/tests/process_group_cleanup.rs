@@ -9,6 +9,7 @@ use std::time::Duration;
 use anyhow::Context;
 use anyhow::Result;
 use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::StdioServerLimits;
 
 fn process_exists(pid: u32) -> bool {
     std::process::Command::new("kill")
@@ -72,7 +73,9 @@ async fn drop_kills_wrapper_process_group() -> Result<()> {
             child_pid_file_str,
         )])),
         &[],
+        true,
         None,
+        StdioServerLimits::default(),
     )
     .await?;
 
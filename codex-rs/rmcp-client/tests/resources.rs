@@ -5,6 +5,7 @@ use std::time::Duration;
 use codex_rmcp_client::ElicitationAction;
 use codex_rmcp_client::ElicitationResponse;
 use codex_rmcp_client::RmcpClient;
+use codex_rmcp_client::StdioServerLimits;
 use codex_utils_cargo_bin::CargoBinError;
 use futures::FutureExt as _;
 use rmcp::model::AnnotateAble;
@@ -60,7 +61,9 @@ async fn rmcp_client_can_list_and_read_resources() -> anyhow::Result<()> {
         Vec::<OsString>::new(),
         None,
         &[],
+        true,
         None,
+        StdioServerLimits::default(),
     )
     .await?;
 
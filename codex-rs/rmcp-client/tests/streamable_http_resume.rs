@@ -0,0 +1,156 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::Router;
+use axum::body::Body;
+use axum::extract::State;
+use axum::http::HeaderMap;
+use axum::http::StatusCode;
+use axum::response::Response;
+use axum::routing::post;
+use codex_rmcp_client::ElicitationAction;
+use codex_rmcp_client::ElicitationResponse;
+use codex_rmcp_client::OAuthCredentialsStoreMode;
+use codex_rmcp_client::RmcpClient;
+use futures::FutureExt as _;
+use futures::StreamExt as _;
+use pretty_assertions::assert_eq;
+use rmcp::model::ClientCapabilities;
+use rmcp::model::Implementation;
+use rmcp::model::InitializeRequestParams;
+use rmcp::model::ProtocolVersion;
+use serde_json::Value;
+use serde_json::json;
+use tokio::sync::mpsc;
+
+const SESSION_ID: &str = "session-1";
+
+/// Serves the handshake, then drops the session's event stream right after
+/// its first event and records the `Last-Event-ID` of every stream request.
+#[derive(Clone)]
+struct ResumingServer {
+    stream_requests: mpsc::UnboundedSender<Option<String>>,
+}
+
+async fn handle_post(body: String) -> Response {
+    let message: Value = serde_json::from_str(&body).unwrap_or_default();
+    let Some(id) = message.get("id").cloned() else {
+        return response(StatusCode::ACCEPTED, Body::empty());
+    };
+    let result = json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "result": {
+            "protocolVersion": "2025-06-18",
+            "capabilities": { "tools": { "listChanged": true } },
+            "serverInfo": { "name": "resuming", "version": "1.0.0" }
+        }
+    });
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "application/json")
+        .header("mcp-session-id", SESSION_ID)
+        .body(Body::from(result.to_string()))
+        .unwrap_or_else(|_| response(StatusCode::INTERNAL_SERVER_ERROR, Body::empty()))
+}
+
+async fn handle_get(State(server): State<Arc<ResumingServer>>, headers: HeaderMap) -> Response {
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let resumed = last_event_id.is_some();
+    let _ = server.stream_requests.send(last_event_id);
+    let body = if resumed {
+        Body::from_stream(futures::stream::pending::<Result<String, std::io::Error>>())
+    } else {
+        let event = format!(
+            "id: 1\ndata: {}\n\n",
+            json!({ "jsonrpc": "2.0", "method": "notifications/tools/list_changed" })
+        );
+        // Let the event reach the client before the connection drops.
+        Body::from_stream(
+            futures::stream::iter([Ok(event), Err(std::io::Error::other("connection dropped"))])
+                .then(|item| async move {
+                    tokio::time::sleep(Duration::from_millis(100)).await;
+                    item
+                }),
+        )
+    };
+    Response::builder()
+        .status(StatusCode::OK)
+        .header("content-type", "text/event-stream")
+        .body(body)
+        .unwrap_or_else(|_| response(StatusCode::INTERNAL_SERVER_ERROR, Body::empty()))
+}
+
+fn response(status: StatusCode, body: Body) -> Response {
+    let mut response = Response::new(body);
+    *response.status_mut() = status;
+    response
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn dropped_event_stream_resumes_with_last_event_id() -> anyhow::Result<()> {
+    let (stream_requests, mut stream_requests_rx) = mpsc::unbounded_channel();
+    let router = Router::new()
+        .route("/mcp", post(handle_post).get(handle_get))
+        .with_state(Arc::new(ResumingServer { stream_requests }));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+    let url = format!("http://{}/mcp", listener.local_addr()?);
+    tokio::spawn(async move { axum::serve(listener, router).await });
+
+    let client = RmcpClient::new_streamable_http_client(
+        "resuming",
+        &url,
+        None,
+        None,
+        None,
+        OAuthCredentialsStoreMode::File,
+    )
+    .await?;
+    client
+        .initialize(
+            InitializeRequestParams {
+                meta: None,
+                capabilities: ClientCapabilities::default(),
+                client_info: Implementation {
+                    name: "codex-test".into(),
+                    version: "0.0.0-test".into(),
+                    title: None,
+                    description: None,
+                    icons: None,
+                    website_url: None,
+                },
+                protocol_version: ProtocolVersion::V_2025_06_18,
+            },
+            Some(Duration::from_secs(5)),
+            Box::new(|_, _| {
+                async {
+                    Ok(ElicitationResponse {
+                        action: ElicitationAction::Decline,
+                        content: None,
+                    })
+                }
+                .boxed()
+            }),
+        )
+        .await?;
+
+    let mut last_event_ids = Vec::new();
+    while last_event_ids.len() < 2 {
+        let last_event_id =
+            tokio::time::timeout(Duration::from_secs(10), stream_requests_rx.recv()).await?;
+        last_event_ids.push(last_event_id.flatten());
+    }
+    assert_eq!(last_event_ids, vec![None, Some("1".to_string())]);
+    // The event read before the drop still reaches the client.
+    let list_changed = tokio::time::timeout(Duration::from_secs(5), async {
+        while !client.take_tool_list_changed() {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await;
+    assert!(list_changed.is_ok());
+    Ok(())
+}
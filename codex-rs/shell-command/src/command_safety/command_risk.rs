@@ -0,0 +1,358 @@
+//! Local, pattern-based risk classification for commands awaiting approval.
+//!
+//! The assessment is attached to exec approval requests so clients can show
+//! what a command does, whether it is destructive, and which paths it touches
+//! before the user decides. It is deliberately conservative: anything we do
+//! not recognize is reported as [`CommandRiskLevel::Medium`].
+
+use std::path::Path;
+
+use codex_protocol::approvals::CommandRiskAssessment;
+use codex_protocol::approvals::CommandRiskLevel;
+
+use crate::bash::extract_bash_command;
+use crate::bash::parse_shell_lc_plain_commands;
+use crate::command_safety::is_dangerous_command::command_might_be_dangerous;
+use crate::command_safety::is_dangerous_command::find_git_subcommand;
+use crate::command_safety::is_safe_command::is_known_safe_command;
+use crate::parse_command::shlex_join;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Effect {
+    ReadOnly,
+    Modifies,
+    Destructive,
+}
+
+struct Classified {
+    summary: String,
+    effect: Effect,
+    paths: Vec<String>,
+    elevated: bool,
+}
+
+pub fn assess_command_risk(command: &[String]) -> CommandRiskAssessment {
+    let commands = match parse_shell_lc_plain_commands(command) {
+        Some(commands) if !commands.is_empty() => commands,
+        _ if extract_bash_command(command).is_some() => {
+            // Redirects, substitutions and the like cannot be split reliably.
+            let destructive = command_might_be_dangerous(command);
+            return CommandRiskAssessment {
+                summary: "Runs a shell script that could not be analyzed".to_string(),
+                level: if destructive {
+                    CommandRiskLevel::High
+                } else {
+                    CommandRiskLevel::Medium
+                },
+                destructive,
+                paths: Vec::new(),
+            };
+        }
+        _ => vec![command.to_vec()],
+    };
+
+    let classified: Vec<Classified> = commands.iter().map(|command| classify(command)).collect();
+
+    let mut effect = classified
+        .iter()
+        .map(|classified| classified.effect)
+        .max()
+        .unwrap_or(Effect::ReadOnly);
+    // Also catches platform-specific patterns (e.g. PowerShell `Remove-Item`).
+    if command_might_be_dangerous(command) {
+        effect = Effect::Destructive;
+    }
+    let elevated = classified.iter().any(|classified| classified.elevated);
+
+    let mut paths: Vec<String> = Vec::new();
+    for path in classified.iter().flat_map(|classified| &classified.paths) {
+        if !paths.contains(path) {
+            paths.push(path.clone());
+        }
+    }
+
+    CommandRiskAssessment {
+        summary: classified
+            .iter()
+            .map(|classified| classified.summary.as_str())
+            .collect::<Vec<_>>()
+            .join("; "),
+        level: match effect {
+            _ if elevated => CommandRiskLevel::High,
+            Effect::ReadOnly => CommandRiskLevel::Low,
+            Effect::Modifies => CommandRiskLevel::Medium,
+            Effect::Destructive => CommandRiskLevel::High,
+        },
+        destructive: effect == Effect::Destructive,
+        paths,
+    }
+}
+
+fn classify(command: &[String]) -> Classified {
+    let (command, elevated) = match command.split_first() {
+        Some((first, rest)) if first == "sudo" => (rest, true),
+        _ => (command, false),
+    };
+    let program = command
+        .first()
+        .and_then(|program| Path::new(program).file_name())
+        .and_then(|name| name.to_str())
+        .unwrap_or_default();
+    let operands: Vec<String> = command
+        .iter()
+        .skip(1)
+        .filter(|arg| !arg.starts_with('-'))
+        .cloned()
+        .collect();
+
+    let (summary, effect, paths) = match program {
+        "rm" | "rmdir" | "shred" | "unlink" => (
+            describe("Deletes", &operands),
+            Effect::Destructive,
+            operands,
+        ),
+        "truncate" => (
+            describe("Truncates", &operands),
+            Effect::Destructive,
+            operands,
+        ),
+        "dd" => {
+            let paths: Vec<String> = command
+                .iter()
+                .filter_map(|arg| arg.strip_prefix("if=").or_else(|| arg.strip_prefix("of=")))
+                .map(str::to_string)
+                .collect();
+            (
+                describe("Copies raw data with dd between", &paths),
+                Effect::Destructive,
+                paths,
+            )
+        }
+        "mv" => (
+            describe_transfer("Moves", &operands),
+            Effect::Modifies,
+            operands,
+        ),
+        "cp" | "install" => (
+            describe_transfer("Copies", &operands),
+            Effect::Modifies,
+            operands,
+        ),
+        "ln" => (
+            describe_transfer("Links", &operands),
+            Effect::Modifies,
+            operands,
+        ),
+        "mkdir" => (
+            describe("Creates directory", &operands),
+            Effect::Modifies,
+            operands,
+        ),
+        "touch" => (
+            describe("Creates or updates", &operands),
+            Effect::Modifies,
+            operands,
+        ),
+        "tee" => (describe("Writes to", &operands), Effect::Modifies, operands),
+        "chmod" | "chown" | "chgrp" => {
+            let paths = operands.get(1..).unwrap_or_default().to_vec();
+            (
+                describe("Changes ownership or permissions of", &paths),
+                Effect::Modifies,
+                paths,
+            )
+        }
+        "sed" if command.iter().any(|arg| arg.starts_with("-i")) => {
+            let paths = operands.get(1..).unwrap_or_default().to_vec();
+            (describe("Edits in place", &paths), Effect::Modifies, paths)
+        }
+        "curl" | "wget" | "ssh" | "scp" | "rsync" => (
+            format!(
+                "Transfers data over the network (`{}`)",
+                shlex_join(command)
+            ),
+            Effect::Modifies,
+            Vec::new(),
+        ),
+        "git" => classify_git(command),
+        _ if is_known_safe_command(command) => {
+            let paths = match program {
+                "cat" | "ls" | "nl" | "stat" | "tree" | "wc" => operands,
+                "grep" | "rg" => operands.get(1..).unwrap_or_default().to_vec(),
+                _ => Vec::new(),
+            };
+            (
+                format!("Runs `{}` (read-only)", shlex_join(command)),
+                Effect::ReadOnly,
+                paths,
+            )
+        }
+        _ => (
+            format!("Runs `{}`", shlex_join(command)),
+            Effect::Modifies,
+            Vec::new(),
+        ),
+    };
+
+    let effect = if command_might_be_dangerous(command) {
+        Effect::Destructive
+    } else {
+        effect
+    };
+    let summary = if elevated {
+        format!("{summary} as root")
+    } else {
+        summary
+    };
+    Classified {
+        summary,
+        effect,
+        paths,
+        elevated,
+    }
+}
+
+fn classify_git(command: &[String]) -> (String, Effect, Vec<String>) {
+    let has_arg =
+        |candidates: &[&str]| command.iter().any(|arg| candidates.contains(&arg.as_str()));
+    let subcommand = find_git_subcommand(
+        command,
+        &[
+            "branch", "checkout", "clean", "diff", "log", "push", "reset", "restore", "rm", "show",
+            "status",
+        ],
+    )
+    .map(|(_, subcommand)| subcommand);
+
+    let (description, effect) = match subcommand {
+        Some("reset") if has_arg(&["--hard"]) => {
+            ("Discards uncommitted changes", Effect::Destructive)
+        }
+        Some("clean") => ("Deletes untracked files", Effect::Destructive),
+        Some("push") if has_arg(&["--force", "-f", "--force-with-lease"]) => {
+            ("Force-pushes to a remote", Effect::Destructive)
+        }
+        Some("push") => ("Pushes commits to a remote", Effect::Modifies),
+        Some("branch") if has_arg(&["-D", "-d", "--delete"]) => {
+            ("Deletes a branch", Effect::Destructive)
+        }
+        Some("restore") => ("Discards working-tree changes", Effect::Destructive),
+        Some("checkout") if has_arg(&["--", "."]) => {
+            ("Discards working-tree changes", Effect::Destructive)
+        }
+        Some("rm") => ("Removes tracked files", Effect::Destructive),
+        Some("diff" | "log" | "show" | "status") => ("Inspects the repository", Effect::ReadOnly),
+        Some(_) | None => ("Runs git", Effect::Modifies),
+    };
+    (
+        format!("{description} (`{}`)", shlex_join(command)),
+        effect,
+        Vec::new(),
+    )
+}
+
+fn describe(verb: &str, paths: &[String]) -> String {
+    if paths.is_empty() {
+        verb.to_string()
+    } else {
+        format!("{verb} {}", paths.join(", "))
+    }
+}
+
+fn describe_transfer(verb: &str, paths: &[String]) -> String {
+    match paths.split_last() {
+        Some((destination, sources)) if !sources.is_empty() => {
+            format!("{verb} {} to {destination}", sources.join(", "))
+        }
+        _ => describe(verb, paths),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn vec_str(items: &[&str]) -> Vec<String> {
+        items.iter().map(std::string::ToString::to_string).collect()
+    }
+
+    #[test]
+    fn read_only_commands_are_low_risk() {
+        assert_eq!(
+            assess_command_risk(&vec_str(&["ls", "-la", "src"])),
+            CommandRiskAssessment {
+                summary: "Runs `ls -la src` (read-only)".to_string(),
+                level: CommandRiskLevel::Low,
+                destructive: false,
+                paths: vec_str(&["src"]),
+            }
+        );
+    }
+
+    #[test]
+    fn shell_scripts_are_classified_per_command() {
+        assert_eq!(
+            assess_command_risk(&vec_str(&[
+                "bash",
+                "-lc",
+                "rm -rf build && mkdir -p build && cp config.toml build",
+            ])),
+            CommandRiskAssessment {
+                summary: "Deletes build; Creates directory build; Copies config.toml to build"
+                    .to_string(),
+                level: CommandRiskLevel::High,
+                destructive: true,
+                paths: vec_str(&["build", "config.toml"]),
+            }
+        );
+    }
+
+    #[test]
+    fn destructive_git_commands_are_high_risk() {
+        assert_eq!(
+            assess_command_risk(&vec_str(&["git", "reset", "--hard", "HEAD~1"])),
+            CommandRiskAssessment {
+                summary: "Discards uncommitted changes (`git reset --hard HEAD~1`)".to_string(),
+                level: CommandRiskLevel::High,
+                destructive: true,
+                paths: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn unknown_and_elevated_commands() {
+        assert_eq!(
+            assess_command_risk(&vec_str(&["cargo", "test"])),
+            CommandRiskAssessment {
+                summary: "Runs `cargo test`".to_string(),
+                level: CommandRiskLevel::Medium,
+                destructive: false,
+                paths: Vec::new(),
+            }
+        );
+        assert_eq!(
+            assess_command_risk(&vec_str(&["sudo", "apt-get", "install", "jq"])),
+            CommandRiskAssessment {
+                summary: "Runs `apt-get install jq` as root".to_string(),
+                level: CommandRiskLevel::High,
+                destructive: false,
+                paths: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn unparseable_scripts_are_medium_risk() {
+        assert_eq!(
+            assess_command_risk(&vec_str(&["bash", "-lc", "echo $(whoami) > out.txt"])),
+            CommandRiskAssessment {
+                summary: "Runs a shell script that could not be analyzed".to_string(),
+                level: CommandRiskLevel::Medium,
+                destructive: false,
+                paths: Vec::new(),
+            }
+        );
+    }
+}
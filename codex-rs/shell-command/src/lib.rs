@@ -5,7 +5,9 @@ mod shell_detect;
 pub mod bash;
 pub mod command_safety;
 pub mod parse_command;
+pub mod posix_translation;
 pub mod powershell;
 
+pub use command_safety::command_risk;
 pub use command_safety::is_dangerous_command;
 pub use command_safety::is_safe_command;
@@ -0,0 +1,510 @@
+//! Best-effort translation of common POSIX one-liners into PowerShell.
+//!
+//! Models frequently emit `ls -la`, `rm -rf build && mkdir -p build`, or
+//! `export FOO=bar` even when the session shell is PowerShell. Rewriting those
+//! scripts before launch keeps them from failing outright on Windows hosts.
+//! Anything that is not understood exactly (pipes, redirects, globs, unknown
+//! flags) is left untouched so native PowerShell keeps working as written.
+
+use crate::powershell::extract_powershell_command;
+
+/// Programs we know how to rewrite. Everything else is passed through.
+const TRANSLATED_PROGRAMS: &[&str] = &[
+    "cat", "cp", "export", "head", "ls", "mkdir", "mv", "pwd", "rm", "tail", "touch", "which",
+];
+
+/// Rewrites the script of a PowerShell invocation (see
+/// [`extract_powershell_command`]) when it contains POSIX commands we know how
+/// to translate. Any other command is returned unchanged.
+pub fn translate_posix_in_powershell_command(command: &[String]) -> Vec<String> {
+    let Some(translated) = extract_powershell_command(command)
+        .and_then(|(_, script)| translate_posix_to_powershell(script))
+    else {
+        return command.to_vec();
+    };
+
+    let mut command = command[..command.len() - 1].to_vec();
+    command.push(translated);
+    command
+}
+
+/// Translates `script` into PowerShell. Returns `None` when nothing needs to
+/// change or when the script uses constructs that cannot be translated
+/// faithfully.
+pub fn translate_posix_to_powershell(script: &str) -> Option<String> {
+    let raw_segments = split_on_and(script)?;
+    // Wrapping a segment in `if ($?) { ... }` would also make anything after a
+    // `;`, `||`, `&`, or newline in it depend on the previous segment.
+    if raw_segments.len() > 1 && raw_segments.iter().any(|segment| segment.has_sequencing) {
+        return None;
+    }
+
+    let mut translated_any = false;
+    let mut segments = Vec::new();
+    for segment in raw_segments {
+        match translate_segment(&segment) {
+            Segment::Translated(rewritten) => {
+                translated_any = true;
+                segments.push(rewritten);
+            }
+            Segment::Native => segments.push(segment.text.trim().to_string()),
+            Segment::Unsupported => return None,
+        }
+    }
+
+    if !translated_any {
+        return None;
+    }
+
+    // Windows PowerShell 5.1 has no `&&`, so emulate short-circuiting with `$?`.
+    segments
+        .into_iter()
+        .rev()
+        .reduce(|rest, segment| format!("{segment}; if ($?) {{ {rest} }}"))
+}
+
+struct RawSegment<'a> {
+    text: &'a str,
+    /// Set when the segment contains anything beyond plain words: pipes,
+    /// redirects, substitutions, globs, escapes, and so on.
+    has_shell_syntax: bool,
+    /// Set when the segment runs more than one command: `;`, `||`, a
+    /// background `&`, or a newline.
+    has_sequencing: bool,
+}
+
+enum Segment {
+    Translated(String),
+    Native,
+    Unsupported,
+}
+
+/// Splits `script` on top-level `&&`. Returns `None` for unbalanced quotes or
+/// empty segments.
+fn split_on_and(script: &str) -> Option<Vec<RawSegment<'_>>> {
+    let bytes = script.as_bytes();
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut has_shell_syntax = false;
+    let mut has_sequencing = false;
+    let mut quote: Option<u8> = None;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let byte = bytes[i];
+        match quote {
+            Some(b'\'') => {
+                if byte == b'\'' {
+                    quote = None;
+                }
+            }
+            Some(_) => match byte {
+                b'"' => quote = None,
+                b'\\' => {
+                    has_shell_syntax = true;
+                    i += 1;
+                }
+                b'$' | b'`' => has_shell_syntax = true,
+                _ => {}
+            },
+            None => match byte {
+                b'\'' | b'"' => quote = Some(byte),
+                b'\\' => {
+                    has_shell_syntax = true;
+                    i += 1;
+                }
+                b'&' if bytes.get(i + 1) == Some(&b'&') => {
+                    let text = &script[start..i];
+                    if text.trim().is_empty() {
+                        return None;
+                    }
+                    segments.push(RawSegment {
+                        text,
+                        has_shell_syntax,
+                        has_sequencing,
+                    });
+                    i += 2;
+                    start = i;
+                    has_shell_syntax = false;
+                    has_sequencing = false;
+                    continue;
+                }
+                b';' | b'\n' => {
+                    has_shell_syntax = true;
+                    has_sequencing = true;
+                }
+                // A lone `&` backgrounds a command unless it is part of a
+                // redirect such as `2>&1`.
+                b'&' => {
+                    has_shell_syntax = true;
+                    let redirect = i > 0 && matches!(bytes[i - 1], b'>' | b'<');
+                    has_sequencing |= !redirect;
+                }
+                b'|' if bytes.get(i + 1) == Some(&b'|') => {
+                    has_shell_syntax = true;
+                    has_sequencing = true;
+                }
+                b'|' | b'<' | b'>' | b'`' | b'$' | b'(' | b')' | b'{' | b'}' | b'[' | b']'
+                | b'*' | b'?' | b'~' | b'#' => {
+                    has_shell_syntax = true;
+                }
+                _ => {}
+            },
+        }
+        i += 1;
+    }
+
+    let text = &script[start..];
+    if quote.is_some() || text.trim().is_empty() {
+        return None;
+    }
+    segments.push(RawSegment {
+        text,
+        has_shell_syntax,
+        has_sequencing,
+    });
+    Some(segments)
+}
+
+fn translate_segment(segment: &RawSegment<'_>) -> Segment {
+    let Some(words) = shlex::split(segment.text) else {
+        return Segment::Unsupported;
+    };
+    let Some((program, args)) = words.split_first() else {
+        return Segment::Unsupported;
+    };
+    if !TRANSLATED_PROGRAMS.contains(&program.as_str()) {
+        return Segment::Native;
+    }
+    if segment.has_shell_syntax {
+        return Segment::Unsupported;
+    }
+    translate_program(program, args).map_or(Segment::Unsupported, Segment::Translated)
+}
+
+fn translate_program(program: &str, args: &[String]) -> Option<String> {
+    match program {
+        "ls" => {
+            let (flags, paths) = parse_flags(args, "1ahl")?;
+            let mut out = "Get-ChildItem".to_string();
+            if !paths.is_empty() {
+                out.push_str(&format!(" -LiteralPath {}", quote_list(&paths)));
+            }
+            if flags.contains(&'a') {
+                out.push_str(" -Force");
+            }
+            Some(out)
+        }
+        "cat" => {
+            let (_, paths) = parse_flags(args, "")?;
+            (!paths.is_empty()).then(|| format!("Get-Content -LiteralPath {}", quote_list(&paths)))
+        }
+        "rm" => {
+            let (flags, paths) = parse_flags(args, "Rfr")?;
+            if paths.is_empty() {
+                return None;
+            }
+            let recurse = if flags.contains(&'r') || flags.contains(&'R') {
+                " -Recurse"
+            } else {
+                ""
+            };
+            // `rm -f` must not fail on missing paths, and an error suppressed
+            // with `-ErrorAction` would still clear `$?` for the next segment.
+            Some(if flags.contains(&'f') {
+                format!(
+                    "foreach ($path in {}) {{ if (Test-Path -LiteralPath $path) {{ Remove-Item -LiteralPath $path{recurse} -Force }} }}",
+                    quote_list(&paths)
+                )
+            } else {
+                format!("Remove-Item -LiteralPath {}{recurse}", quote_list(&paths))
+            })
+        }
+        "mkdir" => {
+            let (flags, paths) = parse_flags(args, "p")?;
+            if paths.is_empty() {
+                return None;
+            }
+            let mut out = format!("New-Item -ItemType Directory -Path {}", quote_list(&paths));
+            if flags.contains(&'p') {
+                out.push_str(" -Force");
+            }
+            out.push_str(" | Out-Null");
+            Some(out)
+        }
+        "touch" => {
+            let (_, paths) = parse_flags(args, "")?;
+            if paths.is_empty() {
+                return None;
+            }
+            let statements: Vec<String> = paths
+                .iter()
+                .map(|path| {
+                    let path = quote(path);
+                    format!(
+                        "if (Test-Path -LiteralPath {path}) {{ (Get-Item -LiteralPath {path}).LastWriteTime = Get-Date }} else {{ New-Item -ItemType File -Path {path} | Out-Null }}"
+                    )
+                })
+                .collect();
+            Some(statements.join("; "))
+        }
+        "pwd" => args.is_empty().then(|| "(Get-Location).Path".to_string()),
+        "which" => match parse_flags(args, "")?.1.as_slice() {
+            [name] => Some(format!(
+                "(Get-Command {} -ErrorAction Stop).Source",
+                quote(name)
+            )),
+            _ => None,
+        },
+        "export" => {
+            if args.is_empty() {
+                return None;
+            }
+            let assignments = args
+                .iter()
+                .map(|arg| {
+                    let (name, value) = arg.split_once('=')?;
+                    is_env_var_name(name).then(|| format!("$env:{name} = {}", quote(value)))
+                })
+                .collect::<Option<Vec<_>>>()?;
+            Some(assignments.join("; "))
+        }
+        "cp" => {
+            let (flags, paths) = parse_flags(args, "Rfr")?;
+            let [source, destination] = paths.as_slice() else {
+                return None;
+            };
+            let mut out = format!(
+                "Copy-Item -LiteralPath {} -Destination {}",
+                quote(source),
+                quote(destination)
+            );
+            if flags.contains(&'r') || flags.contains(&'R') {
+                out.push_str(" -Recurse");
+            }
+            if flags.contains(&'f') {
+                out.push_str(" -Force");
+            }
+            Some(out)
+        }
+        "mv" => {
+            let (flags, paths) = parse_flags(args, "f")?;
+            let [source, destination] = paths.as_slice() else {
+                return None;
+            };
+            let mut out = format!(
+                "Move-Item -LiteralPath {} -Destination {}",
+                quote(source),
+                quote(destination)
+            );
+            if flags.contains(&'f') {
+                out.push_str(" -Force");
+            }
+            Some(out)
+        }
+        "head" | "tail" => {
+            let (count, path) = parse_line_count(args)?;
+            let parameter = if program == "head" {
+                "-TotalCount"
+            } else {
+                "-Tail"
+            };
+            Some(format!(
+                "Get-Content -LiteralPath {} {parameter} {count}",
+                quote(path)
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Splits `args` into single-letter flags and operands. Returns `None` when a
+/// flag is not in `allowed`.
+fn parse_flags<'a>(args: &'a [String], allowed: &str) -> Option<(Vec<char>, Vec<&'a str>)> {
+    let mut flags = Vec::new();
+    let mut operands = Vec::new();
+    let mut end_of_flags = false;
+    for arg in args {
+        if end_of_flags {
+            operands.push(arg.as_str());
+            continue;
+        }
+        if arg == "--" {
+            end_of_flags = true;
+            continue;
+        }
+        match arg.strip_prefix('-') {
+            Some("") => return None,
+            Some(cluster) => {
+                for flag in cluster.chars() {
+                    if !allowed.contains(flag) {
+                        return None;
+                    }
+                    flags.push(flag);
+                }
+            }
+            None => operands.push(arg.as_str()),
+        }
+    }
+    Some((flags, operands))
+}
+
+/// Parses `head`/`tail` arguments: an optional `-n N`, `-nN`, or `-N` and a
+/// single file.
+fn parse_line_count(args: &[String]) -> Option<(usize, &str)> {
+    let mut count = 10;
+    let mut path = None;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let value = if arg == "-n" {
+            Some(iter.next()?.as_str())
+        } else {
+            arg.strip_prefix("-n").or_else(|| arg.strip_prefix('-'))
+        };
+        match value {
+            // Reject `+N` and friends, which mean something else to `tail`.
+            Some(value) if value.bytes().all(|byte| byte.is_ascii_digit()) => {
+                count = value.parse().ok()?;
+            }
+            Some(_) => return None,
+            None if path.is_none() => path = Some(arg.as_str()),
+            None => return None,
+        }
+    }
+    Some((count, path?))
+}
+
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Quotes `value` as a PowerShell verbatim string. PowerShell treats the
+/// typographic single quotes as delimiters too, so they are doubled as well.
+fn quote(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('\'');
+    for c in value.chars() {
+        if matches!(c, '\'' | '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}') {
+            quoted.push(c);
+        }
+        quoted.push(c);
+    }
+    quoted.push('\'');
+    quoted
+}
+
+fn quote_list(values: &[&str]) -> String {
+    values
+        .iter()
+        .map(|value| quote(value))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::translate_posix_in_powershell_command;
+    use super::translate_posix_to_powershell;
+    use pretty_assertions::assert_eq;
+
+    fn translate(script: &str) -> Option<String> {
+        translate_posix_to_powershell(script)
+    }
+
+    #[test]
+    fn translates_common_file_commands() {
+        assert_eq!(
+            translate("ls -la"),
+            Some("Get-ChildItem -Force".to_string())
+        );
+        assert_eq!(
+            translate("cat README.md 'my notes.txt'"),
+            Some("Get-Content -LiteralPath 'README.md','my notes.txt'".to_string())
+        );
+        assert_eq!(
+            translate("cp -r src dst"),
+            Some("Copy-Item -LiteralPath 'src' -Destination 'dst' -Recurse".to_string())
+        );
+        assert_eq!(
+            translate("tail -n 20 build.log"),
+            Some("Get-Content -LiteralPath 'build.log' -Tail 20".to_string())
+        );
+        assert_eq!(
+            translate("export RUST_LOG=debug"),
+            Some("$env:RUST_LOG = 'debug'".to_string())
+        );
+    }
+
+    #[test]
+    fn chains_segments_with_short_circuiting() {
+        assert_eq!(
+            translate("rm -rf build && mkdir -p build && cargo build"),
+            Some(
+                "foreach ($path in 'build') { if (Test-Path -LiteralPath $path) { Remove-Item -LiteralPath $path -Recurse -Force } }; if ($?) { New-Item -ItemType Directory -Path 'build' -Force | Out-Null; if ($?) { cargo build } }"
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn leaves_chains_with_other_separators_unwrapped() {
+        for script in [
+            "mkdir -p build && cargo build; echo done",
+            "pwd && cargo test || echo failed",
+            "ls && cargo build\nls",
+            "cd src; cargo build && pwd",
+        ] {
+            assert_eq!(translate(script), None, "{script}");
+        }
+    }
+
+    #[test]
+    fn escapes_single_quotes_in_arguments() {
+        assert_eq!(
+            translate("cat \"it's.txt\""),
+            Some("Get-Content -LiteralPath 'it''s.txt'".to_string())
+        );
+    }
+
+    #[test]
+    fn leaves_untranslatable_scripts_alone() {
+        for script in [
+            "Get-ChildItem -Recurse",
+            "cargo test && cargo fmt",
+            "ls | Select-Object -First 3",
+            "rm *.log",
+            "ls --color=auto",
+            "cat > out.txt",
+            "tail -n +5 file.txt",
+            "echo 'unterminated && ls",
+        ] {
+            assert_eq!(translate(script), None, "{script}");
+        }
+    }
+
+    #[test]
+    fn rewrites_only_powershell_invocations() {
+        let pwsh = vec![
+            "pwsh".to_string(),
+            "-NoProfile".to_string(),
+            "-Command".to_string(),
+            "pwd".to_string(),
+        ];
+        assert_eq!(
+            translate_posix_in_powershell_command(&pwsh),
+            vec![
+                "pwsh".to_string(),
+                "-NoProfile".to_string(),
+                "-Command".to_string(),
+                "(Get-Location).Path".to_string(),
+            ]
+        );
+
+        let bash = vec!["bash".to_string(), "-lc".to_string(), "pwd".to_string()];
+        assert_eq!(translate_posix_in_powershell_command(&bash), bash);
+    }
+}
@@ -0,0 +1,5 @@
+CREATE TABLE tool_result_cache (
+    key TEXT PRIMARY KEY,
+    output TEXT NOT NULL,
+    stored_at INTEGER NOT NULL
+);
@@ -0,0 +1,10 @@
+/// Seals text columns that hold conversation content (thread titles, first
+/// user messages, cached tool results) before they are written, and opens
+/// them when they are read, so the database can be encrypted at rest.
+///
+/// `open` must pass through values that were stored before sealing was
+/// turned on.
+pub trait TextCipher: Send + Sync {
+    fn seal(&self, plaintext: &str) -> anyhow::Result<String>;
+    fn open(&self, stored: &str) -> anyhow::Result<String>;
+}
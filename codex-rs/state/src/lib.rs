@@ -4,6 +4,7 @@
 //! from JSONL rollouts and mirrors it into a local SQLite database. Backfill
 //! orchestration and rollout scanning live in `codex-core`.
 
+mod cipher;
 mod extract;
 pub mod log_db;
 mod migrations;
@@ -11,6 +12,7 @@ mod model;
 mod paths;
 mod runtime;
 
+pub use cipher::TextCipher;
 pub use model::LogEntry;
 pub use model::LogQuery;
 pub use model::LogRow;
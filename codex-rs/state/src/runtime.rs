@@ -6,6 +6,7 @@ use crate::METRIC_DB_INIT;
 use crate::STATE_DB_FILENAME;
 use crate::STATE_DB_VERSION;
 use crate::SortKey;
+use crate::TextCipher;
 use crate::ThreadMetadata;
 use crate::ThreadMetadataBuilder;
 use crate::ThreadsPage;
@@ -37,12 +38,15 @@ use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::sync::OnceLock;
 use std::time::Duration;
 use tracing::warn;
 use uuid::Uuid;
 
 mod memories;
 // Memory-specific CRUD and phase job lifecycle methods live in `runtime/memories.rs`.
+mod tool_cache;
+// Shared tool result cache reads, writes, and eviction live in `runtime/tool_cache.rs`.
 
 // "Partition" is the retention bucket we cap at 10 MiB:
 // - one bucket per non-null thread_id
@@ -55,6 +59,7 @@ pub struct StateRuntime {
     codex_home: PathBuf,
     default_provider: String,
     pool: Arc<sqlx::SqlitePool>,
+    text_cipher: OnceLock<Arc<dyn TextCipher>>,
 }
 
 impl StateRuntime {
@@ -87,6 +92,7 @@ impl StateRuntime {
             pool,
             codex_home,
             default_provider,
+            text_cipher: OnceLock::new(),
         });
         if !existed && let Some(otel) = otel.as_ref() {
             otel.counter(METRIC_DB_INIT, 1, &[("status", "created")]);
@@ -94,6 +100,35 @@ impl StateRuntime {
         Ok(runtime)
     }
 
+    /// Seal conversation content with `cipher` from now on. Only the first
+    /// cipher set takes effect; without one, text is stored as is.
+    pub fn set_text_cipher(&self, cipher: Arc<dyn TextCipher>) {
+        let _ = self.text_cipher.set(cipher);
+    }
+
+    fn seal_text(&self, plaintext: &str) -> anyhow::Result<String> {
+        match self.text_cipher.get() {
+            Some(cipher) => cipher.seal(plaintext),
+            None => Ok(plaintext.to_string()),
+        }
+    }
+
+    fn open_text(&self, stored: String) -> anyhow::Result<String> {
+        match self.text_cipher.get() {
+            Some(cipher) => cipher.open(&stored),
+            None => Ok(stored),
+        }
+    }
+
+    fn open_thread(&self, mut metadata: ThreadMetadata) -> anyhow::Result<ThreadMetadata> {
+        metadata.title = self.open_text(metadata.title)?;
+        metadata.first_user_message = metadata
+            .first_user_message
+            .map(|message| self.open_text(message))
+            .transpose()?;
+        Ok(metadata)
+    }
+
     /// Return the configured Codex home directory for this runtime.
     pub fn codex_home(&self) -> &Path {
         self.codex_home.as_path()
@@ -232,8 +267,12 @@ WHERE id = ?
         .bind(id.to_string())
         .fetch_optional(self.pool.as_ref())
         .await?;
-        row.map(|row| ThreadRow::try_from_row(&row).and_then(ThreadMetadata::try_from))
-            .transpose()
+        row.map(|row| {
+            ThreadRow::try_from_row(&row)
+                .and_then(ThreadMetadata::try_from)
+                .and_then(|metadata| self.open_thread(metadata))
+        })
+        .transpose()
     }
 
     /// Get dynamic tools for a thread, if present.
@@ -342,7 +381,11 @@ FROM threads
         let rows = builder.build().fetch_all(self.pool.as_ref()).await?;
         let mut items = rows
             .into_iter()
-            .map(|row| ThreadRow::try_from_row(&row).and_then(ThreadMetadata::try_from))
+            .map(|row| {
+                ThreadRow::try_from_row(&row)
+                    .and_then(ThreadMetadata::try_from)
+                    .and_then(|metadata| self.open_thread(metadata))
+            })
             .collect::<Result<Vec<_>, _>>()?;
         let num_scanned_rows = items.len();
         let next_anchor = if items.len() > page_size {
@@ -687,6 +730,9 @@ WHERE id IN (
 
     /// Insert or replace thread metadata directly.
     pub async fn upsert_thread(&self, metadata: &crate::ThreadMetadata) -> anyhow::Result<()> {
+        let title = self.seal_text(&metadata.title)?;
+        let first_user_message =
+            self.seal_text(metadata.first_user_message.as_deref().unwrap_or_default())?;
         sqlx::query(
             r#"
 INSERT INTO threads (
@@ -743,11 +789,11 @@ ON CONFLICT(id) DO UPDATE SET
         .bind(metadata.model_provider.as_str())
         .bind(metadata.cwd.display().to_string())
         .bind(metadata.cli_version.as_str())
-        .bind(metadata.title.as_str())
+        .bind(title)
         .bind(metadata.sandbox_policy.as_str())
         .bind(metadata.approval_mode.as_str())
         .bind(metadata.tokens_used)
-        .bind(metadata.first_user_message.as_deref().unwrap_or_default())
+        .bind(first_user_message)
         .bind(metadata.archived_at.is_some())
         .bind(metadata.archived_at.map(datetime_to_epoch_seconds))
         .bind(metadata.git_sha.as_deref())
@@ -1158,6 +1204,7 @@ mod tests {
     use crate::LogQuery;
     use crate::STATE_DB_FILENAME;
     use crate::STATE_DB_VERSION;
+    use crate::TextCipher;
     use crate::model::Phase2JobClaimOutcome;
     use crate::model::Stage1JobClaimOutcome;
     use crate::model::Stage1StartupClaimParams;
@@ -1366,6 +1413,67 @@ WHERE id = 1
         let _ = tokio::fs::remove_dir_all(codex_home).await;
     }
 
+    #[tokio::test]
+    async fn text_cipher_seals_content_columns() {
+        struct ReversingCipher;
+        impl TextCipher for ReversingCipher {
+            fn seal(&self, plaintext: &str) -> anyhow::Result<String> {
+                Ok(plaintext.chars().rev().collect())
+            }
+            fn open(&self, stored: &str) -> anyhow::Result<String> {
+                Ok(stored.chars().rev().collect())
+            }
+        }
+
+        let codex_home = unique_temp_dir();
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+        runtime.set_text_cipher(Arc::new(ReversingCipher));
+        let thread_id = ThreadId::from_string(&Uuid::new_v4().to_string()).expect("thread id");
+        let mut metadata = test_thread_metadata(&codex_home, thread_id, codex_home.join("a"));
+        metadata.title = "fix the parser".to_string();
+        runtime
+            .upsert_thread(&metadata)
+            .await
+            .expect("upsert thread");
+        runtime
+            .put_shared_tool_result("key", "cached output", 1)
+            .await
+            .expect("put tool result");
+
+        let stored: (String, String) =
+            sqlx::query_as("SELECT title, first_user_message FROM threads WHERE id = ?")
+                .bind(thread_id.to_string())
+                .fetch_one(runtime.pool.as_ref())
+                .await
+                .expect("read stored thread");
+        let stored_output: (String,) =
+            sqlx::query_as("SELECT output FROM tool_result_cache WHERE key = 'key'")
+                .fetch_one(runtime.pool.as_ref())
+                .await
+                .expect("read stored tool result");
+        assert_eq!(
+            (stored, stored_output),
+            (
+                ("resrap eht xif".to_string(), "olleh".to_string()),
+                ("tuptuo dehcac".to_string(),),
+            )
+        );
+        assert_eq!(
+            (
+                runtime.get_thread(thread_id).await.expect("get thread"),
+                runtime
+                    .get_shared_tool_result("key")
+                    .await
+                    .expect("get tool result"),
+            ),
+            (Some(metadata), Some(("cached output".to_string(), 1)))
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
     #[tokio::test]
     async fn stage1_claim_skips_when_up_to_date() {
         let codex_home = unique_temp_dir();
@@ -3196,6 +3304,48 @@ VALUES (?, ?, ?, ?, ?)
         let _ = tokio::fs::remove_dir_all(codex_home).await;
     }
 
+    #[tokio::test]
+    async fn shared_tool_results_keep_the_latest_output_and_evict_old_ones() {
+        let codex_home = unique_temp_dir();
+        tokio::fs::create_dir_all(&codex_home)
+            .await
+            .expect("create codex_home");
+        let runtime = StateRuntime::init(codex_home.clone(), "test-provider".to_string(), None)
+            .await
+            .expect("initialize runtime");
+
+        assert_eq!(
+            runtime
+                .get_shared_tool_result("abc")
+                .await
+                .expect("get missing"),
+            None
+        );
+        runtime
+            .put_shared_tool_result("abc", "first", 1_700_000_000)
+            .await
+            .expect("put first");
+        runtime
+            .put_shared_tool_result("abc", "second", 1_700_000_100)
+            .await
+            .expect("put second");
+        assert_eq!(
+            runtime.get_shared_tool_result("abc").await.expect("get"),
+            Some(("second".to_string(), 1_700_000_100))
+        );
+
+        runtime
+            .put_shared_tool_result("def", "later", 1_700_000_100 + 31 * 24 * 60 * 60)
+            .await
+            .expect("put later");
+        assert_eq!(
+            runtime.get_shared_tool_result("abc").await.expect("get"),
+            None
+        );
+
+        let _ = tokio::fs::remove_dir_all(codex_home).await;
+    }
+
     fn test_thread_metadata(
         codex_home: &Path,
         thread_id: ThreadId,
@@ -146,7 +146,11 @@ LEFT JOIN jobs
             .fetch_all(self.pool.as_ref())
             .await?
             .into_iter()
-            .map(|row| ThreadRow::try_from_row(&row).and_then(ThreadMetadata::try_from))
+            .map(|row| {
+                ThreadRow::try_from_row(&row)
+                    .and_then(ThreadMetadata::try_from)
+                    .and_then(|metadata| self.open_thread(metadata))
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         let mut claimed = Vec::new();
@@ -0,0 +1,66 @@
+use super::*;
+
+/// Rows kept; the least recently stored are evicted first.
+const MAX_SHARED_TOOL_RESULTS: i64 = 10_000;
+/// Rows stored longer ago than this are evicted.
+const MAX_SHARED_TOOL_RESULT_AGE_SECS: i64 = 30 * 24 * 60 * 60;
+
+impl StateRuntime {
+    /// The tool result stored under the content-addressed `key`, with the
+    /// unix time it was stored at.
+    pub async fn get_shared_tool_result(&self, key: &str) -> anyhow::Result<Option<(String, i64)>> {
+        let row = sqlx::query(
+            r#"
+SELECT output, stored_at
+FROM tool_result_cache
+WHERE key = ?
+            "#,
+        )
+        .bind(key)
+        .fetch_optional(self.pool.as_ref())
+        .await?;
+        row.map(|row| {
+            Ok((
+                self.open_text(row.try_get("output")?)?,
+                row.try_get("stored_at")?,
+            ))
+        })
+        .transpose()
+    }
+
+    /// Stores a tool result for every session under `key`, replacing an
+    /// earlier one, and evicts results that are too old or too many.
+    pub async fn put_shared_tool_result(
+        &self,
+        key: &str,
+        output: &str,
+        stored_at: i64,
+    ) -> anyhow::Result<()> {
+        sqlx::query(
+            r#"
+INSERT INTO tool_result_cache (key, output, stored_at)
+VALUES (?, ?, ?)
+ON CONFLICT(key) DO UPDATE SET output = excluded.output, stored_at = excluded.stored_at
+            "#,
+        )
+        .bind(key)
+        .bind(self.seal_text(output)?)
+        .bind(stored_at)
+        .execute(self.pool.as_ref())
+        .await?;
+        sqlx::query(
+            r#"
+DELETE FROM tool_result_cache
+WHERE stored_at < ?
+   OR key NOT IN (
+        SELECT key FROM tool_result_cache ORDER BY stored_at DESC LIMIT ?
+   )
+            "#,
+        )
+        .bind(stored_at - MAX_SHARED_TOOL_RESULT_AGE_SECS)
+        .bind(MAX_SHARED_TOOL_RESULTS)
+        .execute(self.pool.as_ref())
+        .await?;
+        Ok(())
+    }
+}
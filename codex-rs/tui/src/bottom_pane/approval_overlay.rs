@@ -17,6 +17,8 @@ use crate::render::highlight::highlight_bash_to_lines;
 use crate::render::renderable::ColumnRenderable;
 use crate::render::renderable::Renderable;
 use codex_core::features::Features;
+use codex_core::localization::Locale;
+use codex_core::localization::Message;
 use codex_protocol::mcp::RequestId;
 use codex_protocol::protocol::ElicitationAction;
 use codex_protocol::protocol::ExecPolicyAmendment;
@@ -59,6 +61,17 @@ pub(crate) enum ApprovalRequest {
     },
 }
 
+impl ApprovalRequest {
+    /// Id of an exec or patch approval, which `EventMsg::ApprovalResolved`
+    /// refers to.
+    fn id(&self) -> Option<&str> {
+        match self {
+            ApprovalRequest::Exec { id, .. } | ApprovalRequest::ApplyPatch { id, .. } => Some(id),
+            ApprovalRequest::McpElicitation { .. } => None,
+        }
+    }
+}
+
 /// Modal overlay asking the user to approve or deny one or more requests.
 pub(crate) struct ApprovalOverlay {
     current_request: Option<ApprovalRequest>,
@@ -70,10 +83,16 @@ pub(crate) struct ApprovalOverlay {
     current_complete: bool,
     done: bool,
     features: Features,
+    locale: Locale,
 }
 
 impl ApprovalOverlay {
-    pub fn new(request: ApprovalRequest, app_event_tx: AppEventSender, features: Features) -> Self {
+    pub fn new(
+        request: ApprovalRequest,
+        app_event_tx: AppEventSender,
+        features: Features,
+        locale: Locale,
+    ) -> Self {
         let mut view = Self {
             current_request: None,
             current_variant: None,
@@ -84,6 +103,7 @@ impl ApprovalOverlay {
             current_complete: false,
             done: false,
             features,
+            locale,
         };
         view.set_current(request);
         view
@@ -95,10 +115,11 @@ impl ApprovalOverlay {
 
     fn set_current(&mut self, request: ApprovalRequest) {
         self.current_request = Some(request.clone());
-        let ApprovalRequestState { variant, header } = ApprovalRequestState::from(request);
+        let ApprovalRequestState { variant, header } =
+            ApprovalRequestState::new(request, self.locale);
         self.current_variant = Some(variant.clone());
         self.current_complete = false;
-        let (options, params) = Self::build_options(variant, header, &self.features);
+        let (options, params) = Self::build_options(variant, header, &self.features, self.locale);
         self.options = options;
         self.list = ListSelectionView::new(params, self.app_event_tx.clone());
     }
@@ -107,6 +128,7 @@ impl ApprovalOverlay {
         variant: ApprovalVariant,
         header: Box<dyn Renderable>,
         _features: &Features,
+        locale: Locale,
     ) -> (Vec<ApprovalOption>, SelectionViewParams) {
         let (options, title) = match &variant {
             ApprovalVariant::Exec {
@@ -117,24 +139,25 @@ impl ApprovalOverlay {
                 exec_options(
                     proposed_execpolicy_amendment.clone(),
                     network_approval_context.as_ref(),
+                    locale,
                 ),
                 network_approval_context.as_ref().map_or_else(
-                    || "Would you like to run the following command?".to_string(),
+                    || locale.text(Message::RunCommandPrompt).to_string(),
                     |network_approval_context| {
-                        format!(
-                            "Do you want to approve network access to \"{}\"?",
-                            network_approval_context.host
+                        locale.format(
+                            Message::NetworkAccessPrompt,
+                            &[("host", &network_approval_context.host)],
                         )
                     },
                 ),
             ),
             ApprovalVariant::ApplyPatch { .. } => (
-                patch_options(),
-                "Would you like to make the following edits?".to_string(),
+                patch_options(locale),
+                locale.text(Message::EditFilesPrompt).to_string(),
             ),
             ApprovalVariant::McpElicitation { server_name, .. } => (
-                elicitation_options(),
-                format!("{server_name} needs your approval."),
+                elicitation_options(locale),
+                locale.format(Message::ElicitationPrompt, &[("server", server_name)]),
             ),
         };
 
@@ -232,6 +255,7 @@ impl ApprovalOverlay {
                 server_name: server_name.to_string(),
                 request_id: request_id.clone(),
                 decision,
+                content: None,
             }));
     }
 
@@ -328,6 +352,17 @@ impl BottomPaneView for ApprovalOverlay {
         self.enqueue_request(request);
         None
     }
+
+    fn dismiss_approval_request(&mut self, id: &str) {
+        self.queue.retain(|request| request.id() != Some(id));
+        if !self.current_complete
+            && let Some(request) = &self.current_request
+            && request.id() == Some(id)
+        {
+            self.current_complete = true;
+            self.advance_queue();
+        }
+    }
 }
 
 impl Renderable for ApprovalOverlay {
@@ -349,8 +384,8 @@ struct ApprovalRequestState {
     header: Box<dyn Renderable>,
 }
 
-impl From<ApprovalRequest> for ApprovalRequestState {
-    fn from(value: ApprovalRequest) -> Self {
+impl ApprovalRequestState {
+    fn new(value: ApprovalRequest, locale: Locale) -> Self {
         match value {
             ApprovalRequest::Exec {
                 id,
@@ -361,7 +396,10 @@ impl From<ApprovalRequest> for ApprovalRequestState {
             } => {
                 let mut header: Vec<Line<'static>> = Vec::new();
                 if let Some(reason) = reason {
-                    header.push(Line::from(vec!["Reason: ".into(), reason.italic()]));
+                    header.push(Line::from(vec![
+                        locale.text(Message::ApprovalReason).into(),
+                        reason.italic(),
+                    ]));
                     header.push(Line::from(""));
                 }
                 if network_approval_context.is_none() {
@@ -393,8 +431,11 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                     && !reason.is_empty()
                 {
                     header.push(Box::new(
-                        Paragraph::new(Line::from_iter(["Reason: ".into(), reason.italic()]))
-                            .wrap(Wrap { trim: false }),
+                        Paragraph::new(Line::from_iter([
+                            locale.text(Message::ApprovalReason).into(),
+                            reason.italic(),
+                        ]))
+                        .wrap(Wrap { trim: false }),
                     ));
                     header.push(Box::new(Line::from("")));
                 }
@@ -410,7 +451,10 @@ impl From<ApprovalRequest> for ApprovalRequestState {
                 message,
             } => {
                 let header = Paragraph::new(vec![
-                    Line::from(vec!["Server: ".into(), server_name.clone().bold()]),
+                    Line::from(vec![
+                        locale.text(Message::ElicitationServer).into(),
+                        server_name.clone().bold(),
+                    ]),
                     Line::from(""),
                     Line::from(message),
                 ])
@@ -469,23 +513,24 @@ impl ApprovalOption {
 fn exec_options(
     proposed_execpolicy_amendment: Option<ExecPolicyAmendment>,
     network_approval_context: Option<&NetworkApprovalContext>,
+    locale: Locale,
 ) -> Vec<ApprovalOption> {
     if network_approval_context.is_some() {
         return vec![
             ApprovalOption {
-                label: "Yes, just this once".to_string(),
+                label: locale.text(Message::ApproveOnce).to_string(),
                 decision: ApprovalDecision::Review(ReviewDecision::Approved),
                 display_shortcut: None,
                 additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
             },
             ApprovalOption {
-                label: "Yes, and allow this host for this session".to_string(),
+                label: locale.text(Message::ApproveHostForSession).to_string(),
                 decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
                 display_shortcut: None,
                 additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
             },
             ApprovalOption {
-                label: "No, and tell Codex what to do differently".to_string(),
+                label: locale.text(Message::DenyAndExplain).to_string(),
                 decision: ApprovalDecision::Review(ReviewDecision::Abort),
                 display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
                 additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
@@ -494,7 +539,7 @@ fn exec_options(
     }
 
     vec![ApprovalOption {
-        label: "Yes, proceed".to_string(),
+        label: locale.text(Message::ApproveProceed).to_string(),
         decision: ApprovalDecision::Review(ReviewDecision::Approved),
         display_shortcut: None,
         additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
@@ -507,8 +552,9 @@ fn exec_options(
         }
 
         Some(ApprovalOption {
-            label: format!(
-                "Yes, and don't ask again for commands that start with `{rendered_prefix}`"
+            label: locale.format(
+                Message::ApproveCommandPrefix,
+                &[("prefix", &rendered_prefix)],
             ),
             decision: ApprovalDecision::Review(ReviewDecision::ApprovedExecpolicyAmendment {
                 proposed_execpolicy_amendment: prefix,
@@ -518,7 +564,7 @@ fn exec_options(
         })
     }))
     .chain([ApprovalOption {
-        label: "No, and tell Codex what to do differently".to_string(),
+        label: locale.text(Message::DenyAndExplain).to_string(),
         decision: ApprovalDecision::Review(ReviewDecision::Abort),
         display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
         additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
@@ -526,22 +572,22 @@ fn exec_options(
     .collect()
 }
 
-fn patch_options() -> Vec<ApprovalOption> {
+fn patch_options(locale: Locale) -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
-            label: "Yes, proceed".to_string(),
+            label: locale.text(Message::ApproveProceed).to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Approved),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
-            label: "Yes, and don't ask again for these files".to_string(),
+            label: locale.text(Message::ApproveFilesForSession).to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::ApprovedForSession),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('a'))],
         },
         ApprovalOption {
-            label: "No, and tell Codex what to do differently".to_string(),
+            label: locale.text(Message::DenyAndExplain).to_string(),
             decision: ApprovalDecision::Review(ReviewDecision::Abort),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
@@ -549,22 +595,22 @@ fn patch_options() -> Vec<ApprovalOption> {
     ]
 }
 
-fn elicitation_options() -> Vec<ApprovalOption> {
+fn elicitation_options(locale: Locale) -> Vec<ApprovalOption> {
     vec![
         ApprovalOption {
-            label: "Yes, provide the requested info".to_string(),
+            label: locale.text(Message::ProvideRequestedInfo).to_string(),
             decision: ApprovalDecision::McpElicitation(ElicitationAction::Accept),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('y'))],
         },
         ApprovalOption {
-            label: "No, but continue without it".to_string(),
+            label: locale.text(Message::ContinueWithoutInfo).to_string(),
             decision: ApprovalDecision::McpElicitation(ElicitationAction::Decline),
             display_shortcut: None,
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('n'))],
         },
         ApprovalOption {
-            label: "Cancel this request".to_string(),
+            label: locale.text(Message::CancelRequest).to_string(),
             decision: ApprovalDecision::McpElicitation(ElicitationAction::Cancel),
             display_shortcut: Some(key_hint::plain(KeyCode::Esc)),
             additional_shortcuts: vec![key_hint::plain(KeyCode::Char('c'))],
@@ -594,18 +640,53 @@ mod tests {
     fn ctrl_c_aborts_and_clears_queue() {
         let (tx, _rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            Locale::default(),
+        );
         view.enqueue_request(make_exec_request());
         assert_eq!(CancellationEvent::Handled, view.on_ctrl_c());
         assert!(view.queue.is_empty());
         assert!(view.is_complete());
     }
 
+    #[test]
+    fn requests_answered_elsewhere_are_dismissed_without_a_decision() {
+        let (tx, mut rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            Locale::default(),
+        );
+        let mut queued = make_exec_request();
+        if let ApprovalRequest::Exec { id, .. } = &mut queued {
+            *id = "queued".to_string();
+        }
+        view.enqueue_request(queued);
+
+        view.dismiss_approval_request("queued");
+        assert!(view.queue.is_empty());
+        assert!(!view.is_complete());
+
+        view.dismiss_approval_request("test");
+        assert!(view.is_complete());
+        assert!(rx.try_recv().is_err());
+    }
+
     #[test]
     fn shortcut_triggers_selection() {
         let (tx, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            Locale::default(),
+        );
         assert!(!view.is_complete());
         view.handle_key_event(KeyEvent::new(KeyCode::Char('y'), KeyModifiers::NONE));
         // We expect at least one CodexOp message in the queue.
@@ -635,6 +716,7 @@ mod tests {
             },
             tx,
             Features::with_defaults(),
+            Locale::default(),
         );
         view.handle_key_event(KeyEvent::new(KeyCode::Char('p'), KeyModifiers::NONE));
         let mut saw_op = false;
@@ -671,7 +753,12 @@ mod tests {
             proposed_execpolicy_amendment: None,
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(
+            exec_request,
+            tx,
+            Features::with_defaults(),
+            Locale::default(),
+        );
         let mut buf = Buffer::empty(Rect::new(0, 0, 80, view.desired_height(80)));
         view.render(Rect::new(0, 0, 80, view.desired_height(80)), &mut buf);
 
@@ -727,7 +814,12 @@ mod tests {
             proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(vec!["curl".into()])),
         };
 
-        let view = ApprovalOverlay::new(exec_request, tx, Features::with_defaults());
+        let view = ApprovalOverlay::new(
+            exec_request,
+            tx,
+            Features::with_defaults(),
+            Locale::default(),
+        );
         let mut buf = Buffer::empty(Rect::new(0, 0, 100, view.desired_height(100)));
         view.render(Rect::new(0, 0, 100, view.desired_height(100)), &mut buf);
 
@@ -755,6 +847,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn exec_prompt_uses_configured_locale() {
+        let (tx, _rx) = unbounded_channel::<AppEvent>();
+        let tx = AppEventSender::new(tx);
+        let exec_request = ApprovalRequest::Exec {
+            id: "test".into(),
+            command: vec!["echo".into(), "hola".into()],
+            reason: Some("needs output".into()),
+            network_approval_context: None,
+            proposed_execpolicy_amendment: None,
+        };
+
+        let view =
+            ApprovalOverlay::new(exec_request, tx, Features::with_defaults(), Locale::Spanish);
+        let mut buf = Buffer::empty(Rect::new(0, 0, 100, view.desired_height(100)));
+        view.render(Rect::new(0, 0, 100, view.desired_height(100)), &mut buf);
+
+        let rendered: Vec<String> = (0..buf.area.height)
+            .map(|row| {
+                (0..buf.area.width)
+                    .map(|col| buf[(col, row)].symbol().to_string())
+                    .collect()
+            })
+            .collect();
+
+        for expected in [
+            "¿Quieres ejecutar el siguiente comando?",
+            "Motivo: needs output",
+            "Sí, continuar",
+            "No, y decirle a Codex qué hacer de otra forma",
+        ] {
+            assert!(
+                rendered.iter().any(|line| line.contains(expected)),
+                "expected {expected:?} in {rendered:?}"
+            );
+        }
+    }
+
     #[test]
     fn exec_history_cell_wraps_with_two_space_indent() {
         let command = vec![
@@ -786,7 +916,12 @@ mod tests {
     fn enter_sets_last_selected_index_without_dismissing() {
         let (tx_raw, mut rx) = unbounded_channel::<AppEvent>();
         let tx = AppEventSender::new(tx_raw);
-        let mut view = ApprovalOverlay::new(make_exec_request(), tx, Features::with_defaults());
+        let mut view = ApprovalOverlay::new(
+            make_exec_request(),
+            tx,
+            Features::with_defaults(),
+            Locale::default(),
+        );
         view.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
 
         assert!(
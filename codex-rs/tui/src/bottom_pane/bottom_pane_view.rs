@@ -1,4 +1,5 @@
 use crate::bottom_pane::ApprovalRequest;
+use crate::bottom_pane::ElicitationForm;
 use crate::render::renderable::Renderable;
 use codex_protocol::request_user_input::RequestUserInputEvent;
 use crossterm::event::KeyEvent;
@@ -63,6 +64,10 @@ pub(crate) trait BottomPaneView: Renderable {
         Some(request)
     }
 
+    /// Drop the approval request `id`, answered elsewhere (e.g. by another
+    /// client attached to the thread), without sending a decision.
+    fn dismiss_approval_request(&mut self, _id: &str) {}
+
     /// Try to handle request_user_input; return the original value if not
     /// consumed.
     fn try_consume_user_input_request(
@@ -71,4 +76,14 @@ pub(crate) trait BottomPaneView: Renderable {
     ) -> Option<RequestUserInputEvent> {
         Some(request)
     }
+
+    /// Try to queue an MCP form elicitation; return the original values if
+    /// not consumed.
+    fn try_consume_elicitation_form(
+        &mut self,
+        form: ElicitationForm,
+        request: RequestUserInputEvent,
+    ) -> Option<(ElicitationForm, RequestUserInputEvent)> {
+        Some((form, request))
+    }
 }
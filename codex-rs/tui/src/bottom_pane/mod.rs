@@ -27,6 +27,7 @@ use crate::render::renderable::RenderableItem;
 use crate::tui::FrameRequester;
 use bottom_pane_view::BottomPaneView;
 use codex_core::features::Features;
+use codex_core::localization::Locale;
 use codex_core::skills::model::SkillMetadata;
 use codex_file_search::FileMatch;
 use codex_protocol::request_user_input::RequestUserInputEvent;
@@ -47,6 +48,7 @@ pub(crate) use app_link_view::AppLinkView;
 pub(crate) use app_link_view::AppLinkViewParams;
 pub(crate) use approval_overlay::ApprovalOverlay;
 pub(crate) use approval_overlay::ApprovalRequest;
+pub(crate) use request_user_input::ElicitationForm;
 pub(crate) use request_user_input::RequestUserInputOverlay;
 mod bottom_pane_view;
 
@@ -777,7 +779,12 @@ impl BottomPane {
     }
 
     /// Called when the agent requests user approval.
-    pub fn push_approval_request(&mut self, request: ApprovalRequest, features: &Features) {
+    pub fn push_approval_request(
+        &mut self,
+        request: ApprovalRequest,
+        features: &Features,
+        locale: Locale,
+    ) {
         let request = if let Some(view) = self.view_stack.last_mut() {
             match view.try_consume_approval_request(request) {
                 Some(request) => request,
@@ -791,11 +798,25 @@ impl BottomPane {
         };
 
         // Otherwise create a new approval modal overlay.
-        let modal = ApprovalOverlay::new(request, self.app_event_tx.clone(), features.clone());
+        let modal =
+            ApprovalOverlay::new(request, self.app_event_tx.clone(), features.clone(), locale);
         self.pause_status_timer_for_modal();
         self.push_view(Box::new(modal));
     }
 
+    /// Drops the approval request `id` once it was answered elsewhere.
+    pub fn dismiss_approval_request(&mut self, id: &str) {
+        let Some(view) = self.view_stack.last_mut() else {
+            return;
+        };
+        view.dismiss_approval_request(id);
+        if view.is_complete() {
+            self.view_stack.pop();
+            self.on_active_view_complete();
+        }
+        self.request_redraw();
+    }
+
     /// Called when the agent requests user input.
     pub fn push_user_input_request(&mut self, request: RequestUserInputEvent) {
         let request = if let Some(view) = self.view_stack.last_mut() {
@@ -825,6 +846,36 @@ impl BottomPane {
         self.push_view(Box::new(modal));
     }
 
+    /// Asks an MCP server's form through the request-user-input overlay.
+    pub fn push_elicitation_form(&mut self, form: ElicitationForm, request: RequestUserInputEvent) {
+        let (form, request) = if let Some(view) = self.view_stack.last_mut() {
+            match view.try_consume_elicitation_form(form, request) {
+                Some(pending) => pending,
+                None => {
+                    self.request_redraw();
+                    return;
+                }
+            }
+        } else {
+            (form, request)
+        };
+
+        let modal = RequestUserInputOverlay::for_elicitation_form(
+            form,
+            request,
+            self.app_event_tx.clone(),
+            self.has_input_focus,
+            self.enhanced_keys_supported,
+            self.disable_paste_burst,
+        );
+        self.pause_status_timer_for_modal();
+        self.set_composer_input_enabled(
+            false,
+            Some("Answer the questions to continue.".to_string()),
+        );
+        self.push_view(Box::new(modal));
+    }
+
     fn on_active_view_complete(&mut self) {
         self.resume_status_timer_after_modal();
         self.set_composer_input_enabled(true, None);
@@ -1036,7 +1087,7 @@ mod tests {
             animations_enabled: true,
             skills: Some(Vec::new()),
         });
-        pane.push_approval_request(exec_request(), &features);
+        pane.push_approval_request(exec_request(), &features, Locale::default());
         assert_eq!(CancellationEvent::Handled, pane.on_ctrl_c());
         assert!(!pane.quit_shortcut_hint_visible());
         assert_eq!(CancellationEvent::NotHandled, pane.on_ctrl_c());
@@ -1061,7 +1112,7 @@ mod tests {
         });
 
         // Create an approval modal (active view).
-        pane.push_approval_request(exec_request(), &features);
+        pane.push_approval_request(exec_request(), &features, Locale::default());
 
         // Render and verify the top row does not include an overlay.
         let area = Rect::new(0, 0, 60, 6);
@@ -1098,7 +1149,7 @@ mod tests {
         pane.set_task_running(true);
 
         // Push an approval modal (e.g., command approval) which should hide the status view.
-        pane.push_approval_request(exec_request(), &features);
+        pane.push_approval_request(exec_request(), &features, Locale::default());
 
         // Simulate pressing 'n' (No) on the modal.
         use crossterm::event::KeyCode;
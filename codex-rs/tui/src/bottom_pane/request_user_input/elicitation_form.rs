@@ -0,0 +1,243 @@
+//! MCP form elicitations asked through the request-user-input overlay.
+//!
+//! Each property of the form's `requested_schema` becomes one question:
+//! booleans and enums offer their values as options, everything else is
+//! typed into the notes field. The answers are converted back to the
+//! declared JSON types and sent as the `content` of `Op::ResolveElicitation`.
+
+use std::collections::HashMap;
+
+use codex_protocol::approvals::ElicitationRequestEvent;
+use codex_protocol::mcp::RequestId;
+use codex_protocol::protocol::ElicitationAction;
+use codex_protocol::protocol::Op;
+use codex_protocol::request_user_input::RequestUserInputAnswer;
+use codex_protocol::request_user_input::RequestUserInputEvent;
+use codex_protocol::request_user_input::RequestUserInputQuestion;
+use codex_protocol::request_user_input::RequestUserInputQuestionOption;
+use serde_json::Map;
+use serde_json::Value;
+
+const NOTE_PREFIX: &str = "user_note: ";
+
+/// An MCP server's form question, answered through the overlay instead of
+/// going back to the model.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct ElicitationForm {
+    server_name: String,
+    request_id: RequestId,
+    /// Name and declared `type` of each field, in question order.
+    fields: Vec<(String, String)>,
+}
+
+impl ElicitationForm {
+    /// The form and its questions, or `None` when the request asks for no
+    /// fields and a plain accept/decline is enough.
+    pub(crate) fn from_request(
+        ev: &ElicitationRequestEvent,
+    ) -> Option<(Self, RequestUserInputEvent)> {
+        let schema = ev.requested_schema.as_ref()?;
+        let properties = schema.get("properties").and_then(Value::as_object)?;
+        if properties.is_empty() {
+            return None;
+        }
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+            .collect();
+
+        let mut fields = Vec::new();
+        let mut questions = Vec::new();
+        for (name, property) in properties {
+            let kind = property
+                .get("type")
+                .and_then(Value::as_str)
+                .unwrap_or("string")
+                .to_string();
+            let title = property
+                .get("title")
+                .and_then(Value::as_str)
+                .unwrap_or(name);
+            let header = if required.contains(&name.as_str()) {
+                format!("{title} (required)")
+            } else {
+                title.to_string()
+            };
+            let question = property
+                .get("description")
+                .and_then(Value::as_str)
+                .unwrap_or(&ev.message)
+                .to_string();
+            questions.push(RequestUserInputQuestion {
+                id: name.clone(),
+                header,
+                question,
+                is_other: false,
+                is_secret: false,
+                options: field_options(&kind, property),
+            });
+            fields.push((name.clone(), kind));
+        }
+
+        let form = Self {
+            server_name: ev.server_name.clone(),
+            request_id: ev.id.clone(),
+            fields,
+        };
+        let request = RequestUserInputEvent {
+            call_id: format!("mcp-elicitation:{}:{}", ev.server_name, ev.id),
+            turn_id: String::new(),
+            questions,
+        };
+        Some((form, request))
+    }
+
+    /// Accepts the form with `answers`. Unanswered fields are left out, and
+    /// the server rejects a form missing required ones so it can be asked
+    /// again.
+    pub(crate) fn accept(&self, answers: &HashMap<String, RequestUserInputAnswer>) -> Op {
+        let mut content = Map::new();
+        for (name, kind) in &self.fields {
+            let Some(answer) = answers.get(name).and_then(|answer| answer.answers.first()) else {
+                continue;
+            };
+            let text = answer.strip_prefix(NOTE_PREFIX).unwrap_or(answer);
+            let value = match kind.as_str() {
+                "boolean" => Value::Bool(text == "Yes"),
+                "integer" => text
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .unwrap_or_else(|_| Value::from(text)),
+                "number" => text
+                    .parse::<f64>()
+                    .map(Value::from)
+                    .unwrap_or_else(|_| Value::from(text)),
+                _ => Value::from(text),
+            };
+            content.insert(name.clone(), value);
+        }
+        Op::ResolveElicitation {
+            server_name: self.server_name.clone(),
+            request_id: self.request_id.clone(),
+            decision: ElicitationAction::Accept,
+            content: Some(Value::Object(content)),
+        }
+    }
+
+    /// Cancels the form without answering it.
+    pub(crate) fn cancel(&self) -> Op {
+        Op::ResolveElicitation {
+            server_name: self.server_name.clone(),
+            request_id: self.request_id.clone(),
+            decision: ElicitationAction::Cancel,
+            content: None,
+        }
+    }
+}
+
+fn field_options(kind: &str, property: &Value) -> Option<Vec<RequestUserInputQuestionOption>> {
+    if let Some(values) = property.get("enum").and_then(Value::as_array) {
+        let names = property.get("enumNames").and_then(Value::as_array);
+        return Some(
+            values
+                .iter()
+                .filter_map(Value::as_str)
+                .enumerate()
+                .map(|(idx, value)| RequestUserInputQuestionOption {
+                    label: value.to_string(),
+                    description: names
+                        .and_then(|names| names.get(idx))
+                        .and_then(Value::as_str)
+                        .unwrap_or_default()
+                        .to_string(),
+                })
+                .collect(),
+        );
+    }
+    (kind == "boolean").then(|| {
+        ["Yes", "No"]
+            .into_iter()
+            .map(|label| RequestUserInputQuestionOption {
+                label: label.to_string(),
+                description: String::new(),
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde_json::json;
+
+    #[test]
+    fn form_answers_are_sent_with_the_declared_types() {
+        let ev = ElicitationRequestEvent {
+            server_name: "docs".to_string(),
+            id: RequestId::Integer(7),
+            message: "Configure the export".to_string(),
+            requested_schema: Some(json!({
+                "type": "object",
+                "properties": {
+                    "count": { "type": "integer", "description": "How many pages?" },
+                    "format": { "type": "string", "enum": ["pdf", "html"] },
+                    "public": { "type": "boolean" }
+                },
+                "required": ["count"]
+            })),
+            url: None,
+            timeout_ms: None,
+        };
+        let (form, request) = ElicitationForm::from_request(&ev).expect("form with fields");
+        assert_eq!(
+            request
+                .questions
+                .iter()
+                .map(|question| (
+                    question.header.as_str(),
+                    question.question.as_str(),
+                    question.options.as_ref().map(Vec::len)
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("count (required)", "How many pages?", None),
+                ("format", "Configure the export", Some(2)),
+                ("public", "Configure the export", Some(2)),
+            ]
+        );
+
+        let answers = HashMap::from([
+            (
+                "count".to_string(),
+                RequestUserInputAnswer {
+                    answers: vec!["user_note: 3".to_string()],
+                },
+            ),
+            (
+                "format".to_string(),
+                RequestUserInputAnswer {
+                    answers: vec!["html".to_string()],
+                },
+            ),
+            (
+                "public".to_string(),
+                RequestUserInputAnswer {
+                    answers: Vec::new(),
+                },
+            ),
+        ]);
+        assert_eq!(
+            form.accept(&answers),
+            Op::ResolveElicitation {
+                server_name: "docs".to_string(),
+                request_id: RequestId::Integer(7),
+                decision: ElicitationAction::Accept,
+                content: Some(json!({ "count": 3, "format": "html" })),
+            }
+        );
+    }
+}
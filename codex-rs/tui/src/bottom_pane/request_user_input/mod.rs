@@ -6,6 +6,8 @@
 //! - Typing while focused on options jumps into notes to keep freeform input fast.
 //! - Enter advances to the next question; the last question submits all answers.
 //! - Freeform-only questions submit an empty answer list when empty.
+//! - MCP form elicitations reuse the overlay; their answers go back to the
+//!   server instead of the model (see [`ElicitationForm`]).
 use std::collections::HashMap;
 use std::collections::VecDeque;
 use std::path::PathBuf;
@@ -14,9 +16,12 @@ use crossterm::event::KeyCode;
 use crossterm::event::KeyEvent;
 use crossterm::event::KeyEventKind;
 use crossterm::event::KeyModifiers;
+mod elicitation_form;
 mod layout;
 mod render;
 
+pub(crate) use elicitation_form::ElicitationForm;
+
 use crate::app_event::AppEvent;
 use crate::app_event_sender::AppEventSender;
 use crate::bottom_pane::CancellationEvent;
@@ -121,8 +126,10 @@ impl FooterTip {
 pub(crate) struct RequestUserInputOverlay {
     app_event_tx: AppEventSender,
     request: RequestUserInputEvent,
+    // Set when `request` asks an MCP server's form.
+    form: Option<ElicitationForm>,
     // Queue of incoming requests to process after the current one.
-    queue: VecDeque<RequestUserInputEvent>,
+    queue: VecDeque<(RequestUserInputEvent, Option<ElicitationForm>)>,
     // Reuse the shared chat composer so notes/freeform answers match the
     // primary input styling and behavior.
     composer: ChatComposer,
@@ -158,6 +165,7 @@ impl RequestUserInputOverlay {
         let mut overlay = Self {
             app_event_tx,
             request,
+            form: None,
             queue: VecDeque::new(),
             composer,
             answers: Vec::new(),
@@ -173,6 +181,27 @@ impl RequestUserInputOverlay {
         overlay
     }
 
+    /// An overlay asking an MCP server's `form`, with `request` holding its
+    /// questions.
+    pub(crate) fn for_elicitation_form(
+        form: ElicitationForm,
+        request: RequestUserInputEvent,
+        app_event_tx: AppEventSender,
+        has_input_focus: bool,
+        enhanced_keys_supported: bool,
+        disable_paste_burst: bool,
+    ) -> Self {
+        let mut overlay = Self::new(
+            request,
+            app_event_tx,
+            has_input_focus,
+            enhanced_keys_supported,
+            disable_paste_burst,
+        );
+        overlay.form = Some(form);
+        overlay
+    }
+
     fn current_index(&self) -> usize {
         self.current_idx
     }
@@ -710,6 +739,24 @@ impl RequestUserInputOverlay {
         }
     }
 
+    /// Drops the current and queued requests: MCP forms are cancelled, and
+    /// the turn is interrupted when the model asked any of them.
+    fn abandon_requests(&mut self) {
+        let pending = std::iter::once(&self.form).chain(self.queue.iter().map(|(_, form)| form));
+        let mut interrupt = false;
+        for form in pending {
+            match form {
+                Some(form) => self.app_event_tx.send(AppEvent::CodexOp(form.cancel())),
+                None => interrupt = true,
+            }
+        }
+        if interrupt {
+            self.app_event_tx.send(AppEvent::CodexOp(Op::Interrupt));
+        }
+        self.queue.clear();
+        self.done = true;
+    }
+
     /// Build the response payload and dispatch it to the app.
     fn submit_answers(&mut self) {
         self.confirm_unanswered = None;
@@ -745,13 +792,16 @@ impl RequestUserInputOverlay {
                 },
             );
         }
-        self.app_event_tx
-            .send(AppEvent::CodexOp(Op::UserInputAnswer {
+        let op = match &self.form {
+            Some(form) => form.accept(&answers),
+            None => Op::UserInputAnswer {
                 id: self.request.turn_id.clone(),
                 response: RequestUserInputResponse {
                     answers: answers.clone(),
                 },
-            }));
+            },
+        };
+        self.app_event_tx.send(AppEvent::CodexOp(op));
         self.app_event_tx.send(AppEvent::InsertHistoryCell(Box::new(
             history_cell::RequestUserInputResultCell {
                 questions: self.request.questions.clone(),
@@ -759,8 +809,9 @@ impl RequestUserInputOverlay {
                 interrupted: false,
             },
         )));
-        if let Some(next) = self.queue.pop_front() {
+        if let Some((next, form)) = self.queue.pop_front() {
             self.request = next;
+            self.form = form;
             self.reset_for_request();
             self.ensure_focus_available();
             self.restore_current_draft();
@@ -1007,8 +1058,7 @@ impl BottomPaneView for RequestUserInputOverlay {
             }
             // TODO: Emit interrupted request_user_input results (including committed answers)
             // once core supports persisting them reliably without follow-up turn issues.
-            self.app_event_tx.send(AppEvent::CodexOp(Op::Interrupt));
-            self.done = true;
+            self.abandon_requests();
             return;
         }
 
@@ -1223,8 +1273,7 @@ impl BottomPaneView for RequestUserInputOverlay {
             self.close_unanswered_confirmation();
             // TODO: Emit interrupted request_user_input results (including committed answers)
             // once core supports persisting them reliably without follow-up turn issues.
-            self.app_event_tx.send(AppEvent::CodexOp(Op::Interrupt));
-            self.done = true;
+            self.abandon_requests();
             return CancellationEvent::Handled;
         }
         if self.focus_is_notes() && !self.composer.current_text_with_pending().is_empty() {
@@ -1234,8 +1283,7 @@ impl BottomPaneView for RequestUserInputOverlay {
 
         // TODO: Emit interrupted request_user_input results (including committed answers)
         // once core supports persisting them reliably without follow-up turn issues.
-        self.app_event_tx.send(AppEvent::CodexOp(Op::Interrupt));
-        self.done = true;
+        self.abandon_requests();
         CancellationEvent::Handled
     }
 
@@ -1270,7 +1318,16 @@ impl BottomPaneView for RequestUserInputOverlay {
         &mut self,
         request: RequestUserInputEvent,
     ) -> Option<RequestUserInputEvent> {
-        self.queue.push_back(request);
+        self.queue.push_back((request, None));
+        None
+    }
+
+    fn try_consume_elicitation_form(
+        &mut self,
+        form: ElicitationForm,
+        request: RequestUserInputEvent,
+    ) -> Option<(ElicitationForm, RequestUserInputEvent)> {
+        self.queue.push_back((request, Some(form)));
         None
     }
 }
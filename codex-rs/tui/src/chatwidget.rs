@@ -89,9 +89,11 @@ use codex_protocol::protocol::AgentReasoningEvent;
 use codex_protocol::protocol::AgentReasoningRawContentDeltaEvent;
 use codex_protocol::protocol::AgentReasoningRawContentEvent;
 use codex_protocol::protocol::ApplyPatchApprovalRequestEvent;
+use codex_protocol::protocol::ApprovalResolvedEvent;
 use codex_protocol::protocol::BackgroundEventEvent;
 use codex_protocol::protocol::CodexErrorInfo;
 use codex_protocol::protocol::CreditsSnapshot;
+use codex_protocol::protocol::DependencyAuditEvent;
 use codex_protocol::protocol::DeprecationNoticeEvent;
 use codex_protocol::protocol::ErrorEvent;
 use codex_protocol::protocol::Event;
@@ -102,6 +104,7 @@ use codex_protocol::protocol::ExecCommandEndEvent;
 use codex_protocol::protocol::ExecCommandOutputDeltaEvent;
 use codex_protocol::protocol::ExecCommandSource;
 use codex_protocol::protocol::ExitedReviewModeEvent;
+use codex_protocol::protocol::LicenseReportEvent;
 use codex_protocol::protocol::ListCustomPromptsResponseEvent;
 use codex_protocol::protocol::ListSkillsResponseEvent;
 use codex_protocol::protocol::McpListToolsResponseEvent;
@@ -112,14 +115,17 @@ use codex_protocol::protocol::McpToolCallBeginEvent;
 use codex_protocol::protocol::McpToolCallEndEvent;
 use codex_protocol::protocol::Op;
 use codex_protocol::protocol::PatchApplyBeginEvent;
+use codex_protocol::protocol::ProviderErrorKind;
 use codex_protocol::protocol::RateLimitSnapshot;
 use codex_protocol::protocol::ReviewRequest;
 use codex_protocol::protocol::ReviewTarget;
+use codex_protocol::protocol::SessionsPrunedEvent;
 use codex_protocol::protocol::SkillMetadata as ProtocolSkillMetadata;
 use codex_protocol::protocol::StreamErrorEvent;
 use codex_protocol::protocol::TerminalInteractionEvent;
 use codex_protocol::protocol::TokenUsage;
 use codex_protocol::protocol::TokenUsageInfo;
+use codex_protocol::protocol::ToolProgressEvent;
 use codex_protocol::protocol::TurnAbortReason;
 use codex_protocol::protocol::TurnCompleteEvent;
 use codex_protocol::protocol::TurnDiffEvent;
@@ -204,6 +210,7 @@ use crate::bottom_pane::CancellationEvent;
 use crate::bottom_pane::CollaborationModeIndicator;
 use crate::bottom_pane::ColumnWidthMode;
 use crate::bottom_pane::DOUBLE_PRESS_QUIT_SHORTCUT_ENABLED;
+use crate::bottom_pane::ElicitationForm;
 use crate::bottom_pane::ExperimentalFeatureItem;
 use crate::bottom_pane::ExperimentalFeaturesView;
 use crate::bottom_pane::FeedbackAudience;
@@ -487,6 +494,10 @@ fn rate_limit_error_kind(info: &CodexErrorInfo) -> Option<RateLimitErrorKind> {
         CodexErrorInfo::UsageLimitExceeded => Some(RateLimitErrorKind::UsageLimit),
         CodexErrorInfo::ResponseTooManyFailedAttempts {
             http_status_code: Some(429),
+        }
+        | CodexErrorInfo::ProviderError {
+            kind: ProviderErrorKind::RateLimited,
+            ..
         } => Some(RateLimitErrorKind::Generic),
         _ => None,
     }
@@ -1868,6 +1879,13 @@ impl ChatWidget {
         );
     }
 
+    /// Another client attached to the thread may have answered an approval
+    /// this one is still showing or holding back.
+    fn on_approval_resolved(&mut self, ev: ApprovalResolvedEvent) {
+        self.interrupts.remove_approval(&ev.id);
+        self.bottom_pane.dismiss_approval_request(&ev.id);
+    }
+
     fn on_elicitation_request(&mut self, ev: ElicitationRequestEvent) {
         let ev2 = ev.clone();
         self.defer_or_handle(
@@ -2183,6 +2201,20 @@ impl ChatWidget {
         self.set_status_header(message);
     }
 
+    /// Shows a tool call's progress under the current status header.
+    fn on_tool_progress(&mut self, event: ToolProgressEvent) {
+        let steps = match event.total {
+            Some(total) if total > 0 => format!("{}%", event.completed.saturating_mul(100) / total),
+            _ => format!("{} done", event.completed),
+        };
+        let details = match event.message {
+            Some(message) => format!("{message} · {steps}"),
+            None => steps,
+        };
+        self.bottom_pane.ensure_status_indicator();
+        self.set_status(self.current_status_header.clone(), Some(details));
+    }
+
     fn on_undo_started(&mut self, event: UndoStartedEvent) {
         self.bottom_pane.ensure_status_indicator();
         self.bottom_pane.set_interrupt_hint_visible(false);
@@ -2443,7 +2475,7 @@ impl ChatWidget {
             proposed_execpolicy_amendment: ev.proposed_execpolicy_amendment,
         };
         self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+            .push_approval_request(request, &self.config.features, self.config.locale);
         self.request_redraw();
     }
 
@@ -2457,7 +2489,7 @@ impl ChatWidget {
             cwd: self.config.cwd.clone(),
         };
         self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+            .push_approval_request(request, &self.config.features, self.config.locale);
         self.request_redraw();
         self.notify(Notification::EditApprovalRequested {
             cwd: self.config.cwd.clone(),
@@ -2472,13 +2504,19 @@ impl ChatWidget {
             server_name: ev.server_name.clone(),
         });
 
+        // Forms that ask for fields are answered like the model's questions.
+        if let Some((form, request)) = ElicitationForm::from_request(&ev) {
+            self.bottom_pane.push_elicitation_form(form, request);
+            self.request_redraw();
+            return;
+        }
         let request = ApprovalRequest::McpElicitation {
             server_name: ev.server_name,
             request_id: ev.id,
             message: ev.message,
         };
         self.bottom_pane
-            .push_approval_request(request, &self.config.features);
+            .push_approval_request(request, &self.config.features, self.config.locale);
         self.request_redraw();
     }
 
@@ -3491,6 +3529,12 @@ impl ChatWidget {
             SlashCommand::Ps => {
                 self.add_ps_output();
             }
+            SlashCommand::Audit => {
+                self.submit_op(Op::AuditDependencies { upgrade: false });
+            }
+            SlashCommand::Licenses => {
+                self.submit_op(Op::ScanLicenses);
+            }
             SlashCommand::Clean => {
                 self.clean_background_terminals();
             }
@@ -3643,6 +3687,19 @@ impl ChatWidget {
                 });
                 self.bottom_pane.drain_pending_submission_state();
             }
+            SlashCommand::Audit if !trimmed.is_empty() => {
+                let Some((prepared_args, _prepared_elements)) =
+                    self.bottom_pane.prepare_inline_args_submission(false)
+                else {
+                    return;
+                };
+                if prepared_args.trim() == "upgrade" {
+                    self.submit_op(Op::AuditDependencies { upgrade: true });
+                } else {
+                    self.add_error_message("Usage: /audit [upgrade]".to_string());
+                }
+                self.bottom_pane.drain_pending_submission_state();
+            }
             SlashCommand::SandboxReadRoot if !trimmed.is_empty() => {
                 let Some((prepared_args, _prepared_elements)) =
                     self.bottom_pane.prepare_inline_args_submission(false)
@@ -3926,6 +3983,7 @@ impl ChatWidget {
             final_output_json_schema: None,
             collaboration_mode,
             personality,
+            sampling: None,
         };
 
         self.codex_op_tx.send(op).unwrap_or_else(|e| {
@@ -4156,7 +4214,40 @@ impl ChatWidget {
             EventMsg::McpListToolsResponse(ev) => self.on_list_mcp_tools(ev),
             EventMsg::ListCustomPromptsResponse(ev) => self.on_list_custom_prompts(ev),
             EventMsg::ListSkillsResponse(ev) => self.on_list_skills(ev),
-            EventMsg::ListRemoteSkillsResponse(_) | EventMsg::RemoteSkillDownloaded(_) => {}
+            EventMsg::SessionsPruned(ev) => self.on_sessions_pruned(ev),
+            EventMsg::DependencyAudit(ev) => self.on_dependency_audit(ev),
+            EventMsg::LicenseReport(ev) => self.on_license_report(ev),
+            EventMsg::ApprovalResolved(ev) => self.on_approval_resolved(ev),
+            EventMsg::ListRemoteSkillsResponse(_)
+            | EventMsg::RemoteSkillDownloaded(_)
+            | EventMsg::PatchArtifact(_)
+            | EventMsg::ToolCallArgumentsPreview(_)
+            | EventMsg::SessionExported(_)
+            | EventMsg::SessionImported(_)
+            | EventMsg::ObserverPromoted(_)
+            | EventMsg::InlineEditResult(_)
+            | EventMsg::SessionTools(_)
+            | EventMsg::ToolSelection(_)
+            | EventMsg::ToolSelectionRestored(_)
+            | EventMsg::ElicitationTimedOut(_)
+            | EventMsg::ToolInvocationResult(_)
+            | EventMsg::CompletionResult(_)
+            | EventMsg::EnabledToolsUpdated(_)
+            | EventMsg::SuggestedAllowlist(_)
+            | EventMsg::ResponseCandidates(_)
+            | EventMsg::ResponseCandidateSelected(_)
+            | EventMsg::RateLimitForecast(_)
+            | EventMsg::PlanOrCreditsChanged(_)
+            | EventMsg::OfflineStatus(_)
+            | EventMsg::UsageRollups(_)
+            | EventMsg::TurnTimings(_)
+            | EventMsg::TurnTimingRecorded(_)
+            | EventMsg::ContextBreakdown(_)
+            | EventMsg::HistoryMetadataRecorded(_)
+            | EventMsg::ActiveToolCalls(_)
+            | EventMsg::ToolLockHeld(_)
+            | EventMsg::McpToolCallProgress(_)
+            | EventMsg::McpToolCallLog(_) => {}
             EventMsg::SkillsUpdateAvailable => {
                 self.submit_op(Op::ListSkills {
                     cwds: Vec::new(),
@@ -4164,8 +4255,11 @@ impl ChatWidget {
                 });
             }
             EventMsg::ShutdownComplete => self.on_shutdown_complete(),
-            EventMsg::TurnDiff(TurnDiffEvent { unified_diff }) => self.on_turn_diff(unified_diff),
+            EventMsg::TurnDiff(TurnDiffEvent { unified_diff, .. }) => {
+                self.on_turn_diff(unified_diff)
+            }
             EventMsg::DeprecationNotice(ev) => self.on_deprecation_notice(ev),
+            EventMsg::ToolProgress(ev) => self.on_tool_progress(ev),
             EventMsg::BackgroundEvent(BackgroundEventEvent { message }) => {
                 self.on_background_event(message)
             }
@@ -7110,6 +7204,105 @@ impl ChatWidget {
         self.set_skills_from_response(&ev);
     }
 
+    fn on_sessions_pruned(&mut self, ev: SessionsPrunedEvent) {
+        let verb = if ev.dry_run { "Would prune" } else { "Pruned" };
+        let mut lines: Vec<Line<'static>> = vec![
+            vec![
+                "• ".dim(),
+                format!(
+                    "{verb} {} stored sessions, freeing {} bytes",
+                    ev.pruned.len(),
+                    ev.freed_bytes
+                )
+                .into(),
+                format!(" ({} bytes kept)", ev.kept_bytes).dark_gray(),
+            ]
+            .into(),
+        ];
+        lines.extend(ev.pruned.iter().map(|session| {
+            vec![
+                "  └ ".dim(),
+                session.path.display().to_string().into(),
+                format!(" {}", session.reason).dark_gray(),
+            ]
+            .into()
+        }));
+        self.add_plain_history_lines(lines);
+    }
+
+    fn on_dependency_audit(&mut self, ev: DependencyAuditEvent) {
+        let mut lines: Vec<Line<'static>> = vec![
+            vec![
+                "• ".dim(),
+                format!(
+                    "Found {} advisories affecting {} packages",
+                    ev.findings.len(),
+                    ev.packages_checked
+                )
+                .into(),
+                format!(" (from {} lockfiles)", ev.lockfiles.len()).dark_gray(),
+            ]
+            .into(),
+        ];
+        lines.extend(ev.findings.iter().map(|finding| {
+            let fix = finding
+                .suggested_version
+                .as_deref()
+                .map_or(" no fixed release".to_string(), |version| {
+                    format!(" fixed in {version}")
+                });
+            vec![
+                "  └ ".dim(),
+                format!("{} {} ", finding.package, finding.version).into(),
+                finding.advisory_id.clone().red(),
+                format!(" {}", finding.summary).into(),
+                fix.dark_gray(),
+            ]
+            .into()
+        }));
+        lines.extend(
+            ev.errors
+                .iter()
+                .map(|error| vec!["  └ ".dim(), error.clone().red()].into()),
+        );
+        self.add_plain_history_lines(lines);
+    }
+
+    fn on_license_report(&mut self, ev: LicenseReportEvent) {
+        let mut lines: Vec<Line<'static>> = vec![
+            vec![
+                "• ".dim(),
+                format!("Found {} license violations", ev.violations.len()).into(),
+                format!(
+                    " ({} dependencies, {} source licenses)",
+                    ev.dependencies.len(),
+                    ev.source_headers.len()
+                )
+                .dark_gray(),
+            ]
+            .into(),
+        ];
+        lines.extend(ev.violations.iter().map(|violation| {
+            vec![
+                "  └ ".dim(),
+                format!("{} ", violation.subject).into(),
+                violation
+                    .license
+                    .clone()
+                    .unwrap_or_else(|| "unknown".to_string())
+                    .red(),
+                format!(" {}", violation.reason).dark_gray(),
+            ]
+            .into()
+        }));
+        lines.extend(
+            ev.errors
+                .iter()
+                .map(|error| vec!["  └ ".dim(), error.clone().red()].into()),
+        );
+        self.add_plain_history_lines(lines);
+    }
+
     pub(crate) fn on_connectors_loaded(
         &mut self,
         result: Result<ConnectorsSnapshot, String>,
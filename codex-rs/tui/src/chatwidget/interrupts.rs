@@ -59,6 +59,15 @@ impl InterruptManager {
         self.queue.push_back(QueuedInterrupt::RequestUserInput(ev));
     }
 
+    /// Drops a queued exec or patch approval request answered elsewhere.
+    pub(crate) fn remove_approval(&mut self, id: &str) {
+        self.queue.retain(|interrupt| match interrupt {
+            QueuedInterrupt::ExecApproval(ev) => ev.call_id != id,
+            QueuedInterrupt::ApplyPatchApproval(ev) => ev.call_id != id,
+            _ => true,
+        });
+    }
+
     pub(crate) fn push_exec_begin(&mut self, ev: ExecCommandBeginEvent) {
         self.queue.push_back(QueuedInterrupt::ExecBegin(ev));
     }
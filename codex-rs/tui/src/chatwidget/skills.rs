@@ -186,6 +186,9 @@ fn protocol_skill_to_core(skill: &ProtocolSkillMetadata) -> SkillMetadata {
                         transport: tool.transport,
                         command: tool.command,
                         url: tool.url,
+                        version: tool.version,
+                        install: None,
+                        check: None,
                     })
                     .collect(),
             }),
@@ -1394,6 +1394,7 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_review_tokens, context_window)),
             rate_limits: None,
+            request: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -1413,6 +1414,7 @@ async fn review_restores_context_window_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(review_tokens, context_window)),
             rate_limits: None,
+            request: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(97));
@@ -1442,6 +1444,7 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(make_token_info(pre_compact_tokens, context_window)),
             rate_limits: None,
+            request: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), Some(30));
@@ -1451,6 +1454,7 @@ async fn token_count_none_resets_context_indicator() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: None,
             rate_limits: None,
+            request: None,
         }),
     });
     assert_eq!(chat.bottom_pane.context_window_percent(), None);
@@ -1481,6 +1485,7 @@ async fn context_indicator_shows_used_tokens_when_window_unknown() {
         msg: EventMsg::TokenCount(TokenCountEvent {
             info: Some(token_info),
             rate_limits: None,
+            request: None,
         }),
     });
 
@@ -2724,6 +2729,7 @@ async fn exec_approval_emits_proposed_command_and_decision_history() {
         network_approval_context: None,
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-short".into(),
@@ -2770,6 +2776,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         network_approval_context: None,
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-multi".into(),
@@ -2822,6 +2829,7 @@ async fn exec_approval_decision_truncates_multiline_and_long_commands() {
         network_approval_context: None,
         proposed_execpolicy_amendment: None,
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-long".into(),
@@ -6172,6 +6180,7 @@ async fn approval_modal_exec_snapshot() -> anyhow::Result<()> {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve".into(),
@@ -6230,6 +6239,7 @@ async fn approval_modal_exec_without_reason_snapshot() -> anyhow::Result<()> {
             "world".into(),
         ])),
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-noreason".into(),
@@ -6275,6 +6285,7 @@ async fn approval_modal_exec_multiline_prefix_hides_execpolicy_option_snapshot()
         network_approval_context: None,
         proposed_execpolicy_amendment: Some(ExecPolicyAmendment::new(command)),
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-multiline-trunc".into(),
@@ -6639,6 +6650,7 @@ async fn status_widget_and_approval_modal_snapshot() {
             "hello world".into(),
         ])),
         parsed_cmd: vec![],
+        risk_assessment: None,
     };
     chat.handle_codex_event(Event {
         id: "sub-approve-exec".into(),
@@ -6841,6 +6853,7 @@ async fn apply_patch_events_emit_history_cells() {
         success: true,
         changes: end_changes,
         status: CorePatchApplyStatus::Completed,
+        anchors: Vec::new(),
     };
     chat.handle_codex_event(Event {
         id: "s1".into(),
@@ -7069,6 +7082,7 @@ async fn apply_patch_full_flow_integration_like() {
             success: true,
             changes: end_changes,
             status: CorePatchApplyStatus::Completed,
+            anchors: Vec::new(),
         }),
     });
 }
@@ -2593,9 +2593,12 @@ mod tests {
             },
             enabled: true,
             required: false,
+            lazy: false,
             disabled_reason: None,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
@@ -2616,9 +2619,12 @@ mod tests {
             },
             enabled: true,
             required: false,
+            lazy: false,
             disabled_reason: None,
             startup_timeout_sec: None,
             tool_timeout_sec: None,
+            elicitation: false,
+            elicitation_timeout_sec: None,
             enabled_tools: None,
             disabled_tools: None,
             scopes: None,
@@ -11,6 +11,7 @@ use codex_cloud_requirements::cloud_requirements_loader;
 use codex_core::AuthManager;
 use codex_core::CodexAuth;
 use codex_core::INTERACTIVE_SESSION_SOURCES;
+use codex_core::RolloutLines;
 use codex_core::RolloutRecorder;
 use codex_core::ThreadSortKey;
 use codex_core::auth::AuthMode;
@@ -25,7 +26,9 @@ use codex_core::config::resolve_oss_provider;
 use codex_core::config_loader::CloudRequirementsLoader;
 use codex_core::config_loader::ConfigLoadError;
 use codex_core::config_loader::format_config_error_with_source;
+use codex_core::default_client::set_default_client_http_network_config;
 use codex_core::default_client::set_default_client_residency_requirement;
+use codex_core::features::Feature;
 use codex_core::find_thread_path_by_id_str;
 use codex_core::find_thread_path_by_name_str;
 use codex_core::format_exec_policy_error_with_source;
@@ -306,6 +309,13 @@ pub async fn run_main(
     }
 
     set_default_client_residency_requirement(config.enforce_residency.value());
+    if let Err(err) = set_default_client_http_network_config(&config.http) {
+        #[allow(clippy::print_stderr)]
+        {
+            eprintln!("Error loading [http] settings: {err}");
+        }
+        std::process::exit(1);
+    }
 
     if let Some(warning) =
         add_dir_warning_message(&cli.add_dir, config.permissions.sandbox_policy.get())
@@ -407,9 +417,15 @@ pub async fn run_main(
 
     let otel_tracing_layer = otel.as_ref().and_then(|o| o.tracing_layer());
 
-    let log_db_layer = codex_core::state_db::get_state_db(&config, None)
-        .await
-        .map(|db| log_db::start(db).with_filter(env_filter()));
+    // Log messages can quote session content, which the log DB would keep
+    // in plaintext.
+    let log_db_layer = if config.features.enabled(Feature::SessionEncryption) {
+        None
+    } else {
+        codex_core::state_db::get_state_db(&config, None)
+            .await
+            .map(|db| log_db::start(db).with_filter(env_filter()))
+    };
 
     let _ = tracing_subscriber::registry()
         .with(file_layer)
@@ -719,6 +735,11 @@ async fn run_ratatui_app(
     }
 
     set_default_client_residency_requirement(config.enforce_residency.value());
+    if let Err(err) = set_default_client_http_network_config(&config.http) {
+        config
+            .startup_warnings
+            .push(format!("Ignoring [http] settings: {err}"));
+    }
     let active_profile = config.active_profile.clone();
     let should_show_trust_screen = should_show_trust_screen(&config);
     let should_prompt_windows_sandbox_nux_at_startup = cfg!(target_os = "windows")
@@ -782,8 +803,9 @@ pub(crate) async fn read_session_cwd(path: &Path) -> Option<PathBuf> {
 }
 
 async fn parse_latest_turn_context_cwd(path: &Path) -> Option<PathBuf> {
-    let text = tokio::fs::read_to_string(path).await.ok()?;
-    for line in text.lines().rev() {
+    let mut lines = RolloutLines::open(path).await.ok()?;
+    let mut latest_cwd = None;
+    while let Ok(Some(line)) = lines.next_line().await {
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
@@ -792,10 +814,10 @@ async fn parse_latest_turn_context_cwd(path: &Path) -> Option<PathBuf> {
             continue;
         };
         if let RolloutItem::TurnContext(item) = rollout_line.item {
-            return Some(item.cwd);
+            latest_cwd = Some(item.cwd);
         }
     }
-    None
+    latest_cwd
 }
 
 pub(crate) fn cwds_differ(current_cwd: &Path, session_cwd: &Path) -> bool {
@@ -1071,6 +1093,7 @@ mod tests {
             developer_instructions: None,
             final_output_json_schema: None,
             truncation_policy: None,
+            sampling: None,
         }
     }
 
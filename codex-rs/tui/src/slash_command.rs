@@ -47,6 +47,8 @@ pub enum SlashCommand {
     Rollout,
     Ps,
     Clean,
+    Audit,
+    Licenses,
     Clear,
     Personality,
     TestApproval,
@@ -81,6 +83,8 @@ impl SlashCommand {
             SlashCommand::Theme => "choose a syntax highlighting theme",
             SlashCommand::Ps => "list background terminals",
             SlashCommand::Clean => "stop all background terminals",
+            SlashCommand::Audit => "check lockfiles for known vulnerabilities: /audit [upgrade]",
+            SlashCommand::Licenses => "check dependency and source licenses against the policy",
             SlashCommand::MemoryDrop => "DO NOT USE",
             SlashCommand::MemoryUpdate => "DO NOT USE",
             SlashCommand::Model => "choose what model and reasoning effort to use",
@@ -116,6 +120,7 @@ impl SlashCommand {
             SlashCommand::Review
                 | SlashCommand::Rename
                 | SlashCommand::Plan
+                | SlashCommand::Audit
                 | SlashCommand::SandboxReadRoot
         )
     }
@@ -138,6 +143,8 @@ impl SlashCommand {
             | SlashCommand::Experimental
             | SlashCommand::Review
             | SlashCommand::Plan
+            | SlashCommand::Audit
+            | SlashCommand::Licenses
             | SlashCommand::Clear
             | SlashCommand::Logout
             | SlashCommand::MemoryDrop
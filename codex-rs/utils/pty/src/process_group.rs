@@ -12,6 +12,7 @@
 //! - `set_parent_death_signal` (Linux only) arranges for the child to receive a
 //!   `SIGTERM` when the parent exits, and re-checks the parent PID to avoid
 //!   races during fork/exec.
+//! - `set_resource_limits` caps the child's address space and CPU time.
 //!
 //! On non-Unix platforms these helpers are no-ops.
 
@@ -83,6 +84,37 @@ pub fn set_process_group() -> io::Result<()> {
     Ok(())
 }
 
+#[cfg(unix)]
+/// Cap the address space of the calling process at `memory_bytes` and its
+/// CPU time at `cpu_secs`, via `setrlimit`.
+///
+/// Intended for use in `pre_exec`; the limits carry over to the program it
+/// executes and to that program's children.
+pub fn set_resource_limits(memory_bytes: Option<u64>, cpu_secs: Option<u64>) -> io::Result<()> {
+    for (resource, limit) in [
+        (libc::RLIMIT_AS, memory_bytes),
+        (libc::RLIMIT_CPU, cpu_secs),
+    ] {
+        let Some(limit) = limit else {
+            continue;
+        };
+        let limit = libc::rlimit {
+            rlim_cur: limit as libc::rlim_t,
+            rlim_max: limit as libc::rlim_t,
+        };
+        if unsafe { libc::setrlimit(resource, &limit) } == -1 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+/// No-op on non-Unix platforms.
+pub fn set_resource_limits(_memory_bytes: Option<u64>, _cpu_secs: Option<u64>) -> io::Result<()> {
+    Ok(())
+}
+
 #[cfg(unix)]
 /// Kill the process group for the given PID (best-effort).
 ///
@@ -37,6 +37,30 @@ pub fn take_last_bytes_at_char_boundary(s: &str, maxb: usize) -> &str {
     &s[start..]
 }
 
+/// Fill the `{{ name }}` placeholders of `template` with `values` in a single
+/// pass, so text inserted for one placeholder is never treated as another.
+/// Placeholders without a value are left as they are.
+pub fn fill_template(template: &str, values: &[(&str, &str)]) -> String {
+    let mut filled = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("{{ ") {
+        filled.push_str(&rest[..start]);
+        let placeholder = &rest[start..];
+        let Some(end) = placeholder.find(" }}") else {
+            rest = placeholder;
+            break;
+        };
+        let name = &placeholder[3..end];
+        match values.iter().find(|(key, _)| *key == name) {
+            Some((_, value)) => filled.push_str(value),
+            None => filled.push_str(&placeholder[..end + 3]),
+        }
+        rest = &placeholder[end + 3..];
+    }
+    filled.push_str(rest);
+    filled
+}
+
 /// Sanitize a tag value to comply with metric tag validation rules:
 /// only ASCII alphanumeric, '.', '_', '-', and '/' are allowed.
 pub fn sanitize_metric_tag_value(value: &str) -> String {
@@ -64,9 +88,19 @@ pub fn sanitize_metric_tag_value(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
+    use super::fill_template;
     use super::sanitize_metric_tag_value;
     use pretty_assertions::assert_eq;
 
+    #[test]
+    fn fill_template_does_not_substitute_inside_inserted_values() {
+        let filled = fill_template(
+            "{{ a }} and {{ b }}, {{ unknown }}",
+            &[("a", "literal {{ b }}"), ("b", "B")],
+        );
+        assert_eq!(filled, "literal {{ b }} and B, {{ unknown }}");
+    }
+
     #[test]
     fn sanitize_metric_tag_value_trims_and_fills_unspecified() {
         let msg = "///";